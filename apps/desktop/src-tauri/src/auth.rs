@@ -2,10 +2,13 @@
 
 use codex_database::{
     DatabaseConnection,
+    entities::user_identity,
     repository::{
         user_repository::{UserRepository, CreateUserData},
         user_session_repository::{UserSessionRepository, CreateSessionData},
+        user_identity_repository::{UserIdentityRepository, LinkIdentityData},
     },
+    two_factor::TwoFactorService,
 };
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
@@ -14,17 +17,37 @@ use chrono::Utc;
 use std::sync::Arc;
 use tauri::State;
 
+use crate::oidc::{self, ExternalIdentity, TokenResponse};
+use crate::settings::{AppSettings, OidcProviderKind, SettingsManager};
+
 /// 认证服务
 #[derive(Clone)]
 pub struct AuthService {
     db: DatabaseConnection,
 }
 
+/// 访问令牌有效期（小时）
+const ACCESS_TOKEN_TTL_HOURS: i64 = 24;
+
+/// 刷新令牌有效期（小时）
+const REFRESH_TOKEN_TTL_HOURS: i64 = 24 * 30;
+
+/// 启用两步验证的用户登录时未提交验证码返回的错误，前端据此显示验证码输入框
+pub const TWO_FACTOR_REQUIRED: &str = "TWO_FACTOR_REQUIRED";
+
+/// 被要求使用两步验证但尚未登记的用户调用登记以外的命令时返回的错误，前端据此引导完成登记
+pub const TWO_FACTOR_SETUP_REQUIRED: &str = "TWO_FACTOR_SETUP_REQUIRED";
+
 /// 登录请求数据
 #[derive(Debug, Deserialize)]
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
+    /// 两步验证码或备用恢复码
+    #[serde(default)]
+    pub two_factor_code: Option<String>,
+    #[serde(default)]
+    pub device: Option<DeviceInfo>,
 }
 
 /// 注册请求数据
@@ -33,6 +56,29 @@ pub struct RegisterRequest {
     pub username: String,
     pub email: String,
     pub password: String,
+    #[serde(default)]
+    pub device: Option<DeviceInfo>,
+}
+
+/// 客户端设备信息
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DeviceInfo {
+    pub device_name: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+/// 会话信息
+#[derive(Debug, Serialize)]
+pub struct SessionInfo {
+    pub session_id: String,
+    pub device_name: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: String,
+    pub last_active_at: String,
+    pub expires_at: String,
+    pub is_current: bool,
 }
 
 /// 修改密码请求数据
@@ -56,6 +102,8 @@ pub struct AuthResponse {
     pub token: String,
     pub refresh_token: String,
     pub expires_in: i64, // 秒数
+    /// 用户被要求使用两步验证但尚未登记，令牌只能用于完成登记
+    pub two_factor_setup_required: bool,
 }
 
 /// 用户信息
@@ -68,6 +116,38 @@ pub struct UserInfo {
     pub profile_data: Option<serde_json::Value>,
 }
 
+/// 可用的单点登录提供方
+#[derive(Debug, Serialize)]
+pub struct OidcProviderInfo {
+    pub id: String,
+    pub name: String,
+    pub kind: OidcProviderKind,
+}
+
+/// 已关联的外部身份
+#[derive(Debug, Serialize)]
+pub struct LinkedIdentityInfo {
+    pub identity_id: String,
+    pub provider: String,
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    pub created_at: String,
+    pub last_login_at: Option<String>,
+}
+
+impl From<user_identity::Model> for LinkedIdentityInfo {
+    fn from(identity: user_identity::Model) -> Self {
+        Self {
+            identity_id: identity.identity_id.to_string(),
+            provider: identity.provider,
+            email: identity.email,
+            display_name: identity.display_name,
+            created_at: identity.created_at.to_rfc3339(),
+            last_login_at: identity.last_login_at.map(|time| time.to_rfc3339()),
+        }
+    }
+}
+
 /// 当前用户状态
 #[derive(Debug, Clone)]
 pub struct CurrentUser {
@@ -114,7 +194,7 @@ impl AuthService {
             .map_err(|e| format!("创建用户失败: {}", e))?;
         
         // 创建会话
-        let auth_response = self.create_session_for_user(&user).await?;
+        let auth_response = self.create_session_for_user(&user, request.device.unwrap_or_default()).await?;
         
         Ok(auth_response)
     }
@@ -138,18 +218,35 @@ impl AuthService {
             return Err("用户账户已被禁用".to_string());
         }
         
+        self.verify_two_factor(user.user_id, request.two_factor_code.as_deref()).await?;
+        
         // 更新最后登录时间
         user_repo.update_last_login(user.user_id).await
             .map_err(|e| format!("更新登录时间失败: {}", e))?;
         
         // 创建会话
-        let auth_response = self.create_session_for_user(&user).await?;
+        let auth_response = self.create_session_for_user(&user, request.device.unwrap_or_default()).await?;
         
         Ok(auth_response)
     }
 
     /// 验证令牌
+    ///
+    /// 被要求使用两步验证但尚未登记的用户返回 [`TWO_FACTOR_SETUP_REQUIRED`]，
+    /// 这类用户的令牌只能通过 [`Self::validate_setup_token`] 用于登记命令
     pub async fn validate_token(&self, token: &str) -> Result<CurrentUser, String> {
+        let current_user = self.validate_setup_token(token).await?;
+        let setup_required = TwoFactorService::new(self.db.clone())
+            .setup_required(current_user.user_id).await
+            .map_err(|e| format!("查询两步验证设置失败: {}", e))?;
+        if setup_required {
+            return Err(TWO_FACTOR_SETUP_REQUIRED.to_string());
+        }
+        Ok(current_user)
+    }
+
+    /// 验证令牌，不检查两步验证登记，只供两步验证登记和查询当前用户的命令使用
+    pub async fn validate_setup_token(&self, token: &str) -> Result<CurrentUser, String> {
         let session_repo = UserSessionRepository::new(self.db.clone());
         
         let session = session_repo.validate_session(token).await
@@ -169,35 +266,30 @@ impl AuthService {
         })
     }
 
-    /// 刷新令牌
+    /// 刷新令牌（轮换：旧刷新令牌只能使用一次）
     pub async fn refresh_token(&self, refresh_token: &str) -> Result<AuthResponse, String> {
         let session_repo = UserSessionRepository::new(self.db.clone());
         
-        let session = session_repo.find_by_refresh_token(refresh_token).await
-            .map_err(|e| format!("查询会话失败: {}", e))?
-            .ok_or("无效的刷新令牌")?;
+        // 生成新的令牌
+        let new_token = self.generate_token();
+        let new_refresh_token = self.generate_token();
         
-        if !session.is_valid() {
-            return Err("刷新令牌已过期".to_string());
-        }
+        // 轮换会话令牌，重放已使用的刷新令牌会撤销整个会话
+        let session = session_repo.rotate_refresh_token(
+            refresh_token,
+            new_token.clone(),
+            new_refresh_token.clone(),
+            ACCESS_TOKEN_TTL_HOURS,
+            REFRESH_TOKEN_TTL_HOURS,
+        ).await.map_err(|e| format!("刷新令牌失败: {}", e))?;
         
         let user_repo = UserRepository::new(self.db.clone());
         let user = user_repo.find_by_id(session.user_id).await
             .map_err(|e| format!("查询用户失败: {}", e))?
             .ok_or("用户不存在")?;
         
-        // 生成新的令牌
-        let new_token = self.generate_token();
-        let new_refresh_token = self.generate_token();
-        let expires_in_hours = 24;
-        
-        // 更新会话
-        let _updated_session = session_repo.refresh_token(
-            session.session_id,
-            new_token.clone(),
-            new_refresh_token.clone(),
-            expires_in_hours,
-        ).await.map_err(|e| format!("更新会话失败: {}", e))?;
+        let two_factor = TwoFactorService::new(self.db.clone()).status(user.user_id).await
+            .map_err(|e| format!("查询两步验证设置失败: {}", e))?;
         
         let user_info = UserInfo {
             user_id: user.user_id.to_string(),
@@ -211,10 +303,47 @@ impl AuthService {
             user: user_info,
             token: new_token,
             refresh_token: new_refresh_token,
-            expires_in: expires_in_hours * 3600,
+            expires_in: ACCESS_TOKEN_TTL_HOURS * 3600,
+            two_factor_setup_required: two_factor.required && !two_factor.enabled,
         })
     }
 
+    /// 列出当前用户的活跃会话
+    pub async fn list_active_sessions(&self, current_user: &CurrentUser) -> Result<Vec<SessionInfo>, String> {
+        let session_repo = UserSessionRepository::new(self.db.clone());
+        let sessions = session_repo.list_usable_sessions_by_user(current_user.user_id).await
+            .map_err(|e| format!("查询会话失败: {}", e))?;
+        
+        Ok(sessions
+            .into_iter()
+            .map(|session| SessionInfo {
+                is_current: session.session_id == current_user.session_id,
+                session_id: session.session_id.to_string(),
+                device_name: session.device_name,
+                user_agent: session.user_agent,
+                ip_address: session.ip_address,
+                created_at: session.created_at.to_rfc3339(),
+                last_active_at: session.last_active_at.to_rfc3339(),
+                expires_at: session.refresh_expires_at.unwrap_or(session.expires_at).to_rfc3339(),
+            })
+            .collect())
+    }
+
+    /// 撤销当前用户的指定会话
+    pub async fn revoke_session(&self, current_user: &CurrentUser, session_id: Uuid) -> Result<(), String> {
+        let session_repo = UserSessionRepository::new(self.db.clone());
+        session_repo.revoke_session(current_user.user_id, session_id).await
+            .map_err(|e| format!("撤销会话失败: {}", e))?;
+        Ok(())
+    }
+
+    /// 清理过期会话
+    pub async fn cleanup_expired_sessions(&self) -> Result<u64, String> {
+        let session_repo = UserSessionRepository::new(self.db.clone());
+        session_repo.cleanup_expired_sessions().await
+            .map_err(|e| format!("清理过期会话失败: {}", e))
+    }
+
     /// 注销
     pub async fn logout(&self, token: &str) -> Result<(), String> {
         let session_repo = UserSessionRepository::new(self.db.clone());
@@ -228,32 +357,203 @@ impl AuthService {
         Ok(())
     }
 
+    /// 通过外部身份登录：已关联的身份直接登录；未关联且邮箱未注册时创建新用户
+    ///
+    /// 邮箱已注册的本地用户不会被自动关联，需要先用密码登录后再关联，避免身份提供方的邮箱被冒用；
+    /// 启用了两步验证的用户与密码登录一样需要提交验证码
+    pub async fn login_with_identity(
+        &self,
+        provider_id: &str,
+        identity: ExternalIdentity,
+        tokens: &TokenResponse,
+        two_factor_code: Option<&str>,
+        device: DeviceInfo,
+    ) -> Result<AuthResponse, String> {
+        let user_repo = UserRepository::new(self.db.clone());
+        let identity_repo = UserIdentityRepository::new(self.db.clone());
+
+        let user = match identity_repo.find_by_subject(provider_id, &identity.subject).await
+            .map_err(|e| format!("查询关联身份失败: {}", e))?
+        {
+            Some(linked) => {
+                identity_repo.record_login(
+                    linked.identity_id,
+                    identity.email.clone(),
+                    identity.display_name.clone(),
+                    tokens.refresh_token.clone(),
+                    tokens.expires_at(),
+                ).await.map_err(|e| format!("更新关联身份失败: {}", e))?;
+                user_repo.find_by_id(linked.user_id).await
+                    .map_err(|e| format!("查询用户失败: {}", e))?
+                    .ok_or("用户不存在")?
+            }
+            None => {
+                let email = match identity.email.as_deref().filter(|_| identity.email_verified) {
+                    Some(email) => {
+                        if user_repo.find_by_email(email).await
+                            .map_err(|e| format!("查询用户失败: {}", e))?
+                            .is_some()
+                        {
+                            return Err(format!("邮箱 {} 已注册，请先使用密码登录后在账户设置中关联该身份", email));
+                        }
+                        email.to_string()
+                    }
+                    // 没有已验证邮箱时使用不可投递的占位邮箱
+                    None => format!("{}@{}.identity.invalid", identity.subject, provider_id),
+                };
+                let base = identity.username.clone()
+                    .or_else(|| email.split('@').next().map(str::to_string))
+                    .unwrap_or_else(|| provider_id.to_string());
+                let username = self.unique_username(&base).await?;
+
+                // 外部身份创建的用户没有密码，可在账户设置中设置密码作为备用登录方式
+                let user = user_repo.create(CreateUserData {
+                    username,
+                    email,
+                    password_hash: String::new(),
+                    profile_data: identity.display_name.clone()
+                        .map(|name| serde_json::json!({ "display_name": name })),
+                    settings: None,
+                }).await.map_err(|e| format!("创建用户失败: {}", e))?;
+                let linked = identity_repo.link(LinkIdentityData {
+                    user_id: user.user_id,
+                    provider: provider_id.to_string(),
+                    subject: identity.subject,
+                    email: identity.email,
+                    display_name: identity.display_name,
+                    refresh_token: tokens.refresh_token.clone(),
+                    token_expires_at: tokens.expires_at(),
+                }).await.map_err(|e| format!("关联身份失败: {}", e))?;
+                identity_repo.record_login(linked.identity_id, None, None, None, tokens.expires_at()).await
+                    .map_err(|e| format!("更新关联身份失败: {}", e))?;
+                println!("通过 {} 创建用户 {}", provider_id, user.username);
+                user
+            }
+        };
+
+        if !user.is_active {
+            return Err("用户账户已被禁用".to_string());
+        }
+        self.verify_two_factor(user.user_id, two_factor_code).await?;
+        user_repo.update_last_login(user.user_id).await
+            .map_err(|e| format!("更新登录时间失败: {}", e))?;
+        self.create_session_for_user(&user, device).await
+    }
+
+    /// 为已登录用户关联外部身份
+    pub async fn link_identity(
+        &self,
+        user_id: Uuid,
+        provider_id: &str,
+        identity: ExternalIdentity,
+        tokens: &TokenResponse,
+    ) -> Result<LinkedIdentityInfo, String> {
+        let linked = UserIdentityRepository::new(self.db.clone())
+            .link(LinkIdentityData {
+                user_id,
+                provider: provider_id.to_string(),
+                subject: identity.subject,
+                email: identity.email,
+                display_name: identity.display_name,
+                refresh_token: tokens.refresh_token.clone(),
+                token_expires_at: tokens.expires_at(),
+            })
+            .await
+            .map_err(|e| format!("关联身份失败: {}", e))?;
+        Ok(linked.into())
+    }
+
+    /// 解除外部身份关联，用户至少需要保留密码或另一个身份作为登录方式
+    pub async fn unlink_identity(&self, user_id: Uuid, identity_id: Uuid) -> Result<(), String> {
+        let user = UserRepository::new(self.db.clone())
+            .find_by_id(user_id).await
+            .map_err(|e| format!("查询用户失败: {}", e))?
+            .ok_or("用户不存在")?;
+        let identity_repo = UserIdentityRepository::new(self.db.clone());
+        let identities = identity_repo.find_by_user(user_id).await
+            .map_err(|e| format!("查询关联身份失败: {}", e))?;
+        if user.password_hash.is_empty() && identities.len() <= 1 {
+            return Err("请先设置密码或关联其他身份，再解除最后一个关联身份".to_string());
+        }
+        identity_repo.unlink(user_id, identity_id).await
+            .map_err(|e| format!("解除关联失败: {}", e))?;
+        Ok(())
+    }
+
+    /// 启用两步验证的用户需要提交验证码或备用恢复码，未提交时返回 [`TWO_FACTOR_REQUIRED`]
+    async fn verify_two_factor(&self, user_id: Uuid, code: Option<&str>) -> Result<(), String> {
+        let two_factor = TwoFactorService::new(self.db.clone());
+        if !two_factor.is_enabled(user_id).await
+            .map_err(|e| format!("查询两步验证设置失败: {}", e))?
+        {
+            return Ok(());
+        }
+        let code = code
+            .filter(|code| !code.trim().is_empty())
+            .ok_or(TWO_FACTOR_REQUIRED)?;
+        two_factor.verify(user_id, code).await
+            .map_err(|e| format!("两步验证失败: {}", e))?;
+        Ok(())
+    }
+
+    /// 生成未被占用的用户名
+    async fn unique_username(&self, base: &str) -> Result<String, String> {
+        let user_repo = UserRepository::new(self.db.clone());
+        let base: String = base.chars()
+            .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-' || *c == '.')
+            .take(32)
+            .collect();
+        let base = if base.is_empty() { "user".to_string() } else { base };
+        let mut candidate = base.clone();
+        while user_repo.find_by_username(&candidate).await
+            .map_err(|e| format!("查询用户失败: {}", e))?
+            .is_some()
+        {
+            candidate = format!("{}-{}", base, &Uuid::new_v4().simple().to_string()[..6]);
+        }
+        Ok(candidate)
+    }
+
     /// 为用户创建会话
     async fn create_session_for_user(
         &self, 
-        user: &codex_database::entities::user::Model
+        user: &codex_database::entities::user::Model,
+        device: DeviceInfo,
     ) -> Result<AuthResponse, String> {
         let session_repo = UserSessionRepository::new(self.db.clone());
         
         // 生成令牌
         let token = self.generate_token();
         let refresh_token = self.generate_token();
-        let expires_in_hours = 24;
+        let expires_in_hours = ACCESS_TOKEN_TTL_HOURS;
         
-        // 创建会话数据
+        // 创建会话数据，未提供设备信息时使用本机信息
         let session_data = CreateSessionData {
             user_id: user.user_id,
             token: token.clone(),
             refresh_token: refresh_token.clone(),
-            ip_address: None, // 桌面应用暂时不需要IP
-            user_agent: Some("Sker Desktop App".to_string()),
+            ip_address: device.ip_address,
+            user_agent: device.user_agent.or_else(|| Some(format!(
+                "Sker Desktop App ({} {})",
+                std::env::consts::OS,
+                std::env::consts::ARCH
+            ))),
+            device_name: device.device_name.or_else(|| {
+                std::env::var("COMPUTERNAME")
+                    .or_else(|_| std::env::var("HOSTNAME"))
+                    .ok()
+            }),
             expires_in_hours,
+            refresh_expires_in_hours: Some(REFRESH_TOKEN_TTL_HOURS),
         };
         
         // 创建会话
         let _session = session_repo.create(session_data).await
             .map_err(|e| format!("创建会话失败: {}", e))?;
         
+        let two_factor = TwoFactorService::new(self.db.clone()).status(user.user_id).await
+            .map_err(|e| format!("查询两步验证设置失败: {}", e))?;
+        
         let user_info = UserInfo {
             user_id: user.user_id.to_string(),
             username: user.username.clone(),
@@ -267,6 +567,7 @@ impl AuthService {
             token,
             refresh_token,
             expires_in: expires_in_hours * 3600,
+            two_factor_setup_required: two_factor.required && !two_factor.enabled,
         })
     }
 
@@ -298,8 +599,8 @@ impl AuthService {
             .map_err(|e| format!("查询用户失败: {}", e))?
             .ok_or("用户不存在")?;
         
-        // 验证当前密码
-        if !self.verify_password(&request.current_password, &user.password_hash) {
+        // 验证当前密码，通过外部身份创建、尚未设置密码的用户直接设置
+        if !user.password_hash.is_empty() && !self.verify_password(&request.current_password, &user.password_hash) {
             return Err("当前密码错误".to_string());
         }
         
@@ -402,7 +703,17 @@ pub async fn refresh_token(
     db: State<'_, Arc<DatabaseConnection>>,
 ) -> Result<AuthResponse, String> {
     let auth_service = AuthService::new((**db).clone());
-    auth_service.refresh_token(&refresh_token).await
+    let response = auth_service.refresh_token(&refresh_token).await?;
+    
+    // 通过单点登录的用户同时刷新身份提供方的令牌，授权被撤销时结束会话
+    let user_id = Uuid::parse_str(&response.user.user_id)
+        .map_err(|_| "无效的用户ID格式")?;
+    let settings = load_settings().await?;
+    if let Err(e) = oidc::refresh_user_identities(&db, user_id, &settings.system.auth).await {
+        auth_service.logout(&response.token).await?;
+        return Err(e);
+    }
+    Ok(response)
 }
 
 /// 注销命令
@@ -424,7 +735,8 @@ pub async fn get_current_user(
     db: State<'_, Arc<DatabaseConnection>>,
 ) -> Result<UserInfo, String> {
     let auth_service = AuthService::new((**db).clone());
-    let current_user = auth_service.validate_token(&token).await?;
+    // 尚未完成两步验证登记的用户也需要在登记页面显示自己的信息
+    let current_user = auth_service.validate_setup_token(&token).await?;
     
     let user_repo = UserRepository::new((**db).clone());
     let user = user_repo.find_by_id(current_user.user_id).await
@@ -464,4 +776,188 @@ pub async fn update_user_info(
     let updated_user = auth_service.update_user(current_user.user_id, request).await?;
     
     Ok(updated_user)
-}
\ No newline at end of file
+}
+
+/// 列出活跃会话命令
+#[tauri::command]
+pub async fn list_active_sessions(
+    token: String,
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<Vec<SessionInfo>, String> {
+    let auth_service = AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await?;
+    auth_service.list_active_sessions(&current_user).await
+}
+
+/// 撤销会话命令
+#[tauri::command]
+pub async fn revoke_session(
+    session_id: String,
+    token: String,
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<(), String> {
+    let auth_service = AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await?;
+    let session_uuid = Uuid::parse_str(&session_id)
+        .map_err(|_| "无效的会话ID格式")?;
+    
+    println!("撤销会话: {} (用户: {})", session_id, current_user.username);
+    auth_service.revoke_session(&current_user, session_uuid).await
+}
+
+/// 列出可用的单点登录提供方
+#[tauri::command]
+pub async fn list_oidc_providers() -> Result<Vec<OidcProviderInfo>, String> {
+    let settings = load_settings().await?;
+    Ok(settings.system.auth.oidc_providers
+        .into_iter()
+        .map(|provider| OidcProviderInfo {
+            id: provider.id,
+            name: provider.name,
+            kind: provider.kind,
+        })
+        .collect())
+}
+
+/// 通过单点登录提供方登录，在系统浏览器中完成授权
+///
+/// 启用两步验证的用户未提交验证码时返回 [`TWO_FACTOR_REQUIRED`]，需带上验证码重新登录
+#[tauri::command]
+pub async fn login_with_oidc(
+    provider_id: String,
+    two_factor_code: Option<String>,
+    device: Option<DeviceInfo>,
+    app: tauri::AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<AuthResponse, String> {
+    let settings = load_settings().await?;
+    let provider = oidc::find_provider(&settings.system.auth, &provider_id)?;
+    let (identity, tokens) = oidc::authorize(&app, provider).await?;
+    
+    let auth_service = AuthService::new((**db).clone());
+    auth_service.login_with_identity(
+        &provider.id,
+        identity,
+        &tokens,
+        two_factor_code.as_deref(),
+        device.unwrap_or_default(),
+    ).await
+}
+
+/// 为当前用户关联单点登录身份
+#[tauri::command]
+pub async fn link_oidc_identity(
+    provider_id: String,
+    token: String,
+    app: tauri::AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<LinkedIdentityInfo, String> {
+    let auth_service = AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await?;
+    let settings = load_settings().await?;
+    let provider = oidc::find_provider(&settings.system.auth, &provider_id)?;
+    let (identity, tokens) = oidc::authorize(&app, provider).await?;
+    
+    println!("用户 {} 关联 {} 身份", current_user.username, provider.name);
+    auth_service.link_identity(current_user.user_id, &provider.id, identity, &tokens).await
+}
+
+/// 列出当前用户关联的外部身份
+#[tauri::command]
+pub async fn list_linked_identities(
+    token: String,
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<Vec<LinkedIdentityInfo>, String> {
+    let auth_service = AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await?;
+    let identities = UserIdentityRepository::new((**db).clone())
+        .find_by_user(current_user.user_id).await
+        .map_err(|e| format!("查询关联身份失败: {}", e))?;
+    Ok(identities.into_iter().map(LinkedIdentityInfo::from).collect())
+}
+
+/// 解除外部身份关联
+#[tauri::command]
+pub async fn unlink_identity(
+    identity_id: String,
+    token: String,
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<(), String> {
+    let auth_service = AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await?;
+    let identity_uuid = Uuid::parse_str(&identity_id)
+        .map_err(|_| "无效的身份ID格式")?;
+    auth_service.unlink_identity(current_user.user_id, identity_uuid).await
+}
+
+async fn load_settings() -> Result<AppSettings, String> {
+    let manager = SettingsManager::new()
+        .map_err(|e| format!("创建设置管理器失败: {}", e))?;
+    manager.load_settings().await
+        .map_err(|e| format!("加载设置失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_database::{
+        establish_connection, migrations::Migrator,
+        two_factor::{time_step, totp_code},
+    };
+
+    async fn setup_test_db() -> DatabaseConnection {
+        let db = establish_connection("sqlite::memory:").await.unwrap();
+        Migrator::up(&db, None).await.unwrap();
+        db
+    }
+
+    fn github_identity() -> (ExternalIdentity, TokenResponse) {
+        let identity = ExternalIdentity {
+            subject: "gh-1001".to_string(),
+            email: Some("octo@example.com".to_string()),
+            email_verified: true,
+            display_name: None,
+            username: Some("octo".to_string()),
+        };
+        let tokens = TokenResponse {
+            access_token: "access".to_string(),
+            refresh_token: None,
+            expires_in: None,
+        };
+        (identity, tokens)
+    }
+
+    #[tokio::test]
+    async fn test_oidc_login_requires_two_factor_code_when_enabled() {
+        let db = setup_test_db().await;
+        let auth = AuthService::new(db.clone());
+        let (identity, tokens) = github_identity();
+        let first = auth
+            .login_with_identity("github", identity.clone(), &tokens, None, DeviceInfo::default())
+            .await
+            .unwrap();
+        let user_id = Uuid::parse_str(&first.user.user_id).unwrap();
+
+        let two_factor = TwoFactorService::new(db.clone());
+        let enrollment = two_factor.begin_enrollment(user_id, "Sker", "octo").await.unwrap();
+        let code = totp_code(&enrollment.secret, time_step(Utc::now())).unwrap();
+        let backup_codes = two_factor.confirm_enrollment(user_id, &code).await.unwrap();
+
+        let err = auth
+            .login_with_identity("github", identity.clone(), &tokens, None, DeviceInfo::default())
+            .await
+            .unwrap_err();
+        assert_eq!(err, TWO_FACTOR_REQUIRED);
+        let err = auth
+            .login_with_identity("github", identity.clone(), &tokens, Some("000000"), DeviceInfo::default())
+            .await
+            .unwrap_err();
+        assert!(err.starts_with("两步验证失败"));
+
+        let response = auth
+            .login_with_identity("github", identity, &tokens, Some(&backup_codes[0]), DeviceInfo::default())
+            .await
+            .unwrap();
+        assert_eq!(response.user.user_id, first.user.user_id);
+    }
+}
@@ -0,0 +1,109 @@
+use tauri::State;
+use codex_database::acceptance::{
+    self, AcceptanceChecklist, ChecklistItem, CriterionEvidence, CriterionStatus, MarkCriterionData,
+};
+use codex_database::entities::task;
+use codex_database::repository::TaskRepository;
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::require_project_role;
+use crate::commands::projects::DatabaseHandle;
+
+/// 获取任务的验收标准核对清单
+#[tauri::command]
+pub async fn get_acceptance_checklist(
+    task_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<AcceptanceChecklist, String> {
+    let (task_uuid, _) = authorize_task(&task_id, &token, &db, ProjectRole::Viewer).await?;
+
+    acceptance::checklist(&db, task_uuid).await
+        .map_err(|e| format!("获取验收清单失败: {}", e))
+}
+
+/// 提交某项验收标准的验证结果和证据
+#[tauri::command]
+pub async fn mark_acceptance_criterion(
+    task_id: String,
+    criterion: String,
+    status: CriterionStatus,
+    evidence: Vec<CriterionEvidence>,
+    agent_id: Option<String>,
+    notes: Option<String>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ChecklistItem, String> {
+    let (task_uuid, _) = authorize_task(&task_id, &token, &db, ProjectRole::Contributor).await?;
+    let agent_uuid = agent_id
+        .map(|id| Uuid::parse_str(&id).map_err(|_| "无效的AgentID格式"))
+        .transpose()?;
+
+    let item = acceptance::mark_criterion(&db, MarkCriterionData {
+        task_id: task_uuid,
+        criterion,
+        status,
+        evidence,
+        agent_id: agent_uuid,
+        notes,
+    }).await
+        .map_err(|e| format!("提交验收结果失败: {}", e))?;
+    println!("任务 {} 验收标准「{}」标记为 {}", task_id, item.criterion, item.status);
+    Ok(item)
+}
+
+/// 人工签核某项验收标准
+#[tauri::command]
+pub async fn sign_off_acceptance_criterion(
+    task_id: String,
+    criterion: String,
+    approved: bool,
+    comment: Option<String>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ChecklistItem, String> {
+    let (task_uuid, user_id) = authorize_task(&task_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    let item = acceptance::sign_off(&db, task_uuid, &criterion, user_id, approved, comment).await
+        .map_err(|e| format!("签核验收标准失败: {}", e))?;
+    println!("用户 {} {}任务 {} 的验收标准「{}」", user_id, if approved { "签核通过" } else { "驳回" }, task_id, criterion);
+    Ok(item)
+}
+
+/// 跳过未验证的验收标准强制完成任务
+#[tauri::command]
+pub async fn complete_task_with_override(
+    task_id: String,
+    reason: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<task::Model, String> {
+    let (task_uuid, user_id) = authorize_task(&task_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    let task = acceptance::complete_with_override(&db, task_uuid, user_id, &reason).await
+        .map_err(|e| format!("强制完成任务失败: {}", e))?;
+    println!("用户 {} 强制完成任务 {}: {}", user_id, task_id, reason);
+    Ok(task)
+}
+
+/// 校验令牌，并要求当前用户在任务所属项目中具有指定角色，返回任务ID和用户ID
+async fn authorize_task(
+    task_id: &str,
+    token: &str,
+    db: &DatabaseHandle,
+    role: ProjectRole,
+) -> Result<(Uuid, Uuid), String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let task_uuid = Uuid::parse_str(task_id)
+        .map_err(|_| "无效的任务ID格式")?;
+    let task = TaskRepository::new((**db).clone())
+        .find_by_id(task_uuid).await
+        .map_err(|e| format!("查询任务失败: {}", e))?
+        .ok_or_else(|| format!("任务不存在: {}", task_id))?;
+
+    require_project_role(db, task.project_id, current_user.user_id, role).await?;
+    Ok((task_uuid, current_user.user_id))
+}
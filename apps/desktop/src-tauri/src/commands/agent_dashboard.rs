@@ -0,0 +1,77 @@
+use tauri::State;
+use codex_database::agent_dashboard;
+use codex_database::repository::AgentRepository;
+use codex_multi_agent::{
+    AgentMetricsReport, MetricsPeriod, PaginationParams, PaginationResponse, ProjectRole, TeamLeaderboard,
+    WorkHistoryEntry,
+};
+use uuid::Uuid;
+use crate::commands::members::require_project_role;
+use crate::commands::projects::DatabaseHandle;
+
+/// 获取智能体在统计周期内的性能汇总，默认统计最近一个月
+#[tauri::command]
+pub async fn get_agent_metrics(
+    agent_id: String,
+    period: Option<MetricsPeriod>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<AgentMetricsReport, String> {
+    let agent_uuid = authorize_agent(&agent_id, &token, &db).await?;
+
+    agent_dashboard::agent_metrics(&db, agent_uuid, period.unwrap_or(MetricsPeriod::Month), chrono::Utc::now())
+        .await
+        .map_err(|e| format!("获取智能体性能指标失败: {}", e))
+}
+
+/// 获取项目的团队排行榜，默认统计最近一个月
+#[tauri::command]
+pub async fn get_team_leaderboard(
+    project_id: String,
+    period: Option<MetricsPeriod>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<TeamLeaderboard, String> {
+    let project_uuid = Uuid::parse_str(&project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    require_project_role(&db, project_uuid, current_user.user_id, ProjectRole::Viewer).await?;
+
+    agent_dashboard::team_leaderboard(&db, project_uuid, period.unwrap_or(MetricsPeriod::Month), chrono::Utc::now())
+        .await
+        .map_err(|e| format!("获取团队排行榜失败: {}", e))
+}
+
+/// 分页获取智能体的工作历史
+#[tauri::command]
+pub async fn get_agent_history(
+    agent_id: String,
+    pagination: Option<PaginationParams>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<PaginationResponse<WorkHistoryEntry>, String> {
+    let agent_uuid = authorize_agent(&agent_id, &token, &db).await?;
+
+    agent_dashboard::agent_history(&db, agent_uuid, &pagination.unwrap_or_default())
+        .await
+        .map_err(|e| format!("获取工作历史失败: {}", e))
+}
+
+/// 校验令牌，并要求智能体属于当前用户
+async fn authorize_agent(agent_id: &str, token: &str, db: &DatabaseHandle) -> Result<Uuid, String> {
+    let agent_uuid = Uuid::parse_str(agent_id)
+        .map_err(|_| "无效的智能体ID格式")?;
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let agent = AgentRepository::new((**db).clone()).find_by_id(agent_uuid).await
+        .map_err(|e| format!("查询智能体失败: {}", e))?
+        .ok_or("智能体不存在")?;
+    if agent.user_id != current_user.user_id {
+        return Err("无权查看该智能体".to_string());
+    }
+    Ok(agent_uuid)
+}
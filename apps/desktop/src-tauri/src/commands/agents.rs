@@ -1,10 +1,15 @@
 use tauri::State;
 use std::sync::Arc;
-use codex_database::DatabaseConnection;
+use codex_database::{
+    DatabaseConnection,
+    agent_bundle::{AgentBundle, ImportConflictStrategy},
+    agent_provisioning::{self, ProvisioningReport, ProvisioningSpec},
+};
+use codex_multi_agent::AgentApprovalPolicy;
 use uuid::Uuid;
 use crate::models::{
     Agent, CreateAgentRequest, UpdateAgentRequest, 
-    AgentWorkHistory, AgentPerformanceMetrics
+    AgentWorkHistory, AgentPerformanceMetrics, ImportAgentBundleResult
 };
 
 // 数据库连接管理器
@@ -27,6 +32,12 @@ pub async fn create_agent(
     let db = &**db;
     let agent_repo = codex_database::repository::agent_repository::AgentRepository::new(db.clone());
 
+    // 校验配置中的命令审批策略
+    if let Some(config) = &request.config {
+        AgentApprovalPolicy::from_agent_config_json(config)
+            .map_err(|e| format!("智能体审批策略无效: {}", e))?;
+    }
+
     // 处理能力数组
     let capabilities_json = serde_json::to_value(&request.capabilities)
         .map_err(|e| format!("能力序列化失败: {}", e))?;
@@ -46,32 +57,29 @@ pub async fn create_agent(
         .map_err(|e| format!("创建智能体失败: {}", e))?;
 
     // 转换为前端模型
-    let agent = Agent {
-        agent_id: created_agent.agent_id.to_string(),
-        user_id: created_agent.user_id.to_string(),
-        name: created_agent.name,
-        description: created_agent.description,
-        prompt_template: created_agent.prompt_template,
-        capabilities: serde_json::from_value(created_agent.capabilities).unwrap_or_default(),
-        config: created_agent.config,
-        git_config: created_agent.git_config,
-        status: created_agent.status,
-        current_task_id: created_agent.current_task_id.map(|id| id.to_string()),
-        total_tasks_completed: created_agent.total_tasks_completed,
-        success_rate: created_agent.success_rate,
-        average_completion_time: created_agent.average_completion_time,
-        created_at: created_agent.created_at.to_rfc3339(),
-        updated_at: created_agent.updated_at.to_rfc3339(),
-        last_active_at: created_agent.last_active_at.to_rfc3339(),
-        skill_profile: created_agent.skill_profile,
-        skill_assessments: created_agent.skill_assessments,
-        performance_trend: created_agent.performance_trend,
-    };
+    let agent = Agent::try_from(created_agent)?;
 
     println!("智能体创建成功: {}", agent.agent_id);
     Ok(agent)
 }
 
+/// 按能力矩阵批量创建智能体，全部成功或全部回滚
+#[tauri::command]
+pub async fn provision_agents(
+    spec: ProvisioningSpec,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ProvisioningReport, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    println!("批量创建智能体: {} 个角色 (用户: {})", spec.roles.len(), current_user.username);
+
+    agent_provisioning::provision_agents(&db, current_user.user_id, &spec).await
+        .map_err(|e| format!("批量创建智能体失败: {}", e))
+}
+
 /// 获取智能体列表
 #[tauri::command]
 pub async fn get_agents(
@@ -92,29 +100,9 @@ pub async fn get_agents(
     let agents = agent_repo.find_by_user_id(current_user.user_id).await
         .map_err(|e| format!("查询智能体失败: {}", e))?;
 
-    let result: Vec<Agent> = agents.into_iter().map(|a| {
-        Agent {
-            agent_id: a.agent_id.to_string(),
-            user_id: a.user_id.to_string(),
-            name: a.name,
-            description: a.description,
-            prompt_template: a.prompt_template,
-            capabilities: serde_json::from_value(a.capabilities).unwrap_or_default(),
-            config: a.config,
-            git_config: a.git_config,
-            status: a.status,
-            current_task_id: a.current_task_id.map(|id| id.to_string()),
-            total_tasks_completed: a.total_tasks_completed,
-            success_rate: a.success_rate,
-            average_completion_time: a.average_completion_time,
-            created_at: a.created_at.to_rfc3339(),
-            updated_at: a.updated_at.to_rfc3339(),
-            last_active_at: a.last_active_at.to_rfc3339(),
-            skill_profile: a.skill_profile,
-            skill_assessments: a.skill_assessments,
-            performance_trend: a.performance_trend,
-        }
-    }).collect();
+    let result = agents.into_iter()
+        .map(Agent::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
 
     println!("返回智能体数量: {}", result.len());
     Ok(result)
@@ -139,33 +127,39 @@ pub async fn get_agent(
 
     match agent {
         Some(a) => {
-            let result = Agent {
-                agent_id: a.agent_id.to_string(),
-                user_id: a.user_id.to_string(),
-                name: a.name,
-                description: a.description,
-                prompt_template: a.prompt_template,
-                capabilities: serde_json::from_value(a.capabilities).unwrap_or_default(),
-                config: a.config,
-                git_config: a.git_config,
-                status: a.status,
-                current_task_id: a.current_task_id.map(|id| id.to_string()),
-                total_tasks_completed: a.total_tasks_completed,
-                success_rate: a.success_rate,
-                average_completion_time: a.average_completion_time,
-                created_at: a.created_at.to_rfc3339(),
-                updated_at: a.updated_at.to_rfc3339(),
-                last_active_at: a.last_active_at.to_rfc3339(),
-                skill_profile: a.skill_profile,
-                skill_assessments: a.skill_assessments,
-                performance_trend: a.performance_trend,
-            };
+            let result = Agent::try_from(a)?;
             Ok(Some(result))
         }
         None => Ok(None)
     }
 }
 
+/// 获取智能体生效的命令审批策略（未配置时为全局审批行为）
+#[tauri::command]
+pub async fn get_agent_approval_policy(
+    agent_id: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<AgentApprovalPolicy, String> {
+    let agent_uuid = Uuid::parse_str(&agent_id)
+        .map_err(|_| "无效的智能体ID格式")?;
+
+    load_approval_policy(&db, agent_uuid).await
+}
+
+/// 读取智能体配置中的命令审批策略
+pub(crate) async fn load_approval_policy(
+    db: &DatabaseConnection,
+    agent_id: Uuid,
+) -> Result<AgentApprovalPolicy, String> {
+    let agent = codex_database::repository::agent_repository::AgentRepository::new(db.clone())
+        .find_by_id(agent_id).await
+        .map_err(|e| format!("查询智能体失败: {}", e))?
+        .ok_or_else(|| "智能体不存在".to_string())?;
+
+    AgentApprovalPolicy::from_agent_config_json(&agent.config)
+        .map_err(|e| format!("智能体审批策略无效: {}", e))
+}
+
 /// 更新智能体
 #[tauri::command]
 pub async fn update_agent(
@@ -199,27 +193,7 @@ pub async fn update_agent(
         let updated_agent = agent_repo.update_status(agent_uuid, agent_status, None).await
             .map_err(|e| format!("更新智能体状态失败: {}", e))?;
 
-        let result = Agent {
-            agent_id: updated_agent.agent_id.to_string(),
-            user_id: updated_agent.user_id.to_string(),
-            name: updated_agent.name,
-            description: updated_agent.description,
-            prompt_template: updated_agent.prompt_template,
-            capabilities: serde_json::from_value(updated_agent.capabilities).unwrap_or_default(),
-            config: updated_agent.config,
-            git_config: updated_agent.git_config,
-            status: updated_agent.status,
-            current_task_id: updated_agent.current_task_id.map(|id| id.to_string()),
-            total_tasks_completed: updated_agent.total_tasks_completed,
-            success_rate: updated_agent.success_rate,
-            average_completion_time: updated_agent.average_completion_time,
-            created_at: updated_agent.created_at.to_rfc3339(),
-            updated_at: updated_agent.updated_at.to_rfc3339(),
-            last_active_at: updated_agent.last_active_at.to_rfc3339(),
-            skill_profile: updated_agent.skill_profile,
-            skill_assessments: updated_agent.skill_assessments,
-            performance_trend: updated_agent.performance_trend,
-        };
+        let result = Agent::try_from(updated_agent)?;
 
         println!("智能体状态更新成功: {}", result.agent_id);
         Ok(result)
@@ -323,4 +297,81 @@ pub async fn get_agent_performance_metrics(
 
     println!("返回性能指标记录数量: {}", result.len());
     Ok(result)
-}
\ No newline at end of file
+}
+/// 导出智能体为Agent包（JSON文本）
+#[tauri::command]
+pub async fn export_agent_bundle(
+    agent_id: String,
+    version: Option<String>,
+    signing_key: Option<String>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<String, String> {
+    // 验证token并获取当前用户
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    println!("导出智能体包: {}", agent_id);
+
+    let agent_uuid = Uuid::parse_str(&agent_id)
+        .map_err(|_| "无效的智能体ID格式")?;
+
+    let db = &**db;
+    let agent_repo = codex_database::repository::agent_repository::AgentRepository::new(db.clone());
+
+    let agent = agent_repo.find_by_id(agent_uuid).await
+        .map_err(|e| format!("查询智能体失败: {}", e))?
+        .ok_or("智能体不存在")?;
+    if agent.user_id != current_user.user_id {
+        return Err("无权导出该智能体".to_string());
+    }
+
+    let bundle = agent_repo.export_bundle(
+        agent_uuid,
+        version.as_deref().unwrap_or("1.0.0"),
+        Some(current_user.username.clone()),
+        signing_key.as_deref().map(str::as_bytes),
+    ).await
+        .map_err(|e| format!("导出智能体包失败: {}", e))?;
+
+    bundle.to_json().map_err(|e| format!("序列化智能体包失败: {}", e))
+}
+
+/// 导入Agent包，按 on_conflict 处理同名智能体（fail / rename / overwrite）
+#[tauri::command]
+pub async fn import_agent_bundle(
+    bundle_json: String,
+    on_conflict: Option<ImportConflictStrategy>,
+    signing_key: Option<String>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ImportAgentBundleResult, String> {
+    // 验证token并获取当前用户
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let bundle = AgentBundle::from_json(&bundle_json)
+        .map_err(|e| format!("解析智能体包失败: {}", e))?;
+
+    println!("导入智能体包: {} v{} (用户: {})", bundle.name, bundle.version, current_user.username);
+
+    let db = &**db;
+    let agent_repo = codex_database::repository::agent_repository::AgentRepository::new(db.clone());
+
+    let imported = agent_repo.import_bundle(
+        current_user.user_id,
+        &bundle,
+        on_conflict.unwrap_or_default(),
+        signing_key.as_deref().map(str::as_bytes),
+    ).await
+        .map_err(|e| format!("导入智能体包失败: {}", e))?;
+
+    println!("智能体包导入成功: {}", imported.agent.agent_id);
+    Ok(ImportAgentBundleResult {
+        agent: Agent::try_from(imported.agent)?,
+        renamed_from: imported.renamed_from,
+        overwritten: imported.overwritten,
+    })
+}
@@ -0,0 +1,78 @@
+use tauri::State;
+use codex_database::api_keys::{self, ApiScope, IssueApiKey, IssuedApiKey};
+use codex_database::entities::api_key;
+use codex_database::repository::ApiKeyRepository;
+use uuid::Uuid;
+use crate::commands::projects::DatabaseHandle;
+
+/// 为当前用户创建 API 密钥，返回的明文只在创建时可见
+#[tauri::command]
+pub async fn create_api_key(
+    name: String,
+    scopes: Vec<ApiScope>,
+    project_id: Option<String>,
+    rate_limit_per_minute: Option<u32>,
+    expires_in_days: Option<u32>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<IssuedApiKey, String> {
+    let user_id = authenticate(&token, &db).await?;
+    let project_id = project_id
+        .map(|id| Uuid::parse_str(&id).map_err(|_| "无效的项目ID格式"))
+        .transpose()?;
+    let expires_at = expires_in_days
+        .map(|days| chrono::Utc::now() + chrono::Duration::days(i64::from(days)));
+
+    let issued = api_keys::issue_api_key(&db, IssueApiKey {
+        user_id,
+        project_id,
+        name,
+        scopes,
+        rate_limit_per_minute,
+        expires_at,
+    }).await
+        .map_err(|e| format!("创建API密钥失败: {}", e))?;
+    println!("用户 {} 创建了API密钥 {}", user_id, issued.key.key_prefix);
+    Ok(issued)
+}
+
+/// 列出当前用户的 API 密钥及使用计数
+#[tauri::command]
+pub async fn list_api_keys(
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<api_key::Model>, String> {
+    let user_id = authenticate(&token, &db).await?;
+
+    ApiKeyRepository::new((**db).clone()).find_by_user(user_id).await
+        .map_err(|e| format!("获取API密钥失败: {}", e))
+}
+
+/// 吊销当前用户的 API 密钥
+#[tauri::command]
+pub async fn revoke_api_key(
+    key_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<api_key::Model, String> {
+    let user_id = authenticate(&token, &db).await?;
+    let key_uuid = Uuid::parse_str(&key_id)
+        .map_err(|_| "无效的API密钥ID格式")?;
+
+    let repo = ApiKeyRepository::new((**db).clone());
+    let key = repo.find_by_id(key_uuid).await
+        .map_err(|e| format!("获取API密钥失败: {}", e))?
+        .filter(|key| key.user_id == user_id)
+        .ok_or("API密钥不存在")?;
+    let key = repo.revoke(key.key_id).await
+        .map_err(|e| format!("吊销API密钥失败: {}", e))?;
+    println!("API密钥 {} 已吊销", key.key_prefix);
+    Ok(key)
+}
+
+async fn authenticate(token: &str, db: &DatabaseHandle) -> Result<Uuid, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    Ok(current_user.user_id)
+}
@@ -0,0 +1,151 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+use codex_database::artifact_store::ArtifactStore;
+use codex_database::entities::execution_artifact;
+use codex_database::repository::ExecutionArtifactRepository;
+use codex_database::session_diff::{self, SessionDiff};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::authorize_session;
+use crate::commands::projects::DatabaseHandle;
+use crate::models::SessionArtifact;
+use crate::settings::ArtifactSettings;
+
+// 执行工件存储
+pub type ArtifactStoreHandle = Arc<ArtifactStore>;
+
+/// 按设置清理过期的执行工件
+pub async fn apply_artifact_retention(store: &ArtifactStore, settings: &ArtifactSettings) {
+    match store.apply_retention(&settings.retention_policy()).await {
+        Ok(report) if report.removed_artifacts > 0 || report.removed_blobs > 0 => {
+            println!(
+                "已清理 {} 个执行工件，释放 {} 字节",
+                report.removed_artifacts, report.freed_bytes
+            );
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("清理执行工件失败: {}", e),
+    }
+}
+
+/// 列出执行会话的工件
+#[tauri::command]
+pub async fn list_session_artifacts(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<SessionArtifact>, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Viewer).await?;
+
+    let artifacts = ExecutionArtifactRepository::new((**db).clone())
+        .find_by_session_id(session_uuid).await
+        .map_err(|e| format!("查询执行工件失败: {}", e))?;
+
+    Ok(artifacts.into_iter().map(to_session_artifact).collect())
+}
+
+/// 将工件另存到指定路径
+#[tauri::command]
+pub async fn download_artifact(
+    artifact_id: String,
+    destination: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    store: State<'_, ArtifactStoreHandle>,
+) -> Result<(), String> {
+    let (artifact, content) = fetch_authorized(&artifact_id, &token, &db, &store).await?;
+
+    tokio::fs::write(&destination, content).await
+        .map_err(|e| format!("保存工件失败 {}: {}", destination, e))?;
+
+    println!("工件已下载: {} -> {}", artifact.name, destination);
+    Ok(())
+}
+
+/// 使用系统默认程序打开工件
+#[tauri::command]
+pub async fn open_artifact(
+    artifact_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    store: State<'_, ArtifactStoreHandle>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let (artifact, content) = fetch_authorized(&artifact_id, &token, &db, &store).await?;
+
+    // 数据文件没有扩展名，复制到临时目录并保留原文件名，系统才能选择合适的程序
+    let dir = std::env::temp_dir().join("sker-artifacts").join(artifact.artifact_id.to_string());
+    tokio::fs::create_dir_all(&dir).await
+        .map_err(|e| format!("创建临时目录失败: {}", e))?;
+    let path = dir.join(sanitize_file_name(&artifact.name));
+    tokio::fs::write(&path, content).await
+        .map_err(|e| format!("写入临时文件失败: {}", e))?;
+
+    app.opener()
+        .open_path(path.to_string_lossy(), None::<&str>)
+        .map_err(|e| format!("打开工件失败: {}", e))?;
+
+    println!("已打开工件: {}", artifact.name);
+    Ok(())
+}
+
+/// 获取执行会话的结构化代码差异，未采集时返回空
+#[tauri::command]
+pub async fn get_session_diff(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    store: State<'_, ArtifactStoreHandle>,
+) -> Result<Option<SessionDiff>, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Viewer).await?;
+
+    session_diff::get_session_diff(&store, session_uuid).await
+        .map_err(|e| format!("读取代码差异失败: {}", e))
+}
+
+/// 读取工件内容，并校验访问权限
+async fn fetch_authorized(
+    artifact_id: &str,
+    token: &str,
+    db: &DatabaseHandle,
+    store: &ArtifactStore,
+) -> Result<(execution_artifact::Model, Vec<u8>), String> {
+    let artifact_uuid = Uuid::parse_str(artifact_id)
+        .map_err(|_| "无效的工件ID格式")?;
+    let artifact = ExecutionArtifactRepository::new((**db).clone())
+        .find_by_id(artifact_uuid).await
+        .map_err(|e| format!("查询执行工件失败: {}", e))?
+        .ok_or_else(|| format!("工件不存在: {}", artifact_id))?;
+
+    authorize_session(&artifact.session_id.to_string(), token, db, ProjectRole::Viewer).await?;
+
+    store.fetch(artifact_uuid).await
+        .map_err(|e| format!("读取工件失败: {}", e))
+}
+
+/// 去掉文件名中的路径分隔符，防止写到临时目录之外
+fn sanitize_file_name(name: &str) -> PathBuf {
+    let sanitized: String = name
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':') { '_' } else { c })
+        .collect();
+    match sanitized.trim_start_matches('.') {
+        "" => PathBuf::from("artifact"),
+        name => PathBuf::from(name),
+    }
+}
+
+fn to_session_artifact(artifact: execution_artifact::Model) -> SessionArtifact {
+    SessionArtifact {
+        artifact_id: artifact.artifact_id.to_string(),
+        session_id: artifact.session_id.to_string(),
+        name: artifact.name,
+        artifact_type: artifact.artifact_type,
+        size_bytes: artifact.size_bytes as u64,
+        checksum: artifact.checksum,
+        description: artifact.description,
+        created_at: artifact.created_at.to_rfc3339(),
+    }
+}
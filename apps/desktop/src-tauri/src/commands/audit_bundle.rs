@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+use tauri::State;
+use codex_database::audit_bundle::{self, AuditBundleManifest};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::require_project_role;
+use crate::commands::projects::DatabaseHandle;
+
+/// 导出项目审计包（zip），返回归档清单
+#[tauri::command]
+pub async fn export_project_audit(
+    project_id: String,
+    path: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<AuditBundleManifest, String> {
+    let project_uuid = Uuid::parse_str(&project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    require_project_role(&db, project_uuid, current_user.user_id, ProjectRole::Maintainer).await?;
+
+    let manifest = audit_bundle::export_project_audit(&db, project_uuid, &PathBuf::from(&path), chrono::Utc::now())
+        .await
+        .map_err(|e| format!("导出审计包失败: {}", e))?;
+
+    println!("已导出项目审计包: {} -> {}", manifest.project_name, path);
+    Ok(manifest)
+}
@@ -0,0 +1,80 @@
+use tauri::State;
+use codex_database::blackboard::{self, BlackboardEntry, EntryAuthor, WriteEntryData};
+use codex_database::entities::blackboard_entry;
+use codex_multi_agent::{ProjectRole, SharedContextCategory};
+use crate::commands::members::{authorize_project, authorize_project_user, parse_project_id};
+use crate::commands::projects::DatabaseHandle;
+
+/// 列出项目黑板的全部条目
+#[tauri::command]
+pub async fn list_blackboard_entries(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<BlackboardEntry>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    blackboard::list_entries(&db, project_uuid).await
+        .map_err(|e| format!("获取黑板条目失败: {}", e))
+}
+
+/// 获取黑板条目的历史版本
+#[tauri::command]
+pub async fn get_blackboard_entry_history(
+    project_id: String,
+    key: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<blackboard_entry::Model>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    blackboard::entry_history(&db, project_uuid, &key).await
+        .map_err(|e| format!("获取黑板条目历史失败: {}", e))
+}
+
+/// 写入黑板条目，`expected_version` 为上次读取到的版本，新建时为空
+#[tauri::command]
+pub async fn write_blackboard_entry(
+    project_id: String,
+    key: String,
+    category: SharedContextCategory,
+    value: String,
+    include_in_prompts: bool,
+    expected_version: Option<u32>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<BlackboardEntry, String> {
+    let project_uuid = parse_project_id(&project_id)?;
+    let user_id = authorize_project_user(project_uuid, &token, &db, ProjectRole::Contributor).await?.user_id;
+
+    let entry = blackboard::write_entry(&db, WriteEntryData {
+        project_id: project_uuid,
+        key,
+        category,
+        value,
+        include_in_prompts,
+        expected_version,
+        author: EntryAuthor::User(user_id),
+    }).await
+        .map_err(|e| format!("写入黑板条目失败: {}", e))?;
+    println!("项目 {} 黑板条目 {} 已更新到版本 {}", project_id, entry.key, entry.version);
+    Ok(entry)
+}
+
+/// 删除黑板条目
+#[tauri::command]
+pub async fn delete_blackboard_entry(
+    project_id: String,
+    key: String,
+    expected_version: u32,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<(), String> {
+    let project_uuid = parse_project_id(&project_id)?;
+    let user_id = authorize_project_user(project_uuid, &token, &db, ProjectRole::Contributor).await?.user_id;
+
+    blackboard::delete_entry(&db, project_uuid, &key, expected_version, EntryAuthor::User(user_id)).await
+        .map_err(|e| format!("删除黑板条目失败: {}", e))?;
+    println!("项目 {} 黑板条目 {} 已删除", project_id, key);
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use tauri::State;
+use codex_database::session_checkpoint::{self, CheckpointData, SessionCheckpoint};
+use codex_multi_agent::ProjectRole;
+use serde::Serialize;
+use uuid::Uuid;
+use crate::commands::members::authorize_session;
+use crate::commands::projects::DatabaseHandle;
+
+/// 恢复执行会话的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ResumedSessionInfo {
+    pub session_id: String,
+    pub task_id: String,
+    pub agent_id: String,
+    pub git_branch: String,
+    pub base_commit: Option<String>,
+    pub checkpoint: SessionCheckpoint,
+    pub prompt: String,
+}
+
+/// 为运行中的执行会话保存检查点
+#[tauri::command]
+pub async fn save_session_checkpoint(
+    session_id: String,
+    checkpoint: CheckpointData,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<SessionCheckpoint, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Contributor).await?;
+
+    session_checkpoint::save_checkpoint(&db, session_uuid, checkpoint).await
+        .map_err(|e| format!("保存检查点失败: {}", e))
+}
+
+/// 获取执行会话最近一次检查点，没有检查点时返回 None
+#[tauri::command]
+pub async fn get_session_checkpoint(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<SessionCheckpoint>, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Viewer).await?;
+
+    session_checkpoint::latest_checkpoint(&db, session_uuid).await
+        .map_err(|e| format!("获取检查点失败: {}", e))
+}
+
+/// 从最近一次检查点恢复已中断的执行会话，未指定Agent时交给原Agent继续
+#[tauri::command]
+pub async fn resume_execution_session(
+    session_id: String,
+    agent_id: Option<String>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ResumedSessionInfo, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Contributor).await?;
+    let agent_uuid = agent_id
+        .map(|id| Uuid::parse_str(&id).map_err(|_| "无效的Agent ID格式"))
+        .transpose()?;
+
+    let resumed = session_checkpoint::resume_session(&db, session_uuid, agent_uuid).await
+        .map_err(|e| format!("恢复执行会话失败: {}", e))?;
+    println!("执行会话 {} 已从检查点恢复为 {}", session_id, resumed.session.session_id);
+    Ok(ResumedSessionInfo {
+        session_id: resumed.session.session_id.to_string(),
+        task_id: resumed.session.task_id.to_string(),
+        agent_id: resumed.session.agent_id.to_string(),
+        git_branch: resumed.session.git_branch,
+        base_commit: resumed.session.base_commit,
+        checkpoint: resumed.checkpoint,
+        prompt: resumed.prompt,
+    })
+}
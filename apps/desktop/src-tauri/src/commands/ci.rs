@@ -0,0 +1,133 @@
+use tauri::State;
+use codex_database::ci_integration::{self, CiProvider};
+use codex_database::entities::{ci_check_run, code_review};
+use codex_database::entities::code_review::ReviewDecision;
+use codex_database::repository::{CiCheckRunRepository, CodeReviewRepository, TaskRepository};
+use codex_multi_agent::{CiStatus, ProjectRole};
+use uuid::Uuid;
+use crate::commands::members::authorize_project;
+use crate::commands::projects::DatabaseHandle;
+use crate::settings::SettingsManager;
+
+/// 默认返回的检查结果条数
+const DEFAULT_CHECK_LIMIT: u64 = 50;
+
+/// 接收转发的 CI webhook，写入检查结果
+///
+/// `event` 为 GitHub 的 X-GitHub-Event；`signature` 为 GitHub 的 X-Hub-Signature-256
+/// 或 GitLab 的 X-Gitlab-Token，设置中配置了密钥时必须提供。不相关的事件返回 None
+#[tauri::command]
+pub async fn ingest_ci_webhook(
+    project_id: String,
+    provider: CiProvider,
+    event: Option<String>,
+    payload: String,
+    signature: Option<String>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<ci_check_run::Model>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Contributor).await?;
+
+    let settings = SettingsManager::new()
+        .map_err(|e| format!("创建设置管理器失败: {}", e))?
+        .load_settings().await
+        .map_err(|e| format!("加载设置失败: {}", e))?;
+    let ci_settings = settings.system.ci;
+    let secret = match provider {
+        CiProvider::GithubActions => ci_settings.github_webhook_secret,
+        CiProvider::GitlabCi => ci_settings.gitlab_webhook_token,
+    };
+    if let Some(secret) = secret.filter(|secret| !secret.is_empty()) {
+        let signature = signature.ok_or("缺少 webhook 签名")?;
+        match provider {
+            CiProvider::GithubActions => ci_integration::verify_github_signature(&secret, payload.as_bytes(), &signature),
+            CiProvider::GitlabCi => ci_integration::verify_gitlab_token(&secret, &signature),
+        }
+        .map_err(|e| e.to_string())?;
+    }
+
+    let payload: serde_json::Value = serde_json::from_str(&payload)
+        .map_err(|e| format!("webhook 载荷不是有效的JSON: {}", e))?;
+    let update = match provider {
+        CiProvider::GithubActions => {
+            let event = event.ok_or("缺少 GitHub 事件类型")?;
+            ci_integration::parse_github_webhook(&event, &payload)
+        }
+        CiProvider::GitlabCi => ci_integration::parse_gitlab_webhook(&payload),
+    }
+    .map_err(|e| format!("解析 webhook 失败: {}", e))?;
+
+    let Some(update) = update else {
+        return Ok(None);
+    };
+    let run = ci_integration::ingest_check(&db, project_uuid, update).await
+        .map_err(|e| format!("写入 CI 检查结果失败: {}", e))?;
+    println!("CI 检查 {} ({}) 状态: {}", run.name, run.commit_sha, run.status);
+    Ok(Some(run))
+}
+
+/// 列出项目最近的 CI 检查结果
+#[tauri::command]
+pub async fn list_ci_checks(
+    project_id: String,
+    limit: Option<u64>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<ci_check_run::Model>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    CiCheckRunRepository::new((**db).clone())
+        .find_by_project(project_uuid, limit.unwrap_or(DEFAULT_CHECK_LIMIT)).await
+        .map_err(|e| format!("获取 CI 检查结果失败: {}", e))
+}
+
+/// 获取任务的 CI 状态，尚无检查结果时返回 None
+#[tauri::command]
+pub async fn get_task_ci_status(
+    task_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<CiStatus>, String> {
+    let task_uuid = Uuid::parse_str(&task_id)
+        .map_err(|_| "无效的任务ID格式")?;
+    let task = TaskRepository::new((**db).clone()).find_by_id(task_uuid).await
+        .map_err(|e| format!("获取任务失败: {}", e))?
+        .ok_or("任务不存在")?;
+    authorize_project(&task.project_id.to_string(), &token, &db, ProjectRole::Viewer).await?;
+
+    ci_integration::task_ci_status(&db, task_uuid).await
+        .map_err(|e| format!("获取 CI 状态失败: {}", e))
+}
+
+/// 提交代码审查决策，CI 为红色且质量门禁要求所有检查通过时拒绝批准
+#[tauri::command]
+pub async fn submit_review_decision(
+    review_id: String,
+    decision: String,
+    overall_comment: Option<String>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<code_review::Model, String> {
+    let review_uuid = Uuid::parse_str(&review_id)
+        .map_err(|_| "无效的审查ID格式")?;
+    let decision = match decision.as_str() {
+        "approved" => ReviewDecision::Approved,
+        "changes_requested" => ReviewDecision::ChangesRequested,
+        "rejected" => ReviewDecision::Rejected,
+        other => return Err(format!("无效的审查决策: {}", other)),
+    };
+
+    let reviews = CodeReviewRepository::new((**db).clone());
+    let review = reviews.find_by_id(review_uuid).await
+        .map_err(|e| format!("获取代码审查失败: {}", e))?
+        .ok_or("代码审查不存在")?;
+    let task = TaskRepository::new((**db).clone()).find_by_id(review.task_id).await
+        .map_err(|e| format!("获取任务失败: {}", e))?
+        .ok_or("任务不存在")?;
+    authorize_project(&task.project_id.to_string(), &token, &db, ProjectRole::Contributor).await?;
+
+    let review = reviews.submit_decision(review_uuid, decision, overall_comment).await
+        .map_err(|e| format!("提交审查决策失败: {}", e))?;
+    println!("代码审查 {} 决策: {}", review_id, review.decision.as_deref().unwrap_or_default());
+    Ok(review)
+}
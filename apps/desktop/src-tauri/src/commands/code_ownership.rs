@@ -0,0 +1,74 @@
+use tauri::State;
+use codex_database::code_ownership::{self, CodeOwnership};
+use codex_database::repository::ProjectRepository;
+use codex_database::review_sla::Reviewer;
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::authorize_project;
+use crate::commands::projects::DatabaseHandle;
+
+/// 获取项目的代码归属规则，未配置时返回 None
+#[tauri::command]
+pub async fn get_project_code_ownership(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<CodeOwnership>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    code_ownership::project_ownership(&db, project_uuid).await
+        .map_err(|e| format!("获取代码归属规则失败: {}", e))
+}
+
+/// 更新项目的代码归属规则，传入 None 清除规则
+#[tauri::command]
+pub async fn update_project_code_ownership(
+    project_id: String,
+    ownership: Option<CodeOwnership>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<(), String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    ProjectRepository::new((**db).clone()).set_code_ownership(project_uuid, ownership).await
+        .map_err(|e| format!("更新代码归属规则失败: {}", e))?;
+    println!("已更新项目代码归属规则: {}", project_id);
+    Ok(())
+}
+
+/// 导入 CODEOWNERS 格式的归属规则，替换项目现有规则
+#[tauri::command]
+pub async fn import_project_codeowners(
+    project_id: String,
+    content: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<CodeOwnership, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    let ownership = CodeOwnership::parse(&content)
+        .map_err(|e| format!("解析CODEOWNERS失败: {}", e))?;
+    ProjectRepository::new((**db).clone()).set_code_ownership(project_uuid, Some(ownership.clone())).await
+        .map_err(|e| format!("更新代码归属规则失败: {}", e))?;
+    println!("已导入项目代码归属规则: {}，共 {} 条", project_id, ownership.rules.len());
+    Ok(ownership)
+}
+
+/// 按代码归属为修改了指定文件的变更推荐审查者
+#[tauri::command]
+pub async fn suggest_code_reviewers(
+    project_id: String,
+    files: Vec<String>,
+    author_agent_id: Option<String>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<Reviewer>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+    let author_agent_id = author_agent_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|_| "无效的智能体ID格式")?;
+
+    code_ownership::suggest_reviewers(&db, project_uuid, &files, author_agent_id).await
+        .map_err(|e| format!("推荐审查者失败: {}", e))
+}
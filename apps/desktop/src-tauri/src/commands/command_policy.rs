@@ -0,0 +1,36 @@
+use std::sync::Arc;
+use tauri::State;
+use codex_database::command_policy::CommandPolicyEngine;
+use codex_database::entities::command_audit_log;
+use codex_database::repository::CommandAuditLogRepository;
+use uuid::Uuid;
+use crate::commands::projects::DatabaseHandle;
+
+// 命令策略引擎
+pub type CommandPolicyEngineHandle = Arc<CommandPolicyEngine>;
+
+/// 默认返回的审计记录条数
+const DEFAULT_AUDIT_LIMIT: u64 = 100;
+
+/// 查询命令审批审计日志，按对话或智能体过滤
+#[tauri::command]
+pub async fn list_command_audit_logs(
+    conversation_id: Option<String>,
+    agent_id: Option<String>,
+    limit: Option<u64>,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<command_audit_log::Model>, String> {
+    let repo = CommandAuditLogRepository::new((**db).clone());
+
+    let entries = match (conversation_id, agent_id) {
+        (Some(conversation_id), _) => repo.find_by_conversation(&conversation_id).await,
+        (None, Some(agent_id)) => {
+            let agent_uuid = Uuid::parse_str(&agent_id)
+                .map_err(|_| "无效的智能体ID格式")?;
+            repo.find_by_agent(agent_uuid, limit.unwrap_or(DEFAULT_AUDIT_LIMIT)).await
+        }
+        (None, None) => return Err("需要指定对话ID或智能体ID".to_string()),
+    };
+
+    entries.map_err(|e| format!("获取命令审计日志失败: {}", e))
+}
@@ -1,25 +1,15 @@
 use codex_core::config::{Config, ConfigOverrides, ConfigToml};
+use codex_core::config_types::SandboxWorkspaceWrite;
 use codex_database::{DatabaseConnection, DatabaseConfig, initialize_database};
+use codex_protocol::config_types::{ReasoningEffort, SandboxMode};
 use crate::settings::{SettingsManager, ApiProvider};
+use codex_multi_agent::AgentApprovalPolicy;
+use crate::models::ConversationOverrides;
 
 /// 创建数据库连接的辅助函数
 pub async fn create_database_connection() -> Result<DatabaseConnection, String> {
-    // 创建数据库配置目录
-    let data_dir = std::env::var("SKER_DATA_HOME")
-        .map(|h| std::path::PathBuf::from(h))
-        .unwrap_or_else(|_| {
-            let home = std::env::var("HOME")
-                .or_else(|_| std::env::var("USERPROFILE"))
-                .unwrap_or_else(|_| std::env::temp_dir().to_string_lossy().to_string());
-            std::path::PathBuf::from(home).join(".sker")
-        });
-    
-    // 确保目录存在
-    std::fs::create_dir_all(&data_dir)
-        .map_err(|e| format!("创建数据目录失败: {}", e))?;
-    
-    // 数据库文件路径
-    let db_path = data_dir.join("sker.db");
+    // 当前档案的数据库文件（SKER_DATA_HOME 仍可覆盖档案目录）
+    let db_path = crate::profiles::database_path();
     let database_url = format!("sqlite://{}?mode=rwc", db_path.display());
     
     // 创建数据库配置
@@ -30,6 +20,7 @@ pub async fn create_database_connection() -> Result<DatabaseConnection, String>
         connect_timeout: 30,
         idle_timeout: 300,
         enable_logging: false,
+        ..Default::default()
     };
     
     // 初始化数据库
@@ -39,6 +30,14 @@ pub async fn create_database_connection() -> Result<DatabaseConnection, String>
 
 /// 创建配置的辅助函数
 pub async fn create_config() -> Result<Config, String> {
+    create_config_with_overrides(None, None).await
+}
+
+/// 创建配置，并应用对话级参数覆盖
+pub async fn create_config_with_overrides(
+    overrides: Option<&ConversationOverrides>,
+    approval_policy: Option<&AgentApprovalPolicy>,
+) -> Result<Config, String> {
     // 创建配置目录
     let codex_home = std::env::var("SKER_HOME")
         .map(|h| std::path::PathBuf::from(h))
@@ -61,7 +60,8 @@ pub async fn create_config() -> Result<Config, String> {
         return Err("未配置API密钥！请在设置中配置API密钥".to_string());
     }
     
-    // 设置环境变量
+    // 设置环境变量，代理和自定义 CA 证书也通过环境变量传给 codex-core
+    crate::network::apply_settings(&app_settings.system);
     match api_config.provider {
         ApiProvider::Openai => {
             std::env::set_var("OPENAI_API_KEY", &api_config.api_key);
@@ -92,13 +92,38 @@ pub async fn create_config() -> Result<Config, String> {
     let mut config_toml = ConfigToml::default();
     config_toml.mcp_servers = mcp_servers;
     
+    let mut config_overrides = ConfigOverrides {
+        show_raw_agent_reasoning: Some(show_raw_reasoning),
+        ..Default::default()
+    };
+    
+    // 应用对话级覆盖，仅影响当前对话的配置
+    if let Some(overrides) = overrides {
+        config_overrides.model = overrides.model.clone();
+        if let Some(effort) = &overrides.reasoning_effort {
+            config_toml.model_reasoning_effort = Some(parse_reasoning_effort(effort)?);
+        }
+        if let Some(sandbox) = &overrides.sandbox_policy {
+            config_overrides.sandbox_mode = Some(parse_sandbox_mode(sandbox)?);
+        }
+        if overrides.temperature.is_some() {
+            // Codex配置暂不支持temperature，仅随对话持久化
+            println!("对话temperature覆盖已保存，当前模型客户端暂不支持该参数");
+        }
+    }
+    
+    // 智能体审批策略决定沙箱内能否访问网络
+    if let Some(policy) = approval_policy {
+        config_toml.sandbox_workspace_write = Some(SandboxWorkspaceWrite {
+            network_access: policy.network_access,
+            ..Default::default()
+        });
+    }
+    
     println!("开始创建Codex配置...");
     Config::load_from_base_config_with_overrides(
         config_toml,
-        ConfigOverrides {
-            show_raw_agent_reasoning: Some(show_raw_reasoning),
-            ..Default::default()
-        },
+        config_overrides,
         codex_home,
     ).map_err(|e| {
         eprintln!("创建配置失败详情: {:#}", e);
@@ -106,6 +131,27 @@ pub async fn create_config() -> Result<Config, String> {
     })
 }
 
+/// 解析推理强度
+pub fn parse_reasoning_effort(value: &str) -> Result<ReasoningEffort, String> {
+    match value {
+        "minimal" => Ok(ReasoningEffort::Minimal),
+        "low" => Ok(ReasoningEffort::Low),
+        "medium" => Ok(ReasoningEffort::Medium),
+        "high" => Ok(ReasoningEffort::High),
+        _ => Err(format!("无效的推理强度: {}", value)),
+    }
+}
+
+/// 解析沙箱策略
+pub fn parse_sandbox_mode(value: &str) -> Result<SandboxMode, String> {
+    match value {
+        "read-only" => Ok(SandboxMode::ReadOnly),
+        "workspace-write" => Ok(SandboxMode::WorkspaceWrite),
+        "danger-full-access" => Ok(SandboxMode::DangerFullAccess),
+        _ => Err(format!("无效的沙箱策略: {}", value)),
+    }
+}
+
 /// 从应用设置中获取 MCP 服务器配置
 fn get_mcp_servers_from_settings(
     app_settings: &crate::settings::AppSettings,
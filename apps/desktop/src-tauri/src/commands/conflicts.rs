@@ -0,0 +1,344 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use tauri::{AppHandle, Emitter, State};
+use codex_database::entities::conflict::{self, ConflictSeverity, ConflictStatus, ConflictType};
+use codex_database::entities::human_decision::{self, DecisionType};
+use codex_database::repository::{ConflictRepository, HumanDecisionRepository, TaskRepository};
+use codex_database::repository::conflict_repository::ConflictFilter;
+use codex_database::repository::human_decision_repository::CreateHumanDecisionData;
+use codex_database::DatabaseConnection;
+use codex_multi_agent::ProjectRole;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::commands::members::require_project_role;
+use crate::commands::projects::DatabaseHandle;
+
+/// 冲突变更后通知前端刷新的事件名
+const CONFLICT_UPDATED_EVENT: &str = "conflict_updated";
+
+/// 默认分页大小
+const DEFAULT_PAGE_SIZE: u64 = 20;
+
+/// 最大分页大小
+const MAX_PAGE_SIZE: u64 = 100;
+
+/// 冲突列表过滤条件，取值与数据库中的存储名称一致
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConflictListFilter {
+    pub conflict_type: Option<String>,
+    pub severity: Option<String>,
+    pub status: Option<String>,
+    pub escalated_to_human: Option<bool>,
+}
+
+/// 人工决策输入
+#[derive(Debug, Clone, Deserialize)]
+pub struct HumanDecisionInput {
+    /// approve / reject / modify / escalate
+    pub decision_type: String,
+    pub decision_data: Option<serde_json::Value>,
+    pub reasoning: Option<String>,
+    #[serde(default)]
+    pub follow_up_actions: Vec<String>,
+}
+
+/// 冲突
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictInfo {
+    pub conflict_id: String,
+    pub conflict_type: String,
+    pub severity: String,
+    pub title: String,
+    pub description: String,
+    pub related_entities: serde_json::Value,
+    pub affected_tasks: Vec<String>,
+    pub affected_agents: Vec<String>,
+    pub status: String,
+    pub escalated_to_human: bool,
+    pub assigned_user_id: Option<String>,
+    pub resolution_strategy: Option<String>,
+    pub resolution_note: Option<String>,
+    pub auto_resolved: bool,
+    pub detected_at: String,
+    pub escalated_at: Option<String>,
+    pub resolved_at: Option<String>,
+}
+
+impl From<conflict::Model> for ConflictInfo {
+    fn from(conflict: conflict::Model) -> Self {
+        Self {
+            affected_tasks: string_list(&conflict.affected_tasks),
+            affected_agents: string_list(&conflict.affected_agents),
+            conflict_id: conflict.conflict_id.to_string(),
+            conflict_type: conflict.conflict_type,
+            severity: conflict.severity,
+            title: conflict.title,
+            description: conflict.description,
+            related_entities: conflict.related_entities,
+            status: conflict.status,
+            escalated_to_human: conflict.escalated_to_human,
+            assigned_user_id: conflict.assigned_user_id.map(|id| id.to_string()),
+            resolution_strategy: conflict.resolution_strategy,
+            resolution_note: conflict.resolution_note,
+            auto_resolved: conflict.auto_resolved,
+            detected_at: conflict.detected_at.to_rfc3339(),
+            escalated_at: conflict.escalated_at.map(|t| t.to_rfc3339()),
+            resolved_at: conflict.resolved_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// 人工决策
+#[derive(Debug, Clone, Serialize)]
+pub struct HumanDecisionInfo {
+    pub decision_id: String,
+    pub conflict_id: String,
+    pub user_id: String,
+    pub decision_type: String,
+    pub decision_data: Option<serde_json::Value>,
+    pub reasoning: Option<String>,
+    pub affected_entities: serde_json::Value,
+    pub follow_up_actions: serde_json::Value,
+    pub created_at: String,
+}
+
+impl From<human_decision::Model> for HumanDecisionInfo {
+    fn from(decision: human_decision::Model) -> Self {
+        Self {
+            decision_id: decision.decision_id.to_string(),
+            conflict_id: decision.conflict_id.to_string(),
+            user_id: decision.user_id.to_string(),
+            decision_type: decision.decision_type,
+            decision_data: decision.decision_data,
+            reasoning: decision.reasoning,
+            affected_entities: decision.affected_entities,
+            follow_up_actions: decision.follow_up_actions,
+            created_at: decision.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// 冲突分页结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictPage {
+    pub conflicts: Vec<ConflictInfo>,
+    pub page: u64,
+    pub page_size: u64,
+    pub total_pages: u64,
+}
+
+/// 冲突详情
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictDetail {
+    pub conflict: ConflictInfo,
+    /// 人工决策，按时间倒序
+    pub decisions: Vec<HumanDecisionInfo>,
+}
+
+/// 分页列出影响项目任务的冲突，页码从0开始
+#[tauri::command]
+pub async fn list_conflicts(
+    project_id: String,
+    filter: Option<ConflictListFilter>,
+    page: Option<u64>,
+    page_size: Option<u64>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ConflictPage, String> {
+    let project_uuid = Uuid::parse_str(&project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    let user_id = authenticate(&token, &db).await?;
+    require_project_role(&db, project_uuid, user_id, ProjectRole::Viewer).await?;
+
+    let filter = filter.unwrap_or_default();
+    let filter = ConflictFilter {
+        conflict_type: filter.conflict_type.as_deref().map(ConflictType::from_str).transpose()?,
+        severity: filter.severity.as_deref().map(ConflictSeverity::from_str).transpose()?,
+        status: filter.status.as_deref().map(ConflictStatus::from_str).transpose()?,
+        escalated_to_human: filter.escalated_to_human,
+        project_id: Some(project_uuid),
+    };
+    let page = page.unwrap_or(0);
+    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let (conflicts, total_pages) = ConflictRepository::new((**db).clone())
+        .find_with_pagination(page, page_size, Some(filter)).await
+        .map_err(|e| format!("查询冲突失败: {}", e))?;
+    Ok(ConflictPage {
+        conflicts: conflicts.into_iter().map(ConflictInfo::from).collect(),
+        page,
+        page_size,
+        total_pages,
+    })
+}
+
+/// 获取冲突详情及其人工决策
+#[tauri::command]
+pub async fn get_conflict_detail(
+    conflict_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ConflictDetail, String> {
+    let (conflict, _) = authorize_conflict(&conflict_id, &token, &db, ProjectRole::Viewer).await?;
+
+    let decisions = HumanDecisionRepository::new((**db).clone())
+        .find_by_conflict_id(conflict.conflict_id).await
+        .map_err(|e| format!("查询人工决策失败: {}", e))?;
+    Ok(ConflictDetail {
+        conflict: conflict.into(),
+        decisions: decisions.into_iter().map(HumanDecisionInfo::from).collect(),
+    })
+}
+
+/// 人工解决冲突
+#[tauri::command]
+pub async fn resolve_conflict(
+    conflict_id: String,
+    resolution_strategy: String,
+    resolution_note: Option<String>,
+    token: String,
+    app: AppHandle,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ConflictInfo, String> {
+    let (conflict, user_id) = authorize_conflict(&conflict_id, &token, &db, ProjectRole::Contributor).await?;
+    ensure_open(&conflict)?;
+    if resolution_strategy.trim().is_empty() {
+        return Err("解决策略不能为空".to_string());
+    }
+
+    let resolved = ConflictRepository::new((**db).clone())
+        .resolve_conflict(conflict.conflict_id, resolution_strategy, resolution_note, false).await
+        .map_err(|e| format!("解决冲突失败: {}", e))?;
+    println!("用户 {} 已解决冲突: {}", user_id, conflict_id);
+    Ok(emit_conflict_updated(&app, resolved))
+}
+
+/// 把冲突上报给人工处理，可指定负责人
+#[tauri::command]
+pub async fn escalate_conflict(
+    conflict_id: String,
+    assigned_user_id: Option<String>,
+    token: String,
+    app: AppHandle,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ConflictInfo, String> {
+    let (conflict, _) = authorize_conflict(&conflict_id, &token, &db, ProjectRole::Contributor).await?;
+    ensure_open(&conflict)?;
+    let assigned_user_id = assigned_user_id
+        .map(|id| Uuid::parse_str(&id).map_err(|_| "无效的用户ID格式"))
+        .transpose()?;
+
+    let escalated = ConflictRepository::new((**db).clone())
+        .escalate_to_human(conflict.conflict_id, assigned_user_id).await
+        .map_err(|e| format!("上报冲突失败: {}", e))?;
+    Ok(emit_conflict_updated(&app, escalated))
+}
+
+/// 记录人工决策（approve / reject / modify / escalate）
+///
+/// escalate 决策会上报冲突；其他决策表示已有人介入，未处理的冲突进入解决中状态
+#[tauri::command]
+pub async fn record_human_decision(
+    conflict_id: String,
+    decision: HumanDecisionInput,
+    token: String,
+    app: AppHandle,
+    db: State<'_, DatabaseHandle>,
+) -> Result<HumanDecisionInfo, String> {
+    let (conflict, user_id) = authorize_conflict(&conflict_id, &token, &db, ProjectRole::Contributor).await?;
+    ensure_open(&conflict)?;
+    if !matches!(decision.decision_type.as_str(), "approve" | "reject" | "modify" | "escalate") {
+        return Err(format!("无效的决策类型: {}", decision.decision_type));
+    }
+    let decision_type = DecisionType::from(decision.decision_type);
+
+    let recorded = HumanDecisionRepository::new((**db).clone())
+        .create(CreateHumanDecisionData {
+            conflict_id: conflict.conflict_id,
+            user_id,
+            decision_type: decision_type.to_string(),
+            decision_data: decision.decision_data,
+            reasoning: decision.reasoning,
+            affected_entities: conflict.related_entities.clone(),
+            follow_up_actions: serde_json::json!(decision.follow_up_actions),
+        }).await
+        .map_err(|e| format!("记录人工决策失败: {}", e))?;
+
+    let conflicts = ConflictRepository::new((**db).clone());
+    let updated = match decision_type {
+        DecisionType::Escalate => Some(conflicts.escalate_to_human(conflict.conflict_id, conflict.assigned_user_id).await),
+        _ if conflict.status != ConflictStatus::Resolving.to_string() => {
+            Some(conflicts.update_status(conflict.conflict_id, ConflictStatus::Resolving).await)
+        }
+        _ => None,
+    };
+    if let Some(updated) = updated {
+        let updated = updated.map_err(|e| format!("更新冲突状态失败: {}", e))?;
+        emit_conflict_updated(&app, updated);
+    }
+    Ok(recorded.into())
+}
+
+/// 解析JSON字符串数组
+fn string_list(value: &serde_json::Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| items.iter().filter_map(|item| item.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+fn ensure_open(conflict: &conflict::Model) -> Result<(), String> {
+    if conflict.status == ConflictStatus::Resolved.to_string() || conflict.status == ConflictStatus::Ignored.to_string() {
+        return Err(format!("冲突已{}，不能再处理", conflict.status));
+    }
+    Ok(())
+}
+
+fn emit_conflict_updated(app: &AppHandle, conflict: conflict::Model) -> ConflictInfo {
+    let info = ConflictInfo::from(conflict);
+    if let Err(e) = app.emit(CONFLICT_UPDATED_EVENT, &info) {
+        eprintln!("发送冲突更新事件失败: {e}");
+    }
+    info
+}
+
+async fn authenticate(token: &str, db: &DatabaseConnection) -> Result<Uuid, String> {
+    let auth_service = crate::auth::AuthService::new(db.clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    Ok(current_user.user_id)
+}
+
+/// 校验令牌，并要求当前用户在冲突影响的每个项目中具有指定角色
+///
+/// 冲突没有直接关联项目，项目取自受影响的任务；未关联任务的冲突只校验身份
+async fn authorize_conflict(
+    conflict_id: &str,
+    token: &str,
+    db: &DatabaseHandle,
+    role: ProjectRole,
+) -> Result<(conflict::Model, Uuid), String> {
+    let conflict_uuid = Uuid::parse_str(conflict_id)
+        .map_err(|_| "无效的冲突ID格式")?;
+    let user_id = authenticate(token, db).await?;
+    let conflict = ConflictRepository::new((**db).clone())
+        .find_by_id(conflict_uuid).await
+        .map_err(|e| format!("获取冲突失败: {}", e))?
+        .ok_or("冲突不存在")?;
+
+    let tasks = TaskRepository::new((**db).clone());
+    let mut project_ids = HashSet::new();
+    for task_id in string_list(&conflict.affected_tasks) {
+        let Ok(task_uuid) = Uuid::parse_str(&task_id) else {
+            continue;
+        };
+        if let Some(task) = tasks.find_by_id(task_uuid).await.map_err(|e| format!("获取任务失败: {}", e))? {
+            project_ids.insert(task.project_id);
+        }
+    }
+    for project_id in project_ids {
+        require_project_role(db, project_id, user_id, role).await?;
+    }
+    Ok((conflict, user_id))
+}
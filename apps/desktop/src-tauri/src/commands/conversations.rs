@@ -1,11 +1,23 @@
-use tauri::{State, Emitter, AppHandle};
+use tauri::{State, Emitter, AppHandle, Manager};
 use std::sync::Arc;
 use codex_core::{ConversationManager, NewConversation};
-use codex_core::protocol::{Op, InputItem, EventMsg};
+use codex_core::protocol::{Op, InputItem, EventMsg, ExecOutputStream, ReviewDecision, SandboxPolicy};
+use codex_database::{command_policy::{CommandDecision, CommandRequest}, git_ops};
+use codex_database::llm_transcripts::{record_transcript, LlmCall, LlmCallKind, TranscriptLink};
+use std::time::Instant;
+use codex_multi_agent::AgentApprovalPolicy;
+use uuid::Uuid;
+use codex_protocol::config_types::SandboxMode;
 use codex_protocol::mcp_protocol::ConversationId;
 use crate::{
-    models::{Conversation, SendMessageRequest},
-    commands::config::create_config,
+    models::{Conversation, Message, SendMessageRequest, ConversationOverrides, ConversationRecord, UpdateConversationSettingsRequest},
+    commands::config::{create_config_with_overrides, parse_reasoning_effort, parse_sandbox_mode},
+    conversation_store::ConversationStore,
+    settings::SettingsManager,
+    commands::patches::{PendingPatchesHandle, record_pending_patch, take_pending_patch},
+    commands::agents::load_approval_policy,
+    commands::{CommandPolicyEngineHandle, DatabaseHandle},
+    commands::log_stream::{LogPipelineHandle, push_command_output},
 };
 
 // 全局对话管理器
@@ -14,12 +26,16 @@ pub type ConversationManagerHandle = Arc<ConversationManager>;
 /// 创建新对话 - 直接使用ConversationManager
 #[tauri::command]
 pub async fn create_conversation(
+    overrides: Option<ConversationOverrides>,
     conversation_manager: State<'_, ConversationManagerHandle>,
+    app: AppHandle,
 ) -> Result<String, String> {
     println!("开始创建新对话...");
+    let overrides = overrides.unwrap_or_default();
+    let approval_policy = resolve_approval_policy(&app, &overrides).await?;
     
     // 创建配置时增加更详细的错误处理
-    let config = match create_config().await {
+    let config = match create_config_with_overrides(Some(&overrides), approval_policy.as_ref()).await {
         Ok(config) => {
             println!("配置创建成功");
             config
@@ -51,6 +67,11 @@ pub async fn create_conversation(
         })?;
     
     let conversation_id = new_conversation.conversation_id;
+    
+    // 持久化对话记录及参数覆盖
+    let store = ConversationStore::new()?;
+    store.create(&conversation_id.to_string(), overrides).await?;
+    
     println!("成功创建对话: {}", conversation_id);
     Ok(conversation_id.to_string())
 }
@@ -60,6 +81,7 @@ pub async fn create_conversation(
 pub async fn send_message(
     request: SendMessageRequest,
     conversation_manager: State<'_, ConversationManagerHandle>,
+    pending_patches: State<'_, PendingPatchesHandle>,
     app: AppHandle,
 ) -> Result<(), String> {
     let conversation_id_str = request.conversation_id.clone();
@@ -73,14 +95,52 @@ pub async fn send_message(
         .await
         .map_err(|e| format!("获取对话失败: {e}"))?;
     
+    // 命令审批交给命令策略引擎，对话绑定智能体时一并遵循其审批策略
+    let (agent_id, approval_policy, model) = match ConversationStore::new()?.get(&conversation_id_str).await? {
+        Some(record) => (
+            record.overrides.agent_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()),
+            resolve_approval_policy(&app, &record.overrides).await?,
+            record.overrides.model.clone(),
+        ),
+        None => (None, None, None),
+    };
+    let command_policy = app.try_state::<CommandPolicyEngineHandle>().map(|engine| engine.inner().clone());
+    let database = app.try_state::<DatabaseHandle>().map(|db| db.inner().clone());
+    
     // 启动异步事件处理，使用标准的事件处理模式
     let app_handle = app.clone();
     let conv_id = conversation_id_str.clone();
     let message_content = request.content.clone();
     
+    // 首条消息时自动生成对话标题并通知前端刷新侧边栏
+    match ConversationStore::new()?.ensure_title_from_message(&conversation_id_str, &request.content).await {
+        Ok(Some(record)) => {
+            println!("已生成对话标题: {}", record.title);
+            let _ = app.emit("conversation_title_updated", &record);
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("生成对话标题失败: {}", e),
+    }
+    
+    let pending_patches = pending_patches.inner().clone();
+    
+    // 记录用户消息，便于后续从消息创建任务
+    let store = ConversationStore::new()?;
+    if let Err(e) = store
+        .append_message(Message::new_user_message(conversation_id_str.clone(), request.content.clone()))
+        .await
+    {
+        eprintln!("保存用户消息失败: {}", e);
+    }
+    
     tokio::spawn(async move {
         println!("开始提交用户输入: {}", message_content);
         
+        // 本轮的Agent输出，轮次结束时连同用户输入保存为一条LLM调用记录
+        let started = Instant::now();
+        let mut turn_output: Vec<String> = Vec::new();
+        let transcript_prompt = message_content.clone();
+        
         // 提交用户输入
         if let Err(e) = conversation.submit(Op::UserInput {
             items: vec![InputItem::Text {
@@ -115,6 +175,65 @@ pub async fn send_message(
                         // 检查是否为关闭完成事件
                         let is_shutdown_complete = matches!(event.msg, EventMsg::ShutdownComplete);
                         
+                        // 按命令策略判定命令审批：自动批准或拒绝的命令不再转发审批请求
+                        if let (EventMsg::ExecApprovalRequest(exec_event), Some(engine)) = (&event.msg, &command_policy) {
+                            let mut request = CommandRequest::new(exec_event.command.clone(), exec_event.cwd.clone());
+                            request.agent_id = agent_id;
+                            request.conversation_id = Some(conv_id.clone());
+                            request.approval_policy = approval_policy.clone();
+                            if let Some(db) = &database {
+                                apply_branch_protection(db, &mut request).await;
+                            }
+                            match engine.evaluate(&request).await {
+                                Ok(classification) => {
+                                    let decision = match classification.decision {
+                                        CommandDecision::AutoApprove => Some(ReviewDecision::Approved),
+                                        CommandDecision::Deny => Some(ReviewDecision::Denied),
+                                        CommandDecision::Escalate => None,
+                                    };
+                                    if let Some(decision) = decision {
+                                        match conversation.submit(Op::ExecApproval {
+                                            id: event.id.clone(),
+                                            decision,
+                                        }).await {
+                                            Ok(_) => {
+                                                println!("命令策略已{}命令: {} (风险: {})",
+                                                    if classification.decision == CommandDecision::Deny { "拒绝" } else { "批准" },
+                                                    classification.command, classification.risk.as_str());
+                                                let _ = app_handle.emit(&format!("conversation_command_policy_{}", conv_id), &classification);
+                                                continue;
+                                            }
+                                            Err(e) => eprintln!("提交命令审批失败: {e}"),
+                                        }
+                                    }
+                                }
+                                Err(e) => eprintln!("命令策略判定失败: {}", e),
+                            }
+                        }
+                        
+                        // 命令输出量可能很大，经日志管道合并后按批次推送，不再逐条发送
+                        if let EventMsg::ExecCommandOutputDelta(ref delta) = event.msg {
+                            let pipeline = app_handle.state::<LogPipelineHandle>();
+                            push_command_output(&pipeline, &conv_id, &delta.chunk, matches!(delta.stream, ExecOutputStream::Stderr));
+                            continue;
+                        }
+                        
+                        // 缓存补丁审批请求，供前端渲染差异
+                        if let EventMsg::ApplyPatchApprovalRequest(ref patch_event) = event.msg {
+                            record_pending_patch(&pending_patches, &conv_id, &event.id, patch_event).await;
+                        }
+                        
+                        // 记录助手消息，消息ID使用事件ID以便前端引用
+                        if let EventMsg::AgentMessage(ref agent_message) = event.msg {
+                            turn_output.push(agent_message.message.clone());
+                            let mut message = Message::new_assistant_message(conv_id.clone(), agent_message.message.clone(), false);
+                            message.id = event.id.clone();
+                            message.is_streaming = None;
+                            if let Err(e) = store.append_message(message).await {
+                                eprintln!("保存助手消息失败: {}", e);
+                            }
+                        }
+                        
                         // 发送完整的事件对象到前端（包含ID和消息）
                         if let Err(e) = app_handle.emit(&format!("conversation_events_{}", conv_id), &event) {
                             eprintln!("发送事件失败: {e}");
@@ -122,6 +241,34 @@ pub async fn send_message(
                             println!("成功发送事件: {}", event.id);
                         }
                         
+                        // 轮次结束时保存Agent执行的调用记录
+                        let turn_error = match &event.msg {
+                            EventMsg::TaskComplete(_) => Some(None),
+                            EventMsg::Error(error) => Some(Some(error.message.clone())),
+                            EventMsg::TurnAborted(_) => Some(Some("轮次被中断".to_string())),
+                            _ => None,
+                        };
+                        if let (Some(error_message), Some(db)) = (turn_error, &database) {
+                            let call = LlmCall {
+                                provider: "codex".to_string(),
+                                model: model.clone().unwrap_or_else(|| "default".to_string()),
+                                prompt: transcript_prompt.clone(),
+                                request_params: None,
+                                response: (!turn_output.is_empty()).then(|| turn_output.join("\n\n")),
+                                error_message,
+                                tokens_used: None,
+                                latency: started.elapsed(),
+                            };
+                            let link = TranscriptLink {
+                                conversation_id: Some(conv_id.clone()),
+                                ..Default::default()
+                            };
+                            if let Err(e) = record_transcript(db, LlmCallKind::AgentExecution, &link, call).await {
+                                eprintln!("保存Agent调用记录失败: {}", e);
+                            }
+                            turn_output.clear();
+                        }
+                        
                         // 处理生命周期管理
                         match event.msg {
                             EventMsg::TaskComplete(_) => {
@@ -187,20 +334,154 @@ pub async fn send_message(
     Ok(())
 }
 
-/// 加载对话历史 - 简化实现
+/// 读取对话绑定智能体的审批策略，未绑定智能体时返回 None
+async fn resolve_approval_policy(
+    app: &AppHandle,
+    overrides: &ConversationOverrides,
+) -> Result<Option<AgentApprovalPolicy>, String> {
+    let Some(agent_id) = &overrides.agent_id else {
+        return Ok(None);
+    };
+    let agent_uuid = Uuid::parse_str(agent_id)
+        .map_err(|_| "无效的智能体ID格式")?;
+    let db = app.try_state::<DatabaseHandle>()
+        .ok_or("数据库连接尚未初始化")?;
+    
+    load_approval_policy(&db, agent_uuid).await.map(Some)
+}
+
+/// 命令在项目工作空间内执行时，按项目编码规范附加分支保护和当前分支
+async fn apply_branch_protection(db: &DatabaseHandle, request: &mut CommandRequest) {
+    let project = match git_ops::find_project_for_path(db, &request.working_dir).await {
+        Ok(Some(project)) => project,
+        Ok(None) => return,
+        Err(e) => {
+            eprintln!("查找命令所属项目失败: {}", e);
+            return;
+        }
+    };
+    match git_ops::resolve_protection(db, project.project_id).await {
+        Ok(protection) => {
+            request.project_id = Some(project.project_id);
+            request.branch_protection = Some(protection);
+            request.current_branch = git_ops::current_branch(&request.working_dir).await;
+        }
+        Err(e) => eprintln!("加载项目分支保护失败: {}", e),
+    }
+}
+
+/// 加载对话历史 - 返回持久化的对话记录（不含消息内容）
+#[tauri::command]
+pub async fn load_conversations(
+    conversation_manager: State<'_, ConversationManagerHandle>,
+) -> Result<Vec<Conversation>, String> {
+    let records = ConversationStore::new()?.load_all().await?;
+    
+    // 使用嵌套块来避免跨await边界的Send问题
+    let default_model = async {
+        let settings_manager = SettingsManager::new().map_err(|e| e.to_string())?;
+        let app_settings = settings_manager.load_settings().await.map_err(|e| e.to_string())?;
+        Ok::<String, String>(app_settings.model.current_model)
+    }.await.unwrap_or_default();
+    
+    let mut conversations = Vec::with_capacity(records.len());
+    for record in records.into_values() {
+        let is_active = match ConversationId::from_string(&record.conversation_id) {
+            Ok(id) => conversation_manager.get_conversation(id).await.is_ok(),
+            Err(_) => false,
+        };
+        conversations.push(Conversation {
+            id: record.conversation_id,
+            title: record.title,
+            messages: Vec::new(),
+            created_at: parse_timestamp_millis(&record.created_at),
+            updated_at: parse_timestamp_millis(&record.updated_at),
+            model: record.overrides.model.unwrap_or_else(|| default_model.clone()),
+            is_active,
+        });
+    }
+    
+    // 最近更新的对话排在前面
+    conversations.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    Ok(conversations)
+}
+
+/// 重命名对话
 #[tauri::command]
-pub async fn load_conversations() -> Result<Vec<Conversation>, String> {
-    // 暂时返回空列表，后续可以实现持久化存储
-    Ok(Vec::new())
+pub async fn rename_conversation(
+    conversation_id: String,
+    title: String,
+) -> Result<ConversationRecord, String> {
+    println!("重命名对话: {} -> {}", conversation_id, title);
+    ConversationStore::new()?.rename(&conversation_id, &title).await
+}
+
+fn parse_timestamp_millis(value: &str) -> i64 {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.timestamp_millis())
+        .unwrap_or(0)
 }
 
 /// 删除对话 - 简化实现
 #[tauri::command]
 pub async fn delete_conversation(
-    _conversation_id: String,
+    conversation_id: String,
 ) -> Result<(), String> {
-    // TODO: 实现对话删除功能
-    Ok(())
+    // TODO: 实现对话删除功能，目前仅清理持久化的对话记录
+    ConversationStore::new()?.remove(&conversation_id).await
+}
+
+/// 更新对话级参数覆盖（模型、temperature、推理强度、沙箱策略）
+#[tauri::command]
+pub async fn update_conversation_settings(
+    request: UpdateConversationSettingsRequest,
+    conversation_manager: State<'_, ConversationManagerHandle>,
+) -> Result<ConversationRecord, String> {
+    println!("更新对话设置: {}", request.conversation_id);
+    
+    // 先校验参数，避免持久化无效值
+    let effort = request.overrides.reasoning_effort
+        .as_deref()
+        .map(parse_reasoning_effort)
+        .transpose()?;
+    let sandbox_mode = request.overrides.sandbox_policy
+        .as_deref()
+        .map(parse_sandbox_mode)
+        .transpose()?;
+    if let Some(temperature) = request.overrides.temperature {
+        if !(0.0..=2.0).contains(&temperature) {
+            return Err(format!("temperature 必须在 0 到 2 之间: {}", temperature));
+        }
+    }
+    
+    let store = ConversationStore::new()?;
+    let record = store
+        .update_overrides(&request.conversation_id, request.overrides.clone())
+        .await?;
+    
+    // 对话仍在运行时，让后续回合立即使用新参数
+    let conversation_id = ConversationId::from_string(&request.conversation_id)
+        .map_err(|_| "无效的对话ID")?;
+    if let Ok(conversation) = conversation_manager.get_conversation(conversation_id).await {
+        let sandbox_policy = sandbox_mode.map(|mode| match mode {
+            SandboxMode::ReadOnly => SandboxPolicy::new_read_only_policy(),
+            SandboxMode::WorkspaceWrite => SandboxPolicy::new_workspace_write_policy(),
+            SandboxMode::DangerFullAccess => SandboxPolicy::DangerFullAccess,
+        });
+        
+        conversation.submit(Op::OverrideTurnContext {
+            cwd: None,
+            approval_policy: None,
+            sandbox_policy,
+            model: request.overrides.model.clone(),
+            effort: effort.map(Some),
+            summary: None,
+        }).await
+            .map_err(|e| format!("应用对话设置失败: {e}"))?;
+        println!("对话设置已应用到运行中的对话: {}", request.conversation_id);
+    }
+    
+    Ok(record)
 }
 
 /// 中断对话 - 发送中断信号给对话
@@ -294,6 +575,7 @@ pub async fn approve_patch_command(
     approval_id: String,
     decision: String, // "approved" | "approved_for_session" | "denied" | "abort"
     conversation_manager: State<'_, ConversationManagerHandle>,
+    pending_patches: State<'_, PendingPatchesHandle>,
 ) -> Result<(), String> {
     println!("处理补丁应用审批: {} -> {}", approval_id, decision);
     
@@ -318,11 +600,14 @@ pub async fn approve_patch_command(
     
     // 提交审批决策
     conversation.submit(Op::PatchApproval {
-        id: approval_id,
+        id: approval_id.clone(),
         decision: review_decision,
     }).await
         .map_err(|e| format!("提交审批决策失败: {e}"))?;
     
+    // 审批已提交，清理缓存的补丁差异
+    take_pending_patch(&pending_patches, &approval_id).await;
+    
     println!("补丁审批决策已提交: {}", decision);
     Ok(())
 }
\ No newline at end of file
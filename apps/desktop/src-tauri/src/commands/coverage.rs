@@ -0,0 +1,49 @@
+use tauri::State;
+use codex_database::coverage::{self, CoverageDelta, CoverageFormat, CoverageGateResult};
+use codex_multi_agent::ProjectRole;
+use crate::commands::members::authorize_session;
+use crate::commands::projects::DatabaseHandle;
+
+/// 上报执行会话的覆盖率报告（lcov 或 Cobertura XML），返回覆盖率及相对基准提交的变化
+#[tauri::command]
+pub async fn ingest_session_coverage(
+    session_id: String,
+    format: CoverageFormat,
+    content: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<CoverageDelta, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Contributor).await?;
+
+    coverage::ingest_session_coverage(&db, session_uuid, format, &content).await
+        .map_err(|e| format!("导入覆盖率报告失败: {}", e))?;
+    coverage::session_coverage(&db, session_uuid).await
+        .map_err(|e| format!("获取覆盖率失败: {}", e))?
+        .ok_or_else(|| "覆盖率报告不存在".to_string())
+}
+
+/// 获取执行会话的覆盖率及变化，未上报时返回 None
+#[tauri::command]
+pub async fn get_session_coverage(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<CoverageDelta>, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Viewer).await?;
+
+    coverage::session_coverage(&db, session_uuid).await
+        .map_err(|e| format!("获取覆盖率失败: {}", e))
+}
+
+/// 评估执行会话的覆盖率门禁，未上报覆盖率或未启用检查时返回 None
+#[tauri::command]
+pub async fn evaluate_session_coverage_gate(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<CoverageGateResult>, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Viewer).await?;
+
+    coverage::evaluate_coverage_gate(&db, session_uuid).await
+        .map_err(|e| format!("评估覆盖率门禁失败: {}", e))
+}
@@ -0,0 +1,157 @@
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use codex_database::{
+    DatabaseConnection,
+    fixtures::{self, DemoData},
+    repository::ProjectMemberRepository,
+};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::projects::DatabaseHandle;
+use crate::models::{DemoExecutionLog, DemoWorkspaceSummary};
+
+/// 演示会话回放日志事件名
+const DEMO_EXECUTION_LOG_EVENT: &str = "demo_execution_log";
+
+/// 回放时相邻两条日志的间隔
+const DEMO_REPLAY_INTERVAL: Duration = Duration::from_millis(800);
+
+/// 加载演示工作区（示例项目、Agent、任务、冲突、事件和执行会话，不调用真实LLM）
+#[tauri::command]
+pub async fn load_demo_workspace(
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<DemoWorkspaceSummary, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let db = &**db;
+    if find_demo_data(db, current_user.user_id).await?.is_some() {
+        return Err("演示工作区已存在，请先删除后再重新加载".to_string());
+    }
+
+    println!("加载演示工作区 (用户: {})", current_user.username);
+
+    let data = fixtures::seed_demo_data_for_user(db, current_user.user_id).await
+        .map_err(|e| format!("加载演示数据失败: {}", e))?;
+
+    // 与手动创建的项目一致，当前用户成为演示项目的所有者
+    let member_repo = ProjectMemberRepository::new(db.clone());
+    for project in &data.projects {
+        member_repo
+            .add_member(project.project_id, current_user.user_id, ProjectRole::Owner, None).await
+            .map_err(|e| format!("添加项目所有者失败: {}", e))?;
+    }
+
+    println!("演示工作区加载完成: {} 个项目, {} 个任务", data.projects.len(), data.tasks.len());
+    Ok(summarize(&data))
+}
+
+/// 获取当前用户的演示工作区，未加载时返回 None
+#[tauri::command]
+pub async fn get_demo_workspace(
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<DemoWorkspaceSummary>, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    Ok(find_demo_data(&db, current_user.user_id).await?.as_ref().map(summarize))
+}
+
+/// 删除演示工作区的全部数据，返回是否有数据被删除
+#[tauri::command]
+pub async fn delete_demo_workspace(
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<bool, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let db = &**db;
+    let Some(data) = find_demo_data(db, current_user.user_id).await? else {
+        return Ok(false);
+    };
+
+    println!("删除演示工作区 (用户: {})", current_user.username);
+
+    fixtures::delete_demo_data(db, &data).await
+        .map_err(|e| format!("删除演示数据失败: {}", e))?;
+    Ok(true)
+}
+
+/// 按时间顺序回放演示执行会话的日志，逐条发送 `demo_execution_log` 事件
+#[tauri::command]
+pub async fn replay_demo_session(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let session_uuid = Uuid::parse_str(&session_id)
+        .map_err(|e| format!("无效的会话ID: {}", e))?;
+    let data = find_demo_data(&db, current_user.user_id).await?
+        .ok_or_else(|| "演示工作区不存在".to_string())?;
+    if !data.sessions.iter().any(|s| s.session_id == session_uuid) {
+        return Err("只能回放演示工作区中的执行会话".to_string());
+    }
+
+    let mut logs: Vec<_> = data.logs.into_iter()
+        .filter(|log| log.session_id == session_uuid)
+        .collect();
+    logs.sort_by_key(|log| log.timestamp_ms);
+
+    println!("回放演示执行会话: {} ({} 条日志)", session_id, logs.len());
+
+    let total = logs.len();
+    tokio::spawn(async move {
+        for (index, log) in logs.into_iter().enumerate() {
+            let event = DemoExecutionLog {
+                session_id: log.session_id.to_string(),
+                log_level: log.log_level,
+                event_type: log.event_type,
+                message: log.message,
+                timestamp_ms: log.timestamp_ms,
+                is_last: index + 1 == total,
+            };
+            if let Err(e) = app.emit(DEMO_EXECUTION_LOG_EVENT, &event) {
+                eprintln!("发送演示日志事件失败: {e}");
+                return;
+            }
+            tokio::time::sleep(DEMO_REPLAY_INTERVAL).await;
+        }
+    });
+
+    Ok(())
+}
+
+async fn find_demo_data(db: &DatabaseConnection, user_id: Uuid) -> Result<Option<DemoData>, String> {
+    fixtures::find_demo_data(db, user_id).await
+        .map_err(|e| format!("查询演示数据失败: {}", e))
+}
+
+/// 转换为前端概况模型
+fn summarize(data: &DemoData) -> DemoWorkspaceSummary {
+    // 优先回放进行中的会话，日志最能体现执行过程
+    let scripted_session = data.sessions.iter()
+        .find(|s| s.status == "running")
+        .or_else(|| data.sessions.first());
+
+    DemoWorkspaceSummary {
+        project_ids: data.projects.iter().map(|p| p.project_id.to_string()).collect(),
+        agent_count: data.agents.len(),
+        task_count: data.tasks.len(),
+        conflict_count: data.conflicts.len(),
+        session_count: data.sessions.len(),
+        log_count: data.logs.len(),
+        event_count: data.events.len(),
+        scripted_session_id: scripted_session.map(|s| s.session_id.to_string()),
+    }
+}
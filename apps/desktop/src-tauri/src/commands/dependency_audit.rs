@@ -0,0 +1,75 @@
+use tauri::State;
+use codex_database::dependency_audit::{self, DependencyAuditOptions, DEPENDENCY_AUDIT_TASK_TYPE};
+use codex_database::repository::{
+    ExecutionSessionRepository, TaskRepository,
+    execution_session_repository::CreateSessionData,
+};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::artifacts::ArtifactStoreHandle;
+use crate::commands::members::require_project_role;
+use crate::commands::operations::OperationRegistryHandle;
+use crate::commands::projects::DatabaseHandle;
+use crate::osv::OsvVulnerabilitySource;
+use crate::shutdown::ShutdownCoordinatorHandle;
+
+/// 依赖审计会话的超时时间
+const AUDIT_TIMEOUT_MINUTES: i32 = 30;
+
+/// 在后台执行依赖审计任务，返回操作ID
+///
+/// 审计报告保存为执行会话的工件，操作结果中包含报告、工件ID和新建的修复子任务
+#[tauri::command]
+pub async fn start_dependency_audit(
+    task_id: String,
+    agent_id: String,
+    options: Option<DependencyAuditOptions>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    store: State<'_, ArtifactStoreHandle>,
+    registry: State<'_, OperationRegistryHandle>,
+    shutdown: State<'_, ShutdownCoordinatorHandle>,
+) -> Result<String, String> {
+    let task_uuid = Uuid::parse_str(&task_id)
+        .map_err(|_| "无效的任务ID格式")?;
+    let agent_uuid = Uuid::parse_str(&agent_id)
+        .map_err(|_| "无效的智能体ID格式")?;
+
+    let task = TaskRepository::new((**db).clone()).find_by_id(task_uuid).await
+        .map_err(|e| format!("获取任务失败: {}", e))?
+        .ok_or("任务不存在")?;
+    if task.task_type != DEPENDENCY_AUDIT_TASK_TYPE {
+        return Err("该任务不是依赖审计任务".to_string());
+    }
+
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    require_project_role(&db, task.project_id, current_user.user_id, ProjectRole::Contributor).await?;
+    let work = shutdown.begin_work().map_err(|e| e.to_string())?;
+
+    let sessions = ExecutionSessionRepository::new((**db).clone());
+    let session = sessions.create(CreateSessionData {
+        task_id: task_uuid,
+        agent_id: agent_uuid,
+        project_id: task.project_id,
+        git_branch: format!("audit/{}", &task_uuid.to_string()[..8]),
+        base_commit: None,
+        execution_config: None,
+        timeout_minutes: AUDIT_TIMEOUT_MINUTES,
+    }).await
+        .map_err(|e| format!("创建执行会话失败: {}", e))?;
+    sessions.start_session(session.session_id).await
+        .map_err(|e| format!("启动执行会话失败: {}", e))?;
+
+    let store = store.inner().clone();
+    let options = options.unwrap_or_default();
+    let session_id = session.session_id;
+    let operation_id = registry.spawn("dependency_audit", Some(task.project_id), move |_operation| async move {
+        let _work = work;
+        let source = OsvVulnerabilitySource::new();
+        dependency_audit::run_dependency_audit(&store, Some(&source), session_id, &options).await
+    });
+    println!("开始依赖审计: 任务 {} 会话 {} (操作 {})", task_id, session_id, operation_id);
+    Ok(operation_id.to_string())
+}
@@ -0,0 +1,70 @@
+use tauri::{AppHandle, Emitter, State};
+use codex_database::parallel_planner::{LaunchReport, ParallelExecutionConfig, ParallelPlanner, WaveView};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::require_project_role;
+use crate::commands::projects::DatabaseHandle;
+use crate::commands::worktrees::WorktreePoolHandle;
+
+/// 波次视图更新事件
+const EXECUTION_WAVES_EVENT: &str = "execution_waves_updated";
+
+/// 获取项目的执行波次视图
+#[tauri::command]
+pub async fn get_execution_waves(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<WaveView, String> {
+    let project_uuid = authorize(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    ParallelPlanner::new((**db).clone(), ParallelExecutionConfig::default())
+        .wave_view(project_uuid).await
+        .map_err(|e| format!("生成执行波次失败: {}", e))
+}
+
+/// 在并发上限内并行启动项目中所有就绪的任务，并推送最新的波次视图
+#[tauri::command]
+pub async fn launch_parallel_execution(
+    project_id: String,
+    token: String,
+    max_parallel_sessions: Option<usize>,
+    app: AppHandle,
+    db: State<'_, DatabaseHandle>,
+    pool: State<'_, WorktreePoolHandle>,
+) -> Result<LaunchReport, String> {
+    let project_uuid = authorize(&project_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    let mut config = ParallelExecutionConfig::default();
+    if let Some(limit) = max_parallel_sessions {
+        config.max_parallel_sessions = limit;
+    }
+    let planner = ParallelPlanner::new((**db).clone(), config)
+        .with_worktree_pool(pool.inner().clone());
+    let report = planner.launch_ready(project_uuid).await
+        .map_err(|e| format!("启动并行执行失败: {}", e))?;
+    println!("项目 {} 并行启动 {} 个任务，推迟 {} 个", project_id, report.launched.len(), report.deferred.len());
+
+    match planner.wave_view(project_uuid).await {
+        Ok(view) => {
+            let _ = app.emit(EXECUTION_WAVES_EVENT, &view);
+        }
+        Err(e) => eprintln!("生成执行波次失败: {}", e),
+    }
+    Ok(report)
+}
+
+async fn authorize(
+    project_id: &str,
+    token: &str,
+    db: &DatabaseHandle,
+    role: ProjectRole,
+) -> Result<Uuid, String> {
+    let project_uuid = Uuid::parse_str(project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    require_project_role(db, project_uuid, current_user.user_id, role).await?;
+    Ok(project_uuid)
+}
@@ -0,0 +1,65 @@
+use tauri::State;
+use codex_database::export::{self, ExportFormat, ExportSummary};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::require_project_role;
+use crate::commands::projects::DatabaseHandle;
+
+/// 流式导出项目的全部任务到指定路径（ndjson 或 csv）
+#[tauri::command]
+pub async fn export_project_tasks(
+    project_id: String,
+    format: String,
+    path: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ExportSummary, String> {
+    let (project_uuid, format) = authorize_export(&project_id, &format, &token, &db).await?;
+    let file = tokio::fs::File::create(&path).await
+        .map_err(|e| format!("创建导出文件失败: {}", e))?;
+
+    let summary = export::export_project_tasks(&db, project_uuid, format, file)
+        .await
+        .map_err(|e| format!("导出任务失败: {}", e))?;
+
+    println!("已导出项目任务: {} 行 -> {}", summary.rows, path);
+    Ok(summary)
+}
+
+/// 流式导出项目所有执行会话的日志到指定路径（ndjson 或 csv）
+#[tauri::command]
+pub async fn export_project_logs(
+    project_id: String,
+    format: String,
+    path: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ExportSummary, String> {
+    let (project_uuid, format) = authorize_export(&project_id, &format, &token, &db).await?;
+    let file = tokio::fs::File::create(&path).await
+        .map_err(|e| format!("创建导出文件失败: {}", e))?;
+
+    let summary = export::export_project_logs(&db, project_uuid, format, file)
+        .await
+        .map_err(|e| format!("导出执行日志失败: {}", e))?;
+
+    println!("已导出执行日志: {} 行 -> {}", summary.rows, path);
+    Ok(summary)
+}
+
+/// 校验参数和令牌，并要求当前用户可以查看项目
+async fn authorize_export(
+    project_id: &str,
+    format: &str,
+    token: &str,
+    db: &DatabaseHandle,
+) -> Result<(Uuid, ExportFormat), String> {
+    let project_uuid = Uuid::parse_str(project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    let format = format.parse::<ExportFormat>()?;
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    require_project_role(db, project_uuid, current_user.user_id, ProjectRole::Viewer).await?;
+    Ok((project_uuid, format))
+}
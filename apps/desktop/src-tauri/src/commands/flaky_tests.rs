@@ -0,0 +1,48 @@
+use tauri::State;
+use codex_database::flaky_tests::{self, FlakyTestConfig, FlakyTestReport, TestCaseReport, TestGateResult};
+use codex_multi_agent::ProjectRole;
+use crate::commands::members::{authorize_project, authorize_session};
+use crate::commands::projects::DatabaseHandle;
+
+/// 记录执行会话的测试用例结果，覆盖该会话之前的记录，返回记录的条数
+#[tauri::command]
+pub async fn record_session_test_results(
+    session_id: String,
+    results: Vec<TestCaseReport>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<usize, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Contributor).await?;
+
+    let recorded = flaky_tests::record_session_results(&db, session_uuid, &results).await
+        .map_err(|e| format!("记录测试结果失败: {}", e))?;
+    println!("已记录执行会话 {} 的 {} 条测试结果", session_id, recorded.len());
+    Ok(recorded.len())
+}
+
+/// 获取项目的不稳定测试报告，未指定判定参数时使用默认值
+#[tauri::command]
+pub async fn get_flaky_test_report(
+    project_id: String,
+    config: Option<FlakyTestConfig>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<FlakyTestReport, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    flaky_tests::flaky_candidates(&db, project_uuid, &config.unwrap_or_default()).await
+        .map_err(|e| format!("获取不稳定测试报告失败: {}", e))
+}
+
+/// 按项目质量门禁评估执行会话的测试结果，会话没有记录测试结果时返回 None
+#[tauri::command]
+pub async fn evaluate_session_test_gate(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<TestGateResult>, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Viewer).await?;
+
+    flaky_tests::evaluate_session_tests(&db, session_uuid, &FlakyTestConfig::default()).await
+        .map_err(|e| format!("评估测试门禁失败: {}", e))
+}
@@ -0,0 +1,119 @@
+use tauri::{AppHandle, Emitter, State};
+use codex_database::checkpoint_gates::{self, DefineGateData, GateTimeline};
+use codex_database::entities::task_gate;
+use codex_database::repository::{TaskGateRepository, TaskRepository};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::require_project_role;
+use crate::commands::projects::DatabaseHandle;
+
+/// 检查点状态变化时发送的事件，负载为任务的检查点时间线
+const TASK_GATES_UPDATED_EVENT: &str = "task_gates_updated";
+
+/// 为任务定义人工检查点
+#[tauri::command]
+pub async fn define_task_gate(
+    task_id: String,
+    name: String,
+    description: Option<String>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<task_gate::Model, String> {
+    let (task_uuid, _) = authorize_task(&task_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    checkpoint_gates::define_gate(&db, DefineGateData { task_id: task_uuid, name, description }).await
+        .map_err(|e| format!("定义检查点失败: {}", e))
+}
+
+/// 获取任务的检查点及等待时间线
+#[tauri::command]
+pub async fn get_task_gate_timeline(
+    task_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<GateTimeline, String> {
+    let (task_uuid, _) = authorize_task(&task_id, &token, &db, ProjectRole::Viewer).await?;
+
+    checkpoint_gates::gate_timeline(&db, task_uuid).await
+        .map_err(|e| format!("获取检查点时间线失败: {}", e))
+}
+
+/// 任务到达检查点，暂停等待人工决策
+#[tauri::command]
+pub async fn request_task_gate(
+    task_id: String,
+    gate_name: String,
+    token: String,
+    app: AppHandle,
+    db: State<'_, DatabaseHandle>,
+) -> Result<task_gate::Model, String> {
+    let (task_uuid, _) = authorize_task(&task_id, &token, &db, ProjectRole::Contributor).await?;
+
+    let gate = checkpoint_gates::request_gate(&db, task_uuid, &gate_name).await
+        .map_err(|e| format!("进入检查点失败: {}", e))?;
+    emit_gates_updated(&app, &db, task_uuid).await;
+    Ok(gate)
+}
+
+/// 批准或驳回检查点，驳回时必须填写意见
+#[tauri::command]
+pub async fn decide_task_gate(
+    task_id: String,
+    gate_id: String,
+    approved: bool,
+    comment: Option<String>,
+    token: String,
+    app: AppHandle,
+    db: State<'_, DatabaseHandle>,
+) -> Result<task_gate::Model, String> {
+    let (task_uuid, user_id) = authorize_task(&task_id, &token, &db, ProjectRole::Maintainer).await?;
+    let gate_uuid = Uuid::parse_str(&gate_id)
+        .map_err(|_| "无效的检查点ID格式")?;
+
+    let belongs_to_task = TaskGateRepository::new((**db).clone())
+        .find_by_id(gate_uuid).await
+        .map_err(|e| format!("查询检查点失败: {}", e))?
+        .is_some_and(|gate| gate.task_id == task_uuid);
+    if !belongs_to_task {
+        return Err(format!("任务 {} 没有该检查点", task_id));
+    }
+
+    let gate = checkpoint_gates::decide_gate(&db, gate_uuid, user_id, approved, comment).await
+        .map_err(|e| format!("提交检查点决策失败: {}", e))?;
+    println!("用户 {} {}任务 {} 的检查点「{}」", user_id, if approved { "批准" } else { "驳回" }, task_id, gate.name);
+    emit_gates_updated(&app, &db, task_uuid).await;
+    Ok(gate)
+}
+
+async fn emit_gates_updated(app: &AppHandle, db: &DatabaseHandle, task_id: Uuid) {
+    match checkpoint_gates::gate_timeline(db, task_id).await {
+        Ok(timeline) => {
+            if let Err(e) = app.emit(TASK_GATES_UPDATED_EVENT, &timeline) {
+                eprintln!("发送检查点更新事件失败: {e}");
+            }
+        }
+        Err(e) => eprintln!("读取检查点时间线失败: {e}"),
+    }
+}
+
+/// 校验令牌，并要求当前用户在任务所属项目中具有指定角色，返回任务ID和用户ID
+async fn authorize_task(
+    task_id: &str,
+    token: &str,
+    db: &DatabaseHandle,
+    role: ProjectRole,
+) -> Result<(Uuid, Uuid), String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let task_uuid = Uuid::parse_str(task_id)
+        .map_err(|_| "无效的任务ID格式")?;
+    let task = TaskRepository::new((**db).clone())
+        .find_by_id(task_uuid).await
+        .map_err(|e| format!("查询任务失败: {}", e))?
+        .ok_or_else(|| format!("任务不存在: {}", task_id))?;
+
+    require_project_role(db, task.project_id, current_user.user_id, role).await?;
+    Ok((task_uuid, current_user.user_id))
+}
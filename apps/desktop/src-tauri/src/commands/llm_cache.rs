@@ -0,0 +1,54 @@
+use std::sync::Arc;
+use tauri::State;
+use codex_database::llm_cache::{LlmCache, LlmCacheConfig, LlmCacheStats};
+use codex_database::DatabaseConnection;
+use crate::commands::projects::DatabaseHandle;
+use crate::settings::LlmCacheSettings;
+
+// LLM响应缓存
+pub type LlmCacheHandle = Arc<LlmCache>;
+
+/// 按设置创建LLM响应缓存
+pub fn create_llm_cache(db: &DatabaseConnection, settings: &LlmCacheSettings) -> LlmCacheHandle {
+    let config = LlmCacheConfig {
+        enabled: settings.enabled,
+        ttl_seconds: settings.ttl_hours as i64 * 3600,
+        max_entries: settings.max_entries,
+        max_total_bytes: settings.max_size_mb * 1024 * 1024,
+        ..LlmCacheConfig::default()
+    };
+    Arc::new(LlmCache::new(db.clone(), config))
+}
+
+/// 获取LLM响应缓存的命中统计和容量
+#[tauri::command]
+pub async fn get_llm_cache_stats(
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    cache: State<'_, LlmCacheHandle>,
+) -> Result<LlmCacheStats, String> {
+    authenticate(&token, &db).await?;
+
+    cache.stats().await
+        .map_err(|e| format!("查询LLM缓存统计失败: {}", e))
+}
+
+/// 清空LLM响应缓存，返回删除的条目数
+#[tauri::command]
+pub async fn clear_llm_cache(
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    cache: State<'_, LlmCacheHandle>,
+) -> Result<u64, String> {
+    authenticate(&token, &db).await?;
+
+    cache.clear().await
+        .map_err(|e| format!("清空LLM缓存失败: {}", e))
+}
+
+async fn authenticate(token: &str, db: &DatabaseHandle) -> Result<(), String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    Ok(())
+}
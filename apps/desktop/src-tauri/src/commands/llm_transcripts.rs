@@ -0,0 +1,92 @@
+use tauri::State;
+use codex_database::llm_replay::{self, ReplayReport};
+use codex_database::llm_transcripts::{self, TranscriptQuery, TranscriptView};
+use codex_database::repository::LlmSessionRepository;
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::require_project_role;
+use crate::commands::projects::DatabaseHandle;
+
+/// 查看项目的LLM调用记录，提示词和输出中的密钥已脱敏
+#[tauri::command]
+pub async fn list_llm_transcripts(
+    project_id: String,
+    query: Option<TranscriptQuery>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<TranscriptView>, String> {
+    let project_uuid = Uuid::parse_str(&project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    let user_id = authenticate(&token, &db).await?;
+    require_project_role(&db, project_uuid, user_id, ProjectRole::Viewer).await?;
+
+    let query = TranscriptQuery {
+        project_id: Some(project_uuid),
+        ..query.unwrap_or_default()
+    };
+    llm_transcripts::list_transcripts(&db, &query).await
+        .map_err(|e| format!("获取LLM调用记录失败: {}", e))
+}
+
+/// 查看桌面端对话中Agent执行的LLM调用记录
+#[tauri::command]
+pub async fn list_conversation_llm_transcripts(
+    conversation_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<TranscriptView>, String> {
+    authenticate(&token, &db).await?;
+
+    let query = TranscriptQuery {
+        conversation_id: Some(conversation_id),
+        ..Default::default()
+    };
+    llm_transcripts::list_transcripts(&db, &query).await
+        .map_err(|e| format!("获取LLM调用记录失败: {}", e))
+}
+
+/// 查看单条LLM调用记录，归属项目的记录要求项目查看权限
+#[tauri::command]
+pub async fn get_llm_transcript(
+    transcript_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<TranscriptView, String> {
+    let transcript_uuid = Uuid::parse_str(&transcript_id)
+        .map_err(|_| "无效的调用记录ID格式")?;
+    let user_id = authenticate(&token, &db).await?;
+
+    let transcript = llm_transcripts::get_transcript(&db, transcript_uuid).await
+        .map_err(|e| format!("获取LLM调用记录失败: {}", e))?
+        .ok_or("LLM调用记录不存在")?;
+    if let Some(project_id) = transcript.link.project_id {
+        require_project_role(&db, project_id, user_id, ProjectRole::Viewer).await?;
+    }
+    Ok(transcript)
+}
+
+/// 查看LLM会话的重放报告（按时间倒序），重放由命令行 `sker replay-session` 发起
+#[tauri::command]
+pub async fn list_llm_replay_reports(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<ReplayReport>, String> {
+    let session_uuid = Uuid::parse_str(&session_id)
+        .map_err(|_| "无效的会话ID格式")?;
+    let user_id = authenticate(&token, &db).await?;
+    let session = LlmSessionRepository::new((**db).clone()).find_by_id(session_uuid).await
+        .map_err(|e| format!("获取LLM会话失败: {}", e))?
+        .ok_or("LLM会话不存在")?;
+    require_project_role(&db, session.project_id, user_id, ProjectRole::Viewer).await?;
+
+    llm_replay::list_replay_reports(&db, session_uuid).await
+        .map_err(|e| format!("获取重放报告失败: {}", e))
+}
+
+async fn authenticate(token: &str, db: &DatabaseHandle) -> Result<Uuid, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    Ok(current_user.user_id)
+}
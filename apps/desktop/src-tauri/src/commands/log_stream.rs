@@ -0,0 +1,43 @@
+use tauri::{AppHandle, Emitter, State};
+use codex_database::log_pipeline::{LogLine, LogPipeline, LogPipelineConfig, LogPipelineMetrics};
+use codex_multi_agent::EventType;
+
+// 执行日志背压管道，执行器提交日志行，按批次推送给前端
+pub type LogPipelineHandle = LogPipeline;
+
+/// 执行日志批次事件名，载荷为一个日志流在一个发送间隔内合并后的日志
+pub const EXECUTION_LOG_BATCH_EVENT: &str = "execution_log_batch";
+
+/// 创建执行日志管道，发送任务把每个批次作为前端事件发出
+pub fn start_log_pipeline(app_handle: AppHandle) -> LogPipelineHandle {
+    let (pipeline, task) = LogPipeline::new(LogPipelineConfig::default(), move |batch| {
+        if let Err(e) = app_handle.emit(EXECUTION_LOG_BATCH_EVENT, &batch) {
+            eprintln!("发送执行日志批次失败: {}", e);
+        }
+    })
+    .expect("默认日志管道配置有效");
+    tauri::async_runtime::spawn(task);
+    pipeline
+}
+
+/// 把命令输出的一个片段按行提交到日志管道，日志流为对话ID
+pub fn push_command_output(pipeline: &LogPipelineHandle, conversation_id: &str, chunk: &[u8], is_stderr: bool) {
+    let timestamp_ms = chrono::Utc::now().timestamp_millis();
+    for message in String::from_utf8_lossy(chunk).lines().filter(|line| !line.trim().is_empty()) {
+        pipeline.push(LogLine {
+            stream: conversation_id.to_string(),
+            log_level: if is_stderr { "warn" } else { "info" }.to_string(),
+            event_type: EventType::Progress.to_string(),
+            message: message.to_string(),
+            timestamp_ms,
+        });
+    }
+}
+
+/// 获取执行日志管道的指标（接收、发送、合并、丢弃的行数）
+#[tauri::command]
+pub async fn get_log_pipeline_metrics(
+    pipeline: State<'_, LogPipelineHandle>,
+) -> Result<LogPipelineMetrics, String> {
+    Ok(pipeline.metrics())
+}
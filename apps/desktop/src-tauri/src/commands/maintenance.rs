@@ -0,0 +1,153 @@
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use codex_database::entities::{maintenance_job, maintenance_job_run};
+use codex_database::maintenance::MaintenanceRunner;
+use codex_database::repository::{MaintenanceJobRepository, MaintenanceJobRunRepository};
+use uuid::Uuid;
+use crate::commands::projects::DatabaseHandle;
+
+// 定期维护任务执行器
+pub type MaintenanceRunnerHandle = Arc<MaintenanceRunner>;
+
+/// 执行记录列表默认返回的数量
+const DEFAULT_RUN_LIMIT: u64 = 20;
+
+/// 维护任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceJobInfo {
+    pub job_id: String,
+    pub name: String,
+    pub kind: String,
+    pub cron_expression: String,
+    pub jitter_secs: i32,
+    pub params: serde_json::Value,
+    pub enabled: bool,
+    pub next_run_at: DateTime<Utc>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_status: Option<String>,
+    pub running: bool,
+}
+
+impl From<maintenance_job::Model> for MaintenanceJobInfo {
+    fn from(job: maintenance_job::Model) -> Self {
+        Self {
+            job_id: job.job_id.to_string(),
+            running: job.is_running(),
+            name: job.name,
+            kind: job.kind,
+            cron_expression: job.cron_expression,
+            jitter_secs: job.jitter_secs,
+            params: job.params,
+            enabled: job.enabled,
+            next_run_at: job.next_run_at.with_timezone(&Utc),
+            last_run_at: job.last_run_at.map(|at| at.with_timezone(&Utc)),
+            last_status: job.last_status,
+        }
+    }
+}
+
+/// 维护任务的一次执行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceJobRunInfo {
+    pub run_id: String,
+    pub job_id: String,
+    pub trigger: String,
+    pub status: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<i64>,
+    pub result: Option<serde_json::Value>,
+    pub error_message: Option<String>,
+}
+
+impl From<maintenance_job_run::Model> for MaintenanceJobRunInfo {
+    fn from(run: maintenance_job_run::Model) -> Self {
+        Self {
+            run_id: run.run_id.to_string(),
+            job_id: run.job_id.to_string(),
+            trigger: run.trigger,
+            status: run.status,
+            started_at: run.started_at.with_timezone(&Utc),
+            finished_at: run.finished_at.map(|at| at.with_timezone(&Utc)),
+            duration_ms: run.duration_ms,
+            result: run.result,
+            error_message: run.error_message,
+        }
+    }
+}
+
+/// 列出定期维护任务
+#[tauri::command]
+pub async fn list_maintenance_jobs(
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<MaintenanceJobInfo>, String> {
+    authenticate(&token, &db).await?;
+
+    let jobs = MaintenanceJobRepository::new((**db).clone()).list().await
+        .map_err(|e| format!("查询维护任务失败: {}", e))?;
+    Ok(jobs.into_iter().map(MaintenanceJobInfo::from).collect())
+}
+
+/// 列出维护任务最近的执行记录
+#[tauri::command]
+pub async fn list_maintenance_job_runs(
+    job_id: String,
+    limit: Option<u64>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<MaintenanceJobRunInfo>, String> {
+    authenticate(&token, &db).await?;
+    let job_uuid = Uuid::parse_str(&job_id).map_err(|_| "无效的维护任务ID格式")?;
+
+    let runs = MaintenanceJobRunRepository::new((**db).clone())
+        .find_by_job(job_uuid, limit.unwrap_or(DEFAULT_RUN_LIMIT)).await
+        .map_err(|e| format!("查询维护任务执行记录失败: {}", e))?;
+    Ok(runs.into_iter().map(MaintenanceJobRunInfo::from).collect())
+}
+
+/// 立即执行维护任务，任务正在执行时返回错误
+#[tauri::command]
+pub async fn trigger_maintenance_job(
+    job_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    runner: State<'_, MaintenanceRunnerHandle>,
+) -> Result<MaintenanceJobRunInfo, String> {
+    authenticate(&token, &db).await?;
+    let job_uuid = Uuid::parse_str(&job_id).map_err(|_| "无效的维护任务ID格式")?;
+
+    let run = runner.trigger(job_uuid, Utc::now()).await
+        .map_err(|e| format!("执行维护任务失败: {}", e))?;
+    Ok(run.into())
+}
+
+/// 更新维护任务的执行计划
+#[tauri::command]
+pub async fn update_maintenance_job(
+    job_id: String,
+    cron_expression: String,
+    jitter_secs: u32,
+    enabled: bool,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    runner: State<'_, MaintenanceRunnerHandle>,
+) -> Result<MaintenanceJobInfo, String> {
+    authenticate(&token, &db).await?;
+    let job_uuid = Uuid::parse_str(&job_id).map_err(|_| "无效的维护任务ID格式")?;
+
+    let job = runner.update_schedule(job_uuid, cron_expression, jitter_secs, enabled, Utc::now()).await
+        .map_err(|e| format!("更新维护任务失败: {}", e))?;
+    Ok(job.into())
+}
+
+async fn authenticate(token: &str, db: &DatabaseHandle) -> Result<(), String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    Ok(())
+}
@@ -0,0 +1,258 @@
+use tauri::{AppHandle, Emitter, State};
+use codex_database::{
+    DatabaseConnection,
+    entities::project_member,
+    repository::{ExecutionSessionRepository, ProjectMemberRepository, UserRepository},
+};
+use codex_multi_agent::{EventFactory, ProjectId, ProjectRole};
+use uuid::Uuid;
+use crate::auth::CurrentUser;
+use crate::commands::projects::DatabaseHandle;
+use crate::models::{AddProjectMemberRequest, ProjectMember};
+
+/// 项目成员变更事件名
+const PROJECT_MEMBER_CHANGED_EVENT: &str = "project_member_changed";
+
+/// 列出项目成员（需要 viewer 及以上角色）
+#[tauri::command]
+pub async fn list_project_members(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<ProjectMember>, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let project_uuid = parse_project_id(&project_id)?;
+    let db = &**db;
+    require_project_role(db, project_uuid, current_user.user_id, ProjectRole::Viewer).await?;
+
+    let members = ProjectMemberRepository::new(db.clone())
+        .list_members(project_uuid).await
+        .map_err(|e| format!("查询项目成员失败: {}", e))?;
+
+    let mut result = Vec::with_capacity(members.len());
+    for member in members {
+        result.push(member_to_model(db, member).await?);
+    }
+    Ok(result)
+}
+
+/// 添加项目成员（需要 maintainer 及以上角色，只有 owner 可以授予 owner）
+#[tauri::command]
+pub async fn add_project_member(
+    request: AddProjectMemberRequest,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    app: AppHandle,
+) -> Result<ProjectMember, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let project_uuid = parse_project_id(&request.project_id)?;
+    let role = request.role.parse::<ProjectRole>()?;
+    let db = &**db;
+
+    let operator_role = require_project_role(db, project_uuid, current_user.user_id, ProjectRole::Maintainer).await?;
+    if role == ProjectRole::Owner && operator_role != ProjectRole::Owner {
+        return Err("只有项目所有者可以添加所有者".to_string());
+    }
+
+    let user = UserRepository::new(db.clone())
+        .find_by_username(&request.username).await
+        .map_err(|e| format!("查询用户失败: {}", e))?
+        .ok_or_else(|| format!("用户不存在: {}", request.username))?;
+
+    println!("添加项目成员: {} -> {} ({})", request.username, request.project_id, role.as_str());
+
+    let member = ProjectMemberRepository::new(db.clone())
+        .add_member(project_uuid, user.user_id, role, Some(current_user.user_id)).await
+        .map_err(|e| format!("添加项目成员失败: {}", e))?;
+
+    emit_member_changed(&app, project_uuid, user.user_id, None, Some(role), current_user.user_id);
+    member_to_model(db, member).await
+}
+
+/// 更新项目成员角色（需要 maintainer 及以上角色，涉及 owner 的变更需要 owner）
+#[tauri::command]
+pub async fn update_project_member_role(
+    project_id: String,
+    user_id: String,
+    role: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    app: AppHandle,
+) -> Result<ProjectMember, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let project_uuid = parse_project_id(&project_id)?;
+    let user_uuid = Uuid::parse_str(&user_id)
+        .map_err(|_| "无效的用户ID格式")?;
+    let role = role.parse::<ProjectRole>()?;
+    let db = &**db;
+
+    let operator_role = require_project_role(db, project_uuid, current_user.user_id, ProjectRole::Maintainer).await?;
+    let member_repo = ProjectMemberRepository::new(db.clone());
+    let target_role = member_repo.get_role(project_uuid, user_uuid).await
+        .map_err(|e| format!("查询成员角色失败: {}", e))?;
+    if (role == ProjectRole::Owner || target_role == Some(ProjectRole::Owner))
+        && operator_role != ProjectRole::Owner
+    {
+        return Err("只有项目所有者可以变更所有者角色".to_string());
+    }
+
+    println!("更新项目成员角色: {} -> {} ({})", user_id, project_id, role.as_str());
+
+    let (member, previous_role) = member_repo.update_role(project_uuid, user_uuid, role).await
+        .map_err(|e| format!("更新成员角色失败: {}", e))?;
+
+    emit_member_changed(&app, project_uuid, user_uuid, Some(previous_role), Some(role), current_user.user_id);
+    member_to_model(db, member).await
+}
+
+/// 移除项目成员（需要 maintainer 及以上角色，成员可以自行退出）
+#[tauri::command]
+pub async fn remove_project_member(
+    project_id: String,
+    user_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let project_uuid = parse_project_id(&project_id)?;
+    let user_uuid = Uuid::parse_str(&user_id)
+        .map_err(|_| "无效的用户ID格式")?;
+    let db = &**db;
+
+    let member_repo = ProjectMemberRepository::new(db.clone());
+    if user_uuid != current_user.user_id {
+        let operator_role = require_project_role(db, project_uuid, current_user.user_id, ProjectRole::Maintainer).await?;
+        let target_role = member_repo.get_role(project_uuid, user_uuid).await
+            .map_err(|e| format!("查询成员角色失败: {}", e))?;
+        if target_role == Some(ProjectRole::Owner) && operator_role != ProjectRole::Owner {
+            return Err("只有项目所有者可以移除所有者".to_string());
+        }
+    }
+
+    println!("移除项目成员: {} <- {}", project_id, user_id);
+
+    let previous_role = member_repo.remove_member(project_uuid, user_uuid).await
+        .map_err(|e| format!("移除项目成员失败: {}", e))?;
+
+    emit_member_changed(&app, project_uuid, user_uuid, Some(previous_role), None, current_user.user_id);
+    Ok(())
+}
+
+/// 校验当前用户在项目中至少具备指定角色，供项目、任务和工作空间命令复用
+pub(crate) async fn require_project_role(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    user_id: Uuid,
+    required: ProjectRole,
+) -> Result<ProjectRole, String> {
+    ProjectMemberRepository::new(db.clone())
+        .require_role(project_id, user_id, required).await
+        .map_err(|e| format!("无权执行该操作: {}", e))
+}
+
+/// 校验令牌，并要求当前用户在项目中至少具备指定角色，返回项目ID
+pub(crate) async fn authorize_project(
+    project_id: &str,
+    token: &str,
+    db: &DatabaseConnection,
+    required: ProjectRole,
+) -> Result<Uuid, String> {
+    let project_uuid = parse_project_id(project_id)?;
+    authorize_project_user(project_uuid, token, db, required).await?;
+    Ok(project_uuid)
+}
+
+/// 校验令牌，并要求当前用户在项目中至少具备指定角色，返回当前用户
+pub(crate) async fn authorize_project_user(
+    project_id: Uuid,
+    token: &str,
+    db: &DatabaseConnection,
+    required: ProjectRole,
+) -> Result<CurrentUser, String> {
+    let current_user = authenticate(token, db).await?;
+    require_project_role(db, project_id, current_user.user_id, required).await?;
+    Ok(current_user)
+}
+
+/// 校验令牌，并要求当前用户在执行会话所属项目中至少具备指定角色，返回会话ID
+pub(crate) async fn authorize_session(
+    session_id: &str,
+    token: &str,
+    db: &DatabaseConnection,
+    required: ProjectRole,
+) -> Result<Uuid, String> {
+    let session_uuid = Uuid::parse_str(session_id)
+        .map_err(|_| "无效的会话ID格式")?;
+    let current_user = authenticate(token, db).await?;
+
+    let session = ExecutionSessionRepository::new(db.clone()).find_by_id(session_uuid).await
+        .map_err(|e| format!("获取执行会话失败: {}", e))?
+        .ok_or("执行会话不存在")?;
+    require_project_role(db, session.project_id, current_user.user_id, required).await?;
+    Ok(session_uuid)
+}
+
+async fn authenticate(token: &str, db: &DatabaseConnection) -> Result<CurrentUser, String> {
+    crate::auth::AuthService::new(db.clone())
+        .validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))
+}
+
+pub(crate) fn parse_project_id(project_id: &str) -> Result<Uuid, String> {
+    Uuid::parse_str(project_id).map_err(|_| "无效的项目ID格式".to_string())
+}
+
+fn emit_member_changed(
+    app: &AppHandle,
+    project_id: Uuid,
+    user_id: Uuid,
+    previous_role: Option<ProjectRole>,
+    new_role: Option<ProjectRole>,
+    changed_by: Uuid,
+) {
+    let event = EventFactory::project_member_changed(
+        ProjectId::from(project_id),
+        user_id.to_string(),
+        previous_role,
+        new_role,
+        changed_by.to_string(),
+    );
+    if let Err(e) = app.emit(PROJECT_MEMBER_CHANGED_EVENT, &event) {
+        eprintln!("发送项目成员变更事件失败: {e}");
+    }
+}
+
+/// 转换为前端成员模型
+async fn member_to_model(
+    db: &DatabaseConnection,
+    member: project_member::Model,
+) -> Result<ProjectMember, String> {
+    let username = UserRepository::new(db.clone())
+        .find_by_id(member.user_id).await
+        .map_err(|e| format!("查询用户失败: {}", e))?
+        .map(|u| u.username)
+        .unwrap_or_default();
+
+    Ok(ProjectMember {
+        member_id: member.member_id.to_string(),
+        project_id: member.project_id.to_string(),
+        user_id: member.user_id.to_string(),
+        username,
+        role: member.role,
+        invited_by: member.invited_by.map(|id| id.to_string()),
+        joined_at: member.joined_at.to_rfc3339(),
+    })
+}
@@ -3,13 +3,119 @@ pub mod projects;
 pub mod agents;
 pub mod config;
 pub mod diagnostics;
+pub mod patches;
+pub mod workspace;
+pub mod tasks;
+pub mod members;
+pub mod demo;
+pub mod artifacts;
+pub mod search;
+pub mod traceability;
+pub mod acceptance;
+pub mod blackboard;
+pub mod operations;
+pub mod command_policy;
+pub mod pii_scrubbing;
+pub mod preemption;
+pub mod checkpoints;
+pub mod sla;
+pub mod dependency_audit;
+pub mod ci;
+pub mod webhooks;
+pub mod conflicts;
+pub mod agent_dashboard;
+pub mod reports;
+pub mod audit_bundle;
+pub mod project_import;
+pub mod worktrees;
+pub mod execution_waves;
+pub mod llm_cache;
+pub mod llm_transcripts;
+pub mod prompt_eval;
+pub mod gates;
+pub mod review_sla;
+pub mod code_ownership;
+pub mod flaky_tests;
+pub mod coverage;
+pub mod static_analysis;
+pub mod security_scan;
+pub mod perf_budget;
+pub mod updates;
+pub mod profiles;
+pub mod privacy;
+pub mod api_keys;
+pub mod two_factor;
+pub mod notifications;
+pub mod orchestration_config;
+pub mod palette;
+pub mod maintenance;
+pub mod exports;
+pub mod log_stream;
 
 // 重新导出所有命令函数
 pub use conversations::*;
 pub use projects::*;
 pub use agents::*;
 pub use diagnostics::*;
+pub use patches::*;
+pub use workspace::*;
+pub use tasks::*;
+pub use members::*;
+pub use demo::*;
+pub use artifacts::*;
+pub use search::*;
+pub use traceability::*;
+pub use acceptance::*;
+pub use blackboard::*;
+pub use operations::*;
+pub use command_policy::*;
+pub use pii_scrubbing::*;
+pub use preemption::*;
+pub use checkpoints::*;
+pub use sla::*;
+pub use dependency_audit::*;
+pub use ci::*;
+pub use webhooks::*;
+pub use conflicts::*;
+pub use agent_dashboard::*;
+pub use reports::*;
+pub use audit_bundle::*;
+pub use project_import::*;
+pub use worktrees::*;
+pub use execution_waves::*;
+pub use llm_cache::*;
+pub use llm_transcripts::*;
+pub use prompt_eval::*;
+pub use gates::*;
+pub use review_sla::*;
+pub use code_ownership::*;
+pub use flaky_tests::*;
+pub use coverage::*;
+pub use static_analysis::*;
+pub use security_scan::*;
+pub use perf_budget::*;
+pub use updates::*;
+pub use profiles::*;
+pub use privacy::*;
+pub use api_keys::*;
+pub use two_factor::*;
+pub use notifications::*;
+pub use orchestration_config::*;
+pub use palette::*;
+pub use maintenance::*;
+pub use exports::*;
+pub use log_stream::*;
 
 // 重新导出类型别名
 pub use conversations::ConversationManagerHandle;
-pub use projects::DatabaseHandle;
\ No newline at end of file
+pub use projects::DatabaseHandle;
+pub use patches::PendingPatchesHandle;
+pub use workspace::WorkspaceWatchersHandle;
+pub use artifacts::ArtifactStoreHandle;
+pub use search::EmbeddingIndexHandle;
+pub use operations::OperationRegistryHandle;
+pub use command_policy::CommandPolicyEngineHandle;
+pub use worktrees::WorktreePoolHandle;
+pub use llm_cache::LlmCacheHandle;
+pub use maintenance::MaintenanceRunnerHandle;
+pub use log_stream::LogPipelineHandle;
\ No newline at end of file
@@ -0,0 +1,73 @@
+use tauri::State;
+use codex_database::entities::notification;
+use codex_database::notifications::{NotificationChannel, NotificationPreferences, NotificationRouter};
+use codex_database::repository::NotificationRepository;
+use uuid::Uuid;
+use crate::commands::projects::DatabaseHandle;
+
+/// 应用内通知列表的默认条数
+const DEFAULT_NOTIFICATION_LIMIT: u64 = 50;
+
+/// 获取当前用户的通知偏好
+#[tauri::command]
+pub async fn get_notification_preferences(
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<NotificationPreferences, String> {
+    let user_id = authenticate(&token, &db).await?;
+
+    NotificationRouter::new((**db).clone()).preferences(user_id).await
+        .map_err(|e| format!("获取通知偏好失败: {}", e))
+}
+
+/// 保存当前用户的通知偏好
+#[tauri::command]
+pub async fn update_notification_preferences(
+    preferences: NotificationPreferences,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<NotificationPreferences, String> {
+    let user_id = authenticate(&token, &db).await?;
+
+    NotificationRouter::new((**db).clone()).update_preferences(user_id, preferences).await
+        .map_err(|e| format!("保存通知偏好失败: {}", e))
+}
+
+/// 列出当前用户的应用内通知
+#[tauri::command]
+pub async fn list_notifications(
+    limit: Option<u64>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<notification::Model>, String> {
+    let user_id = authenticate(&token, &db).await?;
+
+    NotificationRepository::new((**db).clone())
+        .find_by_user(user_id, NotificationChannel::InApp.as_str(), limit.unwrap_or(DEFAULT_NOTIFICATION_LIMIT))
+        .await
+        .map_err(|e| format!("获取通知失败: {}", e))
+}
+
+/// 把通知标记为已读
+#[tauri::command]
+pub async fn mark_notifications_read(
+    notification_ids: Vec<String>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<u64, String> {
+    let user_id = authenticate(&token, &db).await?;
+    let ids = notification_ids
+        .iter()
+        .map(|id| Uuid::parse_str(id).map_err(|_| "无效的通知ID格式".to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    NotificationRepository::new((**db).clone()).mark_read(user_id, ids).await
+        .map_err(|e| format!("标记通知失败: {}", e))
+}
+
+async fn authenticate(token: &str, db: &DatabaseHandle) -> Result<Uuid, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    Ok(current_user.user_id)
+}
@@ -0,0 +1,156 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, State};
+use codex_database::context::CodebaseScanner;
+use codex_database::operations::{OperationProgress, OperationRegistry};
+use codex_database::repository::ProjectRepository;
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::{authorize_project, authorize_project_user};
+use crate::commands::projects::DatabaseHandle;
+use crate::commands::search::EmbeddingIndexHandle;
+use crate::shutdown::ShutdownCoordinatorHandle;
+
+// 长时间运行操作登记表
+pub type OperationRegistryHandle = Arc<OperationRegistry>;
+
+/// 操作进度事件名
+pub const OPERATION_PROGRESS_EVENT: &str = "operation_progress";
+
+/// 把操作进度转发为前端事件
+pub fn forward_operation_progress(app_handle: AppHandle, registry: OperationRegistryHandle) {
+    let mut receiver = registry.subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(progress) => {
+                    if let Err(e) = app_handle.emit(OPERATION_PROGRESS_EVENT, &progress) {
+                        eprintln!("发送操作进度事件失败: {}", e);
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    eprintln!("操作进度事件积压，跳过 {} 条", skipped);
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// 列出项目中运行中和最近结束的操作
+#[tauri::command]
+pub async fn list_operations(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    registry: State<'_, OperationRegistryHandle>,
+) -> Result<Vec<OperationProgress>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    Ok(registry
+        .list()
+        .into_iter()
+        .filter(|operation| operation.project_id == Some(project_uuid))
+        .collect())
+}
+
+/// 获取操作进度
+#[tauri::command]
+pub async fn get_operation(
+    operation_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    registry: State<'_, OperationRegistryHandle>,
+) -> Result<OperationProgress, String> {
+    let operation = find_operation(&operation_id, &registry)?;
+    authorize_operation(&operation, &token, &db, ProjectRole::Viewer).await?;
+    Ok(operation)
+}
+
+/// 请求取消操作，操作在下一个检查点结束
+#[tauri::command]
+pub async fn cancel_operation(
+    operation_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    registry: State<'_, OperationRegistryHandle>,
+) -> Result<bool, String> {
+    let operation = find_operation(&operation_id, &registry)?;
+    authorize_operation(&operation, &token, &db, ProjectRole::Contributor).await?;
+
+    let requested = registry.cancel(operation.operation_id)
+        .map_err(|e| format!("取消操作失败: {}", e))?;
+    if requested {
+        println!("已请求取消操作 {} ({})", operation_id, operation.kind);
+    }
+    Ok(requested)
+}
+
+/// 在后台更新项目的语义检索索引，返回操作ID
+#[tauri::command]
+pub async fn start_search_index_rebuild(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    index: State<'_, EmbeddingIndexHandle>,
+    registry: State<'_, OperationRegistryHandle>,
+    shutdown: State<'_, ShutdownCoordinatorHandle>,
+) -> Result<String, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Maintainer).await?;
+    let work = shutdown.begin_work().map_err(|e| e.to_string())?;
+
+    let index = index.inner().clone();
+    let operation_id = registry.spawn("search_index", Some(project_uuid), move |operation| async move {
+        let _work = work;
+        index.index_project_with_progress(project_uuid, &operation).await
+    });
+    println!("开始更新语义索引: {} (操作 {})", project_id, operation_id);
+    Ok(operation_id.to_string())
+}
+
+/// 在后台扫描项目工作空间的代码结构，返回操作ID
+#[tauri::command]
+pub async fn start_workspace_scan(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    registry: State<'_, OperationRegistryHandle>,
+    shutdown: State<'_, ShutdownCoordinatorHandle>,
+) -> Result<String, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Contributor).await?;
+    let work = shutdown.begin_work().map_err(|e| e.to_string())?;
+
+    let project = ProjectRepository::new((**db).clone()).find_by_id(project_uuid).await
+        .map_err(|e| format!("获取项目失败: {}", e))?
+        .ok_or("项目不存在")?;
+    let workspace = PathBuf::from(project.workspace_path);
+    let operation_id = registry.spawn("codebase_scan", Some(project_uuid), move |operation| async move {
+        let _work = work;
+        CodebaseScanner::new().scan_with_progress(&workspace, &operation).await
+    });
+    println!("开始扫描工作空间: {} (操作 {})", project_id, operation_id);
+    Ok(operation_id.to_string())
+}
+
+fn find_operation(operation_id: &str, registry: &OperationRegistryHandle) -> Result<OperationProgress, String> {
+    let operation_uuid = Uuid::parse_str(operation_id)
+        .map_err(|_| "无效的操作ID格式")?;
+    registry.get(operation_uuid).ok_or_else(|| "操作不存在或已过期".to_string())
+}
+
+/// 项目内的操作按项目角色校验，其余操作只校验令牌
+async fn authorize_operation(
+    operation: &OperationProgress,
+    token: &str,
+    db: &DatabaseHandle,
+    role: ProjectRole,
+) -> Result<(), String> {
+    match operation.project_id {
+        Some(project_id) => authorize_project_user(project_id, token, db, role).await.map(|_| ()),
+        None => {
+            crate::auth::AuthService::new((**db).clone()).validate_token(token).await
+                .map_err(|e| format!("身份验证失败: {}", e))?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use tauri::{AppHandle, Emitter, State};
+use codex_database::entities::project;
+use codex_database::orchestration_config::{
+    OrchestrationConfigLoader, OrchestrationConfigWatcher, ResolvedOrchestrationConfig,
+};
+use codex_database::repository::{OrganizationRepository, ProjectRepository};
+use codex_database::DatabaseConnection;
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::authorize_project;
+use crate::commands::projects::DatabaseHandle;
+use crate::orchestration_config_monitor::ORCHESTRATION_CONFIG_CHANGED_EVENT;
+use crate::settings::SettingsManager;
+
+/// 获取项目合并后的编排配置（默认值 < 组织 < sker.toml < 界面覆盖）及每个配置键的来源
+#[tauri::command]
+pub async fn get_orchestration_config(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ResolvedOrchestrationConfig, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+    let project = load_project(&db, project_uuid).await?;
+    let overrides = load_project_overrides(&project_id).await?;
+
+    project_config_loader(&db, &project, overrides.as_ref()).await?
+        .load()
+        .map_err(|e| format!("加载编排配置失败: {}", e))
+}
+
+/// 更新项目的界面覆盖，传入 null 清除覆盖；生效值变化时推送配置变更事件
+#[tauri::command]
+pub async fn update_orchestration_overrides(
+    project_id: String,
+    overrides: serde_json::Value,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    app: AppHandle,
+) -> Result<ResolvedOrchestrationConfig, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Maintainer).await?;
+    let project = load_project(&db, project_uuid).await?;
+
+    let manager = SettingsManager::new()
+        .map_err(|e| format!("创建设置管理器失败: {}", e))?;
+    let mut settings = manager.load_settings().await
+        .map_err(|e| format!("加载设置失败: {}", e))?;
+    let previous = settings.system.orchestration_config.project_overrides.get(&project_id).cloned();
+
+    // 先以新的覆盖校验合并结果，无效的覆盖不会保存
+    let loader = project_config_loader(&db, &project, previous.as_ref()).await?;
+    let mut watcher = OrchestrationConfigWatcher::new(Some(project.project_id), loader)
+        .map_err(|e| format!("加载编排配置失败: {}", e))?;
+    let event = watcher.set_ui_overrides(&overrides)
+        .map_err(|e| format!("界面覆盖无效: {}", e))?;
+
+    let project_overrides = &mut settings.system.orchestration_config.project_overrides;
+    if overrides.is_null() {
+        project_overrides.remove(&project_id);
+    } else {
+        project_overrides.insert(project_id.clone(), overrides);
+    }
+    manager.save_settings(&settings).await
+        .map_err(|e| format!("保存设置失败: {}", e))?;
+
+    if let Some(event) = event {
+        println!("项目 {} 的编排配置已更新: {}", project_id, event.changed_keys.join(", "));
+        if let Err(e) = app.emit(ORCHESTRATION_CONFIG_CHANGED_EVENT, &event) {
+            eprintln!("推送编排配置变更事件失败: {}", e);
+        }
+    }
+    Ok(watcher.current().clone())
+}
+
+/// 为项目构建编排配置加载器，包含组织默认值和界面覆盖
+pub async fn project_config_loader(
+    db: &DatabaseConnection,
+    project: &project::Model,
+    overrides: Option<&serde_json::Value>,
+) -> Result<OrchestrationConfigLoader, String> {
+    let mut loader = OrchestrationConfigLoader::new(&project.workspace_path);
+    if let Some(organization_id) = project.organization_id {
+        let organization = OrganizationRepository::new(db.clone()).find_by_id(organization_id).await
+            .map_err(|e| format!("获取组织失败: {}", e))?;
+        if let Some(organization) = organization {
+            loader = loader.with_organization_settings(&organization.get_settings())
+                .map_err(|e| format!("组织默认配置无效: {}", e))?;
+        }
+    }
+    if let Some(overrides) = overrides {
+        loader = loader.with_ui_overrides(overrides)
+            .map_err(|e| format!("界面覆盖无效: {}", e))?;
+    }
+    Ok(loader)
+}
+
+/// 读取项目保存的界面覆盖
+async fn load_project_overrides(project_id: &str) -> Result<Option<serde_json::Value>, String> {
+    let settings = SettingsManager::new()
+        .map_err(|e| format!("创建设置管理器失败: {}", e))?
+        .load_settings().await
+        .map_err(|e| format!("加载设置失败: {}", e))?;
+    Ok(settings.system.orchestration_config.project_overrides.get(project_id).cloned())
+}
+
+/// 读取已通过权限校验的项目
+async fn load_project(db: &DatabaseConnection, project_id: Uuid) -> Result<project::Model, String> {
+    ProjectRepository::new(db.clone()).find_by_id(project_id).await
+        .map_err(|e| format!("获取项目失败: {}", e))?
+        .ok_or_else(|| "项目不存在".to_string())
+}
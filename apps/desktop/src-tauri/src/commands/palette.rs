@@ -0,0 +1,380 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tauri::{AppHandle, Manager, State};
+use codex_database::repository::ProjectMemberRepository;
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::projects::DatabaseHandle;
+use crate::commands::*;
+
+/// 执行命令需要的权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "role", rename_all = "snake_case")]
+pub enum CommandPermission {
+    /// 无需登录
+    Public,
+    /// 需要登录
+    Authenticated,
+    /// 需要项目内至少具备该角色
+    ProjectRole(ProjectRole),
+}
+
+/// 命令参数说明
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandParam {
+    /// 参数名，与前端 `invoke` 一致使用驼峰命名
+    pub name: String,
+    /// Rust 类型
+    pub param_type: String,
+    pub required: bool,
+}
+
+impl CommandParam {
+    fn new(name: &str, param_type: &str) -> Self {
+        let param_type = param_type.replace(' ', "");
+        Self {
+            name: to_camel_case(name),
+            required: !param_type.starts_with("Option<"),
+            param_type,
+        }
+    }
+}
+
+/// 命令面板中的一条命令
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandMetadata {
+    /// 命令ID，与 Tauri 命令名一致
+    pub id: &'static str,
+    pub title: &'static str,
+    pub category: &'static str,
+    /// 除登录令牌外的参数
+    pub params: Vec<CommandParam>,
+    pub permission: CommandPermission,
+}
+
+/// 声明可从命令面板执行的命令
+///
+/// 每条命令同时生成元数据和分发逻辑：参数按声明反序列化后调用对应的 Tauri 命令函数，
+/// 状态参数通过 [`managed`] 获取，登录令牌由 `execute_command_by_id` 统一传入。
+macro_rules! palette_commands {
+    ($(
+        $id:ident {
+            title: $title:literal,
+            category: $category:literal,
+            permission: $permission:expr,
+            params: { $($param:ident: $ty:ty),* $(,)? },
+            run: |$app:ident, $args:ident, $token:ident| $body:expr $(,)?
+        }
+    ),* $(,)?) => {
+        /// 命令面板中全部命令的元数据
+        pub fn palette_catalog() -> Vec<CommandMetadata> {
+            vec![$(CommandMetadata {
+                id: stringify!($id),
+                title: $title,
+                category: $category,
+                params: vec![$(CommandParam::new(stringify!($param), stringify!($ty))),*],
+                permission: $permission,
+            }),*]
+        }
+
+        async fn dispatch(app: &AppHandle, id: &str, args: JsonValue, token: String) -> Result<JsonValue, String> {
+            match id {
+                $(stringify!($id) => {
+                    #[derive(Deserialize)]
+                    #[serde(rename_all = "camelCase")]
+                    struct Args { $($param: $ty),* }
+
+                    let parsed: Args = serde_json::from_value(args)
+                        .map_err(|e| format!("命令 {} 的参数无效: {}", stringify!($id), e))?;
+                    #[allow(unused_variables)]
+                    let ($app, $args, $token) = (app, parsed, token);
+                    let result = $body.await?;
+                    serde_json::to_value(result).map_err(|e| format!("序列化命令结果失败: {}", e))
+                })*
+                _ => Err(format!("未知命令: {}", id)),
+            }
+        }
+    };
+}
+
+palette_commands! {
+    get_projects {
+        title: "列出项目",
+        category: "项目",
+        permission: CommandPermission::Authenticated,
+        params: {},
+        run: |app, args, token| get_projects(token, managed(app)?),
+    },
+    get_project {
+        title: "查看项目",
+        category: "项目",
+        permission: CommandPermission::ProjectRole(ProjectRole::Viewer),
+        params: { project_id: String },
+        run: |app, args, token| get_project(args.project_id, token, managed(app)?),
+    },
+    list_project_members {
+        title: "列出项目成员",
+        category: "项目",
+        permission: CommandPermission::ProjectRole(ProjectRole::Viewer),
+        params: { project_id: String },
+        run: |app, args, token| list_project_members(args.project_id, token, managed(app)?),
+    },
+    get_orchestration_config {
+        title: "查看编排配置",
+        category: "项目",
+        permission: CommandPermission::ProjectRole(ProjectRole::Viewer),
+        params: { project_id: String },
+        run: |app, args, token| get_orchestration_config(args.project_id, token, managed(app)?),
+    },
+    get_agents {
+        title: "列出智能体",
+        category: "智能体",
+        permission: CommandPermission::Authenticated,
+        params: {},
+        run: |app, args, token| get_agents(token, managed(app)?),
+    },
+    get_execution_waves {
+        title: "查看执行波次",
+        category: "执行",
+        permission: CommandPermission::ProjectRole(ProjectRole::Viewer),
+        params: { project_id: String },
+        run: |app, args, token| get_execution_waves(args.project_id, token, managed(app)?),
+    },
+    list_operations {
+        title: "列出后台操作",
+        category: "执行",
+        permission: CommandPermission::ProjectRole(ProjectRole::Viewer),
+        params: { project_id: String },
+        run: |app, args, token| list_operations(args.project_id, token, managed(app)?, managed(app)?),
+    },
+    cancel_operation {
+        title: "取消后台操作",
+        category: "执行",
+        permission: CommandPermission::ProjectRole(ProjectRole::Contributor),
+        params: { operation_id: String },
+        run: |app, args, token| cancel_operation(args.operation_id, token, managed(app)?, managed(app)?),
+    },
+    list_project_worktrees {
+        title: "列出工作树",
+        category: "执行",
+        permission: CommandPermission::ProjectRole(ProjectRole::Viewer),
+        params: { project_id: String },
+        run: |app, args, token| list_project_worktrees(args.project_id, token, managed(app)?, managed(app)?),
+    },
+    prune_project_worktrees {
+        title: "清理工作树",
+        category: "执行",
+        permission: CommandPermission::ProjectRole(ProjectRole::Maintainer),
+        params: { project_id: String },
+        run: |app, args, token| prune_project_worktrees(args.project_id, token, managed(app)?, managed(app)?),
+    },
+    semantic_search {
+        title: "语义检索",
+        category: "检索",
+        permission: CommandPermission::ProjectRole(ProjectRole::Viewer),
+        params: { project_id: String, query: String, source_types: Option<Vec<String>>, top_k: Option<usize> },
+        run: |app, args, token| semantic_search(
+            args.project_id, args.query, args.source_types, args.top_k, token, managed(app)?, managed(app)?,
+        ),
+    },
+    rebuild_search_index {
+        title: "重建检索索引",
+        category: "检索",
+        permission: CommandPermission::ProjectRole(ProjectRole::Maintainer),
+        params: { project_id: String },
+        run: |app, args, token| rebuild_search_index(args.project_id, token, managed(app)?, managed(app)?),
+    },
+    list_conflicts {
+        title: "列出冲突",
+        category: "质量",
+        permission: CommandPermission::ProjectRole(ProjectRole::Viewer),
+        params: { project_id: String, filter: Option<ConflictListFilter>, page: Option<u64>, page_size: Option<u64> },
+        run: |app, args, token| list_conflicts(args.project_id, args.filter, args.page, args.page_size, token, managed(app)?),
+    },
+    evaluate_project_sla {
+        title: "检查SLA",
+        category: "质量",
+        permission: CommandPermission::ProjectRole(ProjectRole::Contributor),
+        params: { project_id: String },
+        run: |app, args, token| evaluate_project_sla(args.project_id, token, managed(app)?),
+    },
+    get_flaky_test_report {
+        title: "查看不稳定测试",
+        category: "质量",
+        permission: CommandPermission::ProjectRole(ProjectRole::Viewer),
+        params: { project_id: String, config: Option<codex_database::flaky_tests::FlakyTestConfig> },
+        run: |app, args, token| get_flaky_test_report(args.project_id, args.config, token, managed(app)?),
+    },
+    generate_project_report {
+        title: "生成项目报告",
+        category: "报告",
+        permission: CommandPermission::ProjectRole(ProjectRole::Contributor),
+        params: { project_id: String, options: Option<codex_database::reporting::ReportOptions> },
+        run: |app, args, token| generate_project_report(args.project_id, args.options, token, managed(app)?, managed(app)?),
+    },
+    list_notifications {
+        title: "查看通知",
+        category: "通知",
+        permission: CommandPermission::Authenticated,
+        params: { limit: Option<u64> },
+        run: |app, args, token| list_notifications(args.limit, token, managed(app)?),
+    },
+    mark_notifications_read {
+        title: "标记通知已读",
+        category: "通知",
+        permission: CommandPermission::Authenticated,
+        params: { notification_ids: Vec<String> },
+        run: |app, args, token| mark_notifications_read(args.notification_ids, token, managed(app)?),
+    },
+    get_llm_cache_stats {
+        title: "查看LLM缓存统计",
+        category: "系统",
+        permission: CommandPermission::Authenticated,
+        params: {},
+        run: |app, args, token| get_llm_cache_stats(token, managed(app)?, managed(app)?),
+    },
+    clear_llm_cache {
+        title: "清空LLM缓存",
+        category: "系统",
+        permission: CommandPermission::Authenticated,
+        params: {},
+        run: |app, args, token| clear_llm_cache(token, managed(app)?, managed(app)?),
+    },
+    list_maintenance_jobs {
+        title: "列出维护任务",
+        category: "系统",
+        permission: CommandPermission::Authenticated,
+        params: {},
+        run: |app, args, token| list_maintenance_jobs(token, managed(app)?),
+    },
+    trigger_maintenance_job {
+        title: "立即执行维护任务",
+        category: "系统",
+        permission: CommandPermission::Authenticated,
+        params: { job_id: String },
+        run: |app, args, token| trigger_maintenance_job(args.job_id, token, managed(app)?, managed(app)?),
+    },
+    load_demo_workspace {
+        title: "加载演示工作区",
+        category: "系统",
+        permission: CommandPermission::Authenticated,
+        params: {},
+        run: |app, args, token| load_demo_workspace(token, managed(app)?),
+    },
+    list_profiles {
+        title: "列出配置档案",
+        category: "系统",
+        permission: CommandPermission::Public,
+        params: {},
+        run: |app, args, token| list_profiles(),
+    },
+    switch_profile {
+        title: "切换配置档案",
+        category: "系统",
+        permission: CommandPermission::Public,
+        params: { name: String },
+        run: |app, args, token| switch_profile(args.name, app.clone()),
+    },
+    check_for_updates {
+        title: "检查更新",
+        category: "系统",
+        permission: CommandPermission::Public,
+        params: {},
+        run: |app, args, token| check_for_updates(app.clone()),
+    },
+    diagnose_system {
+        title: "系统诊断",
+        category: "系统",
+        permission: CommandPermission::Public,
+        params: {},
+        run: |app, args, token| diagnose_system(),
+    },
+}
+
+/// 列出当前用户可执行的命令
+///
+/// 未登录时只返回无需登录的命令；指定项目时按用户在项目中的角色过滤需要项目角色的命令。
+#[tauri::command]
+pub async fn list_available_commands(
+    token: Option<String>,
+    project_id: Option<String>,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<CommandMetadata>, String> {
+    let user_id = match token {
+        Some(token) => crate::auth::AuthService::new((**db).clone())
+            .validate_token(&token).await
+            .ok()
+            .map(|user| user.user_id),
+        None => None,
+    };
+
+    let role = match (user_id, project_id) {
+        (Some(user_id), Some(project_id)) => {
+            let project_uuid = Uuid::parse_str(&project_id).map_err(|_| "无效的项目ID格式")?;
+            Some(ProjectMemberRepository::new((**db).clone())
+                .get_role(project_uuid, user_id).await
+                .map_err(|e| format!("查询项目角色失败: {}", e))?)
+        }
+        _ => None,
+    };
+
+    Ok(palette_catalog()
+        .into_iter()
+        .filter(|command| match command.permission {
+            CommandPermission::Public => true,
+            CommandPermission::Authenticated => user_id.is_some(),
+            CommandPermission::ProjectRole(required) => match role {
+                Some(role) => role.is_some_and(|role| role.at_least(required)),
+                None => user_id.is_some(),
+            },
+        })
+        .collect())
+}
+
+/// 按命令ID执行命令，`args` 为参数对象（驼峰命名），返回命令结果的 JSON
+#[tauri::command]
+pub async fn execute_command_by_id(
+    command_id: String,
+    args: Option<JsonValue>,
+    token: Option<String>,
+    app: AppHandle,
+) -> Result<JsonValue, String> {
+    let command = palette_catalog()
+        .into_iter()
+        .find(|command| command.id == command_id)
+        .ok_or_else(|| format!("未知命令: {}", command_id))?;
+    if command.permission != CommandPermission::Public && token.is_none() {
+        return Err(format!("命令 {} 需要登录", command.title));
+    }
+
+    let args = match args {
+        None | Some(JsonValue::Null) => JsonValue::Object(Default::default()),
+        Some(args @ JsonValue::Object(_)) => args,
+        Some(_) => return Err("命令参数必须是对象".to_string()),
+    };
+
+    println!("命令面板执行: {}", command_id);
+    dispatch(&app, &command_id, args, token.unwrap_or_default()).await
+}
+
+/// 获取托管状态，状态尚未初始化时返回错误而不是 panic
+fn managed<T: Send + Sync + 'static>(app: &AppHandle) -> Result<State<'_, T>, String> {
+    app.try_state::<T>()
+        .ok_or_else(|| format!("{} 尚未初始化", std::any::type_name::<T>()))
+}
+
+fn to_camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::Mutex;
+use codex_core::protocol::{ApplyPatchApprovalRequestEvent, FileChange};
+use codex_database::session_diff::{parse_unified_diff, whole_file_hunk, DiffHunk, DiffLineKind, FileChangeType, FileDiff};
+use crate::models::PatchDiff;
+
+// 待审批补丁缓存，键为审批ID（即事件ID）
+pub type PendingPatchesHandle = Arc<Mutex<HashMap<String, PatchDiff>>>;
+
+/// 获取待审批补丁的结构化差异
+#[tauri::command]
+pub async fn get_pending_patch(
+    approval_id: String,
+    pending_patches: State<'_, PendingPatchesHandle>,
+) -> Result<PatchDiff, String> {
+    let pending = pending_patches.lock().await;
+    pending
+        .get(&approval_id)
+        .cloned()
+        .ok_or_else(|| format!("未找到待审批补丁: {}", approval_id))
+}
+
+/// 记录补丁审批请求，供前端在审批前渲染差异
+pub async fn record_pending_patch(
+    pending_patches: &PendingPatchesHandle,
+    conversation_id: &str,
+    approval_id: &str,
+    event: &ApplyPatchApprovalRequestEvent,
+) {
+    let diff = build_patch_diff(conversation_id, approval_id, event);
+    println!(
+        "记录待审批补丁: {} ({} 个文件, +{} -{})",
+        approval_id,
+        diff.files.len(),
+        diff.total_additions,
+        diff.total_deletions
+    );
+    pending_patches.lock().await.insert(approval_id.to_string(), diff);
+}
+
+/// 审批完成后移除缓存的补丁
+pub async fn take_pending_patch(
+    pending_patches: &PendingPatchesHandle,
+    approval_id: &str,
+) -> Option<PatchDiff> {
+    pending_patches.lock().await.remove(approval_id)
+}
+
+/// 从Codex补丁事件构建结构化差异
+pub fn build_patch_diff(
+    conversation_id: &str,
+    approval_id: &str,
+    event: &ApplyPatchApprovalRequestEvent,
+) -> PatchDiff {
+    let mut files: Vec<FileDiff> = event
+        .changes
+        .iter()
+        .map(|(path, change)| build_file_diff(path, change))
+        .collect();
+    // HashMap无序，按路径排序保证前端展示稳定
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let total_additions = files.iter().map(|f| f.additions).sum();
+    let total_deletions = files.iter().map(|f| f.deletions).sum();
+
+    PatchDiff {
+        approval_id: approval_id.to_string(),
+        conversation_id: conversation_id.to_string(),
+        call_id: event.call_id.clone(),
+        reason: event.reason.clone(),
+        grant_root: event.grant_root.as_ref().map(|p| p.display().to_string()),
+        files,
+        total_additions,
+        total_deletions,
+        requested_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+fn build_file_diff(path: &Path, change: &FileChange) -> FileDiff {
+    let path_str = path.display().to_string();
+    match change {
+        FileChange::Add { content } => {
+            let is_binary = is_binary_content(content);
+            let hunks: Vec<DiffHunk> = if is_binary {
+                Vec::new()
+            } else {
+                whole_file_hunk(content, DiffLineKind::Addition).into_iter().collect()
+            };
+            FileDiff {
+                path: path_str,
+                move_path: None,
+                change_type: FileChangeType::Add,
+                is_binary,
+                additions: count_lines(&hunks, DiffLineKind::Addition),
+                deletions: 0,
+                hunks,
+            }
+        }
+        FileChange::Delete { content } => {
+            let is_binary = is_binary_content(content);
+            let hunks: Vec<DiffHunk> = if is_binary {
+                Vec::new()
+            } else {
+                whole_file_hunk(content, DiffLineKind::Deletion).into_iter().collect()
+            };
+            FileDiff {
+                path: path_str,
+                move_path: None,
+                change_type: FileChangeType::Delete,
+                is_binary,
+                additions: 0,
+                deletions: count_lines(&hunks, DiffLineKind::Deletion),
+                hunks,
+            }
+        }
+        FileChange::Update { unified_diff, move_path } => {
+            let is_binary = is_binary_content(unified_diff)
+                || unified_diff.lines().any(|l| l.starts_with("Binary files") || l.starts_with("GIT binary patch"));
+            let hunks = if is_binary { Vec::new() } else { parse_unified_diff(unified_diff) };
+            FileDiff {
+                path: path_str,
+                move_path: move_path.as_ref().map(|p| p.display().to_string()),
+                change_type: FileChangeType::Update,
+                is_binary,
+                additions: count_lines(&hunks, DiffLineKind::Addition),
+                deletions: count_lines(&hunks, DiffLineKind::Deletion),
+                hunks,
+            }
+        }
+    }
+}
+
+fn is_binary_content(content: &str) -> bool {
+    content.contains('\0')
+}
+
+fn count_lines(hunks: &[DiffHunk], kind: DiffLineKind) -> usize {
+    hunks.iter().map(|hunk| hunk.count(kind)).sum()
+}
@@ -0,0 +1,113 @@
+use tauri::State;
+use codex_database::entities::benchmark_result;
+use codex_database::perf_budget::{self, CommandBenchmarkRunner, PerfBudget, PerfCheckReport, PerfGateResult};
+use codex_database::repository::ProjectRepository;
+use codex_database::static_analysis;
+use codex_multi_agent::ProjectRole;
+use crate::commands::members::{authorize_project, authorize_session};
+use crate::commands::projects::DatabaseHandle;
+
+/// 历史查询默认返回的结果数量
+const DEFAULT_HISTORY_LIMIT: u64 = 50;
+
+/// 获取项目的性能预算
+#[tauri::command]
+pub async fn get_project_perf_budget(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<PerfBudget>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    perf_budget::project_perf_budget(&db, project_uuid).await
+        .map_err(|e| format!("获取性能预算失败: {}", e))
+}
+
+/// 更新项目的性能预算，传入空值表示不做性能回归检查
+#[tauri::command]
+pub async fn update_project_perf_budget(
+    project_id: String,
+    budget: Option<PerfBudget>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<(), String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    ProjectRepository::new((**db).clone()).set_perf_budget(project_uuid, budget).await
+        .map_err(|e| format!("更新性能预算失败: {}", e))?;
+    println!("项目 {} 的性能预算已更新", project_id);
+    Ok(())
+}
+
+/// 在执行会话的工作空间中运行性能预算中的基准测试，并与基线比较
+#[tauri::command]
+pub async fn run_session_perf_check(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<PerfCheckReport, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Contributor).await?;
+
+    let workspace = static_analysis::session_workspace(&db, session_uuid).await
+        .map_err(|e| format!("获取工作空间失败: {}", e))?;
+    let report = perf_budget::run_session_benchmarks(&db, session_uuid, &workspace, &CommandBenchmarkRunner).await
+        .map_err(|e| format!("运行性能检查失败: {}", e))?;
+    println!(
+        "执行会话 {} 性能检查完成，共 {} 项基准测试，{}",
+        session_id,
+        report.gate.comparisons.len(),
+        if report.gate.passed { "未超出预算" } else { "存在超出预算的回归" }
+    );
+    Ok(report)
+}
+
+/// 评估执行会话的性能门禁，项目没有配置性能预算或还没有运行基准测试时返回空
+#[tauri::command]
+pub async fn evaluate_session_perf_gate(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<PerfGateResult>, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Viewer).await?;
+
+    perf_budget::evaluate_perf_gate(&db, session_uuid).await
+        .map_err(|e| format!("评估性能门禁失败: {}", e))
+}
+
+/// 把执行会话的基准测试结果提升为所在分支的基线
+#[tauri::command]
+pub async fn promote_session_perf_baseline(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<usize, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    perf_budget::promote_session_baseline(&db, session_uuid).await
+        .map_err(|e| format!("提升性能基线失败: {}", e))
+}
+
+/// 获取分支上某项基准测试的历史结果，用于趋势图
+#[tauri::command]
+pub async fn get_benchmark_history(
+    project_id: String,
+    branch: String,
+    suite: String,
+    name: String,
+    limit: Option<u64>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<benchmark_result::Model>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    perf_budget::benchmark_history(
+        &db,
+        project_uuid,
+        &branch,
+        &suite,
+        &name,
+        limit.unwrap_or(DEFAULT_HISTORY_LIMIT),
+    )
+    .await
+    .map_err(|e| format!("获取基准测试历史失败: {}", e))
+}
@@ -0,0 +1,77 @@
+use tauri::State;
+use codex_database::pii_scrubbing::{self, DecompositionPrompt, PiiScrubbingConfig};
+use codex_database::repository::{ProjectRepository, RequirementDocumentRepository};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::authorize_project;
+use crate::commands::projects::DatabaseHandle;
+
+/// 获取项目的个人信息脱敏配置，未配置时返回 None
+#[tauri::command]
+pub async fn get_project_pii_scrubbing(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<PiiScrubbingConfig>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    let project = ProjectRepository::new((**db).clone()).find_by_id(project_uuid).await
+        .map_err(|e| format!("获取项目失败: {}", e))?
+        .ok_or("项目不存在")?;
+    project.pii_scrubbing
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| format!("脱敏配置无效: {}", e))
+}
+
+/// 更新项目的个人信息脱敏配置，传入 None 关闭脱敏
+#[tauri::command]
+pub async fn update_project_pii_scrubbing(
+    project_id: String,
+    config: Option<PiiScrubbingConfig>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<(), String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    ProjectRepository::new((**db).clone()).set_pii_scrubbing(project_uuid, config).await
+        .map_err(|e| format!("更新脱敏配置失败: {}", e))?;
+    println!("已更新项目脱敏配置: {}", project_id);
+    Ok(())
+}
+
+/// 为需求文档构建任务分解提示词，项目启用脱敏时其中的个人信息已替换为占位符
+#[tauri::command]
+pub async fn build_decomposition_prompt(
+    document_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<DecompositionPrompt, String> {
+    let document_uuid = Uuid::parse_str(&document_id)
+        .map_err(|_| "无效的文档ID格式")?;
+    let document = RequirementDocumentRepository::new((**db).clone()).find_by_id(document_uuid).await
+        .map_err(|e| format!("获取需求文档失败: {}", e))?
+        .ok_or("需求文档不存在")?;
+    authorize_project(&document.project_id.to_string(), &token, &db, ProjectRole::Contributor).await?;
+
+    let prompt = pii_scrubbing::build_decomposition_prompt(&db, document_uuid).await
+        .map_err(|e| format!("构建分解提示词失败: {}", e))?;
+    if prompt.scrubbed_items > 0 {
+        println!("分解提示词已脱敏 {} 处个人信息: {}", prompt.scrubbed_items, document_id);
+    }
+    Ok(prompt)
+}
+
+/// 把 LLM 返回结果中的占位符还原为原文
+#[tauri::command]
+pub async fn reidentify_pii(
+    project_id: String,
+    text: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<String, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Contributor).await?;
+
+    pii_scrubbing::reidentify(&db, project_uuid, &text).await
+        .map_err(|e| format!("还原个人信息失败: {}", e))
+}
@@ -0,0 +1,63 @@
+use tauri::State;
+use codex_database::preemption::{self, PreemptionPolicy};
+use codex_database::repository::{ProjectRepository, TaskRepository};
+use codex_multi_agent::{ProjectRole, SystemClock, TaskPreemptedEvent};
+use uuid::Uuid;
+use crate::commands::members::authorize_project;
+use crate::commands::projects::DatabaseHandle;
+
+/// 获取项目的紧急任务抢占策略，未配置时返回 None
+#[tauri::command]
+pub async fn get_project_preemption_policy(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<PreemptionPolicy>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    let project = ProjectRepository::new((**db).clone()).find_by_id(project_uuid).await
+        .map_err(|e| format!("获取项目失败: {}", e))?
+        .ok_or("项目不存在")?;
+    project.preemption_policy
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| format!("抢占策略无效: {}", e))
+}
+
+/// 更新项目的紧急任务抢占策略，传入 None 关闭抢占
+#[tauri::command]
+pub async fn update_project_preemption_policy(
+    project_id: String,
+    policy: Option<PreemptionPolicy>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<(), String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    ProjectRepository::new((**db).clone()).set_preemption_policy(project_uuid, policy).await
+        .map_err(|e| format!("更新抢占策略失败: {}", e))?;
+    println!("已更新项目抢占策略: {}", project_id);
+    Ok(())
+}
+
+/// 为紧急任务抢占一个运行中的低优先级任务，没有可抢占的任务时返回 None
+#[tauri::command]
+pub async fn preempt_for_critical_task(
+    task_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<TaskPreemptedEvent>, String> {
+    let task_uuid = Uuid::parse_str(&task_id)
+        .map_err(|_| "无效的任务ID格式")?;
+    let task = TaskRepository::new((**db).clone()).find_by_id(task_uuid).await
+        .map_err(|e| format!("获取任务失败: {}", e))?
+        .ok_or("任务不存在")?;
+    authorize_project(&task.project_id.to_string(), &token, &db, ProjectRole::Maintainer).await?;
+
+    let event = preemption::preempt_for_critical_task(&db, task_uuid, &SystemClock).await
+        .map_err(|e| format!("抢占任务失败: {}", e))?;
+    if let Some(event) = &event {
+        println!("紧急任务 {} 抢占了任务 {}", task_id, event.preempted_task_id);
+    }
+    Ok(event)
+}
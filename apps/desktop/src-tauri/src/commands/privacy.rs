@@ -0,0 +1,53 @@
+use tauri::State;
+use codex_database::data_purge::{self, PurgeReport};
+use codex_database::repository::OrganizationRepository;
+use codex_multi_agent::OrganizationRole;
+use uuid::Uuid;
+use crate::commands::projects::DatabaseHandle;
+
+/// 清除用户的全部个人数据，返回清除报告
+///
+/// 用户可以清除自己的数据，组织所有者可以清除本组织成员的数据；清除自己的数据后当前登录会话随之失效
+#[tauri::command]
+pub async fn purge_user_data(
+    user_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<PurgeReport, String> {
+    let target_id = Uuid::parse_str(&user_id)
+        .map_err(|_| "无效的用户ID格式")?;
+    let operator_id = authorize_purge(target_id, &token, &db).await?;
+
+    let report = data_purge::purge_user_data(&db, target_id, Some(operator_id)).await
+        .map_err(|e| format!("清除用户数据失败: {}", e))?;
+    println!(
+        "用户 {} 的数据已清除：删除 {} 个项目，转交 {} 个项目，审计事件 {}",
+        user_id,
+        report.deleted_projects.len(),
+        report.transferred_projects.len(),
+        report.audit_event_id
+    );
+    Ok(report)
+}
+
+/// 校验令牌，要求当前用户是目标用户本人或目标用户所在组织的所有者
+async fn authorize_purge(target_id: Uuid, token: &str, db: &DatabaseHandle) -> Result<Uuid, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    if current_user.user_id == target_id {
+        return Ok(current_user.user_id);
+    }
+
+    let organizations = OrganizationRepository::new((**db).clone());
+    for organization in organizations.find_by_user(target_id).await
+        .map_err(|e| format!("查询用户所在组织失败: {}", e))?
+    {
+        let role = organizations.get_role(organization.organization_id, current_user.user_id).await
+            .map_err(|e| format!("查询组织角色失败: {}", e))?;
+        if role == Some(OrganizationRole::Owner) {
+            return Ok(current_user.user_id);
+        }
+    }
+    Err("无权清除该用户的数据".to_string())
+}
@@ -0,0 +1,64 @@
+use serde::Serialize;
+use crate::profiles::{self, ActiveProfile, Profile};
+
+/// 档案信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub data_dir: String,
+    pub created_at: i64,
+    /// 本次运行正在使用
+    pub is_current: bool,
+    /// 下次启动默认使用
+    pub is_default_on_startup: bool,
+}
+
+/// 档案列表
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileList {
+    pub current: ActiveProfile,
+    pub profiles: Vec<ProfileInfo>,
+}
+
+/// 获取全部档案及当前使用的档案
+#[tauri::command]
+pub async fn list_profiles() -> Result<ProfileList, String> {
+    let registry = profiles::load_registry()?;
+    let current = profiles::active().clone();
+    let profiles = registry.profiles
+        .iter()
+        .map(|profile| ProfileInfo {
+            name: profile.name.clone(),
+            data_dir: profile.data_dir().to_string_lossy().to_string(),
+            created_at: profile.created_at,
+            is_current: profile.name == current.name,
+            is_default_on_startup: profile.name == registry.active,
+        })
+        .collect();
+    Ok(ProfileList { current, profiles })
+}
+
+/// 创建档案，`data_dir` 为空时使用默认位置
+#[tauri::command]
+pub async fn create_profile(name: String, data_dir: Option<String>) -> Result<Profile, String> {
+    let profile = profiles::create_profile(&name, data_dir)?;
+    println!("已创建档案 {}: {}", profile.name, profile.data_dir().display());
+    Ok(profile)
+}
+
+/// 切换到指定档案并重启应用
+#[tauri::command]
+pub async fn switch_profile(name: String, app: tauri::AppHandle) -> Result<(), String> {
+    profiles::set_active(&name)?;
+    if profiles::active().name == name {
+        return Ok(());
+    }
+    println!("切换到档案 {}，正在重启", name);
+    app.restart();
+}
+
+/// 从档案列表中移除档案，数据目录保留在磁盘上
+#[tauri::command]
+pub async fn delete_profile(name: String) -> Result<Profile, String> {
+    profiles::remove_profile(&name)
+}
@@ -0,0 +1,65 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::State;
+use codex_database::{
+    project_bootstrap::{self, BootstrapRequest, RepositoryDetection},
+    repository::ProjectMemberRepository,
+};
+use codex_multi_agent::{CodebaseInfo, ProjectRole};
+use crate::commands::projects::DatabaseHandle;
+
+/// 从Git仓库导入的项目
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedProject {
+    pub project: crate::models::Project,
+    pub detection: RepositoryDetection,
+    pub codebase_info: CodebaseInfo,
+}
+
+/// 检测本地Git仓库，返回建议的项目配置，不创建项目
+#[tauri::command]
+pub async fn detect_git_repository(
+    path: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<RepositoryDetection, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    project_bootstrap::detect_repository(&PathBuf::from(&path)).await
+        .map_err(|e| format!("检测仓库失败: {}", e))
+}
+
+/// 从本地仓库路径或远程地址导入项目，自动识别项目配置并采集初始代码库快照
+#[tauri::command]
+pub async fn import_project_from_git(
+    request: BootstrapRequest,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ImportedProject, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    println!("从Git仓库导入项目: {} (用户: {})", request.source, current_user.username);
+
+    let db = &**db;
+    let result = project_bootstrap::bootstrap_project(db, current_user.user_id, request).await
+        .map_err(|e| format!("导入项目失败: {}", e))?;
+
+    // 导入者成为项目所有者
+    ProjectMemberRepository::new(db.clone())
+        .add_member(result.project.project_id, current_user.user_id, ProjectRole::Owner, None).await
+        .map_err(|e| format!("添加项目所有者失败: {}", e))?;
+
+    let created_project = result.project;
+    let project = crate::models::Project::try_from(created_project)?;
+
+    println!("项目导入成功: {}", project.project_id);
+    Ok(ImportedProject {
+        project,
+        detection: result.detection,
+        codebase_info: result.codebase_info,
+    })
+}
@@ -1,10 +1,15 @@
 use tauri::State;
 use std::sync::Arc;
 use codex_database::{
-    DatabaseConnection,
-    repository::project_repository::{ProjectRepository, CreateProjectData},
+    DatabaseConnection, TenantScope,
+    repository::{
+        ProjectMemberRepository,
+        project_repository::{ProjectRepository, CreateProjectData},
+    },
 };
+use codex_multi_agent::ProjectRole;
 use uuid::Uuid;
+use crate::commands::members::require_project_role;
 use crate::models::{CreateProjectRequest, UpdateProjectRequest};
 
 // 数据库连接管理器
@@ -41,23 +46,14 @@ pub async fn create_project(
     
     let created_project = project_repo.create(project_data).await
         .map_err(|e| format!("创建项目失败: {}", e))?;
+
+    // 创建者成为项目所有者
+    ProjectMemberRepository::new(db.clone())
+        .add_member(created_project.project_id, user_id, ProjectRole::Owner, None).await
+        .map_err(|e| format!("添加项目所有者失败: {}", e))?;
     
     // 转换为前端模型
-    let project = crate::models::Project {
-        project_id: created_project.project_id.to_string(),
-        user_id: created_project.user_id.to_string(),
-        name: created_project.name,
-        description: created_project.description,
-        repository_url: created_project.repository_url,
-        main_branch: created_project.main_branch,
-        workspace_path: created_project.workspace_path,
-        technology_stack: created_project.technology_stack
-            .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
-            .unwrap_or_default(),
-        status: created_project.status,
-        created_at: created_project.created_at.to_rfc3339(),
-        updated_at: created_project.updated_at.to_rfc3339(),
-    };
+    let project = crate::models::Project::try_from(created_project)?;
     
     println!("项目创建成功: {}", project.project_id);
     Ok(project)
@@ -77,29 +73,15 @@ pub async fn get_projects(
     println!("获取用户 {} 的项目列表", current_user.username);
     
     let db = &**db;
-    let project_repo = ProjectRepository::new(db.clone());
-    
-    // 获取当前用户的所有项目
-    let projects = project_repo.find_by_user(current_user.user_id).await
+    // 用户范围内即当前用户创建的项目以及作为成员参与的项目
+    let projects = ProjectRepository::new(db.clone())
+        .with_scope(TenantScope::user(current_user.user_id))
+        .find_all().await
         .map_err(|e| format!("查询项目失败: {}", e))?;
     
-    let result: Vec<crate::models::Project> = projects.into_iter().map(|p| {
-        crate::models::Project {
-            project_id: p.project_id.to_string(),
-            user_id: p.user_id.to_string(),
-            name: p.name,
-            description: p.description,
-            repository_url: p.repository_url,
-            main_branch: p.main_branch,
-            workspace_path: p.workspace_path,
-            technology_stack: p.technology_stack
-                .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
-                .unwrap_or_default(),
-            status: p.status,
-            created_at: p.created_at.to_rfc3339(),
-            updated_at: p.updated_at.to_rfc3339(),
-        }
-    }).collect();
+    let result = projects.into_iter()
+        .map(crate::models::Project::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
     
     println!("返回项目数量: {}", result.len());
     Ok(result)
@@ -109,36 +91,32 @@ pub async fn get_projects(
 #[tauri::command]
 pub async fn get_project(
     project_id: String,
+    token: String,
     db: State<'_, DatabaseHandle>,
 ) -> Result<Option<crate::models::Project>, String> {
+    // 验证token并获取当前用户
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
     println!("获取项目详情: {}", project_id);
     
     let project_uuid = Uuid::parse_str(&project_id)
         .map_err(|_| "无效的项目ID格式")?;
     
     let db = &**db;
-    let project_repo = ProjectRepository::new(db.clone());
+    let project_repo = ProjectRepository::new(db.clone())
+        .with_scope(TenantScope::user(current_user.user_id));
     
     let project = project_repo.find_by_id(project_uuid).await
         .map_err(|e| format!("查询项目失败: {}", e))?;
+    if project.is_some() {
+        require_project_role(db, project_uuid, current_user.user_id, ProjectRole::Viewer).await?;
+    }
     
     match project {
         Some(p) => {
-            let result = crate::models::Project {
-                project_id: p.project_id.to_string(),
-                user_id: p.user_id.to_string(),
-                name: p.name,
-                description: p.description,
-                repository_url: p.repository_url,
-                main_branch: p.main_branch,
-                workspace_path: p.workspace_path,
-                technology_stack: p.technology_stack
-                    .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
-                    .unwrap_or_default(),
-                status: p.status,
-                created_at: p.created_at.to_rfc3339(),
-                updated_at: p.updated_at.to_rfc3339(),
-            };
+            let result = crate::models::Project::try_from(p)?;
             Ok(Some(result))
         }
         None => Ok(None)
@@ -149,36 +127,30 @@ pub async fn get_project(
 #[tauri::command]
 pub async fn update_project(
     request: UpdateProjectRequest,
+    token: String,
     db: State<'_, DatabaseHandle>,
 ) -> Result<crate::models::Project, String> {
+    // 验证token并获取当前用户
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
     println!("更新项目: {}", request.project_id);
     
     let project_uuid = Uuid::parse_str(&request.project_id)
         .map_err(|_| "无效的项目ID格式")?;
     
     let db = &**db;
-    let project_repo = ProjectRepository::new(db.clone());
+    require_project_role(db, project_uuid, current_user.user_id, ProjectRole::Maintainer).await?;
+    let project_repo = ProjectRepository::new(db.clone())
+        .with_scope(TenantScope::user(current_user.user_id));
     
     // 目前简化实现：只支持状态更新
     if let Some(status) = request.status {
         let updated_project = project_repo.update_status(project_uuid, &status).await
             .map_err(|e| format!("更新项目状态失败: {}", e))?;
         
-        let result = crate::models::Project {
-            project_id: updated_project.project_id.to_string(),
-            user_id: updated_project.user_id.to_string(),
-            name: updated_project.name,
-            description: updated_project.description,
-            repository_url: updated_project.repository_url,
-            main_branch: updated_project.main_branch,
-            workspace_path: updated_project.workspace_path,
-            technology_stack: updated_project.technology_stack
-                .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
-                .unwrap_or_default(),
-            status: updated_project.status,
-            created_at: updated_project.created_at.to_rfc3339(),
-            updated_at: updated_project.updated_at.to_rfc3339(),
-        };
+        let result = crate::models::Project::try_from(updated_project)?;
         
         println!("项目状态更新成功: {}", result.project_id);
         Ok(result)
@@ -191,15 +163,23 @@ pub async fn update_project(
 #[tauri::command]
 pub async fn delete_project(
     project_id: String,
+    token: String,
     db: State<'_, DatabaseHandle>,
 ) -> Result<(), String> {
+    // 验证token并获取当前用户
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
     println!("删除项目: {}", project_id);
     
     let project_uuid = Uuid::parse_str(&project_id)
         .map_err(|_| "无效的项目ID格式")?;
     
     let db = &**db;
-    let project_repo = ProjectRepository::new(db.clone());
+    require_project_role(db, project_uuid, current_user.user_id, ProjectRole::Owner).await?;
+    let project_repo = ProjectRepository::new(db.clone())
+        .with_scope(TenantScope::user(current_user.user_id));
     
     project_repo.delete(project_uuid).await
         .map_err(|e| format!("删除项目失败: {}", e))?;
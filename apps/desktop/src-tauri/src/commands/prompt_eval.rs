@@ -0,0 +1,86 @@
+use tauri::State;
+use codex_database::entities::eval_set;
+use codex_database::prompt_eval::{self, EvalRunReport, EvalSet};
+use codex_database::repository::EvalSetRepository;
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::require_project_role;
+use crate::commands::projects::DatabaseHandle;
+
+/// 列出项目的评估集
+#[tauri::command]
+pub async fn list_eval_sets(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<eval_set::Model>, String> {
+    let project_uuid = Uuid::parse_str(&project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    let user_id = authenticate(&token, &db).await?;
+    require_project_role(&db, project_uuid, user_id, ProjectRole::Viewer).await?;
+
+    EvalSetRepository::new((**db).clone()).find_by_project(Some(project_uuid)).await
+        .map_err(|e| format!("获取评估集失败: {}", e))
+}
+
+/// 保存项目的评估集，`eval_set_id` 为空时新建
+#[tauri::command]
+pub async fn save_eval_set(
+    project_id: String,
+    eval_set_id: Option<String>,
+    eval_set: EvalSet,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<eval_set::Model, String> {
+    let project_uuid = Uuid::parse_str(&project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    let user_id = authenticate(&token, &db).await?;
+    require_project_role(&db, project_uuid, user_id, ProjectRole::Contributor).await?;
+
+    let eval_set_uuid = match eval_set_id {
+        Some(eval_set_id) => {
+            let eval_set_uuid = Uuid::parse_str(&eval_set_id)
+                .map_err(|_| "无效的评估集ID格式")?;
+            let existing = find_eval_set(&db, eval_set_uuid).await?;
+            if existing.project_id != Some(project_uuid) {
+                return Err("评估集不属于该项目".to_string());
+            }
+            Some(eval_set_uuid)
+        }
+        None => None,
+    };
+    prompt_eval::save_eval_set(&db, eval_set_uuid, Some(project_uuid), &eval_set).await
+        .map_err(|e| format!("保存评估集失败: {}", e))
+}
+
+/// 查看评估集的评估报告（按时间倒序），评估由命令行 `sker eval run` 发起
+#[tauri::command]
+pub async fn list_eval_reports(
+    eval_set_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<EvalRunReport>, String> {
+    let eval_set_uuid = Uuid::parse_str(&eval_set_id)
+        .map_err(|_| "无效的评估集ID格式")?;
+    let user_id = authenticate(&token, &db).await?;
+    let eval_set = find_eval_set(&db, eval_set_uuid).await?;
+    if let Some(project_id) = eval_set.project_id {
+        require_project_role(&db, project_id, user_id, ProjectRole::Viewer).await?;
+    }
+
+    prompt_eval::list_eval_reports(&db, eval_set_uuid).await
+        .map_err(|e| format!("获取评估报告失败: {}", e))
+}
+
+async fn find_eval_set(db: &DatabaseHandle, eval_set_id: Uuid) -> Result<eval_set::Model, String> {
+    EvalSetRepository::new((**db).clone()).find_by_id(eval_set_id).await
+        .map_err(|e| format!("获取评估集失败: {}", e))?
+        .ok_or_else(|| "评估集不存在".to_string())
+}
+
+async fn authenticate(token: &str, db: &DatabaseHandle) -> Result<Uuid, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    Ok(current_user.user_id)
+}
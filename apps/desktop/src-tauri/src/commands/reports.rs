@@ -0,0 +1,228 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use codex_database::entities::{project_report, report_schedule};
+use codex_database::reporting::{self, ReportOptions, ScheduleConfig};
+use codex_database::repository::{ProjectReportRepository, ReportScheduleRepository};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::{authorize_project, authorize_project_user, parse_project_id};
+use crate::commands::projects::DatabaseHandle;
+use crate::commands::ArtifactStoreHandle;
+
+/// 报告列表默认返回的数量
+const DEFAULT_REPORT_LIMIT: u64 = 20;
+
+/// 报告计划
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportScheduleInfo {
+    pub schedule_id: String,
+    pub project_id: String,
+    pub config: ScheduleConfig,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub next_run_at: DateTime<Utc>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ReportScheduleInfo {
+    fn from_model(schedule: report_schedule::Model) -> Result<Self, String> {
+        let config = reporting::schedule_config(&schedule)
+            .map_err(|e| format!("解析报告计划失败: {}", e))?;
+        Ok(Self {
+            schedule_id: schedule.schedule_id.to_string(),
+            project_id: schedule.project_id.to_string(),
+            config,
+            last_run_at: schedule.last_run_at.map(|at| at.with_timezone(&Utc)),
+            next_run_at: schedule.next_run_at.with_timezone(&Utc),
+            created_by: schedule.created_by.to_string(),
+            created_at: schedule.created_at.with_timezone(&Utc),
+            updated_at: schedule.updated_at.with_timezone(&Utc),
+        })
+    }
+}
+
+/// 已生成的项目报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectReportInfo {
+    pub report_id: String,
+    pub project_id: String,
+    pub schedule_id: Option<String>,
+    pub title: String,
+    pub format: String,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub size_bytes: i64,
+    pub summary: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&project_report::Model> for ProjectReportInfo {
+    fn from(report: &project_report::Model) -> Self {
+        Self {
+            report_id: report.report_id.to_string(),
+            project_id: report.project_id.to_string(),
+            schedule_id: report.schedule_id.map(|id| id.to_string()),
+            title: report.title.clone(),
+            format: report.format.clone(),
+            period_start: report.period_start.with_timezone(&Utc),
+            period_end: report.period_end.with_timezone(&Utc),
+            size_bytes: report.size_bytes,
+            summary: report.summary.clone(),
+            created_at: report.created_at.with_timezone(&Utc),
+        }
+    }
+}
+
+/// 报告及其渲染内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectReportContent {
+    pub report: ProjectReportInfo,
+    pub content: String,
+}
+
+/// 创建报告计划
+#[tauri::command]
+pub async fn create_report_schedule(
+    project_id: String,
+    config: ScheduleConfig,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ReportScheduleInfo, String> {
+    let project_uuid = parse_project_id(&project_id)?;
+    let user_id = authorize_project_user(project_uuid, &token, &db, ProjectRole::Maintainer).await?.user_id;
+
+    let schedule = reporting::create_schedule(&db, project_uuid, user_id, config, Utc::now())
+        .await
+        .map_err(|e| format!("创建报告计划失败: {}", e))?;
+    ReportScheduleInfo::from_model(schedule)
+}
+
+/// 列出项目的报告计划
+#[tauri::command]
+pub async fn list_report_schedules(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<ReportScheduleInfo>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    ReportScheduleRepository::new((**db).clone())
+        .find_by_project(project_uuid)
+        .await
+        .map_err(|e| format!("查询报告计划失败: {}", e))?
+        .into_iter()
+        .map(ReportScheduleInfo::from_model)
+        .collect()
+}
+
+/// 更新报告计划，下次触发时间按新的 cron 表达式重新计算
+#[tauri::command]
+pub async fn update_report_schedule(
+    schedule_id: String,
+    config: ScheduleConfig,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ReportScheduleInfo, String> {
+    let schedule = authorize_schedule(&schedule_id, &token, &db).await?;
+
+    let schedule = reporting::update_schedule(&db, schedule.schedule_id, config, Utc::now())
+        .await
+        .map_err(|e| format!("更新报告计划失败: {}", e))?;
+    ReportScheduleInfo::from_model(schedule)
+}
+
+/// 删除报告计划，已生成的报告保留
+#[tauri::command]
+pub async fn delete_report_schedule(
+    schedule_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<(), String> {
+    let schedule = authorize_schedule(&schedule_id, &token, &db).await?;
+
+    ReportScheduleRepository::new((**db).clone())
+        .delete(schedule.schedule_id)
+        .await
+        .map_err(|e| format!("删除报告计划失败: {}", e))?;
+    println!("已删除报告计划: {}", schedule.name);
+    Ok(())
+}
+
+/// 立即生成项目报告
+#[tauri::command]
+pub async fn generate_project_report(
+    project_id: String,
+    options: Option<ReportOptions>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    store: State<'_, ArtifactStoreHandle>,
+) -> Result<ProjectReportContent, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Contributor).await?;
+
+    let generated = reporting::generate_report(&store, project_uuid, &options.unwrap_or_default(), None, Utc::now())
+        .await
+        .map_err(|e| format!("生成项目报告失败: {}", e))?;
+    Ok(ProjectReportContent {
+        report: ProjectReportInfo::from(&generated.report),
+        content: generated.content,
+    })
+}
+
+/// 列出项目最近生成的报告
+#[tauri::command]
+pub async fn list_project_reports(
+    project_id: String,
+    limit: Option<u64>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<ProjectReportInfo>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    let reports = ProjectReportRepository::new((**db).clone())
+        .find_by_project(project_uuid, limit.unwrap_or(DEFAULT_REPORT_LIMIT))
+        .await
+        .map_err(|e| format!("查询项目报告失败: {}", e))?;
+    Ok(reports.iter().map(ProjectReportInfo::from).collect())
+}
+
+/// 获取报告及其渲染内容
+#[tauri::command]
+pub async fn get_project_report(
+    report_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    store: State<'_, ArtifactStoreHandle>,
+) -> Result<ProjectReportContent, String> {
+    let report_uuid = Uuid::parse_str(&report_id)
+        .map_err(|_| "无效的报告ID格式")?;
+    let (report, content) = reporting::read_report(&store, report_uuid).await
+        .map_err(|e| format!("读取项目报告失败: {}", e))?;
+    authorize_project(&report.project_id.to_string(), &token, &db, ProjectRole::Viewer).await?;
+
+    Ok(ProjectReportContent {
+        report: ProjectReportInfo::from(&report),
+        content,
+    })
+}
+
+/// 校验令牌，并要求当前用户是报告计划所属项目的维护者
+async fn authorize_schedule(
+    schedule_id: &str,
+    token: &str,
+    db: &DatabaseHandle,
+) -> Result<report_schedule::Model, String> {
+    let schedule_uuid = Uuid::parse_str(schedule_id)
+        .map_err(|_| "无效的报告计划ID格式")?;
+    let schedule = ReportScheduleRepository::new((**db).clone())
+        .find_by_id(schedule_uuid)
+        .await
+        .map_err(|e| format!("查询报告计划失败: {}", e))?
+        .ok_or("报告计划不存在")?;
+    authorize_project(&schedule.project_id.to_string(), token, db, ProjectRole::Maintainer).await?;
+    Ok(schedule)
+}
@@ -0,0 +1,85 @@
+use tauri::{AppHandle, State};
+use codex_database::entities::code_review;
+use codex_database::repository::{CodeReviewRepository, TaskRepository};
+use codex_database::review_sla::{self, Reviewer, ReviewSlaReport};
+use codex_multi_agent::{Clock, ProjectRole, ReviewPriority, SystemClock};
+use uuid::Uuid;
+use crate::commands::members::require_project_role;
+use crate::commands::projects::DatabaseHandle;
+
+/// 调整代码审查的优先级，按新优先级重新计算截止时间
+#[tauri::command]
+pub async fn set_review_priority(
+    review_id: String,
+    priority: ReviewPriority,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<code_review::Model, String> {
+    let (review_uuid, _) = authorize_review(&review_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    review_sla::set_review_priority(&db, review_uuid, priority).await
+        .map_err(|e| format!("调整审查优先级失败: {}", e))
+}
+
+/// 把代码审查改派给指定的审查员Agent或项目成员
+#[tauri::command]
+pub async fn assign_code_reviewer(
+    review_id: String,
+    reviewer: Reviewer,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<code_review::Model, String> {
+    let (review_uuid, user_id) = authorize_review(&review_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    let review = review_sla::assign_reviewer(&db, review_uuid, reviewer, user_id, &SystemClock).await
+        .map_err(|e| format!("改派审查者失败: {}", e))?;
+    println!("用户 {} 把审查 {} 改派给 {:?}", user_id, review_id, reviewer);
+    Ok(review)
+}
+
+/// 立即评估项目中进行中审查的时限，并推送提醒、改派和超时通知
+#[tauri::command]
+pub async fn evaluate_project_review_sla(
+    project_id: String,
+    token: String,
+    app: AppHandle,
+    db: State<'_, DatabaseHandle>,
+) -> Result<ReviewSlaReport, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    let project_uuid = Uuid::parse_str(&project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    require_project_role(&db, project_uuid, current_user.user_id, ProjectRole::Maintainer).await?;
+
+    let report = review_sla::evaluate_project(&db, project_uuid, SystemClock.now()).await
+        .map_err(|e| format!("评估审查时限失败: {}", e))?;
+    crate::sla_monitor::emit_review_sla_report(&app, &report);
+    Ok(report)
+}
+
+/// 校验令牌，并要求当前用户在审查所属项目中具有指定角色，返回审查ID和用户ID
+async fn authorize_review(
+    review_id: &str,
+    token: &str,
+    db: &DatabaseHandle,
+    role: ProjectRole,
+) -> Result<(Uuid, Uuid), String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let review_uuid = Uuid::parse_str(review_id)
+        .map_err(|_| "无效的审查ID格式")?;
+    let review = CodeReviewRepository::new((**db).clone())
+        .find_by_id(review_uuid).await
+        .map_err(|e| format!("查询代码审查失败: {}", e))?
+        .ok_or_else(|| format!("代码审查不存在: {}", review_id))?;
+    let task = TaskRepository::new((**db).clone())
+        .find_by_id(review.task_id).await
+        .map_err(|e| format!("查询任务失败: {}", e))?
+        .ok_or_else(|| format!("任务不存在: {}", review.task_id))?;
+
+    require_project_role(db, task.project_id, current_user.user_id, role).await?;
+    Ok((review_uuid, current_user.user_id))
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+use tauri::State;
+use codex_database::embeddings::{EmbeddingIndex, IndexReport, SearchHit, SearchScope};
+use codex_database::entities::embedding::EmbeddingSourceType;
+use codex_multi_agent::ProjectRole;
+use crate::commands::members::authorize_project;
+use crate::commands::projects::DatabaseHandle;
+
+// 语义检索索引
+pub type EmbeddingIndexHandle = Arc<EmbeddingIndex>;
+
+/// 单次检索返回的最大结果数
+const MAX_TOP_K: usize = 50;
+
+/// 在项目的需求文档、任务和经验教训中进行语义检索
+#[tauri::command]
+pub async fn semantic_search(
+    project_id: String,
+    query: String,
+    source_types: Option<Vec<String>>,
+    top_k: Option<usize>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    index: State<'_, EmbeddingIndexHandle>,
+) -> Result<Vec<SearchHit>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    let source_types = source_types
+        .unwrap_or_default()
+        .iter()
+        .map(|source_type| source_type.parse::<EmbeddingSourceType>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let scope = SearchScope {
+        project_id: project_uuid,
+        source_types,
+    };
+
+    index.semantic_search(&query, &scope, top_k.unwrap_or(10).min(MAX_TOP_K)).await
+        .map_err(|e| format!("语义检索失败: {}", e))
+}
+
+/// 立即更新项目的语义检索索引
+#[tauri::command]
+pub async fn rebuild_search_index(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    index: State<'_, EmbeddingIndexHandle>,
+) -> Result<IndexReport, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    let report = index.index_project(project_uuid).await
+        .map_err(|e| format!("更新语义索引失败: {}", e))?;
+    println!(
+        "语义索引已更新: {} (新增 {}，未变化 {}，移除 {})",
+        project_id, report.embedded, report.unchanged, report.removed
+    );
+    Ok(report)
+}
@@ -0,0 +1,56 @@
+use tauri::State;
+use codex_database::entities::security_finding;
+use codex_database::repository::SecurityFindingRepository;
+use codex_database::security_scan::{self, CommandScanRunner, SecurityGateResult, SecurityScanReport};
+use codex_database::static_analysis;
+use codex_multi_agent::ProjectRole;
+use crate::commands::members::authorize_session;
+use crate::commands::projects::DatabaseHandle;
+
+/// 在执行会话的工作空间中运行依赖漏洞扫描和密钥扫描，保存发现并创建修复任务
+#[tauri::command]
+pub async fn run_session_security_scan(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<SecurityScanReport, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Contributor).await?;
+
+    let workspace = static_analysis::session_workspace(&db, session_uuid).await
+        .map_err(|e| format!("获取工作空间失败: {}", e))?;
+    let report = security_scan::run_security_scan(&db, session_uuid, &workspace, &CommandScanRunner).await
+        .map_err(|e| format!("运行安全扫描失败: {}", e))?;
+    println!(
+        "执行会话 {} 安全扫描完成，共 {} 个发现，其中 {} 个阻塞",
+        session_id,
+        report.findings.len(),
+        report.blocking_count
+    );
+    Ok(report)
+}
+
+/// 获取执行会话已保存的安全扫描发现
+#[tauri::command]
+pub async fn get_session_security_findings(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<security_finding::Model>, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Viewer).await?;
+
+    SecurityFindingRepository::new((**db).clone()).find_by_session(session_uuid).await
+        .map_err(|e| format!("获取安全扫描发现失败: {}", e))
+}
+
+/// 按项目质量门禁评估执行会话的安全扫描发现，执行配置关闭了安全检查时返回空
+#[tauri::command]
+pub async fn evaluate_session_security_gate(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<SecurityGateResult>, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Viewer).await?;
+
+    security_scan::evaluate_security_gate(&db, session_uuid).await
+        .map_err(|e| format!("评估安全扫描门禁失败: {}", e))
+}
@@ -0,0 +1,110 @@
+use tauri::State;
+use codex_database::entities::sla_breach;
+use codex_database::repository::{ProjectRepository, SlaBreachRepository};
+use codex_database::sla::{self, SlaPolicy};
+use codex_multi_agent::{ProjectRole, SlaBreachedEvent};
+use serde::Serialize;
+use crate::commands::members::authorize_project;
+use crate::commands::projects::DatabaseHandle;
+
+/// SLA 违约记录
+#[derive(Debug, Clone, Serialize)]
+pub struct SlaBreachInfo {
+    pub breach_id: String,
+    pub project_id: String,
+    pub sla_type: String,
+    pub subject_type: String,
+    pub subject_id: String,
+    pub subject_name: String,
+    pub severity: String,
+    pub threshold_minutes: i64,
+    pub actual_minutes: i64,
+    pub deadline: Option<String>,
+    pub detected_at: String,
+    pub updated_at: String,
+    pub resolved_at: Option<String>,
+}
+
+impl From<sla_breach::Model> for SlaBreachInfo {
+    fn from(breach: sla_breach::Model) -> Self {
+        Self {
+            breach_id: breach.breach_id.to_string(),
+            project_id: breach.project_id.to_string(),
+            sla_type: breach.sla_type,
+            subject_type: breach.subject_type,
+            subject_id: breach.subject_id,
+            subject_name: breach.subject_name,
+            severity: breach.severity,
+            threshold_minutes: breach.threshold_minutes,
+            actual_minutes: breach.actual_minutes,
+            deadline: breach.deadline.map(|t| t.to_rfc3339()),
+            detected_at: breach.detected_at.to_rfc3339(),
+            updated_at: breach.updated_at.to_rfc3339(),
+            resolved_at: breach.resolved_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// 获取项目的 SLA 配置，未配置时返回 None
+#[tauri::command]
+pub async fn get_project_sla_policy(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Option<SlaPolicy>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    let project = ProjectRepository::new((**db).clone()).find_by_id(project_uuid).await
+        .map_err(|e| format!("获取项目失败: {}", e))?
+        .ok_or("项目不存在")?;
+    project.sla_policy
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| format!("SLA配置无效: {}", e))
+}
+
+/// 更新项目的 SLA 配置，传入 None 关闭监控
+#[tauri::command]
+pub async fn update_project_sla_policy(
+    project_id: String,
+    policy: Option<SlaPolicy>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<(), String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    ProjectRepository::new((**db).clone()).set_sla_policy(project_uuid, policy).await
+        .map_err(|e| format!("更新SLA配置失败: {}", e))?;
+    println!("已更新项目SLA配置: {}", project_id);
+    Ok(())
+}
+
+/// 列出项目的 SLA 违约，默认只返回未解决的违约
+#[tauri::command]
+pub async fn list_project_sla_breaches(
+    project_id: String,
+    include_resolved: Option<bool>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<SlaBreachInfo>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    let breaches = SlaBreachRepository::new((**db).clone())
+        .find_by_project(project_uuid, include_resolved.unwrap_or(false))
+        .await
+        .map_err(|e| format!("查询SLA违约失败: {}", e))?;
+    Ok(breaches.into_iter().map(SlaBreachInfo::from).collect())
+}
+
+/// 立即评估项目的 SLA，返回新产生的违约事件
+#[tauri::command]
+pub async fn evaluate_project_sla(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<SlaBreachedEvent>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Contributor).await?;
+
+    sla::evaluate_project(&db, project_uuid, chrono::Utc::now()).await
+        .map_err(|e| format!("评估SLA失败: {}", e))
+}
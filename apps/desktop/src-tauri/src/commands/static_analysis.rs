@@ -0,0 +1,50 @@
+use tauri::State;
+use codex_database::entities::lint_finding;
+use codex_database::repository::LintFindingRepository;
+use codex_database::static_analysis::{self, CommandLintRunner, LintGateResult, StaticAnalysisReport};
+use codex_multi_agent::ProjectRole;
+use crate::commands::members::authorize_session;
+use crate::commands::projects::DatabaseHandle;
+
+/// 在执行会话的工作空间中运行项目配置的 Linter，保存并返回发现
+#[tauri::command]
+pub async fn run_session_static_analysis(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<StaticAnalysisReport, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Contributor).await?;
+
+    let workspace = static_analysis::session_workspace(&db, session_uuid).await
+        .map_err(|e| format!("获取工作空间失败: {}", e))?;
+    let report = static_analysis::run_session_analysis(&db, session_uuid, &workspace, &CommandLintRunner).await
+        .map_err(|e| format!("运行静态分析失败: {}", e))?;
+    println!("执行会话 {} 静态分析完成，共 {} 个发现", session_id, report.findings.len());
+    Ok(report)
+}
+
+/// 获取执行会话已保存的静态分析发现
+#[tauri::command]
+pub async fn get_session_lint_findings(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<lint_finding::Model>, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Viewer).await?;
+
+    LintFindingRepository::new((**db).clone()).find_by_session(session_uuid).await
+        .map_err(|e| format!("获取静态分析发现失败: {}", e))
+}
+
+/// 按项目质量门禁评估执行会话的静态分析发现
+#[tauri::command]
+pub async fn evaluate_session_lint_gate(
+    session_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<LintGateResult, String> {
+    let session_uuid = authorize_session(&session_id, &token, &db, ProjectRole::Viewer).await?;
+
+    static_analysis::evaluate_lint_gate(&db, session_uuid).await
+        .map_err(|e| format!("评估静态分析门禁失败: {}", e))
+}
@@ -0,0 +1,254 @@
+use tauri::State;
+use codex_database::{
+    entities::llm_session,
+    idempotency::{IdempotencyClaim, IdempotencyStore},
+    repository::{
+        LlmConversationRepository, LlmSessionRepository, ProjectRepository, TaskRepository,
+        llm_conversation_repository::CreateConversationMessageData,
+        llm_session_repository::CreateLlmSessionData,
+        task_repository::CreateTaskData,
+    },
+    structured_output::parse_task_draft,
+    TenantScope,
+};
+use codex_multi_agent::{ProjectRole, SystemClock};
+use uuid::Uuid;
+use crate::{
+    commands::{members::require_project_role, projects::DatabaseHandle},
+    conversation_store::ConversationStore,
+    models::{MessageRole, Task, TaskFromMessageOverrides},
+};
+
+/// 桌面对话在数据库中对应的LLM会话类型
+const CONVERSATION_SESSION_TYPE: &str = "conversation";
+
+/// 从对话消息创建任务
+///
+/// 携带幂等键的重试返回首次创建的任务，不会重复创建
+#[tauri::command]
+pub async fn create_task_from_message(
+    conversation_id: String,
+    message_id: String,
+    overrides: TaskFromMessageOverrides,
+    idempotency_key: Option<String>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Task, String> {
+    // 验证token并获取当前用户
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    println!("从消息创建任务: {} / {}", conversation_id, message_id);
+
+    let Some(idempotency_key) = idempotency_key else {
+        return create_task_once(&db, current_user.user_id, conversation_id, message_id, overrides).await;
+    };
+    let scope = format!("create_task_from_message:{}", current_user.user_id);
+    let request = (&conversation_id, &message_id, &overrides);
+    match IdempotencyStore::new((**db).clone()).begin(&scope, &idempotency_key, &request).await
+        .map_err(|e| format!("处理幂等键失败: {}", e))?
+    {
+        IdempotencyClaim::Replay(task) => {
+            println!("幂等键 {} 重复请求，返回已创建的任务", idempotency_key);
+            serde_json::from_value(task).map_err(|e| format!("读取已创建的任务失败: {}", e))
+        }
+        IdempotencyClaim::Acquired(guard) => {
+            let result = create_task_once(&db, current_user.user_id, conversation_id, message_id, overrides).await;
+            match &result {
+                Ok(task) => guard.complete(task).await,
+                Err(_) => guard.release().await,
+            }
+            .map_err(|e| format!("保存幂等键失败: {}", e))?;
+            result
+        }
+    }
+}
+
+async fn create_task_once(
+    db: &codex_database::DatabaseConnection,
+    user_id: Uuid,
+    conversation_id: String,
+    message_id: String,
+    overrides: TaskFromMessageOverrides,
+) -> Result<Task, String> {
+    let project_uuid = Uuid::parse_str(&overrides.project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    let parent_task_id = overrides.parent_task_id
+        .as_deref()
+        .map(Uuid::parse_str)
+        .transpose()
+        .map_err(|_| "无效的父任务ID格式")?;
+
+    ProjectRepository::new(db.clone())
+        .find_by_id(project_uuid).await
+        .map_err(|e| format!("查询项目失败: {}", e))?
+        .ok_or("项目不存在")?;
+    require_project_role(db, project_uuid, user_id, ProjectRole::Contributor).await?;
+
+    // 加载源消息
+    let message = ConversationStore::new()?
+        .find_message(&conversation_id, &message_id)
+        .await?
+        .ok_or_else(|| format!("消息不存在: {}", message_id))?;
+
+    // 通过结构化输出解析器提取任务字段，覆盖项优先
+    let draft = parse_task_draft(&message.content).unwrap_or_default();
+    let title = overrides.title
+        .or_else(|| (!draft.title.is_empty()).then(|| draft.title.clone()))
+        .ok_or("无法从消息中提取任务标题，请手动填写")?;
+    let description = overrides.description.unwrap_or_else(|| {
+        if draft.description.is_empty() {
+            message.content.clone()
+        } else {
+            draft.description.clone()
+        }
+    });
+    let acceptance_criteria = overrides.acceptance_criteria.unwrap_or(draft.acceptance_criteria);
+    let task_type = overrides.task_type
+        .or(draft.task_type)
+        .unwrap_or_else(|| "feature".to_string());
+    let priority = overrides.priority.or(draft.priority);
+
+    // 关联源对话：每个项目中的桌面对话对应一个LLM会话
+    let session = find_or_create_conversation_session(db, project_uuid, user_id, &conversation_id).await?;
+    let conversation_repo = LlmConversationRepository::new(db.clone());
+    let message_order = conversation_repo.find_by_session(session.session_id).await
+        .map_err(|e| format!("查询会话消息失败: {}", e))?
+        .len() as i32;
+    conversation_repo.create(CreateConversationMessageData {
+        session_id: session.session_id,
+        role: match message.role {
+            MessageRole::User => "user",
+            MessageRole::Assistant => "assistant",
+            MessageRole::System => "system",
+        }.to_string(),
+        content: message.content.clone(),
+        message_order,
+        token_count: None,
+        model_used: None,
+        processing_time_ms: None,
+    }).await
+        .map_err(|e| format!("保存源消息失败: {}", e))?;
+
+    let task_repo = TaskRepository::new(db.clone());
+    let created = task_repo.create(CreateTaskData {
+        project_id: project_uuid,
+        parent_task_id,
+        llm_session_id: Some(session.session_id),
+        title,
+        description,
+        task_type,
+    }).await
+        .map_err(|e| format!("创建任务失败: {}", e))?;
+
+    let mut created = task_repo.update_requirements(
+        created.task_id,
+        None,
+        Some(serde_json::json!(acceptance_criteria)),
+    ).await
+        .map_err(|e| format!("保存验收标准失败: {}", e))?;
+
+    if priority.is_some() {
+        created = task_repo.update_details(created.task_id, None, None, priority, None).await
+            .map_err(|e| format!("更新任务优先级失败: {}", e))?;
+    }
+
+    // 未指定工作范围时沿用父任务的范围
+    if overrides.scope_path.is_some() {
+        created = task_repo.set_scope_path(created.task_id, overrides.scope_path).await
+            .map_err(|e| format!("设置任务工作范围失败: {}", e))?;
+    } else if let Some(parent_task_id) = parent_task_id {
+        let parent_scope = task_repo.find_by_id(parent_task_id).await
+            .map_err(|e| format!("查询父任务失败: {}", e))?
+            .and_then(|parent| parent.scope_path);
+        if parent_scope.is_some() {
+            created = task_repo.set_scope_path(created.task_id, parent_scope).await
+                .map_err(|e| format!("设置任务工作范围失败: {}", e))?;
+        }
+    }
+
+    // 紧急任务没有空闲Agent时按项目抢占策略让出一个运行中的任务，失败不影响任务创建
+    if created.priority == "critical" {
+        match codex_database::preemption::preempt_for_critical_task(db, created.task_id, &SystemClock).await {
+            Ok(Some(event)) => {
+                println!("紧急任务 {} 抢占了任务 {}", created.task_id, event.preempted_task_id);
+                if let Ok(Some(task)) = task_repo.find_by_id(created.task_id).await {
+                    created = task;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("紧急任务抢占失败: {}", e),
+        }
+    }
+
+    println!("任务创建成功: {} (来源对话: {})", created.task_id, conversation_id);
+    let mut task = Task::try_from(created)?;
+    task.source_conversation_id = Some(conversation_id);
+    task.source_message_id = Some(message_id);
+    Ok(task)
+}
+
+/// 设置任务的工作范围，`scope_path` 为空表示整个仓库
+#[tauri::command]
+pub async fn set_task_scope(
+    task_id: String,
+    scope_path: Option<String>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Task, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let task_uuid = Uuid::parse_str(&task_id)
+        .map_err(|_| "无效的任务ID格式")?;
+    let task_repo = TaskRepository::new((**db).clone())
+        .with_scope(TenantScope::user(current_user.user_id));
+    let task = task_repo.find_by_id(task_uuid).await
+        .map_err(|e| format!("查询任务失败: {}", e))?
+        .ok_or("任务不存在")?;
+    require_project_role(&db, task.project_id, current_user.user_id, ProjectRole::Contributor).await?;
+
+    let updated = task_repo.set_scope_path(task_uuid, scope_path.filter(|scope| !scope.trim().is_empty())).await
+        .map_err(|e| format!("设置任务工作范围失败: {}", e))?;
+    Task::try_from(updated)
+}
+
+/// 查找或创建对话对应的LLM会话，会话结果中记录对话ID
+async fn find_or_create_conversation_session(
+    db: &codex_database::DatabaseConnection,
+    project_id: Uuid,
+    user_id: Uuid,
+    conversation_id: &str,
+) -> Result<llm_session::Model, String> {
+    let session_repo = LlmSessionRepository::new(db.clone());
+    let sessions = session_repo.find_by_type(project_id, CONVERSATION_SESSION_TYPE).await
+        .map_err(|e| format!("查询LLM会话失败: {}", e))?;
+
+    if let Some(session) = sessions.into_iter().find(|s| {
+        s.result_data
+            .as_ref()
+            .and_then(|data| data.get("conversation_id"))
+            .and_then(|id| id.as_str())
+            == Some(conversation_id)
+    }) {
+        return Ok(session);
+    }
+
+    let session = session_repo.create(CreateLlmSessionData {
+        project_id,
+        user_id,
+        session_type: CONVERSATION_SESSION_TYPE.to_string(),
+        system_prompt: None,
+        decomposition_prompt: None,
+    }).await
+        .map_err(|e| format!("创建LLM会话失败: {}", e))?;
+
+    session_repo.update_result(
+        session.session_id,
+        serde_json::json!({ "conversation_id": conversation_id }),
+        "active".to_string(),
+    ).await
+        .map_err(|e| format!("关联对话失败: {}", e))
+}
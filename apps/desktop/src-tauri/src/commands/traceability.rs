@@ -0,0 +1,104 @@
+use tauri::State;
+use codex_database::entities::requirement_coverage;
+use codex_database::repository::{RequirementCoverageRepository, RequirementDocumentRepository};
+use codex_database::traceability::{self, CoverageReport, RequirementSection};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::require_project_role;
+use crate::commands::projects::DatabaseHandle;
+
+/// 获取需求文档的章节
+#[tauri::command]
+pub async fn get_requirement_sections(
+    document_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<RequirementSection>, String> {
+    let document_uuid = authorize_document(&document_id, &token, &db, ProjectRole::Viewer).await?;
+
+    traceability::document_sections(&db, document_uuid).await
+        .map_err(|e| format!("解析需求章节失败: {}", e))
+}
+
+/// 记录任务由需求文档的某个章节生成
+#[tauri::command]
+pub async fn link_task_to_requirement(
+    document_id: String,
+    section_id: String,
+    task_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<requirement_coverage::Model, String> {
+    let document_uuid = authorize_document(&document_id, &token, &db, ProjectRole::Contributor).await?;
+    let task_uuid = Uuid::parse_str(&task_id)
+        .map_err(|_| "无效的任务ID格式")?;
+
+    let coverage = traceability::link_task_to_section(&db, document_uuid, &section_id, task_uuid).await
+        .map_err(|e| format!("关联需求章节失败: {}", e))?;
+    println!("任务 {} 已关联需求章节 {} / {}", task_id, document_id, section_id);
+    Ok(coverage)
+}
+
+/// 取消任务与需求章节的关联
+#[tauri::command]
+pub async fn unlink_task_from_requirement(
+    document_id: String,
+    section_id: String,
+    task_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<bool, String> {
+    let document_uuid = authorize_document(&document_id, &token, &db, ProjectRole::Contributor).await?;
+    let task_uuid = Uuid::parse_str(&task_id)
+        .map_err(|_| "无效的任务ID格式")?;
+
+    RequirementCoverageRepository::new((**db).clone())
+        .delete_link(document_uuid, &section_id, task_uuid).await
+        .map_err(|e| format!("取消需求关联失败: {}", e))
+}
+
+/// 获取项目的需求覆盖报告（未覆盖的章节和孤立任务）
+#[tauri::command]
+pub async fn get_requirement_coverage_report(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<CoverageReport, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let project_uuid = Uuid::parse_str(&project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    require_project_role(&db, project_uuid, current_user.user_id, ProjectRole::Viewer).await?;
+
+    let report = traceability::coverage_report(&db, project_uuid).await
+        .map_err(|e| format!("生成需求覆盖报告失败: {}", e))?;
+    println!(
+        "需求覆盖: {} ({}/{} 个章节，{} 个孤立任务)",
+        project_id, report.covered_sections, report.total_sections, report.orphan_tasks.len()
+    );
+    Ok(report)
+}
+
+/// 校验令牌，并要求当前用户在需求文档所属项目中具有指定角色，返回文档ID
+async fn authorize_document(
+    document_id: &str,
+    token: &str,
+    db: &DatabaseHandle,
+    role: ProjectRole,
+) -> Result<Uuid, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let document_uuid = Uuid::parse_str(document_id)
+        .map_err(|_| "无效的需求文档ID格式")?;
+    let document = RequirementDocumentRepository::new((**db).clone())
+        .find_by_id(document_uuid).await
+        .map_err(|e| format!("查询需求文档失败: {}", e))?
+        .ok_or_else(|| format!("需求文档不存在: {}", document_id))?;
+
+    require_project_role(db, document.project_id, current_user.user_id, role).await?;
+    Ok(document_uuid)
+}
@@ -0,0 +1,126 @@
+use tauri::State;
+use codex_database::repository::OrganizationRepository;
+use codex_database::two_factor::{TotpEnrollment, TwoFactorService, TwoFactorStatus};
+use codex_multi_agent::OrganizationRole;
+use uuid::Uuid;
+use crate::auth::CurrentUser;
+use crate::commands::projects::DatabaseHandle;
+
+/// 验证器应用中显示的签发方名称
+const TOTP_ISSUER: &str = "Sker";
+
+/// 获取当前用户的两步验证状态
+#[tauri::command]
+pub async fn get_two_factor_status(
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<TwoFactorStatus, String> {
+    let current_user = authenticate_for_enrollment(&token, &db).await?;
+
+    TwoFactorService::new((**db).clone()).status(current_user.user_id).await
+        .map_err(|e| format!("获取两步验证状态失败: {}", e))
+}
+
+/// 开始登记两步验证，返回密钥和用于生成二维码的配置地址
+#[tauri::command]
+pub async fn begin_two_factor_enrollment(
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<TotpEnrollment, String> {
+    let current_user = authenticate_for_enrollment(&token, &db).await?;
+
+    TwoFactorService::new((**db).clone())
+        .begin_enrollment(current_user.user_id, TOTP_ISSUER, &current_user.email).await
+        .map_err(|e| format!("开始登记两步验证失败: {}", e))
+}
+
+/// 用验证器应用中的验证码确认登记，返回备用恢复码（只显示这一次）
+#[tauri::command]
+pub async fn confirm_two_factor_enrollment(
+    code: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<String>, String> {
+    let current_user = authenticate_for_enrollment(&token, &db).await?;
+
+    let codes = TwoFactorService::new((**db).clone())
+        .confirm_enrollment(current_user.user_id, &code).await
+        .map_err(|e| format!("确认两步验证失败: {}", e))?;
+    println!("用户 {} 启用了两步验证", current_user.username);
+    Ok(codes)
+}
+
+/// 重新生成备用恢复码，旧的恢复码全部失效
+#[tauri::command]
+pub async fn regenerate_backup_codes(
+    code: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<String>, String> {
+    let current_user = authenticate(&token, &db).await?;
+
+    TwoFactorService::new((**db).clone())
+        .regenerate_backup_codes(current_user.user_id, &code).await
+        .map_err(|e| format!("重新生成恢复码失败: {}", e))
+}
+
+/// 停用两步验证，需要提交验证码或备用恢复码
+#[tauri::command]
+pub async fn disable_two_factor(
+    code: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<TwoFactorStatus, String> {
+    let current_user = authenticate(&token, &db).await?;
+
+    let status = TwoFactorService::new((**db).clone())
+        .disable(current_user.user_id, &code).await
+        .map_err(|e| format!("停用两步验证失败: {}", e))?;
+    println!("用户 {} 停用了两步验证", current_user.username);
+    Ok(status)
+}
+
+/// 设置是否强制用户使用两步验证，只有用户所在组织的所有者可以修改
+#[tauri::command]
+pub async fn set_two_factor_required(
+    user_id: String,
+    required: bool,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<TwoFactorStatus, String> {
+    let target_id = Uuid::parse_str(&user_id)
+        .map_err(|_| "无效的用户ID格式")?;
+    let current_user = authenticate(&token, &db).await?;
+
+    let organizations = OrganizationRepository::new((**db).clone());
+    let mut is_owner = false;
+    for organization in organizations.find_by_user(target_id).await
+        .map_err(|e| format!("查询用户所在组织失败: {}", e))?
+    {
+        let role = organizations.get_role(organization.organization_id, current_user.user_id).await
+            .map_err(|e| format!("查询组织角色失败: {}", e))?;
+        if role == Some(OrganizationRole::Owner) {
+            is_owner = true;
+            break;
+        }
+    }
+    if !is_owner {
+        return Err("只有用户所在组织的所有者可以修改两步验证强制设置".to_string());
+    }
+
+    TwoFactorService::new((**db).clone()).set_required(target_id, required).await
+        .map_err(|e| format!("更新两步验证强制设置失败: {}", e))
+}
+
+async fn authenticate(token: &str, db: &DatabaseHandle) -> Result<CurrentUser, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))
+}
+
+/// 登记相关的命令允许被要求使用两步验证但尚未登记的用户调用
+async fn authenticate_for_enrollment(token: &str, db: &DatabaseHandle) -> Result<CurrentUser, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    auth_service.validate_setup_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))
+}
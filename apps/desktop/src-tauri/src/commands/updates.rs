@@ -0,0 +1,58 @@
+use crate::settings::{AppSettings, SettingsManager};
+use crate::updater::{self, InstallHistory, ScheduledInstall, UpdateInfo};
+
+/// 按设置的更新通道检查更新，返回新版本及发布说明
+#[tauri::command]
+pub async fn check_for_updates(app: tauri::AppHandle) -> Result<UpdateInfo, String> {
+    let settings = load_settings().await?;
+    let (info, _) = updater::check(&app, &settings.system).await?;
+    Ok(info)
+}
+
+/// 立即下载并安装更新，完成后重启应用
+#[tauri::command]
+pub async fn install_update(app: tauri::AppHandle) -> Result<(), String> {
+    let settings = load_settings().await?;
+    let (_, update) = updater::check(&app, &settings.system).await?;
+    let update = update.ok_or("当前已是最新版本")?;
+    updater::install(&app, update).await
+}
+
+/// 安排在指定时间（毫秒时间戳）安装更新
+#[tauri::command]
+pub async fn schedule_update_install(install_at: i64, app: tauri::AppHandle) -> Result<ScheduledInstall, String> {
+    let settings = load_settings().await?;
+    updater::schedule(&app, &settings.system, install_at).await
+}
+
+/// 获取已安排的延期安装
+#[tauri::command]
+pub async fn get_scheduled_update() -> Result<Option<ScheduledInstall>, String> {
+    Ok(updater::scheduled())
+}
+
+/// 取消已安排的延期安装
+#[tauri::command]
+pub async fn cancel_scheduled_update() -> Result<Option<ScheduledInstall>, String> {
+    Ok(updater::cancel_scheduled())
+}
+
+/// 获取安装历史
+#[tauri::command]
+pub async fn get_update_history(app: tauri::AppHandle) -> Result<InstallHistory, String> {
+    updater::load_history(&app).await
+}
+
+/// 回滚到上一次安装前的版本，完成后重启应用
+#[tauri::command]
+pub async fn rollback_update(app: tauri::AppHandle) -> Result<(), String> {
+    let settings = load_settings().await?;
+    updater::rollback(&app, &settings.system).await
+}
+
+async fn load_settings() -> Result<AppSettings, String> {
+    SettingsManager::new()
+        .map_err(|e| format!("创建设置管理器失败: {}", e))?
+        .load_settings().await
+        .map_err(|e| format!("加载设置失败: {}", e))
+}
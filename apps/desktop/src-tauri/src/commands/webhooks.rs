@@ -0,0 +1,109 @@
+use tauri::State;
+use codex_database::entities::webhook_dead_letter;
+use codex_database::repository::WebhookDeadLetterRepository;
+use codex_database::webhook_ingestion::{self, IngestOutcome};
+use codex_multi_agent::ProjectRole;
+use serde::Serialize;
+use uuid::Uuid;
+use crate::commands::members::authorize_project;
+use crate::commands::projects::DatabaseHandle;
+
+/// 默认返回的死信条数
+const DEFAULT_DEAD_LETTER_LIMIT: u64 = 50;
+
+/// Webhook 死信
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookDeadLetterInfo {
+    pub dead_letter_id: String,
+    pub project_id: String,
+    pub source: String,
+    pub event_name: Option<String>,
+    pub delivery_id: Option<String>,
+    pub payload: String,
+    pub error: String,
+    pub received_at: String,
+    pub replay_count: i32,
+    pub last_replayed_at: Option<String>,
+    pub resolved_at: Option<String>,
+}
+
+impl From<webhook_dead_letter::Model> for WebhookDeadLetterInfo {
+    fn from(dead_letter: webhook_dead_letter::Model) -> Self {
+        Self {
+            dead_letter_id: dead_letter.dead_letter_id.to_string(),
+            project_id: dead_letter.project_id.to_string(),
+            source: dead_letter.source,
+            event_name: dead_letter.event_name,
+            delivery_id: dead_letter.delivery_id,
+            payload: dead_letter.payload,
+            error: dead_letter.error,
+            received_at: dead_letter.received_at.to_rfc3339(),
+            replay_count: dead_letter.replay_count,
+            last_replayed_at: dead_letter.last_replayed_at.map(|t| t.to_rfc3339()),
+            resolved_at: dead_letter.resolved_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// 列出项目的 webhook 死信，默认只返回待处理的死信
+#[tauri::command]
+pub async fn list_webhook_dead_letters(
+    project_id: String,
+    include_resolved: Option<bool>,
+    limit: Option<u64>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<WebhookDeadLetterInfo>, String> {
+    let project_uuid = authorize_project(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    let dead_letters = WebhookDeadLetterRepository::new((**db).clone())
+        .find_by_project(
+            project_uuid,
+            include_resolved.unwrap_or(false),
+            limit.unwrap_or(DEFAULT_DEAD_LETTER_LIMIT),
+        )
+        .await
+        .map_err(|e| format!("查询webhook死信失败: {}", e))?;
+    Ok(dead_letters.into_iter().map(WebhookDeadLetterInfo::from).collect())
+}
+
+/// 重放死信，映射规则支持该载荷后写入领域事件
+#[tauri::command]
+pub async fn replay_webhook_dead_letter(
+    dead_letter_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<IngestOutcome, String> {
+    let dead_letter = find_dead_letter(&dead_letter_id, &db).await?;
+    authorize_project(&dead_letter.project_id.to_string(), &token, &db, ProjectRole::Maintainer).await?;
+
+    let outcome = webhook_ingestion::replay_dead_letter(&db, dead_letter.dead_letter_id).await
+        .map_err(|e| format!("重放webhook死信失败: {}", e))?;
+    println!("已重放webhook死信: {}", dead_letter_id);
+    Ok(outcome)
+}
+
+/// 丢弃死信，不再重放
+#[tauri::command]
+pub async fn discard_webhook_dead_letter(
+    dead_letter_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<(), String> {
+    let dead_letter = find_dead_letter(&dead_letter_id, &db).await?;
+    authorize_project(&dead_letter.project_id.to_string(), &token, &db, ProjectRole::Maintainer).await?;
+
+    WebhookDeadLetterRepository::new((**db).clone())
+        .discard(dead_letter.dead_letter_id).await
+        .map_err(|e| format!("丢弃webhook死信失败: {}", e))?;
+    Ok(())
+}
+
+async fn find_dead_letter(dead_letter_id: &str, db: &DatabaseHandle) -> Result<webhook_dead_letter::Model, String> {
+    let dead_letter_uuid = Uuid::parse_str(dead_letter_id)
+        .map_err(|_| "无效的死信ID格式")?;
+    WebhookDeadLetterRepository::new((**db).clone())
+        .find_by_id(dead_letter_uuid).await
+        .map_err(|e| format!("获取webhook死信失败: {}", e))?
+        .ok_or_else(|| "webhook死信不存在".to_string())
+}
@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::AsyncReadExt;
+use tokio::sync::Mutex;
+use codex_database::entities::project;
+use codex_database::repository::project_repository::ProjectRepository;
+use codex_database::workspace_quota::{self, QuotaReport, WorkspaceQuota};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::require_project_role;
+use crate::commands::projects::DatabaseHandle;
+use crate::models::{WorkspaceEntry, FilePreview, WorkspaceChangeEvent};
+
+// 工作空间监听任务，键为项目ID
+pub type WorkspaceWatchersHandle = Arc<Mutex<HashMap<String, tokio::task::JoinHandle<()>>>>;
+
+/// 浏览和监听时忽略的目录
+const IGNORED_DIRS: &[&str] = &[".git", "node_modules", "target", ".idea", ".vscode"];
+
+/// 文件预览默认最大字节数
+const DEFAULT_PREVIEW_BYTES: usize = 64 * 1024;
+
+/// 文件预览允许的最大字节数
+const MAX_PREVIEW_BYTES: usize = 1024 * 1024;
+
+/// 监听轮询间隔
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 监听时最多跟踪的文件数，防止超大仓库拖慢轮询
+const MAX_WATCHED_FILES: usize = 20_000;
+
+/// 列出工作空间目录内容
+#[tauri::command]
+pub async fn list_workspace_entries(
+    project_id: String,
+    path: Option<String>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<Vec<WorkspaceEntry>, String> {
+    let root = load_workspace_root(&project_id, &token, &db).await?;
+    let dir = resolve_workspace_path(&root, path.as_deref().unwrap_or(""))?;
+
+    if !dir.is_dir() {
+        return Err(format!("不是目录: {}", path.unwrap_or_default()));
+    }
+
+    let mut read_dir = tokio::fs::read_dir(&dir).await
+        .map_err(|e| format!("读取目录失败: {}", e))?;
+
+    let mut entries = Vec::new();
+    while let Some(entry) = read_dir.next_entry().await
+        .map_err(|e| format!("读取目录项失败: {}", e))?
+    {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let metadata = match entry.metadata().await {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        if metadata.is_dir() && IGNORED_DIRS.contains(&name.as_str()) {
+            continue;
+        }
+
+        entries.push(WorkspaceEntry {
+            path: relative_path(&root, &entry.path()),
+            name,
+            is_dir: metadata.is_dir(),
+            size: if metadata.is_dir() { 0 } else { metadata.len() },
+            modified_at: metadata.modified().ok().map(format_system_time),
+        });
+    }
+
+    // 目录在前，其余按名称排序
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    Ok(entries)
+}
+
+/// 读取文件预览（超过 max_bytes 时截断）
+#[tauri::command]
+pub async fn read_file_preview(
+    project_id: String,
+    path: String,
+    max_bytes: Option<usize>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<FilePreview, String> {
+    let root = load_workspace_root(&project_id, &token, &db).await?;
+    let file = resolve_workspace_path(&root, &path)?;
+
+    if !file.is_file() {
+        return Err(format!("不是文件: {}", path));
+    }
+
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_PREVIEW_BYTES).min(MAX_PREVIEW_BYTES);
+    // 只读取预览需要的字节，大文件不整体载入内存
+    let handle = tokio::fs::File::open(&file).await
+        .map_err(|e| format!("读取文件失败: {}", e))?;
+    let size = handle.metadata().await
+        .map_err(|e| format!("读取文件失败: {}", e))?
+        .len();
+    let mut bytes = Vec::with_capacity(max_bytes.min(size as usize));
+    handle.take(max_bytes as u64).read_to_end(&mut bytes).await
+        .map_err(|e| format!("读取文件失败: {}", e))?;
+    let truncated = size > bytes.len() as u64;
+    let slice = bytes.as_slice();
+
+    // 含空字节视为二进制文件，不返回内容
+    let is_binary = slice.contains(&0);
+    let content = if is_binary {
+        String::new()
+    } else {
+        String::from_utf8_lossy(slice).to_string()
+    };
+
+    Ok(FilePreview {
+        path: relative_path(&root, &file),
+        content,
+        size,
+        truncated,
+        is_binary,
+    })
+}
+
+/// 监听工作空间文件变化，通过 workspace_changed_{project_id} 事件通知前端
+#[tauri::command]
+pub async fn watch_workspace(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    watchers: State<'_, WorkspaceWatchersHandle>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let root = load_workspace_root(&project_id, &token, &db).await?;
+
+    let mut watchers = watchers.lock().await;
+    if let Some(handle) = watchers.get(&project_id) {
+        if !handle.is_finished() {
+            println!("工作空间已在监听中: {}", project_id);
+            return Ok(());
+        }
+    }
+
+    println!("开始监听工作空间: {} ({})", project_id, root.display());
+    let event_name = format!("workspace_changed_{}", project_id);
+    let watched_project_id = project_id.clone();
+
+    let handle = tokio::spawn(async move {
+        let mut snapshot = snapshot_workspace(root.clone()).await;
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+            if !root.exists() {
+                eprintln!("工作空间目录已不存在，停止监听: {}", root.display());
+                break;
+            }
+
+            let current = snapshot_workspace(root.clone()).await;
+            let change = diff_snapshots(&watched_project_id, &snapshot, &current);
+            snapshot = current;
+
+            if change.created.is_empty() && change.modified.is_empty() && change.removed.is_empty() {
+                continue;
+            }
+            if let Err(e) = app.emit(&event_name, &change) {
+                eprintln!("发送工作空间变更事件失败: {e}");
+            }
+        }
+    });
+
+    watchers.insert(project_id, handle);
+    Ok(())
+}
+
+/// 停止监听工作空间，需要项目的查看权限
+#[tauri::command]
+pub async fn unwatch_workspace(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    watchers: State<'_, WorkspaceWatchersHandle>,
+) -> Result<(), String> {
+    // 工作空间目录可能已被删除，这里只校验权限，不解析目录
+    authorize_workspace(&project_id, &token, &db).await?;
+    if let Some(handle) = watchers.lock().await.remove(&project_id) {
+        handle.abort();
+        println!("已停止监听工作空间: {}", project_id);
+    }
+    Ok(())
+}
+
+/// 扫描工作空间磁盘占用并检查配额，占用跨越阈值时记录事件并返回清理建议
+#[tauri::command]
+pub async fn get_workspace_quota_report(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<QuotaReport, String> {
+    load_workspace_root(&project_id, &token, &db).await?;
+    let project_uuid = Uuid::parse_str(&project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+
+    let report = workspace_quota::check_workspace_quota(&db, project_uuid).await
+        .map_err(|e| format!("检查工作空间配额失败: {}", e))?;
+    println!("工作空间占用: {} ({} 字节，级别 {})", project_id, report.usage.total_bytes, report.level);
+    Ok(report)
+}
+
+/// 设置项目的工作空间磁盘配额，传入 null 取消限制
+#[tauri::command]
+pub async fn set_workspace_quota(
+    project_id: String,
+    quota: Option<WorkspaceQuota>,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+) -> Result<(), String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(&token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let project_uuid = Uuid::parse_str(&project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    let db = &**db;
+    require_project_role(db, project_uuid, current_user.user_id, ProjectRole::Maintainer).await?;
+
+    ProjectRepository::new(db.clone())
+        .set_workspace_quota(project_uuid, quota).await
+        .map_err(|e| format!("设置工作空间配额失败: {}", e))?;
+
+    println!("工作空间配额已更新: {}", project_id);
+    Ok(())
+}
+
+/// 校验令牌与项目成员角色，返回规范化后的工作空间根目录
+async fn load_workspace_root(
+    project_id: &str,
+    token: &str,
+    db: &DatabaseHandle,
+) -> Result<PathBuf, String> {
+    let project = authorize_workspace(project_id, token, db).await?;
+    std::fs::canonicalize(&project.workspace_path)
+        .map_err(|e| format!("工作空间路径无效 {}: {}", project.workspace_path, e))
+}
+
+/// 校验令牌和项目查看权限，返回项目
+async fn authorize_workspace(
+    project_id: &str,
+    token: &str,
+    db: &DatabaseHandle,
+) -> Result<project::Model, String> {
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+
+    let project_uuid = Uuid::parse_str(project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    let project = ProjectRepository::new((**db).clone())
+        .find_by_id(project_uuid).await
+        .map_err(|e| format!("查询项目失败: {}", e))?
+        .ok_or_else(|| format!("项目不存在: {}", project_id))?;
+
+    require_project_role(db, project_uuid, current_user.user_id, ProjectRole::Viewer).await?;
+    Ok(project)
+}
+
+/// 将请求路径解析到工作空间内，拒绝目录穿越和指向外部的符号链接
+fn resolve_workspace_path(root: &Path, requested: &str) -> Result<PathBuf, String> {
+    let requested = Path::new(requested);
+    let candidate = if requested.is_absolute() {
+        requested.to_path_buf()
+    } else {
+        root.join(requested)
+    };
+
+    // canonicalize 会解析 .. 和符号链接，之后再做前缀校验
+    let canonical = std::fs::canonicalize(&candidate)
+        .map_err(|e| format!("路径不存在或不可访问 {}: {}", requested.display(), e))?;
+
+    if !canonical.starts_with(root) {
+        return Err(format!("禁止访问工作空间之外的路径: {}", requested.display()));
+    }
+    Ok(canonical)
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn format_system_time(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339()
+}
+
+/// 采集工作空间文件的修改时间快照
+async fn snapshot_workspace(root: PathBuf) -> HashMap<String, SystemTime> {
+    tokio::task::spawn_blocking(move || {
+        let mut snapshot = HashMap::new();
+        let mut stack = vec![root.clone()];
+        while let Some(dir) = stack.pop() {
+            let Ok(read_dir) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in read_dir.flatten() {
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                // 不跟随符号链接，避免监听工作空间之外的内容
+                if file_type.is_symlink() {
+                    continue;
+                }
+                if file_type.is_dir() {
+                    let name = entry.file_name();
+                    if !IGNORED_DIRS.contains(&name.to_string_lossy().as_ref()) {
+                        stack.push(entry.path());
+                    }
+                } else if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+                    snapshot.insert(relative_path(&root, &entry.path()), modified);
+                    if snapshot.len() >= MAX_WATCHED_FILES {
+                        return snapshot;
+                    }
+                }
+            }
+        }
+        snapshot
+    })
+    .await
+    .unwrap_or_default()
+}
+
+fn diff_snapshots(
+    project_id: &str,
+    previous: &HashMap<String, SystemTime>,
+    current: &HashMap<String, SystemTime>,
+) -> WorkspaceChangeEvent {
+    let mut created = Vec::new();
+    let mut modified = Vec::new();
+    for (path, time) in current {
+        match previous.get(path) {
+            None => created.push(path.clone()),
+            Some(old) if old != time => modified.push(path.clone()),
+            _ => {}
+        }
+    }
+    let mut removed: Vec<String> = previous
+        .keys()
+        .filter(|path| !current.contains_key(*path))
+        .cloned()
+        .collect();
+
+    created.sort();
+    modified.sort();
+    removed.sort();
+
+    WorkspaceChangeEvent {
+        project_id: project_id.to_string(),
+        created,
+        modified,
+        removed,
+    }
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+use tauri::State;
+use codex_database::worktree_pool::{WorktreeInfo, WorktreePool};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+use crate::commands::members::require_project_role;
+use crate::commands::projects::DatabaseHandle;
+
+// 执行会话工作树池
+pub type WorktreePoolHandle = Arc<WorktreePool>;
+
+/// 列出项目的执行会话工作树
+#[tauri::command]
+pub async fn list_project_worktrees(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    pool: State<'_, WorktreePoolHandle>,
+) -> Result<Vec<WorktreeInfo>, String> {
+    let project_uuid = authorize(&project_id, &token, &db, ProjectRole::Viewer).await?;
+
+    pool.list(project_uuid).await
+        .map_err(|e| format!("查询工作树失败: {}", e))
+}
+
+/// 清理项目中超出空闲上限的工作树，返回被删除的路径
+#[tauri::command]
+pub async fn prune_project_worktrees(
+    project_id: String,
+    token: String,
+    db: State<'_, DatabaseHandle>,
+    pool: State<'_, WorktreePoolHandle>,
+) -> Result<Vec<String>, String> {
+    let project_uuid = authorize(&project_id, &token, &db, ProjectRole::Maintainer).await?;
+
+    let removed = pool.prune(project_uuid).await
+        .map_err(|e| format!("清理工作树失败: {}", e))?;
+    println!("已清理项目 {} 的 {} 个空闲工作树", project_id, removed.len());
+    Ok(removed.into_iter().map(|path| path.to_string_lossy().to_string()).collect())
+}
+
+async fn authorize(
+    project_id: &str,
+    token: &str,
+    db: &DatabaseHandle,
+    role: ProjectRole,
+) -> Result<Uuid, String> {
+    let project_uuid = Uuid::parse_str(project_id)
+        .map_err(|_| "无效的项目ID格式")?;
+    let auth_service = crate::auth::AuthService::new((**db).clone());
+    let current_user = auth_service.validate_token(token).await
+        .map_err(|e| format!("身份验证失败: {}", e))?;
+    require_project_role(db, project_uuid, current_user.user_id, role).await?;
+    Ok(project_uuid)
+}
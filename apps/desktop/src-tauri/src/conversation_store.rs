@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::fs;
+use crate::models::{ConversationOverrides, ConversationRecord, Message, MessageRole};
+
+/// 默认对话标题
+pub const DEFAULT_TITLE: &str = "新的对话";
+
+/// 标题最大字符数
+const MAX_TITLE_CHARS: usize = 30;
+
+/// 根据首条用户消息生成标题：取第一行有效文本，去除Markdown标记并截断
+pub fn generate_title(content: &str) -> String {
+    let first_line = content
+        .lines()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty() && !line.starts_with("```"))
+        .unwrap_or("");
+
+    let cleaned = first_line
+        .trim_start_matches(|c: char| c == '#' || c == '>' || c == '-' || c == '*')
+        .replace(['`', '*', '_'], "");
+    let cleaned = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if cleaned.is_empty() {
+        return DEFAULT_TITLE.to_string();
+    }
+
+    let mut title: String = cleaned.chars().take(MAX_TITLE_CHARS).collect();
+    if cleaned.chars().count() > MAX_TITLE_CHARS {
+        title.push_str("...");
+    }
+    title
+}
+
+/// 对话记录存储（标题、参数覆盖等元数据）
+pub struct ConversationStore {
+    store_path: PathBuf,
+}
+
+impl ConversationStore {
+    /// 创建新的对话存储
+    pub fn new() -> Result<Self, String> {
+        Ok(Self {
+            store_path: crate::profiles::data_home().join("conversations.json"),
+        })
+    }
+
+    /// 加载全部对话记录
+    pub async fn load_all(&self) -> Result<HashMap<String, ConversationRecord>, String> {
+        if !self.store_path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let contents = fs::read_to_string(&self.store_path).await
+            .map_err(|e| format!("读取对话记录失败: {}", e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("解析对话记录失败: {}", e))
+    }
+
+    /// 保存全部对话记录
+    async fn save_all(&self, records: &HashMap<String, ConversationRecord>) -> Result<(), String> {
+        if let Some(parent) = self.store_path.parent() {
+            fs::create_dir_all(parent).await
+                .map_err(|e| format!("创建对话存储目录失败: {}", e))?;
+        }
+
+        let contents = serde_json::to_string_pretty(records)
+            .map_err(|e| format!("序列化对话记录失败: {}", e))?;
+        fs::write(&self.store_path, contents).await
+            .map_err(|e| format!("写入对话记录失败: {}", e))
+    }
+
+    /// 获取单个对话记录
+    pub async fn get(&self, conversation_id: &str) -> Result<Option<ConversationRecord>, String> {
+        Ok(self.load_all().await?.remove(conversation_id))
+    }
+
+    /// 新建对话记录
+    pub async fn create(
+        &self,
+        conversation_id: &str,
+        overrides: ConversationOverrides,
+    ) -> Result<ConversationRecord, String> {
+        let mut records = self.load_all().await?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let record = ConversationRecord {
+            conversation_id: conversation_id.to_string(),
+            title: DEFAULT_TITLE.to_string(),
+            title_is_custom: false,
+            overrides,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        records.insert(conversation_id.to_string(), record.clone());
+        self.save_all(&records).await?;
+        Ok(record)
+    }
+
+    /// 更新对话参数覆盖
+    pub async fn update_overrides(
+        &self,
+        conversation_id: &str,
+        overrides: ConversationOverrides,
+    ) -> Result<ConversationRecord, String> {
+        let mut records = self.load_all().await?;
+        let record = records
+            .get_mut(conversation_id)
+            .ok_or_else(|| format!("对话记录不存在: {}", conversation_id))?;
+        record.overrides = overrides;
+        record.updated_at = chrono::Utc::now().to_rfc3339();
+        let updated = record.clone();
+        self.save_all(&records).await?;
+        Ok(updated)
+    }
+
+    /// 重命名对话
+    pub async fn rename(&self, conversation_id: &str, title: &str) -> Result<ConversationRecord, String> {
+        let title = title.trim();
+        if title.is_empty() {
+            return Err("对话标题不能为空".to_string());
+        }
+
+        let mut records = self.load_all().await?;
+        let record = records
+            .get_mut(conversation_id)
+            .ok_or_else(|| format!("对话记录不存在: {}", conversation_id))?;
+        record.title = title.chars().take(100).collect();
+        record.title_is_custom = true;
+        record.updated_at = chrono::Utc::now().to_rfc3339();
+        let updated = record.clone();
+        self.save_all(&records).await?;
+        Ok(updated)
+    }
+
+    /// 根据首条用户消息自动生成标题；已有标题或手动命名时返回None
+    pub async fn ensure_title_from_message(
+        &self,
+        conversation_id: &str,
+        content: &str,
+    ) -> Result<Option<ConversationRecord>, String> {
+        let mut records = self.load_all().await?;
+        let Some(record) = records.get_mut(conversation_id) else {
+            return Ok(None);
+        };
+        if record.title_is_custom || record.title != DEFAULT_TITLE {
+            return Ok(None);
+        }
+
+        let title = generate_title(content);
+        if title == DEFAULT_TITLE {
+            return Ok(None);
+        }
+
+        record.title = title;
+        record.updated_at = chrono::Utc::now().to_rfc3339();
+        let updated = record.clone();
+        self.save_all(&records).await?;
+        Ok(Some(updated))
+    }
+
+    /// 删除对话记录
+    pub async fn remove(&self, conversation_id: &str) -> Result<(), String> {
+        let mut records = self.load_all().await?;
+        if records.remove(conversation_id).is_some() {
+            self.save_all(&records).await?;
+        }
+
+        let messages_path = self.messages_path(conversation_id);
+        if messages_path.exists() {
+            fs::remove_file(&messages_path).await
+                .map_err(|e| format!("删除对话消息失败: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// 对话消息文件路径，每个对话单独存储
+    fn messages_path(&self, conversation_id: &str) -> PathBuf {
+        self.store_path
+            .with_file_name("conversations")
+            .join(format!("{}.json", conversation_id))
+    }
+
+    /// 加载对话消息
+    pub async fn load_messages(&self, conversation_id: &str) -> Result<Vec<Message>, String> {
+        let path = self.messages_path(conversation_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let contents = fs::read_to_string(&path).await
+            .map_err(|e| format!("读取对话消息失败: {}", e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("解析对话消息失败: {}", e))
+    }
+
+    /// 追加消息；同一ID的助手消息（同一回合的多段输出）合并为一条
+    pub async fn append_message(&self, message: Message) -> Result<(), String> {
+        let mut messages = self.load_messages(&message.conversation_id).await?;
+        match messages.iter_mut().find(|m| m.id == message.id) {
+            Some(existing) if message.role == MessageRole::Assistant => {
+                existing.content.push_str("\n\n");
+                existing.content.push_str(&message.content);
+                existing.timestamp = message.timestamp;
+            }
+            _ => messages.push(message.clone()),
+        }
+
+        let path = self.messages_path(&message.conversation_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await
+                .map_err(|e| format!("创建对话消息目录失败: {}", e))?;
+        }
+        let contents = serde_json::to_string_pretty(&messages)
+            .map_err(|e| format!("序列化对话消息失败: {}", e))?;
+        fs::write(&path, contents).await
+            .map_err(|e| format!("写入对话消息失败: {}", e))
+    }
+
+    /// 查找单条消息
+    pub async fn find_message(
+        &self,
+        conversation_id: &str,
+        message_id: &str,
+    ) -> Result<Option<Message>, String> {
+        Ok(self
+            .load_messages(conversation_id)
+            .await?
+            .into_iter()
+            .find(|m| m.id == message_id))
+    }
+}
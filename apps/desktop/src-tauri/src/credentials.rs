@@ -65,7 +65,7 @@ impl CredentialsService {
         let app_data_dir = app_handle.path().app_data_dir()
             .map_err(|e| format!("获取应用数据目录失败: {}", e))?;
         
-        let storage_dir = app_data_dir.join("sker").join("credentials");
+        let storage_dir = crate::profiles::app_home(app_data_dir.join("sker")).join("credentials");
         if !storage_dir.exists() {
             fs::create_dir_all(&storage_dir)
                 .map_err(|e| format!("创建存储目录失败: {}", e))?;
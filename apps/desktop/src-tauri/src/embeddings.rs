@@ -0,0 +1,107 @@
+// 语义检索 - 根据设置选择嵌入模型并启动后台索引
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_database::embeddings::{
+    self, EmbeddingFuture, EmbeddingIndex, EmbeddingProvider, HashingEmbeddingProvider,
+};
+use codex_database::DatabaseError;
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::{DatabaseHandle, EmbeddingIndexHandle};
+use crate::settings::{ApiProvider, EmbeddingProviderKind, SystemSettings};
+
+/// 后台索引的最短间隔
+const MIN_INDEX_INTERVAL_SECS: u64 = 60;
+
+/// OpenAI 兼容的 /embeddings 接口
+struct ApiEmbeddingProvider {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl EmbeddingProvider for ApiEmbeddingProvider {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> EmbeddingFuture<'a> {
+        Box::pin(async move {
+            let response = self.client
+                .post(format!("{}/embeddings", self.base_url))
+                .bearer_auth(&self.api_key)
+                .json(&serde_json::json!({ "model": self.model, "input": texts }))
+                .timeout(Duration::from_secs(30))
+                .send()
+                .await
+                .map_err(|e| DatabaseError::business_logic(format!("嵌入接口请求失败: {}", e)))?;
+
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(DatabaseError::business_logic(format!("嵌入接口返回错误 {}: {}", status, body)));
+            }
+
+            let mut body: EmbeddingResponse = response.json().await
+                .map_err(|e| DatabaseError::business_logic(format!("解析嵌入接口响应失败: {}", e)))?;
+            body.data.sort_by_key(|data| data.index);
+            Ok(body.data.into_iter().map(|data| data.embedding).collect())
+        })
+    }
+}
+
+/// 按设置创建嵌入提供方，API 配置不可用时退回本地模型
+fn build_provider(settings: &SystemSettings) -> Arc<dyn EmbeddingProvider> {
+    if settings.search.provider == EmbeddingProviderKind::Local {
+        return Arc::new(HashingEmbeddingProvider::default());
+    }
+
+    let api = &settings.api_config;
+    let base_url = match api.provider {
+        ApiProvider::Openai => Some(api.base_url.as_deref().unwrap_or("https://api.openai.com/v1")),
+        ApiProvider::Custom => api.base_url.as_deref(),
+        // Anthropic 没有嵌入接口
+        ApiProvider::Anthropic => None,
+    };
+    match base_url {
+        Some(base_url) if !api.api_key.is_empty() => Arc::new(ApiEmbeddingProvider {
+            client: crate::network::http_client(crate::network::llm_target(&api.provider)),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api.api_key.clone(),
+            model: settings.search.embedding_model.clone(),
+        }),
+        _ => {
+            eprintln!("当前 API 配置不支持嵌入接口，语义检索使用本地模型");
+            Arc::new(HashingEmbeddingProvider::default())
+        }
+    }
+}
+
+/// 注册语义检索索引，并按设置启动后台索引
+pub fn start(app: &AppHandle, db: &DatabaseHandle, settings: &SystemSettings) {
+    let index: EmbeddingIndexHandle = Arc::new(EmbeddingIndex::new((**db).clone(), build_provider(settings)));
+    app.manage(index.clone());
+
+    if !settings.search.enabled {
+        println!("语义检索后台索引已关闭");
+        return;
+    }
+
+    let interval = settings.search.index_interval_secs.max(MIN_INDEX_INTERVAL_SECS);
+    println!("语义检索已启用，嵌入模型 {}，每 {} 秒更新索引", index.model(), interval);
+    embeddings::spawn_background_indexing(index, Duration::from_secs(interval));
+}
@@ -3,6 +3,11 @@ use std::sync::Arc;
 use tauri::Manager;
 
 use codex_core::{ConversationManager, AuthManager};
+use codex_database::artifact_store::ArtifactStore;
+use codex_database::worktree_pool::WorktreePool;
+use codex_database::maintenance::MaintenanceRunner;
+use codex_database::payload_storage::{PayloadOffloadJob, PayloadStore};
+use codex_database::command_policy::{CommandPolicy, CommandPolicyEngine};
 
 // 启用核心模块
 pub mod commands;
@@ -10,7 +15,24 @@ pub mod models;
 pub mod settings;
 pub mod settings_migration;
 pub mod auth;
+pub mod oidc;
 pub mod credentials;
+pub mod conversation_store;
+pub mod telemetry;
+pub mod network;
+pub mod embeddings;
+pub mod osv;
+pub mod remote_workers;
+pub mod sla_monitor;
+pub mod orchestration_config_monitor;
+pub mod notification_dispatcher;
+pub mod report_scheduler;
+pub mod maintenance_scheduler;
+pub mod webhook_server;
+pub mod public_api_server;
+pub mod shutdown;
+pub mod updater;
+pub mod profiles;
 
 /// 简化的应用程序入口
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -30,9 +52,15 @@ pub fn run() {
             
 
             // 初始化认证管理器
-            let codex_home = app.path().app_data_dir()
+            let profile = profiles::active();
+            println!("当前档案: {} ({})", profile.name, profile.data_dir.display());
+            let codex_home = profiles::app_home(app.path().app_data_dir()
                 .expect("无法获取应用数据目录")
-                .join("sker");
+                .join("sker"));
+            let artifacts_root = codex_home.join("artifacts");
+            let worktrees_root = codex_home.join("worktrees");
+            let snapshots_root = codex_home.join("snapshots");
+            let data_dir = codex_home.clone();
             let auth_manager = Arc::new(AuthManager::new(codex_home));
             app.manage(auth_manager.clone());
 
@@ -40,14 +68,104 @@ pub fn run() {
             let conversation_manager = Arc::new(ConversationManager::new(auth_manager));
             app.manage(conversation_manager);
             
+            // 初始化待审批补丁缓存
+            let pending_patches: commands::PendingPatchesHandle = Default::default();
+            app.manage(pending_patches);
+            
+            // 初始化工作空间监听任务表
+            let workspace_watchers: commands::WorkspaceWatchersHandle = Default::default();
+            app.manage(workspace_watchers);
+            
+            // 初始化长时间操作登记表，并把进度转发给前端
+            let operation_registry: commands::OperationRegistryHandle = Default::default();
+            app.manage(operation_registry.clone());
+            commands::forward_operation_progress(app.handle().clone(), operation_registry.clone());
+            
+            // 初始化执行日志管道，命令输出合并后按批次推送给前端
+            app.manage(commands::start_log_pipeline(app.handle().clone()));
+            
+            // 初始化关闭协调器
+            let shutdown_coordinator = shutdown::install(app.handle(), &data_dir, operation_registry);
+            
             // 初始化数据库连接
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 match commands::config::create_database_connection().await {
                     Ok(db) => {
                         let db_handle = Arc::new(db);
-                        app_handle.manage(db_handle);
+                        app_handle.manage(db_handle.clone());
                         println!("数据库连接初始化成功");
+                        
+                        // 检查上次是否正常关闭，结束遗留的执行会话
+                        shutdown::recover(&shutdown_coordinator, &db_handle).await;
+                        
+                        // 初始化命令策略引擎
+                        match CommandPolicyEngine::new((*db_handle).clone(), CommandPolicy::default()) {
+                            Ok(engine) => {
+                                let engine: commands::CommandPolicyEngineHandle = Arc::new(engine);
+                                app_handle.manage(engine);
+                            }
+                            Err(e) => eprintln!("初始化命令策略引擎失败: {}", e),
+                        }
+                        
+                        // 初始化执行工件存储
+                        let artifact_store: commands::ArtifactStoreHandle =
+                            Arc::new(ArtifactStore::new((*db_handle).clone(), artifacts_root));
+                        app_handle.manage(artifact_store.clone());
+                        
+                        // 初始化执行会话工作树池
+                        let worktree_pool: commands::WorktreePoolHandle =
+                            Arc::new(WorktreePool::new((*db_handle).clone(), worktrees_root));
+                        app_handle.manage(worktree_pool);
+                        
+                        // 初始化定期维护任务执行器
+                        let mut maintenance_runner = MaintenanceRunner::with_builtin_jobs((*db_handle).clone(), Some(snapshots_root));
+                        maintenance_runner.register(Arc::new(PayloadOffloadJob::new(PayloadStore::new((*artifact_store).clone()))));
+                        let maintenance_runner: commands::MaintenanceRunnerHandle = Arc::new(maintenance_runner);
+                        app_handle.manage(maintenance_runner.clone());
+                        
+                        // 按设置应用网络代理，启动遥测指标导出、语义检索、远程工作进程服务、Webhook接入、自动化接口、SLA监控、编排配置监控、定时报告、定期维护和更新检查，并清理过期的执行工件
+                        match settings::SettingsManager::new() {
+                            Ok(manager) => match manager.load_settings().await {
+                                Ok(app_settings) => {
+                                    network::apply_settings(&app_settings.system);
+                                    telemetry::apply_settings(&app_handle, &app_settings.system.telemetry).await;
+                                    commands::apply_artifact_retention(&artifact_store, &app_settings.system.artifacts).await;
+                                    embeddings::start(&app_handle, &db_handle, &app_settings.system);
+                                    remote_workers::start(&db_handle, &app_settings.system.remote_workers);
+                                    webhook_server::start(&db_handle, &app_settings.system);
+                                    public_api_server::start(&db_handle, &app_settings.system.public_api);
+                                    sla_monitor::start(&app_handle, &db_handle, &app_settings.system.sla_monitor);
+                                    orchestration_config_monitor::start(&app_handle, &db_handle, &app_settings.system.orchestration_config);
+                                    notification_dispatcher::start(&app_handle, &db_handle);
+                                    report_scheduler::start(&app_handle, &artifact_store, &app_settings.system.report_scheduler);
+                                    maintenance_scheduler::start(&app_handle, &maintenance_runner, &app_settings.system.maintenance);
+                                    updater::start(&app_handle, &app_settings.system);
+                                    app_handle.manage(commands::create_llm_cache(&db_handle, &app_settings.system.llm_cache));
+                                }
+                                Err(e) => eprintln!("加载设置失败: {}", e),
+                            },
+                            Err(e) => eprintln!("创建设置管理器失败: {}", e),
+                        }
+                        
+                        // 设置加载失败时使用默认配置的LLM响应缓存（关闭）
+                        if app_handle.try_state::<commands::LlmCacheHandle>().is_none() {
+                            app_handle.manage(commands::create_llm_cache(&db_handle, &settings::LlmCacheSettings::default()));
+                        }
+                        
+                        // 定期清理过期会话
+                        let auth_service = auth::AuthService::new((*db_handle).clone());
+                        tauri::async_runtime::spawn(async move {
+                            let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+                            loop {
+                                interval.tick().await;
+                                match auth_service.cleanup_expired_sessions().await {
+                                    Ok(0) => {}
+                                    Ok(count) => println!("已清理 {} 个过期会话", count),
+                                    Err(e) => eprintln!("{}", e),
+                                }
+                            }
+                        });
                     }
                     Err(e) => {
                         eprintln!("数据库连接初始化失败: {}", e);
@@ -68,6 +186,13 @@ pub fn run() {
             auth::get_current_user,
             auth::change_password,
             auth::update_user_info,
+            auth::list_active_sessions,
+            auth::revoke_session,
+            auth::list_oidc_providers,
+            auth::login_with_oidc,
+            auth::link_oidc_identity,
+            auth::list_linked_identities,
+            auth::unlink_identity,
             // 简化的对话命令
             commands::create_conversation,
             commands::send_message,
@@ -76,9 +201,12 @@ pub fn run() {
             commands::interrupt_conversation,
             commands::add_conversation_listener,
             commands::remove_conversation_listener,
+            commands::update_conversation_settings,
+            commands::rename_conversation,
             // 审批命令
             commands::approve_exec_command,
             commands::approve_patch_command,
+            commands::get_pending_patch,
             commands::diagnose_system,
             // 设置管理命令
             settings::get_app_settings,
@@ -100,6 +228,21 @@ pub fn run() {
             commands::get_project,
             commands::update_project,
             commands::delete_project,
+            // 项目成员命令
+            commands::list_project_members,
+            commands::add_project_member,
+            commands::update_project_member_role,
+            commands::remove_project_member,
+            // 任务命令
+            commands::create_task_from_message,
+            commands::set_task_scope,
+            // 工作空间文件浏览命令
+            commands::list_workspace_entries,
+            commands::read_file_preview,
+            commands::watch_workspace,
+            commands::unwatch_workspace,
+            commands::get_workspace_quota_report,
+            commands::set_workspace_quota,
             // 凭据管理命令
             credentials::save_credentials,
             credentials::get_saved_credentials,
@@ -107,13 +250,208 @@ pub fn run() {
             credentials::has_saved_credentials,
             // 智能体管理命令
             commands::create_agent,
+            commands::provision_agents,
             commands::get_agents,
             commands::get_agent,
+            commands::get_agent_approval_policy,
             commands::update_agent,
             commands::delete_agent,
             commands::get_agent_work_history,
             commands::get_agent_performance_metrics,
+            commands::export_agent_bundle,
+            commands::import_agent_bundle,
+            // 演示工作区命令
+            commands::load_demo_workspace,
+            commands::get_demo_workspace,
+            commands::delete_demo_workspace,
+            commands::replay_demo_session,
+            // 执行工件命令
+            commands::list_session_artifacts,
+            commands::download_artifact,
+            commands::open_artifact,
+            commands::get_session_diff,
+            // 语义检索命令
+            commands::semantic_search,
+            commands::rebuild_search_index,
+            // 需求追踪命令
+            commands::get_requirement_sections,
+            commands::link_task_to_requirement,
+            commands::unlink_task_from_requirement,
+            commands::get_requirement_coverage_report,
+            // 验收标准命令
+            commands::get_acceptance_checklist,
+            commands::mark_acceptance_criterion,
+            commands::sign_off_acceptance_criterion,
+            commands::complete_task_with_override,
+            // 项目黑板命令
+            commands::list_blackboard_entries,
+            commands::get_blackboard_entry_history,
+            commands::write_blackboard_entry,
+            commands::delete_blackboard_entry,
+            // 长时间操作命令
+            commands::list_operations,
+            commands::get_operation,
+            commands::cancel_operation,
+            commands::start_search_index_rebuild,
+            commands::start_workspace_scan,
+            // 命令策略命令
+            commands::list_command_audit_logs,
+            // 个人信息脱敏命令
+            commands::get_project_pii_scrubbing,
+            commands::update_project_pii_scrubbing,
+            commands::build_decomposition_prompt,
+            commands::reidentify_pii,
+            // 紧急任务抢占命令
+            commands::get_project_preemption_policy,
+            commands::update_project_preemption_policy,
+            commands::preempt_for_critical_task,
+            // 编排配置命令
+            commands::get_orchestration_config,
+            commands::update_orchestration_overrides,
+            // 执行会话检查点命令
+            commands::save_session_checkpoint,
+            commands::get_session_checkpoint,
+            commands::resume_execution_session,
+            // SLA 命令
+            commands::get_project_sla_policy,
+            commands::update_project_sla_policy,
+            commands::list_project_sla_breaches,
+            commands::evaluate_project_sla,
+            // 依赖审计命令
+            commands::start_dependency_audit,
+            // CI 集成命令
+            commands::ingest_ci_webhook,
+            commands::list_ci_checks,
+            commands::get_task_ci_status,
+            commands::submit_review_decision,
+            // Webhook 接入命令
+            commands::list_webhook_dead_letters,
+            commands::replay_webhook_dead_letter,
+            commands::discard_webhook_dead_letter,
+            // 冲突处理中心命令
+            commands::list_conflicts,
+            commands::get_conflict_detail,
+            commands::resolve_conflict,
+            commands::escalate_conflict,
+            commands::record_human_decision,
+            // 智能体性能仪表盘命令
+            commands::get_agent_metrics,
+            commands::get_team_leaderboard,
+            commands::get_agent_history,
+            // 定时报告命令
+            commands::create_report_schedule,
+            commands::list_report_schedules,
+            commands::update_report_schedule,
+            commands::delete_report_schedule,
+            commands::generate_project_report,
+            commands::list_project_reports,
+            commands::get_project_report,
+            // 审计包导出命令
+            commands::export_project_audit,
+            // 任务与日志的流式导出命令
+            commands::export_project_tasks,
+            commands::export_project_logs,
+            // 执行日志管道命令
+            commands::get_log_pipeline_metrics,
+            // Git仓库导入命令
+            commands::detect_git_repository,
+            commands::import_project_from_git,
+            // 工作树池命令
+            commands::list_project_worktrees,
+            commands::prune_project_worktrees,
+            // 并行执行命令
+            commands::get_execution_waves,
+            commands::launch_parallel_execution,
+            // LLM响应缓存命令
+            commands::get_llm_cache_stats,
+            commands::clear_llm_cache,
+            // LLM调用记录命令
+            commands::list_llm_transcripts,
+            commands::list_conversation_llm_transcripts,
+            commands::get_llm_transcript,
+            commands::list_llm_replay_reports,
+            // 提示词评估命令
+            commands::list_eval_sets,
+            commands::save_eval_set,
+            commands::list_eval_reports,
+            // 人工检查点命令
+            commands::define_task_gate,
+            commands::get_task_gate_timeline,
+            commands::request_task_gate,
+            commands::decide_task_gate,
+            // 审查时限命令
+            commands::set_review_priority,
+            commands::assign_code_reviewer,
+            commands::evaluate_project_review_sla,
+            // 代码归属命令
+            commands::get_project_code_ownership,
+            commands::update_project_code_ownership,
+            commands::import_project_codeowners,
+            commands::suggest_code_reviewers,
+            // 不稳定测试命令
+            commands::record_session_test_results,
+            commands::get_flaky_test_report,
+            commands::evaluate_session_test_gate,
+            // 覆盖率命令
+            commands::ingest_session_coverage,
+            commands::get_session_coverage,
+            commands::evaluate_session_coverage_gate,
+            // 静态分析命令
+            commands::run_session_static_analysis,
+            commands::get_session_lint_findings,
+            commands::evaluate_session_lint_gate,
+            // 安全扫描命令
+            commands::run_session_security_scan,
+            commands::get_session_security_findings,
+            commands::evaluate_session_security_gate,
+            // 性能预算命令
+            commands::get_project_perf_budget,
+            commands::update_project_perf_budget,
+            commands::run_session_perf_check,
+            commands::evaluate_session_perf_gate,
+            commands::promote_session_perf_baseline,
+            commands::get_benchmark_history,
+            // 自动更新命令
+            commands::check_for_updates,
+            commands::install_update,
+            commands::schedule_update_install,
+            commands::get_scheduled_update,
+            commands::cancel_scheduled_update,
+            commands::get_update_history,
+            commands::rollback_update,
+            // 配置档案命令
+            commands::list_profiles,
+            commands::create_profile,
+            commands::switch_profile,
+            commands::delete_profile,
+            // 数据隐私命令
+            commands::purge_user_data,
+            // API密钥命令
+            commands::create_api_key,
+            commands::list_api_keys,
+            commands::revoke_api_key,
+            // 两步验证命令
+            commands::get_two_factor_status,
+            commands::begin_two_factor_enrollment,
+            commands::confirm_two_factor_enrollment,
+            commands::regenerate_backup_codes,
+            commands::disable_two_factor,
+            commands::set_two_factor_required,
+            // 通知命令
+            commands::get_notification_preferences,
+            commands::update_notification_preferences,
+            commands::list_notifications,
+            commands::mark_notifications_read,
+            // 命令面板
+            commands::list_available_commands,
+            commands::execute_command_by_id,
+            // 定期维护命令
+            commands::list_maintenance_jobs,
+            commands::list_maintenance_job_runs,
+            commands::trigger_maintenance_job,
+            commands::update_maintenance_job,
         ])
-        .run(tauri::generate_context!())
-        .expect("运行Tauri应用程序时出错");
+        .build(tauri::generate_context!())
+        .expect("运行Tauri应用程序时出错")
+        .run(|app_handle, event| shutdown::handle_run_event(app_handle, &event));
 }
\ No newline at end of file
@@ -0,0 +1,46 @@
+// 定期维护 - 登记内置维护任务，按设置定期执行到期的任务并把执行结果通知前端
+use std::time::Duration;
+
+use chrono::Utc;
+use codex_database::maintenance;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::{MaintenanceJobRunInfo, MaintenanceRunnerHandle};
+use crate::settings::MaintenanceSettings;
+
+/// 前端监听的维护任务执行事件名
+pub const MAINTENANCE_RUN_EVENT: &str = "maintenance_job_finished";
+
+/// 检查的最短间隔
+const MIN_INTERVAL_SECS: u64 = 30;
+
+/// 启动定期维护
+pub fn start(app: &AppHandle, runner: &MaintenanceRunnerHandle, settings: &MaintenanceSettings) {
+    if !settings.enabled {
+        return;
+    }
+
+    let app = app.clone();
+    let runner = runner.clone();
+    let period = Duration::from_secs(settings.interval_secs.max(MIN_INTERVAL_SECS));
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = runner.ensure_jobs(maintenance::default_jobs(), Utc::now()).await {
+            eprintln!("登记维护任务失败: {}", e);
+        }
+
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            match runner.run_due(Utc::now()).await {
+                Ok(runs) => {
+                    for run in runs {
+                        if let Err(e) = app.emit(MAINTENANCE_RUN_EVENT, MaintenanceJobRunInfo::from(run)) {
+                            eprintln!("推送维护任务事件失败: {}", e);
+                        }
+                    }
+                }
+                Err(e) => eprintln!("执行维护任务失败: {}", e),
+            }
+        }
+    });
+}
@@ -1,3 +1,9 @@
+use codex_database::{
+    entities::{agent, project, task},
+    fixtures::is_demo_marked,
+    mapping,
+    session_diff::FileDiff,
+};
 use serde::{Deserialize, Serialize};
 
 
@@ -64,6 +70,7 @@ pub struct Project {
     pub workspace_path: String,
     pub technology_stack: Vec<String>,
     pub status: String,
+    pub is_demo: bool, // 演示工作区创建的项目
     pub created_at: String,
     pub updated_at: String,
 }
@@ -149,6 +156,51 @@ pub struct UpdateAgentRequest {
     pub status: Option<String>,
 }
 
+/// 导入Agent包的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportAgentBundleResult {
+    pub agent: Agent,
+    pub renamed_from: Option<String>, // 因同名而改名时的原名称
+    pub overwritten: bool,
+}
+
+/// 演示工作区概况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DemoWorkspaceSummary {
+    pub project_ids: Vec<String>,
+    pub agent_count: usize,
+    pub task_count: usize,
+    pub conflict_count: usize,
+    pub session_count: usize,
+    pub log_count: usize,
+    pub event_count: usize,
+    pub scripted_session_id: Option<String>, // 可回放的演示执行会话
+}
+
+/// 演示执行会话回放的日志事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DemoExecutionLog {
+    pub session_id: String,
+    pub log_level: String,
+    pub event_type: String,
+    pub message: String,
+    pub timestamp_ms: i64,
+    pub is_last: bool,
+}
+
+/// 执行会话产出的工件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionArtifact {
+    pub artifact_id: String,
+    pub session_id: String,
+    pub name: String,
+    pub artifact_type: String,
+    pub size_bytes: u64,
+    pub checksum: String,
+    pub description: Option<String>,
+    pub created_at: String,
+}
+
 /// 智能体工作历史
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentWorkHistory {
@@ -182,6 +234,135 @@ pub struct AgentPerformanceMetrics {
     pub created_at: String,
 }
 
+/// 对话级参数覆盖（未设置的字段沿用全局设置）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConversationOverrides {
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    pub reasoning_effort: Option<String>, // "minimal" | "low" | "medium" | "high"
+    pub sandbox_policy: Option<String>,   // "read-only" | "workspace-write" | "danger-full-access"
+    /// 以指定智能体身份运行，命令审批和网络访问遵循其审批策略
+    #[serde(default)]
+    pub agent_id: Option<String>,
+}
+
+/// 持久化的对话记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationRecord {
+    pub conversation_id: String,
+    pub title: String,
+    /// 用户手动重命名后不再自动生成标题
+    #[serde(default)]
+    pub title_is_custom: bool,
+    #[serde(default)]
+    pub overrides: ConversationOverrides,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 更新对话设置请求
+#[derive(Debug, Deserialize)]
+pub struct UpdateConversationSettingsRequest {
+    pub conversation_id: String,
+    pub overrides: ConversationOverrides,
+}
+
+/// 从消息创建任务时的覆盖项（未设置的字段使用从消息中解析的结果）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskFromMessageOverrides {
+    pub project_id: String,
+    pub parent_task_id: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub acceptance_criteria: Option<Vec<String>>,
+    pub task_type: Option<String>,
+    pub priority: Option<String>,
+    /// 工作范围：限定Agent可修改的子目录（相对工作空间根目录）
+    pub scope_path: Option<String>,
+}
+
+/// 任务实体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Task {
+    pub task_id: String,
+    pub project_id: String,
+    pub parent_task_id: Option<String>,
+    pub llm_session_id: Option<String>,
+    pub title: String,
+    pub description: String,
+    pub task_type: String,
+    pub priority: String,
+    pub status: String,
+    pub acceptance_criteria: Vec<String>,
+    pub scope_path: Option<String>,
+    pub source_conversation_id: Option<String>,
+    pub source_message_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 项目成员
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectMember {
+    pub member_id: String,
+    pub project_id: String,
+    pub user_id: String,
+    pub username: String,
+    pub role: String, // owner, maintainer, contributor, viewer
+    pub invited_by: Option<String>,
+    pub joined_at: String,
+}
+
+/// 添加项目成员请求
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddProjectMemberRequest {
+    pub project_id: String,
+    pub username: String,
+    pub role: String,
+}
+
+/// 工作空间目录项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceEntry {
+    pub name: String,
+    pub path: String, // 相对于工作空间根目录
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified_at: Option<String>,
+}
+
+/// 文件预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilePreview {
+    pub path: String,
+    pub content: String,
+    pub size: u64,
+    pub truncated: bool,
+    pub is_binary: bool,
+}
+
+/// 工作空间变更事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceChangeEvent {
+    pub project_id: String,
+    pub created: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// 待审批补丁的结构化差异
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchDiff {
+    pub approval_id: String,
+    pub conversation_id: String,
+    pub call_id: String,
+    pub reason: Option<String>,
+    pub grant_root: Option<String>,
+    pub files: Vec<FileDiff>,
+    pub total_additions: usize,
+    pub total_deletions: usize,
+    pub requested_at: String,
+}
 
 impl Message {
     /// 创建用户消息
@@ -243,10 +424,7 @@ impl Conversation {
         
         // 如果是第一条用户消息，使用其内容作为标题
         if self.messages.len() == 1 && self.messages[0].role == MessageRole::User {
-            self.title = self.messages[0].content.chars().take(30).collect::<String>();
-            if self.messages[0].content.len() > 30 {
-                self.title.push_str("...");
-            }
+            self.title = crate::conversation_store::generate_title(&self.messages[0].content);
         }
     }
 
@@ -264,3 +442,75 @@ impl Conversation {
     }
 }
 
+impl TryFrom<project::Model> for Project {
+    type Error = String;
+
+    fn try_from(p: project::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            technology_stack: mapping::project_technology_stack(&p).map_err(|e| e.to_string())?,
+            is_demo: p.project_context.as_ref().is_some_and(is_demo_marked),
+            project_id: p.project_id.to_string(),
+            user_id: p.user_id.to_string(),
+            name: p.name,
+            description: p.description,
+            repository_url: p.repository_url,
+            main_branch: p.main_branch,
+            workspace_path: p.workspace_path,
+            status: p.status,
+            created_at: p.created_at.to_rfc3339(),
+            updated_at: p.updated_at.to_rfc3339(),
+        })
+    }
+}
+
+impl TryFrom<agent::Model> for Agent {
+    type Error = String;
+
+    fn try_from(a: agent::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            capabilities: mapping::decode_json("agent", "capabilities", &a.capabilities).map_err(|e| e.to_string())?,
+            agent_id: a.agent_id.to_string(),
+            user_id: a.user_id.to_string(),
+            name: a.name,
+            description: a.description,
+            prompt_template: a.prompt_template,
+            config: a.config,
+            git_config: a.git_config,
+            status: a.status,
+            current_task_id: a.current_task_id.map(|id| id.to_string()),
+            total_tasks_completed: a.total_tasks_completed,
+            success_rate: a.success_rate,
+            average_completion_time: a.average_completion_time,
+            created_at: a.created_at.to_rfc3339(),
+            updated_at: a.updated_at.to_rfc3339(),
+            last_active_at: a.last_active_at.to_rfc3339(),
+            skill_profile: a.skill_profile,
+            skill_assessments: a.skill_assessments,
+            performance_trend: a.performance_trend,
+        })
+    }
+}
+
+impl TryFrom<task::Model> for Task {
+    type Error = String;
+
+    fn try_from(t: task::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            acceptance_criteria: codex_database::acceptance::task_criteria(&t),
+            task_id: t.task_id.to_string(),
+            project_id: t.project_id.to_string(),
+            parent_task_id: t.parent_task_id.map(|id| id.to_string()),
+            llm_session_id: t.llm_session_id.map(|id| id.to_string()),
+            title: t.title,
+            description: t.description,
+            task_type: t.task_type,
+            priority: t.priority,
+            status: t.status,
+            scope_path: t.scope_path,
+            source_conversation_id: None,
+            source_message_id: None,
+            created_at: t.created_at.to_rfc3339(),
+            updated_at: t.updated_at.to_rfc3339(),
+        })
+    }
+}
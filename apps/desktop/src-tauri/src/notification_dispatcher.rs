@@ -0,0 +1,35 @@
+// 通知投递 - 定期取出到期的通知（免打扰时段结束、摘要到点），推送给前端显示
+use std::time::Duration;
+
+use codex_database::notifications::NotificationRouter;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::DatabaseHandle;
+
+/// 前端监听的通知投递事件名，载荷为一批通知（立即投递的单条或合并后的摘要）
+pub const NOTIFICATION_DELIVERED_EVENT: &str = "notification_delivered";
+
+/// 检查到期通知的间隔
+const DISPATCH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 启动通知投递
+pub fn start(app: &AppHandle, db: &DatabaseHandle) {
+    let app = app.clone();
+    let router = NotificationRouter::new((**db).clone());
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(DISPATCH_INTERVAL);
+        loop {
+            interval.tick().await;
+            match router.take_due(chrono::Utc::now()).await {
+                Ok(batches) => {
+                    for batch in batches {
+                        if let Err(e) = app.emit(NOTIFICATION_DELIVERED_EVENT, &batch) {
+                            eprintln!("推送通知失败: {}", e);
+                        }
+                    }
+                }
+                Err(e) => eprintln!("读取到期通知失败: {}", e),
+            }
+        }
+    });
+}
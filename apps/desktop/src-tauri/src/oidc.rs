@@ -0,0 +1,441 @@
+// 单点登录 - OIDC / OAuth 授权码流程（PKCE），通过本机回环地址接收回调
+//
+// 桌面应用是公开客户端，授权码只能配合本次生成的 code_verifier 兑换；用户信息通过 userinfo 端点
+// （GitHub 为 REST API）用访问令牌获取，因此不需要在本地校验 id_token 的签名。
+use std::time::Duration;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Utc};
+use codex_database::{repository::UserIdentityRepository, DatabaseConnection};
+use rand::RngCore;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+use crate::network;
+use crate::settings::{AuthSettings, OidcProviderKind, OidcProviderSettings};
+
+/// 等待浏览器完成登录的最长时间
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+
+const GOOGLE_ISSUER: &str = "https://accounts.google.com";
+const GITHUB_AUTHORIZE_URL: &str = "https://github.com/login/oauth/authorize";
+const GITHUB_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// 身份提供方的端点
+#[derive(Debug, Clone)]
+pub struct ProviderEndpoints {
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: Option<String>,
+}
+
+/// OIDC 发现文档中用到的字段
+#[derive(Debug, Deserialize)]
+struct DiscoveryDocument {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    #[serde(default)]
+    userinfo_endpoint: Option<String>,
+}
+
+/// PKCE 参数
+#[derive(Debug, Clone)]
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl Pkce {
+    /// 生成新的 code_verifier 及其 S256 摘要
+    pub fn generate() -> Self {
+        let verifier = random_token();
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Self { verifier, challenge }
+    }
+}
+
+/// 32 字节随机数的 base64url 编码
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// 令牌端点的响应
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub expires_in: Option<i64>,
+}
+
+impl TokenResponse {
+    /// 访问令牌的过期时间
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_in.map(|secs| Utc::now() + chrono::Duration::seconds(secs))
+    }
+}
+
+/// 令牌端点的错误响应
+#[derive(Debug, Deserialize)]
+struct TokenError {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+/// 身份提供方返回的用户信息
+#[derive(Debug, Clone)]
+pub struct ExternalIdentity {
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub display_name: Option<String>,
+    /// 建议的用户名（preferred_username 或 GitHub 登录名）
+    pub username: Option<String>,
+}
+
+/// 标准 OIDC userinfo 响应
+#[derive(Debug, Deserialize)]
+struct UserInfoClaims {
+    sub: String,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: Option<bool>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    preferred_username: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubUser {
+    id: i64,
+    login: String,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubEmail {
+    email: String,
+    primary: bool,
+    verified: bool,
+}
+
+/// 刷新令牌的结果
+#[derive(Debug)]
+pub enum TokenRefresh {
+    Refreshed(TokenResponse),
+    /// 身份提供方拒绝刷新令牌（已撤销授权或账号被禁用）
+    Revoked,
+}
+
+/// 按ID查找已配置的身份提供方
+pub fn find_provider<'a>(settings: &'a AuthSettings, provider_id: &str) -> Result<&'a OidcProviderSettings, String> {
+    settings.oidc_providers
+        .iter()
+        .find(|provider| provider.id == provider_id)
+        .ok_or_else(|| format!("未配置身份提供方 {}", provider_id))
+}
+
+/// 解析身份提供方的端点，OIDC 提供方读取发现文档
+pub async fn endpoints(client: &reqwest::Client, provider: &OidcProviderSettings) -> Result<ProviderEndpoints, String> {
+    let issuer = match provider.kind {
+        OidcProviderKind::Github => {
+            return Ok(ProviderEndpoints {
+                authorization_endpoint: GITHUB_AUTHORIZE_URL.to_string(),
+                token_endpoint: GITHUB_TOKEN_URL.to_string(),
+                userinfo_endpoint: None,
+            });
+        }
+        OidcProviderKind::Google => GOOGLE_ISSUER.to_string(),
+        OidcProviderKind::Oidc => provider.issuer.as_deref()
+            .map(str::trim)
+            .filter(|issuer| !issuer.is_empty())
+            .ok_or_else(|| format!("身份提供方 {} 缺少 issuer", provider.name))?
+            .to_string(),
+    };
+    let url = format!("{}/.well-known/openid-configuration", issuer.trim_end_matches('/'));
+    let document: DiscoveryDocument = client.get(&url).send().await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("获取 {} 的发现文档失败: {}", provider.name, e))?
+        .json().await
+        .map_err(|e| format!("解析 {} 的发现文档失败: {}", provider.name, e))?;
+    Ok(ProviderEndpoints {
+        authorization_endpoint: document.authorization_endpoint,
+        token_endpoint: document.token_endpoint,
+        userinfo_endpoint: document.userinfo_endpoint,
+    })
+}
+
+/// 申请的权限范围
+fn scopes(provider: &OidcProviderSettings) -> String {
+    if !provider.scopes.is_empty() {
+        return provider.scopes.join(" ");
+    }
+    match provider.kind {
+        OidcProviderKind::Github => "read:user user:email".to_string(),
+        OidcProviderKind::Google | OidcProviderKind::Oidc => "openid email profile".to_string(),
+    }
+}
+
+/// 构造授权地址
+pub fn authorization_url(
+    provider: &OidcProviderSettings,
+    endpoints: &ProviderEndpoints,
+    redirect_uri: &str,
+    state: &str,
+    pkce: &Pkce,
+) -> Result<String, String> {
+    let scope = scopes(provider);
+    let mut params = vec![
+        ("response_type", "code"),
+        ("client_id", provider.client_id.as_str()),
+        ("redirect_uri", redirect_uri),
+        ("scope", scope.as_str()),
+        ("state", state),
+        ("code_challenge", pkce.challenge.as_str()),
+        ("code_challenge_method", "S256"),
+    ];
+    // Google 只在离线授权时签发刷新令牌
+    if provider.kind == OidcProviderKind::Google {
+        params.push(("access_type", "offline"));
+        params.push(("prompt", "consent"));
+    }
+    reqwest::Url::parse_with_params(&endpoints.authorization_endpoint, &params)
+        .map(String::from)
+        .map_err(|e| format!("无效的授权地址 {}: {}", endpoints.authorization_endpoint, e))
+}
+
+/// 在回环地址上等待浏览器带回授权码
+async fn wait_for_callback(listener: TcpListener, expected_state: &str) -> Result<String, String> {
+    loop {
+        let (stream, _) = listener.accept().await
+            .map_err(|e| format!("接收登录回调失败: {}", e))?;
+        let (reader, mut writer) = stream.into_split();
+        let mut request_line = String::new();
+        BufReader::new(reader).read_line(&mut request_line).await
+            .map_err(|e| format!("读取登录回调失败: {}", e))?;
+        let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+        let url = reqwest::Url::parse(&format!("http://127.0.0.1{}", path))
+            .map_err(|e| format!("无效的登录回调: {}", e))?;
+        if url.path() != "/callback" {
+            // 浏览器会顺带请求 favicon 等资源
+            let _ = writer.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+            continue;
+        }
+
+        let param = |name: &str| url.query_pairs().find(|(key, _)| key == name).map(|(_, value)| value.into_owned());
+        let result = if let Some(error) = param("error") {
+            Err(format!("身份提供方拒绝登录: {}", param("error_description").unwrap_or(error)))
+        } else if param("state").as_deref() != Some(expected_state) {
+            Err("登录回调的 state 不匹配".to_string())
+        } else {
+            param("code").ok_or_else(|| "登录回调缺少授权码".to_string())
+        };
+
+        let message = match &result {
+            Ok(_) => "登录完成，可以关闭此页面并返回 Sker。",
+            Err(_) => "登录失败，请关闭此页面并返回 Sker 重试。",
+        };
+        let body = format!("<!DOCTYPE html><html><head><meta charset=\"utf-8\"></head><body><p>{}</p></body></html>", message);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let _ = writer.write_all(response.as_bytes()).await;
+        let _ = writer.shutdown().await;
+        return result;
+    }
+}
+
+/// 向令牌端点提交表单；`Ok(Err(_))` 表示身份提供方返回了 OAuth 错误
+async fn token_request(
+    client: &reqwest::Client,
+    provider: &OidcProviderSettings,
+    endpoints: &ProviderEndpoints,
+    mut form: Vec<(&str, String)>,
+) -> Result<Result<TokenResponse, TokenError>, String> {
+    form.push(("client_id", provider.client_id.clone()));
+    if let Some(secret) = provider.client_secret.as_deref().filter(|secret| !secret.is_empty()) {
+        form.push(("client_secret", secret.to_string()));
+    }
+    // GitHub 默认返回表单编码，需要显式要求 JSON
+    let response = client.post(&endpoints.token_endpoint)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .form(&form)
+        .send().await
+        .map_err(|e| format!("请求 {} 令牌失败: {}", provider.name, e))?;
+    let body: serde_json::Value = response.json().await
+        .map_err(|e| format!("解析 {} 令牌响应失败: {}", provider.name, e))?;
+    if body.get("error").is_some() {
+        return serde_json::from_value(body)
+            .map(Err)
+            .map_err(|e| format!("解析 {} 令牌错误失败: {}", provider.name, e));
+    }
+    serde_json::from_value(body)
+        .map(Ok)
+        .map_err(|e| format!("解析 {} 令牌响应失败: {}", provider.name, e))
+}
+
+/// 用授权码和 code_verifier 兑换令牌
+async fn exchange_code(
+    client: &reqwest::Client,
+    provider: &OidcProviderSettings,
+    endpoints: &ProviderEndpoints,
+    code: String,
+    redirect_uri: &str,
+    pkce: &Pkce,
+) -> Result<TokenResponse, String> {
+    let form = vec![
+        ("grant_type", "authorization_code".to_string()),
+        ("code", code),
+        ("redirect_uri", redirect_uri.to_string()),
+        ("code_verifier", pkce.verifier.clone()),
+    ];
+    token_request(client, provider, endpoints, form).await?
+        .map_err(|e| format!("兑换 {} 授权码失败: {}", provider.name, e.error_description.unwrap_or(e.error)))
+}
+
+/// 获取身份提供方中的用户信息
+async fn fetch_identity(
+    client: &reqwest::Client,
+    provider: &OidcProviderSettings,
+    endpoints: &ProviderEndpoints,
+    access_token: &str,
+) -> Result<ExternalIdentity, String> {
+    if provider.kind == OidcProviderKind::Github {
+        let user: GithubUser = github_get(client, "/user", access_token).await?;
+        let emails: Vec<GithubEmail> = github_get(client, "/user/emails", access_token).await.unwrap_or_default();
+        let primary = emails.into_iter().find(|email| email.primary && email.verified);
+        return Ok(ExternalIdentity {
+            subject: user.id.to_string(),
+            email_verified: primary.is_some(),
+            email: primary.map(|email| email.email),
+            display_name: user.name,
+            username: Some(user.login),
+        });
+    }
+
+    let userinfo = endpoints.userinfo_endpoint.as_deref()
+        .ok_or_else(|| format!("身份提供方 {} 没有 userinfo 端点", provider.name))?;
+    let claims: UserInfoClaims = client.get(userinfo)
+        .bearer_auth(access_token)
+        .send().await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("获取 {} 用户信息失败: {}", provider.name, e))?
+        .json().await
+        .map_err(|e| format!("解析 {} 用户信息失败: {}", provider.name, e))?;
+    Ok(ExternalIdentity {
+        subject: claims.sub,
+        email: claims.email,
+        email_verified: claims.email_verified.unwrap_or(false),
+        display_name: claims.name,
+        username: claims.preferred_username,
+    })
+}
+
+async fn github_get<T: serde::de::DeserializeOwned>(
+    client: &reqwest::Client,
+    path: &str,
+    access_token: &str,
+) -> Result<T, String> {
+    client.get(format!("{}{}", GITHUB_API_URL, path))
+        .bearer_auth(access_token)
+        .header(reqwest::header::USER_AGENT, "sker-desktop")
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send().await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("请求 GitHub {} 失败: {}", path, e))?
+        .json().await
+        .map_err(|e| format!("解析 GitHub {} 响应失败: {}", path, e))
+}
+
+/// 在系统浏览器中完成授权，返回外部身份和令牌
+pub async fn authorize(
+    app: &AppHandle,
+    provider: &OidcProviderSettings,
+) -> Result<(ExternalIdentity, TokenResponse), String> {
+    let client = network::try_http_client(network::IDENTITY_TARGET)?;
+    let endpoints = endpoints(&client, provider).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await
+        .map_err(|e| format!("监听登录回调失败: {}", e))?;
+    let port = listener.local_addr()
+        .map_err(|e| format!("获取回调端口失败: {}", e))?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{}/callback", port);
+    let pkce = Pkce::generate();
+    let state = random_token();
+    let url = authorization_url(provider, &endpoints, &redirect_uri, &state, &pkce)?;
+
+    app.opener()
+        .open_url(&url, None::<&str>)
+        .map_err(|e| format!("打开浏览器失败: {}", e))?;
+    println!("等待 {} 登录回调: {}", provider.name, redirect_uri);
+    let code = tokio::time::timeout(CALLBACK_TIMEOUT, wait_for_callback(listener, &state)).await
+        .map_err(|_| "等待登录超时".to_string())??;
+
+    let tokens = exchange_code(&client, provider, &endpoints, code, &redirect_uri, &pkce).await?;
+    let identity = fetch_identity(&client, provider, &endpoints, &tokens.access_token).await?;
+    Ok((identity, tokens))
+}
+
+/// 用刷新令牌换取新的访问令牌
+pub async fn refresh(provider: &OidcProviderSettings, refresh_token: &str) -> Result<TokenRefresh, String> {
+    let client = network::try_http_client(network::IDENTITY_TARGET)?;
+    let endpoints = endpoints(&client, provider).await?;
+    let form = vec![
+        ("grant_type", "refresh_token".to_string()),
+        ("refresh_token", refresh_token.to_string()),
+    ];
+    match token_request(&client, provider, &endpoints, form).await? {
+        Ok(tokens) => Ok(TokenRefresh::Refreshed(tokens)),
+        Err(error) if error.error == "invalid_grant" => Ok(TokenRefresh::Revoked),
+        Err(error) => Err(format!("刷新 {} 令牌失败: {}", provider.name, error.error_description.unwrap_or(error.error))),
+    }
+}
+
+/// 续期本地会话时刷新用户关联身份的令牌
+///
+/// 身份提供方明确拒绝（授权被撤销）时返回错误，要求重新登录；网络等临时故障只记录日志，
+/// 以免身份提供方不可用时把用户锁在应用外
+pub async fn refresh_user_identities(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    settings: &AuthSettings,
+) -> Result<(), String> {
+    let repo = UserIdentityRepository::new(db.clone());
+    let identities = repo.find_by_user(user_id).await
+        .map_err(|e| format!("查询关联身份失败: {}", e))?;
+    for identity in identities {
+        let Some(refresh_token) = identity.refresh_token.as_deref() else {
+            continue;
+        };
+        let Ok(provider) = find_provider(settings, &identity.provider) else {
+            continue;
+        };
+        match refresh(provider, refresh_token).await {
+            Ok(TokenRefresh::Refreshed(tokens)) => {
+                repo.update_tokens(identity.identity_id, tokens.refresh_token.clone(), tokens.expires_at()).await
+                    .map_err(|e| format!("保存 {} 令牌失败: {}", provider.name, e))?;
+            }
+            Ok(TokenRefresh::Revoked) => {
+                return Err(format!("{} 登录已失效，请重新登录", provider.name));
+            }
+            Err(e) => eprintln!("{}，沿用本地会话", e),
+        }
+    }
+    Ok(())
+}
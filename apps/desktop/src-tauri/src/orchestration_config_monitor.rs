@@ -0,0 +1,111 @@
+// 编排配置监控 - 定期检查各项目工作区的 sker.toml 和界面覆盖，生效值变化时通知前端
+use std::collections::HashMap;
+use std::time::Duration;
+
+use codex_database::orchestration_config::OrchestrationConfigWatcher;
+use codex_database::repository::ProjectRepository;
+use codex_multi_agent::OrchestrationConfigChangedEvent;
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use crate::commands::orchestration_config::project_config_loader;
+use crate::commands::DatabaseHandle;
+use crate::settings::{OrchestrationConfigSettings, SettingsManager};
+
+/// 前端监听的编排配置变更事件名
+pub const ORCHESTRATION_CONFIG_CHANGED_EVENT: &str = "orchestration_config_changed";
+
+/// 检查的最短间隔
+const MIN_INTERVAL_SECS: u64 = 2;
+
+/// 启动编排配置监控
+pub fn start(app: &AppHandle, db: &DatabaseHandle, settings: &OrchestrationConfigSettings) {
+    if !settings.watch_enabled {
+        return;
+    }
+
+    let app = app.clone();
+    let db = (**db).clone();
+    let period = Duration::from_secs(settings.interval_secs.max(MIN_INTERVAL_SECS));
+    tauri::async_runtime::spawn(async move {
+        let mut watchers: HashMap<Uuid, OrchestrationConfigWatcher> = HashMap::new();
+        // 每个项目最近一次报告的错误，相同错误不重复输出
+        let mut reported_errors: HashMap<Uuid, String> = HashMap::new();
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            let projects = match ProjectRepository::new(db.clone()).find_all().await {
+                Ok(projects) => projects,
+                Err(e) => {
+                    eprintln!("获取项目列表失败: {}", e);
+                    continue;
+                }
+            };
+            // 界面覆盖可能在命令中被修改，每轮重新读取
+            let overrides = match SettingsManager::new() {
+                Ok(manager) => manager.load_settings().await
+                    .map(|settings| settings.system.orchestration_config.project_overrides)
+                    .unwrap_or_default(),
+                Err(_) => HashMap::new(),
+            };
+            watchers.retain(|project_id, _| projects.iter().any(|p| p.project_id == *project_id));
+
+            for project in &projects {
+                let project_overrides = overrides.get(&project.project_id.to_string());
+                let result = match watchers.get_mut(&project.project_id) {
+                    Some(watcher) => {
+                        let overrides = project_overrides.cloned().unwrap_or(serde_json::Value::Null);
+                        check_watcher(watcher, &overrides).map_err(|e| e.to_string())
+                    }
+                    None => match project_config_loader(&db, project, project_overrides).await {
+                        Ok(loader) => OrchestrationConfigWatcher::new(Some(project.project_id), loader)
+                            .map(|watcher| {
+                                watchers.insert(project.project_id, watcher);
+                                None
+                            })
+                            .map_err(|e| e.to_string()),
+                        Err(e) => Err(e),
+                    },
+                };
+
+                match result {
+                    Ok(event) => {
+                        reported_errors.remove(&project.project_id);
+                        if let Some(event) = event {
+                            if let Err(e) = app.emit(ORCHESTRATION_CONFIG_CHANGED_EVENT, &event) {
+                                eprintln!("推送编排配置变更事件失败: {}", e);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        if reported_errors.get(&project.project_id) != Some(&e) {
+                            eprintln!("项目 {} 的编排配置无效: {}", project.name, e);
+                            reported_errors.insert(project.project_id, e);
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// 应用最新的界面覆盖并检查配置文件，两者都有变化时合并变更的配置键
+fn check_watcher(
+    watcher: &mut OrchestrationConfigWatcher,
+    overrides: &serde_json::Value,
+) -> codex_database::Result<Option<OrchestrationConfigChangedEvent>> {
+    let from_overrides = watcher.set_ui_overrides(overrides)?;
+    let from_file = watcher.poll()?;
+    Ok(match (from_overrides, from_file) {
+        (Some(mut event), Some(file_event)) => {
+            for key in file_event.changed_keys {
+                if !event.changed_keys.contains(&key) {
+                    event.changed_keys.push(key);
+                }
+            }
+            event.changed_keys.sort();
+            Some(event)
+        }
+        (event, None) | (None, event) => event,
+    })
+}
@@ -0,0 +1,124 @@
+// 依赖审计 - 通过 OSV API 查询已知漏洞
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use codex_database::dependency_audit::{Dependency, Vulnerability, VulnerabilityFuture, VulnerabilitySource};
+use codex_database::DatabaseError;
+use serde::Deserialize;
+
+const OSV_API_URL: &str = "https://api.osv.dev/v1";
+
+/// querybatch 单次请求的最大查询数
+const MAX_BATCH_SIZE: usize = 1000;
+
+/// OSV 漏洞数据源
+///
+/// querybatch 只返回漏洞ID，详情逐个获取并在进程内缓存
+pub struct OsvVulnerabilitySource {
+    client: reqwest::Client,
+    details: Mutex<HashMap<String, Vulnerability>>,
+}
+
+#[derive(Deserialize)]
+struct BatchResponse {
+    results: Vec<BatchResult>,
+}
+
+#[derive(Deserialize)]
+struct BatchResult {
+    #[serde(default)]
+    vulns: Vec<VulnId>,
+}
+
+#[derive(Deserialize)]
+struct VulnId {
+    id: String,
+}
+
+impl OsvVulnerabilitySource {
+    pub fn new() -> Self {
+        Self {
+            client: crate::network::http_client(crate::network::OSV_TARGET),
+            details: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn query_batch(&self, dependencies: &[Dependency]) -> Result<Vec<Vec<String>>, DatabaseError> {
+        let queries: Vec<_> = dependencies
+            .iter()
+            .map(|dependency| serde_json::json!({
+                "package": { "name": dependency.name, "ecosystem": dependency.ecosystem.osv_name() },
+                "version": dependency.version,
+            }))
+            .collect();
+
+        let response = self.client
+            .post(format!("{}/querybatch", OSV_API_URL))
+            .json(&serde_json::json!({ "queries": queries }))
+            .timeout(Duration::from_secs(60))
+            .send()
+            .await
+            .map_err(|e| DatabaseError::business_logic(format!("OSV 请求失败: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(DatabaseError::business_logic(format!("OSV 返回错误 {}: {}", status, body)));
+        }
+
+        let body: BatchResponse = response.json().await
+            .map_err(|e| DatabaseError::business_logic(format!("解析 OSV 响应失败: {}", e)))?;
+        Ok(body.results.into_iter().map(|result| result.vulns.into_iter().map(|v| v.id).collect()).collect())
+    }
+
+    async fn vulnerability(&self, id: &str) -> Result<Vulnerability, DatabaseError> {
+        if let Some(cached) = self.details.lock().ok().and_then(|details| details.get(id).cloned()) {
+            return Ok(cached);
+        }
+
+        let response = self.client
+            .get(format!("{}/vulns/{}", OSV_API_URL, id))
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| DatabaseError::business_logic(format!("获取漏洞 {} 详情失败: {}", id, e)))?;
+        let value: serde_json::Value = response.json().await
+            .map_err(|e| DatabaseError::business_logic(format!("解析漏洞 {} 详情失败: {}", id, e)))?;
+
+        let vulnerability = Vulnerability::from_osv(&value);
+        if let Ok(mut details) = self.details.lock() {
+            details.insert(id.to_string(), vulnerability.clone());
+        }
+        Ok(vulnerability)
+    }
+}
+
+impl Default for OsvVulnerabilitySource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VulnerabilitySource for OsvVulnerabilitySource {
+    fn query<'a>(&'a self, dependencies: &'a [Dependency]) -> VulnerabilityFuture<'a> {
+        Box::pin(async move {
+            let mut results = Vec::with_capacity(dependencies.len());
+            for chunk in dependencies.chunks(MAX_BATCH_SIZE) {
+                let ids = self.query_batch(chunk).await?;
+                if ids.len() != chunk.len() {
+                    return Err(DatabaseError::business_logic("OSV 返回的结果数量与查询不一致"));
+                }
+                for vulnerability_ids in ids {
+                    let mut vulnerabilities = Vec::with_capacity(vulnerability_ids.len());
+                    for id in vulnerability_ids {
+                        vulnerabilities.push(self.vulnerability(&id).await?);
+                    }
+                    results.push(vulnerabilities);
+                }
+            }
+            Ok(results)
+        })
+    }
+}
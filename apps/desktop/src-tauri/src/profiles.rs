@@ -0,0 +1,316 @@
+// 多配置档案 - 每个档案使用独立的数据目录（数据库、设置、对话记录），启动时选择
+//
+// 档案列表保存在 <系统数据目录>/sker/profiles.json。启动时依次按 --profile 参数、SKER_PROFILE
+// 环境变量和档案列表中的当前档案选择；SKER_DATA_HOME 仍可直接指定数据目录。切换档案需要重启应用，
+// 因为数据库连接和各后台服务在启动时按数据目录创建。
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+/// 默认档案名
+pub const DEFAULT_PROFILE: &str = "default";
+/// 档案列表文件名
+const REGISTRY_FILE: &str = "profiles.json";
+/// 档案内的数据库文件名
+const DATABASE_FILE: &str = "sker.db";
+/// 迁移到默认档案的旧版设置文件
+const LEGACY_FILES: [&str; 2] = ["settings.json", "conversations.json"];
+
+/// 一个配置档案
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub name: String,
+    // 自定义数据目录，为空时使用 <系统数据目录>/sker/profiles/<name>
+    #[serde(default)]
+    pub data_dir: Option<String>,
+    pub created_at: i64,
+}
+
+/// 档案列表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileRegistry {
+    pub active: String,
+    pub profiles: Vec<Profile>,
+}
+
+impl Default for ProfileRegistry {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE.to_string(),
+            profiles: vec![Profile {
+                name: DEFAULT_PROFILE.to_string(),
+                data_dir: None,
+                created_at: chrono::Utc::now().timestamp_millis(),
+            }],
+        }
+    }
+}
+
+/// 本次运行使用的档案
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveProfile {
+    pub name: String,
+    pub data_dir: PathBuf,
+}
+
+static ACTIVE: OnceLock<ActiveProfile> = OnceLock::new();
+
+/// 档案列表和默认档案所在的目录
+fn sker_root() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("sker")
+}
+
+fn registry_path() -> PathBuf {
+    sker_root().join(REGISTRY_FILE)
+}
+
+/// 旧版本的数据库目录 ~/.sker
+fn legacy_data_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".sker"))
+}
+
+/// 校验档案名：1-32 个字母、数字、下划线或连字符
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 32
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("无效的档案名 {}：只能包含字母、数字、下划线和连字符，最长 32 个字符", name))
+    }
+}
+
+impl Profile {
+    /// 档案的数据目录
+    pub fn data_dir(&self) -> PathBuf {
+        match self.data_dir.as_deref().filter(|dir| !dir.trim().is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => sker_root().join("profiles").join(&self.name),
+        }
+    }
+}
+
+/// 读取档案列表，不存在时返回只有默认档案的列表
+pub fn load_registry() -> Result<ProfileRegistry, String> {
+    let path = registry_path();
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("解析档案列表失败: {}", e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ProfileRegistry::default()),
+        Err(e) => Err(format!("读取档案列表失败: {}", e)),
+    }
+}
+
+fn save_registry(registry: &ProfileRegistry) -> Result<(), String> {
+    let path = registry_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("创建数据目录失败: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(registry)
+        .map_err(|e| format!("序列化档案列表失败: {}", e))?;
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("写入档案列表失败: {}", e))
+}
+
+/// 启动参数中的 --profile <name> 或 --profile=<name>
+fn profile_from_args() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            return args.next();
+        }
+        if let Some(name) = arg.strip_prefix("--profile=") {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+/// 递归复制目录，用于无法直接重命名（跨文件系统）时迁移
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+/// SKER_DATA_HOME 指定的数据目录，未设置或为空时返回 None
+fn data_home_override() -> Option<PathBuf> {
+    std::env::var("SKER_DATA_HOME").ok().filter(|dir| !dir.is_empty()).map(PathBuf::from)
+}
+
+/// 把旧版的 ~/.sker 和 <系统数据目录>/sker 下的设置文件迁移到默认档案
+///
+/// 默认档案目录已存在，或通过 SKER_DATA_HOME 指定了数据目录时跳过，避免移走其他实例正在使用的旧数据
+fn migrate_legacy_data(default_dir: &Path, data_home: Option<&Path>) -> Result<(), String> {
+    if data_home.is_some() || default_dir.exists() {
+        return Ok(());
+    }
+
+    if let Some(legacy) = legacy_data_dir().filter(|dir| dir.is_dir()) {
+        if let Some(parent) = default_dir.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("创建档案目录失败: {}", e))?;
+        }
+        if std::fs::rename(&legacy, default_dir).is_err() {
+            copy_dir(&legacy, default_dir)
+                .map_err(|e| format!("迁移 {} 失败: {}", legacy.display(), e))?;
+            println!("已复制 {} 到默认档案，旧目录保留", legacy.display());
+        } else {
+            println!("已将 {} 迁移到默认档案", legacy.display());
+        }
+    }
+
+    std::fs::create_dir_all(default_dir)
+        .map_err(|e| format!("创建档案目录失败: {}", e))?;
+    for file in LEGACY_FILES {
+        let legacy = sker_root().join(file);
+        if legacy.is_file() {
+            std::fs::rename(&legacy, default_dir.join(file))
+                .map_err(|e| format!("迁移 {} 失败: {}", legacy.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 选择本次运行使用的档案，首次运行时迁移旧版数据
+fn resolve_active() -> Result<ActiveProfile, String> {
+    let mut registry = load_registry()?;
+    if !registry.profiles.iter().any(|profile| profile.name == DEFAULT_PROFILE) {
+        registry.profiles.insert(0, ProfileRegistry::default().profiles.remove(0));
+    }
+    let default_dir = registry.profiles.iter()
+        .find(|profile| profile.name == DEFAULT_PROFILE)
+        .map(Profile::data_dir)
+        .unwrap_or_default();
+    let data_home = data_home_override();
+    migrate_legacy_data(&default_dir, data_home.as_deref())?;
+    if !registry_path().exists() {
+        save_registry(&registry)?;
+    }
+
+    let name = profile_from_args()
+        .or_else(|| std::env::var("SKER_PROFILE").ok().filter(|name| !name.is_empty()))
+        .unwrap_or_else(|| registry.active.clone());
+    let profile = registry.profiles.iter()
+        .find(|profile| profile.name == name)
+        .ok_or_else(|| format!("档案 {} 不存在", name))?;
+
+    let data_dir = data_home.unwrap_or_else(|| profile.data_dir());
+    std::fs::create_dir_all(&data_dir)
+        .map_err(|e| format!("创建档案目录失败: {}", e))?;
+    Ok(ActiveProfile { name: profile.name.clone(), data_dir })
+}
+
+/// 本次运行使用的档案；首次调用时选择，选择失败时退回默认档案
+pub fn active() -> &'static ActiveProfile {
+    ACTIVE.get_or_init(|| {
+        resolve_active().unwrap_or_else(|e| {
+            eprintln!("选择档案失败，使用默认档案: {}", e);
+            ActiveProfile {
+                name: DEFAULT_PROFILE.to_string(),
+                data_dir: sker_root().join("profiles").join(DEFAULT_PROFILE),
+            }
+        })
+    })
+}
+
+/// 当前档案的数据目录
+pub fn data_home() -> PathBuf {
+    active().data_dir.clone()
+}
+
+/// 当前档案的数据库文件
+pub fn database_path() -> PathBuf {
+    data_home().join(DATABASE_FILE)
+}
+
+/// 当前档案的应用目录（执行工件、工作树、凭据）
+///
+/// 默认档案继续使用原来的 `base`：git 工作树和工件记录保存的是绝对路径，不能随意移动
+pub fn app_home(base: PathBuf) -> PathBuf {
+    let active = active();
+    if active.name == DEFAULT_PROFILE {
+        base
+    } else {
+        active.data_dir.join("app")
+    }
+}
+
+/// 创建档案
+pub fn create_profile(name: &str, data_dir: Option<String>) -> Result<Profile, String> {
+    validate_name(name)?;
+    let mut registry = load_registry()?;
+    if registry.profiles.iter().any(|profile| profile.name == name) {
+        return Err(format!("档案 {} 已存在", name));
+    }
+    let profile = Profile {
+        name: name.to_string(),
+        data_dir: data_dir.filter(|dir| !dir.trim().is_empty()),
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    std::fs::create_dir_all(profile.data_dir())
+        .map_err(|e| format!("创建档案目录失败: {}", e))?;
+    registry.profiles.push(profile.clone());
+    save_registry(&registry)?;
+    Ok(profile)
+}
+
+/// 设置下次启动使用的档案
+pub fn set_active(name: &str) -> Result<(), String> {
+    let mut registry = load_registry()?;
+    if !registry.profiles.iter().any(|profile| profile.name == name) {
+        return Err(format!("档案 {} 不存在", name));
+    }
+    registry.active = name.to_string();
+    save_registry(&registry)
+}
+
+/// 从档案列表中移除档案，数据目录保留在磁盘上
+pub fn remove_profile(name: &str) -> Result<Profile, String> {
+    if name == DEFAULT_PROFILE {
+        return Err("不能删除默认档案".to_string());
+    }
+    if name == active().name {
+        return Err("不能删除正在使用的档案".to_string());
+    }
+    let mut registry = load_registry()?;
+    let index = registry.profiles.iter()
+        .position(|profile| profile.name == name)
+        .ok_or_else(|| format!("档案 {} 不存在", name))?;
+    let profile = registry.profiles.remove(index);
+    if registry.active == name {
+        registry.active = DEFAULT_PROFILE.to_string();
+    }
+    save_registry(&registry)?;
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_home_override_skips_legacy_migration() {
+        let default_dir = std::env::temp_dir().join(format!("sker-profile-{}", uuid::Uuid::new_v4()));
+        let data_home = std::env::temp_dir().join(format!("sker-data-home-{}", uuid::Uuid::new_v4()));
+
+        migrate_legacy_data(&default_dir, Some(&data_home)).unwrap();
+        assert!(!default_dir.exists());
+    }
+}
@@ -0,0 +1,31 @@
+// 自动化 REST 接口 - 根据设置启动使用 API 密钥认证的 HTTP 端点
+use std::sync::Arc;
+
+use codex_database::public_api::{self, PublicApi};
+use tokio::net::TcpListener;
+
+use crate::commands::DatabaseHandle;
+use crate::settings::PublicApiSettings;
+
+/// 启动自动化 REST 接口
+pub fn start(db: &DatabaseHandle, settings: &PublicApiSettings) {
+    if !settings.enabled {
+        return;
+    }
+
+    let api = Arc::new(PublicApi::new((**db).clone()));
+    let bind_address = settings.bind_address.clone();
+    tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::bind(&bind_address).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("自动化接口监听 {} 失败: {}", bind_address, e);
+                return;
+            }
+        };
+        println!("自动化接口已启动: http://{}/api/v1", bind_address);
+        if let Err(e) = public_api::serve(listener, api).await {
+            eprintln!("自动化接口异常退出: {}", e);
+        }
+    });
+}
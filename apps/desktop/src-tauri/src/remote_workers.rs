@@ -0,0 +1,54 @@
+// 远程工作进程 - 根据设置启动工作进程协议服务并回收心跳超时的工作进程
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_database::worker_coordinator::{self, WorkerCoordinator};
+use tokio::net::TcpListener;
+
+use crate::commands::DatabaseHandle;
+use crate::settings::RemoteWorkerSettings;
+
+/// 心跳超时的最短时长
+const MIN_HEARTBEAT_TIMEOUT_SECS: u64 = 10;
+
+/// 启动工作进程协议服务
+pub fn start(db: &DatabaseHandle, settings: &RemoteWorkerSettings) {
+    if !settings.enabled {
+        return;
+    }
+
+    // 任务租约与心跳超时一致，工作进程失联后租约随之过期
+    let timeout = Duration::from_secs(settings.heartbeat_timeout_secs.max(MIN_HEARTBEAT_TIMEOUT_SECS));
+    let coordinator = Arc::new(
+        WorkerCoordinator::new((**db).clone())
+            .with_heartbeat_interval(Duration::from_secs(settings.heartbeat_interval_secs.max(1)))
+            .with_lease_ttl(timeout),
+    );
+    let bind_address = settings.bind_address.clone();
+    let server = coordinator.clone();
+    tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::bind(&bind_address).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("远程工作进程服务监听 {} 失败: {}", bind_address, e);
+                return;
+            }
+        };
+        println!("远程工作进程服务已启动: {}", bind_address);
+        if let Err(e) = worker_coordinator::serve(listener, server).await {
+            eprintln!("远程工作进程服务异常退出: {}", e);
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(coordinator.heartbeat_interval());
+        loop {
+            interval.tick().await;
+            match coordinator.reap_stale_workers(timeout).await {
+                Ok(reaped) if !reaped.is_empty() => println!("已回收 {} 个心跳超时的远程工作进程", reaped.len()),
+                Ok(_) => {}
+                Err(e) => eprintln!("回收远程工作进程失败: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,73 @@
+// 定时报告 - 按设置定期生成到期报告计划的项目报告，并投递到计划配置的通知渠道
+use std::time::Duration;
+
+use codex_database::reporting::{self, GeneratedReport, ReportChannel};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::{ArtifactStoreHandle, ProjectReportInfo};
+use crate::settings::ReportSchedulerSettings;
+
+/// 前端监听的报告生成事件名
+pub const PROJECT_REPORT_EVENT: &str = "project_report_generated";
+
+/// 检查的最短间隔
+const MIN_INTERVAL_SECS: u64 = 30;
+
+/// Webhook 投递的超时时间
+const WEBHOOK_TIMEOUT_SECS: u64 = 30;
+
+/// 启动定时报告
+pub fn start(app: &AppHandle, store: &ArtifactStoreHandle, settings: &ReportSchedulerSettings) {
+    if !settings.enabled {
+        return;
+    }
+
+    let app = app.clone();
+    let store = store.clone();
+    let period = Duration::from_secs(settings.interval_secs.max(MIN_INTERVAL_SECS));
+    tauri::async_runtime::spawn(async move {
+        let client = crate::network::http_client(crate::network::WEBHOOK_TARGET);
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            match reporting::run_due_schedules(&store, store.clock().now()).await {
+                Ok(reports) => {
+                    for report in reports {
+                        deliver(&app, &client, report).await;
+                    }
+                }
+                Err(e) => eprintln!("生成定时报告失败: {}", e),
+            }
+        }
+    });
+}
+
+/// 把报告投递到各通知渠道，单个渠道失败不影响其他渠道
+async fn deliver(app: &AppHandle, client: &reqwest::Client, generated: GeneratedReport) {
+    let info = ProjectReportInfo::from(&generated.report);
+    for channel in &generated.channels {
+        match channel {
+            ReportChannel::Desktop => {
+                if let Err(e) = app.emit(PROJECT_REPORT_EVENT, &info) {
+                    eprintln!("推送报告事件失败: {}", e);
+                }
+            }
+            ReportChannel::Webhook { url } => {
+                let payload = serde_json::json!({
+                    "report": &info,
+                    "content": &generated.content,
+                });
+                let result = client
+                    .post(url)
+                    .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+                    .json(&payload)
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status());
+                if let Err(e) = result {
+                    eprintln!("投递报告到Webhook失败 {}: {}", url, e);
+                }
+            }
+        }
+    }
+}
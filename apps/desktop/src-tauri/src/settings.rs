@@ -101,6 +101,32 @@ pub struct ProxyAuth {
     pub password: String,
 }
 
+// 代理类型
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyType {
+    #[default]
+    Http,
+    Socks5,
+}
+
+// 按服务覆盖的代理设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyOverride {
+    // 服务标识：LLM 提供商（openai、anthropic、custom）或 webhooks、scm、telemetry、osv、updater、identity
+    pub target: String,
+    // 为 true 时该服务直连，忽略代理地址
+    #[serde(default)]
+    pub direct: bool,
+    #[serde(default)]
+    pub proxy_type: ProxyType,
+    pub host: Option<String>,
+    pub port: Option<i32>,
+    #[serde(default)]
+    pub auth: Option<ProxyAuth>,
+}
+
 // MCP 服务器配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -113,6 +139,338 @@ pub struct McpServerConfig {
     pub enabled: bool,
 }
 
+// 遥测设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+    // Prometheus 抓取端点（仅监听本机）
+    pub prometheus_enabled: bool,
+    pub prometheus_port: u16,
+    // OTLP/HTTP 推送，endpoint 形如 http://localhost:4318/v1/metrics
+    pub otlp_enabled: bool,
+    pub otlp_endpoint: Option<String>,
+    pub export_interval_secs: u64,
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            prometheus_enabled: true,
+            prometheus_port: 9464,
+            otlp_enabled: false,
+            otlp_endpoint: None,
+            export_interval_secs: 15,
+        }
+    }
+}
+
+// 执行工件保留设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactSettings {
+    // 工件最长保留天数，为空表示不按时间清理
+    pub retention_days: Option<u32>,
+    // 工件存储总大小上限（MB），为空表示不限制
+    pub max_total_mb: Option<u64>,
+}
+
+impl Default for ArtifactSettings {
+    fn default() -> Self {
+        Self {
+            retention_days: Some(30),
+            max_total_mb: Some(2048),
+        }
+    }
+}
+
+impl ArtifactSettings {
+    /// 转换为工件存储的保留策略
+    pub fn retention_policy(&self) -> codex_database::artifact_store::RetentionPolicy {
+        codex_database::artifact_store::RetentionPolicy {
+            max_age_days: self.retention_days,
+            max_total_bytes: self.max_total_mb.map(|mb| mb * 1024 * 1024),
+        }
+    }
+}
+
+// 语义检索使用的嵌入模型来源
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProviderKind {
+    // 本地特征哈希，无需网络
+    Local,
+    // 通过 API 配置调用 OpenAI 兼容的 /embeddings 接口
+    Api,
+}
+
+// 语义检索设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSettings {
+    pub enabled: bool,
+    pub provider: EmbeddingProviderKind,
+    // provider 为 api 时使用的嵌入模型
+    pub embedding_model: String,
+    // 后台索引间隔（秒）
+    pub index_interval_secs: u64,
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            provider: EmbeddingProviderKind::Local,
+            embedding_model: "text-embedding-3-small".to_string(),
+            index_interval_secs: 600,
+        }
+    }
+}
+
+// CI 集成设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CiSettings {
+    // GitHub webhook 的签名密钥，为空时不校验签名
+    pub github_webhook_secret: Option<String>,
+    // GitLab webhook 的 X-Gitlab-Token，为空时不校验
+    pub gitlab_webhook_token: Option<String>,
+}
+
+// 远程工作进程设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteWorkerSettings {
+    pub enabled: bool,
+    // 工作进程协议监听地址
+    pub bind_address: String,
+    // 下发给工作进程的心跳间隔（秒）
+    pub heartbeat_interval_secs: u64,
+    // 超过该时长没有心跳的工作进程被标记为离线（秒）
+    pub heartbeat_timeout_secs: u64,
+}
+
+impl Default for RemoteWorkerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1:7420".to_string(),
+            heartbeat_interval_secs: 15,
+            heartbeat_timeout_secs: 60,
+        }
+    }
+}
+
+// Webhook 接入设置，GitHub / GitLab 的签名密钥沿用 CI 集成设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookIngestionSettings {
+    pub enabled: bool,
+    // 接入端点监听地址
+    pub bind_address: String,
+}
+
+impl Default for WebhookIngestionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1:7421".to_string(),
+        }
+    }
+}
+
+// 自动化 REST 接口设置，请求使用 API 密钥认证
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicApiSettings {
+    pub enabled: bool,
+    // 接口监听地址
+    pub bind_address: String,
+}
+
+impl Default for PublicApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1:7422".to_string(),
+        }
+    }
+}
+
+// 身份提供方类型
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OidcProviderKind {
+    Google,
+    // GitHub 只支持 OAuth，用户信息来自 GitHub API
+    Github,
+    // 企业身份提供方，端点由 issuer 的发现文档提供
+    #[default]
+    Oidc,
+}
+
+// OIDC / OAuth 身份提供方
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcProviderSettings {
+    // 提供方标识，关联的外部身份按此区分
+    pub id: String,
+    // 登录按钮上显示的名称
+    pub name: String,
+    #[serde(default)]
+    pub kind: OidcProviderKind,
+    // kind 为 oidc 时必填
+    #[serde(default)]
+    pub issuer: Option<String>,
+    pub client_id: String,
+    // 桌面应用通常是公开客户端，只依赖 PKCE；部分提供方（如 Google）仍要求密钥
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    // 额外申请的权限范围，为空时使用提供方默认值
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+// 登录设置，密码登录始终可用
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthSettings {
+    #[serde(default)]
+    pub oidc_providers: Vec<OidcProviderSettings>,
+}
+
+// SLA 监控设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlaMonitorSettings {
+    pub enabled: bool,
+    // 评估间隔（秒）
+    pub interval_secs: u64,
+}
+
+impl Default for SlaMonitorSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 300,
+        }
+    }
+}
+
+// 定时报告设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportSchedulerSettings {
+    pub enabled: bool,
+    // 检查到期报告计划的间隔（秒）
+    pub interval_secs: u64,
+}
+
+impl Default for ReportSchedulerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 60,
+        }
+    }
+}
+
+// 定期维护设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceSettings {
+    pub enabled: bool,
+    // 检查到期维护任务的间隔（秒）
+    pub interval_secs: u64,
+}
+
+impl Default for MaintenanceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 60,
+        }
+    }
+}
+
+// LLM响应缓存设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmCacheSettings {
+    pub enabled: bool,
+    // 条目有效期（小时）
+    pub ttl_hours: u32,
+    // 最多保留的条目数
+    pub max_entries: usize,
+    // 缓存总大小上限（MB）
+    pub max_size_mb: u64,
+}
+
+impl Default for LlmCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_hours: 168,
+            max_entries: 1000,
+            max_size_mb: 64,
+        }
+    }
+}
+
+// 更新通道
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+// 自动更新设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSettings {
+    pub channel: UpdateChannel,
+    // 启动时检查更新
+    pub check_on_startup: bool,
+    // 自定义更新清单地址，设置后忽略通道
+    pub custom_endpoint: Option<String>,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            channel: UpdateChannel::Stable,
+            check_on_startup: true,
+            custom_endpoint: None,
+        }
+    }
+}
+
+// 编排配置（sker.toml）设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrchestrationConfigSettings {
+    // 是否监视项目配置文件并推送变更
+    pub watch_enabled: bool,
+    // 检查配置文件的间隔（秒）
+    pub interval_secs: u64,
+    // 按项目ID保存的界面覆盖，结构与 sker.toml 相同
+    #[serde(default)]
+    pub project_overrides: std::collections::HashMap<String, serde_json::Value>,
+}
+
+impl Default for OrchestrationConfigSettings {
+    fn default() -> Self {
+        Self {
+            watch_enabled: true,
+            interval_secs: 10,
+            project_overrides: std::collections::HashMap::new(),
+        }
+    }
+}
+
 // API 密钥集合（兼容性）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKeys {
@@ -143,6 +501,17 @@ pub struct SystemSettings {
     pub proxy_port: Option<i32>,
     #[serde(default)]
     pub proxy_auth: Option<ProxyAuth>,
+    #[serde(default)]
+    pub proxy_type: ProxyType,
+    // 不经过代理的主机（格式同 NO_PROXY，如 localhost、.corp.example.com、10.0.0.0/8）
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    // 按服务覆盖全局代理
+    #[serde(default)]
+    pub proxy_overrides: Vec<ProxyOverride>,
+    // 自定义 CA 证书（PEM 文件路径，可包含多个证书），在系统证书之外额外信任
+    #[serde(default)]
+    pub ca_certificate_path: Option<String>,
     
     // 兼容性：保留原有的apiKeys字段
     #[serde(default)]
@@ -152,6 +521,62 @@ pub struct SystemSettings {
     #[serde(default)]
     pub mcp_servers: Vec<McpServerConfig>,
     
+    // 遥测指标导出
+    #[serde(default)]
+    pub telemetry: TelemetrySettings,
+    
+    // 执行工件保留
+    #[serde(default)]
+    pub artifacts: ArtifactSettings,
+    
+    // 语义检索
+    #[serde(default)]
+    pub search: SearchSettings,
+    
+    // CI 集成
+    #[serde(default)]
+    pub ci: CiSettings,
+    
+    // 远程工作进程
+    #[serde(default)]
+    pub remote_workers: RemoteWorkerSettings,
+    
+    // Webhook 接入
+    #[serde(default)]
+    pub webhook_ingestion: WebhookIngestionSettings,
+    
+    // 自动化 REST 接口
+    #[serde(default)]
+    pub public_api: PublicApiSettings,
+    
+    // 单点登录
+    #[serde(default)]
+    pub auth: AuthSettings,
+    
+    // SLA 监控
+    #[serde(default)]
+    pub sla_monitor: SlaMonitorSettings,
+    
+    // 定时报告
+    #[serde(default)]
+    pub report_scheduler: ReportSchedulerSettings,
+    
+    // 定期维护
+    #[serde(default)]
+    pub maintenance: MaintenanceSettings,
+    
+    // LLM响应缓存
+    #[serde(default)]
+    pub llm_cache: LlmCacheSettings,
+    
+    // 自动更新
+    #[serde(default)]
+    pub updates: UpdateSettings,
+    
+    // 编排配置
+    #[serde(default)]
+    pub orchestration_config: OrchestrationConfigSettings,
+    
     // 系统行为设置
     pub auto_start: bool,
     pub minimize_to_tray: bool,
@@ -220,11 +645,29 @@ impl Default for AppSettings {
                 proxy_host: None,
                 proxy_port: None,
                 proxy_auth: None,
+                proxy_type: ProxyType::Http,
+                no_proxy: Vec::new(),
+                proxy_overrides: Vec::new(),
+                ca_certificate_path: None,
                 api_keys: ApiKeys {
                     openai: None,
                     anthropic: None,
                 },
                 mcp_servers: Vec::new(),
+                telemetry: TelemetrySettings::default(),
+                artifacts: ArtifactSettings::default(),
+                search: SearchSettings::default(),
+                ci: CiSettings::default(),
+                remote_workers: RemoteWorkerSettings::default(),
+                webhook_ingestion: WebhookIngestionSettings::default(),
+                public_api: PublicApiSettings::default(),
+                auth: AuthSettings::default(),
+                sla_monitor: SlaMonitorSettings::default(),
+                report_scheduler: ReportSchedulerSettings::default(),
+                maintenance: MaintenanceSettings::default(),
+                llm_cache: LlmCacheSettings::default(),
+                updates: UpdateSettings::default(),
+                orchestration_config: OrchestrationConfigSettings::default(),
                 auto_start: false,
                 minimize_to_tray: true,
             },
@@ -242,11 +685,8 @@ pub struct SettingsManager {
 impl SettingsManager {
     /// 创建新的设置管理器
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let app_data_dir = dirs::data_dir()
-            .ok_or("无法获取应用数据目录")?
-            .join("sker");
-        
-        let settings_path = app_data_dir.join("settings.json");
+        // 每个档案使用独立的设置文件
+        let settings_path = crate::profiles::data_home().join("settings.json");
         
         Ok(Self { settings_path })
     }
@@ -444,32 +884,41 @@ pub async fn get_app_settings() -> Result<AppSettings, String> {
 
 /// 保存应用设置
 #[tauri::command]
-pub async fn save_app_settings(settings: AppSettings) -> Result<(), String> {
+pub async fn save_app_settings(settings: AppSettings, app: tauri::AppHandle) -> Result<(), String> {
     let settings_manager = SettingsManager::new()
         .map_err(|e| format!("创建设置管理器失败: {}", e))?;
     
     settings_manager.save_settings(&settings).await
-        .map_err(|e| format!("保存设置失败: {}", e))
+        .map_err(|e| format!("保存设置失败: {}", e))?;
+    crate::network::apply_settings(&settings.system);
+    crate::telemetry::apply_settings(&app, &settings.system.telemetry).await;
+    Ok(())
 }
 
 /// 更新应用设置的特定部分
 #[tauri::command]
-pub async fn update_app_settings(request: UpdateSettingsRequest) -> Result<AppSettings, String> {
+pub async fn update_app_settings(request: UpdateSettingsRequest, app: tauri::AppHandle) -> Result<AppSettings, String> {
     let settings_manager = SettingsManager::new()
         .map_err(|e| format!("创建设置管理器失败: {}", e))?;
     
-    settings_manager.update_settings(&request.section, request.settings).await
-        .map_err(|e| format!("更新设置失败: {}", e))
+    let settings = settings_manager.update_settings(&request.section, request.settings).await
+        .map_err(|e| format!("更新设置失败: {}", e))?;
+    crate::network::apply_settings(&settings.system);
+    crate::telemetry::apply_settings(&app, &settings.system.telemetry).await;
+    Ok(settings)
 }
 
 /// 重置应用设置为默认值
 #[tauri::command]
-pub async fn reset_app_settings() -> Result<AppSettings, String> {
+pub async fn reset_app_settings(app: tauri::AppHandle) -> Result<AppSettings, String> {
     let settings_manager = SettingsManager::new()
         .map_err(|e| format!("创建设置管理器失败: {}", e))?;
     
-    settings_manager.reset_settings().await
-        .map_err(|e| format!("重置设置失败: {}", e))
+    let settings = settings_manager.reset_settings().await
+        .map_err(|e| format!("重置设置失败: {}", e))?;
+    crate::network::apply_settings(&settings.system);
+    crate::telemetry::apply_settings(&app, &settings.system.telemetry).await;
+    Ok(settings)
 }
 
 /// 导出应用设置
@@ -484,12 +933,15 @@ pub async fn export_app_settings() -> Result<String, String> {
 
 /// 导入应用设置
 #[tauri::command]
-pub async fn import_app_settings(data: String) -> Result<AppSettings, String> {
+pub async fn import_app_settings(data: String, app: tauri::AppHandle) -> Result<AppSettings, String> {
     let settings_manager = SettingsManager::new()
         .map_err(|e| format!("创建设置管理器失败: {}", e))?;
     
-    settings_manager.import_settings(&data).await
-        .map_err(|e| format!("导入设置失败: {}", e))
+    let settings = settings_manager.import_settings(&data).await
+        .map_err(|e| format!("导入设置失败: {}", e))?;
+    crate::network::apply_settings(&settings.system);
+    crate::telemetry::apply_settings(&app, &settings.system.telemetry).await;
+    Ok(settings)
 }
 
 /// 获取 MCP 服务器列表
@@ -595,8 +1047,8 @@ pub async fn toggle_mcp_server(name: String, enabled: bool) -> Result<Vec<McpSer
 /// 测试API连接
 #[tauri::command]
 pub async fn test_api_connection(config: ApiConfig) -> Result<String, String> {
-    // 创建HTTP客户端
-    let client = reqwest::Client::new();
+    // 按网络设置创建HTTP客户端
+    let client = crate::network::try_http_client(crate::network::llm_target(&config.provider))?;
     
     // 根据提供商类型构建测试请求
     let test_url = match config.provider {
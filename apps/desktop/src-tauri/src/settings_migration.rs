@@ -2,11 +2,7 @@ use tokio::fs;
 
 /// 清除旧的设置文件以避免兼容性问题
 pub async fn clear_incompatible_settings() -> Result<(), Box<dyn std::error::Error>> {
-    let app_data_dir = dirs::data_dir()
-        .ok_or("无法获取应用数据目录")?
-        .join("sker");
-    
-    let settings_path = app_data_dir.join("settings.json");
+    let settings_path = crate::profiles::data_home().join("settings.json");
     
     if settings_path.exists() {
         // 读取设置文件内容
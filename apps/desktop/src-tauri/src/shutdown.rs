@@ -0,0 +1,95 @@
+// 优雅关闭 - 退出前停止接受新工作、保存执行会话检查点并写入关闭标记
+use std::path::Path;
+use std::sync::Arc;
+
+use codex_database::shutdown::{self, ShutdownCoordinator, ShutdownHookFuture};
+use tauri::{AppHandle, Manager, RunEvent};
+
+use crate::commands::{DatabaseHandle, OperationRegistryHandle};
+
+// 关闭协调器
+pub type ShutdownCoordinatorHandle = Arc<ShutdownCoordinator>;
+
+/// 关闭标记文件名
+const SHUTDOWN_MARKER_FILE: &str = "shutdown.json";
+
+/// 创建关闭协调器，关闭信号触发时取消所有长时间操作
+pub fn install(app: &AppHandle, data_dir: &Path, operations: OperationRegistryHandle) -> ShutdownCoordinatorHandle {
+    let coordinator: ShutdownCoordinatorHandle = Arc::new(ShutdownCoordinator::new(data_dir.join(SHUTDOWN_MARKER_FILE)));
+    app.manage(coordinator.clone());
+
+    let signal = coordinator.token();
+    tauri::async_runtime::spawn(async move {
+        signal.cancelled().await;
+        let cancelled = operations.cancel_all();
+        if cancelled > 0 {
+            println!("应用关闭，已请求取消 {} 个长时间操作", cancelled);
+        }
+    });
+
+    coordinator.register_hook("停止遥测导出", || -> ShutdownHookFuture {
+        Box::pin(async {
+            crate::telemetry::stop();
+            Ok(())
+        })
+    });
+    coordinator
+}
+
+/// 读取上次的关闭标记，并结束上次运行遗留的执行会话
+pub async fn recover(coordinator: &ShutdownCoordinator, db: &DatabaseHandle) {
+    let previous = match coordinator.begin_run().await {
+        Ok(previous) => previous,
+        Err(e) => {
+            eprintln!("读取关闭标记失败: {}", e);
+            return;
+        }
+    };
+    if previous.is_unclean() {
+        eprintln!("检测到上次应用异常退出");
+    }
+
+    match shutdown::recover_interrupted_sessions(db, &previous).await {
+        Ok(sessions) if sessions.is_empty() => {}
+        Ok(sessions) => println!("已结束 {} 个上次运行遗留的执行会话", sessions.len()),
+        Err(e) => eprintln!("结束遗留执行会话失败: {}", e),
+    }
+}
+
+/// 处理退出请求：首次请求时阻止退出，关闭流程完成后再退出
+pub fn handle_run_event(app: &AppHandle, event: &RunEvent) {
+    let RunEvent::ExitRequested { api, code, .. } = event else {
+        return;
+    };
+    let Some(coordinator) = app.try_state::<ShutdownCoordinatorHandle>() else {
+        return;
+    };
+    if coordinator.is_finished() {
+        return;
+    }
+
+    api.prevent_exit();
+    if !coordinator.is_accepting() {
+        // 关闭流程已在进行中
+        return;
+    }
+
+    let coordinator = coordinator.inner().clone();
+    let app = app.clone();
+    let code = code.unwrap_or(0);
+    tauri::async_runtime::spawn(async move {
+        println!("正在关闭应用...");
+        let db = app.try_state::<DatabaseHandle>().map(|db| db.inner().clone());
+        let report = coordinator.shutdown(db.as_deref()).await;
+        if !report.interrupted_sessions.is_empty() {
+            println!("已为 {} 个执行中的会话保存检查点", report.interrupted_sessions.len());
+        }
+        if report.abandoned_work > 0 {
+            eprintln!("{} 项工作未在宽限期内结束", report.abandoned_work);
+        }
+        for step in &report.failed_steps {
+            eprintln!("关闭步骤失败: {}", step);
+        }
+        app.exit(code);
+    });
+}
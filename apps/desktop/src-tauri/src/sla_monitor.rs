@@ -0,0 +1,72 @@
+// SLA 监控 - 按设置定期评估各项目的 SLA 和代码审查时限，并把新的违约、提醒和改派通知前端
+use std::time::Duration;
+
+use codex_database::{review_sla, sla};
+use codex_multi_agent::{Clock, SystemClock};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::DatabaseHandle;
+use crate::settings::SlaMonitorSettings;
+
+/// 前端监听的 SLA 违约事件名
+pub const SLA_BREACHED_EVENT: &str = "sla_breached";
+
+/// 审查即将到期的提醒事件名
+pub const REVIEW_REMINDER_EVENT: &str = "review_reminder";
+
+/// 审查超时改派事件名
+pub const REVIEW_REASSIGNED_EVENT: &str = "review_reassigned";
+
+/// 审查超时且无法改派的事件名
+pub const REVIEW_DEADLINE_MISSED_EVENT: &str = "review_deadline_missed";
+
+/// 评估的最短间隔
+const MIN_INTERVAL_SECS: u64 = 30;
+
+/// 启动 SLA 监控
+pub fn start(app: &AppHandle, db: &DatabaseHandle, settings: &SlaMonitorSettings) {
+    if !settings.enabled {
+        return;
+    }
+
+    let app = app.clone();
+    let db = (**db).clone();
+    let period = Duration::from_secs(settings.interval_secs.max(MIN_INTERVAL_SECS));
+    tauri::async_runtime::spawn(async move {
+        let clock = SystemClock;
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            match sla::evaluate_all(&db, &clock).await {
+                Ok(events) => {
+                    for event in events {
+                        if let Err(e) = app.emit(SLA_BREACHED_EVENT, &event) {
+                            eprintln!("推送SLA违约事件失败: {}", e);
+                        }
+                    }
+                }
+                Err(e) => eprintln!("评估SLA失败: {}", e),
+            }
+            match review_sla::evaluate_all(&db, clock.now()).await {
+                Ok(report) => emit_review_sla_report(&app, &report),
+                Err(e) => eprintln!("评估代码审查时限失败: {}", e),
+            }
+        }
+    });
+}
+
+/// 把审查时限的评估结果逐条推送给前端
+pub fn emit_review_sla_report(app: &AppHandle, report: &review_sla::ReviewSlaReport) {
+    let notices = [
+        (REVIEW_REMINDER_EVENT, &report.reminders),
+        (REVIEW_REASSIGNED_EVENT, &report.reassignments),
+        (REVIEW_DEADLINE_MISSED_EVENT, &report.escalations),
+    ];
+    for (event, notices) in notices {
+        for notice in notices {
+            if let Err(e) = app.emit(event, notice) {
+                eprintln!("推送代码审查时限事件失败: {}", e);
+            }
+        }
+    }
+}
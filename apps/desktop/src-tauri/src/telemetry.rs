@@ -0,0 +1,95 @@
+// 遥测指标导出 - 根据设置启动 Prometheus 抓取端点和 OTLP 定时推送
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use codex_database::telemetry::{self, PrometheusServer};
+use tauri::{AppHandle, Manager};
+
+use crate::commands::DatabaseHandle;
+use crate::settings::TelemetrySettings;
+
+/// OTLP 资源中的服务名
+const SERVICE_NAME: &str = "sker-desktop";
+
+/// 当前运行的导出任务
+struct TelemetryRuntime {
+    prometheus: Option<PrometheusServer>,
+    exporter: tauri::async_runtime::JoinHandle<()>,
+}
+
+static RUNTIME: Mutex<Option<TelemetryRuntime>> = Mutex::new(None);
+
+/// 按设置重新启动遥测导出，关闭时停止所有导出并停止记录
+pub async fn apply_settings(app: &AppHandle, settings: &TelemetrySettings) {
+    stop();
+    telemetry::global().set_enabled(settings.enabled);
+    if !settings.enabled {
+        println!("遥测指标已关闭");
+        return;
+    }
+
+    let prometheus = if settings.prometheus_enabled {
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, settings.prometheus_port));
+        match telemetry::serve_prometheus(addr).await {
+            Ok(server) => {
+                println!("Prometheus 抓取端点: http://{}/metrics", server.local_addr());
+                Some(server)
+            }
+            Err(e) => {
+                eprintln!("启动 Prometheus 抓取端点失败: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let otlp_endpoint = settings.otlp_enabled
+        .then(|| settings.otlp_endpoint.clone())
+        .flatten()
+        .filter(|endpoint| !endpoint.trim().is_empty());
+    let interval = Duration::from_secs(settings.export_interval_secs.max(1));
+    let app = app.clone();
+
+    // 定期刷新队列深度，并在配置了 OTLP 时推送指标
+    let exporter = tauri::async_runtime::spawn(async move {
+        let client = crate::network::http_client(crate::network::TELEMETRY_TARGET);
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Some(db) = app.try_state::<DatabaseHandle>() {
+                if let Err(e) = telemetry::refresh_queue_depths(&db).await {
+                    eprintln!("刷新队列深度失败: {}", e);
+                }
+            }
+            if let Some(endpoint) = &otlp_endpoint {
+                let payload = telemetry::global().otlp_payload(SERVICE_NAME);
+                match client.post(endpoint).json(&payload).send().await {
+                    Ok(response) if !response.status().is_success() => {
+                        eprintln!("OTLP 推送失败: HTTP {}", response.status());
+                    }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("OTLP 推送失败: {}", e),
+                }
+            }
+        }
+    });
+
+    println!("遥测指标已开启");
+    *RUNTIME.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(TelemetryRuntime {
+        prometheus,
+        exporter,
+    });
+}
+
+/// 停止正在运行的导出任务
+pub(crate) fn stop() {
+    let runtime = RUNTIME.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+    if let Some(runtime) = runtime {
+        if let Some(server) = runtime.prometheus {
+            server.shutdown();
+        }
+        runtime.exporter.abort();
+    }
+}
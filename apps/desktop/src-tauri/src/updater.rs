@@ -0,0 +1,232 @@
+// 自动更新 - 按通道检查更新、延期安装，并记录安装历史以便回滚到上一个版本
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_updater::{Update, Updater, UpdaterExt};
+
+use crate::network::{self, ProxyChoice};
+use crate::settings::{SystemSettings, UpdateChannel, UpdateSettings};
+
+/// 发布页地址，更新清单作为发布附件上传
+const RELEASES_URL: &str = "https://github.com/imeepos/sker/releases";
+/// 安装历史文件名
+const HISTORY_FILE: &str = "updates.json";
+/// 检查到新版本时推送的事件
+pub const UPDATE_AVAILABLE_EVENT: &str = "update_available";
+
+/// 更新检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub available: bool,
+    pub current_version: String,
+    pub version: Option<String>,
+    pub channel: UpdateChannel,
+    pub release_notes: Option<String>,
+    pub published_at: Option<String>,
+}
+
+/// 安装历史，记录最近一次安装前的版本
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstallHistory {
+    pub previous_version: Option<String>,
+    pub installed_version: Option<String>,
+    pub installed_at: Option<i64>,
+}
+
+/// 已安排的延期安装
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledInstall {
+    pub version: String,
+    /// 安装时间（毫秒时间戳）
+    pub install_at: i64,
+}
+
+struct PendingInstall {
+    info: ScheduledInstall,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+static PENDING: Mutex<Option<PendingInstall>> = Mutex::new(None);
+
+/// 通道对应的更新清单地址
+pub fn channel_endpoint(settings: &UpdateSettings) -> String {
+    if let Some(endpoint) = settings.custom_endpoint.as_deref().filter(|endpoint| !endpoint.trim().is_empty()) {
+        return endpoint.trim().to_string();
+    }
+    match settings.channel {
+        UpdateChannel::Stable => format!("{}/latest/download/latest.json", RELEASES_URL),
+        // beta 通道使用滚动更新的 beta 标签
+        UpdateChannel::Beta => format!("{}/download/beta/latest.json", RELEASES_URL),
+    }
+}
+
+/// 指定版本的更新清单地址
+fn version_endpoint(version: &str) -> String {
+    format!("{}/download/v{}/latest.json", RELEASES_URL, version)
+}
+
+/// 创建更新器；指定 `exact_version` 时只接受该版本（允许降级，用于回滚）
+fn build_updater(
+    app: &AppHandle,
+    settings: &SystemSettings,
+    endpoint: &str,
+    exact_version: Option<String>,
+) -> Result<Updater, String> {
+    let endpoint = endpoint.parse()
+        .map_err(|e| format!("无效的更新地址 {}: {}", endpoint, e))?;
+    let mut builder = app.updater_builder()
+        .endpoints(vec![endpoint])
+        .map_err(|e| format!("设置更新地址失败: {}", e))?
+        .timeout(Duration::from_secs(30));
+    if let ProxyChoice::Proxy(proxy) = network::resolve_proxy(settings, network::UPDATER_TARGET) {
+        let url = proxy.url_with_auth().parse()
+            .map_err(|e| format!("无效的代理地址 {}: {}", proxy.url, e))?;
+        builder = builder.proxy(url);
+    }
+    if let Some(version) = exact_version {
+        builder = builder.version_comparator(move |_, release| release.version.to_string() == version);
+    }
+    builder.build().map_err(|e| format!("创建更新器失败: {}", e))
+}
+
+fn update_info(app: &AppHandle, channel: UpdateChannel, update: Option<&Update>) -> UpdateInfo {
+    UpdateInfo {
+        available: update.is_some(),
+        current_version: app.package_info().version.to_string(),
+        version: update.map(|update| update.version.clone()),
+        channel,
+        release_notes: update.and_then(|update| update.body.clone()),
+        published_at: update.and_then(|update| update.date).map(|date| date.to_string()),
+    }
+}
+
+/// 按设置的通道检查更新
+pub async fn check(app: &AppHandle, settings: &SystemSettings) -> Result<(UpdateInfo, Option<Update>), String> {
+    let updater = build_updater(app, settings, &channel_endpoint(&settings.updates), None)?;
+    let update = updater.check().await
+        .map_err(|e| format!("检查更新失败: {}", e))?;
+    Ok((update_info(app, settings.updates.channel, update.as_ref()), update))
+}
+
+fn history_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir()
+        .map_err(|e| format!("无法获取应用数据目录: {}", e))?
+        .join("sker");
+    Ok(dir.join(HISTORY_FILE))
+}
+
+/// 读取安装历史
+pub async fn load_history(app: &AppHandle) -> Result<InstallHistory, String> {
+    let path = history_path(app)?;
+    match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("解析安装历史失败: {}", e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(InstallHistory::default()),
+        Err(e) => Err(format!("读取安装历史失败: {}", e)),
+    }
+}
+
+async fn save_history(app: &AppHandle, history: &InstallHistory) -> Result<(), String> {
+    let path = history_path(app)?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await
+            .map_err(|e| format!("创建数据目录失败: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(history)
+        .map_err(|e| format!("序列化安装历史失败: {}", e))?;
+    tokio::fs::write(&path, contents).await
+        .map_err(|e| format!("写入安装历史失败: {}", e))
+}
+
+/// 下载并安装更新，记录安装前的版本后重启应用
+pub async fn install(app: &AppHandle, update: Update) -> Result<(), String> {
+    let history = InstallHistory {
+        previous_version: Some(update.current_version.clone()),
+        installed_version: Some(update.version.clone()),
+        installed_at: Some(Utc::now().timestamp_millis()),
+    };
+    println!("开始安装版本 {}（当前 {}）", update.version, update.current_version);
+    update.download_and_install(|_, _| {}, || println!("更新下载完成")).await
+        .map_err(|e| format!("安装更新失败: {}", e))?;
+    save_history(app, &history).await?;
+    app.restart();
+}
+
+/// 回滚到上一次安装前的版本
+pub async fn rollback(app: &AppHandle, settings: &SystemSettings) -> Result<(), String> {
+    let history = load_history(app).await?;
+    let previous = history.previous_version.ok_or("没有可回滚的版本")?;
+    let updater = build_updater(app, settings, &version_endpoint(&previous), Some(previous.clone()))?;
+    let update = updater.check().await
+        .map_err(|e| format!("获取版本 {} 失败: {}", previous, e))?
+        .ok_or_else(|| format!("版本 {} 的安装包不可用", previous))?;
+    install(app, update).await
+}
+
+/// 当前安排的延期安装
+pub fn scheduled() -> Option<ScheduledInstall> {
+    PENDING.lock().unwrap_or_else(|e| e.into_inner()).as_ref().map(|pending| pending.info.clone())
+}
+
+/// 取消已安排的延期安装
+pub fn cancel_scheduled() -> Option<ScheduledInstall> {
+    let pending = PENDING.lock().unwrap_or_else(|e| e.into_inner()).take()?;
+    pending.task.abort();
+    Some(pending.info)
+}
+
+/// 在指定时间安装更新，替换之前的安排；到时重新检查，安装届时通道上的最新版本
+pub async fn schedule(app: &AppHandle, settings: &SystemSettings, install_at: i64) -> Result<ScheduledInstall, String> {
+    let (info, _) = check(app, settings).await?;
+    let version = info.version.ok_or("当前已是最新版本")?;
+    let delay = Duration::from_millis((install_at - Utc::now().timestamp_millis()).max(0) as u64);
+
+    let app = app.clone();
+    let settings = settings.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        PENDING.lock().unwrap_or_else(|e| e.into_inner()).take();
+        let result = match check(&app, &settings).await {
+            Ok((_, Some(update))) => install(&app, update).await,
+            Ok((_, None)) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            eprintln!("延期安装更新失败: {}", e);
+        }
+    });
+
+    let info = ScheduledInstall { version, install_at };
+    let previous = PENDING.lock().unwrap_or_else(|e| e.into_inner())
+        .replace(PendingInstall { info: info.clone(), task });
+    if let Some(previous) = previous {
+        previous.task.abort();
+    }
+    println!("已安排在 {} 安装版本 {}", install_at, info.version);
+    Ok(info)
+}
+
+/// 启动时按设置检查更新，有新版本时推送事件
+pub fn start(app: &AppHandle, settings: &SystemSettings) {
+    if !settings.updates.check_on_startup {
+        return;
+    }
+
+    let app = app.clone();
+    let settings = settings.clone();
+    tauri::async_runtime::spawn(async move {
+        match check(&app, &settings).await {
+            Ok((info, Some(_))) => {
+                if let Err(e) = app.emit(UPDATE_AVAILABLE_EVENT, &info) {
+                    eprintln!("推送更新事件失败: {}", e);
+                }
+            }
+            Ok((_, None)) => {}
+            Err(e) => eprintln!("{}", e),
+        }
+    });
+}
@@ -0,0 +1,44 @@
+// Webhook 接入 - 根据设置启动接收 GitHub / GitLab webhook 的 HTTP 端点
+use std::sync::Arc;
+
+use codex_database::webhook_ingestion::{self, WebhookIngestor};
+use codex_multi_agent::WebhookSource;
+use tokio::net::TcpListener;
+
+use crate::commands::DatabaseHandle;
+use crate::settings::SystemSettings;
+
+/// 启动 Webhook 接入端点，签名密钥取自 CI 集成设置，未配置任何密钥时不启动
+pub fn start(db: &DatabaseHandle, settings: &SystemSettings) {
+    if !settings.webhook_ingestion.enabled {
+        return;
+    }
+
+    let mut ingestor = WebhookIngestor::new((**db).clone());
+    if let Some(secret) = &settings.ci.github_webhook_secret {
+        ingestor = ingestor.with_secret(WebhookSource::Github, secret.clone());
+    }
+    if let Some(token) = &settings.ci.gitlab_webhook_token {
+        ingestor = ingestor.with_secret(WebhookSource::Gitlab, token.clone());
+    }
+    // 未配置密钥时所有投递都会被拒绝，不启动端点
+    if !ingestor.has_secrets() {
+        eprintln!("未配置 GitHub webhook 密钥或 GitLab webhook 令牌，不启动 Webhook 接入端点");
+        return;
+    }
+    let ingestor = Arc::new(ingestor);
+    let bind_address = settings.webhook_ingestion.bind_address.clone();
+    tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::bind(&bind_address).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Webhook 接入端点监听 {} 失败: {}", bind_address, e);
+                return;
+            }
+        };
+        println!("Webhook 接入端点已启动: http://{}/webhooks/{{source}}/{{project_id}}", bind_address);
+        if let Err(e) = webhook_ingestion::serve(listener, ingestor).await {
+            eprintln!("Webhook 接入端点异常退出: {}", e);
+        }
+    });
+}
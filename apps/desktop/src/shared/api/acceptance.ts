@@ -0,0 +1,106 @@
+/**
+ * 验收标准API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type {
+  AcceptanceChecklist,
+  ChecklistItem,
+  CompletedTask,
+  CriterionEvidence,
+  CriterionStatus,
+} from '../types/acceptance';
+import { handleIpcError } from './client';
+
+/**
+ * 验收标准API类
+ */
+export class AcceptanceApi {
+  /**
+   * 获取任务的验收标准核对清单
+   */
+  static async getChecklist(taskId: string, token: string): Promise<AcceptanceChecklist> {
+    try {
+      const result = await invoke<AcceptanceChecklist>('get_acceptance_checklist', {
+        taskId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 提交某项验收标准的验证结果，标记为已验证时必须提供证据
+   */
+  static async markCriterion(
+    taskId: string,
+    criterion: string,
+    status: CriterionStatus,
+    evidence: CriterionEvidence[],
+    token: string,
+    agentId?: string,
+    notes?: string,
+  ): Promise<ChecklistItem> {
+    try {
+      const result = await invoke<ChecklistItem>('mark_acceptance_criterion', {
+        taskId,
+        criterion,
+        status,
+        evidence,
+        agentId,
+        notes,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 人工签核某项验收标准，approved 为 false 时驳回
+   */
+  static async signOff(
+    taskId: string,
+    criterion: string,
+    approved: boolean,
+    token: string,
+    comment?: string,
+  ): Promise<ChecklistItem> {
+    try {
+      const result = await invoke<ChecklistItem>('sign_off_acceptance_criterion', {
+        taskId,
+        criterion,
+        approved,
+        comment,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 跳过未验证的验收标准强制完成任务
+   */
+  static async completeWithOverride(taskId: string, reason: string, token: string): Promise<CompletedTask> {
+    try {
+      const result = await invoke<CompletedTask>('complete_task_with_override', {
+        taskId,
+        reason,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出验收标准API
+ */
+export default AcceptanceApi;
@@ -0,0 +1,76 @@
+/**
+ * 智能体性能仪表盘API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type {
+  AgentMetricsReport,
+  MetricsPeriod,
+  PaginationParams,
+  PaginationResponse,
+  TeamLeaderboard,
+  WorkHistoryEntry,
+} from '../types/agent-dashboard';
+import { handleIpcError } from './client';
+
+/**
+ * 智能体性能仪表盘API类
+ */
+export class AgentDashboardApi {
+  /**
+   * 获取智能体在统计周期内的性能汇总，默认统计最近一个月
+   */
+  static async getAgentMetrics(agentId: string, token: string, period?: MetricsPeriod): Promise<AgentMetricsReport> {
+    try {
+      const result = await invoke<AgentMetricsReport>('get_agent_metrics', {
+        agentId,
+        period: period ?? null,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取项目的团队排行榜，默认统计最近一个月
+   */
+  static async getTeamLeaderboard(projectId: string, token: string, period?: MetricsPeriod): Promise<TeamLeaderboard> {
+    try {
+      const result = await invoke<TeamLeaderboard>('get_team_leaderboard', {
+        projectId,
+        period: period ?? null,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 分页获取智能体的工作历史
+   */
+  static async getAgentHistory(
+    agentId: string,
+    token: string,
+    pagination?: PaginationParams
+  ): Promise<PaginationResponse<WorkHistoryEntry>> {
+    try {
+      const result = await invoke<PaginationResponse<WorkHistoryEntry>>('get_agent_history', {
+        agentId,
+        pagination: pagination ?? null,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出智能体性能仪表盘API
+ */
+export default AgentDashboardApi;
@@ -9,6 +9,9 @@ import type {
   UpdateAgentRequest,
   AgentWorkHistory,
   AgentPerformanceMetrics,
+  AgentBundleConflictStrategy,
+  ImportAgentBundleResult,
+  AgentApprovalPolicy,
 } from '../types/agent';
 import { handleIpcError } from './client';
 
@@ -62,6 +65,20 @@ export class AgentsApi {
     }
   }
 
+  /**
+   * 获取智能体生效的命令审批策略
+   */
+  static async getAgentApprovalPolicy(agentId: string): Promise<AgentApprovalPolicy> {
+    try {
+      const result = await invoke<AgentApprovalPolicy>('get_agent_approval_policy', {
+        agentId,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
   /**
    * 更新智能体
    */
@@ -157,6 +174,50 @@ export class AgentsApi {
   static async offlineAgent(agentId: string): Promise<Agent> {
     return this.updateAgentStatus(agentId, 'offline');
   }
+
+  /**
+   * 导出智能体包（JSON文本）
+   */
+  static async exportAgentBundle(
+    agentId: string,
+    token: string,
+    version?: string,
+    signingKey?: string
+  ): Promise<string> {
+    try {
+      const result = await invoke<string>('export_agent_bundle', {
+        agentId,
+        version,
+        signingKey,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 导入智能体包
+   */
+  static async importAgentBundle(
+    bundleJson: string,
+    token: string,
+    onConflict?: AgentBundleConflictStrategy,
+    signingKey?: string
+  ): Promise<ImportAgentBundleResult> {
+    try {
+      const result = await invoke<ImportAgentBundleResult>('import_agent_bundle', {
+        bundleJson,
+        onConflict,
+        signingKey,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
 }
 
 /**
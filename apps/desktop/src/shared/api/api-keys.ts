@@ -0,0 +1,59 @@
+/**
+ * API密钥API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { ApiKey, CreateApiKeyRequest, IssuedApiKey } from '../types/api-key';
+import { handleIpcError } from './client';
+
+/**
+ * API密钥API类
+ */
+export class ApiKeysApi {
+  /**
+   * 创建API密钥，返回的明文只在此时可见
+   */
+  static async create(request: CreateApiKeyRequest, token: string): Promise<IssuedApiKey> {
+    try {
+      const result = await invoke<IssuedApiKey>('create_api_key', {
+        ...request,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 列出当前用户的API密钥及使用计数
+   */
+  static async list(token: string): Promise<ApiKey[]> {
+    try {
+      const result = await invoke<ApiKey[]>('list_api_keys', { token });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 吊销API密钥
+   */
+  static async revoke(keyId: string, token: string): Promise<ApiKey> {
+    try {
+      const result = await invoke<ApiKey>('revoke_api_key', {
+        keyId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出API密钥API
+ */
+export default ApiKeysApi;
@@ -0,0 +1,76 @@
+/**
+ * 执行工件API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { SessionArtifact, SessionDiff } from '../types/artifact';
+import { handleIpcError } from './client';
+
+/**
+ * 执行工件API类
+ */
+export class ArtifactsApi {
+  /**
+   * 列出执行会话的工件
+   */
+  static async listSessionArtifacts(sessionId: string, token: string): Promise<SessionArtifact[]> {
+    try {
+      const result = await invoke<SessionArtifact[]>('list_session_artifacts', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 将工件另存到指定路径（路径通常来自保存对话框）
+   */
+  static async downloadArtifact(artifactId: string, destination: string, token: string): Promise<void> {
+    try {
+      await invoke<void>('download_artifact', {
+        artifactId,
+        destination,
+        token,
+      });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 使用系统默认程序打开工件
+   */
+  static async openArtifact(artifactId: string, token: string): Promise<void> {
+    try {
+      await invoke<void>('open_artifact', {
+        artifactId,
+        token,
+      });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取执行会话的结构化代码差异，会话尚未采集差异时返回 null
+   */
+  static async getSessionDiff(sessionId: string, token: string): Promise<SessionDiff | null> {
+    try {
+      const result = await invoke<SessionDiff | null>('get_session_diff', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出执行工件API
+ */
+export default ArtifactsApi;
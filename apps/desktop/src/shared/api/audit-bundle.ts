@@ -0,0 +1,33 @@
+/**
+ * 项目审计包API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { AuditBundleManifest } from '../types/audit-bundle';
+import { handleIpcError } from './client';
+
+/**
+ * 项目审计包API类
+ */
+export class AuditBundleApi {
+  /**
+   * 导出项目审计包到指定路径
+   */
+  static async exportProjectAudit(projectId: string, path: string, token: string): Promise<AuditBundleManifest> {
+    try {
+      const result = await invoke<AuditBundleManifest>('export_project_audit', {
+        projectId,
+        path,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出项目审计包API
+ */
+export default AuditBundleApi;
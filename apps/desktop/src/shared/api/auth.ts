@@ -5,7 +5,9 @@ import type {
   AuthResponse, 
   CurrentUser,
   ChangePasswordRequest,
-  UpdateUserRequest
+  UpdateUserRequest,
+  OidcProvider,
+  LinkedIdentity
 } from '../types/auth';
 import { handleIpcError } from './client';
 
@@ -109,6 +111,63 @@ export class AuthApi {
       throw handleIpcError(error);
     }
   }
+
+  /**
+   * 获取可用的单点登录提供方
+   */
+  listOidcProviders = async (): Promise<OidcProvider[]> => {
+    try {
+      return await invoke<OidcProvider[]>('list_oidc_providers');
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 通过单点登录提供方登录，在系统浏览器中完成授权
+   *
+   * 启用两步验证的用户需要提交验证码，未提交时返回 TWO_FACTOR_REQUIRED
+   */
+  loginWithOidc = async (providerId: string, twoFactorCode?: string): Promise<AuthResponse> => {
+    try {
+      return await invoke<AuthResponse>('login_with_oidc', { providerId, twoFactorCode });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 为当前用户关联单点登录身份
+   */
+  linkOidcIdentity = async (providerId: string, token: string): Promise<LinkedIdentity> => {
+    try {
+      return await invoke<LinkedIdentity>('link_oidc_identity', { providerId, token });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取当前用户关联的外部身份
+   */
+  listLinkedIdentities = async (token: string): Promise<LinkedIdentity[]> => {
+    try {
+      return await invoke<LinkedIdentity[]>('list_linked_identities', { token });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 解除外部身份关联
+   */
+  unlinkIdentity = async (identityId: string, token: string): Promise<void> => {
+    try {
+      await invoke<void>('unlink_identity', { identityId, token });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
 }
 
 // 单例实例
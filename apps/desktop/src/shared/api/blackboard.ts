@@ -0,0 +1,93 @@
+/**
+ * 项目黑板API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { BlackboardEntry, BlackboardEntryVersion, SharedContextCategory } from '../types/blackboard';
+import { handleIpcError } from './client';
+
+/**
+ * 项目黑板API类
+ */
+export class BlackboardApi {
+  /**
+   * 列出项目黑板的全部条目
+   */
+  static async listEntries(projectId: string, token: string): Promise<BlackboardEntry[]> {
+    try {
+      const result = await invoke<BlackboardEntry[]>('list_blackboard_entries', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取条目的历史版本
+   */
+  static async getEntryHistory(projectId: string, key: string, token: string): Promise<BlackboardEntryVersion[]> {
+    try {
+      const result = await invoke<BlackboardEntryVersion[]>('get_blackboard_entry_history', {
+        projectId,
+        key,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 写入条目，expectedVersion 为上次读取到的版本（新建时省略），
+   * 期间有其他写入时返回冲突错误
+   */
+  static async writeEntry(
+    projectId: string,
+    key: string,
+    category: SharedContextCategory,
+    value: string,
+    includeInPrompts: boolean,
+    token: string,
+    expectedVersion?: number,
+  ): Promise<BlackboardEntry> {
+    try {
+      const result = await invoke<BlackboardEntry>('write_blackboard_entry', {
+        projectId,
+        key,
+        category,
+        value,
+        includeInPrompts,
+        expectedVersion,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 删除条目
+   */
+  static async deleteEntry(projectId: string, key: string, expectedVersion: number, token: string): Promise<void> {
+    try {
+      await invoke('delete_blackboard_entry', {
+        projectId,
+        key,
+        expectedVersion,
+        token,
+      });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出项目黑板API
+ */
+export default BlackboardApi;
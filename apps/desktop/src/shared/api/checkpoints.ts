@@ -0,0 +1,64 @@
+/**
+ * 执行会话检查点API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { CheckpointData, ResumedSessionInfo, SessionCheckpoint } from '../types/checkpoint';
+import { handleIpcError } from './client';
+
+/**
+ * 执行会话检查点API类
+ */
+export class CheckpointApi {
+  /**
+   * 为运行中的执行会话保存检查点
+   */
+  static async save(sessionId: string, checkpoint: CheckpointData, token: string): Promise<SessionCheckpoint> {
+    try {
+      const result = await invoke<SessionCheckpoint>('save_session_checkpoint', {
+        sessionId,
+        checkpoint,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取执行会话最近一次检查点，没有检查点时返回 null
+   */
+  static async getLatest(sessionId: string, token: string): Promise<SessionCheckpoint | null> {
+    try {
+      const result = await invoke<SessionCheckpoint | null>('get_session_checkpoint', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 从最近一次检查点恢复已中断的执行会话，未指定Agent时交给原Agent继续
+   */
+  static async resume(sessionId: string, token: string, agentId?: string): Promise<ResumedSessionInfo> {
+    try {
+      const result = await invoke<ResumedSessionInfo>('resume_execution_session', {
+        sessionId,
+        agentId: agentId ?? null,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出执行会话检查点API
+ */
+export default CheckpointApi;
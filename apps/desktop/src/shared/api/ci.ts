@@ -0,0 +1,96 @@
+/**
+ * CI 集成API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { CiCheckRun, CiProvider, CiStatus, CodeReview, ReviewDecision } from '../types/ci';
+import { handleIpcError } from './client';
+
+/**
+ * CI 集成API类
+ */
+export class CiApi {
+  /**
+   * 写入转发的 CI webhook，不相关的事件返回 null
+   */
+  static async ingestWebhook(
+    projectId: string,
+    provider: CiProvider,
+    event: string | null,
+    payload: string,
+    signature: string | null,
+    token: string,
+  ): Promise<CiCheckRun | null> {
+    try {
+      const result = await invoke<CiCheckRun | null>('ingest_ci_webhook', {
+        projectId,
+        provider,
+        event,
+        payload,
+        signature,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 列出项目最近的 CI 检查结果
+   */
+  static async listChecks(projectId: string, token: string, limit?: number): Promise<CiCheckRun[]> {
+    try {
+      const result = await invoke<CiCheckRun[]>('list_ci_checks', {
+        projectId,
+        limit,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取任务的 CI 状态，尚无检查结果时返回 null
+   */
+  static async getTaskStatus(taskId: string, token: string): Promise<CiStatus | null> {
+    try {
+      const result = await invoke<CiStatus | null>('get_task_ci_status', {
+        taskId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 提交代码审查决策，CI 为红色时批准会被质量门禁拒绝
+   */
+  static async submitReviewDecision(
+    reviewId: string,
+    decision: ReviewDecision,
+    overallComment: string | null,
+    token: string,
+  ): Promise<CodeReview> {
+    try {
+      const result = await invoke<CodeReview>('submit_review_decision', {
+        reviewId,
+        decision,
+        overallComment,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出 CI 集成API
+ */
+export default CiApi;
@@ -0,0 +1,86 @@
+/**
+ * 代码归属API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { CodeOwnership } from '../types/code-ownership';
+import type { Reviewer } from '../types/review-sla';
+import { handleIpcError } from './client';
+
+/**
+ * 代码归属API类
+ */
+export class CodeOwnershipApi {
+  /**
+   * 获取项目的代码归属规则，未配置时返回 null
+   */
+  static async getOwnership(projectId: string, token: string): Promise<CodeOwnership | null> {
+    try {
+      const result = await invoke<CodeOwnership | null>('get_project_code_ownership', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 更新项目的代码归属规则，传入 null 清除规则
+   */
+  static async updateOwnership(projectId: string, ownership: CodeOwnership | null, token: string): Promise<void> {
+    try {
+      await invoke('update_project_code_ownership', {
+        projectId,
+        ownership,
+        token,
+      });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 导入 CODEOWNERS 格式的规则，负责人写作 agent:<ID>、user:<ID> 或 capability:<能力>
+   */
+  static async importCodeowners(projectId: string, content: string, token: string): Promise<CodeOwnership> {
+    try {
+      const result = await invoke<CodeOwnership>('import_project_codeowners', {
+        projectId,
+        content,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 按代码归属为修改了指定文件的变更推荐审查者
+   */
+  static async suggestReviewers(
+    projectId: string,
+    files: string[],
+    token: string,
+    authorAgentId?: string,
+  ): Promise<Reviewer[]> {
+    try {
+      const result = await invoke<Reviewer[]>('suggest_code_reviewers', {
+        projectId,
+        files,
+        authorAgentId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出代码归属API
+ */
+export default CodeOwnershipApi;
@@ -0,0 +1,59 @@
+/**
+ * 命令策略API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { CommandAuditLog, CommandClassification } from '../types/command-policy';
+import { handleIpcError } from './client';
+
+/**
+ * 命令策略API类
+ */
+export class CommandPolicyApi {
+  /**
+   * 获取对话中的命令审批审计记录
+   */
+  static async listConversationAuditLogs(conversationId: string): Promise<CommandAuditLog[]> {
+    try {
+      const result = await invoke<CommandAuditLog[]>('list_command_audit_logs', {
+        conversationId,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取智能体最近的命令审批审计记录
+   */
+  static async listAgentAuditLogs(agentId: string, limit?: number): Promise<CommandAuditLog[]> {
+    try {
+      const result = await invoke<CommandAuditLog[]>('list_command_audit_logs', {
+        agentId,
+        limit,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 监听对话中被命令策略自动批准或拒绝的命令
+   */
+  static async onDecision(
+    conversationId: string,
+    onDecision: (classification: CommandClassification) => void
+  ): Promise<UnlistenFn> {
+    return listen<CommandClassification>(`conversation_command_policy_${conversationId}`, (event) => {
+      onDecision(event.payload);
+    });
+  }
+}
+
+/**
+ * 默认导出命令策略API
+ */
+export default CommandPolicyApi;
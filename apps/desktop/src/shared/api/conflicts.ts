@@ -0,0 +1,123 @@
+/**
+ * 冲突处理中心 API客户端
+ *
+ * 冲突变更后后端发送 conflict_updated 事件，载荷为最新的 Conflict
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type {
+  Conflict,
+  ConflictDetail,
+  ConflictListFilter,
+  ConflictPage,
+  HumanDecision,
+  HumanDecisionInput,
+} from '../types/conflict';
+import { handleIpcError } from './client';
+
+/**
+ * 冲突 API类
+ */
+export class ConflictsApi {
+  /**
+   * 分页列出影响项目任务的冲突
+   */
+  static async list(
+    projectId: string,
+    token: string,
+    filter?: ConflictListFilter,
+    page = 0,
+    pageSize?: number
+  ): Promise<ConflictPage> {
+    try {
+      const result = await invoke<ConflictPage>('list_conflicts', {
+        projectId,
+        filter,
+        page,
+        pageSize,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取冲突详情及其人工决策
+   */
+  static async getDetail(conflictId: string, token: string): Promise<ConflictDetail> {
+    try {
+      const result = await invoke<ConflictDetail>('get_conflict_detail', {
+        conflictId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 人工解决冲突
+   */
+  static async resolve(
+    conflictId: string,
+    resolutionStrategy: string,
+    token: string,
+    resolutionNote?: string
+  ): Promise<Conflict> {
+    try {
+      const result = await invoke<Conflict>('resolve_conflict', {
+        conflictId,
+        resolutionStrategy,
+        resolutionNote,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 把冲突上报给人工处理，可指定负责人
+   */
+  static async escalate(conflictId: string, token: string, assignedUserId?: string): Promise<Conflict> {
+    try {
+      const result = await invoke<Conflict>('escalate_conflict', {
+        conflictId,
+        assignedUserId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 记录人工决策
+   */
+  static async recordDecision(
+    conflictId: string,
+    decision: HumanDecisionInput,
+    token: string
+  ): Promise<HumanDecision> {
+    try {
+      const result = await invoke<HumanDecision>('record_human_decision', {
+        conflictId,
+        decision,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出冲突 API
+ */
+export default ConflictsApi;
@@ -0,0 +1,64 @@
+/**
+ * 测试覆盖率API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { CoverageDelta, CoverageFormat, CoverageGateResult } from '../types/coverage';
+import { handleIpcError } from './client';
+
+/**
+ * 测试覆盖率API类
+ */
+export class CoverageApi {
+  /**
+   * 上报执行会话的覆盖率报告，覆盖该会话之前的报告
+   */
+  static async ingest(sessionId: string, format: CoverageFormat, content: string, token: string): Promise<CoverageDelta> {
+    try {
+      const result = await invoke<CoverageDelta>('ingest_session_coverage', {
+        sessionId,
+        format,
+        content,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取执行会话的覆盖率及相对基准提交的变化
+   */
+  static async getSessionCoverage(sessionId: string, token: string): Promise<CoverageDelta | null> {
+    try {
+      const result = await invoke<CoverageDelta | null>('get_session_coverage', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 评估执行会话的覆盖率门禁
+   */
+  static async evaluateGate(sessionId: string, token: string): Promise<CoverageGateResult | null> {
+    try {
+      const result = await invoke<CoverageGateResult | null>('evaluate_session_coverage_gate', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出测试覆盖率API
+ */
+export default CoverageApi;
@@ -0,0 +1,90 @@
+/**
+ * 演示工作区API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { DemoWorkspaceSummary, DemoExecutionLog } from '../types/demo';
+import { handleIpcError } from './client';
+
+/**
+ * 演示会话回放日志事件名
+ */
+export const DEMO_EXECUTION_LOG_EVENT = 'demo_execution_log';
+
+/**
+ * 演示工作区API类
+ */
+export class DemoApi {
+  /**
+   * 加载演示工作区（示例项目、任务、智能体、事件和执行会话）
+   */
+  static async loadDemoWorkspace(token: string): Promise<DemoWorkspaceSummary> {
+    try {
+      const result = await invoke<DemoWorkspaceSummary>('load_demo_workspace', {
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取当前演示工作区，未加载时返回 null
+   */
+  static async getDemoWorkspace(token: string): Promise<DemoWorkspaceSummary | null> {
+    try {
+      const result = await invoke<DemoWorkspaceSummary | null>('get_demo_workspace', {
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 删除演示工作区的全部数据
+   */
+  static async deleteDemoWorkspace(token: string): Promise<boolean> {
+    try {
+      const result = await invoke<boolean>('delete_demo_workspace', {
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 回放演示执行会话，日志通过 onLog 逐条推送
+   */
+  static async replayDemoSession(
+    sessionId: string,
+    token: string,
+    onLog: (log: DemoExecutionLog) => void
+  ): Promise<UnlistenFn> {
+    const unlisten = await listen<DemoExecutionLog>(DEMO_EXECUTION_LOG_EVENT, (event) => {
+      if (event.payload.session_id === sessionId) {
+        onLog(event.payload);
+      }
+    });
+    try {
+      await invoke('replay_demo_session', {
+        sessionId,
+        token,
+      });
+      return unlisten;
+    } catch (error) {
+      unlisten();
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出演示工作区API
+ */
+export default DemoApi;
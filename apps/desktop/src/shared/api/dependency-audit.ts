@@ -0,0 +1,39 @@
+/**
+ * 依赖审计API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { DependencyAuditOptions } from '../types/dependency-audit';
+import { handleIpcError } from './client';
+
+/**
+ * 依赖审计API类
+ */
+export class DependencyAuditApi {
+  /**
+   * 在后台执行依赖审计任务，返回操作ID，进度通过 operation_progress 事件推送
+   */
+  static async start(
+    taskId: string,
+    agentId: string,
+    options: DependencyAuditOptions | null,
+    token: string,
+  ): Promise<string> {
+    try {
+      const result = await invoke<string>('start_dependency_audit', {
+        taskId,
+        agentId,
+        options,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出依赖审计API
+ */
+export default DependencyAuditApi;
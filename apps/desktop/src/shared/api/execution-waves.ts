@@ -0,0 +1,52 @@
+/**
+ * 并行执行API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { LaunchReport, WaveView } from '../types/execution-wave';
+import { handleIpcError } from './client';
+
+/**
+ * 并行执行API类
+ */
+export class ExecutionWavesApi {
+  /**
+   * 获取项目的执行波次视图
+   */
+  static async getExecutionWaves(projectId: string, token: string): Promise<WaveView> {
+    try {
+      const result = await invoke<WaveView>('get_execution_waves', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 并行启动所有就绪的任务，完成后推送 execution_waves_updated 事件
+   */
+  static async launchParallelExecution(
+    projectId: string,
+    token: string,
+    maxParallelSessions?: number
+  ): Promise<LaunchReport> {
+    try {
+      const result = await invoke<LaunchReport>('launch_parallel_execution', {
+        projectId,
+        token,
+        maxParallelSessions,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出并行执行API
+ */
+export default ExecutionWavesApi;
@@ -0,0 +1,61 @@
+/**
+ * 任务与日志导出API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { ExportFormat, ExportSummary } from '../types/export';
+import { handleIpcError } from './client';
+
+/**
+ * 导出API类
+ */
+export class ExportApi {
+  /**
+   * 导出项目的全部任务到指定路径
+   */
+  static async exportProjectTasks(
+    projectId: string,
+    format: ExportFormat,
+    path: string,
+    token: string,
+  ): Promise<ExportSummary> {
+    try {
+      const result = await invoke<ExportSummary>('export_project_tasks', {
+        projectId,
+        format,
+        path,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 导出项目所有执行会话的日志到指定路径
+   */
+  static async exportProjectLogs(
+    projectId: string,
+    format: ExportFormat,
+    path: string,
+    token: string,
+  ): Promise<ExportSummary> {
+    try {
+      const result = await invoke<ExportSummary>('export_project_logs', {
+        projectId,
+        format,
+        path,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出任务与日志导出API
+ */
+export default ExportApi;
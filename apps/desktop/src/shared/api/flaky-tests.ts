@@ -0,0 +1,64 @@
+/**
+ * 不稳定测试API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { FlakyTestConfig, FlakyTestReport, TestCaseReport, TestGateResult } from '../types/flaky-test';
+import { handleIpcError } from './client';
+
+/**
+ * 不稳定测试API类
+ */
+export class FlakyTestsApi {
+  /**
+   * 记录执行会话的测试用例结果，覆盖该会话之前的记录
+   */
+  static async recordSessionResults(sessionId: string, results: TestCaseReport[], token: string): Promise<number> {
+    try {
+      const result = await invoke<number>('record_session_test_results', {
+        sessionId,
+        results,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取项目的不稳定测试报告
+   */
+  static async getReport(projectId: string, token: string, config?: FlakyTestConfig): Promise<FlakyTestReport> {
+    try {
+      const result = await invoke<FlakyTestReport>('get_flaky_test_report', {
+        projectId,
+        config: config ?? null,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 按项目质量门禁评估执行会话的测试结果
+   */
+  static async evaluateSessionGate(sessionId: string, token: string): Promise<TestGateResult | null> {
+    try {
+      const result = await invoke<TestGateResult | null>('evaluate_session_test_gate', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出不稳定测试API
+ */
+export default FlakyTestsApi;
@@ -0,0 +1,89 @@
+/**
+ * 任务人工检查点API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { GateTimeline, TaskGate } from '../types/gate';
+import { handleIpcError } from './client';
+
+/**
+ * 任务人工检查点API类
+ */
+export class GatesApi {
+  /**
+   * 为任务定义人工检查点，新检查点排在已有检查点之后
+   */
+  static async defineGate(taskId: string, name: string, token: string, description?: string): Promise<TaskGate> {
+    try {
+      const result = await invoke<TaskGate>('define_task_gate', {
+        taskId,
+        name,
+        description,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取任务的检查点及等待时间线
+   */
+  static async getTimeline(taskId: string, token: string): Promise<GateTimeline> {
+    try {
+      const result = await invoke<GateTimeline>('get_task_gate_timeline', {
+        taskId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 任务到达检查点，暂停等待人工决策
+   */
+  static async requestGate(taskId: string, gateName: string, token: string): Promise<TaskGate> {
+    try {
+      const result = await invoke<TaskGate>('request_task_gate', {
+        taskId,
+        gateName,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 批准或驳回检查点，驳回时必须填写意见
+   */
+  static async decideGate(
+    taskId: string,
+    gateId: string,
+    approved: boolean,
+    token: string,
+    comment?: string,
+  ): Promise<TaskGate> {
+    try {
+      const result = await invoke<TaskGate>('decide_task_gate', {
+        taskId,
+        gateId,
+        approved,
+        comment,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出任务人工检查点API
+ */
+export default GatesApi;
@@ -0,0 +1,41 @@
+/**
+ * LLM响应缓存API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { LlmCacheStats } from '../types/llm-cache';
+import { handleIpcError } from './client';
+
+/**
+ * LLM响应缓存API类
+ */
+export class LlmCacheApi {
+  /**
+   * 获取缓存的命中统计和容量
+   */
+  static async getStats(token: string): Promise<LlmCacheStats> {
+    try {
+      const result = await invoke<LlmCacheStats>('get_llm_cache_stats', { token });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 清空缓存，返回删除的条目数
+   */
+  static async clear(token: string): Promise<number> {
+    try {
+      const result = await invoke<number>('clear_llm_cache', { token });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出LLM响应缓存API
+ */
+export default LlmCacheApi;
@@ -0,0 +1,69 @@
+/**
+ * LLM调用记录API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { ReplayReport, TranscriptQuery, TranscriptView } from '../types/llm-transcript';
+import { handleIpcError } from './client';
+
+/**
+ * LLM调用记录API类
+ */
+export class LlmTranscriptsApi {
+  /**
+   * 查看项目的调用记录（按时间倒序）
+   */
+  static async list(projectId: string, token: string, query?: TranscriptQuery): Promise<TranscriptView[]> {
+    try {
+      const result = await invoke<TranscriptView[]>('list_llm_transcripts', {
+        projectId,
+        query: query ?? null,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 查看对话中Agent执行的调用记录
+   */
+  static async listForConversation(conversationId: string, token: string): Promise<TranscriptView[]> {
+    try {
+      const result = await invoke<TranscriptView[]>('list_conversation_llm_transcripts', { conversationId, token });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 查看单条调用记录
+   */
+  static async get(transcriptId: string, token: string): Promise<TranscriptView> {
+    try {
+      const result = await invoke<TranscriptView>('get_llm_transcript', { transcriptId, token });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 查看LLM会话的重放报告（按时间倒序）
+   */
+  static async listReplayReports(sessionId: string, token: string): Promise<ReplayReport[]> {
+    try {
+      const result = await invoke<ReplayReport[]>('list_llm_replay_reports', { sessionId, token });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出LLM调用记录API
+ */
+export default LlmTranscriptsApi;
@@ -0,0 +1,49 @@
+/**
+ * 执行日志管道API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { LogBatch, LogPipelineMetrics } from '../types/log-stream';
+import { handleIpcError } from './client';
+
+/**
+ * 执行日志批次事件名
+ */
+export const EXECUTION_LOG_BATCH_EVENT = 'execution_log_batch';
+
+/**
+ * 执行日志管道API类
+ */
+export class LogStreamApi {
+  /**
+   * 获取管道指标
+   */
+  static async getMetrics(): Promise<LogPipelineMetrics> {
+    try {
+      const result = await invoke<LogPipelineMetrics>('get_log_pipeline_metrics');
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 监听日志批次，stream 为空时接收全部日志流
+   */
+  static async onBatch(
+    onBatch: (batch: LogBatch) => void,
+    stream?: string
+  ): Promise<UnlistenFn> {
+    return listen<LogBatch>(EXECUTION_LOG_BATCH_EVENT, (event) => {
+      if (!stream || event.payload.stream === stream) {
+        onBatch(event.payload);
+      }
+    });
+  }
+}
+
+/**
+ * 默认导出执行日志管道API
+ */
+export default LogStreamApi;
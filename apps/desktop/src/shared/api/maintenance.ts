@@ -0,0 +1,77 @@
+/**
+ * 定期维护任务API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { MaintenanceJob, MaintenanceJobRun } from '../types/maintenance';
+import { handleIpcError } from './client';
+
+/**
+ * 定期维护任务API类
+ */
+export class MaintenanceApi {
+  /**
+   * 列出维护任务
+   */
+  static async listJobs(token: string): Promise<MaintenanceJob[]> {
+    try {
+      const result = await invoke<MaintenanceJob[]>('list_maintenance_jobs', { token });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 列出维护任务最近的执行记录
+   */
+  static async listRuns(jobId: string, token: string, limit?: number): Promise<MaintenanceJobRun[]> {
+    try {
+      const result = await invoke<MaintenanceJobRun[]>('list_maintenance_job_runs', { jobId, limit, token });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 立即执行维护任务
+   */
+  static async trigger(jobId: string, token: string): Promise<MaintenanceJobRun> {
+    try {
+      const result = await invoke<MaintenanceJobRun>('trigger_maintenance_job', { jobId, token });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 更新维护任务的执行计划
+   */
+  static async updateSchedule(
+    jobId: string,
+    cronExpression: string,
+    jitterSecs: number,
+    enabled: boolean,
+    token: string,
+  ): Promise<MaintenanceJob> {
+    try {
+      const result = await invoke<MaintenanceJob>('update_maintenance_job', {
+        jobId,
+        cronExpression,
+        jitterSecs,
+        enabled,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出定期维护任务API
+ */
+export default MaintenanceApi;
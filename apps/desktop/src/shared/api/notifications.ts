@@ -0,0 +1,64 @@
+/**
+ * 通知API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { Notification, NotificationPreferences } from '../types/notification';
+import { handleIpcError } from './client';
+
+/**
+ * 通知API类
+ */
+export class NotificationsApi {
+  /**
+   * 获取当前用户的通知偏好
+   */
+  static async getPreferences(token: string): Promise<NotificationPreferences> {
+    try {
+      return await invoke<NotificationPreferences>('get_notification_preferences', { token });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 保存当前用户的通知偏好
+   */
+  static async updatePreferences(
+    preferences: NotificationPreferences,
+    token: string
+  ): Promise<NotificationPreferences> {
+    try {
+      return await invoke<NotificationPreferences>('update_notification_preferences', { preferences, token });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 列出应用内通知
+   */
+  static async list(token: string, limit?: number): Promise<Notification[]> {
+    try {
+      return await invoke<Notification[]>('list_notifications', { limit, token });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 标记通知为已读
+   */
+  static async markRead(notificationIds: string[], token: string): Promise<number> {
+    try {
+      return await invoke<number>('mark_notifications_read', { notificationIds, token });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出通知API
+ */
+export default NotificationsApi;
@@ -0,0 +1,112 @@
+/**
+ * 长时间运行操作API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { OperationProgress } from '../types/operation';
+import { handleIpcError } from './client';
+
+/**
+ * 操作进度事件名
+ */
+export const OPERATION_PROGRESS_EVENT = 'operation_progress';
+
+/**
+ * 长时间运行操作API类
+ */
+export class OperationsApi {
+  /**
+   * 列出项目中运行中和最近结束的操作
+   */
+  static async listOperations(projectId: string, token: string): Promise<OperationProgress[]> {
+    try {
+      const result = await invoke<OperationProgress[]>('list_operations', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取操作进度
+   */
+  static async getOperation(operationId: string, token: string): Promise<OperationProgress> {
+    try {
+      const result = await invoke<OperationProgress>('get_operation', {
+        operationId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 请求取消操作，操作已结束时返回 false
+   */
+  static async cancelOperation(operationId: string, token: string): Promise<boolean> {
+    try {
+      const result = await invoke<boolean>('cancel_operation', {
+        operationId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 在后台更新项目的语义检索索引，返回操作ID
+   */
+  static async startSearchIndexRebuild(projectId: string, token: string): Promise<string> {
+    try {
+      const result = await invoke<string>('start_search_index_rebuild', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 在后台扫描项目工作空间，返回操作ID
+   */
+  static async startWorkspaceScan(projectId: string, token: string): Promise<string> {
+    try {
+      const result = await invoke<string>('start_workspace_scan', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 监听操作进度，operationId 为空时接收全部操作
+   */
+  static async onProgress(
+    onProgress: (progress: OperationProgress) => void,
+    operationId?: string
+  ): Promise<UnlistenFn> {
+    return listen<OperationProgress>(OPERATION_PROGRESS_EVENT, (event) => {
+      if (!operationId || event.payload.operation_id === operationId) {
+        onProgress(event.payload);
+      }
+    });
+  }
+}
+
+/**
+ * 默认导出长时间运行操作API
+ */
+export default OperationsApi;
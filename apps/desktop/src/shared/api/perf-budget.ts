@@ -0,0 +1,118 @@
+/**
+ * 性能预算API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { BenchmarkResult, PerfBudget, PerfCheckReport, PerfGateResult } from '../types/perf-budget';
+import { handleIpcError } from './client';
+
+/**
+ * 性能预算API类
+ */
+export class PerfBudgetApi {
+  /**
+   * 获取项目的性能预算
+   */
+  static async getBudget(projectId: string, token: string): Promise<PerfBudget | null> {
+    try {
+      const result = await invoke<PerfBudget | null>('get_project_perf_budget', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 更新项目的性能预算，传入 null 表示不做性能回归检查
+   */
+  static async updateBudget(projectId: string, budget: PerfBudget | null, token: string): Promise<void> {
+    try {
+      await invoke('update_project_perf_budget', {
+        projectId,
+        budget,
+        token,
+      });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 在执行会话的工作空间中运行基准测试并与基线比较
+   */
+  static async run(sessionId: string, token: string): Promise<PerfCheckReport> {
+    try {
+      const result = await invoke<PerfCheckReport>('run_session_perf_check', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 评估执行会话的性能门禁，没有配置预算或没有结果时返回 null
+   */
+  static async evaluateGate(sessionId: string, token: string): Promise<PerfGateResult | null> {
+    try {
+      const result = await invoke<PerfGateResult | null>('evaluate_session_perf_gate', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 把执行会话的基准测试结果提升为所在分支的基线
+   */
+  static async promoteBaseline(sessionId: string, token: string): Promise<number> {
+    try {
+      const result = await invoke<number>('promote_session_perf_baseline', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取分支上某项基准测试的历史结果，用于趋势图
+   */
+  static async getHistory(
+    projectId: string,
+    branch: string,
+    suite: string,
+    name: string,
+    token: string,
+    limit?: number,
+  ): Promise<BenchmarkResult[]> {
+    try {
+      const result = await invoke<BenchmarkResult[]>('get_benchmark_history', {
+        projectId,
+        branch,
+        suite,
+        name,
+        limit,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出性能预算API
+ */
+export default PerfBudgetApi;
@@ -0,0 +1,78 @@
+/**
+ * 个人信息脱敏API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { DecompositionPrompt, PiiScrubbingConfig } from '../types/pii-scrubbing';
+import { handleIpcError } from './client';
+
+/**
+ * 个人信息脱敏API类
+ */
+export class PiiScrubbingApi {
+  /**
+   * 获取项目的脱敏配置，未配置时返回 null
+   */
+  static async getConfig(projectId: string, token: string): Promise<PiiScrubbingConfig | null> {
+    try {
+      const result = await invoke<PiiScrubbingConfig | null>('get_project_pii_scrubbing', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 更新项目的脱敏配置，传入 null 关闭脱敏
+   */
+  static async updateConfig(projectId: string, config: PiiScrubbingConfig | null, token: string): Promise<void> {
+    try {
+      await invoke('update_project_pii_scrubbing', {
+        projectId,
+        config,
+        token,
+      });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 为需求文档构建任务分解提示词
+   */
+  static async buildDecompositionPrompt(documentId: string, token: string): Promise<DecompositionPrompt> {
+    try {
+      const result = await invoke<DecompositionPrompt>('build_decomposition_prompt', {
+        documentId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 把 LLM 返回结果中的占位符还原为原文
+   */
+  static async reidentify(projectId: string, text: string, token: string): Promise<string> {
+    try {
+      const result = await invoke<string>('reidentify_pii', {
+        projectId,
+        text,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出个人信息脱敏API
+ */
+export default PiiScrubbingApi;
@@ -0,0 +1,62 @@
+/**
+ * 紧急任务抢占API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { PreemptionPolicy, TaskPreemptedEvent } from '../types/preemption';
+import { handleIpcError } from './client';
+
+/**
+ * 紧急任务抢占API类
+ */
+export class PreemptionApi {
+  /**
+   * 获取项目的抢占策略，未配置时返回 null
+   */
+  static async getPolicy(projectId: string, token: string): Promise<PreemptionPolicy | null> {
+    try {
+      const result = await invoke<PreemptionPolicy | null>('get_project_preemption_policy', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 更新项目的抢占策略，传入 null 关闭抢占
+   */
+  static async updatePolicy(projectId: string, policy: PreemptionPolicy | null, token: string): Promise<void> {
+    try {
+      await invoke('update_project_preemption_policy', {
+        projectId,
+        policy,
+        token,
+      });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 为紧急任务抢占一个运行中的低优先级任务，没有可抢占的任务时返回 null
+   */
+  static async preemptForCriticalTask(taskId: string, token: string): Promise<TaskPreemptedEvent | null> {
+    try {
+      const result = await invoke<TaskPreemptedEvent | null>('preempt_for_critical_task', {
+        taskId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出紧急任务抢占API
+ */
+export default PreemptionApi;
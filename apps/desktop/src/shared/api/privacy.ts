@@ -0,0 +1,32 @@
+/**
+ * 数据隐私API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { PurgeReport } from '../types/privacy';
+import { handleIpcError } from './client';
+
+/**
+ * 数据隐私API类
+ */
+export class PrivacyApi {
+  /**
+   * 清除用户的全部个人数据，只允许本人或所在组织的所有者操作
+   */
+  static async purgeUserData(userId: string, token: string): Promise<PurgeReport> {
+    try {
+      const result = await invoke<PurgeReport>('purge_user_data', {
+        userId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出数据隐私API
+ */
+export default PrivacyApi;
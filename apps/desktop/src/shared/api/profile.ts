@@ -0,0 +1,67 @@
+/**
+ * 配置档案API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { Profile, ProfileList } from '../types/profile';
+import { handleIpcError } from './client';
+
+/**
+ * 配置档案API类
+ */
+export class ProfileApi {
+  /**
+   * 获取全部档案及当前使用的档案
+   */
+  static async list(): Promise<ProfileList> {
+    try {
+      const result = await invoke<ProfileList>('list_profiles');
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 创建档案，dataDir 为空时使用默认位置
+   */
+  static async create(name: string, dataDir?: string): Promise<Profile> {
+    try {
+      const result = await invoke<Profile>('create_profile', {
+        name,
+        dataDir,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 切换到指定档案，应用会重启
+   */
+  static async switch(name: string): Promise<void> {
+    try {
+      await invoke('switch_profile', { name });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 从档案列表中移除档案，数据目录保留在磁盘上
+   */
+  static async remove(name: string): Promise<Profile> {
+    try {
+      const result = await invoke<Profile>('delete_profile', { name });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出配置档案API
+ */
+export default ProfileApi;
@@ -7,7 +7,12 @@ import type {
   Project, 
   CreateProjectRequest, 
   UpdateProjectRequest,
-  ProjectQueryParams 
+  ProjectQueryParams,
+  QuotaReport,
+  WorkspaceQuota,
+  BootstrapRequest,
+  RepositoryDetection,
+  ImportedProject
 } from '../types/project';
 
 /**
@@ -45,10 +50,11 @@ export async function getProjects(token: string): Promise<Project[]> {
 /**
  * 获取项目详情
  */
-export async function getProject(projectId: string): Promise<Project | null> {
+export async function getProject(projectId: string, token: string): Promise<Project | null> {
   try {
     const result = await invoke<Project | null>('get_project', {
-      projectId
+      projectId,
+      token
     });
     return result;
   } catch (error) {
@@ -59,10 +65,11 @@ export async function getProject(projectId: string): Promise<Project | null> {
 /**
  * 更新项目
  */
-export async function updateProject(request: UpdateProjectRequest): Promise<Project> {
+export async function updateProject(request: UpdateProjectRequest, token: string): Promise<Project> {
   try {
     const result = await invoke<Project>('update_project', {
-      request
+      request,
+      token
     });
     return result;
   } catch (error) {
@@ -73,10 +80,11 @@ export async function updateProject(request: UpdateProjectRequest): Promise<Proj
 /**
  * 删除项目
  */
-export async function deleteProject(projectId: string): Promise<void> {
+export async function deleteProject(projectId: string, token: string): Promise<void> {
   try {
     await invoke<void>('delete_project', {
-      projectId
+      projectId,
+      token
     });
   } catch (error) {
     throw handleIpcError(error);
@@ -104,4 +112,96 @@ export async function getProjectStats(token: string): Promise<{
   } catch (error) {
     throw handleIpcError(error);
   }
-}
\ No newline at end of file
+}
+
+/**
+ * 检查工作空间磁盘占用和配额
+ */
+export async function getWorkspaceQuotaReport(projectId: string, token: string): Promise<QuotaReport> {
+  try {
+    const result = await invoke<QuotaReport>('get_workspace_quota_report', {
+      projectId,
+      token
+    });
+    return result;
+  } catch (error) {
+    throw handleIpcError(error);
+  }
+}
+
+/**
+ * 监听工作空间文件变化，变化通过 workspace_changed_{projectId} 事件推送
+ */
+export async function watchWorkspace(projectId: string, token: string): Promise<void> {
+  try {
+    await invoke<void>('watch_workspace', {
+      projectId,
+      token
+    });
+  } catch (error) {
+    throw handleIpcError(error);
+  }
+}
+
+/**
+ * 停止监听工作空间
+ */
+export async function unwatchWorkspace(projectId: string, token: string): Promise<void> {
+  try {
+    await invoke<void>('unwatch_workspace', {
+      projectId,
+      token
+    });
+  } catch (error) {
+    throw handleIpcError(error);
+  }
+}
+
+/**
+ * 设置工作空间磁盘配额，传入 null 取消限制
+ */
+export async function setWorkspaceQuota(
+  projectId: string,
+  quota: WorkspaceQuota | null,
+  token: string
+): Promise<void> {
+  try {
+    await invoke<void>('set_workspace_quota', {
+      projectId,
+      quota,
+      token
+    });
+  } catch (error) {
+    throw handleIpcError(error);
+  }
+}
+
+/**
+ * 检测本地Git仓库，返回建议的项目配置
+ */
+export async function detectGitRepository(path: string, token: string): Promise<RepositoryDetection> {
+  try {
+    const result = await invoke<RepositoryDetection>('detect_git_repository', {
+      path,
+      token
+    });
+    return result;
+  } catch (error) {
+    throw handleIpcError(error);
+  }
+}
+
+/**
+ * 从本地仓库路径或远程地址导入项目
+ */
+export async function importProjectFromGit(request: BootstrapRequest, token: string): Promise<ImportedProject> {
+  try {
+    const result = await invoke<ImportedProject>('import_project_from_git', {
+      request,
+      token
+    });
+    return result;
+  } catch (error) {
+    throw handleIpcError(error);
+  }
+}
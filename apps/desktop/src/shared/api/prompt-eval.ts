@@ -0,0 +1,58 @@
+/**
+ * 提示词评估API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { EvalRunReport, EvalSet, EvalSetRecord } from '../types/prompt-eval';
+import { handleIpcError } from './client';
+
+/**
+ * 提示词评估API类
+ */
+export class PromptEvalApi {
+  /**
+   * 列出项目的评估集
+   */
+  static async listSets(projectId: string, token: string): Promise<EvalSetRecord[]> {
+    try {
+      const result = await invoke<EvalSetRecord[]>('list_eval_sets', { projectId, token });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 保存评估集，未指定 evalSetId 时新建
+   */
+  static async saveSet(
+    projectId: string,
+    evalSet: EvalSet,
+    token: string,
+    evalSetId?: string,
+  ): Promise<EvalSetRecord> {
+    try {
+      const result = await invoke<EvalSetRecord>('save_eval_set', {
+        projectId,
+        evalSetId: evalSetId ?? null,
+        evalSet,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 查看评估集的评估报告（按时间倒序）
+   */
+  static async listReports(evalSetId: string, token: string): Promise<EvalRunReport[]> {
+    try {
+      const result = await invoke<EvalRunReport[]>('list_eval_reports', { evalSetId, token });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
@@ -0,0 +1,131 @@
+/**
+ * 定时报告API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type {
+  ProjectReportContent,
+  ProjectReportInfo,
+  ReportOptions,
+  ReportScheduleInfo,
+  ScheduleConfig,
+} from '../types/report';
+import { handleIpcError } from './client';
+
+/**
+ * 定时报告API类
+ */
+export class ReportsApi {
+  /**
+   * 创建报告计划
+   */
+  static async createSchedule(projectId: string, config: ScheduleConfig, token: string): Promise<ReportScheduleInfo> {
+    try {
+      const result = await invoke<ReportScheduleInfo>('create_report_schedule', {
+        projectId,
+        config,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 列出项目的报告计划
+   */
+  static async listSchedules(projectId: string, token: string): Promise<ReportScheduleInfo[]> {
+    try {
+      const result = await invoke<ReportScheduleInfo[]>('list_report_schedules', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 更新报告计划
+   */
+  static async updateSchedule(scheduleId: string, config: ScheduleConfig, token: string): Promise<ReportScheduleInfo> {
+    try {
+      const result = await invoke<ReportScheduleInfo>('update_report_schedule', {
+        scheduleId,
+        config,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 删除报告计划
+   */
+  static async deleteSchedule(scheduleId: string, token: string): Promise<void> {
+    try {
+      await invoke('delete_report_schedule', {
+        scheduleId,
+        token,
+      });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 立即生成项目报告，默认使用 Markdown 内置模板
+   */
+  static async generateReport(projectId: string, token: string, options?: ReportOptions): Promise<ProjectReportContent> {
+    try {
+      const result = await invoke<ProjectReportContent>('generate_project_report', {
+        projectId,
+        options: options ?? null,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 列出项目最近生成的报告
+   */
+  static async listReports(projectId: string, token: string, limit?: number): Promise<ProjectReportInfo[]> {
+    try {
+      const result = await invoke<ProjectReportInfo[]>('list_project_reports', {
+        projectId,
+        limit: limit ?? null,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取报告及其渲染内容
+   */
+  static async getReport(reportId: string, token: string): Promise<ProjectReportContent> {
+    try {
+      const result = await invoke<ProjectReportContent>('get_project_report', {
+        reportId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出定时报告API
+ */
+export default ReportsApi;
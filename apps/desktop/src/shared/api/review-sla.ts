@@ -0,0 +1,64 @@
+/**
+ * 代码审查时限API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { CodeReview, Reviewer, ReviewPriority, ReviewSlaReport } from '../types/review-sla';
+import { handleIpcError } from './client';
+
+/**
+ * 代码审查时限API类
+ */
+export class ReviewSlaApi {
+  /**
+   * 调整审查优先级，按新优先级重新计算截止时间
+   */
+  static async setPriority(reviewId: string, priority: ReviewPriority, token: string): Promise<CodeReview> {
+    try {
+      const result = await invoke<CodeReview>('set_review_priority', {
+        reviewId,
+        priority,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 把审查改派给指定的审查员 Agent 或项目成员，重新开始计时
+   */
+  static async assignReviewer(reviewId: string, reviewer: Reviewer, token: string): Promise<CodeReview> {
+    try {
+      const result = await invoke<CodeReview>('assign_code_reviewer', {
+        reviewId,
+        reviewer,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 立即评估项目中进行中审查的时限
+   */
+  static async evaluate(projectId: string, token: string): Promise<ReviewSlaReport> {
+    try {
+      const result = await invoke<ReviewSlaReport>('evaluate_project_review_sla', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出代码审查时限API
+ */
+export default ReviewSlaApi;
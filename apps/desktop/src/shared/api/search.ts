@@ -0,0 +1,56 @@
+/**
+ * 语义检索API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { IndexReport, SearchHit, SearchSourceType } from '../types/search';
+import { handleIpcError } from './client';
+
+/**
+ * 语义检索API类
+ */
+export class SearchApi {
+  /**
+   * 在项目的需求文档、任务和经验教训中检索，sourceTypes 为空时检索全部来源
+   */
+  static async semanticSearch(
+    projectId: string,
+    query: string,
+    token: string,
+    sourceTypes?: SearchSourceType[],
+    topK?: number,
+  ): Promise<SearchHit[]> {
+    try {
+      const result = await invoke<SearchHit[]>('semantic_search', {
+        projectId,
+        query,
+        sourceTypes,
+        topK,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 立即更新项目的语义检索索引（需要维护者权限）
+   */
+  static async rebuildSearchIndex(projectId: string, token: string): Promise<IndexReport> {
+    try {
+      const result = await invoke<IndexReport>('rebuild_search_index', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出语义检索API
+ */
+export default SearchApi;
@@ -0,0 +1,62 @@
+/**
+ * 安全扫描API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { SecurityFinding, SecurityGateResult, SecurityScanReport } from '../types/security-scan';
+import { handleIpcError } from './client';
+
+/**
+ * 安全扫描API类
+ */
+export class SecurityScanApi {
+  /**
+   * 在执行会话的工作空间中运行依赖漏洞扫描和密钥扫描
+   */
+  static async run(sessionId: string, token: string): Promise<SecurityScanReport> {
+    try {
+      const result = await invoke<SecurityScanReport>('run_session_security_scan', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取执行会话已保存的安全扫描发现
+   */
+  static async getFindings(sessionId: string, token: string): Promise<SecurityFinding[]> {
+    try {
+      const result = await invoke<SecurityFinding[]>('get_session_security_findings', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 按项目质量门禁评估执行会话的安全扫描发现，关闭安全检查时返回 null
+   */
+  static async evaluateGate(sessionId: string, token: string): Promise<SecurityGateResult | null> {
+    try {
+      const result = await invoke<SecurityGateResult | null>('evaluate_session_security_gate', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出安全扫描API
+ */
+export default SecurityScanApi;
@@ -0,0 +1,78 @@
+/**
+ * SLA API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { SlaBreach, SlaBreachedEvent, SlaPolicy } from '../types/sla';
+import { handleIpcError } from './client';
+
+/**
+ * SLA API类
+ */
+export class SlaApi {
+  /**
+   * 获取项目的 SLA 配置，未配置时返回 null
+   */
+  static async getPolicy(projectId: string, token: string): Promise<SlaPolicy | null> {
+    try {
+      const result = await invoke<SlaPolicy | null>('get_project_sla_policy', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 更新项目的 SLA 配置，传入 null 关闭监控
+   */
+  static async updatePolicy(projectId: string, policy: SlaPolicy | null, token: string): Promise<void> {
+    try {
+      await invoke('update_project_sla_policy', {
+        projectId,
+        policy,
+        token,
+      });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 列出项目的 SLA 违约，默认只返回未解决的违约
+   */
+  static async listBreaches(projectId: string, token: string, includeResolved = false): Promise<SlaBreach[]> {
+    try {
+      const result = await invoke<SlaBreach[]>('list_project_sla_breaches', {
+        projectId,
+        includeResolved,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 立即评估项目的 SLA，返回新产生的违约事件
+   */
+  static async evaluate(projectId: string, token: string): Promise<SlaBreachedEvent[]> {
+    try {
+      const result = await invoke<SlaBreachedEvent[]>('evaluate_project_sla', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出SLA API
+ */
+export default SlaApi;
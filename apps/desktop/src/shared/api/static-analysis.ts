@@ -0,0 +1,62 @@
+/**
+ * 静态分析API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { LintFinding, LintGateResult, StaticAnalysisReport } from '../types/static-analysis';
+import { handleIpcError } from './client';
+
+/**
+ * 静态分析API类
+ */
+export class StaticAnalysisApi {
+  /**
+   * 在执行会话的工作空间中运行项目配置的 Linter
+   */
+  static async run(sessionId: string, token: string): Promise<StaticAnalysisReport> {
+    try {
+      const result = await invoke<StaticAnalysisReport>('run_session_static_analysis', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取执行会话已保存的静态分析发现
+   */
+  static async getFindings(sessionId: string, token: string): Promise<LintFinding[]> {
+    try {
+      const result = await invoke<LintFinding[]>('get_session_lint_findings', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 按项目质量门禁评估执行会话的静态分析发现
+   */
+  static async evaluateGate(sessionId: string, token: string): Promise<LintGateResult> {
+    try {
+      const result = await invoke<LintGateResult>('evaluate_session_lint_gate', {
+        sessionId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出静态分析API
+ */
+export default StaticAnalysisApi;
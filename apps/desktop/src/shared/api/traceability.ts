@@ -0,0 +1,91 @@
+/**
+ * 需求追踪API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { CoverageReport, RequirementCoverage, RequirementSection } from '../types/traceability';
+import { handleIpcError } from './client';
+
+/**
+ * 需求追踪API类
+ */
+export class TraceabilityApi {
+  /**
+   * 获取需求文档的章节
+   */
+  static async getRequirementSections(documentId: string, token: string): Promise<RequirementSection[]> {
+    try {
+      const result = await invoke<RequirementSection[]>('get_requirement_sections', {
+        documentId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 记录任务由需求文档的某个章节生成
+   */
+  static async linkTaskToRequirement(
+    documentId: string,
+    sectionId: string,
+    taskId: string,
+    token: string,
+  ): Promise<RequirementCoverage> {
+    try {
+      const result = await invoke<RequirementCoverage>('link_task_to_requirement', {
+        documentId,
+        sectionId,
+        taskId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 取消任务与需求章节的关联，返回关联是否存在
+   */
+  static async unlinkTaskFromRequirement(
+    documentId: string,
+    sectionId: string,
+    taskId: string,
+    token: string,
+  ): Promise<boolean> {
+    try {
+      const result = await invoke<boolean>('unlink_task_from_requirement', {
+        documentId,
+        sectionId,
+        taskId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取项目的需求覆盖报告
+   */
+  static async getCoverageReport(projectId: string, token: string): Promise<CoverageReport> {
+    try {
+      const result = await invoke<CoverageReport>('get_requirement_coverage_report', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出需求追踪API
+ */
+export default TraceabilityApi;
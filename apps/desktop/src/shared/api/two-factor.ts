@@ -0,0 +1,83 @@
+/**
+ * 两步验证API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { TotpEnrollment, TwoFactorStatus } from '../types/two-factor';
+import { handleIpcError } from './client';
+
+/**
+ * 两步验证API类
+ */
+export class TwoFactorApi {
+  /**
+   * 获取当前用户的两步验证状态
+   */
+  static async getStatus(token: string): Promise<TwoFactorStatus> {
+    try {
+      return await invoke<TwoFactorStatus>('get_two_factor_status', { token });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 开始登记，返回密钥和二维码配置地址
+   */
+  static async beginEnrollment(token: string): Promise<TotpEnrollment> {
+    try {
+      return await invoke<TotpEnrollment>('begin_two_factor_enrollment', { token });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 确认登记，返回只显示一次的备用恢复码
+   */
+  static async confirmEnrollment(code: string, token: string): Promise<string[]> {
+    try {
+      return await invoke<string[]>('confirm_two_factor_enrollment', { code, token });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 重新生成备用恢复码
+   */
+  static async regenerateBackupCodes(code: string, token: string): Promise<string[]> {
+    try {
+      return await invoke<string[]>('regenerate_backup_codes', { code, token });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 停用两步验证
+   */
+  static async disable(code: string, token: string): Promise<TwoFactorStatus> {
+    try {
+      return await invoke<TwoFactorStatus>('disable_two_factor', { code, token });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 设置是否强制用户使用两步验证（组织所有者）
+   */
+  static async setRequired(userId: string, required: boolean, token: string): Promise<TwoFactorStatus> {
+    try {
+      return await invoke<TwoFactorStatus>('set_two_factor_required', { userId, required, token });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出两步验证API
+ */
+export default TwoFactorApi;
@@ -0,0 +1,101 @@
+/**
+ * 自动更新API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { InstallHistory, ScheduledInstall, UpdateInfo } from '../types/update';
+import { handleIpcError } from './client';
+
+/**
+ * 自动更新API类
+ */
+export class UpdateApi {
+  /**
+   * 按设置的更新通道检查更新
+   */
+  static async check(): Promise<UpdateInfo> {
+    try {
+      const result = await invoke<UpdateInfo>('check_for_updates');
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 立即下载并安装更新，完成后应用会重启
+   */
+  static async install(): Promise<void> {
+    try {
+      await invoke('install_update');
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 安排在指定时间安装更新
+   */
+  static async schedule(installAt: number): Promise<ScheduledInstall> {
+    try {
+      const result = await invoke<ScheduledInstall>('schedule_update_install', {
+        installAt,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取已安排的延期安装
+   */
+  static async getScheduled(): Promise<ScheduledInstall | null> {
+    try {
+      const result = await invoke<ScheduledInstall | null>('get_scheduled_update');
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 取消已安排的延期安装
+   */
+  static async cancelScheduled(): Promise<ScheduledInstall | null> {
+    try {
+      const result = await invoke<ScheduledInstall | null>('cancel_scheduled_update');
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 获取安装历史
+   */
+  static async getHistory(): Promise<InstallHistory> {
+    try {
+      const result = await invoke<InstallHistory>('get_update_history');
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 回滚到上一次安装前的版本，完成后应用会重启
+   */
+  static async rollback(): Promise<void> {
+    try {
+      await invoke('rollback_update');
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出自动更新API
+ */
+export default UpdateApi;
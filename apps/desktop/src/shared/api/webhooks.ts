@@ -0,0 +1,68 @@
+/**
+ * Webhook 接入 API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { IngestOutcome, WebhookDeadLetter } from '../types/webhook';
+import { handleIpcError } from './client';
+
+/**
+ * Webhook 接入 API类
+ */
+export class WebhooksApi {
+  /**
+   * 列出项目的 webhook 死信，默认只返回待处理的死信
+   */
+  static async listDeadLetters(
+    projectId: string,
+    token: string,
+    includeResolved = false,
+    limit?: number
+  ): Promise<WebhookDeadLetter[]> {
+    try {
+      const result = await invoke<WebhookDeadLetter[]>('list_webhook_dead_letters', {
+        projectId,
+        includeResolved,
+        limit,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 重放死信，仍无法映射时返回 dead_lettered
+   */
+  static async replayDeadLetter(deadLetterId: string, token: string): Promise<IngestOutcome> {
+    try {
+      const result = await invoke<IngestOutcome>('replay_webhook_dead_letter', {
+        deadLetterId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 丢弃死信，不再重放
+   */
+  static async discardDeadLetter(deadLetterId: string, token: string): Promise<void> {
+    try {
+      await invoke('discard_webhook_dead_letter', {
+        deadLetterId,
+        token,
+      });
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出Webhook API
+ */
+export default WebhooksApi;
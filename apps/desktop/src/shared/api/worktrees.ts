@@ -0,0 +1,47 @@
+/**
+ * 执行会话工作树API客户端
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { WorktreeInfo } from '../types/worktree';
+import { handleIpcError } from './client';
+
+/**
+ * 工作树API类
+ */
+export class WorktreesApi {
+  /**
+   * 列出项目的执行会话工作树
+   */
+  static async listProjectWorktrees(projectId: string, token: string): Promise<WorktreeInfo[]> {
+    try {
+      const result = await invoke<WorktreeInfo[]>('list_project_worktrees', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+
+  /**
+   * 清理超出空闲上限的工作树，返回被删除的路径
+   */
+  static async pruneProjectWorktrees(projectId: string, token: string): Promise<string[]> {
+    try {
+      const result = await invoke<string[]>('prune_project_worktrees', {
+        projectId,
+        token,
+      });
+      return result;
+    } catch (error) {
+      throw handleIpcError(error);
+    }
+  }
+}
+
+/**
+ * 默认导出工作树API
+ */
+export default WorktreesApi;
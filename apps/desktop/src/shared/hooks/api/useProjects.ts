@@ -46,10 +46,12 @@ export function useProjects() {
  * 获取项目详情
  */
 export function useProject(projectId: string | undefined) {
+  const { token } = useAuth();
+
   return useQuery({
     queryKey: PROJECT_QUERY_KEYS.detail(projectId || ''),
-    queryFn: () => getProject(projectId!),
-    enabled: !!projectId,
+    queryFn: () => getProject(projectId!, token!),
+    enabled: !!projectId && !!token,
     staleTime: 1000 * 60 * 5, // 5分钟缓存
   });
 }
@@ -110,10 +112,11 @@ export function useCreateProject() {
  */
 export function useUpdateProject() {
   const queryClient = useQueryClient();
+  const { token } = useAuth();
   const { showToast } = useToast();
 
   return useMutation({
-    mutationFn: (request: UpdateProjectRequest) => updateProject(request),
+    mutationFn: (request: UpdateProjectRequest) => updateProject(request, token!),
     onSuccess: (updatedProject) => {
       // 更新缓存
       queryClient.invalidateQueries({ queryKey: PROJECT_QUERY_KEYS.lists() });
@@ -144,10 +147,11 @@ export function useUpdateProject() {
  */
 export function useDeleteProject() {
   const queryClient = useQueryClient();
+  const { token } = useAuth();
   const { showToast } = useToast();
 
   return useMutation({
-    mutationFn: (projectId: string) => deleteProject(projectId),
+    mutationFn: (projectId: string) => deleteProject(projectId, token!),
     onSuccess: (_, projectId) => {
       // 更新缓存
       queryClient.invalidateQueries({ queryKey: PROJECT_QUERY_KEYS.lists() });
@@ -175,12 +179,13 @@ export function useDeleteProject() {
  */
 export function useBatchUpdateProjectStatus() {
   const queryClient = useQueryClient();
+  const { token } = useAuth();
   const { showToast } = useToast();
 
   return useMutation({
     mutationFn: async (requests: { projectId: string; status: string }[]) => {
       const promises = requests.map(({ projectId, status }) =>
-        updateProject({ project_id: projectId, status })
+        updateProject({ project_id: projectId, status }, token!)
       );
       return Promise.all(promises);
     },
@@ -0,0 +1,46 @@
+/**
+ * 验收标准相关的类型定义
+ * 对应后端 acceptance 模块
+ */
+
+// 验收标准的验证状态
+export type CriterionStatus = 'pending' | 'verified' | 'failed';
+
+// 证据类型：执行日志、执行工件或文字说明
+export type EvidenceKind = 'log' | 'artifact' | 'note';
+
+// 验证证据
+export interface CriterionEvidence {
+  kind: EvidenceKind;
+  reference: string;                  // 日志ID、工件ID或说明内容
+  description?: string;
+}
+
+// 核对清单中的一项
+export interface ChecklistItem {
+  criterion: string;
+  status: CriterionStatus;
+  evidence: CriterionEvidence[];
+  verified_by_agent_id?: string;
+  notes?: string;
+  signed_off_by?: string;
+  signed_off_at?: string;
+  updated_at?: string;               // 尚未验证时为空
+}
+
+// 任务的验收标准核对清单
+export interface AcceptanceChecklist {
+  task_id: string;
+  items: ChecklistItem[];
+  verified_count: number;
+  unverified: string[];              // 尚未验证通过的验收标准
+}
+
+// 强制完成后的任务记录
+export interface CompletedTask {
+  task_id: string;
+  project_id: string;
+  title: string;
+  status: string;
+  completed_at?: string;
+}
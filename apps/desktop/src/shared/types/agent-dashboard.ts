@@ -0,0 +1,92 @@
+/**
+ * 智能体性能仪表盘相关的类型定义
+ * 对应后端 codex-multi-agent 的仪表盘类型
+ */
+
+export type MetricsPeriod = 'day' | 'week' | 'month' | 'quarter' | 'all';
+
+// 定期汇总的周期性能快照
+export interface PerformanceSnapshot {
+  period_start: string;
+  period_end: string;
+  tasks_completed: number;
+  tasks_successful: number;
+  avg_completion_hours: number;
+  avg_code_quality: number;               // 0-10
+  performance_score: number;              // 0-10
+}
+
+// 智能体在统计周期内的性能汇总
+export interface AgentMetricsReport {
+  agent_id: string;
+  agent_name: string;
+  period: MetricsPeriod;
+  period_start?: string | null;           // 全部历史时为空
+  period_end: string;
+  tasks_completed: number;
+  tasks_successful: number;
+  tasks_failed: number;
+  success_rate: number;                   // 0.0-1.0
+  avg_completion_minutes?: number | null;
+  avg_quality_score?: number | null;      // 0.0-1.0
+  performance_score: number;              // 0-10
+  task_type_breakdown: Record<string, number>;
+  technologies: Record<string, number>;
+  trend: PerformanceSnapshot[];           // 按时间升序
+}
+
+// 团队排行榜条目
+export interface LeaderboardEntry {
+  rank: number;
+  agent_id: string;
+  agent_name: string;
+  tasks_completed: number;
+  tasks_successful: number;
+  success_rate: number;
+  avg_completion_minutes?: number | null;
+  avg_quality_score?: number | null;
+  performance_score: number;
+}
+
+// 项目团队排行榜
+export interface TeamLeaderboard {
+  project_id: string;
+  period: MetricsPeriod;
+  generated_at: string;
+  entries: LeaderboardEntry[];            // 按绩效评分降序
+}
+
+// 工作历史条目
+export interface WorkHistoryEntry {
+  history_id: string;
+  agent_id: string;
+  task_id: string;
+  task_title?: string | null;             // 任务已删除时为空
+  task_type: string;
+  started_at: string;
+  completed_at?: string | null;
+  success?: boolean | null;
+  completion_time_minutes?: number | null;
+  quality_score?: number | null;
+  technologies_used: string[];
+  error_message?: string | null;
+}
+
+// 分页请求参数，页码从1开始
+export interface PaginationParams {
+  page?: number | null;
+  page_size?: number | null;
+  sort_by?: string | null;
+  sort_order?: 'asc' | 'desc' | null;
+}
+
+// 分页响应
+export interface PaginationResponse<T> {
+  items: T[];
+  total_count: number;
+  current_page: number;
+  page_size: number;
+  total_pages: number;
+  has_next_page: boolean;
+  has_previous_page: boolean;
+}
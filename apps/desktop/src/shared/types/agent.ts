@@ -49,6 +49,13 @@ export interface Agent {
   performance_trend?: Record<string, any>;
 }
 
+// 智能体命令审批策略，存放在 config.approval_policy，未配置时沿用全局审批行为
+export interface AgentApprovalPolicy {
+  auto_approve_commands: string[];    // 自动批准的安全命令，按词前缀匹配，例如 "cargo test"
+  always_ask_patterns: string[];      // 总是需要人工确认的命令模式，* 匹配任意字符
+  network_access: boolean;            // 是否允许命令访问网络
+}
+
 // 创建智能体请求
 export interface CreateAgentRequest {
   name: string;
@@ -102,6 +109,15 @@ export interface AgentPerformanceMetrics {
   created_at: string;
 }
 
+// 导入智能体包时同名冲突的处理方式
+export type AgentBundleConflictStrategy = 'fail' | 'rename' | 'overwrite';
+
+export interface ImportAgentBundleResult {
+  agent: Agent;
+  renamed_from?: string;
+  overwritten: boolean;
+}
+
 // 智能体能力标签映射
 export const AGENT_CAPABILITY_LABELS: Record<AgentCapability, string> = {
   [AgentCapability.FrontendDevelopment]: "前端开发",
@@ -0,0 +1,37 @@
+/**
+ * API 密钥相关的类型定义
+ * 对应后端 api_keys 模块
+ */
+
+export type ApiScope = 'projects:read' | 'tasks:read' | 'tasks:write';
+
+// API 密钥（不含密钥明文和摘要）
+export interface ApiKey {
+  key_id: string;
+  user_id: string;
+  project_id?: string | null;              // 为空时可访问用户有权限的全部项目
+  name: string;
+  key_prefix: string;
+  scopes: ApiScope[];
+  rate_limit_per_minute: number;
+  request_count: number;
+  throttled_count: number;                 // 因超出速率限制被拒绝的请求数
+  last_used_at?: string | null;
+  expires_at?: string | null;
+  revoked_at?: string | null;
+  created_at: string;
+}
+
+// 新创建的 API 密钥，secret 只在创建时返回
+export interface IssuedApiKey {
+  key: ApiKey;
+  secret: string;
+}
+
+export interface CreateApiKeyRequest {
+  name: string;
+  scopes: ApiScope[];
+  projectId?: string;
+  rateLimitPerMinute?: number;             // 默认 60
+  expiresInDays?: number;                  // 为空时不过期
+}
@@ -0,0 +1,74 @@
+/**
+ * 执行工件相关的类型定义
+ * 对应后端 SessionArtifact
+ */
+
+// 工件类型
+export type ArtifactType =
+  | 'source_code'
+  | 'test_file'
+  | 'config_file'
+  | 'documentation'
+  | 'database_script'
+  | 'build_script'
+  | 'binary'
+  | 'patch'
+  | 'other';
+
+// 执行会话产出的工件
+export interface SessionArtifact {
+  artifact_id: string;
+  session_id: string;
+  name: string;
+  artifact_type: ArtifactType;
+  size_bytes: number;
+  checksum: string;                   // 内容 SHA-256 校验和
+  description?: string;
+  created_at: string;
+}
+
+// 文件变更类型
+export type FileChangeType = 'add' | 'delete' | 'update';
+
+// 差异行
+export interface SessionDiffLine {
+  kind: 'context' | 'addition' | 'deletion';
+  content: string;
+  old_line_number?: number;
+  new_line_number?: number;
+}
+
+// 差异块
+export interface SessionDiffHunk {
+  header: string;
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+  lines: SessionDiffLine[];
+}
+
+// 单个文件的差异
+export interface SessionFileDiff {
+  path: string;
+  move_path?: string;                 // 重命名后的路径
+  change_type: FileChangeType;
+  is_binary: boolean;
+  additions: number;
+  deletions: number;
+  hunks: SessionDiffHunk[];
+}
+
+// 执行会话 base_commit..final_commit 的代码差异
+export interface SessionDiff {
+  session_id: string;
+  base_commit: string;
+  final_commit: string;
+  files: SessionFileDiff[];
+  stats: {
+    files_changed: number;
+    additions: number;
+    deletions: number;
+  };
+  captured_at: string;
+}
@@ -0,0 +1,31 @@
+/**
+ * 项目审计包相关的类型定义
+ * 对应后端 codex-database 的 audit_bundle 模块
+ */
+
+// 归档中的文件及其校验和
+export interface BundleFile {
+  path: string;
+  sha256: string;
+  size_bytes: number;
+}
+
+// 审计包中各类记录的数量
+export interface AuditCounts {
+  tasks: number;
+  events: number;
+  conflicts: number;
+  decisions: number;
+  reviews: number;
+  executions: number;
+}
+
+// 审计包清单
+export interface AuditBundleManifest {
+  format_version: number;
+  project_id: string;
+  project_name: string;
+  generated_at: string;
+  counts: AuditCounts;
+  files: BundleFile[];
+}
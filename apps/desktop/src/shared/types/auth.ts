@@ -7,6 +7,7 @@
 export interface LoginRequest {
   email: string;
   password: string;
+  two_factor_code?: string;  // 两步验证码或备用恢复码
 }
 
 // 注册请求
@@ -22,6 +23,7 @@ export interface AuthResponse {
   refresh_token: string;
   user: CurrentUser;
   expires_in: number; // 过期时间（秒数）
+  two_factor_setup_required: boolean; // 被要求使用两步验证但尚未登记
 }
 
 // 当前用户信息
@@ -72,6 +74,26 @@ export interface UpdateUserRequest {
   email?: string;
 }
 
+// 单点登录提供方类型
+export type OidcProviderKind = 'google' | 'github' | 'oidc';
+
+// 单点登录提供方
+export interface OidcProvider {
+  id: string;
+  name: string;
+  kind: OidcProviderKind;
+}
+
+// 已关联的外部身份
+export interface LinkedIdentity {
+  identity_id: string;
+  provider: string;
+  email?: string;
+  display_name?: string;
+  created_at: string;
+  last_login_at?: string;
+}
+
 // 认证操作
 export interface AuthActions {
   login: (credentials: LoginRequest) => Promise<void>;
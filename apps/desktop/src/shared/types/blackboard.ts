@@ -0,0 +1,39 @@
+/**
+ * 项目黑板相关的类型定义
+ * 对应后端 blackboard 模块
+ */
+
+// 条目分类
+export type SharedContextCategory = 'decision' | 'api_spec' | 'naming_convention' | 'note';
+
+// 条目写入者
+export type EntryAuthor =
+  | { type: 'user'; id: string }
+  | { type: 'agent'; id: string };
+
+// 黑板条目的当前值
+export interface BlackboardEntry {
+  project_id: string;
+  key: string;
+  category: SharedContextCategory;
+  value: string;
+  version: number;
+  include_in_prompts: boolean;       // 是否纳入任务分解和执行提示词
+  author?: EntryAuthor;
+  updated_at: string;
+}
+
+// 黑板条目的历史版本
+export interface BlackboardEntryVersion {
+  entry_id: string;
+  project_id: string;
+  key: string;
+  version: number;
+  category: SharedContextCategory;
+  value: string;
+  include_in_prompts: boolean;
+  deleted: boolean;                  // 该版本为删除记录
+  author_user_id?: string;
+  author_agent_id?: string;
+  created_at: string;
+}
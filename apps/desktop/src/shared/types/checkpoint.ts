@@ -0,0 +1,31 @@
+/**
+ * 执行会话检查点相关的类型定义
+ * 对应后端 session_checkpoint 模块
+ */
+
+// Agent 上报的中间状态
+export interface CheckpointData {
+  completed_steps: string[];
+  current_step?: string | null;       // 中断时正在进行的步骤
+  scratch_notes: string[];            // 工作笔记
+  partial_diff?: string | null;       // 尚未提交的差异（git diff 输出）
+  last_commit?: string | null;        // 工作分支上最近一次提交
+  completion_percentage?: number | null; // 完成度（0-1）
+}
+
+// 保存在执行会话上的检查点
+export interface SessionCheckpoint extends CheckpointData {
+  sequence: number;                   // 检查点序号，恢复后的会话继续递增
+  saved_at: string;
+}
+
+// 从检查点恢复的执行会话
+export interface ResumedSessionInfo {
+  session_id: string;
+  task_id: string;
+  agent_id: string;
+  git_branch: string;
+  base_commit?: string | null;
+  checkpoint: SessionCheckpoint;
+  prompt: string;                     // 根据检查点重建的提示词
+}
@@ -0,0 +1,49 @@
+/**
+ * CI 集成相关的类型定义
+ * 对应后端 ci_integration 模块和协议类型 CiStatus
+ */
+
+// CI 提供方
+export type CiProvider = 'github_actions' | 'gitlab_ci';
+
+// CI 状态，出现在协议的 TaskInfo.ci_status 和 PullRequestInfo.ci_status 中
+export type CiStatus = 'pending' | 'running' | 'passed' | 'failed' | 'cancelled';
+
+// 审查决策
+export type ReviewDecision = 'approved' | 'changes_requested' | 'rejected';
+
+// CI 检查结果
+export interface CiCheckRun {
+  check_run_id: string;
+  project_id: string;
+  provider: CiProvider;
+  external_id: string;                // 例如 workflow_run:123、job:456
+  name: string;
+  commit_sha: string;
+  branch: string | null;
+  pull_request_url: string | null;
+  execution_session_id: string | null; // 映射到的执行会话
+  review_id: string | null;           // 映射到的代码审查
+  status: CiStatus;
+  details_url: string | null;
+  started_at: string | null;
+  completed_at: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+// 代码审查
+export interface CodeReview {
+  review_id: string;
+  task_id: string;
+  execution_session_id: string;
+  reviewer_agent_id: string;
+  pull_request_url: string;
+  source_branch: string;
+  target_branch: string;
+  status: string;
+  decision: ReviewDecision | null;
+  overall_comment: string | null;
+  created_at: string;
+  reviewed_at: string | null;
+}
@@ -0,0 +1,18 @@
+/**
+ * 代码归属相关的类型定义
+ * 对应后端 code_ownership 模块
+ */
+
+import type { Reviewer } from './review-sla';
+
+// 一条归属规则，路径模式语义与 CODEOWNERS 一致，多条命中时以最后一条为准
+export interface OwnershipRule {
+  pattern: string;
+  owners: Reviewer[];                     // 负责人：Agent 或项目成员
+  capabilities: string[];                 // 审查这些路径需要的 Agent 能力
+}
+
+// 项目的代码归属规则
+export interface CodeOwnership {
+  rules: OwnershipRule[];
+}
@@ -0,0 +1,44 @@
+/**
+ * 命令策略相关的类型定义
+ * 对应后端 command_policy 模块和 command_audit_logs 表
+ */
+
+// 风险等级
+export type RiskLevel = 'low' | 'medium' | 'high' | 'critical';
+
+// 规则命中后的动作
+export type RuleAction = 'allow' | 'escalate' | 'deny';
+
+// 判定结果
+export type CommandDecision = 'auto_approve' | 'escalate' | 'deny';
+
+// 命中的规则
+export interface RuleMatch {
+  rule: string;                       // 规则名称，例如 write_outside_workspace、force_push
+  action: RuleAction;
+  risk: RiskLevel;
+  reason: string;
+}
+
+// 命令分类结果，自动批准或拒绝时通过 conversation_command_policy_{对话ID} 事件推送
+export interface CommandClassification {
+  command: string;
+  risk: RiskLevel;
+  decision: CommandDecision;
+  matched_rules: RuleMatch[];
+  audit_id?: string;
+}
+
+// 命令审批审计记录
+export interface CommandAuditLog {
+  audit_id: string;
+  project_id?: string;
+  agent_id?: string;
+  conversation_id?: string;
+  command: string;
+  working_dir: string;
+  risk_level: RiskLevel;
+  decision: CommandDecision;
+  matched_rules: RuleMatch[];
+  created_at: string;
+}
@@ -0,0 +1,74 @@
+/**
+ * 冲突处理中心相关的类型定义
+ * 对应后端 conflicts 命令
+ */
+
+export type ConflictType = 'git_merge' | 'resource' | 'task_dependency' | 'capability' | 'timeline';
+
+export type ConflictSeverity = 'low' | 'medium' | 'high' | 'critical';
+
+export type ConflictStatus = 'detected' | 'analyzing' | 'escalated' | 'resolving' | 'resolved' | 'ignored';
+
+export type DecisionType = 'approve' | 'reject' | 'modify' | 'escalate';
+
+// 冲突列表过滤条件
+export interface ConflictListFilter {
+  conflict_type?: ConflictType;
+  severity?: ConflictSeverity;
+  status?: ConflictStatus;
+  escalated_to_human?: boolean;
+}
+
+export interface Conflict {
+  conflict_id: string;
+  conflict_type: ConflictType;
+  severity: ConflictSeverity;
+  title: string;
+  description: string;
+  related_entities: Record<string, unknown>;
+  affected_tasks: string[];
+  affected_agents: string[];
+  status: ConflictStatus;
+  escalated_to_human: boolean;
+  assigned_user_id?: string | null;
+  resolution_strategy?: string | null;
+  resolution_note?: string | null;
+  auto_resolved: boolean;
+  detected_at: string;
+  escalated_at?: string | null;
+  resolved_at?: string | null;
+}
+
+// 人工决策
+export interface HumanDecision {
+  decision_id: string;
+  conflict_id: string;
+  user_id: string;
+  decision_type: DecisionType;
+  decision_data?: Record<string, unknown> | null;
+  reasoning?: string | null;
+  affected_entities: Record<string, unknown>;
+  follow_up_actions: string[];
+  created_at: string;
+}
+
+// 记录人工决策的输入
+export interface HumanDecisionInput {
+  decision_type: DecisionType;
+  decision_data?: Record<string, unknown> | null;
+  reasoning?: string | null;
+  follow_up_actions?: string[];
+}
+
+// 冲突分页结果，页码从0开始
+export interface ConflictPage {
+  conflicts: Conflict[];
+  page: number;
+  page_size: number;
+  total_pages: number;
+}
+
+export interface ConflictDetail {
+  conflict: Conflict;
+  decisions: HumanDecision[];    // 按时间倒序
+}
@@ -0,0 +1,46 @@
+/**
+ * 项目上下文快照比较相关的类型定义
+ * 对应后端 context::diff 模块
+ */
+
+export type ContextRiskLevel = 'low' | 'medium' | 'high' | 'critical';
+export type ContextRiskType = 'technical' | 'timeline' | 'resource' | 'dependency' | 'quality' | 'security' | 'business';
+
+// 风险变化
+export interface RiskChange {
+  risk_id: string;
+  description: string;
+  risk_type: ContextRiskType;
+  risk_score: number;
+}
+
+// 指标变化
+export interface MetricChange {
+  metric: string;                     // 指标标识，如 test_coverage
+  label: string;                      // 显示名称
+  previous: number;
+  current: number;
+}
+
+// 新完成的任务
+export interface CompletedTaskChange {
+  task_id: string;
+  title: string;
+}
+
+// 共享上下文条目变化
+export interface SharedContextChange {
+  key: string;
+  version: number;                    // 当前版本
+  added: boolean;                     // 上次快照中没有该条目
+}
+
+// 两次分解之间项目上下文的变化
+export interface ContextDiff {
+  new_risks: RiskChange[];
+  resolved_risks: RiskChange[];
+  risk_level_change: [ContextRiskLevel, ContextRiskLevel] | null;  // [之前, 现在]
+  changed_metrics: MetricChange[];
+  completed_tasks: CompletedTaskChange[];
+  shared_context_changes: SharedContextChange[];
+}
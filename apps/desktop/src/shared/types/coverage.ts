@@ -0,0 +1,35 @@
+/**
+ * 测试覆盖率相关的类型定义
+ * 对应后端 coverage 模块
+ */
+
+export type CoverageFormat = 'lcov' | 'cobertura';
+
+// 单个文件相对基准提交的覆盖率变化
+export interface FileCoverageDelta {
+  path: string;
+  line_rate: number;                    // 0.0-1.0
+  base_line_rate?: number | null;       // 基准报告中没有该文件时为空
+  delta?: number | null;
+}
+
+// 执行会话的覆盖率及相对基准提交的变化
+export interface CoverageDelta {
+  session_id: string;
+  commit_sha?: string | null;
+  line_rate: number;
+  lines_total: number;
+  lines_covered: number;
+  base_line_rate?: number | null;       // 没有基准报告时为空
+  delta?: number | null;
+  files: FileCoverageDelta[];           // 下降最多的在前，新文件排在最后
+}
+
+// 覆盖率门禁结果
+export interface CoverageGateResult {
+  session_id: string;
+  line_rate: number;
+  threshold: number;                    // 生效的覆盖率门槛
+  delta?: number | null;
+  passed: boolean;
+}
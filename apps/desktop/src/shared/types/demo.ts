@@ -0,0 +1,28 @@
+/**
+ * 演示工作区相关的类型定义
+ * 对应后端 DemoWorkspaceSummary / DemoExecutionLog
+ */
+
+import type { ExecutionEventType } from './execution';
+
+// 演示工作区概况
+export interface DemoWorkspaceSummary {
+  project_ids: string[];              // 演示项目ID
+  agent_count: number;                // 智能体数量
+  task_count: number;                 // 任务数量
+  conflict_count: number;             // 冲突数量
+  session_count: number;              // 执行会话数量
+  log_count: number;                  // 执行日志数量
+  event_count: number;                // 领域事件数量
+  scripted_session_id?: string;       // 可回放的演示执行会话
+}
+
+// 演示执行会话回放的日志事件（事件名 demo_execution_log）
+export interface DemoExecutionLog {
+  session_id: string;
+  log_level: string;
+  event_type: ExecutionEventType;
+  message: string;
+  timestamp_ms: number;
+  is_last: boolean;                   // 是否为最后一条日志
+}
@@ -0,0 +1,67 @@
+/**
+ * 许可证与依赖审计相关的类型定义
+ * 对应后端 dependency_audit 模块
+ */
+
+// 依赖所属生态
+export type Ecosystem = 'crates_io' | 'npm' | 'pypi' | 'go';
+
+// 依赖包
+export interface Dependency {
+  name: string;
+  version: string;
+  ecosystem: Ecosystem;
+  manifest: string;                   // 声明该依赖的清单文件（相对工作空间）
+  license: string | null;             // SPDX 许可证表达式
+}
+
+// 已知漏洞
+export interface Vulnerability {
+  id: string;
+  summary: string | null;
+  severity: string | null;            // CRITICAL、HIGH、MODERATE、LOW
+  aliases: string[];
+  fixed_versions: string[];
+}
+
+// 许可证策略
+export interface LicensePolicy {
+  denied: string[];                   // 禁止使用的许可证
+  flag_unknown: boolean;              // 无法确定许可证时是否视为问题
+}
+
+// 审计选项
+export interface DependencyAuditOptions {
+  license_policy?: LicensePolicy;
+  create_remediation_tasks?: boolean; // 为发现的问题创建修复子任务
+}
+
+export interface LicenseIssue {
+  dependency: Dependency;
+  reason: string;
+}
+
+export interface VulnerableDependency {
+  dependency: Dependency;
+  vulnerabilities: Vulnerability[];
+}
+
+// 依赖审计报告，同时保存为 dependency-audit.json 工件
+export interface DependencyAuditReport {
+  project_id: string;
+  scanned_at: string;
+  manifests: string[];
+  dependency_count: number;
+  licenses: Record<string, number>;   // 按许可证统计，未知许可证记为 UNKNOWN
+  license_issues: LicenseIssue[];
+  vulnerable_dependencies: VulnerableDependency[];
+  vulnerabilities_checked: boolean;
+  vulnerability_error: string | null; // 漏洞查询失败的原因
+}
+
+// 审计操作完成后记录在 OperationProgress.result 中
+export interface DependencyAuditOutcome {
+  report: DependencyAuditReport;
+  artifact_id: string;
+  remediation_task_ids: string[];
+}
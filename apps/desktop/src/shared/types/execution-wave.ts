@@ -0,0 +1,60 @@
+/**
+ * 并行执行波次相关的类型定义
+ * 对应后端 codex-database 的 parallel_planner 模块
+ */
+
+// 任务在波次视图中的状态
+export type WaveTaskState = 'running' | 'ready' | 'waiting' | 'blocked';
+
+// 波次中的任务
+export interface WaveTask {
+  task_id: string;
+  title: string;
+  priority: string;
+  agent_id: string | null;
+  scope_path: string | null;       // 为空表示整个仓库
+  state: WaveTaskState;
+  session_id: string | null;       // 运行中的执行会话
+  waiting_on: string[];            // 未完成的前置任务
+  blocked_reason: string | null;
+}
+
+// 执行波次，同一波次内的任务互不依赖
+export interface ExecutionWave {
+  index: number;
+  tasks: WaveTask[];
+}
+
+// 项目的实时波次视图
+export interface WaveView {
+  project_id: string;
+  generated_at: string;
+  waves: ExecutionWave[];
+  running_sessions: number;
+  max_parallel_sessions: number;
+  completed_tasks: number;
+}
+
+// 本轮启动的执行会话
+export interface LaunchedSession {
+  task_id: string;
+  session_id: string;
+  agent_id: string;
+  git_branch: string;
+  worktree_path: string | null;
+}
+
+// 本轮暂不启动的就绪任务
+export interface DeferredTask {
+  task_id: string;
+  reason: string;
+}
+
+// 一轮启动的结果
+export interface LaunchReport {
+  launched: LaunchedSession[];
+  deferred: DeferredTask[];
+}
+
+// 波次视图更新事件
+export const EXECUTION_WAVES_EVENT = 'execution_waves_updated';
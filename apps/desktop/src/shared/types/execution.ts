@@ -0,0 +1,26 @@
+/**
+ * 执行日志相关的类型定义
+ * 对应后端 codex_multi_agent::EventType
+ */
+
+// 执行日志事件类型（execution_logs.event_type 的取值全集）
+export type ExecutionEventType =
+  | 'session_started'
+  | 'session_completed'
+  | 'session_failed'
+  | 'progress'
+  | 'git_operation'
+  | 'git_checkout'
+  | 'file_change'
+  | 'llm_request'
+  | 'llm_response'
+  | 'tool_call'
+  | 'test_run'
+  | 'tests_passed'
+  | 'tests_failed'
+  | 'compilation'
+  | 'deployment'
+  | 'code_analysis'
+  | 'dependency_install'
+  | 'environment_setup'
+  | 'error';
@@ -0,0 +1,13 @@
+/**
+ * 流式导出相关的类型定义
+ * 对应后端 codex-database 的 export 模块
+ */
+
+// 导出格式
+export type ExportFormat = 'ndjson' | 'csv';
+
+// 导出结果
+export interface ExportSummary {
+  rows: number;   // 数据行数，不含 CSV 表头
+  bytes: number;
+}
@@ -0,0 +1,54 @@
+/**
+ * 不稳定测试相关的类型定义
+ * 对应后端 flaky_tests 模块
+ */
+
+export type TestOutcome = 'passed' | 'failed' | 'skipped';
+
+// 执行会话上报的单个测试用例结果
+export interface TestCaseReport {
+  name: string;
+  outcome: TestOutcome;
+  duration_ms?: number | null;
+}
+
+// 不稳定测试的判定参数
+export interface FlakyTestConfig {
+  window: number;               // 每个测试参与判定的最近运行次数
+  min_runs: number;             // 判定所需的最少运行次数
+  min_flip_rate: number;        // 判定为不稳定的最低结果翻转比例
+  max_failure_rate: number;     // 失败率达到该值时视为持续失败
+}
+
+export type TestStability = 'stable' | 'flaky' | 'failing' | 'insufficient_data';
+
+// 单个测试的历史统计
+export interface TestHistory {
+  test_name: string;
+  runs: number;
+  failures: number;
+  failure_rate: number;
+  flip_rate: number;
+  mixed_commit_outcomes: boolean;   // 同一提交上既通过又失败
+  last_outcome: TestOutcome;
+  last_run_at: string;
+  stability: TestStability;
+}
+
+// 项目的不稳定测试报告
+export interface FlakyTestReport {
+  project_id: string;
+  total_tests: number;
+  flaky: TestHistory[];
+  failing: TestHistory[];
+}
+
+export type TestGateVerdict = 'passed' | 'warning' | 'blocked';
+
+// 执行会话的测试门禁结果
+export interface TestGateResult {
+  session_id: string;
+  verdict: TestGateVerdict;
+  blocking_failures: string[];
+  quarantined_failures: string[];
+}
@@ -0,0 +1,48 @@
+/**
+ * 任务人工检查点相关的类型定义
+ * 对应后端 checkpoint_gates 模块
+ */
+
+// 检查点状态：尚未到达、等待人工决策、已批准、已驳回
+export type GateStatus = 'pending' | 'waiting' | 'approved' | 'rejected';
+
+// 检查点记录
+export interface TaskGate {
+  gate_id: string;
+  task_id: string;
+  name: string;
+  description?: string;
+  sequence: number;
+  status: GateStatus;
+  resume_status?: string;            // 进入等待时任务的状态
+  requested_at?: string;
+  decided_at?: string;
+  decided_by?: string;
+  comment?: string;
+  waiting_seconds: number;           // 已结束的等待累计时长
+  request_count: number;
+  created_at: string;
+}
+
+// 时间线中的一个检查点
+export interface GateTimelineEntry {
+  gate_id: string;
+  name: string;
+  description?: string;
+  sequence: number;
+  status: GateStatus;
+  requested_at?: string;
+  decided_at?: string;
+  decided_by?: string;
+  comment?: string;
+  request_count: number;
+  waiting_seconds: number;           // 包括正在进行的等待
+}
+
+// 任务在人工检查点上的等待时间线
+export interface GateTimeline {
+  task_id: string;
+  gates: GateTimelineEntry[];
+  total_waiting_seconds: number;
+  waiting_gate_id?: string;
+}
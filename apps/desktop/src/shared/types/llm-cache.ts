@@ -0,0 +1,23 @@
+/**
+ * LLM响应缓存相关的类型定义
+ * 对应后端 codex-database 的 llm_cache 模块
+ */
+
+// 进程内的缓存计数
+export interface LlmCacheMetrics {
+  hits: number;
+  misses: number;
+  bypassed: number;
+  stores: number;       // 写入的条目数
+  evictions: number;    // 因过期或容量超限被删除的条目数
+}
+
+// 缓存状态
+export interface LlmCacheStats {
+  enabled: boolean;
+  metrics: LlmCacheMetrics;
+  hit_rate: number;
+  entries: number;
+  total_bytes: number;
+  total_hits: number;   // 表中条目的累计命中次数
+}
@@ -0,0 +1,73 @@
+/**
+ * LLM调用记录相关的类型定义
+ * 对应后端 llm_transcripts 模块
+ */
+
+// LLM调用类型
+export type LlmCallKind = 'decomposition' | 'review' | 'agent_execution' | 'evaluation';
+
+// 调用记录的归属，为 null 的字段表示与该实体无关
+export interface TranscriptLink {
+  project_id: string | null;
+  task_id: string | null;
+  llm_session_id: string | null;
+  execution_session_id: string | null;
+  conversation_id: string | null;     // 桌面端对话ID
+}
+
+// 调用记录查询条件，项目ID由调用参数指定
+export interface TranscriptQuery {
+  task_id?: string;
+  llm_session_id?: string;
+  execution_session_id?: string;
+  call_kind?: LlmCallKind;
+  limit?: number;                     // 默认 100 条
+}
+
+// 脱敏后的调用记录，密钥已替换为 [REDACTED:<规则>]
+export interface TranscriptView {
+  transcript_id: string;
+  call_kind: LlmCallKind;
+  provider: string;
+  model: string;
+  prompt: string;
+  request_params: { temperature: number | null; max_tokens: number | null } | null;
+  response: string | null;            // 调用失败时为 null
+  error_message: string | null;
+  tokens_used: number | null;
+  latency_ms: number;
+  link: TranscriptLink;
+  redacted_secrets: number;           // 被替换的密钥数量
+  created_at: string;
+}
+
+// 一次调用的重放结果
+export interface ReplayCallResult {
+  transcript_id: string;              // 原始调用记录ID
+  call_kind: LlmCallKind;
+  original_model: string;
+  replay_model: string;
+  original_response: string | null;
+  replay_response: string | null;     // 重放失败时为 null
+  replay_error: string | null;
+  identical: boolean;                 // 新输出与原始输出完全相同
+  similarity: number;                 // 按行计算的相似度，0 到 1
+  diff: string;                       // 统一差异格式，输出相同时为空
+  original_tokens: number | null;
+  replay_tokens: number | null;
+  original_latency_ms: number;
+  replay_latency_ms: number;
+}
+
+// LLM会话重放报告
+export interface ReplayReport {
+  report_id: string;
+  llm_session_id: string;
+  provider: string;
+  model_override: string | null;      // 为 null 时使用每次调用的原始模型
+  total_calls: number;
+  changed_calls: number;              // 包括重放失败的调用
+  failed_calls: number;
+  calls: ReplayCallResult[];
+  created_at: string;
+}
@@ -0,0 +1,29 @@
+/**
+ * 执行日志管道相关的类型定义
+ * 对应后端 codex_database::log_pipeline
+ */
+
+// 批次中的一行，repeat 大于 1 表示连续重复的日志被合并
+export interface LogBatchLine {
+  log_level: string;
+  event_type: string;
+  message: string;
+  timestamp_ms: number;               // 最后一次出现的时间
+  repeat: number;
+}
+
+// 一个日志流在一个发送间隔内的日志，通过 execution_log_batch 事件推送
+export interface LogBatch {
+  stream: string;                     // 日志流标识（执行会话ID或对话ID）
+  lines: LogBatchLine[];
+  omitted: number;                    // 因背压被省略的行数，显示为一行摘要
+}
+
+// 管道指标（行数按合并前计）
+export interface LogPipelineMetrics {
+  received: number;
+  emitted: number;
+  coalesced: number;
+  dropped: number;
+  batches: number;
+}
@@ -0,0 +1,35 @@
+/**
+ * 定期维护任务相关的类型定义
+ * 对应后端 codex-database 的 maintenance 模块
+ */
+
+// 维护任务
+export interface MaintenanceJob {
+  jobId: string;
+  name: string;
+  kind: string;             // vacuum, prune, snapshot 或其他注册的类型
+  cronExpression: string;   // 秒 分 时 日 月 周
+  jitterSecs: number;       // 下一次执行时间的随机延后上限
+  params: Record<string, unknown>;
+  enabled: boolean;
+  nextRunAt: string;
+  lastRunAt?: string;
+  lastStatus?: MaintenanceRunStatus;
+  running: boolean;
+}
+
+// 执行状态
+export type MaintenanceRunStatus = 'running' | 'succeeded' | 'failed' | 'skipped';
+
+// 维护任务的一次执行
+export interface MaintenanceJobRun {
+  runId: string;
+  jobId: string;
+  trigger: 'schedule' | 'manual';
+  status: MaintenanceRunStatus;
+  startedAt: string;
+  finishedAt?: string;
+  durationMs?: number;
+  result?: Record<string, unknown>;
+  errorMessage?: string;
+}
@@ -0,0 +1,65 @@
+/**
+ * 通知偏好与通知相关的类型定义
+ * 对应后端 notifications 模块
+ */
+
+// 通知事件类别
+export type NotificationCategory =
+  | 'task_updates'
+  | 'reviews'
+  | 'sla_alerts'
+  | 'ci_results'
+  | 'security'
+  | 'system';
+
+// 投递渠道
+export type NotificationChannel = 'in_app' | 'desktop' | 'email';
+
+// 投递方式
+export type DeliveryMode = 'immediate' | 'digest';
+
+// 单个事件类别的偏好
+export interface CategoryPreference {
+  channels: NotificationChannel[];
+  delivery: DeliveryMode;
+}
+
+// 免打扰时段（本地时间）
+export interface QuietHours {
+  start: string;                           // HH:MM:SS
+  end: string;                             // HH:MM:SS，早于 start 表示跨越午夜
+  utc_offset_minutes: number;              // 本地时间相对 UTC 的偏移，保存时取 -new Date().getTimezoneOffset()
+  allow_urgent: boolean;                   // 紧急通知是否穿透
+}
+
+// 用户的通知偏好
+export interface NotificationPreferences {
+  categories: Partial<Record<NotificationCategory, CategoryPreference>>;
+  quiet_hours?: QuietHours | null;
+  digest_interval_minutes: number;
+}
+
+// 通知记录
+export interface Notification {
+  notification_id: string;
+  user_id: string;
+  category: NotificationCategory;
+  channel: NotificationChannel;
+  title: string;
+  body: string;
+  project_id?: string;
+  urgent: boolean;
+  digest: boolean;
+  deliver_at: string;
+  delivered_at?: string;
+  read_at?: string;
+  created_at: string;
+}
+
+// 一批投递的通知（notification_delivered 事件载荷）
+export interface DeliveryBatch {
+  user_id: string;
+  channel: NotificationChannel;
+  digest: boolean;
+  notifications: Notification[];
+}
@@ -0,0 +1,23 @@
+/**
+ * 长时间运行操作相关的类型定义
+ * 对应后端 OperationProgress
+ */
+
+// 操作状态
+export type OperationStatus = 'running' | 'completed' | 'failed' | 'cancelled';
+
+// 操作进度，每次变化通过 operation_progress 事件推送
+export interface OperationProgress {
+  operation_id: string;
+  kind: string;                       // 操作类型，例如 search_index、codebase_scan
+  project_id?: string;
+  status: OperationStatus;
+  phase: string;                      // 当前阶段
+  percentage: number;                 // 完成百分比（0-100）
+  message?: string;
+  cancel_requested: boolean;
+  result?: unknown;                   // 完成时的结果
+  error?: string;                     // 失败原因
+  started_at: string;
+  updated_at: string;
+}
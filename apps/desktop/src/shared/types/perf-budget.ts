@@ -0,0 +1,69 @@
+/**
+ * 性能回归预算相关的类型定义
+ * 对应后端 perf_budget 模块
+ */
+
+export type BenchmarkFormat = 'criterion' | 'custom';
+
+// 一条基准测试命令
+export interface BenchmarkCommand {
+  name: string;
+  command: string;
+  format?: BenchmarkFormat;
+  max_regression_percent?: number | null;  // 覆盖预算的回归百分比
+}
+
+// 项目的性能预算
+export interface PerfBudget {
+  benchmarks: BenchmarkCommand[];
+  max_regression_percent?: number;         // 默认 10
+  baseline_branch?: string | null;         // 为空时使用项目主分支
+}
+
+// 保存的基准测试结果
+export interface BenchmarkResult {
+  result_id: string;
+  project_id: string;
+  execution_session_id: string;
+  branch: string;
+  commit_sha?: string | null;
+  suite: string;
+  name: string;
+  value: number;
+  unit: string;                            // criterion 结果统一为 ns
+  higher_is_better: boolean;
+  is_baseline: boolean;
+  created_at: string;
+}
+
+export interface BenchmarkRun {
+  suite: string;
+  measurement_count: number;
+  error?: string | null;
+}
+
+// 一项基准测试与基线的比较
+export interface BenchmarkComparison {
+  suite: string;
+  name: string;
+  value: number;
+  unit: string;
+  baseline?: number | null;
+  baseline_branch?: string | null;
+  regression_percent?: number | null;      // 正数表示变差
+  threshold_percent: number;
+  regressed: boolean;
+}
+
+export interface PerfGateResult {
+  session_id: string;
+  passed: boolean;
+  comparisons: BenchmarkComparison[];
+}
+
+export interface PerfCheckReport {
+  session_id: string;
+  runs: BenchmarkRun[];
+  gate: PerfGateResult;
+  promoted_baselines: number;
+}
@@ -0,0 +1,30 @@
+/**
+ * 个人信息脱敏相关的类型定义
+ * 对应后端 pii_scrubbing 模块
+ */
+
+import type { ContextDiff } from './context-diff';
+
+// 自定义脱敏模式
+export interface PiiPattern {
+  label: string;                      // 类别标签，只能包含大写字母、数字和下划线，例如 EMPLOYEE_ID
+  pattern: string;                    // 正则表达式
+}
+
+// 项目的个人信息脱敏配置
+export interface PiiScrubbingConfig {
+  enabled: boolean;
+  emails: boolean;                    // 替换邮箱
+  phone_numbers: boolean;             // 替换电话号码
+  names: string[];                    // 需要替换的姓名
+  patterns: PiiPattern[];
+}
+
+// 任务分解提示词，个人信息已替换为 [EMAIL_1] 形式的占位符
+export interface DecompositionPrompt {
+  project_id: string;
+  document_id: string;
+  prompt: string;
+  scrubbed_items: number;             // 替换的个人信息数量
+  context_diff: ContextDiff | null;   // 与上次分解相比的变化，首次分解或没有变化时为 null
+}
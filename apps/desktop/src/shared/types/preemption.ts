@@ -0,0 +1,25 @@
+/**
+ * 紧急任务抢占相关的类型定义
+ * 对应后端 preemption 模块
+ */
+
+// 项目的紧急任务抢占策略
+export interface PreemptionPolicy {
+  enabled: boolean;
+  preemptible_priorities: string[];   // 可以被抢占的任务优先级，例如 ['low']
+  max_completion_percentage: number;  // 完成度超过该值（0-1）的任务不会被抢占
+}
+
+// 任务被抢占事件
+export interface TaskPreemptedEvent {
+  metadata: Record<string, unknown>;
+  preempted_task_id: string;
+  preempted_session_id: string;       // 被中断的执行会话，已写入检查点
+  preempted_priority: string;
+  completion_percentage: number;
+  elapsed_minutes: number;
+  preempting_task_id: string;
+  preempting_priority: string;
+  agent_id: string;                   // 让出并改为执行紧急任务的Agent
+  reason: string;
+}
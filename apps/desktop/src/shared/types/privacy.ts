@@ -0,0 +1,25 @@
+/**
+ * 用户数据清除相关的类型定义
+ * 对应后端 data_purge 模块
+ */
+
+// 所有权转交记录
+export interface OwnershipTransfer {
+  id: string;                              // 项目或组织ID
+  new_owner_id: string;
+}
+
+// 用户数据清除报告
+export interface PurgeReport {
+  user_id: string;
+  requested_by?: string | null;
+  purged_at: string;
+  deleted: Record<string, number>;         // 按表统计删除的行数
+  anonymized: Record<string, number>;      // 按表统计匿名化的行数
+  deleted_projects: string[];
+  transferred_projects: OwnershipTransfer[];
+  deleted_organizations: string[];
+  transferred_organizations: OwnershipTransfer[];
+  retained_agents: string[];               // 仍被共享项目引用而保留的 Agent
+  audit_event_id: string;
+}
@@ -0,0 +1,30 @@
+/**
+ * 配置档案相关的类型定义
+ * 对应后端 profiles 模块
+ */
+
+// 档案定义
+export interface Profile {
+  name: string;
+  dataDir?: string | null;        // 自定义数据目录，为空时使用默认位置
+  createdAt: number;
+}
+
+// 本次运行使用的档案
+export interface ActiveProfile {
+  name: string;
+  dataDir: string;
+}
+
+export interface ProfileInfo {
+  name: string;
+  data_dir: string;
+  created_at: number;
+  is_current: boolean;            // 本次运行正在使用
+  is_default_on_startup: boolean; // 下次启动默认使用
+}
+
+export interface ProfileList {
+  current: ActiveProfile;
+  profiles: ProfileInfo[];
+}
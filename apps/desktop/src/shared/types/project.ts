@@ -23,6 +23,7 @@ export interface Project {
   workspace_path: string;   // 工作空间路径
   technology_stack: string[]; // 技术栈
   status: string;           // 项目状态
+  is_demo: boolean;         // 是否为演示工作区项目
   created_at: string;       // 创建时间 (RFC3339格式)
   updated_at: string;       // 更新时间 (RFC3339格式)
 }
@@ -76,6 +77,80 @@ export interface ProjectStats {
   paused: number;       // 暂停项目数
 }
 
+// 工作空间磁盘配额
+export interface WorkspaceQuota {
+  max_bytes: number;
+  warning_ratio: number;              // 达到该比例时发出警告，默认 0.8
+  critical_ratio: number;             // 达到该比例时发出严重警告，默认 0.95
+}
+
+// 配额占用级别
+export type QuotaLevel = 'normal' | 'warning' | 'critical' | 'exceeded';
+
+// 目录占用
+export interface DirectoryUsage {
+  path: string;                       // 相对工作空间根目录的路径
+  bytes: number;
+}
+
+// 清理建议
+export interface CleanupSuggestion {
+  path: string;
+  bytes: number;                      // 可释放的字节数
+  reason: string;
+}
+
+// 工作空间配额检查结果
+export interface QuotaReport {
+  project_id: string;
+  workspace_path: string;
+  quota?: WorkspaceQuota;
+  usage: {
+    total_bytes: number;
+    file_count: number;
+    top_level: DirectoryUsage[];
+    regenerable: DirectoryUsage[];
+    scanned_at?: string;
+  };
+  level: QuotaLevel;
+  suggestions: CleanupSuggestion[];
+}
+
+// 从Git仓库导入项目的请求
+export interface BootstrapRequest {
+  source: string;                         // 本地仓库路径或远程仓库地址
+  workspace_path?: string | null;         // 远程仓库克隆到该目录（必填）
+  name?: string | null;                   // 为空时使用仓库目录名
+  description?: string | null;
+}
+
+// 仓库检测结果
+export interface RepositoryDetection {
+  name: string;
+  repository_url: string;                 // 已去除其中的凭据
+  workspace_path: string;
+  main_branch: string;
+  head_commit?: string | null;
+  technology_stack: string[];
+  coding_standards: Record<string, unknown>;
+  scan: {
+    commit_hash?: string | null;
+    total_files: number;
+    total_lines: number;
+    languages: Array<{ language: string; file_count: number; line_count: number; percentage: number }>;
+    frameworks: Array<{ name: string; version: string; usage_scope: string }>;
+    average_complexity: number;
+    scanned_at?: string | null;
+  };
+}
+
+// 从Git仓库导入的项目
+export interface ImportedProject {
+  project: Project;
+  detection: RepositoryDetection;
+  codebase_info: Record<string, unknown>;
+}
+
 // 常用技术栈选项
 export const TECHNOLOGY_STACK_OPTIONS = [
   'React',
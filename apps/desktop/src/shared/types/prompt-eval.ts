@@ -0,0 +1,96 @@
+/**
+ * 提示词评估相关的类型定义
+ * 对应后端 prompt_eval 模块
+ */
+
+// 输出应满足的属性
+export type ExpectedProperty =
+  | { type: 'contains'; text: string }
+  | { type: 'not_contains'; text: string }
+  | { type: 'matches'; pattern: string }   // 正则表达式
+  | { type: 'json_object' }
+  | { type: 'min_tasks'; count: number }
+  | { type: 'max_chars'; count: number }
+  | { type: 'judge'; criterion: string };  // 由LLM裁判判定
+
+// 评估用例
+export interface EvalCase {
+  name: string;                        // 在评估集内唯一
+  inputs: Record<string, string>;      // 填入模板 {{名称}} 占位符的输入
+  expected: ExpectedProperty[];
+}
+
+// 评估集定义
+export interface EvalSet {
+  name: string;
+  description: string | null;
+  cases: EvalCase[];
+}
+
+// 已保存的评估集
+export interface EvalSetRecord {
+  eval_set_id: string;
+  project_id: string | null;
+  name: string;
+  description: string | null;
+  cases: EvalCase[];
+  created_at: string;
+  updated_at: string;
+}
+
+// 评估变体：提示词模板 × 模型
+export interface EvalVariant {
+  name: string;
+  template: string;
+  model: string;
+  temperature: number | null;
+  max_tokens: number | null;
+}
+
+// 单个属性的检查结果
+export interface PropertyCheck {
+  property: ExpectedProperty;
+  passed: boolean | null;              // 跳过的检查为 null
+  detail: string | null;               // 失败原因或裁判理由
+}
+
+// 变体在一个用例上的结果
+export interface EvalCaseResult {
+  case_name: string;
+  score: number;                       // 0 到 1
+  passed: boolean;
+  error_message: string | null;
+  checks: PropertyCheck[];
+}
+
+// 变体汇总
+export interface VariantSummary {
+  variant: string;
+  model: string;
+  mean_score: number;
+  pass_rate: number;
+  cases_passed: number;
+  total_tokens: number;
+  mean_latency_ms: number;
+  cases: EvalCaseResult[];
+}
+
+// 变体相对基线的变化
+export interface VariantDelta {
+  variant: string;
+  baseline: string;
+  score_delta: number;
+  pass_rate_delta: number;
+  regressions: string[];               // 得分低于基线的用例
+  improvements: string[];              // 得分高于基线的用例
+}
+
+// 评估报告，第一个变体为基线
+export interface EvalRunReport {
+  run_id: string;
+  eval_set_id: string;
+  variants: VariantSummary[];
+  deltas: VariantDelta[];
+  judged: boolean;                     // 是否配置了LLM裁判
+  created_at: string;
+}
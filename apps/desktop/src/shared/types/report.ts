@@ -0,0 +1,64 @@
+/**
+ * 定时报告相关的类型定义
+ * 对应后端 codex-database 的 reporting 模块
+ */
+
+export type ReportFormat = 'markdown' | 'html';
+
+// 报告投递的通知渠道
+export type ReportChannel =
+  | { type: 'desktop' }
+  | { type: 'webhook'; url: string };
+
+// 报告计划配置
+export interface ScheduleConfig {
+  name: string;
+  cron_expression: string;                // 秒 分 时 日 月 周，例如 "0 0 9 * * Mon"
+  format: ReportFormat;
+  template?: string | null;               // 为空时使用内置模板
+  channels?: ReportChannel[];
+  cost_per_1k_tokens?: number | null;     // 为空时只统计 token 用量
+  enabled?: boolean;                      // 默认启用
+}
+
+// 立即生成报告的选项
+export interface ReportOptions {
+  format: ReportFormat;
+  template?: string | null;
+  cost_per_1k_tokens?: number | null;
+}
+
+// 报告计划
+export interface ReportScheduleInfo {
+  scheduleId: string;
+  projectId: string;
+  config: ScheduleConfig;
+  lastRunAt?: string | null;
+  nextRunAt: string;
+  createdBy: string;
+  createdAt: string;
+  updatedAt: string;
+}
+
+// 已生成的项目报告
+export interface ProjectReportInfo {
+  reportId: string;
+  projectId: string;
+  scheduleId?: string | null;             // 手动生成时为空
+  title: string;
+  format: ReportFormat;
+  periodStart: string;
+  periodEnd: string;
+  sizeBytes: number;
+  summary: Record<string, unknown>;
+  createdAt: string;
+}
+
+// 报告及其渲染内容
+export interface ProjectReportContent {
+  report: ProjectReportInfo;
+  content: string;
+}
+
+// 定时报告生成后推送的事件名
+export const PROJECT_REPORT_EVENT = 'project_report_generated';
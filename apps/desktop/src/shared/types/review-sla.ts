@@ -0,0 +1,53 @@
+/**
+ * 代码审查时限相关的类型定义
+ * 对应后端 review_sla 模块
+ */
+
+export type ReviewPriority = 'low' | 'normal' | 'high' | 'urgent';
+
+// 审查者：审查员 Agent 或项目成员
+export type Reviewer =
+  | { kind: 'agent'; id: string }
+  | { kind: 'user'; id: string };
+
+// 代码审查记录
+export interface CodeReview {
+  review_id: string;
+  task_id: string;
+  execution_session_id: string;
+  reviewer_agent_id: string;
+  reviewer_user_id?: string | null;       // 指定了人工审查者时以人为准
+  pull_request_url: string;
+  source_branch: string;
+  target_branch: string;
+  status: 'pending' | 'in_progress' | 'completed' | 'cancelled';
+  decision?: string | null;
+  overall_comment?: string | null;
+  priority: ReviewPriority;
+  assigned_at?: string | null;
+  review_deadline?: string | null;
+  reminded_at?: string | null;
+  escalated_at?: string | null;
+  reassignment_count: number;
+  created_at: string;
+  reviewed_at?: string | null;
+}
+
+// 提醒、改派或超时通知，后台监控通过 review_reminder、review_reassigned、review_deadline_missed 事件推送
+export interface ReviewSlaNotice {
+  review_id: string;
+  task_id: string;
+  project_id: string;
+  priority: ReviewPriority;
+  reviewer: Reviewer;
+  previous_reviewer?: Reviewer | null;    // 仅改派通知有值
+  review_deadline: string;
+  reassignment_count: number;
+}
+
+// 一次评估的结果
+export interface ReviewSlaReport {
+  reminders: ReviewSlaNotice[];
+  reassignments: ReviewSlaNotice[];
+  escalations: ReviewSlaNotice[];
+}
@@ -0,0 +1,23 @@
+/**
+ * 语义检索相关的类型定义
+ * 对应后端 SearchHit / IndexReport
+ */
+
+// 检索来源类型
+export type SearchSourceType = 'requirement_document' | 'task' | 'lesson';
+
+// 检索结果，每个来源只返回最相关的分块
+export interface SearchHit {
+  source_type: SearchSourceType;
+  source_id: string;                  // 需求文档ID或任务ID（经验教训对应其任务）
+  chunk_index: number;
+  content: string;
+  score: number;                      // 余弦相似度
+}
+
+// 索引更新结果
+export interface IndexReport {
+  embedded: number;
+  unchanged: number;
+  removed: number;
+}
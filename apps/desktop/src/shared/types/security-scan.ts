@@ -0,0 +1,56 @@
+/**
+ * 安全扫描相关的类型定义
+ * 对应后端 security_scan 模块
+ */
+
+import type { IssueSeverity } from './static-analysis';
+
+export type SecurityScanner = 'cargo_audit' | 'npm_audit' | 'secrets';
+
+export type SecurityCategory = 'vulnerability' | 'secret';
+
+export type SeverityLevel = 'info' | 'warning' | 'error' | 'blocker';
+
+// 执行会话中保存的安全扫描发现
+export interface SecurityFinding {
+  finding_id: string;
+  project_id: string;
+  execution_session_id: string;
+  scanner: SecurityScanner;
+  category: SecurityCategory;
+  severity: IssueSeverity;
+  title: string;
+  advisory_id?: string | null;          // RUSTSEC、GHSA 编号或密钥规则标识
+  package_name?: string | null;
+  package_version?: string | null;
+  fixed_versions: string[];
+  file_path?: string | null;
+  line?: number | null;
+  remediation_task_id?: string | null;  // 自动创建的修复任务
+  created_at: string;
+}
+
+// 单个扫描器的运行情况
+export interface ScanRun {
+  scanner: SecurityScanner;
+  finding_count: number;
+  error?: string | null;                // 运行或解析失败时保留之前的发现
+}
+
+// 执行会话的安全扫描报告
+export interface SecurityScanReport {
+  session_id: string;
+  enabled: boolean;                     // 执行配置关闭安全检查时不运行扫描器
+  runs: ScanRun[];
+  findings: SecurityFinding[];
+  blocking_count: number;
+  remediation_task_ids: string[];
+}
+
+// 安全扫描门禁结果
+export interface SecurityGateResult {
+  session_id: string;
+  threshold: SeverityLevel;             // 项目质量门禁的 security_blocking_severity
+  passed: boolean;
+  blocking: SecurityFinding[];
+}
@@ -0,0 +1,59 @@
+/**
+ * SLA 相关的类型定义
+ * 对应后端 sla 模块
+ */
+
+export type SlaType = 'pending_time' | 'execution_time' | 'milestone_deadline';
+
+export type SlaSeverity = 'warning' | 'critical';
+
+// 项目的 SLA 配置，未设置的项不做检查
+export interface SlaPolicy {
+  enabled: boolean;
+  max_pending_minutes?: number | null;    // 任务在待处理状态的最长时间
+  max_execution_minutes?: number | null;  // 任务执行的最长时长
+  milestone_buffer_hours?: number | null; // 里程碑截止日期前的缓冲时间
+  review_deadlines?: ReviewDeadlinePolicy; // 代码审查时限，未设置时使用默认值
+}
+
+// 代码审查时限配置（对应后端 review_sla 模块）
+export interface ReviewDeadlinePolicy {
+  enabled: boolean;                       // 是否自动提醒、改派和升级超时的审查
+  urgent_hours: number;
+  high_hours: number;
+  normal_hours: number;
+  low_hours: number;
+  reminder_ratio: number;                 // 剩余时间不足时限的该比例时提醒，0 表示不提醒
+  max_reassignments: number;              // 超时自动改派的最多次数
+}
+
+// SLA 违约记录
+export interface SlaBreach {
+  breach_id: string;
+  project_id: string;
+  sla_type: SlaType;
+  subject_type: 'task' | 'milestone';
+  subject_id: string;
+  subject_name: string;
+  severity: SlaSeverity;
+  threshold_minutes: number;
+  actual_minutes: number;                 // 里程碑为距截止日期的剩余分钟数，逾期为负数
+  deadline?: string | null;
+  detected_at: string;
+  updated_at: string;
+  resolved_at?: string | null;
+}
+
+// SLA 违约事件，后台监控通过 sla_breached 事件推送
+export interface SlaBreachedEvent {
+  metadata: Record<string, unknown>;
+  breach_id: string;
+  project_id: string;
+  sla_type: SlaType;
+  severity: SlaSeverity;
+  subject_id: string;
+  subject_name: string;
+  threshold_minutes: number;
+  actual_minutes: number;
+  deadline?: string | null;
+}
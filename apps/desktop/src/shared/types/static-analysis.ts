@@ -0,0 +1,47 @@
+/**
+ * 静态分析相关的类型定义
+ * 对应后端 static_analysis 模块
+ */
+
+export type LintTool = 'clippy' | 'eslint';
+
+export type IssueSeverity = 'info' | 'minor' | 'moderate' | 'major' | 'critical' | 'blocker';
+
+// 执行会话中保存的静态分析发现
+export interface LintFinding {
+  finding_id: string;
+  project_id: string;
+  execution_session_id: string;
+  tool: LintTool;
+  language: string;                 // 对应编码规范中的语言配置
+  file_path: string;
+  line?: number | null;
+  column?: number | null;
+  rule?: string | null;             // 例如 clippy::needless_return、no-unused-vars
+  severity: IssueSeverity;
+  message: string;
+  created_at: string;
+}
+
+// 单个 Linter 的运行情况
+export interface LintRun {
+  tool: LintTool;
+  languages: string[];
+  finding_count: number;
+  error?: string | null;            // 运行或解析失败时保留之前的发现
+}
+
+// 执行会话的静态分析报告
+export interface StaticAnalysisReport {
+  session_id: string;
+  runs: LintRun[];
+  findings: LintFinding[];
+}
+
+// 静态分析门禁结果
+export interface LintGateResult {
+  session_id: string;
+  passed: boolean;
+  counts: Record<string, number>;   // 各严重程度的发现数量
+  blocking: LintFinding[];
+}
@@ -0,0 +1,54 @@
+/**
+ * 需求追踪相关的类型定义
+ * 对应后端 traceability 模块
+ */
+
+// 需求章节（由 Markdown 标题划分，没有标题时整篇文档为一个章节）
+export interface RequirementSection {
+  section_id: string;
+  title: string;
+  level: number;                      // 标题级别，整篇文档为 0
+  parent_id?: string;
+}
+
+// 任务与需求章节的关联
+export interface RequirementCoverage {
+  coverage_id: string;
+  project_id: string;
+  document_id: string;
+  section_id: string;
+  section_title: string;
+  task_id: string;
+  created_at: string;
+}
+
+// 章节覆盖情况
+export interface SectionCoverage {
+  document_id: string;
+  document_title: string;
+  section_id: string;
+  section_title: string;
+  level: number;
+  task_ids: string[];                 // 直接关联的未取消任务
+  covered: boolean;                   // 本身或下级章节有未取消的任务
+}
+
+// 没有关联需求章节的任务
+export interface OrphanTask {
+  task_id: string;
+  title: string;
+  status: string;
+}
+
+// 需求覆盖报告
+export interface CoverageReport {
+  project_id: string;
+  total_sections: number;
+  covered_sections: number;
+  coverage_ratio: number;
+  sections: SectionCoverage[];
+  uncovered: SectionCoverage[];
+  orphan_tasks: OrphanTask[];
+  stale_links: RequirementCoverage[]; // 指向已不存在章节的关联
+  generated_at: string;
+}
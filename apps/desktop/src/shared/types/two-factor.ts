@@ -0,0 +1,25 @@
+/**
+ * 两步验证相关的类型定义
+ * 对应后端 two_factor 模块
+ */
+
+// 启用两步验证的用户登录时未提交验证码返回的错误信息
+export const TWO_FACTOR_REQUIRED = 'TWO_FACTOR_REQUIRED';
+
+// 被要求使用两步验证但尚未登记的用户调用登记以外的命令时返回的错误信息
+export const TWO_FACTOR_SETUP_REQUIRED = 'TWO_FACTOR_SETUP_REQUIRED';
+
+// 两步验证状态
+export interface TwoFactorStatus {
+  enabled: boolean;
+  required: boolean;                       // 是否被组织所有者强制要求
+  pending_enrollment: boolean;             // 已生成密钥但尚未确认
+  backup_codes_remaining: number;
+  confirmed_at?: string;
+}
+
+// 新登记的密钥
+export interface TotpEnrollment {
+  secret: string;                          // Base32 密钥，供手动输入
+  provisioning_uri: string;                // otpauth:// 地址，用于生成二维码
+}
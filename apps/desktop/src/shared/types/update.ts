@@ -0,0 +1,29 @@
+/**
+ * 自动更新相关的类型定义
+ * 对应后端 updater 模块
+ */
+
+export type UpdateChannel = 'stable' | 'beta';
+
+// 更新检查结果
+export interface UpdateInfo {
+  available: boolean;
+  current_version: string;
+  version?: string | null;
+  channel: UpdateChannel;
+  release_notes?: string | null;
+  published_at?: string | null;
+}
+
+// 安装历史，记录最近一次安装前的版本
+export interface InstallHistory {
+  previous_version?: string | null;   // 回滚的目标版本
+  installed_version?: string | null;
+  installed_at?: number | null;
+}
+
+// 已安排的延期安装
+export interface ScheduledInstall {
+  version: string;
+  install_at: number;                 // 毫秒时间戳
+}
@@ -0,0 +1,27 @@
+/**
+ * Webhook 接入相关的类型定义
+ * 对应后端 webhook_ingestion 模块
+ */
+
+export type WebhookSource = 'github' | 'gitlab';
+
+// 一次投递或重放的处理结果
+export type IngestOutcome =
+  | { status: 'ingested'; event_id: string; event_type: string }
+  | { status: 'ignored'; reason: string }
+  | { status: 'dead_lettered'; dead_letter_id: string; error: string };
+
+// 签名有效但无法映射为领域事件的 webhook 载荷
+export interface WebhookDeadLetter {
+  dead_letter_id: string;
+  project_id: string;
+  source: WebhookSource;
+  event_name?: string | null;       // 例如 GitHub 的 X-GitHub-Event
+  delivery_id?: string | null;
+  payload: string;                  // 原始载荷
+  error: string;                    // 最近一次映射失败的原因
+  received_at: string;
+  replay_count: number;
+  last_replayed_at?: string | null;
+  resolved_at?: string | null;      // 重放成功或被丢弃的时间
+}
@@ -0,0 +1,15 @@
+/**
+ * 执行会话工作树相关的类型定义
+ * 对应后端 codex-database 的 worktree_pool 模块
+ */
+
+// 工作树状态
+export type WorktreeState = 'in_use' | 'idle';
+
+// 工作树信息
+export interface WorktreeInfo {
+  path: string;
+  branch: string | null;        // 分离头指针时为空
+  session_id: string | null;    // 使用中的执行会话
+  state: WorktreeState;
+}
@@ -0,0 +1,543 @@
+//! 子命令实现，全部委托给 codex-database 的仓储和编排服务
+
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use codex_database::agent_provisioning::{plan_agents, provision_agents, ProvisioningSpec};
+use codex_database::autoscaler::{AgentAutoscaler, AutoscalerConfig};
+use codex_database::decomposition_progress::{stream_decomposition, DecompositionProgressTracker};
+use codex_database::entities::{agent, project, task, user};
+use codex_database::llm_provider::LlmRequest;
+use codex_database::llm_replay::{self, ReplayOptions};
+use codex_database::llm_transcripts::{LlmCallKind, RecordingProvider, TranscriptLink};
+use codex_database::orchestration_config::{OrchestrationConfigLoader, ResolvedOrchestrationConfig};
+use codex_database::parallel_planner::{ParallelPlanner, WaveTaskState};
+use codex_database::prompt_eval::{self, EvalHarness, EvalSet, EvalVariant};
+use codex_database::repository::project_repository::CreateProjectData;
+use codex_database::repository::task_repository::CreateTaskData;
+use codex_database::repository::llm_session_repository::CreateLlmSessionData;
+use codex_database::repository::user_repository::CreateUserData;
+use codex_database::repository::{
+    AgentRepository, LlmSessionRepository, OrganizationRepository, ProjectRepository, TaskRepository, UserRepository,
+};
+use codex_database::structured_output::{extract_json, TaskDraft};
+use codex_database::{initialize_database, pii_scrubbing, reporting, DatabaseConfig, DatabaseConnection, TenantScope};
+use codex_multi_agent::{LlmSessionId, ProjectId};
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use uuid::Uuid;
+
+use crate::llm::CommandLlmProvider;
+use crate::output::Output;
+use crate::{
+    AgentCommand, Cli, Command, CreateProjectArgs, DecomposeArgs, EvalCommand, EvalRunArgs, ExportReportArgs,
+    ProjectCommand, ReplaySessionArgs, TaskCommand,
+};
+
+/// 服务账号的密码哈希，不对应任何密码，无法登录
+const LOCKED_PASSWORD_HASH: &str = "!";
+
+/// 导入任务未指定类型时使用的类型
+const DEFAULT_TASK_TYPE: &str = "development";
+
+/// 执行命令
+pub async fn run(cli: Cli, output: &Output) -> anyhow::Result<()> {
+    let config = DatabaseConfig {
+        database_url: cli.database.clone(),
+        enable_logging: false,
+        ..Default::default()
+    };
+    config.validate()?;
+    let db = initialize_database(&config)
+        .await
+        .with_context(|| format!("连接数据库失败: {}", cli.database))?;
+
+    match cli.command {
+        Command::Project(ProjectCommand::Create(args)) => {
+            let owner = ensure_user(&db, &cli.user).await?;
+            create_project(&db, &owner, args, output).await
+        }
+        Command::Project(ProjectCommand::List { all }) => {
+            let projects = if all {
+                ProjectRepository::new(db.clone()).find_all().await?
+            } else {
+                let owner = ensure_user(&db, &cli.user).await?;
+                ProjectRepository::new(db.clone())
+                    .with_scope(TenantScope::user(owner.user_id))
+                    .find_all()
+                    .await?
+            };
+            output.emit(&projects, |projects| {
+                projects
+                    .iter()
+                    .map(|p| format!("{}  {}  [{}]  {}", p.project_id, p.name, p.status, p.workspace_path))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+        }
+        Command::Task(TaskCommand::Import { project, file }) => {
+            let project = find_project(&db, project.project).await?;
+            let drafts = read_task_drafts(&file)?;
+            let tasks = create_tasks(&db, project.project_id, drafts).await?;
+            output.emit(&tasks, |tasks| format!("已导入 {} 个任务\n{}", tasks.len(), task_lines(tasks)))
+        }
+        Command::Task(TaskCommand::List { project, status }) => {
+            let project = find_project(&db, project.project).await?;
+            let mut tasks = TaskRepository::new(db.clone()).find_by_project(project.project_id).await?;
+            if let Some(status) = status {
+                tasks.retain(|task| task.status == status);
+            }
+            output.emit(&tasks, |tasks| task_lines(tasks))
+        }
+        Command::Task(TaskCommand::Run(project)) => {
+            let project = find_project(&db, project.project).await?;
+            let config = load_config(&db, &project).await?;
+            let report = ParallelPlanner::new(db.clone(), config.config.scheduler)
+                .launch_ready(project.project_id)
+                .await?;
+            output.emit(&report, |report| {
+                let mut lines: Vec<String> = report
+                    .launched
+                    .iter()
+                    .map(|s| format!("已启动 {} -> Agent {}（分支 {}）", s.task_id, s.agent_id, s.git_branch))
+                    .collect();
+                lines.extend(report.deferred.iter().map(|d| format!("暂缓 {}: {}", d.task_id, d.reason)));
+                if lines.is_empty() {
+                    lines.push("没有就绪的任务".to_string());
+                }
+                lines.join("\n")
+            })
+        }
+        Command::Agent(AgentCommand::List { status }) => {
+            let owner = ensure_user(&db, &cli.user).await?;
+            let mut agents = AgentRepository::new(db.clone()).find_by_user_id(owner.user_id).await?;
+            if let Some(status) = status {
+                agents.retain(|agent| agent.status == status);
+            }
+            output.emit(&agents, |agents| agent_lines(agents))
+        }
+        Command::Agent(AgentCommand::Provision { spec, dry_run }) => {
+            let spec: ProvisioningSpec = serde_json::from_str(&read_input(&spec)?).context("配置规格格式错误")?;
+            if dry_run {
+                let planned = plan_agents(&spec)?;
+                return output.emit(&planned, |planned| {
+                    planned
+                        .iter()
+                        .map(|agent| format!("{}  [{}]", agent.config.name, agent.role))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                });
+            }
+            let owner = ensure_user(&db, &cli.user).await?;
+            let report = provision_agents(&db, owner.user_id, &spec).await?;
+            output.emit(&report, |report| {
+                let mut lines = vec![format!("已创建 {} 个Agent，跳过 {} 个", report.created.len(), report.skipped.len())];
+                lines.extend(report.created.iter().map(|a| format!("{}  {}  [{}]", a.agent_id, a.name, a.role)));
+                lines.extend(report.skipped.iter().map(|name| format!("跳过已存在的 {}", name)));
+                lines.join("\n")
+            })
+        }
+        Command::Decompose(args) => decompose(&db, args, output).await,
+        Command::ReplaySession(args) => replay_session(&db, args, output).await,
+        Command::Eval(EvalCommand::SaveSet { file, project, set }) => {
+            let eval_set: EvalSet = serde_json::from_str(&read_input(&file)?).context("评估集文件不是有效的JSON")?;
+            let model = prompt_eval::save_eval_set(&db, set, project, &eval_set).await?;
+            output.emit(&model, |model| {
+                format!("已保存评估集 {}  {}（{} 个用例）", model.eval_set_id, model.name, eval_set.cases.len())
+            })
+        }
+        Command::Eval(EvalCommand::Run(args)) => run_eval(&db, args, output).await,
+        Command::Eval(EvalCommand::Report { run }) => {
+            let report = prompt_eval::get_eval_report(&db, run)
+                .await?
+                .with_context(|| format!("评估运行不存在: {}", run))?;
+            output.emit(&report, prompt_eval::render_report_summary)
+        }
+        Command::Schedule(project) => {
+            let project = find_project(&db, project.project).await?;
+            let config = load_config(&db, &project).await?;
+            let view = ParallelPlanner::new(db.clone(), config.config.scheduler)
+                .wave_view(project.project_id)
+                .await?;
+            output.emit(&view, |view| {
+                let mut lines = vec![format!(
+                    "运行中 {}/{}，已完成 {} 个任务",
+                    view.running_sessions, view.max_parallel_sessions, view.completed_tasks
+                )];
+                for wave in &view.waves {
+                    lines.push(format!("波次 {}:", wave.index + 1));
+                    for task in &wave.tasks {
+                        let state = match &task.state {
+                            WaveTaskState::Blocked => format!(
+                                "阻塞: {}",
+                                task.blocked_reason.as_deref().unwrap_or("")
+                            ),
+                            state => format!("{:?}", state),
+                        };
+                        lines.push(format!("  {}  {}  [{}]", task.task_id, task.title, state));
+                    }
+                }
+                lines.join("\n")
+            })
+        }
+        Command::ExportReport(args) => export_report(&db, args, output).await,
+        Command::Daemon(args) => {
+            let autoscaler = match &args.autoscale_config {
+                Some(path) => {
+                    let config: AutoscalerConfig =
+                        serde_json::from_str(&read_input(path)?).context("自动伸缩配置不是有效的JSON")?;
+                    let owner = ensure_user(&db, &cli.user).await?;
+                    Some(AgentAutoscaler::new(db.clone(), owner.user_id, config)?)
+                }
+                None => None,
+            };
+            crate::daemon::run(db, args, autoscaler, output).await
+        }
+    }
+}
+
+/// 查找命令使用的用户，不存在时创建不可登录的服务账号
+async fn ensure_user(db: &DatabaseConnection, username: &str) -> anyhow::Result<user::Model> {
+    let repository = UserRepository::new(db.clone());
+    if let Some(user) = repository.find_by_username(username).await? {
+        return Ok(user);
+    }
+    let user = repository
+        .create(CreateUserData {
+            username: username.to_string(),
+            email: format!("{}@sker.local", username),
+            password_hash: LOCKED_PASSWORD_HASH.to_string(),
+            profile_data: Some(json!({ "service_account": true })),
+            settings: None,
+        })
+        .await?;
+    Ok(user)
+}
+
+async fn find_project(db: &DatabaseConnection, project_id: Uuid) -> anyhow::Result<project::Model> {
+    ProjectRepository::new(db.clone())
+        .find_by_id(project_id)
+        .await?
+        .with_context(|| format!("项目不存在: {}", project_id))
+}
+
+async fn create_project(
+    db: &DatabaseConnection,
+    owner: &user::Model,
+    args: CreateProjectArgs,
+    output: &Output,
+) -> anyhow::Result<()> {
+    let workspace = std::path::absolute(&args.workspace)
+        .with_context(|| format!("无效的工作区路径: {}", args.workspace.display()))?;
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: owner.user_id,
+            name: args.name,
+            description: args.description,
+            repository_url: args.repository_url,
+            workspace_path: workspace.to_string_lossy().into_owned(),
+        })
+        .await?;
+    output.emit(&project, |project| format!("已创建项目 {}（{}）", project.name, project.project_id))
+}
+
+/// 为项目构建编排配置加载器（默认值 < 组织 < sker.toml），命令行没有界面覆盖
+pub async fn project_config_loader(
+    db: &DatabaseConnection,
+    project: &project::Model,
+) -> codex_database::Result<OrchestrationConfigLoader> {
+    let mut loader = OrchestrationConfigLoader::new(&project.workspace_path);
+    if let Some(organization_id) = project.organization_id {
+        if let Some(organization) = OrganizationRepository::new(db.clone()).find_by_id(organization_id).await? {
+            loader = loader.with_organization_settings(&organization.get_settings())?;
+        }
+    }
+    Ok(loader)
+}
+
+/// 加载项目的编排配置
+async fn load_config(db: &DatabaseConnection, project: &project::Model) -> anyhow::Result<ResolvedOrchestrationConfig> {
+    let loader = project_config_loader(db, project).await?;
+    loader
+        .load()
+        .with_context(|| format!("加载编排配置失败: {}", loader.config_path().display()))
+}
+
+/// 读取输入文件，`-` 表示标准输入
+fn read_input(file: &Path) -> anyhow::Result<String> {
+    if file == Path::new("-") {
+        std::io::read_to_string(std::io::stdin()).context("读取标准输入失败")
+    } else {
+        std::fs::read_to_string(file).with_context(|| format!("读取文件失败: {}", file.display()))
+    }
+}
+
+/// 读取任务草稿文件，`-` 表示标准输入
+fn read_task_drafts(file: &Path) -> anyhow::Result<Vec<TaskDraft>> {
+    let content = read_input(file)?;
+    let value: JsonValue = serde_json::from_str(&content).context("任务文件不是有效的JSON")?;
+    let drafts = task_draft_values(value)
+        .context("任务文件应为任务数组或带 tasks 字段的对象")?
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| {
+            serde_json::from_value::<TaskDraft>(value).with_context(|| format!("第 {} 个任务格式错误", index + 1))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(drafts)
+}
+
+/// 取出任务数组：顶层数组或对象的 `tasks` 字段
+fn task_draft_values(value: JsonValue) -> Option<Vec<JsonValue>> {
+    match value {
+        JsonValue::Array(items) => Some(items),
+        JsonValue::Object(mut fields) => match fields.remove("tasks") {
+            Some(JsonValue::Array(items)) => Some(items),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// 把任务草稿创建为项目任务
+async fn create_tasks(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    drafts: Vec<TaskDraft>,
+) -> anyhow::Result<Vec<task::Model>> {
+    if let Some(index) = drafts.iter().position(|draft| draft.title.trim().is_empty()) {
+        bail!("第 {} 个任务缺少标题", index + 1);
+    }
+
+    let repository = TaskRepository::new(db.clone());
+    let mut tasks = Vec::with_capacity(drafts.len());
+    for draft in drafts {
+        let mut task = repository
+            .create(CreateTaskData {
+                project_id,
+                parent_task_id: None,
+                llm_session_id: None,
+                title: draft.title.trim().to_string(),
+                description: draft.description,
+                task_type: draft.task_type.unwrap_or_else(|| DEFAULT_TASK_TYPE.to_string()),
+            })
+            .await?;
+        if draft.priority.is_some() {
+            task = repository.update_details(task.task_id, None, None, draft.priority, None).await?;
+        }
+        if !draft.acceptance_criteria.is_empty() {
+            task = repository
+                .update_requirements(task.task_id, None, Some(json!(draft.acceptance_criteria)))
+                .await?;
+        }
+        tasks.push(task);
+    }
+    Ok(tasks)
+}
+
+/// 需求分解结果
+#[derive(Debug, Serialize)]
+struct DecompositionResult {
+    project_id: Uuid,
+    document_id: Uuid,
+    /// 本次分解的LLM会话，可用 `replay-session` 重放
+    llm_session_id: Uuid,
+    /// 提示词中替换的个人信息数量
+    scrubbed_items: usize,
+    tokens_used: u32,
+    tasks: Vec<TaskDraft>,
+    /// `--apply` 时创建的任务
+    created: Vec<task::Model>,
+}
+
+async fn decompose(db: &DatabaseConnection, args: DecomposeArgs, output: &Output) -> anyhow::Result<()> {
+    let prompt = pii_scrubbing::build_decomposition_prompt(db, args.document).await?;
+    let project = find_project(db, prompt.project_id).await?;
+    let provider_config = load_config(db, &project).await?.config.provider;
+
+    let mut request = LlmRequest::new(provider_config.model, &prompt.prompt);
+    request.temperature = provider_config.temperature;
+    request.max_tokens = provider_config.max_tokens;
+
+    // 调用记录关联到本次分解的LLM会话，便于之后重放
+    let sessions = LlmSessionRepository::new(db.clone());
+    let session = sessions
+        .create(CreateLlmSessionData {
+            project_id: prompt.project_id,
+            user_id: project.user_id,
+            session_type: LlmCallKind::Decomposition.as_str().to_string(),
+            system_prompt: None,
+            decomposition_prompt: Some(prompt.prompt.clone()),
+        })
+        .await?;
+    let command_provider = CommandLlmProvider::new(args.llm_command);
+    let provider = RecordingProvider::new(&command_provider, db.clone(), LlmCallKind::Decomposition).with_link(
+        TranscriptLink {
+            llm_session_id: Some(session.session_id),
+            ..TranscriptLink::project(prompt.project_id)
+        },
+    );
+    let mut tracker = DecompositionProgressTracker::new(LlmSessionId(session.session_id), ProjectId::from(prompt.project_id));
+    let completion = stream_decomposition(&provider, &request, &mut tracker, |event| {
+        if let Some(title) = event.tasks_identified.last().filter(|_| !event.finished) {
+            output.progress(&format!("已识别 {} 个任务：{}", event.tasks_identified.len(), title));
+        }
+    })
+    .await?;
+
+    // 回复中的占位符还原为原始个人信息后再解析
+    let content = pii_scrubbing::reidentify(db, prompt.project_id, &completion.content).await?;
+    let tasks = parse_decomposition(&content);
+    if tasks.is_empty() {
+        sessions.update_result(session.session_id, json!({ "tasks": [] }), "failed".to_string()).await?;
+        bail!("未能从LLM输出中识别出任务");
+    }
+    sessions
+        .update_result(session.session_id, json!({ "tasks": tasks }), "completed".to_string())
+        .await?;
+    let created = if args.apply {
+        create_tasks(db, prompt.project_id, tasks.clone()).await?
+    } else {
+        Vec::new()
+    };
+
+    let result = DecompositionResult {
+        project_id: prompt.project_id,
+        document_id: prompt.document_id,
+        llm_session_id: session.session_id,
+        scrubbed_items: prompt.scrubbed_items,
+        tokens_used: completion.tokens_used,
+        tasks,
+        created,
+    };
+    output.emit(&result, |result| {
+        let mut lines: Vec<String> = result
+            .tasks
+            .iter()
+            .enumerate()
+            .map(|(index, task)| format!("{}. {}", index + 1, task.title))
+            .collect();
+        if !result.created.is_empty() {
+            lines.push(format!("已创建 {} 个任务", result.created.len()));
+        }
+        lines.join("\n")
+    })
+}
+
+async fn replay_session(db: &DatabaseConnection, args: ReplaySessionArgs, output: &Output) -> anyhow::Result<()> {
+    let provider = CommandLlmProvider::new(args.llm_command);
+    let options = ReplayOptions { model: args.model };
+    let report = llm_replay::replay_session(db, &provider, args.session, &options).await?;
+    output.emit(&report, |report| {
+        let mut lines = vec![format!(
+            "重放 {} 次调用：{} 次输出变化，{} 次失败（报告 {}）",
+            report.total_calls, report.changed_calls, report.failed_calls, report.report_id
+        )];
+        for call in &report.calls {
+            let status = match (&call.replay_error, call.identical) {
+                (Some(error), _) => format!("失败：{}", error),
+                (None, true) => "相同".to_string(),
+                (None, false) => format!("变化，相似度 {:.0}%", call.similarity * 100.0),
+            };
+            lines.push(format!("{}  {} → {}  {}", call.transcript_id, call.original_model, call.replay_model, status));
+            if !call.diff.is_empty() {
+                lines.push(call.diff.trim_end().to_string());
+            }
+        }
+        lines.join("\n")
+    })
+}
+
+async fn run_eval(db: &DatabaseConnection, args: EvalRunArgs, output: &Output) -> anyhow::Result<()> {
+    let variants: Vec<EvalVariant> =
+        serde_json::from_str(&read_input(&args.variants)?).context("变体文件不是有效的JSON数组")?;
+    let provider = CommandLlmProvider::new(args.llm_command);
+    let judge = args.judge_command.map(CommandLlmProvider::new);
+    let mut harness = EvalHarness::new(db.clone(), &provider);
+    if let (Some(judge), Some(model)) = (&judge, args.judge_model) {
+        harness = harness.with_judge(judge, model);
+    }
+    let report = harness.run(args.set, &variants).await?;
+    output.emit(&report, |report| {
+        format!("评估运行 {}\n{}", report.run_id, prompt_eval::render_report_summary(report))
+    })
+}
+
+/// 解析分解结果：优先使用JSON中的任务数组，否则退回到识别出的任务标题
+fn parse_decomposition(content: &str) -> Vec<TaskDraft> {
+    let from_json: Option<Vec<TaskDraft>> = extract_json(content)
+        .and_then(task_draft_values)
+        .map(|items| items.into_iter().filter_map(|item| serde_json::from_value(item).ok()).collect());
+    match from_json {
+        Some(tasks) if !tasks.is_empty() => tasks,
+        _ => codex_database::structured_output::partial_task_titles(content)
+            .into_iter()
+            .map(|title| TaskDraft {
+                title,
+                ..Default::default()
+            })
+            .collect(),
+    }
+}
+
+async fn export_report(db: &DatabaseConnection, args: ExportReportArgs, output: &Output) -> anyhow::Result<()> {
+    let project = find_project(db, args.project.project).await?;
+    let template = args
+        .template
+        .as_deref()
+        .map(|path| {
+            std::fs::read_to_string(path).with_context(|| format!("读取模板失败: {}", path.display()))
+        })
+        .transpose()?;
+    if let Some(template) = &template {
+        reporting::validate_template(template)?;
+    }
+
+    let now = chrono::Utc::now();
+    let report = reporting::collect_weekly_report(
+        db,
+        project.project_id,
+        args.period_end.unwrap_or(now),
+        args.cost_per_1k_tokens,
+    )
+    .await?;
+    let content = reporting::render(&report, args.format, template.as_deref(), now)?;
+
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, &content).with_context(|| format!("写入报告失败: {}", path.display()))?;
+            let result = json!({
+                "title": reporting::report_title(&report),
+                "format": args.format,
+                "path": path,
+                "report": report,
+            });
+            output.emit(&result, |_| format!("报告已写入 {}", path.display()))
+        }
+        None => {
+            let result = json!({
+                "title": reporting::report_title(&report),
+                "format": args.format,
+                "content": content,
+                "report": report,
+            });
+            output.emit(&result, |_| content.clone())
+        }
+    }
+}
+
+fn task_lines(tasks: &[task::Model]) -> String {
+    tasks
+        .iter()
+        .map(|t| format!("{}  {}  [{}/{}]", t.task_id, t.title, t.status, t.priority))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn agent_lines(agents: &[agent::Model]) -> String {
+    agents
+        .iter()
+        .map(|a| format!("{}  {}  [{}]", a.agent_id, a.name, a.status))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
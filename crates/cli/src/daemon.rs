@@ -0,0 +1,368 @@
+//! 守护进程模式
+//!
+//! 常驻服务器上运行 `sker daemon`：调度器、事件投递、SLA 监控、Webhook 接入、工件保留清理和
+//! Agent池自动伸缩作为后台服务由 [`Supervisor`] 按各自的重启策略监督，健康状态通过 HTTP 端点暴露。
+//! 收到 SIGTERM 或 Ctrl-C 后走与桌面应用相同的优雅关闭流程：停止接受新工作、等待执行中的
+//! 调度轮次结束、为运行中的执行会话保存检查点并写入干净关闭标记。
+//!
+//! 服务产生的事件（SLA 违约、通知投递、报告生成等）以 JSON 行写到标准输出，日志写到标准错误。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_database::artifact_store::{ArtifactStore, RetentionPolicy};
+use codex_database::autoscaler::AgentAutoscaler;
+use codex_database::notifications::NotificationRouter;
+use codex_database::operations::CancellationToken;
+use codex_database::parallel_planner::ParallelPlanner;
+use codex_database::plugins::{self, CommandPlugin, PluginDispatcher, PluginLimits, PluginRegistry};
+use codex_database::repository::ProjectRepository;
+use codex_database::shutdown::{self, ShutdownCoordinator};
+use codex_database::supervisor::{serve_health, RestartPolicy, Supervisor};
+use codex_database::webhook_ingestion::{self, WebhookIngestor};
+use codex_database::{review_sla, reporting, sla, DatabaseConnection, Result};
+use codex_multi_agent::{SystemClock, WebhookSource};
+use serde::Serialize;
+use serde_json::json;
+use tokio::net::TcpListener;
+
+use crate::commands::project_config_loader;
+use crate::output::Output;
+use crate::DaemonArgs;
+
+/// 关闭标记文件名，与桌面应用一致
+const SHUTDOWN_MARKER_FILE: &str = "shutdown.json";
+
+/// 工件存储目录名
+const ARTIFACTS_DIR: &str = "artifacts";
+
+/// 轮询类服务的最短间隔
+const MIN_INTERVAL_SECS: u64 = 1;
+
+/// Webhook 接入端点连续失败后的最大重启次数
+const WEBHOOK_MAX_RESTARTS: u32 = 5;
+
+/// 运行守护进程，直到收到关闭信号
+pub async fn run(
+    db: DatabaseConnection,
+    args: DaemonArgs,
+    autoscaler: Option<AgentAutoscaler>,
+    output: &Output,
+) -> anyhow::Result<()> {
+    std::fs::create_dir_all(&args.data_dir)?;
+    let coordinator = Arc::new(
+        ShutdownCoordinator::new(args.data_dir.join(SHUTDOWN_MARKER_FILE))
+            .with_grace_period(Duration::from_secs(args.grace_period_secs)),
+    );
+    recover(&coordinator, &db, output).await;
+
+    let token = coordinator.token();
+    let store = ArtifactStore::new(db.clone(), args.data_dir.join(ARTIFACTS_DIR));
+    let plugins = load_plugins(&db, &args, output)?;
+    let supervisor = build_supervisor(&db, &store, &coordinator, plugins, autoscaler, &args, token.clone());
+    let handle = supervisor.handle();
+
+    let health_listener = TcpListener::bind(&args.health_bind).await?;
+    output.progress(&format!("健康检查端点已启动: http://{}/healthz", health_listener.local_addr()?));
+    let health_server = tokio::spawn(serve_health(health_listener, handle.clone(), token.clone()));
+    let mut services = tokio::spawn(supervisor.run(handle));
+
+    tokio::select! {
+        signal = shutdown_signal() => signal?,
+        _ = &mut services => output.progress("所有后台服务已结束"),
+    }
+
+    output.progress("正在关闭守护进程...");
+    let report = coordinator.shutdown(Some(&db)).await;
+    if tokio::time::timeout(Duration::from_secs(args.grace_period_secs), &mut services).await.is_err() {
+        output.progress("部分后台服务未在宽限期内结束");
+    }
+    let _ = health_server.await;
+
+    if !report.interrupted_sessions.is_empty() {
+        output.progress(&format!("已为 {} 个执行中的会话保存检查点", report.interrupted_sessions.len()));
+    }
+    for step in &report.failed_steps {
+        output.progress(&format!("关闭步骤失败: {}", step));
+    }
+    output.progress("守护进程已关闭");
+    Ok(())
+}
+
+/// 读取上次的关闭标记，并结束上次运行遗留的执行会话
+async fn recover(coordinator: &ShutdownCoordinator, db: &DatabaseConnection, output: &Output) {
+    let previous = match coordinator.begin_run().await {
+        Ok(previous) => previous,
+        Err(e) => {
+            output.progress(&format!("读取关闭标记失败: {}", e));
+            return;
+        }
+    };
+    if previous.is_unclean() {
+        output.progress("检测到守护进程上次异常退出");
+    }
+    match shutdown::recover_interrupted_sessions(db, &previous).await {
+        Ok(sessions) if sessions.is_empty() => {}
+        Ok(sessions) => output.progress(&format!("已结束 {} 个上次运行遗留的执行会话", sessions.len())),
+        Err(e) => output.progress(&format!("结束遗留执行会话失败: {}", e)),
+    }
+}
+
+/// 按插件目录中的清单注册命令插件，未指定目录时返回 None
+fn load_plugins(db: &DatabaseConnection, args: &DaemonArgs, output: &Output) -> Result<Option<PluginDispatcher>> {
+    let Some(plugin_dir) = &args.plugin_dir else {
+        return Ok(None);
+    };
+    let registry = Arc::new(PluginRegistry::new());
+    for manifest in plugins::load_manifests(plugin_dir)? {
+        registry.register(Arc::new(CommandPlugin::new(manifest)))?;
+    }
+    output.progress(&format!("已加载 {} 个插件", registry.statuses().len()));
+    let limits = PluginLimits {
+        timeout: Duration::from_secs(args.plugin_timeout_secs.max(MIN_INTERVAL_SECS)),
+        ..PluginLimits::default()
+    };
+    Ok(Some(PluginDispatcher::new(db.clone(), registry).with_limits(limits)))
+}
+
+/// 等待 SIGTERM 或 Ctrl-C
+async fn shutdown_signal() -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        let mut terminate = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+        tokio::select! {
+            _ = terminate.recv() => {}
+            result = tokio::signal::ctrl_c() => result?,
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
+
+fn build_supervisor(
+    db: &DatabaseConnection,
+    store: &ArtifactStore,
+    coordinator: &Arc<ShutdownCoordinator>,
+    plugins: Option<PluginDispatcher>,
+    autoscaler: Option<AgentAutoscaler>,
+    args: &DaemonArgs,
+    token: CancellationToken,
+) -> Supervisor {
+    // 轮询类服务单轮出错（通常是数据库不可用）时退出，由监督器退避后重启
+    let polling = RestartPolicy::OnFailure { max_restarts: None };
+    let mut supervisor = Supervisor::new(token)
+        .add_service("scheduler", polling, {
+            let (db, coordinator) = (db.clone(), coordinator.clone());
+            let period = interval(args.scheduler_interval_secs);
+            move |token| run_scheduler(db.clone(), coordinator.clone(), period, token)
+        })
+        .add_service("event_dispatcher", polling, {
+            let (db, store) = (db.clone(), store.clone());
+            let period = interval(args.dispatch_interval_secs);
+            move |token| run_event_dispatcher(db.clone(), store.clone(), period, token)
+        })
+        .add_service("sla_monitor", polling, {
+            let db = db.clone();
+            let period = interval(args.sla_interval_secs);
+            move |token| run_sla_monitor(db.clone(), period, token)
+        })
+        .add_service("retention", polling, {
+            let store = store.clone();
+            let period = interval(args.retention_interval_secs);
+            let policy = RetentionPolicy {
+                max_age_days: Some(args.retention_max_age_days),
+                max_total_bytes: args.retention_max_bytes,
+            };
+            move |token| run_retention(store.clone(), policy.clone(), period, token)
+        });
+
+    if let Some(dispatcher) = plugins {
+        let period = interval(args.dispatch_interval_secs);
+        supervisor = supervisor.add_service("plugin_dispatcher", polling, move |token| {
+            run_plugin_dispatcher(dispatcher.clone(), period, token)
+        });
+    }
+
+    if let Some(autoscaler) = autoscaler {
+        let autoscaler = Arc::new(autoscaler);
+        let period = interval(args.autoscale_interval_secs);
+        supervisor = supervisor.add_service("agent_autoscaler", polling, move |token| {
+            run_autoscaler(autoscaler.clone(), period, token)
+        });
+    }
+
+    if let Some(bind_address) = &args.webhook_bind {
+        let mut ingestor = WebhookIngestor::new(db.clone());
+        if let Some(secret) = &args.github_webhook_secret {
+            ingestor = ingestor.with_secret(WebhookSource::Github, secret.clone());
+        }
+        if let Some(token) = &args.gitlab_webhook_token {
+            ingestor = ingestor.with_secret(WebhookSource::Gitlab, token.clone());
+        }
+        if ingestor.has_secrets() {
+            let ingestor = Arc::new(ingestor);
+            let bind_address = bind_address.clone();
+            supervisor = supervisor.add_service(
+                "webhook_ingestion",
+                RestartPolicy::OnFailure { max_restarts: Some(WEBHOOK_MAX_RESTARTS) },
+                move |token| run_webhook_ingestion(bind_address.clone(), ingestor.clone(), token),
+            );
+        } else {
+            eprintln!("未配置 GitHub webhook 密钥或 GitLab webhook 令牌，不启动 Webhook 接入端点");
+        }
+    }
+    supervisor
+}
+
+fn interval(secs: u64) -> Duration {
+    Duration::from_secs(secs.max(MIN_INTERVAL_SECS))
+}
+
+/// 等待下一轮，收到关闭信号时返回 false
+async fn next_tick(interval: &mut tokio::time::Interval, token: &CancellationToken) -> bool {
+    tokio::select! {
+        _ = interval.tick() => !token.is_cancelled(),
+        _ = token.cancelled() => false,
+    }
+}
+
+/// 以 JSON 行输出服务产生的事件
+fn emit_event<T: Serialize>(event: &str, payload: &T) {
+    println!("{}", json!({ "event": event, "payload": payload }));
+}
+
+/// 调度器：定期为各活跃项目的就绪任务启动执行会话
+async fn run_scheduler(
+    db: DatabaseConnection,
+    coordinator: Arc<ShutdownCoordinator>,
+    period: Duration,
+    token: CancellationToken,
+) -> Result<()> {
+    let mut interval = tokio::time::interval(period);
+    while next_tick(&mut interval, &token).await {
+        // 关闭时等待本轮调度结束，进入关闭流程后不再开始新的一轮
+        let Ok(_work) = coordinator.begin_work() else {
+            return Ok(());
+        };
+        let projects = ProjectRepository::new(db.clone()).find_all().await?;
+        for project in projects.iter().filter(|project| project.status == "active") {
+            let config = match project_config_loader(&db, project).await.and_then(|loader| loader.load()) {
+                Ok(resolved) => resolved.config,
+                Err(e) => {
+                    eprintln!("项目 {} 的编排配置无效，跳过调度: {}", project.name, e);
+                    continue;
+                }
+            };
+            let report = ParallelPlanner::new(db.clone(), config.scheduler)
+                .launch_ready(project.project_id)
+                .await?;
+            if !report.launched.is_empty() {
+                emit_event("sessions_launched", &json!({ "project_id": project.project_id, "report": report }));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 事件投递：取出到期的通知，并生成到期报告计划的报告
+async fn run_event_dispatcher(
+    db: DatabaseConnection,
+    store: ArtifactStore,
+    period: Duration,
+    token: CancellationToken,
+) -> Result<()> {
+    let router = NotificationRouter::new(db);
+    let mut interval = tokio::time::interval(period);
+    while next_tick(&mut interval, &token).await {
+        let now = chrono::Utc::now();
+        for batch in router.take_due(now).await? {
+            emit_event("notification_delivered", &batch);
+        }
+        for generated in reporting::run_due_schedules(&store, now).await? {
+            emit_event(
+                "project_report_generated",
+                &json!({ "report": generated.report, "channels": generated.channels }),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// 插件投递：把新的领域事件投递给插件，并重试失败的投递
+async fn run_plugin_dispatcher(dispatcher: PluginDispatcher, period: Duration, token: CancellationToken) -> Result<()> {
+    let mut interval = tokio::time::interval(period);
+    while next_tick(&mut interval, &token).await {
+        let retried = dispatcher.retry_failed().await?;
+        let report = dispatcher.dispatch_pending().await?;
+        for delivery in retried.deliveries.iter().chain(&report.deliveries).filter(|delivery| !delivery.delivered) {
+            emit_event("plugin_delivery_failed", delivery);
+        }
+    }
+    Ok(())
+}
+
+/// SLA 监控：评估各项目的 SLA 和代码审查时限
+async fn run_sla_monitor(db: DatabaseConnection, period: Duration, token: CancellationToken) -> Result<()> {
+    let clock = SystemClock;
+    let mut interval = tokio::time::interval(period);
+    while next_tick(&mut interval, &token).await {
+        for event in sla::evaluate_all(&db, &clock).await? {
+            emit_event("sla_breached", &event);
+        }
+        let report = review_sla::evaluate_all(&db, chrono::Utc::now()).await?;
+        let notices = [
+            ("review_reminder", &report.reminders),
+            ("review_reassigned", &report.reassignments),
+            ("review_deadline_missed", &report.escalations),
+        ];
+        for (event, notices) in notices {
+            for notice in notices {
+                emit_event(event, notice);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 工件保留：按保留策略清理过期工件
+async fn run_retention(
+    store: ArtifactStore,
+    policy: RetentionPolicy,
+    period: Duration,
+    token: CancellationToken,
+) -> Result<()> {
+    let mut interval = tokio::time::interval(period);
+    while next_tick(&mut interval, &token).await {
+        let report = store.apply_retention(&policy).await?;
+        if report.removed_artifacts > 0 || report.removed_blobs > 0 {
+            emit_event("retention_applied", &report);
+        }
+    }
+    Ok(())
+}
+
+/// Agent池自动伸缩：按就绪任务队列调整各能力池的活跃Agent数
+async fn run_autoscaler(autoscaler: Arc<AgentAutoscaler>, period: Duration, token: CancellationToken) -> Result<()> {
+    let mut interval = tokio::time::interval(period);
+    while next_tick(&mut interval, &token).await {
+        for action in autoscaler.evaluate(chrono::Utc::now()).await? {
+            emit_event("agent_pool_scaled", &action);
+        }
+    }
+    Ok(())
+}
+
+/// Webhook 接入端点，监听失败时返回错误由监督器重启
+async fn run_webhook_ingestion(
+    bind_address: String,
+    ingestor: Arc<WebhookIngestor>,
+    token: CancellationToken,
+) -> Result<()> {
+    let listener = TcpListener::bind(&bind_address).await?;
+    eprintln!("Webhook 接入端点已启动: http://{}/webhooks/{{source}}/{{project_id}}", bind_address);
+    tokio::select! {
+        result = webhook_ingestion::serve(listener, ingestor) => result,
+        _ = token.cancelled() => Ok(()),
+    }
+}
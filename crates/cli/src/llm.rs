@@ -0,0 +1,104 @@
+//! 外部命令形式的LLM提供方
+//!
+//! 无界面环境没有桌面端配置的提供方，改为执行用户指定的命令：提示词写入标准输入，
+//! 标准输出按行作为流式片段。模型参数通过 `SKER_LLM_MODEL`、`SKER_LLM_TEMPERATURE`、
+//! `SKER_LLM_MAX_TOKENS` 环境变量传给命令。
+
+use std::process::Stdio;
+
+use codex_database::llm_provider::{LlmCompletion, LlmFuture, LlmProvider, LlmRequest};
+use codex_database::{DatabaseError, Result};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// 通过 `sh -c` 执行的LLM命令
+pub struct CommandLlmProvider {
+    command: String,
+}
+
+impl CommandLlmProvider {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self { command: command.into() }
+    }
+
+    async fn run(&self, request: &LlmRequest, tokens: Option<UnboundedSender<String>>) -> Result<LlmCompletion> {
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(&self.command)
+            .env("SKER_LLM_MODEL", &request.model)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(temperature) = request.temperature {
+            command.env("SKER_LLM_TEMPERATURE", temperature.to_string());
+        }
+        if let Some(max_tokens) = request.max_tokens {
+            command.env("SKER_LLM_MAX_TOKENS", max_tokens.to_string());
+        }
+        let mut child = command
+            .spawn()
+            .map_err(|e| DatabaseError::business_logic(format!("启动LLM命令失败: {}", e)))?;
+
+        let mut stdin = child.stdin.take().expect("标准输入已设置为管道");
+        let prompt = request.prompt.clone();
+        // 命令可能不读取提示词就退出，写入失败不视为错误
+        let writer = tokio::spawn(async move {
+            let _ = stdin.write_all(prompt.as_bytes()).await;
+        });
+        let mut stderr = child.stderr.take().expect("标准错误已设置为管道");
+        let error_reader = tokio::spawn(async move {
+            let mut text = String::new();
+            let _ = stderr.read_to_string(&mut text).await;
+            text
+        });
+
+        let mut content = String::new();
+        let mut lines = BufReader::new(child.stdout.take().expect("标准输出已设置为管道")).lines();
+        while let Some(line) = lines
+            .next_line()
+            .await
+            .map_err(|e| DatabaseError::business_logic(format!("读取LLM输出失败: {}", e)))?
+        {
+            let delta = format!("{}\n", line);
+            if let Some(tokens) = &tokens {
+                let _ = tokens.send(delta.clone());
+            }
+            content.push_str(&delta);
+        }
+
+        let status = child
+            .wait()
+            .await
+            .map_err(|e| DatabaseError::business_logic(format!("等待LLM命令失败: {}", e)))?;
+        let _ = writer.await;
+        let stderr = error_reader.await.unwrap_or_default();
+        if !status.success() {
+            return Err(DatabaseError::business_logic(format!(
+                "LLM命令执行失败（{}）: {}",
+                status,
+                stderr.trim()
+            )));
+        }
+
+        // 命令不报告用量，按输出的词数估算
+        let tokens_used = content.split_whitespace().count() as u32;
+        Ok(LlmCompletion { content, tokens_used })
+    }
+}
+
+impl LlmProvider for CommandLlmProvider {
+    fn name(&self) -> &str {
+        "command"
+    }
+
+    fn complete<'a>(&'a self, request: &'a LlmRequest) -> LlmFuture<'a> {
+        Box::pin(self.run(request, None))
+    }
+
+    fn complete_streaming<'a>(&'a self, request: &'a LlmRequest, tokens: UnboundedSender<String>) -> LlmFuture<'a> {
+        Box::pin(self.run(request, Some(tokens)))
+    }
+}
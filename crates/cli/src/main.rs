@@ -0,0 +1,294 @@
+//! # sker 命令行
+//!
+//! 无界面环境（服务器、CI）使用的命令行工具，与桌面应用共用同一套仓储和编排服务。
+//! 所有子命令都支持 `--json`，以单个JSON文档输出结果，便于脚本处理。
+
+mod commands;
+mod daemon;
+mod llm;
+mod output;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand};
+use codex_database::reporting::ReportFormat;
+use uuid::Uuid;
+
+use crate::output::Output;
+
+#[derive(Debug, Parser)]
+#[command(name = "sker", version, about = "sker 多Agent协同开发命令行")]
+pub struct Cli {
+    /// 数据库连接URL
+    #[arg(long, global = true, env = "SKER_DATABASE_URL", default_value = "sqlite://sker_database.db?mode=rwc")]
+    pub database: String,
+
+    /// 执行命令的用户名，不存在时自动创建不可登录的服务账号
+    #[arg(long, global = true, env = "SKER_USER", default_value = "sker")]
+    pub user: String,
+
+    /// 以JSON格式输出结果
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// 项目管理
+    #[command(subcommand)]
+    Project(ProjectCommand),
+    /// 任务管理
+    #[command(subcommand)]
+    Task(TaskCommand),
+    /// Agent管理
+    #[command(subcommand)]
+    Agent(AgentCommand),
+    /// 调用外部LLM命令分解需求文档
+    Decompose(DecomposeArgs),
+    /// 按原始输入重放LLM会话，并与原始输出比较
+    ReplaySession(ReplaySessionArgs),
+    /// 提示词与模型的 A/B 评估
+    #[command(subcommand)]
+    Eval(EvalCommand),
+    /// 查看项目的执行波次
+    Schedule(ProjectArg),
+    /// 导出项目周报
+    ExportReport(ExportReportArgs),
+    /// 以守护进程方式运行调度、事件投递、SLA 监控等后台服务
+    Daemon(DaemonArgs),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProjectCommand {
+    /// 创建项目
+    Create(CreateProjectArgs),
+    /// 列出当前用户创建或参与的项目
+    List {
+        /// 列出所有用户的项目
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct CreateProjectArgs {
+    /// 项目名称
+    #[arg(long)]
+    pub name: String,
+    /// 仓库地址
+    #[arg(long)]
+    pub repository_url: String,
+    /// 工作区路径
+    #[arg(long)]
+    pub workspace: PathBuf,
+    /// 项目描述
+    #[arg(long)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TaskCommand {
+    /// 从JSON文件导入任务草稿（数组或带 tasks 字段的对象）
+    Import {
+        #[command(flatten)]
+        project: ProjectArg,
+        /// 任务文件，`-` 表示标准输入
+        #[arg(long, default_value = "-")]
+        file: PathBuf,
+    },
+    /// 列出项目任务
+    List {
+        #[command(flatten)]
+        project: ProjectArg,
+        /// 按状态过滤
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// 为就绪任务启动执行会话
+    Run(ProjectArg),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AgentCommand {
+    /// 列出当前用户的Agent
+    List {
+        /// 按状态过滤
+        #[arg(long)]
+        status: Option<String>,
+    },
+    /// 按能力矩阵批量创建Agent
+    Provision {
+        /// JSON格式的配置规格，`-` 表示标准输入
+        #[arg(long, default_value = "-")]
+        spec: PathBuf,
+        /// 只输出将要创建的Agent，不写入数据库
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct ProjectArg {
+    /// 项目ID
+    #[arg(long)]
+    pub project: Uuid,
+}
+
+#[derive(Debug, Args)]
+pub struct DecomposeArgs {
+    /// 需求文档ID
+    #[arg(long)]
+    pub document: Uuid,
+    /// LLM命令，通过 `sh -c` 执行：提示词写入标准输入，标准输出作为回复
+    #[arg(long, env = "SKER_LLM_COMMAND")]
+    pub llm_command: String,
+    /// 把分解结果创建为项目任务
+    #[arg(long)]
+    pub apply: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ReplaySessionArgs {
+    /// LLM会话ID
+    #[arg(long)]
+    pub session: Uuid,
+    /// LLM命令，通过 `sh -c` 执行：提示词写入标准输入，标准输出作为回复
+    #[arg(long, env = "SKER_LLM_COMMAND")]
+    pub llm_command: String,
+    /// 重放使用的模型，默认使用每次调用的原始模型
+    #[arg(long)]
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum EvalCommand {
+    /// 从JSON文件保存评估集
+    SaveSet {
+        /// 评估集文件，`-` 表示标准输入
+        #[arg(long, default_value = "-")]
+        file: PathBuf,
+        /// 所属项目ID，未指定时为全局评估集
+        #[arg(long)]
+        project: Option<Uuid>,
+        /// 覆盖已有的评估集
+        #[arg(long)]
+        set: Option<Uuid>,
+    },
+    /// 对评估集运行一组变体并比较结果
+    Run(EvalRunArgs),
+    /// 查看已保存的评估报告
+    Report {
+        /// 评估运行ID
+        #[arg(long)]
+        run: Uuid,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct EvalRunArgs {
+    /// 评估集ID
+    #[arg(long)]
+    pub set: Uuid,
+    /// 变体文件（JSON数组），第一个变体为基线
+    #[arg(long)]
+    pub variants: PathBuf,
+    /// LLM命令，通过 `sh -c` 执行：提示词写入标准输入，标准输出作为回复
+    #[arg(long, env = "SKER_LLM_COMMAND")]
+    pub llm_command: String,
+    /// LLM裁判命令，未指定时跳过裁判属性
+    #[arg(long, requires = "judge_model")]
+    pub judge_command: Option<String>,
+    /// LLM裁判使用的模型
+    #[arg(long)]
+    pub judge_model: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportReportArgs {
+    #[command(flatten)]
+    pub project: ProjectArg,
+    /// 报告格式：markdown 或 html
+    #[arg(long, default_value = "markdown")]
+    pub format: ReportFormat,
+    /// 自定义模板文件
+    #[arg(long)]
+    pub template: Option<PathBuf>,
+    /// 输出文件，未指定时写到标准输出
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+    /// 统计周期的结束时间（RFC 3339），默认为当前时间
+    #[arg(long)]
+    pub period_end: Option<chrono::DateTime<chrono::Utc>>,
+    /// 每千token的价格，用于估算成本
+    #[arg(long)]
+    pub cost_per_1k_tokens: Option<f64>,
+}
+
+#[derive(Debug, Args)]
+pub struct DaemonArgs {
+    /// 数据目录，存放工件和关闭标记
+    #[arg(long, env = "SKER_DATA_DIR", default_value = ".sker")]
+    pub data_dir: PathBuf,
+    /// 健康检查端点的监听地址
+    #[arg(long, default_value = "127.0.0.1:8790")]
+    pub health_bind: String,
+    /// Webhook 接入端点的监听地址，未指定或未配置任何密钥时不启动
+    #[arg(long)]
+    pub webhook_bind: Option<String>,
+    /// GitHub webhook 签名密钥
+    #[arg(long, env = "SKER_GITHUB_WEBHOOK_SECRET", hide_env_values = true)]
+    pub github_webhook_secret: Option<String>,
+    /// GitLab webhook 令牌
+    #[arg(long, env = "SKER_GITLAB_WEBHOOK_TOKEN", hide_env_values = true)]
+    pub gitlab_webhook_token: Option<String>,
+    /// 调度间隔（秒）
+    #[arg(long, default_value_t = 30)]
+    pub scheduler_interval_secs: u64,
+    /// 通知和定时报告的投递间隔（秒）
+    #[arg(long, default_value_t = 30)]
+    pub dispatch_interval_secs: u64,
+    /// SLA 评估间隔（秒）
+    #[arg(long, default_value_t = 60)]
+    pub sla_interval_secs: u64,
+    /// 工件保留清理间隔（秒）
+    #[arg(long, default_value_t = 3600)]
+    pub retention_interval_secs: u64,
+    /// 工件最长保留天数
+    #[arg(long, default_value_t = 30)]
+    pub retention_max_age_days: u32,
+    /// 工件存储总大小上限（字节）
+    #[arg(long)]
+    pub retention_max_bytes: Option<u64>,
+    /// 关闭时等待后台服务结束的宽限期（秒）
+    #[arg(long, default_value_t = 10)]
+    pub grace_period_secs: u64,
+    /// 插件清单目录（`*.toml`），未指定时不启动插件投递
+    #[arg(long, env = "SKER_PLUGIN_DIR")]
+    pub plugin_dir: Option<PathBuf>,
+    /// 单个插件处理一个事件的超时（秒）
+    #[arg(long, default_value_t = 10)]
+    pub plugin_timeout_secs: u64,
+    /// Agent池自动伸缩配置（JSON），未指定时不启动自动伸缩
+    #[arg(long, env = "SKER_AUTOSCALE_CONFIG")]
+    pub autoscale_config: Option<PathBuf>,
+    /// Agent池自动伸缩的评估间隔（秒）
+    #[arg(long, default_value_t = 30)]
+    pub autoscale_interval_secs: u64,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let output = Output::new(cli.json);
+    match commands::run(cli, &output).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            output.error(&e);
+            ExitCode::FAILURE
+        }
+    }
+}
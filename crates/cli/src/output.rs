@@ -0,0 +1,45 @@
+//! 命令输出：文本模式面向人，JSON模式向标准输出写入单个JSON文档
+
+use serde::Serialize;
+use serde_json::json;
+
+/// 输出方式
+#[derive(Debug, Clone, Copy)]
+pub struct Output {
+    json: bool,
+}
+
+impl Output {
+    pub fn new(json: bool) -> Self {
+        Self { json }
+    }
+
+    /// 输出命令结果，文本模式下使用 `text` 渲染
+    pub fn emit<T: Serialize>(&self, value: &T, text: impl FnOnce(&T) -> String) -> anyhow::Result<()> {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(value)?);
+        } else {
+            let text = text(value);
+            if !text.is_empty() {
+                println!("{}", text.trim_end());
+            }
+        }
+        Ok(())
+    }
+
+    /// 输出进度信息，始终写到标准错误，JSON模式下不输出
+    pub fn progress(&self, message: &str) {
+        if !self.json {
+            eprintln!("{}", message);
+        }
+    }
+
+    /// 输出错误，JSON模式下同样写到标准错误以免和结果混在一起
+    pub fn error(&self, error: &anyhow::Error) {
+        if self.json {
+            eprintln!("{}", json!({ "error": format!("{:#}", error) }));
+        } else {
+            eprintln!("错误: {:#}", error);
+        }
+    }
+}
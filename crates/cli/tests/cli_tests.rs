@@ -0,0 +1,276 @@
+//! sker 命令行端到端测试：在临时 SQLite 数据库上执行二进制
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use codex_database::repository::requirement_document_repository::CreateRequirementDocumentData;
+use codex_database::repository::RequirementDocumentRepository;
+use codex_database::{initialize_database, DatabaseConfig};
+use serde_json::Value;
+
+fn sker(database: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sker"))
+        .arg("--database")
+        .arg(format!("sqlite://{}?mode=rwc", database.display()))
+        .args(["--user", "ci", "--json"])
+        .args(args)
+        .env_remove("SKER_LLM_COMMAND")
+        .output()
+        .expect("执行 sker 失败")
+}
+
+fn sker_json(database: &Path, args: &[&str]) -> Value {
+    let output = sker(database, args);
+    assert!(
+        output.status.success(),
+        "sker {:?} 失败: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("输出应为JSON")
+}
+
+#[test]
+fn test_project_task_schedule_and_report_commands() {
+    let dir = tempfile::tempdir().unwrap();
+    let database = dir.path().join("sker.db");
+    let workspace = dir.path().join("workspace");
+    std::fs::create_dir(&workspace).unwrap();
+    std::fs::write(workspace.join("sker.toml"), "[scheduler]\nmax_parallel_sessions = 2\n").unwrap();
+
+    let project = sker_json(
+        &database,
+        &[
+            "project", "create",
+            "--name", "命令行项目",
+            "--repository-url", "https://example.com/repo.git",
+            "--workspace", workspace.to_str().unwrap(),
+        ],
+    );
+    let project_id = project["project_id"].as_str().unwrap().to_string();
+
+    let projects = sker_json(&database, &["project", "list"]);
+    assert_eq!(projects.as_array().unwrap().len(), 1);
+    assert_eq!(projects[0]["name"], "命令行项目");
+
+    let tasks_file = dir.path().join("tasks.json");
+    std::fs::write(
+        &tasks_file,
+        r#"{"tasks": [
+            {"title": "设计数据库表", "description": "用户表和会话表", "acceptance_criteria": ["迁移可重复执行"], "priority": "high"},
+            {"title": "实现登录接口", "description": "基于会话表"}
+        ]}"#,
+    )
+    .unwrap();
+    let imported = sker_json(
+        &database,
+        &["task", "import", "--project", &project_id, "--file", tasks_file.to_str().unwrap()],
+    );
+    assert_eq!(imported.as_array().unwrap().len(), 2);
+    assert_eq!(imported[0]["priority"], "high");
+    assert_eq!(imported[0]["acceptance_criteria"], serde_json::json!(["迁移可重复执行"]));
+    assert_eq!(imported[1]["task_type"], "development");
+
+    let listed = sker_json(&database, &["task", "list", "--project", &project_id, "--status", "pending"]);
+    assert_eq!(listed.as_array().unwrap().len(), 2);
+
+    let view = sker_json(&database, &["schedule", "--project", &project_id]);
+    assert_eq!(view["max_parallel_sessions"], 2);
+    let scheduled: usize = view["waves"].as_array().unwrap().iter().map(|w| w["tasks"].as_array().unwrap().len()).sum();
+    assert_eq!(scheduled, 2);
+
+    let agents = sker_json(&database, &["agent", "list"]);
+    assert!(agents.as_array().unwrap().is_empty());
+
+    let spec_file = dir.path().join("agents.json");
+    std::fs::write(
+        &spec_file,
+        r#"{"roles": [{"role": "backend", "capabilities": ["backend_development"], "count": 2}]}"#,
+    )
+    .unwrap();
+    let planned = sker_json(&database, &["agent", "provision", "--spec", spec_file.to_str().unwrap(), "--dry-run"]);
+    assert_eq!(planned.as_array().unwrap().len(), 2);
+    assert!(sker_json(&database, &["agent", "list"]).as_array().unwrap().is_empty());
+    let provisioned = sker_json(&database, &["agent", "provision", "--spec", spec_file.to_str().unwrap()]);
+    assert_eq!(provisioned["created"][1]["name"], "backend-02");
+    assert_eq!(sker_json(&database, &["agent", "list"]).as_array().unwrap().len(), 2);
+
+    let report_path = dir.path().join("report.md");
+    let report = sker_json(
+        &database,
+        &["export-report", "--project", &project_id, "--output", report_path.to_str().unwrap()],
+    );
+    assert_eq!(report["format"], "markdown");
+    assert!(std::fs::read_to_string(&report_path).unwrap().contains("命令行项目"));
+
+    // 错误以JSON写到标准错误，退出码非零
+    let missing = sker(&database, &["task", "list", "--project", &uuid::Uuid::new_v4().to_string()]);
+    assert!(!missing.status.success());
+    let error: Value = serde_json::from_slice(&missing.stderr).unwrap();
+    assert!(error["error"].as_str().unwrap().contains("项目不存在"));
+}
+
+#[test]
+fn test_decompose_runs_llm_command_and_applies_tasks() {
+    let dir = tempfile::tempdir().unwrap();
+    let database = dir.path().join("sker.db");
+    let project = sker_json(
+        &database,
+        &[
+            "project", "create",
+            "--name", "分解项目",
+            "--repository-url", "https://example.com/repo.git",
+            "--workspace", dir.path().to_str().unwrap(),
+        ],
+    );
+    let project_id: uuid::Uuid = project["project_id"].as_str().unwrap().parse().unwrap();
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let document = runtime.block_on(async {
+        let db = initialize_database(&DatabaseConfig::file(&database)).await.unwrap();
+        RequirementDocumentRepository::new(db)
+            .create(CreateRequirementDocumentData {
+                project_id,
+                title: "用户登录".to_string(),
+                content: "支持邮箱和密码登录".to_string(),
+                document_type: "prd".to_string(),
+            })
+            .await
+            .unwrap()
+    });
+
+    // 命令读取提示词并检查其中包含需求内容，再输出任务列表
+    let llm_command = r#"grep -q 邮箱 && echo '{"tasks": [{"title": "实现登录接口", "description": "邮箱和密码"},' && echo '{"title": "编写登录测试", "description": ""}]}'"#;
+    let document_id = document.document_id.to_string();
+    let result = sker_json(
+        &database,
+        &["decompose", "--document", &document_id, "--llm-command", llm_command, "--apply"],
+    );
+    let titles: Vec<&str> = result["tasks"].as_array().unwrap().iter().map(|t| t["title"].as_str().unwrap()).collect();
+    assert_eq!(titles, vec!["实现登录接口", "编写登录测试"]);
+    assert_eq!(result["created"].as_array().unwrap().len(), 2);
+
+    let listed = sker_json(&database, &["task", "list", "--project", &project_id.to_string()]);
+    assert_eq!(listed.as_array().unwrap().len(), 2);
+
+    // 按原始提示词重放分解会话：相同命令输出一致，不同命令的输出差异记录在报告中
+    let session_id = result["llm_session_id"].as_str().unwrap().to_string();
+    let same = sker_json(&database, &["replay-session", "--session", &session_id, "--llm-command", llm_command]);
+    assert_eq!(same["total_calls"], 1);
+    assert_eq!(same["changed_calls"], 0);
+    let changed = sker_json(
+        &database,
+        &["replay-session", "--session", &session_id, "--llm-command", "cat > /dev/null; echo '1. 实现登录接口'", "--model", "other-model"],
+    );
+    assert_eq!(changed["changed_calls"], 1);
+    assert_eq!(changed["model_override"], "other-model");
+    assert_eq!(changed["calls"][0]["replay_model"], "other-model");
+    assert!(changed["calls"][0]["diff"].as_str().unwrap().contains("+1. 实现登录接口"));
+
+    let failed = sker(&database, &["decompose", "--document", &document_id, "--llm-command", "exit 3"]);
+    assert!(!failed.status.success());
+    assert!(String::from_utf8_lossy(&failed.stderr).contains("LLM命令执行失败"));
+}
+
+#[test]
+fn test_eval_commands_compare_variants() {
+    let dir = tempfile::tempdir().unwrap();
+    let database = dir.path().join("sker.db");
+    let set_file = dir.path().join("set.json");
+    std::fs::write(
+        &set_file,
+        r#"{"name": "分解", "cases": [{"name": "登录", "inputs": {"feature": "登录"},
+            "expected": [{"type": "contains", "text": "登录"}, {"type": "min_tasks", "count": 2}]}]}"#,
+    )
+    .unwrap();
+    let variants_file = dir.path().join("variants.json");
+    std::fs::write(
+        &variants_file,
+        r#"[{"name": "简短", "template": "分解：{{feature}}", "model": "a"},
+            {"name": "详细", "template": "请按JSON分解：{{feature}}", "model": "b"}]"#,
+    )
+    .unwrap();
+
+    let set = sker_json(&database, &["eval", "save-set", "--file", set_file.to_str().unwrap()]);
+    let set_id = set["eval_set_id"].as_str().unwrap().to_string();
+
+    // 提示词要求JSON时输出两个任务，否则只输出一行
+    let llm_command = r#"if grep -q JSON; then echo '{"tasks": [{"title": "实现登录"}, {"title": "测试登录"}]}'; else echo '1. 登录'; fi"#;
+    let report = sker_json(
+        &database,
+        &["eval", "run", "--set", &set_id, "--variants", variants_file.to_str().unwrap(), "--llm-command", llm_command],
+    );
+    assert_eq!(report["variants"][0]["mean_score"], 0.5);
+    assert_eq!(report["variants"][1]["mean_score"], 1.0);
+    assert_eq!(report["deltas"][0]["improvements"][0], "登录");
+
+    let run_id = report["run_id"].as_str().unwrap().to_string();
+    let stored = sker_json(&database, &["eval", "report", "--run", &run_id]);
+    assert_eq!(stored["deltas"], report["deltas"]);
+}
+
+fn http_get(address: &str, path: &str) -> Option<String> {
+    use std::io::{Read, Write};
+    let mut stream = std::net::TcpStream::connect(address).ok()?;
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).ok()?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+    Some(response)
+}
+
+#[cfg(unix)]
+#[test]
+fn test_daemon_serves_health_and_shuts_down_cleanly_on_sigterm() {
+    let dir = tempfile::tempdir().unwrap();
+    let database = dir.path().join("sker.db");
+    let data_dir = dir.path().join("data");
+    let address = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    };
+
+    let mut daemon = Command::new(env!("CARGO_BIN_EXE_sker"))
+        .arg("--database")
+        .arg(format!("sqlite://{}?mode=rwc", database.display()))
+        .arg("daemon")
+        .arg("--data-dir")
+        .arg(&data_dir)
+        .args(["--health-bind", &address, "--scheduler-interval-secs", "1", "--sla-interval-secs", "1"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let mut health = None;
+    for _ in 0..200 {
+        health = http_get(&address, "/health");
+        if health.is_some() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    let health = health.expect("健康检查端点应可访问");
+    assert!(health.starts_with("HTTP/1.1 200"), "{}", health);
+    let body: Value = serde_json::from_str(health.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+    let services: Vec<&str> = body["services"].as_array().unwrap().iter().map(|s| s["name"].as_str().unwrap()).collect();
+    assert_eq!(services, vec!["scheduler", "event_dispatcher", "sla_monitor", "retention"]);
+
+    let status = Command::new("kill").args(["-TERM", &daemon.id().to_string()]).status().unwrap();
+    assert!(status.success());
+    let mut exit = None;
+    for _ in 0..200 {
+        exit = daemon.try_wait().unwrap();
+        if exit.is_some() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    let exit = exit.unwrap_or_else(|| {
+        let _ = daemon.kill();
+        panic!("守护进程未在收到 SIGTERM 后退出");
+    });
+    assert!(exit.success());
+
+    let marker: Value = serde_json::from_str(&std::fs::read_to_string(data_dir.join("shutdown.json")).unwrap()).unwrap();
+    assert_eq!(marker["clean"], true);
+}
@@ -0,0 +1,194 @@
+//! # 多Agent协议事件过程宏
+//!
+//! 每个事件结构体都要重复声明元数据字段、serde 派生和 TypeScript 导出属性，并手写
+//! `MultiAgentEvent` 实现。`#[multi_agent_event]` 统一生成这些样板代码：
+//! - 在字段最前面注入 `metadata: EventMetadata`；
+//! - 派生 `Debug`、`Clone`、`Serialize`、`Deserialize`，启用 `typescript` 特性时派生 `TS`；
+//! - 实现 `MultiAgentEvent`，事件类型名为结构体名去掉 `Event` 后缀后的 snake_case 形式；
+//! - 实现到 `EventEnvelope` 的 `From` 转换，信封中缺少同名变体时编译失败，避免新事件漏注册。
+//!
+//! 相关实体ID取自类型名以 `Id` 结尾（如 `TaskId`）或字段名以 `_id`、`_ids` 结尾的字段，
+//! `Option` 和 `Vec` 包装的字段会展开其中的值。
+//!
+//! ```ignore
+//! /// 任务被抢占事件
+//! #[multi_agent_event(critical)]
+//! pub struct TaskPreemptedEvent {
+//!     /// 被抢占的任务ID
+//!     pub preempted_task_id: TaskId,
+//! }
+//! ```
+//!
+//! 生成的代码通过 `::codex_multi_agent`、`::serde`、`::chrono` 和 `::ts_rs` 路径引用依赖。
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::parse::Parser;
+use syn::{parse_macro_input, Field, Fields, GenericArgument, ItemStruct, PathArguments, Type};
+
+/// 把结构体声明为多Agent协议事件
+///
+/// 可选参数 `critical` 表示该事件需要特殊处理，生成的 `is_critical` 返回 `true`。
+#[proc_macro_attribute]
+pub fn multi_agent_event(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut critical = false;
+    let args_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("critical") {
+            critical = true;
+            Ok(())
+        } else {
+            Err(meta.error("不支持的参数，可用参数：critical"))
+        }
+    });
+    parse_macro_input!(args with args_parser);
+    let item = parse_macro_input!(input as ItemStruct);
+
+    expand(item, critical)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(mut item: ItemStruct, critical: bool) -> syn::Result<TokenStream2> {
+    let ident = item.ident.clone();
+    let name = ident.to_string();
+    let variant_name = match name.strip_suffix("Event") {
+        Some(variant_name) if !variant_name.is_empty() => variant_name.to_string(),
+        _ => return Err(syn::Error::new_spanned(&ident, "事件结构体名必须以 Event 结尾")),
+    };
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&item.generics, "事件结构体不支持泛型参数"));
+    }
+
+    let Fields::Named(fields) = &mut item.fields else {
+        return Err(syn::Error::new_spanned(&ident, "事件结构体必须使用具名字段"));
+    };
+    if let Some(field) = fields
+        .named
+        .iter()
+        .find(|field| field.ident.as_ref().is_some_and(|ident| ident == "metadata"))
+    {
+        return Err(syn::Error::new_spanned(field, "metadata 字段由 #[multi_agent_event] 注入，无需手写"));
+    }
+
+    let entity_ids: Vec<TokenStream2> = fields.named.iter().filter_map(collect_entity_id).collect();
+    let metadata = Field::parse_named.parse2(quote! {
+        /// 事件元数据
+        pub metadata: ::codex_multi_agent::events::EventMetadata
+    })?;
+    fields.named.insert(0, metadata);
+
+    let variant = format_ident!("{}", variant_name);
+    let event_type = to_snake_case(&variant_name);
+    let attrs = std::mem::take(&mut item.attrs);
+    let is_critical = critical.then(|| {
+        quote! {
+            fn is_critical(&self) -> bool {
+                true
+            }
+        }
+    });
+
+    Ok(quote! {
+        #(#attrs)*
+        #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
+        #[cfg_attr(feature = "typescript", derive(::ts_rs::TS))]
+        #item
+
+        impl ::codex_multi_agent::events::MultiAgentEvent for #ident {
+            fn event_type(&self) -> &'static str {
+                #event_type
+            }
+
+            fn timestamp(&self) -> ::chrono::DateTime<::chrono::Utc> {
+                self.metadata.timestamp
+            }
+
+            fn related_entity_ids(&self) -> ::std::vec::Vec<::std::string::String> {
+                #[allow(unused_mut)]
+                let mut ids = ::std::vec::Vec::new();
+                #(#entity_ids)*
+                ids
+            }
+
+            #is_critical
+        }
+
+        impl ::std::convert::From<#ident> for ::codex_multi_agent::events::EventEnvelope {
+            fn from(event: #ident) -> Self {
+                Self::#variant(event)
+            }
+        }
+    })
+}
+
+/// 为实体ID字段生成收集代码，非ID字段返回 None
+fn collect_entity_id(field: &Field) -> Option<TokenStream2> {
+    let ident = field.ident.as_ref()?;
+    let field_name = ident.to_string();
+    let named_as_id = field_name.ends_with("_id") || field_name.ends_with("_ids");
+
+    match wrapped_type(&field.ty) {
+        Some(("Option", inner)) if named_as_id || is_id_type(inner) => Some(quote! {
+            if let ::std::option::Option::Some(id) = &self.#ident {
+                ids.push(::std::string::ToString::to_string(id));
+            }
+        }),
+        Some(("Vec", inner)) if named_as_id || is_id_type(inner) => Some(quote! {
+            ids.extend(self.#ident.iter().map(::std::string::ToString::to_string));
+        }),
+        None if named_as_id || is_id_type(&field.ty) => Some(quote! {
+            ids.push(::std::string::ToString::to_string(&self.#ident));
+        }),
+        _ => None,
+    }
+}
+
+/// 拆出 `Option<T>` 或 `Vec<T>` 的包装名和内部类型
+fn wrapped_type(ty: &Type) -> Option<(&'static str, &Type)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let wrapper = if segment.ident == "Option" {
+        "Option"
+    } else if segment.ident == "Vec" {
+        "Vec"
+    } else {
+        return None;
+    };
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first()? {
+        GenericArgument::Type(inner) => Some((wrapper, inner)),
+        _ => None,
+    }
+}
+
+/// 类型名以 `Id` 结尾且不带泛型参数的类型视为强类型ID
+fn is_id_type(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.path.segments.last().is_some_and(|segment| {
+        let name = segment.ident.to_string();
+        name.len() > 2 && name.ends_with("Id") && segment.arguments.is_empty()
+    })
+}
+
+/// 把 CamelCase 名称转换为 snake_case
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (index, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() {
+            if index > 0 {
+                snake.push('_');
+            }
+            snake.extend(ch.to_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+    snake
+}
@@ -4,7 +4,7 @@
 
 use codex_multi_agent::{
     types::*,
-    agent_management::{AgentConfig, GitConfig, ResourceLimits},
+    agent_management::{AgentApprovalPolicy, AgentConfig, GitConfig, ResourceLimits},
     project_management::{ProjectInfo, CodingStandards, ProjectType, ProjectPriority, 
                          TeamMember, Permission, ExternalDependency, DependencyType, 
                          EnvironmentConfig},
@@ -61,6 +61,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             max_network_bandwidth_kbps: Some(5000),
             max_execution_time_seconds: Some(5400), // 90分钟
         }),
+        approval_policy: AgentApprovalPolicy {
+            auto_approve_commands: vec!["npm test".to_string(), "npm run lint".to_string()],
+            always_ask_patterns: vec!["rm -rf *".to_string()],
+            network_access: false,
+        },
     };
     
     // 验证Agent配置
@@ -239,6 +244,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         ],
         subtasks: vec![],
         related_issues: vec!["#456".to_string(), "#789".to_string()],
+        ci_status: None,
     };
     
     // 验证Agent能力匹配
@@ -75,6 +75,10 @@ pub struct AgentConfig {
 
     /// 资源限制配置
     pub resource_limits: Option<ResourceLimits>,
+
+    /// 命令审批策略，未配置时沿用全局审批行为
+    #[serde(default)]
+    pub approval_policy: AgentApprovalPolicy,
 }
 
 /// Agent配置更新结构
@@ -125,6 +129,10 @@ pub struct AgentConfigUpdate {
     /// 更新资源限制
     #[serde(skip_serializing_if = "Option::is_none")]
     pub resource_limits: Option<Option<ResourceLimits>>,
+
+    /// 更新命令审批策略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval_policy: Option<AgentApprovalPolicy>,
 }
 
 /// Agent过滤器
@@ -300,6 +308,36 @@ pub struct ResourceLimits {
     pub max_execution_time_seconds: Option<u64>,
 }
 
+/// Agent命令审批策略
+///
+/// 默认值即全局审批行为：所有命令都需要人工确认，沙箱内不允许访问网络
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct AgentApprovalPolicy {
+    /// 自动批准的安全命令，按词前缀匹配，例如 "cargo test" 匹配 `cargo test --all`
+    #[serde(default)]
+    pub auto_approve_commands: Vec<String>,
+
+    /// 总是需要人工确认的命令模式，`*` 匹配任意字符，例如 "git push *"；优先于自动批准
+    #[serde(default)]
+    pub always_ask_patterns: Vec<String>,
+
+    /// 是否允许命令访问网络
+    #[serde(default)]
+    pub network_access: bool,
+}
+
+/// 命令审批结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(rename_all = "snake_case")]
+pub enum CommandApproval {
+    /// 自动批准
+    AutoApprove,
+    /// 需要人工确认
+    AskUser,
+}
+
 /// 性能指标
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
@@ -377,6 +415,239 @@ pub struct ErrorRecord {
     pub task_id: Option<TaskId>,
 }
 
+// ============================================================================
+// 性能仪表盘类型
+// ============================================================================
+
+/// 性能统计周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(rename_all = "snake_case")]
+pub enum MetricsPeriod {
+    /// 最近一天
+    Day,
+    /// 最近一周
+    Week,
+    /// 最近一个月（30天）
+    Month,
+    /// 最近一个季度（90天）
+    Quarter,
+    /// 全部历史
+    All,
+}
+
+impl MetricsPeriod {
+    /// 字符串表示
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetricsPeriod::Day => "day",
+            MetricsPeriod::Week => "week",
+            MetricsPeriod::Month => "month",
+            MetricsPeriod::Quarter => "quarter",
+            MetricsPeriod::All => "all",
+        }
+    }
+
+    /// 统计周期的起始时间，全部历史返回 None
+    pub fn start_from(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let days = match self {
+            MetricsPeriod::Day => 1,
+            MetricsPeriod::Week => 7,
+            MetricsPeriod::Month => 30,
+            MetricsPeriod::Quarter => 90,
+            MetricsPeriod::All => return None,
+        };
+        Some(now - chrono::Duration::days(days))
+    }
+}
+
+impl std::fmt::Display for MetricsPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for MetricsPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(MetricsPeriod::Day),
+            "week" => Ok(MetricsPeriod::Week),
+            "month" => Ok(MetricsPeriod::Month),
+            "quarter" => Ok(MetricsPeriod::Quarter),
+            "all" => Ok(MetricsPeriod::All),
+            _ => Err(format!("未知的统计周期: {}", s)),
+        }
+    }
+}
+
+/// 周期性能快照（来自定期汇总的性能指标记录）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct PerformanceSnapshot {
+    /// 周期开始时间
+    pub period_start: DateTime<Utc>,
+
+    /// 周期结束时间
+    pub period_end: DateTime<Utc>,
+
+    /// 完成任务数
+    pub tasks_completed: u32,
+
+    /// 成功任务数
+    pub tasks_successful: u32,
+
+    /// 平均完成时间（小时）
+    pub avg_completion_hours: f64,
+
+    /// 平均代码质量（0-10）
+    pub avg_code_quality: f64,
+
+    /// 综合绩效评分（0-10）
+    pub performance_score: f64,
+}
+
+/// 智能体在统计周期内的性能汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct AgentMetricsReport {
+    /// Agent ID
+    pub agent_id: AgentId,
+
+    /// Agent名称
+    pub agent_name: String,
+
+    /// 统计周期
+    pub period: MetricsPeriod,
+
+    /// 周期开始时间，全部历史时为空
+    pub period_start: Option<DateTime<Utc>>,
+
+    /// 周期结束时间
+    pub period_end: DateTime<Utc>,
+
+    /// 已结束的任务数
+    pub tasks_completed: u32,
+
+    /// 成功任务数
+    pub tasks_successful: u32,
+
+    /// 失败任务数
+    pub tasks_failed: u32,
+
+    /// 成功率（0.0-1.0）
+    pub success_rate: f64,
+
+    /// 平均完成时间（分钟）
+    pub avg_completion_minutes: Option<f64>,
+
+    /// 平均质量评分（0.0-1.0）
+    pub avg_quality_score: Option<f64>,
+
+    /// 综合绩效评分（0-10）
+    pub performance_score: f64,
+
+    /// 各任务类型的完成数
+    pub task_type_breakdown: HashMap<String, u32>,
+
+    /// 使用过的技术及次数
+    pub technologies: HashMap<String, u32>,
+
+    /// 最近的周期性能快照，按时间升序
+    pub trend: Vec<PerformanceSnapshot>,
+}
+
+/// 团队排行榜条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct LeaderboardEntry {
+    /// 排名，从1开始
+    pub rank: u32,
+
+    /// Agent ID
+    pub agent_id: AgentId,
+
+    /// Agent名称
+    pub agent_name: String,
+
+    /// 已结束的任务数
+    pub tasks_completed: u32,
+
+    /// 成功任务数
+    pub tasks_successful: u32,
+
+    /// 成功率（0.0-1.0）
+    pub success_rate: f64,
+
+    /// 平均完成时间（分钟）
+    pub avg_completion_minutes: Option<f64>,
+
+    /// 平均质量评分（0.0-1.0）
+    pub avg_quality_score: Option<f64>,
+
+    /// 综合绩效评分（0-10）
+    pub performance_score: f64,
+}
+
+/// 项目团队排行榜
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct TeamLeaderboard {
+    /// 项目ID
+    pub project_id: ProjectId,
+
+    /// 统计周期
+    pub period: MetricsPeriod,
+
+    /// 生成时间
+    pub generated_at: DateTime<Utc>,
+
+    /// 按绩效评分降序排列的条目
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+/// 工作历史条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct WorkHistoryEntry {
+    /// 历史记录ID
+    pub history_id: String,
+
+    /// Agent ID
+    pub agent_id: AgentId,
+
+    /// 任务ID
+    pub task_id: TaskId,
+
+    /// 任务标题（任务已删除时为空）
+    pub task_title: Option<String>,
+
+    /// 任务类型
+    pub task_type: String,
+
+    /// 开始时间
+    pub started_at: DateTime<Utc>,
+
+    /// 完成时间
+    pub completed_at: Option<DateTime<Utc>>,
+
+    /// 是否成功
+    pub success: Option<bool>,
+
+    /// 完成时间（分钟）
+    pub completion_time_minutes: Option<u32>,
+
+    /// 质量评分（0.0-1.0）
+    pub quality_score: Option<f64>,
+
+    /// 使用的技术栈
+    pub technologies_used: Vec<String>,
+
+    /// 错误信息
+    pub error_message: Option<String>,
+}
+
 // ============================================================================
 // 请求和响应类型
 // ============================================================================
@@ -449,6 +720,8 @@ impl AgentConfig {
             return Err("priority_weight must be between 0.0 and 1.0".to_string());
         }
 
+        self.approval_policy.validate()?;
+
         Ok(())
     }
 
@@ -463,6 +736,132 @@ impl AgentConfig {
     }
 }
 
+/// 需要访问网络的命令前缀，未开启网络访问时不自动批准
+const NETWORK_COMMAND_PREFIXES: &[&str] = &[
+    "curl",
+    "wget",
+    "ssh",
+    "scp",
+    "rsync",
+    "git clone",
+    "git fetch",
+    "git pull",
+    "git push",
+    "npm install",
+    "npm publish",
+    "pnpm install",
+    "yarn install",
+    "pip install",
+    "cargo install",
+    "cargo publish",
+];
+
+/// 出现这些 shell 语法时命令可能串联其他命令，不自动批准
+const SHELL_CONTROL_TOKENS: &[&str] = &["&&", "||", ";", "|", ">", "<", "`", "$("];
+
+impl AgentApprovalPolicy {
+    /// 从Agent的 `config` JSON 中读取 `approval_policy`，未配置时返回默认策略
+    pub fn from_agent_config_json(config: &serde_json::Value) -> Result<Self, String> {
+        match config.get("approval_policy") {
+            None | Some(serde_json::Value::Null) => Ok(Self::default()),
+            Some(value) => {
+                let policy: Self = serde_json::from_value(value.clone())
+                    .map_err(|e| format!("approval_policy is invalid: {}", e))?;
+                policy.validate()?;
+                Ok(policy)
+            }
+        }
+    }
+
+    /// 验证策略的有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.auto_approve_commands.iter().any(|command| command.trim().is_empty()) {
+            return Err("auto_approve_commands cannot contain empty commands".to_string());
+        }
+
+        if self.always_ask_patterns.iter().any(|pattern| pattern.trim().is_empty()) {
+            return Err("always_ask_patterns cannot contain empty patterns".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// 判断命令是否可以自动批准
+    ///
+    /// `command` 为待执行命令的参数列表；`bash -lc "<脚本>"` 形式按脚本内容判断。
+    pub fn evaluate_command(&self, command: &[String]) -> CommandApproval {
+        let script = command_script(command);
+        let words: Vec<&str> = script.split_whitespace().collect();
+        if words.is_empty() {
+            return CommandApproval::AskUser;
+        }
+
+        let always_ask = self
+            .always_ask_patterns
+            .iter()
+            .any(|pattern| wildcard_match(pattern.trim(), &script));
+        let chained = SHELL_CONTROL_TOKENS.iter().any(|token| script.contains(token));
+        let needs_network = NETWORK_COMMAND_PREFIXES.iter().any(|prefix| starts_with_words(&words, prefix));
+        if always_ask || chained || (needs_network && !self.network_access) {
+            return CommandApproval::AskUser;
+        }
+
+        if self
+            .auto_approve_commands
+            .iter()
+            .any(|safe| starts_with_words(&words, safe))
+        {
+            CommandApproval::AutoApprove
+        } else {
+            CommandApproval::AskUser
+        }
+    }
+}
+
+/// 取出命令实际执行的脚本文本
+fn command_script(command: &[String]) -> String {
+    match command {
+        [shell, flag, script] if is_shell(shell) && matches!(flag.as_str(), "-c" | "-lc") => script.trim().to_string(),
+        _ => command.join(" "),
+    }
+}
+
+fn is_shell(program: &str) -> bool {
+    let name = program.rsplit('/').next().unwrap_or(program);
+    matches!(name, "bash" | "sh" | "zsh")
+}
+
+/// 通配符匹配，`*` 匹配任意长度的字符
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// 命令是否以 `prefix` 中的全部词开头
+fn starts_with_words(words: &[&str], prefix: &str) -> bool {
+    let prefix: Vec<&str> = prefix.split_whitespace().collect();
+    !prefix.is_empty() && words.len() >= prefix.len() && words[..prefix.len()] == prefix[..]
+}
+
 impl AgentFilter {
     /// 创建只查询可用Agent的过滤器
     pub fn available_only() -> Self {
@@ -531,6 +930,7 @@ mod tests {
             priority_weight: 0.5,
             verbose_logging: false,
             resource_limits: None,
+            approval_policy: AgentApprovalPolicy::default(),
         };
 
         assert!(config.validate().is_ok());
@@ -558,6 +958,7 @@ mod tests {
             priority_weight: 0.5,
             verbose_logging: false,
             resource_limits: None,
+            approval_policy: AgentApprovalPolicy::default(),
         };
 
         assert!(config.has_capability(&AgentCapability::Testing));
@@ -574,6 +975,46 @@ mod tests {
         ]));
     }
 
+    #[test]
+    fn test_approval_policy() {
+        let command = |script: &str| vec!["bash".to_string(), "-lc".to_string(), script.to_string()];
+        let mut policy = AgentApprovalPolicy {
+            auto_approve_commands: vec!["cargo test".to_string(), "git status".to_string(), "curl".to_string()],
+            always_ask_patterns: vec!["* --release*".to_string()],
+            network_access: false,
+        };
+        assert!(policy.validate().is_ok());
+
+        assert_eq!(policy.evaluate_command(&command("cargo test --all")), CommandApproval::AutoApprove);
+        assert_eq!(
+            policy.evaluate_command(&["git".to_string(), "status".to_string()]),
+            CommandApproval::AutoApprove
+        );
+        // 只匹配完整的词，串联命令和总是确认的模式不自动批准
+        assert_eq!(policy.evaluate_command(&command("cargo testing")), CommandApproval::AskUser);
+        assert_eq!(policy.evaluate_command(&command("cargo test && rm -rf /")), CommandApproval::AskUser);
+        assert_eq!(policy.evaluate_command(&command("cargo test --release")), CommandApproval::AskUser);
+        assert_eq!(policy.evaluate_command(&command("ls")), CommandApproval::AskUser);
+
+        // 未开启网络访问时网络命令需要确认
+        assert_eq!(policy.evaluate_command(&command("curl https://example.com")), CommandApproval::AskUser);
+        policy.network_access = true;
+        assert_eq!(policy.evaluate_command(&command("curl https://example.com")), CommandApproval::AutoApprove);
+
+        // 默认策略沿用全局行为，全部需要确认
+        assert_eq!(
+            AgentApprovalPolicy::default().evaluate_command(&command("cargo test")),
+            CommandApproval::AskUser
+        );
+
+        let config = serde_json::json!({ "approval_policy": { "always_ask_patterns": [" "] } });
+        assert!(AgentApprovalPolicy::from_agent_config_json(&config).is_err());
+        assert_eq!(
+            AgentApprovalPolicy::from_agent_config_json(&serde_json::json!({})).unwrap(),
+            AgentApprovalPolicy::default()
+        );
+    }
+
     #[test]
     fn test_serialization() {
         let config = AgentConfig {
@@ -596,6 +1037,7 @@ mod tests {
             priority_weight: 0.5,
             verbose_logging: false,
             resource_limits: None,
+            approval_policy: AgentApprovalPolicy::default(),
         };
 
         let json = serde_json::to_string(&config).unwrap();
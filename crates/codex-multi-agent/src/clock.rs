@@ -0,0 +1,69 @@
+//! 时钟抽象
+//!
+//! 定时评估、保留清理和事件时间戳都依赖当前时间。通过 [`Clock`] 注入时间来源后，
+//! 生产环境使用 [`SystemClock`]，测试使用可手动拨动的 [`TestClock`]，不再需要 sleep 等待真实时间流逝。
+
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+/// 当前时间的来源
+pub trait Clock: Send + Sync + Debug {
+    /// 当前时间
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// 可在线程间共享的时钟
+pub type SharedClock = Arc<dyn Clock>;
+
+/// 系统时钟，返回真实的当前时间
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// 以共享时钟的形式创建系统时钟
+    pub fn shared() -> SharedClock {
+        Arc::new(SystemClock)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 测试时钟，时间只在调用 [`TestClock::set`] 或 [`TestClock::advance`] 时变化
+#[derive(Debug)]
+pub struct TestClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl TestClock {
+    /// 创建停在指定时间的测试时钟
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(now) }
+    }
+
+    /// 创建停在指定时间的共享测试时钟
+    pub fn shared(now: DateTime<Utc>) -> Arc<Self> {
+        Arc::new(Self::new(now))
+    }
+
+    /// 把时钟拨到指定时间
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+
+    /// 让时钟前进指定时长
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now += duration;
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
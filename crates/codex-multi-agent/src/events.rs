@@ -10,10 +10,12 @@
 //!
 //! 所有事件都包含时间戳和相关的上下文信息，支持事件溯源和审计。
 
+use crate::clock::{Clock, SystemClock};
 use crate::agent_management::*;
 use crate::llm_orchestration::*;
 use crate::project_management::*;
 use crate::types::*;
+use codex_multi_agent_macros::multi_agent_event;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -63,6 +65,10 @@ pub struct EventMetadata {
     /// 用户ID（如果有）
     pub user_id: Option<String>,
 
+    /// 关联ID，串联同一任务生命周期（分解、分配、执行、审查）内的事件
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+
     /// 事件优先级
     pub priority: EventPriority,
 
@@ -73,6 +79,20 @@ pub struct EventMetadata {
     pub custom_attributes: HashMap<String, serde_json::Value>,
 }
 
+impl EventMetadata {
+    /// 设置关联ID
+    pub fn with_correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    /// 设置事件时间戳
+    pub fn with_timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+}
+
 /// 事件来源枚举
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
@@ -90,6 +110,8 @@ pub enum EventSource {
     Scheduler,
     /// Webhook
     Webhook,
+    /// 安全守卫
+    Security,
 }
 
 /// 事件优先级枚举
@@ -107,17 +129,128 @@ pub enum EventPriority {
     Critical,
 }
 
+/// 执行日志事件类型
+///
+/// `execution_logs.event_type` 列的取值全集，与数据库crate共用。
+/// 以 snake_case 字符串存储和传输，前端按事件类型过滤、统计时使用同一套取值。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(rename_all = "snake_case")]
+pub enum EventType {
+    /// 执行会话开始
+    SessionStarted,
+    /// 执行会话成功结束
+    SessionCompleted,
+    /// 执行会话失败
+    SessionFailed,
+    /// 执行进度
+    Progress,
+    /// Git操作
+    GitOperation,
+    /// 切换分支
+    GitCheckout,
+    /// 文件变更
+    FileChange,
+    /// 请求模型
+    LlmRequest,
+    /// 模型响应
+    LlmResponse,
+    /// 工具调用
+    ToolCall,
+    /// 测试运行
+    TestRun,
+    /// 测试通过
+    TestsPassed,
+    /// 测试失败
+    TestsFailed,
+    /// 编译
+    Compilation,
+    /// 部署
+    Deployment,
+    /// 代码分析
+    CodeAnalysis,
+    /// 依赖安装
+    DependencyInstall,
+    /// 环境配置
+    EnvironmentSetup,
+    /// 执行错误
+    Error,
+}
+
+impl EventType {
+    /// 全部事件类型
+    pub const ALL: [EventType; 19] = [
+        EventType::SessionStarted,
+        EventType::SessionCompleted,
+        EventType::SessionFailed,
+        EventType::Progress,
+        EventType::GitOperation,
+        EventType::GitCheckout,
+        EventType::FileChange,
+        EventType::LlmRequest,
+        EventType::LlmResponse,
+        EventType::ToolCall,
+        EventType::TestRun,
+        EventType::TestsPassed,
+        EventType::TestsFailed,
+        EventType::Compilation,
+        EventType::Deployment,
+        EventType::CodeAnalysis,
+        EventType::DependencyInstall,
+        EventType::EnvironmentSetup,
+        EventType::Error,
+    ];
+
+    /// 存储使用的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventType::SessionStarted => "session_started",
+            EventType::SessionCompleted => "session_completed",
+            EventType::SessionFailed => "session_failed",
+            EventType::Progress => "progress",
+            EventType::GitOperation => "git_operation",
+            EventType::GitCheckout => "git_checkout",
+            EventType::FileChange => "file_change",
+            EventType::LlmRequest => "llm_request",
+            EventType::LlmResponse => "llm_response",
+            EventType::ToolCall => "tool_call",
+            EventType::TestRun => "test_run",
+            EventType::TestsPassed => "tests_passed",
+            EventType::TestsFailed => "tests_failed",
+            EventType::Compilation => "compilation",
+            EventType::Deployment => "deployment",
+            EventType::CodeAnalysis => "code_analysis",
+            EventType::DependencyInstall => "dependency_install",
+            EventType::EnvironmentSetup => "environment_setup",
+            EventType::Error => "error",
+        }
+    }
+}
+
+impl std::fmt::Display for EventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for EventType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EventType::ALL
+            .into_iter()
+            .find(|event_type| event_type.as_str() == s)
+            .ok_or_else(|| format!("未知的事件类型: {}", s))
+    }
+}
+
 // ============================================================================
 // Agent管理事件
 // ============================================================================
 
 /// Agent创建事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct AgentCreatedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// 新创建的Agent ID
     pub agent_id: AgentId,
 
@@ -132,12 +265,8 @@ pub struct AgentCreatedEvent {
 }
 
 /// Agent更新事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct AgentUpdatedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// 被更新的Agent ID
     pub agent_id: AgentId,
 
@@ -158,12 +287,8 @@ pub struct AgentUpdatedEvent {
 }
 
 /// Agent删除事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct AgentDeletedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// 被删除的Agent ID
     pub agent_id: AgentId,
 
@@ -181,12 +306,8 @@ pub struct AgentDeletedEvent {
 }
 
 /// Agent状态变更事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct AgentStatusChangedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// Agent ID
     pub agent_id: AgentId,
 
@@ -204,12 +325,8 @@ pub struct AgentStatusChangedEvent {
 }
 
 /// Agent列表查询响应事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct AgentListResponseEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// Agent列表
     pub agents: Vec<AgentSummary>,
 
@@ -228,12 +345,8 @@ pub struct AgentListResponseEvent {
 // ============================================================================
 
 /// 项目创建事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct ProjectCreatedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// 新创建的项目ID
     pub project_id: ProjectId,
 
@@ -251,12 +364,8 @@ pub struct ProjectCreatedEvent {
 }
 
 /// 项目更新事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct ProjectUpdatedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// 被更新的项目ID
     pub project_id: ProjectId,
 
@@ -299,13 +408,44 @@ pub enum VersionChangeType {
     PreRelease,
 }
 
-/// 需求文档上传事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 项目成员变更事件
+#[multi_agent_event]
+pub struct ProjectMemberChangedEvent {
+    /// 项目ID
+    pub project_id: ProjectId,
+
+    /// 被变更的成员用户ID
+    pub user_id: String,
+
+    /// 变更类型
+    pub change_type: MemberChangeType,
+
+    /// 变更前角色
+    pub previous_role: Option<ProjectRole>,
+
+    /// 变更后角色
+    pub new_role: Option<ProjectRole>,
+
+    /// 操作者用户ID
+    pub changed_by: String,
+}
+
+/// 成员变更类型枚举
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
-pub struct RequirementsUploadedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
+#[serde(rename_all = "snake_case")]
+pub enum MemberChangeType {
+    /// 添加成员
+    Added,
+    /// 角色变更
+    RoleChanged,
+    /// 移除成员
+    Removed,
+}
 
+/// 需求文档上传事件
+#[multi_agent_event]
+pub struct RequirementsUploadedEvent {
     /// 项目ID
     pub project_id: ProjectId,
 
@@ -345,12 +485,8 @@ pub enum DocumentProcessingStatus {
 // ============================================================================
 
 /// 需求分解开始事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct RequirementDecompositionStartedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// LLM会话ID
     pub session_id: LlmSessionId,
 
@@ -368,12 +504,8 @@ pub struct RequirementDecompositionStartedEvent {
 }
 
 /// 需求分解完成事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct RequirementDecompositionCompletedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// LLM会话ID
     pub session_id: LlmSessionId,
 
@@ -396,13 +528,36 @@ pub struct RequirementDecompositionCompletedEvent {
     pub quality_score: Option<f32>,
 }
 
+/// 需求分解进度事件
+///
+/// 流式分解过程中按增量发送，携带目前已识别出的任务，供界面逐步渲染
+#[multi_agent_event]
+pub struct DecompositionProgressEvent {
+    /// LLM会话ID
+    pub session_id: LlmSessionId,
+
+    /// 项目ID
+    pub project_id: ProjectId,
+
+    /// 已收到的token片段数
+    pub tokens_received: u32,
+
+    /// 已收到的输出字符数
+    pub chars_received: u64,
+
+    /// 目前已识别出的任务标题
+    pub tasks_identified: Vec<String>,
+
+    /// 部分输出摘要（最近输出的片段）
+    pub partial_summary: String,
+
+    /// 输出是否已结束
+    pub finished: bool,
+}
+
 /// 任务分配完成事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct TaskAllocationCompletedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// LLM会话ID
     pub session_id: LlmSessionId,
 
@@ -426,12 +581,8 @@ pub struct TaskAllocationCompletedEvent {
 }
 
 /// LLM会话状态变更事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct LlmSessionStatusChangedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// LLM会话ID
     pub session_id: LlmSessionId,
 
@@ -502,12 +653,8 @@ pub struct LlmSessionStats {
 // ============================================================================
 
 /// 任务执行开始事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct TaskExecutionStartedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// 执行会话ID
     pub session_id: ExecutionSessionId,
 
@@ -568,14 +715,14 @@ pub struct QualityCheckConfig {
 }
 
 /// 任务进度更新事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct TaskProgressUpdatedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
+    /// 执行会话ID（父任务由子任务汇总进度时为空）
+    pub session_id: Option<ExecutionSessionId>,
 
-    /// 执行会话ID
-    pub session_id: ExecutionSessionId,
+    /// 任务ID
+    #[serde(default)]
+    pub task_id: Option<TaskId>,
 
     /// 进度信息
     pub progress_info: ProgressInfo,
@@ -687,12 +834,8 @@ pub enum IssueSeverity {
 }
 
 /// 任务执行完成事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct TaskExecutionCompletedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// 执行会话ID
     pub session_id: ExecutionSessionId,
 
@@ -739,6 +882,25 @@ pub struct TaskResult {
 
     /// 验收标准完成情况
     pub acceptance_criteria_status: HashMap<String, bool>,
+
+    /// Agent对本次结果的自我评估（旧版本Agent不上报）
+    #[serde(default)]
+    pub self_assessment: Option<SelfAssessment>,
+}
+
+/// Agent完成任务时的自我评估
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct SelfAssessment {
+    /// 对结果正确性的置信度（0.0-1.0）
+    pub confidence: f32,
+
+    /// 自我评估摘要
+    pub summary: String,
+
+    /// Agent自认为存在风险、需要审查者关注的地方
+    #[serde(default)]
+    pub concerns: Vec<String>,
 }
 
 /// 任务执行状态枚举
@@ -805,10 +967,54 @@ pub enum ArtifactType {
     BuildScript,
     /// 二进制文件
     Binary,
+    /// 代码变更补丁
+    Patch,
     /// 其他
     Other,
 }
 
+impl ArtifactType {
+    /// 存储使用的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArtifactType::SourceCode => "source_code",
+            ArtifactType::TestFile => "test_file",
+            ArtifactType::ConfigFile => "config_file",
+            ArtifactType::Documentation => "documentation",
+            ArtifactType::DatabaseScript => "database_script",
+            ArtifactType::BuildScript => "build_script",
+            ArtifactType::Binary => "binary",
+            ArtifactType::Patch => "patch",
+            ArtifactType::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for ArtifactType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ArtifactType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "source_code" => Ok(ArtifactType::SourceCode),
+            "test_file" => Ok(ArtifactType::TestFile),
+            "config_file" => Ok(ArtifactType::ConfigFile),
+            "documentation" => Ok(ArtifactType::Documentation),
+            "database_script" => Ok(ArtifactType::DatabaseScript),
+            "build_script" => Ok(ArtifactType::BuildScript),
+            "binary" => Ok(ArtifactType::Binary),
+            "patch" => Ok(ArtifactType::Patch),
+            "other" => Ok(ArtifactType::Other),
+            _ => Err(format!("未知的工件类型: {}", s)),
+        }
+    }
+}
+
 /// 执行摘要
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
@@ -832,17 +1038,45 @@ pub struct ExecutionSummary {
     pub impact_on_other_tasks: Vec<String>,
 }
 
+/// 任务被抢占事件
+/// 紧急任务到达且没有空闲Agent时，低优先级的运行中任务被检查点保存并重新排队
+#[multi_agent_event]
+pub struct TaskPreemptedEvent {
+    /// 被抢占的任务ID
+    pub preempted_task_id: TaskId,
+
+    /// 被中断的执行会话ID
+    pub preempted_session_id: ExecutionSessionId,
+
+    /// 被抢占任务的优先级
+    pub preempted_priority: TaskPriority,
+
+    /// 抢占时被抢占任务的完成度（0.0-1.0）
+    pub completion_percentage: f32,
+
+    /// 被抢占任务已执行时间（分钟）
+    pub elapsed_minutes: u32,
+
+    /// 发起抢占的紧急任务ID
+    pub preempting_task_id: TaskId,
+
+    /// 紧急任务的优先级
+    pub preempting_priority: TaskPriority,
+
+    /// 让出并改为执行紧急任务的Agent ID
+    pub agent_id: AgentId,
+
+    /// 抢占原因
+    pub reason: String,
+}
+
 // ============================================================================
 // Git和代码审查事件
 // ============================================================================
 
 /// Git分支创建事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct GitBranchCreatedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// 分支名称
     pub branch_name: String,
 
@@ -880,12 +1114,8 @@ pub enum BranchType {
 }
 
 /// 代码审查请求事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct CodeReviewRequestedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// 审查ID
     pub review_id: ReviewId,
 
@@ -932,6 +1162,10 @@ pub struct PullRequestInfo {
 
     /// 提交数量
     pub commits_count: u32,
+
+    /// 源分支最新提交的 CI 状态，尚无 CI 结果时为空
+    #[serde(default)]
+    pub ci_status: Option<CiStatus>,
 }
 
 /// 审查优先级枚举
@@ -950,12 +1184,8 @@ pub enum ReviewPriority {
 }
 
 /// 代码审查完成事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct CodeReviewCompletedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// 审查ID
     pub review_id: ReviewId,
 
@@ -1037,17 +1267,227 @@ pub enum SuggestionType {
     Documentation,
 }
 
+// ============================================================================
+// SLA事件
+// ============================================================================
+
+/// SLA类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(rename_all = "snake_case")]
+pub enum SlaType {
+    /// 任务等待分配的时间
+    PendingTime,
+    /// 任务执行时长
+    ExecutionTime,
+    /// 里程碑截止日期
+    MilestoneDeadline,
+}
+
+impl SlaType {
+    /// 获取存储使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlaType::PendingTime => "pending_time",
+            SlaType::ExecutionTime => "execution_time",
+            SlaType::MilestoneDeadline => "milestone_deadline",
+        }
+    }
+}
+
+impl std::fmt::Display for SlaType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// SLA违约严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(rename_all = "snake_case")]
+pub enum SlaSeverity {
+    /// 超出阈值或进入里程碑缓冲期
+    Warning,
+    /// 严重超出阈值或已错过截止日期
+    Critical,
+}
+
+impl SlaSeverity {
+    /// 获取存储使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlaSeverity::Warning => "warning",
+            SlaSeverity::Critical => "critical",
+        }
+    }
+}
+
+impl std::fmt::Display for SlaSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// SLA违约事件
+/// 首次发现违约或违约升级为更高严重程度时产生
+#[multi_agent_event(critical)]
+pub struct SlaBreachedEvent {
+    /// 违约记录ID
+    pub breach_id: String,
+
+    /// 所属项目ID
+    pub project_id: ProjectId,
+
+    /// SLA类型
+    pub sla_type: SlaType,
+
+    /// 严重程度
+    pub severity: SlaSeverity,
+
+    /// 违约对象ID（任务ID或里程碑ID）
+    pub subject_id: String,
+
+    /// 违约对象名称
+    pub subject_name: String,
+
+    /// SLA阈值（分钟）
+    pub threshold_minutes: i64,
+
+    /// 实际耗时（分钟），里程碑为距截止日期的剩余时间，已逾期时为负数
+    pub actual_minutes: i64,
+
+    /// 里程碑截止日期
+    pub deadline: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// 外部Webhook事件
+// ============================================================================
+
+/// Webhook 来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookSource {
+    /// GitHub
+    Github,
+    /// GitLab
+    Gitlab,
+}
+
+impl WebhookSource {
+    /// 获取存储使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookSource::Github => "github",
+            WebhookSource::Gitlab => "gitlab",
+        }
+    }
+}
+
+impl std::fmt::Display for WebhookSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for WebhookSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "github" => Ok(WebhookSource::Github),
+            "gitlab" => Ok(WebhookSource::Gitlab),
+            other => Err(format!("未知的webhook来源: {}", other)),
+        }
+    }
+}
+
+/// 外部仓库推送事件
+#[multi_agent_event]
+pub struct GitPushReceivedEvent {
+    /// 所属项目ID
+    pub project_id: ProjectId,
+
+    /// Webhook 来源
+    pub source: WebhookSource,
+
+    /// 仓库名称
+    pub repository: String,
+
+    /// 推送的分支，标签推送时为标签引用
+    pub git_ref: String,
+
+    /// 推送前的提交
+    pub before: String,
+
+    /// 推送后的提交
+    pub after: String,
+
+    /// 本次推送包含的提交数
+    pub commit_count: u32,
+
+    /// 推送者
+    pub pusher: Option<String>,
+}
+
+/// 外部CI状态事件
+#[multi_agent_event]
+pub struct CiStatusReceivedEvent {
+    /// 所属项目ID
+    pub project_id: ProjectId,
+
+    /// Webhook 来源
+    pub source: WebhookSource,
+
+    /// 检查名称
+    pub check_name: String,
+
+    /// 检查的提交
+    pub commit_sha: String,
+
+    /// 检查的分支
+    pub branch: Option<String>,
+
+    /// 检查状态
+    pub status: CiStatus,
+
+    /// 详情链接
+    pub details_url: Option<String>,
+}
+
+/// 外部议题更新事件
+#[multi_agent_event]
+pub struct ExternalIssueUpdatedEvent {
+    /// 所属项目ID
+    pub project_id: ProjectId,
+
+    /// Webhook 来源
+    pub source: WebhookSource,
+
+    /// 议题编号
+    pub issue_number: u64,
+
+    /// 议题标题
+    pub title: String,
+
+    /// 触发更新的动作，例如 opened、closed、reopened
+    pub action: String,
+
+    /// 议题当前状态
+    pub state: String,
+
+    /// 议题链接
+    pub url: Option<String>,
+}
+
 // ============================================================================
 // 系统状态和错误事件
 // ============================================================================
 
 /// 系统状态变更事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event]
 pub struct SystemStatusChangedEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// 之前的系统状态
     pub previous_status: SystemStatus,
 
@@ -1081,13 +1521,23 @@ pub enum SystemStatus {
     Maintenance,
 }
 
+/// 编排配置变更事件
+/// 项目的 sker.toml、组织默认值或界面覆盖变更并通过校验后发出
+#[multi_agent_event]
+pub struct OrchestrationConfigChangedEvent {
+    /// 所属项目ID
+    pub project_id: Option<ProjectId>,
+
+    /// 项目配置文件路径
+    pub config_path: String,
+
+    /// 生效值发生变化的配置键（点分路径，如 scheduler.max_parallel_sessions）
+    pub changed_keys: Vec<String>,
+}
+
 /// 错误事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[cfg_attr(feature = "typescript", derive(TS))]
+#[multi_agent_event(critical)]
 pub struct ErrorEvent {
-    /// 事件元数据
-    pub metadata: EventMetadata,
-
     /// 错误类型
     pub error_type: String,
 
@@ -1110,6 +1560,122 @@ pub struct ErrorEvent {
     pub suggested_actions: Vec<String>,
 }
 
+// ============================================================================
+// 事件信封
+// ============================================================================
+
+/// 定义事件信封枚举，并把 [`MultiAgentEvent`] 委托给具体事件
+///
+/// 使用 `#[multi_agent_event]` 声明的事件会生成到信封的 `From` 转换，
+/// 新增事件后需要在这里登记同名变体，否则编译失败。
+macro_rules! event_envelope {
+    ($($(#[$doc:meta])* $variant:ident($event:ty),)*) => {
+        /// 事件信封
+        /// 以 `event_type` 字段区分具体事件，用于在事件总线、持久化和前端之间传递任意事件
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[cfg_attr(feature = "typescript", derive(TS))]
+        #[serde(tag = "event_type", content = "event", rename_all = "snake_case")]
+        #[allow(clippy::large_enum_variant)]
+        pub enum EventEnvelope {
+            $($(#[$doc])* $variant($event),)*
+        }
+
+        impl MultiAgentEvent for EventEnvelope {
+            fn event_type(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(event) => event.event_type(),)*
+                }
+            }
+
+            fn timestamp(&self) -> DateTime<Utc> {
+                match self {
+                    $(Self::$variant(event) => event.timestamp(),)*
+                }
+            }
+
+            fn related_entity_ids(&self) -> Vec<String> {
+                match self {
+                    $(Self::$variant(event) => event.related_entity_ids(),)*
+                }
+            }
+
+            fn is_critical(&self) -> bool {
+                match self {
+                    $(Self::$variant(event) => event.is_critical(),)*
+                }
+            }
+        }
+
+        impl EventEnvelope {
+            /// 事件元数据
+            pub fn metadata(&self) -> &EventMetadata {
+                match self {
+                    $(Self::$variant(event) => &event.metadata,)*
+                }
+            }
+        }
+    };
+}
+
+event_envelope! {
+    /// Agent创建事件
+    AgentCreated(AgentCreatedEvent),
+    /// Agent更新事件
+    AgentUpdated(AgentUpdatedEvent),
+    /// Agent删除事件
+    AgentDeleted(AgentDeletedEvent),
+    /// Agent状态变更事件
+    AgentStatusChanged(AgentStatusChangedEvent),
+    /// Agent列表查询响应事件
+    AgentListResponse(AgentListResponseEvent),
+    /// 项目创建事件
+    ProjectCreated(ProjectCreatedEvent),
+    /// 项目更新事件
+    ProjectUpdated(ProjectUpdatedEvent),
+    /// 项目成员变更事件
+    ProjectMemberChanged(ProjectMemberChangedEvent),
+    /// 需求文档上传事件
+    RequirementsUploaded(RequirementsUploadedEvent),
+    /// 需求分解开始事件
+    RequirementDecompositionStarted(RequirementDecompositionStartedEvent),
+    /// 需求分解完成事件
+    RequirementDecompositionCompleted(RequirementDecompositionCompletedEvent),
+    /// 需求分解进度事件
+    DecompositionProgress(DecompositionProgressEvent),
+    /// 任务分配完成事件
+    TaskAllocationCompleted(TaskAllocationCompletedEvent),
+    /// LLM会话状态变更事件
+    LlmSessionStatusChanged(LlmSessionStatusChangedEvent),
+    /// 任务执行开始事件
+    TaskExecutionStarted(TaskExecutionStartedEvent),
+    /// 任务进度更新事件
+    TaskProgressUpdated(TaskProgressUpdatedEvent),
+    /// 任务执行完成事件
+    TaskExecutionCompleted(TaskExecutionCompletedEvent),
+    /// 任务被抢占事件
+    TaskPreempted(TaskPreemptedEvent),
+    /// Git分支创建事件
+    GitBranchCreated(GitBranchCreatedEvent),
+    /// 代码审查请求事件
+    CodeReviewRequested(CodeReviewRequestedEvent),
+    /// 代码审查完成事件
+    CodeReviewCompleted(CodeReviewCompletedEvent),
+    /// SLA违约事件
+    SlaBreached(SlaBreachedEvent),
+    /// 外部仓库推送事件
+    GitPushReceived(GitPushReceivedEvent),
+    /// 外部CI状态事件
+    CiStatusReceived(CiStatusReceivedEvent),
+    /// 外部议题更新事件
+    ExternalIssueUpdated(ExternalIssueUpdatedEvent),
+    /// 系统状态变更事件
+    SystemStatusChanged(SystemStatusChangedEvent),
+    /// 编排配置变更事件
+    OrchestrationConfigChanged(OrchestrationConfigChangedEvent),
+    /// 错误事件
+    Error(ErrorEvent),
+}
+
 // ============================================================================
 // 事件工厂和工具函数
 // ============================================================================
@@ -1121,16 +1687,27 @@ pub struct EventFactory;
 impl EventFactory {
     /// 创建基础事件元数据
     pub fn create_metadata(
+        event_type: &str,
+        source: EventSource,
+        priority: EventPriority,
+    ) -> EventMetadata {
+        Self::create_metadata_with_clock(event_type, source, priority, &SystemClock)
+    }
+
+    /// 使用指定时钟创建基础事件元数据
+    pub fn create_metadata_with_clock(
         _event_type: &str,
         source: EventSource,
         priority: EventPriority,
+        clock: &dyn Clock,
     ) -> EventMetadata {
         EventMetadata {
             event_id: uuid::Uuid::new_v4().to_string(),
-            timestamp: Utc::now(),
+            timestamp: clock.now(),
             source,
             session_id: None,
             user_id: None,
+            correlation_id: None,
             priority,
             tags: vec![],
             custom_attributes: HashMap::new(),
@@ -1156,6 +1733,61 @@ impl EventFactory {
         }
     }
 
+    /// 创建项目成员变更事件
+    pub fn project_member_changed(
+        project_id: ProjectId,
+        user_id: String,
+        previous_role: Option<ProjectRole>,
+        new_role: Option<ProjectRole>,
+        changed_by: String,
+    ) -> ProjectMemberChangedEvent {
+        let change_type = match (previous_role, new_role) {
+            (None, _) => MemberChangeType::Added,
+            (Some(_), None) => MemberChangeType::Removed,
+            (Some(_), Some(_)) => MemberChangeType::RoleChanged,
+        };
+
+        ProjectMemberChangedEvent {
+            metadata: Self::create_metadata(
+                "project_member_changed",
+                EventSource::User,
+                EventPriority::Normal,
+            ),
+            project_id,
+            user_id,
+            change_type,
+            previous_role,
+            new_role,
+            changed_by,
+        }
+    }
+
+    /// 创建需求分解进度事件
+    pub fn decomposition_progress(
+        session_id: LlmSessionId,
+        project_id: ProjectId,
+        tokens_received: u32,
+        chars_received: u64,
+        tasks_identified: Vec<String>,
+        partial_summary: String,
+        finished: bool,
+    ) -> DecompositionProgressEvent {
+        DecompositionProgressEvent {
+            metadata: Self::create_metadata(
+                "decomposition_progress",
+                EventSource::System,
+                EventPriority::Low,
+            ),
+            session_id,
+            project_id,
+            tokens_received,
+            chars_received,
+            tasks_identified,
+            partial_summary,
+            finished,
+        }
+    }
+
     /// 创建任务执行开始事件
     pub fn task_execution_started(
         session_id: ExecutionSessionId,
@@ -1178,6 +1810,197 @@ impl EventFactory {
         }
     }
 
+    /// 创建父任务进度汇总事件
+    /// 子任务状态变化后由父任务汇总结果生成
+    pub fn parent_task_progress_updated(rollup: &TaskProgressRollup) -> TaskProgressUpdatedEvent {
+        let mut quality_metrics = HashMap::new();
+        quality_metrics.insert("total_subtasks".to_string(), rollup.total_subtasks as f32);
+        quality_metrics.insert("completed_subtasks".to_string(), rollup.completed_subtasks as f32);
+        quality_metrics.insert("failed_subtasks".to_string(), rollup.failed_subtasks as f32);
+
+        TaskProgressUpdatedEvent {
+            metadata: Self::create_metadata(
+                "task_progress_updated",
+                EventSource::System,
+                EventPriority::Normal,
+            )
+            .with_correlation_id(rollup.parent_task_id.to_string()),
+            session_id: None,
+            task_id: Some(rollup.parent_task_id.clone()),
+            progress_info: ProgressInfo {
+                completion_percentage: rollup.completion_percentage,
+                completed_steps: vec![],
+                current_step: None,
+                remaining_steps: vec![],
+                elapsed_minutes: 0,
+                estimated_remaining_minutes: 0,
+                quality_metrics,
+            },
+            current_phase: format!(
+                "子任务汇总: {}/{} 已完成",
+                rollup.completed_subtasks, rollup.total_subtasks
+            ),
+            next_steps: vec![],
+            encountered_issues: vec![],
+        }
+    }
+
+    /// 创建任务抢占事件
+    /// 关联ID设置为紧急任务，便于把抢占与紧急任务的后续执行串联起来
+    #[allow(clippy::too_many_arguments)]
+    pub fn task_preempted(
+        preempted_task_id: TaskId,
+        preempted_session_id: ExecutionSessionId,
+        preempted_priority: TaskPriority,
+        completion_percentage: f32,
+        elapsed_minutes: u32,
+        preempting_task_id: TaskId,
+        agent_id: AgentId,
+        reason: String,
+    ) -> TaskPreemptedEvent {
+        TaskPreemptedEvent {
+            metadata: Self::create_metadata(
+                "task_preempted",
+                EventSource::System,
+                EventPriority::High,
+            )
+            .with_correlation_id(preempting_task_id.to_string()),
+            preempted_task_id,
+            preempted_session_id,
+            preempted_priority,
+            completion_percentage,
+            elapsed_minutes,
+            preempting_task_id,
+            preempting_priority: TaskPriority::Critical,
+            agent_id,
+            reason,
+        }
+    }
+
+    /// 创建SLA违约事件
+    #[allow(clippy::too_many_arguments)]
+    pub fn sla_breached(
+        breach_id: String,
+        project_id: ProjectId,
+        sla_type: SlaType,
+        severity: SlaSeverity,
+        subject_id: String,
+        subject_name: String,
+        threshold_minutes: i64,
+        actual_minutes: i64,
+        deadline: Option<DateTime<Utc>>,
+    ) -> SlaBreachedEvent {
+        let priority = match severity {
+            SlaSeverity::Warning => EventPriority::High,
+            SlaSeverity::Critical => EventPriority::Critical,
+        };
+        SlaBreachedEvent {
+            metadata: Self::create_metadata("sla_breached", EventSource::System, priority)
+                .with_correlation_id(subject_id.clone()),
+            breach_id,
+            project_id,
+            sla_type,
+            severity,
+            subject_id,
+            subject_name,
+            threshold_minutes,
+            actual_minutes,
+            deadline,
+        }
+    }
+
+    /// 创建外部仓库推送事件
+    #[allow(clippy::too_many_arguments)]
+    pub fn git_push_received(
+        project_id: ProjectId,
+        source: WebhookSource,
+        repository: String,
+        git_ref: String,
+        before: String,
+        after: String,
+        commit_count: u32,
+        pusher: Option<String>,
+    ) -> GitPushReceivedEvent {
+        GitPushReceivedEvent {
+            metadata: Self::create_metadata("git_push_received", EventSource::Webhook, EventPriority::Normal)
+                .with_correlation_id(after.clone()),
+            project_id,
+            source,
+            repository,
+            git_ref,
+            before,
+            after,
+            commit_count,
+            pusher,
+        }
+    }
+
+    /// 创建外部CI状态事件
+    pub fn ci_status_received(
+        project_id: ProjectId,
+        source: WebhookSource,
+        check_name: String,
+        commit_sha: String,
+        branch: Option<String>,
+        status: CiStatus,
+        details_url: Option<String>,
+    ) -> CiStatusReceivedEvent {
+        // 失败的检查需要尽快处理
+        let priority = if status.is_red() { EventPriority::High } else { EventPriority::Normal };
+        CiStatusReceivedEvent {
+            metadata: Self::create_metadata("ci_status_received", EventSource::Webhook, priority)
+                .with_correlation_id(commit_sha.clone()),
+            project_id,
+            source,
+            check_name,
+            commit_sha,
+            branch,
+            status,
+            details_url,
+        }
+    }
+
+    /// 创建外部议题更新事件
+    pub fn external_issue_updated(
+        project_id: ProjectId,
+        source: WebhookSource,
+        issue_number: u64,
+        title: String,
+        action: String,
+        state: String,
+        url: Option<String>,
+    ) -> ExternalIssueUpdatedEvent {
+        ExternalIssueUpdatedEvent {
+            metadata: Self::create_metadata("external_issue_updated", EventSource::Webhook, EventPriority::Normal)
+                .with_correlation_id(format!("{}#{}", source, issue_number)),
+            project_id,
+            source,
+            issue_number,
+            title,
+            action,
+            state,
+            url,
+        }
+    }
+
+    /// 创建编排配置变更事件
+    pub fn orchestration_config_changed(
+        project_id: Option<ProjectId>,
+        config_path: String,
+        changed_keys: Vec<String>,
+    ) -> OrchestrationConfigChangedEvent {
+        OrchestrationConfigChangedEvent {
+            metadata: Self::create_metadata(
+                "orchestration_config_changed",
+                EventSource::System,
+                EventPriority::Normal,
+            ),
+            project_id,
+            config_path,
+            changed_keys,
+        }
+    }
+
     /// 创建错误事件
     pub fn error(
         error_type: String,
@@ -1195,6 +2018,25 @@ impl EventFactory {
             suggested_actions: vec![],
         }
     }
+
+    /// 创建安全守卫拦截操作时的错误事件
+    pub fn security_violation(
+        error_type: String,
+        error_message: String,
+        related_entity_id: Option<String>,
+        suggested_actions: Vec<String>,
+    ) -> ErrorEvent {
+        ErrorEvent {
+            metadata: Self::create_metadata("security_violation", EventSource::Security, EventPriority::High),
+            error_type,
+            error_message,
+            stack_trace: None,
+            related_entity_id,
+            error_code: None,
+            can_auto_recover: false,
+            suggested_actions,
+        }
+    }
 }
 
 // ============================================================================
@@ -1220,6 +2062,7 @@ mod tests {
             priority_weight: 0.5,
             verbose_logging: false,
             resource_limits: None,
+            approval_policy: Default::default(),
         };
 
         let event = EventFactory::agent_created(agent_id.clone(), config, "test-user".to_string());
@@ -1247,10 +2090,62 @@ mod tests {
         assert_eq!(issue.severity, deserialized.severity);
     }
 
+    #[test]
+    fn test_parent_task_progress_event() {
+        let parent_id = TaskId::new();
+        let rollup = TaskProgressRollup::from_subtasks(
+            parent_id.clone(),
+            &[SubtaskProgress {
+                task_id: TaskId::new(),
+                status: TaskStatus::Completed,
+                completion_percentage: 1.0,
+                weight: 1.0,
+            }],
+        );
+
+        let event = EventFactory::parent_task_progress_updated(&rollup);
+
+        assert_eq!(event.task_id, Some(parent_id));
+        assert!(event.session_id.is_none());
+        assert_eq!(event.progress_info.completion_percentage, 1.0);
+    }
+
+    #[test]
+    fn test_project_member_changed_event() {
+        let project_id = ProjectId::new();
+        let added = EventFactory::project_member_changed(
+            project_id.clone(),
+            "user-1".to_string(),
+            None,
+            Some(ProjectRole::Contributor),
+            "owner-1".to_string(),
+        );
+        assert_eq!(added.change_type, MemberChangeType::Added);
+
+        let removed = EventFactory::project_member_changed(
+            project_id,
+            "user-1".to_string(),
+            Some(ProjectRole::Contributor),
+            None,
+            "owner-1".to_string(),
+        );
+        assert_eq!(removed.change_type, MemberChangeType::Removed);
+    }
+
     #[test]
     fn test_priority_ordering() {
         assert!(EventPriority::Critical > EventPriority::High);
         assert!(ReviewPriority::Urgent > ReviewPriority::Normal);
         assert!(IssueSeverity::Blocker > IssueSeverity::Critical);
     }
+
+    #[test]
+    fn test_event_type_parse() {
+        assert_eq!("tests_failed".parse::<EventType>(), Ok(EventType::TestsFailed));
+        assert_eq!(EventType::GitOperation.to_string(), "git_operation");
+        assert!("test_failed".parse::<EventType>().is_err());
+
+        let unique: std::collections::HashSet<_> = EventType::ALL.iter().map(EventType::as_str).collect();
+        assert_eq!(unique.len(), EventType::ALL.len());
+    }
 }
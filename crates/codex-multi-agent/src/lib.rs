@@ -12,6 +12,7 @@
 //! - **安全管理**: 权限控制和审计日志
 //! - **性能监控**: 系统性能和Agent效率监控
 //! - **外部集成**: 与外部工具和系统的集成
+//! - **远程执行**: 与远程工作进程交换注册、心跳和任务租约消息
 //! 
 //! ## 使用示例
 //! 
@@ -42,6 +43,9 @@
 #![deny(missing_docs)]
 #![warn(clippy::all)]
 
+// 让 #[multi_agent_event] 生成的 ::codex_multi_agent 路径在本crate内也能解析
+extern crate self as codex_multi_agent;
+
 // 核心类型模块
 pub mod types;
 
@@ -57,9 +61,28 @@ pub mod llm_orchestration;
 // pub mod performance;
 // pub mod integration;
 
+// 时钟抽象
+pub mod clock;
+
 // 事件定义模块
 pub mod events;
 
+// 远程工作进程协议
+pub mod worker_protocol;
+
+// 协议载荷校验
+pub mod validation;
+
+// WebAssembly导出
+#[cfg(feature = "wasm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wasm")))]
+pub mod wasm;
+
+// 属性测试支持
+#[cfg(any(test, feature = "test-util"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub mod test_util;
+
 // TypeScript支持
 #[cfg(feature = "typescript")]
 #[cfg_attr(docsrs, doc(cfg(feature = "typescript")))]
@@ -71,19 +94,36 @@ pub use types::*;
 // 重新导出事件类型
 pub use events::*;
 
+// 事件声明宏
+pub use codex_multi_agent_macros::multi_agent_event;
+
+pub use clock::{Clock, SharedClock, SystemClock, TestClock};
+
 // 重新导出各功能模块的主要类型
-pub use agent_management::{AgentConfig, AgentConfigUpdate, AgentFilter, AgentSummary, GitConfig};
+pub use agent_management::{
+    AgentApprovalPolicy, AgentConfig, AgentConfigUpdate, AgentFilter, AgentSummary, CommandApproval, GitConfig,
+    AgentMetricsReport, LeaderboardEntry, MetricsPeriod, PerformanceSnapshot, TeamLeaderboard, WorkHistoryEntry,
+};
 
 pub use project_management::{
     ProjectInfo, ProjectUpdate, RequirementDocument, CodingStandards, QualityGates,
-    TestRequirements, DocumentType, DocumentPriority,
+    TestRequirements, DocumentType, DocumentPriority, ProjectRole, ProjectMemberInfo,
+    OrganizationRole, OrganizationSettings, OrganizationInfo, TeamInfo,
 };
 
 pub use llm_orchestration::{
     ProjectContext, TaskInfo, TaskAssignment, TaskDependency, DependencyType,
     CodebaseInfo, LanguageStats, FrameworkInfo, TimelineRequirements, Milestone,
+    SubtaskProgress, TaskProgressRollup, SimulationAgent, SimulationReport, SimulationConflict,
+    SimulationConflictType, SimulationBottleneck, SharedContextCategory, SharedContextEntry,
+    PlanValidator, PlanValidationReport, PlanViolation, PlanViolationType, AssignmentPolicy,
+    AssignmentStrategy,
 };
 
+pub use worker_protocol::{CoordinatorMessage, TaskLease, WorkerMessage, WORKER_PROTOCOL_VERSION};
+
+pub use validation::{PayloadValidation, PlanValidationRequest};
+
 /// 版本信息
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
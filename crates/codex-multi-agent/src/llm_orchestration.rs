@@ -68,6 +68,10 @@ pub struct ProjectContext {
 
     /// 外部依赖状态
     pub external_dependencies_status: Vec<DependencyStatus>,
+
+    /// 项目共享上下文（已确定的决策、接口规范、命名约定等）
+    #[serde(default)]
+    pub shared_context: Vec<SharedContextEntry>,
 }
 
 /// 代码库信息
@@ -278,6 +282,27 @@ pub struct TimelineRequirements {
     pub work_calendar: WorkCalendar,
 }
 
+impl TimelineRequirements {
+    /// 各任务的截止时间：扣除缓冲后的目标完成时间与所属未完成里程碑的截止日期中最早者
+    pub fn task_deadlines(&self, tasks: &[TaskInfo]) -> HashMap<TaskId, DateTime<Utc>> {
+        let target = self.target_completion - chrono::Duration::hours(self.buffer_time_hours as i64);
+        let mut deadlines: HashMap<TaskId, DateTime<Utc>> =
+            tasks.iter().map(|task| (task.task_id.clone(), target)).collect();
+        for milestone in self
+            .milestone_deadlines
+            .iter()
+            .filter(|milestone| milestone.status != MilestoneStatus::Completed)
+        {
+            for task_id in &milestone.dependent_tasks {
+                if let Some(deadline) = deadlines.get_mut(task_id) {
+                    *deadline = (*deadline).min(milestone.deadline);
+                }
+            }
+        }
+        deadlines
+    }
+}
+
 /// 里程碑定义
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
@@ -428,6 +453,10 @@ pub struct TaskInfo {
 
     /// 相关问题/Bug ID
     pub related_issues: Vec<String>,
+
+    /// 最近一次执行对应的 CI 状态，尚无 CI 结果时为空
+    #[serde(default)]
+    pub ci_status: Option<CiStatus>,
 }
 
 /// 任务测试要求
@@ -574,6 +603,126 @@ pub enum DependencyStrength {
     Optional,
 }
 
+// ============================================================================
+// 任务层级和进度汇总
+// ============================================================================
+
+/// 子任务进度快照
+/// 用于计算父任务的汇总进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct SubtaskProgress {
+    /// 子任务ID
+    pub task_id: TaskId,
+
+    /// 子任务状态
+    pub status: TaskStatus,
+
+    /// 子任务完成度（0.0-1.0），子任务本身有下级任务时为其汇总值
+    pub completion_percentage: f32,
+
+    /// 权重（通常为预估工时），用于加权计算父任务进度
+    pub weight: f32,
+}
+
+/// 父任务进度汇总结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct TaskProgressRollup {
+    /// 父任务ID
+    pub parent_task_id: TaskId,
+
+    /// 参与统计的子任务数量（不含已取消）
+    pub total_subtasks: u32,
+
+    /// 已完成的子任务数量
+    pub completed_subtasks: u32,
+
+    /// 失败的子任务数量
+    pub failed_subtasks: u32,
+
+    /// 进行中的子任务数量
+    pub active_subtasks: u32,
+
+    /// 已取消的子任务数量
+    pub cancelled_subtasks: u32,
+
+    /// 加权完成度（0.0-1.0）
+    pub completion_percentage: f32,
+
+    /// 根据子任务推导出的父任务状态
+    pub derived_status: TaskStatus,
+}
+
+impl TaskProgressRollup {
+    /// 根据子任务快照计算父任务进度
+    ///
+    /// 已取消的子任务不参与完成度计算；全部子任务完成时父任务视为完成，
+    /// 仍有未结束子任务时父任务视为进行中，其余子任务都结束但存在失败时父任务视为失败。
+    pub fn from_subtasks(parent_task_id: TaskId, subtasks: &[SubtaskProgress]) -> Self {
+        let mut total_subtasks = 0;
+        let mut completed_subtasks = 0;
+        let mut failed_subtasks = 0;
+        let mut active_subtasks = 0;
+        let mut cancelled_subtasks = 0;
+        let mut pending_subtasks = 0;
+        let mut weighted_progress = 0.0_f32;
+        let mut total_weight = 0.0_f32;
+
+        for subtask in subtasks {
+            if subtask.status == TaskStatus::Cancelled {
+                cancelled_subtasks += 1;
+                continue;
+            }
+
+            total_subtasks += 1;
+            let weight = subtask.weight.max(1.0);
+            let progress = match subtask.status {
+                TaskStatus::Completed => 1.0,
+                _ => subtask.completion_percentage.clamp(0.0, 1.0),
+            };
+            weighted_progress += progress * weight;
+            total_weight += weight;
+
+            match subtask.status {
+                TaskStatus::Completed => completed_subtasks += 1,
+                TaskStatus::Failed => failed_subtasks += 1,
+                TaskStatus::Pending => pending_subtasks += 1,
+                _ => active_subtasks += 1,
+            }
+        }
+
+        let completion_percentage = if total_weight > 0.0 {
+            weighted_progress / total_weight
+        } else {
+            0.0
+        };
+
+        let derived_status = if total_subtasks == 0 {
+            TaskStatus::Pending
+        } else if completed_subtasks == total_subtasks {
+            TaskStatus::Completed
+        } else if active_subtasks > 0 || (pending_subtasks > 0 && completed_subtasks + failed_subtasks > 0) {
+            TaskStatus::InProgress
+        } else if failed_subtasks > 0 && pending_subtasks == 0 {
+            TaskStatus::Failed
+        } else {
+            TaskStatus::Pending
+        };
+
+        Self {
+            parent_task_id,
+            total_subtasks,
+            completed_subtasks,
+            failed_subtasks,
+            active_subtasks,
+            cancelled_subtasks,
+            completion_percentage,
+            derived_status,
+        }
+    }
+}
+
 // ============================================================================
 // 任务分配和调度
 // ============================================================================
@@ -625,6 +774,42 @@ pub enum AssignmentStrategy {
     AvailabilityBased,
     /// 混合策略
     Hybrid,
+    /// 基于历史成本，兼顾截止时间压力
+    CostOptimized,
+}
+
+/// 项目的任务分配策略和成本上限
+///
+/// 调度模拟目前区分 `cost_optimized` 和其他策略，其他策略都按混合策略（能力匹配、熟悉度、最早完成）分配。
+/// 成本上限只在 `cost_optimized` 下生效，成本取自 [`SimulationAgent::task_costs`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct AssignmentPolicy {
+    /// 分配策略
+    #[serde(default = "default_assignment_strategy")]
+    pub strategy: AssignmentStrategy,
+
+    /// 单个任务的成本上限，历史成本超过上限的Agent不参与分配
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost_per_task: Option<f64>,
+
+    /// 整个计划的成本上限，累计成本超过上限的任务不再分配
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total_cost: Option<f64>,
+}
+
+impl Default for AssignmentPolicy {
+    fn default() -> Self {
+        Self {
+            strategy: default_assignment_strategy(),
+            max_cost_per_task: None,
+            max_total_cost: None,
+        }
+    }
+}
+
+fn default_assignment_strategy() -> AssignmentStrategy {
+    AssignmentStrategy::Hybrid
 }
 
 /// 调度计划
@@ -931,6 +1116,80 @@ pub enum ExternalDependencyStatus {
     ScheduledMaintenance,
 }
 
+/// 共享上下文条目分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(rename_all = "snake_case")]
+pub enum SharedContextCategory {
+    /// 架构或技术决策
+    Decision,
+    /// 接口规范
+    ApiSpec,
+    /// 命名约定
+    NamingConvention,
+    /// 其他说明
+    Note,
+}
+
+impl SharedContextCategory {
+    /// 存储使用的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SharedContextCategory::Decision => "decision",
+            SharedContextCategory::ApiSpec => "api_spec",
+            SharedContextCategory::NamingConvention => "naming_convention",
+            SharedContextCategory::Note => "note",
+        }
+    }
+
+    /// 提示词中使用的中文名称
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SharedContextCategory::Decision => "决策",
+            SharedContextCategory::ApiSpec => "接口规范",
+            SharedContextCategory::NamingConvention => "命名约定",
+            SharedContextCategory::Note => "说明",
+        }
+    }
+}
+
+impl std::fmt::Display for SharedContextCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for SharedContextCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "decision" => Ok(SharedContextCategory::Decision),
+            "api_spec" => Ok(SharedContextCategory::ApiSpec),
+            "naming_convention" => Ok(SharedContextCategory::NamingConvention),
+            "note" => Ok(SharedContextCategory::Note),
+            _ => Err(format!("未知的共享上下文分类: {}", s)),
+        }
+    }
+}
+
+/// 项目共享上下文条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct SharedContextEntry {
+    /// 条目键
+    pub key: String,
+
+    /// 分类
+    pub category: SharedContextCategory,
+
+    /// 内容
+    pub value: String,
+
+    /// 版本号
+    pub version: u32,
+}
+
 /// 已完成任务摘要
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
@@ -957,6 +1216,862 @@ pub struct CompletedTaskSummary {
     pub lessons_learned: Vec<String>,
 }
 
+// ============================================================================
+// 调度模拟（Dry-run）
+// ============================================================================
+
+/// 已排期任务的 (开始时间, 结束时间, Agent下标, 依赖层级)
+type ScheduledSlot = (DateTime<Utc>, DateTime<Utc>, usize, usize);
+
+/// 判定Agent为瓶颈的利用率阈值
+pub const BOTTLENECK_UTILIZATION_THRESHOLD: f32 = 0.8;
+
+/// 参与模拟的Agent快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct SimulationAgent {
+    /// Agent ID
+    pub agent_id: AgentId,
+
+    /// Agent能力
+    pub capabilities: Vec<AgentCapability>,
+
+    /// 最早可接新任务的时间（None 表示立即可用）
+    pub available_from: Option<DateTime<Utc>>,
+
+    /// 由自评与审查结果校准出的置信度（0.0-1.0），None 表示没有足够的历史数据
+    #[serde(default)]
+    pub calibrated_confidence: Option<f32>,
+
+    /// Agent修改或负责过的路径（文件或目录），任务相关文件落在其中时优先分配给该Agent
+    #[serde(default)]
+    pub familiar_paths: Vec<String>,
+
+    /// 按任务类型统计的单个任务历史平均成本，没有历史记录的类型不在其中
+    #[serde(default)]
+    pub task_costs: HashMap<TaskType, f64>,
+}
+
+impl SimulationAgent {
+    /// 任务相关文件中Agent熟悉的数量
+    ///
+    /// 文件位于熟悉的目录下、与熟悉的文件相同或同在一个目录时视为熟悉
+    pub fn path_familiarity(&self, files: &[String]) -> usize {
+        files
+            .iter()
+            .filter(|file| {
+                let file = file.trim_start_matches("./");
+                self.familiar_paths.iter().any(|path| {
+                    let path = path.trim_start_matches("./").trim_end_matches('/');
+                    !path.is_empty()
+                        && (file == path
+                            || file.strip_prefix(path).is_some_and(|rest| rest.starts_with('/'))
+                            || parent_dir(file) == parent_dir(path) && parent_dir(file).is_some())
+                })
+            })
+            .count()
+    }
+}
+
+fn parent_dir(path: &str) -> Option<&str> {
+    path.rsplit_once('/').map(|(parent, _)| parent)
+}
+
+/// 模拟冲突类型
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(rename_all = "snake_case")]
+pub enum SimulationConflictType {
+    /// 没有具备所需能力的Agent
+    NoCapableAgent,
+    /// 依赖的任务不在本次模拟范围内（已忽略该依赖）
+    MissingDependency,
+    /// 存在循环依赖
+    CircularDependency,
+    /// 前置任务无法调度，导致本任务被阻塞
+    BlockedByDependency,
+    /// 候选Agent的成本都超过上限
+    ExceedsCostCeiling,
+}
+
+/// 模拟中发现的冲突
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct SimulationConflict {
+    /// 相关任务
+    pub task_id: TaskId,
+
+    /// 冲突类型
+    pub conflict_type: SimulationConflictType,
+
+    /// 冲突描述
+    pub description: String,
+}
+
+/// 模拟中识别出的资源瓶颈
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct SimulationBottleneck {
+    /// 瓶颈Agent
+    pub agent_id: AgentId,
+
+    /// 分配的任务数
+    pub assigned_tasks: u32,
+
+    /// 忙碌时长（小时）
+    pub busy_hours: u32,
+
+    /// 在整个计划周期内的利用率（0.0-1.0）
+    pub utilization: f32,
+
+    /// 位于关键路径上的任务数
+    pub critical_path_tasks: u32,
+}
+
+/// 调度模拟报告
+///
+/// 基于任务和Agent状态的副本运行分配与调度流程，不做任何持久化，供负责人在确认计划前预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct SimulationReport {
+    /// 项目ID
+    pub project_id: ProjectId,
+
+    /// 模拟起始时间
+    pub simulated_at: DateTime<Utc>,
+
+    /// 模拟得到的任务分配
+    pub assignments: Vec<TaskAssignment>,
+
+    /// 按依赖层级划分的执行时间线
+    pub timeline: Vec<ExecutionPhase>,
+
+    /// 关键路径
+    pub critical_path: Vec<TaskId>,
+
+    /// 冲突列表
+    pub conflicts: Vec<SimulationConflict>,
+
+    /// 瓶颈列表（按利用率降序）
+    pub bottlenecks: Vec<SimulationBottleneck>,
+
+    /// 无法调度的任务
+    pub unscheduled_tasks: Vec<TaskId>,
+
+    /// 预计整体完成时间
+    pub estimated_completion: DateTime<Utc>,
+
+    /// 按历史成本估算的计划总成本，所有分配都没有成本数据时为空
+    #[serde(default)]
+    pub estimated_cost: Option<f64>,
+}
+
+/// 成本优化策略下的候选Agent
+struct CostCandidate {
+    agent: usize,
+    finish: DateTime<Utc>,
+    cost: Option<f64>,
+}
+
+impl SimulationReport {
+    /// 以混合策略运行模拟
+    ///
+    /// 按依赖拓扑顺序（同层优先级高者优先）逐个分配任务，在具备全部所需能力的Agent中选择
+    /// 完成时间最早者；工时按连续小时计算，不考虑工作日历
+    pub fn simulate(
+        project_id: ProjectId,
+        tasks: &[TaskInfo],
+        agents: &[SimulationAgent],
+        start: DateTime<Utc>,
+    ) -> Self {
+        Self::simulate_with_policy(project_id, tasks, agents, start, &AssignmentPolicy::default(), &HashMap::new())
+    }
+
+    /// 按分配策略运行模拟
+    ///
+    /// `deadlines` 为任务的截止时间（见 [`TimelineRequirements::task_deadlines`]），成本优化策略据此计算截止时间压力：
+    /// 余量越少越偏向完成早的Agent，余量充足时偏向历史成本低的Agent
+    pub fn simulate_with_policy(
+        project_id: ProjectId,
+        tasks: &[TaskInfo],
+        agents: &[SimulationAgent],
+        start: DateTime<Utc>,
+        policy: &AssignmentPolicy,
+        deadlines: &HashMap<TaskId, DateTime<Utc>>,
+    ) -> Self {
+        let index: HashMap<&TaskId, usize> = tasks
+            .iter()
+            .enumerate()
+            .map(|(i, task)| (&task.task_id, i))
+            .collect();
+        let mut conflicts = Vec::new();
+
+        // 只保留模拟范围内的依赖
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); tasks.len()];
+        for (i, task) in tasks.iter().enumerate() {
+            for dependency in &task.dependencies {
+                match index.get(dependency) {
+                    Some(&j) if j != i => predecessors[i].push(j),
+                    Some(_) => conflicts.push(SimulationConflict {
+                        task_id: task.task_id.clone(),
+                        conflict_type: SimulationConflictType::CircularDependency,
+                        description: format!("任务「{}」依赖自身", task.title),
+                    }),
+                    None => conflicts.push(SimulationConflict {
+                        task_id: task.task_id.clone(),
+                        conflict_type: SimulationConflictType::MissingDependency,
+                        description: format!("任务「{}」的依赖 {} 不在模拟范围内，已忽略", task.title, dependency.0),
+                    }),
+                }
+            }
+        }
+
+        let order = Self::topological_order(tasks, &predecessors);
+        let mut ordered = vec![false; tasks.len()];
+        for &i in &order {
+            ordered[i] = true;
+        }
+        for (i, task) in tasks.iter().enumerate() {
+            if !ordered[i] {
+                conflicts.push(SimulationConflict {
+                    task_id: task.task_id.clone(),
+                    conflict_type: SimulationConflictType::CircularDependency,
+                    description: format!("任务「{}」处于循环依赖中", task.title),
+                });
+            }
+        }
+
+        let mut agent_free: Vec<DateTime<Utc>> = agents
+            .iter()
+            .map(|agent| agent.available_from.map_or(start, |t| t.max(start)))
+            .collect();
+        let mut agent_hours = vec![0u32; agents.len()];
+        // 每个已调度任务的 (开始, 结束, Agent下标, 层级)
+        let mut scheduled: Vec<Option<ScheduledSlot>> = vec![None; tasks.len()];
+        let mut assignments = Vec::new();
+        let mut total_cost: Option<f64> = None;
+
+        for &i in &order {
+            let task = &tasks[i];
+            if predecessors[i].iter().any(|&j| scheduled[j].is_none()) {
+                conflicts.push(SimulationConflict {
+                    task_id: task.task_id.clone(),
+                    conflict_type: SimulationConflictType::BlockedByDependency,
+                    description: format!("任务「{}」的前置任务无法调度", task.title),
+                });
+                continue;
+            }
+
+            let earliest = predecessors[i]
+                .iter()
+                .filter_map(|&j| scheduled[j].map(|(_, end, _, _)| end))
+                .fold(start, DateTime::max);
+            let level = predecessors[i]
+                .iter()
+                .filter_map(|&j| scheduled[j].map(|(_, _, _, level)| level + 1))
+                .max()
+                .unwrap_or(0);
+
+            let capable: Vec<usize> = agents
+                .iter()
+                .enumerate()
+                .filter(|(_, agent)| {
+                    task.required_capabilities
+                        .iter()
+                        .all(|capability| agent.capabilities.contains(capability))
+                })
+                .map(|(a, _)| a)
+                .collect();
+            if capable.is_empty() {
+                conflicts.push(SimulationConflict {
+                    task_id: task.task_id.clone(),
+                    conflict_type: SimulationConflictType::NoCapableAgent,
+                    description: format!("没有Agent具备任务「{}」所需的全部能力", task.title),
+                });
+                continue;
+            }
+            let duration = chrono::Duration::hours(task.estimated_hours as i64);
+
+            let (chosen, reasoning) = if policy.strategy == AssignmentStrategy::CostOptimized {
+                let candidates: Vec<CostCandidate> = capable
+                    .iter()
+                    .map(|&a| CostCandidate {
+                        agent: a,
+                        finish: agent_free[a].max(earliest) + duration,
+                        cost: agents[a].task_costs.get(&task.task_type).copied(),
+                    })
+                    .collect();
+                let spent = total_cost.unwrap_or(0.0);
+                match Self::choose_by_cost(&candidates, deadlines.get(&task.task_id).copied(), start, policy, spent) {
+                    Ok(choice) => choice,
+                    Err(reason) => {
+                        conflicts.push(SimulationConflict {
+                            task_id: task.task_id.clone(),
+                            conflict_type: SimulationConflictType::ExceedsCostCeiling,
+                            description: format!("任务「{}」{}", task.title, reason),
+                        });
+                        continue;
+                    }
+                }
+            } else {
+                // 有Agent熟悉任务相关文件时，只在最熟悉的Agent中选择
+                let familiarity: Vec<usize> = capable
+                    .iter()
+                    .map(|&a| agents[a].path_familiarity(&task.related_files))
+                    .collect();
+                let best_familiarity = familiarity.iter().copied().max().unwrap_or(0);
+                let chosen = capable
+                    .iter()
+                    .zip(&familiarity)
+                    .filter(|&(_, &familiar)| familiar == best_familiarity)
+                    .map(|(&a, _)| a)
+                    .min_by_key(|&a| (agent_free[a].max(earliest) + duration, agent_hours[a], a))
+                    .expect("候选Agent不为空");
+                let reasoning = if best_familiarity > 0 {
+                    format!(
+                        "能力匹配，熟悉{}个相关文件，{}个候选Agent中预计完成最早",
+                        best_familiarity,
+                        capable.len()
+                    )
+                } else {
+                    format!("能力匹配，{}个候选Agent中预计完成最早", capable.len())
+                };
+                (chosen, reasoning)
+            };
+            if let Some(cost) = agents[chosen].task_costs.get(&task.task_type) {
+                *total_cost.get_or_insert(0.0) += cost;
+            }
+
+            let begin = agent_free[chosen].max(earliest);
+            let end = begin + duration;
+            agent_free[chosen] = end;
+            agent_hours[chosen] += task.estimated_hours;
+            scheduled[i] = Some((begin, end, chosen, level));
+
+            let mut confidence = 1.0 - task.complexity_assessment.overall_complexity.min(10) as f32 / 20.0;
+            if capable.len() == 1 {
+                confidence *= 0.9;
+            }
+            if let Some(calibrated) = agents[chosen].calibrated_confidence {
+                confidence *= calibrated.clamp(0.0, 1.0);
+            }
+            assignments.push(TaskAssignment {
+                task_id: task.task_id.clone(),
+                agent_id: agents[chosen].agent_id.clone(),
+                assigned_at: start,
+                estimated_start_time: begin,
+                estimated_completion: end,
+                assignment_reasoning: reasoning,
+                confidence_score: confidence,
+                assignment_strategy: if policy.strategy == AssignmentStrategy::CostOptimized {
+                    AssignmentStrategy::CostOptimized
+                } else {
+                    AssignmentStrategy::Hybrid
+                },
+                alternative_agents: capable
+                    .iter()
+                    .filter(|&&a| a != chosen)
+                    .map(|&a| agents[a].agent_id.clone())
+                    .collect(),
+            });
+        }
+
+        let estimated_completion = scheduled
+            .iter()
+            .flatten()
+            .map(|&(_, end, _, _)| end)
+            .fold(start, DateTime::max);
+
+        // 关键路径：从最晚完成的任务沿最晚完成的前置任务回溯
+        let mut critical_path = Vec::new();
+        let mut current = (0..tasks.len())
+            .filter(|&i| scheduled[i].is_some())
+            .max_by_key(|&i| scheduled[i].map(|(_, end, _, _)| end));
+        while let Some(i) = current {
+            critical_path.push(tasks[i].task_id.clone());
+            current = predecessors[i]
+                .iter()
+                .copied()
+                .max_by_key(|&j| scheduled[j].map(|(_, end, _, _)| end));
+        }
+        critical_path.reverse();
+
+        let timeline = Self::build_timeline(tasks, &scheduled);
+
+        let span_hours = (estimated_completion - start).num_minutes() as f32 / 60.0;
+        let mut bottlenecks: Vec<SimulationBottleneck> = agents
+            .iter()
+            .enumerate()
+            .filter(|&(a, _)| agent_hours[a] > 0 && span_hours > 0.0)
+            .map(|(a, agent)| SimulationBottleneck {
+                agent_id: agent.agent_id.clone(),
+                assigned_tasks: scheduled.iter().flatten().filter(|s| s.2 == a).count() as u32,
+                busy_hours: agent_hours[a],
+                utilization: (agent_hours[a] as f32 / span_hours).min(1.0),
+                critical_path_tasks: critical_path
+                    .iter()
+                    .filter(|id| scheduled[index[id]].is_some_and(|s| s.2 == a))
+                    .count() as u32,
+            })
+            .filter(|b| b.utilization >= BOTTLENECK_UTILIZATION_THRESHOLD)
+            .collect();
+        bottlenecks.sort_by(|a, b| b.utilization.total_cmp(&a.utilization));
+
+        let unscheduled_tasks = tasks
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| scheduled[i].is_none())
+            .map(|(_, task)| task.task_id.clone())
+            .collect();
+
+        Self {
+            project_id,
+            simulated_at: start,
+            assignments,
+            timeline,
+            critical_path,
+            conflicts,
+            bottlenecks,
+            unscheduled_tasks,
+            estimated_completion,
+            estimated_cost: total_cost,
+        }
+    }
+
+    /// 成本优化策略选择Agent，返回 (Agent下标, 分配理由)；候选Agent都超过成本上限时返回原因
+    ///
+    /// 没有历史成本的Agent按其他候选的平均成本估算。有截止时间时只在能按时完成的Agent中选择（都不能按时完成时不限制），
+    /// 并按截止时间压力 `p`（0 表示余量充足，1 表示没有余量）对归一化后的成本和完成时间加权：
+    /// `score = (1 - p) * 成本 + p * 完成时间`
+    fn choose_by_cost(
+        candidates: &[CostCandidate],
+        deadline: Option<DateTime<Utc>>,
+        start: DateTime<Utc>,
+        policy: &AssignmentPolicy,
+        spent: f64,
+    ) -> std::result::Result<(usize, String), String> {
+        let known: Vec<f64> = candidates.iter().filter_map(|c| c.cost).collect();
+        let fallback_cost = (!known.is_empty()).then(|| known.iter().sum::<f64>() / known.len() as f64);
+        let effective_cost = |c: &CostCandidate| c.cost.or(fallback_cost);
+
+        let within_ceiling: Vec<&CostCandidate> = candidates
+            .iter()
+            .filter(|c| {
+                c.cost.is_none_or(|cost| {
+                    policy.max_cost_per_task.is_none_or(|ceiling| cost <= ceiling)
+                        && policy.max_total_cost.is_none_or(|ceiling| spent + cost <= ceiling)
+                })
+            })
+            .collect();
+        if within_ceiling.is_empty() {
+            let cheapest = known.iter().copied().fold(f64::INFINITY, f64::min);
+            return Err(match policy.max_total_cost {
+                Some(total) if policy.max_cost_per_task.is_none_or(|ceiling| cheapest <= ceiling) => format!(
+                    "的最低历史成本 {:.2} 会使计划累计成本超过上限 {:.2}（已分配 {:.2}）",
+                    cheapest, total, spent
+                ),
+                _ => format!(
+                    "的最低历史成本 {:.2} 超过单个任务的成本上限 {:.2}",
+                    cheapest,
+                    policy.max_cost_per_task.unwrap_or_default()
+                ),
+            });
+        }
+
+        let on_time: Vec<&CostCandidate> = match deadline {
+            Some(deadline) => within_ceiling.iter().copied().filter(|c| c.finish <= deadline).collect(),
+            None => Vec::new(),
+        };
+        let pool = if on_time.is_empty() { &within_ceiling } else { &on_time };
+
+        let earliest = pool.iter().map(|c| c.finish).min().expect("候选Agent不为空");
+        let latest = pool.iter().map(|c| c.finish).max().expect("候选Agent不为空");
+        let pressure = match deadline {
+            None => 0.0,
+            Some(_) if on_time.is_empty() => 1.0,
+            Some(deadline) => {
+                let window = (deadline - start).num_minutes();
+                if window <= 0 {
+                    1.0
+                } else {
+                    (1.0 - (deadline - earliest).num_minutes() as f64 / window as f64).clamp(0.0, 1.0)
+                }
+            }
+        };
+
+        let costs: Vec<f64> = pool.iter().filter_map(|&c| effective_cost(c)).collect();
+        let min_cost = costs.iter().copied().fold(f64::INFINITY, f64::min);
+        let max_cost = costs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let normalize = |value: f64, min: f64, max: f64| if max > min { (value - min) / (max - min) } else { 0.0 };
+        let span = (latest - earliest).num_minutes() as f64;
+        let score = |c: &CostCandidate| {
+            let cost = effective_cost(c).map_or(0.0, |cost| normalize(cost, min_cost, max_cost));
+            let delay = normalize((c.finish - earliest).num_minutes() as f64, 0.0, span);
+            (1.0 - pressure) * cost + pressure * delay
+        };
+        let chosen = pool
+            .iter()
+            .copied()
+            .min_by(|a, b| score(a).total_cmp(&score(b)).then(a.finish.cmp(&b.finish)).then(a.agent.cmp(&b.agent)))
+            .expect("候选Agent不为空");
+
+        let mut reasoning = match (chosen.cost, effective_cost(chosen)) {
+            (Some(cost), _) => format!("成本优化：历史平均成本 {:.2}", cost),
+            (None, Some(estimate)) => format!("成本优化：没有历史成本，按候选Agent平均成本估算为 {:.2}", estimate),
+            (None, None) => "成本优化：候选Agent都没有该类型任务的历史成本，按完成时间选择".to_string(),
+        };
+        if !costs.is_empty() {
+            reasoning.push_str(&format!("，{}个候选Agent成本 {:.2}-{:.2}", pool.len(), min_cost, max_cost));
+        }
+        match deadline {
+            Some(deadline) if on_time.is_empty() => reasoning.push_str(&format!(
+                "；没有Agent能在截止时间 {} 前完成，选择最早完成者",
+                deadline.format("%Y-%m-%d %H:%M")
+            )),
+            Some(deadline) => reasoning.push_str(&format!(
+                "；截止时间 {}，截止压力 {:.2}",
+                deadline.format("%Y-%m-%d %H:%M"),
+                pressure
+            )),
+            None => reasoning.push_str("；无截止时间，优先低成本"),
+        }
+        if candidates.len() > within_ceiling.len() {
+            reasoning.push_str(&format!("；{}个Agent因超过成本上限被排除", candidates.len() - within_ceiling.len()));
+        }
+        Ok((chosen.agent, reasoning))
+    }
+
+    /// 模拟结果是否可直接执行（所有任务均已调度且无冲突）
+    pub fn is_feasible(&self) -> bool {
+        self.conflicts.is_empty() && self.unscheduled_tasks.is_empty()
+    }
+
+    /// 转换为调度计划，确认模拟结果后用于正式执行
+    pub fn to_schedule_plan(&self) -> SchedulePlan {
+        let plan_confidence = if self.assignments.is_empty() {
+            0.0
+        } else {
+            self.assignments.iter().map(|a| a.confidence_score).sum::<f32>() / self.assignments.len() as f32
+        };
+
+        SchedulePlan {
+            plan_id: uuid::Uuid::new_v4().to_string(),
+            project_id: self.project_id.clone(),
+            task_assignments: self.assignments.clone(),
+            execution_phases: self.timeline.clone(),
+            critical_path: self.critical_path.clone(),
+            created_at: Utc::now(),
+            valid_until: Utc::now() + chrono::Duration::days(1),
+            estimated_total_completion: self.estimated_completion,
+            plan_confidence,
+        }
+    }
+
+    /// Kahn 拓扑排序，就绪任务中优先级高者、输入顺序靠前者先出；循环依赖中的任务不会出现在结果中
+    fn topological_order(tasks: &[TaskInfo], predecessors: &[Vec<usize>]) -> Vec<usize> {
+        let mut in_degree: Vec<usize> = predecessors.iter().map(Vec::len).collect();
+        let mut ready: Vec<usize> = (0..tasks.len()).filter(|&i| in_degree[i] == 0).collect();
+        let mut order = Vec::with_capacity(tasks.len());
+
+        while let Some(position) = ready
+            .iter()
+            .enumerate()
+            .max_by_key(|&(_, &i)| (tasks[i].priority.clone(), std::cmp::Reverse(i)))
+            .map(|(position, _)| position)
+        {
+            let i = ready.swap_remove(position);
+            order.push(i);
+            for (k, preds) in predecessors.iter().enumerate() {
+                let edges = preds.iter().filter(|&&j| j == i).count();
+                if edges > 0 {
+                    in_degree[k] -= edges;
+                    if in_degree[k] == 0 {
+                        ready.push(k);
+                    }
+                }
+            }
+        }
+
+        order
+    }
+
+    fn build_timeline(
+        tasks: &[TaskInfo],
+        scheduled: &[Option<ScheduledSlot>],
+    ) -> Vec<ExecutionPhase> {
+        let max_level = scheduled.iter().flatten().map(|s| s.3).max();
+        let Some(max_level) = max_level else {
+            return Vec::new();
+        };
+
+        (0..=max_level)
+            .filter_map(|level| {
+                let members: Vec<(usize, DateTime<Utc>, DateTime<Utc>)> = scheduled
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, s)| s.filter(|s| s.3 == level).map(|s| (i, s.0, s.1)))
+                    .collect();
+                let start_time = members.iter().map(|m| m.1).min()?;
+                let end_time = members.iter().map(|m| m.2).max()?;
+
+                Some(ExecutionPhase {
+                    name: format!("阶段 {}", level + 1),
+                    description: format!("{} 个任务", members.len()),
+                    tasks: members.iter().map(|m| tasks[m.0].task_id.clone()).collect(),
+                    start_time,
+                    end_time,
+                    dependencies: if level == 0 { vec![] } else { vec![format!("阶段 {}", level)] },
+                    gate_conditions: if level == 0 { vec![] } else { vec!["前置阶段的依赖任务全部完成".to_string()] },
+                })
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// 计划护栏校验
+// ============================================================================
+
+/// 默认单个任务的工时上限（小时），超过时要求拆分
+pub const DEFAULT_MAX_TASK_HOURS: u32 = 40;
+
+/// 计划违规类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(rename_all = "snake_case")]
+pub enum PlanViolationType {
+    /// 没有具备所需能力的Agent
+    MissingCapability,
+    /// 单个任务工时超过上限
+    OversizedTask,
+    /// 依赖的任务不在计划中
+    UnknownDependency,
+    /// 存在循环依赖
+    CircularDependency,
+    /// 前置任务无法调度，导致本任务被阻塞
+    BlockedByDependency,
+    /// 预计完成时间（含缓冲）超过目标完成时间
+    ExceedsTimeline,
+    /// 里程碑依赖的任务预计在截止日期后完成
+    MissesMilestone,
+    /// 任务成本超过项目的成本上限
+    ExceedsCostCeiling,
+}
+
+impl PlanViolationType {
+    /// 对应的任务风险类型
+    fn risk_type(&self) -> RiskType {
+        match self {
+            PlanViolationType::MissingCapability | PlanViolationType::ExceedsCostCeiling => RiskType::Resource,
+            PlanViolationType::OversizedTask => RiskType::Technical,
+            PlanViolationType::UnknownDependency
+            | PlanViolationType::CircularDependency
+            | PlanViolationType::BlockedByDependency => RiskType::Dependency,
+            PlanViolationType::ExceedsTimeline | PlanViolationType::MissesMilestone => RiskType::Timeline,
+        }
+    }
+}
+
+/// 计划违规
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct PlanViolation {
+    /// 相关任务，针对整个计划的违规为空
+    pub task_id: Option<TaskId>,
+
+    /// 违规类型
+    pub violation_type: PlanViolationType,
+
+    /// 严重程度
+    pub severity: RiskLevel,
+
+    /// 违规说明
+    pub message: String,
+}
+
+/// 计划校验报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct PlanValidationReport {
+    /// 违规列表
+    pub violations: Vec<PlanViolation>,
+
+    /// 模拟得到的预计完成时间
+    pub estimated_completion: DateTime<Utc>,
+}
+
+impl PlanValidationReport {
+    /// 计划是否通过校验
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// 把违规写入相关任务的风险因子，针对整个计划的违规写入所有任务
+    pub fn annotate(&self, tasks: &mut [TaskInfo]) {
+        for violation in &self.violations {
+            for task in tasks
+                .iter_mut()
+                .filter(|task| violation.task_id.as_ref().is_none_or(|id| id == &task.task_id))
+            {
+                task.risk_factors.push(RiskFactor {
+                    risk_type: violation.violation_type.risk_type(),
+                    risk_level: violation.severity.clone(),
+                    description: violation.message.clone(),
+                    impact_assessment: "计划护栏校验未通过".to_string(),
+                    mitigation_strategies: vec![],
+                });
+            }
+        }
+    }
+
+    /// 要求LLM修订计划的反馈，列出全部违规
+    pub fn revision_feedback(&self, tasks: &[TaskInfo]) -> String {
+        let title = |id: &TaskId| {
+            tasks
+                .iter()
+                .find(|task| &task.task_id == id)
+                .map(|task| task.title.as_str())
+                .unwrap_or("未知任务")
+        };
+        let mut feedback = String::from("上一版任务计划未通过校验，存在以下问题：\n");
+        for violation in &self.violations {
+            match &violation.task_id {
+                Some(id) => feedback.push_str(&format!("- 任务「{}」：{}\n", title(id), violation.message)),
+                None => feedback.push_str(&format!("- {}\n", violation.message)),
+            }
+        }
+        feedback.push_str("请修订任务计划解决上述问题（例如拆分任务、调整依赖、缩减范围或只使用现有Agent具备的能力），并按原格式输出完整计划。");
+        feedback
+    }
+}
+
+/// 计划护栏校验器
+///
+/// 在持久化LLM提出的任务计划前，按项目上下文的约束检查计划：所需能力、任务工时、依赖关系、
+/// 目标完成时间和里程碑截止日期。调度相关的检查基于 [`SimulationReport::simulate`]
+#[derive(Debug, Clone)]
+pub struct PlanValidator {
+    agents: Vec<SimulationAgent>,
+    max_task_hours: u32,
+    policy: AssignmentPolicy,
+}
+
+impl PlanValidator {
+    /// 以可用Agent创建校验器
+    pub fn new(agents: Vec<SimulationAgent>) -> Self {
+        Self {
+            agents,
+            max_task_hours: DEFAULT_MAX_TASK_HOURS,
+            policy: AssignmentPolicy::default(),
+        }
+    }
+
+    /// 设置单个任务的工时上限
+    pub fn with_max_task_hours(mut self, max_task_hours: u32) -> Self {
+        self.max_task_hours = max_task_hours;
+        self
+    }
+
+    /// 设置模拟使用的分配策略和成本上限
+    pub fn with_assignment_policy(mut self, policy: AssignmentPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// 校验计划
+    pub fn validate(
+        &self,
+        project_id: ProjectId,
+        context: &ProjectContext,
+        tasks: &[TaskInfo],
+        start: DateTime<Utc>,
+    ) -> PlanValidationReport {
+        let deadlines = context
+            .timeline_requirements
+            .as_ref()
+            .map(|timeline| timeline.task_deadlines(tasks))
+            .unwrap_or_default();
+        let simulation =
+            SimulationReport::simulate_with_policy(project_id, tasks, &self.agents, start, &self.policy, &deadlines);
+        let mut violations: Vec<PlanViolation> = simulation
+            .conflicts
+            .iter()
+            .map(|conflict| {
+                let (violation_type, severity) = match conflict.conflict_type {
+                    SimulationConflictType::NoCapableAgent => (PlanViolationType::MissingCapability, RiskLevel::High),
+                    SimulationConflictType::MissingDependency => (PlanViolationType::UnknownDependency, RiskLevel::Medium),
+                    SimulationConflictType::CircularDependency => (PlanViolationType::CircularDependency, RiskLevel::High),
+                    SimulationConflictType::BlockedByDependency => (PlanViolationType::BlockedByDependency, RiskLevel::Medium),
+                    SimulationConflictType::ExceedsCostCeiling => (PlanViolationType::ExceedsCostCeiling, RiskLevel::Medium),
+                };
+                PlanViolation {
+                    task_id: Some(conflict.task_id.clone()),
+                    violation_type,
+                    severity,
+                    message: conflict.description.clone(),
+                }
+            })
+            .collect();
+
+        for task in tasks.iter().filter(|task| task.estimated_hours > self.max_task_hours) {
+            violations.push(PlanViolation {
+                task_id: Some(task.task_id.clone()),
+                violation_type: PlanViolationType::OversizedTask,
+                severity: RiskLevel::Medium,
+                message: format!(
+                    "预估 {} 小时，超过单个任务上限 {} 小时，需要拆分",
+                    task.estimated_hours, self.max_task_hours
+                ),
+            });
+        }
+
+        if let Some(timeline) = &context.timeline_requirements {
+            let buffered = simulation.estimated_completion + chrono::Duration::hours(timeline.buffer_time_hours as i64);
+            if buffered > timeline.target_completion {
+                violations.push(PlanViolation {
+                    task_id: None,
+                    violation_type: PlanViolationType::ExceedsTimeline,
+                    severity: RiskLevel::High,
+                    message: format!(
+                        "计划预计在 {} 完成（含 {} 小时缓冲），晚于目标完成时间 {}",
+                        buffered.format("%Y-%m-%d %H:%M"),
+                        timeline.buffer_time_hours,
+                        timeline.target_completion.format("%Y-%m-%d %H:%M")
+                    ),
+                });
+            }
+
+            for milestone in timeline
+                .milestone_deadlines
+                .iter()
+                .filter(|milestone| milestone.status != MilestoneStatus::Completed)
+            {
+                for assignment in simulation.assignments.iter().filter(|assignment| {
+                    milestone.dependent_tasks.contains(&assignment.task_id)
+                        && assignment.estimated_completion > milestone.deadline
+                }) {
+                    violations.push(PlanViolation {
+                        task_id: Some(assignment.task_id.clone()),
+                        violation_type: PlanViolationType::MissesMilestone,
+                        severity: RiskLevel::High,
+                        message: format!(
+                            "预计在 {} 完成，晚于里程碑「{}」的截止日期 {}",
+                            assignment.estimated_completion.format("%Y-%m-%d %H:%M"),
+                            milestone.name,
+                            milestone.deadline.format("%Y-%m-%d %H:%M")
+                        ),
+                    });
+                }
+            }
+        }
+
+        PlanValidationReport {
+            violations,
+            estimated_completion: simulation.estimated_completion,
+        }
+    }
+}
+
 // ============================================================================
 // 测试模块
 // ============================================================================
@@ -996,12 +2111,378 @@ mod tests {
             risk_factors: vec![],
             subtasks: vec![],
             related_issues: vec![],
+            ci_status: None,
         };
 
         assert_eq!(task.title, "测试任务");
         assert_eq!(task.priority, TaskPriority::Medium);
     }
 
+    #[test]
+    fn test_task_progress_rollup() {
+        let parent_id = TaskId::new();
+        let subtasks = vec![
+            SubtaskProgress {
+                task_id: TaskId::new(),
+                status: TaskStatus::Completed,
+                completion_percentage: 1.0,
+                weight: 3.0,
+            },
+            SubtaskProgress {
+                task_id: TaskId::new(),
+                status: TaskStatus::InProgress,
+                completion_percentage: 0.0,
+                weight: 1.0,
+            },
+            SubtaskProgress {
+                task_id: TaskId::new(),
+                status: TaskStatus::Cancelled,
+                completion_percentage: 0.0,
+                weight: 5.0,
+            },
+        ];
+
+        let rollup = TaskProgressRollup::from_subtasks(parent_id.clone(), &subtasks);
+        assert_eq!(rollup.parent_task_id, parent_id);
+        assert_eq!(rollup.total_subtasks, 2);
+        assert_eq!(rollup.cancelled_subtasks, 1);
+        assert_eq!(rollup.derived_status, TaskStatus::InProgress);
+        assert!((rollup.completion_percentage - 0.75).abs() < f32::EPSILON);
+
+        let all_done: Vec<SubtaskProgress> = subtasks
+            .into_iter()
+            .map(|mut s| {
+                s.status = TaskStatus::Completed;
+                s
+            })
+            .collect();
+        let rollup = TaskProgressRollup::from_subtasks(parent_id, &all_done);
+        assert_eq!(rollup.derived_status, TaskStatus::Completed);
+        assert!((rollup.completion_percentage - 1.0).abs() < f32::EPSILON);
+    }
+
+    fn simulation_task(title: &str, hours: u32, capabilities: Vec<AgentCapability>, dependencies: Vec<TaskId>) -> TaskInfo {
+        TaskInfo {
+            task_id: TaskId::new(),
+            title: title.to_string(),
+            description: String::new(),
+            task_type: TaskType::Development,
+            priority: TaskPriority::Medium,
+            estimated_hours: hours,
+            required_capabilities: capabilities,
+            dependencies,
+            acceptance_criteria: vec![],
+            tags: vec![],
+            related_files: vec![],
+            test_requirements: TaskTestRequirements {
+                needs_unit_tests: false,
+                needs_integration_tests: false,
+                needs_e2e_tests: false,
+                required_coverage: 0.0,
+                special_test_scenarios: vec![],
+            },
+            complexity_assessment: ComplexityAssessment {
+                technical_complexity: 4,
+                business_complexity: 4,
+                integration_complexity: 4,
+                overall_complexity: 4,
+                complexity_notes: vec![],
+            },
+            risk_factors: vec![],
+            subtasks: vec![],
+            related_issues: vec![],
+            ci_status: None,
+        }
+    }
+
+    fn simulation_agent(capabilities: Vec<AgentCapability>) -> SimulationAgent {
+        SimulationAgent {
+            agent_id: AgentId::new(),
+            capabilities,
+            available_from: None,
+            calibrated_confidence: None,
+            familiar_paths: vec![],
+            task_costs: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_simulation_schedules_by_dependency_and_availability() {
+        let start = Utc::now();
+        let frontend = simulation_agent(vec![AgentCapability::FrontendDevelopment]);
+        let fullstack = simulation_agent(vec![
+            AgentCapability::FrontendDevelopment,
+            AgentCapability::BackendDevelopment,
+        ]);
+
+        let api = simulation_task("后端API", 4, vec![AgentCapability::BackendDevelopment], vec![]);
+        let layout = simulation_task("页面布局", 2, vec![AgentCapability::FrontendDevelopment], vec![]);
+        let page = simulation_task("对接API", 3, vec![AgentCapability::FrontendDevelopment], vec![api.task_id.clone()]);
+        let tasks = vec![api.clone(), layout.clone(), page.clone()];
+
+        let report = SimulationReport::simulate(ProjectId::new(), &tasks, &[frontend.clone(), fullstack.clone()], start);
+
+        assert!(report.is_feasible());
+        assert_eq!(report.assignments.len(), 3);
+        let assignment = |id: &TaskId| report.assignments.iter().find(|a| &a.task_id == id).unwrap();
+        assert_eq!(assignment(&api.task_id).agent_id, fullstack.agent_id);
+        assert_eq!(assignment(&layout.task_id).agent_id, frontend.agent_id);
+        // 两个Agent同时空闲时选择负载更低的一方
+        assert_eq!(assignment(&page.task_id).agent_id, frontend.agent_id);
+        assert_eq!(assignment(&page.task_id).estimated_start_time, start + chrono::Duration::hours(4));
+
+        assert_eq!(report.estimated_completion, start + chrono::Duration::hours(7));
+        assert_eq!(report.critical_path, vec![api.task_id.clone(), page.task_id.clone()]);
+        assert_eq!(report.timeline.len(), 2);
+        assert_eq!(report.timeline[1].tasks, vec![page.task_id.clone()]);
+        assert!(report.bottlenecks.is_empty());
+
+        let plan = report.to_schedule_plan();
+        assert_eq!(plan.task_assignments.len(), 3);
+        assert_eq!(plan.estimated_total_completion, report.estimated_completion);
+    }
+
+    #[test]
+    fn test_cost_optimized_assignment_weighs_cost_against_deadline() {
+        let start = Utc::now();
+        let task = simulation_task("后端API", 4, vec![AgentCapability::BackendDevelopment], vec![]);
+        let mut cheap = simulation_agent(vec![AgentCapability::BackendDevelopment]);
+        cheap.task_costs.insert(TaskType::Development, 2.0);
+        cheap.available_from = Some(start + chrono::Duration::hours(6));
+        let mut fast = simulation_agent(vec![AgentCapability::BackendDevelopment]);
+        fast.task_costs.insert(TaskType::Development, 5.0);
+        let agents = [cheap.clone(), fast.clone()];
+        let tasks = std::slice::from_ref(&task);
+        let policy = AssignmentPolicy { strategy: AssignmentStrategy::CostOptimized, ..Default::default() };
+        let simulate = |policy: &AssignmentPolicy, deadline: Option<i64>| {
+            let deadlines = deadline
+                .map(|hours| HashMap::from([(task.task_id.clone(), start + chrono::Duration::hours(hours))]))
+                .unwrap_or_default();
+            SimulationReport::simulate_with_policy(ProjectId::new(), tasks, &agents, start, policy, &deadlines)
+        };
+
+        // 混合策略选择最早完成者
+        let hybrid = SimulationReport::simulate(ProjectId::new(), tasks, &agents, start);
+        assert_eq!(hybrid.assignments[0].agent_id, fast.agent_id);
+        assert_eq!(hybrid.estimated_cost, Some(5.0));
+
+        // 没有截止时间或余量充足时选择成本低者
+        for deadline in [None, Some(100)] {
+            let report = simulate(&policy, deadline);
+            let assignment = &report.assignments[0];
+            assert_eq!(assignment.agent_id, cheap.agent_id);
+            assert_eq!(assignment.assignment_strategy, AssignmentStrategy::CostOptimized);
+            assert!(assignment.assignment_reasoning.contains("历史平均成本 2.00"));
+            assert_eq!(report.estimated_cost, Some(2.0));
+        }
+
+        // 低成本Agent无法按时完成时选择能按时完成者
+        let report = simulate(&policy, Some(8));
+        assert_eq!(report.assignments[0].agent_id, fast.agent_id);
+        assert!(report.assignments[0].assignment_reasoning.contains("截止压力"));
+
+        // 超过成本上限的Agent被排除，全部超过时产生冲突
+        let capped = AssignmentPolicy { max_cost_per_task: Some(3.0), ..policy.clone() };
+        let report = simulate(&capped, Some(8));
+        assert_eq!(report.assignments[0].agent_id, cheap.agent_id);
+        assert!(report.assignments[0].assignment_reasoning.contains("1个Agent因超过成本上限被排除"));
+        let report = simulate(&AssignmentPolicy { max_cost_per_task: Some(1.0), ..policy }, None);
+        assert!(report.assignments.is_empty());
+        assert_eq!(report.conflicts[0].conflict_type, SimulationConflictType::ExceedsCostCeiling);
+        assert_eq!(report.unscheduled_tasks, vec![task.task_id.clone()]);
+    }
+
+    #[test]
+    fn test_simulation_applies_calibrated_confidence() {
+        let start = Utc::now();
+        let task = simulation_task("后端API", 4, vec![AgentCapability::BackendDevelopment], vec![]);
+        let mut agent = simulation_agent(vec![AgentCapability::BackendDevelopment]);
+
+        let baseline = SimulationReport::simulate(ProjectId::new(), std::slice::from_ref(&task), std::slice::from_ref(&agent), start);
+        agent.calibrated_confidence = Some(0.5);
+        let calibrated = SimulationReport::simulate(ProjectId::new(), std::slice::from_ref(&task), std::slice::from_ref(&agent), start);
+
+        let expected = baseline.assignments[0].confidence_score * 0.5;
+        assert!((calibrated.assignments[0].confidence_score - expected).abs() < f32::EPSILON);
+        assert!(calibrated.to_schedule_plan().plan_confidence < baseline.to_schedule_plan().plan_confidence);
+    }
+
+    #[test]
+    fn test_simulation_prefers_agents_familiar_with_related_files() {
+        let start = Utc::now();
+        let newcomer = simulation_agent(vec![AgentCapability::BackendDevelopment]);
+        let mut veteran = simulation_agent(vec![AgentCapability::BackendDevelopment]);
+        veteran.available_from = Some(start + chrono::Duration::hours(2));
+        veteran.familiar_paths = vec!["src/payments".to_string(), "src/orders/model.rs".to_string()];
+
+        let mut refund = simulation_task("退款接口", 4, vec![AgentCapability::BackendDevelopment], vec![]);
+        refund.related_files = vec!["src/payments/refund.rs".to_string()];
+        let mut order = simulation_task("订单查询", 2, vec![AgentCapability::BackendDevelopment], vec![]);
+        order.related_files = vec!["src/orders/query.rs".to_string()];
+        let report_task = simulation_task("报表导出", 2, vec![AgentCapability::BackendDevelopment], vec![]);
+        let tasks = vec![refund.clone(), order.clone(), report_task.clone()];
+
+        let report = SimulationReport::simulate(ProjectId::new(), &tasks, &[newcomer.clone(), veteran.clone()], start);
+
+        let assignment = |id: &TaskId| report.assignments.iter().find(|a| &a.task_id == id).unwrap();
+        // 熟悉相关文件的Agent即使更晚空闲也优先分配
+        assert_eq!(assignment(&refund.task_id).agent_id, veteran.agent_id);
+        assert_eq!(assignment(&order.task_id).agent_id, veteran.agent_id);
+        assert!(assignment(&refund.task_id).assignment_reasoning.contains("熟悉1个相关文件"));
+        // 没有相关文件的任务仍按完成时间分配
+        assert_eq!(assignment(&report_task.task_id).agent_id, newcomer.agent_id);
+        assert_eq!(veteran.path_familiarity(&["src/users/mod.rs".to_string()]), 0);
+    }
+
+    #[test]
+    fn test_simulation_reports_conflicts_and_bottlenecks() {
+        let start = Utc::now();
+        let agent = simulation_agent(vec![AgentCapability::BackendDevelopment]);
+
+        let audit = simulation_task("安全审计", 2, vec![AgentCapability::SecurityAudit], vec![]);
+        let fix = simulation_task("修复漏洞", 2, vec![AgentCapability::BackendDevelopment], vec![audit.task_id.clone()]);
+        let mut first = simulation_task("循环A", 1, vec![], vec![]);
+        let second = simulation_task("循环B", 1, vec![], vec![first.task_id.clone()]);
+        first.dependencies.push(second.task_id.clone());
+        let orphan = simulation_task("孤立任务", 5, vec![AgentCapability::BackendDevelopment], vec![TaskId::new()]);
+        let tasks = vec![audit.clone(), fix.clone(), first, second, orphan.clone()];
+
+        let report = SimulationReport::simulate(ProjectId::new(), &tasks, std::slice::from_ref(&agent), start);
+
+        assert!(!report.is_feasible());
+        let conflict_types: Vec<_> = report.conflicts.iter().map(|c| c.conflict_type.clone()).collect();
+        assert!(conflict_types.contains(&SimulationConflictType::NoCapableAgent));
+        assert!(conflict_types.contains(&SimulationConflictType::BlockedByDependency));
+        assert!(conflict_types.contains(&SimulationConflictType::CircularDependency));
+        assert!(conflict_types.contains(&SimulationConflictType::MissingDependency));
+
+        assert_eq!(report.unscheduled_tasks.len(), 4);
+        assert_eq!(report.assignments.len(), 1);
+        assert_eq!(report.assignments[0].task_id, orphan.task_id);
+
+        assert_eq!(report.bottlenecks.len(), 1);
+        assert_eq!(report.bottlenecks[0].agent_id, agent.agent_id);
+        assert_eq!(report.bottlenecks[0].critical_path_tasks, 1);
+    }
+
+    fn plan_context(timeline_requirements: Option<TimelineRequirements>) -> ProjectContext {
+        ProjectContext {
+            codebase_info: CodebaseInfo {
+                total_files: 0,
+                total_lines: 0,
+                main_languages: vec![],
+                framework_info: vec![],
+                quality_metrics: CodeQualityMetrics {
+                    test_coverage: 0.0,
+                    average_complexity: 0.0,
+                    duplication_rate: 0.0,
+                    style_violations: 0,
+                    security_issues: 0,
+                    performance_issues: 0,
+                    overall_quality_score: 0,
+                },
+                recent_commit_stats: CommitStats {
+                    commits_last_30_days: 0,
+                    active_contributors: 0,
+                    average_commits_per_day: 0.0,
+                    commit_type_distribution: HashMap::new(),
+                    last_commit_time: Utc::now(),
+                },
+                technical_debt: TechnicalDebtMetrics {
+                    estimated_hours: 0,
+                    severity_distribution: HashMap::new(),
+                    main_debt_types: vec![],
+                    trend: DebtTrend::Stable,
+                },
+            },
+            existing_architecture: None,
+            development_constraints: vec![],
+            timeline_requirements,
+            completed_tasks_summary: vec![],
+            active_tasks: vec![],
+            risk_assessment: RiskAssessment {
+                overall_risk_level: RiskLevel::Low,
+                risk_items: vec![],
+                risk_matrix: RiskMatrix {
+                    high_risk_count: 0,
+                    medium_risk_count: 0,
+                    low_risk_count: 0,
+                    risk_distribution: HashMap::new(),
+                },
+                mitigation_plan: vec![],
+            },
+            resource_availability: ResourceAvailability {
+                available_agents: vec![],
+                agent_workloads: HashMap::new(),
+                estimated_resource_demand: ResourceDemand {
+                    capability_demands: HashMap::new(),
+                    peak_demand_periods: vec![],
+                    total_estimated_hours: 0,
+                },
+                resource_gaps: vec![],
+            },
+            external_dependencies_status: vec![],
+            shared_context: vec![],
+        }
+    }
+
+    #[test]
+    fn test_plan_validator_reports_and_annotates_violations() {
+        let start = Utc::now();
+        let agent = simulation_agent(vec![AgentCapability::BackendDevelopment]);
+        let api = simulation_task("后端API", 8, vec![AgentCapability::BackendDevelopment], vec![]);
+        let audit = simulation_task("安全审计", 2, vec![AgentCapability::SecurityAudit], vec![]);
+        let migration = simulation_task("数据迁移", 60, vec![AgentCapability::BackendDevelopment], vec![api.task_id.clone()]);
+        let mut tasks = vec![api.clone(), audit.clone(), migration.clone()];
+
+        let context = plan_context(Some(TimelineRequirements {
+            target_completion: start + chrono::Duration::hours(40),
+            milestone_deadlines: vec![Milestone {
+                id: "m1".to_string(),
+                name: "接口冻结".to_string(),
+                deadline: start + chrono::Duration::hours(4),
+                deliverables: vec![],
+                dependent_tasks: vec![api.task_id.clone()],
+                status: MilestoneStatus::Planned,
+                completion_rate: 0.0,
+                risk_level: RiskLevel::Medium,
+            }],
+            critical_path_tasks: vec![],
+            buffer_time_hours: 8,
+            risk_factor: 1.0,
+            work_calendar: WorkCalendar {
+                working_days: vec![1, 2, 3, 4, 5],
+                hours_per_day: 8,
+                holidays: vec![],
+                team_leave_periods: vec![],
+            },
+        }));
+
+        let report = PlanValidator::new(vec![agent]).validate(ProjectId::new(), &context, &tasks, start);
+        assert!(!report.is_valid());
+        let kinds: Vec<PlanViolationType> = report.violations.iter().map(|v| v.violation_type).collect();
+        assert!(kinds.contains(&PlanViolationType::MissingCapability));
+        assert!(kinds.contains(&PlanViolationType::OversizedTask));
+        assert!(kinds.contains(&PlanViolationType::ExceedsTimeline));
+        assert!(kinds.contains(&PlanViolationType::MissesMilestone));
+
+        let feedback = report.revision_feedback(&tasks);
+        assert!(feedback.contains("任务「安全审计」") && feedback.contains("接口冻结"));
+
+        report.annotate(&mut tasks);
+        // 计划级违规（超出时间线）写入所有任务
+        assert!(tasks.iter().all(|task| task.risk_factors.iter().any(|risk| risk.risk_type == RiskType::Timeline)));
+        assert!(tasks[1].risk_factors.iter().any(|risk| risk.risk_type == RiskType::Resource));
+
+        // 拆分任务、去掉缺少能力的任务并放宽时间线后通过
+        let small = vec![
+            simulation_task("后端API", 4, vec![AgentCapability::BackendDevelopment], vec![]),
+            simulation_task("数据迁移", 8, vec![AgentCapability::BackendDevelopment], vec![]),
+        ];
+        let agent = simulation_agent(vec![AgentCapability::BackendDevelopment]);
+        assert!(PlanValidator::new(vec![agent]).validate(ProjectId::new(), &plan_context(None), &small, start).is_valid());
+    }
+
     #[test]
     fn test_risk_level_ordering() {
         assert!(RiskLevel::Critical > RiskLevel::High);
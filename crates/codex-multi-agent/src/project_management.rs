@@ -31,6 +31,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::types::{OrganizationId, ProjectId, TeamId};
+
 #[cfg(feature = "typescript")]
 use ts_rs::TS;
 
@@ -174,6 +176,239 @@ pub enum Permission {
     Delete,
 }
 
+/// 项目成员角色，按权限从高到低排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectRole {
+    /// 所有者：全部权限，可删除项目
+    Owner,
+    /// 维护者：管理成员和项目配置
+    Maintainer,
+    /// 贡献者：创建和更新任务
+    Contributor,
+    /// 访客：只读
+    Viewer,
+}
+
+impl ProjectRole {
+    /// 角色等级，数值越大权限越高
+    pub fn rank(self) -> u8 {
+        match self {
+            ProjectRole::Owner => 3,
+            ProjectRole::Maintainer => 2,
+            ProjectRole::Contributor => 1,
+            ProjectRole::Viewer => 0,
+        }
+    }
+
+    /// 是否至少具备指定角色的权限
+    pub fn at_least(self, required: ProjectRole) -> bool {
+        self.rank() >= required.rank()
+    }
+
+    /// 角色对应的权限集合
+    pub fn permissions(self) -> Vec<Permission> {
+        match self {
+            ProjectRole::Owner => vec![
+                Permission::Read,
+                Permission::Write,
+                Permission::Review,
+                Permission::Deploy,
+                Permission::Admin,
+                Permission::Delete,
+            ],
+            ProjectRole::Maintainer => vec![
+                Permission::Read,
+                Permission::Write,
+                Permission::Review,
+                Permission::Deploy,
+                Permission::Admin,
+            ],
+            ProjectRole::Contributor => vec![Permission::Read, Permission::Write],
+            ProjectRole::Viewer => vec![Permission::Read],
+        }
+    }
+
+    /// 是否拥有指定权限
+    pub fn has_permission(self, permission: &Permission) -> bool {
+        self.permissions().contains(permission)
+    }
+
+    /// 角色的字符串表示（与序列化格式一致）
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProjectRole::Owner => "owner",
+            ProjectRole::Maintainer => "maintainer",
+            ProjectRole::Contributor => "contributor",
+            ProjectRole::Viewer => "viewer",
+        }
+    }
+}
+
+impl std::str::FromStr for ProjectRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "owner" => Ok(ProjectRole::Owner),
+            "maintainer" => Ok(ProjectRole::Maintainer),
+            "contributor" => Ok(ProjectRole::Contributor),
+            "viewer" => Ok(ProjectRole::Viewer),
+            _ => Err(format!("未知的项目角色: {}", s)),
+        }
+    }
+}
+
+/// 项目成员信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct ProjectMemberInfo {
+    /// 项目ID
+    pub project_id: ProjectId,
+
+    /// 用户ID
+    pub user_id: String,
+
+    /// 成员角色
+    pub role: ProjectRole,
+
+    /// 邀请者用户ID
+    pub invited_by: Option<String>,
+
+    /// 加入时间
+    pub joined_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// 组织与团队
+// ============================================================================
+
+/// 组织成员角色，按权限从高到低排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(rename_all = "snake_case")]
+pub enum OrganizationRole {
+    /// 所有者：管理组织设置、成员和删除组织
+    Owner,
+    /// 管理员：管理团队、项目归属和Agent池
+    Admin,
+    /// 普通成员
+    Member,
+}
+
+impl OrganizationRole {
+    /// 角色等级，数值越大权限越高
+    pub fn rank(self) -> u8 {
+        match self {
+            OrganizationRole::Owner => 3,
+            OrganizationRole::Admin => 2,
+            OrganizationRole::Member => 1,
+        }
+    }
+
+    /// 是否至少具备指定角色的权限
+    pub fn at_least(self, required: OrganizationRole) -> bool {
+        self.rank() >= required.rank()
+    }
+
+    /// 角色的字符串表示
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OrganizationRole::Owner => "owner",
+            OrganizationRole::Admin => "admin",
+            OrganizationRole::Member => "member",
+        }
+    }
+}
+
+impl std::str::FromStr for OrganizationRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "owner" => Ok(OrganizationRole::Owner),
+            "admin" => Ok(OrganizationRole::Admin),
+            "member" => Ok(OrganizationRole::Member),
+            _ => Err(format!("未知的组织角色: {}", s)),
+        }
+    }
+}
+
+/// 组织级设置，作为所属项目的默认配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct OrganizationSettings {
+    /// 默认编码规范
+    #[serde(default)]
+    pub default_coding_standards: Option<CodingStandards>,
+
+    /// 默认质量门禁
+    #[serde(default)]
+    pub default_quality_gates: Option<QualityGates>,
+}
+
+impl OrganizationSettings {
+    /// 计算项目生效的编码规范
+    ///
+    /// 项目自身的规范优先；未配置时继承组织默认规范，
+    /// 组织单独配置的质量门禁会覆盖继承规范中的质量门禁。
+    pub fn resolve_coding_standards(&self, project_standards: Option<CodingStandards>) -> CodingStandards {
+        if let Some(standards) = project_standards {
+            return standards;
+        }
+
+        let mut standards = self.default_coding_standards.clone().unwrap_or_default();
+        if let Some(quality_gates) = &self.default_quality_gates {
+            standards.quality_gates = quality_gates.clone();
+        }
+        standards
+    }
+}
+
+/// 组织信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct OrganizationInfo {
+    /// 组织ID
+    pub organization_id: OrganizationId,
+
+    /// 组织名称
+    pub name: String,
+
+    /// 组织描述
+    pub description: Option<String>,
+
+    /// 组织所有者用户ID
+    pub owner_id: String,
+
+    /// 组织级设置
+    pub settings: OrganizationSettings,
+
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+}
+
+/// 团队信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct TeamInfo {
+    /// 团队ID
+    pub team_id: TeamId,
+
+    /// 所属组织ID
+    pub organization_id: OrganizationId,
+
+    /// 团队名称
+    pub name: String,
+
+    /// 团队描述
+    pub description: Option<String>,
+
+    /// 团队成员用户ID
+    pub member_ids: Vec<String>,
+}
+
 /// 外部依赖信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
@@ -279,6 +514,10 @@ pub struct LanguageConfig {
     /// Linter配置文件路径或内容
     pub linter_config: Option<String>,
 
+    /// 自定义 Linter 命令，为空时使用该语言的内置命令；输出格式需与内置 Linter 一致
+    #[serde(default)]
+    pub linter_command: Option<String>,
+
     /// 格式化工具配置
     pub formatter_config: Option<String>,
 
@@ -381,6 +620,14 @@ pub struct QualityGates {
     #[serde(default = "default_true")]
     pub require_all_checks_pass: bool,
 
+    /// 失败的测试全部是统计上不稳定的测试时，只给出警告而不阻止
+    #[serde(default)]
+    pub quarantine_flaky_tests: bool,
+
+    /// 安全扫描发现达到该级别时阻止任务完成
+    #[serde(default = "default_security_blocking_severity")]
+    pub security_blocking_severity: SeverityLevel,
+
     /// 允许的严重性级别
     pub allowed_severity_levels: Vec<SeverityLevel>,
 }
@@ -856,6 +1103,8 @@ impl Default for CodingStandards {
                 max_duplication_rate: 0.05,
                 required_reviewers: 1,
                 require_all_checks_pass: true,
+                quarantine_flaky_tests: false,
+                security_blocking_severity: SeverityLevel::Blocker,
                 allowed_severity_levels: vec![SeverityLevel::Info, SeverityLevel::Warning],
             },
             test_requirements: TestRequirements {
@@ -920,6 +1169,10 @@ fn default_true() -> bool {
     true
 }
 
+fn default_security_blocking_severity() -> SeverityLevel {
+    SeverityLevel::Blocker
+}
+
 // ============================================================================
 // 测试模块
 // ============================================================================
@@ -928,6 +1181,38 @@ fn default_true() -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_organization_settings_inheritance() {
+        let gates = QualityGates {
+            min_test_coverage: 0.9,
+            max_complexity: 8,
+            max_tech_debt_hours: 4,
+            max_duplication_rate: 0.02,
+            required_reviewers: 2,
+            require_all_checks_pass: true,
+            quarantine_flaky_tests: false,
+            security_blocking_severity: SeverityLevel::Blocker,
+            allowed_severity_levels: vec![SeverityLevel::Info],
+        };
+        let settings = OrganizationSettings {
+            default_coding_standards: None,
+            default_quality_gates: Some(gates),
+        };
+
+        // 项目未配置时继承组织质量门禁
+        let inherited = settings.resolve_coding_standards(None);
+        assert_eq!(inherited.quality_gates.required_reviewers, 2);
+        assert_eq!(inherited.quality_gates.max_complexity, 8);
+
+        // 项目自身配置优先
+        let own = settings.resolve_coding_standards(Some(CodingStandards::default()));
+        assert_eq!(own.quality_gates.required_reviewers, 1);
+
+        assert!(OrganizationRole::Owner.at_least(OrganizationRole::Admin));
+        assert!(!OrganizationRole::Member.at_least(OrganizationRole::Admin));
+        assert_eq!("admin".parse::<OrganizationRole>(), Ok(OrganizationRole::Admin));
+    }
+
     #[test]
     fn test_project_info_serialization() {
         let project = ProjectInfo {
@@ -965,6 +1250,16 @@ mod tests {
         assert_eq!(standards.review_rules.min_reviewers, 1);
     }
 
+    #[test]
+    fn test_project_role_permissions() {
+        assert!(ProjectRole::Owner.at_least(ProjectRole::Maintainer));
+        assert!(!ProjectRole::Viewer.at_least(ProjectRole::Contributor));
+        assert!(ProjectRole::Contributor.has_permission(&Permission::Write));
+        assert!(!ProjectRole::Maintainer.has_permission(&Permission::Delete));
+        assert_eq!("maintainer".parse::<ProjectRole>().unwrap(), ProjectRole::Maintainer);
+        assert_eq!(serde_json::to_string(&ProjectRole::Viewer).unwrap(), "\"viewer\"");
+    }
+
     #[test]
     fn test_priority_ordering() {
         assert!(ProjectPriority::Critical > ProjectPriority::High);
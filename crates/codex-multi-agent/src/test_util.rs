@@ -0,0 +1,963 @@
+//! # 属性测试支持模块
+//!
+//! 为核心协议类型（任务信息、项目上下文、事件）提供 `proptest::Arbitrary` 实现，
+//! 下游crate启用 `test-util` feature 后即可对这些类型做序列化往返等属性测试。
+//!
+//! ## 使用示例
+//!
+//! ```rust,ignore
+//! use codex_multi_agent::TaskInfo;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     #[test]
+//!     fn task_roundtrip(task in any::<TaskInfo>()) {
+//!         let json = serde_json::to_string(&task).unwrap();
+//!         let _: TaskInfo = serde_json::from_str(&json).unwrap();
+//!     }
+//! }
+//! ```
+//!
+//! 生成的浮点数均为有限值，时间戳精确到纳秒且落在 1970-2100 年之间，
+//! 保证 JSON 往返不丢失信息。
+
+use crate::agent_management::ResourceLimits;
+use crate::events::*;
+use crate::llm_orchestration::*;
+use crate::types::*;
+
+use chrono::{DateTime, Utc};
+use proptest::collection::{hash_map, vec};
+use proptest::prelude::*;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// ============================================================================
+// 基础生成策略
+// ============================================================================
+
+/// 任意短文本（包含中文等非ASCII字符）
+pub fn text() -> impl Strategy<Value = String> + Clone {
+    "\\PC{0,16}"
+}
+
+/// 短文本列表
+pub fn text_list() -> impl Strategy<Value = Vec<String>> + Clone {
+    vec(text(), 0..4)
+}
+
+/// 1970-2100 年之间的任意时间
+pub fn timestamp() -> impl Strategy<Value = DateTime<Utc>> + Clone {
+    (0i64..4_102_444_800, 0u32..1_000_000_000)
+        .prop_map(|(secs, nanos)| DateTime::from_timestamp(secs, nanos).expect("时间戳在有效范围内"))
+}
+
+/// 0.0-1.0 之间的比例值
+pub fn ratio() -> impl Strategy<Value = f32> + Clone {
+    0.0f32..=1.0
+}
+
+/// 有限的浮点数
+pub fn finite_f32() -> impl Strategy<Value = f32> + Clone {
+    -1.0e6f32..1.0e6
+}
+
+/// 1-10 的评分
+pub fn score() -> impl Strategy<Value = u8> + Clone {
+    1u8..=10
+}
+
+/// 不含浮点数的任意JSON标量值
+pub fn json_scalar() -> impl Strategy<Value = serde_json::Value> + Clone {
+    prop_oneof![
+        Just(serde_json::Value::Null),
+        any::<bool>().prop_map(serde_json::Value::Bool),
+        any::<i64>().prop_map(serde_json::Value::from),
+        text().prop_map(serde_json::Value::String),
+    ]
+}
+
+fn text_map<V: Strategy + Clone>(values: V) -> impl Strategy<Value = HashMap<String, V::Value>> + Clone
+where
+    V::Value: Clone + std::fmt::Debug,
+{
+    hash_map(text(), values, 0..4)
+}
+
+// ============================================================================
+// ID 和枚举
+// ============================================================================
+
+macro_rules! arbitrary_id {
+    ($($ty:ident),+ $(,)?) => {$(
+        impl Arbitrary for $ty {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                any::<u128>().prop_map(|n| $ty(Uuid::from_u128(n))).boxed()
+            }
+        }
+    )+};
+}
+
+macro_rules! arbitrary_enum {
+    ($($ty:ident { $($variant:ident),+ $(,)? })+) => {$(
+        impl Arbitrary for $ty {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                prop_oneof![$(Just($ty::$variant)),+].boxed()
+            }
+        }
+    )+};
+}
+
+arbitrary_id!(AgentId, ProjectId, TaskId, ExecutionSessionId);
+
+arbitrary_enum! {
+    AgentCapability {
+        FrontendDevelopment, BackendDevelopment, DatabaseDesign, Testing, CodeReview, DevOps,
+        Documentation, UIDesign, SecurityAudit, PerformanceTuning, ApiDesign, ArchitectureDesign,
+        DataAnalysis, MachineLearning,
+    }
+    AgentStatus { Idle, Working, Paused, Error, Offline, Maintenance }
+    TaskType {
+        Development, Testing, CodeReview, Documentation, Deployment, Bugfix, Refactoring,
+        Research, Design, Optimization,
+    }
+    TaskPriority { Low, Medium, High, Critical }
+    CiStatus { Pending, Running, Passed, Failed, Cancelled }
+    RiskType { Technical, Timeline, Resource, Dependency, Quality, Security, Business }
+    RiskLevel { Low, Medium, High, Critical }
+    RiskStatus { Identified, Monitoring, Mitigating, Mitigated, Realized }
+    MitigationActionType { Avoid, Reduce, Transfer, Accept, Monitor }
+    ActionStatus { Planned, InProgress, Completed, Cancelled, Delayed }
+    ConfigurationStatus { UpToDate, NeedsUpdate, Misconfigured, Unknown }
+    DebtType {
+        CodeDuplication, ComplexLogic, MissingTests, OutdatedDependencies, PoorDesign,
+        HardcodedValues, PerformanceIssues,
+    }
+    DebtTrend { Increasing, Stable, Decreasing }
+    MilestoneStatus { Planned, InProgress, Completed, Delayed, AtRisk }
+    LeaveType { AnnualLeave, SickLeave, Conference, Other }
+    ExternalDependencyStatus { Available, Unavailable, LimitedAvailability, Unknown, ScheduledMaintenance }
+    SharedContextCategory { Decision, ApiSpec, NamingConvention, Note }
+    EventSource { System, Agent, User, External, Scheduler, Webhook }
+    EventPriority { Low, Normal, High, Critical }
+    EventType {
+        SessionStarted, SessionCompleted, SessionFailed, Progress, GitOperation, GitCheckout, FileChange,
+        LlmRequest, LlmResponse, ToolCall, TestRun, TestsPassed, TestsFailed, Compilation, Deployment,
+        CodeAnalysis, DependencyInstall, EnvironmentSetup, Error,
+    }
+    IssueType {
+        CompilationError, TestFailure, StyleViolation, PerformanceIssue, SecurityVulnerability,
+        DependencyIssue, ConfigurationError, ResourceShortage, Other,
+    }
+    IssueSeverity { Info, Minor, Moderate, Major, Critical, Blocker }
+    SystemStatus { Healthy, Degraded, PartialOutage, MajorOutage, Maintenance }
+}
+
+// ============================================================================
+// 任务信息
+// ============================================================================
+
+impl Arbitrary for TaskTestRequirements {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<bool>(), any::<bool>(), any::<bool>(), ratio(), text_list())
+            .prop_map(|(unit, integration, e2e, coverage, scenarios)| TaskTestRequirements {
+                needs_unit_tests: unit,
+                needs_integration_tests: integration,
+                needs_e2e_tests: e2e,
+                required_coverage: coverage,
+                special_test_scenarios: scenarios,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for ComplexityAssessment {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (score(), score(), score(), score(), text_list())
+            .prop_map(|(technical, business, integration, overall, notes)| ComplexityAssessment {
+                technical_complexity: technical,
+                business_complexity: business,
+                integration_complexity: integration,
+                overall_complexity: overall,
+                complexity_notes: notes,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for RiskFactor {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<RiskType>(), any::<RiskLevel>(), text(), text(), text_list())
+            .prop_map(|(risk_type, risk_level, description, impact, mitigations)| RiskFactor {
+                risk_type,
+                risk_level,
+                description,
+                impact_assessment: impact,
+                mitigation_strategies: mitigations,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for TaskInfo {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        let header = (
+            any::<TaskId>(),
+            text(),
+            text(),
+            any::<TaskType>(),
+            any::<TaskPriority>(),
+            0u32..1000,
+            vec(any::<AgentCapability>(), 0..4),
+            vec(any::<TaskId>(), 0..3),
+        );
+        let details = (
+            text_list(),
+            text_list(),
+            text_list(),
+            any::<TaskTestRequirements>(),
+            any::<ComplexityAssessment>(),
+            vec(any::<RiskFactor>(), 0..3),
+            vec(any::<TaskId>(), 0..3),
+            text_list(),
+        );
+
+        (header, details, proptest::option::of(any::<CiStatus>()))
+            .prop_map(
+                |(
+                    (task_id, title, description, task_type, priority, estimated_hours, required_capabilities, dependencies),
+                    (acceptance_criteria, tags, related_files, test_requirements, complexity_assessment, risk_factors, subtasks, related_issues),
+                    ci_status,
+                )| TaskInfo {
+                    task_id,
+                    title,
+                    description,
+                    task_type,
+                    priority,
+                    estimated_hours,
+                    required_capabilities,
+                    dependencies,
+                    acceptance_criteria,
+                    tags,
+                    related_files,
+                    test_requirements,
+                    complexity_assessment,
+                    risk_factors,
+                    subtasks,
+                    related_issues,
+                    ci_status,
+                },
+            )
+            .boxed()
+    }
+}
+
+// ============================================================================
+// 项目上下文
+// ============================================================================
+
+impl Arbitrary for LanguageStats {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (text(), any::<u32>(), any::<u32>(), ratio(), score(), score())
+            .prop_map(|(language, file_count, line_count, percentage, complexity, maintenance)| LanguageStats {
+                language,
+                file_count,
+                line_count,
+                percentage,
+                complexity_score: complexity,
+                maintenance_score: maintenance,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for FrameworkInfo {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (text(), text(), text(), any::<ConfigurationStatus>(), proptest::option::of(text()))
+            .prop_map(|(name, version, usage_scope, configuration_status, update_recommendation)| FrameworkInfo {
+                name,
+                version,
+                usage_scope,
+                configuration_status,
+                update_recommendation,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for CodebaseInfo {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        let quality_metrics = (ratio(), 0.0f32..100.0, ratio(), any::<u32>(), any::<u32>(), any::<u32>(), score())
+            .prop_map(|(coverage, complexity, duplication, style, security, performance, overall)| CodeQualityMetrics {
+                test_coverage: coverage,
+                average_complexity: complexity,
+                duplication_rate: duplication,
+                style_violations: style,
+                security_issues: security,
+                performance_issues: performance,
+                overall_quality_score: overall,
+            });
+        let commit_stats = (any::<u32>(), any::<u32>(), 0.0f32..1000.0, text_map(any::<u32>()), timestamp())
+            .prop_map(|(commits, contributors, per_day, distribution, last_commit_time)| CommitStats {
+                commits_last_30_days: commits,
+                active_contributors: contributors,
+                average_commits_per_day: per_day,
+                commit_type_distribution: distribution,
+                last_commit_time,
+            });
+        let technical_debt = (any::<u32>(), text_map(any::<u32>()), vec(any::<DebtType>(), 0..3), any::<DebtTrend>())
+            .prop_map(|(estimated_hours, severity_distribution, main_debt_types, trend)| TechnicalDebtMetrics {
+                estimated_hours,
+                severity_distribution,
+                main_debt_types,
+                trend,
+            });
+
+        (
+            any::<u32>(),
+            any::<u32>(),
+            vec(any::<LanguageStats>(), 0..3),
+            vec(any::<FrameworkInfo>(), 0..3),
+            quality_metrics,
+            commit_stats,
+            technical_debt,
+        )
+            .prop_map(
+                |(total_files, total_lines, main_languages, framework_info, quality_metrics, recent_commit_stats, technical_debt)| {
+                    CodebaseInfo {
+                        total_files,
+                        total_lines,
+                        main_languages,
+                        framework_info,
+                        quality_metrics,
+                        recent_commit_stats,
+                        technical_debt,
+                    }
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for Milestone {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            text(),
+            text(),
+            timestamp(),
+            text_list(),
+            vec(any::<TaskId>(), 0..3),
+            any::<MilestoneStatus>(),
+            ratio(),
+            any::<RiskLevel>(),
+        )
+            .prop_map(
+                |(id, name, deadline, deliverables, dependent_tasks, status, completion_rate, risk_level)| Milestone {
+                    id,
+                    name,
+                    deadline,
+                    deliverables,
+                    dependent_tasks,
+                    status,
+                    completion_rate,
+                    risk_level,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for TimelineRequirements {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        let leave_period = (timestamp(), timestamp(), any::<LeaveType>(), text_list())
+            .prop_map(|(start_date, end_date, leave_type, affected_members)| LeavePeriod {
+                start_date,
+                end_date,
+                leave_type,
+                affected_members,
+            });
+        let work_calendar = (vec(0u8..7, 0..7), 1u8..=24, vec(timestamp(), 0..3), vec(leave_period, 0..2))
+            .prop_map(|(working_days, hours_per_day, holidays, team_leave_periods)| WorkCalendar {
+                working_days,
+                hours_per_day,
+                holidays,
+                team_leave_periods,
+            });
+
+        (timestamp(), vec(any::<Milestone>(), 0..3), text_list(), any::<u32>(), finite_f32(), work_calendar)
+            .prop_map(
+                |(target_completion, milestone_deadlines, critical_path_tasks, buffer_time_hours, risk_factor, work_calendar)| {
+                    TimelineRequirements {
+                        target_completion,
+                        milestone_deadlines,
+                        critical_path_tasks,
+                        buffer_time_hours,
+                        risk_factor,
+                        work_calendar,
+                    }
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for CompletedTaskSummary {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<TaskId>(), text(), timestamp(), any::<u32>(), any::<AgentId>(), ratio(), text_list())
+            .prop_map(
+                |(task_id, title, completed_at, actual_hours, executed_by, quality_score, lessons_learned)| {
+                    CompletedTaskSummary {
+                        task_id,
+                        title,
+                        completed_at,
+                        actual_hours,
+                        executed_by,
+                        quality_score,
+                        lessons_learned,
+                    }
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for RiskAssessment {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        let risk_item = (text(), text(), any::<RiskType>(), ratio(), ratio(), ratio(), any::<RiskStatus>(), proptest::option::of(text()))
+            .prop_map(|(risk_id, description, risk_type, probability, impact, risk_score, status, owner)| RiskItem {
+                risk_id,
+                description,
+                risk_type,
+                probability,
+                impact,
+                risk_score,
+                status,
+                owner,
+            });
+        let risk_matrix = (any::<u32>(), any::<u32>(), any::<u32>(), text_map(any::<u32>()))
+            .prop_map(|(high, medium, low, distribution)| RiskMatrix {
+                high_risk_count: high,
+                medium_risk_count: medium,
+                low_risk_count: low,
+                risk_distribution: distribution,
+            });
+        let mitigation = (text(), text(), text(), any::<MitigationActionType>(), text(), timestamp(), any::<ActionStatus>())
+            .prop_map(
+                |(action_id, target_risk_id, description, action_type, responsible_person, due_date, status)| {
+                    MitigationAction {
+                        action_id,
+                        target_risk_id,
+                        description,
+                        action_type,
+                        responsible_person,
+                        due_date,
+                        status,
+                    }
+                },
+            );
+
+        (any::<RiskLevel>(), vec(risk_item, 0..3), risk_matrix, vec(mitigation, 0..3))
+            .prop_map(|(overall_risk_level, risk_items, risk_matrix, mitigation_plan)| RiskAssessment {
+                overall_risk_level,
+                risk_items,
+                risk_matrix,
+                mitigation_plan,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for ResourceAvailability {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        let period = (timestamp(), timestamp(), any::<u32>(), text())
+            .prop_map(|(start_time, end_time, required_resources, description)| DemandPeriod {
+                start_time,
+                end_time,
+                required_resources,
+                description,
+            });
+        let demand = (text_map(any::<u32>()), vec(period, 0..3), any::<u32>())
+            .prop_map(|(capability_demands, peak_demand_periods, total_estimated_hours)| ResourceDemand {
+                capability_demands,
+                peak_demand_periods,
+                total_estimated_hours,
+            });
+        let gap = (any::<AgentCapability>(), any::<u32>(), vec(any::<TaskId>(), 0..3), text_list())
+            .prop_map(|(capability, gap_hours, affected_tasks, suggested_solutions)| ResourceGap {
+                capability,
+                gap_hours,
+                affected_tasks,
+                suggested_solutions,
+            });
+
+        (vec(any::<AgentId>(), 0..4), text_map(ratio()), demand, vec(gap, 0..3))
+            .prop_map(
+                |(available_agents, agent_workloads, estimated_resource_demand, resource_gaps)| ResourceAvailability {
+                    available_agents,
+                    agent_workloads,
+                    estimated_resource_demand,
+                    resource_gaps,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for DependencyStatus {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (text(), any::<ExternalDependencyStatus>(), text(), timestamp(), text())
+            .prop_map(|(name, status, status_description, last_checked, impact)| DependencyStatus {
+                name,
+                status,
+                status_description,
+                last_checked,
+                impact_if_unavailable: impact,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for SharedContextEntry {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (text(), any::<SharedContextCategory>(), text(), any::<u32>())
+            .prop_map(|(key, category, value, version)| SharedContextEntry {
+                key,
+                category,
+                value,
+                version,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for ProjectContext {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            any::<CodebaseInfo>(),
+            proptest::option::of(text()),
+            text_list(),
+            proptest::option::of(any::<TimelineRequirements>()),
+            vec(any::<CompletedTaskSummary>(), 0..3),
+            vec(any::<TaskId>(), 0..3),
+            any::<RiskAssessment>(),
+            any::<ResourceAvailability>(),
+            vec(any::<DependencyStatus>(), 0..3),
+            vec(any::<SharedContextEntry>(), 0..3),
+        )
+            .prop_map(
+                |(
+                    codebase_info,
+                    existing_architecture,
+                    development_constraints,
+                    timeline_requirements,
+                    completed_tasks_summary,
+                    active_tasks,
+                    risk_assessment,
+                    resource_availability,
+                    external_dependencies_status,
+                    shared_context,
+                )| ProjectContext {
+                    codebase_info,
+                    existing_architecture,
+                    development_constraints,
+                    timeline_requirements,
+                    completed_tasks_summary,
+                    active_tasks,
+                    risk_assessment,
+                    resource_availability,
+                    external_dependencies_status,
+                    shared_context,
+                },
+            )
+            .boxed()
+    }
+}
+
+// ============================================================================
+// 事件
+// ============================================================================
+
+impl Arbitrary for EventMetadata {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            text(),
+            timestamp(),
+            any::<EventSource>(),
+            proptest::option::of(text()),
+            proptest::option::of(text()),
+            proptest::option::of(text()),
+            any::<EventPriority>(),
+            text_list(),
+            text_map(json_scalar()),
+        )
+            .prop_map(
+                |(event_id, timestamp, source, session_id, user_id, correlation_id, priority, tags, custom_attributes)| EventMetadata {
+                    event_id,
+                    timestamp,
+                    source,
+                    session_id,
+                    user_id,
+                    correlation_id,
+                    priority,
+                    tags,
+                    custom_attributes,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for AgentStatusChangedEvent {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            any::<EventMetadata>(),
+            any::<AgentId>(),
+            any::<AgentStatus>(),
+            any::<AgentStatus>(),
+            text(),
+            proptest::option::of(text()),
+        )
+            .prop_map(
+                |(metadata, agent_id, previous_status, new_status, reason, status_details)| AgentStatusChangedEvent {
+                    metadata,
+                    agent_id,
+                    previous_status,
+                    new_status,
+                    reason,
+                    status_details,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for TaskExecutionStartedEvent {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::option::of;
+
+        let resource_limits = (of(any::<u64>()), of(ratio()), of(any::<u64>()), of(any::<u64>()), of(any::<u64>()))
+            .prop_map(|(memory, cpu, disk, network, execution_time)| ResourceLimits {
+                max_memory_mb: memory,
+                max_cpu_usage: cpu,
+                max_disk_usage_mb: disk,
+                max_network_bandwidth_kbps: network,
+                max_execution_time_seconds: execution_time,
+            });
+        let quality_checks = (any::<bool>(), any::<bool>(), any::<bool>(), of(ratio()), text_list())
+            .prop_map(|(style, coverage, security, min_coverage_threshold, custom_rules)| QualityCheckConfig {
+                enable_style_check: style,
+                enable_coverage_check: coverage,
+                enable_security_check: security,
+                min_coverage_threshold,
+                custom_rules,
+            });
+        let execution_config = (any::<u32>(), any::<u32>(), any::<bool>(), text_map(text()), of(resource_limits), quality_checks)
+            .prop_map(
+                |(timeout_seconds, max_retries, verbose_logging, environment_variables, resource_limits, quality_checks)| {
+                    ExecutionConfig {
+                        timeout_seconds,
+                        max_retries,
+                        verbose_logging,
+                        environment_variables,
+                        resource_limits,
+                        quality_checks,
+                    }
+                },
+            );
+
+        (
+            any::<EventMetadata>(),
+            any::<ExecutionSessionId>(),
+            any::<TaskId>(),
+            any::<AgentId>(),
+            timestamp(),
+            execution_config,
+        )
+            .prop_map(
+                |(metadata, session_id, task_id, agent_id, estimated_completion_time, execution_config)| {
+                    TaskExecutionStartedEvent {
+                        metadata,
+                        session_id,
+                        task_id,
+                        agent_id,
+                        estimated_completion_time,
+                        execution_config,
+                    }
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for TaskProgressUpdatedEvent {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        let progress_info = (
+            ratio(),
+            text_list(),
+            proptest::option::of(text()),
+            text_list(),
+            any::<u32>(),
+            any::<u32>(),
+            text_map(finite_f32()),
+        )
+            .prop_map(
+                |(completion_percentage, completed_steps, current_step, remaining_steps, elapsed, remaining, quality_metrics)| {
+                    ProgressInfo {
+                        completion_percentage,
+                        completed_steps,
+                        current_step,
+                        remaining_steps,
+                        elapsed_minutes: elapsed,
+                        estimated_remaining_minutes: remaining,
+                        quality_metrics,
+                    }
+                },
+            );
+        let issue = (any::<IssueType>(), text(), any::<IssueSeverity>(), timestamp(), text_list(), text_list(), any::<bool>())
+            .prop_map(
+                |(issue_type, description, severity, discovered_at, related_files, suggested_solutions, auto_fixed)| {
+                    IssueReport {
+                        issue_type,
+                        description,
+                        severity,
+                        discovered_at,
+                        related_files,
+                        suggested_solutions,
+                        auto_fixed,
+                    }
+                },
+            );
+
+        (
+            any::<EventMetadata>(),
+            proptest::option::of(any::<ExecutionSessionId>()),
+            proptest::option::of(any::<TaskId>()),
+            progress_info,
+            text(),
+            text_list(),
+            vec(issue, 0..3),
+        )
+            .prop_map(
+                |(metadata, session_id, task_id, progress_info, current_phase, next_steps, encountered_issues)| {
+                    TaskProgressUpdatedEvent {
+                        metadata,
+                        session_id,
+                        task_id,
+                        progress_info,
+                        current_phase,
+                        next_steps,
+                        encountered_issues,
+                    }
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for SystemStatusChangedEvent {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            any::<EventMetadata>(),
+            any::<SystemStatus>(),
+            any::<SystemStatus>(),
+            text(),
+            text_list(),
+            proptest::option::of(timestamp()),
+        )
+            .prop_map(
+                |(metadata, previous_status, new_status, reason, affected_components, estimated_recovery_time)| {
+                    SystemStatusChangedEvent {
+                        metadata,
+                        previous_status,
+                        new_status,
+                        reason,
+                        affected_components,
+                        estimated_recovery_time,
+                    }
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for ErrorEvent {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::option::of;
+
+        (any::<EventMetadata>(), text(), text(), of(text()), of(text()), of(text()), any::<bool>(), text_list())
+            .prop_map(
+                |(metadata, error_type, error_message, stack_trace, related_entity_id, error_code, can_auto_recover, suggested_actions)| {
+                    ErrorEvent {
+                        metadata,
+                        error_type,
+                        error_message,
+                        stack_trace,
+                        related_entity_id,
+                        error_code,
+                        can_auto_recover,
+                        suggested_actions,
+                    }
+                },
+            )
+            .boxed()
+    }
+}
+
+// ============================================================================
+// 测试模块
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{de::DeserializeOwned, Serialize};
+
+    /// 序列化 -> 反序列化 -> 再序列化，两次JSON必须完全一致
+    fn assert_roundtrip<T: Serialize + DeserializeOwned>(value: &T) -> Result<(), TestCaseError> {
+        let first = serde_json::to_value(value).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        let decoded: T = serde_json::from_value(first.clone()).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        let second = serde_json::to_value(&decoded).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        prop_assert_eq!(first, second);
+        Ok(())
+    }
+
+    /// 枚举序列化为 snake_case 字符串，与生成的 TypeScript 字符串联合类型一致
+    fn assert_snake_case<T: Serialize>(value: &T) -> Result<(), TestCaseError> {
+        let json = serde_json::to_value(value).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        let text = json.as_str().ok_or_else(|| TestCaseError::fail(format!("枚举应序列化为字符串: {json}")))?;
+        prop_assert!(
+            !text.is_empty() && text.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+            "不是 snake_case: {}",
+            text
+        );
+        Ok(())
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn task_info_roundtrip(task in any::<TaskInfo>()) {
+            assert_roundtrip(&task)?;
+        }
+
+        #[test]
+        fn project_context_roundtrip(context in any::<ProjectContext>()) {
+            assert_roundtrip(&context)?;
+        }
+
+        #[test]
+        fn events_roundtrip(
+            status_changed in any::<AgentStatusChangedEvent>(),
+            execution_started in any::<TaskExecutionStartedEvent>(),
+            progress_updated in any::<TaskProgressUpdatedEvent>(),
+            system_status in any::<SystemStatusChangedEvent>(),
+            error in any::<ErrorEvent>(),
+        ) {
+            assert_roundtrip(&status_changed)?;
+            assert_roundtrip(&execution_started)?;
+            assert_roundtrip(&progress_updated)?;
+            assert_roundtrip(&system_status)?;
+            assert_roundtrip(&error)?;
+        }
+
+        #[test]
+        fn ids_serialize_as_uuid_strings(task_id in any::<TaskId>()) {
+            let json = serde_json::to_value(&task_id).unwrap();
+            prop_assert_eq!(json.as_str().map(str::to_string), Some(task_id.0.to_string()));
+        }
+
+        #[test]
+        fn enums_serialize_as_snake_case(
+            capability in any::<AgentCapability>(),
+            task_type in any::<TaskType>(),
+            status in any::<AgentStatus>(),
+            issue_type in any::<IssueType>(),
+            source in any::<EventSource>(),
+            event_type in any::<EventType>(),
+        ) {
+            assert_snake_case(&capability)?;
+            assert_snake_case(&task_type)?;
+            assert_snake_case(&status)?;
+            assert_snake_case(&issue_type)?;
+            assert_snake_case(&source)?;
+            assert_snake_case(&event_type)?;
+        }
+
+        #[test]
+        fn event_type_string_matches_serde(event_type in any::<EventType>()) {
+            let json = serde_json::to_value(event_type).unwrap();
+            prop_assert_eq!(json.as_str(), Some(event_type.as_str()));
+            prop_assert_eq!(event_type.as_str().parse::<EventType>(), Ok(event_type));
+        }
+    }
+}
@@ -27,36 +27,42 @@ use uuid::Uuid;
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
 #[cfg_attr(feature = "typescript", ts(as = "string"))]
+#[serde(transparent)]
 pub struct AgentId(pub Uuid);
 
 /// 项目唯一标识符
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
 #[cfg_attr(feature = "typescript", ts(as = "string"))]
+#[serde(transparent)]
 pub struct ProjectId(pub Uuid);
 
 /// 任务唯一标识符
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
 #[cfg_attr(feature = "typescript", ts(as = "string"))]
+#[serde(transparent)]
 pub struct TaskId(pub Uuid);
 
 /// 执行会话唯一标识符
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
 #[cfg_attr(feature = "typescript", ts(as = "string"))]
+#[serde(transparent)]
 pub struct ExecutionSessionId(pub Uuid);
 
 /// 代码审查唯一标识符
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
 #[cfg_attr(feature = "typescript", ts(as = "string"))]
+#[serde(transparent)]
 pub struct ReviewId(pub Uuid);
 
 /// 冲突唯一标识符
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
 #[cfg_attr(feature = "typescript", ts(as = "string"))]
+#[serde(transparent)]
 pub struct ConflictId(pub Uuid);
 
 /// LLM会话唯一标识符
@@ -64,8 +70,30 @@ pub struct ConflictId(pub Uuid);
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
 #[cfg_attr(feature = "typescript", ts(as = "string"))]
+#[serde(transparent)]
 pub struct LlmSessionId(pub Uuid);
 
+/// 组织唯一标识符
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[cfg_attr(feature = "typescript", ts(as = "string"))]
+#[serde(transparent)]
+pub struct OrganizationId(pub Uuid);
+
+/// 团队唯一标识符
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[cfg_attr(feature = "typescript", ts(as = "string"))]
+#[serde(transparent)]
+pub struct TeamId(pub Uuid);
+
+/// 远程工作进程唯一标识符
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[cfg_attr(feature = "typescript", ts(as = "string"))]
+#[serde(transparent)]
+pub struct WorkerId(pub Uuid);
+
 // 为所有ID类型实现共同的trait和方法
 macro_rules! impl_id_traits {
     ($id_type:ident) => {
@@ -122,6 +150,34 @@ macro_rules! impl_id_traits {
                 Ok(Self(Uuid::parse_str(s)?))
             }
         }
+
+        impl From<&$id_type> for Uuid {
+            fn from(value: &$id_type) -> Self {
+                value.0
+            }
+        }
+
+        impl TryFrom<&str> for $id_type {
+            type Error = uuid::Error;
+
+            fn try_from(value: &str) -> Result<Self, Self::Error> {
+                Ok(Self(Uuid::parse_str(value)?))
+            }
+        }
+
+        impl TryFrom<String> for $id_type {
+            type Error = uuid::Error;
+
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                Self::try_from(value.as_str())
+            }
+        }
+
+        impl AsRef<Uuid> for $id_type {
+            fn as_ref(&self) -> &Uuid {
+                &self.0
+            }
+        }
     };
 }
 
@@ -132,6 +188,9 @@ impl_id_traits!(ExecutionSessionId);
 impl_id_traits!(ReviewId);
 impl_id_traits!(ConflictId);
 impl_id_traits!(LlmSessionId);
+impl_id_traits!(OrganizationId);
+impl_id_traits!(TeamId);
+impl_id_traits!(WorkerId);
 
 // ============================================================================
 // Agent相关枚举类型
@@ -197,7 +256,7 @@ pub enum AgentStatus {
 // ============================================================================
 
 /// 任务类型枚举
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "typescript", derive(TS))]
 #[serde(rename_all = "snake_case")]
 pub enum TaskType {
@@ -259,6 +318,67 @@ pub enum TaskStatus {
     WaitingForDependency,
     /// 等待审查
     WaitingForReview,
+    /// 在人工检查点等待批准
+    WaitingForHuman,
+}
+
+/// CI 流水线状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(rename_all = "snake_case")]
+pub enum CiStatus {
+    /// 排队等待
+    Pending,
+    /// 运行中
+    Running,
+    /// 全部通过
+    Passed,
+    /// 存在失败的检查
+    Failed,
+    /// 已取消
+    Cancelled,
+}
+
+impl CiStatus {
+    /// CI 是否为红色（失败或被取消）
+    pub fn is_red(&self) -> bool {
+        matches!(self, CiStatus::Failed | CiStatus::Cancelled)
+    }
+
+    /// 汇总多个检查的状态：任一失败即失败，其次是取消、运行中、排队，全部通过才算通过
+    pub fn combine<I: IntoIterator<Item = CiStatus>>(statuses: I) -> Option<CiStatus> {
+        statuses.into_iter().max_by_key(|status| match status {
+            CiStatus::Passed => 0,
+            CiStatus::Pending => 1,
+            CiStatus::Running => 2,
+            CiStatus::Cancelled => 3,
+            CiStatus::Failed => 4,
+        })
+    }
+}
+
+impl std::fmt::Display for CiStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CiStatus::Pending => write!(f, "pending"),
+            CiStatus::Running => write!(f, "running"),
+            CiStatus::Passed => write!(f, "passed"),
+            CiStatus::Failed => write!(f, "failed"),
+            CiStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl From<String> for CiStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "running" => CiStatus::Running,
+            "passed" => CiStatus::Passed,
+            "failed" => CiStatus::Failed,
+            "cancelled" => CiStatus::Cancelled,
+            _ => CiStatus::Pending,
+        }
+    }
 }
 
 // ============================================================================
@@ -88,6 +88,9 @@ impl TypeScriptGenerator {
             output.push_str(&ReviewId::typescript_definition());
             output.push_str(&ConflictId::typescript_definition());
             output.push_str(&LlmSessionId::typescript_definition());
+            output.push_str(&OrganizationId::typescript_definition());
+            output.push_str(&TeamId::typescript_definition());
+            output.push_str(&WorkerId::typescript_definition());
         }
         
         // 生成枚举类型
@@ -96,6 +99,7 @@ impl TypeScriptGenerator {
         output.push_str(&TaskType::typescript_definition());
         output.push_str(&TaskPriority::typescript_definition());
         output.push_str(&TaskStatus::typescript_definition());
+        output.push_str(&CiStatus::typescript_definition());
         output.push_str(&ConflictType::typescript_definition());
         output.push_str(&ConflictSeverity::typescript_definition());
         output.push_str(&EntityReference::typescript_definition());
@@ -121,10 +125,18 @@ impl TypeScriptGenerator {
         output.push_str(&AgentDetails::typescript_definition());
         output.push_str(&GitConfig::typescript_definition());
         output.push_str(&ResourceLimits::typescript_definition());
+        output.push_str(&AgentApprovalPolicy::typescript_definition());
+        output.push_str(&CommandApproval::typescript_definition());
         output.push_str(&PerformanceMetrics::typescript_definition());
         output.push_str(&ResourceUsage::typescript_definition());
         output.push_str(&ErrorStats::typescript_definition());
         output.push_str(&ErrorRecord::typescript_definition());
+        output.push_str(&MetricsPeriod::typescript_definition());
+        output.push_str(&PerformanceSnapshot::typescript_definition());
+        output.push_str(&AgentMetricsReport::typescript_definition());
+        output.push_str(&LeaderboardEntry::typescript_definition());
+        output.push_str(&TeamLeaderboard::typescript_definition());
+        output.push_str(&WorkHistoryEntry::typescript_definition());
         output.push_str(&CreateAgentRequest::typescript_definition());
         output.push_str(&UpdateAgentRequest::typescript_definition());
         output.push_str(&ListAgentsRequest::typescript_definition());
@@ -143,6 +155,12 @@ impl TypeScriptGenerator {
         output.push_str(&ProjectPriority::typescript_definition());
         output.push_str(&TeamMember::typescript_definition());
         output.push_str(&Permission::typescript_definition());
+        output.push_str(&ProjectRole::typescript_definition());
+        output.push_str(&ProjectMemberInfo::typescript_definition());
+        output.push_str(&OrganizationRole::typescript_definition());
+        output.push_str(&OrganizationSettings::typescript_definition());
+        output.push_str(&OrganizationInfo::typescript_definition());
+        output.push_str(&TeamInfo::typescript_definition());
         output.push_str(&ExternalDependency::typescript_definition());
         output.push_str(&DependencyType::typescript_definition());
         output.push_str(&EnvironmentConfig::typescript_definition());
@@ -183,6 +201,8 @@ impl TypeScriptGenerator {
         let mut output = String::new();
         
         output.push_str(&ProjectContext::typescript_definition());
+        output.push_str(&SharedContextCategory::typescript_definition());
+        output.push_str(&SharedContextEntry::typescript_definition());
         output.push_str(&CodebaseInfo::typescript_definition());
         output.push_str(&LanguageStats::typescript_definition());
         output.push_str(&FrameworkInfo::typescript_definition());
@@ -205,11 +225,24 @@ impl TypeScriptGenerator {
         output.push_str(&RiskType::typescript_definition());
         output.push_str(&RiskLevel::typescript_definition());
         output.push_str(&TaskDependency::typescript_definition());
+        output.push_str(&SubtaskProgress::typescript_definition());
+        output.push_str(&TaskProgressRollup::typescript_definition());
         output.push_str(&DependencyStrength::typescript_definition());
         output.push_str(&TaskAssignment::typescript_definition());
         output.push_str(&AssignmentStrategy::typescript_definition());
+        output.push_str(&AssignmentPolicy::typescript_definition());
         output.push_str(&SchedulePlan::typescript_definition());
         output.push_str(&ExecutionPhase::typescript_definition());
+        output.push_str(&SimulationAgent::typescript_definition());
+        output.push_str(&SimulationConflictType::typescript_definition());
+        output.push_str(&SimulationConflict::typescript_definition());
+        output.push_str(&SimulationBottleneck::typescript_definition());
+        output.push_str(&SimulationReport::typescript_definition());
+        output.push_str(&PlanViolationType::typescript_definition());
+        output.push_str(&PlanViolation::typescript_definition());
+        output.push_str(&PlanValidationReport::typescript_definition());
+        output.push_str(&crate::validation::PlanValidationRequest::typescript_definition());
+        output.push_str(&crate::validation::PayloadValidation::typescript_definition());
         
         Ok(output)
     }
@@ -223,6 +256,7 @@ impl TypeScriptGenerator {
         output.push_str(&EventMetadata::typescript_definition());
         output.push_str(&EventSource::typescript_definition());
         output.push_str(&EventPriority::typescript_definition());
+        output.push_str(&EventType::typescript_definition());
         output.push_str(&AgentCreatedEvent::typescript_definition());
         output.push_str(&AgentUpdatedEvent::typescript_definition());
         output.push_str(&AgentDeletedEvent::typescript_definition());
@@ -230,13 +264,25 @@ impl TypeScriptGenerator {
         output.push_str(&AgentListResponseEvent::typescript_definition());
         output.push_str(&ProjectCreatedEvent::typescript_definition());
         output.push_str(&ProjectUpdatedEvent::typescript_definition());
+        output.push_str(&ProjectMemberChangedEvent::typescript_definition());
+        output.push_str(&MemberChangeType::typescript_definition());
         output.push_str(&RequirementsUploadedEvent::typescript_definition());
         output.push_str(&RequirementDecompositionStartedEvent::typescript_definition());
         output.push_str(&RequirementDecompositionCompletedEvent::typescript_definition());
+        output.push_str(&DecompositionProgressEvent::typescript_definition());
         output.push_str(&TaskAllocationCompletedEvent::typescript_definition());
         output.push_str(&TaskExecutionStartedEvent::typescript_definition());
         output.push_str(&TaskProgressUpdatedEvent::typescript_definition());
         output.push_str(&TaskExecutionCompletedEvent::typescript_definition());
+        output.push_str(&TaskPreemptedEvent::typescript_definition());
+        output.push_str(&SlaType::typescript_definition());
+        output.push_str(&SlaSeverity::typescript_definition());
+        output.push_str(&SlaBreachedEvent::typescript_definition());
+        output.push_str(&WebhookSource::typescript_definition());
+        output.push_str(&GitPushReceivedEvent::typescript_definition());
+        output.push_str(&CiStatusReceivedEvent::typescript_definition());
+        output.push_str(&ExternalIssueUpdatedEvent::typescript_definition());
+        output.push_str(&OrchestrationConfigChangedEvent::typescript_definition());
         output.push_str(&ErrorEvent::typescript_definition());
         
         Ok(output)
@@ -285,7 +331,7 @@ export function isTaskPriority(value: any): value is TaskPriority {
 export function isTaskStatus(value: any): value is TaskStatus {
     const validStatuses = [
         'pending', 'in_progress', 'completed', 'failed', 'cancelled', 'on_hold',
-        'waiting_for_dependency', 'waiting_for_review'
+        'waiting_for_dependency', 'waiting_for_review', 'waiting_for_human'
     ];
     return validStatuses.includes(value);
 }
@@ -431,7 +477,8 @@ export const DEFAULT_QUALITY_GATES = {
     min_test_coverage: 0.8,
     max_complexity: 10,
     required_reviewers: 1,
-    require_all_checks_pass: true
+    require_all_checks_pass: true,
+    quarantine_flaky_tests: false
 };
 
 /**
@@ -532,6 +579,7 @@ impl TypeScriptGenerator {
 }
 
 /// CLI工具函数
+#[cfg(not(target_arch = "wasm32"))]
 pub fn save_typescript_definitions(output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
     let definitions = TypeScriptGenerator::generate_all_types()?;
     std::fs::write(output_path, definitions)?;
@@ -0,0 +1,124 @@
+//! 协议载荷校验
+//!
+//! 前端和边缘函数需要在提交前用与服务端相同的规则校验载荷。这里的函数只接收 JSON 文本、
+//! 不依赖文件系统和系统时钟，启用 `wasm` feature 后由 [`crate::wasm`] 导出给 JavaScript。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::agent_management::AgentConfig;
+use crate::events::{EventEnvelope, MultiAgentEvent};
+use crate::llm_orchestration::{
+    AssignmentPolicy, PlanValidationReport, PlanValidator, ProjectContext, SimulationAgent, TaskInfo,
+};
+use crate::types::ProjectId;
+use crate::worker_protocol::{WorkerMessage, WORKER_PROTOCOL_VERSION};
+
+#[cfg(feature = "typescript")]
+use ts_rs::TS;
+
+/// 载荷校验结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct PayloadValidation {
+    /// 载荷是否有效
+    pub valid: bool,
+    /// 解析出的载荷类型，例如事件类型或工作进程消息类型
+    pub kind: Option<String>,
+    /// 校验失败的原因
+    pub errors: Vec<String>,
+}
+
+impl PayloadValidation {
+    fn valid(kind: impl Into<String>) -> Self {
+        Self { valid: true, kind: Some(kind.into()), errors: Vec::new() }
+    }
+
+    fn invalid(kind: Option<String>, error: impl Into<String>) -> Self {
+        Self { valid: false, kind, errors: vec![error.into()] }
+    }
+}
+
+/// 计划校验请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct PlanValidationRequest {
+    /// 项目ID
+    pub project_id: ProjectId,
+    /// 项目上下文
+    pub context: ProjectContext,
+    /// 待校验的任务计划
+    pub tasks: Vec<TaskInfo>,
+    /// 可用Agent
+    pub agents: Vec<SimulationAgent>,
+    /// 计划开始时间
+    pub start: DateTime<Utc>,
+    /// 单个任务的工时上限，未指定时使用默认值
+    #[serde(default)]
+    pub max_task_hours: Option<u32>,
+    /// 分配策略和成本上限，未指定时使用混合策略
+    #[serde(default)]
+    pub assignment_policy: Option<AssignmentPolicy>,
+}
+
+/// 校验事件信封（`{"event_type": ..., "event": ...}`）
+pub fn validate_event_json(json: &str) -> PayloadValidation {
+    let envelope: EventEnvelope = match serde_json::from_str(json) {
+        Ok(envelope) => envelope,
+        Err(e) => return PayloadValidation::invalid(None, e.to_string()),
+    };
+    let kind = envelope.event_type().to_string();
+    if envelope.metadata().event_id.trim().is_empty() {
+        return PayloadValidation::invalid(Some(kind), "metadata.event_id cannot be empty");
+    }
+    PayloadValidation::valid(kind)
+}
+
+/// 校验工作进程发送给协调器的消息，注册消息还会检查协议版本
+pub fn validate_worker_message_json(json: &str) -> PayloadValidation {
+    let message: WorkerMessage = match serde_json::from_str(json) {
+        Ok(message) => message,
+        Err(e) => return PayloadValidation::invalid(None, e.to_string()),
+    };
+    let kind = message.message_type().to_string();
+    match &message {
+        WorkerMessage::Register { protocol_version, .. } if *protocol_version != WORKER_PROTOCOL_VERSION => {
+            PayloadValidation::invalid(
+                Some(kind),
+                format!(
+                    "unsupported protocol_version {}, expected {}",
+                    protocol_version, WORKER_PROTOCOL_VERSION
+                ),
+            )
+        }
+        WorkerMessage::Register { name, .. } if name.trim().is_empty() => {
+            PayloadValidation::invalid(Some(kind), "worker name cannot be empty")
+        }
+        _ => PayloadValidation::valid(kind),
+    }
+}
+
+/// 校验Agent配置
+pub fn validate_agent_config_json(json: &str) -> PayloadValidation {
+    let config: AgentConfig = match serde_json::from_str(json) {
+        Ok(config) => config,
+        Err(e) => return PayloadValidation::invalid(None, e.to_string()),
+    };
+    match config.validate() {
+        Ok(()) => PayloadValidation::valid("agent_config"),
+        Err(e) => PayloadValidation::invalid(Some("agent_config".to_string()), e),
+    }
+}
+
+/// 按 [`PlanValidator`] 的规则校验任务计划，请求无法解析时返回错误
+pub fn validate_plan_json(json: &str) -> Result<PlanValidationReport, String> {
+    let request: PlanValidationRequest = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let mut validator = PlanValidator::new(request.agents);
+    if let Some(max_task_hours) = request.max_task_hours {
+        validator = validator.with_max_task_hours(max_task_hours);
+    }
+    if let Some(policy) = request.assignment_policy {
+        validator = validator.with_assignment_policy(policy);
+    }
+    Ok(validator.validate(request.project_id, &request.context, &request.tasks, request.start))
+}
@@ -0,0 +1,52 @@
+//! WebAssembly 导出
+//!
+//! 以 `wasm` feature 编译到 `wasm32-unknown-unknown` 后，通过 wasm-bindgen 向 JavaScript 导出
+//! [`crate::validation`] 中的校验函数。参数和返回值都是 JSON 文本，结构与对应的 Rust 类型一致。
+//! 本crate只构建 rlib，wasm 模块由 `crates/sker-wasm` 打包为 cdylib。
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::validation;
+use crate::worker_protocol::WORKER_PROTOCOL_VERSION;
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, JsError> {
+    serde_json::to_string(value).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// 协议crate版本
+#[wasm_bindgen(js_name = protocolVersion)]
+pub fn protocol_version() -> String {
+    crate::VERSION.to_string()
+}
+
+/// 远程工作进程协议版本
+#[wasm_bindgen(js_name = workerProtocolVersion)]
+pub fn worker_protocol_version() -> u32 {
+    WORKER_PROTOCOL_VERSION
+}
+
+/// 校验事件信封，返回 `PayloadValidation` JSON
+#[wasm_bindgen(js_name = validateEvent)]
+pub fn validate_event(json: &str) -> Result<String, JsError> {
+    to_json(&validation::validate_event_json(json))
+}
+
+/// 校验工作进程消息，返回 `PayloadValidation` JSON
+#[wasm_bindgen(js_name = validateWorkerMessage)]
+pub fn validate_worker_message(json: &str) -> Result<String, JsError> {
+    to_json(&validation::validate_worker_message_json(json))
+}
+
+/// 校验Agent配置，返回 `PayloadValidation` JSON
+#[wasm_bindgen(js_name = validateAgentConfig)]
+pub fn validate_agent_config(json: &str) -> Result<String, JsError> {
+    to_json(&validation::validate_agent_config_json(json))
+}
+
+/// 校验任务计划，参数为 `PlanValidationRequest` JSON，返回 `PlanValidationReport` JSON
+#[wasm_bindgen(js_name = validatePlan)]
+pub fn validate_plan(json: &str) -> Result<String, JsError> {
+    let report = validation::validate_plan_json(json).map_err(|e| JsError::new(&e))?;
+    to_json(&report)
+}
@@ -0,0 +1,224 @@
+//! # 远程工作进程协议
+//!
+//! 本模块定义了协调器与远程工作进程之间交换的消息，使任务执行可以分布到
+//! 其他机器上的工作进程，而任务、会话等状态仍然保存在中心数据库中。
+//!
+//! 一个工作进程的典型交互流程：
+//!
+//! 1. 连接后发送 [`WorkerMessage::Register`]，声明托管的Agent与能力，
+//!    协调器回复 [`CoordinatorMessage::Registered`] 并分配 [`WorkerId`]
+//! 2. 按 `heartbeat_interval_secs` 周期发送 [`WorkerMessage::Heartbeat`]
+//! 3. 空闲时发送 [`WorkerMessage::LeaseRequest`]，协调器回复
+//!    [`CoordinatorMessage::TaskLease`] 或 [`CoordinatorMessage::NoWork`]
+//! 4. 收到租约后发送 [`WorkerMessage::Ack`] 确认开始执行
+//! 5. 执行结束后发送 [`WorkerMessage::Complete`] 或 [`WorkerMessage::Fail`]
+//!
+//! 每条消息都序列化为带 `type` 标签的JSON对象，传输层可以是按行分隔的TCP流，
+//! 也可以是WebSocket文本帧，消息格式保持一致。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+#[cfg(feature = "typescript")]
+use ts_rs::TS;
+
+use crate::types::{AgentCapability, AgentId, ExecutionSessionId, ProjectId, TaskId, WorkerId};
+
+/// 当前协议版本，注册时版本不一致的工作进程会被拒绝
+pub const WORKER_PROTOCOL_VERSION: u32 = 1;
+
+/// 工作进程发送给协调器的消息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorkerMessage {
+    /// 注册工作进程并声明能力
+    Register {
+        /// 工作进程使用的协议版本
+        protocol_version: u32,
+        /// 工作进程名称，通常为主机名
+        name: String,
+        /// 工作进程托管的Agent
+        agent_ids: Vec<AgentId>,
+        /// 工作进程可以提供的能力
+        capabilities: Vec<AgentCapability>,
+        /// 同时执行的最大任务数
+        max_concurrent_tasks: u32,
+    },
+
+    /// 心跳，同时上报仍在执行的租约
+    Heartbeat {
+        /// 工作进程ID
+        worker_id: WorkerId,
+        /// 仍在执行的租约
+        active_leases: Vec<ExecutionSessionId>,
+    },
+
+    /// 请求一个可执行的任务
+    LeaseRequest {
+        /// 工作进程ID
+        worker_id: WorkerId,
+    },
+
+    /// 确认租约并开始执行
+    Ack {
+        /// 工作进程ID
+        worker_id: WorkerId,
+        /// 租约ID
+        lease_id: ExecutionSessionId,
+    },
+
+    /// 任务执行完成
+    Complete {
+        /// 工作进程ID
+        worker_id: WorkerId,
+        /// 租约ID
+        lease_id: ExecutionSessionId,
+        /// 最终提交
+        final_commit: Option<String>,
+        /// 执行结果
+        result: Option<JsonValue>,
+    },
+
+    /// 任务执行失败
+    Fail {
+        /// 工作进程ID
+        worker_id: WorkerId,
+        /// 租约ID
+        lease_id: ExecutionSessionId,
+        /// 错误信息
+        error: String,
+        /// 是否可以交给其他工作进程重试
+        retryable: bool,
+    },
+}
+
+impl WorkerMessage {
+    /// 获取消息类型名称
+    pub fn message_type(&self) -> &'static str {
+        match self {
+            WorkerMessage::Register { .. } => "register",
+            WorkerMessage::Heartbeat { .. } => "heartbeat",
+            WorkerMessage::LeaseRequest { .. } => "lease_request",
+            WorkerMessage::Ack { .. } => "ack",
+            WorkerMessage::Complete { .. } => "complete",
+            WorkerMessage::Fail { .. } => "fail",
+        }
+    }
+
+    /// 获取发送消息的工作进程ID，注册消息尚无ID
+    pub fn worker_id(&self) -> Option<&WorkerId> {
+        match self {
+            WorkerMessage::Register { .. } => None,
+            WorkerMessage::Heartbeat { worker_id, .. }
+            | WorkerMessage::LeaseRequest { worker_id }
+            | WorkerMessage::Ack { worker_id, .. }
+            | WorkerMessage::Complete { worker_id, .. }
+            | WorkerMessage::Fail { worker_id, .. } => Some(worker_id),
+        }
+    }
+}
+
+/// 协调器发送给工作进程的消息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CoordinatorMessage {
+    /// 注册成功
+    Registered {
+        /// 分配的工作进程ID
+        worker_id: WorkerId,
+        /// 心跳间隔（秒）
+        heartbeat_interval_secs: u64,
+    },
+
+    /// 心跳已记录
+    HeartbeatAck {
+        /// 协调器认为已失效、工作进程应停止执行的租约
+        revoked_leases: Vec<ExecutionSessionId>,
+    },
+
+    /// 分配的任务租约
+    TaskLease(TaskLease),
+
+    /// 当前没有可执行的任务
+    NoWork,
+
+    /// 租约已确认、完成或失败的处理结果
+    Accepted {
+        /// 租约ID
+        lease_id: ExecutionSessionId,
+    },
+
+    /// 处理消息失败
+    Error {
+        /// 错误信息
+        message: String,
+    },
+}
+
+/// 任务租约
+///
+/// 租约ID即为协调器为本次执行创建的执行会话ID
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "typescript", derive(TS))]
+pub struct TaskLease {
+    /// 租约ID
+    pub lease_id: ExecutionSessionId,
+    /// 任务ID
+    pub task_id: TaskId,
+    /// 所属项目ID
+    pub project_id: ProjectId,
+    /// 执行任务的Agent
+    pub agent_id: AgentId,
+    /// 任务标题
+    pub title: String,
+    /// 任务描述
+    pub description: String,
+    /// 任务类型
+    pub task_type: String,
+    /// 所需Agent能力
+    pub required_capabilities: Vec<AgentCapability>,
+    /// 分配提示词
+    pub assignment_prompt: Option<String>,
+    /// 工作分支
+    pub git_branch: String,
+    /// 执行超时（分钟）
+    pub timeout_minutes: u32,
+    /// 租约发放时间
+    pub leased_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_messages_use_tagged_wire_format() {
+        let worker_id = WorkerId::new();
+        let register = WorkerMessage::Register {
+            protocol_version: WORKER_PROTOCOL_VERSION,
+            name: "build-01".to_string(),
+            agent_ids: vec![],
+            capabilities: vec![AgentCapability::BackendDevelopment],
+            max_concurrent_tasks: 2,
+        };
+        let value = serde_json::to_value(&register).unwrap();
+        assert_eq!(value["type"], "register");
+        assert_eq!(value["capabilities"], json!(["backend_development"]));
+        assert_eq!(register.worker_id(), None);
+
+        let request: WorkerMessage =
+            serde_json::from_value(json!({ "type": "lease_request", "worker_id": worker_id })).unwrap();
+        assert_eq!(request.message_type(), "lease_request");
+        assert_eq!(request.worker_id(), Some(&worker_id));
+
+        let registered = CoordinatorMessage::Registered { worker_id, heartbeat_interval_secs: 15 };
+        let text = serde_json::to_string(&registered).unwrap();
+        assert!(text.starts_with(r#"{"type":"registered""#));
+        assert_eq!(serde_json::from_str::<CoordinatorMessage>(&text).unwrap(), registered);
+        assert_eq!(serde_json::to_value(CoordinatorMessage::NoWork).unwrap(), json!({ "type": "no_work" }));
+    }
+}
@@ -42,6 +42,7 @@ mod integration_tests {
                 max_network_bandwidth_kbps: Some(1000),
                 max_execution_time_seconds: Some(3600),
             }),
+            approval_policy: Default::default(),
         };
         
         // 验证配置有效性
@@ -139,6 +140,7 @@ mod integration_tests {
             ],
             subtasks: vec![],
             related_issues: vec!["#123".to_string()],
+            ci_status: None,
         };
         
         // 验证Agent能力匹配
@@ -192,6 +194,7 @@ mod integration_tests {
             priority_weight: 0.5,
             verbose_logging: false,
             resource_limits: None,
+            approval_policy: Default::default(),
         };
         
         let agent_event = EventFactory::agent_created(
@@ -290,6 +293,13 @@ mod integration_tests {
             assert!(uuid_regex.is_match(&agent_id.to_string()));
             assert!(uuid_regex.is_match(&project_id.to_string()));
             assert!(uuid_regex.is_match(&task_id.to_string()));
+
+            // 与原始UUID和字符串之间的转换，序列化形式与UUID相同
+            let uuid: uuid::Uuid = (&task_id).into();
+            assert_eq!(TaskId::from(uuid), task_id);
+            assert_eq!(TaskId::try_from(task_id.to_string()).unwrap(), task_id);
+            assert!(AgentId::try_from("not-a-uuid").is_err());
+            assert_eq!(serde_json::to_string(&task_id).unwrap(), serde_json::to_string(&uuid).unwrap());
         }
         
         // 3. 测试能力枚举的一致性
@@ -529,6 +539,7 @@ mod integration_tests {
                     impact_if_unavailable: "无法处理支付".to_string(),
                 },
             ],
+            shared_context: vec![],
         };
         
         // 验证数据一致性
@@ -619,6 +630,7 @@ mod integration_tests {
             priority_weight: 0.8,
             verbose_logging: true,
             resource_limits: None,
+            approval_policy: Default::default(),
         };
         
         let start = Instant::now();
@@ -639,6 +651,113 @@ mod integration_tests {
         
         println!("✅ 性能基准测试通过");
     }
+
+    /// 测试事件声明宏生成的事件特征实现和事件信封
+    #[test]
+    fn test_multi_agent_event_macro_and_envelope() {
+        let preempted_task_id = TaskId::new();
+        let preempting_task_id = TaskId::new();
+        let session_id = ExecutionSessionId::new();
+        let agent_id = AgentId::new();
+        let event = EventFactory::task_preempted(
+            preempted_task_id.clone(),
+            session_id.clone(),
+            TaskPriority::Low,
+            0.4,
+            25,
+            preempting_task_id.clone(),
+            agent_id.clone(),
+            "紧急任务到达".to_string(),
+        );
+
+        assert_eq!(event.event_type(), "task_preempted");
+        assert_eq!(event.timestamp(), event.metadata.timestamp);
+        assert!(!event.is_critical());
+        assert_eq!(
+            event.related_entity_ids(),
+            vec![
+                preempted_task_id.to_string(),
+                session_id.to_string(),
+                preempting_task_id.to_string(),
+                agent_id.to_string(),
+            ]
+        );
+
+        // 信封以事件类型名作为标签，反序列化后委托给具体事件
+        let envelope = EventEnvelope::from(event);
+        let json = serde_json::to_value(&envelope).expect("事件信封序列化失败");
+        assert_eq!(json["event_type"], "task_preempted");
+        assert_eq!(json["event"]["reason"], "紧急任务到达");
+        let decoded: EventEnvelope = serde_json::from_value(json).expect("事件信封反序列化失败");
+        assert!(matches!(decoded, EventEnvelope::TaskPreempted(_)));
+        assert_eq!(decoded.event_type(), "task_preempted");
+        assert_eq!(decoded.metadata().event_id, envelope.metadata().event_id);
+
+        // 关键事件和可选ID字段
+        let error = EventFactory::error("Timeout".to_string(), "执行超时".to_string(), None);
+        assert!(error.is_critical());
+        assert!(error.related_entity_ids().is_empty());
+        let envelope = EventEnvelope::from(error);
+        assert_eq!(envelope.event_type(), "error");
+        assert!(envelope.is_critical());
+    }
+
+    /// 测试前端和边缘函数使用的载荷校验函数
+    #[test]
+    fn test_payload_validation_helpers() {
+        use codex_multi_agent::validation::*;
+
+        let event = EventEnvelope::from(EventFactory::error("Timeout".to_string(), "执行超时".to_string(), None));
+        let mut json = serde_json::to_value(&event).expect("事件信封序列化失败");
+        let result = validate_event_json(&json.to_string());
+        assert!(result.valid, "{:?}", result.errors);
+        assert_eq!(result.kind.as_deref(), Some("error"));
+
+        json["event"]["metadata"]["event_id"] = serde_json::json!(" ");
+        let result = validate_event_json(&json.to_string());
+        assert!(!result.valid);
+        assert_eq!(result.kind.as_deref(), Some("error"));
+        let result = validate_event_json(r#"{"event_type": "unknown_event", "event": {}}"#);
+        assert!(!result.valid && result.kind.is_none());
+
+        // 注册消息必须使用当前协议版本
+        let register = |protocol_version: u32| {
+            serde_json::to_string(&WorkerMessage::Register {
+                protocol_version,
+                name: "worker-1".to_string(),
+                agent_ids: vec![AgentId::new()],
+                capabilities: vec![AgentCapability::Testing],
+                max_concurrent_tasks: 1,
+            })
+            .expect("消息序列化失败")
+        };
+        assert!(validate_worker_message_json(&register(WORKER_PROTOCOL_VERSION)).valid);
+        let result = validate_worker_message_json(&register(WORKER_PROTOCOL_VERSION + 1));
+        assert!(!result.valid);
+        assert!(result.errors[0].contains("protocol_version"));
+
+        let mut config = serde_json::to_value(agent_management::AgentConfig {
+            name: "校验Agent".to_string(),
+            description: String::new(),
+            prompt_template: String::new(),
+            capabilities: vec![AgentCapability::Testing],
+            max_concurrent_tasks: 1,
+            timeout_minutes: 30,
+            git_config: None,
+            custom_settings: HashMap::new(),
+            priority_weight: 0.5,
+            verbose_logging: false,
+            resource_limits: None,
+            approval_policy: Default::default(),
+        })
+        .expect("配置序列化失败");
+        assert!(validate_agent_config_json(&config.to_string()).valid);
+        config["max_concurrent_tasks"] = serde_json::json!(0);
+        let result = validate_agent_config_json(&config.to_string());
+        assert_eq!(result.errors, vec!["max_concurrent_tasks must be greater than 0".to_string()]);
+
+        assert!(validate_plan_json("{}").is_err());
+    }
 }
 
 // 添加辅助测试工具
@@ -661,6 +780,7 @@ mod test_helpers {
             priority_weight: 0.5,
             verbose_logging: false,
             resource_limits: None,
+            approval_policy: Default::default(),
         }
     }
     
@@ -719,6 +839,7 @@ mod test_helpers {
             risk_factors: vec![],
             subtasks: vec![],
             related_issues: vec![],
+            ci_status: None,
         }
     }
 }
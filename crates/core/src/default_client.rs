@@ -104,8 +104,61 @@ fn sanitize_user_agent(candidate: String, fallback: &str) -> String {
     }
 }
 
+/// Path to a PEM bundle of extra root certificates to trust in addition to the
+/// system store, e.g. the CA of a TLS-inspecting corporate proxy.
+pub const CODEX_CA_CERTIFICATE_ENV_VAR: &str = "CODEX_CA_CERTIFICATE";
+
+/// The extra root certificates named by [`CODEX_CA_CERTIFICATE_ENV_VAR`] could
+/// not be loaded.
+#[derive(Debug, thiserror::Error)]
+pub enum CaCertificateError {
+    #[error("failed to read CODEX_CA_CERTIFICATE={path}: {source}")]
+    Read {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("failed to parse CODEX_CA_CERTIFICATE={path}: {source}")]
+    Parse {
+        path: String,
+        source: reqwest::Error,
+    },
+    #[error("CODEX_CA_CERTIFICATE={path} does not contain any PEM certificates")]
+    Empty { path: String },
+}
+
 /// Create a reqwest client with default `originator` and `User-Agent` headers set.
+///
+/// Proxies are picked up from the standard `HTTPS_PROXY`/`ALL_PROXY`/`NO_PROXY`
+/// environment variables by reqwest itself.
+///
+/// If [`CODEX_CA_CERTIFICATE_ENV_VAR`] is set but cannot be loaded, the error is
+/// logged and the returned client trusts no roots at all, so requests fail
+/// instead of silently falling back to the system store. Use
+/// [`try_create_client`] to handle the error instead.
 pub fn create_client() -> reqwest::Client {
+    match try_create_client() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!("{err}; HTTPS requests will fail until it is fixed");
+            client_builder()
+                .tls_built_in_root_certs(false)
+                .build()
+                .unwrap_or_else(|_| reqwest::Client::new())
+        }
+    }
+}
+
+/// Like [`create_client`], but returns an error if the extra root certificates
+/// named by [`CODEX_CA_CERTIFICATE_ENV_VAR`] cannot be loaded.
+pub fn try_create_client() -> Result<reqwest::Client, CaCertificateError> {
+    let mut builder = client_builder();
+    for certificate in extra_root_certificates()? {
+        builder = builder.add_root_certificate(certificate);
+    }
+    Ok(builder.build().unwrap_or_else(|_| reqwest::Client::new()))
+}
+
+fn client_builder() -> reqwest::ClientBuilder {
     use reqwest::header::HeaderMap;
 
     let mut headers = HeaderMap::new();
@@ -116,8 +169,31 @@ pub fn create_client() -> reqwest::Client {
         // Set UA via dedicated helper to avoid header validation pitfalls
         .user_agent(ua)
         .default_headers(headers)
-        .build()
-        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+fn extra_root_certificates() -> Result<Vec<reqwest::Certificate>, CaCertificateError> {
+    match std::env::var(CODEX_CA_CERTIFICATE_ENV_VAR) {
+        Ok(path) if !path.trim().is_empty() => load_root_certificates(&path),
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn load_root_certificates(path: &str) -> Result<Vec<reqwest::Certificate>, CaCertificateError> {
+    let pem = std::fs::read(path).map_err(|source| CaCertificateError::Read {
+        path: path.to_string(),
+        source,
+    })?;
+    let certificates =
+        reqwest::Certificate::from_pem_bundle(&pem).map_err(|source| CaCertificateError::Parse {
+            path: path.to_string(),
+            source,
+        })?;
+    if certificates.is_empty() {
+        return Err(CaCertificateError::Empty {
+            path: path.to_string(),
+        });
+    }
+    Ok(certificates)
 }
 
 #[cfg(test)]
@@ -176,6 +252,48 @@ mod tests {
         assert_eq!(ua_header.to_str().unwrap(), expected_ua);
     }
 
+    fn write_pem(contents: &str) -> tempfile::NamedTempFile {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().expect("create temp file");
+        file.write_all(contents.as_bytes()).expect("write temp file");
+        file
+    }
+
+    fn path_of(file: &tempfile::NamedTempFile) -> &str {
+        file.path().to_str().expect("temp path is utf-8")
+    }
+
+    #[test]
+    fn test_load_root_certificates_reads_every_certificate_in_bundle() {
+        let bundle = write_pem(include_str!("../tests/fixtures/test_root_cas.pem"));
+
+        let certificates = load_root_certificates(path_of(&bundle)).expect("bundle should load");
+
+        assert_eq!(certificates.len(), 2);
+    }
+
+    #[test]
+    fn test_load_root_certificates_rejects_malformed_pem() {
+        let malformed = write_pem(
+            "-----BEGIN CERTIFICATE-----\nnot base64!\n-----END CERTIFICATE-----\n",
+        );
+        let err = load_root_certificates(path_of(&malformed)).unwrap_err();
+        assert!(matches!(err, CaCertificateError::Parse { .. }), "{err}");
+
+        let empty = write_pem("no certificates here\n");
+        let err = load_root_certificates(path_of(&empty)).unwrap_err();
+        assert!(matches!(err, CaCertificateError::Empty { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_load_root_certificates_reports_missing_file() {
+        let err = load_root_certificates("/nonexistent/codex-ca.pem").unwrap_err();
+
+        assert!(matches!(err, CaCertificateError::Read { .. }));
+        assert!(err.to_string().contains("/nonexistent/codex-ca.pem"));
+    }
+
     #[test]
     fn test_invalid_suffix_is_sanitized() {
         let prefix = "codex_cli_rs/0.0.0";
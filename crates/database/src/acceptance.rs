@@ -0,0 +1,383 @@
+//! 验收标准核对清单
+//!
+//! 任务 `acceptance_criteria` 中的每一项对应一条验证记录：Agent 提交验证结果并附上执行日志、
+//! 工件等证据，人工可以签核确认或驳回。Agent 重新提交结果会清除之前的签核。
+//!
+//! 任务完成时要求全部验收标准已验证，否则 [`TaskRepository::update_status`] 返回
+//! [`DatabaseError::UnverifiedAcceptanceCriteria`]；确需跳过时通过 [`complete_with_override`]
+//! 强制完成，并记录 `AcceptanceCriteriaOverridden` 事件。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        acceptance_verification,
+        domain_event::{AggregateType, DomainEventType},
+        task,
+    },
+    repository::{
+        acceptance_verification_repository::UpsertVerificationData,
+        domain_event_repository::CreateDomainEventData,
+        AcceptanceVerificationRepository, DomainEventRepository, ExecutionArtifactRepository,
+        ExecutionLogRepository, ExecutionSessionRepository, TaskRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 验收标准的验证状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CriterionStatus {
+    /// 尚未验证
+    Pending,
+    /// 已验证通过
+    Verified,
+    /// 验证未通过
+    Failed,
+}
+
+impl CriterionStatus {
+    /// 数据库中存储的字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CriterionStatus::Pending => "pending",
+            CriterionStatus::Verified => "verified",
+            CriterionStatus::Failed => "failed",
+        }
+    }
+}
+
+impl std::fmt::Display for CriterionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for CriterionStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(CriterionStatus::Pending),
+            "verified" => Ok(CriterionStatus::Verified),
+            "failed" => Ok(CriterionStatus::Failed),
+            _ => Err(format!("未知的验收状态: {}", s)),
+        }
+    }
+}
+
+/// 证据类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EvidenceKind {
+    /// 执行日志，引用为日志ID
+    Log,
+    /// 执行工件，引用为工件ID
+    Artifact,
+    /// 文字说明或外部链接
+    Note,
+}
+
+/// 验证证据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CriterionEvidence {
+    pub kind: EvidenceKind,
+    /// 日志ID、工件ID或说明内容
+    pub reference: String,
+    pub description: Option<String>,
+}
+
+/// Agent 提交的验证结果
+#[derive(Debug, Clone)]
+pub struct MarkCriterionData {
+    pub task_id: Uuid,
+    /// 验收标准内容，需与任务中的某一项一致
+    pub criterion: String,
+    pub status: CriterionStatus,
+    pub evidence: Vec<CriterionEvidence>,
+    pub agent_id: Option<Uuid>,
+    pub notes: Option<String>,
+}
+
+/// 核对清单中的一项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub criterion: String,
+    pub status: CriterionStatus,
+    pub evidence: Vec<CriterionEvidence>,
+    pub verified_by_agent_id: Option<Uuid>,
+    pub notes: Option<String>,
+    pub signed_off_by: Option<Uuid>,
+    pub signed_off_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// 任务的验收标准核对清单
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AcceptanceChecklist {
+    pub task_id: Uuid,
+    /// 按任务中验收标准的顺序排列
+    pub items: Vec<ChecklistItem>,
+    pub verified_count: usize,
+    /// 尚未验证通过的验收标准
+    pub unverified: Vec<String>,
+}
+
+impl AcceptanceChecklist {
+    /// 全部验收标准是否已验证通过
+    pub fn is_complete(&self) -> bool {
+        self.unverified.is_empty()
+    }
+
+    /// 转换为 `TaskResult::acceptance_criteria_status` 使用的格式
+    pub fn status_map(&self) -> HashMap<String, bool> {
+        self.items
+            .iter()
+            .map(|item| (item.criterion.clone(), item.status == CriterionStatus::Verified))
+            .collect()
+    }
+}
+
+/// 读取任务的验收标准
+///
+/// 验收标准可以是字符串，也可以是带 `description` 或 `type` 字段的对象。
+pub fn task_criteria(task: &task::Model) -> Vec<String> {
+    let Some(JsonValue::Array(criteria)) = &task.acceptance_criteria else {
+        return Vec::new();
+    };
+
+    let mut labels: Vec<String> = Vec::new();
+    for criterion in criteria {
+        let label = match criterion {
+            JsonValue::String(text) => text.trim().to_string(),
+            JsonValue::Object(fields) => fields
+                .get("description")
+                .or_else(|| fields.get("type"))
+                .and_then(JsonValue::as_str)
+                .map(|text| text.trim().to_string())
+                .unwrap_or_else(|| criterion.to_string()),
+            other => other.to_string(),
+        };
+        if !label.is_empty() && !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    labels
+}
+
+/// 生成任务的验收标准核对清单
+pub async fn checklist(db: &DatabaseConnection, task_id: Uuid) -> Result<AcceptanceChecklist> {
+    let task = find_task(db, task_id).await?;
+    build_checklist(db, &task).await
+}
+
+/// Agent 提交某项验收标准的验证结果
+///
+/// 日志和工件证据必须来自该任务的执行会话。
+pub async fn mark_criterion(db: &DatabaseConnection, data: MarkCriterionData) -> Result<ChecklistItem> {
+    let task = find_task(db, data.task_id).await?;
+    ensure_criterion(&task, &data.criterion)?;
+    if data.status == CriterionStatus::Verified && data.evidence.is_empty() {
+        return Err(DatabaseError::validation("标记验收标准为已验证时必须提供证据"));
+    }
+    for evidence in &data.evidence {
+        validate_evidence(db, data.task_id, evidence).await?;
+    }
+
+    let verification = AcceptanceVerificationRepository::new(db.clone())
+        .upsert(UpsertVerificationData {
+            task_id: data.task_id,
+            criterion: data.criterion,
+            status: data.status.to_string(),
+            evidence: serde_json::to_value(&data.evidence)?,
+            verified_by_agent_id: data.agent_id,
+            notes: data.notes,
+        })
+        .await?;
+    Ok(checklist_item(verification.criterion.clone(), Some(&verification)))
+}
+
+/// 人工签核某项验收标准，`approved` 为 false 时驳回
+pub async fn sign_off(
+    db: &DatabaseConnection,
+    task_id: Uuid,
+    criterion: &str,
+    user_id: Uuid,
+    approved: bool,
+    comment: Option<String>,
+) -> Result<ChecklistItem> {
+    let task = find_task(db, task_id).await?;
+    ensure_criterion(&task, criterion)?;
+
+    let status = if approved { CriterionStatus::Verified } else { CriterionStatus::Failed };
+    let verification = AcceptanceVerificationRepository::new(db.clone())
+        .sign_off(task_id, criterion, status.as_str(), user_id, comment)
+        .await?;
+    Ok(checklist_item(verification.criterion.clone(), Some(&verification)))
+}
+
+/// 跳过未验证的验收标准强制完成任务
+pub async fn complete_with_override(
+    db: &DatabaseConnection,
+    task_id: Uuid,
+    user_id: Uuid,
+    reason: &str,
+) -> Result<task::Model> {
+    if reason.trim().is_empty() {
+        return Err(DatabaseError::validation("强制完成任务必须填写原因"));
+    }
+    let task = find_task(db, task_id).await?;
+    let checklist = build_checklist(db, &task).await?;
+
+    if !checklist.is_complete() {
+        let repo = DomainEventRepository::new(db.clone());
+        let version = repo.get_latest_version(task_id).await? + 1;
+        repo.create(CreateDomainEventData {
+            aggregate_type: AggregateType::Task.to_string(),
+            aggregate_id: task_id,
+            event_type: DomainEventType::AcceptanceCriteriaOverridden.to_string(),
+            event_data: json!({
+                "task_id": task_id,
+                "project_id": task.project_id,
+                "overridden_by": user_id,
+                "reason": reason.trim(),
+                "unverified_criteria": checklist.unverified,
+            }),
+            event_version: version,
+        })
+        .await?;
+    }
+
+    TaskRepository::new(db.clone())
+        .set_status(task_id, "completed", false)
+        .await
+}
+
+/// 任务完成前检查验收标准是否全部验证通过
+pub(crate) async fn ensure_criteria_verified(db: &DatabaseConnection, task: &task::Model) -> Result<()> {
+    if task_criteria(task).is_empty() {
+        return Ok(());
+    }
+    let checklist = build_checklist(db, task).await?;
+    if checklist.is_complete() {
+        return Ok(());
+    }
+    Err(DatabaseError::UnverifiedAcceptanceCriteria {
+        task_id: task.task_id.to_string(),
+        criteria: checklist.unverified,
+    })
+}
+
+async fn build_checklist(db: &DatabaseConnection, task: &task::Model) -> Result<AcceptanceChecklist> {
+    let verifications = AcceptanceVerificationRepository::new(db.clone())
+        .find_by_task(task.task_id)
+        .await?;
+
+    // 验收标准修改后，旧标准的验证记录不再出现在清单中
+    let items: Vec<ChecklistItem> = task_criteria(task)
+        .into_iter()
+        .map(|criterion| {
+            let verification = verifications.iter().find(|v| v.criterion == criterion);
+            checklist_item(criterion, verification)
+        })
+        .collect();
+    let unverified: Vec<String> = items
+        .iter()
+        .filter(|item| item.status != CriterionStatus::Verified)
+        .map(|item| item.criterion.clone())
+        .collect();
+
+    Ok(AcceptanceChecklist {
+        task_id: task.task_id,
+        verified_count: items.len() - unverified.len(),
+        items,
+        unverified,
+    })
+}
+
+fn checklist_item(
+    criterion: String,
+    verification: Option<&acceptance_verification::Model>,
+) -> ChecklistItem {
+    let Some(verification) = verification else {
+        return ChecklistItem {
+            criterion,
+            status: CriterionStatus::Pending,
+            evidence: Vec::new(),
+            verified_by_agent_id: None,
+            notes: None,
+            signed_off_by: None,
+            signed_off_at: None,
+            updated_at: None,
+        };
+    };
+
+    ChecklistItem {
+        criterion,
+        status: verification.status.parse().unwrap_or(CriterionStatus::Pending),
+        evidence: serde_json::from_value(verification.evidence.clone()).unwrap_or_default(),
+        verified_by_agent_id: verification.verified_by_agent_id,
+        notes: verification.notes.clone(),
+        signed_off_by: verification.signed_off_by,
+        signed_off_at: verification.signed_off_at.map(|at| at.with_timezone(&Utc)),
+        updated_at: Some(verification.updated_at.with_timezone(&Utc)),
+    }
+}
+
+async fn find_task(db: &DatabaseConnection, task_id: Uuid) -> Result<task::Model> {
+    TaskRepository::new(db.clone())
+        .find_by_id(task_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Task", task_id))
+}
+
+fn ensure_criterion(task: &task::Model, criterion: &str) -> Result<()> {
+    if task_criteria(task).iter().any(|label| label == criterion) {
+        Ok(())
+    } else {
+        Err(DatabaseError::validation(format!("任务中不存在验收标准: {}", criterion)))
+    }
+}
+
+/// 检查日志、工件证据存在且来自该任务的执行会话
+async fn validate_evidence(db: &DatabaseConnection, task_id: Uuid, evidence: &CriterionEvidence) -> Result<()> {
+    if evidence.reference.trim().is_empty() {
+        return Err(DatabaseError::validation("证据引用不能为空"));
+    }
+
+    let session_id = match evidence.kind {
+        EvidenceKind::Note => return Ok(()),
+        EvidenceKind::Log => {
+            let log_id = Uuid::parse_str(&evidence.reference)?;
+            ExecutionLogRepository::new(db.clone())
+                .find_by_id(log_id)
+                .await?
+                .ok_or_else(|| DatabaseError::entity_not_found("ExecutionLog", log_id))?
+                .session_id
+        }
+        EvidenceKind::Artifact => {
+            let artifact_id = Uuid::parse_str(&evidence.reference)?;
+            ExecutionArtifactRepository::new(db.clone())
+                .find_by_id(artifact_id)
+                .await?
+                .ok_or_else(|| DatabaseError::entity_not_found("ExecutionArtifact", artifact_id))?
+                .session_id
+        }
+    };
+
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    if session.task_id != task_id {
+        return Err(DatabaseError::validation(format!(
+            "证据 {} 不属于该任务的执行会话",
+            evidence.reference
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,355 @@
+//! Agent包格式
+//!
+//! 用于导出和分享Agent定义：包含配置、提示词模板和能力元数据，
+//! 带格式版本号和内容签名，导入前进行结构校验和签名校验。
+
+use std::collections::BTreeMap;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+
+use crate::{entities::agent, DatabaseError, Result};
+
+/// 当前支持的包格式版本
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// 仅校验内容摘要的签名算法
+pub const SIGNATURE_SHA256: &str = "sha256";
+
+/// 使用共享密钥的签名算法
+pub const SIGNATURE_HMAC_SHA256: &str = "hmac-sha256";
+
+/// 额外提示词模板在Agent配置中的键名
+pub const PROMPT_TEMPLATES_CONFIG_KEY: &str = "prompt_templates";
+
+/// Agent名称最大字符数
+const MAX_NAME_CHARS: usize = 100;
+
+/// 可导出的Agent包
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentBundle {
+    /// 包格式版本
+    pub format_version: u32,
+    /// Agent定义版本（语义化版本号）
+    pub version: String,
+    /// Agent名称
+    pub name: String,
+    /// Agent描述
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 主提示词模板
+    pub prompt_template: String,
+    /// 额外的命名提示词模板
+    #[serde(default)]
+    pub prompt_templates: BTreeMap<String, String>,
+    /// 能力元数据
+    pub capabilities: Vec<CapabilityMetadata>,
+    /// Agent配置（不含额外提示词模板）
+    #[serde(default)]
+    pub config: JsonValue,
+    /// Git配置
+    #[serde(default)]
+    pub git_config: Option<JsonValue>,
+    /// 作者
+    #[serde(default)]
+    pub author: Option<String>,
+    /// 导出时间（RFC3339）
+    pub exported_at: String,
+    /// 签名
+    #[serde(default)]
+    pub signature: Option<BundleSignature>,
+}
+
+/// 能力元数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CapabilityMetadata {
+    /// 能力名称
+    pub name: String,
+    /// 能力说明
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// 包签名
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BundleSignature {
+    /// 签名算法：sha256 或 hmac-sha256
+    pub algorithm: String,
+    /// 十六进制签名值
+    pub value: String,
+    /// 签名者
+    #[serde(default)]
+    pub signer: Option<String>,
+}
+
+/// 导入时同名Agent的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflictStrategy {
+    /// 报错，不导入
+    #[default]
+    Fail,
+    /// 以新名称导入（追加序号）
+    Rename,
+    /// 覆盖已有Agent的定义
+    Overwrite,
+}
+
+impl AgentBundle {
+    /// 从Agent实体构建包（未签名）
+    pub fn from_agent(agent: &agent::Model, version: &str, author: Option<String>) -> Self {
+        let mut config = agent.config.clone();
+        let prompt_templates = config
+            .as_object_mut()
+            .and_then(|object| object.remove(PROMPT_TEMPLATES_CONFIG_KEY))
+            .and_then(|templates| serde_json::from_value(templates).ok())
+            .unwrap_or_default();
+
+        let capabilities = agent.capabilities
+            .as_array()
+            .map(|items| {
+                items.iter()
+                    .filter_map(JsonValue::as_str)
+                    .map(|name| CapabilityMetadata { name: name.to_string(), description: None })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            format_version: BUNDLE_FORMAT_VERSION,
+            version: version.to_string(),
+            name: agent.name.clone(),
+            description: agent.description.clone(),
+            prompt_template: agent.prompt_template.clone(),
+            prompt_templates,
+            capabilities,
+            config,
+            git_config: agent.git_config.clone(),
+            author,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            signature: None,
+        }
+    }
+
+    /// 从JSON文本解析包，先检查格式版本再做严格反序列化
+    pub fn from_json(text: &str) -> Result<Self> {
+        let value: JsonValue = serde_json::from_str(text)
+            .map_err(|e| DatabaseError::validation(format!("Agent包不是有效的JSON: {}", e)))?;
+
+        let format_version = value.get("format_version")
+            .and_then(JsonValue::as_u64)
+            .ok_or_else(|| DatabaseError::validation("Agent包缺少 format_version"))?;
+        if format_version > BUNDLE_FORMAT_VERSION as u64 {
+            return Err(DatabaseError::validation(format!(
+                "不支持的Agent包格式版本 {}（当前支持 {}）",
+                format_version, BUNDLE_FORMAT_VERSION
+            )));
+        }
+
+        serde_json::from_value(value)
+            .map_err(|e| DatabaseError::validation(format!("Agent包结构无效: {}", e)))
+    }
+
+    /// 序列化为格式化的JSON文本
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(DatabaseError::from)
+    }
+
+    /// 结构校验，返回所有问题
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = Vec::new();
+
+        if self.format_version == 0 || self.format_version > BUNDLE_FORMAT_VERSION {
+            errors.push(format!("不支持的格式版本: {}", self.format_version));
+        }
+        if !is_semver(&self.version) {
+            errors.push(format!("版本号必须为 x.y.z 格式: {}", self.version));
+        }
+        let name = self.name.trim();
+        if name.is_empty() {
+            errors.push("名称不能为空".to_string());
+        } else if name.chars().count() > MAX_NAME_CHARS {
+            errors.push(format!("名称不能超过{}个字符", MAX_NAME_CHARS));
+        }
+        if self.prompt_template.trim().is_empty() {
+            errors.push("提示词模板不能为空".to_string());
+        }
+        for (key, template) in &self.prompt_templates {
+            if key.trim().is_empty() || template.trim().is_empty() {
+                errors.push(format!("提示词模板 \"{}\" 的名称和内容不能为空", key));
+            }
+        }
+        if self.capabilities.is_empty() {
+            errors.push("至少需要一项能力".to_string());
+        }
+        let mut seen = std::collections::HashSet::new();
+        for capability in &self.capabilities {
+            if capability.name.trim().is_empty() {
+                errors.push("能力名称不能为空".to_string());
+            } else if !seen.insert(capability.name.as_str()) {
+                errors.push(format!("能力重复: {}", capability.name));
+            }
+        }
+        if !self.config.is_object() && !self.config.is_null() {
+            errors.push("config 必须是JSON对象".to_string());
+        }
+        if chrono::DateTime::parse_from_rfc3339(&self.exported_at).is_err() {
+            errors.push(format!("导出时间格式无效: {}", self.exported_at));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(DatabaseError::validation(format!("Agent包校验失败: {}", errors.join("; "))))
+        }
+    }
+
+    /// 签名：提供密钥时使用 HMAC-SHA256，否则记录内容的 SHA-256 摘要
+    pub fn sign(&mut self, key: Option<&[u8]>, signer: Option<String>) -> Result<()> {
+        let payload = self.signing_payload()?;
+        let (algorithm, value) = match key {
+            Some(key) => (SIGNATURE_HMAC_SHA256, hmac_hex(key, &payload)?),
+            None => (SIGNATURE_SHA256, to_hex(&Sha256::digest(&payload))),
+        };
+
+        self.signature = Some(BundleSignature {
+            algorithm: algorithm.to_string(),
+            value,
+            signer,
+        });
+        Ok(())
+    }
+
+    /// 校验签名；HMAC 签名的包必须提供密钥，提供密钥时只接受 HMAC 签名
+    pub fn verify(&self, key: Option<&[u8]>) -> Result<()> {
+        let signature = self.signature
+            .as_ref()
+            .ok_or_else(|| DatabaseError::validation("Agent包未签名"))?;
+        let payload = self.signing_payload()?;
+        let mismatch = || DatabaseError::validation("Agent包签名不匹配，内容可能已被篡改");
+
+        match (signature.algorithm.as_str(), key) {
+            // 配置了密钥时拒绝摘要签名，防止篡改后改用摘要重新签名绕过校验
+            (SIGNATURE_SHA256, Some(_)) => Err(DatabaseError::validation(
+                "已配置签名密钥，拒绝未使用密钥签名的Agent包",
+            )),
+            (SIGNATURE_SHA256, None) => {
+                if to_hex(&Sha256::digest(&payload)) != signature.value.to_lowercase() {
+                    return Err(mismatch());
+                }
+                Ok(())
+            }
+            (SIGNATURE_HMAC_SHA256, None) => Err(DatabaseError::validation(
+                "该Agent包使用密钥签名，需要提供签名密钥",
+            )),
+            (SIGNATURE_HMAC_SHA256, Some(key)) => {
+                let tag = from_hex(&signature.value).ok_or_else(mismatch)?;
+                let mut mac = Hmac::<Sha256>::new_from_slice(key)
+                    .map_err(|e| DatabaseError::validation(format!("签名密钥无效: {}", e)))?;
+                mac.update(&payload);
+                // 常量时间比较
+                mac.verify_slice(&tag).map_err(|_| mismatch())
+            }
+            (other, _) => Err(DatabaseError::validation(format!("不支持的签名算法: {}", other))),
+        }
+    }
+
+    /// 导入到数据库时使用的配置：额外提示词模板并回配置中
+    pub fn agent_config(&self) -> JsonValue {
+        let mut config = match &self.config {
+            JsonValue::Object(object) => object.clone(),
+            _ => serde_json::Map::new(),
+        };
+        if !self.prompt_templates.is_empty() {
+            config.insert(
+                PROMPT_TEMPLATES_CONFIG_KEY.to_string(),
+                serde_json::json!(self.prompt_templates),
+            );
+        }
+        JsonValue::Object(config)
+    }
+
+    /// 能力名称列表（与Agent实体的存储格式一致）
+    pub fn capability_names(&self) -> JsonValue {
+        JsonValue::Array(
+            self.capabilities
+                .iter()
+                .map(|capability| JsonValue::String(capability.name.clone()))
+                .collect(),
+        )
+    }
+
+    /// 签名内容：去掉签名字段后按键排序的规范化JSON
+    fn signing_payload(&self) -> Result<Vec<u8>> {
+        let mut unsigned = self.clone();
+        unsigned.signature = None;
+        let value = serde_json::to_value(&unsigned)?;
+        let mut output = String::new();
+        write_canonical(&value, &mut output);
+        Ok(output.into_bytes())
+    }
+}
+
+/// 按键排序输出JSON，保证签名与 serde_json 的 map 实现无关
+fn write_canonical(value: &JsonValue, output: &mut String) {
+    match value {
+        JsonValue::Object(object) => {
+            let mut keys: Vec<&String> = object.keys().collect();
+            keys.sort();
+            output.push('{');
+            for (index, key) in keys.into_iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                output.push_str(&JsonValue::String(key.clone()).to_string());
+                output.push(':');
+                write_canonical(&object[key], output);
+            }
+            output.push('}');
+        }
+        JsonValue::Array(items) => {
+            output.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_canonical(item, output);
+            }
+            output.push(']');
+        }
+        other => output.push_str(&other.to_string()),
+    }
+}
+
+fn hmac_hex(key: &[u8], payload: &[u8]) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key)
+        .map_err(|e| DatabaseError::validation(format!("签名密钥无效: {}", e)))?;
+    mac.update(payload);
+    Ok(to_hex(&mac.finalize().into_bytes()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) || !value.is_ascii() {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&value[index..index + 2], 16).ok())
+        .collect()
+}
+
+fn is_semver(version: &str) -> bool {
+    let core = version.split(['-', '+']).next().unwrap_or("");
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3
+        && parts.iter().all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
@@ -0,0 +1,286 @@
+//! 智能体性能仪表盘
+//!
+//! 仪表盘数据以 `agent_work_history` 为准按统计周期实时汇总，
+//! `agent_performance_metrics` 中定期生成的记录作为趋势快照附在结果中。
+//! 综合绩效评分沿用 [`agent_performance_metrics::Model::calculate_overall_performance_score`]
+//! 的算法，保证实时汇总与历史快照的评分口径一致。
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{
+    entities::{agent, agent_performance_metrics, agent_work_history, task},
+    repository::{AgentPerformanceMetricsRepository, AgentWorkHistoryRepository, TaskRepository},
+    DatabaseConnection, DatabaseError, Result,
+};
+use codex_multi_agent::{
+    AgentMetricsReport, LeaderboardEntry, MetricsPeriod, PaginationParams, PaginationResponse, PerformanceSnapshot,
+    TeamLeaderboard, WorkHistoryEntry,
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+/// 趋势中保留的快照数量
+const TREND_LIMIT: u64 = 12;
+
+/// 工作历史默认每页数量
+const DEFAULT_PAGE_SIZE: u32 = 20;
+
+/// 工作历史每页数量上限
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// 一组工作历史的汇总
+#[derive(Debug, Default)]
+struct Aggregate {
+    tasks_completed: u32,
+    tasks_successful: u32,
+    completion_minutes: Vec<f64>,
+    quality_scores: Vec<f64>,
+    task_types: HashMap<String, u32>,
+    technologies: HashMap<String, u32>,
+}
+
+impl Aggregate {
+    fn add(&mut self, record: &agent_work_history::Model) {
+        // 尚未结束的工作不计入统计
+        let Some(success) = record.success else {
+            return;
+        };
+        self.tasks_completed += 1;
+        if success {
+            self.tasks_successful += 1;
+        }
+        if let Some(minutes) = record.completion_time_minutes {
+            self.completion_minutes.push(minutes as f64);
+        }
+        if let Some(quality) = record.quality_score {
+            self.quality_scores.push(quality);
+        }
+        *self.task_types.entry(record.task_type.clone()).or_default() += 1;
+        for technology in technologies(record) {
+            *self.technologies.entry(technology).or_default() += 1;
+        }
+    }
+
+    fn success_rate(&self) -> f64 {
+        if self.tasks_completed == 0 {
+            return 0.0;
+        }
+        self.tasks_successful as f64 / self.tasks_completed as f64
+    }
+
+    fn avg_completion_minutes(&self) -> Option<f64> {
+        average(&self.completion_minutes)
+    }
+
+    fn avg_quality_score(&self) -> Option<f64> {
+        average(&self.quality_scores)
+    }
+
+    /// 综合绩效评分（0-10）
+    ///
+    /// 质量评分换算为0-10分；没有完成时间记录时不计效率分。
+    fn performance_score(&self, agent_id: Uuid, start: DateTime<Utc>, end: DateTime<Utc>) -> f64 {
+        let metrics = agent_performance_metrics::Model::new(
+            agent_id,
+            start.into(),
+            end.into(),
+            self.tasks_completed as i32,
+            self.tasks_successful as i32,
+            self.avg_completion_minutes().map(|minutes| minutes / 60.0).unwrap_or(f64::INFINITY),
+            self.avg_quality_score().unwrap_or(0.0) * 10.0,
+            serde_json::json!({}),
+        );
+        metrics.calculate_overall_performance_score()
+    }
+}
+
+/// 汇总智能体在统计周期内的性能
+pub async fn agent_metrics(
+    db: &DatabaseConnection,
+    agent_id: Uuid,
+    period: MetricsPeriod,
+    now: DateTime<Utc>,
+) -> Result<AgentMetricsReport> {
+    let agent = agent::Entity::find_by_id(agent_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Agent", agent_id))?;
+
+    let period_start = period.start_from(now);
+    let records = AgentWorkHistoryRepository::new(db.clone())
+        .find_by_agent_since(agent_id, period_start.map(Into::into))
+        .await?;
+    let mut aggregate = Aggregate::default();
+    records.iter().for_each(|record| aggregate.add(record));
+
+    let mut trend: Vec<PerformanceSnapshot> = AgentPerformanceMetricsRepository::new(db.clone())
+        .get_performance_trend(agent_id, TREND_LIMIT)
+        .await?
+        .iter()
+        .map(snapshot)
+        .collect();
+    trend.reverse();
+
+    let score_start = period_start.unwrap_or_else(|| agent.created_at.with_timezone(&Utc));
+    Ok(AgentMetricsReport {
+        agent_id: agent_id.into(),
+        agent_name: agent.name,
+        period,
+        period_start,
+        period_end: now,
+        tasks_completed: aggregate.tasks_completed,
+        tasks_successful: aggregate.tasks_successful,
+        tasks_failed: aggregate.tasks_completed - aggregate.tasks_successful,
+        success_rate: aggregate.success_rate(),
+        avg_completion_minutes: aggregate.avg_completion_minutes(),
+        avg_quality_score: aggregate.avg_quality_score(),
+        performance_score: aggregate.performance_score(agent_id, score_start, now),
+        task_type_breakdown: aggregate.task_types,
+        technologies: aggregate.technologies,
+        trend,
+    })
+}
+
+/// 按项目任务的工作历史生成团队排行榜
+///
+/// 统计周期内没有完成任何任务的智能体不进入排行榜。
+pub async fn team_leaderboard(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    period: MetricsPeriod,
+    now: DateTime<Utc>,
+) -> Result<TeamLeaderboard> {
+    let task_ids: Vec<Uuid> = TaskRepository::new(db.clone())
+        .find_by_project(project_id)
+        .await?
+        .into_iter()
+        .map(|task| task.task_id)
+        .collect();
+    let period_start = period.start_from(now);
+    let records = AgentWorkHistoryRepository::new(db.clone())
+        .find_by_tasks_since(&task_ids, period_start.map(Into::into))
+        .await?;
+
+    let mut aggregates: HashMap<Uuid, Aggregate> = HashMap::new();
+    for record in &records {
+        aggregates.entry(record.agent_id).or_default().add(record);
+    }
+    aggregates.retain(|_, aggregate| aggregate.tasks_completed > 0);
+
+    let agent_names: HashMap<Uuid, String> = agent::Entity::find()
+        .filter(agent::Column::AgentId.is_in(aggregates.keys().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|agent| (agent.agent_id, agent.name))
+        .collect();
+
+    let score_start = period_start.unwrap_or_else(|| {
+        records
+            .iter()
+            .map(|record| record.started_at.with_timezone(&Utc))
+            .min()
+            .unwrap_or(now)
+    });
+    let mut entries: Vec<LeaderboardEntry> = aggregates
+        .into_iter()
+        .map(|(agent_id, aggregate)| LeaderboardEntry {
+            rank: 0,
+            agent_id: agent_id.into(),
+            agent_name: agent_names.get(&agent_id).cloned().unwrap_or_else(|| agent_id.to_string()),
+            tasks_completed: aggregate.tasks_completed,
+            tasks_successful: aggregate.tasks_successful,
+            success_rate: aggregate.success_rate(),
+            avg_completion_minutes: aggregate.avg_completion_minutes(),
+            avg_quality_score: aggregate.avg_quality_score(),
+            performance_score: aggregate.performance_score(agent_id, score_start, now),
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.performance_score
+            .total_cmp(&a.performance_score)
+            .then(b.tasks_completed.cmp(&a.tasks_completed))
+            .then_with(|| a.agent_name.cmp(&b.agent_name))
+    });
+    for (index, entry) in entries.iter_mut().enumerate() {
+        entry.rank = index as u32 + 1;
+    }
+
+    Ok(TeamLeaderboard {
+        project_id: project_id.into(),
+        period,
+        generated_at: now,
+        entries,
+    })
+}
+
+/// 分页查询智能体的工作历史，按开始时间倒序
+pub async fn agent_history(
+    db: &DatabaseConnection,
+    agent_id: Uuid,
+    pagination: &PaginationParams,
+) -> Result<PaginationResponse<WorkHistoryEntry>> {
+    let page = pagination.page.unwrap_or(1).max(1);
+    let page_size = pagination.page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let (records, total) = AgentWorkHistoryRepository::new(db.clone())
+        .find_by_agent_paginated(agent_id, (page - 1) as u64, page_size as u64)
+        .await?;
+
+    let task_ids: Vec<Uuid> = records.iter().map(|record| record.task_id).collect();
+    let titles: HashMap<Uuid, String> = if task_ids.is_empty() {
+        HashMap::new()
+    } else {
+        task::Entity::find()
+            .filter(task::Column::TaskId.is_in(task_ids))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|task| (task.task_id, task.title))
+            .collect()
+    };
+
+    let items = records
+        .into_iter()
+        .map(|record| WorkHistoryEntry {
+            history_id: record.history_id.to_string(),
+            agent_id: record.agent_id.into(),
+            task_id: record.task_id.into(),
+            task_title: titles.get(&record.task_id).cloned(),
+            technologies_used: technologies(&record),
+            task_type: record.task_type,
+            started_at: record.started_at.with_timezone(&Utc),
+            completed_at: record.completed_at.map(|at| at.with_timezone(&Utc)),
+            success: record.success,
+            completion_time_minutes: record.completion_time_minutes.map(|minutes| minutes.max(0) as u32),
+            quality_score: record.quality_score,
+            error_message: record.error_message,
+        })
+        .collect();
+
+    Ok(PaginationResponse::new(items, total as usize, page, page_size))
+}
+
+fn snapshot(metrics: &agent_performance_metrics::Model) -> PerformanceSnapshot {
+    PerformanceSnapshot {
+        period_start: metrics.period_start.with_timezone(&Utc),
+        period_end: metrics.period_end.with_timezone(&Utc),
+        tasks_completed: metrics.tasks_completed.max(0) as u32,
+        tasks_successful: metrics.tasks_successful.max(0) as u32,
+        avg_completion_hours: metrics.avg_completion_time,
+        avg_code_quality: metrics.avg_code_quality,
+        performance_score: metrics.calculate_overall_performance_score(),
+    }
+}
+
+fn technologies(record: &agent_work_history::Model) -> Vec<String> {
+    serde_json::from_value(record.technologies_used.clone()).unwrap_or_default()
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
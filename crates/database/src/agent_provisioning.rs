@@ -0,0 +1,290 @@
+//! Agent批量配置
+//!
+//! 按能力矩阵（角色 × 能力 × 数量）生成Agent：每个角色按模板生成 [`AgentConfig`]，
+//! 名称按命名规则编号，全部Agent在一个事务中创建，任一失败时整体回滚。
+//!
+//! 模板支持占位符 `{role}`、`{index}`（按数量补零的序号）和 `{capabilities}`（逗号分隔的能力名称）。
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use codex_multi_agent::{AgentApprovalPolicy, AgentCapability, AgentConfig};
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use uuid::Uuid;
+
+use crate::entities::agent;
+use crate::repository::agent_repository::{new_agent_model, CreateAgentData};
+use crate::{DatabaseError, Result};
+
+/// 默认的命名规则
+pub const DEFAULT_NAME_PATTERN: &str = "{role}-{index}";
+
+/// 默认的描述模板
+pub const DEFAULT_DESCRIPTION_TEMPLATE: &str = "{role} Agent（{capabilities}）";
+
+/// 默认的提示词模板
+pub const DEFAULT_PROMPT_TEMPLATE: &str =
+    "你是负责{role}工作的开发Agent，擅长：{capabilities}。请按项目规范完成分配的任务。";
+
+/// 单次配置允许创建的Agent上限
+pub const MAX_PROVISIONED_AGENTS: u32 = 100;
+
+/// 序号的最小位数
+const MIN_INDEX_WIDTH: usize = 2;
+
+/// 配置规格
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProvisioningSpec {
+    /// 命名规则，默认 `{role}-{index}`
+    #[serde(default = "default_name_pattern")]
+    pub name_pattern: String,
+    /// 所有角色共用的模板
+    #[serde(default)]
+    pub template: AgentTemplate,
+    /// 角色矩阵
+    pub roles: Vec<RoleSpec>,
+    /// 与已有Agent重名时跳过而不是报错
+    #[serde(default)]
+    pub skip_existing: bool,
+}
+
+/// 矩阵中的一个角色
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoleSpec {
+    /// 角色名，用于命名和模板
+    pub role: String,
+    pub capabilities: Vec<AgentCapability>,
+    /// 创建数量
+    pub count: u32,
+    /// 覆盖共用模板的字段
+    #[serde(default)]
+    pub template: AgentTemplate,
+}
+
+/// Agent模板，未设置的字段使用默认值
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentTemplate {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub prompt_template: Option<String>,
+    #[serde(default)]
+    pub max_concurrent_tasks: Option<u32>,
+    #[serde(default)]
+    pub timeout_minutes: Option<u32>,
+    #[serde(default)]
+    pub priority_weight: Option<f32>,
+    #[serde(default)]
+    pub approval_policy: Option<AgentApprovalPolicy>,
+    /// 与共用模板的设置合并，同名键以角色为准
+    #[serde(default)]
+    pub custom_settings: HashMap<String, JsonValue>,
+}
+
+impl AgentTemplate {
+    /// 以 `self` 为准合并共用模板
+    fn merged_with(&self, shared: &AgentTemplate) -> AgentTemplate {
+        let mut custom_settings = shared.custom_settings.clone();
+        custom_settings.extend(self.custom_settings.clone());
+        AgentTemplate {
+            description: self.description.clone().or_else(|| shared.description.clone()),
+            prompt_template: self.prompt_template.clone().or_else(|| shared.prompt_template.clone()),
+            max_concurrent_tasks: self.max_concurrent_tasks.or(shared.max_concurrent_tasks),
+            timeout_minutes: self.timeout_minutes.or(shared.timeout_minutes),
+            priority_weight: self.priority_weight.or(shared.priority_weight),
+            approval_policy: self.approval_policy.clone().or_else(|| shared.approval_policy.clone()),
+            custom_settings,
+        }
+    }
+}
+
+fn default_name_pattern() -> String {
+    DEFAULT_NAME_PATTERN.to_string()
+}
+
+/// 生成的一个Agent
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedAgent {
+    pub role: String,
+    pub config: AgentConfig,
+}
+
+/// 已创建的Agent
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProvisionedAgent {
+    pub agent_id: Uuid,
+    pub name: String,
+    pub role: String,
+    pub capabilities: Vec<AgentCapability>,
+}
+
+/// 配置报告
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProvisioningReport {
+    pub created: Vec<ProvisionedAgent>,
+    /// 因重名跳过的Agent名称
+    pub skipped: Vec<String>,
+    /// 每个角色创建的数量
+    pub created_by_role: BTreeMap<String, usize>,
+}
+
+/// 按规格生成Agent配置，不访问数据库
+pub fn plan_agents(spec: &ProvisioningSpec) -> Result<Vec<PlannedAgent>> {
+    if spec.roles.is_empty() {
+        return Err(DatabaseError::validation("配置规格至少需要一个角色"));
+    }
+    if !spec.name_pattern.contains("{index}") {
+        return Err(DatabaseError::validation("命名规则必须包含 {index}，否则同角色的Agent会重名"));
+    }
+    let total: u32 = spec.roles.iter().map(|role| role.count).sum();
+    if total > MAX_PROVISIONED_AGENTS {
+        return Err(DatabaseError::validation(format!(
+            "一次最多创建 {} 个Agent，规格要求 {} 个",
+            MAX_PROVISIONED_AGENTS, total
+        )));
+    }
+
+    let mut roles = HashSet::new();
+    let mut names = HashSet::new();
+    let mut planned = Vec::with_capacity(total as usize);
+    for role_spec in &spec.roles {
+        let role = role_spec.role.trim();
+        if role.is_empty() {
+            return Err(DatabaseError::validation("角色名不能为空"));
+        }
+        if !roles.insert(role.to_string()) {
+            return Err(DatabaseError::validation(format!("角色重复: {}", role)));
+        }
+        if role_spec.count == 0 {
+            return Err(DatabaseError::validation(format!("角色 {} 的数量必须大于0", role)));
+        }
+
+        let template = role_spec.template.merged_with(&spec.template);
+        let width = role_spec.count.to_string().len().max(MIN_INDEX_WIDTH);
+        for index in 1..=role_spec.count {
+            let index = format!("{:0width$}", index, width = width);
+            let config = render_agent_config(&spec.name_pattern, role, &index, &role_spec.capabilities, &template)?;
+            if !names.insert(config.name.clone()) {
+                return Err(DatabaseError::validation(format!("生成的Agent名称重复: {}", config.name)));
+            }
+            planned.push(PlannedAgent { role: role.to_string(), config });
+        }
+    }
+    Ok(planned)
+}
+
+/// 按模板生成一个Agent配置，`index` 为已补零的序号
+pub(crate) fn render_agent_config(
+    name_pattern: &str,
+    role: &str,
+    index: &str,
+    capabilities: &[AgentCapability],
+    template: &AgentTemplate,
+) -> Result<AgentConfig> {
+    let capability_list = capability_names(capabilities);
+    let fill = |pattern: &str| {
+        pattern
+            .replace("{role}", role)
+            .replace("{index}", index)
+            .replace("{capabilities}", &capability_list)
+    };
+    let config = AgentConfig {
+        name: fill(name_pattern),
+        description: fill(template.description.as_deref().unwrap_or(DEFAULT_DESCRIPTION_TEMPLATE)),
+        prompt_template: fill(template.prompt_template.as_deref().unwrap_or(DEFAULT_PROMPT_TEMPLATE)),
+        capabilities: capabilities.to_vec(),
+        max_concurrent_tasks: template.max_concurrent_tasks.unwrap_or(1),
+        timeout_minutes: template.timeout_minutes.unwrap_or(60),
+        git_config: None,
+        custom_settings: template.custom_settings.clone(),
+        priority_weight: template.priority_weight.unwrap_or(0.5),
+        verbose_logging: false,
+        resource_limits: None,
+        approval_policy: template.approval_policy.clone().unwrap_or_default(),
+    };
+    config
+        .validate()
+        .map_err(|e| DatabaseError::validation(format!("Agent {} 的配置无效: {}", config.name, e)))?;
+    Ok(config)
+}
+
+/// 按规格批量创建Agent，全部在一个事务中完成
+pub async fn provision_agents(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    spec: &ProvisioningSpec,
+) -> Result<ProvisioningReport> {
+    let planned = plan_agents(spec)?;
+
+    let txn = db.begin().await?;
+    let existing: HashSet<String> = agent::Entity::find()
+        .filter(agent::Column::UserId.eq(user_id))
+        .filter(agent::Column::Name.is_in(planned.iter().map(|agent| agent.config.name.clone())))
+        .all(&txn)
+        .await?
+        .into_iter()
+        .map(|agent| agent.name)
+        .collect();
+    if !existing.is_empty() && !spec.skip_existing {
+        let mut names: Vec<_> = existing.into_iter().collect();
+        names.sort();
+        return Err(DatabaseError::validation(format!("已存在同名Agent: {}", names.join(", "))));
+    }
+
+    let mut report = ProvisioningReport::default();
+    for PlannedAgent { role, config } in planned {
+        if existing.contains(&config.name) {
+            report.skipped.push(config.name);
+            continue;
+        }
+        let model = new_agent_model(CreateAgentData {
+            user_id,
+            name: config.name.clone(),
+            description: Some(config.description.clone()),
+            prompt_template: config.prompt_template.clone(),
+            capabilities: serde_json::to_value(&config.capabilities)?,
+            config: agent_config_json(&role, &config)?,
+            git_config: None,
+        })
+        .insert(&txn)
+        .await?;
+        *report.created_by_role.entry(role.clone()).or_default() += 1;
+        report.created.push(ProvisionedAgent {
+            agent_id: model.agent_id,
+            name: model.name,
+            role,
+            capabilities: config.capabilities,
+        });
+    }
+    txn.commit().await?;
+
+    tracing::info!(user_id = %user_id, created = report.created.len(), skipped = report.skipped.len(), "批量配置Agent完成");
+    Ok(report)
+}
+
+/// Agent表 `config` 列的内容：运行参数、审批策略和来源角色
+pub(crate) fn agent_config_json(role: &str, config: &AgentConfig) -> Result<JsonValue> {
+    Ok(json!({
+        "max_concurrent_tasks": config.max_concurrent_tasks,
+        "timeout_minutes": config.timeout_minutes,
+        "priority_weight": config.priority_weight,
+        "custom_settings": config.custom_settings,
+        "approval_policy": serde_json::to_value(&config.approval_policy)?,
+        "provisioned_role": role,
+    }))
+}
+
+pub(crate) fn capability_names(capabilities: &[AgentCapability]) -> String {
+    capabilities
+        .iter()
+        .filter_map(|capability| match serde_json::to_value(capability) {
+            Ok(JsonValue::String(name)) => Some(name),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
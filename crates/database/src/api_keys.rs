@@ -0,0 +1,324 @@
+//! 自动化脚本使用的 API 密钥
+//!
+//! 密钥属于某个用户，可以限定到单个项目，带有权限范围、过期时间和每分钟请求上限：
+//! - [`issue_api_key`] 生成密钥，数据库只保存摘要，明文只在创建时返回一次；
+//! - [`ApiKeyAuthenticator::authenticate`] 是各传输层共用的认证中间件：校验密钥、权限范围、
+//!   项目限定和请求用户在项目中的角色，并按密钥做固定窗口限流，同时累计使用计数。
+//!
+//! HTTP 接入见 [`crate::public_api`]，其他传输只需把请求携带的密钥交给认证器。
+
+use chrono::{DateTime, Utc};
+use codex_multi_agent::ProjectRole;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::{
+    entities::api_key,
+    repository::{api_key_repository::CreateApiKeyData, ApiKeyRepository, ProjectMemberRepository},
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 密钥明文的前缀，便于密钥扫描工具识别
+pub const API_KEY_PREFIX: &str = "sker";
+
+/// 默认每分钟请求上限
+pub const DEFAULT_RATE_LIMIT_PER_MINUTE: u32 = 60;
+
+/// 每分钟请求上限的最大值
+pub const MAX_RATE_LIMIT_PER_MINUTE: u32 = 10_000;
+
+/// 限流窗口
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// API 密钥的权限范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ApiScope {
+    /// 查看项目
+    #[serde(rename = "projects:read")]
+    ProjectsRead,
+    /// 查看任务
+    #[serde(rename = "tasks:read")]
+    TasksRead,
+    /// 创建任务
+    #[serde(rename = "tasks:write")]
+    TasksWrite,
+}
+
+impl ApiScope {
+    /// 权限范围的字符串表示（与序列化格式一致）
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApiScope::ProjectsRead => "projects:read",
+            ApiScope::TasksRead => "tasks:read",
+            ApiScope::TasksWrite => "tasks:write",
+        }
+    }
+
+    /// 使用该权限范围时密钥所属用户在项目中至少需要的角色
+    pub fn required_role(self) -> ProjectRole {
+        match self {
+            ApiScope::ProjectsRead | ApiScope::TasksRead => ProjectRole::Viewer,
+            ApiScope::TasksWrite => ProjectRole::Contributor,
+        }
+    }
+}
+
+impl std::str::FromStr for ApiScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "projects:read" => Ok(ApiScope::ProjectsRead),
+            "tasks:read" => Ok(ApiScope::TasksRead),
+            "tasks:write" => Ok(ApiScope::TasksWrite),
+            _ => Err(format!("未知的权限范围: {}", s)),
+        }
+    }
+}
+
+/// 创建 API 密钥的请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueApiKey {
+    pub user_id: Uuid,
+    /// 限定的项目，为空时可访问用户有权限的全部项目
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+    /// 每分钟请求上限，为空时使用 [`DEFAULT_RATE_LIMIT_PER_MINUTE`]
+    #[serde(default)]
+    pub rate_limit_per_minute: Option<u32>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// 新创建的 API 密钥
+#[derive(Debug, Clone, Serialize)]
+pub struct IssuedApiKey {
+    pub key: api_key::Model,
+    /// 密钥明文，只在创建时返回
+    pub secret: String,
+}
+
+/// 认证通过的请求方
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiPrincipal {
+    pub key_id: Uuid,
+    pub user_id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub scopes: Vec<ApiScope>,
+}
+
+impl ApiPrincipal {
+    /// 密钥是否可以访问指定项目
+    pub fn allows_project(&self, project_id: Uuid) -> bool {
+        self.project_id.is_none_or(|allowed| allowed == project_id)
+    }
+}
+
+/// API 密钥认证失败
+#[derive(Debug, thiserror::Error)]
+pub enum ApiAuthError {
+    #[error("缺少 API 密钥")]
+    MissingKey,
+    #[error("无效的 API 密钥")]
+    InvalidKey,
+    #[error("API 密钥已吊销")]
+    Revoked,
+    #[error("API 密钥已过期")]
+    Expired,
+    #[error("API 密钥没有 {} 权限", .0.as_str())]
+    InsufficientScope(ApiScope),
+    #[error("API 密钥不能访问项目 {0}")]
+    ProjectNotAllowed(Uuid),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("超出速率限制，请在 {retry_after_secs} 秒后重试")]
+    RateLimited { retry_after_secs: u64 },
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+impl ApiAuthError {
+    /// 对应的 HTTP 状态码
+    pub fn status(&self) -> u16 {
+        match self {
+            ApiAuthError::MissingKey | ApiAuthError::InvalidKey | ApiAuthError::Revoked | ApiAuthError::Expired => 401,
+            ApiAuthError::InsufficientScope(_) | ApiAuthError::ProjectNotAllowed(_) | ApiAuthError::Forbidden(_) => 403,
+            ApiAuthError::RateLimited { .. } => 429,
+            ApiAuthError::Database(_) => 500,
+        }
+    }
+}
+
+/// 计算密钥明文的摘要
+pub fn hash_secret(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+/// 从 `sker_<前缀>_<密钥>` 格式的明文中取出前缀
+pub fn parse_key_prefix(secret: &str) -> Option<&str> {
+    let rest = secret.strip_prefix(API_KEY_PREFIX)?.strip_prefix('_')?;
+    let (prefix, body) = rest.split_once('_')?;
+    (!prefix.is_empty() && !body.is_empty()).then_some(prefix)
+}
+
+/// 从 `Authorization: Bearer <key>` 取出密钥
+pub fn bearer_token(authorization: &str) -> Option<&str> {
+    let (scheme, token) = authorization.trim().split_once(' ')?;
+    scheme.eq_ignore_ascii_case("bearer").then(|| token.trim()).filter(|token| !token.is_empty())
+}
+
+/// 生成 API 密钥，返回包含明文的结果
+pub async fn issue_api_key(db: &DatabaseConnection, request: IssueApiKey) -> Result<IssuedApiKey> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(DatabaseError::validation("API 密钥名称不能为空"));
+    }
+    if request.scopes.is_empty() {
+        return Err(DatabaseError::validation("API 密钥至少需要一个权限范围"));
+    }
+    let rate_limit = request.rate_limit_per_minute.unwrap_or(DEFAULT_RATE_LIMIT_PER_MINUTE);
+    if rate_limit == 0 || rate_limit > MAX_RATE_LIMIT_PER_MINUTE {
+        return Err(DatabaseError::validation(format!(
+            "每分钟请求上限应在 1 到 {} 之间",
+            MAX_RATE_LIMIT_PER_MINUTE
+        )));
+    }
+    if request.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+        return Err(DatabaseError::validation("过期时间必须晚于当前时间"));
+    }
+    if let Some(project_id) = request.project_id {
+        ProjectMemberRepository::new(db.clone())
+            .require_role(project_id, request.user_id, ProjectRole::Viewer)
+            .await?;
+    }
+
+    let mut scopes = request.scopes;
+    scopes.sort_by_key(|scope| scope.as_str());
+    scopes.dedup();
+
+    // 前缀和密钥体都取自 v4 UUID 的随机位
+    let prefix = Uuid::new_v4().simple().to_string()[..12].to_string();
+    let secret = format!(
+        "{}_{}_{}{}",
+        API_KEY_PREFIX,
+        prefix,
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    );
+    let key = ApiKeyRepository::new(db.clone())
+        .create(CreateApiKeyData {
+            user_id: request.user_id,
+            project_id: request.project_id,
+            name: name.to_string(),
+            key_prefix: prefix,
+            key_hash: hash_secret(&secret),
+            scopes: scopes.iter().map(|scope| scope.as_str().to_string()).collect(),
+            rate_limit_per_minute: rate_limit as i32,
+            expires_at: request.expires_at,
+        })
+        .await?;
+    tracing::info!(key_id = %key.key_id, user_id = %key.user_id, "已创建 API 密钥");
+    Ok(IssuedApiKey { key, secret })
+}
+
+/// 密钥的权限范围，忽略无法识别的值
+pub fn key_scopes(key: &api_key::Model) -> Vec<ApiScope> {
+    serde_json::from_value::<Vec<String>>(key.scopes.clone())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|scope| scope.parse().ok())
+        .collect()
+}
+
+/// 一个密钥当前的限流窗口
+struct RateWindow {
+    started_at: Instant,
+    count: u32,
+}
+
+/// API 密钥认证器，限流状态保存在内存中，多个传输层应共用同一个实例
+pub struct ApiKeyAuthenticator {
+    db: DatabaseConnection,
+    windows: Mutex<HashMap<Uuid, RateWindow>>,
+}
+
+impl ApiKeyAuthenticator {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db, windows: Mutex::new(HashMap::new()) }
+    }
+
+    /// 认证一次请求：`project_id` 为请求访问的项目，为空表示不针对单个项目
+    pub async fn authenticate(
+        &self,
+        secret: Option<&str>,
+        scope: ApiScope,
+        project_id: Option<Uuid>,
+    ) -> std::result::Result<ApiPrincipal, ApiAuthError> {
+        let secret = secret.map(str::trim).filter(|secret| !secret.is_empty()).ok_or(ApiAuthError::MissingKey)?;
+        let prefix = parse_key_prefix(secret).ok_or(ApiAuthError::InvalidKey)?;
+        let repo = ApiKeyRepository::new(self.db.clone());
+        let key = repo.find_by_prefix(prefix).await?.ok_or(ApiAuthError::InvalidKey)?;
+        if key.key_hash != hash_secret(secret) {
+            return Err(ApiAuthError::InvalidKey);
+        }
+        if key.revoked_at.is_some() {
+            return Err(ApiAuthError::Revoked);
+        }
+        if key.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            return Err(ApiAuthError::Expired);
+        }
+
+        if let Err(retry_after) = self.acquire(key.key_id, key.rate_limit_per_minute.max(1) as u32) {
+            repo.record_usage(key.key_id, true).await?;
+            return Err(ApiAuthError::RateLimited { retry_after_secs: retry_after.as_secs().max(1) });
+        }
+        repo.record_usage(key.key_id, false).await?;
+
+        let principal = ApiPrincipal {
+            key_id: key.key_id,
+            user_id: key.user_id,
+            project_id: key.project_id,
+            scopes: key_scopes(&key),
+        };
+        if !principal.scopes.contains(&scope) {
+            return Err(ApiAuthError::InsufficientScope(scope));
+        }
+        if let Some(project_id) = project_id {
+            if !principal.allows_project(project_id) {
+                return Err(ApiAuthError::ProjectNotAllowed(project_id));
+            }
+            ProjectMemberRepository::new(self.db.clone())
+                .require_role(project_id, principal.user_id, scope.required_role())
+                .await
+                .map_err(|e| match e {
+                    DatabaseError::EntityNotFound { .. } => ApiAuthError::ProjectNotAllowed(project_id),
+                    e if e.is_business_error() => ApiAuthError::Forbidden(e.to_string()),
+                    e => ApiAuthError::Database(e),
+                })?;
+        }
+        Ok(principal)
+    }
+
+    /// 占用一次请求配额，超出时返回距离窗口结束的时间
+    fn acquire(&self, key_id: Uuid, limit: u32) -> std::result::Result<(), Duration> {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let window = windows.entry(key_id).or_insert(RateWindow { started_at: now, count: 0 });
+        let elapsed = now.duration_since(window.started_at);
+        if elapsed >= RATE_WINDOW {
+            window.started_at = now;
+            window.count = 0;
+        }
+        if window.count >= limit {
+            return Err(RATE_WINDOW.saturating_sub(now.duration_since(window.started_at)));
+        }
+        window.count += 1;
+        Ok(())
+    }
+}
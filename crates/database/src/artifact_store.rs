@@ -0,0 +1,316 @@
+//! 执行工件存储
+//!
+//! 执行会话产出的工件（补丁、构建产物、测试报告等）按内容的 SHA-256 校验和存放在
+//! 文件系统中（`<root>/objects/<前两位>/<校验和>`），元数据记录在 `execution_artifacts` 表。
+//! 相同内容只存一份，多个工件记录可以引用同一份数据；最后一个引用被删除时数据随之删除。
+//!
+//! [`ArtifactStore::apply_retention`] 按保留策略清理过期工件，并回收执行会话删除后
+//! 不再被引用的数据。项目报告和转存的大字段（见 [`crate::payload_storage`]）也把内容存放在这里，
+//! 其引用同样计入。
+
+use chrono::{Duration, Utc};
+use codex_multi_agent::{ArtifactInfo, ArtifactType, Clock, SharedClock, SystemClock};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::{
+    entities::execution_artifact,
+    repository::{
+        execution_artifact_repository::CreateArtifactData, ExecutionArtifactRepository, OffloadedPayloadRepository,
+        ProjectReportRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 工件保留策略
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// 工件最长保留天数，为空表示不按时间清理
+    pub max_age_days: Option<u32>,
+    /// 存储总大小上限（字节），超出时从最旧的工件开始清理
+    pub max_total_bytes: Option<u64>,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_age_days: Some(30),
+            max_total_bytes: None,
+        }
+    }
+}
+
+/// 保留策略执行结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetentionReport {
+    /// 删除的工件记录数
+    pub removed_artifacts: usize,
+    /// 删除的数据文件数
+    pub removed_blobs: usize,
+    /// 释放的字节数
+    pub freed_bytes: u64,
+}
+
+/// 执行工件存储
+#[derive(Clone)]
+pub struct ArtifactStore {
+    db: DatabaseConnection,
+    root: PathBuf,
+    clock: SharedClock,
+}
+
+impl ArtifactStore {
+    /// 创建工件存储，数据存放在 `root` 目录下
+    pub fn new(db: DatabaseConnection, root: impl Into<PathBuf>) -> Self {
+        Self {
+            db,
+            root: root.into(),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// 替换保留清理和定时任务使用的时钟
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 工件元数据所在的数据库连接
+    pub fn db(&self) -> &DatabaseConnection {
+        &self.db
+    }
+
+    /// 存储根目录
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 存储使用的时钟
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// 校验和对应的数据文件路径
+    pub fn blob_path(&self, checksum: &str) -> Result<PathBuf> {
+        if checksum.len() != 64 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(DatabaseError::validation(format!("无效的工件校验和: {}", checksum)));
+        }
+        Ok(self.objects_dir().join(&checksum[..2]).join(checksum))
+    }
+
+    /// 保存工件内容并记录到执行会话下
+    pub async fn save(
+        &self,
+        session_id: Uuid,
+        name: &str,
+        artifact_type: ArtifactType,
+        content: &[u8],
+        description: Option<String>,
+    ) -> Result<execution_artifact::Model> {
+        if name.trim().is_empty() {
+            return Err(DatabaseError::validation("工件名称不能为空"));
+        }
+
+        let checksum = self.write_blob(content).await?;
+        ExecutionArtifactRepository::new(self.db.clone())
+            .create(CreateArtifactData {
+                session_id,
+                name: name.to_string(),
+                artifact_type,
+                checksum,
+                size_bytes: content.len() as u64,
+                description,
+            })
+            .await
+    }
+
+    /// 保存磁盘上的文件，工件名称取文件名
+    pub async fn save_file(
+        &self,
+        session_id: Uuid,
+        file: &Path,
+        artifact_type: ArtifactType,
+        description: Option<String>,
+    ) -> Result<execution_artifact::Model> {
+        let name = file
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| DatabaseError::validation(format!("无效的工件文件路径: {}", file.display())))?;
+        let content = tokio::fs::read(file).await?;
+        self.save(session_id, &name, artifact_type, &content, description).await
+    }
+
+    /// 读取工件内容，并校验内容未被篡改
+    pub async fn fetch(&self, artifact_id: Uuid) -> Result<(execution_artifact::Model, Vec<u8>)> {
+        let artifact = ExecutionArtifactRepository::new(self.db.clone())
+            .find_by_id(artifact_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ExecutionArtifact", artifact_id))?;
+
+        let content = self.read_blob(&artifact.checksum, &artifact.name).await?;
+        Ok((artifact, content))
+    }
+
+    /// 按校验和写入内容，返回校验和；相同内容只存一份
+    ///
+    /// 不记录工件元数据，调用方需要自行记录对校验和的引用（例如项目报告）。
+    pub async fn write_blob(&self, content: &[u8]) -> Result<String> {
+        let checksum = to_hex(&Sha256::digest(content));
+        let path = self.blob_path(&checksum)?;
+        if !path.exists() {
+            // 先写临时文件再重命名，避免并发读取到写了一半的数据
+            let dir = path.parent().expect("数据文件总有父目录");
+            tokio::fs::create_dir_all(dir).await?;
+            let tmp = dir.join(format!(".{}.{}.tmp", checksum, Uuid::new_v4()));
+            tokio::fs::write(&tmp, content).await?;
+            tokio::fs::rename(&tmp, &path).await?;
+        }
+        Ok(checksum)
+    }
+
+    /// 按校验和读取内容，并校验内容未被篡改；`name` 用于错误信息
+    pub async fn read_blob(&self, checksum: &str, name: &str) -> Result<Vec<u8>> {
+        let content = tokio::fs::read(self.blob_path(checksum)?).await?;
+        if to_hex(&Sha256::digest(&content)) != checksum {
+            return Err(DatabaseError::business_logic(format!("工件内容校验失败: {}", name)));
+        }
+        Ok(content)
+    }
+
+    /// 内容不再被任何工件、报告或转存负载引用时删除数据文件
+    pub async fn release_blob(&self, checksum: &str) -> Result<()> {
+        let artifacts = ExecutionArtifactRepository::new(self.db.clone()).count_by_checksum(checksum).await?;
+        let reports = ProjectReportRepository::new(self.db.clone()).count_by_checksum(checksum).await?;
+        let payloads = OffloadedPayloadRepository::new(self.db.clone()).count_by_checksum(checksum).await?;
+        if artifacts == 0 && reports == 0 && payloads == 0 {
+            remove_blob(&self.blob_path(checksum)?).await?;
+        }
+        Ok(())
+    }
+
+    /// 列出执行会话的工件
+    pub async fn list(&self, session_id: Uuid) -> Result<Vec<ArtifactInfo>> {
+        ExecutionArtifactRepository::new(self.db.clone())
+            .find_by_session_id(session_id)
+            .await?
+            .iter()
+            .map(|artifact| self.artifact_info(artifact))
+            .collect()
+    }
+
+    /// 转换为对外的工件信息，文件路径指向存储中的数据文件
+    pub fn artifact_info(&self, artifact: &execution_artifact::Model) -> Result<ArtifactInfo> {
+        Ok(ArtifactInfo {
+            name: artifact.name.clone(),
+            artifact_type: artifact.artifact_type.parse().unwrap_or(ArtifactType::Other),
+            file_path: self.blob_path(&artifact.checksum)?.to_string_lossy().to_string(),
+            size_bytes: artifact.size_bytes as u64,
+            checksum: artifact.checksum.clone(),
+            created_at: artifact.created_at.with_timezone(&Utc),
+            description: artifact.description.clone(),
+        })
+    }
+
+    /// 删除工件，内容不再被引用时一并删除数据文件
+    pub async fn delete(&self, artifact_id: Uuid) -> Result<()> {
+        let repo = ExecutionArtifactRepository::new(self.db.clone());
+        let artifact = repo
+            .find_by_id(artifact_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ExecutionArtifact", artifact_id))?;
+
+        repo.delete(artifact_id).await?;
+        self.release_blob(&artifact.checksum).await
+    }
+
+    /// 执行保留策略
+    ///
+    /// 先删除超过保留期的工件，再在总大小超限时从最旧的工件开始删除，
+    /// 最后回收不再被任何工件引用的数据文件。
+    pub async fn apply_retention(&self, policy: &RetentionPolicy) -> Result<RetentionReport> {
+        let repo = ExecutionArtifactRepository::new(self.db.clone());
+        let artifacts = repo.find_all_oldest_first().await?;
+
+        // 每份内容的引用数和大小
+        let mut blobs: HashMap<String, (usize, u64)> = HashMap::new();
+        for artifact in &artifacts {
+            let entry = blobs.entry(artifact.checksum.clone()).or_insert((0, artifact.size_bytes as u64));
+            entry.0 += 1;
+        }
+        let mut total_bytes: u64 = blobs.values().map(|(_, size)| size).sum();
+
+        let cutoff = policy.max_age_days.map(|days| self.clock.now() - Duration::days(days as i64));
+        let mut report = RetentionReport::default();
+        for artifact in &artifacts {
+            let expired = cutoff.is_some_and(|cutoff| artifact.created_at.with_timezone(&Utc) < cutoff);
+            let over_limit = policy.max_total_bytes.is_some_and(|limit| total_bytes > limit);
+            if !expired && !over_limit {
+                continue;
+            }
+
+            repo.delete(artifact.artifact_id).await?;
+            report.removed_artifacts += 1;
+            if let Some((references, size)) = blobs.get_mut(&artifact.checksum) {
+                *references -= 1;
+                if *references == 0 {
+                    total_bytes -= *size;
+                }
+            }
+        }
+
+        // 重新读取引用，避免删除清理期间新保存的工件的数据；项目报告和转存负载的内容不受保留策略影响
+        let mut referenced: HashSet<String> = repo
+            .find_all_oldest_first()
+            .await?
+            .into_iter()
+            .map(|artifact| artifact.checksum)
+            .collect();
+        referenced.extend(ProjectReportRepository::new(self.db.clone()).find_all_checksums().await?);
+        referenced.extend(OffloadedPayloadRepository::new(self.db.clone()).find_all_checksums().await?);
+        self.collect_garbage(&referenced, &mut report).await?;
+
+        Ok(report)
+    }
+
+    fn objects_dir(&self) -> PathBuf {
+        self.root.join("objects")
+    }
+
+    /// 删除不在 `referenced` 中的数据文件
+    async fn collect_garbage(&self, referenced: &HashSet<String>, report: &mut RetentionReport) -> Result<()> {
+        let Ok(mut prefixes) = tokio::fs::read_dir(self.objects_dir()).await else {
+            return Ok(());
+        };
+        while let Some(prefix) = prefixes.next_entry().await? {
+            let Ok(mut blobs) = tokio::fs::read_dir(prefix.path()).await else {
+                continue;
+            };
+            while let Some(blob) = blobs.next_entry().await? {
+                let name = blob.file_name().to_string_lossy().to_string();
+                // 跳过正在写入的临时文件
+                if name.starts_with('.') || referenced.contains(&name) {
+                    continue;
+                }
+                let size = blob.metadata().await.map(|metadata| metadata.len()).unwrap_or(0);
+                remove_blob(&blob.path()).await?;
+                report.removed_blobs += 1;
+                report.freed_bytes += size;
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn remove_blob(path: &Path) -> Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,71 @@
+//! 任务分配的历史成本
+//!
+//! Agent完成任务后在工作历史的 `work_details` 中记录成本：`cost` 为直接记录的费用，
+//! 没有 `cost` 时按 `token_count` 和每千token单价折算。按任务类型统计的平均成本通过
+//! [`annotate_task_costs`] 交给调度模拟，供 `cost_optimized` 分配策略比较Agent的成本。
+
+use std::collections::HashMap;
+
+use codex_multi_agent::{SimulationAgent, TaskType};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::repository::AgentWorkHistoryRepository;
+use crate::{DatabaseConnection, Result};
+
+/// 工作详情中记录费用的键
+pub const COST_KEY: &str = "cost";
+
+/// 工作详情中记录token消耗的键
+pub const TOKEN_COUNT_KEY: &str = "token_count";
+
+/// 每个Agent参与统计的最近工作记录数
+pub const COST_HISTORY_WINDOW: usize = 50;
+
+/// 一条工作记录的成本，没有成本数据时返回 None
+pub fn work_cost(work_details: Option<&JsonValue>, cost_per_1k_tokens: Option<f64>) -> Option<f64> {
+    let details = work_details?;
+    if let Some(cost) = details.get(COST_KEY).and_then(JsonValue::as_f64) {
+        return (cost.is_finite() && cost >= 0.0).then_some(cost);
+    }
+    let tokens = details.get(TOKEN_COUNT_KEY).and_then(JsonValue::as_u64)?;
+    cost_per_1k_tokens.map(|price| tokens as f64 / 1000.0 * price)
+}
+
+/// Agent最近工作记录按任务类型统计的平均成本
+pub async fn task_cost_history(
+    db: &DatabaseConnection,
+    agent_id: Uuid,
+    cost_per_1k_tokens: Option<f64>,
+) -> Result<HashMap<TaskType, f64>> {
+    let mut history = AgentWorkHistoryRepository::new(db.clone()).find_by_agent_id(agent_id).await?;
+    history.truncate(COST_HISTORY_WINDOW);
+
+    let mut totals: HashMap<TaskType, (f64, usize)> = HashMap::new();
+    for record in &history {
+        let Ok(task_type) = serde_json::from_value::<TaskType>(JsonValue::String(record.task_type.clone())) else {
+            continue;
+        };
+        if let Some(cost) = work_cost(record.work_details.as_ref(), cost_per_1k_tokens) {
+            let total = totals.entry(task_type).or_default();
+            total.0 += cost;
+            total.1 += 1;
+        }
+    }
+    Ok(totals
+        .into_iter()
+        .map(|(task_type, (sum, count))| (task_type, sum / count as f64))
+        .collect())
+}
+
+/// 为调度模拟中的Agent填入按任务类型统计的历史平均成本
+pub async fn annotate_task_costs(
+    db: &DatabaseConnection,
+    agents: &mut [SimulationAgent],
+    cost_per_1k_tokens: Option<f64>,
+) -> Result<()> {
+    for agent in agents.iter_mut() {
+        agent.task_costs = task_cost_history(db, agent.agent_id.0, cost_per_1k_tokens).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,534 @@
+//! 项目审计包
+//!
+//! 为合规审查导出项目历史的离线记录：[`collect_project_audit`] 汇总项目的任务、领域事件、
+//! 冲突决策、代码评审和执行会话摘要，[`write_audit_bundle`] 把它们写成 zip 归档，
+//! 每类记录各有一份 JSON，另附一份可直接在浏览器中阅读的 `index.html`。
+//!
+//! 归档中的 `manifest.json` 记录每个文件的 SHA-256 和大小，`checksums.sha256`
+//! 以 `sha256sum -c` 可识别的格式列出同样的校验和；[`verify_audit_bundle`] 按清单重新校验归档内容。
+
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{
+    entities::{code_review, conflict, domain_event, execution_artifact, execution_log, human_decision, project, task},
+    repository::{ExecutionArtifactRepository, ExecutionSessionRepository, TaskRepository},
+    reporting::escape_html,
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+
+/// 当前的审计包格式版本
+pub const AUDIT_BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// 清单文件名
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// `sha256sum` 格式的校验和文件名
+pub const CHECKSUMS_FILE: &str = "checksums.sha256";
+
+/// 项目的完整审计记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectAudit {
+    pub project: project::Model,
+    pub tasks: Vec<task::Model>,
+    /// 以项目、任务、执行会话、冲突或评审为聚合根的领域事件，按发生时间升序
+    pub events: Vec<domain_event::Model>,
+    pub decisions: Vec<ConflictDecisions>,
+    pub reviews: Vec<code_review::Model>,
+    pub executions: Vec<ExecutionSummary>,
+}
+
+/// 影响项目任务的冲突及其人工决策
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictDecisions {
+    pub conflict: conflict::Model,
+    pub decisions: Vec<human_decision::Model>,
+}
+
+/// 执行会话摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionSummary {
+    pub session_id: Uuid,
+    pub task_id: Uuid,
+    pub agent_id: Uuid,
+    pub git_branch: String,
+    pub base_commit: Option<String>,
+    pub final_commit: Option<String>,
+    pub status: String,
+    pub success: Option<bool>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub duration_seconds: Option<i64>,
+    /// 按日志级别统计的执行日志条数
+    pub log_counts: BTreeMap<String, u32>,
+    pub artifacts: Vec<ArtifactDigest>,
+}
+
+/// 执行工件的摘要，工件内容本身不进入审计包
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactDigest {
+    pub name: String,
+    pub artifact_type: String,
+    pub checksum: String,
+    pub size_bytes: i64,
+}
+
+/// 归档中的文件及其校验和
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleFile {
+    pub path: String,
+    pub sha256: String,
+    pub size_bytes: u64,
+}
+
+/// 审计包中各类记录的数量
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditCounts {
+    pub tasks: usize,
+    pub events: usize,
+    pub conflicts: usize,
+    pub decisions: usize,
+    pub reviews: usize,
+    pub executions: usize,
+}
+
+/// 审计包清单
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditBundleManifest {
+    pub format_version: u32,
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub generated_at: DateTime<Utc>,
+    pub counts: AuditCounts,
+    pub files: Vec<BundleFile>,
+}
+
+/// 汇总项目的审计记录
+pub async fn collect_project_audit(db: &DatabaseConnection, project_id: Uuid) -> Result<ProjectAudit> {
+    let project = project::Entity::find_by_id(project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+
+    let mut tasks = TaskRepository::new(db.clone()).find_by_project(project_id).await?;
+    tasks.sort_by_key(|task| (task.created_at, task.task_id));
+    let task_ids: Vec<Uuid> = tasks.iter().map(|task| task.task_id).collect();
+    let task_keys: HashSet<String> = task_ids.iter().map(Uuid::to_string).collect();
+
+    // 冲突没有直接关联项目，按受影响的任务归属筛选
+    let conflicts: Vec<conflict::Model> = conflict::Entity::find()
+        .order_by_asc(conflict::Column::DetectedAt)
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|conflict| {
+            conflict
+                .affected_tasks
+                .as_array()
+                .is_some_and(|affected| affected.iter().any(|id| id.as_str().is_some_and(|id| task_keys.contains(id))))
+        })
+        .collect();
+    let conflict_ids: Vec<Uuid> = conflicts.iter().map(|conflict| conflict.conflict_id).collect();
+    let mut decisions_by_conflict: BTreeMap<Uuid, Vec<human_decision::Model>> = BTreeMap::new();
+    if !conflict_ids.is_empty() {
+        for decision in human_decision::Entity::find()
+            .filter(human_decision::Column::ConflictId.is_in(conflict_ids.clone()))
+            .order_by_asc(human_decision::Column::CreatedAt)
+            .all(db)
+            .await?
+        {
+            decisions_by_conflict.entry(decision.conflict_id).or_default().push(decision);
+        }
+    }
+    let decisions = conflicts
+        .into_iter()
+        .map(|conflict| ConflictDecisions {
+            decisions: decisions_by_conflict.remove(&conflict.conflict_id).unwrap_or_default(),
+            conflict,
+        })
+        .collect();
+
+    let reviews = if task_ids.is_empty() {
+        Vec::new()
+    } else {
+        code_review::Entity::find()
+            .filter(code_review::Column::TaskId.is_in(task_ids.clone()))
+            .order_by_asc(code_review::Column::CreatedAt)
+            .all(db)
+            .await?
+    };
+
+    let mut sessions = ExecutionSessionRepository::new(db.clone()).find_by_project_id(project_id).await?;
+    sessions.sort_by_key(|session| (session.created_at, session.session_id));
+    let artifacts = ExecutionArtifactRepository::new(db.clone());
+    let mut executions = Vec::with_capacity(sessions.len());
+    for session in &sessions {
+        let logs = execution_log::Entity::find()
+            .filter(execution_log::Column::SessionId.eq(session.session_id))
+            .all(db)
+            .await?;
+        let mut log_counts = BTreeMap::new();
+        for log in logs {
+            *log_counts.entry(log.log_level).or_default() += 1;
+        }
+        let artifacts = artifacts
+            .find_by_session_id(session.session_id)
+            .await?
+            .iter()
+            .map(artifact_digest)
+            .collect();
+
+        let started_at = session.started_at.map(|at| at.with_timezone(&Utc));
+        let completed_at = session.completed_at.map(|at| at.with_timezone(&Utc));
+        executions.push(ExecutionSummary {
+            session_id: session.session_id,
+            task_id: session.task_id,
+            agent_id: session.agent_id,
+            git_branch: session.git_branch.clone(),
+            base_commit: session.base_commit.clone(),
+            final_commit: session.final_commit.clone(),
+            status: session.status.clone(),
+            success: session.success,
+            error_message: session.error_message.clone(),
+            created_at: session.created_at.with_timezone(&Utc),
+            started_at,
+            completed_at,
+            duration_seconds: started_at.zip(completed_at).map(|(start, end)| (end - start).num_seconds()),
+            log_counts,
+            artifacts,
+        });
+    }
+
+    let mut aggregate_ids = vec![project_id];
+    aggregate_ids.extend(&task_ids);
+    aggregate_ids.extend(sessions.iter().map(|session| session.session_id));
+    aggregate_ids.extend(&conflict_ids);
+    aggregate_ids.extend(reviews.iter().map(|review| review.review_id));
+    let events = domain_event::Entity::find()
+        .filter(domain_event::Column::AggregateId.is_in(aggregate_ids))
+        .order_by_asc(domain_event::Column::OccurredAt)
+        .order_by_asc(domain_event::Column::EventVersion)
+        .all(db)
+        .await?;
+
+    Ok(ProjectAudit {
+        project,
+        tasks,
+        events,
+        decisions,
+        reviews,
+        executions,
+    })
+}
+
+/// 把审计记录写成 zip 归档，返回归档清单
+pub fn write_audit_bundle<W: Write + Seek>(
+    audit: &ProjectAudit,
+    writer: W,
+    generated_at: DateTime<Utc>,
+) -> Result<AuditBundleManifest> {
+    let counts = AuditCounts {
+        tasks: audit.tasks.len(),
+        events: audit.events.len(),
+        conflicts: audit.decisions.len(),
+        decisions: audit.decisions.iter().map(|entry| entry.decisions.len()).sum(),
+        reviews: audit.reviews.len(),
+        executions: audit.executions.len(),
+    };
+
+    let contents = vec![
+        ("project.json", serde_json::to_vec_pretty(&audit.project)?),
+        ("tasks.json", serde_json::to_vec_pretty(&audit.tasks)?),
+        ("events.json", serde_json::to_vec_pretty(&audit.events)?),
+        ("decisions.json", serde_json::to_vec_pretty(&audit.decisions)?),
+        ("reviews.json", serde_json::to_vec_pretty(&audit.reviews)?),
+        ("executions.json", serde_json::to_vec_pretty(&audit.executions)?),
+        ("index.html", render_html(audit, &counts, generated_at).into_bytes()),
+    ];
+
+    let files: Vec<BundleFile> = contents
+        .iter()
+        .map(|(path, content)| BundleFile {
+            path: path.to_string(),
+            sha256: sha256_hex(content),
+            size_bytes: content.len() as u64,
+        })
+        .collect();
+    let checksums: String = files.iter().map(|file| format!("{}  {}\n", file.sha256, file.path)).collect();
+    let manifest = AuditBundleManifest {
+        format_version: AUDIT_BUNDLE_FORMAT_VERSION,
+        project_id: audit.project.project_id,
+        project_name: audit.project.name.clone(),
+        generated_at,
+        counts,
+        files,
+    };
+
+    // 归档中的文件时间统一使用生成时间，相同的审计记录总能得到相同的归档
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(zip_time(generated_at));
+    let mut zip = ZipWriter::new(writer);
+    let mut add = |path: &str, content: &[u8]| -> Result<()> {
+        zip.start_file(path, options).map_err(zip_error)?;
+        zip.write_all(content)?;
+        Ok(())
+    };
+    for (path, content) in &contents {
+        add(path, content)?;
+    }
+    add(CHECKSUMS_FILE, checksums.as_bytes())?;
+    add(MANIFEST_FILE, &serde_json::to_vec_pretty(&manifest)?)?;
+    zip.finish().map_err(zip_error)?;
+
+    Ok(manifest)
+}
+
+/// 导出项目审计包到指定路径
+pub async fn export_project_audit(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    path: &Path,
+    generated_at: DateTime<Utc>,
+) -> Result<AuditBundleManifest> {
+    let audit = collect_project_audit(db, project_id).await?;
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || -> Result<AuditBundleManifest> {
+        // 先写临时文件再改名，导出失败时不会留下不完整的归档
+        let partial = path.with_extension("zip.partial");
+        let result = File::create(&partial)
+            .map_err(DatabaseError::from)
+            .and_then(|file| write_audit_bundle(&audit, file, generated_at));
+        match result {
+            Ok(manifest) => {
+                std::fs::rename(&partial, &path)?;
+                Ok(manifest)
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(&partial);
+                Err(e)
+            }
+        }
+    })
+    .await
+    .map_err(|e| DatabaseError::business_logic(format!("导出审计包失败: {}", e)))?
+}
+
+/// 按清单校验审计包中每个文件的校验和，返回清单
+pub fn verify_audit_bundle<R: Read + Seek>(reader: R) -> Result<AuditBundleManifest> {
+    let mut archive = ZipArchive::new(reader).map_err(zip_error)?;
+    let manifest: AuditBundleManifest = serde_json::from_slice(&read_entry(&mut archive, MANIFEST_FILE)?)?;
+    if manifest.format_version != AUDIT_BUNDLE_FORMAT_VERSION {
+        return Err(DatabaseError::validation(format!(
+            "不支持的审计包格式版本: {}",
+            manifest.format_version
+        )));
+    }
+    for file in &manifest.files {
+        let content = read_entry(&mut archive, &file.path)?;
+        if content.len() as u64 != file.size_bytes || sha256_hex(&content) != file.sha256 {
+            return Err(DatabaseError::validation(format!("审计包文件校验失败: {}", file.path)));
+        }
+    }
+    Ok(manifest)
+}
+
+fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, path: &str) -> Result<Vec<u8>> {
+    let mut entry = archive
+        .by_name(path)
+        .map_err(|_| DatabaseError::validation(format!("审计包缺少文件: {}", path)))?;
+    let mut content = Vec::new();
+    entry.read_to_end(&mut content)?;
+    Ok(content)
+}
+
+fn render_html(audit: &ProjectAudit, counts: &AuditCounts, generated_at: DateTime<Utc>) -> String {
+    let time = |at: DateTime<Utc>| at.format("%Y-%m-%d %H:%M:%S UTC").to_string();
+    let optional = |value: Option<&str>| value.map(escape_html).unwrap_or_else(|| "-".to_string());
+
+    let tasks = table(
+        &["任务", "类型", "状态", "创建时间", "完成时间"],
+        audit.tasks.iter().map(|task| {
+            vec![
+                escape_html(&task.title),
+                escape_html(&task.task_type),
+                escape_html(&task.status),
+                time(task.created_at.with_timezone(&Utc)),
+                task.completed_at.map(|at| time(at.with_timezone(&Utc))).unwrap_or_else(|| "-".to_string()),
+            ]
+        }),
+    );
+    let events = table(
+        &["发生时间", "事件", "聚合类型", "聚合ID", "版本"],
+        audit.events.iter().map(|event| {
+            vec![
+                time(event.occurred_at.with_timezone(&Utc)),
+                escape_html(&event.event_type),
+                escape_html(&event.aggregate_type),
+                event.aggregate_id.to_string(),
+                event.event_version.to_string(),
+            ]
+        }),
+    );
+    let decisions = table(
+        &["冲突", "严重程度", "状态", "决策", "决策理由", "决策时间"],
+        audit.decisions.iter().flat_map(|entry| {
+            let conflict = &entry.conflict;
+            let head = vec![
+                escape_html(&conflict.title),
+                escape_html(&conflict.severity),
+                escape_html(&conflict.status),
+            ];
+            if entry.decisions.is_empty() {
+                let mut row = head;
+                row.extend(["-".to_string(), optional(conflict.resolution_note.as_deref()), "-".to_string()]);
+                return vec![row];
+            }
+            entry
+                .decisions
+                .iter()
+                .map(|decision| {
+                    let mut row = head.clone();
+                    row.extend([
+                        escape_html(&decision.decision_type),
+                        optional(decision.reasoning.as_deref()),
+                        time(decision.created_at.with_timezone(&Utc)),
+                    ]);
+                    row
+                })
+                .collect()
+        }),
+    );
+    let reviews = table(
+        &["任务ID", "分支", "状态", "结论", "评审意见", "评审时间"],
+        audit.reviews.iter().map(|review| {
+            vec![
+                review.task_id.to_string(),
+                escape_html(&format!("{} → {}", review.source_branch, review.target_branch)),
+                escape_html(&review.status),
+                optional(review.decision.as_deref()),
+                optional(review.overall_comment.as_deref()),
+                review.reviewed_at.map(|at| time(at.with_timezone(&Utc))).unwrap_or_else(|| "-".to_string()),
+            ]
+        }),
+    );
+    let executions = table(
+        &["会话ID", "任务ID", "分支", "状态", "结果", "耗时（秒）", "日志", "工件"],
+        audit.executions.iter().map(|execution| {
+            vec![
+                execution.session_id.to_string(),
+                execution.task_id.to_string(),
+                escape_html(&execution.git_branch),
+                escape_html(&execution.status),
+                match execution.success {
+                    Some(true) => "成功".to_string(),
+                    Some(false) => format!("失败：{}", optional(execution.error_message.as_deref())),
+                    None => "-".to_string(),
+                },
+                execution.duration_seconds.map(|seconds| seconds.to_string()).unwrap_or_else(|| "-".to_string()),
+                execution
+                    .log_counts
+                    .iter()
+                    .map(|(level, count)| format!("{} {}", escape_html(level), count))
+                    .collect::<Vec<_>>()
+                    .join("，"),
+                execution.artifacts.len().to_string(),
+            ]
+        }),
+    );
+
+    let project = &audit.project;
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"zh-CN\">
+<head>
+<meta charset=\"utf-8\">
+<title>{name} 审计记录</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; width: 100%; margin-bottom: 2em; }}
+th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }}
+th {{ background: #f4f4f4; }}
+</style>
+</head>
+<body>
+<h1>{name} 审计记录</h1>
+<p>项目ID：{project_id}<br>仓库：{repository}<br>生成时间：{generated_at}</p>
+<p>任务 {tasks_count} 个，事件 {events_count} 条，冲突 {conflicts_count} 个，人工决策 {decisions_count} 条，代码评审 {reviews_count} 次，执行会话 {executions_count} 个。各文件的校验和见 manifest.json 和 checksums.sha256。</p>
+<h2>任务</h2>
+{tasks}
+<h2>领域事件</h2>
+{events}
+<h2>冲突与决策</h2>
+{decisions}
+<h2>代码评审</h2>
+{reviews}
+<h2>执行会话</h2>
+{executions}
+</body>
+</html>
+",
+        name = escape_html(&project.name),
+        project_id = project.project_id,
+        repository = escape_html(&project.repository_url),
+        generated_at = time(generated_at),
+        tasks_count = counts.tasks,
+        events_count = counts.events,
+        conflicts_count = counts.conflicts,
+        decisions_count = counts.decisions,
+        reviews_count = counts.reviews,
+        executions_count = counts.executions,
+    )
+}
+
+/// 渲染 HTML 表格，单元格内容需已转义
+fn table(headers: &[&str], rows: impl Iterator<Item = Vec<String>>) -> String {
+    let rows: Vec<String> = rows
+        .map(|cells| format!("<tr>{}</tr>", cells.iter().map(|cell| format!("<td>{}</td>", cell)).collect::<String>()))
+        .collect();
+    if rows.is_empty() {
+        return "<p>无记录</p>".to_string();
+    }
+    let header: String = headers.iter().map(|header| format!("<th>{}</th>", header)).collect();
+    format!("<table>\n<tr>{}</tr>\n{}\n</table>", header, rows.join("\n"))
+}
+
+fn artifact_digest(artifact: &execution_artifact::Model) -> ArtifactDigest {
+    ArtifactDigest {
+        name: artifact.name.clone(),
+        artifact_type: artifact.artifact_type.clone(),
+        checksum: artifact.checksum.clone(),
+        size_bytes: artifact.size_bytes,
+    }
+}
+
+fn zip_time(at: DateTime<Utc>) -> zip::DateTime {
+    zip::DateTime::from_date_and_time(
+        at.year().clamp(1980, 2107) as u16,
+        at.month() as u8,
+        at.day() as u8,
+        at.hour() as u8,
+        at.minute() as u8,
+        at.second() as u8,
+    )
+    .unwrap_or_default()
+}
+
+fn zip_error(error: zip::result::ZipError) -> DatabaseError {
+    DatabaseError::business_logic(format!("审计包归档失败: {}", error))
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    Sha256::digest(content).iter().map(|b| format!("{:02x}", b)).collect()
+}
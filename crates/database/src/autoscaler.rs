@@ -0,0 +1,528 @@
+//! Agent池自动伸缩
+//!
+//! 按能力划分Agent池，根据就绪任务队列调整池中活跃Agent的数量：
+//! - 每个活跃Agent平均排队任务数超过扩容阈值时扩容，先恢复池中已下线的Agent，不足时按模板创建新Agent；
+//! - 低于缩容阈值时把空闲Agent（没有活动会话和进行中的任务）设为 `offline`，调度器不会再使用；
+//! - 池大小始终保持在 `[min_agents, max_agents]` 之内，扩容和缩容各有冷却时间，避免频繁抖动。
+//!
+//! 池成员由Agent配置中的 `autoscale_pool` 键标识，手动创建的Agent不受影响。
+//! 每次伸缩记录一条 `AgentPoolScaled` 领域事件。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+use codex_multi_agent::AgentCapability;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::agent_provisioning::{agent_config_json, capability_names, render_agent_config, AgentTemplate};
+use crate::entities::agent::{self, AgentStatus};
+use crate::entities::domain_event::{AggregateType, DomainEventType};
+use crate::entities::{execution_session, project, task};
+use crate::repository::agent_repository::CreateAgentData;
+use crate::repository::domain_event_repository::CreateDomainEventData;
+use crate::repository::{AgentRepository, DomainEventRepository};
+use crate::{DatabaseError, Result};
+
+/// Agent配置中标识所属池的键
+pub const AUTOSCALE_POOL_CONFIG_KEY: &str = "autoscale_pool";
+
+/// 自动创建的Agent的命名规则，`{role}` 为能力名称
+pub const AUTOSCALE_NAME_PATTERN: &str = "auto-{role}-{index}";
+
+/// 自动伸缩配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AutoscalerConfig {
+    #[serde(default)]
+    pub pools: Vec<PoolPolicy>,
+}
+
+impl AutoscalerConfig {
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        let mut capabilities = HashSet::new();
+        for pool in &self.pools {
+            if !capabilities.insert(&pool.capability) {
+                return Err(DatabaseError::validation(format!(
+                    "能力 {} 配置了多个Agent池",
+                    capability_name(&pool.capability)
+                )));
+            }
+            pool.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// 单个能力的伸缩策略
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PoolPolicy {
+    pub capability: AgentCapability,
+    #[serde(default)]
+    pub min_agents: u32,
+    pub max_agents: u32,
+    /// 每个活跃Agent平均排队任务数超过该值时扩容
+    #[serde(default = "default_scale_up_threshold")]
+    pub scale_up_threshold: f64,
+    /// 每个活跃Agent平均排队任务数低于该值时缩容
+    #[serde(default = "default_scale_down_threshold")]
+    pub scale_down_threshold: f64,
+    /// 单次伸缩的最大Agent数
+    #[serde(default = "default_max_step")]
+    pub max_step: u32,
+    /// 两次扩容之间的最短间隔（秒）
+    #[serde(default = "default_scale_up_cooldown_secs")]
+    pub scale_up_cooldown_secs: u64,
+    /// 上次伸缩后到缩容的最短间隔（秒）
+    #[serde(default = "default_scale_down_cooldown_secs")]
+    pub scale_down_cooldown_secs: u64,
+    /// 新建Agent使用的模板
+    #[serde(default)]
+    pub template: AgentTemplate,
+}
+
+impl PoolPolicy {
+    fn validate(&self) -> Result<()> {
+        let name = capability_name(&self.capability);
+        if self.max_agents == 0 {
+            return Err(DatabaseError::validation(format!("Agent池 {} 的 max_agents 必须大于0", name)));
+        }
+        if self.min_agents > self.max_agents {
+            return Err(DatabaseError::validation(format!("Agent池 {} 的 min_agents 不能大于 max_agents", name)));
+        }
+        if self.max_step == 0 {
+            return Err(DatabaseError::validation(format!("Agent池 {} 的 max_step 必须大于0", name)));
+        }
+        if !(self.scale_up_threshold > 0.0 && self.scale_down_threshold >= 0.0)
+            || self.scale_down_threshold >= self.scale_up_threshold
+        {
+            return Err(DatabaseError::validation(format!(
+                "Agent池 {} 的阈值无效，需要 0 <= scale_down_threshold < scale_up_threshold",
+                name
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn default_scale_up_threshold() -> f64 {
+    2.0
+}
+
+fn default_scale_down_threshold() -> f64 {
+    0.5
+}
+
+fn default_max_step() -> u32 {
+    2
+}
+
+fn default_scale_up_cooldown_secs() -> u64 {
+    60
+}
+
+fn default_scale_down_cooldown_secs() -> u64 {
+    300
+}
+
+/// 伸缩方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScalingDirection {
+    Up,
+    Down,
+}
+
+/// Agent池的当前状态
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PoolSnapshot {
+    pub capability: AgentCapability,
+    /// 未下线的池成员数
+    pub active: usize,
+    /// 有活动会话或进行中任务的池成员数
+    pub busy: usize,
+    /// 已下线的池成员数
+    pub offline: usize,
+    /// 需要该能力的待执行任务数
+    pub queued: usize,
+}
+
+/// 一次伸缩
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScalingAction {
+    pub capability: AgentCapability,
+    pub direction: ScalingDirection,
+    /// 伸缩前的活跃Agent数
+    pub from: usize,
+    /// 伸缩后的活跃Agent数
+    pub to: usize,
+    /// 恢复上线的Agent
+    pub reactivated: Vec<Uuid>,
+    /// 新创建的Agent
+    pub created: Vec<Uuid>,
+    /// 下线的Agent
+    pub deactivated: Vec<Uuid>,
+    pub queued: usize,
+    pub reason: String,
+    pub scaled_at: DateTime<Utc>,
+}
+
+/// 上次伸缩的时间
+#[derive(Debug, Clone, Copy, Default)]
+struct Cooldown {
+    last_scale_up: Option<DateTime<Utc>>,
+    last_scaled: Option<DateTime<Utc>>,
+}
+
+/// 池成员及其负载
+struct PoolMembers {
+    active: Vec<agent::Model>,
+    offline: Vec<agent::Model>,
+    busy: HashSet<Uuid>,
+}
+
+/// 按策略伸缩一个用户的Agent池
+pub struct AgentAutoscaler {
+    db: DatabaseConnection,
+    user_id: Uuid,
+    config: AutoscalerConfig,
+    cooldowns: Mutex<HashMap<AgentCapability, Cooldown>>,
+}
+
+impl AgentAutoscaler {
+    /// 创建伸缩器，配置无效时返回错误
+    pub fn new(db: DatabaseConnection, user_id: Uuid, config: AutoscalerConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self { db, user_id, config, cooldowns: Mutex::new(HashMap::new()) })
+    }
+
+    /// 伸缩配置
+    pub fn config(&self) -> &AutoscalerConfig {
+        &self.config
+    }
+
+    /// 各Agent池的当前状态
+    pub async fn snapshot(&self) -> Result<Vec<PoolSnapshot>> {
+        let state = self.load_state().await?;
+        Ok(self
+            .config
+            .pools
+            .iter()
+            .map(|policy| {
+                let members = state.members(&policy.capability);
+                PoolSnapshot {
+                    capability: policy.capability.clone(),
+                    active: members.active.len(),
+                    busy: members.busy.len(),
+                    offline: members.offline.len(),
+                    queued: state.queued(&policy.capability),
+                }
+            })
+            .collect())
+    }
+
+    /// 评估所有Agent池并执行需要的伸缩
+    pub async fn evaluate(&self, now: DateTime<Utc>) -> Result<Vec<ScalingAction>> {
+        let state = self.load_state().await?;
+        let mut actions = Vec::new();
+        for policy in &self.config.pools {
+            let members = state.members(&policy.capability);
+            let queued = state.queued(&policy.capability);
+            let cooldown = self.cooldown(&policy.capability);
+            let Some((direction, target, reason)) = decide(policy, &members, queued, cooldown, now) else {
+                continue;
+            };
+
+            let action = match direction {
+                ScalingDirection::Up => self.scale_up(policy, &state, &members, target, queued, reason, now).await?,
+                ScalingDirection::Down => self.scale_down(policy, &members, target, queued, reason, now).await?,
+            };
+            self.record(&action).await?;
+            {
+                let mut cooldowns = self.cooldowns.lock().unwrap_or_else(|e| e.into_inner());
+                let entry = cooldowns.entry(policy.capability.clone()).or_default();
+                entry.last_scaled = Some(now);
+                if direction == ScalingDirection::Up {
+                    entry.last_scale_up = Some(now);
+                }
+            }
+            tracing::info!(
+                capability = %capability_name(&policy.capability),
+                from = action.from,
+                to = action.to,
+                "Agent池已伸缩: {}",
+                action.reason
+            );
+            actions.push(action);
+        }
+        Ok(actions)
+    }
+
+    fn cooldown(&self, capability: &AgentCapability) -> Cooldown {
+        self.cooldowns
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(capability)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn scale_up(
+        &self,
+        policy: &PoolPolicy,
+        state: &AutoscaleState,
+        members: &PoolMembers,
+        target: usize,
+        queued: usize,
+        reason: String,
+        now: DateTime<Utc>,
+    ) -> Result<ScalingAction> {
+        let repo = AgentRepository::new(self.db.clone());
+        let mut needed = target - members.active.len();
+        let mut action = ScalingAction::new(policy, ScalingDirection::Up, members.active.len(), queued, reason, now);
+
+        for agent in members.offline.iter().take(needed) {
+            repo.update_status(agent.agent_id, AgentStatus::Idle, None).await?;
+            action.reactivated.push(agent.agent_id);
+        }
+        needed -= action.reactivated.len();
+
+        let role = capability_name(&policy.capability);
+        let mut index = 0;
+        while needed > 0 {
+            index += 1;
+            let config = render_agent_config(
+                AUTOSCALE_NAME_PATTERN,
+                &role,
+                &format!("{:02}", index),
+                std::slice::from_ref(&policy.capability),
+                &policy.template,
+            )?;
+            if state.names.contains(&config.name) {
+                continue;
+            }
+            let mut config_json = agent_config_json(&role, &config)?;
+            config_json[AUTOSCALE_POOL_CONFIG_KEY] = role.clone().into();
+            let agent = repo
+                .create(CreateAgentData {
+                    user_id: self.user_id,
+                    name: config.name.clone(),
+                    description: Some(config.description.clone()),
+                    prompt_template: config.prompt_template.clone(),
+                    capabilities: serde_json::to_value(&config.capabilities)?,
+                    config: config_json,
+                    git_config: None,
+                })
+                .await?;
+            action.created.push(agent.agent_id);
+            needed -= 1;
+        }
+        action.to = target;
+        Ok(action)
+    }
+
+    async fn scale_down(
+        &self,
+        policy: &PoolPolicy,
+        members: &PoolMembers,
+        target: usize,
+        queued: usize,
+        reason: String,
+        now: DateTime<Utc>,
+    ) -> Result<ScalingAction> {
+        let repo = AgentRepository::new(self.db.clone());
+        let mut action = ScalingAction::new(policy, ScalingDirection::Down, members.active.len(), queued, reason, now);
+        // 最近创建的空闲Agent先下线
+        let removable = members.active.iter().rev().filter(|agent| !members.busy.contains(&agent.agent_id));
+        for agent in removable.take(members.active.len() - target) {
+            repo.update_status(agent.agent_id, AgentStatus::Offline, None).await?;
+            action.deactivated.push(agent.agent_id);
+        }
+        action.to = members.active.len() - action.deactivated.len();
+        Ok(action)
+    }
+
+    async fn record(&self, action: &ScalingAction) -> Result<()> {
+        let events = DomainEventRepository::new(self.db.clone());
+        let version = events.get_latest_version(self.user_id).await? + 1;
+        events
+            .create(CreateDomainEventData {
+                aggregate_type: AggregateType::User.to_string(),
+                aggregate_id: self.user_id,
+                event_type: DomainEventType::AgentPoolScaled.to_string(),
+                event_data: serde_json::to_value(action)?,
+                event_version: version,
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn load_state(&self) -> Result<AutoscaleState> {
+        let agents = agent::Entity::find()
+            .filter(agent::Column::UserId.eq(self.user_id))
+            .all(&self.db)
+            .await?;
+        let project_ids: Vec<Uuid> = project::Entity::find()
+            .filter(project::Column::UserId.eq(self.user_id))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|project| project.project_id)
+            .collect();
+        let tasks = task::Entity::find()
+            .filter(task::Column::ProjectId.is_in(project_ids))
+            .filter(task::Column::Status.is_in(["pending", "in_progress"]))
+            .all(&self.db)
+            .await?;
+        let sessions = execution_session::Entity::find()
+            .filter(execution_session::Column::AgentId.is_in(agents.iter().map(|agent| agent.agent_id)))
+            .filter(execution_session::Column::Status.is_in(["pending", "running"]))
+            .all(&self.db)
+            .await?;
+
+        let mut busy: HashSet<Uuid> = sessions.iter().map(|session| session.agent_id).collect();
+        busy.extend(
+            tasks
+                .iter()
+                .filter(|task| task.status == "in_progress")
+                .filter_map(|task| task.assigned_agent_id),
+        );
+        Ok(AutoscaleState {
+            names: agents.iter().map(|agent| agent.name.clone()).collect(),
+            agents,
+            pending: tasks.into_iter().filter(|task| task.status == "pending").collect(),
+            busy,
+        })
+    }
+}
+
+impl ScalingAction {
+    fn new(
+        policy: &PoolPolicy,
+        direction: ScalingDirection,
+        from: usize,
+        queued: usize,
+        reason: String,
+        scaled_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            capability: policy.capability.clone(),
+            direction,
+            from,
+            to: from,
+            reactivated: Vec::new(),
+            created: Vec::new(),
+            deactivated: Vec::new(),
+            queued,
+            reason,
+            scaled_at,
+        }
+    }
+}
+
+/// 一轮评估使用的数据
+struct AutoscaleState {
+    agents: Vec<agent::Model>,
+    names: HashSet<String>,
+    pending: Vec<task::Model>,
+    busy: HashSet<Uuid>,
+}
+
+impl AutoscaleState {
+    fn members(&self, capability: &AgentCapability) -> PoolMembers {
+        let name = capability_name(capability);
+        let mut members = PoolMembers { active: Vec::new(), offline: Vec::new(), busy: HashSet::new() };
+        for agent in &self.agents {
+            if agent.config.get(AUTOSCALE_POOL_CONFIG_KEY).and_then(|pool| pool.as_str()) != Some(name.as_str()) {
+                continue;
+            }
+            if agent.status == AgentStatus::Offline.to_string() {
+                members.offline.push(agent.clone());
+            } else {
+                if self.busy.contains(&agent.agent_id) {
+                    members.busy.insert(agent.agent_id);
+                }
+                members.active.push(agent.clone());
+            }
+        }
+        members.active.sort_by_key(|agent| agent.created_at);
+        members
+    }
+
+    fn queued(&self, capability: &AgentCapability) -> usize {
+        let name = capability_name(capability);
+        self.pending
+            .iter()
+            .filter(|task| {
+                task.required_capabilities
+                    .as_ref()
+                    .and_then(|required| required.as_array())
+                    .is_some_and(|required| required.iter().any(|value| value.as_str() == Some(name.as_str())))
+            })
+            .count()
+    }
+}
+
+/// 决定伸缩方向和目标活跃数，不需要伸缩时返回 None
+fn decide(
+    policy: &PoolPolicy,
+    members: &PoolMembers,
+    queued: usize,
+    cooldown: Cooldown,
+    now: DateTime<Utc>,
+) -> Option<(ScalingDirection, usize, String)> {
+    let active = members.active.len();
+    let (min, max, step) = (policy.min_agents as usize, policy.max_agents as usize, policy.max_step as usize);
+    // 按队列长度需要的活跃数，单次最多增加 max_step
+    let wanted = ((queued as f64 / policy.scale_up_threshold).ceil() as usize).min((active + step).min(max));
+    if active < min {
+        return Some((ScalingDirection::Up, wanted.max(min), format!("活跃Agent数 {} 低于下限 {}", active, min)));
+    }
+
+    let idle = active - members.busy.len();
+    if active > max {
+        let target = max.max(active - idle);
+        return (target < active)
+            .then(|| (ScalingDirection::Down, target, format!("活跃Agent数 {} 超过上限 {}", active, max)));
+    }
+
+    let elapsed = |since: Option<DateTime<Utc>>, secs: u64| {
+        since.is_none_or(|since| now - since >= Duration::seconds(secs as i64))
+    };
+    let load = if active == 0 { f64::INFINITY } else { queued as f64 / active as f64 };
+
+    if queued > 0 && load > policy.scale_up_threshold && active < max {
+        if !elapsed(cooldown.last_scale_up, policy.scale_up_cooldown_secs) {
+            return None;
+        }
+        let target = wanted.max(active + 1);
+        let reason = if active == 0 {
+            format!("{} 个待执行任务，池中没有活跃Agent", queued)
+        } else {
+            format!("{} 个待执行任务，平均每个Agent {:.1} 个，超过扩容阈值 {}", queued, load, policy.scale_up_threshold)
+        };
+        return Some((ScalingDirection::Up, target, reason));
+    }
+
+    if load < policy.scale_down_threshold && active > min && idle > 0 {
+        if !elapsed(cooldown.last_scaled, policy.scale_down_cooldown_secs) {
+            return None;
+        }
+        let target = (active - step.min(idle)).max(min);
+        return Some((
+            ScalingDirection::Down,
+            target,
+            format!("{} 个待执行任务，平均每个Agent {:.1} 个，低于缩容阈值 {}", queued, load, policy.scale_down_threshold),
+        ));
+    }
+    None
+}
+
+fn capability_name(capability: &AgentCapability) -> String {
+    capability_names(std::slice::from_ref(capability))
+}
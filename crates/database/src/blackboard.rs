@@ -0,0 +1,261 @@
+//! 项目黑板（共享上下文）
+//!
+//! 项目范围内的键值存储，记录已确定的决策、接口规范、命名约定等，Agent 和人工都可以读写。
+//! 每次写入生成一个新版本；写入时携带上次读取到的版本号，版本已变化说明期间有其他写入，
+//! 返回 [`DatabaseError::Conflict`]，由调用方重新读取、合并后再写。
+//!
+//! 标记为纳入提示词的条目由 [`ContextBuilder`](crate::ContextBuilder) 放入
+//! `ProjectContext::shared_context`，并在分配任务时附加到执行提示词末尾。
+
+use chrono::{DateTime, Utc};
+use codex_multi_agent::{SharedContextCategory, SharedContextEntry};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    entities::blackboard_entry,
+    repository::{blackboard_repository::CreateEntryVersionData, BlackboardRepository},
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 条目键的最大长度
+pub const MAX_KEY_LENGTH: usize = 128;
+
+/// 条目的写入者
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "id", rename_all = "lowercase")]
+pub enum EntryAuthor {
+    User(Uuid),
+    Agent(Uuid),
+}
+
+/// 黑板条目的当前值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlackboardEntry {
+    pub project_id: Uuid,
+    pub key: String,
+    pub category: SharedContextCategory,
+    pub value: String,
+    pub version: u32,
+    pub include_in_prompts: bool,
+    /// 最近一次写入者
+    pub author: Option<EntryAuthor>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BlackboardEntry {
+    fn from_model(model: blackboard_entry::Model) -> Self {
+        let author = match (model.author_user_id, model.author_agent_id) {
+            (Some(user_id), _) => Some(EntryAuthor::User(user_id)),
+            (None, Some(agent_id)) => Some(EntryAuthor::Agent(agent_id)),
+            (None, None) => None,
+        };
+        Self {
+            project_id: model.project_id,
+            key: model.key,
+            category: model.category.parse().unwrap_or(SharedContextCategory::Note),
+            value: model.value,
+            version: model.version.max(0) as u32,
+            include_in_prompts: model.include_in_prompts,
+            author,
+            updated_at: model.created_at.with_timezone(&Utc),
+        }
+    }
+
+    /// 转换为提示词使用的共享上下文条目
+    pub fn to_shared_context(&self) -> SharedContextEntry {
+        SharedContextEntry {
+            key: self.key.clone(),
+            category: self.category,
+            value: self.value.clone(),
+            version: self.version,
+        }
+    }
+}
+
+/// 写入条目的数据结构
+#[derive(Debug, Clone)]
+pub struct WriteEntryData {
+    pub project_id: Uuid,
+    pub key: String,
+    pub category: SharedContextCategory,
+    pub value: String,
+    pub include_in_prompts: bool,
+    /// 上次读取到的版本号，新建条目时为 `None`
+    pub expected_version: Option<u32>,
+    pub author: EntryAuthor,
+}
+
+/// 读取条目的当前值，不存在或已删除时返回 `None`
+pub async fn get_entry(db: &DatabaseConnection, project_id: Uuid, key: &str) -> Result<Option<BlackboardEntry>> {
+    Ok(BlackboardRepository::new(db.clone())
+        .find_latest(project_id, key)
+        .await?
+        .filter(|entry| !entry.deleted)
+        .map(BlackboardEntry::from_model))
+}
+
+/// 列出项目的全部条目（按键排序）
+pub async fn list_entries(db: &DatabaseConnection, project_id: Uuid) -> Result<Vec<BlackboardEntry>> {
+    Ok(BlackboardRepository::new(db.clone())
+        .find_latest_by_project(project_id)
+        .await?
+        .into_iter()
+        .filter(|entry| !entry.deleted)
+        .map(BlackboardEntry::from_model)
+        .collect())
+}
+
+/// 读取条目的全部历史版本（包括删除记录）
+pub async fn entry_history(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    key: &str,
+) -> Result<Vec<blackboard_entry::Model>> {
+    BlackboardRepository::new(db.clone()).find_history(project_id, key).await
+}
+
+/// 写入条目
+///
+/// `expected_version` 与当前版本不一致（包括新建时条目已存在）时返回并发冲突错误。
+pub async fn write_entry(db: &DatabaseConnection, data: WriteEntryData) -> Result<BlackboardEntry> {
+    let key = normalize_key(&data.key)?;
+    if data.value.trim().is_empty() {
+        return Err(DatabaseError::validation("黑板条目内容不能为空"));
+    }
+
+    let repo = BlackboardRepository::new(db.clone());
+    let latest = repo.find_latest(data.project_id, &key).await?;
+    check_version(&key, latest.as_ref(), data.expected_version)?;
+
+    let (author_user_id, author_agent_id) = author_ids(data.author);
+    let entry = repo
+        .create_version(CreateEntryVersionData {
+            project_id: data.project_id,
+            key,
+            version: next_version(latest.as_ref()),
+            category: data.category.to_string(),
+            value: data.value,
+            include_in_prompts: data.include_in_prompts,
+            deleted: false,
+            author_user_id,
+            author_agent_id,
+        })
+        .await?;
+    Ok(BlackboardEntry::from_model(entry))
+}
+
+/// 删除条目，删除记录为一个新版本，历史仍可查询
+pub async fn delete_entry(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    key: &str,
+    expected_version: u32,
+    author: EntryAuthor,
+) -> Result<()> {
+    let repo = BlackboardRepository::new(db.clone());
+    let latest = repo
+        .find_latest(project_id, key)
+        .await?
+        .filter(|entry| !entry.deleted)
+        .ok_or_else(|| DatabaseError::entity_not_found("BlackboardEntry", key))?;
+    check_version(key, Some(&latest), Some(expected_version))?;
+
+    let (author_user_id, author_agent_id) = author_ids(author);
+    repo.create_version(CreateEntryVersionData {
+        project_id,
+        key: latest.key.clone(),
+        version: latest.version + 1,
+        category: latest.category.clone(),
+        value: latest.value.clone(),
+        include_in_prompts: false,
+        deleted: true,
+        author_user_id,
+        author_agent_id,
+    })
+    .await?;
+    Ok(())
+}
+
+/// 读取需要纳入提示词的条目
+pub async fn prompt_entries(db: &DatabaseConnection, project_id: Uuid) -> Result<Vec<SharedContextEntry>> {
+    Ok(list_entries(db, project_id)
+        .await?
+        .iter()
+        .filter(|entry| entry.include_in_prompts)
+        .map(BlackboardEntry::to_shared_context)
+        .collect())
+}
+
+/// 把共享上下文条目渲染为提示词片段，没有条目时返回空字符串
+pub fn render_prompt_section(entries: &[SharedContextEntry]) -> String {
+    if entries.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from("## 项目共享上下文\n以下内容已在项目中确定，请严格遵循：\n");
+    for entry in entries {
+        section.push_str(&format!(
+            "- [{}] {}: {}\n",
+            entry.category.display_name(),
+            entry.key,
+            entry.value.trim()
+        ));
+    }
+    section
+}
+
+/// 在提示词末尾附加项目共享上下文
+pub(crate) async fn append_to_prompt(db: &DatabaseConnection, project_id: Uuid, prompt: String) -> Result<String> {
+    let section = render_prompt_section(&prompt_entries(db, project_id).await?);
+    if section.is_empty() {
+        return Ok(prompt);
+    }
+    Ok(format!("{}\n\n{}", prompt.trim_end(), section))
+}
+
+fn normalize_key(key: &str) -> Result<String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(DatabaseError::validation("黑板条目键不能为空"));
+    }
+    if key.chars().count() > MAX_KEY_LENGTH {
+        return Err(DatabaseError::validation(format!(
+            "黑板条目键不能超过 {} 个字符",
+            MAX_KEY_LENGTH
+        )));
+    }
+    Ok(key.to_string())
+}
+
+fn check_version(key: &str, latest: Option<&blackboard_entry::Model>, expected: Option<u32>) -> Result<()> {
+    let current = latest
+        .filter(|entry| !entry.deleted)
+        .map(|entry| entry.version.max(0) as u32);
+    if current == expected {
+        return Ok(());
+    }
+
+    Err(DatabaseError::conflict(match (current, expected) {
+        (Some(current), None) => format!("黑板条目 {} 已存在（版本 {}）", key, current),
+        (None, Some(_)) => format!("黑板条目 {} 已被删除", key),
+        (current, expected) => format!(
+            "黑板条目 {} 已被修改：期望版本 {}，当前版本 {}",
+            key,
+            expected.unwrap_or(0),
+            current.unwrap_or(0)
+        ),
+    }))
+}
+
+/// 版本号在删除后继续递增，避免与历史版本重复
+fn next_version(latest: Option<&blackboard_entry::Model>) -> i32 {
+    latest.map_or(1, |entry| entry.version + 1)
+}
+
+fn author_ids(author: EntryAuthor) -> (Option<Uuid>, Option<Uuid>) {
+    match author {
+        EntryAuthor::User(user_id) => (Some(user_id), None),
+        EntryAuthor::Agent(agent_id) => (None, Some(agent_id)),
+    }
+}
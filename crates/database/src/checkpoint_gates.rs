@@ -0,0 +1,337 @@
+//! 任务人工检查点
+//!
+//! 有些任务需要在流程中的固定位置暂停等待人工审查，例如设计完成后、执行数据迁移前。
+//! 每个任务可以按顺序定义若干检查点：
+//! - 执行方到达检查点时调用 [`request_gate`]，任务进入 `waiting_for_human` 状态；
+//! - 人工通过 [`decide_gate`] 批准或驳回（驳回必须填写意见），任务恢复到等待前的状态，
+//!   被驳回的检查点可以在修改后重新提交；
+//! - 检查点必须按顺序通过，存在未批准的检查点时任务不能完成；
+//! - [`gate_timeline`] 汇总每个检查点的等待时长，包括正在进行的等待。
+//!
+//! 每次决策记录 `HumanGateDecided` 领域事件。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        domain_event::{AggregateType, DomainEventType},
+        task, task_gate,
+    },
+    repository::{
+        domain_event_repository::CreateDomainEventData,
+        task_gate_repository::{CreateTaskGateData, GateDecisionData},
+        DomainEventRepository, TaskGateRepository, TaskRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 等待人工检查点时的任务状态
+pub const WAITING_FOR_HUMAN: &str = "waiting_for_human";
+
+/// 检查点状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GateStatus {
+    /// 尚未到达
+    Pending,
+    /// 等待人工决策
+    Waiting,
+    /// 已批准
+    Approved,
+    /// 已驳回，修改后可以重新提交
+    Rejected,
+}
+
+impl GateStatus {
+    /// 数据库中存储的字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GateStatus::Pending => "pending",
+            GateStatus::Waiting => "waiting",
+            GateStatus::Approved => "approved",
+            GateStatus::Rejected => "rejected",
+        }
+    }
+}
+
+impl std::fmt::Display for GateStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for GateStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(GateStatus::Pending),
+            "waiting" => Ok(GateStatus::Waiting),
+            "approved" => Ok(GateStatus::Approved),
+            "rejected" => Ok(GateStatus::Rejected),
+            _ => Err(format!("未知的检查点状态: {}", s)),
+        }
+    }
+}
+
+/// 定义检查点的数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefineGateData {
+    pub task_id: Uuid,
+    /// 检查点名称，例如「设计评审」「迁移前确认」
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// 时间线中的一个检查点
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GateTimelineEntry {
+    pub gate_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub sequence: i32,
+    pub status: GateStatus,
+    pub requested_at: Option<DateTime<Utc>>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub decided_by: Option<Uuid>,
+    pub comment: Option<String>,
+    /// 提交等待的次数
+    pub request_count: i32,
+    /// 累计等待时长（秒），包括正在进行的等待
+    pub waiting_seconds: i64,
+}
+
+/// 任务在人工检查点上的等待时间线
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GateTimeline {
+    pub task_id: Uuid,
+    /// 按流程顺序排列
+    pub gates: Vec<GateTimelineEntry>,
+    /// 所有检查点的累计等待时长（秒）
+    pub total_waiting_seconds: i64,
+    /// 正在等待决策的检查点
+    pub waiting_gate_id: Option<Uuid>,
+}
+
+/// 为任务定义检查点，新检查点排在已有检查点之后
+pub async fn define_gate(db: &DatabaseConnection, data: DefineGateData) -> Result<task_gate::Model> {
+    let name = data.name.trim();
+    if name.is_empty() {
+        return Err(DatabaseError::validation("检查点名称不能为空"));
+    }
+    find_task(db, data.task_id).await?;
+
+    let repo = TaskGateRepository::new(db.clone());
+    let gates = repo.find_by_task(data.task_id).await?;
+    if gates.iter().any(|gate| gate.name == name) {
+        return Err(DatabaseError::conflict(format!("任务已存在名为「{}」的检查点", name)));
+    }
+    let sequence = gates.iter().map(|gate| gate.sequence + 1).max().unwrap_or(0);
+
+    repo.create(CreateTaskGateData {
+        task_id: data.task_id,
+        name: name.to_string(),
+        description: data.description.filter(|description| !description.trim().is_empty()),
+        sequence,
+    })
+    .await
+}
+
+/// 任务到达检查点，暂停等待人工决策
+///
+/// 检查点必须处于未到达或已驳回状态，且之前的检查点都已批准
+pub async fn request_gate(db: &DatabaseConnection, task_id: Uuid, gate_name: &str) -> Result<task_gate::Model> {
+    let task = find_task(db, task_id).await?;
+    if matches!(task.status.as_str(), "completed" | "failed" | "cancelled") {
+        return Err(DatabaseError::business_logic(format!("任务已结束（{}），不能进入检查点", task.status)));
+    }
+
+    let repo = TaskGateRepository::new(db.clone());
+    let gates = repo.find_by_task(task_id).await?;
+    let gate = gates
+        .iter()
+        .find(|gate| gate.name == gate_name)
+        .cloned()
+        .ok_or_else(|| DatabaseError::entity_not_found("TaskGate", gate_name))?;
+
+    if let Some(waiting) = gates.iter().find(|gate| gate.status == GateStatus::Waiting.as_str()) {
+        return Err(DatabaseError::conflict(format!("任务正在检查点「{}」等待人工决策", waiting.name)));
+    }
+    if gate.status == GateStatus::Approved.as_str() {
+        return Err(DatabaseError::validation(format!("检查点「{}」已批准", gate.name)));
+    }
+    if let Some(earlier) = gates
+        .iter()
+        .find(|earlier| earlier.sequence < gate.sequence && earlier.status != GateStatus::Approved.as_str())
+    {
+        return Err(DatabaseError::business_logic(format!(
+            "检查点「{}」之前的检查点「{}」尚未批准",
+            gate.name, earlier.name
+        )));
+    }
+
+    let resume_status = task.status.clone();
+    let gate = repo.mark_waiting(gate, resume_status, Utc::now()).await?;
+    TaskRepository::new(db.clone())
+        .set_status(task_id, WAITING_FOR_HUMAN, false)
+        .await?;
+    tracing::info!("任务 {} 在检查点「{}」等待人工决策", task_id, gate.name);
+    Ok(gate)
+}
+
+/// 人工批准或驳回检查点，任务恢复到等待前的状态
+///
+/// 驳回时必须填写意见，供执行方修改
+pub async fn decide_gate(
+    db: &DatabaseConnection,
+    gate_id: Uuid,
+    user_id: Uuid,
+    approved: bool,
+    comment: Option<String>,
+) -> Result<task_gate::Model> {
+    let comment = comment.map(|comment| comment.trim().to_string()).filter(|comment| !comment.is_empty());
+    if !approved && comment.is_none() {
+        return Err(DatabaseError::validation("驳回检查点必须填写意见"));
+    }
+
+    let repo = TaskGateRepository::new(db.clone());
+    let gate = repo
+        .find_by_id(gate_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("TaskGate", gate_id))?;
+    if gate.status != GateStatus::Waiting.as_str() {
+        return Err(DatabaseError::validation(format!("检查点「{}」没有在等待人工决策", gate.name)));
+    }
+
+    let now = Utc::now();
+    let waited_seconds = gate
+        .requested_at
+        .map(|requested_at| (now - requested_at.with_timezone(&Utc)).num_seconds())
+        .unwrap_or(0);
+    let resume_status = gate.resume_status.clone().unwrap_or_else(|| "in_progress".to_string());
+    let status = if approved { GateStatus::Approved } else { GateStatus::Rejected };
+    let gate = repo
+        .record_decision(gate, GateDecisionData {
+            status: status.to_string(),
+            decided_by: user_id,
+            comment: comment.clone(),
+            decided_at: now,
+            waited_seconds,
+        })
+        .await?;
+
+    let tasks = TaskRepository::new(db.clone());
+    let task = find_task(db, gate.task_id).await?;
+    if task.status == WAITING_FOR_HUMAN {
+        tasks.set_status(gate.task_id, &resume_status, false).await?;
+    }
+
+    let events = DomainEventRepository::new(db.clone());
+    let version = events.get_latest_version(gate.task_id).await? + 1;
+    events
+        .create(CreateDomainEventData {
+            aggregate_type: AggregateType::Task.to_string(),
+            aggregate_id: gate.task_id,
+            event_type: DomainEventType::HumanGateDecided.to_string(),
+            event_data: json!({
+                "task_id": gate.task_id,
+                "project_id": task.project_id,
+                "gate_id": gate.gate_id,
+                "gate_name": gate.name,
+                "decision": status.as_str(),
+                "decided_by": user_id,
+                "comment": comment,
+                "waited_seconds": waited_seconds,
+            }),
+            event_version: version,
+        })
+        .await?;
+
+    tracing::info!(
+        "用户 {} {}了任务 {} 的检查点「{}」，等待 {} 秒",
+        user_id,
+        if approved { "批准" } else { "驳回" },
+        gate.task_id,
+        gate.name,
+        waited_seconds
+    );
+    Ok(gate)
+}
+
+/// 任务在各检查点上的等待时间线
+pub async fn gate_timeline(db: &DatabaseConnection, task_id: Uuid) -> Result<GateTimeline> {
+    find_task(db, task_id).await?;
+    let gates = TaskGateRepository::new(db.clone()).find_by_task(task_id).await?;
+    let now = Utc::now();
+
+    let entries: Vec<GateTimelineEntry> = gates.into_iter().map(|gate| timeline_entry(gate, now)).collect();
+    Ok(GateTimeline {
+        task_id,
+        total_waiting_seconds: entries.iter().map(|entry| entry.waiting_seconds).sum(),
+        waiting_gate_id: entries
+            .iter()
+            .find(|entry| entry.status == GateStatus::Waiting)
+            .map(|entry| entry.gate_id),
+        gates: entries,
+    })
+}
+
+/// 所有正在等待人工决策的检查点，等待最久的在前
+pub async fn waiting_gates(db: &DatabaseConnection) -> Result<Vec<GateTimelineEntry>> {
+    let now = Utc::now();
+    Ok(TaskGateRepository::new(db.clone())
+        .find_waiting()
+        .await?
+        .into_iter()
+        .map(|gate| timeline_entry(gate, now))
+        .collect())
+}
+
+/// 任务完成前检查所有检查点是否已批准
+pub(crate) async fn ensure_gates_approved(db: &DatabaseConnection, task: &task::Model) -> Result<()> {
+    let gates = TaskGateRepository::new(db.clone()).find_by_task(task.task_id).await?;
+    let unapproved: Vec<&str> = gates
+        .iter()
+        .filter(|gate| gate.status != GateStatus::Approved.as_str())
+        .map(|gate| gate.name.as_str())
+        .collect();
+    if unapproved.is_empty() {
+        Ok(())
+    } else {
+        Err(DatabaseError::business_logic(format!(
+            "任务还有未批准的人工检查点：{}",
+            unapproved.join("、")
+        )))
+    }
+}
+
+fn timeline_entry(gate: task_gate::Model, now: DateTime<Utc>) -> GateTimelineEntry {
+    let status = gate.status.parse().unwrap_or(GateStatus::Pending);
+    let current_wait = match (status, gate.requested_at) {
+        (GateStatus::Waiting, Some(requested_at)) => (now - requested_at.with_timezone(&Utc)).num_seconds().max(0),
+        _ => 0,
+    };
+    GateTimelineEntry {
+        gate_id: gate.gate_id,
+        name: gate.name,
+        description: gate.description,
+        sequence: gate.sequence,
+        status,
+        requested_at: gate.requested_at.map(|time| time.with_timezone(&Utc)),
+        decided_at: gate.decided_at.map(|time| time.with_timezone(&Utc)),
+        decided_by: gate.decided_by,
+        comment: gate.comment,
+        request_count: gate.request_count,
+        waiting_seconds: gate.waiting_seconds + current_wait,
+    }
+}
+
+async fn find_task(db: &DatabaseConnection, task_id: Uuid) -> Result<task::Model> {
+    TaskRepository::new(db.clone())
+        .find_by_id(task_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Task", task_id))
+}
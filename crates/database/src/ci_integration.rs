@@ -0,0 +1,371 @@
+//! CI 流水线集成
+//!
+//! 接收 GitHub Actions / GitLab CI 的状态 webhook，把流水线运行映射到执行会话和代码审查：
+//! - [`parse_github_webhook`] / [`parse_gitlab_webhook`] 把 webhook 载荷转换为统一的 [`CiCheckUpdate`]；
+//! - [`ingest_check`] 按提交或分支找到对应的执行会话和审查，写入 `ci_check_runs`；
+//! - [`session_ci_status`] / [`task_ci_status`] / [`review_ci_status`] 汇总 CI 状态；
+//! - [`ensure_review_can_be_approved`] 在项目质量门禁要求所有检查通过时，阻止 CI 为红色的审查被批准；
+//!   门禁开启不稳定测试隔离且失败的测试全部是已知不稳定测试时改为警告。
+
+use chrono::{DateTime, FixedOffset};
+use codex_multi_agent::CiStatus;
+use hmac::{Hmac, Mac};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::Sha256;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::{
+    entities::{ci_check_run, code_review, execution_session},
+    flaky_tests::{self, FlakyTestConfig, TestGateVerdict},
+    repository::{
+        ci_check_run_repository::UpsertCiCheckRunData, CiCheckRunRepository, OrganizationRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// CI 提供方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CiProvider {
+    /// GitHub Actions
+    GithubActions,
+    /// GitLab CI
+    GitlabCi,
+}
+
+impl CiProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CiProvider::GithubActions => "github_actions",
+            CiProvider::GitlabCi => "gitlab_ci",
+        }
+    }
+}
+
+/// 从 webhook 解析出的检查状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CiCheckUpdate {
+    pub provider: CiProvider,
+    /// 提供方内的唯一标识
+    pub external_id: String,
+    pub name: String,
+    pub commit_sha: String,
+    pub branch: Option<String>,
+    pub pull_request_url: Option<String>,
+    pub status: CiStatus,
+    pub details_url: Option<String>,
+    pub started_at: Option<DateTime<FixedOffset>>,
+    pub completed_at: Option<DateTime<FixedOffset>>,
+}
+
+/// 校验 GitHub webhook 的 `X-Hub-Signature-256` 签名
+pub fn verify_github_signature(secret: &str, body: &[u8], signature: &str) -> Result<()> {
+    let hex = signature
+        .strip_prefix("sha256=")
+        .ok_or_else(|| DatabaseError::validation("webhook 签名格式无效"))?;
+    let expected = (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| DatabaseError::validation("webhook 签名格式无效"))?;
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|e| DatabaseError::validation(format!("webhook 密钥无效: {}", e)))?;
+    mac.update(body);
+    mac.verify_slice(&expected)
+        .map_err(|_| DatabaseError::validation("webhook 签名不匹配"))
+}
+
+/// 校验 GitLab webhook 的 `X-Gitlab-Token`
+pub fn verify_gitlab_token(secret: &str, token: &str) -> Result<()> {
+    // 逐字节比较全部内容，避免提前返回泄露匹配长度
+    let matches = secret.len() == token.len()
+        && secret.bytes().zip(token.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0;
+    if matches {
+        Ok(())
+    } else {
+        Err(DatabaseError::validation("webhook 令牌不匹配"))
+    }
+}
+
+/// 解析 GitHub webhook，支持 `workflow_run` 和 `check_run` 事件，其余事件返回 None
+pub fn parse_github_webhook(event: &str, payload: &JsonValue) -> Result<Option<CiCheckUpdate>> {
+    let (kind, run) = match event {
+        "workflow_run" => ("workflow_run", &payload["workflow_run"]),
+        "check_run" => ("check_run", &payload["check_run"]),
+        _ => return Ok(None),
+    };
+    let id = run["id"]
+        .as_u64()
+        .ok_or_else(|| DatabaseError::validation(format!("{} 事件缺少 id", event)))?;
+    let commit_sha = required_str(run, "head_sha", event)?;
+
+    let status = match run["status"].as_str().unwrap_or_default() {
+        "completed" => match run["conclusion"].as_str().unwrap_or_default() {
+            "success" | "neutral" | "skipped" => CiStatus::Passed,
+            "cancelled" => CiStatus::Cancelled,
+            _ => CiStatus::Failed,
+        },
+        "in_progress" => CiStatus::Running,
+        _ => CiStatus::Pending,
+    };
+
+    let branch = run["head_branch"]
+        .as_str()
+        .or_else(|| run["check_suite"]["head_branch"].as_str())
+        .map(str::to_string);
+    let pull_request_url = run["pull_requests"]
+        .as_array()
+        .and_then(|prs| prs.first())
+        .and_then(|pr| pr["number"].as_u64())
+        .and_then(|number| {
+            payload["repository"]["html_url"]
+                .as_str()
+                .map(|repo| format!("{}/pull/{}", repo, number))
+        });
+
+    Ok(Some(CiCheckUpdate {
+        provider: CiProvider::GithubActions,
+        external_id: format!("{}:{}", kind, id),
+        name: run["name"].as_str().unwrap_or(kind).to_string(),
+        commit_sha,
+        branch,
+        pull_request_url,
+        status,
+        details_url: run["html_url"].as_str().or_else(|| run["details_url"].as_str()).map(str::to_string),
+        started_at: timestamp(&run["run_started_at"]).or_else(|| timestamp(&run["started_at"])),
+        completed_at: if status == CiStatus::Running || status == CiStatus::Pending {
+            None
+        } else {
+            timestamp(&run["completed_at"]).or_else(|| timestamp(&run["updated_at"]))
+        },
+    }))
+}
+
+/// 解析 GitLab webhook，支持 Pipeline Hook 和 Job Hook，其余事件返回 None
+pub fn parse_gitlab_webhook(payload: &JsonValue) -> Result<Option<CiCheckUpdate>> {
+    let map_status = |status: &str| match status {
+        "success" | "skipped" => CiStatus::Passed,
+        "failed" => CiStatus::Failed,
+        "canceled" | "cancelled" => CiStatus::Cancelled,
+        "running" => CiStatus::Running,
+        _ => CiStatus::Pending,
+    };
+
+    match payload["object_kind"].as_str() {
+        Some("pipeline") => {
+            let attributes = &payload["object_attributes"];
+            let id = attributes["id"]
+                .as_u64()
+                .ok_or_else(|| DatabaseError::validation("pipeline 事件缺少 id"))?;
+            Ok(Some(CiCheckUpdate {
+                provider: CiProvider::GitlabCi,
+                external_id: format!("pipeline:{}", id),
+                name: attributes["name"].as_str().unwrap_or("pipeline").to_string(),
+                commit_sha: required_str(attributes, "sha", "pipeline")?,
+                branch: attributes["ref"].as_str().map(str::to_string),
+                pull_request_url: payload["merge_request"]["url"].as_str().map(str::to_string),
+                status: map_status(attributes["status"].as_str().unwrap_or_default()),
+                details_url: attributes["url"].as_str().map(str::to_string),
+                started_at: timestamp(&attributes["created_at"]),
+                completed_at: timestamp(&attributes["finished_at"]),
+            }))
+        }
+        Some("build") => {
+            let id = payload["build_id"]
+                .as_u64()
+                .ok_or_else(|| DatabaseError::validation("job 事件缺少 build_id"))?;
+            let details_url = payload["repository"]["homepage"]
+                .as_str()
+                .map(|homepage| format!("{}/-/jobs/{}", homepage, id));
+            Ok(Some(CiCheckUpdate {
+                provider: CiProvider::GitlabCi,
+                external_id: format!("job:{}", id),
+                name: payload["build_name"].as_str().unwrap_or("job").to_string(),
+                commit_sha: required_str(payload, "sha", "job")?,
+                branch: payload["ref"].as_str().map(str::to_string),
+                pull_request_url: None,
+                status: map_status(payload["build_status"].as_str().unwrap_or_default()),
+                details_url,
+                started_at: timestamp(&payload["build_started_at"]),
+                completed_at: timestamp(&payload["build_finished_at"]),
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn required_str(value: &JsonValue, key: &str, event: &str) -> Result<String> {
+    value[key]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| DatabaseError::validation(format!("{} 事件缺少 {}", event, key)))
+}
+
+/// 解析 RFC 3339 或 GitLab 的 `2024-01-01 10:00:00 UTC` 格式时间
+fn timestamp(value: &JsonValue) -> Option<DateTime<FixedOffset>> {
+    let text = value.as_str()?;
+    DateTime::parse_from_rfc3339(text)
+        .or_else(|_| DateTime::parse_from_str(&text.replace(" UTC", " +0000"), "%Y-%m-%d %H:%M:%S %z"))
+        .ok()
+}
+
+/// 写入检查结果，并映射到执行会话和代码审查
+///
+/// 优先按提交匹配执行会话的最终提交，其次匹配同一分支最近的执行会话；
+/// 审查优先按 PR 地址匹配，其次取执行会话最近的审查
+pub async fn ingest_check(db: &DatabaseConnection, project_id: Uuid, update: CiCheckUpdate) -> Result<ci_check_run::Model> {
+    let session = find_session(db, project_id, &update).await?;
+
+    let mut review = None;
+    if let Some(url) = &update.pull_request_url {
+        review = code_review::Entity::find()
+            .filter(code_review::Column::PullRequestUrl.eq(url.as_str()))
+            .order_by_desc(code_review::Column::CreatedAt)
+            .one(db)
+            .await?;
+    }
+    if review.is_none() {
+        if let Some(session) = &session {
+            review = code_review::Entity::find()
+                .filter(code_review::Column::ExecutionSessionId.eq(session.session_id))
+                .order_by_desc(code_review::Column::CreatedAt)
+                .one(db)
+                .await?;
+        }
+    }
+
+    let execution_session_id = session
+        .map(|session| session.session_id)
+        .or_else(|| review.as_ref().map(|review| review.execution_session_id));
+
+    CiCheckRunRepository::new(db.clone())
+        .upsert(UpsertCiCheckRunData {
+            project_id,
+            provider: update.provider.as_str().to_string(),
+            external_id: update.external_id,
+            name: update.name,
+            commit_sha: update.commit_sha,
+            branch: update.branch,
+            pull_request_url: update.pull_request_url,
+            execution_session_id,
+            review_id: review.map(|review| review.review_id),
+            status: update.status.to_string(),
+            details_url: update.details_url,
+            started_at: update.started_at,
+            completed_at: update.completed_at,
+        })
+        .await
+}
+
+async fn find_session(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    update: &CiCheckUpdate,
+) -> Result<Option<execution_session::Model>> {
+    let by_commit = execution_session::Entity::find()
+        .filter(execution_session::Column::ProjectId.eq(project_id))
+        .filter(execution_session::Column::FinalCommit.eq(update.commit_sha.as_str()))
+        .order_by_desc(execution_session::Column::CreatedAt)
+        .one(db)
+        .await?;
+    if by_commit.is_some() {
+        return Ok(by_commit);
+    }
+
+    match &update.branch {
+        Some(branch) => Ok(execution_session::Entity::find()
+            .filter(execution_session::Column::ProjectId.eq(project_id))
+            .filter(execution_session::Column::GitBranch.eq(branch.as_str()))
+            .order_by_desc(execution_session::Column::CreatedAt)
+            .one(db)
+            .await?),
+        None => Ok(None),
+    }
+}
+
+/// 汇总一组检查结果的状态
+///
+/// 只看最近一次更新所在的提交；同一检查多次运行时以最新一次为准
+pub fn summarize(runs: &[ci_check_run::Model]) -> Option<CiStatus> {
+    let latest_commit = runs.iter().max_by_key(|run| run.updated_at)?.commit_sha.clone();
+    let mut runs: Vec<_> = runs.iter().filter(|run| run.commit_sha == latest_commit).collect();
+    runs.sort_by_key(|run| std::cmp::Reverse(run.updated_at));
+
+    let mut seen = HashSet::new();
+    CiStatus::combine(
+        runs.into_iter()
+            .filter(|run| seen.insert((run.provider.as_str(), run.name.as_str())))
+            .map(|run| CiStatus::from(run.status.clone())),
+    )
+}
+
+/// 执行会话的 CI 状态，尚无检查结果时返回 None
+pub async fn session_ci_status(db: &DatabaseConnection, session_id: Uuid) -> Result<Option<CiStatus>> {
+    let runs = CiCheckRunRepository::new(db.clone()).find_by_session(session_id).await?;
+    Ok(summarize(&runs))
+}
+
+/// 任务的 CI 状态：取最近一次有检查结果的执行会话
+pub async fn task_ci_status(db: &DatabaseConnection, task_id: Uuid) -> Result<Option<CiStatus>> {
+    let sessions = execution_session::Entity::find()
+        .filter(execution_session::Column::TaskId.eq(task_id))
+        .order_by_desc(execution_session::Column::CreatedAt)
+        .all(db)
+        .await?;
+    for session in sessions {
+        if let Some(status) = session_ci_status(db, session.session_id).await? {
+            return Ok(Some(status));
+        }
+    }
+    Ok(None)
+}
+
+/// 代码审查的 CI 状态：直接关联到审查的检查优先，其次是审查所属执行会话的检查
+pub async fn review_ci_status(db: &DatabaseConnection, review: &code_review::Model) -> Result<Option<CiStatus>> {
+    let repo = CiCheckRunRepository::new(db.clone());
+    let mut runs = repo.find_by_review(review.review_id).await?;
+    if runs.is_empty() {
+        runs = repo.find_by_session(review.execution_session_id).await?;
+    }
+    Ok(summarize(&runs))
+}
+
+/// 检查审查能否被批准：项目质量门禁要求所有检查通过时，CI 为红色的审查不能批准
+pub async fn ensure_review_can_be_approved(db: &DatabaseConnection, review: &code_review::Model) -> Result<()> {
+    let Some(status) = review_ci_status(db, review).await? else {
+        return Ok(());
+    };
+    if !status.is_red() {
+        return Ok(());
+    }
+
+    let session = execution_session::Entity::find_by_id(review.execution_session_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", review.execution_session_id))?;
+    let standards = OrganizationRepository::new(db.clone())
+        .resolve_project_standards(session.project_id)
+        .await?;
+    if standards.quality_gates.require_all_checks_pass {
+        // 失败的测试全部是被隔离的不稳定测试时只给出警告
+        let test_gate = flaky_tests::evaluate_session_tests(db, session.session_id, &FlakyTestConfig::default()).await?;
+        if let Some(result) = test_gate.filter(|result| result.verdict == TestGateVerdict::Warning) {
+            tracing::warn!(
+                "审查 {} 的 CI 失败仅来自不稳定测试 {:?}，质量门禁按隔离规则放行",
+                review.review_id,
+                result.quarantined_failures
+            );
+            return Ok(());
+        }
+        return Err(DatabaseError::business_logic(format!(
+            "CI 状态为 {}，质量门禁要求所有检查通过后才能批准审查",
+            status
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,374 @@
+//! 代码归属（CODEOWNERS 风格）
+//!
+//! 每个项目保存一组「路径模式 → 负责人/能力」规则，规则语义与 CODEOWNERS 一致：
+//! - 模式以 `/` 开头时相对仓库根目录匹配，否则在任意层级匹配；以 `/` 结尾只匹配目录；
+//! - `*` 匹配单层路径中的任意字符，`**` 匹配任意层级；
+//! - 同一文件命中多条规则时以最后一条为准。
+//!
+//! 归属规则有两个用途：
+//! - [`reviewer_candidates`] 为代码审查挑选审查者时，先考虑变更文件的负责人，
+//!   以及具备规则所需全部能力的审查员Agent；
+//! - [`annotate_simulation_agents`] 把Agent修改过的文件交给调度模拟，
+//!   任务相关文件落在其中时优先分配给该Agent。
+
+use codex_multi_agent::{AgentCapability, ProjectRole, SimulationAgent};
+use regex::Regex;
+use sea_orm::EntityTrait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        agent, code_review,
+        execution_session::ExecutionStatus,
+        project,
+    },
+    repository::{
+        AgentRepository, CodeReviewRepository, ExecutionSessionRepository, ProjectMemberRepository,
+        TaskRepository,
+    },
+    review_sla::Reviewer,
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 一条归属规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OwnershipRule {
+    /// 路径模式
+    pub pattern: String,
+    /// 负责人：Agent或项目成员
+    #[serde(default)]
+    pub owners: Vec<Reviewer>,
+    /// 审查这些路径需要的Agent能力
+    #[serde(default)]
+    pub capabilities: Vec<AgentCapability>,
+}
+
+/// 项目的代码归属规则
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CodeOwnership {
+    pub rules: Vec<OwnershipRule>,
+}
+
+/// 一组文件的归属结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OwnershipMatch {
+    /// 负责人，负责的文件多的在前
+    pub owners: Vec<Reviewer>,
+    /// 命中规则要求的全部能力
+    pub capabilities: Vec<AgentCapability>,
+    /// 没有命中任何规则的文件
+    pub unowned_files: Vec<String>,
+}
+
+impl CodeOwnership {
+    /// 校验规则
+    pub fn validate(&self) -> Result<()> {
+        for rule in &self.rules {
+            compile_pattern(&rule.pattern)?;
+            if rule.owners.is_empty() && rule.capabilities.is_empty() {
+                return Err(DatabaseError::validation(format!(
+                    "归属规则 {} 至少需要一个负责人或能力",
+                    rule.pattern
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// 解析 CODEOWNERS 格式的文本
+    ///
+    /// 每行为一个路径模式加若干负责人，负责人写作 `agent:<ID>`、`user:<ID>` 或 `capability:<能力>`，
+    /// `#` 开头的行为注释。
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut tokens = line.split_whitespace();
+            let pattern = tokens.next().unwrap_or_default().to_string();
+            let mut rule = OwnershipRule { pattern, owners: Vec::new(), capabilities: Vec::new() };
+            for token in tokens {
+                let invalid = || DatabaseError::validation(format!("第 {} 行的负责人无法识别: {}", index + 1, token));
+                let (kind, value) = token.split_once(':').ok_or_else(invalid)?;
+                match kind {
+                    "agent" => rule.owners.push(Reviewer::Agent(Uuid::parse_str(value).map_err(|_| invalid())?)),
+                    "user" => rule.owners.push(Reviewer::User(Uuid::parse_str(value).map_err(|_| invalid())?)),
+                    "capability" => rule.capabilities.push(
+                        serde_json::from_value(serde_json::Value::String(value.to_string())).map_err(|_| invalid())?,
+                    ),
+                    _ => return Err(invalid()),
+                }
+            }
+            rules.push(rule);
+        }
+
+        let ownership = Self { rules };
+        ownership.validate()?;
+        Ok(ownership)
+    }
+
+    /// 文件命中的规则，多条命中时取最后一条
+    pub fn rule_for(&self, path: &str) -> Option<&OwnershipRule> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| compile_pattern(&rule.pattern).is_ok_and(|pattern| pattern.is_match(normalize_path(path))))
+    }
+
+    /// 计算一组文件的负责人和所需能力
+    pub fn resolve(&self, files: &[String]) -> OwnershipMatch {
+        let mut result = OwnershipMatch::default();
+        let mut owned: Vec<(Reviewer, usize)> = Vec::new();
+        for file in files {
+            let Some(rule) = self.rule_for(file) else {
+                result.unowned_files.push(file.clone());
+                continue;
+            };
+            for owner in &rule.owners {
+                match owned.iter_mut().find(|(existing, _)| existing == owner) {
+                    Some((_, count)) => *count += 1,
+                    None => owned.push((*owner, 1)),
+                }
+            }
+            for capability in &rule.capabilities {
+                if !result.capabilities.contains(capability) {
+                    result.capabilities.push(capability.clone());
+                }
+            }
+        }
+        // 稳定排序，负责文件数相同时保持规则中的顺序
+        owned.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        result.owners = owned.into_iter().map(|(owner, _)| owner).collect();
+        result
+    }
+}
+
+fn normalize_path(path: &str) -> &str {
+    path.trim_start_matches("./").trim_start_matches('/')
+}
+
+/// 把路径模式转换为正则表达式
+fn compile_pattern(pattern: &str) -> Result<Regex> {
+    let trimmed = pattern.trim();
+    if trimmed.is_empty() || trimmed == "/" {
+        return Err(DatabaseError::validation("归属规则的路径模式不能为空"));
+    }
+
+    let directory_only = trimmed.ends_with('/');
+    let body = trimmed.trim_end_matches('/');
+    let anchored = body.starts_with('/') || body.contains('/');
+    let body = body.trim_start_matches('/');
+
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    // 目录模式只匹配其下的文件；其他模式既匹配同名文件，也匹配同名目录下的文件
+    regex.push_str(if directory_only { "/.*$" } else { "(?:/.*)?$" });
+
+    Regex::new(&regex).map_err(|e| DatabaseError::validation(format!("无效的路径模式 {}: {}", pattern, e)))
+}
+
+/// 读取项目的代码归属规则，未配置时返回 None
+pub async fn project_ownership(db: &DatabaseConnection, project_id: Uuid) -> Result<Option<CodeOwnership>> {
+    let project = project::Entity::find_by_id(project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+    ownership_of(&project)
+}
+
+fn ownership_of(project: &project::Model) -> Result<Option<CodeOwnership>> {
+    match &project.code_ownership {
+        Some(value) => Ok(Some(serde_json::from_value(value.clone())?)),
+        None => Ok(None),
+    }
+}
+
+/// 代码审查涉及的文件
+pub fn review_files(review: &code_review::Model) -> Vec<String> {
+    review
+        .code_changes
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|change| change.get("file_path").and_then(|path| path.as_str()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 按优先顺序分组的候选审查者
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReviewerCandidates {
+    /// 变更文件的负责人，以及具备归属规则所需全部能力的审查员Agent
+    pub owners: Vec<Reviewer>,
+    /// 其他具备代码审查能力且在线的Agent
+    pub agents: Vec<Reviewer>,
+    /// 项目的所有者和维护者
+    pub users: Vec<Reviewer>,
+}
+
+impl ReviewerCandidates {
+    /// 按优先顺序排列的全部候选审查者
+    pub fn ordered(&self) -> Vec<Reviewer> {
+        let mut ordered = self.owners.clone();
+        for reviewer in self.agents.iter().chain(&self.users) {
+            if !ordered.contains(reviewer) {
+                ordered.push(*reviewer);
+            }
+        }
+        ordered
+    }
+}
+
+/// 为修改了指定文件的变更挑选候选审查者，不包括提交变更的Agent
+pub async fn reviewer_candidates(
+    db: &DatabaseConnection,
+    project: &project::Model,
+    files: &[String],
+    author_agent_id: Option<Uuid>,
+) -> Result<ReviewerCandidates> {
+    let ownership = ownership_of(project)?.unwrap_or_default().resolve(files);
+    let members = ProjectMemberRepository::new(db.clone());
+    let agents = AgentRepository::new(db.clone());
+
+    let mut owners_of_agents = vec![project.user_id];
+    for member in members.list_members(project.project_id).await? {
+        if !owners_of_agents.contains(&member.user_id) {
+            owners_of_agents.push(member.user_id);
+        }
+    }
+    let review_capability = AgentCapability::CodeReview;
+    let mut reviewer_agents = Vec::new();
+    for user_id in owners_of_agents {
+        for agent in agents.find_by_user_id(user_id).await? {
+            if is_available(&agent) && Some(agent.agent_id) != author_agent_id && has_capability(&agent, &review_capability) {
+                reviewer_agents.push(agent);
+            }
+        }
+    }
+
+    let mut candidates = ReviewerCandidates::default();
+    for owner in &ownership.owners {
+        let eligible = match owner {
+            Reviewer::Agent(agent_id) => Some(*agent_id) != author_agent_id
+                && agents.find_by_id(*agent_id).await?.is_some_and(|agent| is_available(&agent)),
+            Reviewer::User(user_id) => members
+                .get_role(project.project_id, *user_id)
+                .await?
+                .is_some_and(|role| role.at_least(ProjectRole::Contributor)),
+        };
+        if eligible {
+            candidates.owners.push(*owner);
+        }
+    }
+    for agent in &reviewer_agents {
+        let reviewer = Reviewer::Agent(agent.agent_id);
+        let qualified = !ownership.capabilities.is_empty()
+            && ownership.capabilities.iter().all(|capability| has_capability(agent, capability));
+        if candidates.owners.contains(&reviewer) {
+            continue;
+        }
+        if qualified {
+            candidates.owners.push(reviewer);
+        } else {
+            candidates.agents.push(reviewer);
+        }
+    }
+
+    let project_members = members.list_members(project.project_id).await?;
+    candidates.users = if project_members.is_empty() {
+        vec![Reviewer::User(project.user_id)]
+    } else {
+        project_members
+            .into_iter()
+            .filter(|member| {
+                member
+                    .role
+                    .parse::<ProjectRole>()
+                    .is_ok_and(|role| role.at_least(ProjectRole::Maintainer))
+            })
+            .map(|member| Reviewer::User(member.user_id))
+            .collect()
+    };
+    Ok(candidates)
+}
+
+/// 按优先顺序为修改了指定文件的变更推荐审查者
+pub async fn suggest_reviewers(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    files: &[String],
+    author_agent_id: Option<Uuid>,
+) -> Result<Vec<Reviewer>> {
+    let project = project::Entity::find_by_id(project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+    Ok(reviewer_candidates(db, &project, files, author_agent_id).await?.ordered())
+}
+
+fn is_available(agent: &agent::Model) -> bool {
+    !matches!(agent.status.as_str(), "offline" | "error" | "maintenance")
+}
+
+fn has_capability(agent: &agent::Model, capability: &AgentCapability) -> bool {
+    let Ok(capability) = serde_json::to_value(capability) else {
+        return false;
+    };
+    agent
+        .capabilities
+        .as_array()
+        .is_some_and(|capabilities| capabilities.contains(&capability))
+}
+
+/// Agent成功完成的执行会话中修改过的文件和负责过的目录
+pub async fn agent_path_history(db: &DatabaseConnection, agent_id: Uuid) -> Result<Vec<String>> {
+    let reviews = CodeReviewRepository::new(db.clone());
+    let tasks = TaskRepository::new(db.clone());
+    let mut paths = Vec::new();
+    let mut push = |path: String| {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    };
+
+    for session in ExecutionSessionRepository::new(db.clone()).find_by_agent_id(agent_id).await? {
+        if session.status != ExecutionStatus::Completed.to_string() {
+            continue;
+        }
+        for review in reviews.find_by_execution_session_id(session.session_id).await? {
+            review_files(&review).into_iter().for_each(&mut push);
+        }
+        if let Some(scope_path) = tasks.find_by_id(session.task_id).await?.and_then(|task| task.scope_path) {
+            push(scope_path);
+        }
+    }
+    Ok(paths)
+}
+
+/// 为调度模拟中的Agent填入修改过的路径
+pub async fn annotate_simulation_agents(db: &DatabaseConnection, agents: &mut [SimulationAgent]) -> Result<()> {
+    for agent in agents.iter_mut() {
+        agent.familiar_paths = agent_path_history(db, agent.agent_id.0).await?;
+    }
+    Ok(())
+}
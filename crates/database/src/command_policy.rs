@@ -0,0 +1,707 @@
+//! 命令策略引擎
+//!
+//! Agent 提出的 shell 命令在执行前由 [`CommandPolicyEngine`] 分类：
+//! - 正则规则（[`CommandRule`]）按动作分为允许、上报和拒绝；
+//! - 语义规则识别写入工作空间之外、写入任务工作范围之外、发布软件包和强制推送；
+//! - 请求携带分支保护配置时，违反分支保护的 git 命令由 [`crate::git_ops`] 识别并拒绝；
+//! - 绑定了 Agent 审批策略时，策略的判定同样作为一条规则参与。
+//!
+//! 引擎据此给出风险等级 [`RiskLevel`] 和判定 [`CommandDecision`]：
+//! 命中拒绝规则时拒绝执行；命中上报规则、风险超过自动批准上限或存在未被允许的子命令时
+//! 上报人工确认；其余自动批准。[`CommandPolicyEngine::evaluate`] 把每次判定写入命令审批审计日志，
+//! 违反分支保护时还为项目记录一条安全错误事件。
+
+use codex_multi_agent::{AgentApprovalPolicy, CommandApproval};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use uuid::Uuid;
+
+use crate::{
+    git_ops::{self, BranchGuard, BranchProtection, BranchViolation},
+    repository::{command_audit_log_repository::CreateCommandAuditData, CommandAuditLogRepository},
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 语义规则：写入工作空间之外
+pub const RULE_WRITE_OUTSIDE_WORKSPACE: &str = "write_outside_workspace";
+
+/// 语义规则：写入任务工作范围之外
+pub const RULE_WRITE_OUTSIDE_SCOPE: &str = "write_outside_scope";
+
+/// 语义规则：发布软件包
+pub const RULE_PACKAGE_PUBLISH: &str = "package_publish";
+
+/// 语义规则：强制推送
+pub const RULE_FORCE_PUSH: &str = "force_push";
+
+/// 语义规则：违反分支保护
+pub const RULE_PROTECTED_BRANCH: &str = "protected_branch";
+
+/// 绑定的 Agent 审批策略
+pub const RULE_AGENT_APPROVAL_POLICY: &str = "agent_approval_policy";
+
+/// 发布软件包的命令前缀
+const PUBLISH_COMMAND_PREFIXES: &[&str] = &[
+    "npm publish",
+    "pnpm publish",
+    "yarn publish",
+    "yarn npm publish",
+    "cargo publish",
+    "twine upload",
+    "python -m twine upload",
+    "python3 -m twine upload",
+    "poetry publish",
+    "flit publish",
+    "gem push",
+    "dotnet nuget push",
+    "mvn deploy",
+    "docker push",
+];
+
+/// 命令前的包装程序，判断时跳过
+const COMMAND_WRAPPERS: &[&str] = &["sudo", "env", "command", "nohup", "time", "exec"];
+
+/// 风险等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl RiskLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskLevel::Low => "low",
+            RiskLevel::Medium => "medium",
+            RiskLevel::High => "high",
+            RiskLevel::Critical => "critical",
+        }
+    }
+}
+
+/// 规则命中后的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    /// 允许自动批准
+    Allow,
+    /// 上报人工确认
+    Escalate,
+    /// 拒绝执行
+    Deny,
+}
+
+/// 正则规则
+///
+/// 允许规则逐个匹配串联命令中的子命令，上报和拒绝规则匹配完整脚本。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandRule {
+    /// 规则名称，记录在审计日志中
+    pub name: String,
+    /// 正则表达式
+    pub pattern: String,
+    pub action: RuleAction,
+    /// 命中时的风险等级
+    pub risk: RiskLevel,
+    /// 说明
+    pub reason: String,
+}
+
+impl CommandRule {
+    /// 允许规则
+    pub fn allow(name: &str, pattern: &str, reason: &str) -> Self {
+        Self::new(name, pattern, RuleAction::Allow, RiskLevel::Low, reason)
+    }
+
+    /// 上报规则
+    pub fn escalate(name: &str, pattern: &str, risk: RiskLevel, reason: &str) -> Self {
+        Self::new(name, pattern, RuleAction::Escalate, risk, reason)
+    }
+
+    /// 拒绝规则
+    pub fn deny(name: &str, pattern: &str, reason: &str) -> Self {
+        Self::new(name, pattern, RuleAction::Deny, RiskLevel::Critical, reason)
+    }
+
+    fn new(name: &str, pattern: &str, action: RuleAction, risk: RiskLevel, reason: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            action,
+            risk,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// 命令策略配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandPolicy {
+    /// 正则规则
+    #[serde(default)]
+    pub rules: Vec<CommandRule>,
+    /// 未命中上报规则时，风险不超过该等级的命令自动批准
+    #[serde(default = "default_auto_approve_max_risk")]
+    pub auto_approve_max_risk: RiskLevel,
+}
+
+fn default_auto_approve_max_risk() -> RiskLevel {
+    RiskLevel::Low
+}
+
+impl Default for CommandPolicy {
+    /// 默认策略：拒绝明显破坏性的命令，其余命令需要被允许后才能自动批准
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                CommandRule::deny(
+                    "recursive_delete_root",
+                    r"\brm\s+(-\S+\s+)*-[a-zA-Z]*[rR][a-zA-Z]*\s+(-\S+\s+)*(/|~/?|\$HOME/?)\*?(\s|$)",
+                    "递归删除根目录或用户目录",
+                ),
+                CommandRule::deny("filesystem_format", r"\bmkfs(\.\w+)?\b", "格式化文件系统"),
+                CommandRule::deny(
+                    "raw_disk_write",
+                    r"\bdd\b.*\bof=/dev/(sd|hd|nvme|disk|mmcblk)",
+                    "直接写入磁盘设备",
+                ),
+                CommandRule::deny("fork_bomb", r":\(\)\s*\{\s*:\s*\|\s*:\s*&\s*\}\s*;\s*:", "fork 炸弹"),
+                CommandRule::escalate(
+                    "pipe_to_shell",
+                    r"\b(curl|wget)\b[^|]*\|\s*(sudo\s+)?(ba|z)?sh\b",
+                    RiskLevel::High,
+                    "下载脚本并直接执行",
+                ),
+            ],
+            auto_approve_max_risk: default_auto_approve_max_risk(),
+        }
+    }
+}
+
+/// 判定结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandDecision {
+    AutoApprove,
+    Escalate,
+    Deny,
+}
+
+impl CommandDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommandDecision::AutoApprove => "auto_approve",
+            CommandDecision::Escalate => "escalate",
+            CommandDecision::Deny => "deny",
+        }
+    }
+}
+
+/// 命中的规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleMatch {
+    pub rule: String,
+    pub action: RuleAction,
+    pub risk: RiskLevel,
+    pub reason: String,
+}
+
+/// 命令分类结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandClassification {
+    /// 命令文本
+    pub command: String,
+    pub risk: RiskLevel,
+    pub decision: CommandDecision,
+    pub matched_rules: Vec<RuleMatch>,
+    /// 审计记录ID，仅 [`CommandPolicyEngine::evaluate`] 返回
+    pub audit_id: Option<Uuid>,
+}
+
+impl CommandClassification {
+    /// 是否命中了指定规则
+    pub fn matched(&self, rule: &str) -> bool {
+        self.matched_rules.iter().any(|matched| matched.rule == rule)
+    }
+}
+
+/// 待判定的命令
+#[derive(Debug, Clone)]
+pub struct CommandRequest {
+    /// 命令参数列表，`bash -lc "<脚本>"` 形式按脚本内容判断
+    pub command: Vec<String>,
+    /// 命令的工作目录
+    pub working_dir: PathBuf,
+    /// 工作空间根目录，写入其外部的命令需要上报
+    pub workspace: PathBuf,
+    /// 任务的工作范围（相对工作空间根目录），写入工作空间内但范围之外的命令需要上报
+    pub scope_path: Option<String>,
+    pub project_id: Option<Uuid>,
+    pub agent_id: Option<Uuid>,
+    pub conversation_id: Option<String>,
+    /// 提出命令的 Agent 的审批策略
+    pub approval_policy: Option<AgentApprovalPolicy>,
+    /// 项目的分支保护配置，未设置时不检查 git 命令的目标分支
+    pub branch_protection: Option<BranchProtection>,
+    /// 工作目录当前检出的分支
+    pub current_branch: Option<String>,
+}
+
+impl CommandRequest {
+    /// 在工作空间根目录执行的命令
+    pub fn new(command: Vec<String>, workspace: impl Into<PathBuf>) -> Self {
+        let workspace = workspace.into();
+        Self {
+            command,
+            working_dir: workspace.clone(),
+            workspace,
+            scope_path: None,
+            project_id: None,
+            agent_id: None,
+            conversation_id: None,
+            approval_policy: None,
+            branch_protection: None,
+            current_branch: None,
+        }
+    }
+}
+
+/// 命令策略引擎
+pub struct CommandPolicyEngine {
+    db: DatabaseConnection,
+    rules: Vec<(CommandRule, Regex)>,
+    auto_approve_max_risk: RiskLevel,
+}
+
+impl CommandPolicyEngine {
+    /// 创建引擎，规则的正则表达式无效时返回验证错误
+    pub fn new(db: DatabaseConnection, policy: CommandPolicy) -> Result<Self> {
+        let rules = policy
+            .rules
+            .into_iter()
+            .map(|rule| {
+                let regex = Regex::new(&rule.pattern)
+                    .map_err(|e| DatabaseError::validation(format!("命令规则 {} 的正则表达式无效: {}", rule.name, e)))?;
+                Ok((rule, regex))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            db,
+            rules,
+            auto_approve_max_risk: policy.auto_approve_max_risk,
+        })
+    }
+
+    /// 对命令分类，不记录审计日志
+    pub fn classify(&self, request: &CommandRequest) -> CommandClassification {
+        self.classify_with_violations(request).0
+    }
+
+    /// 对命令分类，同时返回违反分支保护的 git 操作
+    fn classify_with_violations(&self, request: &CommandRequest) -> (CommandClassification, Vec<BranchViolation>) {
+        let script = command_script(&request.command);
+        let segments = split_segments(&script);
+        let mut matched_rules = Vec::new();
+
+        // 上报和拒绝规则匹配完整脚本
+        for (rule, regex) in &self.rules {
+            if rule.action != RuleAction::Allow && regex.is_match(&script) {
+                matched_rules.push(rule_match(rule));
+            }
+        }
+
+        // 允许规则要求每个子命令都被允许
+        let mut allowed = !segments.is_empty()
+            && segments.iter().all(|segment| {
+                let text = segment.join(" ");
+                self.rules
+                    .iter()
+                    .any(|(rule, regex)| rule.action == RuleAction::Allow && regex.is_match(&text))
+            });
+        if allowed {
+            for (rule, regex) in &self.rules {
+                if rule.action == RuleAction::Allow && segments.iter().any(|segment| regex.is_match(&segment.join(" "))) {
+                    matched_rules.push(rule_match(rule));
+                }
+            }
+        }
+
+        if let Some(policy) = &request.approval_policy {
+            if policy.evaluate_command(&request.command) == CommandApproval::AutoApprove {
+                allowed = !segments.is_empty();
+                matched_rules.push(semantic_match(
+                    RULE_AGENT_APPROVAL_POLICY,
+                    RuleAction::Allow,
+                    RiskLevel::Low,
+                    "Agent 审批策略允许自动批准".to_string(),
+                ));
+            } else {
+                matched_rules.push(semantic_match(
+                    RULE_AGENT_APPROVAL_POLICY,
+                    RuleAction::Escalate,
+                    RiskLevel::Medium,
+                    "Agent 审批策略要求人工确认".to_string(),
+                ));
+            }
+        }
+
+        let scope = request.scope_path.as_ref().map(|scope| request.workspace.join(scope));
+        matched_rules.extend(semantic_matches(&segments, &request.working_dir, &request.workspace, scope.as_deref()));
+        let violations = match &request.branch_protection {
+            Some(protection) => branch_violations(&segments, protection, request.current_branch.clone()),
+            None => Vec::new(),
+        };
+        matched_rules.extend(violations.iter().map(|violation| {
+            semantic_match(RULE_PROTECTED_BRANCH, RuleAction::Deny, RiskLevel::High, violation.reason.clone())
+        }));
+
+        let flagged = matched_rules.iter().filter(|matched| matched.action != RuleAction::Allow);
+        let baseline = if allowed { RiskLevel::Low } else { RiskLevel::Medium };
+        let risk = flagged.map(|matched| matched.risk).max().unwrap_or(baseline);
+
+        let decision = if matched_rules.iter().any(|matched| matched.action == RuleAction::Deny) {
+            CommandDecision::Deny
+        } else if segments.is_empty()
+            || matched_rules.iter().any(|matched| matched.action == RuleAction::Escalate)
+            || risk > self.auto_approve_max_risk
+        {
+            CommandDecision::Escalate
+        } else {
+            CommandDecision::AutoApprove
+        };
+
+        let classification = CommandClassification {
+            command: script,
+            risk,
+            decision,
+            matched_rules,
+            audit_id: None,
+        };
+        (classification, violations)
+    }
+
+    /// 对命令分类并记录审计日志
+    pub async fn evaluate(&self, request: &CommandRequest) -> Result<CommandClassification> {
+        let (mut classification, violations) = self.classify_with_violations(request);
+        let audit = CommandAuditLogRepository::new(self.db.clone())
+            .create(CreateCommandAuditData {
+                project_id: request.project_id,
+                agent_id: request.agent_id,
+                conversation_id: request.conversation_id.clone(),
+                command: classification.command.clone(),
+                working_dir: request.working_dir.to_string_lossy().to_string(),
+                risk_level: classification.risk.as_str().to_string(),
+                decision: classification.decision.as_str().to_string(),
+                matched_rules: serde_json::to_value(&classification.matched_rules)?,
+            })
+            .await?;
+
+        if classification.decision != CommandDecision::AutoApprove {
+            tracing::info!(
+                command = %classification.command,
+                risk = classification.risk.as_str(),
+                decision = classification.decision.as_str(),
+                "命令需要人工处理"
+            );
+        }
+        if let Some(project_id) = request.project_id {
+            for violation in &violations {
+                git_ops::record_violation(&self.db, project_id, audit.audit_id, violation).await?;
+            }
+        }
+        classification.audit_id = Some(audit.audit_id);
+        Ok(classification)
+    }
+}
+
+fn rule_match(rule: &CommandRule) -> RuleMatch {
+    RuleMatch {
+        rule: rule.name.clone(),
+        action: rule.action,
+        risk: rule.risk,
+        reason: rule.reason.clone(),
+    }
+}
+
+fn semantic_match(rule: &str, action: RuleAction, risk: RiskLevel, reason: String) -> RuleMatch {
+    RuleMatch {
+        rule: rule.to_string(),
+        action,
+        risk,
+        reason,
+    }
+}
+
+/// 逐个子命令检查语义规则
+fn semantic_matches(
+    segments: &[Vec<String>],
+    working_dir: &Path,
+    workspace: &Path,
+    scope: Option<&Path>,
+) -> Vec<RuleMatch> {
+    let workspace = normalize(workspace);
+    let scope = scope.map(normalize);
+    // None 表示 `cd` 到了无法确定的位置
+    let mut cwd = Some(normalize(working_dir));
+    let mut matches = Vec::new();
+
+    for segment in segments {
+        let (words, redirects) = split_redirects(segment);
+        let words = strip_wrappers(&words);
+
+        if words.first().map(String::as_str) == Some("cd") {
+            cwd = match words.get(1) {
+                Some(dir) => resolve(dir, cwd.as_deref()),
+                None => None,
+            };
+            continue;
+        }
+
+        for target in redirects.iter().chain(write_targets(words).iter()) {
+            let resolved = resolve(target, cwd.as_deref());
+            let outside = match &resolved {
+                Some(path) => !path.starts_with(&workspace) && !is_special_device(path),
+                None => true,
+            };
+            if outside {
+                matches.push(semantic_match(
+                    RULE_WRITE_OUTSIDE_WORKSPACE,
+                    RuleAction::Escalate,
+                    RiskLevel::High,
+                    format!("写入工作空间之外: {}", target),
+                ));
+            } else if let (Some(path), Some(scope)) = (&resolved, &scope) {
+                if !path.starts_with(scope) && !is_special_device(path) {
+                    matches.push(semantic_match(
+                        RULE_WRITE_OUTSIDE_SCOPE,
+                        RuleAction::Escalate,
+                        RiskLevel::High,
+                        format!("写入任务工作范围之外: {}", target),
+                    ));
+                }
+            }
+        }
+
+        let text = words.join(" ");
+        if let Some(prefix) = PUBLISH_COMMAND_PREFIXES.iter().find(|prefix| starts_with_words(words, prefix)) {
+            matches.push(semantic_match(
+                RULE_PACKAGE_PUBLISH,
+                RuleAction::Escalate,
+                RiskLevel::High,
+                format!("发布软件包: {}", prefix),
+            ));
+        }
+        if is_force_push(words) {
+            matches.push(semantic_match(
+                RULE_FORCE_PUSH,
+                RuleAction::Escalate,
+                RiskLevel::High,
+                format!("强制推送会覆盖远程历史: {}", text),
+            ));
+        }
+    }
+    matches
+}
+
+/// 按顺序检查子命令中违反分支保护的 git 操作
+fn branch_violations(
+    segments: &[Vec<String>],
+    protection: &BranchProtection,
+    current_branch: Option<String>,
+) -> Vec<BranchViolation> {
+    let mut guard = BranchGuard::new(protection, current_branch);
+    segments
+        .iter()
+        .filter_map(|segment| {
+            let (words, _) = split_redirects(segment);
+            guard.check(strip_wrappers(&words))
+        })
+        .collect()
+}
+
+/// 命令会写入的路径参数
+fn write_targets(words: &[String]) -> Vec<String> {
+    let Some(program) = words.first() else {
+        return Vec::new();
+    };
+    let program = program.rsplit('/').next().unwrap_or(program);
+    let args: Vec<&String> = words[1..].iter().filter(|arg| !arg.starts_with('-')).collect();
+
+    let targets: Vec<&String> = match program {
+        "rm" | "rmdir" | "touch" | "mkdir" | "truncate" | "shred" | "unlink" | "tee" => args,
+        "chmod" | "chown" | "chgrp" => args.into_iter().skip(1).collect(),
+        "cp" | "mv" | "ln" | "install" | "rsync" => args.last().copied().into_iter().collect(),
+        "sed" if words.iter().any(|arg| arg.starts_with("-i") || arg.starts_with("--in-place")) => {
+            args.into_iter().skip(1).collect()
+        }
+        "dd" => return words.iter().filter_map(|arg| arg.strip_prefix("of=").map(str::to_string)).collect(),
+        _ => Vec::new(),
+    };
+    targets.into_iter().cloned().collect()
+}
+
+fn is_force_push(words: &[String]) -> bool {
+    if words.len() < 2 || words[0] != "git" || words[1] != "push" {
+        return false;
+    }
+    words[2..].iter().any(|arg| {
+        arg.starts_with("--force")
+            || (arg.starts_with('-') && !arg.starts_with("--") && arg.contains('f'))
+            || (arg.starts_with('+') && arg.len() > 1)
+    })
+}
+
+fn is_special_device(path: &Path) -> bool {
+    path == Path::new("/dev/null") || path.starts_with("/dev/stdout") || path.starts_with("/dev/stderr")
+}
+
+/// 解析路径，`~` 和环境变量无法确定时返回 None
+fn resolve(target: &str, cwd: Option<&Path>) -> Option<PathBuf> {
+    if target.starts_with('~') || target.contains('$') || target == "-" {
+        return None;
+    }
+    let path = Path::new(target);
+    if path.is_absolute() {
+        Some(normalize(path))
+    } else {
+        cwd.map(|cwd| normalize(&cwd.join(path)))
+    }
+}
+
+/// 按词法规范化路径，不访问文件系统
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+/// 跳过开头的环境变量赋值和包装程序
+fn strip_wrappers(words: &[String]) -> &[String] {
+    let mut start = 0;
+    while let Some(word) = words.get(start) {
+        let assignment = word.contains('=') && !word.starts_with('-') && !word.starts_with('=');
+        if assignment || COMMAND_WRAPPERS.contains(&word.as_str()) {
+            start += 1;
+        } else {
+            break;
+        }
+    }
+    &words[start..]
+}
+
+/// 分离输出重定向目标，返回剩余参数和重定向目标
+fn split_redirects(segment: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut words = Vec::new();
+    let mut redirects = Vec::new();
+    let mut iter = segment.iter();
+    while let Some(word) = iter.next() {
+        match word.as_str() {
+            ">" => {
+                if let Some(target) = iter.next() {
+                    redirects.push(target.clone());
+                }
+            }
+            "<" => {
+                iter.next();
+            }
+            _ => words.push(word.clone()),
+        }
+    }
+    (words, redirects)
+}
+
+/// 按 `&&`、`||`、`;`、`|`、`&` 和换行把脚本拆成子命令，重定向符号作为单独的词
+fn split_segments(script: &str) -> Vec<Vec<String>> {
+    let mut segments = Vec::new();
+    let mut segment: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = script.chars().peekable();
+
+    fn end_word(word: &mut String, segment: &mut Vec<String>) {
+        if !word.is_empty() {
+            segment.push(std::mem::take(word));
+        }
+    }
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            } else {
+                word.push(c);
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' => quote = Some(c),
+            c if c.is_whitespace() && c != '\n' => end_word(&mut word, &mut segment),
+            ';' | '\n' | '&' | '|' => {
+                end_word(&mut word, &mut segment);
+                if matches!(chars.peek(), Some(&next) if next == c) {
+                    chars.next();
+                }
+                if !segment.is_empty() {
+                    segments.push(std::mem::take(&mut segment));
+                }
+            }
+            '>' | '<' => {
+                // `2>` 中的文件描述符不是参数
+                if !word.is_empty() && word.chars().all(|d| d.is_ascii_digit()) {
+                    word.clear();
+                }
+                end_word(&mut word, &mut segment);
+                if chars.peek() == Some(&'>') {
+                    chars.next();
+                }
+                if chars.peek() == Some(&'&') {
+                    // `>&1` 复制文件描述符，不写入文件
+                    chars.next();
+                    while matches!(chars.peek(), Some(d) if d.is_ascii_digit() || *d == '-') {
+                        chars.next();
+                    }
+                } else {
+                    segment.push(c.to_string());
+                }
+            }
+            _ => word.push(c),
+        }
+    }
+    end_word(&mut word, &mut segment);
+    if !segment.is_empty() {
+        segments.push(segment);
+    }
+    segments
+}
+
+/// 取出命令实际执行的脚本文本
+fn command_script(command: &[String]) -> String {
+    match command {
+        [shell, flag, script] if is_shell(shell) && matches!(flag.as_str(), "-c" | "-lc") => script.trim().to_string(),
+        _ => command.join(" "),
+    }
+}
+
+fn is_shell(program: &str) -> bool {
+    let name = program.rsplit('/').next().unwrap_or(program);
+    matches!(name, "bash" | "sh" | "zsh")
+}
+
+fn starts_with_words(words: &[String], prefix: &str) -> bool {
+    let prefix: Vec<&str> = prefix.split_whitespace().collect();
+    !prefix.is_empty() && words.len() >= prefix.len() && words.iter().zip(&prefix).all(|(word, p)| word == p)
+}
+
@@ -22,6 +22,47 @@ pub struct DatabaseConfig {
     
     /// 是否启用SQL日志
     pub enable_logging: bool,
+    
+    /// 文件数据库的日志模式，默认 WAL，读写互不阻塞
+    #[serde(default)]
+    pub journal_mode: JournalMode,
+    
+    /// 等待其他连接释放锁的时间（毫秒），超时后返回 SQLITE_BUSY
+    #[serde(default = "default_busy_timeout_ms")]
+    pub busy_timeout_ms: u64,
+    
+    /// 等待超时后仍为 SQLITE_BUSY 时的重试次数，见 [`crate::connection::retry_on_busy`]
+    #[serde(default = "default_busy_retries")]
+    pub busy_retries: u32,
+    
+    /// 首次重试前的等待时间（毫秒），之后每次翻倍
+    #[serde(default = "default_busy_backoff_ms")]
+    pub busy_backoff_ms: u64,
+}
+
+/// SQLite 日志模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalMode {
+    /// 预写日志：一个写入者与多个读取者并发
+    #[default]
+    Wal,
+    /// 回滚日志：写入期间阻塞所有读取
+    Delete,
+    /// 回滚日志，提交时截断而不是删除日志文件
+    Truncate,
+}
+
+fn default_busy_timeout_ms() -> u64 {
+    5_000
+}
+
+fn default_busy_retries() -> u32 {
+    3
+}
+
+fn default_busy_backoff_ms() -> u64 {
+    50
 }
 
 impl Default for DatabaseConfig {
@@ -33,6 +74,10 @@ impl Default for DatabaseConfig {
             connect_timeout: 30,
             idle_timeout: 600,
             enable_logging: true,
+            journal_mode: JournalMode::default(),
+            busy_timeout_ms: default_busy_timeout_ms(),
+            busy_retries: default_busy_retries(),
+            busy_backoff_ms: default_busy_backoff_ms(),
         }
     }
 }
@@ -47,6 +92,7 @@ impl DatabaseConfig {
             connect_timeout: 10,
             idle_timeout: 60,
             enable_logging: false,
+            ..Default::default()
         }
     }
     
@@ -79,6 +125,12 @@ impl DatabaseConfig {
             ));
         }
         
+        if self.busy_retries > 0 && self.busy_backoff_ms == 0 {
+            return Err(crate::error::DatabaseError::Configuration(
+                "启用忙重试时退避时间必须大于0".to_string(),
+            ));
+        }
+        
         Ok(())
     }
 }
@@ -127,5 +179,28 @@ mod tests {
         config.min_connections = 10;
         config.max_connections = 5;
         assert!(config.validate().is_err());
+        
+        // 测试重试没有退避
+        config = DatabaseConfig::default();
+        config.busy_backoff_ms = 0;
+        assert!(config.validate().is_err());
+        config.busy_retries = 0;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_concurrency_defaults_for_old_config() {
+        // 旧版本保存的配置没有并发相关字段，反序列化时使用默认值
+        let config: DatabaseConfig = serde_json::from_value(serde_json::json!({
+            "database_url": "sqlite://old.db?mode=rwc",
+            "max_connections": 5,
+            "min_connections": 1,
+            "connect_timeout": 30,
+            "idle_timeout": 600,
+            "enable_logging": false
+        })).unwrap();
+        assert_eq!(config.journal_mode, JournalMode::Wal);
+        assert_eq!(config.busy_timeout_ms, 5_000);
+        assert!(config.validate().is_ok());
     }
 }
\ No newline at end of file
@@ -1,57 +1,147 @@
 //! 数据库连接模块
+//!
+//! 调度器、日志写入和界面会同时写库，连接按 [`DatabaseConfig`] 配置 SQLite 的并发行为：
+//! 文件数据库使用 WAL 日志模式，每个连接设置 `busy_timeout`，在锁被占用时等待而不是立即失败。
+//! 等待无法解决的忙错误（例如读事务升级为写事务时的 `SQLITE_BUSY`）由 [`retry_on_busy`] 重试。
 
-use crate::{DatabaseConfig, DatabaseError, Result};
-use sea_orm::{ConnectOptions, Database};
+use crate::{config::JournalMode, DatabaseConfig, DatabaseError, Result};
+use sea_orm::sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
+use sea_orm::sqlx::ConnectOptions as _;
+use sea_orm::SqlxSqliteConnector;
+use std::future::Future;
+use std::str::FromStr;
 use std::time::Duration;
 
 /// 数据库连接类型别名
 pub type DatabaseConnection = sea_orm::DatabaseConnection;
 
 /// 建立数据库连接
-/// 
+///
 /// # 参数
 /// * `database_url` - 数据库连接URL
-/// 
+///
 /// # 返回值
 /// 返回配置好的数据库连接
 pub async fn establish_connection(database_url: &str) -> Result<DatabaseConnection> {
-    let mut opt = ConnectOptions::new(database_url);
-    
-    // 基本连接配置
-    opt.max_connections(10)
-        .min_connections(1)
-        .connect_timeout(Duration::from_secs(8))
-        .acquire_timeout(Duration::from_secs(8))
-        .idle_timeout(Duration::from_secs(8))
-        .max_lifetime(Duration::from_secs(8))
-        .sqlx_logging(false); // 默认关闭日志，避免测试时输出过多
-    
-    Database::connect(opt)
-        .await
-        .map_err(DatabaseError::from)
+    // 基本连接配置，默认关闭日志，避免测试时输出过多
+    let config = DatabaseConfig {
+        database_url: database_url.to_string(),
+        max_connections: 10,
+        min_connections: 1,
+        connect_timeout: 8,
+        idle_timeout: 8,
+        enable_logging: false,
+        ..Default::default()
+    };
+
+    establish_connection_with_config(&config).await
 }
 
 /// 使用配置建立数据库连接
 pub async fn establish_connection_with_config(config: &DatabaseConfig) -> Result<DatabaseConnection> {
     config.validate()?;
-    
-    let mut opt = ConnectOptions::new(&config.database_url);
-    
-    opt.max_connections(config.max_connections)
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(config.max_connections)
         .min_connections(config.min_connections)
-        .connect_timeout(Duration::from_secs(config.connect_timeout))
         .acquire_timeout(Duration::from_secs(config.connect_timeout))
         .idle_timeout(Duration::from_secs(config.idle_timeout))
         .max_lifetime(Duration::from_secs(config.idle_timeout))
-        .sqlx_logging(config.enable_logging);
-    
-    if config.enable_logging {
-        opt.sqlx_logging_level(log::LevelFilter::Info);
-    }
-    
-    Database::connect(opt)
+        .connect_with(sqlite_options(config)?)
         .await
-        .map_err(DatabaseError::from)
+        .map_err(|e| DatabaseError::Connection(e.to_string()))?;
+
+    Ok(SqlxSqliteConnector::from_sqlx_sqlite_pool(pool))
+}
+
+/// 由配置生成每个连接的 SQLite 选项
+fn sqlite_options(config: &DatabaseConfig) -> Result<SqliteConnectOptions> {
+    let mut options = SqliteConnectOptions::from_str(&config.database_url)
+        .map_err(|e| DatabaseError::Configuration(format!("无效的数据库URL {}: {}", config.database_url, e)))?
+        .foreign_keys(true)
+        .busy_timeout(Duration::from_millis(config.busy_timeout_ms));
+
+    // 内存数据库没有日志文件，只读连接不能切换日志模式
+    let url = config.database_url.as_str();
+    let in_memory = url.contains(":memory:") || url.contains("mode=memory");
+    let read_only = url.contains("mode=ro");
+    if !in_memory && !read_only {
+        options = match config.journal_mode {
+            // WAL 下 NORMAL 同步只在检查点时 fsync，掉电最多丢失最后几个事务，不会损坏数据库
+            JournalMode::Wal => options.journal_mode(SqliteJournalMode::Wal).synchronous(SqliteSynchronous::Normal),
+            JournalMode::Delete => options.journal_mode(SqliteJournalMode::Delete),
+            JournalMode::Truncate => options.journal_mode(SqliteJournalMode::Truncate),
+        };
+    }
+
+    options = if config.enable_logging {
+        options.log_statements(log::LevelFilter::Info)
+    } else {
+        options.disable_statement_logging()
+    };
+
+    Ok(options)
+}
+
+/// SQLITE_BUSY 重试策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusyRetryPolicy {
+    /// 首次失败后的最多重试次数
+    pub max_retries: u32,
+    /// 首次重试前的等待时间，之后每次翻倍
+    pub initial_backoff: Duration,
+    /// 单次等待的上限
+    pub max_backoff: Duration,
+}
+
+impl Default for BusyRetryPolicy {
+    fn default() -> Self {
+        Self::from_config(&DatabaseConfig::default())
+    }
+}
+
+impl BusyRetryPolicy {
+    /// 使用配置中的重试次数和退避时间
+    pub fn from_config(config: &DatabaseConfig) -> Self {
+        Self {
+            max_retries: config.busy_retries,
+            initial_backoff: Duration::from_millis(config.busy_backoff_ms),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+
+    /// 不重试
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        }
+    }
+}
+
+/// 执行数据库操作，遇到 SQLITE_BUSY / SQLITE_LOCKED 时按策略退避重试
+///
+/// `operation` 每次重试都会被重新调用，必须是可以整体重做的操作（单条语句或完整事务）。
+/// 其他错误以及重试耗尽后的忙错误原样返回。
+pub async fn retry_on_busy<T, F, Fut>(policy: BusyRetryPolicy, mut operation: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 0;
+    let mut backoff = policy.initial_backoff;
+    loop {
+        match operation().await {
+            Err(e) if e.is_busy() && attempt < policy.max_retries => {
+                attempt += 1;
+                log::debug!("数据库忙，{:?} 后第 {} 次重试: {}", backoff, attempt, e);
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(policy.max_backoff);
+            }
+            result => return result,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -61,7 +151,7 @@ mod tests {
     #[tokio::test]
     async fn test_establish_connection() {
         let db = establish_connection("sqlite::memory:").await.unwrap();
-        
+
         // 测试连接是否可用
         db.ping().await.unwrap();
     }
@@ -70,8 +160,20 @@ mod tests {
     async fn test_establish_connection_with_config() {
         let config = DatabaseConfig::memory();
         let db = establish_connection_with_config(&config).await.unwrap();
-        
+
         // 测试连接是否可用
         db.ping().await.unwrap();
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_retry_on_busy_ignores_other_errors() {
+        let mut calls = 0;
+        let result: Result<()> = retry_on_busy(BusyRetryPolicy::default(), || {
+            calls += 1;
+            async { Err(DatabaseError::validation("不是忙错误")) }
+        })
+        .await;
+        assert!(result.unwrap_err().is_validation_error());
+        assert_eq!(calls, 1);
+    }
+}
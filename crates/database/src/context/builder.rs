@@ -0,0 +1,565 @@
+//! 项目上下文构建器
+
+use crate::{
+    context::{
+        codebase_scanner::{CodebaseScan, CodebaseScanner},
+        git_history::{collect_commit_stats, current_commit_hash},
+        technical_debt::{analyze_technical_debt, trend_to_str, TechnicalDebtReport},
+    },
+    entities::{agent, conflict, project, task},
+    repository::{
+        technical_debt_snapshot_repository::CreateTechnicalDebtSnapshotData, AgentRepository,
+        ConflictRepository, ProjectRepository, TaskRepository, TechnicalDebtSnapshotRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+use chrono::{DateTime, Utc};
+use codex_multi_agent::{
+    llm_orchestration::{
+        CodeQualityMetrics, CompletedTaskSummary, DebtTrend, MilestoneStatus, ProjectContext,
+        ResourceAvailability, ResourceDemand, ResourceGap, RiskAssessment, RiskItem, RiskLevel,
+        RiskMatrix, RiskStatus, RiskType, TechnicalDebtMetrics, WorkCalendar,
+    },
+    AgentCapability, AgentId, CodebaseInfo, Milestone, TaskId, TimelineRequirements,
+};
+use sea_orm::EntityTrait;
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 默认的提交统计窗口（天）
+const DEFAULT_COMMIT_WINDOW_DAYS: i64 = 30;
+
+/// 项目 codebase_info 中保存扫描快照的字段名
+const SCAN_SNAPSHOT_KEY: &str = "scan_snapshot";
+
+/// 项目上下文构建器
+///
+/// 汇总数据库中的任务、冲突、Agent和里程碑，以及工作空间的代码统计和
+/// 最近提交，生成完整的 `ProjectContext`
+pub struct ContextBuilder {
+    db: DatabaseConnection,
+    commit_window_days: i64,
+    scan_workspace: bool,
+    scanner: Arc<CodebaseScanner>,
+}
+
+impl ContextBuilder {
+    /// 创建新的上下文构建器
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            commit_window_days: DEFAULT_COMMIT_WINDOW_DAYS,
+            scan_workspace: true,
+            scanner: Arc::new(CodebaseScanner::new()),
+        }
+    }
+
+    /// 使用共享的代码库扫描器（多个构建器共享扫描缓存）
+    pub fn with_scanner(mut self, scanner: Arc<CodebaseScanner>) -> Self {
+        self.scanner = scanner;
+        self
+    }
+
+    /// 设置提交统计窗口（天）
+    pub fn with_commit_window(mut self, days: i64) -> Self {
+        self.commit_window_days = days.max(1);
+        self
+    }
+
+    /// 设置是否扫描工作空间（关闭时只使用数据库数据）
+    pub fn with_workspace_scan(mut self, enabled: bool) -> Self {
+        self.scan_workspace = enabled;
+        self
+    }
+
+    /// 为指定项目构建上下文快照
+    pub async fn build(&self, project_id: Uuid) -> Result<ProjectContext> {
+        let project = project::Entity::find_by_id(project_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+
+        let tasks = TaskRepository::new(self.db.clone()).find_by_project(project_id).await?;
+        let agents = AgentRepository::new(self.db.clone())
+            .find_by_user_id(project.user_id)
+            .await?;
+        let conflicts = self.find_project_conflicts(&tasks).await?;
+
+        let codebase_info = self.build_codebase_info(&project).await?;
+        let milestones = parse_milestones(&project, &tasks);
+        let shared_context = crate::blackboard::prompt_entries(&self.db, project_id).await?;
+
+        Ok(ProjectContext {
+            codebase_info,
+            existing_architecture: existing_architecture(&project),
+            development_constraints: development_constraints(&project),
+            timeline_requirements: build_timeline(milestones),
+            completed_tasks_summary: tasks.iter().filter_map(completed_task_summary).collect(),
+            active_tasks: tasks
+                .iter()
+                .filter(|t| t.status == "in_progress")
+                .map(|t| TaskId::from(t.task_id))
+                .collect(),
+            risk_assessment: build_risk_assessment(&conflicts),
+            resource_availability: build_resource_availability(&tasks, &agents),
+            external_dependencies_status: Vec::new(),
+            shared_context,
+        })
+    }
+
+    /// 查找影响该项目任务的未解决冲突
+    async fn find_project_conflicts(&self, tasks: &[task::Model]) -> Result<Vec<conflict::Model>> {
+        let task_ids: HashSet<String> = tasks.iter().map(|t| t.task_id.to_string()).collect();
+        let conflicts = ConflictRepository::new(self.db.clone()).find_unresolved().await?;
+
+        Ok(conflicts
+            .into_iter()
+            .filter(|c| {
+                string_array(&c.affected_tasks)
+                    .iter()
+                    .any(|id| task_ids.contains(id))
+            })
+            .collect())
+    }
+
+    /// 采集工作空间代码统计、提交统计和技术债务
+    async fn build_codebase_info(&self, project: &project::Model) -> Result<CodebaseInfo> {
+        let workspace = Path::new(&project.workspace_path).to_path_buf();
+        let workspace_exists = self.scan_workspace && workspace.is_dir();
+
+        let scan = if workspace_exists {
+            self.scan_codebase(project, &workspace).await?
+        } else {
+            CodebaseScan::default()
+        };
+
+        let recent_commit_stats = if workspace_exists {
+            collect_commit_stats(&workspace, self.commit_window_days).await
+        } else {
+            super::git_history::summarize_commits(&[], self.commit_window_days)
+        };
+
+        let (debt_report, technical_debt) = if workspace_exists {
+            self.analyze_debt(project.project_id, &workspace, scan.commit_hash.clone()).await?
+        } else {
+            (
+                TechnicalDebtReport::default(),
+                TechnicalDebtMetrics {
+                    estimated_hours: 0,
+                    severity_distribution: HashMap::new(),
+                    main_debt_types: Vec::new(),
+                    trend: DebtTrend::Stable,
+                },
+            )
+        };
+        let duplication_rate = if scan.total_lines > 0 {
+            (debt_report.duplicated_lines as f32 / scan.total_lines as f32).min(1.0)
+        } else {
+            0.0
+        };
+
+        let test_coverage = crate::coverage::latest_project_coverage(&self.db, project.project_id)
+            .await?
+            .unwrap_or(0.0) as f32;
+
+        Ok(CodebaseInfo {
+            total_files: scan.total_files,
+            total_lines: scan.total_lines,
+            main_languages: scan.languages,
+            framework_info: scan.frameworks,
+            // 测试覆盖率取最近一次上报的报告，尚未接入的质量分析使用中性评分
+            quality_metrics: CodeQualityMetrics {
+                test_coverage,
+                average_complexity: scan.average_complexity,
+                duplication_rate,
+                style_violations: 0,
+                security_issues: 0,
+                performance_issues: 0,
+                overall_quality_score: 5,
+            },
+            recent_commit_stats,
+            technical_debt,
+        })
+    }
+
+    /// 分析技术债务并记录快照，同一提交复用最近一次快照
+    async fn analyze_debt(
+        &self,
+        project_id: Uuid,
+        workspace: &Path,
+        commit_hash: Option<String>,
+    ) -> Result<(TechnicalDebtReport, TechnicalDebtMetrics)> {
+        let snapshot_repo = TechnicalDebtSnapshotRepository::new(self.db.clone());
+        let latest = snapshot_repo.find_latest_by_project(project_id).await?;
+
+        if let Some(snapshot) = &latest {
+            if commit_hash.is_some() && snapshot.commit_hash == commit_hash {
+                let report: TechnicalDebtReport = serde_json::from_value(snapshot.findings.clone())?;
+                let metrics = TechnicalDebtMetrics {
+                    estimated_hours: snapshot.estimated_hours.max(0) as u32,
+                    severity_distribution: serde_json::from_value(snapshot.severity_distribution.clone())?,
+                    main_debt_types: serde_json::from_value(snapshot.main_debt_types.clone())?,
+                    trend: serde_json::from_value(JsonValue::String(snapshot.trend.clone()))?,
+                };
+                return Ok((report, metrics));
+            }
+        }
+
+        let root = workspace.to_path_buf();
+        let report = tokio::task::spawn_blocking(move || analyze_technical_debt(&root))
+            .await
+            .map_err(|e| DatabaseError::Other(e.into()))?;
+        let metrics = report.to_metrics(latest.map(|s| s.estimated_hours.max(0) as u32));
+
+        snapshot_repo
+            .create(CreateTechnicalDebtSnapshotData {
+                project_id,
+                commit_hash,
+                estimated_hours: metrics.estimated_hours as i32,
+                severity_distribution: serde_json::to_value(&metrics.severity_distribution)?,
+                main_debt_types: serde_json::to_value(&metrics.main_debt_types)?,
+                findings: serde_json::to_value(&report)?,
+                trend: trend_to_str(&metrics.trend).to_string(),
+            })
+            .await?;
+
+        Ok((report, metrics))
+    }
+
+    /// 扫描代码库，同一提交优先复用项目中持久化的快照
+    async fn scan_codebase(&self, project: &project::Model, workspace: &Path) -> Result<CodebaseScan> {
+        let persisted = project
+            .codebase_info
+            .as_ref()
+            .and_then(|info| info.get(SCAN_SNAPSHOT_KEY))
+            .and_then(|snapshot| serde_json::from_value::<CodebaseScan>(snapshot.clone()).ok());
+
+        if let (Some(head), Some(snapshot)) = (current_commit_hash(workspace).await, persisted) {
+            if snapshot.commit_hash.as_deref() == Some(head.as_str()) {
+                self.scanner.store(workspace, head, snapshot.clone());
+                return Ok(snapshot);
+            }
+        }
+
+        let scan = self.scanner.scan(workspace).await?;
+
+        // 只有能对应到提交的扫描结果才值得持久化
+        if scan.commit_hash.is_some() {
+            let mut info = match project.codebase_info.clone() {
+                Some(JsonValue::Object(map)) => map,
+                _ => serde_json::Map::new(),
+            };
+            info.insert(SCAN_SNAPSHOT_KEY.to_string(), serde_json::to_value(&scan)?);
+            ProjectRepository::new(self.db.clone())
+                .update_context(project.project_id, Some(JsonValue::Object(info)), None)
+                .await?;
+        }
+
+        Ok(scan)
+    }
+}
+
+/// 读取JSON字符串数组
+fn string_array(value: &JsonValue) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 现有架构描述：优先使用项目上下文中的描述，其次是代码库信息中的架构模式
+fn existing_architecture(project: &project::Model) -> Option<String> {
+    project
+        .project_context
+        .as_ref()
+        .and_then(|ctx| ctx.get("architecture"))
+        .or_else(|| {
+            project
+                .codebase_info
+                .as_ref()
+                .and_then(|info| info.get("architecture_pattern"))
+        })
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+/// 开发约束：项目上下文中的约束条件加上技术栈限定
+fn development_constraints(project: &project::Model) -> Vec<String> {
+    let mut constraints = project
+        .project_context
+        .as_ref()
+        .and_then(|ctx| ctx.get("constraints"))
+        .map(string_array)
+        .unwrap_or_default();
+
+    if let Some(stack) = project.technology_stack.as_ref().map(string_array) {
+        if !stack.is_empty() {
+            constraints.push(format!("技术栈: {}", stack.join(", ")));
+        }
+    }
+
+    constraints
+}
+
+/// 从项目上下文读取里程碑，并根据关联任务的状态刷新完成度
+pub(crate) fn parse_milestones(project: &project::Model, tasks: &[task::Model]) -> Vec<Milestone> {
+    let Some(raw) = project
+        .project_context
+        .as_ref()
+        .and_then(|ctx| ctx.get("milestones"))
+        .cloned()
+    else {
+        return Vec::new();
+    };
+
+    let mut milestones: Vec<Milestone> = serde_json::from_value(raw).unwrap_or_default();
+    let status_by_id: HashMap<Uuid, &str> = tasks
+        .iter()
+        .map(|t| (t.task_id, t.status.as_str()))
+        .collect();
+    let now = Utc::now();
+
+    for milestone in &mut milestones {
+        if milestone.dependent_tasks.is_empty() {
+            continue;
+        }
+        let completed = milestone
+            .dependent_tasks
+            .iter()
+            .filter(|id| status_by_id.get(id.as_uuid()) == Some(&"completed"))
+            .count();
+        milestone.completion_rate = completed as f32 / milestone.dependent_tasks.len() as f32;
+        milestone.status = if completed == milestone.dependent_tasks.len() {
+            MilestoneStatus::Completed
+        } else if milestone.deadline < now {
+            MilestoneStatus::Delayed
+        } else if completed > 0 {
+            MilestoneStatus::InProgress
+        } else {
+            milestone.status.clone()
+        };
+    }
+
+    milestones
+}
+
+/// 根据里程碑生成时间线要求
+fn build_timeline(milestones: Vec<Milestone>) -> Option<TimelineRequirements> {
+    let target_completion = milestones.iter().map(|m| m.deadline).max()?;
+    let critical_path_tasks = milestones
+        .iter()
+        .filter(|m| m.status != MilestoneStatus::Completed)
+        .flat_map(|m| m.dependent_tasks.iter().map(|id| id.to_string()))
+        .collect();
+
+    Some(TimelineRequirements {
+        target_completion,
+        milestone_deadlines: milestones,
+        critical_path_tasks,
+        buffer_time_hours: 0,
+        risk_factor: 1.0,
+        work_calendar: WorkCalendar {
+            working_days: vec![1, 2, 3, 4, 5],
+            hours_per_day: 8,
+            holidays: Vec::new(),
+            team_leave_periods: Vec::new(),
+        },
+    })
+}
+
+/// 生成已完成任务摘要（只包含已分配给Agent的任务）
+fn completed_task_summary(task: &task::Model) -> Option<CompletedTaskSummary> {
+    if task.status != "completed" {
+        return None;
+    }
+    let agent_id = task.assigned_agent_id?;
+    let completed_at: DateTime<Utc> = task.completed_at.unwrap_or(task.updated_at).into();
+    let actual_hours = task
+        .started_at
+        .map(|started| {
+            let minutes = (completed_at - DateTime::<Utc>::from(started)).num_minutes().max(0);
+            ((minutes + 59) / 60) as u32
+        })
+        .unwrap_or(0);
+    let quality_score = task
+        .execution_result
+        .as_ref()
+        .and_then(|r| r.get("quality_score"))
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0) as f32;
+
+    Some(CompletedTaskSummary {
+        task_id: TaskId::from(task.task_id),
+        title: task.title.clone(),
+        completed_at,
+        actual_hours,
+        executed_by: AgentId::from(agent_id),
+        quality_score,
+        lessons_learned: Vec::new(),
+    })
+}
+
+/// 冲突严重性映射为风险级别
+fn severity_to_risk_level(severity: &str) -> RiskLevel {
+    match severity {
+        "critical" => RiskLevel::Critical,
+        "high" => RiskLevel::High,
+        "medium" => RiskLevel::Medium,
+        _ => RiskLevel::Low,
+    }
+}
+
+/// 冲突类型映射为风险类型
+fn conflict_to_risk_type(conflict_type: &str) -> RiskType {
+    match conflict_type {
+        "resource" | "capability" => RiskType::Resource,
+        "task_dependency" => RiskType::Dependency,
+        "timeline" => RiskType::Timeline,
+        _ => RiskType::Technical,
+    }
+}
+
+/// 根据未解决冲突生成风险评估
+fn build_risk_assessment(conflicts: &[conflict::Model]) -> RiskAssessment {
+    let mut risk_distribution: HashMap<String, u32> = HashMap::new();
+    let mut matrix_counts = (0u32, 0u32, 0u32);
+    let mut overall_risk_level = RiskLevel::Low;
+
+    let risk_items = conflicts
+        .iter()
+        .map(|c| {
+            let level = severity_to_risk_level(&c.severity);
+            let impact = match level {
+                RiskLevel::Critical => 1.0,
+                RiskLevel::High => 0.75,
+                RiskLevel::Medium => 0.5,
+                RiskLevel::Low => 0.25,
+            };
+            match level {
+                RiskLevel::Critical | RiskLevel::High => matrix_counts.0 += 1,
+                RiskLevel::Medium => matrix_counts.1 += 1,
+                RiskLevel::Low => matrix_counts.2 += 1,
+            }
+            *risk_distribution.entry(c.conflict_type.clone()).or_insert(0) += 1;
+            overall_risk_level = overall_risk_level.clone().max(level);
+
+            // 已检测到的冲突视为已发生的风险
+            RiskItem {
+                risk_id: c.conflict_id.to_string(),
+                description: format!("{}: {}", c.title, c.description),
+                risk_type: conflict_to_risk_type(&c.conflict_type),
+                probability: 1.0,
+                impact,
+                risk_score: impact,
+                status: if c.status == "resolving" {
+                    RiskStatus::Mitigating
+                } else {
+                    RiskStatus::Realized
+                },
+                owner: c.assigned_user_id.map(|id| id.to_string()),
+            }
+        })
+        .collect();
+
+    RiskAssessment {
+        overall_risk_level,
+        risk_items,
+        risk_matrix: RiskMatrix {
+            high_risk_count: matrix_counts.0,
+            medium_risk_count: matrix_counts.1,
+            low_risk_count: matrix_counts.2,
+            risk_distribution,
+        },
+        mitigation_plan: Vec::new(),
+    }
+}
+
+/// 将数据库中的能力名称（PascalCase）转换为协议能力枚举
+fn parse_capability(name: &str) -> Option<AgentCapability> {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.push(ch.to_ascii_lowercase());
+    }
+    serde_json::from_value(JsonValue::String(snake)).ok()
+}
+
+/// 根据未完成任务的能力需求和Agent状态生成资源可用性
+fn build_resource_availability(tasks: &[task::Model], agents: &[agent::Model]) -> ResourceAvailability {
+    let available_agents = agents
+        .iter()
+        .filter(|a| a.status == "idle")
+        .map(|a| AgentId::from(a.agent_id))
+        .collect();
+
+    let agent_workloads = agents
+        .iter()
+        .map(|a| {
+            let load = match a.status.as_str() {
+                "working" => 1.0,
+                "idle" => 0.0,
+                _ => 0.5,
+            };
+            (a.agent_id.to_string(), load)
+        })
+        .collect();
+
+    let agent_capabilities: HashSet<String> = agents
+        .iter()
+        .filter(|a| a.status != "offline")
+        .flat_map(|a| string_array(&a.capabilities))
+        .collect();
+
+    let mut capability_demands: HashMap<String, u32> = HashMap::new();
+    let mut uncovered: HashMap<String, Vec<TaskId>> = HashMap::new();
+    let mut total_estimated_hours = 0u32;
+
+    for task in tasks
+        .iter()
+        .filter(|t| !matches!(t.status.as_str(), "completed" | "cancelled" | "failed"))
+    {
+        let hours = task.estimated_hours.unwrap_or(0).max(0) as u32;
+        total_estimated_hours += hours;
+
+        for capability in task.required_capabilities.as_ref().map(string_array).unwrap_or_default() {
+            *capability_demands.entry(capability.clone()).or_insert(0) += hours;
+            if !agent_capabilities.contains(&capability) {
+                uncovered.entry(capability).or_default().push(TaskId::from(task.task_id));
+            }
+        }
+    }
+
+    let mut resource_gaps: Vec<ResourceGap> = uncovered
+        .into_iter()
+        .filter_map(|(name, affected_tasks)| {
+            Some(ResourceGap {
+                capability: parse_capability(&name)?,
+                gap_hours: capability_demands.get(&name).copied().unwrap_or(0),
+                affected_tasks,
+                suggested_solutions: vec![format!("创建或配置具备 {name} 能力的Agent")],
+            })
+        })
+        .collect();
+    resource_gaps.sort_by_key(|gap| std::cmp::Reverse(gap.gap_hours));
+
+    ResourceAvailability {
+        available_agents,
+        agent_workloads,
+        estimated_resource_demand: ResourceDemand {
+            capability_demands,
+            peak_demand_periods: Vec::new(),
+            total_estimated_hours,
+        },
+        resource_gaps,
+    }
+}
@@ -0,0 +1,496 @@
+//! 代码库扫描器
+//!
+//! 遍历工作空间统计各语言的文件数和行数，从依赖清单（package.json、
+//! Cargo.toml、requirements.txt）识别框架，并粗略估算代码复杂度。
+//! 扫描结果按提交哈希缓存，同一提交不会重复扫描。
+
+use crate::{
+    context::git_history::current_commit_hash,
+    operations::{CancellationToken, OperationHandle},
+    DatabaseError, Result,
+};
+use chrono::{DateTime, Utc};
+use codex_multi_agent::{llm_orchestration::ConfigurationStatus, FrameworkInfo, LanguageStats};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 扫描时跳过的目录
+const IGNORED_DIRS: &[&str] = &["target", "node_modules", "dist", "build", "vendor", "__pycache__"];
+
+/// 视为分支判定点的关键字和运算符（用于粗略估算圈复杂度）
+const DECISION_TOKENS: &[&str] = &[
+    "if ", "else if", "elif ", "for ", "while ", "match ", "case ", "catch", "&&", "||", "? ",
+];
+
+/// 已知框架：(清单中的依赖名, 展示名称)
+const KNOWN_FRAMEWORKS: &[(&str, &str)] = &[
+    // JavaScript / TypeScript
+    ("react", "React"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("next", "Next.js"),
+    ("@angular/core", "Angular"),
+    ("express", "Express"),
+    ("vite", "Vite"),
+    ("@tauri-apps/api", "Tauri"),
+    ("typescript", "TypeScript"),
+    ("jest", "Jest"),
+    ("vitest", "Vitest"),
+    ("tailwindcss", "Tailwind CSS"),
+    // Rust
+    ("tokio", "Tokio"),
+    ("axum", "Axum"),
+    ("actix-web", "Actix Web"),
+    ("rocket", "Rocket"),
+    ("tauri", "Tauri"),
+    ("sea-orm", "SeaORM"),
+    ("diesel", "Diesel"),
+    ("sqlx", "SQLx"),
+    ("serde", "Serde"),
+    // Python
+    ("django", "Django"),
+    ("flask", "Flask"),
+    ("fastapi", "FastAPI"),
+    ("numpy", "NumPy"),
+    ("pandas", "pandas"),
+    ("pytest", "pytest"),
+];
+
+/// 代码库扫描结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CodebaseScan {
+    /// 扫描时的HEAD提交哈希（非Git仓库时为空）
+    pub commit_hash: Option<String>,
+    /// 源代码文件总数
+    pub total_files: u32,
+    /// 源代码总行数
+    pub total_lines: u32,
+    /// 按代码行数降序排列的语言统计
+    pub languages: Vec<LanguageStats>,
+    /// 从依赖清单识别出的框架
+    pub frameworks: Vec<FrameworkInfo>,
+    /// 平均每个文件的圈复杂度估算值
+    pub average_complexity: f32,
+    /// 扫描时间
+    pub scanned_at: Option<DateTime<Utc>>,
+}
+
+/// 单个语言的累计统计
+#[derive(Default)]
+struct LanguageAccumulator {
+    files: u32,
+    lines: u32,
+    decision_points: u32,
+}
+
+/// 代码库扫描器
+///
+/// 内部按（工作空间路径, 提交哈希）缓存扫描结果，可在多个上下文构建器之间共享
+#[derive(Default)]
+pub struct CodebaseScanner {
+    cache: Mutex<HashMap<(PathBuf, String), CodebaseScan>>,
+}
+
+impl CodebaseScanner {
+    /// 创建新的扫描器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 扫描工作空间
+    ///
+    /// 当前提交已扫描过时直接返回缓存结果；非Git仓库每次都重新扫描
+    pub async fn scan(&self, root: &Path) -> Result<CodebaseScan> {
+        self.scan_inner(root, None).await
+    }
+
+    /// 扫描工作空间，通过操作句柄报告进度
+    ///
+    /// 遍历目录时响应取消请求，取消后的部分结果不会写入缓存
+    pub async fn scan_with_progress(&self, root: &Path, operation: &OperationHandle) -> Result<CodebaseScan> {
+        self.scan_inner(root, Some(operation)).await
+    }
+
+    async fn scan_inner(&self, root: &Path, operation: Option<&OperationHandle>) -> Result<CodebaseScan> {
+        if let Some(operation) = operation {
+            operation.set_phase("读取提交", 0.0);
+        }
+        let commit_hash = current_commit_hash(root).await;
+
+        if let Some(hash) = &commit_hash {
+            if let Some(cached) = self.cached(root, hash) {
+                return Ok(cached);
+            }
+        }
+
+        if let Some(operation) = operation {
+            operation.check_cancelled()?;
+            operation.set_phase("扫描文件", 10.0);
+        }
+        let scan_root = root.to_path_buf();
+        let token = operation.map(OperationHandle::token);
+        let mut scan = tokio::task::spawn_blocking(move || build_scan(&scan_root, token.as_ref()))
+            .await
+            .map_err(|e| DatabaseError::Other(e.into()))?;
+        if let Some(operation) = operation {
+            operation.check_cancelled()?;
+        }
+        scan.commit_hash = commit_hash.clone();
+
+        if let Some(hash) = commit_hash {
+            self.store(root, hash, scan.clone());
+        }
+
+        Ok(scan)
+    }
+
+    /// 获取指定提交的缓存结果
+    pub fn cached(&self, root: &Path, commit_hash: &str) -> Option<CodebaseScan> {
+        self.cache
+            .lock()
+            .ok()?
+            .get(&(root.to_path_buf(), commit_hash.to_string()))
+            .cloned()
+    }
+
+    /// 写入缓存（例如从持久化的快照恢复）
+    pub fn store(&self, root: &Path, commit_hash: String, scan: CodebaseScan) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert((root.to_path_buf(), commit_hash), scan);
+        }
+    }
+}
+
+/// 根据文件扩展名识别编程语言
+pub fn detect_language(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    let language = match ext.as_str() {
+        "rs" => "Rust",
+        "ts" | "tsx" => "TypeScript",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "py" => "Python",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" => "C++",
+        "cs" => "C#",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "swift" => "Swift",
+        "vue" => "Vue",
+        "svelte" => "Svelte",
+        "css" | "scss" | "less" => "CSS",
+        "html" | "htm" => "HTML",
+        "sql" => "SQL",
+        "sh" | "bash" => "Shell",
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// 同步扫描工作空间（不读取提交哈希，也不使用缓存）
+pub fn scan_workspace(root: &Path) -> CodebaseScan {
+    build_scan(root, None)
+}
+
+/// 扫描工作空间，令牌被取消时停止遍历
+fn build_scan(root: &Path, cancel: Option<&CancellationToken>) -> CodebaseScan {
+    let mut per_language: HashMap<&'static str, LanguageAccumulator> = HashMap::new();
+    let mut manifests = Vec::new();
+    visit_dir(root, &mut per_language, &mut manifests, cancel);
+
+    let total_files: u32 = per_language.values().map(|acc| acc.files).sum();
+    let total_lines: u32 = per_language.values().map(|acc| acc.lines).sum();
+    let total_decisions: u32 = per_language.values().map(|acc| acc.decision_points).sum();
+
+    let mut languages: Vec<LanguageStats> = per_language
+        .into_iter()
+        .map(|(language, acc)| LanguageStats {
+            language: language.to_string(),
+            file_count: acc.files,
+            line_count: acc.lines,
+            percentage: if total_lines > 0 {
+                acc.lines as f32 / total_lines as f32 * 100.0
+            } else {
+                0.0
+            },
+            complexity_score: complexity_score(&acc),
+            maintenance_score: maintenance_score(&acc),
+        })
+        .collect();
+    languages.sort_by(|a, b| b.line_count.cmp(&a.line_count).then_with(|| a.language.cmp(&b.language)));
+
+    let mut frameworks: Vec<FrameworkInfo> = manifests
+        .iter()
+        .flat_map(|manifest| detect_frameworks(root, manifest))
+        .collect();
+    frameworks.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.usage_scope.cmp(&b.usage_scope)));
+    frameworks.dedup_by(|a, b| a.name == b.name && a.usage_scope == b.usage_scope);
+
+    CodebaseScan {
+        commit_hash: None,
+        total_files,
+        total_lines,
+        languages,
+        frameworks,
+        average_complexity: if total_files > 0 {
+            1.0 + total_decisions as f32 / total_files as f32
+        } else {
+            0.0
+        },
+        scanned_at: Some(Utc::now()),
+    }
+}
+
+/// 收集工作空间中所有可识别语言的源代码文件
+pub(crate) fn collect_source_files(root: &Path) -> Vec<PathBuf> {
+    fn walk(dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if path.is_dir() {
+                if !name.starts_with('.') && !IGNORED_DIRS.contains(&name.as_ref()) {
+                    walk(&path, files);
+                }
+            } else if detect_language(&path).is_some() {
+                files.push(path);
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    walk(root, &mut files);
+    files.sort();
+    files
+}
+
+fn visit_dir(
+    dir: &Path,
+    per_language: &mut HashMap<&'static str, LanguageAccumulator>,
+    manifests: &mut Vec<PathBuf>,
+    cancel: Option<&CancellationToken>,
+) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        if cancel.is_some_and(CancellationToken::is_cancelled) {
+            return;
+        }
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+
+        if path.is_dir() {
+            if name.starts_with('.') || IGNORED_DIRS.contains(&name.as_ref()) {
+                continue;
+            }
+            visit_dir(&path, per_language, manifests, cancel);
+            continue;
+        }
+
+        if matches!(name.as_ref(), "package.json" | "Cargo.toml" | "requirements.txt") {
+            manifests.push(path.clone());
+        }
+
+        let Some(language) = detect_language(&path) else {
+            continue;
+        };
+        let Ok(content) = std::fs::read(&path) else {
+            continue;
+        };
+        let content = String::from_utf8_lossy(&content);
+
+        let acc = per_language.entry(language).or_default();
+        acc.files += 1;
+        for line in content.lines() {
+            acc.lines += 1;
+            let line = line.trim_start();
+            if line.starts_with("//") || line.starts_with('#') {
+                continue;
+            }
+            acc.decision_points += DECISION_TOKENS
+                .iter()
+                .map(|token| line.matches(token).count() as u32)
+                .sum::<u32>();
+        }
+    }
+}
+
+/// 复杂度评分（1-10）：每百行分支判定点越多评分越高
+fn complexity_score(acc: &LanguageAccumulator) -> u8 {
+    if acc.lines == 0 {
+        return 1;
+    }
+    let density = acc.decision_points as f32 * 100.0 / acc.lines as f32;
+    (1.0 + density / 2.0).round().clamp(1.0, 10.0) as u8
+}
+
+/// 维护难度评分（1-10）：综合平均文件长度和复杂度
+fn maintenance_score(acc: &LanguageAccumulator) -> u8 {
+    if acc.files == 0 {
+        return 1;
+    }
+    let average_file_lines = acc.lines as f32 / acc.files as f32;
+    let length_factor = (average_file_lines / 100.0).min(5.0);
+    (1.0 + length_factor + complexity_score(acc) as f32 / 2.0)
+        .round()
+        .clamp(1.0, 10.0) as u8
+}
+
+/// 解析依赖清单并识别已知框架
+fn detect_frameworks(root: &Path, manifest: &Path) -> Vec<FrameworkInfo> {
+    let Ok(content) = std::fs::read_to_string(manifest) else {
+        return Vec::new();
+    };
+    let usage_scope = manifest
+        .strip_prefix(root)
+        .unwrap_or(manifest)
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    let dependencies = match manifest.file_name().and_then(|n| n.to_str()) {
+        Some("package.json") => parse_package_json(&content),
+        Some("Cargo.toml") => parse_cargo_toml(&content),
+        Some("requirements.txt") => parse_requirements(&content),
+        _ => Vec::new(),
+    };
+
+    dependencies
+        .into_iter()
+        .filter_map(|(dependency, version)| {
+            let (_, name) = KNOWN_FRAMEWORKS
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(&dependency))?;
+            let unpinned = version.is_empty() || version == "*" || version == "latest";
+
+            Some(FrameworkInfo {
+                name: name.to_string(),
+                version: if version.is_empty() { "*".to_string() } else { version },
+                usage_scope: usage_scope.clone(),
+                configuration_status: if unpinned {
+                    ConfigurationStatus::NeedsUpdate
+                } else {
+                    ConfigurationStatus::Unknown
+                },
+                update_recommendation: unpinned.then(|| format!("为 {dependency} 指定明确的版本号")),
+            })
+        })
+        .collect()
+}
+
+/// 读取 package.json 中的 dependencies 和 devDependencies
+fn parse_package_json(content: &str) -> Vec<(String, String)> {
+    let Ok(manifest) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+
+    ["dependencies", "devDependencies"]
+        .iter()
+        .filter_map(|section| manifest.get(section)?.as_object())
+        .flat_map(|deps| {
+            deps.iter().map(|(name, version)| {
+                (name.clone(), version.as_str().unwrap_or_default().to_string())
+            })
+        })
+        .collect()
+}
+
+/// 逐行读取 Cargo.toml 的依赖段（不完整解析TOML，只识别常见写法）
+fn parse_cargo_toml(content: &str) -> Vec<(String, String)> {
+    let mut in_dependencies = false;
+    let mut dependencies = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            let section = line.trim_matches(|c| c == '[' || c == ']');
+            in_dependencies = section.ends_with("dependencies");
+            continue;
+        }
+        if !in_dependencies || line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name.trim().to_string();
+        let value = value.trim();
+
+        let version = if value.starts_with('"') {
+            value.trim_matches('"').to_string()
+        } else {
+            value
+                .split("version")
+                .nth(1)
+                .and_then(|rest| rest.split('"').nth(1))
+                .unwrap_or_default()
+                .to_string()
+        };
+        dependencies.push((name, version));
+    }
+
+    dependencies
+}
+
+/// 读取 requirements.txt 中的包名和版本约束
+fn parse_requirements(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('-'))
+        .map(|line| {
+            let split_at = line
+                .find(['=', '>', '<', '~', '!', '[', ';'])
+                .unwrap_or(line.len());
+            let name = line[..split_at].trim().to_string();
+            let version = line[split_at..]
+                .trim_start_matches(['=', '>', '<', '~', '!'])
+                .split([',', ';'])
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            (name, version)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_parsing() {
+        let cargo = "[package]\nname = \"demo\"\n\n[dependencies]\ntokio = { version = \"1.0\", features = [\"full\"] }\nserde = \"1\"\n";
+        let deps = parse_cargo_toml(cargo);
+        assert!(deps.contains(&("tokio".to_string(), "1.0".to_string())));
+        assert!(deps.contains(&("serde".to_string(), "1".to_string())));
+        assert!(!deps.iter().any(|(name, _)| name == "name"));
+
+        let requirements = "django>=4.2,<5\n# 注释\nflask\n";
+        let deps = parse_requirements(requirements);
+        assert_eq!(deps[0], ("django".to_string(), "4.2".to_string()));
+        assert_eq!(deps[1], ("flask".to_string(), String::new()));
+
+        let package = r#"{"dependencies": {"react": "^18.2.0"}, "devDependencies": {"vite": "latest"}}"#;
+        let deps = parse_package_json(package);
+        assert_eq!(deps.len(), 2);
+    }
+
+    #[test]
+    fn test_complexity_score_grows_with_branches() {
+        let simple = LanguageAccumulator { files: 1, lines: 100, decision_points: 2 };
+        let branchy = LanguageAccumulator { files: 1, lines: 100, decision_points: 15 };
+        assert!(complexity_score(&branchy) > complexity_score(&simple));
+        assert!(maintenance_score(&branchy) >= maintenance_score(&simple));
+    }
+}
@@ -0,0 +1,330 @@
+//! 项目上下文快照比较
+//!
+//! 重新分解需求时，规划者需要知道距上次分解项目发生了什么变化。[`diff_contexts`] 比较两份
+//! `ProjectContext`，得出结构化的变更集：新增和消除的风险、总体风险级别、变化的指标、
+//! 新完成的任务以及更新的共享上下文条目，[`ContextDiff::render_prompt_section`] 把变更集渲染为
+//! 分解提示词的一个章节。
+//!
+//! 每次构建分解提示词时 [`record_snapshot`] 保存一份快照，下一次分解与最近一次快照比较。
+
+use codex_multi_agent::llm_orchestration::{ProjectContext, RiskItem, RiskLevel, RiskType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::{
+    entities::context_snapshot,
+    repository::ContextSnapshotRepository,
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 浮点指标被视为变化的最小差值
+const METRIC_TOLERANCE: f64 = 1e-6;
+
+/// 两份项目上下文之间的变更集
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContextDiff {
+    /// 新出现的风险
+    pub new_risks: Vec<RiskChange>,
+    /// 已消除的风险
+    pub resolved_risks: Vec<RiskChange>,
+    /// 总体风险级别变化（之前, 现在）
+    pub risk_level_change: Option<(RiskLevel, RiskLevel)>,
+    /// 发生变化的指标
+    pub changed_metrics: Vec<MetricChange>,
+    /// 新完成的任务
+    pub completed_tasks: Vec<CompletedTaskChange>,
+    /// 新增或更新的共享上下文条目
+    pub shared_context_changes: Vec<SharedContextChange>,
+}
+
+/// 风险变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskChange {
+    pub risk_id: String,
+    pub description: String,
+    pub risk_type: RiskType,
+    pub risk_score: f32,
+}
+
+impl From<&RiskItem> for RiskChange {
+    fn from(item: &RiskItem) -> Self {
+        Self {
+            risk_id: item.risk_id.clone(),
+            description: item.description.clone(),
+            risk_type: item.risk_type.clone(),
+            risk_score: item.risk_score,
+        }
+    }
+}
+
+/// 指标变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricChange {
+    /// 指标标识，如 `test_coverage`
+    pub metric: String,
+    /// 显示名称
+    pub label: String,
+    pub previous: f64,
+    pub current: f64,
+}
+
+impl MetricChange {
+    /// 变化量（现在 - 之前）
+    pub fn delta(&self) -> f64 {
+        self.current - self.previous
+    }
+}
+
+/// 新完成的任务
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompletedTaskChange {
+    pub task_id: Uuid,
+    pub title: String,
+}
+
+/// 共享上下文条目变化
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedContextChange {
+    pub key: String,
+    /// 当前版本
+    pub version: u32,
+    /// 上次快照中没有该条目
+    pub added: bool,
+}
+
+impl ContextDiff {
+    /// 两份上下文之间是否没有变化
+    pub fn is_empty(&self) -> bool {
+        self.new_risks.is_empty()
+            && self.resolved_risks.is_empty()
+            && self.risk_level_change.is_none()
+            && self.changed_metrics.is_empty()
+            && self.completed_tasks.is_empty()
+            && self.shared_context_changes.is_empty()
+    }
+
+    /// 渲染为分解提示词片段，没有变化时返回空字符串
+    pub fn render_prompt_section(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        let mut section = String::from(
+            "## 与上次分解相比的变化\n已完成的工作不要重复安排，新增风险需要有相应的任务应对：\n",
+        );
+        if !self.completed_tasks.is_empty() {
+            section.push_str("### 新完成的任务\n");
+            for task in &self.completed_tasks {
+                section.push_str(&format!("- {}\n", task.title));
+            }
+        }
+        if !self.new_risks.is_empty() {
+            section.push_str("### 新增风险\n");
+            for risk in &self.new_risks {
+                section.push_str(&format!("- {}（风险值 {:.2}）\n", risk.description, risk.risk_score));
+            }
+        }
+        if !self.resolved_risks.is_empty() {
+            section.push_str("### 已消除的风险\n");
+            for risk in &self.resolved_risks {
+                section.push_str(&format!("- {}\n", risk.description));
+            }
+        }
+        if let Some((previous, current)) = &self.risk_level_change {
+            section.push_str(&format!(
+                "### 总体风险级别\n- {} → {}\n",
+                risk_level_label(previous),
+                risk_level_label(current)
+            ));
+        }
+        if !self.changed_metrics.is_empty() {
+            section.push_str("### 指标变化\n");
+            for metric in &self.changed_metrics {
+                section.push_str(&format!(
+                    "- {}: {} → {}\n",
+                    metric.label,
+                    format_value(metric.previous),
+                    format_value(metric.current)
+                ));
+            }
+        }
+        if !self.shared_context_changes.is_empty() {
+            section.push_str("### 共享上下文更新\n");
+            for change in &self.shared_context_changes {
+                let action = if change.added { "新增" } else { "更新" };
+                section.push_str(&format!("- {}: {}（版本 {}）\n", action, change.key, change.version));
+            }
+        }
+        section
+    }
+}
+
+/// 比较两份项目上下文
+pub fn diff_contexts(previous: &ProjectContext, current: &ProjectContext) -> ContextDiff {
+    let previous_risk_ids: HashSet<&str> = previous
+        .risk_assessment
+        .risk_items
+        .iter()
+        .map(|risk| risk.risk_id.as_str())
+        .collect();
+    let current_risk_ids: HashSet<&str> = current
+        .risk_assessment
+        .risk_items
+        .iter()
+        .map(|risk| risk.risk_id.as_str())
+        .collect();
+    let new_risks = current
+        .risk_assessment
+        .risk_items
+        .iter()
+        .filter(|risk| !previous_risk_ids.contains(risk.risk_id.as_str()))
+        .map(RiskChange::from)
+        .collect();
+    let resolved_risks = previous
+        .risk_assessment
+        .risk_items
+        .iter()
+        .filter(|risk| !current_risk_ids.contains(risk.risk_id.as_str()))
+        .map(RiskChange::from)
+        .collect();
+
+    let previous_level = &previous.risk_assessment.overall_risk_level;
+    let current_level = &current.risk_assessment.overall_risk_level;
+    let risk_level_change = (previous_level != current_level).then(|| (previous_level.clone(), current_level.clone()));
+
+    let previous_metrics = metrics(previous);
+    let changed_metrics = metrics(current)
+        .into_iter()
+        .zip(previous_metrics)
+        .filter(|((_, _, current), (_, _, previous))| (current - previous).abs() > METRIC_TOLERANCE)
+        .map(|((metric, label, current), (_, _, previous))| MetricChange {
+            metric: metric.to_string(),
+            label: label.to_string(),
+            previous,
+            current,
+        })
+        .collect();
+
+    let previously_completed: HashSet<Uuid> = previous
+        .completed_tasks_summary
+        .iter()
+        .map(|task| task.task_id.0)
+        .collect();
+    let completed_tasks = current
+        .completed_tasks_summary
+        .iter()
+        .filter(|task| !previously_completed.contains(&task.task_id.0))
+        .map(|task| CompletedTaskChange {
+            task_id: task.task_id.0,
+            title: task.title.clone(),
+        })
+        .collect();
+
+    let previous_entries: HashMap<&str, u32> = previous
+        .shared_context
+        .iter()
+        .map(|entry| (entry.key.as_str(), entry.version))
+        .collect();
+    let shared_context_changes = current
+        .shared_context
+        .iter()
+        .filter(|entry| previous_entries.get(entry.key.as_str()) != Some(&entry.version))
+        .map(|entry| SharedContextChange {
+            key: entry.key.clone(),
+            version: entry.version,
+            added: !previous_entries.contains_key(entry.key.as_str()),
+        })
+        .collect();
+
+    ContextDiff {
+        new_risks,
+        resolved_risks,
+        risk_level_change,
+        changed_metrics,
+        completed_tasks,
+        shared_context_changes,
+    }
+}
+
+/// 保存项目上下文快照
+pub async fn record_snapshot(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    document_id: Option<Uuid>,
+    context: &ProjectContext,
+) -> Result<context_snapshot::Model> {
+    ContextSnapshotRepository::new(db.clone())
+        .create(project_id, document_id, serde_json::to_value(context)?)
+        .await
+}
+
+/// 读取项目最近一次保存的上下文
+pub async fn latest_context(db: &DatabaseConnection, project_id: Uuid) -> Result<Option<ProjectContext>> {
+    ContextSnapshotRepository::new(db.clone())
+        .find_latest_by_project(project_id)
+        .await?
+        .map(|snapshot| decode(&snapshot))
+        .transpose()
+}
+
+/// 比较同一项目的两份已保存快照
+pub async fn diff_snapshots(db: &DatabaseConnection, from_snapshot_id: Uuid, to_snapshot_id: Uuid) -> Result<ContextDiff> {
+    let repo = ContextSnapshotRepository::new(db.clone());
+    let from = repo
+        .find_by_id(from_snapshot_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ContextSnapshot", from_snapshot_id))?;
+    let to = repo
+        .find_by_id(to_snapshot_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ContextSnapshot", to_snapshot_id))?;
+    if from.project_id != to.project_id {
+        return Err(DatabaseError::validation("只能比较同一项目的上下文快照"));
+    }
+    Ok(diff_contexts(&decode(&from)?, &decode(&to)?))
+}
+
+fn decode(snapshot: &context_snapshot::Model) -> Result<ProjectContext> {
+    serde_json::from_value(snapshot.context.clone())
+        .map_err(|e| DatabaseError::column_decode("ContextSnapshot", "context", e))
+}
+
+/// 参与比较的指标：(标识, 显示名称, 值)
+fn metrics(context: &ProjectContext) -> Vec<(&'static str, &'static str, f64)> {
+    let codebase = &context.codebase_info;
+    let quality = &codebase.quality_metrics;
+    vec![
+        ("total_files", "文件数", f64::from(codebase.total_files)),
+        ("total_lines", "代码行数", f64::from(codebase.total_lines)),
+        ("test_coverage", "测试覆盖率", f64::from(quality.test_coverage)),
+        ("average_complexity", "平均圈复杂度", f64::from(quality.average_complexity)),
+        ("duplication_rate", "代码重复率", f64::from(quality.duplication_rate)),
+        ("style_violations", "规范违规数", f64::from(quality.style_violations)),
+        ("security_issues", "安全问题数", f64::from(quality.security_issues)),
+        ("performance_issues", "性能问题数", f64::from(quality.performance_issues)),
+        ("overall_quality_score", "整体质量评分", f64::from(quality.overall_quality_score)),
+        ("technical_debt_hours", "技术债务（小时）", f64::from(codebase.technical_debt.estimated_hours)),
+        ("active_tasks", "进行中的任务数", context.active_tasks.len() as f64),
+        ("available_agents", "可用Agent数", context.resource_availability.available_agents.len() as f64),
+        ("resource_gaps", "资源缺口数", context.resource_availability.resource_gaps.len() as f64),
+    ]
+}
+
+fn risk_level_label(level: &RiskLevel) -> &'static str {
+    match level {
+        RiskLevel::Low => "低",
+        RiskLevel::Medium => "中",
+        RiskLevel::High => "高",
+        RiskLevel::Critical => "极高",
+    }
+}
+
+/// 整数指标按整数显示，比例等小数保留两位
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.2}", value)
+    }
+}
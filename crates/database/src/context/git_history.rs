@@ -0,0 +1,145 @@
+//! Git提交历史采集
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use codex_multi_agent::llm_orchestration::CommitStats;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tokio::process::Command;
+
+/// 单条提交记录
+#[derive(Debug, Clone)]
+pub struct CommitRecord {
+    /// 提交哈希
+    pub hash: String,
+    /// 作者
+    pub author: String,
+    /// 提交时间
+    pub committed_at: DateTime<Utc>,
+    /// 提交标题
+    pub subject: String,
+}
+
+/// 读取最近若干天内的提交记录
+///
+/// 工作空间不是Git仓库或git不可用时返回空列表
+pub async fn read_recent_commits(workspace: &Path, days: i64) -> Vec<CommitRecord> {
+    let output = Command::new("git")
+        .arg("log")
+        .arg(format!("--since={days}.days"))
+        .arg("--pretty=format:%H%x1f%an%x1f%ct%x1f%s")
+        .current_dir(workspace)
+        .output()
+        .await;
+
+    let Ok(output) = output else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_commit_line)
+        .collect()
+}
+
+fn parse_commit_line(line: &str) -> Option<CommitRecord> {
+    let mut parts = line.splitn(4, '\u{1f}');
+    let hash = parts.next()?.to_string();
+    let author = parts.next()?.to_string();
+    let timestamp = parts.next()?.parse::<i64>().ok()?;
+    let subject = parts.next().unwrap_or_default().to_string();
+
+    Some(CommitRecord {
+        hash,
+        author,
+        committed_at: Utc.timestamp_opt(timestamp, 0).single()?,
+        subject,
+    })
+}
+
+/// 读取工作空间当前的HEAD提交哈希
+///
+/// 工作空间不是Git仓库或git不可用时返回 `None`
+pub async fn current_commit_hash(workspace: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(workspace)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let hash = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!hash.is_empty()).then_some(hash)
+}
+
+/// 根据提交标题识别提交类型（约定式提交前缀）
+pub fn commit_type(subject: &str) -> String {
+    let prefix = subject
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('!')
+        .trim()
+        .to_ascii_lowercase();
+
+    match prefix.as_str() {
+        "feat" | "fix" | "docs" | "style" | "refactor" | "perf" | "test" | "build" | "ci" | "chore" => prefix,
+        _ => "other".to_string(),
+    }
+}
+
+/// 汇总提交统计
+pub fn summarize_commits(commits: &[CommitRecord], days: i64) -> CommitStats {
+    let now = Utc::now();
+    let window_start = now - Duration::days(days);
+    let recent: Vec<&CommitRecord> = commits.iter().filter(|c| c.committed_at >= window_start).collect();
+
+    let contributors: HashSet<&str> = recent.iter().map(|c| c.author.as_str()).collect();
+    let mut commit_type_distribution: HashMap<String, u32> = HashMap::new();
+    for commit in &recent {
+        *commit_type_distribution.entry(commit_type(&commit.subject)).or_insert(0) += 1;
+    }
+
+    CommitStats {
+        commits_last_30_days: recent.len() as u32,
+        active_contributors: contributors.len() as u32,
+        average_commits_per_day: if days > 0 { recent.len() as f32 / days as f32 } else { 0.0 },
+        commit_type_distribution,
+        last_commit_time: commits.iter().map(|c| c.committed_at).max().unwrap_or(now),
+    }
+}
+
+/// 采集工作空间最近的提交统计
+pub async fn collect_commit_stats(workspace: &Path, days: i64) -> CommitStats {
+    let commits = read_recent_commits(workspace, days).await;
+    summarize_commits(&commits, days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_type_and_summary() {
+        assert_eq!(commit_type("feat(agent): 新增能力匹配"), "feat");
+        assert_eq!(commit_type("fix!: 修复迁移"), "fix");
+        assert_eq!(commit_type("更新文档"), "other");
+
+        let line = format!("abc123\u{1f}alice\u{1f}{}\u{1f}feat: 初始化", Utc::now().timestamp());
+        let commit = parse_commit_line(&line).unwrap();
+        assert_eq!(commit.author, "alice");
+
+        let stats = summarize_commits(&[commit], 30);
+        assert_eq!(stats.commits_last_30_days, 1);
+        assert_eq!(stats.active_contributors, 1);
+        assert_eq!(stats.commit_type_distribution.get("feat"), Some(&1));
+    }
+}
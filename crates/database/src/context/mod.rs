@@ -0,0 +1,16 @@
+//! 项目上下文构建模块
+//!
+//! 从数据库（任务、冲突、Agent、里程碑、项目黑板）和工作空间（代码统计、Git提交、
+//! 技术债务）采集实时数据，生成用于任务分解提示词的完整 `ProjectContext`
+
+pub mod builder;
+pub mod codebase_scanner;
+pub mod diff;
+pub mod git_history;
+pub mod technical_debt;
+
+pub use builder::ContextBuilder;
+pub use codebase_scanner::{CodebaseScan, CodebaseScanner};
+pub use diff::{diff_contexts, ContextDiff};
+pub use git_history::{collect_commit_stats, current_commit_hash, CommitRecord};
+pub use technical_debt::{analyze_technical_debt, DebtFinding, TechnicalDebtReport};
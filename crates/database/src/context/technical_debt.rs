@@ -0,0 +1,428 @@
+//! 技术债务检测
+//!
+//! 基于启发式规则分析工作空间：重复代码块、过长函数、TODO/FIXME标记
+//! 以及缺少对应测试的源文件，汇总为 `TechnicalDebtMetrics`
+
+use crate::context::codebase_scanner::{collect_source_files, detect_language};
+use codex_multi_agent::llm_orchestration::{DebtTrend, DebtType, TechnicalDebtMetrics};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// 判定为重复代码的最小连续行数
+const DUPLICATE_WINDOW: usize = 6;
+
+/// 超过该行数的函数视为过长
+const LONG_FUNCTION_LINES: usize = 60;
+
+/// 超过该行数的函数视为严重过长
+const VERY_LONG_FUNCTION_LINES: usize = 120;
+
+/// 低于该行数的源文件不要求对应测试
+const MIN_LINES_REQUIRING_TESTS: usize = 20;
+
+/// 债务估算变化超过该比例才视为趋势变化
+const TREND_THRESHOLD: f32 = 0.1;
+
+/// 单条债务发现
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebtFinding {
+    /// 债务类型
+    pub debt_type: DebtType,
+    /// 严重程度（low / medium / high）
+    pub severity: String,
+    /// 相对工作空间的文件路径
+    pub file: String,
+    /// 行号（从1开始）
+    pub line: Option<u32>,
+    /// 问题描述
+    pub description: String,
+    /// 预估修复时间（分钟）
+    pub estimated_minutes: u32,
+}
+
+/// 技术债务分析报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TechnicalDebtReport {
+    /// 所有债务发现
+    pub findings: Vec<DebtFinding>,
+    /// 重复代码块数量
+    pub duplicate_blocks: u32,
+    /// 重复代码行数
+    pub duplicated_lines: u32,
+    /// 过长函数数量
+    pub long_functions: u32,
+    /// TODO标记数量
+    pub todo_count: u32,
+    /// FIXME标记数量
+    pub fixme_count: u32,
+    /// 缺少测试的源文件
+    pub untested_files: Vec<String>,
+}
+
+impl TechnicalDebtReport {
+    /// 预估偿还全部债务所需时间（小时，向上取整）
+    pub fn estimated_hours(&self) -> u32 {
+        let minutes: u32 = self.findings.iter().map(|f| f.estimated_minutes).sum();
+        minutes.div_ceil(60)
+    }
+
+    /// 按严重程度统计发现数量
+    pub fn severity_distribution(&self) -> HashMap<String, u32> {
+        let mut distribution = HashMap::new();
+        for finding in &self.findings {
+            *distribution.entry(finding.severity.clone()).or_insert(0) += 1;
+        }
+        distribution
+    }
+
+    /// 按预估修复时间排序的主要债务类型（最多三种）
+    pub fn main_debt_types(&self) -> Vec<DebtType> {
+        let mut minutes_by_type: Vec<(DebtType, u32)> = Vec::new();
+        for finding in &self.findings {
+            match minutes_by_type.iter_mut().find(|(t, _)| *t == finding.debt_type) {
+                Some((_, minutes)) => *minutes += finding.estimated_minutes,
+                None => minutes_by_type.push((finding.debt_type.clone(), finding.estimated_minutes)),
+            }
+        }
+        minutes_by_type.sort_by_key(|(_, minutes)| std::cmp::Reverse(*minutes));
+        minutes_by_type.into_iter().take(3).map(|(t, _)| t).collect()
+    }
+
+    /// 转换为协议中的技术债务指标，趋势相对上一次的预估时间计算
+    pub fn to_metrics(&self, previous_hours: Option<u32>) -> TechnicalDebtMetrics {
+        let estimated_hours = self.estimated_hours();
+        TechnicalDebtMetrics {
+            estimated_hours,
+            severity_distribution: self.severity_distribution(),
+            main_debt_types: self.main_debt_types(),
+            trend: debt_trend(previous_hours, estimated_hours),
+        }
+    }
+}
+
+/// 比较两次扫描的债务估算得出趋势
+pub fn debt_trend(previous_hours: Option<u32>, current_hours: u32) -> DebtTrend {
+    let Some(previous) = previous_hours else {
+        return DebtTrend::Stable;
+    };
+    let baseline = previous.max(1) as f32;
+    let change = (current_hours as f32 - previous as f32) / baseline;
+
+    if change > TREND_THRESHOLD {
+        DebtTrend::Increasing
+    } else if change < -TREND_THRESHOLD {
+        DebtTrend::Decreasing
+    } else {
+        DebtTrend::Stable
+    }
+}
+
+/// 趋势的存储字符串
+pub fn trend_to_str(trend: &DebtTrend) -> &'static str {
+    match trend {
+        DebtTrend::Increasing => "increasing",
+        DebtTrend::Stable => "stable",
+        DebtTrend::Decreasing => "decreasing",
+    }
+}
+
+/// 分析工作空间的技术债务
+pub fn analyze_technical_debt(root: &Path) -> TechnicalDebtReport {
+    let files: Vec<(String, String)> = collect_source_files(root)
+        .into_iter()
+        .filter_map(|path| {
+            let content = std::fs::read(&path).ok()?;
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            Some((relative, String::from_utf8_lossy(&content).into_owned()))
+        })
+        .collect();
+
+    let mut report = TechnicalDebtReport::default();
+    detect_markers(&files, &mut report);
+    detect_long_functions(&files, &mut report);
+    detect_duplicates(&files, &mut report);
+    detect_missing_tests(&files, &mut report);
+    report
+}
+
+/// 是否为注释行
+fn is_comment(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("//") || line.starts_with('#') || line.starts_with("/*") || line.starts_with('*')
+}
+
+/// 统计TODO/FIXME/HACK标记
+fn detect_markers(files: &[(String, String)], report: &mut TechnicalDebtReport) {
+    for (file, content) in files {
+        for (index, line) in content.lines().enumerate() {
+            if !is_comment(line) {
+                continue;
+            }
+            let (severity, minutes, label) = if line.contains("FIXME") || line.contains("HACK") {
+                report.fixme_count += 1;
+                ("medium", 30, "FIXME")
+            } else if line.contains("TODO") {
+                report.todo_count += 1;
+                ("low", 15, "TODO")
+            } else {
+                continue;
+            };
+
+            report.findings.push(DebtFinding {
+                debt_type: DebtType::PoorDesign,
+                severity: severity.to_string(),
+                file: file.clone(),
+                line: Some(index as u32 + 1),
+                description: format!("{label}: {}", line.trim()),
+                estimated_minutes: minutes,
+            });
+        }
+    }
+}
+
+/// 是否为函数定义行
+fn is_function_start(line: &str) -> bool {
+    let line = line.trim_start();
+    let line = line
+        .strip_prefix("pub(crate) ")
+        .or_else(|| line.strip_prefix("pub "))
+        .or_else(|| line.strip_prefix("export "))
+        .unwrap_or(line);
+    let line = line.strip_prefix("async ").unwrap_or(line);
+    line.starts_with("fn ") || line.starts_with("function ") || line.starts_with("def ")
+}
+
+/// 计算函数体长度：花括号语言按括号配对，Python按缩进
+fn function_length(lines: &[&str], start: usize, indentation_based: bool) -> usize {
+    if indentation_based {
+        let indent = lines[start].len() - lines[start].trim_start().len();
+        let body = lines[start + 1..]
+            .iter()
+            .take_while(|line| {
+                line.trim().is_empty() || line.len() - line.trim_start().len() > indent
+            })
+            .count();
+        return body + 1;
+    }
+
+    let mut depth = 0i32;
+    let mut opened = false;
+    for (offset, line) in lines[start..].iter().enumerate() {
+        for ch in line.chars() {
+            match ch {
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        if opened && depth <= 0 {
+            return offset + 1;
+        }
+        // 没有函数体的声明（如trait方法签名）
+        if !opened && line.trim_end().ends_with(';') {
+            return offset + 1;
+        }
+    }
+    lines.len() - start
+}
+
+/// 检测过长函数
+fn detect_long_functions(files: &[(String, String)], report: &mut TechnicalDebtReport) {
+    for (file, content) in files {
+        let indentation_based = detect_language(Path::new(file)) == Some("Python");
+        let lines: Vec<&str> = content.lines().collect();
+
+        for (index, line) in lines.iter().enumerate() {
+            if !is_function_start(line) {
+                continue;
+            }
+            let length = function_length(&lines, index, indentation_based);
+            if length <= LONG_FUNCTION_LINES {
+                continue;
+            }
+
+            report.long_functions += 1;
+            report.findings.push(DebtFinding {
+                debt_type: DebtType::ComplexLogic,
+                severity: if length > VERY_LONG_FUNCTION_LINES { "high" } else { "medium" }.to_string(),
+                file: file.clone(),
+                line: Some(index as u32 + 1),
+                description: format!("函数长度 {length} 行: {}", line.trim()),
+                estimated_minutes: (length / 2) as u32,
+            });
+        }
+    }
+}
+
+/// 检测重复代码块（连续若干行规范化后完全相同）
+fn detect_duplicates(files: &[(String, String)], report: &mut TechnicalDebtReport) {
+    let mut seen: HashMap<String, (String, usize)> = HashMap::new();
+
+    for (file, content) in files {
+        // 只保留有实际内容的行，同时记录原始行号
+        let lines: Vec<(usize, String)> = content
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i, line.split_whitespace().collect::<Vec<_>>().join(" ")))
+            .filter(|(_, line)| line.len() > 2 && !is_comment(line))
+            .collect();
+
+        let mut index = 0;
+        while index + DUPLICATE_WINDOW <= lines.len() {
+            let window = lines[index..index + DUPLICATE_WINDOW]
+                .iter()
+                .map(|(_, line)| line.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let line_number = lines[index].0 + 1;
+
+            match seen.get(&window) {
+                Some((original_file, original_line)) => {
+                    report.duplicate_blocks += 1;
+                    report.duplicated_lines += DUPLICATE_WINDOW as u32;
+                    report.findings.push(DebtFinding {
+                        debt_type: DebtType::CodeDuplication,
+                        severity: "medium".to_string(),
+                        file: file.clone(),
+                        line: Some(line_number as u32),
+                        description: format!("与 {original_file}:{original_line} 重复"),
+                        estimated_minutes: 30,
+                    });
+                    // 跳过整个重复窗口，避免同一段重复被多次计数
+                    index += DUPLICATE_WINDOW;
+                }
+                None => {
+                    seen.insert(window, (file.clone(), line_number));
+                    index += 1;
+                }
+            }
+        }
+    }
+}
+
+/// 是否为测试文件
+fn is_test_file(file: &str) -> bool {
+    let stem = Path::new(file)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    file.split('/').any(|part| matches!(part, "tests" | "test" | "__tests__"))
+        || stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with("_tests")
+        || stem.ends_with(".test")
+        || stem.ends_with(".spec")
+}
+
+/// 测试文件对应的被测模块名
+fn tested_module(file: &str) -> String {
+    let stem = Path::new(file)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stem = stem.strip_prefix("test_").unwrap_or(&stem);
+    [".test", ".spec", "_tests", "_test"]
+        .iter()
+        .find_map(|suffix| stem.strip_suffix(suffix))
+        .unwrap_or(stem)
+        .to_string()
+}
+
+/// 检测缺少对应测试的源文件
+fn detect_missing_tests(files: &[(String, String)], report: &mut TechnicalDebtReport) {
+    let tested: HashSet<String> = files
+        .iter()
+        .filter(|(file, _)| is_test_file(file))
+        .map(|(file, _)| tested_module(file))
+        .collect();
+
+    for (file, content) in files {
+        let path = Path::new(file);
+        let language = detect_language(path);
+        if !matches!(language, Some("Rust" | "TypeScript" | "JavaScript" | "Python")) || is_test_file(file) {
+            continue;
+        }
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        if matches!(stem.as_str(), "mod" | "lib" | "main" | "index" | "__init__" | "build") {
+            continue;
+        }
+        if content.lines().count() < MIN_LINES_REQUIRING_TESTS {
+            continue;
+        }
+        // Rust允许内联测试模块
+        if content.contains("#[cfg(test)]") || tested.contains(&stem) {
+            continue;
+        }
+
+        report.untested_files.push(file.clone());
+        report.findings.push(DebtFinding {
+            debt_type: DebtType::MissingTests,
+            severity: "low".to_string(),
+            file: file.clone(),
+            line: None,
+            description: "未找到对应的测试".to_string(),
+            estimated_minutes: 60,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(name: &str, content: String) -> (String, String) {
+        (name.to_string(), content)
+    }
+
+    #[test]
+    fn test_detects_markers_long_functions_and_duplicates() {
+        let block = (0..8).map(|i| format!("    let value_{i} = compute({i});\n")).collect::<String>();
+        let long_body = (0..70).map(|i| format!("    step({i});\n")).collect::<String>();
+        let files = vec![
+            sample("src/a.rs", format!("// TODO: 拆分\nfn a() {{\n{block}}}\n")),
+            sample("src/b.rs", format!("// FIXME: 临时方案\nfn b() {{\n{block}}}\nfn long() {{\n{long_body}}}\n")),
+        ];
+
+        let mut report = TechnicalDebtReport::default();
+        detect_markers(&files, &mut report);
+        detect_long_functions(&files, &mut report);
+        detect_duplicates(&files, &mut report);
+
+        assert_eq!(report.todo_count, 1);
+        assert_eq!(report.fixme_count, 1);
+        assert_eq!(report.long_functions, 1);
+        assert_eq!(report.duplicate_blocks, 1);
+        assert!(report.estimated_hours() >= 1);
+        assert!(report.main_debt_types().contains(&DebtType::ComplexLogic));
+    }
+
+    #[test]
+    fn test_missing_tests_mapping() {
+        let body = "fn f() {}\n".repeat(MIN_LINES_REQUIRING_TESTS);
+        let files = vec![
+            sample("src/parser.rs", body.clone()),
+            sample("src/lexer.rs", body.clone()),
+            sample("tests/parser_tests.rs", body),
+        ];
+
+        let mut report = TechnicalDebtReport::default();
+        detect_missing_tests(&files, &mut report);
+        assert_eq!(report.untested_files, vec!["src/lexer.rs".to_string()]);
+    }
+
+    #[test]
+    fn test_debt_trend() {
+        assert_eq!(debt_trend(None, 10), DebtTrend::Stable);
+        assert_eq!(debt_trend(Some(10), 20), DebtTrend::Increasing);
+        assert_eq!(debt_trend(Some(10), 5), DebtTrend::Decreasing);
+        assert_eq!(debt_trend(Some(10), 10), DebtTrend::Stable);
+    }
+}
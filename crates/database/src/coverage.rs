@@ -0,0 +1,460 @@
+//! 测试覆盖率报告接入
+//!
+//! 执行会话上报 lcov 或 Cobertura XML 格式的覆盖率报告，[`ingest_session_coverage`] 解析后按文件
+//! 和总体保存行覆盖率，并关联到基准提交（会话的 `base_commit`）上最近一次的报告，用于计算覆盖率变化。
+//!
+//! 覆盖率参与两处质量评估：
+//! - [`ensure_review_can_be_approved`] 在审查批准前检查执行会话的覆盖率不低于门槛。门槛优先取执行配置
+//!   `quality_checks` 中的 `min_coverage_threshold`，否则取项目质量门禁的 `min_test_coverage`；
+//!   执行配置关闭覆盖率检查或会话没有上报覆盖率时不检查；
+//! - [`latest_project_coverage`] 为项目上下文的 `CodeQualityMetrics::test_coverage` 提供最新数据。
+
+use codex_multi_agent::QualityCheckConfig;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+use crate::{
+    entities::{code_review, coverage_report},
+    repository::{
+        coverage_report_repository::SaveCoverageReportData, CoverageReportRepository, ExecutionSessionRepository,
+        OrganizationRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 覆盖率报告格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageFormat {
+    /// lcov 跟踪文件（`SF:`/`DA:` 记录）
+    Lcov,
+    /// Cobertura XML
+    Cobertura,
+}
+
+impl CoverageFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CoverageFormat::Lcov => "lcov",
+            CoverageFormat::Cobertura => "cobertura",
+        }
+    }
+}
+
+impl FromStr for CoverageFormat {
+    type Err = DatabaseError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "lcov" => Ok(CoverageFormat::Lcov),
+            "cobertura" => Ok(CoverageFormat::Cobertura),
+            other => Err(DatabaseError::validation(format!("不支持的覆盖率报告格式: {}", other))),
+        }
+    }
+}
+
+/// 单个文件的行覆盖率
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileCoverage {
+    pub path: String,
+    pub lines_total: u64,
+    pub lines_covered: u64,
+}
+
+impl FileCoverage {
+    /// 行覆盖率，没有可执行行的文件视为完全覆盖
+    pub fn line_rate(&self) -> f64 {
+        rate(self.lines_covered, self.lines_total)
+    }
+}
+
+/// 解析后的覆盖率报告
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageSummary {
+    /// 按路径排序的文件覆盖率
+    pub files: Vec<FileCoverage>,
+    pub lines_total: u64,
+    pub lines_covered: u64,
+}
+
+impl CoverageSummary {
+    pub fn line_rate(&self) -> f64 {
+        rate(self.lines_covered, self.lines_total)
+    }
+
+    /// 由各文件的行命中次数汇总
+    fn from_hits(files: BTreeMap<String, BTreeMap<u64, u64>>) -> Result<Self> {
+        let files: Vec<FileCoverage> = files
+            .into_iter()
+            .map(|(path, lines)| FileCoverage {
+                path,
+                lines_total: lines.len() as u64,
+                lines_covered: lines.values().filter(|hits| **hits > 0).count() as u64,
+            })
+            .collect();
+        Self::from_files(files)
+    }
+
+    fn from_files(files: Vec<FileCoverage>) -> Result<Self> {
+        let lines_total: u64 = files.iter().map(|file| file.lines_total).sum();
+        if lines_total == 0 {
+            return Err(DatabaseError::validation("覆盖率报告中没有可执行的代码行"));
+        }
+        Ok(Self {
+            lines_covered: files.iter().map(|file| file.lines_covered).sum(),
+            lines_total,
+            files,
+        })
+    }
+}
+
+fn rate(covered: u64, total: u64) -> f64 {
+    if total == 0 {
+        1.0
+    } else {
+        covered as f64 / total as f64
+    }
+}
+
+/// 按格式解析覆盖率报告
+pub fn parse_report(format: CoverageFormat, content: &str) -> Result<CoverageSummary> {
+    match format {
+        CoverageFormat::Lcov => parse_lcov(content),
+        CoverageFormat::Cobertura => parse_cobertura(content),
+    }
+}
+
+/// 解析 lcov 跟踪文件
+///
+/// 以 `DA:` 行级记录为准，同一文件出现在多条记录中时合并命中次数；
+/// 记录中没有 `DA:` 时退回使用 `LF:`/`LH:` 汇总值
+pub fn parse_lcov(content: &str) -> Result<CoverageSummary> {
+    let mut hits: BTreeMap<String, BTreeMap<u64, u64>> = BTreeMap::new();
+    let mut totals: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    let mut current: Option<String> = None;
+
+    let number = |value: &str, line_no: usize| {
+        value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| DatabaseError::validation(format!("lcov 第 {} 行格式错误", line_no)))
+    };
+
+    for (index, line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim();
+        let Some((key, value)) = line.split_once(':') else {
+            if line == "end_of_record" {
+                current = None;
+            }
+            continue;
+        };
+        if key == "SF" {
+            let path = value.trim().to_string();
+            hits.entry(path.clone()).or_default();
+            current = Some(path);
+            continue;
+        }
+        if !matches!(key, "DA" | "LF" | "LH") {
+            continue;
+        }
+        let path = current
+            .as_ref()
+            .ok_or_else(|| DatabaseError::validation(format!("lcov 第 {} 行不在任何文件记录中", line_no)))?;
+        match key {
+            "DA" => {
+                let mut parts = value.split(',');
+                let (Some(line), Some(count)) = (parts.next(), parts.next()) else {
+                    return Err(DatabaseError::validation(format!("lcov 第 {} 行格式错误", line_no)));
+                };
+                let (line, count) = (number(line, line_no)?, number(count, line_no)?);
+                let entry = hits.entry(path.clone()).or_default().entry(line).or_default();
+                *entry = entry.saturating_add(count);
+            }
+            "LF" => totals.entry(path.clone()).or_default().0 += number(value, line_no)?,
+            _ => totals.entry(path.clone()).or_default().1 += number(value, line_no)?,
+        }
+    }
+
+    if hits.is_empty() {
+        return Err(DatabaseError::validation("lcov 报告中没有文件记录"));
+    }
+    let files = hits
+        .into_iter()
+        .map(|(path, lines)| match totals.get(&path) {
+            Some((found, hit)) if lines.is_empty() => FileCoverage {
+                path,
+                lines_total: *found,
+                lines_covered: (*hit).min(*found),
+            },
+            _ => FileCoverage {
+                lines_total: lines.len() as u64,
+                lines_covered: lines.values().filter(|hits| **hits > 0).count() as u64,
+                path,
+            },
+        })
+        .collect();
+    CoverageSummary::from_files(files)
+}
+
+fn cobertura_patterns() -> &'static (Regex, Regex, Regex) {
+    static PATTERNS: OnceLock<(Regex, Regex, Regex)> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        (
+            Regex::new(r"(?s)<class\b([^>]*?)(?:/>|>(.*?)</class>)").expect("有效的正则表达式"),
+            Regex::new(r"<line\b[^>]*>").expect("有效的正则表达式"),
+            Regex::new(r#"\b([A-Za-z_-]+)\s*=\s*"([^"]*)""#).expect("有效的正则表达式"),
+        )
+    })
+}
+
+/// 解析 Cobertura XML
+///
+/// 按 `<class filename>` 归并到文件，`<methods>` 与 `<lines>` 中重复出现的行只计一次
+pub fn parse_cobertura(content: &str) -> Result<CoverageSummary> {
+    if !content.contains("<coverage") {
+        return Err(DatabaseError::validation("不是有效的 Cobertura 报告：缺少 coverage 元素"));
+    }
+    let (class_pattern, line_pattern, attribute_pattern) = cobertura_patterns();
+    let attributes = |tag: &str| -> HashMap<String, String> {
+        attribute_pattern
+            .captures_iter(tag)
+            .map(|captures| (captures[1].to_string(), unescape_xml(&captures[2])))
+            .collect()
+    };
+
+    let mut files: BTreeMap<String, BTreeMap<u64, u64>> = BTreeMap::new();
+    for class in class_pattern.captures_iter(content) {
+        let Some(path) = attributes(&class[1]).remove("filename") else {
+            return Err(DatabaseError::validation("Cobertura 报告中的 class 元素缺少 filename"));
+        };
+        let lines = files.entry(path).or_default();
+        let Some(body) = class.get(2) else {
+            continue;
+        };
+        for tag in line_pattern.find_iter(body.as_str()) {
+            let attributes = attributes(tag.as_str());
+            let parse = |name: &str| {
+                attributes
+                    .get(name)
+                    .and_then(|value| value.parse::<u64>().ok())
+                    .ok_or_else(|| DatabaseError::validation(format!("Cobertura 报告中的 line 元素缺少有效的 {}", name)))
+            };
+            let count = parse("hits")?;
+            let entry = lines.entry(parse("number")?).or_default();
+            *entry = (*entry).max(count);
+        }
+    }
+
+    if files.is_empty() {
+        return Err(DatabaseError::validation("Cobertura 报告中没有 class 元素"));
+    }
+    CoverageSummary::from_hits(files)
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 解析并保存执行会话的覆盖率报告，覆盖该会话之前的报告
+pub async fn ingest_session_coverage(
+    db: &DatabaseConnection,
+    session_id: Uuid,
+    format: CoverageFormat,
+    content: &str,
+) -> Result<coverage_report::Model> {
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    let summary = parse_report(format, content)?;
+
+    let repo = CoverageReportRepository::new(db.clone());
+    let base_report = match &session.base_commit {
+        Some(base_commit) => repo.find_latest_by_commit(session.project_id, base_commit, Some(session_id)).await?,
+        None => None,
+    };
+    let line_rate = summary.line_rate();
+    let report = repo
+        .save(SaveCoverageReportData {
+            project_id: session.project_id,
+            execution_session_id: session_id,
+            format: format.as_str().to_string(),
+            commit_sha: session.final_commit.clone().or(session.base_commit.clone()),
+            base_report_id: base_report.map(|report| report.report_id),
+            lines_total: summary.lines_total as i64,
+            lines_covered: summary.lines_covered as i64,
+            line_rate,
+            files: serde_json::to_value(&summary.files)?,
+        })
+        .await?;
+
+    tracing::info!(
+        "执行会话 {} 的覆盖率为 {:.1}%（{}/{} 行）",
+        session_id,
+        line_rate * 100.0,
+        summary.lines_covered,
+        summary.lines_total
+    );
+    Ok(report)
+}
+
+/// 单个文件相对基准的覆盖率变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileCoverageDelta {
+    pub path: String,
+    pub line_rate: f64,
+    /// 基准报告中没有该文件时为空
+    pub base_line_rate: Option<f64>,
+    pub delta: Option<f64>,
+}
+
+/// 执行会话的覆盖率及相对基准提交的变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoverageDelta {
+    pub session_id: Uuid,
+    pub commit_sha: Option<String>,
+    pub line_rate: f64,
+    pub lines_total: i64,
+    pub lines_covered: i64,
+    /// 没有基准报告时为空
+    pub base_line_rate: Option<f64>,
+    pub delta: Option<f64>,
+    /// 文件覆盖率，下降最多的在前，新文件排在最后
+    pub files: Vec<FileCoverageDelta>,
+}
+
+fn report_files(report: &coverage_report::Model) -> Result<Vec<FileCoverage>> {
+    Ok(serde_json::from_value(report.files.clone())?)
+}
+
+/// 获取执行会话的覆盖率及变化，会话没有上报覆盖率时返回 None
+pub async fn session_coverage(db: &DatabaseConnection, session_id: Uuid) -> Result<Option<CoverageDelta>> {
+    let repo = CoverageReportRepository::new(db.clone());
+    let Some(report) = repo.find_by_session(session_id).await? else {
+        return Ok(None);
+    };
+    let base = match report.base_report_id {
+        Some(base_report_id) => repo.find_by_id(base_report_id).await?,
+        None => None,
+    };
+
+    let base_files: HashMap<String, f64> = match &base {
+        Some(base) => report_files(base)?
+            .into_iter()
+            .map(|file| {
+                let line_rate = file.line_rate();
+                (file.path, line_rate)
+            })
+            .collect(),
+        None => HashMap::new(),
+    };
+    let mut files: Vec<FileCoverageDelta> = report_files(&report)?
+        .into_iter()
+        .map(|file| {
+            let line_rate = file.line_rate();
+            let base_line_rate = base_files.get(&file.path).copied();
+            FileCoverageDelta {
+                path: file.path,
+                line_rate,
+                base_line_rate,
+                delta: base_line_rate.map(|base| line_rate - base),
+            }
+        })
+        .collect();
+    files.sort_by(|a, b| match (a.delta, b.delta) {
+        (Some(a_delta), Some(b_delta)) => a_delta.total_cmp(&b_delta),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.path.cmp(&b.path),
+    });
+
+    let base_line_rate = base.map(|base| base.line_rate);
+    Ok(Some(CoverageDelta {
+        session_id,
+        commit_sha: report.commit_sha,
+        line_rate: report.line_rate,
+        lines_total: report.lines_total,
+        lines_covered: report.lines_covered,
+        base_line_rate,
+        delta: base_line_rate.map(|base| report.line_rate - base),
+        files,
+    }))
+}
+
+/// 覆盖率门禁结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoverageGateResult {
+    pub session_id: Uuid,
+    pub line_rate: f64,
+    /// 生效的覆盖率门槛（0.0-1.0）
+    pub threshold: f64,
+    pub delta: Option<f64>,
+    pub passed: bool,
+}
+
+/// 评估执行会话的覆盖率门禁，没有上报覆盖率或执行配置关闭了覆盖率检查时返回 None
+pub async fn evaluate_coverage_gate(db: &DatabaseConnection, session_id: Uuid) -> Result<Option<CoverageGateResult>> {
+    let Some(coverage) = session_coverage(db, session_id).await? else {
+        return Ok(None);
+    };
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+
+    let quality_checks = session
+        .execution_config
+        .as_ref()
+        .and_then(|config| config.get("quality_checks"))
+        .and_then(|checks| serde_json::from_value::<QualityCheckConfig>(checks.clone()).ok());
+    let threshold = match quality_checks {
+        Some(checks) if !checks.enable_coverage_check => return Ok(None),
+        Some(QualityCheckConfig { min_coverage_threshold: Some(threshold), .. }) => threshold,
+        _ => {
+            OrganizationRepository::new(db.clone())
+                .resolve_project_standards(session.project_id)
+                .await?
+                .quality_gates
+                .min_test_coverage
+        }
+    } as f64;
+
+    Ok(Some(CoverageGateResult {
+        session_id,
+        line_rate: coverage.line_rate,
+        threshold,
+        delta: coverage.delta,
+        // 门槛以 f32 配置，比较时允许其精度误差
+        passed: coverage.line_rate + 1e-6 >= threshold,
+    }))
+}
+
+/// 检查审查能否被批准：执行会话上报的覆盖率低于门槛时不能批准
+pub async fn ensure_review_can_be_approved(db: &DatabaseConnection, review: &code_review::Model) -> Result<()> {
+    match evaluate_coverage_gate(db, review.execution_session_id).await? {
+        Some(gate) if !gate.passed => Err(DatabaseError::business_logic(format!(
+            "测试覆盖率为 {:.1}%，低于质量门禁要求的 {:.1}%",
+            gate.line_rate * 100.0,
+            gate.threshold * 100.0
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// 项目最近一次上报的行覆盖率
+pub async fn latest_project_coverage(db: &DatabaseConnection, project_id: Uuid) -> Result<Option<f64>> {
+    Ok(CoverageReportRepository::new(db.clone())
+        .find_latest_by_project(project_id)
+        .await?
+        .map(|report| report.line_rate))
+}
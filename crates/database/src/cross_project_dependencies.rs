@@ -0,0 +1,240 @@
+//! 跨项目任务依赖
+//!
+//! 一个项目的任务可以依赖其他项目的任务。依赖仍记录在 `task_dependencies` 表中，
+//! 以 `is_cross_project` 标记并记录创建者：
+//! - 只有同时是两个项目成员的用户才能创建或删除跨项目依赖，且在依赖任务所在项目中至少为贡献者；
+//! - 创建前沿整个依赖图（不限项目）检测循环；
+//! - 列出项目的跨项目依赖时，对方项目的任务标题只对该项目成员可见。
+//!
+//! 调度方面，[`crate::parallel_planner::ParallelPlanner`] 会等待其他项目的前置任务完成后再启动依赖任务。
+
+use std::collections::{HashMap, HashSet};
+
+use codex_multi_agent::ProjectRole;
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, Set};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::entities::task_dependency::{self, DependencyType};
+use crate::entities::task;
+use crate::repository::{ProjectMemberRepository, TaskDependencyRepository};
+use crate::{DatabaseConnection, DatabaseError, Result};
+
+/// 项目的一条跨项目依赖
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CrossProjectLink {
+    pub dependency_id: Uuid,
+    pub dependency_type: String,
+    /// 本项目中的任务
+    pub task_id: Uuid,
+    /// 对方项目中的任务
+    pub other_task_id: Uuid,
+    pub other_project_id: Uuid,
+    /// 对方任务的标题和状态，用户不是对方项目成员时为 None
+    pub other_task_title: Option<String>,
+    pub other_task_status: Option<String>,
+    /// true 表示本项目任务依赖对方任务，false 表示对方任务依赖本项目任务
+    pub inbound: bool,
+    pub created_by: Option<Uuid>,
+}
+
+/// 创建任务依赖：`dependent_task_id` 依赖 `prerequisite_task_id`
+///
+/// 两个任务属于不同项目时按跨项目依赖校验成员身份
+pub async fn link_tasks(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    prerequisite_task_id: Uuid,
+    dependent_task_id: Uuid,
+    dependency_type: DependencyType,
+) -> Result<task_dependency::Model> {
+    if prerequisite_task_id == dependent_task_id {
+        return Err(DatabaseError::validation("任务不能依赖自身"));
+    }
+    let prerequisite = find_task(db, prerequisite_task_id).await?;
+    let dependent = find_task(db, dependent_task_id).await?;
+    let cross_project = prerequisite.project_id != dependent.project_id;
+
+    require_link_access(db, user_id, &prerequisite, &dependent).await?;
+
+    let dependencies = TaskDependencyRepository::new(db.clone());
+    if dependencies.exists_dependency(dependent_task_id, prerequisite_task_id).await? {
+        return Err(DatabaseError::validation("依赖关系已存在"));
+    }
+    if would_create_cycle(db, prerequisite_task_id, dependent_task_id).await? {
+        return Err(DatabaseError::business_logic(format!(
+            "任务「{}」已直接或间接依赖任务「{}」，不能形成循环依赖",
+            prerequisite.title, dependent.title
+        )));
+    }
+
+    let model = task_dependency::ActiveModel {
+        dependency_id: Set(Uuid::new_v4()),
+        parent_task_id: Set(prerequisite_task_id),
+        child_task_id: Set(dependent_task_id),
+        dependency_type: Set(dependency_type.to_string()),
+        is_cross_project: Set(cross_project),
+        created_by: Set(Some(user_id)),
+        created_at: Set(chrono::Utc::now().into()),
+    }
+    .insert(db)
+    .await?;
+
+    if cross_project {
+        tracing::info!(
+            user_id = %user_id,
+            prerequisite = %prerequisite_task_id,
+            dependent = %dependent_task_id,
+            "创建跨项目任务依赖"
+        );
+    }
+    Ok(model)
+}
+
+/// 删除任务依赖，权限要求与创建相同
+pub async fn unlink_tasks(db: &DatabaseConnection, user_id: Uuid, dependency_id: Uuid) -> Result<()> {
+    let dependency = task_dependency::Entity::find_by_id(dependency_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("TaskDependency", dependency_id))?;
+    let prerequisite = find_task(db, dependency.parent_task_id).await?;
+    let dependent = find_task(db, dependency.child_task_id).await?;
+    require_link_access(db, user_id, &prerequisite, &dependent).await?;
+
+    TaskDependencyRepository::new(db.clone()).delete(dependency_id).await
+}
+
+/// 新增依赖后是否形成循环，即前置任务是否已直接或间接依赖于依赖任务
+///
+/// 沿整个依赖图检测，不限于单个项目
+pub async fn would_create_cycle(
+    db: &DatabaseConnection,
+    prerequisite_task_id: Uuid,
+    dependent_task_id: Uuid,
+) -> Result<bool> {
+    if prerequisite_task_id == dependent_task_id {
+        return Ok(true);
+    }
+    let mut visited = HashSet::from([prerequisite_task_id]);
+    let mut frontier = vec![prerequisite_task_id];
+    while !frontier.is_empty() {
+        let parents = task_dependency::Entity::find()
+            .filter(task_dependency::Column::ChildTaskId.is_in(frontier))
+            .all(db)
+            .await?;
+        frontier = Vec::new();
+        for dependency in parents {
+            if dependency.parent_task_id == dependent_task_id {
+                return Ok(true);
+            }
+            if visited.insert(dependency.parent_task_id) {
+                frontier.push(dependency.parent_task_id);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// 列出项目的跨项目依赖，用户需为项目成员
+pub async fn list_cross_project_links(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    project_id: Uuid,
+) -> Result<Vec<CrossProjectLink>> {
+    let members = ProjectMemberRepository::new(db.clone());
+    members.require_role(project_id, user_id, ProjectRole::Viewer).await?;
+
+    let task_ids: Vec<Uuid> = task::Entity::find()
+        .filter(task::Column::ProjectId.eq(project_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|task| task.task_id)
+        .collect();
+    let dependencies = task_dependency::Entity::find()
+        .filter(task_dependency::Column::IsCrossProject.eq(true))
+        .filter(
+            Condition::any()
+                .add(task_dependency::Column::ChildTaskId.is_in(task_ids.clone()))
+                .add(task_dependency::Column::ParentTaskId.is_in(task_ids.clone())),
+        )
+        .order_by_asc(task_dependency::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    let local: HashSet<Uuid> = task_ids.into_iter().collect();
+    let other_ids: HashSet<Uuid> = dependencies
+        .iter()
+        .flat_map(|dependency| [dependency.parent_task_id, dependency.child_task_id])
+        .filter(|id| !local.contains(id))
+        .collect();
+    let others: HashMap<Uuid, task::Model> = task::Entity::find()
+        .filter(task::Column::TaskId.is_in(other_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|task| (task.task_id, task))
+        .collect();
+
+    let mut visible_projects: HashMap<Uuid, bool> = HashMap::new();
+    let mut links = Vec::with_capacity(dependencies.len());
+    for dependency in dependencies {
+        let inbound = local.contains(&dependency.child_task_id);
+        let (task_id, other_task_id) = if inbound {
+            (dependency.child_task_id, dependency.parent_task_id)
+        } else {
+            (dependency.parent_task_id, dependency.child_task_id)
+        };
+        let Some(other) = others.get(&other_task_id) else {
+            continue;
+        };
+        let visible = match visible_projects.get(&other.project_id) {
+            Some(visible) => *visible,
+            None => {
+                let visible = members.get_role(other.project_id, user_id).await?.is_some();
+                visible_projects.insert(other.project_id, visible);
+                visible
+            }
+        };
+        links.push(CrossProjectLink {
+            dependency_id: dependency.dependency_id,
+            dependency_type: dependency.dependency_type,
+            task_id,
+            other_task_id,
+            other_project_id: other.project_id,
+            other_task_title: visible.then(|| other.title.clone()),
+            other_task_status: visible.then(|| other.status.clone()),
+            inbound,
+            created_by: dependency.created_by,
+        });
+    }
+    Ok(links)
+}
+
+/// 校验用户可以在两个任务之间创建依赖
+///
+/// 需要在依赖任务所在项目中至少为贡献者；跨项目时还需是前置任务所在项目的成员
+async fn require_link_access(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    prerequisite: &task::Model,
+    dependent: &task::Model,
+) -> Result<()> {
+    let members = ProjectMemberRepository::new(db.clone());
+    members.require_role(dependent.project_id, user_id, ProjectRole::Contributor).await?;
+    if prerequisite.project_id != dependent.project_id
+        && members.get_role(prerequisite.project_id, user_id).await?.is_none()
+    {
+        return Err(DatabaseError::business_logic(
+            "跨项目依赖需要同时是两个项目的成员，当前用户不是前置任务所在项目的成员",
+        ));
+    }
+    Ok(())
+}
+
+async fn find_task(db: &DatabaseConnection, task_id: Uuid) -> Result<task::Model> {
+    task::Entity::find_by_id(task_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Task", task_id))
+}
@@ -0,0 +1,444 @@
+//! 用户数据清除
+//!
+//! [`purge_user_data`] 在一个事务内删除或匿名化与用户关联的全部数据，用于满足隐私合规中的删除请求：
+//!
+//! - 用户行改写为不含个人信息的占位记录而不是直接删除，避免外键级联删掉其他成员共享的执行历史；
+//! - 登录会话、API 密钥、外部身份关联、两步验证设置、通知、项目/组织成员关系、用户发起的 LLM 会话直接删除；
+//! - 用户拥有的项目和组织：没有其他成员时删除（依赖数据随外键级联删除），否则转交给权限最高的其他成员；
+//! - 审查者、冲突处理人、检查点审批人、验收签署人、黑板作者、事件操作人等引用置空，
+//!   人工决策保留决策本身但清除理由文本，以用户为聚合的领域事件清除事件数据；
+//! - 不再被任何执行会话引用的 Agent 删除，仍被共享项目引用的保留在占位用户名下。
+//!
+//! 清除完成后记录一条 `UserDataPurged` 领域事件作为审计记录，事件数据只包含统计信息。
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseTransaction, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        acceptance_verification, agent, api_key, blackboard_entry, code_review, conflict, domain_event, execution_session,
+        human_decision, llm_session, notification, organization, organization_member, project, project_member,
+        report_schedule, task, task_gate, user, user_identity, user_session, user_two_factor,
+        domain_event::{AggregateType, DomainEventType},
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+use codex_multi_agent::{OrganizationRole, ProjectRole};
+
+/// 占位用户邮箱的域名，用于识别已清除的用户
+const PURGED_EMAIL_DOMAIN: &str = "deleted.invalid";
+
+/// 所有权转交记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnershipTransfer {
+    /// 项目或组织ID
+    pub id: Uuid,
+    pub new_owner_id: Uuid,
+}
+
+/// 用户数据清除报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PurgeReport {
+    pub user_id: Uuid,
+    pub requested_by: Option<Uuid>,
+    pub purged_at: DateTime<Utc>,
+    /// 按表统计删除的行数，不含随外键级联删除的行
+    pub deleted: BTreeMap<String, u64>,
+    /// 按表统计匿名化的行数
+    pub anonymized: BTreeMap<String, u64>,
+    pub deleted_projects: Vec<Uuid>,
+    pub transferred_projects: Vec<OwnershipTransfer>,
+    pub deleted_organizations: Vec<Uuid>,
+    pub transferred_organizations: Vec<OwnershipTransfer>,
+    /// 仍被共享项目的执行会话引用而保留的 Agent
+    pub retained_agents: Vec<Uuid>,
+    /// 审计事件ID
+    pub audit_event_id: Uuid,
+}
+
+impl PurgeReport {
+    fn record_deleted(&mut self, table: &str, rows: u64) {
+        if rows > 0 {
+            *self.deleted.entry(table.to_string()).or_default() += rows;
+        }
+    }
+
+    fn record_anonymized(&mut self, table: &str, rows: u64) {
+        if rows > 0 {
+            *self.anonymized.entry(table.to_string()).or_default() += rows;
+        }
+    }
+}
+
+/// 用户是否已被清除
+pub fn is_purged(user: &user::Model) -> bool {
+    user.email.ends_with(&format!("@{}", PURGED_EMAIL_DOMAIN))
+}
+
+/// 删除或匿名化用户的全部关联数据，返回清除报告
+pub async fn purge_user_data(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    requested_by: Option<Uuid>,
+) -> Result<PurgeReport> {
+    let target = user::Entity::find_by_id(user_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("User", user_id))?;
+    if is_purged(&target) {
+        return Err(DatabaseError::business_logic(format!("用户 {} 的数据已清除", user_id)));
+    }
+
+    let txn = db.begin().await?;
+    let mut report = PurgeReport {
+        user_id,
+        requested_by,
+        purged_at: Utc::now(),
+        deleted: BTreeMap::new(),
+        anonymized: BTreeMap::new(),
+        deleted_projects: Vec::new(),
+        transferred_projects: Vec::new(),
+        deleted_organizations: Vec::new(),
+        transferred_organizations: Vec::new(),
+        retained_agents: Vec::new(),
+        audit_event_id: Uuid::nil(),
+    };
+
+    purge_projects(&txn, user_id, &mut report).await?;
+    purge_organizations(&txn, user_id, &mut report).await?;
+    purge_sessions(&txn, user_id, &mut report).await?;
+    anonymize_references(&txn, user_id, &mut report).await?;
+    purge_agents(&txn, user_id, &mut report).await?;
+    anonymize_user(&txn, target, &mut report).await?;
+    report.audit_event_id = record_audit_event(&txn, &report).await?;
+
+    txn.commit().await?;
+    tracing::info!(user_id = %user_id, "用户数据已清除");
+    Ok(report)
+}
+
+/// 删除用户独有的项目，共享项目转交给权限最高的其他成员，并移除用户的成员关系
+async fn purge_projects(txn: &DatabaseTransaction, user_id: Uuid, report: &mut PurgeReport) -> Result<()> {
+    let owned = project::Entity::find()
+        .filter(project::Column::UserId.eq(user_id))
+        .all(txn)
+        .await?;
+    for owned_project in owned {
+        let mut members = project_member::Entity::find()
+            .filter(project_member::Column::ProjectId.eq(owned_project.project_id))
+            .filter(project_member::Column::UserId.ne(user_id))
+            .order_by_asc(project_member::Column::JoinedAt)
+            .all(txn)
+            .await?;
+        // 权限最高者优先，同级时先加入者优先
+        members.sort_by_key(|member| {
+            std::cmp::Reverse(ProjectRole::from_str(&member.role).map(ProjectRole::rank).unwrap_or_default())
+        });
+
+        match members.into_iter().next() {
+            Some(successor) => {
+                let project_id = owned_project.project_id;
+                let mut active: project::ActiveModel = owned_project.into();
+                active.user_id = Set(successor.user_id);
+                active.updated_at = Set(Utc::now().into());
+                active.update(txn).await?;
+
+                let mut member: project_member::ActiveModel = successor.clone().into();
+                member.role = Set(ProjectRole::Owner.as_str().to_string());
+                member.updated_at = Set(Utc::now().into());
+                member.update(txn).await?;
+
+                // 用户创建的定时报告归到新的所有者名下
+                let schedules = report_schedule::Entity::update_many()
+                    .col_expr(report_schedule::Column::CreatedBy, Expr::value(successor.user_id))
+                    .filter(report_schedule::Column::ProjectId.eq(project_id))
+                    .filter(report_schedule::Column::CreatedBy.eq(user_id))
+                    .exec(txn)
+                    .await?;
+                report.record_anonymized("report_schedules", schedules.rows_affected);
+                report.transferred_projects.push(OwnershipTransfer { id: project_id, new_owner_id: successor.user_id });
+            }
+            None => {
+                project::Entity::delete_by_id(owned_project.project_id).exec(txn).await?;
+                report.record_deleted("projects", 1);
+                report.deleted_projects.push(owned_project.project_id);
+            }
+        }
+    }
+
+    // 他人项目中由用户创建的定时报告归到项目所有者名下
+    let schedules = report_schedule::Entity::find()
+        .filter(report_schedule::Column::CreatedBy.eq(user_id))
+        .all(txn)
+        .await?;
+    for schedule in schedules {
+        let owner = project::Entity::find_by_id(schedule.project_id)
+            .one(txn)
+            .await?
+            .map(|project| project.user_id);
+        if let Some(owner) = owner {
+            let mut active: report_schedule::ActiveModel = schedule.into();
+            active.created_by = Set(owner);
+            active.update(txn).await?;
+            report.record_anonymized("report_schedules", 1);
+        }
+    }
+
+    let memberships = project_member::Entity::delete_many()
+        .filter(project_member::Column::UserId.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_deleted("project_members", memberships.rows_affected);
+    let invitations = project_member::Entity::update_many()
+        .col_expr(project_member::Column::InvitedBy, Expr::value(Option::<Uuid>::None))
+        .filter(project_member::Column::InvitedBy.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_anonymized("project_members", invitations.rows_affected);
+    Ok(())
+}
+
+/// 删除用户独有的组织，共享组织转交给权限最高的其他成员，并移除用户的成员关系
+async fn purge_organizations(txn: &DatabaseTransaction, user_id: Uuid, report: &mut PurgeReport) -> Result<()> {
+    let owned = organization::Entity::find()
+        .filter(organization::Column::OwnerId.eq(user_id))
+        .all(txn)
+        .await?;
+    for owned_organization in owned {
+        let mut members = organization_member::Entity::find()
+            .filter(organization_member::Column::OrganizationId.eq(owned_organization.organization_id))
+            .filter(organization_member::Column::UserId.ne(user_id))
+            .order_by_asc(organization_member::Column::JoinedAt)
+            .all(txn)
+            .await?;
+        members.sort_by_key(|member| {
+            std::cmp::Reverse(OrganizationRole::from_str(&member.role).map(OrganizationRole::rank).unwrap_or_default())
+        });
+
+        let organization_id = owned_organization.organization_id;
+        match members.into_iter().next() {
+            Some(successor) => {
+                let mut active: organization::ActiveModel = owned_organization.into();
+                active.owner_id = Set(successor.user_id);
+                active.updated_at = Set(Utc::now().into());
+                active.update(txn).await?;
+
+                let mut member: organization_member::ActiveModel = successor.clone().into();
+                member.role = Set(OrganizationRole::Owner.as_str().to_string());
+                member.update(txn).await?;
+                report.transferred_organizations.push(OwnershipTransfer {
+                    id: organization_id,
+                    new_owner_id: successor.user_id,
+                });
+            }
+            None => {
+                organization::Entity::delete_by_id(organization_id).exec(txn).await?;
+                report.record_deleted("organizations", 1);
+                report.deleted_organizations.push(organization_id);
+            }
+        }
+    }
+
+    let memberships = organization_member::Entity::delete_many()
+        .filter(organization_member::Column::UserId.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_deleted("organization_members", memberships.rows_affected);
+    Ok(())
+}
+
+/// 删除登录会话、API 密钥、外部身份、两步验证设置、通知和用户发起的 LLM 会话（任务上的引用随外键置空）
+async fn purge_sessions(txn: &DatabaseTransaction, user_id: Uuid, report: &mut PurgeReport) -> Result<()> {
+    let sessions = user_session::Entity::delete_many()
+        .filter(user_session::Column::UserId.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_deleted("user_sessions", sessions.rows_affected);
+
+    let keys = api_key::Entity::delete_many()
+        .filter(api_key::Column::UserId.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_deleted("api_keys", keys.rows_affected);
+
+    let identities = user_identity::Entity::delete_many()
+        .filter(user_identity::Column::UserId.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_deleted("user_identities", identities.rows_affected);
+
+    let two_factor = user_two_factor::Entity::delete_many()
+        .filter(user_two_factor::Column::UserId.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_deleted("user_two_factor", two_factor.rows_affected);
+
+    let notifications = notification::Entity::delete_many()
+        .filter(notification::Column::UserId.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_deleted("notifications", notifications.rows_affected);
+
+    let llm_session_ids: Vec<Uuid> = llm_session::Entity::find()
+        .filter(llm_session::Column::UserId.eq(user_id))
+        .all(txn)
+        .await?
+        .into_iter()
+        .map(|session| session.session_id)
+        .collect();
+    if !llm_session_ids.is_empty() {
+        task::Entity::update_many()
+            .col_expr(task::Column::LlmSessionId, Expr::value(Option::<Uuid>::None))
+            .filter(task::Column::LlmSessionId.is_in(llm_session_ids.clone()))
+            .exec(txn)
+            .await?;
+        let deleted = llm_session::Entity::delete_many()
+            .filter(llm_session::Column::SessionId.is_in(llm_session_ids))
+            .exec(txn)
+            .await?;
+        report.record_deleted("llm_sessions", deleted.rows_affected);
+    }
+    Ok(())
+}
+
+/// 把指向用户的引用置空
+async fn anonymize_references(txn: &DatabaseTransaction, user_id: Uuid, report: &mut PurgeReport) -> Result<()> {
+    let none = || Expr::value(Option::<Uuid>::None);
+
+    let rows = code_review::Entity::update_many()
+        .col_expr(code_review::Column::ReviewerUserId, none())
+        .filter(code_review::Column::ReviewerUserId.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_anonymized("code_reviews", rows.rows_affected);
+
+    let rows = conflict::Entity::update_many()
+        .col_expr(conflict::Column::AssignedUserId, none())
+        .filter(conflict::Column::AssignedUserId.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_anonymized("conflicts", rows.rows_affected);
+
+    let rows = task_gate::Entity::update_many()
+        .col_expr(task_gate::Column::DecidedBy, none())
+        .filter(task_gate::Column::DecidedBy.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_anonymized("task_gates", rows.rows_affected);
+
+    let rows = acceptance_verification::Entity::update_many()
+        .col_expr(acceptance_verification::Column::SignedOffBy, none())
+        .filter(acceptance_verification::Column::SignedOffBy.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_anonymized("acceptance_verifications", rows.rows_affected);
+
+    let rows = blackboard_entry::Entity::update_many()
+        .col_expr(blackboard_entry::Column::AuthorUserId, none())
+        .filter(blackboard_entry::Column::AuthorUserId.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_anonymized("blackboard_entries", rows.rows_affected);
+
+    // 人工决策是冲突处理的依据，保留决策本身，只清除可能包含个人信息的理由
+    let rows = human_decision::Entity::update_many()
+        .col_expr(human_decision::Column::Reasoning, Expr::value(Option::<String>::None))
+        .filter(human_decision::Column::UserId.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_anonymized("human_decisions", rows.rows_affected);
+
+    let rows = domain_event::Entity::update_many()
+        .col_expr(domain_event::Column::UserId, none())
+        .filter(domain_event::Column::UserId.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_anonymized("domain_events", rows.rows_affected);
+    let rows = domain_event::Entity::update_many()
+        .col_expr(domain_event::Column::EventData, Expr::value(json!({ "redacted": true })))
+        .filter(domain_event::Column::AggregateType.eq(AggregateType::User.to_string()))
+        .filter(domain_event::Column::AggregateId.eq(user_id))
+        .exec(txn)
+        .await?;
+    report.record_anonymized("domain_events", rows.rows_affected);
+    Ok(())
+}
+
+/// 删除不再被执行会话引用的 Agent，其余保留
+async fn purge_agents(txn: &DatabaseTransaction, user_id: Uuid, report: &mut PurgeReport) -> Result<()> {
+    let agents = agent::Entity::find()
+        .filter(agent::Column::UserId.eq(user_id))
+        .all(txn)
+        .await?;
+    for owned_agent in agents {
+        let sessions = execution_session::Entity::find()
+            .filter(execution_session::Column::AgentId.eq(owned_agent.agent_id))
+            .count(txn)
+            .await?;
+        if sessions > 0 {
+            report.retained_agents.push(owned_agent.agent_id);
+        } else {
+            agent::Entity::delete_by_id(owned_agent.agent_id).exec(txn).await?;
+            report.record_deleted("agents", 1);
+        }
+    }
+    Ok(())
+}
+
+/// 把用户行改写为不含个人信息的占位记录
+async fn anonymize_user(txn: &DatabaseTransaction, target: user::Model, report: &mut PurgeReport) -> Result<()> {
+    let placeholder = format!("deleted-{}", target.user_id.simple());
+    let mut active: user::ActiveModel = target.into();
+    active.email = Set(format!("{}@{}", placeholder, PURGED_EMAIL_DOMAIN));
+    active.username = Set(placeholder);
+    active.password_hash = Set(String::new());
+    active.profile_data = Set(None);
+    active.settings = Set(None);
+    active.is_active = Set(false);
+    active.last_login_at = Set(None);
+    active.updated_at = Set(Utc::now().into());
+    active.update(txn).await?;
+    report.record_anonymized("users", 1);
+    Ok(())
+}
+
+/// 记录审计事件，事件数据只包含统计信息
+async fn record_audit_event<C: ConnectionTrait>(db: &C, report: &PurgeReport) -> Result<Uuid> {
+    let version = domain_event::Entity::find()
+        .filter(domain_event::Column::AggregateId.eq(report.user_id))
+        .order_by_desc(domain_event::Column::EventVersion)
+        .one(db)
+        .await?
+        .map_or(0, |event| event.event_version);
+    let event_id = Uuid::new_v4();
+    let event = domain_event::ActiveModel {
+        event_id: Set(event_id),
+        aggregate_type: Set(AggregateType::User.to_string()),
+        aggregate_id: Set(report.user_id),
+        event_type: Set(DomainEventType::UserDataPurged.to_string()),
+        event_data: Set(json!({
+            "deleted": report.deleted,
+            "anonymized": report.anonymized,
+            "deleted_projects": report.deleted_projects,
+            "transferred_projects": report.transferred_projects,
+            "deleted_organizations": report.deleted_organizations,
+            "transferred_organizations": report.transferred_organizations,
+            "retained_agents": report.retained_agents,
+        })),
+        event_version: Set(version + 1),
+        user_id: Set(report.requested_by.filter(|requested_by| *requested_by != report.user_id)),
+        occurred_at: Set(report.purged_at.into()),
+        is_processed: Set(false),
+        ..Default::default()
+    };
+    domain_event::Entity::insert(event).exec(db).await?;
+    Ok(event_id)
+}
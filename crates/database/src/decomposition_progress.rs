@@ -0,0 +1,142 @@
+//! 需求分解的流式进度
+//!
+//! 需求分解往往要运行数十秒，[`stream_decomposition`] 以流式方式调用 [`LlmProvider`]，
+//! 由 [`DecompositionProgressTracker`] 累积输出片段，在识别出新任务或输出增长到一定长度时
+//! 生成 [`DecompositionProgressEvent`]，界面据此逐步渲染已识别的任务。
+
+use codex_multi_agent::{DecompositionProgressEvent, EventFactory, LlmSessionId, ProjectId};
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+use crate::{
+    llm_provider::{LlmCompletion, LlmProvider, LlmRequest},
+    structured_output::partial_task_titles,
+    telemetry, Result,
+};
+
+/// 没有识别出新任务时，两次进度事件之间至少新增的输出字符数
+pub const PROGRESS_INTERVAL_CHARS: usize = 400;
+
+/// 进度事件中部分输出摘要的最大字符数
+const SUMMARY_CHARS: usize = 200;
+
+/// 需求分解输出的进度跟踪
+pub struct DecompositionProgressTracker {
+    session_id: LlmSessionId,
+    project_id: ProjectId,
+    output: String,
+    tokens_received: u32,
+    tasks: Vec<String>,
+    /// 上次发送事件时的输出字符数
+    reported_chars: usize,
+}
+
+impl DecompositionProgressTracker {
+    pub fn new(session_id: LlmSessionId, project_id: ProjectId) -> Self {
+        Self {
+            session_id,
+            project_id,
+            output: String::new(),
+            tokens_received: 0,
+            tasks: Vec::new(),
+            reported_chars: 0,
+        }
+    }
+
+    /// 追加一个输出片段，识别出新任务或输出增长超过 [`PROGRESS_INTERVAL_CHARS`] 时返回进度事件
+    pub fn push(&mut self, delta: &str) -> Option<DecompositionProgressEvent> {
+        self.output.push_str(delta);
+        self.tokens_received += 1;
+
+        let tasks = partial_task_titles(&self.output);
+        let identified_new = tasks.len() > self.tasks.len();
+        self.tasks = tasks;
+        let chars = self.output.chars().count();
+        if identified_new || chars - self.reported_chars >= PROGRESS_INTERVAL_CHARS {
+            self.reported_chars = chars;
+            Some(self.event(false))
+        } else {
+            None
+        }
+    }
+
+    /// 输出结束时的最终进度事件
+    pub fn finish(&mut self) -> DecompositionProgressEvent {
+        self.tasks = partial_task_titles(&format!("{}\n", self.output));
+        self.reported_chars = self.output.chars().count();
+        self.event(true)
+    }
+
+    /// 目前已识别出的任务标题
+    pub fn tasks(&self) -> &[String] {
+        &self.tasks
+    }
+
+    /// 已收到的完整输出
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    fn event(&self, finished: bool) -> DecompositionProgressEvent {
+        EventFactory::decomposition_progress(
+            self.session_id.clone(),
+            self.project_id.clone(),
+            self.tokens_received,
+            self.output.chars().count() as u64,
+            self.tasks.clone(),
+            summary(&self.output),
+            finished,
+        )
+    }
+}
+
+/// 以流式方式执行需求分解，每个进度事件交给 `on_progress`，最后一个事件的 `finished` 为真
+pub async fn stream_decomposition<F>(
+    provider: &dyn LlmProvider,
+    request: &LlmRequest,
+    tracker: &mut DecompositionProgressTracker,
+    mut on_progress: F,
+) -> Result<LlmCompletion>
+where
+    F: FnMut(DecompositionProgressEvent),
+{
+    let started = Instant::now();
+    let (tokens, mut receiver) = mpsc::unbounded_channel();
+    let completion = provider.complete_streaming(request, tokens);
+    tokio::pin!(completion);
+
+    let result = loop {
+        tokio::select! {
+            biased;
+            Some(delta) = receiver.recv() => {
+                if let Some(event) = tracker.push(&delta) {
+                    on_progress(event);
+                }
+            }
+            result = &mut completion => break result,
+        }
+    };
+    while let Ok(delta) = receiver.try_recv() {
+        if let Some(event) = tracker.push(&delta) {
+            on_progress(event);
+        }
+    }
+    telemetry::record_llm_latency("decomposition", started.elapsed());
+
+    let completion = result?;
+    tracing::debug!(
+        "需求分解输出结束：{} 个片段，识别出 {} 个任务，使用 {} 个token",
+        tracker.tokens_received,
+        tracker.tasks.len(),
+        completion.tokens_used
+    );
+    on_progress(tracker.finish());
+    Ok(completion)
+}
+
+/// 输出末尾的片段，换行折叠为空格
+fn summary(output: &str) -> String {
+    let chars: Vec<char> = output.chars().collect();
+    let tail: String = chars[chars.len().saturating_sub(SUMMARY_CHARS)..].iter().collect();
+    tail.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,722 @@
+//! 许可证与依赖审计
+//!
+//! `dependency_audit` 类型的任务由内置扫描器执行，不需要 LLM：
+//! 1. [`scan_dependencies`] 在项目工作空间中查找依赖清单（`Cargo.lock`、`package-lock.json`、
+//!    `requirements.txt`、`go.mod`），提取依赖的版本和许可证；
+//! 2. [`VulnerabilitySource`]（桌面端使用 OSV API）查询已知漏洞，查询失败时报告中记录原因；
+//! 3. 按 [`LicensePolicy`] 检查许可证；
+//! 4. [`run_dependency_audit`] 把结构化的审计报告保存为执行会话的工件，按需在审计任务下
+//!    创建修复子任务（带有对应的能力要求），并结束执行会话。
+
+use chrono::{DateTime, Utc};
+use codex_multi_agent::{AgentCapability, ArtifactType};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use uuid::Uuid;
+
+use crate::{
+    artifact_store::ArtifactStore,
+    entities::{execution_session::ExecutionStatus, project, task},
+    repository::{ExecutionSessionRepository, TaskRepository},
+    DatabaseError, Result,
+};
+use sea_orm::EntityTrait;
+
+/// 依赖审计任务类型
+pub const DEPENDENCY_AUDIT_TASK_TYPE: &str = "dependency_audit";
+
+/// 审计报告工件名称
+pub const AUDIT_REPORT_ARTIFACT: &str = "dependency-audit.json";
+
+/// 修复子任务的任务类型
+const REMEDIATION_TASK_TYPE: &str = "bug_fix";
+
+/// 扫描时跳过的目录
+pub(crate) const SKIPPED_DIRS: &[&str] = &["node_modules", "target", ".git", "vendor", "dist", "build", ".venv", "venv"];
+
+/// 扫描的最大目录深度
+pub(crate) const MAX_DEPTH: usize = 6;
+
+/// 依赖所属生态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Ecosystem {
+    CratesIo,
+    Npm,
+    #[serde(rename = "pypi")]
+    PyPi,
+    Go,
+}
+
+impl Ecosystem {
+    /// OSV 使用的生态名称
+    pub fn osv_name(&self) -> &'static str {
+        match self {
+            Ecosystem::CratesIo => "crates.io",
+            Ecosystem::Npm => "npm",
+            Ecosystem::PyPi => "PyPI",
+            Ecosystem::Go => "Go",
+        }
+    }
+
+    /// 修复该生态依赖所需的开发能力
+    fn capability(&self) -> AgentCapability {
+        match self {
+            Ecosystem::Npm => AgentCapability::FrontendDevelopment,
+            Ecosystem::CratesIo | Ecosystem::PyPi | Ecosystem::Go => AgentCapability::BackendDevelopment,
+        }
+    }
+}
+
+/// 依赖包
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Dependency {
+    pub name: String,
+    pub version: String,
+    pub ecosystem: Ecosystem,
+    /// 声明该依赖的清单文件（相对工作空间）
+    pub manifest: String,
+    /// SPDX 许可证表达式，无法确定时为空
+    pub license: Option<String>,
+}
+
+/// 已知漏洞
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vulnerability {
+    pub id: String,
+    pub summary: Option<String>,
+    /// 严重程度，例如 CRITICAL、HIGH、MODERATE、LOW
+    pub severity: Option<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// 修复版本
+    #[serde(default)]
+    pub fixed_versions: Vec<String>,
+}
+
+impl Vulnerability {
+    /// 从 OSV 漏洞记录解析
+    pub fn from_osv(value: &serde_json::Value) -> Self {
+        let strings = |value: Option<&serde_json::Value>| -> Vec<String> {
+            value
+                .and_then(|v| v.as_array())
+                .map(|items| items.iter().filter_map(|item| item.as_str().map(str::to_string)).collect())
+                .unwrap_or_default()
+        };
+
+        let mut fixed_versions = Vec::new();
+        for affected in value["affected"].as_array().into_iter().flatten() {
+            for range in affected["ranges"].as_array().into_iter().flatten() {
+                for event in range["events"].as_array().into_iter().flatten() {
+                    if let Some(fixed) = event["fixed"].as_str() {
+                        if !fixed_versions.iter().any(|v| v == fixed) {
+                            fixed_versions.push(fixed.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            id: value["id"].as_str().unwrap_or_default().to_string(),
+            summary: value["summary"].as_str().map(str::to_string),
+            severity: value["database_specific"]["severity"].as_str().map(|s| s.to_uppercase()),
+            aliases: strings(value.get("aliases")),
+            fixed_versions,
+        }
+    }
+
+    fn is_severe(&self) -> bool {
+        matches!(self.severity.as_deref(), Some("CRITICAL" | "HIGH"))
+    }
+}
+
+/// 漏洞查询的异步结果，与输入依赖一一对应
+pub type VulnerabilityFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<Vec<Vulnerability>>>> + Send + 'a>>;
+
+/// 已知漏洞数据源
+pub trait VulnerabilitySource: Send + Sync {
+    /// 批量查询依赖的已知漏洞，返回顺序与输入一致
+    fn query<'a>(&'a self, dependencies: &'a [Dependency]) -> VulnerabilityFuture<'a>;
+}
+
+/// 许可证策略
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LicensePolicy {
+    /// 禁止使用的许可证（SPDX 标识，忽略 `-only`/`-or-later` 后缀）
+    #[serde(default = "default_denied_licenses")]
+    pub denied: Vec<String>,
+    /// 无法确定许可证的依赖是否视为问题
+    #[serde(default)]
+    pub flag_unknown: bool,
+}
+
+fn default_denied_licenses() -> Vec<String> {
+    ["AGPL-3.0", "GPL-2.0", "GPL-3.0", "SSPL-1.0"].iter().map(|s| s.to_string()).collect()
+}
+
+impl Default for LicensePolicy {
+    fn default() -> Self {
+        Self {
+            denied: default_denied_licenses(),
+            flag_unknown: false,
+        }
+    }
+}
+
+impl LicensePolicy {
+    /// 检查许可证，合规时返回 None，否则返回原因
+    pub fn check(&self, license: Option<&str>) -> Option<String> {
+        let Some(license) = license.map(str::trim).filter(|l| !l.is_empty()) else {
+            return self.flag_unknown.then(|| "无法确定许可证".to_string());
+        };
+
+        // `A OR B` 只要有一个选项合规即可，`A AND B` 要求全部合规
+        let expression = license.trim_start_matches('(').trim_end_matches(')');
+        let acceptable = expression.split(" OR ").any(|alternative| {
+            alternative
+                .split(" AND ")
+                .all(|id| !self.is_denied(id.trim_matches(|c: char| c == '(' || c == ')' || c.is_whitespace())))
+        });
+        (!acceptable).then(|| format!("许可证 {} 不在允许范围内", license))
+    }
+
+    fn is_denied(&self, id: &str) -> bool {
+        let base = id.trim_end_matches('+').trim_end_matches("-only").trim_end_matches("-or-later");
+        self.denied.iter().any(|denied| denied.eq_ignore_ascii_case(base))
+    }
+}
+
+/// 许可证问题
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LicenseIssue {
+    pub dependency: Dependency,
+    pub reason: String,
+}
+
+/// 存在已知漏洞的依赖
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VulnerableDependency {
+    pub dependency: Dependency,
+    pub vulnerabilities: Vec<Vulnerability>,
+}
+
+/// 依赖审计报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyAuditReport {
+    pub project_id: Uuid,
+    pub scanned_at: DateTime<Utc>,
+    /// 扫描到的清单文件
+    pub manifests: Vec<String>,
+    pub dependency_count: usize,
+    /// 按许可证统计的依赖数量，未知许可证记为 UNKNOWN
+    pub licenses: BTreeMap<String, usize>,
+    pub license_issues: Vec<LicenseIssue>,
+    pub vulnerable_dependencies: Vec<VulnerableDependency>,
+    /// 是否完成了漏洞查询
+    pub vulnerabilities_checked: bool,
+    /// 漏洞查询失败的原因
+    pub vulnerability_error: Option<String>,
+}
+
+impl DependencyAuditReport {
+    /// 是否发现需要处理的问题
+    pub fn has_findings(&self) -> bool {
+        !self.license_issues.is_empty() || !self.vulnerable_dependencies.is_empty()
+    }
+}
+
+/// 审计选项
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DependencyAuditOptions {
+    #[serde(default)]
+    pub license_policy: LicensePolicy,
+    /// 是否为发现的问题创建修复子任务
+    #[serde(default)]
+    pub create_remediation_tasks: bool,
+}
+
+/// 审计结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyAuditOutcome {
+    pub report: DependencyAuditReport,
+    /// 报告工件ID
+    pub artifact_id: Uuid,
+    /// 新建的修复子任务
+    pub remediation_task_ids: Vec<Uuid>,
+}
+
+/// 扫描工作空间中的依赖清单，返回清单文件列表和依赖
+pub fn scan_dependencies(root: &Path) -> Result<(Vec<String>, Vec<Dependency>)> {
+    let mut manifests = Vec::new();
+    collect_manifests(root, 0, &mut manifests)?;
+    manifests.sort();
+
+    let mut dependencies = Vec::new();
+    let mut seen = HashSet::new();
+    let mut relative_manifests = Vec::new();
+    for manifest in manifests {
+        let relative = manifest
+            .strip_prefix(root)
+            .unwrap_or(&manifest)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let content = std::fs::read_to_string(&manifest)?;
+        let file_name = manifest.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let parsed = match file_name {
+            "Cargo.lock" => parse_cargo_lock(&content, &relative),
+            "package-lock.json" => parse_package_lock(&content, &relative, manifest.parent().unwrap_or(root))?,
+            "requirements.txt" => parse_requirements(&content, &relative),
+            "go.mod" => parse_go_mod(&content, &relative),
+            _ => Vec::new(),
+        };
+        for dependency in parsed {
+            if seen.insert((dependency.ecosystem, dependency.name.clone(), dependency.version.clone())) {
+                dependencies.push(dependency);
+            }
+        }
+        relative_manifests.push(relative);
+    }
+    Ok((relative_manifests, dependencies))
+}
+
+fn collect_manifests(dir: &Path, depth: usize, manifests: &mut Vec<PathBuf>) -> Result<()> {
+    if depth > MAX_DEPTH {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_str()) && !name.starts_with('.') {
+                collect_manifests(&path, depth + 1, manifests)?;
+            }
+        } else if matches!(name.as_str(), "Cargo.lock" | "package-lock.json" | "requirements.txt" | "go.mod") {
+            manifests.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 解析 Cargo.lock，只保留来自注册表的依赖
+fn parse_cargo_lock(content: &str, manifest: &str) -> Vec<Dependency> {
+    let mut dependencies = Vec::new();
+    for block in content.split("[[package]]").skip(1) {
+        let field = |key: &str| {
+            block.lines().find_map(|line| {
+                let (name, value) = line.split_once('=')?;
+                (name.trim() == key).then(|| value.trim().trim_matches('"').to_string())
+            })
+        };
+        let (Some(name), Some(version), Some(source)) = (field("name"), field("version"), field("source")) else {
+            continue;
+        };
+        if !source.starts_with("registry+") {
+            continue;
+        }
+        let license = cargo_registry_license(&name, &version);
+        dependencies.push(Dependency {
+            name,
+            version,
+            ecosystem: Ecosystem::CratesIo,
+            manifest: manifest.to_string(),
+            license,
+        });
+    }
+    dependencies
+}
+
+/// 从本地 cargo 注册表缓存读取 crate 的许可证
+fn cargo_registry_license(name: &str, version: &str) -> Option<String> {
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cargo")))?;
+    let registries = std::fs::read_dir(cargo_home.join("registry").join("src")).ok()?;
+    for registry in registries.flatten() {
+        let manifest = registry.path().join(format!("{}-{}", name, version)).join("Cargo.toml");
+        if let Ok(content) = std::fs::read_to_string(manifest) {
+            return content.lines().find_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                (key.trim() == "license").then(|| value.trim().trim_matches('"').to_string())
+            });
+        }
+    }
+    None
+}
+
+/// 解析 package-lock.json（同时支持 v1 的 dependencies 和 v2/v3 的 packages）
+fn parse_package_lock(content: &str, manifest: &str, dir: &Path) -> Result<Vec<Dependency>> {
+    let lock: serde_json::Value = serde_json::from_str(content)?;
+    let mut dependencies = Vec::new();
+
+    if let Some(packages) = lock["packages"].as_object() {
+        for (path, package) in packages {
+            let Some(name) = path.rsplit("node_modules/").next().filter(|_| path.contains("node_modules/")) else {
+                continue;
+            };
+            let Some(version) = package["version"].as_str() else {
+                continue;
+            };
+            let license = package["license"]
+                .as_str()
+                .map(str::to_string)
+                .or_else(|| installed_package_license(&dir.join(path)));
+            dependencies.push(npm_dependency(name, version, manifest, license));
+        }
+    } else if let Some(packages) = lock["dependencies"].as_object() {
+        for (name, package) in packages {
+            if let Some(version) = package["version"].as_str() {
+                let license = installed_package_license(&dir.join("node_modules").join(name));
+                dependencies.push(npm_dependency(name, version, manifest, license));
+            }
+        }
+    }
+    Ok(dependencies)
+}
+
+fn npm_dependency(name: &str, version: &str, manifest: &str, license: Option<String>) -> Dependency {
+    Dependency {
+        name: name.to_string(),
+        version: version.to_string(),
+        ecosystem: Ecosystem::Npm,
+        manifest: manifest.to_string(),
+        license,
+    }
+}
+
+/// 从已安装的 npm 包读取许可证
+fn installed_package_license(package_dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(package_dir.join("package.json")).ok()?;
+    let package: serde_json::Value = serde_json::from_str(&content).ok()?;
+    package["license"]
+        .as_str()
+        .or_else(|| package["license"]["type"].as_str())
+        .map(str::to_string)
+}
+
+/// 解析 requirements.txt 中固定版本的依赖（`name==version`）
+fn parse_requirements(content: &str, manifest: &str) -> Vec<Dependency> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter_map(|line| {
+            let (name, version) = line.split_once("==")?;
+            let name = name.split('[').next()?.trim();
+            let version = version.split(';').next()?.trim();
+            (!name.is_empty() && !version.is_empty()).then(|| Dependency {
+                name: name.to_string(),
+                version: version.to_string(),
+                ecosystem: Ecosystem::PyPi,
+                manifest: manifest.to_string(),
+                license: None,
+            })
+        })
+        .collect()
+}
+
+/// 解析 go.mod 的 require 指令
+fn parse_go_mod(content: &str, manifest: &str) -> Vec<Dependency> {
+    let mut dependencies = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        let requirement = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if line == "require (" {
+            in_block = true;
+            continue;
+        } else if let Some(rest) = line.strip_prefix("require ") {
+            rest
+        } else {
+            continue;
+        };
+
+        let mut parts = requirement.split_whitespace();
+        if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+            dependencies.push(Dependency {
+                name: module.to_string(),
+                version: version.trim_start_matches('v').to_string(),
+                ecosystem: Ecosystem::Go,
+                manifest: manifest.to_string(),
+                license: None,
+            });
+        }
+    }
+    dependencies
+}
+
+/// 扫描工作空间并生成审计报告，漏洞查询失败时报告中记录原因
+pub async fn audit_workspace(
+    project_id: Uuid,
+    workspace: &Path,
+    source: Option<&dyn VulnerabilitySource>,
+    policy: &LicensePolicy,
+) -> Result<DependencyAuditReport> {
+    let root = workspace.to_path_buf();
+    let (manifests, dependencies) = tokio::task::spawn_blocking(move || scan_dependencies(&root))
+        .await
+        .map_err(|e| DatabaseError::business_logic(format!("依赖扫描任务异常退出: {}", e)))??;
+
+    let mut licenses = BTreeMap::new();
+    let mut license_issues = Vec::new();
+    for dependency in &dependencies {
+        let key = dependency.license.clone().unwrap_or_else(|| "UNKNOWN".to_string());
+        *licenses.entry(key).or_insert(0) += 1;
+        if let Some(reason) = policy.check(dependency.license.as_deref()) {
+            license_issues.push(LicenseIssue {
+                dependency: dependency.clone(),
+                reason,
+            });
+        }
+    }
+
+    let mut vulnerable_dependencies = Vec::new();
+    let mut vulnerability_error = None;
+    let mut vulnerabilities_checked = false;
+    match source {
+        Some(source) if !dependencies.is_empty() => match source.query(&dependencies).await {
+            Ok(results) if results.len() == dependencies.len() => {
+                vulnerabilities_checked = true;
+                for (dependency, vulnerabilities) in dependencies.iter().zip(results) {
+                    if !vulnerabilities.is_empty() {
+                        vulnerable_dependencies.push(VulnerableDependency {
+                            dependency: dependency.clone(),
+                            vulnerabilities,
+                        });
+                    }
+                }
+            }
+            Ok(results) => {
+                vulnerability_error = Some(format!(
+                    "漏洞数据源返回 {} 条结果，期望 {} 条",
+                    results.len(),
+                    dependencies.len()
+                ));
+            }
+            Err(e) => vulnerability_error = Some(e.to_string()),
+        },
+        Some(_) => vulnerabilities_checked = true,
+        None => vulnerability_error = Some("未配置漏洞数据源".to_string()),
+    }
+
+    Ok(DependencyAuditReport {
+        project_id,
+        scanned_at: Utc::now(),
+        manifests,
+        dependency_count: dependencies.len(),
+        licenses,
+        license_issues,
+        vulnerable_dependencies,
+        vulnerabilities_checked,
+        vulnerability_error,
+    })
+}
+
+/// 执行依赖审计任务的会话
+///
+/// 会话必须处于运行中且任务类型为 `dependency_audit`。报告保存为会话工件，
+/// 会话随后按审计是否完成结束；扫描本身失败时会话标记为失败并返回错误。
+pub async fn run_dependency_audit(
+    store: &ArtifactStore,
+    source: Option<&dyn VulnerabilitySource>,
+    session_id: Uuid,
+    options: &DependencyAuditOptions,
+) -> Result<DependencyAuditOutcome> {
+    let db = store.db();
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let session = sessions
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    if session.status != ExecutionStatus::Running.to_string() {
+        return Err(DatabaseError::validation("依赖审计只能在运行中的执行会话中进行"));
+    }
+    let audit_task = task::Entity::find_by_id(session.task_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Task", session.task_id))?;
+    if audit_task.task_type != DEPENDENCY_AUDIT_TASK_TYPE {
+        return Err(DatabaseError::validation(format!(
+            "任务 {} 不是依赖审计任务",
+            audit_task.task_id
+        )));
+    }
+    let project = project::Entity::find_by_id(session.project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", session.project_id))?;
+
+    let outcome = async {
+        let report = audit_workspace(
+            project.project_id,
+            Path::new(&project.workspace_path),
+            source,
+            &options.license_policy,
+        )
+        .await?;
+
+        let artifact = store
+            .save(
+                session_id,
+                AUDIT_REPORT_ARTIFACT,
+                ArtifactType::Documentation,
+                &serde_json::to_vec_pretty(&report)?,
+                Some(format!(
+                    "{} 个依赖，{} 个许可证问题，{} 个存在已知漏洞",
+                    report.dependency_count,
+                    report.license_issues.len(),
+                    report.vulnerable_dependencies.len()
+                )),
+            )
+            .await?;
+
+        let remediation_task_ids = if options.create_remediation_tasks {
+            create_remediation_tasks(store, &audit_task, &report).await?
+        } else {
+            Vec::new()
+        };
+
+        Ok::<_, DatabaseError>(DependencyAuditOutcome {
+            report,
+            artifact_id: artifact.artifact_id,
+            remediation_task_ids,
+        })
+    }
+    .await;
+
+    match outcome {
+        Ok(outcome) => {
+            let summary = json!({
+                "dependency_count": outcome.report.dependency_count,
+                "license_issues": outcome.report.license_issues.len(),
+                "vulnerable_dependencies": outcome.report.vulnerable_dependencies.len(),
+                "vulnerabilities_checked": outcome.report.vulnerabilities_checked,
+                "artifact_id": outcome.artifact_id,
+                "remediation_task_ids": outcome.remediation_task_ids,
+            });
+            sessions
+                .complete_session(session_id, true, None, Some(summary), outcome.report.vulnerability_error.clone())
+                .await?;
+            Ok(outcome)
+        }
+        Err(e) => {
+            sessions
+                .complete_session(session_id, false, None, None, Some(format!("依赖审计失败: {}", e)))
+                .await?;
+            Err(e)
+        }
+    }
+}
+
+/// 为审计发现的问题创建修复子任务，已存在同名子任务时跳过
+async fn create_remediation_tasks(
+    store: &ArtifactStore,
+    audit_task: &task::Model,
+    report: &DependencyAuditReport,
+) -> Result<Vec<Uuid>> {
+    let tasks = TaskRepository::new(store.db().clone());
+    let existing: HashSet<String> = tasks
+        .find_subtasks(audit_task.task_id)
+        .await?
+        .into_iter()
+        .map(|task| task.title)
+        .collect();
+
+    let mut created = Vec::new();
+    for vulnerable in &report.vulnerable_dependencies {
+        let dependency = &vulnerable.dependency;
+        let title = format!("升级存在漏洞的依赖 {} {}", dependency.name, dependency.version);
+        if existing.contains(&title) {
+            continue;
+        }
+
+        let mut description = format!(
+            "{} 中的 {} 依赖 {}@{} 存在已知漏洞：\n",
+            dependency.manifest,
+            dependency.ecosystem.osv_name(),
+            dependency.name,
+            dependency.version
+        );
+        for vulnerability in &vulnerable.vulnerabilities {
+            description.push_str(&format!(
+                "- {}（{}）：{}\n",
+                vulnerability.id,
+                vulnerability.severity.as_deref().unwrap_or("未知严重程度"),
+                vulnerability.summary.as_deref().unwrap_or("无摘要")
+            ));
+        }
+        let fixed: Vec<&str> = vulnerable
+            .vulnerabilities
+            .iter()
+            .flat_map(|v| v.fixed_versions.iter().map(String::as_str))
+            .collect();
+        if !fixed.is_empty() {
+            description.push_str(&format!("修复版本：{}\n", fixed.join(", ")));
+        }
+
+        let priority = if vulnerable.vulnerabilities.iter().any(Vulnerability::is_severe) {
+            "high"
+        } else {
+            "medium"
+        };
+        let capabilities = vec![AgentCapability::SecurityAudit, dependency.ecosystem.capability()];
+        let criteria = vec![
+            format!("{} 升级到不受上述漏洞影响的版本", dependency.name),
+            "依赖审计不再报告该漏洞".to_string(),
+            "现有测试全部通过".to_string(),
+        ];
+        created.push(create_remediation_task(&tasks, audit_task, title, description, priority, capabilities, criteria).await?);
+    }
+
+    if !report.license_issues.is_empty() {
+        let title = "处理许可证不合规的依赖".to_string();
+        if !existing.contains(&title) {
+            let mut description = String::from("以下依赖的许可证不符合项目许可证策略，请替换或移除：\n");
+            for issue in &report.license_issues {
+                description.push_str(&format!(
+                    "- {}@{}（{}）：{}\n",
+                    issue.dependency.name, issue.dependency.version, issue.dependency.manifest, issue.reason
+                ));
+            }
+            let capabilities = vec![AgentCapability::SecurityAudit, AgentCapability::CodeReview];
+            let criteria = vec!["依赖审计不再报告许可证问题".to_string()];
+            created.push(create_remediation_task(&tasks, audit_task, title, description, "medium", capabilities, criteria).await?);
+        }
+    }
+    Ok(created)
+}
+
+pub(crate) async fn create_remediation_task(
+    tasks: &TaskRepository,
+    audit_task: &task::Model,
+    title: String,
+    description: String,
+    priority: &str,
+    capabilities: Vec<AgentCapability>,
+    acceptance_criteria: Vec<String>,
+) -> Result<Uuid> {
+    let subtask = tasks
+        .create_subtask(audit_task.task_id, title, description, REMEDIATION_TASK_TYPE.to_string())
+        .await?;
+    tasks
+        .update_requirements(
+            subtask.task_id,
+            Some(serde_json::to_value(capabilities)?),
+            Some(json!(acceptance_criteria)),
+        )
+        .await?;
+    tasks
+        .update_details(subtask.task_id, None, None, Some(priority.to_string()), None)
+        .await?;
+    Ok(subtask.task_id)
+}
@@ -0,0 +1,506 @@
+//! 语义检索
+//!
+//! 需求文档、任务和任务执行总结中的经验教训被切分为文本分块，由可插拔的
+//! [`EmbeddingProvider`] 计算向量后存入 `embeddings` 表。[`EmbeddingIndex::semantic_search`]
+//! 按余弦相似度返回最相关的内容；单个项目的向量数量有限，检索时直接在内存中逐一比较。
+//!
+//! 内置的 [`HashingEmbeddingProvider`] 不依赖网络，基于词、词内字符片段和汉字 n-gram
+//! 的特征哈希，能匹配用词相近的表述；需要理解同义改写时应接入外部嵌入模型。
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::{
+    entities::{embedding::EmbeddingSourceType, project, task},
+    operations::OperationHandle,
+    repository::{
+        embedding_repository::UpsertEmbeddingData, EmbeddingRepository, RequirementDocumentRepository,
+        TaskRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::EntityTrait;
+
+/// 每次调用嵌入提供方的最大文本数
+const BATCH_SIZE: usize = 32;
+
+/// 文档分块的最大字符数
+const CHUNK_CHARS: usize = 800;
+
+/// 嵌入计算的异步结果
+pub type EmbeddingFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<Vec<f32>>>> + Send + 'a>>;
+
+/// 文本嵌入提供方
+pub trait EmbeddingProvider: Send + Sync {
+    /// 模型标识，模型变化后已有的向量会被重新计算
+    fn model(&self) -> &str;
+
+    /// 批量计算文本向量，返回顺序与输入一致
+    fn embed<'a>(&'a self, texts: &'a [String]) -> EmbeddingFuture<'a>;
+}
+
+/// 基于特征哈希的本地嵌入提供方
+#[derive(Debug, Clone)]
+pub struct HashingEmbeddingProvider {
+    dimensions: usize,
+    model: String,
+}
+
+impl HashingEmbeddingProvider {
+    /// 创建指定维度的提供方
+    pub fn new(dimensions: usize) -> Self {
+        let dimensions = dimensions.max(1);
+        Self {
+            dimensions,
+            model: format!("hashing-{}", dimensions),
+        }
+    }
+
+    /// 计算单条文本的向量（已归一化）
+    pub fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dimensions];
+        for (feature, weight) in text_features(text) {
+            let hash = fnv1a(feature.as_bytes());
+            let bucket = (hash % self.dimensions as u64) as usize;
+            // 用哈希的最高位决定符号，减小哈希冲突带来的偏差
+            vector[bucket] += if hash >> 63 == 0 { weight } else { -weight };
+        }
+
+        let norm = vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|value| *value /= norm);
+        }
+        vector
+    }
+}
+
+impl Default for HashingEmbeddingProvider {
+    fn default() -> Self {
+        Self::new(512)
+    }
+}
+
+impl EmbeddingProvider for HashingEmbeddingProvider {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> EmbeddingFuture<'a> {
+        Box::pin(async move { Ok(texts.iter().map(|text| self.embed_text(text)).collect()) })
+    }
+}
+
+/// 索引更新结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexReport {
+    /// 新计算向量的分块数
+    pub embedded: usize,
+    /// 内容未变化而跳过的分块数
+    pub unchanged: usize,
+    /// 来源已删除而移除的分块数
+    pub removed: usize,
+}
+
+/// 检索范围
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchScope {
+    /// 项目ID
+    pub project_id: Uuid,
+    /// 来源类型，为空表示全部来源
+    #[serde(default)]
+    pub source_types: Vec<EmbeddingSourceType>,
+}
+
+impl SearchScope {
+    /// 检索项目的全部来源
+    pub fn project(project_id: Uuid) -> Self {
+        Self {
+            project_id,
+            source_types: Vec::new(),
+        }
+    }
+}
+
+/// 检索结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub source_type: EmbeddingSourceType,
+    pub source_id: Uuid,
+    /// 最相关的分块序号
+    pub chunk_index: i32,
+    /// 最相关的分块文本
+    pub content: String,
+    /// 余弦相似度
+    pub score: f32,
+}
+
+/// 待索引的文本分块
+struct Chunk {
+    source_type: EmbeddingSourceType,
+    source_id: Uuid,
+    chunk_index: i32,
+    content: String,
+}
+
+/// 语义检索索引
+#[derive(Clone)]
+pub struct EmbeddingIndex {
+    db: DatabaseConnection,
+    provider: Arc<dyn EmbeddingProvider>,
+}
+
+impl EmbeddingIndex {
+    /// 创建使用指定嵌入提供方的索引
+    pub fn new(db: DatabaseConnection, provider: Arc<dyn EmbeddingProvider>) -> Self {
+        Self { db, provider }
+    }
+
+    /// 当前使用的嵌入模型
+    pub fn model(&self) -> &str {
+        self.provider.model()
+    }
+
+    /// 更新项目的索引
+    ///
+    /// 只为内容或模型发生变化的分块重新计算向量，并移除来源已删除的分块。
+    pub async fn index_project(&self, project_id: Uuid) -> Result<IndexReport> {
+        self.index_project_inner(project_id, None).await
+    }
+
+    /// 更新项目的索引，通过操作句柄报告进度
+    ///
+    /// 每批向量计算前检查取消请求，取消时已写入的分块保留，下次更新会跳过它们。
+    pub async fn index_project_with_progress(&self, project_id: Uuid, operation: &OperationHandle) -> Result<IndexReport> {
+        self.index_project_inner(project_id, Some(operation)).await
+    }
+
+    async fn index_project_inner(&self, project_id: Uuid, operation: Option<&OperationHandle>) -> Result<IndexReport> {
+        let repo = EmbeddingRepository::new(self.db.clone());
+        let model = self.provider.model().to_string();
+        if let Some(operation) = operation {
+            operation.set_phase("收集内容", 0.0);
+        }
+        let chunks = self.collect_chunks(project_id).await?;
+        let existing = repo.find_by_project(project_id, &[]).await?;
+
+        let mut report = IndexReport::default();
+        let existing_keys: HashMap<(String, Uuid, i32), (String, String)> = existing
+            .iter()
+            .map(|row| {
+                (
+                    (row.source_type.clone(), row.source_id, row.chunk_index),
+                    (row.content_hash.clone(), row.model.clone()),
+                )
+            })
+            .collect();
+
+        let mut current_keys = HashSet::new();
+        let mut pending = Vec::new();
+        for chunk in chunks {
+            let key = (chunk.source_type.to_string(), chunk.source_id, chunk.chunk_index);
+            let hash = content_hash(&chunk.content);
+            match existing_keys.get(&key) {
+                Some((existing_hash, existing_model)) if *existing_hash == hash && *existing_model == model => {
+                    report.unchanged += 1;
+                }
+                _ => pending.push((chunk, hash)),
+            }
+            current_keys.insert(key);
+        }
+
+        if let Some(operation) = operation {
+            operation.set_phase("计算向量", 10.0);
+        }
+        let total_pending = pending.len();
+        for (batch_index, batch) in pending.chunks(BATCH_SIZE).enumerate() {
+            if let Some(operation) = operation {
+                operation.check_cancelled()?;
+                let done = batch_index * BATCH_SIZE;
+                operation.report(
+                    10.0 + 80.0 * done as f32 / total_pending as f32,
+                    Some(format!("{}/{} 个分块", done, total_pending)),
+                );
+            }
+            let texts: Vec<String> = batch.iter().map(|(chunk, _)| chunk.content.clone()).collect();
+            let vectors = self.provider.embed(&texts).await?;
+            if vectors.len() != batch.len() {
+                return Err(DatabaseError::business_logic(format!(
+                    "嵌入模型 {} 返回了 {} 个向量，期望 {} 个",
+                    model,
+                    vectors.len(),
+                    batch.len()
+                )));
+            }
+
+            for ((chunk, hash), vector) in batch.iter().zip(vectors) {
+                repo.upsert(UpsertEmbeddingData {
+                    project_id,
+                    source_type: chunk.source_type,
+                    source_id: chunk.source_id,
+                    chunk_index: chunk.chunk_index,
+                    content: chunk.content.clone(),
+                    content_hash: hash.clone(),
+                    model: model.clone(),
+                    vector,
+                })
+                .await?;
+                report.embedded += 1;
+            }
+        }
+
+        if let Some(operation) = operation {
+            operation.check_cancelled()?;
+            operation.set_phase("清理过期索引", 90.0);
+        }
+        for row in existing {
+            if !current_keys.contains(&(row.source_type.clone(), row.source_id, row.chunk_index)) {
+                repo.delete(row.embedding_id).await?;
+                report.removed += 1;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 更新全部项目的索引
+    pub async fn index_all(&self) -> Result<IndexReport> {
+        let mut total = IndexReport::default();
+        for project in project::Entity::find().all(&self.db).await? {
+            let report = self.index_project(project.project_id).await?;
+            total.embedded += report.embedded;
+            total.unchanged += report.unchanged;
+            total.removed += report.removed;
+        }
+        Ok(total)
+    }
+
+    /// 语义检索，返回相似度最高的 `top_k` 个来源
+    ///
+    /// 同一来源的多个分块只保留最相关的一个；其他模型生成的向量会被忽略，
+    /// 直到下一次索引更新后重新计算。
+    pub async fn semantic_search(&self, query: &str, scope: &SearchScope, top_k: usize) -> Result<Vec<SearchHit>> {
+        if query.trim().is_empty() {
+            return Err(DatabaseError::validation("检索内容不能为空"));
+        }
+        if top_k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let query_vector = self
+            .provider
+            .embed(&[query.to_string()])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| DatabaseError::business_logic("嵌入模型未返回检索向量"))?;
+
+        let rows = EmbeddingRepository::new(self.db.clone())
+            .find_by_project(scope.project_id, &scope.source_types)
+            .await?;
+
+        let mut best: HashMap<(EmbeddingSourceType, Uuid), SearchHit> = HashMap::new();
+        for row in rows.into_iter().filter(|row| row.model == self.provider.model()) {
+            let Ok(source_type) = row.source_type.parse::<EmbeddingSourceType>() else {
+                continue;
+            };
+            let vector = row.vector_values();
+            if vector.len() != query_vector.len() {
+                continue;
+            }
+
+            let score = cosine_similarity(&query_vector, &vector);
+            let key = (source_type, row.source_id);
+            if best.get(&key).is_some_and(|hit| hit.score >= score) {
+                continue;
+            }
+            best.insert(
+                key,
+                SearchHit {
+                    source_type,
+                    source_id: row.source_id,
+                    chunk_index: row.chunk_index,
+                    content: row.content,
+                    score,
+                },
+            );
+        }
+
+        let mut hits: Vec<SearchHit> = best.into_values().collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(top_k);
+        Ok(hits)
+    }
+
+    /// 收集项目中需要索引的文本分块
+    async fn collect_chunks(&self, project_id: Uuid) -> Result<Vec<Chunk>> {
+        let mut chunks = Vec::new();
+
+        let documents = RequirementDocumentRepository::new(self.db.clone())
+            .find_by_project(project_id)
+            .await?;
+        for document in documents {
+            for (index, part) in split_chunks(&document.content, CHUNK_CHARS).into_iter().enumerate() {
+                chunks.push(Chunk {
+                    source_type: EmbeddingSourceType::RequirementDocument,
+                    source_id: document.document_id,
+                    chunk_index: index as i32,
+                    content: format!("{}\n{}", document.title, part),
+                });
+            }
+        }
+
+        let tasks = TaskRepository::new(self.db.clone()).find_by_project(project_id).await?;
+        for task in tasks {
+            chunks.push(Chunk {
+                source_type: EmbeddingSourceType::Task,
+                source_id: task.task_id,
+                chunk_index: 0,
+                content: format!("{}\n{}", task.title, task.description),
+            });
+            for (index, lesson) in task_lessons(&task).into_iter().enumerate() {
+                chunks.push(Chunk {
+                    source_type: EmbeddingSourceType::Lesson,
+                    source_id: task.task_id,
+                    chunk_index: index as i32,
+                    content: lesson,
+                });
+            }
+        }
+
+        Ok(chunks)
+    }
+}
+
+/// 启动后台索引任务，按固定间隔更新全部项目的索引
+pub fn spawn_background_indexing(index: Arc<EmbeddingIndex>, interval: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match index.index_all().await {
+                Ok(report) if report.embedded > 0 || report.removed > 0 => {
+                    tracing::info!("语义索引已更新: 新增 {} 个分块，移除 {} 个分块", report.embedded, report.removed);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("更新语义索引失败: {}", e),
+            }
+        }
+    })
+}
+
+/// 读取任务执行总结中的经验教训
+fn task_lessons(task: &task::Model) -> Vec<String> {
+    task.execution_result
+        .as_ref()
+        .and_then(|result| result.pointer("/execution_summary/lessons_learned"))
+        .and_then(|lessons| lessons.as_array())
+        .map(|lessons| {
+            lessons
+                .iter()
+                .filter_map(|lesson| lesson.as_str())
+                .filter(|lesson| !lesson.trim().is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 按段落切分文本，每块不超过 `max_chars` 个字符；空文本返回一个空分块
+fn split_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        if !current.is_empty() && current.chars().count() + paragraph.chars().count() > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+
+        // 超长段落按字符硬切
+        let chars: Vec<char> = paragraph.chars().collect();
+        for piece in chars.chunks(max_chars) {
+            if !current.is_empty() {
+                if current.chars().count() + piece.len() > max_chars {
+                    chunks.push(std::mem::take(&mut current));
+                } else {
+                    current.push_str("\n\n");
+                }
+            }
+            current.extend(piece);
+        }
+    }
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// 提取文本特征及权重：拉丁词、词内三字符片段、汉字单字和双字
+fn text_features(text: &str) -> Vec<(String, f32)> {
+    let mut features = Vec::new();
+    let mut word = String::new();
+    let mut previous_cjk: Option<char> = None;
+
+    for c in text.to_lowercase().chars() {
+        if is_cjk(c) {
+            flush_word(&mut word, &mut features);
+            features.push((format!("c:{}", c), 1.0));
+            if let Some(previous) = previous_cjk {
+                features.push((format!("b:{}{}", previous, c), 1.0));
+            }
+            previous_cjk = Some(c);
+        } else if c.is_alphanumeric() {
+            word.push(c);
+            previous_cjk = None;
+        } else {
+            flush_word(&mut word, &mut features);
+            previous_cjk = None;
+        }
+    }
+    flush_word(&mut word, &mut features);
+
+    features
+}
+
+/// 将累积的拉丁词及其三字符片段加入特征
+fn flush_word(word: &mut String, features: &mut Vec<(String, f32)>) {
+    if word.is_empty() {
+        return;
+    }
+    let padded: Vec<char> = format!("#{}#", word).chars().collect();
+    for gram in padded.windows(3) {
+        features.push((format!("g:{}", gram.iter().collect::<String>()), 0.5));
+    }
+    features.push((format!("w:{}", word), 1.0));
+    word.clear();
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' | '\u{f900}'..='\u{faff}')
+}
+
+/// FNV-1a 哈希，结果跨版本稳定
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
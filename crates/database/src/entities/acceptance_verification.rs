@@ -0,0 +1,69 @@
+//! 验收标准验证记录实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 验收标准验证记录实体模型
+///
+/// 每条记录对应任务的一项验收标准，保存Agent提交的验证结果、证据和人工签核
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "acceptance_verifications")]
+pub struct Model {
+    /// 验证记录ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub verification_id: Uuid,
+
+    /// 任务ID
+    pub task_id: Uuid,
+
+    /// 验收标准内容
+    pub criterion: String,
+
+    /// 验证状态：pending, verified, failed
+    pub status: String,
+
+    /// 验证证据（JSON数组，引用执行日志、工件或说明）
+    #[sea_orm(column_type = "Json")]
+    pub evidence: JsonValue,
+
+    /// 提交验证结果的Agent ID
+    pub verified_by_agent_id: Option<Uuid>,
+
+    /// 验证说明
+    pub notes: Option<String>,
+
+    /// 签核用户ID
+    pub signed_off_by: Option<Uuid>,
+
+    /// 签核时间
+    pub signed_off_at: Option<DateTimeWithTimeZone>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 更新时间
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// 验收标准验证记录关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与任务的关联关系
+    #[sea_orm(
+        belongs_to = "super::task::Entity",
+        from = "Column::TaskId",
+        to = "super::task::Column::TaskId"
+    )]
+    Task,
+}
+
+/// 任务关联实现
+impl Related<super::task::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
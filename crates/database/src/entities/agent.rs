@@ -72,6 +72,9 @@ pub struct Model {
     /// 性能趋势（JSON格式存储PerformanceTrend）
     #[sea_orm(column_type = "Json")]
     pub performance_trend: Option<JsonValue>,
+    
+    /// 所属组织ID，设置后该Agent属于组织的共享Agent池
+    pub organization_id: Option<Uuid>,
 }
 
 /// Agent关联关系
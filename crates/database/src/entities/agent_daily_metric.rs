@@ -0,0 +1,61 @@
+//! Agent每日指标汇总实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Agent每日指标汇总实体模型，按Agent和项目分别汇总
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "agent_daily_metrics")]
+pub struct Model {
+    /// 记录ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub metric_id: Uuid,
+
+    /// 统计日期（UTC，YYYY-MM-DD）
+    pub metric_date: String,
+
+    /// Agent ID
+    pub agent_id: Uuid,
+
+    /// 项目ID
+    pub project_id: Uuid,
+
+    /// 当天成功完成的工作数
+    pub tasks_completed: i32,
+
+    /// 当天失败的工作数
+    pub tasks_failed: i32,
+
+    /// 工作详情中记录的 token 数
+    pub total_tokens: i64,
+
+    /// 工作费用
+    pub total_cost: f64,
+
+    /// 当天执行日志中的错误数
+    pub error_count: i32,
+
+    /// 汇总时间
+    pub computed_at: DateTimeWithTimeZone,
+}
+
+/// Agent每日指标汇总关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与Agent的关联关系
+    #[sea_orm(
+        belongs_to = "super::agent::Entity",
+        from = "Column::AgentId",
+        to = "super::agent::Column::AgentId"
+    )]
+    Agent,
+}
+
+impl Related<super::agent::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Agent.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
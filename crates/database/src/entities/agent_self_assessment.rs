@@ -0,0 +1,72 @@
+//! Agent自我评估实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// Agent自我评估实体模型
+///
+/// Agent完成任务时上报的置信度和自评，审查完成后记录审查决策，用于校准Agent的置信度
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "agent_self_assessments")]
+pub struct Model {
+    /// 评估ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub assessment_id: Uuid,
+
+    /// 执行会话ID，每个会话最多一条自评
+    #[sea_orm(unique)]
+    pub session_id: Uuid,
+
+    /// 任务ID
+    pub task_id: Uuid,
+
+    /// Agent ID
+    pub agent_id: Uuid,
+
+    /// Agent上报的置信度（0.0-1.0）
+    pub confidence: f64,
+
+    /// 自我评估摘要
+    pub summary: String,
+
+    /// Agent自认为存在风险的地方（JSON字符串数组）
+    #[sea_orm(column_type = "Json")]
+    pub concerns: JsonValue,
+
+    /// 按历史审查结果校准后的置信度
+    pub calibrated_confidence: f64,
+
+    /// 校准后的置信度低于阈值，需要人工审查
+    pub escalated: bool,
+
+    /// 审查决策：approved, changes_requested, rejected，审查完成前为空
+    pub review_decision: Option<String>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 审查决策的记录时间
+    pub reviewed_at: Option<DateTimeWithTimeZone>,
+}
+
+/// Agent自我评估关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与执行会话的关联关系
+    #[sea_orm(
+        belongs_to = "super::execution_session::Entity",
+        from = "Column::SessionId",
+        to = "super::execution_session::Column::SessionId"
+    )]
+    ExecutionSession,
+}
+
+impl Related<super::execution_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExecutionSession.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
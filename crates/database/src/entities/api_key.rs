@@ -0,0 +1,93 @@
+//! API密钥实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// API密钥实体模型
+///
+/// 只保存密钥的 SHA-256 摘要，明文仅在创建时返回一次；`key_prefix` 是密钥中公开的部分，用于查找和展示
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "api_keys")]
+pub struct Model {
+    /// 密钥ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub key_id: Uuid,
+
+    /// 所属用户ID，请求以该用户的身份执行
+    pub user_id: Uuid,
+
+    /// 限定的项目ID，为空时可访问用户有权限的全部项目
+    pub project_id: Option<Uuid>,
+
+    /// 密钥名称
+    pub name: String,
+
+    /// 密钥前缀（唯一）
+    #[sea_orm(unique)]
+    pub key_prefix: String,
+
+    /// 密钥摘要（十六进制 SHA-256）
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+
+    /// 权限范围（JSON数组）：projects:read, tasks:read, tasks:write
+    pub scopes: Json,
+
+    /// 每分钟允许的请求数
+    pub rate_limit_per_minute: i32,
+
+    /// 累计通过的请求数
+    pub request_count: i64,
+
+    /// 累计因超出速率限制被拒绝的请求数
+    pub throttled_count: i64,
+
+    /// 最近使用时间
+    pub last_used_at: Option<DateTimeWithTimeZone>,
+
+    /// 过期时间，为空时不过期
+    pub expires_at: Option<DateTimeWithTimeZone>,
+
+    /// 吊销时间
+    pub revoked_at: Option<DateTimeWithTimeZone>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// API密钥关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与用户的关联关系
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::UserId"
+    )]
+    User,
+
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+}
+
+/// 用户关联实现
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,85 @@
+//! 基准测试结果实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 基准测试结果实体模型
+///
+/// 每条记录对应执行会话中一项基准测试的测量值，按分支保存用于回归比较和趋势图
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "benchmark_results")]
+pub struct Model {
+    /// 结果ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub result_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// 执行会话ID
+    pub execution_session_id: Uuid,
+
+    /// 测量时所在的分支
+    pub branch: String,
+
+    /// 测量时的提交
+    pub commit_sha: Option<String>,
+
+    /// 基准测试命令名称，对应性能预算中的配置
+    pub suite: String,
+
+    /// 基准测试名称，例如 criterion 的 group/function
+    pub name: String,
+
+    /// 测量值
+    pub value: f64,
+
+    /// 测量值单位，criterion 结果统一为 ns
+    pub unit: String,
+
+    /// 数值越大越好（吞吐量等），否则越小越好（耗时等）
+    pub higher_is_better: bool,
+
+    /// 是否为该分支当前的比较基线
+    pub is_baseline: bool,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 基准测试结果关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+
+    /// 与执行会话的关联关系
+    #[sea_orm(
+        belongs_to = "super::execution_session::Entity",
+        from = "Column::ExecutionSessionId",
+        to = "super::execution_session::Column::SessionId"
+    )]
+    ExecutionSession,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+/// 执行会话关联实现
+impl Related<super::execution_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExecutionSession.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,67 @@
+//! 项目黑板条目实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 项目黑板条目实体模型
+///
+/// 每次写入保存为一个新版本，同一键的最新版本即当前值；删除也记录为一个版本
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "blackboard_entries")]
+pub struct Model {
+    /// 条目版本ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub entry_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// 条目键
+    pub key: String,
+
+    /// 版本号，从1开始递增
+    pub version: i32,
+
+    /// 分类：decision, api_spec, naming_convention, note
+    pub category: String,
+
+    /// 内容
+    pub value: String,
+
+    /// 是否纳入任务分解和执行提示词
+    pub include_in_prompts: bool,
+
+    /// 该版本是否为删除记录
+    pub deleted: bool,
+
+    /// 写入的用户ID
+    pub author_user_id: Option<Uuid>,
+
+    /// 写入的Agent ID
+    pub author_agent_id: Option<Uuid>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 项目黑板条目关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
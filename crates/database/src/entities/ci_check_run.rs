@@ -0,0 +1,98 @@
+//! CI 检查结果实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// CI 检查结果实体模型
+///
+/// 每条记录对应一次流水线运行或其中一个检查（GitHub Actions 的 workflow run / check run，
+/// GitLab CI 的 pipeline / job），同一外部ID的后续 webhook 会更新原记录
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "ci_check_runs")]
+pub struct Model {
+    /// 检查记录ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub check_run_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// CI 提供方：github_actions, gitlab_ci
+    pub provider: String,
+
+    /// 提供方内的唯一标识，例如 workflow_run:123、job:456
+    pub external_id: String,
+
+    /// 工作流或作业名称
+    pub name: String,
+
+    /// 被检查的提交
+    pub commit_sha: String,
+
+    /// 被检查的分支
+    pub branch: Option<String>,
+
+    /// 关联的 Pull Request / Merge Request 地址
+    pub pull_request_url: Option<String>,
+
+    /// 映射到的执行会话ID
+    pub execution_session_id: Option<Uuid>,
+
+    /// 映射到的代码审查ID
+    pub review_id: Option<Uuid>,
+
+    /// 检查状态：pending, running, passed, failed, cancelled
+    pub status: String,
+
+    /// 提供方页面地址
+    pub details_url: Option<String>,
+
+    /// 开始时间
+    pub started_at: Option<DateTimeWithTimeZone>,
+
+    /// 完成时间
+    pub completed_at: Option<DateTimeWithTimeZone>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 最后更新时间
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// CI 检查结果关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+
+    /// 与执行会话的关联关系
+    #[sea_orm(
+        belongs_to = "super::execution_session::Entity",
+        from = "Column::ExecutionSessionId",
+        to = "super::execution_session::Column::SessionId"
+    )]
+    ExecutionSession,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+/// 执行会话关联实现
+impl Related<super::execution_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExecutionSession.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -53,6 +53,30 @@ pub struct Model {
     
     /// 审查完成时间
     pub reviewed_at: Option<DateTimeWithTimeZone>,
+    
+    /// 会话代码差异工件ID（工件被保留策略清理后可能失效）
+    pub diff_artifact_id: Option<Uuid>,
+    
+    /// 审查优先级：low, normal, high, urgent，决定审查时限
+    pub priority: String,
+    
+    /// 人工审查者，设置时由该用户而不是审查员Agent负责审查
+    pub reviewer_user_id: Option<Uuid>,
+    
+    /// 当前审查者的分配时间
+    pub assigned_at: Option<DateTimeWithTimeZone>,
+    
+    /// 审查截止时间
+    pub review_deadline: Option<DateTimeWithTimeZone>,
+    
+    /// 当前分配期内发送提醒的时间
+    pub reminded_at: Option<DateTimeWithTimeZone>,
+    
+    /// 超过截止时间且无法改派时的升级时间
+    pub escalated_at: Option<DateTimeWithTimeZone>,
+    
+    /// 超时自动改派的次数
+    pub reassignment_count: i32,
 }
 
 /// 代码审查关联关系
@@ -0,0 +1,79 @@
+//! 命令审批审计日志实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 命令审批审计日志实体模型
+///
+/// 命令策略引擎的每次判定都会记录一条，包括自动批准、上报人工确认和拒绝
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "command_audit_logs")]
+pub struct Model {
+    /// 审计记录ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub audit_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Option<Uuid>,
+
+    /// 提出命令的Agent ID
+    pub agent_id: Option<Uuid>,
+
+    /// 所在对话ID
+    pub conversation_id: Option<String>,
+
+    /// 命令文本
+    pub command: String,
+
+    /// 命令的工作目录
+    pub working_dir: String,
+
+    /// 风险等级：low, medium, high, critical
+    pub risk_level: String,
+
+    /// 判定结果：auto_approve, escalate, deny
+    pub decision: String,
+
+    /// 命中的规则列表（JSON格式）
+    pub matched_rules: Json,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 命令审批审计日志关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+
+    /// 与Agent的关联关系
+    #[sea_orm(
+        belongs_to = "super::agent::Entity",
+        from = "Column::AgentId",
+        to = "super::agent::Column::AgentId"
+    )]
+    Agent,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+/// Agent关联实现
+impl Related<super::agent::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Agent.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
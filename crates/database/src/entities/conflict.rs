@@ -37,6 +37,9 @@ pub struct Model {
     #[sea_orm(column_type = "Json")]
     pub affected_agents: JsonValue,
     
+    /// 所属项目ID，未关联项目的旧记录为空
+    pub project_id: Option<Uuid>,
+    
     /// 冲突状态：detected, analyzing, escalated, resolving, resolved, ignored
     pub status: String,
     
@@ -124,6 +127,21 @@ impl std::fmt::Display for ConflictType {
     }
 }
 
+impl std::str::FromStr for ConflictType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "git_merge" => Ok(ConflictType::GitMerge),
+            "resource" => Ok(ConflictType::Resource),
+            "task_dependency" => Ok(ConflictType::TaskDependency),
+            "capability" => Ok(ConflictType::Capability),
+            "timeline" => Ok(ConflictType::Timeline),
+            _ => Err(format!("未知的冲突类型: {}", s)),
+        }
+    }
+}
+
 /// 冲突严重性枚举
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ConflictSeverity {
@@ -148,6 +166,20 @@ impl std::fmt::Display for ConflictSeverity {
     }
 }
 
+impl std::str::FromStr for ConflictSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "low" => Ok(ConflictSeverity::Low),
+            "medium" => Ok(ConflictSeverity::Medium),
+            "high" => Ok(ConflictSeverity::High),
+            "critical" => Ok(ConflictSeverity::Critical),
+            _ => Err(format!("未知的冲突严重性: {}", s)),
+        }
+    }
+}
+
 /// 冲突状态枚举
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ConflictStatus {
@@ -176,4 +208,20 @@ impl std::fmt::Display for ConflictStatus {
             ConflictStatus::Ignored => write!(f, "ignored"),
         }
     }
+}
+
+impl std::str::FromStr for ConflictStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "detected" => Ok(ConflictStatus::Detected),
+            "analyzing" => Ok(ConflictStatus::Analyzing),
+            "escalated" => Ok(ConflictStatus::Escalated),
+            "resolving" => Ok(ConflictStatus::Resolving),
+            "resolved" => Ok(ConflictStatus::Resolved),
+            "ignored" => Ok(ConflictStatus::Ignored),
+            _ => Err(format!("未知的冲突状态: {}", s)),
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,51 @@
+//! 项目上下文快照实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 项目上下文快照实体模型
+///
+/// 每次构建任务分解提示词时记录一份 `ProjectContext`，下一次分解与它比较得出变化
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "context_snapshots")]
+pub struct Model {
+    /// 快照ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub snapshot_id: Uuid,
+
+    /// 项目ID
+    pub project_id: Uuid,
+
+    /// 触发分解的需求文档ID
+    pub document_id: Option<Uuid>,
+
+    /// 项目上下文（JSON存储）
+    #[sea_orm(column_type = "Json")]
+    pub context: JsonValue,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 项目上下文快照关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,82 @@
+//! 覆盖率报告实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 覆盖率报告实体模型
+///
+/// 每个执行会话保留一份覆盖率报告，重新上报时覆盖原记录
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "coverage_reports")]
+pub struct Model {
+    /// 报告ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub report_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// 产生该报告的执行会话ID
+    pub execution_session_id: Uuid,
+
+    /// 报告格式：lcov, cobertura
+    pub format: String,
+
+    /// 被测量的提交
+    pub commit_sha: Option<String>,
+
+    /// 对比的基准报告ID（基准提交上最近一次的报告）
+    pub base_report_id: Option<Uuid>,
+
+    /// 可执行行数
+    pub lines_total: i64,
+
+    /// 被覆盖的行数
+    pub lines_covered: i64,
+
+    /// 行覆盖率（0.0-1.0）
+    pub line_rate: f64,
+
+    /// 按文件统计的覆盖率 (JSON)
+    pub files: Json,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 覆盖率报告关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+
+    /// 与执行会话的关联关系
+    #[sea_orm(
+        belongs_to = "super::execution_session::Entity",
+        from = "Column::ExecutionSessionId",
+        to = "super::execution_session::Column::SessionId"
+    )]
+    ExecutionSession,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+/// 执行会话关联实现
+impl Related<super::execution_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExecutionSession.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
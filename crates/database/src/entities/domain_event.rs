@@ -101,6 +101,8 @@ pub enum DomainEventType {
     TaskCompleted,
     /// 任务失败
     TaskFailed,
+    /// 任务进度已更新
+    TaskProgressUpdated,
     /// 冲突已检测
     ConflictDetected,
     /// 冲突已解决
@@ -111,6 +113,36 @@ pub enum DomainEventType {
     ExecutionSessionStarted,
     /// 执行会话已完成
     ExecutionSessionCompleted,
+    /// 工作空间磁盘占用跨越配额阈值
+    WorkspaceQuotaThresholdCrossed,
+    /// 任务取消导致需求章节失去覆盖
+    RequirementCoverageDropped,
+    /// 跳过未验证的验收标准强制完成任务
+    AcceptanceCriteriaOverridden,
+    /// 运行中的任务被紧急任务抢占
+    TaskPreempted,
+    /// 任务或里程碑违反SLA
+    SlaBreached,
+    /// 收到外部仓库推送
+    GitPushReceived,
+    /// 收到外部CI状态
+    CiStatusReceived,
+    /// 外部议题已更新
+    ExternalIssueUpdated,
+    /// Agent 的 Git 操作违反分支保护被拦截
+    BranchProtectionViolated,
+    /// 任务人工检查点已被批准或驳回
+    HumanGateDecided,
+    /// 审查即将到期，已提醒审查者
+    ReviewReminderSent,
+    /// 审查已改派给其他审查者
+    ReviewReassigned,
+    /// 审查超过截止时间且无法改派
+    ReviewDeadlineMissed,
+    /// 用户数据已按删除请求清除
+    UserDataPurged,
+    /// Agent池已自动扩容或缩容
+    AgentPoolScaled,
 }
 
 impl std::fmt::Display for DomainEventType {
@@ -122,11 +154,27 @@ impl std::fmt::Display for DomainEventType {
             DomainEventType::TaskStarted => write!(f, "TaskStarted"),
             DomainEventType::TaskCompleted => write!(f, "TaskCompleted"),
             DomainEventType::TaskFailed => write!(f, "TaskFailed"),
+            DomainEventType::TaskProgressUpdated => write!(f, "TaskProgressUpdated"),
             DomainEventType::ConflictDetected => write!(f, "ConflictDetected"),
             DomainEventType::ConflictResolved => write!(f, "ConflictResolved"),
             DomainEventType::ProjectCreated => write!(f, "ProjectCreated"),
             DomainEventType::ExecutionSessionStarted => write!(f, "ExecutionSessionStarted"),
             DomainEventType::ExecutionSessionCompleted => write!(f, "ExecutionSessionCompleted"),
+            DomainEventType::WorkspaceQuotaThresholdCrossed => write!(f, "WorkspaceQuotaThresholdCrossed"),
+            DomainEventType::RequirementCoverageDropped => write!(f, "RequirementCoverageDropped"),
+            DomainEventType::AcceptanceCriteriaOverridden => write!(f, "AcceptanceCriteriaOverridden"),
+            DomainEventType::TaskPreempted => write!(f, "TaskPreempted"),
+            DomainEventType::SlaBreached => write!(f, "SlaBreached"),
+            DomainEventType::GitPushReceived => write!(f, "GitPushReceived"),
+            DomainEventType::CiStatusReceived => write!(f, "CiStatusReceived"),
+            DomainEventType::ExternalIssueUpdated => write!(f, "ExternalIssueUpdated"),
+            DomainEventType::BranchProtectionViolated => write!(f, "BranchProtectionViolated"),
+            DomainEventType::HumanGateDecided => write!(f, "HumanGateDecided"),
+            DomainEventType::ReviewReminderSent => write!(f, "ReviewReminderSent"),
+            DomainEventType::ReviewReassigned => write!(f, "ReviewReassigned"),
+            DomainEventType::ReviewDeadlineMissed => write!(f, "ReviewDeadlineMissed"),
+            DomainEventType::UserDataPurged => write!(f, "UserDataPurged"),
+            DomainEventType::AgentPoolScaled => write!(f, "AgentPoolScaled"),
         }
     }
 }
@@ -0,0 +1,132 @@
+//! 语义检索向量实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 语义检索向量实体模型
+///
+/// 每条记录对应需求文档、任务或经验教训的一个文本分块，
+/// 向量以小端 f32 序列存储
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "embeddings")]
+pub struct Model {
+    /// 向量ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub embedding_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// 来源类型：requirement_document, task, lesson
+    pub source_type: String,
+
+    /// 来源ID（需求文档ID或任务ID）
+    pub source_id: Uuid,
+
+    /// 分块序号
+    pub chunk_index: i32,
+
+    /// 分块文本
+    pub content: String,
+
+    /// 分块文本的 SHA-256 校验和，内容未变化时跳过重新计算
+    pub content_hash: String,
+
+    /// 生成向量的模型
+    pub model: String,
+
+    /// 向量数据
+    pub vector: Vec<u8>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 更新时间
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// 语义检索向量关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// 解码向量数据
+    pub fn vector_values(&self) -> Vec<f32> {
+        self.vector
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect()
+    }
+}
+
+/// 将向量编码为存储格式
+pub fn encode_vector(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+/// 向量来源类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingSourceType {
+    /// 需求文档
+    RequirementDocument,
+    /// 任务
+    Task,
+    /// 任务执行总结中的经验教训
+    Lesson,
+}
+
+impl EmbeddingSourceType {
+    /// 全部来源类型
+    pub const ALL: [EmbeddingSourceType; 3] = [
+        EmbeddingSourceType::RequirementDocument,
+        EmbeddingSourceType::Task,
+        EmbeddingSourceType::Lesson,
+    ];
+
+    /// 存储使用的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmbeddingSourceType::RequirementDocument => "requirement_document",
+            EmbeddingSourceType::Task => "task",
+            EmbeddingSourceType::Lesson => "lesson",
+        }
+    }
+}
+
+impl std::fmt::Display for EmbeddingSourceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for EmbeddingSourceType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "requirement_document" => Ok(EmbeddingSourceType::RequirementDocument),
+            "task" => Ok(EmbeddingSourceType::Task),
+            "lesson" => Ok(EmbeddingSourceType::Lesson),
+            _ => Err(format!("未知的检索来源类型: {}", s)),
+        }
+    }
+}
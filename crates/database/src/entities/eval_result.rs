@@ -0,0 +1,78 @@
+//! 评估结果实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 评估结果实体模型
+///
+/// 一个变体在一个评估用例上的输出和各属性的检查结果
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "eval_results")]
+pub struct Model {
+    /// 结果ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub result_id: Uuid,
+
+    /// 评估运行ID
+    pub run_id: Uuid,
+
+    /// 变体名称
+    pub variant_name: String,
+
+    /// 用例名称
+    pub case_name: String,
+
+    /// 使用的模型
+    pub model: String,
+
+    /// 填充后的提示词
+    pub prompt: String,
+
+    /// 模型输出，调用失败时为空
+    pub output: Option<String>,
+
+    /// 调用失败的错误信息
+    pub error_message: Option<String>,
+
+    /// 得分：通过的属性占已检查属性的比例
+    pub score: f64,
+
+    /// 是否通过所有已检查的属性
+    pub passed: bool,
+
+    /// 各属性的检查结果（JSON存储）
+    #[sea_orm(column_type = "Json")]
+    pub checks: JsonValue,
+
+    /// 消耗的token数
+    pub tokens_used: Option<i32>,
+
+    /// 调用耗时（毫秒）
+    pub latency_ms: i64,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 评估结果关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与评估运行的关联关系
+    #[sea_orm(
+        belongs_to = "super::eval_run::Entity",
+        from = "Column::RunId",
+        to = "super::eval_run::Column::RunId"
+    )]
+    EvalRun,
+}
+
+/// 评估运行关联实现
+impl Related<super::eval_run::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::EvalRun.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,62 @@
+//! 评估运行实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 评估运行实体模型
+///
+/// 对一个评估集运行一组变体（提示词模板 × 模型），逐用例结果保存在 `eval_results` 表
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "eval_runs")]
+pub struct Model {
+    /// 运行ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub run_id: Uuid,
+
+    /// 评估集ID
+    pub eval_set_id: Uuid,
+
+    /// 参与评估的变体，第一个为基线（JSON存储）
+    #[sea_orm(column_type = "Json")]
+    pub variants: JsonValue,
+
+    /// 是否配置了LLM裁判
+    pub judged: bool,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 评估运行关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与评估集的关联关系
+    #[sea_orm(
+        belongs_to = "super::eval_set::Entity",
+        from = "Column::EvalSetId",
+        to = "super::eval_set::Column::EvalSetId"
+    )]
+    EvalSet,
+
+    /// 与评估结果的关联关系
+    #[sea_orm(has_many = "super::eval_result::Entity")]
+    EvalResults,
+}
+
+/// 评估集关联实现
+impl Related<super::eval_set::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::EvalSet.def()
+    }
+}
+
+/// 评估结果关联实现
+impl Related<super::eval_result::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::EvalResults.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,68 @@
+//! 评估集实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 评估集实体模型
+///
+/// 一组评估用例，每个用例包含填入提示词模板的输入和输出应满足的属性
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "eval_sets")]
+pub struct Model {
+    /// 评估集ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub eval_set_id: Uuid,
+
+    /// 所属项目ID，为空时为全局评估集
+    pub project_id: Option<Uuid>,
+
+    /// 评估集名称
+    pub name: String,
+
+    /// 评估集描述
+    pub description: Option<String>,
+
+    /// 评估用例（JSON存储）
+    #[sea_orm(column_type = "Json")]
+    pub cases: JsonValue,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 更新时间
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// 评估集关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+
+    /// 与评估运行的关联关系
+    #[sea_orm(has_many = "super::eval_run::Entity")]
+    EvalRuns,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+/// 评估运行关联实现
+impl Related<super::eval_run::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::EvalRuns.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,59 @@
+//! 执行工件实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 执行工件实体模型
+///
+/// 记录执行会话产出的工件，内容按校验和存放在工件存储中，
+/// 相同内容的工件共享同一份数据
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "execution_artifacts")]
+pub struct Model {
+    /// 工件ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub artifact_id: Uuid,
+
+    /// 执行会话ID
+    pub session_id: Uuid,
+
+    /// 工件名称
+    pub name: String,
+
+    /// 工件类型：source_code, test_file, binary 等
+    pub artifact_type: String,
+
+    /// 内容校验和（SHA-256 十六进制）
+    pub checksum: String,
+
+    /// 内容大小（字节）
+    pub size_bytes: i64,
+
+    /// 描述
+    pub description: Option<String>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 执行工件关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与执行会话的关联关系
+    #[sea_orm(
+        belongs_to = "super::execution_session::Entity",
+        from = "Column::SessionId",
+        to = "super::execution_session::Column::SessionId"
+    )]
+    ExecutionSession,
+}
+
+/// 执行会话关联实现
+impl Related<super::execution_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExecutionSession.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
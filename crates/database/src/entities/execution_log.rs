@@ -93,54 +93,5 @@ impl From<String> for LogLevel {
     }
 }
 
-/// 事件类型枚举
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum EventType {
-    /// Git操作
-    GitOperation,
-    /// 文件变更
-    FileChange,
-    /// 测试运行
-    TestRun,
-    /// 编译
-    Compilation,
-    /// 部署
-    Deployment,
-    /// 代码分析
-    CodeAnalysis,
-    /// 依赖安装
-    DependencyInstall,
-    /// 环境配置
-    EnvironmentSetup,
-}
-
-impl std::fmt::Display for EventType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            EventType::GitOperation => write!(f, "git_operation"),
-            EventType::FileChange => write!(f, "file_change"),
-            EventType::TestRun => write!(f, "test_run"),
-            EventType::Compilation => write!(f, "compilation"),
-            EventType::Deployment => write!(f, "deployment"),
-            EventType::CodeAnalysis => write!(f, "code_analysis"),
-            EventType::DependencyInstall => write!(f, "dependency_install"),
-            EventType::EnvironmentSetup => write!(f, "environment_setup"),
-        }
-    }
-}
-
-impl From<String> for EventType {
-    fn from(event_type: String) -> Self {
-        match event_type.as_str() {
-            "git_operation" => EventType::GitOperation,
-            "file_change" => EventType::FileChange,
-            "test_run" => EventType::TestRun,
-            "compilation" => EventType::Compilation,
-            "deployment" => EventType::Deployment,
-            "code_analysis" => EventType::CodeAnalysis,
-            "dependency_install" => EventType::DependencyInstall,
-            "environment_setup" => EventType::EnvironmentSetup,
-            _ => EventType::GitOperation,
-        }
-    }
-}
\ No newline at end of file
+/// 事件类型枚举，与 codex-multi-agent 共用同一定义
+pub use codex_multi_agent::EventType;
@@ -59,6 +59,13 @@ pub struct Model {
     
     /// 错误信息
     pub error_message: Option<String>,
+    
+    /// 最近一次检查点（JSON格式存储SessionCheckpoint）
+    #[sea_orm(column_type = "Json")]
+    pub checkpoint: Option<JsonValue>,
+    
+    /// 会话使用的独立Git工作树路径，为空表示直接在项目工作空间中执行
+    pub worktree_path: Option<String>,
 }
 
 /// 执行会话关联关系
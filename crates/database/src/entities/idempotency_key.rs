@@ -0,0 +1,57 @@
+//! 幂等键实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 幂等键实体模型
+///
+/// `(scope, idempotency_key)` 唯一：首次请求以 `processing` 状态占用键，执行成功后写入结果和结果摘要并标记为
+/// `completed`，之后携带相同键的重试直接返回保存的结果；执行失败时删除记录，允许重试重新执行
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "idempotency_keys")]
+pub struct Model {
+    /// 记录ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub record_id: Uuid,
+
+    /// 作用域（命令名、接口或事件来源），不同作用域的键互不影响
+    pub scope: String,
+
+    /// 调用方提供的幂等键
+    pub idempotency_key: String,
+
+    /// 请求内容的 SHA-256 摘要，相同键携带不同请求时拒绝
+    pub request_hash: String,
+
+    /// 状态（processing, completed）
+    pub status: String,
+
+    /// 首次执行的结果
+    pub result: Option<Json>,
+
+    /// 结果的 SHA-256 摘要
+    pub result_hash: Option<String>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 完成时间
+    pub completed_at: Option<DateTimeWithTimeZone>,
+
+    /// 过期时间，过期后相同的键可以重新使用
+    pub expires_at: DateTimeWithTimeZone,
+}
+
+/// 幂等键关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// 是否已完成
+    pub fn is_completed(&self) -> bool {
+        self.status == "completed"
+    }
+}
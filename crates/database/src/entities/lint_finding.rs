@@ -0,0 +1,85 @@
+//! 静态分析发现实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 静态分析发现实体模型
+///
+/// 每条记录对应执行会话中 Linter 报告的一个问题，严重程度已映射到 `IssueSeverity`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "lint_findings")]
+pub struct Model {
+    /// 发现ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub finding_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// 执行会话ID
+    pub execution_session_id: Uuid,
+
+    /// Linter：clippy, eslint
+    pub tool: String,
+
+    /// 被检查的语言，对应编码规范中的语言配置
+    pub language: String,
+
+    /// 相对工作空间的文件路径
+    pub file_path: String,
+
+    /// 行号
+    pub line: Option<i32>,
+
+    /// 列号
+    pub column: Option<i32>,
+
+    /// 规则标识，例如 clippy::needless_return、no-unused-vars
+    pub rule: Option<String>,
+
+    /// 严重程度：info, minor, moderate, major, critical, blocker
+    pub severity: String,
+
+    /// 问题描述
+    pub message: String,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 静态分析发现关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+
+    /// 与执行会话的关联关系
+    #[sea_orm(
+        belongs_to = "super::execution_session::Entity",
+        from = "Column::ExecutionSessionId",
+        to = "super::execution_session::Column::SessionId"
+    )]
+    ExecutionSession,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+/// 执行会话关联实现
+impl Related<super::execution_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExecutionSession.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
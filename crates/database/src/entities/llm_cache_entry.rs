@@ -0,0 +1,45 @@
+//! LLM响应缓存条目实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// LLM响应缓存条目实体模型
+///
+/// 以提示词、模型和调用参数的哈希为键保存LLM的响应，过期或超出容量时被清理
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "llm_cache_entries")]
+pub struct Model {
+    /// 缓存键 - 主键，模型、参数和提示词的 SHA-256
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub cache_key: String,
+
+    /// 模型名称
+    pub model: String,
+
+    /// 提示词的 SHA-256，便于按提示词排查缓存
+    pub prompt_hash: String,
+
+    /// 缓存的响应
+    pub response: String,
+
+    /// 响应大小（字节）
+    pub size_bytes: i64,
+
+    /// 命中次数
+    pub hit_count: i32,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 最近一次写入或命中的时间，容量超限时按此淘汰
+    pub last_used_at: DateTimeWithTimeZone,
+
+    /// 过期时间
+    pub expires_at: DateTimeWithTimeZone,
+}
+
+/// LLM响应缓存条目关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
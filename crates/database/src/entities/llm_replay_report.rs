@@ -0,0 +1,63 @@
+//! LLM会话重放报告实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// LLM会话重放报告实体模型
+///
+/// 按原始输入重新执行一次LLM会话的所有调用，记录每次调用的输出与原始输出的差异
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "llm_replay_reports")]
+pub struct Model {
+    /// 报告ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub report_id: Uuid,
+
+    /// 被重放的LLM会话ID
+    pub llm_session_id: Uuid,
+
+    /// 重放使用的提供方
+    pub provider: String,
+
+    /// 重放指定的模型，为空时使用每次调用的原始模型
+    pub model_override: Option<String>,
+
+    /// 重放的调用数
+    pub total_calls: i32,
+
+    /// 输出与原始输出不同的调用数
+    pub changed_calls: i32,
+
+    /// 重放失败的调用数
+    pub failed_calls: i32,
+
+    /// 每次调用的比较结果（JSON存储）
+    #[sea_orm(column_type = "Json")]
+    pub calls: JsonValue,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// LLM会话重放报告关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与LLM会话的关联关系
+    #[sea_orm(
+        belongs_to = "super::llm_session::Entity",
+        from = "Column::LlmSessionId",
+        to = "super::llm_session::Column::SessionId"
+    )]
+    LlmSession,
+}
+
+/// LLM会话关联实现
+impl Related<super::llm_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LlmSession.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
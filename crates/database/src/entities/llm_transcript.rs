@@ -0,0 +1,98 @@
+//! LLM调用记录实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// LLM调用记录实体模型
+///
+/// 每次调用LLM提供方保存一条，包含完整的提示词和输出，查看时经过密钥脱敏
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "llm_transcripts")]
+pub struct Model {
+    /// 记录ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub transcript_id: Uuid,
+
+    /// 调用类型：decomposition、review、agent_execution、evaluation
+    pub call_kind: String,
+
+    /// 提供方名称
+    pub provider: String,
+
+    /// 使用的模型
+    pub model: String,
+
+    /// 完整提示词
+    pub prompt: String,
+
+    /// 调用参数（温度、最大token数）
+    #[sea_orm(column_type = "Json")]
+    pub request_params: Option<JsonValue>,
+
+    /// 模型输出，调用失败时为空
+    pub response: Option<String>,
+
+    /// 调用失败的错误信息
+    pub error_message: Option<String>,
+
+    /// 消耗的token数
+    pub tokens_used: Option<i32>,
+
+    /// 调用耗时（毫秒）
+    pub latency_ms: i64,
+
+    /// 所属项目ID
+    pub project_id: Option<Uuid>,
+
+    /// 所属任务ID
+    pub task_id: Option<Uuid>,
+
+    /// 所属LLM会话ID
+    pub llm_session_id: Option<Uuid>,
+
+    /// 所属执行会话ID
+    pub execution_session_id: Option<Uuid>,
+
+    /// 桌面端对话ID
+    pub conversation_id: Option<String>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// LLM调用记录关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+    /// 与任务的关联关系
+    #[sea_orm(
+        belongs_to = "super::task::Entity",
+        from = "Column::TaskId",
+        to = "super::task::Column::TaskId"
+    )]
+    Task,
+    /// 与LLM会话的关联关系
+    #[sea_orm(
+        belongs_to = "super::llm_session::Entity",
+        from = "Column::LlmSessionId",
+        to = "super::llm_session::Column::SessionId"
+    )]
+    LlmSession,
+    /// 与执行会话的关联关系
+    #[sea_orm(
+        belongs_to = "super::execution_session::Entity",
+        from = "Column::ExecutionSessionId",
+        to = "super::execution_session::Column::SessionId"
+    )]
+    ExecutionSession,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,78 @@
+//! 维护任务实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 维护任务实体模型
+///
+/// 按 cron 表达式定期执行的系统维护（数据库整理、日志清理、快照等）。`running_since` 是防止重叠执行的锁：
+/// 执行前以条件更新占用，执行结束后清空；超过锁超时仍未清空的视为执行进程已退出，可以被接管
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "maintenance_jobs")]
+pub struct Model {
+    /// 任务ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub job_id: Uuid,
+
+    /// 任务名称，唯一
+    pub name: String,
+
+    /// 任务类型，对应注册的处理器（vacuum, prune, snapshot 等）
+    pub kind: String,
+
+    /// cron 表达式（秒 分 时 日 月 周）
+    pub cron_expression: String,
+
+    /// 下一次执行时间的随机延后上限（秒），避免多个实例同时执行
+    pub jitter_secs: i32,
+
+    /// 传给处理器的参数（JSON格式）
+    #[sea_orm(column_type = "Json")]
+    pub params: JsonValue,
+
+    /// 是否启用
+    pub enabled: bool,
+
+    /// 下一次执行时间
+    pub next_run_at: DateTimeWithTimeZone,
+
+    /// 最近一次执行时间
+    pub last_run_at: Option<DateTimeWithTimeZone>,
+
+    /// 最近一次执行的状态
+    pub last_status: Option<String>,
+
+    /// 当前执行的开始时间，为空表示未在执行
+    pub running_since: Option<DateTimeWithTimeZone>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 更新时间
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// 维护任务关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 执行记录
+    #[sea_orm(has_many = "super::maintenance_job_run::Entity")]
+    Runs,
+}
+
+impl Related<super::maintenance_job_run::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Runs.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// 是否正在执行
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+}
@@ -0,0 +1,60 @@
+//! 维护任务执行记录实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 维护任务执行记录实体模型
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "maintenance_job_runs")]
+pub struct Model {
+    /// 记录ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub run_id: Uuid,
+
+    /// 维护任务ID
+    pub job_id: Uuid,
+
+    /// 触发方式（schedule, manual）
+    pub trigger: String,
+
+    /// 状态（running, succeeded, failed, skipped）
+    pub status: String,
+
+    /// 开始时间
+    pub started_at: DateTimeWithTimeZone,
+
+    /// 结束时间
+    pub finished_at: Option<DateTimeWithTimeZone>,
+
+    /// 执行耗时（毫秒）
+    pub duration_ms: Option<i64>,
+
+    /// 处理器返回的结果（JSON格式）
+    #[sea_orm(column_type = "Json", nullable)]
+    pub result: Option<JsonValue>,
+
+    /// 失败或跳过的原因
+    pub error_message: Option<String>,
+}
+
+/// 维护任务执行记录关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与维护任务的关联关系
+    #[sea_orm(
+        belongs_to = "super::maintenance_job::Entity",
+        from = "Column::JobId",
+        to = "super::maintenance_job::Column::JobId"
+    )]
+    Job,
+}
+
+impl Related<super::maintenance_job::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Job.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,89 @@
+//! 合并冲突解决记录实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 合并冲突解决记录实体模型
+///
+/// 记录每个冲突块（按内容哈希）的解决结果，已批准的记录会被复用
+/// （类似 git rerere）；LLM给出的方案先以待批准状态保存
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "merge_resolutions")]
+pub struct Model {
+    /// 记录ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub resolution_id: Uuid,
+
+    /// 产生该记录的冲突ID
+    pub conflict_id: Option<Uuid>,
+
+    /// 冲突文件路径
+    pub file_path: String,
+
+    /// 冲突块内容哈希（双方内容规范化后计算）
+    pub hunk_hash: String,
+
+    /// 解决后的内容
+    pub resolution: String,
+
+    /// 使用的解决策略
+    pub strategy: String,
+
+    /// 置信度（0.0-1.0）
+    pub confidence: f64,
+
+    /// 记录状态
+    pub status: String,
+
+    /// 被复用次数
+    pub use_count: i32,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 更新时间
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// 合并冲突解决记录关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与冲突的关联关系
+    #[sea_orm(
+        belongs_to = "super::conflict::Entity",
+        from = "Column::ConflictId",
+        to = "super::conflict::Column::ConflictId"
+    )]
+    Conflict,
+}
+
+/// 冲突关联实现
+impl Related<super::conflict::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Conflict.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// 解决记录状态枚举
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MergeResolutionStatus {
+    /// 等待人工批准
+    Pending,
+    /// 已批准，可复用
+    Approved,
+    /// 已拒绝
+    Rejected,
+}
+
+impl std::fmt::Display for MergeResolutionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeResolutionStatus::Pending => write!(f, "pending"),
+            MergeResolutionStatus::Approved => write!(f, "approved"),
+            MergeResolutionStatus::Rejected => write!(f, "rejected"),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! 指标汇总日期实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 已完成汇总的日期，没有任何数据的日期也会记录，用于判断需要补算的日期
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "metric_rollup_days")]
+pub struct Model {
+    /// 统计日期（UTC，YYYY-MM-DD） - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub metric_date: String,
+
+    /// 汇总的项目数
+    pub project_count: i32,
+
+    /// 汇总的Agent记录数
+    pub agent_count: i32,
+
+    /// 汇总时间
+    pub computed_at: DateTimeWithTimeZone,
+}
+
+/// 指标汇总日期关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
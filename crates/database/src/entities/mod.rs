@@ -6,11 +6,17 @@ pub mod project;
 pub mod requirement_document;
 pub mod llm_session;
 pub mod llm_conversation;
+pub mod llm_transcript;
+pub mod llm_replay_report;
+pub mod eval_set;
+pub mod eval_run;
+pub mod eval_result;
 pub mod task;
 pub mod agent;
 pub mod agent_work_history;
 pub mod execution_session;
 pub mod execution_log;
+pub mod execution_artifact;
 pub mod conflict;
 pub mod human_decision;
 pub mod domain_event;
@@ -18,6 +24,45 @@ pub mod event_publish_log;
 pub mod code_review;
 pub mod task_dependency;
 pub mod agent_performance_metrics;
+pub mod technical_debt_snapshot;
+pub mod merge_resolution;
+pub mod project_member;
+pub mod organization;
+pub mod team;
+pub mod organization_member;
+pub mod embedding;
+pub mod requirement_coverage;
+pub mod acceptance_verification;
+pub mod blackboard_entry;
+pub mod command_audit_log;
+pub mod pii_mapping;
+pub mod ci_check_run;
+pub mod remote_worker;
+pub mod sla_breach;
+pub mod webhook_dead_letter;
+pub mod report_schedule;
+pub mod project_report;
+pub mod llm_cache_entry;
+pub mod agent_self_assessment;
+pub mod task_gate;
+pub mod test_case_result;
+pub mod coverage_report;
+pub mod lint_finding;
+pub mod security_finding;
+pub mod benchmark_result;
+pub mod api_key;
+pub mod user_identity;
+pub mod user_two_factor;
+pub mod notification;
+pub mod idempotency_key;
+pub mod maintenance_job;
+pub mod maintenance_job_run;
+pub mod project_daily_metric;
+pub mod agent_daily_metric;
+pub mod metric_rollup_day;
+pub mod offloaded_payload;
+pub mod project_partition;
+pub mod context_snapshot;
 
 // 重新导出所有实体
 pub use user::Entity as User;
@@ -31,10 +76,55 @@ pub use agent::Entity as Agent;
 pub use agent_work_history::Entity as AgentWorkHistory;
 pub use execution_session::Entity as ExecutionSession;
 pub use execution_log::Entity as ExecutionLog;
+pub use execution_artifact::Entity as ExecutionArtifact;
 pub use conflict::Entity as Conflict;
 pub use human_decision::Entity as HumanDecision;
 pub use domain_event::Entity as DomainEvent;
 pub use event_publish_log::Entity as EventPublishLog;
 pub use code_review::Entity as CodeReview;
 pub use task_dependency::Entity as TaskDependency;
-pub use agent_performance_metrics::Entity as AgentPerformanceMetrics;
\ No newline at end of file
+pub use agent_performance_metrics::Entity as AgentPerformanceMetrics;
+pub use technical_debt_snapshot::Entity as TechnicalDebtSnapshot;
+pub use merge_resolution::Entity as MergeResolution;
+pub use project_member::Entity as ProjectMember;
+pub use organization::Entity as Organization;
+pub use team::Entity as Team;
+pub use organization_member::Entity as OrganizationMember;
+pub use embedding::Entity as Embedding;
+pub use requirement_coverage::Entity as RequirementCoverage;
+pub use acceptance_verification::Entity as AcceptanceVerification;
+pub use blackboard_entry::Entity as BlackboardEntry;
+pub use command_audit_log::Entity as CommandAuditLog;
+pub use pii_mapping::Entity as PiiMapping;
+pub use ci_check_run::Entity as CiCheckRun;
+pub use remote_worker::Entity as RemoteWorker;
+pub use sla_breach::Entity as SlaBreach;
+pub use webhook_dead_letter::Entity as WebhookDeadLetter;
+pub use report_schedule::Entity as ReportSchedule;
+pub use project_report::Entity as ProjectReport;
+pub use llm_cache_entry::Entity as LlmCacheEntry;
+pub use agent_self_assessment::Entity as AgentSelfAssessment;
+pub use task_gate::Entity as TaskGate;
+pub use test_case_result::Entity as TestCaseResult;
+pub use coverage_report::Entity as CoverageReport;
+pub use lint_finding::Entity as LintFinding;
+pub use security_finding::Entity as SecurityFinding;
+pub use benchmark_result::Entity as BenchmarkResult;
+pub use api_key::Entity as ApiKey;
+pub use user_identity::Entity as UserIdentity;
+pub use user_two_factor::Entity as UserTwoFactor;
+pub use notification::Entity as Notification;
+pub use idempotency_key::Entity as IdempotencyKey;
+pub use maintenance_job::Entity as MaintenanceJob;
+pub use maintenance_job_run::Entity as MaintenanceJobRun;
+pub use project_daily_metric::Entity as ProjectDailyMetric;
+pub use agent_daily_metric::Entity as AgentDailyMetric;
+pub use metric_rollup_day::Entity as MetricRollupDay;
+pub use offloaded_payload::Entity as OffloadedPayload;
+pub use project_partition::Entity as ProjectPartition;
+pub use context_snapshot::Entity as ContextSnapshot;
+pub use llm_transcript::Entity as LlmTranscript;
+pub use llm_replay_report::Entity as LlmReplayReport;
+pub use eval_set::Entity as EvalSet;
+pub use eval_run::Entity as EvalRun;
+pub use eval_result::Entity as EvalResult;
\ No newline at end of file
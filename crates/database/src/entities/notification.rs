@@ -0,0 +1,74 @@
+//! 通知实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 通知实体模型
+///
+/// 通知路由按用户偏好为每个投递渠道生成一行：`deliver_at` 是免打扰时段和摘要合并后的计划投递时间，
+/// 投递完成后写入 `delivered_at`；应用内渠道的记录同时作为用户的通知收件箱
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "notifications")]
+pub struct Model {
+    /// 通知ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub notification_id: Uuid,
+
+    /// 接收用户ID
+    pub user_id: Uuid,
+
+    /// 事件类别（task_updates, reviews, sla_alerts, ci_results, security, system）
+    pub category: String,
+
+    /// 投递渠道（in_app, desktop, email）
+    pub channel: String,
+
+    /// 标题
+    pub title: String,
+
+    /// 正文
+    pub body: String,
+
+    /// 关联的项目ID
+    pub project_id: Option<Uuid>,
+
+    /// 是否为紧急通知（不受免打扰时段限制）
+    pub urgent: bool,
+
+    /// 是否合并到摘要中投递
+    pub digest: bool,
+
+    /// 计划投递时间
+    pub deliver_at: DateTimeWithTimeZone,
+
+    /// 实际投递时间
+    pub delivered_at: Option<DateTimeWithTimeZone>,
+
+    /// 用户阅读时间（应用内通知）
+    pub read_at: Option<DateTimeWithTimeZone>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 通知关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与用户的关联关系
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::UserId"
+    )]
+    User,
+}
+
+/// 用户关联实现
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,38 @@
+//! 转存负载实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 转存到工件存储的大字段，记录数据所在行以便回收
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "offloaded_payloads")]
+pub struct Model {
+    /// 记录ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub payload_id: Uuid,
+
+    /// 引用所在的表，如 domain_events
+    pub owner_table: String,
+
+    /// 引用所在行的主键
+    pub owner_id: Uuid,
+
+    /// 压缩后内容的 SHA-256 校验和
+    pub checksum: String,
+
+    /// 原始 JSON 的字节数
+    pub original_size: i64,
+
+    /// 压缩后的字节数
+    pub stored_size: i64,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 转存负载关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,83 @@
+//! 组织实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 组织实体模型
+///
+/// 位于项目之上的分组单位，项目和Agent可以归属于组织
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "organizations")]
+pub struct Model {
+    /// 组织ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub organization_id: Uuid,
+
+    /// 组织名称
+    #[sea_orm(unique)]
+    pub name: String,
+
+    /// 组织描述
+    pub description: Option<String>,
+
+    /// 组织所有者用户ID
+    pub owner_id: Uuid,
+
+    /// 组织级设置（JSON格式存储OrganizationSettings）
+    #[sea_orm(column_type = "Json")]
+    pub settings: Option<JsonValue>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 更新时间
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// 组织关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与所有者用户的关联关系
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::OwnerId",
+        to = "super::user::Column::UserId"
+    )]
+    Owner,
+
+    /// 与团队的关联关系
+    #[sea_orm(has_many = "super::team::Entity")]
+    Teams,
+
+    /// 与组织成员的关联关系
+    #[sea_orm(has_many = "super::organization_member::Entity")]
+    Members,
+}
+
+/// 团队关联实现
+impl Related<super::team::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Teams.def()
+    }
+}
+
+/// 组织成员关联实现
+impl Related<super::organization_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Members.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// 获取组织级设置，未配置或格式无效时返回默认值
+    pub fn get_settings(&self) -> codex_multi_agent::OrganizationSettings {
+        self.settings
+            .as_ref()
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+}
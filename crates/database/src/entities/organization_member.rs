@@ -0,0 +1,82 @@
+//! 组织成员实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 组织成员实体模型
+///
+/// 记录用户在组织中的角色（owner / admin / member）及所属团队
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "organization_members")]
+pub struct Model {
+    /// 成员记录ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub member_id: Uuid,
+
+    /// 组织ID
+    pub organization_id: Uuid,
+
+    /// 用户ID
+    pub user_id: Uuid,
+
+    /// 所属团队ID
+    pub team_id: Option<Uuid>,
+
+    /// 成员角色
+    pub role: String,
+
+    /// 加入时间
+    pub joined_at: DateTimeWithTimeZone,
+}
+
+/// 组织成员关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与组织的关联关系
+    #[sea_orm(
+        belongs_to = "super::organization::Entity",
+        from = "Column::OrganizationId",
+        to = "super::organization::Column::OrganizationId"
+    )]
+    Organization,
+
+    /// 与团队的关联关系
+    #[sea_orm(
+        belongs_to = "super::team::Entity",
+        from = "Column::TeamId",
+        to = "super::team::Column::TeamId"
+    )]
+    Team,
+
+    /// 与用户的关联关系
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::UserId"
+    )]
+    User,
+}
+
+/// 组织关联实现
+impl Related<super::organization::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Organization.def()
+    }
+}
+
+/// 团队关联实现
+impl Related<super::team::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Team.def()
+    }
+}
+
+/// 用户关联实现
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,52 @@
+//! 个人信息脱敏映射实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 个人信息脱敏映射实体模型
+///
+/// 记录发送给 LLM 的占位符与原文的对应关系，只保存在本地，用于还原 LLM 的结果
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "pii_mappings")]
+pub struct Model {
+    /// 映射ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub mapping_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// 占位符，例如 [EMAIL_1]
+    pub placeholder: String,
+
+    /// 个人信息类别：EMAIL, PHONE, NAME 或自定义标签
+    pub label: String,
+
+    /// 原文
+    pub original: String,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 个人信息脱敏映射关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -67,6 +67,33 @@ pub struct Model {
     /// 自动化配置（JSON格式存储AutomationConfig）
     #[sea_orm(column_type = "Json")]
     pub automation_config: Option<JsonValue>,
+    
+    /// 所属组织ID
+    pub organization_id: Option<Uuid>,
+    
+    /// 工作空间磁盘配额（JSON格式存储WorkspaceQuota），为空表示不限制
+    #[sea_orm(column_type = "Json")]
+    pub workspace_quota: Option<JsonValue>,
+    
+    /// 个人信息脱敏配置（JSON格式存储PiiScrubbingConfig），为空表示不脱敏
+    #[sea_orm(column_type = "Json")]
+    pub pii_scrubbing: Option<JsonValue>,
+    
+    /// 紧急任务抢占策略（JSON格式存储PreemptionPolicy），为空表示不抢占
+    #[sea_orm(column_type = "Json")]
+    pub preemption_policy: Option<JsonValue>,
+    
+    /// SLA配置（JSON格式存储SlaPolicy），为空表示不监控
+    #[sea_orm(column_type = "Json")]
+    pub sla_policy: Option<JsonValue>,
+    
+    /// 代码归属规则（JSON格式存储CodeOwnership），为空表示未配置
+    #[sea_orm(column_type = "Json")]
+    pub code_ownership: Option<JsonValue>,
+    
+    /// 性能预算（JSON格式存储PerfBudget），为空表示不做性能回归检查
+    #[sea_orm(column_type = "Json")]
+    pub perf_budget: Option<JsonValue>,
 }
 
 /// 项目关联关系
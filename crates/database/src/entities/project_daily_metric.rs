@@ -0,0 +1,58 @@
+//! 项目每日指标汇总实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 项目每日指标汇总实体模型
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "project_daily_metrics")]
+pub struct Model {
+    /// 记录ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub metric_id: Uuid,
+
+    /// 统计日期（UTC，YYYY-MM-DD）
+    pub metric_date: String,
+
+    /// 项目ID
+    pub project_id: Uuid,
+
+    /// 当天完成的任务数
+    pub tasks_completed: i32,
+
+    /// 当天失败的任务数
+    pub tasks_failed: i32,
+
+    /// 当天消耗的 LLM token 数
+    pub total_tokens: i64,
+
+    /// 当天Agent工作的费用
+    pub total_cost: f64,
+
+    /// 当天执行日志中的错误数
+    pub error_count: i32,
+
+    /// 汇总时间
+    pub computed_at: DateTimeWithTimeZone,
+}
+
+/// 项目每日指标汇总关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
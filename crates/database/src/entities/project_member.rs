@@ -0,0 +1,70 @@
+//! 项目成员实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 项目成员实体模型
+///
+/// 记录用户在项目中的角色（owner / maintainer / contributor / viewer）
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "project_members")]
+pub struct Model {
+    /// 成员记录ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub member_id: Uuid,
+
+    /// 项目ID
+    pub project_id: Uuid,
+
+    /// 用户ID
+    pub user_id: Uuid,
+
+    /// 成员角色
+    pub role: String,
+
+    /// 邀请者用户ID
+    pub invited_by: Option<Uuid>,
+
+    /// 加入时间
+    pub joined_at: DateTimeWithTimeZone,
+
+    /// 更新时间
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// 项目成员关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+
+    /// 与用户的关联关系
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::UserId"
+    )]
+    User,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+/// 用户关联实现
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,44 @@
+//! 项目数据库文件实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 拆分到独立数据库文件的项目，登记在目录数据库中
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "project_partitions")]
+pub struct Model {
+    /// 项目ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub project_id: Uuid,
+
+    /// 数据库文件名，相对于拆分目录
+    pub file_name: String,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 最近一次打开的时间
+    pub last_attached_at: Option<DateTimeWithTimeZone>,
+}
+
+/// 项目数据库文件关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId",
+        on_delete = "Cascade"
+    )]
+    Project,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
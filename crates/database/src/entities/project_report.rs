@@ -0,0 +1,68 @@
+//! 项目报告实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 项目报告实体模型
+///
+/// 渲染后的报告内容按校验和存放在工件存储中，这里只记录元数据和结构化摘要
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "project_reports")]
+pub struct Model {
+    /// 报告ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub report_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// 生成报告的计划ID，手动生成时为空
+    pub schedule_id: Option<Uuid>,
+
+    /// 报告标题
+    pub title: String,
+
+    /// 报告格式：markdown, html
+    pub format: String,
+
+    /// 统计周期开始时间
+    pub period_start: DateTimeWithTimeZone,
+
+    /// 统计周期结束时间
+    pub period_end: DateTimeWithTimeZone,
+
+    /// 报告内容的 SHA-256 校验和
+    pub checksum: String,
+
+    /// 报告内容大小（字节）
+    pub size_bytes: i64,
+
+    /// 结构化的报告数据（JSON格式）
+    #[sea_orm(column_type = "Json")]
+    pub summary: JsonValue,
+
+    /// 生成时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 项目报告关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
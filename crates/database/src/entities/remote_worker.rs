@@ -0,0 +1,65 @@
+//! 远程工作进程实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 远程工作进程实体模型
+///
+/// 记录通过工作进程协议注册的远程执行进程，租约对应的执行会话在 `execution_config`
+/// 中记录 `remote_worker_id`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "remote_workers")]
+pub struct Model {
+    /// 工作进程ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub worker_id: Uuid,
+
+    /// 工作进程名称
+    pub name: String,
+
+    /// 托管的Agent ID列表
+    #[sea_orm(column_type = "Json")]
+    pub agent_ids: JsonValue,
+
+    /// 声明的能力列表
+    #[sea_orm(column_type = "Json")]
+    pub capabilities: JsonValue,
+
+    /// 同时执行的最大任务数
+    pub max_concurrent_tasks: i32,
+
+    /// 注册时使用的协议版本
+    pub protocol_version: i32,
+
+    /// 状态：online, offline
+    pub status: String,
+
+    /// 最后一次心跳时间
+    pub last_heartbeat_at: DateTimeWithTimeZone,
+
+    /// 注册时间
+    pub registered_at: DateTimeWithTimeZone,
+
+    /// 最后更新时间
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+impl Model {
+    /// 解析托管的Agent ID列表
+    pub fn agent_id_list(&self) -> Vec<Uuid> {
+        serde_json::from_value(self.agent_ids.clone()).unwrap_or_default()
+    }
+
+    /// 解析声明的能力列表
+    pub fn capability_list(&self) -> Vec<String> {
+        serde_json::from_value(self.capabilities.clone()).unwrap_or_default()
+    }
+}
+
+/// 远程工作进程关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
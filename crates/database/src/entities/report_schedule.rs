@@ -0,0 +1,77 @@
+//! 报告计划实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 报告计划实体模型
+///
+/// 按 cron 表达式定期生成项目周报，并投递到配置的通知渠道
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "report_schedules")]
+pub struct Model {
+    /// 计划ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub schedule_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// 计划名称
+    pub name: String,
+
+    /// cron 表达式（秒 分 时 日 月 周），例如每周一早上九点为 `0 0 9 * * Mon`
+    pub cron_expression: String,
+
+    /// 报告格式：markdown, html
+    pub format: String,
+
+    /// 自定义模板，为空时使用内置模板
+    pub template: Option<String>,
+
+    /// 通知渠道列表（JSON格式）
+    #[sea_orm(column_type = "Json")]
+    pub channels: JsonValue,
+
+    /// 每千 token 的费用，为空时报告只统计 token 用量
+    pub cost_per_1k_tokens: Option<f64>,
+
+    /// 是否启用
+    pub enabled: bool,
+
+    /// 最近一次生成时间
+    pub last_run_at: Option<DateTimeWithTimeZone>,
+
+    /// 下一次生成时间
+    pub next_run_at: DateTimeWithTimeZone,
+
+    /// 创建者
+    pub created_by: Uuid,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 更新时间
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// 报告计划关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
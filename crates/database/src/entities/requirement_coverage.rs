@@ -0,0 +1,70 @@
+//! 需求覆盖关系实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 需求覆盖关系实体模型
+///
+/// 记录任务由需求文档的哪个章节生成，用于需求到任务的追踪矩阵
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "requirement_coverage")]
+pub struct Model {
+    /// 覆盖关系ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub coverage_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// 需求文档ID
+    pub document_id: Uuid,
+
+    /// 章节标识（由章节标题生成）
+    pub section_id: String,
+
+    /// 建立关联时的章节标题
+    pub section_title: String,
+
+    /// 任务ID
+    pub task_id: Uuid,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 需求覆盖关系关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与需求文档的关联关系
+    #[sea_orm(
+        belongs_to = "super::requirement_document::Entity",
+        from = "Column::DocumentId",
+        to = "super::requirement_document::Column::DocumentId"
+    )]
+    RequirementDocument,
+
+    /// 与任务的关联关系
+    #[sea_orm(
+        belongs_to = "super::task::Entity",
+        from = "Column::TaskId",
+        to = "super::task::Column::TaskId"
+    )]
+    Task,
+}
+
+/// 需求文档关联实现
+impl Related<super::requirement_document::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RequirementDocument.def()
+    }
+}
+
+/// 任务关联实现
+impl Related<super::task::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
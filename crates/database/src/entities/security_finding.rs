@@ -0,0 +1,102 @@
+//! 安全扫描发现实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 安全扫描发现实体模型
+///
+/// 每条记录对应执行会话中依赖漏洞扫描或密钥扫描报告的一个问题，严重程度已映射到 `IssueSeverity`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "security_findings")]
+pub struct Model {
+    /// 发现ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub finding_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// 执行会话ID
+    pub execution_session_id: Uuid,
+
+    /// 扫描器：cargo_audit, npm_audit, secrets
+    pub scanner: String,
+
+    /// 问题类别：vulnerability, secret
+    pub category: String,
+
+    /// 严重程度：info, minor, moderate, major, critical, blocker
+    pub severity: String,
+
+    /// 问题标题
+    pub title: String,
+
+    /// 安全公告编号（RUSTSEC、GHSA、CVE）或密钥规则标识
+    pub advisory_id: Option<String>,
+
+    /// 存在漏洞的依赖包
+    pub package_name: Option<String>,
+
+    /// 存在漏洞的依赖版本
+    pub package_version: Option<String>,
+
+    /// 修复版本（JSON数组）
+    pub fixed_versions: Json,
+
+    /// 相对工作空间的文件路径
+    pub file_path: Option<String>,
+
+    /// 行号
+    pub line: Option<i32>,
+
+    /// 自动创建的修复任务ID
+    pub remediation_task_id: Option<Uuid>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 安全扫描发现关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+
+    /// 与执行会话的关联关系
+    #[sea_orm(
+        belongs_to = "super::execution_session::Entity",
+        from = "Column::ExecutionSessionId",
+        to = "super::execution_session::Column::SessionId"
+    )]
+    ExecutionSession,
+
+    /// 与修复任务的关联关系
+    #[sea_orm(
+        belongs_to = "super::task::Entity",
+        from = "Column::RemediationTaskId",
+        to = "super::task::Column::TaskId"
+    )]
+    RemediationTask,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+/// 执行会话关联实现
+impl Related<super::execution_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExecutionSession.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,80 @@
+//! SLA违约记录实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// SLA违约记录实体模型
+///
+/// 同一对象的同一类SLA最多只有一条未解决的记录，违约升级时原地更新严重程度，
+/// 条件不再满足时记录解决时间
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "sla_breaches")]
+pub struct Model {
+    /// 违约记录ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub breach_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// SLA类型：pending_time, execution_time, milestone_deadline
+    pub sla_type: String,
+
+    /// 违约对象类型：task, milestone
+    pub subject_type: String,
+
+    /// 违约对象ID（任务ID或里程碑ID）
+    pub subject_id: String,
+
+    /// 违约对象名称
+    pub subject_name: String,
+
+    /// 严重程度：warning, critical
+    pub severity: String,
+
+    /// SLA阈值（分钟）
+    pub threshold_minutes: i64,
+
+    /// 实际耗时（分钟），里程碑为距截止日期的剩余时间
+    pub actual_minutes: i64,
+
+    /// 里程碑截止日期
+    pub deadline: Option<DateTimeWithTimeZone>,
+
+    /// 首次发现时间
+    pub detected_at: DateTimeWithTimeZone,
+
+    /// 最后评估时间
+    pub updated_at: DateTimeWithTimeZone,
+
+    /// 解决时间，为空表示仍在违约
+    pub resolved_at: Option<DateTimeWithTimeZone>,
+}
+
+impl Model {
+    /// 是否仍在违约
+    pub fn is_open(&self) -> bool {
+        self.resolved_at.is_none()
+    }
+}
+
+/// SLA违约记录关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -78,6 +78,15 @@ pub struct Model {
     /// 执行结果（JSON格式存储TaskResult）
     #[sea_orm(column_type = "Json")]
     pub execution_result: Option<JsonValue>,
+    
+    /// 租约持有者（调度器或工作进程标识）
+    pub lease_owner: Option<String>,
+    
+    /// 租约到期时间，过期后租约可以被其他持有者获取或被回收
+    pub lease_expires_at: Option<DateTimeWithTimeZone>,
+    
+    /// 工作范围：Agent 只能修改该子目录（相对工作空间根目录）下的文件，为空表示整个仓库
+    pub scope_path: Option<String>,
 }
 
 /// 任务关联关系
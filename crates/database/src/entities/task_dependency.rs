@@ -21,6 +21,12 @@ pub struct Model {
     /// 依赖类型
     pub dependency_type: String,
     
+    /// 是否为跨项目依赖（前置任务属于其他项目）
+    pub is_cross_project: bool,
+    
+    /// 创建依赖的用户ID，跨项目依赖必填
+    pub created_by: Option<Uuid>,
+    
     /// 创建时间
     pub created_at: DateTimeWithTimeZone,
 }
@@ -122,6 +128,8 @@ impl Model {
             parent_task_id,
             child_task_id,
             dependency_type: dependency_type.to_string(),
+            is_cross_project: false,
+            created_by: None,
             created_at: now,
         }
     }
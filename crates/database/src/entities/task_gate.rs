@@ -0,0 +1,75 @@
+//! 任务人工检查点实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 任务人工检查点实体模型
+///
+/// 任务执行到检查点（例如设计完成后、执行数据迁移前）时暂停，等待人工批准或驳回
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "task_gates")]
+pub struct Model {
+    /// 检查点ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub gate_id: Uuid,
+
+    /// 任务ID
+    pub task_id: Uuid,
+
+    /// 检查点名称，同一任务内唯一
+    pub name: String,
+
+    /// 需要人工确认的内容
+    pub description: Option<String>,
+
+    /// 检查点在任务流程中的顺序
+    pub sequence: i32,
+
+    /// 检查点状态：pending, waiting, approved, rejected
+    pub status: String,
+
+    /// 进入等待时任务的状态，决策后恢复
+    pub resume_status: Option<String>,
+
+    /// 本次等待开始的时间
+    pub requested_at: Option<DateTimeWithTimeZone>,
+
+    /// 最近一次决策的时间
+    pub decided_at: Option<DateTimeWithTimeZone>,
+
+    /// 最近一次决策的用户
+    pub decided_by: Option<Uuid>,
+
+    /// 最近一次决策的意见
+    pub comment: Option<String>,
+
+    /// 已结束的等待累计时长（秒），不含当前正在进行的等待
+    pub waiting_seconds: i64,
+
+    /// 进入等待的次数，被驳回后重新提交会增加
+    pub request_count: i32,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 任务人工检查点关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与任务的关联关系
+    #[sea_orm(
+        belongs_to = "super::task::Entity",
+        from = "Column::TaskId",
+        to = "super::task::Column::TaskId"
+    )]
+    Task,
+}
+
+impl Related<super::task::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
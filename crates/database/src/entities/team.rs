@@ -0,0 +1,52 @@
+//! 团队实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 团队实体模型
+///
+/// 组织内的成员分组
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "teams")]
+pub struct Model {
+    /// 团队ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub team_id: Uuid,
+
+    /// 所属组织ID
+    pub organization_id: Uuid,
+
+    /// 团队名称
+    pub name: String,
+
+    /// 团队描述
+    pub description: Option<String>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 更新时间
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// 团队关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与组织的关联关系
+    #[sea_orm(
+        belongs_to = "super::organization::Entity",
+        from = "Column::OrganizationId",
+        to = "super::organization::Column::OrganizationId"
+    )]
+    Organization,
+}
+
+/// 组织关联实现
+impl Related<super::organization::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Organization.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
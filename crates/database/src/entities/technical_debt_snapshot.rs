@@ -0,0 +1,65 @@
+//! 技术债务快照实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 技术债务快照实体模型
+///
+/// 每次扫描项目工作空间后记录一份，用于跟踪债务趋势
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "technical_debt_snapshots")]
+pub struct Model {
+    /// 快照ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub snapshot_id: Uuid,
+
+    /// 项目ID
+    pub project_id: Uuid,
+
+    /// 扫描时的提交哈希
+    pub commit_hash: Option<String>,
+
+    /// 预估偿还债务所需时间（小时）
+    pub estimated_hours: i32,
+
+    /// 严重程度分布（JSON存储，严重程度 -> 数量）
+    #[sea_orm(column_type = "Json")]
+    pub severity_distribution: JsonValue,
+
+    /// 主要债务类型（JSON存储）
+    #[sea_orm(column_type = "Json")]
+    pub main_debt_types: JsonValue,
+
+    /// 检测发现明细（JSON存储）
+    #[sea_orm(column_type = "Json")]
+    pub findings: JsonValue,
+
+    /// 相比上一次快照的趋势
+    pub trend: String,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// 技术债务快照关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
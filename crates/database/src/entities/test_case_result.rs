@@ -0,0 +1,73 @@
+//! 测试用例结果实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 测试用例结果实体模型
+///
+/// 每条记录对应一次执行会话中一个测试用例的结果，跨会话累积后用于识别不稳定的测试
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "test_case_results")]
+pub struct Model {
+    /// 结果ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub result_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// 产生该结果的执行会话ID
+    pub execution_session_id: Uuid,
+
+    /// 测试用例的完整名称
+    pub test_name: String,
+
+    /// 测试结果：passed, failed, skipped
+    pub outcome: String,
+
+    /// 运行耗时（毫秒）
+    pub duration_ms: Option<i64>,
+
+    /// 被测试的提交
+    pub commit_sha: Option<String>,
+
+    /// 记录时间
+    pub recorded_at: DateTimeWithTimeZone,
+}
+
+/// 测试用例结果关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+
+    /// 与执行会话的关联关系
+    #[sea_orm(
+        belongs_to = "super::execution_session::Entity",
+        from = "Column::ExecutionSessionId",
+        to = "super::execution_session::Column::SessionId"
+    )]
+    ExecutionSession,
+}
+
+/// 项目关联实现
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+/// 执行会话关联实现
+impl Related<super::execution_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExecutionSession.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
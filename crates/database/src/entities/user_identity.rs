@@ -0,0 +1,69 @@
+//! 外部身份实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 外部身份实体模型
+///
+/// 记录本地用户关联的 OIDC / OAuth 身份，`(provider, subject)` 唯一；
+/// 身份提供方的刷新令牌用于续期时确认外部账号仍然有效
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "user_identities")]
+pub struct Model {
+    /// 身份ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub identity_id: Uuid,
+
+    /// 关联的本地用户ID
+    pub user_id: Uuid,
+
+    /// 身份提供方标识（设置中的提供方ID）
+    pub provider: String,
+
+    /// 身份提供方中的用户标识（OIDC 的 sub，GitHub 的用户ID）
+    pub subject: String,
+
+    /// 身份提供方返回的邮箱
+    pub email: Option<String>,
+
+    /// 身份提供方返回的显示名称
+    pub display_name: Option<String>,
+
+    /// 身份提供方的刷新令牌
+    #[serde(skip_serializing)]
+    pub refresh_token: Option<String>,
+
+    /// 身份提供方访问令牌的过期时间
+    pub token_expires_at: Option<DateTimeWithTimeZone>,
+
+    /// 最近一次通过该身份登录的时间
+    pub last_login_at: Option<DateTimeWithTimeZone>,
+
+    /// 关联时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 更新时间
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// 外部身份关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与用户的关联关系
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::UserId"
+    )]
+    User,
+}
+
+/// 用户关联实现
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
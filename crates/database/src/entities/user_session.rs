@@ -40,6 +40,22 @@ pub struct Model {
     #[sea_orm(nullable)]
     pub user_agent: Option<String>,
     
+    /// 设备名称
+    #[sea_orm(nullable)]
+    pub device_name: Option<String>,
+    
+    /// 刷新令牌过期时间（为空时与会话过期时间一致）
+    #[sea_orm(nullable)]
+    pub refresh_expires_at: Option<DateTimeWithTimeZone>,
+    
+    /// 上一个刷新令牌，用于检测已轮换令牌的重放
+    #[sea_orm(nullable)]
+    pub previous_refresh_token: Option<String>,
+    
+    /// 撤销时间
+    #[sea_orm(nullable)]
+    pub revoked_at: Option<DateTimeWithTimeZone>,
+    
     /// 是否活跃
     pub is_active: bool,
 }
@@ -76,4 +92,15 @@ impl Model {
     pub fn is_valid(&self) -> bool {
         self.is_active && !self.is_expired()
     }
+    
+    /// 检查刷新令牌是否已过期
+    pub fn is_refresh_expired(&self) -> bool {
+        let refresh_expires_at = self.refresh_expires_at.unwrap_or(self.expires_at);
+        chrono::Utc::now() > refresh_expires_at.naive_utc().and_utc()
+    }
+    
+    /// 检查会话是否可刷新（活跃且刷新令牌未过期）
+    pub fn can_refresh(&self) -> bool {
+        self.is_active && !self.is_refresh_expired()
+    }
 }
\ No newline at end of file
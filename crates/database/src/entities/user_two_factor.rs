@@ -0,0 +1,70 @@
+//! 两步验证实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 两步验证实体模型
+///
+/// 每个用户一行：TOTP 密钥、是否已启用、是否强制要求两步验证，以及备用恢复码的摘要。
+/// 尚未确认的登记只保存密钥，`enabled` 为假
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "user_two_factor")]
+pub struct Model {
+    /// 用户ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: Uuid,
+
+    /// Base32 编码的 TOTP 密钥，未登记时为空
+    #[serde(skip_serializing)]
+    pub secret: Option<String>,
+
+    /// 是否已确认登记并在登录时校验
+    pub enabled: bool,
+
+    /// 是否强制该用户使用两步验证（未登记时登录后需要先完成登记）
+    pub required: bool,
+
+    /// 备用恢复码的加盐摘要，使用后移除
+    #[serde(skip_serializing)]
+    pub backup_code_hashes: Json,
+
+    /// 最近一次通过校验的 TOTP 时间步，防止验证码重放
+    pub last_used_step: Option<i64>,
+
+    /// 连续校验失败次数，校验成功或锁定后清零
+    pub failed_attempts: i32,
+
+    /// 连续失败过多后锁定到该时间，锁定期间拒绝所有验证码
+    pub locked_until: Option<DateTimeWithTimeZone>,
+
+    /// 确认登记的时间
+    pub confirmed_at: Option<DateTimeWithTimeZone>,
+
+    /// 创建时间
+    pub created_at: DateTimeWithTimeZone,
+
+    /// 更新时间
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+/// 两步验证关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与用户的关联关系
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::UserId"
+    )]
+    User,
+}
+
+/// 用户关联实现
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,73 @@
+//! Webhook死信实体模型
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Webhook死信实体模型
+///
+/// 签名校验通过但无法映射为领域事件的载荷保存在这里，映射规则补充后可以重放
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook_dead_letters")]
+pub struct Model {
+    /// 死信ID - 主键
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub dead_letter_id: Uuid,
+
+    /// 所属项目ID
+    pub project_id: Uuid,
+
+    /// Webhook 来源：github, gitlab
+    pub source: String,
+
+    /// 来源的事件名称，例如 GitHub 的 X-GitHub-Event
+    pub event_name: Option<String>,
+
+    /// 来源的投递ID，例如 GitHub 的 X-GitHub-Delivery
+    pub delivery_id: Option<String>,
+
+    /// 原始载荷
+    pub payload: String,
+
+    /// 最近一次映射失败的原因
+    pub error: String,
+
+    /// 接收时间
+    pub received_at: DateTimeWithTimeZone,
+
+    /// 重放次数
+    pub replay_count: i32,
+
+    /// 最近一次重放时间
+    pub last_replayed_at: Option<DateTimeWithTimeZone>,
+
+    /// 重放成功或被丢弃的时间，为空表示仍待处理
+    pub resolved_at: Option<DateTimeWithTimeZone>,
+}
+
+impl Model {
+    /// 是否仍待处理
+    pub fn is_pending(&self) -> bool {
+        self.resolved_at.is_none()
+    }
+}
+
+/// Webhook死信关联关系
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// 与项目的关联关系
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::ProjectId"
+    )]
+    Project,
+}
+
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
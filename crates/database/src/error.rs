@@ -43,6 +43,33 @@ pub enum DatabaseError {
     #[error("业务逻辑错误: {message}")]
     BusinessLogic { message: String },
     
+    /// 工作空间超出磁盘配额
+    #[error("工作空间超出磁盘配额: 项目 {project_id} 已使用 {used_bytes} 字节，配额 {max_bytes} 字节")]
+    QuotaExceeded {
+        project_id: String,
+        used_bytes: u64,
+        max_bytes: u64,
+    },
+    
+    /// 任务仍有未验证的验收标准
+    #[error("任务 {task_id} 仍有未验证的验收标准: {}", .criteria.join("；"))]
+    UnverifiedAcceptanceCriteria {
+        task_id: String,
+        criteria: Vec<String>,
+    },
+    
+    /// 操作已被取消
+    #[error("操作已取消: {operation_id}")]
+    OperationCancelled { operation_id: String },
+    
+    /// 应用正在关闭，不再接受新工作
+    #[error("应用正在关闭，不再接受新的工作")]
+    ShuttingDown,
+    
+    /// 实体的列值无法转换为协议类型
+    #[error("列解码失败: {entity}.{column}: {message}")]
+    ColumnDecode { entity: String, column: String, message: String },
+    
     /// IO错误
     #[error("IO错误: {0}")]
     Io(#[from] std::io::Error),
@@ -55,6 +82,11 @@ pub enum DatabaseError {
     #[error("UUID错误: {0}")]
     Uuid(#[from] uuid::Error),
     
+    /// 测试注入的故障
+    #[cfg(feature = "fault_injection")]
+    #[error("注入故障 {point}: {message}")]
+    InjectedFault { point: String, message: String },
+    
     /// 其他错误
     #[error("其他错误: {0}")]
     Other(#[from] anyhow::Error),
@@ -90,6 +122,15 @@ impl DatabaseError {
         }
     }
     
+    /// 创建列解码错误
+    pub fn column_decode<E: ToString, C: ToString, M: ToString>(entity: E, column: C, message: M) -> Self {
+        Self::ColumnDecode {
+            entity: entity.to_string(),
+            column: column.to_string(),
+            message: message.to_string(),
+        }
+    }
+    
     /// 判断是否为连接相关错误
     pub fn is_connection_error(&self) -> bool {
         matches!(self, DatabaseError::Connection(_) | DatabaseError::Database(_))
@@ -100,10 +141,54 @@ impl DatabaseError {
         matches!(self, DatabaseError::Validation { .. })
     }
     
+    /// 判断是否为冲突错误
+    pub fn is_conflict_error(&self) -> bool {
+        matches!(self, DatabaseError::Conflict { .. })
+    }
+    
     /// 判断是否为业务逻辑错误
     pub fn is_business_error(&self) -> bool {
         matches!(self, DatabaseError::BusinessLogic { .. })
     }
+    
+    /// 判断是否为超出配额错误
+    pub fn is_quota_exceeded(&self) -> bool {
+        matches!(self, DatabaseError::QuotaExceeded { .. })
+    }
+    
+    /// 判断是否为验收标准未验证错误
+    pub fn is_unverified_acceptance_criteria(&self) -> bool {
+        matches!(self, DatabaseError::UnverifiedAcceptanceCriteria { .. })
+    }
+    
+    /// 判断是否为操作取消错误
+    pub fn is_operation_cancelled(&self) -> bool {
+        matches!(self, DatabaseError::OperationCancelled { .. })
+    }
+    
+    /// 判断是否为应用关闭错误
+    pub fn is_shutting_down(&self) -> bool {
+        matches!(self, DatabaseError::ShuttingDown)
+    }
+    
+    /// 判断是否为列解码错误
+    pub fn is_column_decode_error(&self) -> bool {
+        matches!(self, DatabaseError::ColumnDecode { .. })
+    }
+    
+    /// 判断是否为 SQLite 忙错误（SQLITE_BUSY / SQLITE_LOCKED），稍后重试可能成功
+    pub fn is_busy(&self) -> bool {
+        let DatabaseError::Database(sea_orm::DbErr::Conn(err) | sea_orm::DbErr::Exec(err) | sea_orm::DbErr::Query(err)) = self else {
+            return false;
+        };
+        let sea_orm::RuntimeErr::SqlxError(sea_orm::sqlx::Error::Database(err)) = err else {
+            return false;
+        };
+        // 扩展错误码的低 8 位是主错误码：5 = SQLITE_BUSY，6 = SQLITE_LOCKED
+        err.code()
+            .and_then(|code| code.parse::<i32>().ok())
+            .is_some_and(|code| matches!(code & 0xff, 5 | 6))
+    }
 }
 
 #[cfg(test)]
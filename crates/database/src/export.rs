@@ -0,0 +1,275 @@
+//! 大结果集的流式导出
+//!
+//! 仓储的 `stream_*` 方法按 `(created_at, id)` 键集分页，每次只读取一批行，以异步 `Stream` 逐行产出；
+//! [`ExportWriter`] 把每行编码为 NDJSON 或 CSV，每写满一批刷新到底层输出。导出过程的内存占用
+//! 只与批次大小有关，与导出的总行数无关。
+
+use futures::{stream, Stream, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::future::Future;
+use std::str::FromStr;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use uuid::Uuid;
+
+use crate::{
+    repository::{ExecutionLogRepository, TaskRepository},
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 每批读取的默认行数
+pub const DEFAULT_BATCH_SIZE: u64 = 500;
+
+/// 默认每写入多少行刷新一次
+pub const DEFAULT_FLUSH_EVERY: u64 = 500;
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// 每行一个 JSON 对象
+    Ndjson,
+    /// 逗号分隔，首行为表头
+    Csv,
+}
+
+impl ExportFormat {
+    /// 字符串表示
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Csv => "csv",
+        }
+    }
+
+    /// 文件扩展名
+    pub fn extension(&self) -> &'static str {
+        self.as_str()
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "ndjson" => Ok(ExportFormat::Ndjson),
+            "csv" => Ok(ExportFormat::Csv),
+            _ => Err(format!("未知的导出格式: {}", s)),
+        }
+    }
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportSummary {
+    /// 写入的数据行数（不含 CSV 表头）
+    pub rows: u64,
+    /// 写入的字节数
+    pub bytes: u64,
+}
+
+/// 逐行写入的导出器
+///
+/// 编码后的行先写入内部缓冲，每满 `flush_every` 行写出并刷新一次。CSV 的列取自
+/// [`with_columns`](Self::with_columns)，未指定时取第一行的字段；嵌套的数组和对象按 JSON 文本写入单元格。
+pub struct ExportWriter<W> {
+    inner: W,
+    format: ExportFormat,
+    columns: Option<Vec<String>>,
+    flush_every: u64,
+    buffer: Vec<u8>,
+    pending: u64,
+    header_written: bool,
+    summary: ExportSummary,
+}
+
+impl<W: AsyncWrite + Unpin> ExportWriter<W> {
+    /// 创建导出器
+    pub fn new(inner: W, format: ExportFormat) -> Self {
+        Self {
+            inner,
+            format,
+            columns: None,
+            flush_every: DEFAULT_FLUSH_EVERY,
+            buffer: Vec::new(),
+            pending: 0,
+            header_written: false,
+            summary: ExportSummary::default(),
+        }
+    }
+
+    /// 指定 CSV 的列及顺序，NDJSON 忽略此设置
+    pub fn with_columns(mut self, columns: Vec<String>) -> Self {
+        self.columns = Some(columns);
+        self
+    }
+
+    /// 每写入多少行刷新一次
+    pub fn with_flush_every(mut self, rows: u64) -> Self {
+        self.flush_every = rows.max(1);
+        self
+    }
+
+    /// 已写入的行数和字节数
+    pub fn summary(&self) -> ExportSummary {
+        self.summary
+    }
+
+    /// 写入一行
+    pub async fn write_row<T: Serialize>(&mut self, row: &T) -> Result<()> {
+        let value = serde_json::to_value(row)?;
+        match self.format {
+            ExportFormat::Ndjson => {
+                serde_json::to_writer(&mut self.buffer, &value)?;
+                self.buffer.push(b'\n');
+            }
+            ExportFormat::Csv => {
+                let JsonValue::Object(fields) = value else {
+                    return Err(DatabaseError::validation("CSV 导出的每一行必须是对象"));
+                };
+                let columns = self
+                    .columns
+                    .get_or_insert_with(|| fields.keys().cloned().collect())
+                    .clone();
+                if !self.header_written {
+                    let header: Vec<String> = columns.iter().map(|column| csv_field(column)).collect();
+                    write_csv_line(&mut self.buffer, &header);
+                    self.header_written = true;
+                }
+                let cells: Vec<String> = columns
+                    .iter()
+                    .map(|column| csv_field(&csv_cell(fields.get(column))))
+                    .collect();
+                write_csv_line(&mut self.buffer, &cells);
+            }
+        }
+        self.summary.rows += 1;
+        self.pending += 1;
+        if self.pending >= self.flush_every {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// 写出缓冲并刷新底层输出
+    pub async fn flush(&mut self) -> Result<()> {
+        if !self.buffer.is_empty() {
+            self.inner.write_all(&self.buffer).await?;
+            self.summary.bytes += self.buffer.len() as u64;
+            self.buffer.clear();
+        }
+        self.pending = 0;
+        self.inner.flush().await?;
+        Ok(())
+    }
+
+    /// 完成导出，没有数据行的 CSV 在指定了列时只写表头
+    pub async fn finish(mut self) -> Result<ExportSummary> {
+        if self.format == ExportFormat::Csv && !self.header_written {
+            if let Some(columns) = &self.columns {
+                let header: Vec<String> = columns.iter().map(|column| csv_field(column)).collect();
+                write_csv_line(&mut self.buffer, &header);
+                self.header_written = true;
+            }
+        }
+        self.flush().await?;
+        self.inner.shutdown().await?;
+        Ok(self.summary)
+    }
+}
+
+/// 把行流全部写入导出器
+pub async fn write_stream<S, T, W>(rows: S, mut writer: ExportWriter<W>) -> Result<ExportSummary>
+where
+    S: Stream<Item = Result<T>>,
+    T: Serialize,
+    W: AsyncWrite + Unpin,
+{
+    futures::pin_mut!(rows);
+    while let Some(row) = rows.try_next().await? {
+        writer.write_row(&row).await?;
+    }
+    writer.finish().await
+}
+
+/// 流式导出项目的全部任务，按创建时间排序
+pub async fn export_project_tasks<W: AsyncWrite + Unpin>(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    format: ExportFormat,
+    out: W,
+) -> Result<ExportSummary> {
+    let rows = TaskRepository::new(db.clone()).stream_by_project(project_id, DEFAULT_BATCH_SIZE);
+    write_stream(rows, ExportWriter::new(out, format)).await
+}
+
+/// 流式导出项目所有执行会话的日志，按创建时间排序
+pub async fn export_project_logs<W: AsyncWrite + Unpin>(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    format: ExportFormat,
+    out: W,
+) -> Result<ExportSummary> {
+    let rows = ExecutionLogRepository::new(db.clone()).stream_by_project(project_id, DEFAULT_BATCH_SIZE);
+    write_stream(rows, ExportWriter::new(out, format)).await
+}
+
+/// 按键集分页逐批读取，把各批次展开为逐行的流
+///
+/// `fetch` 接收上一批最后一行的键（首批为 `None`）和批次大小；返回的行数少于批次大小时结束。
+pub(crate) fn keyset_batches<M, K, F, Fut>(
+    batch_size: u64,
+    key: fn(&M) -> K,
+    mut fetch: F,
+) -> impl Stream<Item = Result<M>>
+where
+    F: FnMut(Option<K>, u64) -> Fut,
+    Fut: Future<Output = Result<Vec<M>>>,
+{
+    let batch_size = batch_size.max(1);
+    stream::try_unfold(Some(None), move |state: Option<Option<K>>| {
+        let next = state.map(|after| fetch(after, batch_size));
+        async move {
+            let Some(next) = next else {
+                return Ok::<_, DatabaseError>(None);
+            };
+            let rows = next.await?;
+            let state = if (rows.len() as u64) < batch_size {
+                None
+            } else {
+                rows.last().map(|row| Some(key(row)))
+            };
+            Ok(Some((stream::iter(rows.into_iter().map(Ok::<M, DatabaseError>)), state)))
+        }
+    })
+    .try_flatten()
+}
+
+fn csv_cell(value: Option<&JsonValue>) -> String {
+    match value {
+        None | Some(JsonValue::Null) => String::new(),
+        Some(JsonValue::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// 含逗号、引号或换行的字段加引号，内部引号加倍
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_csv_line(buffer: &mut Vec<u8>, fields: &[String]) {
+    buffer.extend_from_slice(fields.join(",").as_bytes());
+    buffer.extend_from_slice(b"\r\n");
+}
@@ -0,0 +1,248 @@
+//! 故障注入
+//!
+//! 仅在启用 `fault_injection` feature 时编译，用于在集成测试中模拟LLM超时、
+//! Git推送失败、Agent崩溃等故障，验证调度和执行链路的重试与冲突处理。
+//!
+//! 仓储通过 `with_fault_injector` 挂载注入器，在故障点调用 [`FaultInjector::check`]；
+//! 规则可以按概率触发，也可以针对第N次调用或指定目标（会话、Agent等ID）触发。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use uuid::Uuid;
+
+use crate::{DatabaseError, Result};
+
+/// 故障点
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FaultPoint {
+    /// LLM调用超时
+    LlmTimeout,
+    /// Git推送失败
+    GitPushFailure,
+    /// Agent执行中崩溃
+    AgentCrash,
+}
+
+impl FaultPoint {
+    /// 故障点名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FaultPoint::LlmTimeout => "llm_timeout",
+            FaultPoint::GitPushFailure => "git_push_failure",
+            FaultPoint::AgentCrash => "agent_crash",
+        }
+    }
+
+    fn message(&self) -> &'static str {
+        match self {
+            FaultPoint::LlmTimeout => "LLM调用超时",
+            FaultPoint::GitPushFailure => "Git推送失败",
+            FaultPoint::AgentCrash => "Agent执行崩溃",
+        }
+    }
+}
+
+/// 触发条件
+#[derive(Debug, Clone, PartialEq)]
+pub enum FaultTrigger {
+    /// 每次调用都触发
+    Always,
+    /// 按概率触发（0.0-1.0），使用注入器的种子保证可复现
+    Probability(f64),
+    /// 仅在该故障点的第N次调用（从1开始）时触发
+    NthCall(u32),
+    /// 仅在目标ID匹配时触发
+    Target(Uuid),
+}
+
+/// 故障规则
+#[derive(Debug, Clone)]
+pub struct FaultRule {
+    /// 故障点
+    pub point: FaultPoint,
+    /// 触发条件
+    pub trigger: FaultTrigger,
+    /// 最多触发次数（None 表示不限）
+    pub max_fires: Option<u32>,
+}
+
+impl FaultRule {
+    /// 创建不限触发次数的规则
+    pub fn new(point: FaultPoint, trigger: FaultTrigger) -> Self {
+        Self {
+            point,
+            trigger,
+            max_fires: None,
+        }
+    }
+
+    /// 限制最多触发次数，便于验证“失败后重试成功”
+    pub fn times(mut self, max_fires: u32) -> Self {
+        self.max_fires = Some(max_fires);
+        self
+    }
+}
+
+#[derive(Debug)]
+struct InjectorState {
+    rules: Vec<(FaultRule, u32)>,
+    calls: HashMap<FaultPoint, u32>,
+    fired: HashMap<FaultPoint, u32>,
+    rng_state: u64,
+}
+
+/// 故障注入器，克隆后共享同一份规则和计数
+#[derive(Debug, Clone)]
+pub struct FaultInjector {
+    state: Arc<Mutex<InjectorState>>,
+}
+
+impl FaultInjector {
+    /// 使用指定随机种子创建注入器
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(InjectorState {
+                rules: Vec::new(),
+                calls: HashMap::new(),
+                fired: HashMap::new(),
+                // xorshift 的状态不能为 0
+                rng_state: seed.max(1),
+            })),
+        }
+    }
+
+    /// 添加规则
+    pub fn with_rule(self, rule: FaultRule) -> Self {
+        self.add_rule(rule);
+        self
+    }
+
+    /// 运行中追加规则
+    pub fn add_rule(&self, rule: FaultRule) {
+        self.lock().rules.push((rule, 0));
+    }
+
+    /// 清除所有规则和计数
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.rules.clear();
+        state.calls.clear();
+        state.fired.clear();
+    }
+
+    /// 在故障点检查是否需要注入故障，命中时返回 [`DatabaseError::InjectedFault`]
+    pub fn check(&self, point: FaultPoint, target: Option<Uuid>) -> Result<()> {
+        let mut state = self.lock();
+        let call = {
+            let calls = state.calls.entry(point).or_insert(0);
+            *calls += 1;
+            *calls
+        };
+
+        let mut rng_state = state.rng_state;
+        let fired = state.rules.iter_mut().any(|(rule, fires)| {
+            if rule.point != point || rule.max_fires.is_some_and(|max| *fires >= max) {
+                return false;
+            }
+            let hit = match &rule.trigger {
+                FaultTrigger::Always => true,
+                FaultTrigger::Probability(p) => next_unit(&mut rng_state) < *p,
+                FaultTrigger::NthCall(n) => call == *n,
+                FaultTrigger::Target(id) => target == Some(*id),
+            };
+            if hit {
+                *fires += 1;
+            }
+            hit
+        });
+        state.rng_state = rng_state;
+
+        if !fired {
+            return Ok(());
+        }
+        *state.fired.entry(point).or_insert(0) += 1;
+
+        let message = match target {
+            Some(id) => format!("{}（目标 {}）", point.message(), id),
+            None => point.message().to_string(),
+        };
+        tracing::warn!("注入故障 {}: {}", point.as_str(), message);
+        Err(DatabaseError::InjectedFault { point: point.as_str().to_string(), message })
+    }
+
+    /// 故障点被检查的次数
+    pub fn calls(&self, point: FaultPoint) -> u32 {
+        self.lock().calls.get(&point).copied().unwrap_or(0)
+    }
+
+    /// 故障点实际触发的次数
+    pub fn fired(&self, point: FaultPoint) -> u32 {
+        self.lock().fired.get(&point).copied().unwrap_or(0)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, InjectorState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// xorshift64，返回 [0, 1) 区间的伪随机数
+fn next_unit(state: &mut u64) -> f64 {
+    let mut x = *state;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    *state = x;
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// 可选注入器的检查辅助，未挂载注入器时直接通过
+pub(crate) fn check(injector: &Option<FaultInjector>, point: FaultPoint, target: Option<Uuid>) -> Result<()> {
+    match injector {
+        Some(injector) => injector.check(point, target),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triggers() {
+        let target = Uuid::new_v4();
+        let injector = FaultInjector::new(7)
+            .with_rule(FaultRule::new(FaultPoint::LlmTimeout, FaultTrigger::NthCall(2)))
+            .with_rule(FaultRule::new(FaultPoint::AgentCrash, FaultTrigger::Target(target)))
+            .with_rule(FaultRule::new(FaultPoint::GitPushFailure, FaultTrigger::Always).times(1));
+
+        assert!(injector.check(FaultPoint::LlmTimeout, None).is_ok());
+        assert!(injector.check(FaultPoint::LlmTimeout, None).is_err());
+        assert!(injector.check(FaultPoint::LlmTimeout, None).is_ok());
+
+        assert!(injector.check(FaultPoint::AgentCrash, Some(Uuid::new_v4())).is_ok());
+        assert!(injector.check(FaultPoint::AgentCrash, Some(target)).is_err());
+
+        assert!(injector.check(FaultPoint::GitPushFailure, None).is_err());
+        assert!(injector.check(FaultPoint::GitPushFailure, None).is_ok());
+
+        assert_eq!(injector.calls(FaultPoint::LlmTimeout), 3);
+        assert_eq!(injector.fired(FaultPoint::LlmTimeout), 1);
+    }
+
+    #[test]
+    fn test_probability_is_reproducible() {
+        let run = |seed| {
+            let injector = FaultInjector::new(seed)
+                .with_rule(FaultRule::new(FaultPoint::LlmTimeout, FaultTrigger::Probability(0.3)));
+            (0..200)
+                .map(|_| injector.check(FaultPoint::LlmTimeout, None).is_err())
+                .collect::<Vec<_>>()
+        };
+
+        let first = run(42);
+        assert_eq!(first, run(42));
+        let fired = first.iter().filter(|f| **f).count();
+        assert!((30..90).contains(&fired), "触发次数应接近30%: {fired}");
+    }
+}
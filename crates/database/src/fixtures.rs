@@ -0,0 +1,854 @@
+//! 测试与演示数据
+//!
+//! 仅在启用 `fixtures` feature 时编译。提供用户、项目、Agent、任务的构建器，
+//! 以及 [`seed_demo_data`]：写入一套固定、逼真的数据集（3个项目、10个Agent、100个任务，
+//! 附带冲突、执行会话和执行日志），供集成测试和桌面端演示模式使用。
+//!
+//! 数据按下标确定性生成，不依赖随机数，同一份代码每次写入的数据结构完全一致。
+//! 演示数据带有 [`DEMO_MARKER`] 标记（项目上下文、Agent配置、冲突关联实体、事件数据），
+//! 重启后仍可通过 [`find_demo_data`] 找回并整体删除。
+
+use serde_json::{json, Value as JsonValue};
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        agent::{self, AgentStatus},
+        conflict::{self, ConflictSeverity, ConflictStatus, ConflictType},
+        domain_event, execution_log::{self, EventType}, execution_session, project, task, user,
+    },
+    repository::{
+        agent_repository::{AgentStatistics, CreateAgentData},
+        conflict_repository::CreateConflictData,
+        execution_log_repository::CreateExecutionLogData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        user_repository::CreateUserData,
+        AgentRepository, ConflictRepository, ExecutionLogRepository, ExecutionSessionRepository,
+        ProjectRepository, UserRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+
+/// 演示数据中的项目数量
+pub const DEMO_PROJECT_COUNT: usize = 3;
+
+/// 演示数据中的Agent数量
+pub const DEMO_AGENT_COUNT: usize = 10;
+
+/// 演示数据中的任务数量
+pub const DEMO_TASK_COUNT: usize = 100;
+
+/// 演示用户名
+pub const DEMO_USERNAME: &str = "demo";
+
+/// 演示数据标记，写在各实体的JSON字段中
+pub const DEMO_MARKER: &str = "demo_workspace";
+
+/// JSON字段是否带有演示数据标记
+pub fn is_demo_marked(value: &JsonValue) -> bool {
+    value.get(DEMO_MARKER).and_then(JsonValue::as_bool).unwrap_or(false)
+}
+
+// ============================================================================
+// 构建器
+// ============================================================================
+
+/// 用户构建器
+#[derive(Debug, Clone)]
+pub struct UserBuilder {
+    username: String,
+    email: Option<String>,
+    password_hash: String,
+    settings: Option<JsonValue>,
+}
+
+impl UserBuilder {
+    /// 以用户名创建构建器，邮箱默认为 `<username>@example.com`
+    pub fn new(username: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            email: None,
+            password_hash: "fixture_password_hash".to_string(),
+            settings: None,
+        }
+    }
+
+    /// 设置邮箱
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.email = Some(email.into());
+        self
+    }
+
+    /// 设置用户配置
+    pub fn settings(mut self, settings: JsonValue) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// 写入数据库
+    pub async fn insert(self, db: &DatabaseConnection) -> Result<user::Model> {
+        let email = self.email.unwrap_or_else(|| format!("{}@example.com", self.username));
+        UserRepository::new(db.clone())
+            .create(CreateUserData {
+                username: self.username,
+                email,
+                password_hash: self.password_hash,
+                profile_data: None,
+                settings: self.settings,
+            })
+            .await
+    }
+}
+
+/// 项目构建器
+#[derive(Debug, Clone)]
+pub struct ProjectBuilder {
+    user_id: Uuid,
+    name: String,
+    description: Option<String>,
+    repository_url: Option<String>,
+    workspace_path: Option<String>,
+    technology_stack: Option<JsonValue>,
+    project_context: Option<JsonValue>,
+}
+
+impl ProjectBuilder {
+    /// 创建构建器，仓库地址和工作区路径默认由项目名派生
+    pub fn new(user_id: Uuid, name: impl Into<String>) -> Self {
+        Self {
+            user_id,
+            name: name.into(),
+            description: None,
+            repository_url: None,
+            workspace_path: None,
+            technology_stack: None,
+            project_context: None,
+        }
+    }
+
+    /// 设置描述
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// 设置仓库地址
+    pub fn repository_url(mut self, url: impl Into<String>) -> Self {
+        self.repository_url = Some(url.into());
+        self
+    }
+
+    /// 设置工作区路径
+    pub fn workspace_path(mut self, path: impl Into<String>) -> Self {
+        self.workspace_path = Some(path.into());
+        self
+    }
+
+    /// 设置技术栈
+    pub fn technology_stack(mut self, stack: &[&str]) -> Self {
+        self.technology_stack = Some(json!(stack));
+        self
+    }
+
+    /// 设置项目上下文
+    pub fn project_context(mut self, context: JsonValue) -> Self {
+        self.project_context = Some(context);
+        self
+    }
+
+    /// 写入数据库
+    pub async fn insert(self, db: &DatabaseConnection) -> Result<project::Model> {
+        let slug = Uuid::new_v4().simple().to_string();
+        let project = ProjectRepository::new(db.clone())
+            .create(CreateProjectData {
+                user_id: self.user_id,
+                name: self.name,
+                description: self.description,
+                repository_url: self
+                    .repository_url
+                    .unwrap_or_else(|| format!("https://github.com/fixtures/{}.git", &slug[..8])),
+                workspace_path: self
+                    .workspace_path
+                    .unwrap_or_else(|| format!("/workspace/fixtures/{}", &slug[..8])),
+            })
+            .await?;
+
+        if self.technology_stack.is_none() && self.project_context.is_none() {
+            return Ok(project);
+        }
+        let mut active: project::ActiveModel = project.into();
+        if self.technology_stack.is_some() {
+            active.technology_stack = Set(self.technology_stack);
+        }
+        if self.project_context.is_some() {
+            active.project_context = Set(self.project_context);
+        }
+        active.update(db).await.map_err(DatabaseError::from)
+    }
+}
+
+/// Agent构建器
+#[derive(Debug, Clone)]
+pub struct AgentBuilder {
+    user_id: Uuid,
+    name: String,
+    description: Option<String>,
+    prompt_template: String,
+    capabilities: Vec<String>,
+    config: JsonValue,
+    statistics: Option<AgentStatistics>,
+}
+
+impl AgentBuilder {
+    /// 创建构建器，默认具备后端开发能力
+    pub fn new(user_id: Uuid, name: impl Into<String>) -> Self {
+        Self {
+            user_id,
+            name: name.into(),
+            description: None,
+            prompt_template: "你是一名经验丰富的软件工程师".to_string(),
+            capabilities: vec!["BackendDevelopment".to_string()],
+            config: json!({ "max_concurrent_tasks": 1 }),
+            statistics: None,
+        }
+    }
+
+    /// 设置描述
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// 设置提示词模板
+    pub fn prompt_template(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt_template = prompt.into();
+        self
+    }
+
+    /// 设置能力列表
+    pub fn capabilities(mut self, capabilities: &[&str]) -> Self {
+        self.capabilities = capabilities.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    /// 设置配置
+    pub fn config(mut self, config: JsonValue) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 设置历史统计
+    pub fn statistics(mut self, statistics: AgentStatistics) -> Self {
+        self.statistics = Some(statistics);
+        self
+    }
+
+    /// 写入数据库
+    pub async fn insert(self, db: &DatabaseConnection) -> Result<agent::Model> {
+        let repo = AgentRepository::new(db.clone());
+        let agent = repo
+            .create(CreateAgentData {
+                user_id: self.user_id,
+                name: self.name,
+                description: self.description,
+                prompt_template: self.prompt_template,
+                capabilities: json!(self.capabilities),
+                config: self.config,
+                git_config: None,
+            })
+            .await?;
+
+        match self.statistics {
+            Some(statistics) => repo.update_statistics(agent.agent_id, statistics).await,
+            None => Ok(agent),
+        }
+    }
+}
+
+/// 任务构建器
+#[derive(Debug, Clone)]
+pub struct TaskBuilder {
+    project_id: Uuid,
+    title: String,
+    description: String,
+    task_type: String,
+    priority: String,
+    status: String,
+    estimated_hours: Option<i32>,
+    required_capabilities: Vec<String>,
+    assigned_agent_id: Option<Uuid>,
+    parent_task_id: Option<Uuid>,
+}
+
+impl TaskBuilder {
+    /// 创建构建器，默认为中等优先级的待处理开发任务
+    pub fn new(project_id: Uuid, title: impl Into<String>) -> Self {
+        let title = title.into();
+        Self {
+            project_id,
+            description: format!("{}（测试数据）", title),
+            title,
+            task_type: "development".to_string(),
+            priority: "medium".to_string(),
+            status: "pending".to_string(),
+            estimated_hours: None,
+            required_capabilities: Vec::new(),
+            assigned_agent_id: None,
+            parent_task_id: None,
+        }
+    }
+
+    /// 设置描述
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// 设置任务类型
+    pub fn task_type(mut self, task_type: impl Into<String>) -> Self {
+        self.task_type = task_type.into();
+        self
+    }
+
+    /// 设置优先级
+    pub fn priority(mut self, priority: impl Into<String>) -> Self {
+        self.priority = priority.into();
+        self
+    }
+
+    /// 设置状态（in_progress/completed/failed 会同时填充对应时间戳）
+    pub fn status(mut self, status: impl Into<String>) -> Self {
+        self.status = status.into();
+        self
+    }
+
+    /// 设置预估工时
+    pub fn estimated_hours(mut self, hours: i32) -> Self {
+        self.estimated_hours = Some(hours);
+        self
+    }
+
+    /// 设置所需能力
+    pub fn required_capabilities(mut self, capabilities: &[&str]) -> Self {
+        self.required_capabilities = capabilities.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    /// 分配给Agent
+    pub fn assigned_to(mut self, agent_id: Uuid) -> Self {
+        self.assigned_agent_id = Some(agent_id);
+        self
+    }
+
+    /// 设置父任务
+    pub fn parent(mut self, parent_task_id: Uuid) -> Self {
+        self.parent_task_id = Some(parent_task_id);
+        self
+    }
+
+    /// 写入数据库
+    pub async fn insert(self, db: &DatabaseConnection) -> Result<task::Model> {
+        let now = chrono::Utc::now();
+        let started = matches!(self.status.as_str(), "in_progress" | "completed" | "failed");
+        let finished = matches!(self.status.as_str(), "completed" | "failed");
+        let hours = self.estimated_hours.unwrap_or(4) as i64;
+
+        let task = task::ActiveModel {
+            task_id: Set(Uuid::new_v4()),
+            project_id: Set(self.project_id),
+            parent_task_id: Set(self.parent_task_id),
+            llm_session_id: Set(None),
+            title: Set(self.title),
+            description: Set(self.description),
+            task_type: Set(self.task_type),
+            priority: Set(self.priority),
+            required_capabilities: Set(Some(json!(self.required_capabilities))),
+            estimated_hours: Set(self.estimated_hours),
+            assigned_agent_id: Set(self.assigned_agent_id),
+            assignment_prompt: Set(self.assigned_agent_id.map(|_| "按验收标准完成任务".to_string())),
+            assigned_at: Set(self.assigned_agent_id.map(|_| (now - chrono::Duration::hours(hours * 2)).into())),
+            status: Set(self.status),
+            started_at: Set(started.then(|| (now - chrono::Duration::hours(hours)).into())),
+            completed_at: Set(finished.then(|| now.into())),
+            created_at: Set((now - chrono::Duration::hours(hours * 3)).into()),
+            updated_at: Set(now.into()),
+            ..Default::default()
+        };
+
+        task.insert(db).await.map_err(DatabaseError::from)
+    }
+}
+
+// ============================================================================
+// 演示数据集
+// ============================================================================
+
+/// 写入的演示数据
+#[derive(Debug, Clone)]
+pub struct DemoData {
+    /// 数据所属用户
+    pub user_id: Uuid,
+    /// 项目
+    pub projects: Vec<project::Model>,
+    /// Agent
+    pub agents: Vec<agent::Model>,
+    /// 任务
+    pub tasks: Vec<task::Model>,
+    /// 冲突
+    pub conflicts: Vec<conflict::Model>,
+    /// 执行会话
+    pub sessions: Vec<execution_session::Model>,
+    /// 执行日志
+    pub logs: Vec<execution_log::Model>,
+    /// 领域事件
+    pub events: Vec<domain_event::Model>,
+}
+
+/// (名称, 描述, 仓库名, 技术栈, 模块)
+type DemoProjectSpec = (&'static str, &'static str, &'static str, &'static [&'static str], [&'static str; 10]);
+
+const DEMO_PROJECTS: [DemoProjectSpec; DEMO_PROJECT_COUNT] = [
+    (
+        "电商平台",
+        "面向中小商家的在线商城，包含商品、订单和支付模块",
+        "shop-platform",
+        &["rust", "axum", "react", "postgresql"],
+        ["商品", "订单", "购物车", "支付", "库存", "用户中心", "优惠券", "搜索", "物流", "评价"],
+    ),
+    (
+        "移动端App",
+        "电商平台配套的跨平台移动应用",
+        "shop-mobile",
+        &["typescript", "react-native", "expo"],
+        ["登录", "首页", "消息推送", "个人资料", "设置", "离线缓存", "分享", "通知", "相册", "埋点"],
+    ),
+    (
+        "数据分析平台",
+        "汇总业务数据的报表与告警平台",
+        "data-insights",
+        &["python", "rust", "clickhouse", "vue"],
+        ["数据导入", "报表", "仪表盘", "ETL", "指标", "告警", "权限", "数据血缘", "查询引擎", "导出"],
+    ),
+];
+
+/// (名称, 描述, 能力)
+const DEMO_AGENTS: [(&str, &str, &[&str]); DEMO_AGENT_COUNT] = [
+    ("前端工程师-小林", "擅长React组件和交互细节", &["FrontendDevelopment", "UIDesign"]),
+    ("前端工程师-小周", "关注前端工程化和测试", &["FrontendDevelopment", "Testing"]),
+    ("后端工程师-老王", "负责API和数据库设计", &["BackendDevelopment", "ApiDesign", "DatabaseDesign"]),
+    ("后端工程师-阿杰", "专注服务端性能", &["BackendDevelopment", "PerformanceTuning"]),
+    ("测试工程师-小陈", "编写自动化测试并把关质量", &["Testing", "CodeReview"]),
+    ("架构师-老李", "负责整体架构和关键代码审查", &["ArchitectureDesign", "CodeReview", "BackendDevelopment"]),
+    ("运维工程师-大刘", "负责CI/CD和线上部署", &["DevOps"]),
+    ("安全审计员", "排查安全漏洞", &["SecurityAudit", "CodeReview"]),
+    ("文档工程师", "维护接口文档和用户手册", &["Documentation"]),
+    ("数据工程师", "负责数据建模与分析", &["DataAnalysis", "MachineLearning", "DatabaseDesign"]),
+];
+
+/// (标题模板, 任务类型, 所需能力)
+const DEMO_TASK_TEMPLATES: [(&str, &str, &str); 10] = [
+    ("实现{}接口", "development", "BackendDevelopment"),
+    ("开发{}页面", "development", "FrontendDevelopment"),
+    ("为{}编写测试", "testing", "Testing"),
+    ("审查{}代码", "code_review", "CodeReview"),
+    ("修复{}缺陷", "bugfix", "BackendDevelopment"),
+    ("优化{}性能", "optimization", "PerformanceTuning"),
+    ("编写{}文档", "documentation", "Documentation"),
+    ("设计{}数据表", "design", "DatabaseDesign"),
+    ("部署{}服务", "deployment", "DevOps"),
+    ("重构{}模块", "refactoring", "ArchitectureDesign"),
+];
+
+/// 按下标分布的任务状态：40% 完成、20% 进行中、30% 待处理、10% 失败
+const DEMO_TASK_STATUSES: [&str; 10] = [
+    "completed", "completed", "completed", "completed", "in_progress",
+    "in_progress", "pending", "pending", "pending", "failed",
+];
+
+const DEMO_PRIORITIES: [&str; 10] = [
+    "medium", "high", "medium", "low", "critical", "medium", "high", "low", "medium", "medium",
+];
+
+/// 创建演示用户并写入演示数据
+pub async fn seed_demo_data(db: &DatabaseConnection) -> Result<DemoData> {
+    let user = UserBuilder::new(DEMO_USERNAME).insert(db).await?;
+    seed_demo_data_for_user(db, user.user_id).await
+}
+
+/// 为指定用户写入演示数据
+pub async fn seed_demo_data_for_user(db: &DatabaseConnection, user_id: Uuid) -> Result<DemoData> {
+    let mut projects = Vec::with_capacity(DEMO_PROJECT_COUNT);
+    for (name, description, repo, stack, _) in DEMO_PROJECTS {
+        let project = ProjectBuilder::new(user_id, name)
+            .description(description)
+            .repository_url(format!("https://github.com/demo/{}.git", repo))
+            .workspace_path(format!("/workspace/demo/{}", repo))
+            .technology_stack(stack)
+            .project_context(json!({ DEMO_MARKER: true }))
+            .insert(db)
+            .await?;
+        projects.push(project);
+    }
+
+    let mut agents = Vec::with_capacity(DEMO_AGENT_COUNT);
+    for (i, (name, description, capabilities)) in DEMO_AGENTS.into_iter().enumerate() {
+        let agent = AgentBuilder::new(user_id, name)
+            .description(description)
+            .prompt_template(format!("你是{}，{}。", name, description))
+            .capabilities(capabilities)
+            .config(json!({ "max_concurrent_tasks": 1, DEMO_MARKER: true }))
+            .statistics(AgentStatistics {
+                total_tasks_completed: Some(12 + (i as i32 * 7) % 30),
+                success_rate: Some(0.72 + (i % 5) as f64 * 0.05),
+                average_completion_time: Some(90 + (i as i32 * 37) % 240),
+            })
+            .insert(db)
+            .await?;
+        agents.push(agent);
+    }
+
+    let mut tasks = Vec::with_capacity(DEMO_TASK_COUNT);
+    for i in 0..DEMO_TASK_COUNT {
+        let project_index = i % DEMO_PROJECT_COUNT;
+        let module = DEMO_PROJECTS[project_index].4[(i / DEMO_PROJECT_COUNT) % 10];
+        let (template, task_type, capability) = DEMO_TASK_TEMPLATES[(i + i / DEMO_PROJECT_COUNT) % 10];
+        let status = DEMO_TASK_STATUSES[i % 10];
+
+        let mut builder = TaskBuilder::new(projects[project_index].project_id, template.replace("{}", module))
+            .description(format!("{}：{}相关工作，完成后需通过代码审查", DEMO_PROJECTS[project_index].0, module))
+            .task_type(task_type)
+            .priority(DEMO_PRIORITIES[(i * 3) % 10])
+            .status(status)
+            .estimated_hours(2 + (i as i32 * 5) % 14)
+            .required_capabilities(&[capability]);
+
+        // 待处理任务约一半尚未分配
+        if status != "pending" || i % 2 == 0 {
+            let candidates: Vec<&agent::Model> = DEMO_AGENTS
+                .iter()
+                .zip(&agents)
+                .filter(|((_, _, capabilities), _)| capabilities.contains(&capability))
+                .map(|(_, agent)| agent)
+                .collect();
+            if !candidates.is_empty() {
+                builder = builder.assigned_to(candidates[i % candidates.len()].agent_id);
+            }
+        }
+
+        tasks.push(builder.insert(db).await?);
+    }
+
+    let mut sessions = Vec::new();
+    let mut logs = Vec::new();
+    seed_execution_sessions(db, &tasks, &mut sessions, &mut logs).await?;
+    mark_working_agents(db, &mut agents, &tasks).await?;
+    let conflicts = seed_conflicts(db, user_id, &tasks, &agents).await?;
+    let events = seed_domain_events(db, user_id, &tasks).await?;
+
+    Ok(DemoData {
+        user_id,
+        projects,
+        agents,
+        tasks,
+        conflicts,
+        sessions,
+        logs,
+        events,
+    })
+}
+
+/// 为已开始的任务生成执行会话和日志
+async fn seed_execution_sessions(
+    db: &DatabaseConnection,
+    tasks: &[task::Model],
+    sessions: &mut Vec<execution_session::Model>,
+    logs: &mut Vec<execution_log::Model>,
+) -> Result<()> {
+    let session_repo = ExecutionSessionRepository::new(db.clone());
+    let log_repo = ExecutionLogRepository::new(db.clone());
+
+    for (i, task) in tasks.iter().enumerate() {
+        let Some(agent_id) = task.assigned_agent_id else {
+            continue;
+        };
+        if task.status == "pending" {
+            continue;
+        }
+
+        let session = session_repo
+            .create(CreateSessionData {
+                task_id: task.task_id,
+                agent_id,
+                project_id: task.project_id,
+                git_branch: format!("feature/demo-task-{:03}", i + 1),
+                base_commit: Some(format!("{:040x}", i + 1)),
+                execution_config: Some(json!({ "max_retries": 2, "verbose_logging": false })),
+                timeout_minutes: 60,
+            })
+            .await?;
+        let mut session = session_repo.start_session(session.session_id).await?;
+
+        let base_ms = session.created_at.timestamp_millis();
+        let mut entries = vec![
+            ("info", EventType::SessionStarted, format!("开始执行任务「{}」", task.title), base_ms),
+            ("info", EventType::GitCheckout, format!("已切换到分支 {}", session.git_branch), base_ms + 1_500),
+            ("debug", EventType::LlmRequest, "请求模型生成实现方案".to_string(), base_ms + 4_000),
+        ];
+        match task.status.as_str() {
+            "completed" => {
+                entries.push(("info", EventType::TestsPassed, "单元测试全部通过".to_string(), base_ms + 60_000));
+                entries.push(("info", EventType::SessionCompleted, "任务完成，已提交代码".to_string(), base_ms + 65_000));
+                session = session_repo
+                    .complete_session(
+                        session.session_id,
+                        true,
+                        Some(format!("{:040x}", 1000 + i)),
+                        Some(json!({ "files_changed": 2 + i % 6 })),
+                        None,
+                    )
+                    .await?;
+            }
+            "failed" => {
+                entries.push(("warn", EventType::TestsFailed, "3 个测试用例失败".to_string(), base_ms + 50_000));
+                entries.push(("error", EventType::SessionFailed, "重试次数耗尽，任务失败".to_string(), base_ms + 90_000));
+                session = session_repo
+                    .complete_session(session.session_id, false, None, None, Some("测试未通过".to_string()))
+                    .await?;
+            }
+            _ => {
+                entries.push(("info", EventType::Progress, "已完成 60%，正在编写测试".to_string(), base_ms + 30_000));
+            }
+        }
+
+        let batch = entries
+            .into_iter()
+            .map(|(level, event_type, message, timestamp_ms)| CreateExecutionLogData {
+                session_id: session.session_id,
+                log_level: level.to_string(),
+                event_type,
+                message,
+                details: None,
+                timestamp_ms,
+            })
+            .collect();
+        logs.extend(log_repo.create_batch(batch).await?);
+        sessions.push(session);
+    }
+
+    Ok(())
+}
+
+/// 有进行中任务的Agent标记为工作中
+async fn mark_working_agents(
+    db: &DatabaseConnection,
+    agents: &mut [agent::Model],
+    tasks: &[task::Model],
+) -> Result<()> {
+    let repo = AgentRepository::new(db.clone());
+    for agent in agents.iter_mut() {
+        let current = tasks
+            .iter()
+            .find(|t| t.status == "in_progress" && t.assigned_agent_id == Some(agent.agent_id));
+        if let Some(task) = current {
+            *agent = repo.update_status(agent.agent_id, AgentStatus::Working, Some(task.task_id)).await?;
+        }
+    }
+    Ok(())
+}
+
+/// 生成覆盖各类冲突和处理状态的示例冲突
+async fn seed_conflicts(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    tasks: &[task::Model],
+    agents: &[agent::Model],
+) -> Result<Vec<conflict::Model>> {
+    let repo = ConflictRepository::new(db.clone());
+    let task_ref = |i: usize| tasks[i % tasks.len()].task_id.to_string();
+    let agent_ref = |i: usize| agents[i % agents.len()].agent_id.to_string();
+
+    let specs = [
+        (ConflictType::GitMerge, ConflictSeverity::High, "订单模块合并冲突",
+            "两个Agent同时修改了 src/order/service.rs", ConflictStatus::Detected, 4, 5),
+        (ConflictType::Resource, ConflictSeverity::Medium, "后端Agent负载过高",
+            "后端工程师同时被分配了多个高优先级任务", ConflictStatus::Resolving, 14, 2),
+        (ConflictType::TaskDependency, ConflictSeverity::Critical, "支付接口依赖未完成",
+            "支付页面开发依赖的支付接口仍在进行中", ConflictStatus::Escalated, 24, 3),
+        (ConflictType::Capability, ConflictSeverity::Low, "缺少机器学习能力",
+            "指标预测任务需要机器学习能力，当前仅一个Agent具备", ConflictStatus::Resolved, 34, 9),
+        (ConflictType::Timeline, ConflictSeverity::Medium, "里程碑存在延期风险",
+            "移动端App剩余任务工时超过里程碑剩余时间", ConflictStatus::Ignored, 44, 1),
+    ];
+
+    let mut conflicts = Vec::with_capacity(specs.len());
+    for (conflict_type, severity, title, description, status, task_index, agent_index) in specs {
+        let conflict = repo
+            .create(CreateConflictData {
+                conflict_type,
+                severity,
+                title: title.to_string(),
+                description: description.to_string(),
+                related_entities: json!({
+                    "tasks": [task_ref(task_index), task_ref(task_index + 1)],
+                    "user_id": user_id.to_string(),
+                    DEMO_MARKER: true,
+                }),
+                affected_tasks: json!([task_ref(task_index), task_ref(task_index + 1)]),
+                affected_agents: json!([agent_ref(agent_index)]),
+                project_id: Some(tasks[task_index % tasks.len()].project_id),
+            })
+            .await?;
+        let conflict = if matches!(status, ConflictStatus::Detected) {
+            conflict
+        } else {
+            repo.update_status(conflict.conflict_id, status).await?
+        };
+        conflicts.push(conflict);
+    }
+
+    Ok(conflicts)
+}
+
+/// 按任务状态生成对应的领域事件
+async fn seed_domain_events(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    tasks: &[task::Model],
+) -> Result<Vec<domain_event::Model>> {
+    let mut events = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        let event_type = match task.status.as_str() {
+            "in_progress" => "TaskExecutionStarted",
+            "completed" => "TaskCompleted",
+            "failed" => "TaskFailed",
+            _ => "TaskCreated",
+        };
+        let event = domain_event::ActiveModel {
+            event_id: Set(Uuid::new_v4()),
+            aggregate_type: Set("task".to_string()),
+            aggregate_id: Set(task.task_id),
+            event_type: Set(event_type.to_string()),
+            event_data: Set(json!({
+                "project_id": task.project_id,
+                "title": task.title,
+                "status": task.status,
+                DEMO_MARKER: true,
+            })),
+            event_version: Set(1),
+            user_id: Set(Some(user_id)),
+            occurred_at: Set(task.updated_at),
+            // 历史事件，标记为已处理以免被事件分发重复消费
+            processed_at: Set(Some(task.updated_at)),
+            is_processed: Set(true),
+            ..Default::default()
+        };
+        events.push(event.insert(db).await?);
+    }
+    Ok(events)
+}
+
+/// 查找用户已写入的演示数据，没有时返回 None
+pub async fn find_demo_data(db: &DatabaseConnection, user_id: Uuid) -> Result<Option<DemoData>> {
+    let projects: Vec<project::Model> = project::Entity::find()
+        .filter(project::Column::UserId.eq(user_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|p| p.project_context.as_ref().is_some_and(is_demo_marked))
+        .collect();
+    let agents: Vec<agent::Model> = agent::Entity::find()
+        .filter(agent::Column::UserId.eq(user_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|a| is_demo_marked(&a.config))
+        .collect();
+    if projects.is_empty() && agents.is_empty() {
+        return Ok(None);
+    }
+
+    let project_ids: Vec<Uuid> = projects.iter().map(|p| p.project_id).collect();
+    let tasks = task::Entity::find()
+        .filter(task::Column::ProjectId.is_in(project_ids.clone()))
+        .all(db)
+        .await?;
+    let sessions = execution_session::Entity::find()
+        .filter(execution_session::Column::ProjectId.is_in(project_ids))
+        .all(db)
+        .await?;
+    let logs = execution_log::Entity::find()
+        .filter(execution_log::Column::SessionId.is_in(sessions.iter().map(|s| s.session_id)))
+        .all(db)
+        .await?;
+
+    let owner = user_id.to_string();
+    let conflicts = conflict::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|c| is_demo_marked(&c.related_entities) && c.related_entities["user_id"] == owner.as_str())
+        .collect();
+    let events = domain_event::Entity::find()
+        .filter(domain_event::Column::UserId.eq(user_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|e| is_demo_marked(&e.event_data))
+        .collect();
+
+    Ok(Some(DemoData {
+        user_id,
+        projects,
+        agents,
+        tasks,
+        conflicts,
+        sessions,
+        logs,
+        events,
+    }))
+}
+
+/// 删除写入的演示数据
+///
+/// 按依赖顺序逐表删除，不依赖数据库的级联删除配置；用户本身保留
+pub async fn delete_demo_data(db: &DatabaseConnection, data: &DemoData) -> Result<()> {
+    let session_ids: Vec<Uuid> = data.sessions.iter().map(|s| s.session_id).collect();
+    let task_ids: Vec<Uuid> = data.tasks.iter().map(|t| t.task_id).collect();
+    let agent_ids: Vec<Uuid> = data.agents.iter().map(|a| a.agent_id).collect();
+    let project_ids: Vec<Uuid> = data.projects.iter().map(|p| p.project_id).collect();
+    let conflict_ids: Vec<Uuid> = data.conflicts.iter().map(|c| c.conflict_id).collect();
+    let event_ids: Vec<Uuid> = data.events.iter().map(|e| e.event_id).collect();
+
+    domain_event::Entity::delete_many()
+        .filter(domain_event::Column::EventId.is_in(event_ids))
+        .exec(db)
+        .await?;
+
+    execution_log::Entity::delete_many()
+        .filter(execution_log::Column::SessionId.is_in(session_ids.clone()))
+        .exec(db)
+        .await?;
+    execution_session::Entity::delete_many()
+        .filter(execution_session::Column::SessionId.is_in(session_ids))
+        .exec(db)
+        .await?;
+    conflict::Entity::delete_many()
+        .filter(conflict::Column::ConflictId.is_in(conflict_ids))
+        .exec(db)
+        .await?;
+    agent::Entity::delete_many()
+        .filter(agent::Column::AgentId.is_in(agent_ids))
+        .exec(db)
+        .await?;
+    task::Entity::delete_many()
+        .filter(task::Column::TaskId.is_in(task_ids))
+        .exec(db)
+        .await?;
+    project::Entity::delete_many()
+        .filter(project::Column::ProjectId.is_in(project_ids))
+        .exec(db)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,355 @@
+//! 不稳定测试识别
+//!
+//! 执行会话结束后通过 [`record_session_results`] 记录每个测试用例的结果，跨会话累积成测试历史。
+//! [`classify`] 按历史把测试分为稳定、不稳定、持续失败和数据不足四类：
+//! - 同一提交上既通过又失败的测试直接判定为不稳定；
+//! - 运行次数不足 `min_runs` 时数据不足；
+//! - 失败率达到 `max_failure_rate` 时视为持续失败，是真实的问题而不是不稳定；
+//! - 结果翻转（相邻两次运行一次通过一次失败）的比例达到 `min_flip_rate` 时判定为不稳定。
+//!
+//! 项目质量门禁开启 `quarantine_flaky_tests` 后，[`evaluate_session_tests`] 会把已知不稳定测试的
+//! 失败隔离为警告，只有其他测试失败时才阻止。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use crate::{
+    entities::test_case_result,
+    repository::{
+        test_case_result_repository::CreateTestCaseResultData, ExecutionSessionRepository,
+        OrganizationRepository, TestCaseResultRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 测试用例结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestOutcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+impl TestOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TestOutcome::Passed => "passed",
+            TestOutcome::Failed => "failed",
+            TestOutcome::Skipped => "skipped",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "passed" => Some(TestOutcome::Passed),
+            "failed" => Some(TestOutcome::Failed),
+            "skipped" => Some(TestOutcome::Skipped),
+            _ => None,
+        }
+    }
+}
+
+/// 执行会话上报的单个测试用例结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestCaseReport {
+    pub name: String,
+    pub outcome: TestOutcome,
+    pub duration_ms: Option<i64>,
+}
+
+/// 不稳定测试的判定参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlakyTestConfig {
+    /// 每个测试参与判定的最近运行次数
+    pub window: usize,
+    /// 判定所需的最少运行次数
+    pub min_runs: usize,
+    /// 判定为不稳定的最低结果翻转比例
+    pub min_flip_rate: f64,
+    /// 失败率达到该值时视为持续失败
+    pub max_failure_rate: f64,
+}
+
+impl Default for FlakyTestConfig {
+    fn default() -> Self {
+        Self {
+            window: 30,
+            min_runs: 5,
+            min_flip_rate: 0.2,
+            max_failure_rate: 0.8,
+        }
+    }
+}
+
+impl FlakyTestConfig {
+    /// 校验参数
+    pub fn validate(&self) -> Result<()> {
+        if self.min_runs < 2 || self.window < self.min_runs {
+            return Err(DatabaseError::validation("判定窗口必须不小于最少运行次数，且最少运行次数至少为2"));
+        }
+        for (name, value) in [("min_flip_rate", self.min_flip_rate), ("max_failure_rate", self.max_failure_rate)] {
+            if !(0.0..=1.0).contains(&value) || value == 0.0 {
+                return Err(DatabaseError::validation(format!("{} 必须在 (0, 1] 范围内", name)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 测试的稳定性分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestStability {
+    /// 稳定（可能偶尔失败，但没有反复翻转）
+    Stable,
+    /// 统计上不稳定
+    Flaky,
+    /// 持续失败
+    Failing,
+    /// 运行次数不足，无法判定
+    InsufficientData,
+}
+
+/// 单个测试的历史统计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestHistory {
+    pub test_name: String,
+    /// 参与统计的运行次数（不含跳过）
+    pub runs: usize,
+    pub failures: usize,
+    pub failure_rate: f64,
+    /// 相邻两次运行结果不同的比例
+    pub flip_rate: f64,
+    /// 是否存在同一提交上既通过又失败的情况
+    pub mixed_commit_outcomes: bool,
+    pub last_outcome: TestOutcome,
+    pub last_run_at: DateTime<Utc>,
+    pub stability: TestStability,
+}
+
+/// 按时间升序的结果计算测试历史，只取最近 `window` 次运行；没有有效运行时返回 None
+pub fn classify(test_name: &str, results: &[test_case_result::Model], config: &FlakyTestConfig) -> Option<TestHistory> {
+    let runs: Vec<(TestOutcome, Option<&str>, DateTime<Utc>)> = results
+        .iter()
+        .filter_map(|result| {
+            TestOutcome::parse(&result.outcome)
+                .filter(|outcome| *outcome != TestOutcome::Skipped)
+                .map(|outcome| (outcome, result.commit_sha.as_deref(), result.recorded_at.with_timezone(&Utc)))
+        })
+        .collect();
+    let runs = &runs[runs.len().saturating_sub(config.window)..];
+    let (last_outcome, _, last_run_at) = *runs.last()?;
+
+    let failures = runs.iter().filter(|(outcome, _, _)| *outcome == TestOutcome::Failed).count();
+    let flips = runs.windows(2).filter(|pair| pair[0].0 != pair[1].0).count();
+    let failure_rate = failures as f64 / runs.len() as f64;
+    let flip_rate = if runs.len() > 1 { flips as f64 / (runs.len() - 1) as f64 } else { 0.0 };
+
+    let mut by_commit: HashMap<&str, (bool, bool)> = HashMap::new();
+    for (outcome, commit, _) in runs {
+        if let Some(commit) = commit {
+            let seen = by_commit.entry(commit).or_default();
+            match outcome {
+                TestOutcome::Passed => seen.0 = true,
+                _ => seen.1 = true,
+            }
+        }
+    }
+    let mixed_commit_outcomes = by_commit.values().any(|(passed, failed)| *passed && *failed);
+
+    let stability = if mixed_commit_outcomes {
+        TestStability::Flaky
+    } else if runs.len() < config.min_runs {
+        TestStability::InsufficientData
+    } else if failure_rate >= config.max_failure_rate {
+        TestStability::Failing
+    } else if failures > 0 && flip_rate >= config.min_flip_rate {
+        TestStability::Flaky
+    } else {
+        TestStability::Stable
+    };
+
+    Some(TestHistory {
+        test_name: test_name.to_string(),
+        runs: runs.len(),
+        failures,
+        failure_rate,
+        flip_rate,
+        mixed_commit_outcomes,
+        last_outcome,
+        last_run_at,
+        stability,
+    })
+}
+
+/// 记录执行会话的测试结果，覆盖该会话之前记录的结果
+pub async fn record_session_results(
+    db: &DatabaseConnection,
+    session_id: Uuid,
+    reports: &[TestCaseReport],
+) -> Result<Vec<test_case_result::Model>> {
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    if let Some(report) = reports.iter().find(|report| report.name.trim().is_empty()) {
+        return Err(DatabaseError::validation(format!("测试名称不能为空: {:?}", report)));
+    }
+
+    let repo = TestCaseResultRepository::new(db.clone());
+    repo.delete_by_session(session_id).await?;
+    let commit_sha = session.final_commit.or(session.base_commit);
+    let recorded_at = Utc::now();
+    repo.create_many(
+        reports
+            .iter()
+            .map(|report| CreateTestCaseResultData {
+                project_id: session.project_id,
+                execution_session_id: session_id,
+                test_name: report.name.trim().to_string(),
+                outcome: report.outcome.as_str().to_string(),
+                duration_ms: report.duration_ms,
+                commit_sha: commit_sha.clone(),
+                recorded_at,
+            })
+            .collect(),
+    )
+    .await
+}
+
+/// 项目测试历史，按测试名称分组
+async fn project_histories(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    config: &FlakyTestConfig,
+) -> Result<BTreeMap<String, TestHistory>> {
+    let results = TestCaseResultRepository::new(db.clone())
+        .find_by_project_since(project_id, None)
+        .await?;
+    let mut grouped: BTreeMap<String, Vec<test_case_result::Model>> = BTreeMap::new();
+    for result in results {
+        grouped.entry(result.test_name.clone()).or_default().push(result);
+    }
+    Ok(grouped
+        .into_iter()
+        .filter_map(|(name, results)| classify(&name, &results, config).map(|history| (name, history)))
+        .collect())
+}
+
+/// 项目的不稳定测试报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlakyTestReport {
+    pub project_id: Uuid,
+    /// 有历史记录的测试总数
+    pub total_tests: usize,
+    /// 不稳定的测试，按翻转比例降序
+    pub flaky: Vec<TestHistory>,
+    /// 持续失败的测试
+    pub failing: Vec<TestHistory>,
+}
+
+/// 列出项目中的不稳定测试候选
+pub async fn flaky_candidates(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    config: &FlakyTestConfig,
+) -> Result<FlakyTestReport> {
+    config.validate()?;
+    let histories = project_histories(db, project_id, config).await?;
+    let total_tests = histories.len();
+    let mut flaky = Vec::new();
+    let mut failing = Vec::new();
+    for history in histories.into_values() {
+        match history.stability {
+            TestStability::Flaky => flaky.push(history),
+            TestStability::Failing => failing.push(history),
+            _ => {}
+        }
+    }
+    flaky.sort_by(|a, b| b.flip_rate.total_cmp(&a.flip_rate));
+
+    Ok(FlakyTestReport {
+        project_id,
+        total_tests,
+        flaky,
+        failing,
+    })
+}
+
+/// 测试门禁结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestGateVerdict {
+    /// 没有失败的测试
+    Passed,
+    /// 失败的测试全部是被隔离的不稳定测试
+    Warning,
+    /// 存在需要修复的失败
+    Blocked,
+}
+
+/// 执行会话的测试门禁结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestGateResult {
+    pub session_id: Uuid,
+    pub verdict: TestGateVerdict,
+    /// 阻止通过的失败测试
+    pub blocking_failures: Vec<String>,
+    /// 被隔离为警告的不稳定测试
+    pub quarantined_failures: Vec<String>,
+}
+
+/// 按项目质量门禁评估执行会话的测试结果，会话没有记录测试结果时返回 None
+///
+/// 只有门禁开启 `quarantine_flaky_tests` 时，已知不稳定测试的失败才会被隔离
+pub async fn evaluate_session_tests(
+    db: &DatabaseConnection,
+    session_id: Uuid,
+    config: &FlakyTestConfig,
+) -> Result<Option<TestGateResult>> {
+    config.validate()?;
+    let results = TestCaseResultRepository::new(db.clone()).find_by_session(session_id).await?;
+    let Some(first) = results.first() else {
+        return Ok(None);
+    };
+
+    let standards = OrganizationRepository::new(db.clone())
+        .resolve_project_standards(first.project_id)
+        .await?;
+    let quarantine = standards.quality_gates.quarantine_flaky_tests;
+    let histories = if quarantine {
+        project_histories(db, first.project_id, config).await?
+    } else {
+        BTreeMap::new()
+    };
+
+    let mut blocking_failures = Vec::new();
+    let mut quarantined_failures = Vec::new();
+    for result in results.iter().filter(|result| result.outcome == TestOutcome::Failed.as_str()) {
+        let flaky = histories
+            .get(&result.test_name)
+            .is_some_and(|history| history.stability == TestStability::Flaky);
+        if flaky {
+            quarantined_failures.push(result.test_name.clone());
+        } else {
+            blocking_failures.push(result.test_name.clone());
+        }
+    }
+
+    let verdict = if !blocking_failures.is_empty() {
+        TestGateVerdict::Blocked
+    } else if !quarantined_failures.is_empty() {
+        TestGateVerdict::Warning
+    } else {
+        TestGateVerdict::Passed
+    };
+    Ok(Some(TestGateResult {
+        session_id,
+        verdict,
+        blocking_failures,
+        quarantined_failures,
+    }))
+}
@@ -0,0 +1,363 @@
+//! Git 操作守卫
+//!
+//! 项目编码规范中 `branching_strategy.protected_branches` 由 [`BranchGuard`] 在 Agent 会话中强制执行：
+//! - 受保护分支上的提交、合并、变基等操作被拒绝，更改必须在功能分支上完成并通过 PR 合并；
+//! - 推送到受保护分支、删除受保护分支被拒绝，强制推送受保护分支单独识别；
+//! - 拦截时由 [`violation_event`] 生成来源为 `Security` 的 [`ErrorEvent`]，
+//!   [`record_violation`] 把它写入项目的领域事件，命令本身的审计记录由命令策略引擎写入。
+
+use codex_multi_agent::{project_management::CodingStandards, ErrorEvent, EventFactory};
+use sea_orm::EntityTrait;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        domain_event::{AggregateType, DomainEventType},
+        project,
+    },
+    repository::{domain_event_repository::CreateDomainEventData, DomainEventRepository, OrganizationRepository},
+    DatabaseConnection, Result,
+};
+
+/// 违反分支保护的错误类型
+pub const BRANCH_PROTECTION_ERROR: &str = "branch_protection";
+
+/// 在当前分支上产生新提交的 git 子命令
+const COMMITTING_SUBCOMMANDS: &[&str] = &["commit", "merge", "rebase", "cherry-pick", "revert", "am"];
+
+/// git 全局选项中带参数的选项
+const GIT_OPTIONS_WITH_VALUE: &[&str] = &["-C", "-c", "--git-dir", "--work-tree", "--namespace", "--exec-path"];
+
+/// `git push` 中带参数的选项
+const PUSH_OPTIONS_WITH_VALUE: &[&str] = &["-o", "--push-option", "--repo", "--receive-pack", "--exec"];
+
+/// 分支保护配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BranchProtection {
+    /// 主分支，始终受保护
+    pub main_branch: String,
+    /// 受保护分支，支持 `release/*` 形式的前缀通配
+    pub protected_branches: Vec<String>,
+}
+
+impl BranchProtection {
+    /// 从编码规范的分支策略创建
+    pub fn from_standards(standards: &CodingStandards) -> Self {
+        let strategy = &standards.branching_strategy;
+        Self {
+            main_branch: strategy.main_branch.clone(),
+            protected_branches: strategy.protected_branches.clone(),
+        }
+    }
+
+    /// 分支是否受保护
+    pub fn is_protected(&self, branch: &str) -> bool {
+        let branch = branch.strip_prefix("refs/heads/").unwrap_or(branch);
+        branch == self.main_branch
+            || self.protected_branches.iter().any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => branch.starts_with(prefix),
+                None => branch == pattern,
+            })
+    }
+}
+
+/// 违规类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BranchViolationKind {
+    /// 在受保护分支上直接提交、合并或变基
+    DirectCommit,
+    /// 推送到受保护分支
+    Push,
+    /// 强制推送受保护分支
+    ForcePush,
+    /// 删除受保护分支
+    Delete,
+}
+
+impl BranchViolationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BranchViolationKind::DirectCommit => "direct_commit",
+            BranchViolationKind::Push => "push",
+            BranchViolationKind::ForcePush => "force_push",
+            BranchViolationKind::Delete => "delete",
+        }
+    }
+}
+
+/// 被拦截的 Git 操作
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BranchViolation {
+    pub kind: BranchViolationKind,
+    /// 受保护的分支
+    pub branch: String,
+    /// 触发拦截的命令
+    pub command: String,
+    pub reason: String,
+}
+
+/// 按顺序检查一次会话中的 git 命令，`checkout`/`switch` 会更新当前分支
+#[derive(Debug, Clone)]
+pub struct BranchGuard<'a> {
+    protection: &'a BranchProtection,
+    current_branch: Option<String>,
+}
+
+impl<'a> BranchGuard<'a> {
+    /// 当前分支未知时，不带显式目标的推送和提交不做判断
+    pub fn new(protection: &'a BranchProtection, current_branch: Option<String>) -> Self {
+        Self { protection, current_branch }
+    }
+
+    /// 当前分支
+    pub fn current_branch(&self) -> Option<&str> {
+        self.current_branch.as_deref()
+    }
+
+    /// 检查一条已去除包装程序的命令，非 git 命令返回 `None`
+    pub fn check(&mut self, words: &[String]) -> Option<BranchViolation> {
+        let (subcommand, args) = git_subcommand(words)?;
+        let command = words.join(" ");
+
+        match subcommand {
+            "checkout" | "switch" => {
+                self.track_checkout(args);
+                None
+            }
+            "push" => self.check_push(args, command),
+            "branch" => self.check_branch_delete(args, command),
+            subcommand if COMMITTING_SUBCOMMANDS.contains(&subcommand) => {
+                // 只读的中止和查看操作不产生提交
+                if args.iter().any(|arg| matches!(arg.as_str(), "--abort" | "--quit" | "--show-current-patch")) {
+                    return None;
+                }
+                let branch = self.current_branch.clone().filter(|branch| self.protection.is_protected(branch))?;
+                Some(BranchViolation {
+                    kind: BranchViolationKind::DirectCommit,
+                    reason: format!(
+                        "受保护分支 {} 不允许直接执行 git {}，请在功能分支上提交并通过 PR 合并",
+                        branch, subcommand
+                    ),
+                    branch,
+                    command,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn track_checkout(&mut self, args: &[String]) {
+        if args.iter().any(|arg| arg == "--") {
+            return;
+        }
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-b" | "-B" | "-c" | "-C" | "--orphan" => {
+                    self.current_branch = iter.next().cloned();
+                    return;
+                }
+                arg if arg.starts_with('-') => {}
+                branch => {
+                    self.current_branch = Some(branch.to_string());
+                    return;
+                }
+            }
+        }
+    }
+
+    fn check_push(&self, args: &[String], command: String) -> Option<BranchViolation> {
+        let mut force = false;
+        let mut delete = false;
+        let mut all_branches = false;
+        let mut positional = Vec::new();
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if PUSH_OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+                iter.next();
+            } else if arg == "--delete" {
+                delete = true;
+            } else if arg.starts_with("--force") && arg != "--force-if-includes" {
+                force = true;
+            } else if matches!(arg.as_str(), "--all" | "--mirror" | "--branches") {
+                all_branches = true;
+            } else if arg.starts_with('-') && !arg.starts_with("--") {
+                force |= arg.contains('f');
+                delete |= arg.contains('d');
+            } else if !arg.starts_with('-') {
+                positional.push(arg.as_str());
+            }
+        }
+
+        // 第一个位置参数是远程仓库，其余是引用规格
+        let mut targets = Vec::new();
+        for refspec in positional.iter().skip(1) {
+            let (forced, refspec) = match refspec.strip_prefix('+') {
+                Some(rest) => (true, rest),
+                None => (false, *refspec),
+            };
+            let (source, destination) = refspec.split_once(':').unwrap_or((refspec, refspec));
+            let destination = match destination {
+                "HEAD" => self.current_branch.clone(),
+                "" => None,
+                destination => Some(destination.to_string()),
+            };
+            if let Some(destination) = destination {
+                targets.push((destination, forced, source.is_empty()));
+            }
+        }
+        if all_branches {
+            targets.push((self.protection.main_branch.clone(), false, false));
+        } else if positional.len() <= 1 {
+            if let Some(branch) = &self.current_branch {
+                targets.push((branch.clone(), false, false));
+            }
+        }
+
+        let (branch, forced, deleted) = targets
+            .into_iter()
+            .find(|(branch, _, _)| self.protection.is_protected(branch))?;
+        let branch = branch.strip_prefix("refs/heads/").unwrap_or(&branch).to_string();
+        let (kind, reason) = if delete || deleted {
+            (BranchViolationKind::Delete, format!("不允许删除受保护分支 {}", branch))
+        } else if force || forced {
+            (
+                BranchViolationKind::ForcePush,
+                format!("不允许强制推送受保护分支 {}，强制推送会覆盖远程历史", branch),
+            )
+        } else {
+            (
+                BranchViolationKind::Push,
+                format!("不允许直接推送到受保护分支 {}，请推送功能分支并通过 PR 合并", branch),
+            )
+        };
+        Some(BranchViolation { kind, branch, command, reason })
+    }
+
+    fn check_branch_delete(&self, args: &[String], command: String) -> Option<BranchViolation> {
+        if !args.iter().any(|arg| matches!(arg.as_str(), "-d" | "-D" | "--delete")) {
+            return None;
+        }
+        let branch = args
+            .iter()
+            .filter(|arg| !arg.starts_with('-'))
+            .find(|branch| self.protection.is_protected(branch))?
+            .clone();
+        Some(BranchViolation {
+            kind: BranchViolationKind::Delete,
+            reason: format!("不允许删除受保护分支 {}", branch),
+            branch,
+            command,
+        })
+    }
+}
+
+/// 跳过 git 的全局选项，返回子命令及其参数
+fn git_subcommand(words: &[String]) -> Option<(&str, &[String])> {
+    let program = words.first()?;
+    if program.rsplit('/').next() != Some("git") {
+        return None;
+    }
+    let mut index = 1;
+    while let Some(arg) = words.get(index) {
+        if GIT_OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+            index += 2;
+        } else if arg.starts_with('-') {
+            index += 1;
+        } else {
+            return Some((arg.as_str(), &words[index + 1..]));
+        }
+    }
+    None
+}
+
+/// 拦截时的安全错误事件
+pub fn violation_event(violation: &BranchViolation, related_entity_id: Option<String>) -> ErrorEvent {
+    let mut event = EventFactory::security_violation(
+        BRANCH_PROTECTION_ERROR.to_string(),
+        violation.reason.clone(),
+        related_entity_id,
+        vec![
+            "在功能分支上提交更改".to_string(),
+            format!("推送功能分支并创建 PR 合并到 {}", violation.branch),
+        ],
+    );
+    event.error_code = Some(violation.kind.as_str().to_string());
+    event
+}
+
+/// 生成安全错误事件并写入项目的领域事件，关联到命令审计记录
+pub async fn record_violation(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    audit_id: Uuid,
+    violation: &BranchViolation,
+) -> Result<ErrorEvent> {
+    let event = violation_event(violation, Some(audit_id.to_string()));
+    let event_repo = DomainEventRepository::new(db.clone());
+    let event_version = event_repo.get_latest_version(project_id).await? + 1;
+    event_repo
+        .create_correlated(
+            CreateDomainEventData {
+                aggregate_type: AggregateType::Project.to_string(),
+                aggregate_id: project_id,
+                event_type: DomainEventType::BranchProtectionViolated.to_string(),
+                event_data: serde_json::to_value(&event)?,
+                event_version,
+            },
+            audit_id,
+        )
+        .await?;
+
+    tracing::warn!(
+        "项目 {} 拦截了违反分支保护的 Git 操作（{}）: {}",
+        project_id,
+        violation.kind.as_str(),
+        violation.command
+    );
+    Ok(event)
+}
+
+/// 项目生效的分支保护配置
+pub async fn resolve_protection(db: &DatabaseConnection, project_id: Uuid) -> Result<BranchProtection> {
+    let standards = OrganizationRepository::new(db.clone())
+        .resolve_project_standards(project_id)
+        .await?;
+    Ok(BranchProtection::from_standards(&standards))
+}
+
+/// 查找工作空间包含该目录的项目，多个项目嵌套时取最深的工作空间
+pub async fn find_project_for_path(db: &DatabaseConnection, path: &Path) -> Result<Option<project::Model>> {
+    let path = canonical(path);
+    let projects = project::Entity::find().all(db).await?;
+    Ok(projects
+        .into_iter()
+        .map(|project| (canonical(Path::new(&project.workspace_path)), project))
+        .filter(|(workspace, _)| !workspace.as_os_str().is_empty() && path.starts_with(workspace))
+        .max_by_key(|(workspace, _)| workspace.components().count())
+        .map(|(_, project)| project))
+}
+
+/// 仓库当前检出的分支，分离头指针或不是仓库时返回 `None`
+pub async fn current_branch(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["symbolic-ref", "--quiet", "--short", "HEAD"])
+        .current_dir(dir)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!branch.is_empty()).then_some(branch)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
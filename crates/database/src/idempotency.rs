@@ -0,0 +1,154 @@
+//! 命令与事件处理的幂等键
+//!
+//! 重试会让有副作用的操作重复执行（重复创建任务、重复发送通知）。调用方为一次逻辑操作提供幂等键：
+//! - [`IdempotencyStore::begin`] 以处理中状态占用键，相同键的重复请求直接得到首次执行保存的结果；
+//! - 执行成功后 [`IdempotencyGuard::complete`] 保存结果和结果摘要，失败时 [`IdempotencyGuard::release`]
+//!   释放键，允许重试重新执行；
+//! - 相同键携带不同请求内容时拒绝，避免客户端误用键导致返回无关的结果。
+//!
+//! 结果类型是 [`DatabaseError`] 的调用方可以直接使用 [`IdempotencyStore::execute`]。
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use uuid::Uuid;
+
+use crate::{repository::IdempotencyKeyRepository, DatabaseConnection, DatabaseError, Result};
+
+/// 幂等键默认保留时间（小时）
+pub const DEFAULT_TTL_HOURS: i64 = 24;
+
+/// 处理中的键超过该时间仍未完成时视为执行方已崩溃，允许重新占用（秒）
+const PROCESSING_TIMEOUT_SECS: i64 = 300;
+
+/// 幂等键的最大长度
+const MAX_KEY_LENGTH: usize = 255;
+
+/// 占用幂等键的结果
+pub enum IdempotencyClaim {
+    /// 首次请求，执行操作后通过守卫保存结果
+    Acquired(IdempotencyGuard),
+    /// 重复请求，返回首次执行保存的结果
+    Replay(serde_json::Value),
+}
+
+/// 已占用的幂等键
+pub struct IdempotencyGuard {
+    record_id: Uuid,
+    repo: IdempotencyKeyRepository,
+}
+
+impl IdempotencyGuard {
+    /// 保存执行结果
+    pub async fn complete<T: Serialize>(self, result: &T) -> Result<()> {
+        let value = serde_json::to_value(result)?;
+        let hash = digest(&value)?;
+        self.repo.complete(self.record_id, value, hash).await?;
+        Ok(())
+    }
+
+    /// 释放幂等键，之后携带相同键的请求会重新执行
+    pub async fn release(self) -> Result<()> {
+        self.repo.delete(self.record_id).await
+    }
+}
+
+/// 幂等键存储
+pub struct IdempotencyStore {
+    db: DatabaseConnection,
+    repo: IdempotencyKeyRepository,
+    ttl: Duration,
+}
+
+impl IdempotencyStore {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            repo: IdempotencyKeyRepository::new(db.clone()),
+            db,
+            ttl: Duration::hours(DEFAULT_TTL_HOURS),
+        }
+    }
+
+    /// 设置幂等键的保留时间
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// 占用幂等键
+    ///
+    /// 相同键携带不同请求内容时返回校验错误，相同请求仍在处理中时返回冲突错误
+    pub async fn begin<R: Serialize + ?Sized>(&self, scope: &str, key: &str, request: &R) -> Result<IdempotencyClaim> {
+        let key = key.trim();
+        if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+            return Err(DatabaseError::validation(format!("幂等键长度必须在 1 到 {} 之间", MAX_KEY_LENGTH)));
+        }
+        let request_hash = digest(request)?;
+
+        // 过期或处理超时的记录删除后重新占用一次
+        for _ in 0..2 {
+            let now = Utc::now();
+            if let Some(record) = self.repo.try_claim(scope, key, request_hash.clone(), now + self.ttl).await? {
+                return Ok(IdempotencyClaim::Acquired(IdempotencyGuard {
+                    record_id: record.record_id,
+                    repo: IdempotencyKeyRepository::new(self.db.clone()),
+                }));
+            }
+            let Some(existing) = self.repo.find(scope, key).await? else {
+                continue;
+            };
+            let stale = existing.expires_at < now
+                || (!existing.is_completed()
+                    && existing.created_at + Duration::seconds(PROCESSING_TIMEOUT_SECS) < now);
+            if stale {
+                self.repo.delete(existing.record_id).await?;
+                continue;
+            }
+            if existing.request_hash != request_hash {
+                return Err(DatabaseError::validation(format!("幂等键 {} 已用于不同的请求", key)));
+            }
+            return match existing.result {
+                Some(result) if existing.is_completed() => {
+                    tracing::debug!("幂等键 {}/{} 重复请求，返回首次执行的结果", scope, key);
+                    Ok(IdempotencyClaim::Replay(result))
+                }
+                _ => Err(DatabaseError::conflict(format!("幂等键 {} 的请求正在处理", key))),
+            };
+        }
+        Err(DatabaseError::conflict(format!("幂等键 {} 的请求正在处理", key)))
+    }
+
+    /// 以幂等方式执行操作：首次请求执行并保存结果，重复请求返回首次的结果
+    pub async fn execute<R, T, F, Fut>(&self, scope: &str, key: &str, request: &R, operation: F) -> Result<T>
+    where
+        R: Serialize + ?Sized,
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        match self.begin(scope, key, request).await? {
+            IdempotencyClaim::Replay(result) => Ok(serde_json::from_value(result)?),
+            IdempotencyClaim::Acquired(guard) => match operation().await {
+                Ok(result) => {
+                    guard.complete(&result).await?;
+                    Ok(result)
+                }
+                Err(e) => {
+                    guard.release().await?;
+                    Err(e)
+                }
+            },
+        }
+    }
+
+    /// 删除已过期的幂等键
+    pub async fn purge_expired(&self, now: DateTime<Utc>) -> Result<u64> {
+        self.repo.delete_expired(now).await
+    }
+}
+
+/// 内容的 SHA-256 摘要
+fn digest<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    Ok(format!("{:x}", Sha256::digest(serde_json::to_vec(value)?)))
+}
@@ -0,0 +1,47 @@
+//! 领域ID与数据库主键之间的转换
+//!
+//! 数据库主键统一使用 [`Uuid`]，codex-multi-agent 则用 `AgentId`、`TaskId` 等新类型区分不同实体。
+//! 仓储方法的ID参数声明为 `impl EntityKey<AgentId>` 这类形式：
+//! - 原始 [`Uuid`] 始终可用，已有调用方无需修改；
+//! - 启用 `typed_ids` 特性后，对应的强类型ID也可以直接传入，传错实体的ID会在编译期报错。
+
+#[cfg(feature = "typed_ids")]
+use codex_multi_agent::{AgentId, ExecutionSessionId, ProjectId, TaskId};
+use std::fmt;
+use uuid::Uuid;
+
+/// 可以作为 `Id` 所标识实体主键的值
+///
+/// 要求实现 `Display` 和 `Debug`，便于在追踪字段和错误信息中直接记录ID。
+pub trait EntityKey<Id>: fmt::Display + fmt::Debug {
+    /// 转换为数据库主键
+    fn into_key(self) -> Uuid;
+}
+
+impl<Id> EntityKey<Id> for Uuid {
+    fn into_key(self) -> Uuid {
+        self
+    }
+}
+
+#[cfg(feature = "typed_ids")]
+macro_rules! impl_entity_key {
+    ($($id_type:ident),* $(,)?) => {
+        $(
+            impl EntityKey<$id_type> for $id_type {
+                fn into_key(self) -> Uuid {
+                    self.0
+                }
+            }
+
+            impl EntityKey<$id_type> for &$id_type {
+                fn into_key(self) -> Uuid {
+                    self.0
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "typed_ids")]
+impl_entity_key!(AgentId, ProjectId, TaskId, ExecutionSessionId);
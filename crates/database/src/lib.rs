@@ -2,17 +2,96 @@
 //! 
 //! 基于SeaORM的多Agent协同开发系统数据库访问层
 
+pub mod acceptance;
+pub mod agent_dashboard;
+pub mod agent_bundle;
+pub mod agent_provisioning;
+pub mod api_keys;
+pub mod artifact_store;
+pub mod assignment_costs;
+pub mod audit_bundle;
+pub mod autoscaler;
+pub mod blackboard;
+pub mod checkpoint_gates;
+pub mod ci_integration;
+pub mod code_ownership;
+pub mod command_policy;
 pub mod config;
 pub mod connection;
+pub mod context;
+pub mod coverage;
+pub mod cross_project_dependencies;
+pub mod data_purge;
+pub mod decomposition_progress;
+pub mod dependency_audit;
+pub mod embeddings;
 pub mod entities;
 pub mod error;
+pub mod export;
+#[cfg(feature = "fault_injection")]
+pub mod fault_injection;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+pub mod flaky_tests;
+pub mod git_ops;
+pub mod idempotency;
+pub mod ids;
+pub mod llm_cache;
+pub mod llm_provider;
+pub mod llm_replay;
+pub mod llm_transcripts;
+pub mod log_pipeline;
+pub mod maintenance;
+pub mod mapping;
+pub mod merge_resolver;
+pub mod metrics_rollup;
 pub mod migrations;
+pub mod notifications;
+pub mod operations;
+pub mod orchestration_config;
+pub mod parallel_planner;
+pub mod partitioning;
+pub mod payload_storage;
+pub mod perf_budget;
+pub mod pii_scrubbing;
+pub mod plan_guardrails;
+pub mod plugins;
+pub mod preemption;
+pub mod project_bootstrap;
+pub mod prompt_eval;
+pub mod public_api;
+pub mod query_plan;
 pub mod repository;
+pub mod reporting;
+pub mod review_sla;
+pub mod security_scan;
+pub mod self_assessment;
+pub mod session_checkpoint;
+pub mod session_diff;
+pub mod shutdown;
+pub mod sla;
+pub mod static_analysis;
+pub mod structured_output;
+pub mod supervisor;
+pub mod task_scope;
+pub mod task_trace;
+pub mod telemetry;
+pub mod tenant_scope;
+pub mod traceability;
+pub mod two_factor;
+pub mod webhook_ingestion;
+pub mod worker_coordinator;
+pub mod workspace_quota;
+pub mod worktree_pool;
 
 // 重新导出主要类型
-pub use config::DatabaseConfig;
-pub use connection::{DatabaseConnection, establish_connection};
+pub use config::{DatabaseConfig, JournalMode};
+pub use connection::{
+    DatabaseConnection, establish_connection, establish_connection_with_config, retry_on_busy, BusyRetryPolicy,
+};
+pub use context::ContextBuilder;
 pub use error::{DatabaseError, Result};
+pub use tenant_scope::TenantScope;
 
 // 导出实体模块
 pub use entities::*;
@@ -24,7 +103,7 @@ pub use migrations::Migrator;
 /// 
 /// 创建数据库连接并运行迁移
 pub async fn initialize_database(config: &DatabaseConfig) -> Result<DatabaseConnection> {
-    let db = establish_connection(&config.database_url).await?;
+    let db = establish_connection_with_config(config).await?;
     
     // 运行数据库迁移
     Migrator::up(&db, None).await?;
@@ -58,6 +137,7 @@ mod tests {
             connect_timeout: 10,
             idle_timeout: 60,
             enable_logging: false,
+            ..Default::default()
         };
         
         initialize_database(&config).await
@@ -0,0 +1,342 @@
+//! LLM响应缓存
+//!
+//! 任务分解、代码评审等确定性调用在提示词不变时会被重复计费。[`LlmCache`] 以
+//! 模型、调用参数和提示词的 SHA-256 为键，把响应保存在 `llm_cache_entries` 表中：
+//! - 条目在 [`LlmCacheConfig::ttl_seconds`] 后过期，过期条目不会命中；
+//! - 超过 [`LlmCacheConfig::max_response_bytes`] 的响应不缓存，条目数或总大小超限时按最近使用时间淘汰；
+//! - 非确定性调用（例如较高温度的创意生成）通过 [`LlmCacheRequest::bypass`] 跳过缓存；
+//! - 命中、未命中和跳过的次数记录在 [`LlmCacheMetrics`]，并上报遥测指标
+//!   [`crate::telemetry::LLM_CACHE_REQUESTS_TOTAL`]。
+//!
+//! 缓存默认关闭，由应用设置开启。
+
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{
+    repository::{llm_cache_repository::PutLlmCacheData, LlmCacheRepository},
+    telemetry, DatabaseConnection, Result,
+};
+
+/// LLM响应缓存配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LlmCacheConfig {
+    /// 是否启用缓存
+    pub enabled: bool,
+    /// 条目有效期（秒）
+    pub ttl_seconds: i64,
+    /// 最多保留的条目数
+    pub max_entries: usize,
+    /// 所有条目响应的总大小上限（字节）
+    pub max_total_bytes: u64,
+    /// 单个响应的大小上限（字节），超过时不缓存
+    pub max_response_bytes: usize,
+}
+
+impl Default for LlmCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_seconds: 7 * 24 * 3600,
+            max_entries: 1000,
+            max_total_bytes: 64 * 1024 * 1024,
+            max_response_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// 一次LLM调用的缓存键组成
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LlmCacheRequest {
+    /// 模型名称
+    pub model: String,
+    /// 影响输出的调用参数，例如温度和最大token数
+    pub params: JsonValue,
+    /// 完整提示词
+    pub prompt: String,
+    /// 跳过缓存，用于非确定性调用
+    pub bypass: bool,
+}
+
+impl LlmCacheRequest {
+    pub fn new(model: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            params: JsonValue::Null,
+            prompt: prompt.into(),
+            bypass: false,
+        }
+    }
+
+    /// 设置调用参数
+    pub fn with_params(mut self, params: JsonValue) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// 跳过缓存：既不读取也不写入
+    pub fn bypass(mut self) -> Self {
+        self.bypass = true;
+        self
+    }
+
+    /// 提示词的 SHA-256
+    pub fn prompt_hash(&self) -> String {
+        sha256_hex(self.prompt.as_bytes())
+    }
+
+    /// 缓存键：模型、规范化后的参数和提示词的 SHA-256
+    ///
+    /// 参数对象的键顺序不影响缓存键
+    pub fn cache_key(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.model.as_bytes());
+        hasher.update([0]);
+        hasher.update(canonical_json(&self.params).as_bytes());
+        hasher.update([0]);
+        hasher.update(self.prompt.as_bytes());
+        hex(&hasher.finalize())
+    }
+}
+
+/// 缓存查询的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheOutcome {
+    Hit,
+    Miss,
+    Bypass,
+}
+
+impl CacheOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheOutcome::Hit => "hit",
+            CacheOutcome::Miss => "miss",
+            CacheOutcome::Bypass => "bypass",
+        }
+    }
+}
+
+/// 经过缓存的LLM响应
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub response: String,
+    pub outcome: CacheOutcome,
+}
+
+/// 进程内的缓存计数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LlmCacheMetrics {
+    pub hits: u64,
+    pub misses: u64,
+    pub bypassed: u64,
+    /// 写入的条目数
+    pub stores: u64,
+    /// 因过期或容量超限被删除的条目数
+    pub evictions: u64,
+}
+
+impl LlmCacheMetrics {
+    /// 命中率，没有可缓存的查询时为 0
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// 缓存状态：计数和表容量
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LlmCacheStats {
+    pub enabled: bool,
+    pub metrics: LlmCacheMetrics,
+    pub hit_rate: f64,
+    pub entries: u64,
+    pub total_bytes: u64,
+    /// 表中条目的累计命中次数
+    pub total_hits: u64,
+}
+
+/// LLM响应缓存
+pub struct LlmCache {
+    repository: LlmCacheRepository,
+    config: LlmCacheConfig,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    bypassed: AtomicU64,
+    stores: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl LlmCache {
+    pub fn new(db: DatabaseConnection, config: LlmCacheConfig) -> Self {
+        Self {
+            repository: LlmCacheRepository::new(db),
+            config,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            bypassed: AtomicU64::new(0),
+            stores: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &LlmCacheConfig {
+        &self.config
+    }
+
+    /// 查询缓存，未启用或跳过缓存时返回 `None`
+    pub async fn get(&self, request: &LlmCacheRequest) -> Result<Option<String>> {
+        if !self.config.enabled || request.bypass {
+            self.record(CacheOutcome::Bypass);
+            return Ok(None);
+        }
+        let cache_key = request.cache_key();
+        let now = Utc::now();
+        match self.repository.find_valid(&cache_key, now).await? {
+            Some(entry) => {
+                self.repository.record_hit(&cache_key, now).await?;
+                self.record(CacheOutcome::Hit);
+                Ok(Some(entry.response))
+            }
+            None => {
+                self.record(CacheOutcome::Miss);
+                Ok(None)
+            }
+        }
+    }
+
+    /// 写入响应并按容量上限淘汰旧条目，未启用、跳过缓存或响应过大时不写入
+    pub async fn put(&self, request: &LlmCacheRequest, response: &str) -> Result<()> {
+        if !self.config.enabled || request.bypass || response.len() > self.config.max_response_bytes {
+            return Ok(());
+        }
+        self.repository
+            .put(PutLlmCacheData {
+                cache_key: request.cache_key(),
+                model: request.model.clone(),
+                prompt_hash: request.prompt_hash(),
+                response: response.to_string(),
+                expires_at: Utc::now() + Duration::seconds(self.config.ttl_seconds),
+            })
+            .await?;
+        self.stores.fetch_add(1, Ordering::Relaxed);
+        self.enforce_limits().await?;
+        Ok(())
+    }
+
+    /// 命中时返回缓存的响应，否则调用 `call` 并缓存成功的结果
+    pub async fn get_or_call<F, Fut>(&self, request: &LlmCacheRequest, call: F) -> Result<CachedResponse>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        if let Some(response) = self.get(request).await? {
+            return Ok(CachedResponse { response, outcome: CacheOutcome::Hit });
+        }
+        let outcome = if !self.config.enabled || request.bypass { CacheOutcome::Bypass } else { CacheOutcome::Miss };
+        let response = call().await?;
+        self.put(request, &response).await?;
+        Ok(CachedResponse { response, outcome })
+    }
+
+    /// 删除过期条目，并在条目数或总大小超限时删除最久未使用的条目，返回删除数量
+    pub async fn enforce_limits(&self) -> Result<u64> {
+        let mut removed = self.repository.delete_expired(Utc::now()).await?;
+
+        let entries = self.repository.list_least_recently_used().await?;
+        let mut count = entries.len();
+        let mut total_bytes: u64 = entries.iter().map(|(_, size)| *size as u64).sum();
+        let mut evict = Vec::new();
+        for (cache_key, size) in entries {
+            if count <= self.config.max_entries && total_bytes <= self.config.max_total_bytes {
+                break;
+            }
+            count -= 1;
+            total_bytes = total_bytes.saturating_sub(size as u64);
+            evict.push(cache_key);
+        }
+        removed += self.repository.delete_keys(evict).await?;
+
+        if removed > 0 {
+            self.evictions.fetch_add(removed, Ordering::Relaxed);
+            tracing::debug!("LLM响应缓存清理了 {} 个条目", removed);
+        }
+        Ok(removed)
+    }
+
+    /// 清空缓存，返回删除数量
+    pub async fn clear(&self) -> Result<u64> {
+        let removed = self.repository.clear().await?;
+        tracing::info!("已清空LLM响应缓存（{} 个条目）", removed);
+        Ok(removed)
+    }
+
+    /// 进程内的缓存计数
+    pub fn metrics(&self) -> LlmCacheMetrics {
+        LlmCacheMetrics {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            bypassed: self.bypassed.load(Ordering::Relaxed),
+            stores: self.stores.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 缓存计数和表容量
+    pub async fn stats(&self) -> Result<LlmCacheStats> {
+        let usage = self.repository.usage().await?;
+        let metrics = self.metrics();
+        Ok(LlmCacheStats {
+            enabled: self.config.enabled,
+            hit_rate: metrics.hit_rate(),
+            metrics,
+            entries: usage.entries.max(0) as u64,
+            total_bytes: usage.total_bytes.unwrap_or(0).max(0) as u64,
+            total_hits: usage.total_hits.unwrap_or(0).max(0) as u64,
+        })
+    }
+
+    fn record(&self, outcome: CacheOutcome) {
+        let counter = match outcome {
+            CacheOutcome::Hit => &self.hits,
+            CacheOutcome::Miss => &self.misses,
+            CacheOutcome::Bypass => &self.bypassed,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        telemetry::record_llm_cache(outcome.as_str());
+    }
+}
+
+/// 键按字典序排列的JSON文本
+fn canonical_json(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let fields: Vec<String> = keys
+                .into_iter()
+                .map(|key| format!("{}:{}", JsonValue::String(key.clone()), canonical_json(&map[key])))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        JsonValue::Array(items) => format!("[{}]", items.iter().map(canonical_json).collect::<Vec<_>>().join(",")),
+        other => other.to_string(),
+    }
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    hex(&Sha256::digest(content))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
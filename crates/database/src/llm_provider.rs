@@ -0,0 +1,93 @@
+//! LLM提供方
+//!
+//! [`LlmProvider`] 抽象调度层对LLM的调用（任务分解、代码评审等）。除了一次性返回结果的
+//! [`LlmProvider::complete`]，提供方还可以实现 [`LlmProvider::complete_streaming`]，把输出片段
+//! 逐个发送到通道中，调用方据此向界面推送增量结果；默认实现在生成结束后一次性发送完整输出。
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::future::Future;
+use std::pin::Pin;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{llm_cache::LlmCacheRequest, Result};
+
+/// LLM调用的异步结果
+pub type LlmFuture<'a> = Pin<Box<dyn Future<Output = Result<LlmCompletion>> + Send + 'a>>;
+
+/// LLM调用请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LlmRequest {
+    /// 模型名称
+    pub model: String,
+    /// 完整提示词
+    pub prompt: String,
+    /// 采样温度，为空时使用提供方的默认值
+    pub temperature: Option<f32>,
+    /// 最大输出token数
+    pub max_tokens: Option<u32>,
+}
+
+impl LlmRequest {
+    pub fn new(model: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            prompt: prompt.into(),
+            temperature: None,
+            max_tokens: None,
+        }
+    }
+
+    /// 影响输出的调用参数：温度和最大token数
+    pub fn params(&self) -> JsonValue {
+        json!({
+            "temperature": self.temperature,
+            "max_tokens": self.max_tokens,
+        })
+    }
+
+    /// 按 [`LlmRequest::params`] 的格式恢复调用参数，缺少的参数保持不变
+    pub fn with_params(mut self, params: &JsonValue) -> Self {
+        if let Some(temperature) = params.get("temperature").and_then(JsonValue::as_f64) {
+            self.temperature = Some(temperature as f32);
+        }
+        if let Some(max_tokens) = params.get("max_tokens").and_then(JsonValue::as_u64) {
+            self.max_tokens = Some(max_tokens.min(u32::MAX as u64) as u32);
+        }
+        self
+    }
+
+    /// 对应的缓存键组成，参数包含温度和最大token数
+    pub fn cache_request(&self) -> LlmCacheRequest {
+        LlmCacheRequest::new(&self.model, &self.prompt).with_params(self.params())
+    }
+}
+
+/// LLM调用结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LlmCompletion {
+    /// 完整输出
+    pub content: String,
+    /// 消耗的token数
+    pub tokens_used: u32,
+}
+
+/// LLM提供方
+pub trait LlmProvider: Send + Sync {
+    /// 提供方名称
+    fn name(&self) -> &str;
+
+    /// 生成完整输出
+    fn complete<'a>(&'a self, request: &'a LlmRequest) -> LlmFuture<'a>;
+
+    /// 流式生成：输出片段按顺序发送到 `tokens`，结束后返回完整结果
+    ///
+    /// 接收方关闭时提供方应继续生成，片段发送失败不视为错误
+    fn complete_streaming<'a>(&'a self, request: &'a LlmRequest, tokens: UnboundedSender<String>) -> LlmFuture<'a> {
+        Box::pin(async move {
+            let completion = self.complete(request).await?;
+            let _ = tokens.send(completion.content.clone());
+            Ok(completion)
+        })
+    }
+}
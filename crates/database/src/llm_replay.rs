@@ -0,0 +1,221 @@
+//! LLM会话重放
+//!
+//! 排查不合理的计划需要复现当时的LLM调用。[`replay_session`] 读取LLM会话保存的调用记录
+//! （见 [`crate::llm_transcripts`]），按原始提示词和调用参数依次重新调用提供方，模型可以沿用
+//! 原始模型或由 [`ReplayOptions::model`] 指定，并把每次调用的新输出与原始输出逐行比较。
+//! 比较结果保存为 `llm_replay_reports` 中的一份报告，关联到被重放的会话。
+//!
+//! 报告中的输出和差异经过 [`redact_secrets`] 脱敏；重放调用本身不再保存调用记录，
+//! 避免改变被重放会话的内容。
+
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use std::time::Instant;
+use uuid::Uuid;
+
+use crate::{
+    entities::{llm_replay_report, llm_transcript},
+    llm_provider::{LlmProvider, LlmRequest},
+    repository::{
+        llm_replay_report_repository::CreateLlmReplayReportData, LlmReplayReportRepository, LlmSessionRepository,
+        LlmTranscriptRepository,
+    },
+    security_scan::redact_secrets,
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 差异的上下文行数
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// 重放选项
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayOptions {
+    /// 重放使用的模型，为空时使用每次调用的原始模型
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// 一次调用的重放结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayCallResult {
+    /// 原始调用记录ID
+    pub transcript_id: Uuid,
+    pub call_kind: String,
+    pub original_model: String,
+    pub replay_model: String,
+    /// 原始输出，原始调用失败时为空
+    pub original_response: Option<String>,
+    /// 重放输出，重放失败时为空
+    pub replay_response: Option<String>,
+    /// 重放失败的错误信息
+    pub replay_error: Option<String>,
+    /// 新输出与原始输出完全相同
+    pub identical: bool,
+    /// 按行计算的相似度，0 到 1
+    pub similarity: f32,
+    /// 统一差异格式的逐行差异，输出相同时为空
+    pub diff: String,
+    pub original_tokens: Option<i32>,
+    pub replay_tokens: Option<u32>,
+    pub original_latency_ms: i64,
+    pub replay_latency_ms: i64,
+}
+
+/// 会话重放报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayReport {
+    pub report_id: Uuid,
+    pub llm_session_id: Uuid,
+    pub provider: String,
+    /// 重放指定的模型
+    pub model_override: Option<String>,
+    pub total_calls: u32,
+    /// 输出与原始输出不同的调用数（包括重放失败的调用）
+    pub changed_calls: u32,
+    pub failed_calls: u32,
+    pub calls: Vec<ReplayCallResult>,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl ReplayReport {
+    /// 所有调用的输出都与原始输出相同
+    pub fn is_reproducible(&self) -> bool {
+        self.changed_calls == 0
+    }
+}
+
+impl TryFrom<llm_replay_report::Model> for ReplayReport {
+    type Error = DatabaseError;
+
+    fn try_from(model: llm_replay_report::Model) -> Result<Self> {
+        let calls = serde_json::from_value(model.calls)
+            .map_err(|e| DatabaseError::column_decode("LlmReplayReport", "calls", e))?;
+        Ok(Self {
+            report_id: model.report_id,
+            llm_session_id: model.llm_session_id,
+            provider: model.provider,
+            model_override: model.model_override,
+            total_calls: model.total_calls.max(0) as u32,
+            changed_calls: model.changed_calls.max(0) as u32,
+            failed_calls: model.failed_calls.max(0) as u32,
+            calls,
+            created_at: model.created_at,
+        })
+    }
+}
+
+/// 重放LLM会话的所有调用，保存并返回重放报告
+pub async fn replay_session(
+    db: &DatabaseConnection,
+    provider: &dyn LlmProvider,
+    llm_session_id: Uuid,
+    options: &ReplayOptions,
+) -> Result<ReplayReport> {
+    LlmSessionRepository::new(db.clone())
+        .find_by_id(llm_session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("LlmSession", llm_session_id))?;
+    let transcripts = LlmTranscriptRepository::new(db.clone())
+        .find_by_llm_session(llm_session_id)
+        .await?;
+    if transcripts.is_empty() {
+        return Err(DatabaseError::validation("LLM会话没有可重放的调用记录"));
+    }
+
+    let mut calls = Vec::with_capacity(transcripts.len());
+    for transcript in &transcripts {
+        calls.push(replay_call(provider, transcript, options).await);
+    }
+    let changed_calls = calls.iter().filter(|call| !call.identical).count();
+    let failed_calls = calls.iter().filter(|call| call.replay_error.is_some()).count();
+    tracing::info!(
+        "LLM会话 {} 重放完成：{} 次调用，{} 次输出变化，{} 次失败",
+        llm_session_id,
+        calls.len(),
+        changed_calls,
+        failed_calls
+    );
+
+    let report = LlmReplayReportRepository::new(db.clone())
+        .create(CreateLlmReplayReportData {
+            llm_session_id,
+            provider: provider.name().to_string(),
+            model_override: options.model.clone(),
+            total_calls: calls.len() as i32,
+            changed_calls: changed_calls as i32,
+            failed_calls: failed_calls as i32,
+            calls: serde_json::to_value(&calls)?,
+        })
+        .await?;
+    ReplayReport::try_from(report)
+}
+
+/// 查看LLM会话的重放报告（按时间倒序）
+pub async fn list_replay_reports(db: &DatabaseConnection, llm_session_id: Uuid) -> Result<Vec<ReplayReport>> {
+    LlmReplayReportRepository::new(db.clone())
+        .find_by_session(llm_session_id)
+        .await?
+        .into_iter()
+        .map(ReplayReport::try_from)
+        .collect()
+}
+
+/// 查看单份重放报告
+pub async fn get_replay_report(db: &DatabaseConnection, report_id: Uuid) -> Result<Option<ReplayReport>> {
+    LlmReplayReportRepository::new(db.clone())
+        .find_by_id(report_id)
+        .await?
+        .map(ReplayReport::try_from)
+        .transpose()
+}
+
+async fn replay_call(
+    provider: &dyn LlmProvider,
+    transcript: &llm_transcript::Model,
+    options: &ReplayOptions,
+) -> ReplayCallResult {
+    let model = options.model.clone().unwrap_or_else(|| transcript.model.clone());
+    let mut request = LlmRequest::new(model, transcript.prompt.clone());
+    if let Some(params) = &transcript.request_params {
+        request = request.with_params(params);
+    }
+
+    let started = Instant::now();
+    let result = provider.complete(&request).await;
+    let replay_latency_ms = started.elapsed().as_millis().min(i64::MAX as u128) as i64;
+
+    let original = transcript.response.as_deref().unwrap_or_default();
+    let (replay_response, replay_error, replay_tokens) = match result {
+        Ok(completion) => (Some(completion.content), None, Some(completion.tokens_used)),
+        Err(e) => (None, Some(e.to_string()), None),
+    };
+    let replayed = replay_response.as_deref().unwrap_or_default();
+    let identical = replay_error.is_none() && transcript.response.as_deref() == replay_response.as_deref();
+    let text_diff = TextDiff::from_lines(original, replayed);
+    let diff = if identical {
+        String::new()
+    } else {
+        text_diff
+            .unified_diff()
+            .context_radius(DIFF_CONTEXT_LINES)
+            .header("original", "replay")
+            .to_string()
+    };
+
+    ReplayCallResult {
+        transcript_id: transcript.transcript_id,
+        call_kind: transcript.call_kind.clone(),
+        original_model: transcript.model.clone(),
+        replay_model: request.model,
+        original_response: transcript.response.as_deref().map(|text| redact_secrets(text).0),
+        replay_response: replay_response.as_deref().map(|text| redact_secrets(text).0),
+        replay_error,
+        identical,
+        similarity: text_diff.ratio(),
+        diff: redact_secrets(&diff).0,
+        original_tokens: transcript.tokens_used,
+        replay_tokens,
+        original_latency_ms: transcript.latency_ms,
+        replay_latency_ms,
+    }
+}
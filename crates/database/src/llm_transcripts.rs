@@ -0,0 +1,292 @@
+//! LLM调用记录
+//!
+//! 任务分解、计划评审、Agent执行和提示词评估中的每次LLM调用都保存为一条 `llm_transcripts` 记录：完整的提示词和输出、
+//! 提供方、模型、token数和耗时，以及所属的项目、任务、LLM会话、执行会话或桌面端对话；失败的调用保存错误信息。
+//! - [`RecordingProvider`] 包装任意 [`LlmProvider`]，每次调用结束后自动保存；
+//! - 不经过 [`LlmProvider`] 的调用（例如桌面端的Agent对话）直接使用 [`record_transcript`]；
+//! - [`list_transcripts`] 和 [`get_transcript`] 是查看接口，返回的提示词、输出和错误信息经过
+//!   [`redact_secrets`] 脱敏，原文只保存在数据库中。
+//!
+//! 保存记录失败只记录警告，不影响调用结果。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+use uuid::Uuid;
+
+use crate::{
+    entities::llm_transcript,
+    llm_provider::{LlmCompletion, LlmFuture, LlmProvider, LlmRequest},
+    repository::{
+        llm_transcript_repository::{CreateLlmTranscriptData, LlmTranscriptFilter},
+        LlmTranscriptRepository,
+    },
+    security_scan::redact_secrets,
+    DatabaseConnection, Result,
+};
+
+/// 查看接口默认返回的条数
+pub const DEFAULT_TRANSCRIPT_LIMIT: u64 = 100;
+
+/// LLM调用类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmCallKind {
+    /// 需求分解
+    Decomposition,
+    /// 计划或代码评审
+    Review,
+    /// Agent执行
+    AgentExecution,
+    /// 提示词评估
+    Evaluation,
+}
+
+impl LlmCallKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LlmCallKind::Decomposition => "decomposition",
+            LlmCallKind::Review => "review",
+            LlmCallKind::AgentExecution => "agent_execution",
+            LlmCallKind::Evaluation => "evaluation",
+        }
+    }
+}
+
+/// 调用记录的归属，为空的字段表示与该实体无关
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptLink {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub llm_session_id: Option<Uuid>,
+    pub execution_session_id: Option<Uuid>,
+    /// 桌面端对话ID
+    pub conversation_id: Option<String>,
+}
+
+impl TranscriptLink {
+    /// 只归属于项目
+    pub fn project(project_id: Uuid) -> Self {
+        Self {
+            project_id: Some(project_id),
+            ..Default::default()
+        }
+    }
+}
+
+/// 一次LLM调用的内容
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmCall {
+    pub provider: String,
+    pub model: String,
+    pub prompt: String,
+    /// 调用参数，格式见 [`LlmRequest::params`]
+    pub request_params: Option<JsonValue>,
+    /// 模型输出，调用失败时为空
+    pub response: Option<String>,
+    /// 调用失败的错误信息
+    pub error_message: Option<String>,
+    pub tokens_used: Option<u32>,
+    pub latency: Duration,
+}
+
+impl LlmCall {
+    /// 由提供方调用的请求和结果生成
+    pub fn from_result(provider: &str, request: &LlmRequest, result: &Result<LlmCompletion>, latency: Duration) -> Self {
+        let (response, error_message, tokens_used) = match result {
+            Ok(completion) => (Some(completion.content.clone()), None, Some(completion.tokens_used)),
+            Err(e) => (None, Some(e.to_string()), None),
+        };
+        Self {
+            provider: provider.to_string(),
+            model: request.model.clone(),
+            prompt: request.prompt.clone(),
+            request_params: Some(request.params()),
+            response,
+            error_message,
+            tokens_used,
+            latency,
+        }
+    }
+}
+
+/// 保存一次LLM调用
+pub async fn record_transcript(
+    db: &DatabaseConnection,
+    kind: LlmCallKind,
+    link: &TranscriptLink,
+    call: LlmCall,
+) -> Result<llm_transcript::Model> {
+    LlmTranscriptRepository::new(db.clone())
+        .create(CreateLlmTranscriptData {
+            call_kind: kind.as_str().to_string(),
+            provider: call.provider,
+            model: call.model,
+            prompt: call.prompt,
+            request_params: call.request_params,
+            response: call.response,
+            error_message: call.error_message,
+            tokens_used: call.tokens_used.map(|tokens| tokens.min(i32::MAX as u32) as i32),
+            latency_ms: call.latency.as_millis().min(i64::MAX as u128) as i64,
+            project_id: link.project_id,
+            task_id: link.task_id,
+            llm_session_id: link.llm_session_id,
+            execution_session_id: link.execution_session_id,
+            conversation_id: link.conversation_id.clone(),
+        })
+        .await
+}
+
+/// 保存每次调用的LLM提供方包装
+pub struct RecordingProvider<'a> {
+    inner: &'a dyn LlmProvider,
+    db: DatabaseConnection,
+    kind: LlmCallKind,
+    link: TranscriptLink,
+}
+
+impl<'a> RecordingProvider<'a> {
+    pub fn new(inner: &'a dyn LlmProvider, db: DatabaseConnection, kind: LlmCallKind) -> Self {
+        Self {
+            inner,
+            db,
+            kind,
+            link: TranscriptLink::default(),
+        }
+    }
+
+    /// 设置调用记录的归属
+    pub fn with_link(mut self, link: TranscriptLink) -> Self {
+        self.link = link;
+        self
+    }
+
+    async fn record(&self, request: &LlmRequest, result: &Result<LlmCompletion>, latency: Duration) {
+        let call = LlmCall::from_result(self.inner.name(), request, result, latency);
+        if let Err(e) = record_transcript(&self.db, self.kind, &self.link, call).await {
+            tracing::warn!("保存LLM调用记录失败（{}）: {}", self.kind.as_str(), e);
+        }
+    }
+}
+
+impl LlmProvider for RecordingProvider<'_> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn complete<'b>(&'b self, request: &'b LlmRequest) -> LlmFuture<'b> {
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = self.inner.complete(request).await;
+            self.record(request, &result, started.elapsed()).await;
+            result
+        })
+    }
+
+    fn complete_streaming<'b>(&'b self, request: &'b LlmRequest, tokens: UnboundedSender<String>) -> LlmFuture<'b> {
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = self.inner.complete_streaming(request, tokens).await;
+            self.record(request, &result, started.elapsed()).await;
+            result
+        })
+    }
+}
+
+/// 调用记录查询条件，为空的条件不参与过滤
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptQuery {
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    #[serde(default)]
+    pub task_id: Option<Uuid>,
+    #[serde(default)]
+    pub llm_session_id: Option<Uuid>,
+    #[serde(default)]
+    pub execution_session_id: Option<Uuid>,
+    #[serde(default)]
+    pub conversation_id: Option<String>,
+    #[serde(default)]
+    pub call_kind: Option<LlmCallKind>,
+    /// 最多返回的条数，默认 [`DEFAULT_TRANSCRIPT_LIMIT`]
+    #[serde(default)]
+    pub limit: Option<u64>,
+}
+
+/// 脱敏后的调用记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptView {
+    pub transcript_id: Uuid,
+    pub call_kind: String,
+    pub provider: String,
+    pub model: String,
+    pub prompt: String,
+    pub request_params: Option<JsonValue>,
+    pub response: Option<String>,
+    pub error_message: Option<String>,
+    pub tokens_used: Option<i32>,
+    pub latency_ms: i64,
+    pub link: TranscriptLink,
+    /// 被替换的密钥数量
+    pub redacted_secrets: usize,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl From<llm_transcript::Model> for TranscriptView {
+    fn from(model: llm_transcript::Model) -> Self {
+        let mut redacted_secrets = 0;
+        let mut redact = |text: &str| {
+            let (redacted, count) = redact_secrets(text);
+            redacted_secrets += count;
+            redacted
+        };
+        let prompt = redact(&model.prompt);
+        let response = model.response.as_deref().map(&mut redact);
+        let error_message = model.error_message.as_deref().map(&mut redact);
+        Self {
+            transcript_id: model.transcript_id,
+            call_kind: model.call_kind,
+            provider: model.provider,
+            model: model.model,
+            prompt,
+            request_params: model.request_params,
+            response,
+            error_message,
+            tokens_used: model.tokens_used,
+            latency_ms: model.latency_ms,
+            link: TranscriptLink {
+                project_id: model.project_id,
+                task_id: model.task_id,
+                llm_session_id: model.llm_session_id,
+                execution_session_id: model.execution_session_id,
+                conversation_id: model.conversation_id,
+            },
+            redacted_secrets,
+            created_at: model.created_at,
+        }
+    }
+}
+
+/// 查看调用记录（按时间倒序），内容已脱敏
+pub async fn list_transcripts(db: &DatabaseConnection, query: &TranscriptQuery) -> Result<Vec<TranscriptView>> {
+    let filter = LlmTranscriptFilter {
+        project_id: query.project_id,
+        task_id: query.task_id,
+        llm_session_id: query.llm_session_id,
+        execution_session_id: query.execution_session_id,
+        conversation_id: query.conversation_id.clone(),
+        call_kind: query.call_kind.map(|kind| kind.as_str().to_string()),
+        limit: Some(query.limit.unwrap_or(DEFAULT_TRANSCRIPT_LIMIT)),
+    };
+    let transcripts = LlmTranscriptRepository::new(db.clone()).find(&filter).await?;
+    Ok(transcripts.into_iter().map(TranscriptView::from).collect())
+}
+
+/// 查看单条调用记录，内容已脱敏
+pub async fn get_transcript(db: &DatabaseConnection, transcript_id: Uuid) -> Result<Option<TranscriptView>> {
+    Ok(LlmTranscriptRepository::new(db.clone())
+        .find_by_id(transcript_id)
+        .await?
+        .map(TranscriptView::from))
+}
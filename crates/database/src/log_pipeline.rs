@@ -0,0 +1,295 @@
+//! 执行日志到界面的背压管道
+//!
+//! 执行器产生日志的速度可能远超前端的渲染能力，逐条作为事件发送会让 webview 卡死。
+//! [`LogPipeline`] 位于执行器和事件发送之间：
+//! - 有界队列：[`LogPipeline::push`] 从不等待，队列满时新日志被丢弃，只按日志流累计条数；
+//! - 合并：同一日志流中连续重复的日志合并为一条，记录重复次数；
+//! - 批量发送：每隔 `flush_interval` 把每个日志流积累的日志作为一批交给发送函数，
+//!   单批最多保留最新的 `max_batch_lines` 条，被丢弃和挤出的条数随批次一起发送，由界面显示为一行摘要；
+//! - 指标：接收、发送、合并、丢弃的行数和批次数，见 [`LogPipelineMetrics`]。
+//!
+//! 管道与传输无关，桌面端的发送函数把 [`LogBatch`] 作为 Tauri 事件发出。
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::time::MissedTickBehavior;
+
+use crate::{DatabaseError, Result};
+
+/// 管道配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogPipelineConfig {
+    /// 等待合并的日志队列容量
+    pub capacity: usize,
+    /// 批量发送间隔
+    pub flush_interval: Duration,
+    /// 每个日志流单批最多保留的行数（合并后）
+    pub max_batch_lines: usize,
+}
+
+impl Default for LogPipelineConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 4096,
+            flush_interval: Duration::from_millis(100),
+            max_batch_lines: 500,
+        }
+    }
+}
+
+impl LogPipelineConfig {
+    /// 验证配置
+    pub fn validate(&self) -> Result<()> {
+        if self.capacity == 0 {
+            return Err(DatabaseError::validation("日志队列容量必须大于0"));
+        }
+        if self.flush_interval.is_zero() {
+            return Err(DatabaseError::validation("日志发送间隔必须大于0"));
+        }
+        if self.max_batch_lines == 0 {
+            return Err(DatabaseError::validation("单批日志行数必须大于0"));
+        }
+        Ok(())
+    }
+}
+
+/// 执行器产生的一行日志
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogLine {
+    /// 日志流标识，如执行会话 ID 或对话 ID，界面按它订阅
+    pub stream: String,
+    pub log_level: String,
+    pub event_type: String,
+    pub message: String,
+    pub timestamp_ms: i64,
+}
+
+/// 批次中的一行，`repeat` 大于 1 表示连续重复的日志被合并
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogBatchLine {
+    pub log_level: String,
+    pub event_type: String,
+    pub message: String,
+    /// 最后一次出现的时间
+    pub timestamp_ms: i64,
+    pub repeat: u64,
+}
+
+impl LogBatchLine {
+    fn same_content(&self, line: &LogLine) -> bool {
+        self.message == line.message && self.log_level == line.log_level && self.event_type == line.event_type
+    }
+}
+
+/// 一个日志流在一个发送间隔内的日志
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogBatch {
+    pub stream: String,
+    pub lines: Vec<LogBatchLine>,
+    /// 因背压被省略的行数，界面以一行摘要代替
+    pub omitted: u64,
+}
+
+/// 管道指标（行数按合并前计）
+///
+/// 管道关闭并完成最后一次发送后，`received == emitted + dropped`。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogPipelineMetrics {
+    /// 交给管道的行数
+    pub received: u64,
+    /// 随批次发出的行数，合并的行按重复次数计
+    pub emitted: u64,
+    /// 合并到上一行的行数
+    pub coalesced: u64,
+    /// 队列满或单批超限被丢弃的行数
+    pub dropped: u64,
+    /// 发出的批次数
+    pub batches: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    received: AtomicU64,
+    emitted: AtomicU64,
+    coalesced: AtomicU64,
+    dropped: AtomicU64,
+    batches: AtomicU64,
+}
+
+impl Counters {
+    fn snapshot(&self) -> LogPipelineMetrics {
+        LogPipelineMetrics {
+            received: self.received.load(Ordering::Relaxed),
+            emitted: self.emitted.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            batches: self.batches.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 管道的发送端，可以克隆给多个执行器
+#[derive(Clone)]
+pub struct LogPipeline {
+    sender: mpsc::Sender<LogLine>,
+    counters: Arc<Counters>,
+    /// 队列满时按日志流累计的丢弃条数，下一次发送时并入批次
+    overflow: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+impl LogPipeline {
+    /// 创建管道，返回发送端和发送任务；`emit` 在发送任务中依次收到每个批次
+    ///
+    /// 发送任务由调用方放到自己的运行时上执行；所有 [`LogPipeline`] 克隆都被丢弃后，
+    /// 发送任务发出剩余日志并结束。
+    pub fn new<F>(config: LogPipelineConfig, emit: F) -> Result<(Self, impl Future<Output = ()> + Send + 'static)>
+    where
+        F: FnMut(LogBatch) + Send + 'static,
+    {
+        config.validate()?;
+        let (sender, receiver) = mpsc::channel(config.capacity);
+        let pipeline = Self {
+            sender,
+            counters: Arc::new(Counters::default()),
+            overflow: Arc::new(Mutex::new(HashMap::new())),
+        };
+        let coalescer = Coalescer::new(config.max_batch_lines, pipeline.counters.clone());
+        let task = run(receiver, config.flush_interval, coalescer, pipeline.overflow.clone(), emit);
+        Ok((pipeline, task))
+    }
+
+    /// 提交一行日志，不等待；队列已满或管道已关闭时丢弃并返回 false
+    pub fn push(&self, line: LogLine) -> bool {
+        self.counters.received.fetch_add(1, Ordering::Relaxed);
+        match self.sender.try_send(line) {
+            Ok(()) => true,
+            Err(TrySendError::Full(line)) => {
+                self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                *self.overflow.lock().unwrap().entry(line.stream).or_default() += 1;
+                false
+            }
+            Err(TrySendError::Closed(_)) => {
+                self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+        }
+    }
+
+    /// 当前指标
+    pub fn metrics(&self) -> LogPipelineMetrics {
+        self.counters.snapshot()
+    }
+}
+
+async fn run<F>(
+    mut receiver: mpsc::Receiver<LogLine>,
+    flush_interval: Duration,
+    mut coalescer: Coalescer,
+    overflow: Arc<Mutex<HashMap<String, u64>>>,
+    mut emit: F,
+) where
+    F: FnMut(LogBatch),
+{
+    let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + flush_interval, flush_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            line = receiver.recv() => match line {
+                Some(line) => coalescer.push(line),
+                None => break,
+            },
+            _ = interval.tick() => {
+                let dropped = std::mem::take(&mut *overflow.lock().unwrap());
+                for batch in coalescer.take_batches(dropped) {
+                    emit(batch);
+                }
+            }
+        }
+    }
+    let dropped = std::mem::take(&mut *overflow.lock().unwrap());
+    for batch in coalescer.take_batches(dropped) {
+        emit(batch);
+    }
+}
+
+#[derive(Default)]
+struct StreamBuffer {
+    lines: VecDeque<LogBatchLine>,
+    omitted: u64,
+}
+
+/// 按日志流合并日志，并限制每个流缓存的行数
+struct Coalescer {
+    max_lines: usize,
+    counters: Arc<Counters>,
+    /// 按首次出现顺序排列的日志流
+    streams: Vec<(String, StreamBuffer)>,
+}
+
+impl Coalescer {
+    fn new(max_lines: usize, counters: Arc<Counters>) -> Self {
+        Self { max_lines, counters, streams: Vec::new() }
+    }
+
+    fn buffer(&mut self, stream: &str) -> &mut StreamBuffer {
+        let index = match self.streams.iter().position(|(name, _)| name == stream) {
+            Some(index) => index,
+            None => {
+                self.streams.push((stream.to_string(), StreamBuffer::default()));
+                self.streams.len() - 1
+            }
+        };
+        &mut self.streams[index].1
+    }
+
+    fn push(&mut self, line: LogLine) {
+        let max_lines = self.max_lines;
+        let counters = self.counters.clone();
+        let buffer = self.buffer(&line.stream);
+        if let Some(last) = buffer.lines.back_mut().filter(|last| last.same_content(&line)) {
+            last.repeat += 1;
+            last.timestamp_ms = line.timestamp_ms;
+            counters.coalesced.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        buffer.lines.push_back(LogBatchLine {
+            log_level: line.log_level,
+            event_type: line.event_type,
+            message: line.message,
+            timestamp_ms: line.timestamp_ms,
+            repeat: 1,
+        });
+        // 超出单批上限时挤出最旧的行，保留最新的输出
+        if buffer.lines.len() > max_lines {
+            if let Some(evicted) = buffer.lines.pop_front() {
+                buffer.omitted += evicted.repeat;
+                counters.dropped.fetch_add(evicted.repeat, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// 取出所有待发送的批次，`dropped` 是队列满时各日志流丢弃的条数
+    fn take_batches(&mut self, dropped: HashMap<String, u64>) -> Vec<LogBatch> {
+        for (stream, count) in dropped {
+            self.buffer(&stream).omitted += count;
+        }
+        let batches: Vec<LogBatch> = std::mem::take(&mut self.streams)
+            .into_iter()
+            .filter(|(_, buffer)| !buffer.lines.is_empty() || buffer.omitted > 0)
+            .map(|(stream, buffer)| LogBatch {
+                stream,
+                lines: buffer.lines.into(),
+                omitted: buffer.omitted,
+            })
+            .collect();
+        let emitted: u64 = batches.iter().flat_map(|batch| &batch.lines).map(|line| line.repeat).sum();
+        self.counters.emitted.fetch_add(emitted, Ordering::Relaxed);
+        self.counters.batches.fetch_add(batches.len() as u64, Ordering::Relaxed);
+        batches
+    }
+}
@@ -0,0 +1,518 @@
+//! 定期维护任务
+//!
+//! 维护任务（`maintenance_jobs`）按 cron 表达式定期执行数据库整理、日志清理、快照等系统维护。
+//! [`MaintenanceRunner`] 持有按任务类型注册的 [`MaintenanceJobHandler`]：
+//! - 调度方定期调用 [`MaintenanceRunner::run_due`] 执行到期的任务，[`MaintenanceRunner::trigger`] 用于手动执行；
+//! - 执行前以条件更新占用任务的执行锁，同一任务不会重叠执行，锁超过 [`LOCK_TIMEOUT`] 未释放时可被接管；
+//! - 下一次执行时间在 cron 触发时间上随机延后 `jitter_secs` 以内，避免多个实例同时执行；
+//! - 每次执行（包括因重叠而跳过的调度）都记录在 `maintenance_job_runs`。
+//!
+//! 内置任务类型为 [`JOB_VACUUM`]、[`JOB_PRUNE`]、[`JOB_SNAPSHOT`] 和每日指标汇总
+//! [`JOB_METRICS_ROLLUP`]，其他模块可以注册自己的处理器（如大字段转存 [`JOB_PAYLOAD_OFFLOAD`]），
+//! 再用 [`MaintenanceRunner::ensure_jobs`] 登记默认计划。
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseBackend, EntityTrait, QueryFilter, Statement};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use uuid::Uuid;
+
+use crate::entities::{event_publish_log, execution_log, maintenance_job, maintenance_job_run};
+use crate::metrics_rollup::{MetricsRollupJob, DEFAULT_BACKFILL_DAYS};
+use crate::payload_storage::DEFAULT_OFFLOAD_BATCH;
+use crate::reporting::next_run;
+use crate::repository::maintenance_job_repository::MaintenanceJobData;
+use crate::repository::{
+    IdempotencyKeyRepository, LlmCacheRepository, MaintenanceJobRepository, MaintenanceJobRunRepository,
+    UserSessionRepository,
+};
+use crate::{DatabaseConnection, DatabaseError, Result};
+
+pub use crate::metrics_rollup::JOB_METRICS_ROLLUP;
+pub use crate::payload_storage::JOB_PAYLOAD_OFFLOAD;
+
+/// 数据库整理（SQLite `VACUUM`）
+pub const JOB_VACUUM: &str = "vacuum";
+
+/// 清理过期日志和记录
+pub const JOB_PRUNE: &str = "prune";
+
+/// 数据库快照（SQLite `VACUUM INTO`）
+pub const JOB_SNAPSHOT: &str = "snapshot";
+
+/// 执行锁的超时，超过后视为执行进程已退出
+pub const LOCK_TIMEOUT: chrono::Duration = chrono::Duration::hours(6);
+
+/// 清理任务默认的保留天数
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+/// 快照任务默认保留的快照数
+pub const DEFAULT_SNAPSHOT_KEEP: usize = 7;
+
+/// 快照文件名前缀
+const SNAPSHOT_PREFIX: &str = "sker-snapshot-";
+
+/// 维护任务处理的异步结果，返回值写入执行记录
+pub type MaintenanceFuture<'a> = Pin<Box<dyn Future<Output = Result<JsonValue>> + Send + 'a>>;
+
+/// 维护任务处理器
+pub trait MaintenanceJobHandler: Send + Sync {
+    /// 任务类型，在执行器中唯一
+    fn kind(&self) -> &str;
+
+    /// 执行一次维护，`params` 为任务配置的参数
+    fn run<'a>(&'a self, db: &'a DatabaseConnection, params: &'a JsonValue, now: DateTime<Utc>) -> MaintenanceFuture<'a>;
+}
+
+/// 触发方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunTrigger {
+    /// 按计划执行
+    Schedule,
+    /// 手动执行
+    Manual,
+}
+
+impl RunTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunTrigger::Schedule => "schedule",
+            RunTrigger::Manual => "manual",
+        }
+    }
+}
+
+/// 执行状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Running,
+    Succeeded,
+    Failed,
+    /// 上一次执行尚未结束，本次调度跳过
+    Skipped,
+}
+
+impl RunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunStatus::Running => "running",
+            RunStatus::Succeeded => "succeeded",
+            RunStatus::Failed => "failed",
+            RunStatus::Skipped => "skipped",
+        }
+    }
+}
+
+/// 维护任务的计划
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceJobSpec {
+    pub name: String,
+    pub kind: String,
+    /// cron 表达式（秒 分 时 日 月 周）
+    pub cron_expression: String,
+    #[serde(default)]
+    pub jitter_secs: u32,
+    #[serde(default = "empty_params")]
+    pub params: JsonValue,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn empty_params() -> JsonValue {
+    json!({})
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 内置任务的默认计划：每天凌晨汇总前一天的指标、转存大字段、清理和快照，每周日整理数据库
+///
+/// 指标汇总排在清理之前，被清理的执行日志已计入汇总；转存任务需要调用方注册处理器后才会登记
+pub fn default_jobs() -> Vec<MaintenanceJobSpec> {
+    vec![
+        MaintenanceJobSpec {
+            name: JOB_METRICS_ROLLUP.to_string(),
+            kind: JOB_METRICS_ROLLUP.to_string(),
+            cron_expression: "0 30 0 * * *".to_string(),
+            jitter_secs: 600,
+            params: json!({ "backfill_days": DEFAULT_BACKFILL_DAYS }),
+            enabled: true,
+        },
+        MaintenanceJobSpec {
+            name: JOB_PAYLOAD_OFFLOAD.to_string(),
+            kind: JOB_PAYLOAD_OFFLOAD.to_string(),
+            cron_expression: "0 0 2 * * *".to_string(),
+            jitter_secs: 600,
+            params: json!({ "limit": DEFAULT_OFFLOAD_BATCH }),
+            enabled: true,
+        },
+        MaintenanceJobSpec {
+            name: JOB_PRUNE.to_string(),
+            kind: JOB_PRUNE.to_string(),
+            cron_expression: "0 0 3 * * *".to_string(),
+            jitter_secs: 600,
+            params: json!({ "retention_days": DEFAULT_RETENTION_DAYS }),
+            enabled: true,
+        },
+        MaintenanceJobSpec {
+            name: JOB_SNAPSHOT.to_string(),
+            kind: JOB_SNAPSHOT.to_string(),
+            cron_expression: "0 30 3 * * *".to_string(),
+            jitter_secs: 600,
+            params: json!({ "keep": DEFAULT_SNAPSHOT_KEEP }),
+            enabled: true,
+        },
+        MaintenanceJobSpec {
+            name: JOB_VACUUM.to_string(),
+            kind: JOB_VACUUM.to_string(),
+            cron_expression: "0 0 4 * * Sun".to_string(),
+            jitter_secs: 1800,
+            params: json!({}),
+            enabled: true,
+        },
+    ]
+}
+
+/// 维护任务执行器
+pub struct MaintenanceRunner {
+    db: DatabaseConnection,
+    handlers: HashMap<String, Arc<dyn MaintenanceJobHandler>>,
+}
+
+impl MaintenanceRunner {
+    /// 创建没有任何处理器的执行器
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db, handlers: HashMap::new() }
+    }
+
+    /// 创建注册了内置处理器的执行器，未指定快照目录时不注册快照任务
+    pub fn with_builtin_jobs(db: DatabaseConnection, snapshot_dir: Option<PathBuf>) -> Self {
+        let mut runner = Self::new(db);
+        runner.register(Arc::new(VacuumJob));
+        runner.register(Arc::new(PruneJob));
+        runner.register(Arc::new(MetricsRollupJob));
+        if let Some(directory) = snapshot_dir {
+            runner.register(Arc::new(SnapshotJob::new(directory)));
+        }
+        runner
+    }
+
+    /// 注册处理器，同类型的处理器被替换
+    pub fn register(&mut self, handler: Arc<dyn MaintenanceJobHandler>) {
+        self.handlers.insert(handler.kind().to_string(), handler);
+    }
+
+    /// 已注册的任务类型
+    pub fn kinds(&self) -> Vec<String> {
+        let mut kinds: Vec<String> = self.handlers.keys().cloned().collect();
+        kinds.sort();
+        kinds
+    }
+
+    /// 登记尚不存在的任务，已存在的同名任务保留原有计划；没有处理器的任务类型被忽略
+    pub async fn ensure_jobs(&self, specs: Vec<MaintenanceJobSpec>, now: DateTime<Utc>) -> Result<Vec<maintenance_job::Model>> {
+        let jobs = MaintenanceJobRepository::new(self.db.clone());
+        let mut created = Vec::new();
+        for spec in specs {
+            if !self.handlers.contains_key(&spec.kind) || jobs.find_by_name(&spec.name).await?.is_some() {
+                continue;
+            }
+            created.push(self.create_job(spec, now).await?);
+        }
+        Ok(created)
+    }
+
+    /// 创建维护任务
+    pub async fn create_job(&self, spec: MaintenanceJobSpec, now: DateTime<Utc>) -> Result<maintenance_job::Model> {
+        if spec.name.trim().is_empty() {
+            return Err(DatabaseError::validation("维护任务名称不能为空"));
+        }
+        if !self.handlers.contains_key(&spec.kind) {
+            return Err(DatabaseError::validation(format!("未注册的维护任务类型: {}", spec.kind)));
+        }
+        let jitter_secs = jitter_secs(spec.jitter_secs)?;
+        let next_run_at = next_run_with_jitter(&spec.cron_expression, jitter_secs, now)?;
+        MaintenanceJobRepository::new(self.db.clone())
+            .create(
+                MaintenanceJobData {
+                    name: spec.name,
+                    kind: spec.kind,
+                    cron_expression: spec.cron_expression,
+                    jitter_secs,
+                    params: spec.params,
+                    enabled: spec.enabled,
+                },
+                next_run_at,
+            )
+            .await
+    }
+
+    /// 更新任务的执行计划，下一次执行时间按新的 cron 表达式重新计算
+    pub async fn update_schedule(
+        &self,
+        job_id: Uuid,
+        cron_expression: String,
+        jitter: u32,
+        enabled: bool,
+        now: DateTime<Utc>,
+    ) -> Result<maintenance_job::Model> {
+        let jitter = jitter_secs(jitter)?;
+        let next_run_at = next_run_with_jitter(&cron_expression, jitter, now)?;
+        MaintenanceJobRepository::new(self.db.clone())
+            .update_schedule(job_id, cron_expression, jitter, enabled, next_run_at)
+            .await
+    }
+
+    /// 执行所有到期的任务，返回执行记录
+    pub async fn run_due(&self, now: DateTime<Utc>) -> Result<Vec<maintenance_job_run::Model>> {
+        let due = MaintenanceJobRepository::new(self.db.clone()).find_due(now).await?;
+        let mut runs = Vec::with_capacity(due.len());
+        for job in due {
+            runs.push(self.execute(job, RunTrigger::Schedule, now).await?);
+        }
+        Ok(runs)
+    }
+
+    /// 手动执行任务，任务正在执行时返回错误
+    pub async fn trigger(&self, job_id: Uuid, now: DateTime<Utc>) -> Result<maintenance_job_run::Model> {
+        let job = MaintenanceJobRepository::new(self.db.clone())
+            .find_by_id(job_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("MaintenanceJob", job_id))?;
+        self.execute(job, RunTrigger::Manual, now).await
+    }
+
+    async fn execute(
+        &self,
+        job: maintenance_job::Model,
+        trigger: RunTrigger,
+        now: DateTime<Utc>,
+    ) -> Result<maintenance_job_run::Model> {
+        let jobs = MaintenanceJobRepository::new(self.db.clone());
+        let runs = MaintenanceJobRunRepository::new(self.db.clone());
+        // 按计划执行时无论结果如何都推进下一次执行时间，手动执行不影响计划
+        let next_run_at = match trigger {
+            RunTrigger::Schedule => match next_run_with_jitter(&job.cron_expression, job.jitter_secs, now) {
+                Ok(next_run_at) => Some(next_run_at),
+                Err(e) => {
+                    tracing::warn!("维护任务 {} 的cron表达式无效: {}", job.name, e);
+                    None
+                }
+            },
+            RunTrigger::Manual => None,
+        };
+
+        if !jobs.try_lock(job.job_id, now, now - LOCK_TIMEOUT).await? {
+            if trigger == RunTrigger::Manual {
+                return Err(DatabaseError::business_logic("维护任务正在执行，请稍后再试"));
+            }
+            if let Some(next_run_at) = next_run_at {
+                jobs.set_next_run(job.job_id, next_run_at).await?;
+            }
+            let run = runs
+                .create(job.job_id, trigger.as_str(), RunStatus::Skipped.as_str(), now, Some("上一次执行尚未结束".to_string()))
+                .await?;
+            return Ok(run);
+        }
+        if job.running_since.is_some() {
+            runs.fail_running(job.job_id, now, "执行超时，执行锁已被接管").await?;
+        }
+
+        let run = runs.create(job.job_id, trigger.as_str(), RunStatus::Running.as_str(), now, None).await?;
+        let started = Instant::now();
+        let outcome = match self.handlers.get(&job.kind) {
+            Some(handler) => handler.run(&self.db, &job.params, now).await,
+            None => Err(DatabaseError::validation(format!("未注册的维护任务类型: {}", job.kind))),
+        };
+        let elapsed = started.elapsed();
+        let finished_at = now + chrono::Duration::from_std(elapsed).unwrap_or_default();
+        let duration_ms = elapsed.as_millis() as i64;
+
+        let (status, result, error_message) = match outcome {
+            Ok(result) => (RunStatus::Succeeded, Some(result), None),
+            Err(e) => {
+                tracing::warn!("维护任务 {} 执行失败: {}", job.name, e);
+                (RunStatus::Failed, None, Some(e.to_string()))
+            }
+        };
+        let run = runs.finish(run.run_id, status.as_str(), finished_at, duration_ms, result, error_message).await?;
+        jobs.finish_run(job.job_id, status.as_str(), now, next_run_at).await?;
+        tracing::info!(job = %job.name, trigger = trigger.as_str(), status = status.as_str(), duration_ms, "维护任务执行结束");
+        Ok(run)
+    }
+}
+
+fn jitter_secs(jitter: u32) -> Result<i32> {
+    i32::try_from(jitter).map_err(|_| DatabaseError::validation(format!("随机延后时间过长: {} 秒", jitter)))
+}
+
+/// cron 触发时间加上 `[0, jitter_secs]` 内的随机延后
+pub fn next_run_with_jitter(cron_expression: &str, jitter_secs: i32, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let next = next_run(cron_expression, after)?;
+    if jitter_secs <= 0 {
+        return Ok(next);
+    }
+    let offset = (Uuid::new_v4().as_u128() % (jitter_secs as u128 + 1)) as i64;
+    Ok(next + chrono::Duration::seconds(offset))
+}
+
+/// 整理 SQLite 数据库，回收已删除数据占用的空间
+pub struct VacuumJob;
+
+impl MaintenanceJobHandler for VacuumJob {
+    fn kind(&self) -> &str {
+        JOB_VACUUM
+    }
+
+    fn run<'a>(&'a self, db: &'a DatabaseConnection, _params: &'a JsonValue, _now: DateTime<Utc>) -> MaintenanceFuture<'a> {
+        Box::pin(async move {
+            require_sqlite(db, JOB_VACUUM)?;
+            let pages_before = pragma_value(db, "page_count").await?;
+            db.execute_unprepared("VACUUM").await?;
+            let pages_after = pragma_value(db, "page_count").await?;
+            Ok(json!({
+                "pages_before": pages_before,
+                "pages_after": pages_after,
+                "page_size": pragma_value(db, "page_size").await?,
+            }))
+        })
+    }
+}
+
+/// 清理超过保留期的日志和已过期的记录
+///
+/// 参数 `retention_days`（默认 [`DEFAULT_RETENTION_DAYS`]）控制执行日志、事件发布日志和维护执行记录的保留天数；
+/// 过期的登录会话、幂等键和LLM缓存条目按各自的过期时间删除
+pub struct PruneJob;
+
+impl MaintenanceJobHandler for PruneJob {
+    fn kind(&self) -> &str {
+        JOB_PRUNE
+    }
+
+    fn run<'a>(&'a self, db: &'a DatabaseConnection, params: &'a JsonValue, now: DateTime<Utc>) -> MaintenanceFuture<'a> {
+        Box::pin(async move {
+            let retention_days = params.get("retention_days").and_then(JsonValue::as_i64).unwrap_or(DEFAULT_RETENTION_DAYS);
+            if retention_days < 1 {
+                return Err(DatabaseError::validation("保留天数至少为1天"));
+            }
+            let cutoff = now - chrono::Duration::days(retention_days);
+
+            let execution_logs = execution_log::Entity::delete_many()
+                .filter(execution_log::Column::CreatedAt.lt(cutoff))
+                .exec(db)
+                .await?
+                .rows_affected;
+            let event_publish_logs = event_publish_log::Entity::delete_many()
+                .filter(event_publish_log::Column::CreatedAt.lt(cutoff))
+                .exec(db)
+                .await?
+                .rows_affected;
+            let maintenance_runs = MaintenanceJobRunRepository::new(db.clone()).delete_finished_before(cutoff).await?;
+            let user_sessions = UserSessionRepository::new(db.clone()).cleanup_expired_sessions().await?;
+            let idempotency_keys = IdempotencyKeyRepository::new(db.clone()).delete_expired(now).await?;
+            let llm_cache_entries = LlmCacheRepository::new(db.clone()).delete_expired(now).await?;
+
+            Ok(json!({
+                "retention_days": retention_days,
+                "execution_logs": execution_logs,
+                "event_publish_logs": event_publish_logs,
+                "maintenance_runs": maintenance_runs,
+                "user_sessions": user_sessions,
+                "idempotency_keys": idempotency_keys,
+                "llm_cache_entries": llm_cache_entries,
+            }))
+        })
+    }
+}
+
+/// 把 SQLite 数据库快照写入目录，只保留最近的若干份
+///
+/// 参数 `keep`（默认 [`DEFAULT_SNAPSHOT_KEEP`]）为保留的快照数
+pub struct SnapshotJob {
+    directory: PathBuf,
+}
+
+impl SnapshotJob {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+}
+
+impl MaintenanceJobHandler for SnapshotJob {
+    fn kind(&self) -> &str {
+        JOB_SNAPSHOT
+    }
+
+    fn run<'a>(&'a self, db: &'a DatabaseConnection, params: &'a JsonValue, now: DateTime<Utc>) -> MaintenanceFuture<'a> {
+        Box::pin(async move {
+            require_sqlite(db, JOB_SNAPSHOT)?;
+            let keep = params.get("keep").and_then(JsonValue::as_u64).map_or(DEFAULT_SNAPSHOT_KEEP, |keep| keep as usize);
+            if keep == 0 {
+                return Err(DatabaseError::validation("至少保留一份快照"));
+            }
+            tokio::fs::create_dir_all(&self.directory).await?;
+
+            // 文件名按时间排序，同一秒内的重复快照追加随机后缀
+            let mut path = self.directory.join(format!("{}{}.db", SNAPSHOT_PREFIX, now.format("%Y%m%d%H%M%S")));
+            if tokio::fs::try_exists(&path).await? {
+                let suffix = &Uuid::new_v4().simple().to_string()[..8];
+                path = self.directory.join(format!("{}{}-{}.db", SNAPSHOT_PREFIX, now.format("%Y%m%d%H%M%S"), suffix));
+            }
+            let target = path.to_string_lossy().replace('\'', "''");
+            db.execute_unprepared(&format!("VACUUM INTO '{}'", target)).await?;
+            // 内存数据库的 VACUUM INTO 不会写出文件
+            let size_bytes = match tokio::fs::metadata(&path).await {
+                Ok(metadata) => metadata.len(),
+                Err(_) => return Err(DatabaseError::business_logic("数据库快照未生成，内存数据库不支持快照")),
+            };
+
+            let mut snapshots = Vec::new();
+            let mut entries = tokio::fs::read_dir(&self.directory).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if name.starts_with(SNAPSHOT_PREFIX) && name.ends_with(".db") {
+                    snapshots.push(name);
+                }
+            }
+            snapshots.sort();
+            let mut removed = Vec::new();
+            while snapshots.len() > keep {
+                let name = snapshots.remove(0);
+                tokio::fs::remove_file(self.directory.join(&name)).await?;
+                removed.push(name);
+            }
+
+            Ok(json!({
+                "path": path.to_string_lossy(),
+                "size_bytes": size_bytes,
+                "removed": removed,
+            }))
+        })
+    }
+}
+
+fn require_sqlite(db: &DatabaseConnection, kind: &str) -> Result<()> {
+    if db.get_database_backend() != DatabaseBackend::Sqlite {
+        return Err(DatabaseError::validation(format!("维护任务 {} 只支持 SQLite 数据库", kind)));
+    }
+    Ok(())
+}
+
+async fn pragma_value(db: &DatabaseConnection, pragma: &str) -> Result<i64> {
+    let row = db
+        .query_one(Statement::from_string(DatabaseBackend::Sqlite, format!("PRAGMA {}", pragma)))
+        .await?
+        .ok_or_else(|| DatabaseError::business_logic(format!("PRAGMA {} 没有返回结果", pragma)))?;
+    Ok(row.try_get_by_index::<i64>(0)?)
+}
@@ -0,0 +1,426 @@
+//! 数据库实体与协议结构之间的类型化转换
+//!
+//! 实体把枚举存为字符串、把列表和配置存为 JSON 列，协议层（codex-multi-agent）使用强类型结构。
+//! 本模块集中两者之间的双向转换：
+//! - 实体 → 协议：`TryFrom<&Model>`，列值无法解码时返回 [`DatabaseError::ColumnDecode`]，
+//!   错误中带有实体和列名，不再静默回退为默认值；
+//! - 协议 → 实体：生成仓储的创建数据或待写入的列值。
+//!
+//! 协议结构中实体没有存储的字段（如任务依赖、团队成员）在转换时取空值，需要时由调用方另行填充。
+
+use codex_multi_agent::{
+    agent_management::ResourceLimits,
+    llm_orchestration::{ComplexityAssessment, TaskTestRequirements},
+    project_management::{ProjectPriority, ProjectType},
+    AgentApprovalPolicy, AgentCapability, AgentConfig, AgentId, AgentStatus, AgentSummary, CodingStandards,
+    ConflictSeverity, ConflictType, GitConfig, ProjectInfo, ReviewPriority, ReviewResult, TaskId, TaskInfo,
+    TaskPriority, TaskStatus, TaskType,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use crate::{
+    entities::{agent, code_review, conflict, project, task},
+    repository::{
+        agent_repository::CreateAgentData, project_repository::CreateProjectData, task_repository::CreateTaskData,
+    },
+    DatabaseError, Result,
+};
+
+/// 实体未记录版本号时项目使用的版本
+pub const DEFAULT_PROJECT_VERSION: &str = "0.1.0";
+
+/// Agent配置未指定时的最大并发任务数
+pub const DEFAULT_MAX_CONCURRENT_TASKS: u32 = 1;
+
+/// Agent配置未指定时的任务超时时间（分钟）
+pub const DEFAULT_TIMEOUT_MINUTES: u32 = 60;
+
+/// 实体未记录复杂度时使用的中等评分
+const DEFAULT_COMPLEXITY: u8 = 3;
+
+// ============================================================================
+// 列值编解码
+// ============================================================================
+
+/// 把 JSON 列解码为指定类型
+pub fn decode_json<T: DeserializeOwned>(entity: &str, column: &str, value: &JsonValue) -> Result<T> {
+    T::deserialize(value).map_err(|e| DatabaseError::column_decode(entity, column, e))
+}
+
+/// 把可空的 JSON 列解码为指定类型，列为空时返回默认值
+pub fn decode_optional_json<T: DeserializeOwned + Default>(
+    entity: &str,
+    column: &str,
+    value: Option<&JsonValue>,
+) -> Result<T> {
+    match value {
+        None | Some(JsonValue::Null) => Ok(T::default()),
+        Some(value) => decode_json(entity, column, value),
+    }
+}
+
+/// 把字符串列解码为 snake_case 序列化的协议枚举
+pub fn decode_enum<T: DeserializeOwned>(entity: &str, column: &str, value: &str) -> Result<T> {
+    T::deserialize(JsonValue::String(value.to_string()))
+        .map_err(|_| DatabaseError::column_decode(entity, column, format!("未知的取值 {}", value)))
+}
+
+/// 把协议枚举编码为字符串列
+pub fn encode_enum<T: Serialize>(value: &T) -> Result<String> {
+    match serde_json::to_value(value)? {
+        JsonValue::String(text) => Ok(text),
+        other => Err(DatabaseError::validation(format!("枚举值 {} 不能存为字符串列", other))),
+    }
+}
+
+/// 把 JSON 列中的 UUID 字符串列表解码为强类型ID
+fn decode_ids<T: From<Uuid>>(entity: &str, column: &str, value: &JsonValue) -> Result<Vec<T>> {
+    let ids: Vec<Uuid> = decode_json(entity, column, value)?;
+    Ok(ids.into_iter().map(T::from).collect())
+}
+
+// ============================================================================
+// 项目
+// ============================================================================
+
+impl TryFrom<&project::Model> for ProjectInfo {
+    type Error = DatabaseError;
+
+    fn try_from(project: &project::Model) -> Result<Self> {
+        Ok(Self {
+            name: project.name.clone(),
+            description: project.description.clone().unwrap_or_default(),
+            version: DEFAULT_PROJECT_VERSION.to_string(),
+            repository_url: project.repository_url.clone(),
+            main_branch: project.main_branch.clone(),
+            technology_stack: project_technology_stack(project)?,
+            coding_standards: decode_optional_json::<Option<CodingStandards>>(
+                "project",
+                "coding_standards",
+                project.coding_standards.as_ref(),
+            )?
+            .unwrap_or_default(),
+            workspace_path: PathBuf::from(&project.workspace_path),
+            project_type: ProjectType::FullStackApplication,
+            priority: ProjectPriority::Medium,
+            target_completion_date: None,
+            owner: project.user_id.to_string(),
+            team_members: Vec::new(),
+            tags: Vec::new(),
+            external_dependencies: Vec::new(),
+            environments: HashMap::new(),
+        })
+    }
+}
+
+/// 解码项目的技术栈列
+pub fn project_technology_stack(project: &project::Model) -> Result<Vec<String>> {
+    decode_optional_json("project", "technology_stack", project.technology_stack.as_ref())
+}
+
+/// 由协议项目信息生成创建数据
+pub fn project_create_data(user_id: Uuid, info: &ProjectInfo) -> CreateProjectData {
+    CreateProjectData {
+        user_id,
+        name: info.name.clone(),
+        description: (!info.description.is_empty()).then(|| info.description.clone()),
+        repository_url: info.repository_url.clone(),
+        workspace_path: info.workspace_path.to_string_lossy().into_owned(),
+    }
+}
+
+/// 项目配置列的待写入值，对应 `ProjectRepository::update_config` 的参数
+#[derive(Debug, Clone)]
+pub struct ProjectConfigColumns {
+    pub technology_stack: JsonValue,
+    pub coding_standards: JsonValue,
+}
+
+/// 由协议项目信息生成配置列的值
+pub fn project_config_columns(info: &ProjectInfo) -> Result<ProjectConfigColumns> {
+    Ok(ProjectConfigColumns {
+        technology_stack: serde_json::to_value(&info.technology_stack)?,
+        coding_standards: serde_json::to_value(&info.coding_standards)?,
+    })
+}
+
+// ============================================================================
+// 任务
+// ============================================================================
+
+impl TryFrom<&task::Model> for TaskInfo {
+    type Error = DatabaseError;
+
+    fn try_from(task: &task::Model) -> Result<Self> {
+        Ok(Self {
+            task_id: TaskId::from(task.task_id),
+            title: task.title.clone(),
+            description: task.description.clone(),
+            task_type: decode_enum("task", "task_type", &task.task_type)?,
+            priority: decode_enum("task", "priority", &task.priority)?,
+            estimated_hours: task.estimated_hours.unwrap_or(0).max(0) as u32,
+            required_capabilities: task_required_capabilities(task)?,
+            dependencies: Vec::new(),
+            acceptance_criteria: crate::acceptance::task_criteria(task),
+            tags: Vec::new(),
+            related_files: task.scope_path.iter().cloned().collect(),
+            test_requirements: TaskTestRequirements {
+                needs_unit_tests: false,
+                needs_integration_tests: false,
+                needs_e2e_tests: false,
+                required_coverage: 0.0,
+                special_test_scenarios: Vec::new(),
+            },
+            complexity_assessment: ComplexityAssessment {
+                technical_complexity: DEFAULT_COMPLEXITY,
+                business_complexity: DEFAULT_COMPLEXITY,
+                integration_complexity: DEFAULT_COMPLEXITY,
+                overall_complexity: DEFAULT_COMPLEXITY,
+                complexity_notes: Vec::new(),
+            },
+            risk_factors: Vec::new(),
+            subtasks: Vec::new(),
+            related_issues: Vec::new(),
+            ci_status: None,
+        })
+    }
+}
+
+/// 解码任务状态列
+pub fn task_status(task: &task::Model) -> Result<TaskStatus> {
+    decode_enum("task", "status", &task.status)
+}
+
+/// 解码任务的所需能力列
+pub fn task_required_capabilities(task: &task::Model) -> Result<Vec<AgentCapability>> {
+    decode_optional_json("task", "required_capabilities", task.required_capabilities.as_ref())
+}
+
+/// 由协议任务信息生成创建数据
+pub fn task_create_data(project_id: Uuid, parent_task_id: Option<Uuid>, info: &TaskInfo) -> Result<CreateTaskData> {
+    Ok(CreateTaskData {
+        project_id,
+        parent_task_id,
+        llm_session_id: None,
+        title: info.title.clone(),
+        description: info.description.clone(),
+        task_type: encode_enum(&info.task_type)?,
+    })
+}
+
+/// 任务需求列的待写入值，对应 `TaskRepository::update_requirements` 的参数
+#[derive(Debug, Clone)]
+pub struct TaskRequirementColumns {
+    pub required_capabilities: JsonValue,
+    pub acceptance_criteria: JsonValue,
+}
+
+/// 由协议任务信息生成需求列的值
+pub fn task_requirement_columns(info: &TaskInfo) -> Result<TaskRequirementColumns> {
+    Ok(TaskRequirementColumns {
+        required_capabilities: serde_json::to_value(&info.required_capabilities)?,
+        acceptance_criteria: serde_json::to_value(&info.acceptance_criteria)?,
+    })
+}
+
+/// 把协议任务优先级编码为字符串列
+pub fn task_priority_str(priority: &TaskPriority) -> Result<String> {
+    encode_enum(priority)
+}
+
+/// 把协议任务类型编码为字符串列
+pub fn task_type_str(task_type: &TaskType) -> Result<String> {
+    encode_enum(task_type)
+}
+
+// ============================================================================
+// Agent
+// ============================================================================
+
+/// `config` 列中保存的运行参数，缺失的字段取默认值
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+struct StoredAgentConfig {
+    max_concurrent_tasks: u32,
+    timeout_minutes: u32,
+    custom_settings: HashMap<String, JsonValue>,
+    priority_weight: f32,
+    verbose_logging: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resource_limits: Option<ResourceLimits>,
+    approval_policy: AgentApprovalPolicy,
+}
+
+impl Default for StoredAgentConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_tasks: DEFAULT_MAX_CONCURRENT_TASKS,
+            timeout_minutes: DEFAULT_TIMEOUT_MINUTES,
+            custom_settings: HashMap::new(),
+            priority_weight: 0.5,
+            verbose_logging: false,
+            resource_limits: None,
+            approval_policy: AgentApprovalPolicy::default(),
+        }
+    }
+}
+
+impl TryFrom<&agent::Model> for AgentConfig {
+    type Error = DatabaseError;
+
+    fn try_from(agent: &agent::Model) -> Result<Self> {
+        let stored: StoredAgentConfig = decode_json("agent", "config", &agent.config)?;
+        stored
+            .approval_policy
+            .validate()
+            .map_err(|e| DatabaseError::column_decode("agent", "config", e))?;
+        Ok(Self {
+            name: agent.name.clone(),
+            description: agent.description.clone().unwrap_or_default(),
+            prompt_template: agent.prompt_template.clone(),
+            capabilities: agent_capabilities(agent)?,
+            max_concurrent_tasks: stored.max_concurrent_tasks,
+            timeout_minutes: stored.timeout_minutes,
+            git_config: decode_optional_json::<Option<GitConfig>>("agent", "git_config", agent.git_config.as_ref())?,
+            custom_settings: stored.custom_settings,
+            priority_weight: stored.priority_weight,
+            verbose_logging: stored.verbose_logging,
+            resource_limits: stored.resource_limits,
+            approval_policy: stored.approval_policy,
+        })
+    }
+}
+
+impl TryFrom<&agent::Model> for AgentSummary {
+    type Error = DatabaseError;
+
+    fn try_from(agent: &agent::Model) -> Result<Self> {
+        let status: AgentStatus = decode_enum("agent", "status", &agent.status)?;
+        Ok(Self {
+            agent_id: AgentId::from(agent.agent_id),
+            name: agent.name.clone(),
+            description: agent.description.clone().unwrap_or_default(),
+            current_workload: if agent.current_task_id.is_some() { 1.0 } else { 0.0 },
+            status,
+            current_task: agent.current_task_id.map(TaskId::from),
+            capabilities: agent_capabilities(agent)?,
+            success_rate: agent.success_rate as f32,
+            average_completion_time: agent.average_completion_time.max(0) as u32,
+            total_completed_tasks: agent.total_tasks_completed.max(0) as u32,
+            created_at: agent.created_at.into(),
+            last_active_at: agent.last_active_at.into(),
+        })
+    }
+}
+
+/// 解码Agent的能力列
+pub fn agent_capabilities(agent: &agent::Model) -> Result<Vec<AgentCapability>> {
+    decode_json("agent", "capabilities", &agent.capabilities)
+}
+
+/// 由协议Agent配置生成创建数据
+pub fn agent_create_data(user_id: Uuid, config: &AgentConfig) -> Result<CreateAgentData> {
+    let stored = StoredAgentConfig {
+        max_concurrent_tasks: config.max_concurrent_tasks,
+        timeout_minutes: config.timeout_minutes,
+        custom_settings: config.custom_settings.clone(),
+        priority_weight: config.priority_weight,
+        verbose_logging: config.verbose_logging,
+        resource_limits: config.resource_limits.clone(),
+        approval_policy: config.approval_policy.clone(),
+    };
+    Ok(CreateAgentData {
+        user_id,
+        name: config.name.clone(),
+        description: (!config.description.is_empty()).then(|| config.description.clone()),
+        prompt_template: config.prompt_template.clone(),
+        capabilities: serde_json::to_value(&config.capabilities)?,
+        config: serde_json::to_value(&stored)?,
+        git_config: config.git_config.as_ref().map(serde_json::to_value).transpose()?,
+    })
+}
+
+// ============================================================================
+// 冲突
+// ============================================================================
+
+/// 解码冲突类型列
+pub fn conflict_type(conflict: &conflict::Model) -> Result<ConflictType> {
+    match conflict.conflict_type.as_str() {
+        "git_merge" => Ok(ConflictType::GitMergeConflict),
+        "resource" => Ok(ConflictType::ResourceConflict),
+        "task_dependency" => Ok(ConflictType::TaskDependencyConflict),
+        "capability" => Ok(ConflictType::AgentCapabilityConflict),
+        "timeline" => Ok(ConflictType::TimelineConflict),
+        other => Err(DatabaseError::column_decode("conflict", "conflict_type", format!("未知的取值 {}", other))),
+    }
+}
+
+/// 把协议冲突类型转换为实体冲突类型，实体不支持的类型返回校验错误
+pub fn conflict_type_to_entity(conflict_type: &ConflictType) -> Result<conflict::ConflictType> {
+    match conflict_type {
+        ConflictType::GitMergeConflict => Ok(conflict::ConflictType::GitMerge),
+        ConflictType::ResourceConflict => Ok(conflict::ConflictType::Resource),
+        ConflictType::TaskDependencyConflict => Ok(conflict::ConflictType::TaskDependency),
+        ConflictType::AgentCapabilityConflict => Ok(conflict::ConflictType::Capability),
+        ConflictType::TimelineConflict => Ok(conflict::ConflictType::Timeline),
+        other => Err(DatabaseError::validation(format!("冲突类型 {:?} 无法持久化", other))),
+    }
+}
+
+/// 解码冲突严重程度列
+pub fn conflict_severity(conflict: &conflict::Model) -> Result<ConflictSeverity> {
+    decode_enum("conflict", "severity", &conflict.severity)
+}
+
+/// 把协议冲突严重程度转换为实体严重程度
+pub fn conflict_severity_to_entity(severity: &ConflictSeverity) -> conflict::ConflictSeverity {
+    match severity {
+        ConflictSeverity::Low => conflict::ConflictSeverity::Low,
+        ConflictSeverity::Medium => conflict::ConflictSeverity::Medium,
+        ConflictSeverity::High => conflict::ConflictSeverity::High,
+        ConflictSeverity::Critical => conflict::ConflictSeverity::Critical,
+    }
+}
+
+/// 解码冲突影响的任务列
+pub fn conflict_affected_tasks(conflict: &conflict::Model) -> Result<Vec<TaskId>> {
+    decode_ids("conflict", "affected_tasks", &conflict.affected_tasks)
+}
+
+/// 解码冲突影响的Agent列
+pub fn conflict_affected_agents(conflict: &conflict::Model) -> Result<Vec<AgentId>> {
+    decode_ids("conflict", "affected_agents", &conflict.affected_agents)
+}
+
+// ============================================================================
+// 代码审查
+// ============================================================================
+
+/// 解码审查优先级列
+pub fn review_priority(review: &code_review::Model) -> Result<ReviewPriority> {
+    decode_enum("code_review", "priority", &review.priority)
+}
+
+/// 解码审查决策列，尚未给出决策时返回 None
+pub fn review_result(review: &code_review::Model) -> Result<Option<ReviewResult>> {
+    review
+        .decision
+        .as_deref()
+        .map(|decision| decode_enum("code_review", "decision", decision))
+        .transpose()
+}
+
+/// 把协议审查结果转换为实体审查决策，实体不支持的结果返回校验错误
+pub fn review_result_to_entity(result: &ReviewResult) -> Result<code_review::ReviewDecision> {
+    match result {
+        ReviewResult::Approved => Ok(code_review::ReviewDecision::Approved),
+        ReviewResult::ChangesRequested => Ok(code_review::ReviewDecision::ChangesRequested),
+        ReviewResult::Rejected => Ok(code_review::ReviewDecision::Rejected),
+        ReviewResult::NeedsMoreReview => Err(DatabaseError::validation("审查结果 needs_more_review 无法持久化")),
+    }
+}
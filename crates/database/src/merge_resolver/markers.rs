@@ -0,0 +1,149 @@
+//! 冲突标记解析
+
+use serde::{Deserialize, Serialize};
+
+/// 包含冲突标记的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictedFile {
+    /// 相对仓库根目录的路径
+    pub path: String,
+    /// 含冲突标记的文件内容
+    pub content: String,
+}
+
+/// 单个冲突块
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictHunk {
+    /// 冲突开始的行号（从1开始）
+    pub start_line: usize,
+    /// 当前分支（ours）的内容
+    pub ours: String,
+    /// 共同祖先的内容（仅 diff3 风格标记提供）
+    pub base: Option<String>,
+    /// 合入分支（theirs）的内容
+    pub theirs: String,
+}
+
+impl ConflictHunk {
+    /// 冲突块内容哈希
+    ///
+    /// 忽略行尾空白，并对双方顺序无关，使交换合并方向后仍能命中历史方案
+    pub fn content_hash(&self) -> String {
+        let normalize = |text: &str| {
+            text.lines()
+                .map(str::trim_end)
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let mut sides = [normalize(&self.ours), normalize(&self.theirs)];
+        sides.sort();
+        format!("{:016x}", fnv1a(sides.join("\u{0}").as_bytes()))
+    }
+}
+
+/// 文件片段：普通文本或冲突块
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// 无冲突的文本
+    Text(String),
+    /// 冲突块
+    Conflict(ConflictHunk),
+}
+
+/// FNV-1a 64位哈希（结果需要持久化，不能依赖标准库的哈希实现）
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// 冲突标记解析时所处的区域
+enum Region {
+    Text,
+    Ours,
+    Base,
+    Theirs,
+}
+
+/// 解析带有冲突标记的文件内容
+///
+/// 支持普通和 diff3 风格（包含 `|||||||` 祖先段）的标记；
+/// 标记不完整时返回 `None`
+pub fn parse_conflict_markers(content: &str) -> Option<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut ours = String::new();
+    let mut base: Option<String> = None;
+    let mut theirs = String::new();
+    let mut start_line = 0;
+    let mut region = Region::Text;
+
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        match region {
+            Region::Text if line.starts_with("<<<<<<<") => {
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                start_line = index + 1;
+                region = Region::Ours;
+            }
+            Region::Text => text.push_str(line),
+            Region::Ours if line.starts_with("|||||||") => {
+                base = Some(String::new());
+                region = Region::Base;
+            }
+            Region::Ours | Region::Base if line.starts_with("=======") => region = Region::Theirs,
+            Region::Ours => ours.push_str(line),
+            Region::Base => base.get_or_insert_with(String::new).push_str(line),
+            Region::Theirs if line.starts_with(">>>>>>>") => {
+                segments.push(Segment::Conflict(ConflictHunk {
+                    start_line,
+                    ours: std::mem::take(&mut ours),
+                    base: base.take(),
+                    theirs: std::mem::take(&mut theirs),
+                }));
+                region = Region::Text;
+            }
+            Region::Theirs => theirs.push_str(line),
+        }
+    }
+
+    if !matches!(region, Region::Text) {
+        return None;
+    }
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    Some(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diff3_markers() {
+        let content = "a\n<<<<<<< HEAD\nours\n||||||| base\nbase\n=======\ntheirs\n>>>>>>> feature\nb\n";
+        let segments = parse_conflict_markers(content).unwrap();
+        assert_eq!(segments.len(), 3);
+        let Segment::Conflict(hunk) = &segments[1] else {
+            panic!("应为冲突块");
+        };
+        assert_eq!(hunk.start_line, 2);
+        assert_eq!(hunk.ours, "ours\n");
+        assert_eq!(hunk.base.as_deref(), Some("base\n"));
+        assert_eq!(hunk.theirs, "theirs\n");
+
+        assert!(parse_conflict_markers("<<<<<<< HEAD\nours\n").is_none());
+    }
+
+    #[test]
+    fn test_hunk_hash_is_order_independent() {
+        let hunk = ConflictHunk { start_line: 1, ours: "a\n".into(), base: None, theirs: "b  \n".into() };
+        let swapped = ConflictHunk { start_line: 9, ours: "b\n".into(), base: None, theirs: "a\n".into() };
+        assert_eq!(hunk.content_hash(), swapped.content_hash());
+    }
+}
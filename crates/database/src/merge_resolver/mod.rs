@@ -0,0 +1,19 @@
+//! Git合并冲突自动解决模块
+//!
+//! 按顺序尝试以下策略解决冲突块：
+//! - 平凡合并：双方相同，或只有一方相对共同祖先有改动
+//! - 复用已批准的历史解决方案（类似 git rerere）
+//! - 按路径模式配置的 ours/theirs/union 策略
+//!
+//! 仍无法解决的冲突块可以提交LLM合并方案，方案需人工批准后才会生效；
+//! 整体置信度不足时冲突会上报给人工处理。
+
+pub mod markers;
+pub mod policy;
+pub mod resolver;
+
+pub use markers::{parse_conflict_markers, ConflictHunk, ConflictedFile, Segment};
+pub use policy::{MergeSide, PathPolicy};
+pub use resolver::{
+    FileResolution, HunkResolution, MergeConflictResolver, MergeResolutionReport, ResolutionStrategy,
+};
@@ -0,0 +1,89 @@
+//! 按路径模式配置的合并策略
+
+use serde::{Deserialize, Serialize};
+
+/// 冲突时采用的一方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeSide {
+    /// 保留当前分支
+    Ours,
+    /// 采用合入分支
+    Theirs,
+    /// 双方内容都保留（先 ours 后 theirs）
+    Union,
+}
+
+/// 路径策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathPolicy {
+    /// 路径模式，支持 `*`、`**` 和 `?`
+    pub pattern: String,
+    /// 匹配时采用的一方
+    pub side: MergeSide,
+}
+
+impl PathPolicy {
+    /// 创建路径策略
+    pub fn new(pattern: impl Into<String>, side: MergeSide) -> Self {
+        Self {
+            pattern: pattern.into(),
+            side,
+        }
+    }
+
+    /// 路径是否匹配该策略
+    pub fn matches(&self, path: &str) -> bool {
+        glob_match(&self.pattern, path)
+    }
+}
+
+/// 简单的路径通配符匹配
+///
+/// `*` 和 `?` 不跨越目录分隔符，`**` 可以匹配任意层级目录
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let path: Vec<char> = path.chars().collect();
+    match_from(&pattern, &path)
+}
+
+fn match_from(pattern: &[char], path: &[char]) -> bool {
+    match pattern.first() {
+        None => path.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            // `**/` 也可以匹配零层目录
+            let rest = if pattern.get(2) == Some(&'/') { &pattern[3..] } else { &pattern[2..] };
+            (0..=path.len()).any(|i| match_from(rest, &path[i..]))
+        }
+        Some('*') => {
+            let rest = &pattern[1..];
+            for i in 0..=path.len() {
+                if match_from(rest, &path[i..]) {
+                    return true;
+                }
+                if path.get(i) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => matches!(path.first(), Some(c) if *c != '/') && match_from(&pattern[1..], &path[1..]),
+        Some(c) => path.first() == Some(c) && match_from(&pattern[1..], &path[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.lock", "Cargo.lock"));
+        assert!(!glob_match("*.lock", "sub/Cargo.lock"));
+        assert!(glob_match("**/*.lock", "sub/Cargo.lock"));
+        assert!(glob_match("**/*.lock", "Cargo.lock"));
+        assert!(glob_match("docs/**", "docs/a/b.md"));
+        assert!(glob_match("src/?.rs", "src/a.rs"));
+        assert!(!glob_match("src/*.rs", "src/a/b.rs"));
+    }
+}
@@ -0,0 +1,446 @@
+//! 合并冲突解决器
+
+use crate::{
+    entities::{
+        conflict::{ConflictStatus, ConflictType},
+        merge_resolution::{self, MergeResolutionStatus},
+    },
+    merge_resolver::{
+        markers::{parse_conflict_markers, ConflictHunk, ConflictedFile, Segment},
+        policy::{MergeSide, PathPolicy},
+    },
+    repository::{
+        merge_resolution_repository::CreateMergeResolutionData, ConflictRepository,
+        MergeResolutionRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 默认的自动解决置信度阈值
+const DEFAULT_CONFIDENCE_THRESHOLD: f32 = 0.8;
+
+/// 复用已批准方案时的置信度上限
+const RERERE_CONFIDENCE: f32 = 0.95;
+
+/// 冲突块解决策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionStrategy {
+    /// 平凡合并（双方相同或只有一方改动）
+    Trivial,
+    /// 复用已批准的历史方案
+    Rerere,
+    /// 路径策略
+    PathPolicy(MergeSide),
+    /// 人工给出的方案
+    Manual,
+    /// LLM给出并经批准的方案
+    LlmAssisted,
+}
+
+impl std::fmt::Display for ResolutionStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResolutionStrategy::Trivial => write!(f, "trivial"),
+            ResolutionStrategy::Rerere => write!(f, "rerere"),
+            ResolutionStrategy::PathPolicy(MergeSide::Ours) => write!(f, "path_policy:ours"),
+            ResolutionStrategy::PathPolicy(MergeSide::Theirs) => write!(f, "path_policy:theirs"),
+            ResolutionStrategy::PathPolicy(MergeSide::Union) => write!(f, "path_policy:union"),
+            ResolutionStrategy::Manual => write!(f, "manual"),
+            ResolutionStrategy::LlmAssisted => write!(f, "llm_assisted"),
+        }
+    }
+}
+
+/// 单个冲突块的解决结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HunkResolution {
+    /// 冲突块在文件中的序号
+    pub index: usize,
+    /// 冲突开始的行号
+    pub start_line: usize,
+    /// 冲突块内容哈希
+    pub hunk_hash: String,
+    /// 使用的策略（未解决时为空）
+    pub strategy: Option<ResolutionStrategy>,
+    /// 置信度（0.0-1.0）
+    pub confidence: f32,
+    /// 解决后的内容（未解决时为空）
+    pub resolution: Option<String>,
+    /// 是否有等待批准的LLM方案
+    pub pending_approval: bool,
+}
+
+/// 单个文件的解决结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileResolution {
+    /// 文件路径
+    pub path: String,
+    /// 各冲突块的解决结果
+    pub hunks: Vec<HunkResolution>,
+    /// 全部冲突块解决后的文件内容
+    pub resolved_content: Option<String>,
+    /// 冲突标记是否无法解析
+    pub malformed: bool,
+}
+
+/// 一次冲突解决尝试的汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeResolutionReport {
+    /// 冲突ID
+    pub conflict_id: Uuid,
+    /// 各文件的解决结果
+    pub files: Vec<FileResolution>,
+    /// 整体置信度（各冲突块的最小值，存在未解决块时为0）
+    pub confidence: f32,
+    /// 是否已自动解决
+    pub auto_resolved: bool,
+    /// 是否已上报人工处理
+    pub escalated: bool,
+    /// 等待批准的LLM方案数量
+    pub pending_approvals: u32,
+}
+
+impl MergeResolutionReport {
+    /// 使用到的策略（按出现顺序去重）
+    pub fn strategies(&self) -> Vec<ResolutionStrategy> {
+        let mut strategies = Vec::new();
+        for strategy in self.files.iter().flat_map(|f| f.hunks.iter().filter_map(|h| h.strategy)) {
+            if !strategies.contains(&strategy) {
+                strategies.push(strategy);
+            }
+        }
+        strategies
+    }
+
+    /// 未解决的冲突块位置（文件:行号）
+    pub fn unresolved_locations(&self) -> Vec<String> {
+        self.files
+            .iter()
+            .flat_map(|f| {
+                let malformed = f.malformed.then(|| format!("{}（冲突标记无法解析）", f.path));
+                malformed.into_iter().chain(
+                    f.hunks
+                        .iter()
+                        .filter(|h| h.resolution.is_none())
+                        .map(move |h| format!("{}:{}", f.path, h.start_line)),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Git合并冲突解决器
+pub struct MergeConflictResolver {
+    db: DatabaseConnection,
+    policies: Vec<PathPolicy>,
+    confidence_threshold: f32,
+    #[cfg(feature = "fault_injection")]
+    faults: Option<crate::fault_injection::FaultInjector>,
+}
+
+impl MergeConflictResolver {
+    /// 创建新的解决器
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            policies: Vec::new(),
+            confidence_threshold: DEFAULT_CONFIDENCE_THRESHOLD,
+            #[cfg(feature = "fault_injection")]
+            faults: None,
+        }
+    }
+
+    /// 挂载故障注入器（提交LLM方案时检查 LLM 超时故障点）
+    #[cfg(feature = "fault_injection")]
+    pub fn with_fault_injector(mut self, injector: crate::fault_injection::FaultInjector) -> Self {
+        self.faults = Some(injector);
+        self
+    }
+
+    /// 添加路径策略（先添加的优先匹配）
+    pub fn with_policy(mut self, pattern: impl Into<String>, side: MergeSide) -> Self {
+        self.policies.push(PathPolicy::new(pattern, side));
+        self
+    }
+
+    /// 设置自动解决的置信度阈值
+    pub fn with_confidence_threshold(mut self, threshold: f32) -> Self {
+        self.confidence_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 不访问数据库的本地策略：平凡合并和路径策略
+    pub fn resolve_hunk_locally(&self, path: &str, hunk: &ConflictHunk) -> Option<(ResolutionStrategy, f32, String)> {
+        if let Some((confidence, resolution)) = trivial_resolution(hunk) {
+            return Some((ResolutionStrategy::Trivial, confidence, resolution));
+        }
+
+        let policy = self.policies.iter().find(|p| p.matches(path))?;
+        let (confidence, resolution) = match policy.side {
+            MergeSide::Ours => (0.85, hunk.ours.clone()),
+            MergeSide::Theirs => (0.85, hunk.theirs.clone()),
+            // 双方都保留通常仍需人工确认顺序和去重
+            MergeSide::Union => (0.6, format!("{}{}", hunk.ours, hunk.theirs)),
+        };
+        Some((ResolutionStrategy::PathPolicy(policy.side), confidence, resolution))
+    }
+
+    /// 尝试自动解决Git合并冲突，并把结果记录到冲突上
+    ///
+    /// 全部冲突块解决且置信度达到阈值时标记冲突为已解决；
+    /// 剩余冲突块都有待批准的LLM方案时进入解决中状态；否则上报人工处理
+    pub async fn resolve(&self, conflict_id: Uuid, files: &[ConflictedFile]) -> Result<MergeResolutionReport> {
+        let conflict_repo = ConflictRepository::new(self.db.clone());
+        let conflict = conflict_repo
+            .find_by_id(conflict_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Conflict", conflict_id))?;
+        if conflict.conflict_type != ConflictType::GitMerge.to_string() {
+            return Err(DatabaseError::validation(format!(
+                "冲突 {conflict_id} 不是Git合并冲突: {}",
+                conflict.conflict_type
+            )));
+        }
+
+        let resolution_repo = MergeResolutionRepository::new(self.db.clone());
+        let pending_hashes: Vec<String> = resolution_repo
+            .find_pending_by_conflict(conflict_id)
+            .await?
+            .into_iter()
+            .map(|r| r.hunk_hash)
+            .collect();
+
+        let mut file_results = Vec::with_capacity(files.len());
+        for file in files {
+            file_results.push(self.resolve_file(&resolution_repo, file, &pending_hashes).await?);
+        }
+
+        let all_hunks = || file_results.iter().flat_map(|f| f.hunks.iter());
+        let fully_resolved = file_results.iter().all(|f| f.resolved_content.is_some());
+        let confidence = if fully_resolved {
+            all_hunks().map(|h| h.confidence).fold(1.0, f32::min)
+        } else {
+            0.0
+        };
+        let pending_approvals = all_hunks().filter(|h| h.pending_approval).count() as u32;
+        let unresolved = all_hunks().filter(|h| h.resolution.is_none()).count() as u32;
+        let any_malformed = file_results.iter().any(|f| f.malformed);
+
+        let mut report = MergeResolutionReport {
+            conflict_id,
+            files: file_results,
+            confidence,
+            auto_resolved: false,
+            escalated: false,
+            pending_approvals,
+        };
+
+        let strategy_summary = match report.strategies() {
+            strategies if strategies.is_empty() => "none".to_string(),
+            strategies => strategies.iter().map(ToString::to_string).collect::<Vec<_>>().join("+"),
+        };
+        let resolved_hunks = all_hunks_count(&report) - unresolved as usize;
+        let mut note = format!(
+            "自动解决 {resolved_hunks}/{} 个冲突块，置信度 {confidence:.2}",
+            all_hunks_count(&report)
+        );
+
+        if fully_resolved && confidence >= self.confidence_threshold {
+            conflict_repo
+                .resolve_conflict(conflict_id, strategy_summary, Some(note), true)
+                .await?;
+            report.auto_resolved = true;
+        } else if unresolved > 0 && unresolved == pending_approvals && !any_malformed {
+            note.push_str(&format!("；{pending_approvals} 个LLM方案等待批准"));
+            conflict_repo
+                .record_resolution_attempt(conflict_id, strategy_summary, Some(note))
+                .await?;
+            conflict_repo.update_status(conflict_id, ConflictStatus::Resolving).await?;
+        } else {
+            let unresolved_locations = report.unresolved_locations();
+            if !unresolved_locations.is_empty() {
+                note.push_str(&format!("；未解决: {}", unresolved_locations.join(", ")));
+            }
+            conflict_repo
+                .record_resolution_attempt(conflict_id, strategy_summary, Some(note))
+                .await?;
+            conflict_repo.escalate_to_human(conflict_id, conflict.assigned_user_id).await?;
+            report.escalated = true;
+        }
+
+        Ok(report)
+    }
+
+    async fn resolve_file(
+        &self,
+        resolution_repo: &MergeResolutionRepository,
+        file: &ConflictedFile,
+        pending_hashes: &[String],
+    ) -> Result<FileResolution> {
+        let Some(segments) = parse_conflict_markers(&file.content) else {
+            return Ok(FileResolution {
+                path: file.path.clone(),
+                hunks: Vec::new(),
+                resolved_content: None,
+                malformed: true,
+            });
+        };
+
+        let mut hunks = Vec::new();
+        let mut content = String::new();
+        let mut complete = true;
+
+        for segment in &segments {
+            let hunk = match segment {
+                Segment::Text(text) => {
+                    content.push_str(text);
+                    continue;
+                }
+                Segment::Conflict(hunk) => hunk,
+            };
+            let hunk_hash = hunk.content_hash();
+
+            let mut resolved = self.resolve_hunk_locally(&file.path, hunk);
+            // 平凡合并之外优先复用已批准的历史方案
+            if !matches!(resolved, Some((ResolutionStrategy::Trivial, _, _))) {
+                if let Some(record) = resolution_repo.find_approved_by_hash(&hunk_hash).await? {
+                    resolution_repo.increment_use_count(record.resolution_id).await?;
+                    let confidence = (record.confidence as f32).min(RERERE_CONFIDENCE);
+                    resolved = Some((ResolutionStrategy::Rerere, confidence, record.resolution));
+                }
+            }
+
+            match &resolved {
+                Some((_, _, resolution)) => content.push_str(resolution),
+                None => complete = false,
+            }
+            let (strategy, confidence, resolution) = match resolved {
+                Some((strategy, confidence, resolution)) => (Some(strategy), confidence, Some(resolution)),
+                None => (None, 0.0, None),
+            };
+
+            hunks.push(HunkResolution {
+                index: hunks.len(),
+                start_line: hunk.start_line,
+                pending_approval: resolution.is_none() && pending_hashes.contains(&hunk_hash),
+                hunk_hash,
+                strategy,
+                confidence,
+                resolution,
+            });
+        }
+
+        Ok(FileResolution {
+            path: file.path.clone(),
+            hunks,
+            resolved_content: complete.then_some(content),
+            malformed: false,
+        })
+    }
+
+    /// 提交LLM给出的冲突块合并方案（进入待批准状态，不会直接生效）
+    pub async fn submit_llm_proposal(
+        &self,
+        conflict_id: Uuid,
+        path: &str,
+        hunk: &ConflictHunk,
+        resolution: String,
+        confidence: f32,
+    ) -> Result<merge_resolution::Model> {
+        #[cfg(feature = "fault_injection")]
+        crate::fault_injection::check(
+            &self.faults,
+            crate::fault_injection::FaultPoint::LlmTimeout,
+            Some(conflict_id),
+        )?;
+
+        MergeResolutionRepository::new(self.db.clone())
+            .create(CreateMergeResolutionData {
+                conflict_id: Some(conflict_id),
+                file_path: path.to_string(),
+                hunk_hash: hunk.content_hash(),
+                resolution,
+                strategy: ResolutionStrategy::LlmAssisted.to_string(),
+                confidence: confidence.clamp(0.0, 1.0) as f64,
+                status: MergeResolutionStatus::Pending,
+            })
+            .await
+    }
+
+    /// 记录人工给出的冲突块方案（直接批准，后续相同冲突自动复用）
+    pub async fn record_manual_resolution(
+        &self,
+        conflict_id: Option<Uuid>,
+        path: &str,
+        hunk: &ConflictHunk,
+        resolution: String,
+    ) -> Result<merge_resolution::Model> {
+        MergeResolutionRepository::new(self.db.clone())
+            .create(CreateMergeResolutionData {
+                conflict_id,
+                file_path: path.to_string(),
+                hunk_hash: hunk.content_hash(),
+                resolution,
+                strategy: ResolutionStrategy::Manual.to_string(),
+                confidence: 1.0,
+                status: MergeResolutionStatus::Approved,
+            })
+            .await
+    }
+
+    /// 批准待定方案，批准后再次调用 `resolve` 即可复用
+    pub async fn approve_proposal(&self, resolution_id: Uuid) -> Result<merge_resolution::Model> {
+        self.set_proposal_status(resolution_id, MergeResolutionStatus::Approved).await
+    }
+
+    /// 拒绝待定方案
+    pub async fn reject_proposal(&self, resolution_id: Uuid) -> Result<merge_resolution::Model> {
+        self.set_proposal_status(resolution_id, MergeResolutionStatus::Rejected).await
+    }
+
+    async fn set_proposal_status(
+        &self,
+        resolution_id: Uuid,
+        status: MergeResolutionStatus,
+    ) -> Result<merge_resolution::Model> {
+        let repo = MergeResolutionRepository::new(self.db.clone());
+        let record = repo
+            .find_by_id(resolution_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("MergeResolution", resolution_id))?;
+        if record.status != MergeResolutionStatus::Pending.to_string() {
+            return Err(DatabaseError::business_logic(format!(
+                "方案 {resolution_id} 当前状态为 {}，不能再次审批",
+                record.status
+            )));
+        }
+
+        repo.update_status(resolution_id, status).await
+    }
+}
+
+fn all_hunks_count(report: &MergeResolutionReport) -> usize {
+    report.files.iter().map(|f| f.hunks.len()).sum()
+}
+
+/// 平凡合并：双方相同（忽略行尾空白），或只有一方相对共同祖先有改动
+fn trivial_resolution(hunk: &ConflictHunk) -> Option<(f32, String)> {
+    if hunk.ours == hunk.theirs {
+        return Some((1.0, hunk.ours.clone()));
+    }
+    if hunk.ours.lines().map(str::trim_end).eq(hunk.theirs.lines().map(str::trim_end)) {
+        return Some((0.9, hunk.ours.clone()));
+    }
+
+    let base = hunk.base.as_ref()?;
+    if *base == hunk.ours {
+        Some((0.95, hunk.theirs.clone()))
+    } else if *base == hunk.theirs {
+        Some((0.95, hunk.ours.clone()))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,329 @@
+//! 每日指标汇总
+//!
+//! 仪表盘按天查询任务完成数、token 用量、费用和错误数时，直接扫描任务、工作历史、LLM 对话和执行日志
+//! 会随数据增长越来越慢，执行日志还会被清理任务删除。汇总任务每晚把前一天的数据汇总到
+//! `project_daily_metrics` 和 `agent_daily_metrics`，并在 `metric_rollup_days` 中记录已汇总的日期：
+//! - [`rollup_day`] 重新汇总指定日期，先删除该日期已有的汇总再写入，可以重复执行；
+//! - [`backfill_missing`] 补算最近若干天中尚未汇总的日期，调度中断后不会遗漏；
+//! - [`MetricsRollupJob`] 把补算注册为维护任务，见 [`crate::maintenance`]。
+//!
+//! 读取时通过 [`crate::repository::DailyMetricsRepository`]，已汇总的日期读汇总表，其余日期（如当天）实时计算。
+//! 日期均按 UTC 划分。
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set, TransactionTrait};
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use uuid::Uuid;
+
+use crate::assignment_costs::{work_cost, TOKEN_COUNT_KEY};
+use crate::entities::{
+    agent_daily_metric, agent_work_history, execution_log, execution_session, llm_conversation, llm_session,
+    metric_rollup_day, project_daily_metric, task,
+};
+use crate::maintenance::{MaintenanceFuture, MaintenanceJobHandler};
+use crate::{DatabaseConnection, DatabaseError, Result};
+
+/// 每日指标汇总任务类型
+pub const JOB_METRICS_ROLLUP: &str = "metrics_rollup";
+
+/// 汇总任务默认补算的天数
+pub const DEFAULT_BACKFILL_DAYS: i64 = 7;
+
+/// 汇总表中日期的格式
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// 一天的指标
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DailyCounts {
+    pub tasks_completed: i32,
+    pub tasks_failed: i32,
+    pub total_tokens: i64,
+    pub total_cost: f64,
+    pub error_count: i32,
+}
+
+impl DailyCounts {
+    /// 累加另一组指标
+    pub fn add(&mut self, other: &DailyCounts) {
+        self.tasks_completed += other.tasks_completed;
+        self.tasks_failed += other.tasks_failed;
+        self.total_tokens += other.total_tokens;
+        self.total_cost += other.total_cost;
+        self.error_count += other.error_count;
+    }
+}
+
+/// 一天内各项目和各Agent的指标
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DayAggregates {
+    pub projects: BTreeMap<Uuid, DailyCounts>,
+    /// 按 (Agent ID, 项目ID) 汇总
+    pub agents: BTreeMap<(Uuid, Uuid), DailyCounts>,
+}
+
+/// 一次汇总的结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DayRollup {
+    pub date: NaiveDate,
+    pub project_count: usize,
+    pub agent_count: usize,
+}
+
+/// 汇总表中日期的字符串形式
+pub fn date_key(date: NaiveDate) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+/// 解析汇总表中的日期
+pub fn parse_date_key(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, DATE_FORMAT)
+        .map_err(|e| DatabaseError::validation(format!("无效的统计日期 {}: {}", value, e)))
+}
+
+/// 日期在 UTC 下的起止时间
+fn day_bounds(date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = date.and_hms_opt(0, 0, 0).expect("零点总是有效的时间").and_utc();
+    (start, start + Duration::days(1))
+}
+
+/// 从原始数据实时计算一天的指标
+///
+/// - 任务数按任务的完成时间统计到所属项目；
+/// - Agent的工作数、token 和费用按工作历史的完成时间统计，费用同时计入项目；
+/// - 项目的 token 数为当天 LLM 对话的 token 数；
+/// - 错误数为当天执行日志中 error 级别的条数。
+pub async fn compute_day(
+    db: &DatabaseConnection,
+    date: NaiveDate,
+    cost_per_1k_tokens: Option<f64>,
+) -> Result<DayAggregates> {
+    let (start, end) = day_bounds(date);
+    let start: sea_orm::prelude::DateTimeWithTimeZone = start.into();
+    let end: sea_orm::prelude::DateTimeWithTimeZone = end.into();
+    let mut aggregates = DayAggregates::default();
+
+    let finished_tasks = task::Entity::find()
+        .filter(task::Column::CompletedAt.gte(start))
+        .filter(task::Column::CompletedAt.lt(end))
+        .filter(task::Column::Status.is_in(["completed", "failed"]))
+        .all(db)
+        .await?;
+    for finished in &finished_tasks {
+        let counts = aggregates.projects.entry(finished.project_id).or_default();
+        if finished.status == "completed" {
+            counts.tasks_completed += 1;
+        } else {
+            counts.tasks_failed += 1;
+        }
+    }
+
+    let work = agent_work_history::Entity::find()
+        .filter(agent_work_history::Column::CompletedAt.gte(start))
+        .filter(agent_work_history::Column::CompletedAt.lt(end))
+        .all(db)
+        .await?;
+    let task_projects: BTreeMap<Uuid, Uuid> = task::Entity::find()
+        .filter(task::Column::TaskId.is_in(work.iter().map(|record| record.task_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|task| (task.task_id, task.project_id))
+        .collect();
+    for record in &work {
+        let Some(project_id) = task_projects.get(&record.task_id).copied() else {
+            continue;
+        };
+        let cost = work_cost(record.work_details.as_ref(), cost_per_1k_tokens).unwrap_or(0.0);
+        let counts = aggregates.agents.entry((record.agent_id, project_id)).or_default();
+        match record.success {
+            Some(true) => counts.tasks_completed += 1,
+            Some(false) => counts.tasks_failed += 1,
+            None => {}
+        }
+        counts.total_tokens += record
+            .work_details
+            .as_ref()
+            .and_then(|details| details.get(TOKEN_COUNT_KEY))
+            .and_then(JsonValue::as_i64)
+            .unwrap_or(0);
+        counts.total_cost += cost;
+        aggregates.projects.entry(project_id).or_default().total_cost += cost;
+    }
+
+    let messages = llm_conversation::Entity::find()
+        .filter(llm_conversation::Column::CreatedAt.gte(start))
+        .filter(llm_conversation::Column::CreatedAt.lt(end))
+        .filter(llm_conversation::Column::TokenCount.is_not_null())
+        .all(db)
+        .await?;
+    let session_projects: BTreeMap<Uuid, Uuid> = llm_session::Entity::find()
+        .filter(llm_session::Column::SessionId.is_in(messages.iter().map(|message| message.session_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|session| (session.session_id, session.project_id))
+        .collect();
+    for message in &messages {
+        if let Some(project_id) = session_projects.get(&message.session_id) {
+            aggregates.projects.entry(*project_id).or_default().total_tokens +=
+                i64::from(message.token_count.unwrap_or(0));
+        }
+    }
+
+    let errors = execution_log::Entity::find()
+        .filter(execution_log::Column::LogLevel.eq("error"))
+        .filter(execution_log::Column::CreatedAt.gte(start))
+        .filter(execution_log::Column::CreatedAt.lt(end))
+        .all(db)
+        .await?;
+    let sessions: BTreeMap<Uuid, (Uuid, Uuid)> = execution_session::Entity::find()
+        .filter(execution_session::Column::SessionId.is_in(errors.iter().map(|log| log.session_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|session| (session.session_id, (session.agent_id, session.project_id)))
+        .collect();
+    for log in &errors {
+        if let Some((agent_id, project_id)) = sessions.get(&log.session_id).copied() {
+            aggregates.projects.entry(project_id).or_default().error_count += 1;
+            aggregates.agents.entry((agent_id, project_id)).or_default().error_count += 1;
+        }
+    }
+
+    Ok(aggregates)
+}
+
+/// 汇总指定日期，替换该日期已有的汇总
+pub async fn rollup_day(
+    db: &DatabaseConnection,
+    date: NaiveDate,
+    cost_per_1k_tokens: Option<f64>,
+) -> Result<DayRollup> {
+    let aggregates = compute_day(db, date, cost_per_1k_tokens).await?;
+    let metric_date = date_key(date);
+    let computed_at: sea_orm::prelude::DateTimeWithTimeZone = Utc::now().into();
+
+    let txn = db.begin().await?;
+    project_daily_metric::Entity::delete_many()
+        .filter(project_daily_metric::Column::MetricDate.eq(metric_date.clone()))
+        .exec(&txn)
+        .await?;
+    agent_daily_metric::Entity::delete_many()
+        .filter(agent_daily_metric::Column::MetricDate.eq(metric_date.clone()))
+        .exec(&txn)
+        .await?;
+    metric_rollup_day::Entity::delete_by_id(metric_date.clone()).exec(&txn).await?;
+
+    for (project_id, counts) in &aggregates.projects {
+        project_daily_metric::ActiveModel {
+            metric_id: Set(Uuid::new_v4()),
+            metric_date: Set(metric_date.clone()),
+            project_id: Set(*project_id),
+            tasks_completed: Set(counts.tasks_completed),
+            tasks_failed: Set(counts.tasks_failed),
+            total_tokens: Set(counts.total_tokens),
+            total_cost: Set(counts.total_cost),
+            error_count: Set(counts.error_count),
+            computed_at: Set(computed_at),
+        }
+        .insert(&txn)
+        .await?;
+    }
+    for ((agent_id, project_id), counts) in &aggregates.agents {
+        agent_daily_metric::ActiveModel {
+            metric_id: Set(Uuid::new_v4()),
+            metric_date: Set(metric_date.clone()),
+            agent_id: Set(*agent_id),
+            project_id: Set(*project_id),
+            tasks_completed: Set(counts.tasks_completed),
+            tasks_failed: Set(counts.tasks_failed),
+            total_tokens: Set(counts.total_tokens),
+            total_cost: Set(counts.total_cost),
+            error_count: Set(counts.error_count),
+            computed_at: Set(computed_at),
+        }
+        .insert(&txn)
+        .await?;
+    }
+    metric_rollup_day::ActiveModel {
+        metric_date: Set(metric_date),
+        project_count: Set(aggregates.projects.len() as i32),
+        agent_count: Set(aggregates.agents.len() as i32),
+        computed_at: Set(computed_at),
+    }
+    .insert(&txn)
+    .await?;
+    txn.commit().await?;
+
+    Ok(DayRollup {
+        date,
+        project_count: aggregates.projects.len(),
+        agent_count: aggregates.agents.len(),
+    })
+}
+
+/// 补算 `now` 之前 `days` 天（不含当天）中尚未汇总的日期，按日期先后返回汇总结果
+pub async fn backfill_missing(
+    db: &DatabaseConnection,
+    now: DateTime<Utc>,
+    days: i64,
+    cost_per_1k_tokens: Option<f64>,
+) -> Result<Vec<DayRollup>> {
+    if days < 1 {
+        return Err(DatabaseError::validation("补算天数至少为1天"));
+    }
+    let today = now.date_naive();
+    let first = today - Duration::days(days);
+    let rolled_up = rolled_up_dates(db, first, today - Duration::days(1)).await?;
+
+    let mut rollups = Vec::new();
+    let mut date = first;
+    while date < today {
+        if !rolled_up.contains(&date) {
+            rollups.push(rollup_day(db, date, cost_per_1k_tokens).await?);
+        }
+        date += Duration::days(1);
+    }
+    Ok(rollups)
+}
+
+/// 已汇总的日期，`from` 和 `to` 均包含在内
+pub async fn rolled_up_dates(db: &DatabaseConnection, from: NaiveDate, to: NaiveDate) -> Result<Vec<NaiveDate>> {
+    metric_rollup_day::Entity::find()
+        .filter(metric_rollup_day::Column::MetricDate.gte(date_key(from)))
+        .filter(metric_rollup_day::Column::MetricDate.lte(date_key(to)))
+        .all(db)
+        .await?
+        .iter()
+        .map(|day| parse_date_key(&day.metric_date))
+        .collect()
+}
+
+/// 每晚补算尚未汇总的日期
+///
+/// 参数 `backfill_days`（默认 [`DEFAULT_BACKFILL_DAYS`]）为向前检查的天数，
+/// `cost_per_1k_tokens` 为工作历史没有直接记录费用时的每千 token 单价
+pub struct MetricsRollupJob;
+
+impl MaintenanceJobHandler for MetricsRollupJob {
+    fn kind(&self) -> &str {
+        JOB_METRICS_ROLLUP
+    }
+
+    fn run<'a>(&'a self, db: &'a DatabaseConnection, params: &'a JsonValue, now: DateTime<Utc>) -> MaintenanceFuture<'a> {
+        Box::pin(async move {
+            let days = params.get("backfill_days").and_then(JsonValue::as_i64).unwrap_or(DEFAULT_BACKFILL_DAYS);
+            let cost_per_1k_tokens = params.get("cost_per_1k_tokens").and_then(JsonValue::as_f64);
+            let rollups = backfill_missing(db, now, days, cost_per_1k_tokens).await?;
+            Ok(json!({
+                "backfill_days": days,
+                "dates": rollups.iter().map(|rollup| date_key(rollup.date)).collect::<Vec<_>>(),
+                "projects": rollups.iter().map(|rollup| rollup.project_count).sum::<usize>(),
+                "agents": rollups.iter().map(|rollup| rollup.agent_count).sum::<usize>(),
+            }))
+        })
+    }
+}
@@ -21,6 +21,15 @@ impl Migrator {
         // 创建用户会话表
         Self::create_user_sessions_table(db).await?;
         
+        // 创建组织表（项目和Agent引用组织）
+        Self::create_organizations_table(db).await?;
+        
+        // 创建团队表
+        Self::create_teams_table(db).await?;
+        
+        // 创建组织成员表
+        Self::create_organization_members_table(db).await?;
+        
         // 创建项目表
         Self::create_projects_table(db).await?;
         
@@ -48,6 +57,9 @@ impl Migrator {
         // 创建执行日志表
         Self::create_execution_logs_table(db).await?;
         
+        // 创建执行工件表
+        Self::create_execution_artifacts_table(db).await?;
+        
         // 创建冲突表
         Self::create_conflicts_table(db).await?;
         
@@ -69,6 +81,114 @@ impl Migrator {
         // 创建Agent性能指标表
         Self::create_agent_performance_metrics_table(db).await?;
         
+        // 创建技术债务快照表
+        Self::create_technical_debt_snapshots_table(db).await?;
+        
+        // 创建合并冲突解决记录表
+        Self::create_merge_resolutions_table(db).await?;
+        
+        // 创建项目成员表
+        Self::create_project_members_table(db).await?;
+        
+        // 创建语义检索向量表
+        Self::create_embeddings_table(db).await?;
+        
+        // 创建需求覆盖关系表
+        Self::create_requirement_coverage_table(db).await?;
+        
+        // 创建验收标准验证记录表
+        Self::create_acceptance_verifications_table(db).await?;
+        
+        // 创建项目黑板条目表
+        Self::create_blackboard_entries_table(db).await?;
+        
+        // 创建命令审批审计日志表
+        Self::create_command_audit_logs_table(db).await?;
+        
+        // 创建个人信息脱敏映射表
+        Self::create_pii_mappings_table(db).await?;
+        
+        // 创建 CI 检查结果表
+        Self::create_ci_check_runs_table(db).await?;
+        
+        // 创建远程工作进程表
+        Self::create_remote_workers_table(db).await?;
+        
+        // 创建SLA违约记录表
+        Self::create_sla_breaches_table(db).await?;
+        
+        // 创建webhook死信表
+        Self::create_webhook_dead_letters_table(db).await?;
+        
+        // 创建报告计划表
+        Self::create_report_schedules_table(db).await?;
+        
+        // 创建项目报告表
+        Self::create_project_reports_table(db).await?;
+        
+        // 创建LLM响应缓存表
+        Self::create_llm_cache_entries_table(db).await?;
+        
+        // 创建Agent自我评估表
+        Self::create_agent_self_assessments_table(db).await?;
+        
+        // 创建任务人工检查点表
+        Self::create_task_gates_table(db).await?;
+        
+        // 创建测试用例结果表
+        Self::create_test_case_results_table(db).await?;
+        
+        // 创建覆盖率报告表
+        Self::create_coverage_reports_table(db).await?;
+        
+        // 创建静态分析发现表
+        Self::create_lint_findings_table(db).await?;
+        
+        // 创建安全扫描发现表
+        Self::create_security_findings_table(db).await?;
+        
+        // 创建基准测试结果表
+        Self::create_benchmark_results_table(db).await?;
+        
+        // 创建API密钥表
+        Self::create_api_keys_table(db).await?;
+        
+        // 创建外部身份表
+        Self::create_user_identities_table(db).await?;
+        
+        // 创建两步验证表
+        Self::create_user_two_factor_table(db).await?;
+        
+        // 创建通知表
+        Self::create_notifications_table(db).await?;
+        
+        // 创建幂等键表
+        Self::create_idempotency_keys_table(db).await?;
+        
+        // 创建维护任务表
+        Self::create_maintenance_jobs_table(db).await?;
+        
+        // 创建维护任务执行记录表
+        Self::create_maintenance_job_runs_table(db).await?;
+        
+        // 创建每日指标汇总表
+        Self::create_daily_metrics_tables(db).await?;
+        
+        // 创建转存负载表
+        Self::create_offloaded_payloads_table(db).await?;
+        // 创建项目数据库文件登记表
+        Self::create_project_partitions_table(db).await?;
+        // 创建项目上下文快照表
+        Self::create_context_snapshots_table(db).await?;
+        // 创建LLM调用记录表
+        Self::create_llm_transcripts_table(db).await?;
+        // 创建LLM会话重放报告表
+        Self::create_llm_replay_reports_table(db).await?;
+        // 创建提示词评估表
+        Self::create_eval_tables(db).await?;
+        // 创建热点查询的复合索引
+        Self::create_hot_path_indexes(db).await?;
+        
         Ok(())
     }
     
@@ -124,6 +244,10 @@ impl Migrator {
                 last_active_at TEXT NOT NULL,
                 ip_address TEXT,
                 user_agent TEXT,
+                device_name TEXT,
+                refresh_expires_at TEXT,
+                previous_refresh_token TEXT,
+                revoked_at TEXT,
                 is_active BOOLEAN NOT NULL DEFAULT 1,
                 FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE
             )
@@ -131,9 +255,16 @@ impl Migrator {
         
         db.execute_unprepared(sql).await?;
         
+        // 旧数据库补充会话设备与刷新令牌轮换字段
+        Self::add_column_if_missing(db, "user_sessions", "device_name", "TEXT").await?;
+        Self::add_column_if_missing(db, "user_sessions", "refresh_expires_at", "TEXT").await?;
+        Self::add_column_if_missing(db, "user_sessions", "previous_refresh_token", "TEXT").await?;
+        Self::add_column_if_missing(db, "user_sessions", "revoked_at", "TEXT").await?;
+        
         // 创建索引
         let index_sql = vec![
             "CREATE INDEX IF NOT EXISTS idx_user_sessions_user_id ON user_sessions(user_id)",
+            "CREATE INDEX IF NOT EXISTS idx_user_sessions_previous_refresh_token ON user_sessions(previous_refresh_token)",
             "CREATE INDEX IF NOT EXISTS idx_user_sessions_token ON user_sessions(token)",
             "CREATE INDEX IF NOT EXISTS idx_user_sessions_refresh_token ON user_sessions(refresh_token)",
             "CREATE INDEX IF NOT EXISTS idx_user_sessions_active ON user_sessions(is_active)",
@@ -171,16 +302,46 @@ impl Migrator {
                 updated_at TEXT NOT NULL,
                 quality_standards TEXT,
                 automation_config TEXT,
-                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE
+                organization_id TEXT,
+                workspace_quota TEXT,
+                pii_scrubbing TEXT,
+                preemption_policy TEXT,
+                sla_policy TEXT,
+                code_ownership TEXT,
+                perf_budget TEXT,
+                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+                FOREIGN KEY (organization_id) REFERENCES organizations(organization_id) ON DELETE SET NULL
             )
         "#;
         
         db.execute_unprepared(sql).await?;
         
+        // 旧数据库补充组织归属字段
+        Self::add_column_if_missing(db, "projects", "organization_id", "TEXT").await?;
+        
+        // 旧数据库补充工作空间配额字段
+        Self::add_column_if_missing(db, "projects", "workspace_quota", "TEXT").await?;
+        
+        // 旧数据库补充个人信息脱敏配置字段
+        Self::add_column_if_missing(db, "projects", "pii_scrubbing", "TEXT").await?;
+        
+        // 旧数据库补充抢占策略字段
+        Self::add_column_if_missing(db, "projects", "preemption_policy", "TEXT").await?;
+        
+        // 旧数据库补充SLA配置字段
+        Self::add_column_if_missing(db, "projects", "sla_policy", "TEXT").await?;
+        
+        // 旧数据库补充代码归属规则字段
+        Self::add_column_if_missing(db, "projects", "code_ownership", "TEXT").await?;
+        
+        // 旧数据库补充性能预算字段
+        Self::add_column_if_missing(db, "projects", "perf_budget", "TEXT").await?;
+        
         // 创建索引
         let index_sql = vec![
             "CREATE INDEX IF NOT EXISTS idx_projects_user_id ON projects(user_id)",
             "CREATE INDEX IF NOT EXISTS idx_projects_status ON projects(status)",
+            "CREATE INDEX IF NOT EXISTS idx_projects_organization ON projects(organization_id)",
         ];
         
         for sql in index_sql {
@@ -334,6 +495,9 @@ impl Migrator {
                 dependency_count INTEGER NOT NULL DEFAULT 0,
                 blocking_tasks_count INTEGER NOT NULL DEFAULT 0,
                 execution_result TEXT,
+                lease_owner TEXT,
+                lease_expires_at TEXT,
+                scope_path TEXT,
                 FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
                 FOREIGN KEY (parent_task_id) REFERENCES tasks(task_id) ON DELETE CASCADE,
                 FOREIGN KEY (llm_session_id) REFERENCES llm_sessions(session_id) ON DELETE SET NULL
@@ -342,13 +506,19 @@ impl Migrator {
         
         db.execute_unprepared(sql).await?;
         
+        // 旧数据库补充任务租约字段
+        Self::add_column_if_missing(db, "tasks", "lease_owner", "TEXT").await?;
+        Self::add_column_if_missing(db, "tasks", "lease_expires_at", "TEXT").await?;
+        // 旧数据库补充任务工作范围字段
+        Self::add_column_if_missing(db, "tasks", "scope_path", "TEXT").await?;
+        
         // 创建索引
         let index_sql = vec![
-            "CREATE INDEX IF NOT EXISTS idx_tasks_project ON tasks(project_id)",
             "CREATE INDEX IF NOT EXISTS idx_tasks_agent ON tasks(assigned_agent_id)",
             "CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status)",
             "CREATE INDEX IF NOT EXISTS idx_tasks_parent ON tasks(parent_task_id)",
             "CREATE INDEX IF NOT EXISTS idx_tasks_priority ON tasks(priority)",
+            "CREATE INDEX IF NOT EXISTS idx_tasks_lease ON tasks(lease_expires_at)",
         ];
         
         for sql in index_sql {
@@ -384,15 +554,21 @@ impl Migrator {
                 skill_profile TEXT,
                 skill_assessments TEXT,
                 performance_trend TEXT,
-                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE
+                organization_id TEXT,
+                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+                FOREIGN KEY (organization_id) REFERENCES organizations(organization_id) ON DELETE SET NULL
             )
         "#;
         
         db.execute_unprepared(sql).await?;
         
+        // 旧数据库补充组织Agent池字段
+        Self::add_column_if_missing(db, "agents", "organization_id", "TEXT").await?;
+        
         // 创建索引
         let index_sql = vec![
             "CREATE INDEX IF NOT EXISTS idx_agents_user_id ON agents(user_id)",
+            "CREATE INDEX IF NOT EXISTS idx_agents_organization ON agents(organization_id)",
             "CREATE INDEX IF NOT EXISTS idx_agents_status ON agents(status)",
             "CREATE INDEX IF NOT EXISTS idx_agents_capabilities ON agents(capabilities)",
         ];
@@ -468,6 +644,8 @@ impl Migrator {
                 success BOOLEAN,
                 result_data TEXT,
                 error_message TEXT,
+                checkpoint TEXT,
+                worktree_path TEXT,
                 FOREIGN KEY (task_id) REFERENCES tasks(task_id) ON DELETE CASCADE,
                 FOREIGN KEY (agent_id) REFERENCES agents(agent_id) ON DELETE CASCADE,
                 FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE
@@ -476,6 +654,11 @@ impl Migrator {
         
         db.execute_unprepared(sql).await?;
         
+        // 旧数据库补充检查点字段
+        Self::add_column_if_missing(db, "execution_sessions", "checkpoint", "TEXT").await?;
+        // 旧数据库补充工作树字段
+        Self::add_column_if_missing(db, "execution_sessions", "worktree_path", "TEXT").await?;
+        
         // 创建索引
         let index_sql = vec![
             "CREATE INDEX IF NOT EXISTS idx_execution_sessions_task ON execution_sessions(task_id)",
@@ -513,7 +696,6 @@ impl Migrator {
         
         // 创建索引
         let index_sql = vec![
-            "CREATE INDEX IF NOT EXISTS idx_execution_logs_session ON execution_logs(session_id)",
             "CREATE INDEX IF NOT EXISTS idx_execution_logs_level ON execution_logs(log_level)",
             "CREATE INDEX IF NOT EXISTS idx_execution_logs_type ON execution_logs(event_type)",
             "CREATE INDEX IF NOT EXISTS idx_execution_logs_timestamp ON execution_logs(timestamp_ms)",
@@ -526,31 +708,22 @@ impl Migrator {
         Ok(())
     }
     
-    /// 创建冲突表
-    async fn create_conflicts_table<C>(db: &C) -> Result<(), DbErr>
+    /// 创建执行工件表
+    async fn create_execution_artifacts_table<C>(db: &C) -> Result<(), DbErr>
     where
         C: ConnectionTrait,
     {
         let sql = r#"
-            CREATE TABLE IF NOT EXISTS conflicts (
-                conflict_id TEXT PRIMARY KEY,
-                conflict_type TEXT NOT NULL,
-                severity TEXT NOT NULL,
-                title TEXT NOT NULL,
-                description TEXT NOT NULL,
-                related_entities TEXT NOT NULL,
-                affected_tasks TEXT NOT NULL,
-                affected_agents TEXT NOT NULL,
-                status TEXT NOT NULL DEFAULT 'detected',
-                escalated_to_human BOOLEAN NOT NULL DEFAULT 0,
-                assigned_user_id TEXT,
-                resolution_strategy TEXT,
-                resolution_note TEXT,
-                auto_resolved BOOLEAN NOT NULL DEFAULT 0,
-                detected_at TEXT NOT NULL,
-                escalated_at TEXT,
-                resolved_at TEXT,
-                FOREIGN KEY (assigned_user_id) REFERENCES users(user_id) ON DELETE SET NULL
+            CREATE TABLE IF NOT EXISTS execution_artifacts (
+                artifact_id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                artifact_type TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                description TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (session_id) REFERENCES execution_sessions(session_id) ON DELETE CASCADE
             )
         "#;
         
@@ -558,11 +731,9 @@ impl Migrator {
         
         // 创建索引
         let index_sql = vec![
-            "CREATE INDEX IF NOT EXISTS idx_conflicts_type ON conflicts(conflict_type)",
-            "CREATE INDEX IF NOT EXISTS idx_conflicts_severity ON conflicts(severity)",
-            "CREATE INDEX IF NOT EXISTS idx_conflicts_status ON conflicts(status)",
-            "CREATE INDEX IF NOT EXISTS idx_conflicts_escalated ON conflicts(escalated_to_human)",
-            "CREATE INDEX IF NOT EXISTS idx_conflicts_assigned ON conflicts(assigned_user_id)",
+            "CREATE INDEX IF NOT EXISTS idx_execution_artifacts_session ON execution_artifacts(session_id)",
+            "CREATE INDEX IF NOT EXISTS idx_execution_artifacts_checksum ON execution_artifacts(checksum)",
+            "CREATE INDEX IF NOT EXISTS idx_execution_artifacts_created ON execution_artifacts(created_at)",
         ];
         
         for sql in index_sql {
@@ -572,24 +743,26 @@ impl Migrator {
         Ok(())
     }
     
-    /// 创建人工决策表
-    async fn create_human_decisions_table<C>(db: &C) -> Result<(), DbErr>
+    /// 创建语义检索向量表
+    async fn create_embeddings_table<C>(db: &C) -> Result<(), DbErr>
     where
         C: ConnectionTrait,
     {
         let sql = r#"
-            CREATE TABLE IF NOT EXISTS human_decisions (
-                decision_id TEXT PRIMARY KEY,
-                conflict_id TEXT NOT NULL,
-                user_id TEXT NOT NULL,
-                decision_type TEXT NOT NULL,
-                decision_data TEXT,
-                reasoning TEXT,
-                affected_entities TEXT NOT NULL,
-                follow_up_actions TEXT NOT NULL,
+            CREATE TABLE IF NOT EXISTS embeddings (
+                embedding_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                source_type TEXT NOT NULL,
+                source_id TEXT NOT NULL,
+                chunk_index INTEGER NOT NULL,
+                content TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                model TEXT NOT NULL,
+                vector BLOB NOT NULL,
                 created_at TEXT NOT NULL,
-                FOREIGN KEY (conflict_id) REFERENCES conflicts(conflict_id) ON DELETE CASCADE,
-                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE
+                updated_at TEXT NOT NULL,
+                UNIQUE (source_type, source_id, chunk_index),
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE
             )
         "#;
         
@@ -597,8 +770,7 @@ impl Migrator {
         
         // 创建索引
         let index_sql = vec![
-            "CREATE INDEX IF NOT EXISTS idx_human_decisions_conflict ON human_decisions(conflict_id)",
-            "CREATE INDEX IF NOT EXISTS idx_human_decisions_user ON human_decisions(user_id)",
+            "CREATE INDEX IF NOT EXISTS idx_embeddings_project ON embeddings(project_id, source_type)",
         ];
         
         for sql in index_sql {
@@ -608,28 +780,24 @@ impl Migrator {
         Ok(())
     }
     
-    /// 创建领域事件表
-    async fn create_domain_events_table<C>(db: &C) -> Result<(), DbErr>
+    /// 创建需求覆盖关系表
+    async fn create_requirement_coverage_table<C>(db: &C) -> Result<(), DbErr>
     where
         C: ConnectionTrait,
     {
         let sql = r#"
-            CREATE TABLE IF NOT EXISTS domain_events (
-                event_id TEXT PRIMARY KEY,
-                event_type TEXT NOT NULL,
-                aggregate_type TEXT NOT NULL,
-                aggregate_id TEXT NOT NULL,
-                event_data TEXT NOT NULL,
-                event_version INTEGER NOT NULL DEFAULT 1,
-                user_id TEXT,
-                session_id TEXT,
-                correlation_id TEXT,
-                occurred_at TEXT NOT NULL,
-                processed_at TEXT,
-                is_processed BOOLEAN NOT NULL DEFAULT 0,
-                processing_attempts INTEGER NOT NULL DEFAULT 0,
-                error_message TEXT,
-                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE SET NULL
+            CREATE TABLE IF NOT EXISTS requirement_coverage (
+                coverage_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                document_id TEXT NOT NULL,
+                section_id TEXT NOT NULL,
+                section_title TEXT NOT NULL,
+                task_id TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                UNIQUE (document_id, section_id, task_id),
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
+                FOREIGN KEY (document_id) REFERENCES requirement_documents(document_id) ON DELETE CASCADE,
+                FOREIGN KEY (task_id) REFERENCES tasks(task_id) ON DELETE CASCADE
             )
         "#;
         
@@ -637,11 +805,8 @@ impl Migrator {
         
         // 创建索引
         let index_sql = vec![
-            "CREATE INDEX IF NOT EXISTS idx_domain_events_type ON domain_events(event_type)",
-            "CREATE INDEX IF NOT EXISTS idx_domain_events_aggregate ON domain_events(aggregate_type, aggregate_id)",
-            "CREATE INDEX IF NOT EXISTS idx_domain_events_occurred ON domain_events(occurred_at)",
-            "CREATE INDEX IF NOT EXISTS idx_domain_events_processed ON domain_events(is_processed)",
-            "CREATE INDEX IF NOT EXISTS idx_domain_events_correlation ON domain_events(correlation_id)",
+            "CREATE INDEX IF NOT EXISTS idx_requirement_coverage_project ON requirement_coverage(project_id)",
+            "CREATE INDEX IF NOT EXISTS idx_requirement_coverage_task ON requirement_coverage(task_id)",
         ];
         
         for sql in index_sql {
@@ -651,27 +816,28 @@ impl Migrator {
         Ok(())
     }
     
-    /// 创建事件发布日志表
-    async fn create_event_publish_log_table<C>(db: &C) -> Result<(), DbErr>
+    /// 创建验收标准验证记录表
+    async fn create_acceptance_verifications_table<C>(db: &C) -> Result<(), DbErr>
     where
         C: ConnectionTrait,
     {
         let sql = r#"
-            CREATE TABLE IF NOT EXISTS event_publish_log (
-                log_id TEXT PRIMARY KEY,
-                event_id TEXT NOT NULL,
-                subscriber_type TEXT NOT NULL,
-                subscriber_id TEXT NOT NULL,
+            CREATE TABLE IF NOT EXISTS acceptance_verifications (
+                verification_id TEXT PRIMARY KEY,
+                task_id TEXT NOT NULL,
+                criterion TEXT NOT NULL,
                 status TEXT NOT NULL DEFAULT 'pending',
-                attempts INTEGER NOT NULL DEFAULT 0,
-                max_attempts INTEGER NOT NULL DEFAULT 3,
-                response_data TEXT,
-                error_message TEXT,
+                evidence TEXT NOT NULL DEFAULT '[]',
+                verified_by_agent_id TEXT,
+                notes TEXT,
+                signed_off_by TEXT,
+                signed_off_at TEXT,
                 created_at TEXT NOT NULL,
-                sent_at TEXT,
-                delivered_at TEXT,
-                failed_at TEXT,
-                FOREIGN KEY (event_id) REFERENCES domain_events(event_id) ON DELETE CASCADE
+                updated_at TEXT NOT NULL,
+                UNIQUE (task_id, criterion),
+                FOREIGN KEY (task_id) REFERENCES tasks(task_id) ON DELETE CASCADE,
+                FOREIGN KEY (verified_by_agent_id) REFERENCES agents(agent_id) ON DELETE SET NULL,
+                FOREIGN KEY (signed_off_by) REFERENCES users(user_id) ON DELETE SET NULL
             )
         "#;
         
@@ -679,9 +845,7 @@ impl Migrator {
         
         // 创建索引
         let index_sql = vec![
-            "CREATE INDEX IF NOT EXISTS idx_event_publish_event ON event_publish_log(event_id)",
-            "CREATE INDEX IF NOT EXISTS idx_event_publish_status ON event_publish_log(status)",
-            "CREATE INDEX IF NOT EXISTS idx_event_publish_subscriber ON event_publish_log(subscriber_type, subscriber_id)",
+            "CREATE INDEX IF NOT EXISTS idx_acceptance_verifications_task ON acceptance_verifications(task_id)",
         ];
         
         for sql in index_sql {
@@ -691,30 +855,28 @@ impl Migrator {
         Ok(())
     }
     
-    /// 创建代码审查表
-    async fn create_code_reviews_table<C>(db: &C) -> Result<(), DbErr>
+    /// 创建项目黑板条目表
+    async fn create_blackboard_entries_table<C>(db: &C) -> Result<(), DbErr>
     where
         C: ConnectionTrait,
     {
         let sql = r#"
-            CREATE TABLE IF NOT EXISTS code_reviews (
-                review_id TEXT PRIMARY KEY,
-                task_id TEXT NOT NULL,
-                execution_session_id TEXT NOT NULL,
-                reviewer_agent_id TEXT NOT NULL,
-                pull_request_url TEXT NOT NULL,
-                source_branch TEXT NOT NULL,
-                target_branch TEXT NOT NULL,
-                review_comments TEXT NOT NULL,
-                code_changes TEXT NOT NULL,
-                status TEXT NOT NULL DEFAULT 'pending',
-                decision TEXT,
-                overall_comment TEXT,
+            CREATE TABLE IF NOT EXISTS blackboard_entries (
+                entry_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                key TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                category TEXT NOT NULL,
+                value TEXT NOT NULL,
+                include_in_prompts BOOLEAN NOT NULL DEFAULT FALSE,
+                deleted BOOLEAN NOT NULL DEFAULT FALSE,
+                author_user_id TEXT,
+                author_agent_id TEXT,
                 created_at TEXT NOT NULL,
-                reviewed_at TEXT,
-                FOREIGN KEY (task_id) REFERENCES tasks(task_id) ON DELETE CASCADE,
-                FOREIGN KEY (execution_session_id) REFERENCES execution_sessions(session_id) ON DELETE CASCADE,
-                FOREIGN KEY (reviewer_agent_id) REFERENCES agents(agent_id) ON DELETE CASCADE
+                UNIQUE (project_id, key, version),
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
+                FOREIGN KEY (author_user_id) REFERENCES users(user_id) ON DELETE SET NULL,
+                FOREIGN KEY (author_agent_id) REFERENCES agents(agent_id) ON DELETE SET NULL
             )
         "#;
         
@@ -722,10 +884,7 @@ impl Migrator {
         
         // 创建索引
         let index_sql = vec![
-            "CREATE INDEX IF NOT EXISTS idx_code_reviews_task ON code_reviews(task_id)",
-            "CREATE INDEX IF NOT EXISTS idx_code_reviews_session ON code_reviews(execution_session_id)",
-            "CREATE INDEX IF NOT EXISTS idx_code_reviews_reviewer ON code_reviews(reviewer_agent_id)",
-            "CREATE INDEX IF NOT EXISTS idx_code_reviews_status ON code_reviews(status)",
+            "CREATE INDEX IF NOT EXISTS idx_blackboard_entries_project ON blackboard_entries(project_id, key)",
         ];
         
         for sql in index_sql {
@@ -735,20 +894,25 @@ impl Migrator {
         Ok(())
     }
     
-    /// 创建任务依赖表
-    async fn create_task_dependencies_table<C>(db: &C) -> Result<(), DbErr>
+    /// 创建命令审批审计日志表
+    async fn create_command_audit_logs_table<C>(db: &C) -> Result<(), DbErr>
     where
         C: ConnectionTrait,
     {
         let sql = r#"
-            CREATE TABLE IF NOT EXISTS task_dependencies (
-                dependency_id TEXT PRIMARY KEY,
-                parent_task_id TEXT NOT NULL,
-                child_task_id TEXT NOT NULL,
-                dependency_type TEXT NOT NULL,
+            CREATE TABLE IF NOT EXISTS command_audit_logs (
+                audit_id TEXT PRIMARY KEY,
+                project_id TEXT,
+                agent_id TEXT,
+                conversation_id TEXT,
+                command TEXT NOT NULL,
+                working_dir TEXT NOT NULL,
+                risk_level TEXT NOT NULL,
+                decision TEXT NOT NULL,
+                matched_rules TEXT NOT NULL DEFAULT '[]',
                 created_at TEXT NOT NULL,
-                FOREIGN KEY (parent_task_id) REFERENCES tasks(task_id) ON DELETE CASCADE,
-                FOREIGN KEY (child_task_id) REFERENCES tasks(task_id) ON DELETE CASCADE
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
+                FOREIGN KEY (agent_id) REFERENCES agents(agent_id) ON DELETE SET NULL
             )
         "#;
         
@@ -756,9 +920,8 @@ impl Migrator {
         
         // 创建索引
         let index_sql = vec![
-            "CREATE INDEX IF NOT EXISTS idx_task_deps_parent ON task_dependencies(parent_task_id)",
-            "CREATE INDEX IF NOT EXISTS idx_task_deps_child ON task_dependencies(child_task_id)",
-            "CREATE INDEX IF NOT EXISTS idx_task_deps_type ON task_dependencies(dependency_type)",
+            "CREATE INDEX IF NOT EXISTS idx_command_audit_logs_project ON command_audit_logs(project_id, created_at)",
+            "CREATE INDEX IF NOT EXISTS idx_command_audit_logs_agent ON command_audit_logs(agent_id, created_at)",
         ];
         
         for sql in index_sql {
@@ -768,24 +931,56 @@ impl Migrator {
         Ok(())
     }
     
-    /// 创建Agent性能指标表
-    async fn create_agent_performance_metrics_table<C>(db: &C) -> Result<(), DbErr>
+    /// 创建个人信息脱敏映射表
+    async fn create_pii_mappings_table<C>(db: &C) -> Result<(), DbErr>
     where
         C: ConnectionTrait,
     {
         let sql = r#"
-            CREATE TABLE IF NOT EXISTS agent_performance_metrics (
-                metrics_id TEXT PRIMARY KEY,
-                agent_id TEXT NOT NULL,
-                period_start TEXT NOT NULL,
-                period_end TEXT NOT NULL,
-                tasks_completed INTEGER NOT NULL,
-                tasks_successful INTEGER NOT NULL,
-                avg_completion_time REAL NOT NULL,
-                avg_code_quality REAL NOT NULL,
-                skill_improvements TEXT NOT NULL,
+            CREATE TABLE IF NOT EXISTS pii_mappings (
+                mapping_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                placeholder TEXT NOT NULL,
+                label TEXT NOT NULL,
+                original TEXT NOT NULL,
                 created_at TEXT NOT NULL,
-                FOREIGN KEY (agent_id) REFERENCES agents(agent_id) ON DELETE CASCADE
+                UNIQUE (project_id, placeholder),
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        Ok(())
+    }
+    
+    /// 创建 CI 检查结果表
+    async fn create_ci_check_runs_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS ci_check_runs (
+                check_run_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                external_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                commit_sha TEXT NOT NULL,
+                branch TEXT,
+                pull_request_url TEXT,
+                execution_session_id TEXT,
+                review_id TEXT,
+                status TEXT NOT NULL,
+                details_url TEXT,
+                started_at TEXT,
+                completed_at TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE (project_id, provider, external_id),
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
+                FOREIGN KEY (execution_session_id) REFERENCES execution_sessions(session_id) ON DELETE SET NULL,
+                FOREIGN KEY (review_id) REFERENCES code_reviews(review_id) ON DELETE SET NULL
             )
         "#;
         
@@ -793,8 +988,43 @@ impl Migrator {
         
         // 创建索引
         let index_sql = vec![
-            "CREATE INDEX IF NOT EXISTS idx_perf_metrics_agent ON agent_performance_metrics(agent_id)",
-            "CREATE INDEX IF NOT EXISTS idx_perf_metrics_period ON agent_performance_metrics(period_start, period_end)",
+            "CREATE INDEX IF NOT EXISTS idx_ci_check_runs_commit ON ci_check_runs(project_id, commit_sha)",
+            "CREATE INDEX IF NOT EXISTS idx_ci_check_runs_session ON ci_check_runs(execution_session_id)",
+            "CREATE INDEX IF NOT EXISTS idx_ci_check_runs_review ON ci_check_runs(review_id)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建远程工作进程表
+    async fn create_remote_workers_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS remote_workers (
+                worker_id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                agent_ids TEXT NOT NULL DEFAULT '[]',
+                capabilities TEXT NOT NULL DEFAULT '[]',
+                max_concurrent_tasks INTEGER NOT NULL DEFAULT 1,
+                protocol_version INTEGER NOT NULL,
+                status TEXT NOT NULL DEFAULT 'online',
+                last_heartbeat_at TEXT NOT NULL,
+                registered_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_remote_workers_status ON remote_workers(status, last_heartbeat_at)",
         ];
         
         for sql in index_sql {
@@ -804,6 +1034,1564 @@ impl Migrator {
         Ok(())
     }
     
+    /// 创建SLA违约记录表
+    async fn create_sla_breaches_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS sla_breaches (
+                breach_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                sla_type TEXT NOT NULL,
+                subject_type TEXT NOT NULL,
+                subject_id TEXT NOT NULL,
+                subject_name TEXT NOT NULL,
+                severity TEXT NOT NULL,
+                threshold_minutes INTEGER NOT NULL,
+                actual_minutes INTEGER NOT NULL,
+                deadline TEXT,
+                detected_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                resolved_at TEXT,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_sla_breaches_project ON sla_breaches(project_id, resolved_at)",
+            "CREATE INDEX IF NOT EXISTS idx_sla_breaches_subject ON sla_breaches(sla_type, subject_id)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建webhook死信表
+    async fn create_webhook_dead_letters_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS webhook_dead_letters (
+                dead_letter_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                source TEXT NOT NULL,
+                event_name TEXT,
+                delivery_id TEXT,
+                payload TEXT NOT NULL,
+                error TEXT NOT NULL,
+                received_at TEXT NOT NULL,
+                replay_count INTEGER NOT NULL DEFAULT 0,
+                last_replayed_at TEXT,
+                resolved_at TEXT,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_webhook_dead_letters_project ON webhook_dead_letters(project_id, resolved_at)",
+            "CREATE INDEX IF NOT EXISTS idx_webhook_dead_letters_received ON webhook_dead_letters(received_at)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建报告计划表
+    async fn create_report_schedules_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS report_schedules (
+                schedule_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                cron_expression TEXT NOT NULL,
+                format TEXT NOT NULL,
+                template TEXT,
+                channels TEXT NOT NULL,
+                cost_per_1k_tokens REAL,
+                enabled BOOLEAN NOT NULL DEFAULT 1,
+                last_run_at TEXT,
+                next_run_at TEXT NOT NULL,
+                created_by TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_report_schedules_project ON report_schedules(project_id)",
+            "CREATE INDEX IF NOT EXISTS idx_report_schedules_due ON report_schedules(enabled, next_run_at)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建项目报告表
+    async fn create_project_reports_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS project_reports (
+                report_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                schedule_id TEXT,
+                title TEXT NOT NULL,
+                format TEXT NOT NULL,
+                period_start TEXT NOT NULL,
+                period_end TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                summary TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
+                FOREIGN KEY (schedule_id) REFERENCES report_schedules(schedule_id) ON DELETE SET NULL
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_project_reports_project ON project_reports(project_id, created_at)",
+            "CREATE INDEX IF NOT EXISTS idx_project_reports_checksum ON project_reports(checksum)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建LLM响应缓存表
+    async fn create_llm_cache_entries_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS llm_cache_entries (
+                cache_key TEXT PRIMARY KEY,
+                model TEXT NOT NULL,
+                prompt_hash TEXT NOT NULL,
+                response TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                hit_count INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                last_used_at TEXT NOT NULL,
+                expires_at TEXT NOT NULL
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_llm_cache_entries_expires ON llm_cache_entries(expires_at)",
+            "CREATE INDEX IF NOT EXISTS idx_llm_cache_entries_last_used ON llm_cache_entries(last_used_at)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建Agent自我评估表
+    async fn create_agent_self_assessments_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS agent_self_assessments (
+                assessment_id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL UNIQUE,
+                task_id TEXT NOT NULL,
+                agent_id TEXT NOT NULL,
+                confidence REAL NOT NULL,
+                summary TEXT NOT NULL,
+                concerns TEXT NOT NULL,
+                calibrated_confidence REAL NOT NULL,
+                escalated BOOLEAN NOT NULL DEFAULT 0,
+                review_decision TEXT,
+                created_at TEXT NOT NULL,
+                reviewed_at TEXT,
+                FOREIGN KEY (session_id) REFERENCES execution_sessions(session_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_agent_self_assessments_agent ON agent_self_assessments(agent_id, reviewed_at)",
+            "CREATE INDEX IF NOT EXISTS idx_agent_self_assessments_escalated ON agent_self_assessments(escalated)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建任务人工检查点表
+    async fn create_task_gates_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS task_gates (
+                gate_id TEXT PRIMARY KEY,
+                task_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                sequence INTEGER NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                resume_status TEXT,
+                requested_at TEXT,
+                decided_at TEXT,
+                decided_by TEXT,
+                comment TEXT,
+                waiting_seconds INTEGER NOT NULL DEFAULT 0,
+                request_count INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (task_id) REFERENCES tasks(task_id) ON DELETE CASCADE,
+                FOREIGN KEY (decided_by) REFERENCES users(user_id) ON DELETE SET NULL,
+                UNIQUE (task_id, name)
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_task_gates_task ON task_gates(task_id, sequence)",
+            "CREATE INDEX IF NOT EXISTS idx_task_gates_status ON task_gates(status)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建测试用例结果表
+    async fn create_test_case_results_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS test_case_results (
+                result_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                execution_session_id TEXT NOT NULL,
+                test_name TEXT NOT NULL,
+                outcome TEXT NOT NULL,
+                duration_ms INTEGER,
+                commit_sha TEXT,
+                recorded_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
+                FOREIGN KEY (execution_session_id) REFERENCES execution_sessions(session_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_test_case_results_history ON test_case_results(project_id, test_name, recorded_at)",
+            "CREATE INDEX IF NOT EXISTS idx_test_case_results_session ON test_case_results(execution_session_id)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建覆盖率报告表
+    async fn create_coverage_reports_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS coverage_reports (
+                report_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                execution_session_id TEXT NOT NULL UNIQUE,
+                format TEXT NOT NULL,
+                commit_sha TEXT,
+                base_report_id TEXT,
+                lines_total INTEGER NOT NULL,
+                lines_covered INTEGER NOT NULL,
+                line_rate REAL NOT NULL,
+                files TEXT NOT NULL DEFAULT '[]',
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
+                FOREIGN KEY (execution_session_id) REFERENCES execution_sessions(session_id) ON DELETE CASCADE,
+                FOREIGN KEY (base_report_id) REFERENCES coverage_reports(report_id) ON DELETE SET NULL
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_coverage_reports_commit ON coverage_reports(project_id, commit_sha, created_at)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建静态分析发现表
+    async fn create_lint_findings_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS lint_findings (
+                finding_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                execution_session_id TEXT NOT NULL,
+                tool TEXT NOT NULL,
+                language TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                line INTEGER,
+                "column" INTEGER,
+                rule TEXT,
+                severity TEXT NOT NULL,
+                message TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
+                FOREIGN KEY (execution_session_id) REFERENCES execution_sessions(session_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_lint_findings_session ON lint_findings(execution_session_id, tool)",
+            "CREATE INDEX IF NOT EXISTS idx_lint_findings_rule ON lint_findings(project_id, rule)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建安全扫描发现表
+    async fn create_security_findings_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS security_findings (
+                finding_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                execution_session_id TEXT NOT NULL,
+                scanner TEXT NOT NULL,
+                category TEXT NOT NULL,
+                severity TEXT NOT NULL,
+                title TEXT NOT NULL,
+                advisory_id TEXT,
+                package_name TEXT,
+                package_version TEXT,
+                fixed_versions TEXT NOT NULL DEFAULT '[]',
+                file_path TEXT,
+                line INTEGER,
+                remediation_task_id TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
+                FOREIGN KEY (execution_session_id) REFERENCES execution_sessions(session_id) ON DELETE CASCADE,
+                FOREIGN KEY (remediation_task_id) REFERENCES tasks(task_id) ON DELETE SET NULL
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_security_findings_session ON security_findings(execution_session_id, scanner)",
+            "CREATE INDEX IF NOT EXISTS idx_security_findings_project ON security_findings(project_id, severity)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建基准测试结果表
+    async fn create_benchmark_results_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS benchmark_results (
+                result_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                execution_session_id TEXT NOT NULL,
+                branch TEXT NOT NULL,
+                commit_sha TEXT,
+                suite TEXT NOT NULL,
+                name TEXT NOT NULL,
+                value REAL NOT NULL,
+                unit TEXT NOT NULL,
+                higher_is_better BOOLEAN NOT NULL DEFAULT 0,
+                is_baseline BOOLEAN NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
+                FOREIGN KEY (execution_session_id) REFERENCES execution_sessions(session_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_benchmark_results_session ON benchmark_results(execution_session_id, suite)",
+            "CREATE INDEX IF NOT EXISTS idx_benchmark_results_history ON benchmark_results(project_id, branch, suite, name, created_at)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建API密钥表
+    async fn create_api_keys_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS api_keys (
+                key_id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                project_id TEXT,
+                name TEXT NOT NULL,
+                key_prefix TEXT NOT NULL UNIQUE,
+                key_hash TEXT NOT NULL,
+                scopes TEXT NOT NULL DEFAULT '[]',
+                rate_limit_per_minute INTEGER NOT NULL DEFAULT 60,
+                request_count INTEGER NOT NULL DEFAULT 0,
+                throttled_count INTEGER NOT NULL DEFAULT 0,
+                last_used_at TEXT,
+                expires_at TEXT,
+                revoked_at TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_api_keys_user ON api_keys(user_id)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建外部身份表
+    async fn create_user_identities_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS user_identities (
+                identity_id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                subject TEXT NOT NULL,
+                email TEXT,
+                display_name TEXT,
+                refresh_token TEXT,
+                token_expires_at TEXT,
+                last_login_at TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE (provider, subject),
+                UNIQUE (user_id, provider),
+                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        Ok(())
+    }
+    
+    /// 创建两步验证表
+    async fn create_user_two_factor_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS user_two_factor (
+                user_id TEXT PRIMARY KEY,
+                secret TEXT,
+                enabled BOOLEAN NOT NULL DEFAULT 0,
+                required BOOLEAN NOT NULL DEFAULT 0,
+                backup_code_hashes TEXT NOT NULL DEFAULT '[]',
+                last_used_step INTEGER,
+                failed_attempts INTEGER NOT NULL DEFAULT 0,
+                locked_until TEXT,
+                confirmed_at TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 旧数据库补充失败次数与锁定字段
+        Self::add_column_if_missing(db, "user_two_factor", "failed_attempts", "INTEGER NOT NULL DEFAULT 0").await?;
+        Self::add_column_if_missing(db, "user_two_factor", "locked_until", "TEXT").await?;
+        
+        Ok(())
+    }
+    
+    /// 创建通知表
+    async fn create_notifications_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS notifications (
+                notification_id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                category TEXT NOT NULL,
+                channel TEXT NOT NULL,
+                title TEXT NOT NULL,
+                body TEXT NOT NULL,
+                project_id TEXT,
+                urgent BOOLEAN NOT NULL DEFAULT 0,
+                digest BOOLEAN NOT NULL DEFAULT 0,
+                deliver_at TEXT NOT NULL,
+                delivered_at TEXT,
+                read_at TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_notifications_due ON notifications(delivered_at, deliver_at)",
+            "CREATE INDEX IF NOT EXISTS idx_notifications_user ON notifications(user_id, channel, created_at)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建幂等键表
+    async fn create_idempotency_keys_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS idempotency_keys (
+                record_id TEXT PRIMARY KEY,
+                scope TEXT NOT NULL,
+                idempotency_key TEXT NOT NULL,
+                request_hash TEXT NOT NULL,
+                status TEXT NOT NULL,
+                result TEXT,
+                result_hash TEXT,
+                created_at TEXT NOT NULL,
+                completed_at TEXT,
+                expires_at TEXT NOT NULL,
+                UNIQUE (scope, idempotency_key)
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        db.execute_unprepared(
+            "CREATE INDEX IF NOT EXISTS idx_idempotency_keys_expires ON idempotency_keys(expires_at)",
+        ).await?;
+        
+        Ok(())
+    }
+    
+    /// 创建维护任务表
+    async fn create_maintenance_jobs_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS maintenance_jobs (
+                job_id TEXT PRIMARY KEY,
+                name TEXT NOT NULL UNIQUE,
+                kind TEXT NOT NULL,
+                cron_expression TEXT NOT NULL,
+                jitter_secs INTEGER NOT NULL DEFAULT 0,
+                params TEXT NOT NULL DEFAULT '{}',
+                enabled BOOLEAN NOT NULL DEFAULT 1,
+                next_run_at TEXT NOT NULL,
+                last_run_at TEXT,
+                last_status TEXT,
+                running_since TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        db.execute_unprepared(
+            "CREATE INDEX IF NOT EXISTS idx_maintenance_jobs_due ON maintenance_jobs(enabled, next_run_at)",
+        ).await?;
+        
+        Ok(())
+    }
+    
+    /// 创建每日指标汇总表
+    async fn create_daily_metrics_tables<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let project_sql = r#"
+            CREATE TABLE IF NOT EXISTS project_daily_metrics (
+                metric_id TEXT PRIMARY KEY,
+                metric_date TEXT NOT NULL,
+                project_id TEXT NOT NULL,
+                tasks_completed INTEGER NOT NULL DEFAULT 0,
+                tasks_failed INTEGER NOT NULL DEFAULT 0,
+                total_tokens INTEGER NOT NULL DEFAULT 0,
+                total_cost REAL NOT NULL DEFAULT 0,
+                error_count INTEGER NOT NULL DEFAULT 0,
+                computed_at TEXT NOT NULL,
+                UNIQUE (metric_date, project_id),
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        let agent_sql = r#"
+            CREATE TABLE IF NOT EXISTS agent_daily_metrics (
+                metric_id TEXT PRIMARY KEY,
+                metric_date TEXT NOT NULL,
+                agent_id TEXT NOT NULL,
+                project_id TEXT NOT NULL,
+                tasks_completed INTEGER NOT NULL DEFAULT 0,
+                tasks_failed INTEGER NOT NULL DEFAULT 0,
+                total_tokens INTEGER NOT NULL DEFAULT 0,
+                total_cost REAL NOT NULL DEFAULT 0,
+                error_count INTEGER NOT NULL DEFAULT 0,
+                computed_at TEXT NOT NULL,
+                UNIQUE (metric_date, agent_id, project_id),
+                FOREIGN KEY (agent_id) REFERENCES agents(agent_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        let days_sql = r#"
+            CREATE TABLE IF NOT EXISTS metric_rollup_days (
+                metric_date TEXT PRIMARY KEY,
+                project_count INTEGER NOT NULL DEFAULT 0,
+                agent_count INTEGER NOT NULL DEFAULT 0,
+                computed_at TEXT NOT NULL
+            )
+        "#;
+        
+        db.execute_unprepared(project_sql).await?;
+        db.execute_unprepared(agent_sql).await?;
+        db.execute_unprepared(days_sql).await?;
+        
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_project_daily_metrics_project ON project_daily_metrics(project_id, metric_date)",
+            "CREATE INDEX IF NOT EXISTS idx_agent_daily_metrics_agent ON agent_daily_metrics(agent_id, metric_date)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建转存负载表
+    async fn create_offloaded_payloads_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS offloaded_payloads (
+                payload_id TEXT PRIMARY KEY,
+                owner_table TEXT NOT NULL,
+                owner_id TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                original_size INTEGER NOT NULL,
+                stored_size INTEGER NOT NULL,
+                created_at TEXT NOT NULL
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_offloaded_payloads_owner ON offloaded_payloads(owner_table, owner_id)",
+            "CREATE INDEX IF NOT EXISTS idx_offloaded_payloads_checksum ON offloaded_payloads(checksum)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建项目数据库文件登记表
+    async fn create_project_partitions_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS project_partitions (
+                project_id TEXT PRIMARY KEY,
+                file_name TEXT NOT NULL UNIQUE,
+                created_at TEXT NOT NULL,
+                last_attached_at TEXT,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        Ok(())
+    }
+    
+    /// 创建项目上下文快照表
+    async fn create_context_snapshots_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS context_snapshots (
+                snapshot_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                document_id TEXT,
+                context TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
+                FOREIGN KEY (document_id) REFERENCES requirement_documents(document_id) ON DELETE SET NULL
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_context_snapshots_project ON context_snapshots(project_id, created_at)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建LLM调用记录表
+    async fn create_llm_transcripts_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS llm_transcripts (
+                transcript_id TEXT PRIMARY KEY,
+                call_kind TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                prompt TEXT NOT NULL,
+                request_params TEXT,
+                response TEXT,
+                error_message TEXT,
+                tokens_used INTEGER,
+                latency_ms INTEGER NOT NULL,
+                project_id TEXT,
+                task_id TEXT,
+                llm_session_id TEXT,
+                execution_session_id TEXT,
+                conversation_id TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
+                FOREIGN KEY (task_id) REFERENCES tasks(task_id) ON DELETE SET NULL,
+                FOREIGN KEY (llm_session_id) REFERENCES llm_sessions(session_id) ON DELETE SET NULL,
+                FOREIGN KEY (execution_session_id) REFERENCES execution_sessions(session_id) ON DELETE SET NULL
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 旧数据库补充调用参数字段，重放时使用相同的参数
+        Self::add_column_if_missing(db, "llm_transcripts", "request_params", "TEXT").await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_llm_transcripts_project ON llm_transcripts(project_id, created_at)",
+            "CREATE INDEX IF NOT EXISTS idx_llm_transcripts_task ON llm_transcripts(task_id)",
+            "CREATE INDEX IF NOT EXISTS idx_llm_transcripts_llm_session ON llm_transcripts(llm_session_id)",
+            "CREATE INDEX IF NOT EXISTS idx_llm_transcripts_execution_session ON llm_transcripts(execution_session_id)",
+            "CREATE INDEX IF NOT EXISTS idx_llm_transcripts_conversation ON llm_transcripts(conversation_id)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建LLM会话重放报告表
+    async fn create_llm_replay_reports_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS llm_replay_reports (
+                report_id TEXT PRIMARY KEY,
+                llm_session_id TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                model_override TEXT,
+                total_calls INTEGER NOT NULL,
+                changed_calls INTEGER NOT NULL,
+                failed_calls INTEGER NOT NULL,
+                calls TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (llm_session_id) REFERENCES llm_sessions(session_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_llm_replay_reports_session ON llm_replay_reports(llm_session_id, created_at)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建提示词评估表：评估集、评估运行和逐用例结果
+    async fn create_eval_tables<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS eval_sets (
+                eval_set_id TEXT PRIMARY KEY,
+                project_id TEXT,
+                name TEXT NOT NULL,
+                description TEXT,
+                cases TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS eval_runs (
+                run_id TEXT PRIMARY KEY,
+                eval_set_id TEXT NOT NULL,
+                variants TEXT NOT NULL,
+                judged BOOLEAN NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (eval_set_id) REFERENCES eval_sets(eval_set_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS eval_results (
+                result_id TEXT PRIMARY KEY,
+                run_id TEXT NOT NULL,
+                variant_name TEXT NOT NULL,
+                case_name TEXT NOT NULL,
+                model TEXT NOT NULL,
+                prompt TEXT NOT NULL,
+                output TEXT,
+                error_message TEXT,
+                score REAL NOT NULL,
+                passed BOOLEAN NOT NULL,
+                checks TEXT NOT NULL,
+                tokens_used INTEGER,
+                latency_ms INTEGER NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (run_id) REFERENCES eval_runs(run_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_eval_sets_project ON eval_sets(project_id, updated_at)",
+            "CREATE INDEX IF NOT EXISTS idx_eval_runs_set ON eval_runs(eval_set_id, created_at)",
+            "CREATE INDEX IF NOT EXISTS idx_eval_results_run ON eval_results(run_id, variant_name)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建维护任务执行记录表
+    async fn create_maintenance_job_runs_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS maintenance_job_runs (
+                run_id TEXT PRIMARY KEY,
+                job_id TEXT NOT NULL,
+                trigger TEXT NOT NULL,
+                status TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                finished_at TEXT,
+                duration_ms INTEGER,
+                result TEXT,
+                error_message TEXT,
+                FOREIGN KEY (job_id) REFERENCES maintenance_jobs(job_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_maintenance_job_runs_job ON maintenance_job_runs(job_id, started_at)",
+            "CREATE INDEX IF NOT EXISTS idx_maintenance_job_runs_started ON maintenance_job_runs(started_at)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建冲突表
+    async fn create_conflicts_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS conflicts (
+                conflict_id TEXT PRIMARY KEY,
+                conflict_type TEXT NOT NULL,
+                severity TEXT NOT NULL,
+                title TEXT NOT NULL,
+                description TEXT NOT NULL,
+                related_entities TEXT NOT NULL,
+                affected_tasks TEXT NOT NULL,
+                affected_agents TEXT NOT NULL,
+                project_id TEXT,
+                status TEXT NOT NULL DEFAULT 'detected',
+                escalated_to_human BOOLEAN NOT NULL DEFAULT 0,
+                assigned_user_id TEXT,
+                resolution_strategy TEXT,
+                resolution_note TEXT,
+                auto_resolved BOOLEAN NOT NULL DEFAULT 0,
+                detected_at TEXT NOT NULL,
+                escalated_at TEXT,
+                resolved_at TEXT,
+                FOREIGN KEY (assigned_user_id) REFERENCES users(user_id) ON DELETE SET NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 旧数据库补充所属项目字段
+        Self::add_column_if_missing(db, "conflicts", "project_id", "TEXT").await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_conflicts_type ON conflicts(conflict_type)",
+            "CREATE INDEX IF NOT EXISTS idx_conflicts_project ON conflicts(project_id)",
+            "CREATE INDEX IF NOT EXISTS idx_conflicts_severity ON conflicts(severity)",
+            "CREATE INDEX IF NOT EXISTS idx_conflicts_escalated ON conflicts(escalated_to_human)",
+            "CREATE INDEX IF NOT EXISTS idx_conflicts_assigned ON conflicts(assigned_user_id)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建人工决策表
+    async fn create_human_decisions_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS human_decisions (
+                decision_id TEXT PRIMARY KEY,
+                conflict_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                decision_type TEXT NOT NULL,
+                decision_data TEXT,
+                reasoning TEXT,
+                affected_entities TEXT NOT NULL,
+                follow_up_actions TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (conflict_id) REFERENCES conflicts(conflict_id) ON DELETE CASCADE,
+                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_human_decisions_conflict ON human_decisions(conflict_id)",
+            "CREATE INDEX IF NOT EXISTS idx_human_decisions_user ON human_decisions(user_id)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建领域事件表
+    async fn create_domain_events_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS domain_events (
+                event_id TEXT PRIMARY KEY,
+                event_type TEXT NOT NULL,
+                aggregate_type TEXT NOT NULL,
+                aggregate_id TEXT NOT NULL,
+                event_data TEXT NOT NULL,
+                event_version INTEGER NOT NULL DEFAULT 1,
+                user_id TEXT,
+                session_id TEXT,
+                correlation_id TEXT,
+                occurred_at TEXT NOT NULL,
+                processed_at TEXT,
+                is_processed BOOLEAN NOT NULL DEFAULT 0,
+                processing_attempts INTEGER NOT NULL DEFAULT 0,
+                error_message TEXT,
+                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE SET NULL
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_domain_events_type ON domain_events(event_type)",
+            "CREATE INDEX IF NOT EXISTS idx_domain_events_aggregate ON domain_events(aggregate_type, aggregate_id)",
+            "CREATE INDEX IF NOT EXISTS idx_domain_events_occurred ON domain_events(occurred_at)",
+            "CREATE INDEX IF NOT EXISTS idx_domain_events_processed ON domain_events(is_processed)",
+            "CREATE INDEX IF NOT EXISTS idx_domain_events_correlation ON domain_events(correlation_id)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建事件发布日志表
+    async fn create_event_publish_log_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS event_publish_log (
+                log_id TEXT PRIMARY KEY,
+                event_id TEXT NOT NULL,
+                subscriber_type TEXT NOT NULL,
+                subscriber_id TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                attempts INTEGER NOT NULL DEFAULT 0,
+                max_attempts INTEGER NOT NULL DEFAULT 3,
+                response_data TEXT,
+                error_message TEXT,
+                created_at TEXT NOT NULL,
+                sent_at TEXT,
+                delivered_at TEXT,
+                failed_at TEXT,
+                FOREIGN KEY (event_id) REFERENCES domain_events(event_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_event_publish_event ON event_publish_log(event_id)",
+            "CREATE INDEX IF NOT EXISTS idx_event_publish_status ON event_publish_log(status)",
+            "CREATE INDEX IF NOT EXISTS idx_event_publish_subscriber ON event_publish_log(subscriber_type, subscriber_id)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建代码审查表
+    async fn create_code_reviews_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS code_reviews (
+                review_id TEXT PRIMARY KEY,
+                task_id TEXT NOT NULL,
+                execution_session_id TEXT NOT NULL,
+                reviewer_agent_id TEXT NOT NULL,
+                pull_request_url TEXT NOT NULL,
+                source_branch TEXT NOT NULL,
+                target_branch TEXT NOT NULL,
+                review_comments TEXT NOT NULL,
+                code_changes TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                decision TEXT,
+                overall_comment TEXT,
+                created_at TEXT NOT NULL,
+                reviewed_at TEXT,
+                diff_artifact_id TEXT,
+                priority TEXT NOT NULL DEFAULT 'normal',
+                reviewer_user_id TEXT,
+                assigned_at TEXT,
+                review_deadline TEXT,
+                reminded_at TEXT,
+                escalated_at TEXT,
+                reassignment_count INTEGER NOT NULL DEFAULT 0,
+                FOREIGN KEY (task_id) REFERENCES tasks(task_id) ON DELETE CASCADE,
+                FOREIGN KEY (execution_session_id) REFERENCES execution_sessions(session_id) ON DELETE CASCADE,
+                FOREIGN KEY (reviewer_agent_id) REFERENCES agents(agent_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 旧数据库补充会话差异工件字段
+        Self::add_column_if_missing(db, "code_reviews", "diff_artifact_id", "TEXT").await?;
+        
+        // 旧数据库补充审查时限与改派字段
+        Self::add_column_if_missing(db, "code_reviews", "priority", "TEXT NOT NULL DEFAULT 'normal'").await?;
+        Self::add_column_if_missing(db, "code_reviews", "reviewer_user_id", "TEXT").await?;
+        Self::add_column_if_missing(db, "code_reviews", "assigned_at", "TEXT").await?;
+        Self::add_column_if_missing(db, "code_reviews", "review_deadline", "TEXT").await?;
+        Self::add_column_if_missing(db, "code_reviews", "reminded_at", "TEXT").await?;
+        Self::add_column_if_missing(db, "code_reviews", "escalated_at", "TEXT").await?;
+        Self::add_column_if_missing(db, "code_reviews", "reassignment_count", "INTEGER NOT NULL DEFAULT 0").await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_code_reviews_task ON code_reviews(task_id)",
+            "CREATE INDEX IF NOT EXISTS idx_code_reviews_session ON code_reviews(execution_session_id)",
+            "CREATE INDEX IF NOT EXISTS idx_code_reviews_reviewer ON code_reviews(reviewer_agent_id)",
+            "CREATE INDEX IF NOT EXISTS idx_code_reviews_status ON code_reviews(status)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建任务依赖表
+    async fn create_task_dependencies_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS task_dependencies (
+                dependency_id TEXT PRIMARY KEY,
+                parent_task_id TEXT NOT NULL,
+                child_task_id TEXT NOT NULL,
+                dependency_type TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (parent_task_id) REFERENCES tasks(task_id) ON DELETE CASCADE,
+                FOREIGN KEY (child_task_id) REFERENCES tasks(task_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 旧数据库补充跨项目依赖字段
+        Self::add_column_if_missing(db, "task_dependencies", "is_cross_project", "BOOLEAN NOT NULL DEFAULT 0").await?;
+        Self::add_column_if_missing(db, "task_dependencies", "created_by", "TEXT").await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_task_deps_parent ON task_dependencies(parent_task_id)",
+            "CREATE INDEX IF NOT EXISTS idx_task_deps_child ON task_dependencies(child_task_id)",
+            "CREATE INDEX IF NOT EXISTS idx_task_deps_type ON task_dependencies(dependency_type)",
+            "CREATE INDEX IF NOT EXISTS idx_task_deps_cross_project ON task_dependencies(is_cross_project)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建Agent性能指标表
+    async fn create_agent_performance_metrics_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS agent_performance_metrics (
+                metrics_id TEXT PRIMARY KEY,
+                agent_id TEXT NOT NULL,
+                period_start TEXT NOT NULL,
+                period_end TEXT NOT NULL,
+                tasks_completed INTEGER NOT NULL,
+                tasks_successful INTEGER NOT NULL,
+                avg_completion_time REAL NOT NULL,
+                avg_code_quality REAL NOT NULL,
+                skill_improvements TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (agent_id) REFERENCES agents(agent_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_perf_metrics_agent ON agent_performance_metrics(agent_id)",
+            "CREATE INDEX IF NOT EXISTS idx_perf_metrics_period ON agent_performance_metrics(period_start, period_end)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建技术债务快照表
+    async fn create_technical_debt_snapshots_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS technical_debt_snapshots (
+                snapshot_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                commit_hash TEXT,
+                estimated_hours INTEGER NOT NULL DEFAULT 0,
+                severity_distribution TEXT NOT NULL,
+                main_debt_types TEXT NOT NULL,
+                findings TEXT NOT NULL,
+                trend TEXT NOT NULL DEFAULT 'stable',
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_debt_snapshots_project ON technical_debt_snapshots(project_id, created_at)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建合并冲突解决记录表
+    async fn create_merge_resolutions_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS merge_resolutions (
+                resolution_id TEXT PRIMARY KEY,
+                conflict_id TEXT,
+                file_path TEXT NOT NULL,
+                hunk_hash TEXT NOT NULL,
+                resolution TEXT NOT NULL,
+                strategy TEXT NOT NULL,
+                confidence REAL NOT NULL DEFAULT 0,
+                status TEXT NOT NULL DEFAULT 'pending',
+                use_count INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (conflict_id) REFERENCES conflicts(conflict_id) ON DELETE SET NULL
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_merge_resolutions_hash ON merge_resolutions(hunk_hash, status)",
+            "CREATE INDEX IF NOT EXISTS idx_merge_resolutions_conflict ON merge_resolutions(conflict_id)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建项目成员表
+    async fn create_project_members_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS project_members (
+                member_id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                role TEXT NOT NULL DEFAULT 'viewer',
+                invited_by TEXT,
+                joined_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE (project_id, user_id),
+                FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE,
+                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+                FOREIGN KEY (invited_by) REFERENCES users(user_id) ON DELETE SET NULL
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_project_members_project ON project_members(project_id)",
+            "CREATE INDEX IF NOT EXISTS idx_project_members_user ON project_members(user_id)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建组织表
+    async fn create_organizations_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS organizations (
+                organization_id TEXT PRIMARY KEY,
+                name TEXT NOT NULL UNIQUE,
+                description TEXT,
+                owner_id TEXT NOT NULL,
+                settings TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (owner_id) REFERENCES users(user_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        db.execute_unprepared(
+            "CREATE INDEX IF NOT EXISTS idx_organizations_owner ON organizations(owner_id)"
+        ).await?;
+        
+        Ok(())
+    }
+    
+    /// 创建团队表
+    async fn create_teams_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS teams (
+                team_id TEXT PRIMARY KEY,
+                organization_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE (organization_id, name),
+                FOREIGN KEY (organization_id) REFERENCES organizations(organization_id) ON DELETE CASCADE
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        db.execute_unprepared(
+            "CREATE INDEX IF NOT EXISTS idx_teams_organization ON teams(organization_id)"
+        ).await?;
+        
+        Ok(())
+    }
+    
+    /// 创建组织成员表
+    async fn create_organization_members_table<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let sql = r#"
+            CREATE TABLE IF NOT EXISTS organization_members (
+                member_id TEXT PRIMARY KEY,
+                organization_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                team_id TEXT,
+                role TEXT NOT NULL DEFAULT 'member',
+                joined_at TEXT NOT NULL,
+                UNIQUE (organization_id, user_id),
+                FOREIGN KEY (organization_id) REFERENCES organizations(organization_id) ON DELETE CASCADE,
+                FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+                FOREIGN KEY (team_id) REFERENCES teams(team_id) ON DELETE SET NULL
+            )
+        "#;
+        
+        db.execute_unprepared(sql).await?;
+        
+        // 创建索引
+        let index_sql = vec![
+            "CREATE INDEX IF NOT EXISTS idx_organization_members_organization ON organization_members(organization_id)",
+            "CREATE INDEX IF NOT EXISTS idx_organization_members_user ON organization_members(user_id)",
+            "CREATE INDEX IF NOT EXISTS idx_organization_members_team ON organization_members(team_id)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 创建热点查询的复合索引
+    ///
+    /// 每个索引对应一条高频访问路径，`tests/query_plan_tests.rs` 用 `EXPLAIN QUERY PLAN`
+    /// 断言这些查询命中索引且不需要临时排序。
+    async fn create_hot_path_indexes<C>(db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let index_sql = vec![
+            // 项目任务按创建时间列出，以及导出时按 (created_at, task_id) 键集分页
+            "CREATE INDEX IF NOT EXISTS idx_tasks_project_created ON tasks(project_id, created_at, task_id)",
+            // 按状态筛选项目任务（find_by_status、find_ready_tasks），结果按创建时间排序
+            "CREATE INDEX IF NOT EXISTS idx_tasks_project_status ON tasks(project_id, status, created_at)",
+            // 就绪判断：按依赖方查找其前置任务
+            "CREATE INDEX IF NOT EXISTS idx_task_deps_child_parent ON task_dependencies(child_task_id, parent_task_id)",
+            // 未解决冲突按状态枚举查询，再按严重程度和检测时间排序
+            "CREATE INDEX IF NOT EXISTS idx_conflicts_status_severity ON conflicts(status, severity, detected_at)",
+            // 日志跟踪：会话内按创建时间顺序或倒序读取，导出时按 (created_at, log_id) 键集分页
+            "CREATE INDEX IF NOT EXISTS idx_execution_logs_session_created ON execution_logs(session_id, created_at, log_id)",
+            // 项目的执行会话按创建时间倒序列出，也用于按项目导出日志的子查询
+            "CREATE INDEX IF NOT EXISTS idx_execution_sessions_project ON execution_sessions(project_id, created_at)",
+            // 事件分发按发生时间读取未处理的事件
+            "CREATE INDEX IF NOT EXISTS idx_domain_events_unprocessed ON domain_events(is_processed, occurred_at)",
+        ];
+        
+        for sql in index_sql {
+            db.execute_unprepared(sql).await?;
+        }
+        
+        // 被上面复合索引的前缀覆盖的旧索引
+        for index in ["idx_tasks_project", "idx_conflicts_status", "idx_execution_logs_session"] {
+            db.execute_unprepared(&format!("DROP INDEX IF EXISTS {}", index)).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// 为已存在的表补充缺失的列（CREATE TABLE IF NOT EXISTS 不会修改旧表结构）
+    async fn add_column_if_missing<C>(db: &C, table: &str, column: &str, definition: &str) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let columns = db.query_all(sea_orm::Statement::from_string(
+            sea_orm::DatabaseBackend::Sqlite,
+            format!("PRAGMA table_info({})", table)
+        )).await?;
+        
+        let exists = columns
+            .iter()
+            .any(|row| row.try_get::<String>("", "name").map(|name| name == column).unwrap_or(false));
+        
+        if !exists {
+            db.execute_unprepared(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition)).await?;
+        }
+        
+        Ok(())
+    }
+    
     /// 检查迁移状态
     pub async fn status<C>(db: &C) -> Result<Vec<String>, DbErr>
     where
@@ -814,9 +2602,22 @@ impl Migrator {
             SELECT name FROM sqlite_master 
             WHERE type='table' AND name IN (
                 'users', 'user_sessions', 'projects', 'requirement_documents', 'llm_sessions', 'llm_conversations', 'tasks',
-                'agents', 'agent_work_history', 'execution_sessions', 'execution_logs',
+                'agents', 'agent_work_history', 'execution_sessions', 'execution_logs', 'execution_artifacts',
                 'conflicts', 'human_decisions', 'domain_events', 'event_publish_log',
-                'code_reviews', 'task_dependencies', 'agent_performance_metrics'
+                'code_reviews', 'task_dependencies', 'agent_performance_metrics',
+                'technical_debt_snapshots', 'merge_resolutions', 'project_members',
+                'organizations', 'teams', 'organization_members', 'embeddings',
+                'requirement_coverage', 'acceptance_verifications', 'blackboard_entries',
+                'command_audit_logs', 'pii_mappings', 'ci_check_runs', 'remote_workers',
+                'sla_breaches', 'webhook_dead_letters',
+                'report_schedules', 'project_reports', 'llm_cache_entries',
+                'agent_self_assessments', 'task_gates', 'test_case_results',
+                'coverage_reports', 'lint_findings', 'security_findings',
+                'benchmark_results', 'api_keys', 'user_identities', 'user_two_factor',
+                'notifications', 'idempotency_keys', 'maintenance_jobs', 'maintenance_job_runs',
+                'project_daily_metrics', 'agent_daily_metrics', 'metric_rollup_days',
+                'offloaded_payloads', 'project_partitions', 'context_snapshots',
+                'llm_transcripts', 'llm_replay_reports', 'eval_sets', 'eval_runs', 'eval_results'
             )
         "#;
         
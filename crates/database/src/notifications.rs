@@ -0,0 +1,396 @@
+//! 通知偏好与通知路由
+//!
+//! 每个用户的 [`NotificationPreferences`] 保存在用户设置的 `notification_preferences` 字段中：
+//! - 每个事件类别选择投递渠道，以及立即投递还是合并为摘要；
+//! - 免打扰时段按用户本地时间（UTC 偏移）计算，时段内桌面和邮件通知推迟到时段结束，
+//!   紧急通知可以按设置穿透，应用内通知不打扰用户，始终立即进入收件箱。
+//!
+//! [`NotificationRouter::route`] 按偏好为每个渠道生成一条带计划投递时间的通知记录，
+//! [`NotificationRouter::take_due`] 取出到期的通知，摘要通知按用户和渠道合并成一批。
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::{
+    entities::notification,
+    repository::{notification_repository::CreateNotificationData, NotificationRepository, UserRepository},
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 用户设置中保存通知偏好的字段
+pub const PREFERENCES_SETTINGS_KEY: &str = "notification_preferences";
+
+/// 默认摘要间隔（分钟）
+pub const DEFAULT_DIGEST_INTERVAL_MINUTES: u32 = 60;
+
+/// 摘要间隔的允许范围（分钟）
+const DIGEST_INTERVAL_RANGE: std::ops::RangeInclusive<u32> = 15..=1440;
+
+/// 通知的事件类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationCategory {
+    /// 任务状态变化
+    TaskUpdates,
+    /// 代码审查
+    Reviews,
+    /// SLA 违约
+    SlaAlerts,
+    /// CI 检查结果
+    CiResults,
+    /// 安全发现
+    Security,
+    /// 系统消息
+    System,
+}
+
+impl NotificationCategory {
+    /// 类别的字符串表示（与序列化格式一致）
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NotificationCategory::TaskUpdates => "task_updates",
+            NotificationCategory::Reviews => "reviews",
+            NotificationCategory::SlaAlerts => "sla_alerts",
+            NotificationCategory::CiResults => "ci_results",
+            NotificationCategory::Security => "security",
+            NotificationCategory::System => "system",
+        }
+    }
+}
+
+/// 投递渠道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationChannel {
+    /// 应用内收件箱
+    InApp,
+    /// 桌面系统通知
+    Desktop,
+    /// 邮件
+    Email,
+}
+
+impl NotificationChannel {
+    /// 渠道的字符串表示（与序列化格式一致）
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NotificationChannel::InApp => "in_app",
+            NotificationChannel::Desktop => "desktop",
+            NotificationChannel::Email => "email",
+        }
+    }
+
+    /// 是否会打扰用户（受免打扰时段限制）
+    fn interrupts(self) -> bool {
+        !matches!(self, NotificationChannel::InApp)
+    }
+}
+
+/// 投递方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryMode {
+    /// 立即投递
+    #[default]
+    Immediate,
+    /// 合并到定期摘要
+    Digest,
+}
+
+/// 单个事件类别的偏好
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryPreference {
+    pub channels: Vec<NotificationChannel>,
+    #[serde(default)]
+    pub delivery: DeliveryMode,
+}
+
+impl Default for CategoryPreference {
+    fn default() -> Self {
+        Self {
+            channels: vec![NotificationChannel::InApp, NotificationChannel::Desktop],
+            delivery: DeliveryMode::Immediate,
+        }
+    }
+}
+
+/// 免打扰时段（用户本地时间）
+///
+/// 时区以 UTC 偏移保存，由客户端在保存偏好时写入当前偏移；开始时间晚于结束时间表示跨越午夜
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    /// 本地时间相对 UTC 的偏移（分钟），东八区为 480
+    pub utc_offset_minutes: i32,
+    /// 紧急通知是否穿透免打扰时段
+    #[serde(default = "default_allow_urgent")]
+    pub allow_urgent: bool,
+}
+
+fn default_allow_urgent() -> bool {
+    true
+}
+
+impl QuietHours {
+    fn offset(&self) -> FixedOffset {
+        FixedOffset::east_opt(self.utc_offset_minutes * 60).unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+    }
+
+    /// 时间点是否处于免打扰时段
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let local = at.with_timezone(&self.offset()).time();
+        if self.start < self.end {
+            self.start <= local && local < self.end
+        } else if self.start > self.end {
+            local >= self.start || local < self.end
+        } else {
+            false
+        }
+    }
+
+    /// 时间点之后最近一次免打扰时段结束的时间
+    pub fn next_end(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let offset = self.offset();
+        let local = at.with_timezone(&offset);
+        let mut end = offset
+            .from_local_datetime(&local.date_naive().and_time(self.end))
+            .single()
+            .map(|time| time.with_timezone(&Utc))
+            .unwrap_or(at);
+        if end <= at {
+            end += Duration::days(1);
+        }
+        end
+    }
+}
+
+/// 用户的通知偏好
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    /// 各事件类别的偏好，未配置的类别使用默认值
+    #[serde(default)]
+    pub categories: BTreeMap<NotificationCategory, CategoryPreference>,
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+    /// 摘要间隔（分钟）
+    #[serde(default = "default_digest_interval")]
+    pub digest_interval_minutes: u32,
+}
+
+fn default_digest_interval() -> u32 {
+    DEFAULT_DIGEST_INTERVAL_MINUTES
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            categories: BTreeMap::new(),
+            quiet_hours: None,
+            digest_interval_minutes: DEFAULT_DIGEST_INTERVAL_MINUTES,
+        }
+    }
+}
+
+/// 某个渠道的投递计划
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedDelivery {
+    pub channel: NotificationChannel,
+    pub deliver_at: DateTime<Utc>,
+    pub digest: bool,
+}
+
+impl NotificationPreferences {
+    /// 从用户设置中读取通知偏好，没有配置或格式无效时使用默认值
+    pub fn from_settings(settings: Option<&serde_json::Value>) -> Self {
+        settings
+            .and_then(|settings| settings.get(PREFERENCES_SETTINGS_KEY))
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// 事件类别的偏好
+    pub fn category(&self, category: NotificationCategory) -> CategoryPreference {
+        self.categories.get(&category).cloned().unwrap_or_default()
+    }
+
+    /// 校验偏好
+    pub fn validate(&self) -> Result<()> {
+        if !DIGEST_INTERVAL_RANGE.contains(&self.digest_interval_minutes) {
+            return Err(DatabaseError::validation(format!(
+                "摘要间隔必须在 {} 到 {} 分钟之间",
+                DIGEST_INTERVAL_RANGE.start(),
+                DIGEST_INTERVAL_RANGE.end()
+            )));
+        }
+        if let Some(quiet_hours) = &self.quiet_hours {
+            if !(-720..=840).contains(&quiet_hours.utc_offset_minutes) {
+                return Err(DatabaseError::validation("无效的时区偏移"));
+            }
+            if quiet_hours.start == quiet_hours.end {
+                return Err(DatabaseError::validation("免打扰时段的开始和结束时间不能相同"));
+            }
+        }
+        Ok(())
+    }
+
+    /// 计算一条通知在各渠道的投递时间
+    pub fn plan(&self, category: NotificationCategory, urgent: bool, now: DateTime<Utc>) -> Vec<PlannedDelivery> {
+        let preference = self.category(category);
+        let digest = preference.delivery == DeliveryMode::Digest && !urgent;
+        let mut channels = preference.channels;
+        channels.sort();
+        channels.dedup();
+
+        channels
+            .into_iter()
+            .map(|channel| {
+                if !channel.interrupts() {
+                    return PlannedDelivery { channel, deliver_at: now, digest: false };
+                }
+                let mut deliver_at = if digest { self.next_digest_at(now) } else { now };
+                if let Some(quiet_hours) = &self.quiet_hours {
+                    if quiet_hours.contains(deliver_at) && !(urgent && quiet_hours.allow_urgent) {
+                        deliver_at = quiet_hours.next_end(deliver_at);
+                    }
+                }
+                PlannedDelivery { channel, deliver_at, digest }
+            })
+            .collect()
+    }
+
+    /// 下一个摘要投递时间（按间隔对齐）
+    fn next_digest_at(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let interval = i64::from(self.digest_interval_minutes.max(1)) * 60;
+        let next = (now.timestamp().div_euclid(interval) + 1) * interval;
+        Utc.timestamp_opt(next, 0).single().unwrap_or(now)
+    }
+}
+
+/// 待路由的通知
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    pub user_id: Uuid,
+    pub category: NotificationCategory,
+    pub title: String,
+    pub body: String,
+    pub project_id: Option<Uuid>,
+    pub urgent: bool,
+}
+
+/// 一批待投递的通知：立即投递的通知单独成批，摘要通知按用户和渠道合并
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliveryBatch {
+    pub user_id: Uuid,
+    pub channel: String,
+    pub digest: bool,
+    pub notifications: Vec<notification::Model>,
+}
+
+/// 通知路由
+pub struct NotificationRouter {
+    db: DatabaseConnection,
+}
+
+impl NotificationRouter {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 读取用户的通知偏好
+    pub async fn preferences(&self, user_id: Uuid) -> Result<NotificationPreferences> {
+        let user = UserRepository::new(self.db.clone())
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("User", user_id))?;
+        Ok(NotificationPreferences::from_settings(user.settings.as_ref()))
+    }
+
+    /// 保存用户的通知偏好，用户设置中的其他字段保持不变
+    pub async fn update_preferences(
+        &self,
+        user_id: Uuid,
+        preferences: NotificationPreferences,
+    ) -> Result<NotificationPreferences> {
+        preferences.validate()?;
+        let users = UserRepository::new(self.db.clone());
+        let user = users
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("User", user_id))?;
+        let mut settings = match user.settings {
+            Some(serde_json::Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+        settings.insert(PREFERENCES_SETTINGS_KEY.to_string(), serde_json::to_value(&preferences)?);
+        users.update_settings(user_id, serde_json::Value::Object(settings)).await?;
+        Ok(preferences)
+    }
+
+    /// 按用户偏好路由通知，返回各渠道生成的通知记录
+    pub async fn route(&self, notification: NewNotification) -> Result<Vec<notification::Model>> {
+        self.route_at(notification, Utc::now()).await
+    }
+
+    /// 在指定时间路由通知
+    pub async fn route_at(&self, notification: NewNotification, now: DateTime<Utc>) -> Result<Vec<notification::Model>> {
+        let preferences = self.preferences(notification.user_id).await?;
+        let repo = NotificationRepository::new(self.db.clone());
+        let mut created = Vec::new();
+        for planned in preferences.plan(notification.category, notification.urgent, now) {
+            created.push(
+                repo.create(CreateNotificationData {
+                    user_id: notification.user_id,
+                    category: notification.category.as_str().to_string(),
+                    channel: planned.channel.as_str().to_string(),
+                    title: notification.title.clone(),
+                    body: notification.body.clone(),
+                    project_id: notification.project_id,
+                    urgent: notification.urgent,
+                    digest: planned.digest,
+                    deliver_at: planned.deliver_at,
+                })
+                .await?,
+            );
+        }
+        Ok(created)
+    }
+
+    /// 取出到期的通知并标记为已投递
+    pub async fn take_due(&self, now: DateTime<Utc>) -> Result<Vec<DeliveryBatch>> {
+        let repo = NotificationRepository::new(self.db.clone());
+        let due = repo.find_due(now).await?;
+        if due.is_empty() {
+            return Ok(Vec::new());
+        }
+        repo.mark_delivered(due.iter().map(|notification| notification.notification_id).collect(), now)
+            .await?;
+
+        let mut batches: Vec<DeliveryBatch> = Vec::new();
+        let mut digests: BTreeMap<(Uuid, String), Vec<notification::Model>> = BTreeMap::new();
+        for notification in due {
+            if notification.digest {
+                digests
+                    .entry((notification.user_id, notification.channel.clone()))
+                    .or_default()
+                    .push(notification);
+            } else {
+                batches.push(DeliveryBatch {
+                    user_id: notification.user_id,
+                    channel: notification.channel.clone(),
+                    digest: false,
+                    notifications: vec![notification],
+                });
+            }
+        }
+        batches.extend(digests.into_iter().map(|((user_id, channel), notifications)| DeliveryBatch {
+            user_id,
+            channel,
+            digest: true,
+            notifications,
+        }));
+        Ok(batches)
+    }
+}
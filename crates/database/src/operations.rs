@@ -0,0 +1,378 @@
+//! 长时间运行操作的进度与取消
+//!
+//! [`OperationRegistry::start`] 登记一个操作并返回 [`OperationHandle`]。执行方通过句柄报告
+//! 阶段和进度百分比，并在适当的位置调用 [`OperationHandle::check_cancelled`]：
+//! [`OperationRegistry::cancel`] 只设置取消标记，由执行方在检查点自行结束（协作式取消）。
+//!
+//! 每次进度变化都以 [`OperationProgress`] 广播给 [`OperationRegistry::subscribe`] 的订阅者；
+//! 已结束的操作保留 [`FINISHED_RETENTION`] 供查询。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, Notify};
+use uuid::Uuid;
+
+use crate::{DatabaseError, Result};
+
+/// 已结束操作的保留时长
+pub const FINISHED_RETENTION: chrono::Duration = chrono::Duration::minutes(10);
+
+/// 进度广播通道容量，订阅者落后太多时会丢失中间进度
+const PROGRESS_CHANNEL_CAPACITY: usize = 256;
+
+/// 操作状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationStatus {
+    /// 运行中
+    Running,
+    /// 已完成
+    Completed,
+    /// 失败
+    Failed,
+    /// 已取消
+    Cancelled,
+}
+
+impl OperationStatus {
+    /// 是否已结束
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, OperationStatus::Running)
+    }
+}
+
+/// 操作进度
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationProgress {
+    pub operation_id: Uuid,
+    /// 操作类型，例如 "search_index"、"codebase_scan"
+    pub kind: String,
+    pub project_id: Option<Uuid>,
+    pub status: OperationStatus,
+    /// 当前阶段
+    pub phase: String,
+    /// 完成百分比（0-100）
+    pub percentage: f32,
+    pub message: Option<String>,
+    /// 是否已请求取消
+    pub cancel_requested: bool,
+    /// 完成时的结果
+    pub result: Option<JsonValue>,
+    /// 失败原因
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 取消令牌
+///
+/// 克隆的令牌共享同一个取消状态
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<CancellationState>,
+}
+
+#[derive(Debug, Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    /// 创建未取消的令牌
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 请求取消
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    /// 是否已请求取消
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 等待取消请求
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+struct OperationEntry {
+    progress: OperationProgress,
+    token: CancellationToken,
+}
+
+/// 操作登记表
+pub struct OperationRegistry {
+    operations: Mutex<HashMap<Uuid, OperationEntry>>,
+    sender: broadcast::Sender<OperationProgress>,
+}
+
+impl Default for OperationRegistry {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(PROGRESS_CHANNEL_CAPACITY);
+        Self {
+            operations: Mutex::new(HashMap::new()),
+            sender,
+        }
+    }
+}
+
+impl OperationRegistry {
+    /// 创建空的登记表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记新操作
+    pub fn start(self: &Arc<Self>, kind: &str, project_id: Option<Uuid>) -> OperationHandle {
+        let now = Utc::now();
+        let token = CancellationToken::new();
+        let progress = OperationProgress {
+            operation_id: Uuid::new_v4(),
+            kind: kind.to_string(),
+            project_id,
+            status: OperationStatus::Running,
+            phase: "准备".to_string(),
+            percentage: 0.0,
+            message: None,
+            cancel_requested: false,
+            result: None,
+            error: None,
+            started_at: now,
+            updated_at: now,
+        };
+        let operation_id = progress.operation_id;
+
+        self.prune_finished();
+        if let Ok(mut operations) = self.operations.lock() {
+            operations.insert(
+                operation_id,
+                OperationEntry {
+                    progress: progress.clone(),
+                    token: token.clone(),
+                },
+            );
+        }
+        let _ = self.sender.send(progress);
+
+        OperationHandle {
+            operation_id,
+            registry: self.clone(),
+            token,
+        }
+    }
+
+    /// 在后台运行操作，立即返回操作ID
+    ///
+    /// 操作返回的结果序列化后记录在进度的 `result` 中；操作崩溃时记为失败
+    pub fn spawn<F, Fut, T>(self: &Arc<Self>, kind: &str, project_id: Option<Uuid>, operation: F) -> Uuid
+    where
+        F: FnOnce(OperationHandle) -> Fut,
+        Fut: Future<Output = Result<T>> + Send + 'static,
+        T: Serialize + Send + 'static,
+    {
+        let handle = self.start(kind, project_id);
+        let operation_id = handle.id();
+        let task = tokio::spawn(operation(handle.clone()));
+        tokio::spawn(async move {
+            match task.await {
+                Ok(result) => handle.finish(result),
+                Err(e) => handle.finish::<()>(Err(DatabaseError::business_logic(format!("操作意外结束: {}", e)))),
+            }
+        });
+        operation_id
+    }
+
+    /// 查询操作进度
+    pub fn get(&self, operation_id: Uuid) -> Option<OperationProgress> {
+        self.operations
+            .lock()
+            .ok()?
+            .get(&operation_id)
+            .map(|entry| entry.progress.clone())
+    }
+
+    /// 列出运行中和最近结束的操作（按开始时间倒序）
+    pub fn list(&self) -> Vec<OperationProgress> {
+        self.prune_finished();
+        let mut operations: Vec<OperationProgress> = self
+            .operations
+            .lock()
+            .map(|operations| operations.values().map(|entry| entry.progress.clone()).collect())
+            .unwrap_or_default();
+        operations.sort_by_key(|operation| std::cmp::Reverse(operation.started_at));
+        operations
+    }
+
+    /// 请求取消操作，返回请求是否生效（操作已结束时为 false）
+    pub fn cancel(&self, operation_id: Uuid) -> Result<bool> {
+        let progress = {
+            let mut operations = self
+                .operations
+                .lock()
+                .map_err(|_| DatabaseError::business_logic("操作登记表不可用"))?;
+            let entry = operations
+                .get_mut(&operation_id)
+                .ok_or_else(|| DatabaseError::entity_not_found("Operation", operation_id))?;
+            if entry.progress.status.is_finished() {
+                return Ok(false);
+            }
+            entry.token.cancel();
+            entry.progress.cancel_requested = true;
+            entry.progress.updated_at = Utc::now();
+            entry.progress.clone()
+        };
+
+        let _ = self.sender.send(progress);
+        Ok(true)
+    }
+
+    /// 请求取消全部运行中的操作，返回请求生效的数量
+    pub fn cancel_all(&self) -> usize {
+        let running: Vec<Uuid> = self
+            .operations
+            .lock()
+            .map(|operations| {
+                operations
+                    .values()
+                    .filter(|entry| !entry.progress.status.is_finished())
+                    .map(|entry| entry.progress.operation_id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        running
+            .into_iter()
+            .filter(|operation_id| self.cancel(*operation_id).unwrap_or(false))
+            .count()
+    }
+
+    /// 订阅进度变化
+    pub fn subscribe(&self) -> broadcast::Receiver<OperationProgress> {
+        self.sender.subscribe()
+    }
+
+    fn update(&self, operation_id: Uuid, apply: impl FnOnce(&mut OperationProgress)) {
+        let progress = {
+            let Ok(mut operations) = self.operations.lock() else {
+                return;
+            };
+            let Some(entry) = operations.get_mut(&operation_id) else {
+                return;
+            };
+            if entry.progress.status.is_finished() {
+                return;
+            }
+            apply(&mut entry.progress);
+            entry.progress.updated_at = Utc::now();
+            entry.progress.clone()
+        };
+
+        let _ = self.sender.send(progress);
+    }
+
+    /// 移除超过保留时长的已结束操作
+    fn prune_finished(&self) {
+        let cutoff = Utc::now() - FINISHED_RETENTION;
+        if let Ok(mut operations) = self.operations.lock() {
+            operations.retain(|_, entry| !entry.progress.status.is_finished() || entry.progress.updated_at > cutoff);
+        }
+    }
+}
+
+/// 操作句柄
+///
+/// 克隆的句柄指向同一个操作，可以分发给多个执行方报告进度
+#[derive(Clone)]
+pub struct OperationHandle {
+    operation_id: Uuid,
+    registry: Arc<OperationRegistry>,
+    token: CancellationToken,
+}
+
+impl OperationHandle {
+    /// 操作ID
+    pub fn id(&self) -> Uuid {
+        self.operation_id
+    }
+
+    /// 取消令牌，可传给不直接持有句柄的执行方
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// 是否已请求取消
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// 取消检查点，已请求取消时返回 [`DatabaseError::OperationCancelled`]
+    pub fn check_cancelled(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(DatabaseError::OperationCancelled {
+                operation_id: self.operation_id.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// 进入新阶段
+    pub fn set_phase(&self, phase: &str, percentage: f32) {
+        let phase = phase.to_string();
+        self.registry.update(self.operation_id, |progress| {
+            progress.phase = phase;
+            progress.percentage = clamp_percentage(percentage);
+            progress.message = None;
+        });
+    }
+
+    /// 报告当前阶段的进度
+    pub fn report(&self, percentage: f32, message: Option<String>) {
+        self.registry.update(self.operation_id, |progress| {
+            progress.percentage = clamp_percentage(percentage);
+            progress.message = message;
+        });
+    }
+
+    /// 按操作结果结束操作，之后的进度报告将被忽略
+    ///
+    /// 取消导致的错误记为已取消，其余错误记为失败
+    pub fn finish<T: Serialize>(&self, result: Result<T>) {
+        let (status, result, error) = match result {
+            Ok(value) => (OperationStatus::Completed, serde_json::to_value(value).ok(), None),
+            Err(e) if e.is_operation_cancelled() => (OperationStatus::Cancelled, None, None),
+            Err(e) => (OperationStatus::Failed, None, Some(e.to_string())),
+        };
+        self.registry.update(self.operation_id, |progress| {
+            if status == OperationStatus::Completed {
+                progress.percentage = 100.0;
+            }
+            progress.status = status;
+            progress.result = result;
+            progress.error = error;
+        });
+    }
+}
+
+fn clamp_percentage(percentage: f32) -> f32 {
+    if percentage.is_nan() {
+        0.0
+    } else {
+        percentage.clamp(0.0, 100.0)
+    }
+}
@@ -0,0 +1,461 @@
+//! 编排默认配置（sker.toml）
+//!
+//! 质量门禁、调度上限、抢占策略、任务分配策略和LLM提供方等项目级行为统一由 [`OrchestrationConfig`] 描述，
+//! 按以下顺序逐层合并，后面的层覆盖前面的层：
+//! 1. 内置默认值；
+//! 2. 组织默认值（如组织设置中的默认质量门禁）；
+//! 3. 项目工作区根目录下的 `sker.toml`；
+//! 4. 界面覆盖。
+//!
+//! 合并以配置键为单位进行，每一层只需写出要覆盖的键。未知的键、类型错误和取值不合法都会报错，
+//! 解析结果记录每个键的生效来源，便于界面展示。
+//! [`OrchestrationConfigWatcher`] 轮询配置文件，生效值变化时返回 [`OrchestrationConfigChangedEvent`]。
+//!
+//! ```toml
+//! [quality_gates]
+//! min_test_coverage = 0.9
+//!
+//! [scheduler]
+//! max_parallel_sessions = 8
+//!
+//! [assignment]
+//! strategy = "cost_optimized"
+//! max_cost_per_task = 5.0
+//!
+//! [provider]
+//! name = "anthropic"
+//! model = "claude-sonnet"
+//! ```
+
+use codex_multi_agent::{
+    project_management::QualityGates, AssignmentPolicy, CodingStandards, EventFactory,
+    OrchestrationConfigChangedEvent, OrganizationSettings, ProjectId,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+use uuid::Uuid;
+
+use crate::{parallel_planner::ParallelExecutionConfig, preemption::PreemptionPolicy, DatabaseError, Result};
+
+/// 项目配置文件名，位于项目工作区根目录
+pub const CONFIG_FILE_NAME: &str = "sker.toml";
+
+/// 支持的LLM提供方
+pub const SUPPORTED_PROVIDERS: [&str; 3] = ["openai", "anthropic", "custom"];
+
+/// 配置层，按合并顺序排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigLayer {
+    /// 内置默认值
+    Defaults,
+    /// 组织默认值
+    Organization,
+    /// 项目配置文件
+    ProjectFile,
+    /// 界面覆盖
+    UiOverrides,
+}
+
+impl ConfigLayer {
+    /// 配置层的显示名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Defaults => "内置默认值",
+            Self::Organization => "组织默认值",
+            Self::ProjectFile => CONFIG_FILE_NAME,
+            Self::UiOverrides => "界面覆盖",
+        }
+    }
+}
+
+/// 编排默认配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrchestrationConfig {
+    /// 质量门禁
+    pub quality_gates: QualityGates,
+    /// 并行调度上限
+    pub scheduler: ParallelExecutionConfig,
+    /// 紧急任务抢占策略
+    pub preemption: PreemptionPolicy,
+    /// 任务分配策略和成本上限
+    pub assignment: AssignmentPolicy,
+    /// LLM提供方
+    pub provider: ProviderConfig,
+}
+
+impl Default for OrchestrationConfig {
+    fn default() -> Self {
+        Self {
+            quality_gates: CodingStandards::default().quality_gates,
+            scheduler: ParallelExecutionConfig::default(),
+            preemption: PreemptionPolicy::default(),
+            assignment: AssignmentPolicy::default(),
+            provider: ProviderConfig::default(),
+        }
+    }
+}
+
+impl OrchestrationConfig {
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        let gates = &self.quality_gates;
+        if !(0.0..=1.0).contains(&gates.min_test_coverage) {
+            return Err(DatabaseError::validation("quality_gates.min_test_coverage 必须在0到1之间"));
+        }
+        if !(0.0..=1.0).contains(&gates.max_duplication_rate) {
+            return Err(DatabaseError::validation("quality_gates.max_duplication_rate 必须在0到1之间"));
+        }
+        if self.scheduler.max_parallel_sessions == 0 {
+            return Err(DatabaseError::validation("scheduler.max_parallel_sessions 必须大于0"));
+        }
+        if self.scheduler.session_timeout_minutes <= 0 {
+            return Err(DatabaseError::validation("scheduler.session_timeout_minutes 必须大于0"));
+        }
+        self.preemption.validate()?;
+        for (key, ceiling) in [
+            ("assignment.max_cost_per_task", self.assignment.max_cost_per_task),
+            ("assignment.max_total_cost", self.assignment.max_total_cost),
+        ] {
+            if ceiling.is_some_and(|ceiling| !ceiling.is_finite() || ceiling <= 0.0) {
+                return Err(DatabaseError::validation(format!("{} 必须大于0", key)));
+            }
+        }
+        self.provider.validate()
+    }
+}
+
+/// LLM提供方配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderConfig {
+    /// 提供方名称，取值见 [`SUPPORTED_PROVIDERS`]
+    pub name: String,
+    /// 模型名称
+    pub model: String,
+    /// 接口地址，custom 提供方必填
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// 采样温度，为空时使用提供方的默认值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// 最大输出token数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+}
+
+impl Default for ProviderConfig {
+    fn default() -> Self {
+        Self {
+            name: "openai".to_string(),
+            model: "gpt-4".to_string(),
+            base_url: None,
+            temperature: None,
+            max_tokens: None,
+        }
+    }
+}
+
+impl ProviderConfig {
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        if !SUPPORTED_PROVIDERS.contains(&self.name.as_str()) {
+            return Err(DatabaseError::validation(format!(
+                "provider.name 不支持 {}，可选值：{}",
+                self.name,
+                SUPPORTED_PROVIDERS.join("、")
+            )));
+        }
+        if self.model.trim().is_empty() {
+            return Err(DatabaseError::validation("provider.model 不能为空"));
+        }
+        if self.name == "custom" && self.base_url.as_deref().is_none_or(|url| url.trim().is_empty()) {
+            return Err(DatabaseError::validation("custom 提供方必须配置 provider.base_url"));
+        }
+        if self.temperature.is_some_and(|temperature| !(0.0..=2.0).contains(&temperature)) {
+            return Err(DatabaseError::validation("provider.temperature 必须在0到2之间"));
+        }
+        Ok(())
+    }
+}
+
+/// 合并后的生效配置
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedOrchestrationConfig {
+    /// 生效配置
+    pub config: OrchestrationConfig,
+    /// 每个配置键（点分路径）的生效来源
+    pub sources: BTreeMap<String, ConfigLayer>,
+    /// 项目配置文件路径
+    pub config_path: PathBuf,
+    /// 项目配置文件是否存在
+    pub file_present: bool,
+}
+
+impl ResolvedOrchestrationConfig {
+    /// 配置键的生效来源
+    pub fn source_of(&self, key: &str) -> Option<ConfigLayer> {
+        self.sources.get(key).copied()
+    }
+}
+
+/// 逐层加载编排配置
+#[derive(Debug, Clone)]
+pub struct OrchestrationConfigLoader {
+    config_path: PathBuf,
+    organization: Table,
+    ui_overrides: Table,
+}
+
+impl OrchestrationConfigLoader {
+    /// 加载指定项目工作区根目录下的 sker.toml
+    pub fn new(workspace_root: impl AsRef<Path>) -> Self {
+        Self {
+            config_path: workspace_root.as_ref().join(CONFIG_FILE_NAME),
+            organization: Table::new(),
+            ui_overrides: Table::new(),
+        }
+    }
+
+    /// 设置组织层
+    pub fn with_organization(mut self, layer: Table) -> Self {
+        self.organization = layer;
+        self
+    }
+
+    /// 由组织设置生成组织层，目前包含组织默认质量门禁
+    pub fn with_organization_settings(self, settings: &OrganizationSettings) -> Result<Self> {
+        let mut layer = Table::new();
+        if let Some(gates) = &settings.default_quality_gates {
+            layer.insert("quality_gates".to_string(), Value::Table(to_table("组织", gates)?));
+        }
+        Ok(self.with_organization(layer))
+    }
+
+    /// 设置界面覆盖层，覆盖以 JSON 对象传入，结构与 sker.toml 相同
+    pub fn with_ui_overrides(mut self, overrides: &serde_json::Value) -> Result<Self> {
+        self.set_ui_overrides(overrides)?;
+        Ok(self)
+    }
+
+    /// 替换界面覆盖层
+    pub fn set_ui_overrides(&mut self, overrides: &serde_json::Value) -> Result<()> {
+        self.ui_overrides = match overrides {
+            serde_json::Value::Null => Table::new(),
+            overrides => to_table("界面覆盖", overrides)?,
+        };
+        Ok(())
+    }
+
+    /// 项目配置文件路径
+    pub fn config_path(&self) -> &Path {
+        &self.config_path
+    }
+
+    /// 读取配置文件并合并各层
+    pub fn load(&self) -> Result<ResolvedOrchestrationConfig> {
+        let content = read_config_file(&self.config_path)?;
+        self.resolve(content.as_deref())
+    }
+
+    /// 以给定的配置文件内容合并各层，内容为空表示文件不存在
+    fn resolve(&self, file_content: Option<&str>) -> Result<ResolvedOrchestrationConfig> {
+        let file_layer = match file_content {
+            Some(content) => content.parse::<Table>().map_err(|e| {
+                DatabaseError::Configuration(format!("{} 格式错误: {}", self.config_path.display(), e))
+            })?,
+            None => Table::new(),
+        };
+
+        let mut merged = to_table("默认值", &OrchestrationConfig::default())?;
+        let mut sources = BTreeMap::new();
+        record_sources(&merged, "", ConfigLayer::Defaults, &mut sources);
+        for (layer, table) in [
+            (ConfigLayer::Organization, &self.organization),
+            (ConfigLayer::ProjectFile, &file_layer),
+            (ConfigLayer::UiOverrides, &self.ui_overrides),
+        ] {
+            merge_layer(&mut merged, table, "", layer, &mut sources);
+        }
+
+        let mut merged_values = BTreeMap::new();
+        flatten(&merged, "", &mut merged_values);
+        let config: OrchestrationConfig = Value::Table(merged).try_into().map_err(|e| {
+            DatabaseError::Configuration(format!("编排配置无效（{}）: {}", self.config_path.display(), e))
+        })?;
+
+        // 反序列化会忽略多余的键，与结构体能表示的键比对找出拼写错误等未知配置
+        let mut known_values = BTreeMap::new();
+        flatten(&to_table("生效配置", &config)?, "", &mut known_values);
+        if let Some(unknown) = merged_values.keys().find(|key| !known_values.contains_key(*key)) {
+            let layer = sources.get(unknown).copied().unwrap_or(ConfigLayer::Defaults);
+            return Err(DatabaseError::Configuration(format!("未知的配置键 {}（来自{}）", unknown, layer.label())));
+        }
+        config.validate()?;
+
+        Ok(ResolvedOrchestrationConfig {
+            config,
+            sources,
+            config_path: self.config_path.clone(),
+            file_present: file_content.is_some(),
+        })
+    }
+}
+
+/// 轮询项目配置文件，生效值变化时生成配置变更事件
+///
+/// 文件内容无效时返回错误并保留上一次的有效配置；同一份无效内容只报告一次。
+#[derive(Debug)]
+pub struct OrchestrationConfigWatcher {
+    project_id: Option<Uuid>,
+    loader: OrchestrationConfigLoader,
+    current: ResolvedOrchestrationConfig,
+    last_content: Option<String>,
+}
+
+impl OrchestrationConfigWatcher {
+    /// 加载初始配置并开始监视
+    pub fn new(project_id: Option<Uuid>, loader: OrchestrationConfigLoader) -> Result<Self> {
+        let last_content = read_config_file(loader.config_path())?;
+        let current = loader.resolve(last_content.as_deref())?;
+        Ok(Self {
+            project_id,
+            loader,
+            current,
+            last_content,
+        })
+    }
+
+    /// 当前生效的配置
+    pub fn current(&self) -> &ResolvedOrchestrationConfig {
+        &self.current
+    }
+
+    /// 检查配置文件是否变化，生效值变化时返回变更事件
+    pub fn poll(&mut self) -> Result<Option<OrchestrationConfigChangedEvent>> {
+        let content = read_config_file(self.loader.config_path())?;
+        if content == self.last_content {
+            return Ok(None);
+        }
+        self.last_content = content;
+        let resolved = self.loader.resolve(self.last_content.as_deref())?;
+        self.apply(resolved)
+    }
+
+    /// 替换界面覆盖层，生效值变化时返回变更事件
+    pub fn set_ui_overrides(&mut self, overrides: &serde_json::Value) -> Result<Option<OrchestrationConfigChangedEvent>> {
+        let mut loader = self.loader.clone();
+        loader.set_ui_overrides(overrides)?;
+        let resolved = loader.resolve(self.last_content.as_deref())?;
+        self.loader = loader;
+        self.apply(resolved)
+    }
+
+    fn apply(&mut self, resolved: ResolvedOrchestrationConfig) -> Result<Option<OrchestrationConfigChangedEvent>> {
+        let changed_keys = changed_keys(&self.current.config, &resolved.config)?;
+        self.current = resolved;
+        if changed_keys.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(EventFactory::orchestration_config_changed(
+            self.project_id.map(ProjectId::from),
+            self.current.config_path.display().to_string(),
+            changed_keys,
+        )))
+    }
+}
+
+/// 读取配置文件，文件不存在时返回 None
+fn read_config_file(path: &Path) -> Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(DatabaseError::Configuration(format!("读取 {} 失败: {}", path.display(), e))),
+    }
+}
+
+/// 把配置层转换为 TOML 表
+fn to_table<T: Serialize + ?Sized>(layer: &str, value: &T) -> Result<Table> {
+    match Value::try_from(value) {
+        Ok(Value::Table(table)) => Ok(table),
+        Ok(_) => Err(DatabaseError::validation(format!("{}配置必须是键值表", layer))),
+        Err(e) => Err(DatabaseError::Configuration(format!("{}配置无法转换为 TOML: {}", layer, e))),
+    }
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// 按键把一层配置合并到已合并的结果中，子表递归合并，其余值整体替换
+fn merge_layer(
+    merged: &mut Table,
+    layer: &Table,
+    prefix: &str,
+    source: ConfigLayer,
+    sources: &mut BTreeMap<String, ConfigLayer>,
+) {
+    for (key, value) in layer {
+        let path = join_key(prefix, key);
+        match (merged.get_mut(key), value) {
+            (Some(Value::Table(merged_table)), Value::Table(layer_table)) => {
+                merge_layer(merged_table, layer_table, &path, source, sources);
+            }
+            _ => {
+                if let Value::Table(table) = value {
+                    record_sources(table, &path, source, sources);
+                } else {
+                    sources.insert(path, source);
+                }
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// 记录表中每个值的来源
+fn record_sources(table: &Table, prefix: &str, source: ConfigLayer, sources: &mut BTreeMap<String, ConfigLayer>) {
+    for (key, value) in table {
+        let path = join_key(prefix, key);
+        match value {
+            Value::Table(table) => record_sources(table, &path, source, sources),
+            _ => {
+                sources.insert(path, source);
+            }
+        }
+    }
+}
+
+/// 把配置展开为点分路径到值的映射
+fn flatten(table: &Table, prefix: &str, values: &mut BTreeMap<String, Value>) {
+    for (key, value) in table {
+        let path = join_key(prefix, key);
+        match value {
+            Value::Table(table) => flatten(table, &path, values),
+            _ => {
+                values.insert(path, value.clone());
+            }
+        }
+    }
+}
+
+/// 生效值发生变化的配置键
+fn changed_keys(previous: &OrchestrationConfig, current: &OrchestrationConfig) -> Result<Vec<String>> {
+    let mut before = BTreeMap::new();
+    let mut after = BTreeMap::new();
+    flatten(&to_table("变更前", previous)?, "", &mut before);
+    flatten(&to_table("变更后", current)?, "", &mut after);
+    let mut keys: Vec<String> = after
+        .iter()
+        .filter(|(key, value)| before.get(*key) != Some(value))
+        .map(|(key, _)| key.clone())
+        .collect();
+    keys.extend(before.keys().filter(|key| !after.contains_key(*key)).cloned());
+    keys.sort();
+    Ok(keys)
+}
@@ -0,0 +1,525 @@
+//! 依赖感知的并行执行规划
+//!
+//! [`ParallelPlanner`] 把项目中未结束的任务按依赖关系划分为执行波次，同一波次内的任务互不依赖，
+//! 可以并行执行。每次 [`ParallelPlanner::launch_ready`]：
+//! - 只考虑前置任务均已完成、已分配Agent的待处理任务，按优先级和创建时间排序；
+//! - 遵守项目的并行会话上限和每个Agent配置中的 `max_concurrent_tasks`；
+//! - 工作范围与运行中或本轮已启动任务重叠的任务推迟到下一轮（见 [`crate::task_scope`]）；
+//! - 以条件更新领取任务并创建执行会话，配置了工作树池时为每个会话分配独立工作树。
+//!
+//! [`ParallelPlanner::wave_view`] 根据当前任务和会话状态生成实时的波次视图。
+//!
+//! 跨项目依赖（见 [`crate::cross_project_dependencies`]）的前置任务不计入波次，
+//! 但同样要完成后才能启动依赖它的任务，失败或取消时依赖任务显示为阻塞。
+
+use chrono::{DateTime, Utc};
+use codex_multi_agent::{SharedClock, SystemClock};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    entities::{agent, execution_session, task, task_dependency},
+    repository::{execution_session_repository::CreateSessionData, ExecutionSessionRepository},
+    task_scope,
+    worktree_pool::WorktreePool,
+    DatabaseConnection, Result,
+};
+
+/// Agent未配置 `max_concurrent_tasks` 时的并发上限
+pub const DEFAULT_AGENT_CONCURRENCY: usize = 1;
+
+/// 并行执行配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParallelExecutionConfig {
+    /// 项目同时运行的执行会话上限
+    pub max_parallel_sessions: usize,
+    /// 执行会话超时时间（分钟）
+    pub session_timeout_minutes: i32,
+}
+
+impl Default for ParallelExecutionConfig {
+    fn default() -> Self {
+        Self {
+            max_parallel_sessions: 4,
+            session_timeout_minutes: 60,
+        }
+    }
+}
+
+/// 任务在波次视图中的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaveTaskState {
+    /// 正在执行
+    Running,
+    /// 前置任务均已完成，等待启动
+    Ready,
+    /// 等待前置任务完成
+    Waiting,
+    /// 任务失败、存在循环依赖或前置任务失败/取消，需要人工处理
+    Blocked,
+}
+
+/// 波次中的任务
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WaveTask {
+    pub task_id: Uuid,
+    pub title: String,
+    pub priority: String,
+    pub agent_id: Option<Uuid>,
+    pub scope_path: Option<String>,
+    pub state: WaveTaskState,
+    /// 运行中的执行会话
+    pub session_id: Option<Uuid>,
+    /// 未完成的前置任务
+    pub waiting_on: Vec<Uuid>,
+    /// 阻塞原因
+    pub blocked_reason: Option<String>,
+}
+
+/// 执行波次，同一波次内的任务互不依赖
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionWave {
+    /// 波次序号，从0开始
+    pub index: usize,
+    pub tasks: Vec<WaveTask>,
+}
+
+/// 项目的实时波次视图
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaveView {
+    pub project_id: Uuid,
+    pub generated_at: DateTime<Utc>,
+    pub waves: Vec<ExecutionWave>,
+    /// 项目中运行的执行会话数
+    pub running_sessions: usize,
+    pub max_parallel_sessions: usize,
+    /// 已完成的任务数
+    pub completed_tasks: usize,
+}
+
+/// 本轮启动的执行会话
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchedSession {
+    pub task_id: Uuid,
+    pub session_id: Uuid,
+    pub agent_id: Uuid,
+    pub git_branch: String,
+    /// 分配的工作树，未配置工作树池时为空
+    pub worktree_path: Option<PathBuf>,
+}
+
+/// 本轮暂不启动的就绪任务
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeferredTask {
+    pub task_id: Uuid,
+    pub reason: String,
+}
+
+/// 一轮启动的结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchReport {
+    pub launched: Vec<LaunchedSession>,
+    pub deferred: Vec<DeferredTask>,
+}
+
+/// 并行执行规划器
+pub struct ParallelPlanner {
+    db: DatabaseConnection,
+    config: ParallelExecutionConfig,
+    worktrees: Option<Arc<WorktreePool>>,
+    clock: SharedClock,
+}
+
+/// 项目任务图的快照
+struct TaskGraph {
+    tasks: Vec<task::Model>,
+    /// 任务 -> 前置任务，包括其他项目的任务
+    prerequisites: HashMap<Uuid, Vec<Uuid>>,
+    /// 其他项目中的前置任务
+    external: HashMap<Uuid, task::Model>,
+    /// 运行中任务的执行会话
+    sessions: HashMap<Uuid, execution_session::Model>,
+}
+
+impl TaskGraph {
+    fn find(&self, task_id: Uuid) -> Option<&task::Model> {
+        self.tasks.iter().find(|task| task.task_id == task_id).or_else(|| self.external.get(&task_id))
+    }
+
+    /// 未完成的前置任务
+    fn unfinished_prerequisites(&self, task_id: Uuid) -> Vec<Uuid> {
+        self.prerequisites
+            .get(&task_id)
+            .into_iter()
+            .flatten()
+            .filter(|id| self.find(**id).is_some_and(|task| task.status != "completed"))
+            .copied()
+            .collect()
+    }
+}
+
+impl ParallelPlanner {
+    pub fn new(db: DatabaseConnection, config: ParallelExecutionConfig) -> Self {
+        Self { db, config, worktrees: None, clock: SystemClock::shared() }
+    }
+
+    /// 为启动的会话分配独立工作树
+    pub fn with_worktree_pool(mut self, pool: Arc<WorktreePool>) -> Self {
+        self.worktrees = Some(pool);
+        self
+    }
+
+    /// 替换生成波次视图和领取任务使用的时钟
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 生成项目的实时波次视图
+    ///
+    /// 已完成和已取消的任务不出现在视图中；波次序号为任务到依赖图起点的最长距离
+    pub async fn wave_view(&self, project_id: Uuid) -> Result<WaveView> {
+        let graph = self.load_graph(project_id).await?;
+        let open: Vec<&task::Model> = graph
+            .tasks
+            .iter()
+            .filter(|task| !matches!(task.status.as_str(), "completed" | "cancelled"))
+            .collect();
+        let open_ids: HashSet<Uuid> = open.iter().map(|task| task.task_id).collect();
+
+        // 按拓扑顺序计算波次，剩余未排序的任务处于循环依赖中
+        let mut depth: HashMap<Uuid, usize> = HashMap::new();
+        let mut remaining: Vec<&task::Model> = open.clone();
+        while !remaining.is_empty() {
+            let (placed, rest): (Vec<&task::Model>, Vec<&task::Model>) = remaining.into_iter().partition(|task| {
+                graph.prerequisites.get(&task.task_id).into_iter().flatten().all(|id| !open_ids.contains(id) || depth.contains_key(id))
+            });
+            if placed.is_empty() {
+                remaining = rest;
+                break;
+            }
+            for task in &placed {
+                let wave = graph
+                    .prerequisites
+                    .get(&task.task_id)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|id| depth.get(id).map(|depth| depth + 1))
+                    .max()
+                    .unwrap_or(0);
+                depth.insert(task.task_id, wave);
+            }
+            remaining = rest;
+        }
+        let cyclic: HashSet<Uuid> = remaining.iter().map(|task| task.task_id).collect();
+        let cycle_wave = depth.values().max().map_or(0, |max| max + 1);
+
+        let mut waves: Vec<ExecutionWave> = Vec::new();
+        for task in open {
+            let index = depth.get(&task.task_id).copied().unwrap_or(cycle_wave);
+            let (state, blocked_reason) = self.task_state(&graph, task, cyclic.contains(&task.task_id));
+            let wave_task = WaveTask {
+                task_id: task.task_id,
+                title: task.title.clone(),
+                priority: task.priority.clone(),
+                agent_id: task.assigned_agent_id,
+                scope_path: task.scope_path.clone(),
+                state,
+                session_id: graph.sessions.get(&task.task_id).map(|session| session.session_id),
+                waiting_on: graph.unfinished_prerequisites(task.task_id),
+                blocked_reason,
+            };
+            match waves.iter_mut().find(|wave| wave.index == index) {
+                Some(wave) => wave.tasks.push(wave_task),
+                None => waves.push(ExecutionWave { index, tasks: vec![wave_task] }),
+            }
+        }
+        waves.sort_by_key(|wave| wave.index);
+        for wave in &mut waves {
+            wave.tasks.sort_by_key(|task| (priority_rank(&task.priority), task.title.clone()));
+        }
+
+        Ok(WaveView {
+            project_id,
+            generated_at: self.clock.now(),
+            waves,
+            running_sessions: graph.sessions.len(),
+            max_parallel_sessions: self.config.max_parallel_sessions,
+            completed_tasks: graph.tasks.iter().filter(|task| task.status == "completed").count(),
+        })
+    }
+
+    /// 在并发上限内启动所有可以并行执行的就绪任务
+    pub async fn launch_ready(&self, project_id: Uuid) -> Result<LaunchReport> {
+        let graph = self.load_graph(project_id).await?;
+        let mut report = LaunchReport { launched: Vec::new(), deferred: Vec::new() };
+
+        let mut ready: Vec<&task::Model> = graph
+            .tasks
+            .iter()
+            .filter(|task| task.status == "pending" && graph.unfinished_prerequisites(task.task_id).is_empty())
+            .collect();
+        ready.sort_by_key(|task| (priority_rank(&task.priority), task.created_at));
+
+        // 运行中任务占用的并发额度和工作范围
+        let mut running_scopes: Vec<Option<String>> = graph
+            .tasks
+            .iter()
+            .filter(|task| task.status == "in_progress")
+            .map(|task| task.scope_path.clone())
+            .collect();
+        let mut slots = self.config.max_parallel_sessions.saturating_sub(graph.sessions.len());
+        let mut agent_load = self.agent_load().await?;
+        let agent_ids: Vec<Uuid> = ready.iter().filter_map(|task| task.assigned_agent_id).collect();
+        let agent_limits: HashMap<Uuid, usize> = agent::Entity::find()
+            .filter(agent::Column::AgentId.is_in(agent_ids))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|agent| (agent.agent_id, agent_concurrency(&agent)))
+            .collect();
+
+        for task in ready {
+            let defer = |reason: String| DeferredTask { task_id: task.task_id, reason };
+            let Some(agent_id) = task.assigned_agent_id else {
+                report.deferred.push(defer("任务尚未分配Agent".to_string()));
+                continue;
+            };
+            if slots == 0 {
+                report.deferred.push(defer(format!(
+                    "项目已达到并行会话上限 {}",
+                    self.config.max_parallel_sessions
+                )));
+                continue;
+            }
+            let limit = agent_limits.get(&agent_id).copied().unwrap_or(DEFAULT_AGENT_CONCURRENCY);
+            let load = agent_load.get(&agent_id).copied().unwrap_or(0);
+            if load >= limit {
+                report.deferred.push(defer(format!("Agent 已达到并发上限 {}", limit)));
+                continue;
+            }
+            if let Some(scope) = running_scopes
+                .iter()
+                .find(|scope| task_scope::overlaps(scope.as_deref(), task.scope_path.as_deref()))
+            {
+                report.deferred.push(defer(format!(
+                    "工作范围与运行中的任务（{}）重叠",
+                    scope.as_deref().unwrap_or("整个仓库")
+                )));
+                continue;
+            }
+
+            let Some(launched) = self.launch(task, agent_id).await? else {
+                report.deferred.push(defer("任务已被其他调度器领取".to_string()));
+                continue;
+            };
+            slots -= 1;
+            *agent_load.entry(agent_id).or_insert(0) += 1;
+            running_scopes.push(task.scope_path.clone());
+            report.launched.push(launched);
+        }
+
+        if !report.launched.is_empty() {
+            tracing::info!("项目 {} 并行启动了 {} 个执行会话", project_id, report.launched.len());
+        }
+        Ok(report)
+    }
+
+    /// 领取任务并创建执行会话，任务已被领取时返回 `None`
+    async fn launch(&self, task: &task::Model, agent_id: Uuid) -> Result<Option<LaunchedSession>> {
+        let now: sea_orm::prelude::DateTimeWithTimeZone = self.clock.now().into();
+        let claimed = task::Entity::update_many()
+            .col_expr(task::Column::Status, Expr::value("in_progress"))
+            .col_expr(task::Column::StartedAt, Expr::value(task.started_at.unwrap_or(now)))
+            .col_expr(task::Column::UpdatedAt, Expr::value(now))
+            .filter(task::Column::TaskId.eq(task.task_id))
+            .filter(task::Column::Status.eq("pending"))
+            .exec(&self.db)
+            .await?;
+        if claimed.rows_affected != 1 {
+            return Ok(None);
+        }
+
+        let git_branch = format!("task/{}", &task.task_id.to_string()[..8]);
+        let sessions = ExecutionSessionRepository::new(self.db.clone());
+        let session = sessions
+            .create(CreateSessionData {
+                task_id: task.task_id,
+                agent_id,
+                project_id: task.project_id,
+                git_branch: git_branch.clone(),
+                base_commit: None,
+                execution_config: None,
+                timeout_minutes: self.config.session_timeout_minutes,
+            })
+            .await;
+        let session = match session {
+            Ok(session) => session,
+            Err(e) => {
+                self.requeue(task.task_id).await?;
+                return Err(e);
+            }
+        };
+
+        let worktree_path = match &self.worktrees {
+            Some(pool) => match pool.acquire(session.session_id).await {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    // 没有独立工作树时不能与其他会话并行，归还任务
+                    sessions.delete(session.session_id).await?;
+                    self.requeue(task.task_id).await?;
+                    return Err(e);
+                }
+            },
+            None => None,
+        };
+
+        tracing::info!("任务 {} 启动执行会话 {}（Agent {}）", task.task_id, session.session_id, agent_id);
+        Ok(Some(LaunchedSession {
+            task_id: task.task_id,
+            session_id: session.session_id,
+            agent_id,
+            git_branch,
+            worktree_path,
+        }))
+    }
+
+    /// 启动失败时把任务放回待处理
+    async fn requeue(&self, task_id: Uuid) -> Result<()> {
+        task::Entity::update_many()
+            .col_expr(task::Column::Status, Expr::value("pending"))
+            .col_expr(task::Column::UpdatedAt, Expr::value(sea_orm::prelude::DateTimeWithTimeZone::from(self.clock.now())))
+            .filter(task::Column::TaskId.eq(task_id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn load_graph(&self, project_id: Uuid) -> Result<TaskGraph> {
+        let tasks = task::Entity::find()
+            .filter(task::Column::ProjectId.eq(project_id))
+            .order_by_asc(task::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+        let ids: Vec<Uuid> = tasks.iter().map(|task| task.task_id).collect();
+        let id_set: HashSet<Uuid> = ids.iter().copied().collect();
+
+        let mut prerequisites: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        let mut external_ids = HashSet::new();
+        for dependency in task_dependency::Entity::find()
+            .filter(task_dependency::Column::ChildTaskId.is_in(ids))
+            .all(&self.db)
+            .await?
+        {
+            if dependency.parent_task_id == dependency.child_task_id {
+                continue;
+            }
+            if !id_set.contains(&dependency.parent_task_id) {
+                external_ids.insert(dependency.parent_task_id);
+            }
+            prerequisites.entry(dependency.child_task_id).or_default().push(dependency.parent_task_id);
+        }
+
+        // 其他项目的前置任务只用于判断是否完成
+        let external = if external_ids.is_empty() {
+            HashMap::new()
+        } else {
+            task::Entity::find()
+                .filter(task::Column::TaskId.is_in(external_ids))
+                .all(&self.db)
+                .await?
+                .into_iter()
+                .map(|task| (task.task_id, task))
+                .collect()
+        };
+
+        let sessions = execution_session::Entity::find()
+            .filter(execution_session::Column::ProjectId.eq(project_id))
+            .filter(execution_session::Column::Status.is_in(["pending", "running"]))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|session| (session.task_id, session))
+            .collect();
+
+        Ok(TaskGraph { tasks, prerequisites, external, sessions })
+    }
+
+    /// 各Agent在所有项目中的活动会话数
+    async fn agent_load(&self) -> Result<HashMap<Uuid, usize>> {
+        let mut load = HashMap::new();
+        for session in execution_session::Entity::find()
+            .filter(execution_session::Column::Status.is_in(["pending", "running"]))
+            .all(&self.db)
+            .await?
+        {
+            *load.entry(session.agent_id).or_insert(0) += 1;
+        }
+        Ok(load)
+    }
+
+    fn task_state(&self, graph: &TaskGraph, task: &task::Model, cyclic: bool) -> (WaveTaskState, Option<String>) {
+        if task.status == "in_progress" {
+            return (WaveTaskState::Running, None);
+        }
+        if task.status == "failed" {
+            return (WaveTaskState::Blocked, Some("任务执行失败".to_string()));
+        }
+        if cyclic {
+            return (WaveTaskState::Blocked, Some("存在循环依赖".to_string()));
+        }
+        let stalled = graph
+            .prerequisites
+            .get(&task.task_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| graph.find(*id))
+            .find(|prerequisite| matches!(prerequisite.status.as_str(), "failed" | "cancelled"));
+        if let Some(prerequisite) = stalled {
+            let scope = if graph.external.contains_key(&prerequisite.task_id) { "其他项目的" } else { "" };
+            return (
+                WaveTaskState::Blocked,
+                Some(format!("{}前置任务「{}」{}", scope, prerequisite.title, status_label(&prerequisite.status))),
+            );
+        }
+        if task.status == "pending" && graph.unfinished_prerequisites(task.task_id).is_empty() {
+            (WaveTaskState::Ready, None)
+        } else {
+            (WaveTaskState::Waiting, None)
+        }
+    }
+}
+
+/// Agent配置中的并发上限
+fn agent_concurrency(agent: &agent::Model) -> usize {
+    agent
+        .config
+        .get("max_concurrent_tasks")
+        .and_then(|value| value.as_u64())
+        .map_or(DEFAULT_AGENT_CONCURRENCY, |limit| limit.max(1) as usize)
+}
+
+fn status_label(status: &str) -> &'static str {
+    match status {
+        "failed" => "执行失败",
+        "cancelled" => "已取消",
+        _ => "未完成",
+    }
+}
+
+/// 优先级排序，数值越小越先启动
+fn priority_rank(priority: &str) -> u8 {
+    match priority {
+        "critical" | "urgent" => 0,
+        "high" => 1,
+        "medium" => 2,
+        "low" => 3,
+        _ => 2,
+    }
+}
@@ -0,0 +1,421 @@
+//! 按项目拆分数据库文件
+//!
+//! 目录数据库（catalog）保存用户、组织、Agent 和项目列表。拆分后的项目把任务、会话、日志等数据保存在
+//! 拆分目录下的 `<project_id>.db` 中，首次访问时打开，超过打开上限时移除最久未用的文件。
+//! [`ProjectDatabases::connection_for`] 为项目选择连接：已拆分的项目路由到项目文件，其余项目仍使用目录数据库，
+//! 仓储只需用返回的连接构造（见 [`ProjectDatabases::scoped`]），不需要区分两种情况。
+//!
+//! 项目文件使用与目录数据库相同的表结构，并保存一份项目所需的引用行（项目、成员、所有者、Agent 及其组织）
+//! 以满足外键约束，每次打开时从目录数据库刷新。目录数据库始终是这些行的权威来源。
+
+use crate::{
+    config::DatabaseConfig,
+    connection::establish_connection_with_config,
+    entities::{agent, organization, project, project_member, project_partition, task},
+    migrations::Migrator,
+    repository::ProjectPartitionRepository,
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseBackend, EntityTrait, PaginatorTrait, QueryFilter, Statement, Value};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// 默认同时打开的项目文件数
+pub const DEFAULT_MAX_OPEN: usize = 16;
+
+/// 项目文件的路由与生命周期管理
+#[derive(Clone)]
+pub struct ProjectDatabases {
+    catalog: DatabaseConnection,
+    dir: Option<PathBuf>,
+    max_open: usize,
+    open: Arc<Mutex<OpenPartitions>>,
+}
+
+/// 已打开的项目文件，`recent` 按最近使用排序，队首最久未用
+#[derive(Default)]
+struct OpenPartitions {
+    connections: HashMap<Uuid, DatabaseConnection>,
+    recent: VecDeque<Uuid>,
+}
+
+impl OpenPartitions {
+    fn get(&mut self, project_id: Uuid) -> Option<DatabaseConnection> {
+        let db = self.connections.get(&project_id)?.clone();
+        self.recent.retain(|id| *id != project_id);
+        self.recent.push_back(project_id);
+        Some(db)
+    }
+
+    /// 加入新打开的连接，超过上限时移除最久未用的连接
+    ///
+    /// 被移除的连接不主动关闭：仍持有它的仓储可以继续使用，最后一个引用释放时连接池随之关闭。
+    fn insert(&mut self, project_id: Uuid, db: DatabaseConnection, max_open: usize) {
+        self.connections.insert(project_id, db);
+        self.recent.retain(|id| *id != project_id);
+        self.recent.push_back(project_id);
+        while self.recent.len() > max_open {
+            if let Some(oldest) = self.recent.pop_front() {
+                self.connections.remove(&oldest);
+            }
+        }
+    }
+
+    fn remove(&mut self, project_id: Uuid) -> Option<DatabaseConnection> {
+        self.recent.retain(|id| *id != project_id);
+        self.connections.remove(&project_id)
+    }
+}
+
+/// 导出的项目文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedProject {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+}
+
+impl ProjectDatabases {
+    /// 不拆分：所有项目都使用目录数据库
+    pub fn single(catalog: DatabaseConnection) -> Self {
+        Self {
+            catalog,
+            dir: None,
+            max_open: DEFAULT_MAX_OPEN,
+            open: Arc::new(Mutex::new(OpenPartitions::default())),
+        }
+    }
+
+    /// 拆分的项目文件保存在 `dir` 下
+    pub fn partitioned(catalog: DatabaseConnection, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+            ..Self::single(catalog)
+        }
+    }
+
+    /// 设置同时打开的项目文件数上限
+    pub fn with_max_open(mut self, max_open: usize) -> Result<Self> {
+        if max_open == 0 {
+            return Err(DatabaseError::validation("同时打开的项目文件数必须大于0"));
+        }
+        self.max_open = max_open;
+        Ok(self)
+    }
+
+    /// 目录数据库连接
+    pub fn catalog(&self) -> &DatabaseConnection {
+        &self.catalog
+    }
+
+    /// 当前打开的项目文件数
+    pub async fn open_count(&self) -> usize {
+        self.open.lock().await.connections.len()
+    }
+
+    /// 项目文件路径，未配置拆分目录时返回 `None`
+    pub fn partition_path(&self, project_id: Uuid) -> Option<PathBuf> {
+        self.dir.as_ref().map(|dir| dir.join(partition_file_name(project_id)))
+    }
+
+    /// 项目是否已拆分到独立文件
+    pub async fn is_partitioned(&self, project_id: Uuid) -> Result<bool> {
+        Ok(ProjectPartitionRepository::new(self.catalog.clone())
+            .find_by_project(project_id)
+            .await?
+            .is_some())
+    }
+
+    /// 项目数据所在的连接：已拆分的项目打开其文件，其余项目使用目录数据库
+    pub async fn connection_for(&self, project_id: Uuid) -> Result<DatabaseConnection> {
+        let mut open = self.open.lock().await;
+        if let Some(db) = open.get(project_id) {
+            return Ok(db);
+        }
+        let Some(partition) = ProjectPartitionRepository::new(self.catalog.clone())
+            .find_by_project(project_id)
+            .await?
+        else {
+            return Ok(self.catalog.clone());
+        };
+
+        let db = self.attach(&partition).await?;
+        open.insert(project_id, db.clone(), self.max_open);
+        Ok(db)
+    }
+
+    /// 用项目数据所在的连接构造仓储，如 `databases.scoped(project_id, TaskRepository::new)`
+    pub async fn scoped<R>(&self, project_id: Uuid, make: impl FnOnce(DatabaseConnection) -> R) -> Result<R> {
+        Ok(make(self.connection_for(project_id).await?))
+    }
+
+    /// 为尚无任务数据的项目创建独立文件，之后该项目的数据都写入此文件
+    pub async fn create_partition(&self, project_id: Uuid) -> Result<project_partition::Model> {
+        let path = self
+            .partition_path(project_id)
+            .ok_or_else(|| DatabaseError::validation("未配置项目数据库目录"))?;
+        project::Entity::find_by_id(project_id)
+            .one(&self.catalog)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+        let partitions = ProjectPartitionRepository::new(self.catalog.clone());
+        if partitions.find_by_project(project_id).await?.is_some() {
+            return Err(DatabaseError::validation("项目已拆分到独立文件"));
+        }
+        let tasks = task::Entity::find()
+            .filter(task::Column::ProjectId.eq(project_id))
+            .count(&self.catalog)
+            .await?;
+        if tasks > 0 {
+            return Err(DatabaseError::validation("项目已有任务数据，不能拆分到独立文件"));
+        }
+        if tokio::fs::try_exists(&path).await? {
+            return Err(DatabaseError::business_logic(format!("项目数据库文件已存在: {}", path.display())));
+        }
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        let db = match self.initialize(&path, project_id).await {
+            Ok(db) => db,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&path).await;
+                return Err(e);
+            }
+        };
+        let partition = partitions.create(project_id, &partition_file_name(project_id)).await?;
+        self.open.lock().await.insert(project_id, db, self.max_open);
+        Ok(partition)
+    }
+
+    /// 关闭项目文件，下次访问时重新打开
+    pub async fn detach(&self, project_id: Uuid) -> Result<bool> {
+        let removed = self.open.lock().await.remove(project_id);
+        match removed {
+            Some(db) => {
+                db.close().await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// 关闭所有打开的项目文件
+    pub async fn detach_all(&self) -> Result<()> {
+        let connections: Vec<DatabaseConnection> = {
+            let mut open = self.open.lock().await;
+            open.recent.clear();
+            open.connections.drain().map(|(_, db)| db).collect()
+        };
+        for db in connections {
+            db.close().await?;
+        }
+        Ok(())
+    }
+
+    /// 删除项目文件及其登记记录
+    pub async fn remove_partition(&self, project_id: Uuid) -> Result<bool> {
+        let partitions = ProjectPartitionRepository::new(self.catalog.clone());
+        let Some(partition) = partitions.find_by_project(project_id).await? else {
+            return Ok(false);
+        };
+        self.detach(project_id).await?;
+        if let Some(dir) = &self.dir {
+            let path = dir.join(&partition.file_name);
+            if tokio::fs::try_exists(&path).await? {
+                tokio::fs::remove_file(&path).await?;
+            }
+        }
+        partitions.delete(project_id).await
+    }
+
+    /// 把已拆分的项目导出为单个可移植的 SQLite 文件，导出文件中的密码哈希被清空
+    pub async fn export_project(&self, project_id: Uuid, dest: &Path) -> Result<ExportedProject> {
+        if !self.is_partitioned(project_id).await? {
+            return Err(DatabaseError::validation("项目未拆分到独立文件，无法导出为单个文件"));
+        }
+        if tokio::fs::try_exists(dest).await? {
+            return Err(DatabaseError::validation(format!("导出文件已存在: {}", dest.display())));
+        }
+        let db = self.connection_for(project_id).await?;
+        copy_references(&self.catalog, &db, project_id).await?;
+        db.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "VACUUM INTO ?",
+            [Value::from(dest.display().to_string())],
+        ))
+        .await?;
+
+        let exported = open_file(dest).await?;
+        exported.execute_unprepared("UPDATE users SET password_hash = ''").await?;
+        exported.close().await?;
+        let size_bytes = tokio::fs::metadata(dest).await?.len();
+        Ok(ExportedProject { path: dest.to_path_buf(), size_bytes })
+    }
+
+    /// 打开已登记的项目文件，补齐表结构并刷新引用行
+    async fn attach(&self, partition: &project_partition::Model) -> Result<DatabaseConnection> {
+        let dir = self
+            .dir
+            .as_ref()
+            .ok_or_else(|| DatabaseError::validation("未配置项目数据库目录"))?;
+        let path = dir.join(&partition.file_name);
+        if !tokio::fs::try_exists(&path).await? {
+            return Err(DatabaseError::business_logic(format!("项目数据库文件不存在: {}", path.display())));
+        }
+        let db = self.initialize(&path, partition.project_id).await?;
+        ProjectPartitionRepository::new(self.catalog.clone())
+            .touch_attached(partition.project_id)
+            .await?;
+        Ok(db)
+    }
+
+    async fn initialize(&self, path: &Path, project_id: Uuid) -> Result<DatabaseConnection> {
+        let db = open_file(path).await?;
+        Migrator::up(&db, None).await?;
+        copy_references(&self.catalog, &db, project_id).await?;
+        Ok(db)
+    }
+}
+
+fn partition_file_name(project_id: Uuid) -> String {
+    format!("{}.db", project_id)
+}
+
+async fn open_file(path: &Path) -> Result<DatabaseConnection> {
+    let config = DatabaseConfig {
+        enable_logging: false,
+        ..DatabaseConfig::file(path)
+    };
+    establish_connection_with_config(&config).await
+}
+
+/// 把项目需要的引用行从目录数据库复制到项目文件，已存在的行被更新
+async fn copy_references(catalog: &DatabaseConnection, target: &DatabaseConnection, project_id: Uuid) -> Result<()> {
+    let project = project::Entity::find_by_id(project_id)
+        .one(catalog)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+    let members = project_member::Entity::find()
+        .filter(project_member::Column::ProjectId.eq(project_id))
+        .all(catalog)
+        .await?;
+
+    let mut member_users: BTreeSet<Uuid> = members.iter().map(|member| member.user_id).collect();
+    member_users.insert(project.user_id);
+    let agents = agent::Entity::find()
+        .filter(agent::Column::UserId.is_in(member_users.iter().copied()))
+        .all(catalog)
+        .await?;
+    let organization_ids: BTreeSet<Uuid> = project
+        .organization_id
+        .into_iter()
+        .chain(agents.iter().filter_map(|agent| agent.organization_id))
+        .collect();
+    let organizations = organization::Entity::find()
+        .filter(organization::Column::OrganizationId.is_in(organization_ids.iter().copied()))
+        .all(catalog)
+        .await?;
+
+    let mut users = member_users;
+    users.extend(members.iter().filter_map(|member| member.invited_by));
+    users.extend(organizations.iter().map(|organization| organization.owner_id));
+
+    // 按外键依赖顺序复制
+    copy_rows(catalog, target, "users", "user_id", users).await?;
+    copy_rows(catalog, target, "organizations", "organization_id", organization_ids).await?;
+    copy_rows(catalog, target, "projects", "project_id", [project_id].into()).await?;
+    copy_rows(catalog, target, "project_members", "project_id", [project_id].into()).await?;
+    copy_rows(catalog, target, "agents", "agent_id", agents.iter().map(|agent| agent.agent_id).collect()).await?;
+    Ok(())
+}
+
+/// 以 JSON 数组为中转在两个连接之间复制行，按主键更新已存在的行（两个连接可以都是内存数据库，不依赖 ATTACH）
+///
+/// 不使用 `INSERT OR REPLACE`：替换会先删除旧行，触发外键的级联删除。
+async fn copy_rows(
+    from: &DatabaseConnection,
+    to: &DatabaseConnection,
+    table: &str,
+    key_column: &str,
+    keys: BTreeSet<Uuid>,
+) -> Result<()> {
+    if keys.is_empty() {
+        return Ok(());
+    }
+    let rows = to
+        .query_all(Statement::from_string(DatabaseBackend::Sqlite, format!("PRAGMA table_info({})", table)))
+        .await?;
+    let mut columns = Vec::new();
+    let mut primary_keys = Vec::new();
+    for row in rows {
+        let name: String = row.try_get("", "name")?;
+        if row.try_get::<i32>("", "pk")? > 0 {
+            primary_keys.push(name.clone());
+        }
+        columns.push(name);
+    }
+
+    let placeholders = vec!["?"; keys.len()].join(", ");
+    // UUID 等 BLOB 值无法放入 JSON，编码为只含十六进制串的数组，写入时再还原
+    let fields: Vec<String> = columns
+        .iter()
+        .map(|column| {
+            format!("'{0}', CASE WHEN typeof(\"{0}\") = 'blob' THEN json_array(hex(\"{0}\")) ELSE \"{0}\" END", column)
+        })
+        .collect();
+    let select = Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!(
+            "SELECT json_group_array(json_object({})) AS rows FROM \"{}\" WHERE \"{}\" IN ({})",
+            fields.join(", "),
+            table,
+            key_column,
+            placeholders
+        ),
+        keys.into_iter().map(Value::from),
+    );
+    let json: String = match from.query_one(select).await? {
+        Some(row) => row.try_get("", "rows")?,
+        None => return Ok(()),
+    };
+
+    let quoted: Vec<String> = columns.iter().map(|column| format!("\"{}\"", column)).collect();
+    let extracts: Vec<String> = columns
+        .iter()
+        .map(|column| {
+            format!(
+                "CASE json_type(value, '$.\"{0}\"') WHEN 'array' THEN unhex(json_extract(value, '$.\"{0}\"[0]')) \
+                 ELSE json_extract(value, '$.\"{0}\"') END",
+                column
+            )
+        })
+        .collect();
+    let updates: Vec<String> = columns
+        .iter()
+        .filter(|column| !primary_keys.contains(column))
+        .map(|column| format!("\"{0}\" = excluded.\"{0}\"", column))
+        .collect();
+    let conflict = if updates.is_empty() {
+        "DO NOTHING".to_string()
+    } else {
+        format!("DO UPDATE SET {}", updates.join(", "))
+    };
+    let primary_keys: Vec<String> = primary_keys.iter().map(|column| format!("\"{}\"", column)).collect();
+    to.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        format!(
+            "INSERT INTO \"{}\" ({}) SELECT {} FROM json_each(?) WHERE true ON CONFLICT ({}) {}",
+            table,
+            quoted.join(", "),
+            extracts.join(", "),
+            primary_keys.join(", "),
+            conflict
+        ),
+        [Value::from(json)],
+    ))
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,361 @@
+//! 大字段压缩与转存
+//!
+//! 领域事件的 `event_data`、执行会话的 `result_data` 偶尔会放入完整的 diff 或日志，
+//! 大字段直接存放在行内会拖慢列表查询。写入时按 [`PayloadPolicy`] 处理：
+//! - 序列化后不超过 `compress_threshold` 字节的值原样保存；
+//! - 更大的值用 zstd 压缩后以 base64 内联保存为 `{"$payload": "zstd", "size": 原始字节数, "data": ...}`；
+//! - 超过 `offload_threshold` 字节且配置了工件存储时，压缩数据写入 [`ArtifactStore`]，
+//!   行内只保存 `{"$payload": "artifact", "size": 原始字节数, "checksum": ...}`，
+//!   引用记录在 `offloaded_payloads` 表，工件存储回收数据时会计入这些引用。
+//!
+//! 仓储读取时自动还原内联压缩的值；转存的值只有挂载了 [`PayloadStore`] 的仓储才会读取，
+//! 否则保留引用，列表查询不必读取大文件。[`PayloadStore::offload_existing`] 把已有的大字段转存出去，
+//! [`PayloadStore::release_orphans`] 回收所在行已删除的转存负载，两者由维护任务 [`PayloadOffloadJob`] 定期执行。
+
+use std::io::Read;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use uuid::Uuid;
+
+use crate::artifact_store::ArtifactStore;
+use crate::entities::{domain_event, execution_session};
+use crate::maintenance::{MaintenanceFuture, MaintenanceJobHandler};
+use crate::repository::offloaded_payload_repository::CreateOffloadedPayloadData;
+use crate::repository::OffloadedPayloadRepository;
+use crate::{DatabaseConnection, DatabaseError, Result};
+
+/// 编码后的值中标记编码方式的键
+pub const PAYLOAD_KEY: &str = "$payload";
+
+/// 内联压缩
+pub const CODEC_ZSTD: &str = "zstd";
+
+/// 转存到工件存储
+pub const CODEC_ARTIFACT: &str = "artifact";
+
+/// 领域事件表，`event_data` 列
+pub const DOMAIN_EVENTS: &str = "domain_events";
+
+/// 执行会话表，`result_data` 列
+pub const EXECUTION_SESSIONS: &str = "execution_sessions";
+
+/// 转存任务类型
+pub const JOB_PAYLOAD_OFFLOAD: &str = "payload_offload";
+
+/// 转存任务每张表默认处理的行数
+pub const DEFAULT_OFFLOAD_BATCH: u64 = 500;
+
+/// 解压后的大小上限，防止损坏或伪造的数据耗尽内存
+const MAX_DECODED_BYTES: u64 = 256 * 1024 * 1024;
+
+/// 大字段处理策略
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PayloadPolicy {
+    /// 超过该字节数时压缩
+    pub compress_threshold: usize,
+    /// 超过该字节数时转存到工件存储
+    pub offload_threshold: usize,
+    /// zstd 压缩级别
+    pub level: i32,
+}
+
+impl Default for PayloadPolicy {
+    fn default() -> Self {
+        Self {
+            compress_threshold: 16 * 1024,
+            offload_threshold: 256 * 1024,
+            level: 3,
+        }
+    }
+}
+
+impl PayloadPolicy {
+    /// 校验阈值和压缩级别
+    pub fn validate(&self) -> Result<()> {
+        if self.compress_threshold == 0 {
+            return Err(DatabaseError::validation("压缩阈值必须大于0"));
+        }
+        if self.offload_threshold < self.compress_threshold {
+            return Err(DatabaseError::validation("转存阈值不能小于压缩阈值"));
+        }
+        if !zstd::compression_level_range().contains(&self.level) {
+            return Err(DatabaseError::validation(format!("无效的zstd压缩级别: {}", self.level)));
+        }
+        Ok(())
+    }
+}
+
+/// 值的编码方式，没有编码时返回 None
+pub fn codec(value: &JsonValue) -> Option<&str> {
+    value.as_object().filter(|object| object.len() == 3)?.get(PAYLOAD_KEY)?.as_str()
+}
+
+/// 是否为转存到工件存储的引用
+pub fn is_offloaded(value: &JsonValue) -> bool {
+    codec(value) == Some(CODEC_ARTIFACT)
+}
+
+/// 按策略压缩大字段，不转存
+pub fn compress(value: JsonValue, policy: &PayloadPolicy) -> Result<JsonValue> {
+    let raw = serde_json::to_vec(&value)?;
+    if raw.len() <= policy.compress_threshold || codec(&value).is_some() {
+        return Ok(value);
+    }
+    let compressed = zstd::encode_all(raw.as_slice(), policy.level)?;
+    Ok(json!({
+        PAYLOAD_KEY: CODEC_ZSTD,
+        "size": raw.len(),
+        "data": BASE64.encode(compressed),
+    }))
+}
+
+/// 还原内联压缩的值；未编码的值和转存引用原样返回
+pub fn inflate(value: JsonValue) -> Result<JsonValue> {
+    if codec(&value) != Some(CODEC_ZSTD) {
+        return Ok(value);
+    }
+    let data = value["data"]
+        .as_str()
+        .ok_or_else(|| DatabaseError::validation("压缩数据缺少 data 字段"))?;
+    let compressed = BASE64
+        .decode(data)
+        .map_err(|e| DatabaseError::validation(format!("压缩数据不是有效的base64: {}", e)))?;
+    decompress(&compressed)
+}
+
+/// 还原 `Option` 包装的值
+pub fn inflate_option(value: Option<JsonValue>) -> Result<Option<JsonValue>> {
+    value.map(inflate).transpose()
+}
+
+fn decompress(compressed: &[u8]) -> Result<JsonValue> {
+    let mut raw = Vec::new();
+    zstd::Decoder::new(compressed)?
+        .take(MAX_DECODED_BYTES)
+        .read_to_end(&mut raw)?;
+    Ok(serde_json::from_slice(&raw)?)
+}
+
+/// 转存的结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct OffloadReport {
+    /// 转存的领域事件数
+    pub domain_events: usize,
+    /// 转存的执行会话数
+    pub execution_sessions: usize,
+    /// 转存前的原始字节数
+    pub original_bytes: u64,
+    /// 写入工件存储的字节数
+    pub stored_bytes: u64,
+}
+
+/// 可以转存大字段的负载存储
+#[derive(Clone)]
+pub struct PayloadStore {
+    store: ArtifactStore,
+    policy: PayloadPolicy,
+}
+
+impl PayloadStore {
+    /// 使用默认策略创建负载存储
+    pub fn new(store: ArtifactStore) -> Self {
+        Self { store, policy: PayloadPolicy::default() }
+    }
+
+    /// 替换处理策略
+    pub fn with_policy(mut self, policy: PayloadPolicy) -> Result<Self> {
+        policy.validate()?;
+        self.policy = policy;
+        Ok(self)
+    }
+
+    /// 处理策略
+    pub fn policy(&self) -> &PayloadPolicy {
+        &self.policy
+    }
+
+    /// 编码 `owner_table` 表中 `owner_id` 行的字段，超过转存阈值时写入工件存储并记录引用
+    pub async fn encode(&self, owner_table: &str, owner_id: Uuid, value: JsonValue) -> Result<JsonValue> {
+        let raw = serde_json::to_vec(&value)?;
+        if raw.len() <= self.policy.offload_threshold || codec(&value).is_some() {
+            return compress(value, &self.policy);
+        }
+        let compressed = zstd::encode_all(raw.as_slice(), self.policy.level)?;
+        let checksum = self.store.write_blob(&compressed).await?;
+        OffloadedPayloadRepository::new(self.store.db().clone())
+            .create(CreateOffloadedPayloadData {
+                owner_table: owner_table.to_string(),
+                owner_id,
+                checksum: checksum.clone(),
+                original_size: raw.len() as u64,
+                stored_size: compressed.len() as u64,
+            })
+            .await?;
+        Ok(json!({
+            PAYLOAD_KEY: CODEC_ARTIFACT,
+            "size": raw.len(),
+            "checksum": checksum,
+        }))
+    }
+
+    /// 还原内联压缩或转存的值
+    pub async fn decode(&self, value: JsonValue) -> Result<JsonValue> {
+        if !is_offloaded(&value) {
+            return inflate(value);
+        }
+        let checksum = value["checksum"]
+            .as_str()
+            .ok_or_else(|| DatabaseError::validation("转存引用缺少 checksum 字段"))?;
+        let compressed = self.store.read_blob(checksum, "转存负载").await?;
+        decompress(&compressed)
+    }
+
+    /// 还原 `Option` 包装的值
+    pub async fn decode_option(&self, value: Option<JsonValue>) -> Result<Option<JsonValue>> {
+        match value {
+            Some(value) => Ok(Some(self.decode(value).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 删除行引用的转存负载，数据不再被引用时一并删除，返回删除的引用数
+    pub async fn release(&self, owner_table: &str, owner_id: Uuid) -> Result<usize> {
+        let payloads = OffloadedPayloadRepository::new(self.store.db().clone());
+        let references = payloads.find_by_owner(owner_table, owner_id).await?;
+        for reference in &references {
+            payloads.delete(reference.payload_id).await?;
+            self.store.release_blob(&reference.checksum).await?;
+        }
+        Ok(references.len())
+    }
+
+    /// 回收所在行已被删除（例如随项目级联删除）的转存负载，返回回收的引用数
+    pub async fn release_orphans(&self) -> Result<usize> {
+        let db = self.store.db();
+        let payloads = OffloadedPayloadRepository::new(db.clone());
+        let mut released = 0;
+        for owner_table in [DOMAIN_EVENTS, EXECUTION_SESSIONS] {
+            for reference in payloads.find_by_table(owner_table).await? {
+                let exists = match owner_table {
+                    DOMAIN_EVENTS => domain_event::Entity::find_by_id(reference.owner_id).one(db).await?.is_some(),
+                    _ => execution_session::Entity::find_by_id(reference.owner_id).one(db).await?.is_some(),
+                };
+                if !exists {
+                    payloads.delete(reference.payload_id).await?;
+                    self.store.release_blob(&reference.checksum).await?;
+                    released += 1;
+                }
+            }
+        }
+        Ok(released)
+    }
+
+    /// 把已有的超过转存阈值的字段转存出去，每张表最多处理 `limit` 行
+    ///
+    /// 只按行内数据的长度筛选，已经压缩或转存的字段不会重复处理
+    pub async fn offload_existing(&self, limit: u64) -> Result<OffloadReport> {
+        let db = self.store.db();
+        let threshold = self.policy.offload_threshold as i64;
+        let mut report = OffloadReport::default();
+
+        let events: Vec<(Uuid, JsonValue)> = domain_event::Entity::find()
+            .select_only()
+            .column(domain_event::Column::EventId)
+            .column(domain_event::Column::EventData)
+            .filter(Expr::cust_with_values("length(event_data) > ?", [threshold]))
+            .limit(limit)
+            .into_tuple()
+            .all(db)
+            .await?;
+        for (event_id, event_data) in events {
+            let value = inflate(event_data)?;
+            let encoded = self.encode(DOMAIN_EVENTS, event_id, value.clone()).await?;
+            if !is_offloaded(&encoded) {
+                continue;
+            }
+            self.record_offload(&mut report, &value, &encoded).await?;
+            domain_event::Entity::update_many()
+                .col_expr(domain_event::Column::EventData, Expr::value(encoded))
+                .filter(domain_event::Column::EventId.eq(event_id))
+                .exec(db)
+                .await?;
+            report.domain_events += 1;
+        }
+
+        let sessions: Vec<(Uuid, Option<JsonValue>)> = execution_session::Entity::find()
+            .select_only()
+            .column(execution_session::Column::SessionId)
+            .column(execution_session::Column::ResultData)
+            .filter(Expr::cust_with_values("length(result_data) > ?", [threshold]))
+            .limit(limit)
+            .into_tuple()
+            .all(db)
+            .await?;
+        for (session_id, result_data) in sessions {
+            let Some(value) = inflate_option(result_data)? else {
+                continue;
+            };
+            let encoded = self.encode(EXECUTION_SESSIONS, session_id, value.clone()).await?;
+            if !is_offloaded(&encoded) {
+                continue;
+            }
+            self.record_offload(&mut report, &value, &encoded).await?;
+            execution_session::Entity::update_many()
+                .col_expr(execution_session::Column::ResultData, Expr::value(encoded))
+                .filter(execution_session::Column::SessionId.eq(session_id))
+                .exec(db)
+                .await?;
+            report.execution_sessions += 1;
+        }
+
+        Ok(report)
+    }
+
+    async fn record_offload(&self, report: &mut OffloadReport, value: &JsonValue, encoded: &JsonValue) -> Result<()> {
+        report.original_bytes += serde_json::to_vec(value)?.len() as u64;
+        if let Some(checksum) = encoded["checksum"].as_str() {
+            report.stored_bytes += tokio::fs::metadata(self.store.blob_path(checksum)?).await?.len();
+        }
+        Ok(())
+    }
+}
+
+/// 定期转存已有的大字段并回收孤立的转存负载
+///
+/// 参数 `limit`（默认 [`DEFAULT_OFFLOAD_BATCH`]）为每张表每次处理的行数
+pub struct PayloadOffloadJob {
+    payloads: PayloadStore,
+}
+
+impl PayloadOffloadJob {
+    pub fn new(payloads: PayloadStore) -> Self {
+        Self { payloads }
+    }
+}
+
+impl MaintenanceJobHandler for PayloadOffloadJob {
+    fn kind(&self) -> &str {
+        JOB_PAYLOAD_OFFLOAD
+    }
+
+    fn run<'a>(&'a self, _db: &'a DatabaseConnection, params: &'a JsonValue, _now: DateTime<Utc>) -> MaintenanceFuture<'a> {
+        Box::pin(async move {
+            let limit = params.get("limit").and_then(JsonValue::as_u64).unwrap_or(DEFAULT_OFFLOAD_BATCH);
+            let report = self.payloads.offload_existing(limit).await?;
+            let released = self.payloads.release_orphans().await?;
+            Ok(json!({
+                "domain_events": report.domain_events,
+                "execution_sessions": report.execution_sessions,
+                "original_bytes": report.original_bytes,
+                "stored_bytes": report.stored_bytes,
+                "released_orphans": released,
+            }))
+        })
+    }
+}
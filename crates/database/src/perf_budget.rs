@@ -0,0 +1,485 @@
+//! 性能回归预算
+//!
+//! 每个项目可以配置 [`PerfBudget`]（存放在 `projects.perf_budget`）：一组基准测试命令及允许的
+//! 回归百分比。[`run_session_benchmarks`] 通过 [`BenchmarkRunner`] 在执行会话的工作空间中运行这些
+//! 命令，解析结果（criterion 的 `--message-format=json` 输出，或每行一个 JSON 对象的自定义格式）
+//! 并保存到 `benchmark_results`，同时保留历史供趋势图使用。
+//!
+//! 比较基线按分支保存：先取会话所在分支的基线，没有时取预算的 `baseline_branch`（默认为项目主分支）。
+//! 某项基准测试在两个分支上都还没有基线时，本次结果直接成为基线；在基线分支上运行且没有回归时，
+//! 结果自动成为新的基线，也可以通过 [`promote_session_baseline`] 手动提升。回归超过预算的执行会话
+//! 不能通过代码审查。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashSet;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::{
+    entities::{benchmark_result, code_review, project},
+    repository::{
+        benchmark_result_repository::CreateBenchmarkResultData, BenchmarkResultRepository,
+        ExecutionSessionRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::EntityTrait;
+
+fn default_max_regression_percent() -> f64 {
+    10.0
+}
+
+/// 基准测试输出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BenchmarkFormat {
+    /// `cargo criterion --message-format=json` 输出的 JSON 行
+    #[default]
+    Criterion,
+    /// 每行一个 `{"name", "value", "unit", "higher_is_better"}` 对象，其他行忽略
+    Custom,
+}
+
+impl BenchmarkFormat {
+    /// 按格式解析基准测试输出
+    pub fn parse(&self, output: &str) -> Result<Vec<Measurement>> {
+        match self {
+            BenchmarkFormat::Criterion => parse_criterion(output),
+            BenchmarkFormat::Custom => parse_custom(output),
+        }
+    }
+}
+
+/// 一条基准测试命令
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkCommand {
+    /// 命令名称，同一项目内唯一
+    pub name: String,
+    /// 在工作空间中执行的命令
+    pub command: String,
+    #[serde(default)]
+    pub format: BenchmarkFormat,
+    /// 覆盖预算的回归百分比
+    #[serde(default)]
+    pub max_regression_percent: Option<f64>,
+}
+
+/// 项目的性能预算
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerfBudget {
+    pub benchmarks: Vec<BenchmarkCommand>,
+    /// 允许的最大回归百分比
+    #[serde(default = "default_max_regression_percent")]
+    pub max_regression_percent: f64,
+    /// 会话分支没有基线时使用的分支，为空时使用项目主分支
+    #[serde(default)]
+    pub baseline_branch: Option<String>,
+}
+
+impl PerfBudget {
+    /// 校验预算
+    pub fn validate(&self) -> Result<()> {
+        if self.benchmarks.is_empty() {
+            return Err(DatabaseError::validation("性能预算至少需要一条基准测试命令"));
+        }
+        let mut names = HashSet::new();
+        for benchmark in &self.benchmarks {
+            if benchmark.name.trim().is_empty() || benchmark.command.trim().is_empty() {
+                return Err(DatabaseError::validation("基准测试命令的名称和命令不能为空"));
+            }
+            if !names.insert(benchmark.name.as_str()) {
+                return Err(DatabaseError::validation(format!("基准测试命令 {} 重复", benchmark.name)));
+            }
+        }
+        let percents = std::iter::once(self.max_regression_percent)
+            .chain(self.benchmarks.iter().filter_map(|benchmark| benchmark.max_regression_percent));
+        for percent in percents {
+            if !percent.is_finite() || percent < 0.0 {
+                return Err(DatabaseError::validation(format!("无效的回归百分比: {}", percent)));
+            }
+        }
+        Ok(())
+    }
+
+    /// 基准测试命令允许的回归百分比
+    pub fn threshold_for(&self, suite: &str) -> f64 {
+        self.benchmarks
+            .iter()
+            .find(|benchmark| benchmark.name == suite)
+            .and_then(|benchmark| benchmark.max_regression_percent)
+            .unwrap_or(self.max_regression_percent)
+    }
+}
+
+/// 一项基准测试的测量值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub name: String,
+    pub value: f64,
+    pub unit: String,
+    #[serde(default)]
+    pub higher_is_better: bool,
+}
+
+/// 时间单位换算为纳秒的倍数
+fn nanoseconds_per(unit: &str) -> Option<f64> {
+    match unit {
+        "ps" => Some(1e-3),
+        "ns" => Some(1.0),
+        "us" | "µs" | "μs" => Some(1e3),
+        "ms" => Some(1e6),
+        "s" => Some(1e9),
+        _ => None,
+    }
+}
+
+/// 解析 criterion 的 JSON 行输出，取典型值（typical）并统一换算为纳秒
+pub fn parse_criterion(output: &str) -> Result<Vec<Measurement>> {
+    let mut measurements = Vec::new();
+    for line in output.lines().map(str::trim).filter(|line| line.starts_with('{')) {
+        let message: JsonValue = serde_json::from_str(line)?;
+        if message["reason"] != "benchmark-complete" {
+            continue;
+        }
+        let (Some(id), Some(estimate)) = (message["id"].as_str(), message["typical"]["estimate"].as_f64()) else {
+            continue;
+        };
+        let unit = message["typical"]["unit"].as_str().unwrap_or("ns");
+        let factor = nanoseconds_per(unit)
+            .ok_or_else(|| DatabaseError::validation(format!("基准测试 {} 的单位无法识别: {}", id, unit)))?;
+        measurements.push(Measurement {
+            name: id.to_string(),
+            value: estimate * factor,
+            unit: "ns".to_string(),
+            higher_is_better: false,
+        });
+    }
+    Ok(measurements)
+}
+
+/// 解析自定义格式：每行一个 JSON 对象，非 JSON 行视为日志忽略
+pub fn parse_custom(output: &str) -> Result<Vec<Measurement>> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('{'))
+        .map(|line| {
+            let measurement: Measurement = serde_json::from_str(line)?;
+            if measurement.name.trim().is_empty() || !measurement.value.is_finite() {
+                return Err(DatabaseError::validation(format!("无效的基准测试结果: {}", line)));
+            }
+            Ok(measurement)
+        })
+        .collect()
+}
+
+/// 基准测试运行的异步结果（标准输出）
+pub type BenchmarkOutputFuture<'a> = Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>>;
+
+/// 基准测试运行器
+pub trait BenchmarkRunner: Send + Sync {
+    /// 在工作空间中运行基准测试命令，返回标准输出
+    fn run<'a>(&'a self, benchmark: &'a BenchmarkCommand, workspace: &'a Path) -> BenchmarkOutputFuture<'a>;
+}
+
+/// 以子进程方式运行基准测试
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CommandBenchmarkRunner;
+
+impl BenchmarkRunner for CommandBenchmarkRunner {
+    fn run<'a>(&'a self, benchmark: &'a BenchmarkCommand, workspace: &'a Path) -> BenchmarkOutputFuture<'a> {
+        Box::pin(async move {
+            let mut parts = benchmark.command.split_whitespace();
+            let program = parts
+                .next()
+                .ok_or_else(|| DatabaseError::validation(format!("{} 的命令为空", benchmark.name)))?;
+            let output = Command::new(program).args(parts).current_dir(workspace).output().await?;
+            if !output.status.success() {
+                return Err(DatabaseError::business_logic(format!(
+                    "{} 运行失败: {}",
+                    benchmark.name,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        })
+    }
+}
+
+/// 单条基准测试命令的运行情况
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkRun {
+    pub suite: String,
+    pub measurement_count: usize,
+    /// 运行或解析失败的原因，失败时保留该命令之前的结果
+    pub error: Option<String>,
+}
+
+/// 一项基准测试与基线的比较
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkComparison {
+    pub suite: String,
+    pub name: String,
+    pub value: f64,
+    pub unit: String,
+    /// 基线值，没有基线时为空
+    pub baseline: Option<f64>,
+    pub baseline_branch: Option<String>,
+    /// 相对基线的退化百分比，正数表示变差
+    pub regression_percent: Option<f64>,
+    pub threshold_percent: f64,
+    pub regressed: bool,
+}
+
+/// 性能门禁结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerfGateResult {
+    pub session_id: Uuid,
+    pub passed: bool,
+    pub comparisons: Vec<BenchmarkComparison>,
+}
+
+/// 执行会话的性能检查报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerfCheckReport {
+    pub session_id: Uuid,
+    pub runs: Vec<BenchmarkRun>,
+    pub gate: PerfGateResult,
+    /// 本次成为基线的结果数量
+    pub promoted_baselines: usize,
+}
+
+/// 读取项目的性能预算
+pub async fn project_perf_budget(db: &DatabaseConnection, project_id: Uuid) -> Result<Option<PerfBudget>> {
+    let project = project::Entity::find_by_id(project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+    budget_of(&project)
+}
+
+fn budget_of(project: &project::Model) -> Result<Option<PerfBudget>> {
+    match &project.perf_budget {
+        Some(value) => Ok(Some(serde_json::from_value(value.clone())?)),
+        None => Ok(None),
+    }
+}
+
+/// 相对基线的退化百分比，正数表示变差
+pub fn regression_percent(value: f64, baseline: f64, higher_is_better: bool) -> f64 {
+    if baseline == 0.0 {
+        return 0.0;
+    }
+    let change = (value - baseline) / baseline.abs() * 100.0;
+    if higher_is_better {
+        -change
+    } else {
+        change
+    }
+}
+
+/// 在工作空间中运行项目性能预算中的全部基准测试，保存结果并与基线比较
+pub async fn run_session_benchmarks(
+    db: &DatabaseConnection,
+    session_id: Uuid,
+    workspace: &Path,
+    runner: &dyn BenchmarkRunner,
+) -> Result<PerfCheckReport> {
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    let project = project::Entity::find_by_id(session.project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", session.project_id))?;
+    let budget = budget_of(&project)?
+        .ok_or_else(|| DatabaseError::validation(format!("项目 {} 没有配置性能预算", project.project_id)))?;
+
+    let repository = BenchmarkResultRepository::new(db.clone());
+    let mut runs = Vec::new();
+    for benchmark in &budget.benchmarks {
+        let result = runner
+            .run(benchmark, workspace)
+            .await
+            .and_then(|output| benchmark.format.parse(&output));
+        match result {
+            Ok(measurements) => {
+                runs.push(BenchmarkRun {
+                    suite: benchmark.name.clone(),
+                    measurement_count: measurements.len(),
+                    error: None,
+                });
+                repository.delete_by_session_and_suite(session_id, &benchmark.name).await?;
+                let data = measurements
+                    .into_iter()
+                    .map(|measurement| CreateBenchmarkResultData {
+                        project_id: session.project_id,
+                        execution_session_id: session_id,
+                        branch: session.git_branch.clone(),
+                        commit_sha: session.final_commit.clone().or_else(|| session.base_commit.clone()),
+                        suite: benchmark.name.clone(),
+                        name: measurement.name,
+                        value: measurement.value,
+                        unit: measurement.unit,
+                        higher_is_better: measurement.higher_is_better,
+                    })
+                    .collect();
+                repository.create_many(data).await?;
+            }
+            Err(e) => {
+                tracing::warn!(session_id = %session_id, suite = %benchmark.name, error = %e, "基准测试运行失败");
+                runs.push(BenchmarkRun {
+                    suite: benchmark.name.clone(),
+                    measurement_count: 0,
+                    error: Some(e.to_string()),
+                });
+            }
+        }
+    }
+
+    let results = repository.find_by_session(session_id).await?;
+    let gate = compare_with_baselines(db, &project, &budget, session_id, &results).await?;
+
+    // 还没有任何基线的基准测试以本次结果为基线；基线分支上没有回归时结果成为新的基线
+    let baseline_branch = budget.baseline_branch.clone().unwrap_or_else(|| project.main_branch.clone());
+    let promote_all = session.git_branch == baseline_branch && gate.passed;
+    let mut promoted_baselines = 0;
+    for (result, comparison) in results.iter().zip(&gate.comparisons) {
+        if promote_all || comparison.baseline.is_none() {
+            repository.mark_baseline(result).await?;
+            promoted_baselines += 1;
+        }
+    }
+
+    Ok(PerfCheckReport {
+        session_id,
+        runs,
+        gate,
+        promoted_baselines,
+    })
+}
+
+/// 把结果与会话分支（没有时为基线分支）上的基线比较，结果顺序与 `results` 一致
+async fn compare_with_baselines(
+    db: &DatabaseConnection,
+    project: &project::Model,
+    budget: &PerfBudget,
+    session_id: Uuid,
+    results: &[benchmark_result::Model],
+) -> Result<PerfGateResult> {
+    let repository = BenchmarkResultRepository::new(db.clone());
+    let baseline_branch = budget.baseline_branch.clone().unwrap_or_else(|| project.main_branch.clone());
+
+    let mut comparisons = Vec::with_capacity(results.len());
+    for result in results {
+        let mut baseline = None;
+        for branch in [result.branch.as_str(), baseline_branch.as_str()] {
+            let found = repository
+                .find_baseline(project.project_id, branch, &result.suite, &result.name)
+                .await?
+                .filter(|baseline| baseline.execution_session_id != session_id);
+            if found.is_some() {
+                baseline = found;
+                break;
+            }
+        }
+
+        let threshold_percent = budget.threshold_for(&result.suite);
+        let regression = baseline
+            .as_ref()
+            .map(|baseline| regression_percent(result.value, baseline.value, result.higher_is_better));
+        comparisons.push(BenchmarkComparison {
+            suite: result.suite.clone(),
+            name: result.name.clone(),
+            value: result.value,
+            unit: result.unit.clone(),
+            baseline: baseline.as_ref().map(|baseline| baseline.value),
+            baseline_branch: baseline.map(|baseline| baseline.branch),
+            regression_percent: regression,
+            threshold_percent,
+            regressed: regression.is_some_and(|regression| regression > threshold_percent),
+        });
+    }
+
+    Ok(PerfGateResult {
+        session_id,
+        passed: comparisons.iter().all(|comparison| !comparison.regressed),
+        comparisons,
+    })
+}
+
+/// 评估执行会话的性能门禁，项目没有配置性能预算或会话没有基准测试结果时返回 None
+pub async fn evaluate_perf_gate(db: &DatabaseConnection, session_id: Uuid) -> Result<Option<PerfGateResult>> {
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    let project = project::Entity::find_by_id(session.project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", session.project_id))?;
+    let Some(budget) = budget_of(&project)? else {
+        return Ok(None);
+    };
+    let results = BenchmarkResultRepository::new(db.clone()).find_by_session(session_id).await?;
+    if results.is_empty() {
+        return Ok(None);
+    }
+    compare_with_baselines(db, &project, &budget, session_id, &results).await.map(Some)
+}
+
+/// 把执行会话的全部基准测试结果提升为所在分支的基线，返回提升的数量
+pub async fn promote_session_baseline(db: &DatabaseConnection, session_id: Uuid) -> Result<usize> {
+    let repository = BenchmarkResultRepository::new(db.clone());
+    let results = repository.find_by_session(session_id).await?;
+    for result in &results {
+        repository.mark_baseline(result).await?;
+    }
+    Ok(results.len())
+}
+
+/// 查询分支上某项基准测试的历史结果，按时间先后排列，用于趋势图
+pub async fn benchmark_history(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    branch: &str,
+    suite: &str,
+    name: &str,
+    limit: u64,
+) -> Result<Vec<benchmark_result::Model>> {
+    BenchmarkResultRepository::new(db.clone())
+        .find_history(project_id, branch, suite, name, limit)
+        .await
+}
+
+/// 检查审查能否被批准：执行会话的基准测试回归超过预算时不能批准
+pub async fn ensure_review_can_be_approved(db: &DatabaseConnection, review: &code_review::Model) -> Result<()> {
+    match evaluate_perf_gate(db, review.execution_session_id).await? {
+        Some(gate) if !gate.passed => {
+            let regressed: Vec<String> = gate
+                .comparisons
+                .iter()
+                .filter(|comparison| comparison.regressed)
+                .map(|comparison| {
+                    format!(
+                        "{}/{} 退化 {:.1}%（上限 {:.1}%）",
+                        comparison.suite,
+                        comparison.name,
+                        comparison.regression_percent.unwrap_or_default(),
+                        comparison.threshold_percent
+                    )
+                })
+                .collect();
+            Err(DatabaseError::business_logic(format!(
+                "执行会话的性能回归超出预算，不能批准审查：{}",
+                regressed.join("；")
+            )))
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,344 @@
+//! 需求文档个人信息脱敏
+//!
+//! 企业用户需要避免把个人信息发送给 LLM。项目可以配置 [`PiiScrubbingConfig`]
+//! （存放在 `projects.pii_scrubbing`），构建任务分解提示词时 [`build_decomposition_prompt`]
+//! 把邮箱、电话号码、指定姓名和自定义模式替换为占位符（例如 `[EMAIL_1]`）。
+//!
+//! 占位符与原文的映射只保存在本地数据库（`pii_mappings`），同一原文在项目内始终使用同一占位符，
+//! [`reidentify`] 用它把 LLM 返回的结果还原为原文。
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{
+    context::{diff::{self, diff_contexts, ContextDiff}, ContextBuilder},
+    entities::{pii_mapping, project},
+    repository::{pii_mapping_repository::CreatePiiMappingData, PiiMappingRepository, RequirementDocumentRepository},
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::EntityTrait;
+
+/// 邮箱类别标签
+pub const LABEL_EMAIL: &str = "EMAIL";
+
+/// 电话号码类别标签
+pub const LABEL_PHONE: &str = "PHONE";
+
+/// 姓名类别标签
+pub const LABEL_NAME: &str = "NAME";
+
+const EMAIL_PATTERN: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}";
+
+/// 手机号、带国家码的号码和常见的分段固定电话
+const PHONE_PATTERN: &str = r"\+\d{1,3}[ -]?\d{2,4}[ -]?\d{3,4}[ -]?\d{3,4}|\(\d{2,4}\) ?\d{3,4}-\d{4}|\d{3,4}-\d{3,4}-\d{4}|1[3-9]\d{9}";
+
+/// 自定义模式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PiiPattern {
+    /// 类别标签，用于生成占位符，只能包含大写字母、数字和下划线
+    pub label: String,
+    /// 正则表达式
+    pub pattern: String,
+}
+
+/// 个人信息脱敏配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PiiScrubbingConfig {
+    /// 是否启用
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 替换邮箱
+    #[serde(default = "default_true")]
+    pub emails: bool,
+    /// 替换电话号码
+    #[serde(default = "default_true")]
+    pub phone_numbers: bool,
+    /// 需要替换的姓名
+    #[serde(default)]
+    pub names: Vec<String>,
+    /// 自定义模式
+    #[serde(default)]
+    pub patterns: Vec<PiiPattern>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for PiiScrubbingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            emails: true,
+            phone_numbers: true,
+            names: Vec::new(),
+            patterns: Vec::new(),
+        }
+    }
+}
+
+impl PiiScrubbingConfig {
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        if self.names.iter().any(|name| name.trim().is_empty()) {
+            return Err(DatabaseError::validation("脱敏姓名不能为空"));
+        }
+        for pattern in &self.patterns {
+            let valid_label = !pattern.label.is_empty()
+                && pattern
+                    .label
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+            if !valid_label {
+                return Err(DatabaseError::validation(format!(
+                    "脱敏模式标签 {} 只能包含大写字母、数字和下划线",
+                    pattern.label
+                )));
+            }
+            Regex::new(&pattern.pattern).map_err(|e| {
+                DatabaseError::validation(format!("脱敏模式 {} 的正则表达式无效: {}", pattern.label, e))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// 占位符与原文的映射
+#[derive(Debug, Clone, Default)]
+pub struct PiiMapping {
+    placeholders: HashMap<String, String>,
+    originals: HashMap<String, String>,
+    counters: HashMap<String, usize>,
+    pending: Vec<(String, String, String)>,
+}
+
+impl PiiMapping {
+    /// 从已保存的映射恢复
+    pub fn from_models(models: &[pii_mapping::Model]) -> Self {
+        let mut mapping = Self::default();
+        for model in models {
+            mapping.insert(&model.label, &model.placeholder, &model.original);
+        }
+        mapping
+    }
+
+    /// 原文对应的占位符，首次出现时分配新占位符
+    pub fn placeholder(&mut self, label: &str, original: &str) -> String {
+        if let Some(placeholder) = self.placeholders.get(original) {
+            return placeholder.clone();
+        }
+        let next = self.counters.get(label).copied().unwrap_or(0) + 1;
+        let placeholder = format!("[{}_{}]", label, next);
+        self.insert(label, &placeholder, original);
+        self.pending
+            .push((placeholder.clone(), label.to_string(), original.to_string()));
+        placeholder
+    }
+
+    /// 把文本中的占位符还原为原文
+    pub fn reidentify(&self, text: &str) -> String {
+        let mut result = text.to_string();
+        // 先替换较长的占位符，避免 [NAME_1] 破坏 [NAME_12]
+        let mut placeholders: Vec<_> = self.originals.iter().collect();
+        placeholders.sort_by_key(|(placeholder, _)| std::cmp::Reverse(placeholder.len()));
+        for (placeholder, original) in placeholders {
+            result = result.replace(placeholder.as_str(), original);
+        }
+        result
+    }
+
+    /// 已分配的映射数量
+    pub fn len(&self) -> usize {
+        self.originals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.originals.is_empty()
+    }
+
+    fn insert(&mut self, label: &str, placeholder: &str, original: &str) {
+        self.placeholders.insert(original.to_string(), placeholder.to_string());
+        self.originals.insert(placeholder.to_string(), original.to_string());
+        if let Some(index) = placeholder
+            .strip_prefix(&format!("[{}_", label))
+            .and_then(|rest| rest.strip_suffix(']'))
+            .and_then(|index| index.parse::<usize>().ok())
+        {
+            let counter = self.counters.entry(label.to_string()).or_insert(0);
+            *counter = (*counter).max(index);
+        }
+    }
+}
+
+/// 个人信息脱敏器
+pub struct PiiScrubber {
+    rules: Vec<(String, Regex)>,
+}
+
+impl PiiScrubber {
+    /// 按配置创建脱敏器，依次替换邮箱、电话号码、自定义模式和姓名
+    pub fn new(config: &PiiScrubbingConfig) -> Result<Self> {
+        config.validate()?;
+        let mut rules = Vec::new();
+        if config.emails {
+            rules.push((LABEL_EMAIL.to_string(), compile(EMAIL_PATTERN)?));
+        }
+        if config.phone_numbers {
+            rules.push((LABEL_PHONE.to_string(), compile(PHONE_PATTERN)?));
+        }
+        for pattern in &config.patterns {
+            rules.push((pattern.label.clone(), compile(&pattern.pattern)?));
+        }
+        if !config.names.is_empty() {
+            // 较长的姓名优先匹配
+            let mut names: Vec<&str> = config.names.iter().map(|name| name.trim()).collect();
+            names.sort_by_key(|name| std::cmp::Reverse(name.chars().count()));
+            let alternation = names.iter().map(|name| regex::escape(name)).collect::<Vec<_>>().join("|");
+            rules.push((LABEL_NAME.to_string(), compile(&alternation)?));
+        }
+        Ok(Self { rules })
+    }
+
+    /// 替换文本中的个人信息，返回脱敏后的文本和替换次数
+    pub fn scrub(&self, text: &str, mapping: &mut PiiMapping) -> (String, usize) {
+        let mut result = text.to_string();
+        let mut replaced = 0;
+        for (label, regex) in &self.rules {
+            let mut output = String::with_capacity(result.len());
+            let mut last = 0;
+            for found in regex.find_iter(&result) {
+                if found.as_str().is_empty() || (label == LABEL_PHONE && !digit_bounded(&result, found.start(), found.end())) {
+                    continue;
+                }
+                output.push_str(&result[last..found.start()]);
+                output.push_str(&mapping.placeholder(label, found.as_str()));
+                last = found.end();
+                replaced += 1;
+            }
+            output.push_str(&result[last..]);
+            result = output;
+        }
+        (result, replaced)
+    }
+}
+
+fn compile(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern).map_err(|e| DatabaseError::validation(format!("脱敏正则表达式无效: {}", e)))
+}
+
+/// 号码前后不能紧接其他数字，避免截取更长的数字串
+fn digit_bounded(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    !before.is_some_and(|c| c.is_ascii_digit()) && !after.is_some_and(|c| c.is_ascii_digit())
+}
+
+/// 分解提示词
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecompositionPrompt {
+    pub project_id: Uuid,
+    pub document_id: Uuid,
+    /// 发送给 LLM 的提示词
+    pub prompt: String,
+    /// 替换的个人信息数量，未启用脱敏时为 0
+    pub scrubbed_items: usize,
+    /// 与上次分解时的项目上下文相比的变化，首次分解或没有变化时为空
+    #[serde(default)]
+    pub context_diff: Option<ContextDiff>,
+}
+
+/// 读取项目的脱敏配置，未配置或未启用时返回 None
+pub async fn project_config(db: &DatabaseConnection, project_id: Uuid) -> Result<Option<PiiScrubbingConfig>> {
+    let project = project::Entity::find_by_id(project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+
+    let config = match project.pii_scrubbing {
+        Some(value) => Some(serde_json::from_value::<PiiScrubbingConfig>(value)?),
+        None => None,
+    };
+    Ok(config.filter(|config| config.enabled))
+}
+
+/// 按项目配置脱敏文本，新分配的占位符保存到本地映射
+///
+/// 返回脱敏后的文本和替换次数；项目未启用脱敏时原样返回。
+pub async fn scrub_for_project(db: &DatabaseConnection, project_id: Uuid, text: &str) -> Result<(String, usize)> {
+    let Some(config) = project_config(db, project_id).await? else {
+        return Ok((text.to_string(), 0));
+    };
+    let scrubber = PiiScrubber::new(&config)?;
+    let mut mapping = load_mapping(db, project_id).await?;
+    let scrubbed = scrubber.scrub(text, &mut mapping);
+    save_mapping(db, project_id, &mut mapping).await?;
+    Ok(scrubbed)
+}
+
+/// 读取项目的本地映射
+pub async fn load_mapping(db: &DatabaseConnection, project_id: Uuid) -> Result<PiiMapping> {
+    let models = PiiMappingRepository::new(db.clone()).find_by_project(project_id).await?;
+    Ok(PiiMapping::from_models(&models))
+}
+
+/// 保存新分配的占位符
+pub async fn save_mapping(db: &DatabaseConnection, project_id: Uuid, mapping: &mut PiiMapping) -> Result<()> {
+    let repo = PiiMappingRepository::new(db.clone());
+    for (placeholder, label, original) in std::mem::take(&mut mapping.pending) {
+        repo.create(CreatePiiMappingData {
+            project_id,
+            placeholder,
+            label,
+            original,
+        })
+        .await?;
+    }
+    Ok(())
+}
+
+/// 把 LLM 返回的结果中的占位符还原为原文
+pub async fn reidentify(db: &DatabaseConnection, project_id: Uuid, text: &str) -> Result<String> {
+    Ok(load_mapping(db, project_id).await?.reidentify(text))
+}
+
+/// 为需求文档构建任务分解提示词，项目启用脱敏时替换其中的个人信息
+pub async fn build_decomposition_prompt(db: &DatabaseConnection, document_id: Uuid) -> Result<DecompositionPrompt> {
+    let document = RequirementDocumentRepository::new(db.clone())
+        .find_by_id(document_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("RequirementDocument", document_id))?;
+
+    let prompt = format!(
+        "请将以下需求文档分解为可独立执行的开发任务，并为每个任务给出验收标准。\n\n\
+         ## 需求文档：{}\n类型：{}，优先级：{}，版本：{}\n\n{}",
+        document.title,
+        document.document_type,
+        document.priority,
+        document.version,
+        document.content.trim()
+    );
+    // 与上次分解时的项目上下文比较，变化写入提示词，并保存本次快照供下次比较
+    let context = ContextBuilder::new(db.clone()).build(document.project_id).await?;
+    let context_diff = diff::latest_context(db, document.project_id)
+        .await?
+        .map(|previous| diff_contexts(&previous, &context))
+        .filter(|changes| !changes.is_empty());
+    diff::record_snapshot(db, document.project_id, Some(document_id), &context).await?;
+    let prompt = match &context_diff {
+        Some(changes) => format!("{}\n\n{}", prompt, changes.render_prompt_section()),
+        None => prompt,
+    };
+    // 项目共享上下文和上下文变化同样可能包含个人信息，附加后再统一脱敏
+    let prompt = crate::blackboard::append_to_prompt(db, document.project_id, prompt).await?;
+    let (prompt, scrubbed_items) = scrub_for_project(db, document.project_id, &prompt).await?;
+
+    Ok(DecompositionPrompt {
+        project_id: document.project_id,
+        document_id,
+        prompt,
+        scrubbed_items,
+        context_diff,
+    })
+}
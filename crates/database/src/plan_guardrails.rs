@@ -0,0 +1,125 @@
+//! LLM任务计划的护栏校验与自动修订
+//!
+//! LLM提出的任务计划可能超出时间线或需要现有Agent不具备的能力。[`PlanGuardrail`] 在持久化之前
+//! 用 [`PlanValidator`] 按项目上下文校验计划，未通过时把违规列表作为反馈追加到提示词，
+//! 请LLM修订计划，最多修订 [`PlanGuardrail::with_max_revisions`] 轮。修订后仍未通过的计划
+//! 会在任务的风险因子中标注违规，由调用方决定是否交给人工确认。
+
+use chrono::Utc;
+use codex_multi_agent::{PlanValidationReport, PlanValidator, ProjectContext, ProjectId, TaskInfo};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    llm_provider::{LlmCompletion, LlmProvider, LlmRequest},
+    DatabaseError, Result,
+};
+
+/// 默认最多修订轮数
+pub const DEFAULT_MAX_REVISIONS: u32 = 2;
+
+/// 经过护栏校验的计划
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardedPlan {
+    /// 最终的任务计划，未通过校验时已标注违规
+    pub tasks: Vec<TaskInfo>,
+    /// 最终计划的校验报告
+    pub report: PlanValidationReport,
+    /// 实际修订轮数
+    pub revisions: u32,
+    /// 所有LLM调用累计消耗的token数
+    pub tokens_used: u32,
+}
+
+impl GuardedPlan {
+    /// 计划是否通过校验，可以直接持久化
+    pub fn is_accepted(&self) -> bool {
+        self.report.is_valid()
+    }
+}
+
+/// 任务计划护栏
+pub struct PlanGuardrail<'a> {
+    provider: &'a dyn LlmProvider,
+    validator: PlanValidator,
+    max_revisions: u32,
+}
+
+impl<'a> PlanGuardrail<'a> {
+    pub fn new(provider: &'a dyn LlmProvider, validator: PlanValidator) -> Self {
+        Self {
+            provider,
+            validator,
+            max_revisions: DEFAULT_MAX_REVISIONS,
+        }
+    }
+
+    /// 设置最多修订轮数，为 0 时只校验不修订
+    pub fn with_max_revisions(mut self, max_revisions: u32) -> Self {
+        self.max_revisions = max_revisions;
+        self
+    }
+
+    /// 校验LLM的分解输出，未通过时请LLM修订
+    ///
+    /// `initial` 为分解请求 `request` 的输出，`parse` 把LLM输出解析为任务计划。
+    /// 初始输出无法解析时返回验证错误；修订输出无法解析时保留上一版计划并停止修订
+    pub async fn review<P>(
+        &self,
+        project_id: ProjectId,
+        context: &ProjectContext,
+        request: &LlmRequest,
+        initial: LlmCompletion,
+        parse: P,
+    ) -> Result<GuardedPlan>
+    where
+        P: Fn(&str) -> Option<Vec<TaskInfo>>,
+    {
+        let mut tasks = parse(&initial.content)
+            .ok_or_else(|| DatabaseError::validation("LLM输出中没有可解析的任务计划"))?;
+        let mut output = initial.content;
+        let mut tokens_used = initial.tokens_used;
+        let mut revisions = 0;
+
+        let mut report = self.validator.validate(project_id.clone(), context, &tasks, Utc::now());
+        while !report.is_valid() && revisions < self.max_revisions {
+            let revision = LlmRequest {
+                prompt: revision_prompt(&request.prompt, &output, &report.revision_feedback(&tasks)),
+                ..request.clone()
+            };
+            let completion = self.provider.complete(&revision).await?;
+            tokens_used += completion.tokens_used;
+            revisions += 1;
+
+            let Some(revised) = parse(&completion.content) else {
+                tracing::warn!("第 {} 轮修订的输出无法解析，保留上一版计划", revisions);
+                break;
+            };
+            tasks = revised;
+            output = completion.content;
+            report = self.validator.validate(project_id.clone(), context, &tasks, Utc::now());
+            tracing::debug!("第 {} 轮修订后计划剩余 {} 项违规", revisions, report.violations.len());
+        }
+
+        if !report.is_valid() {
+            tracing::warn!(
+                "项目 {} 的任务计划经过 {} 轮修订仍有 {} 项违规，需要人工确认",
+                project_id,
+                revisions,
+                report.violations.len()
+            );
+            report.annotate(&mut tasks);
+        }
+
+        Ok(GuardedPlan { tasks, report, revisions, tokens_used })
+    }
+}
+
+/// 修订提示词：原始提示词、上一版输出和违规反馈
+fn revision_prompt(prompt: &str, previous_output: &str, feedback: &str) -> String {
+    format!(
+        "{}\n\n## 上一版任务计划\n{}\n\n## 校验反馈\n{}\n",
+        prompt.trim_end(),
+        previous_output.trim(),
+        feedback
+    )
+}
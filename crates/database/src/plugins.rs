@@ -0,0 +1,579 @@
+//! 自定义事件处理插件
+//!
+//! 团队可以在不修改 sker 的情况下对领域事件做出自定义反应（例如写入内部系统）：
+//! - 其他 crate 实现 [`EventPlugin`]，在运行时注册到 [`PluginRegistry`]，按事件类型订阅；
+//! - [`CommandPlugin`] 按清单（`*.toml`）把事件以 JSON 写入外部命令的标准输入，
+//!   命令在清空的环境变量中运行，只传入清单声明的变量。
+//!
+//! [`PluginDispatcher`] 取出尚未处理的领域事件并投递给订阅的插件。每次调用在独立任务中
+//! 运行并受超时限制，插件返回错误、超时或 panic 都只记为该插件的一次失败，不影响其他插件
+//! 和事件的处理；连续失败达到上限的插件被停用，直到调用 [`PluginRegistry::enable`]。
+//! 每次投递写入 `event_publish_log`（订阅者类型为 `plugin`），失败的投递由
+//! [`PluginDispatcher::retry_failed`] 在尝试次数用完前重试。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::{
+    entities::{domain_event, event_publish_log},
+    repository::{
+        event_publish_log_repository::CreateEventPublishLogData, DomainEventRepository, EventPublishLogRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::EntityTrait;
+
+/// 发布日志中插件投递的订阅者类型
+pub const PLUGIN_SUBSCRIBER_TYPE: &str = "plugin";
+
+/// 订阅全部事件类型的通配符
+pub const ALL_EVENTS: &str = "*";
+
+/// 默认的单次调用超时
+pub const DEFAULT_PLUGIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 默认的连续失败上限，达到后停用插件
+pub const DEFAULT_MAX_CONSECUTIVE_FAILURES: u32 = 5;
+
+/// 默认的单个事件最大投递次数（含首次投递）
+pub const DEFAULT_MAX_ATTEMPTS: i32 = 3;
+
+/// 每轮最多处理的事件数
+const DISPATCH_BATCH_SIZE: u64 = 100;
+
+/// 命令插件传入的环境变量，其余环境变量被清空
+const INHERITED_ENV_VARS: &[&str] = &["PATH", "HOME", "TMPDIR", "LANG"];
+
+/// 插件处理的异步结果，返回值写入发布日志的响应数据
+pub type PluginFuture<'a> = Pin<Box<dyn Future<Output = Result<Option<JsonValue>>> + Send + 'a>>;
+
+/// 投递给插件的事件，是领域事件的只读副本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginEvent {
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub aggregate_type: String,
+    pub aggregate_id: Uuid,
+    pub event_data: JsonValue,
+    pub correlation_id: Option<Uuid>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl From<&domain_event::Model> for PluginEvent {
+    fn from(event: &domain_event::Model) -> Self {
+        Self {
+            event_id: event.event_id,
+            event_type: event.event_type.clone(),
+            aggregate_type: event.aggregate_type.clone(),
+            aggregate_id: event.aggregate_id,
+            event_data: event.event_data.clone(),
+            correlation_id: event.correlation_id,
+            occurred_at: event.occurred_at.with_timezone(&Utc),
+        }
+    }
+}
+
+/// 自定义事件处理插件
+pub trait EventPlugin: Send + Sync {
+    /// 插件名称，在注册表中唯一，也是发布日志中的订阅者ID
+    fn name(&self) -> &str;
+
+    /// 订阅的事件类型（如 `TaskCompleted`），[`ALL_EVENTS`] 表示全部
+    fn subscriptions(&self) -> Vec<String>;
+
+    /// 处理一个事件
+    fn handle<'a>(&'a self, event: &'a PluginEvent) -> PluginFuture<'a>;
+}
+
+/// 插件的运行限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PluginLimits {
+    /// 单次调用的超时
+    pub timeout: Duration,
+    /// 连续失败上限，达到后停用插件
+    pub max_consecutive_failures: u32,
+    /// 单个事件的最大投递次数（含首次投递）
+    pub max_attempts: i32,
+}
+
+impl Default for PluginLimits {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_PLUGIN_TIMEOUT,
+            max_consecutive_failures: DEFAULT_MAX_CONSECUTIVE_FAILURES,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+        }
+    }
+}
+
+/// 插件的运行状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginStatus {
+    pub name: String,
+    pub subscriptions: Vec<String>,
+    /// 是否启用，连续失败达到上限后被停用
+    pub enabled: bool,
+    pub consecutive_failures: u32,
+    pub delivered: u64,
+    pub failed: u64,
+    pub last_error: Option<String>,
+}
+
+struct RegisteredPlugin {
+    plugin: Arc<dyn EventPlugin>,
+    status: Mutex<PluginStatus>,
+}
+
+impl RegisteredPlugin {
+    fn subscribes_to(&self, event_type: &str) -> bool {
+        self.status
+            .lock()
+            .map(|status| {
+                status.enabled
+                    && status
+                        .subscriptions
+                        .iter()
+                        .any(|subscription| subscription == ALL_EVENTS || subscription == event_type)
+            })
+            .unwrap_or(false)
+    }
+
+    fn record(&self, outcome: &std::result::Result<Option<JsonValue>, String>, max_consecutive_failures: u32) {
+        let Ok(mut status) = self.status.lock() else {
+            return;
+        };
+        match outcome {
+            Ok(_) => {
+                status.delivered += 1;
+                status.consecutive_failures = 0;
+            }
+            Err(error) => {
+                status.failed += 1;
+                status.consecutive_failures += 1;
+                status.last_error = Some(error.clone());
+                if status.enabled && status.consecutive_failures >= max_consecutive_failures {
+                    status.enabled = false;
+                    tracing::warn!("插件 {} 连续失败 {} 次，已停用: {}", status.name, status.consecutive_failures, error);
+                }
+            }
+        }
+    }
+}
+
+/// 插件注册表，可在运行中注册和移除插件
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: RwLock<Vec<Arc<RegisteredPlugin>>>,
+}
+
+impl PluginRegistry {
+    /// 创建空注册表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册插件，名称重复或没有订阅任何事件时返回验证错误
+    pub fn register(&self, plugin: Arc<dyn EventPlugin>) -> Result<()> {
+        let name = plugin.name().trim().to_string();
+        if name.is_empty() {
+            return Err(DatabaseError::validation("插件名称不能为空"));
+        }
+        let mut subscriptions: Vec<String> = plugin
+            .subscriptions()
+            .into_iter()
+            .map(|subscription| subscription.trim().to_string())
+            .filter(|subscription| !subscription.is_empty())
+            .collect();
+        subscriptions.sort();
+        subscriptions.dedup();
+        if subscriptions.is_empty() {
+            return Err(DatabaseError::validation(format!("插件 {} 没有订阅任何事件类型", name)));
+        }
+
+        let mut plugins = self.write()?;
+        if plugins.iter().any(|registered| registered.plugin.name().trim() == name) {
+            return Err(DatabaseError::validation(format!("插件 {} 已注册", name)));
+        }
+        plugins.push(Arc::new(RegisteredPlugin {
+            plugin,
+            status: Mutex::new(PluginStatus {
+                name,
+                subscriptions,
+                enabled: true,
+                consecutive_failures: 0,
+                delivered: 0,
+                failed: 0,
+                last_error: None,
+            }),
+        }));
+        Ok(())
+    }
+
+    /// 移除插件，返回插件是否存在
+    pub fn unregister(&self, name: &str) -> Result<bool> {
+        let mut plugins = self.write()?;
+        let before = plugins.len();
+        plugins.retain(|registered| registered.plugin.name().trim() != name);
+        Ok(plugins.len() != before)
+    }
+
+    /// 重新启用被停用的插件并清零连续失败次数，返回插件是否存在
+    pub fn enable(&self, name: &str) -> Result<bool> {
+        let Some(registered) = self.find(name)? else {
+            return Ok(false);
+        };
+        if let Ok(mut status) = registered.status.lock() {
+            status.enabled = true;
+            status.consecutive_failures = 0;
+        }
+        Ok(true)
+    }
+
+    /// 所有插件的运行状态，按注册顺序
+    pub fn statuses(&self) -> Vec<PluginStatus> {
+        self.plugins
+            .read()
+            .map(|plugins| {
+                plugins
+                    .iter()
+                    .filter_map(|registered| registered.status.lock().ok().map(|status| status.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn find(&self, name: &str) -> Result<Option<Arc<RegisteredPlugin>>> {
+        let plugins = self
+            .plugins
+            .read()
+            .map_err(|_| DatabaseError::business_logic("插件注册表已损坏"))?;
+        Ok(plugins.iter().find(|registered| registered.plugin.name().trim() == name).cloned())
+    }
+
+    fn subscribers(&self, event_type: &str) -> Vec<Arc<RegisteredPlugin>> {
+        self.plugins
+            .read()
+            .map(|plugins| plugins.iter().filter(|registered| registered.subscribes_to(event_type)).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn write(&self) -> Result<std::sync::RwLockWriteGuard<'_, Vec<Arc<RegisteredPlugin>>>> {
+        self.plugins
+            .write()
+            .map_err(|_| DatabaseError::business_logic("插件注册表已损坏"))
+    }
+}
+
+/// 单次投递的结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginDelivery {
+    pub event_id: Uuid,
+    pub plugin: String,
+    pub log_id: Uuid,
+    pub delivered: bool,
+    pub error: Option<String>,
+}
+
+/// 一轮投递的汇总
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DispatchReport {
+    /// 本轮处理的事件数
+    pub events_processed: usize,
+    pub deliveries: Vec<PluginDelivery>,
+}
+
+impl DispatchReport {
+    /// 失败的投递数
+    pub fn failed_count(&self) -> usize {
+        self.deliveries.iter().filter(|delivery| !delivery.delivered).count()
+    }
+}
+
+/// 插件投递器
+#[derive(Clone)]
+pub struct PluginDispatcher {
+    db: DatabaseConnection,
+    registry: Arc<PluginRegistry>,
+    limits: PluginLimits,
+}
+
+impl PluginDispatcher {
+    /// 使用默认运行限制创建投递器
+    pub fn new(db: DatabaseConnection, registry: Arc<PluginRegistry>) -> Self {
+        Self { db, registry, limits: PluginLimits::default() }
+    }
+
+    /// 设置运行限制
+    pub fn with_limits(mut self, limits: PluginLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// 投递器使用的注册表
+    pub fn registry(&self) -> &Arc<PluginRegistry> {
+        &self.registry
+    }
+
+    /// 把尚未处理的领域事件投递给订阅的插件，并标记事件已处理
+    ///
+    /// 插件失败不会中断本轮投递，失败原因写入发布日志和事件的错误信息
+    pub async fn dispatch_pending(&self) -> Result<DispatchReport> {
+        let events = DomainEventRepository::new(self.db.clone())
+            .find_unprocessed(DISPATCH_BATCH_SIZE)
+            .await?;
+        let mut report = DispatchReport::default();
+        for event in &events {
+            let deliveries = self.dispatch(event).await?;
+            let errors: Vec<String> = deliveries
+                .iter()
+                .filter_map(|delivery| delivery.error.as_ref().map(|error| format!("{}: {}", delivery.plugin, error)))
+                .collect();
+            let error_message = (!errors.is_empty()).then(|| errors.join("; "));
+            DomainEventRepository::new(self.db.clone())
+                .mark_processed(event.event_id, error_message)
+                .await?;
+            report.events_processed += 1;
+            report.deliveries.extend(deliveries);
+        }
+        Ok(report)
+    }
+
+    /// 把一个事件投递给当前订阅它的插件
+    pub async fn dispatch(&self, event: &domain_event::Model) -> Result<Vec<PluginDelivery>> {
+        let plugin_event = PluginEvent::from(event);
+        let logs = EventPublishLogRepository::new(self.db.clone());
+        let mut deliveries = Vec::new();
+        for registered in self.registry.subscribers(&plugin_event.event_type) {
+            let name = registered.plugin.name().trim().to_string();
+            let log = logs
+                .create(CreateEventPublishLogData {
+                    event_id: event.event_id,
+                    subscriber_type: PLUGIN_SUBSCRIBER_TYPE.to_string(),
+                    subscriber_id: name,
+                    status: "pending".to_string(),
+                    attempts: 0,
+                    max_attempts: self.limits.max_attempts,
+                    response_data: None,
+                    error_message: None,
+                })
+                .await?;
+            deliveries.push(self.deliver(&registered, &plugin_event, &log).await?);
+        }
+        Ok(deliveries)
+    }
+
+    /// 重试失败且尚未用完尝试次数的投递，已移除或停用的插件跳过
+    pub async fn retry_failed(&self) -> Result<DispatchReport> {
+        let logs = EventPublishLogRepository::new(self.db.clone())
+            .find_retryable(PLUGIN_SUBSCRIBER_TYPE)
+            .await?;
+        let mut report = DispatchReport::default();
+        let mut events: HashMap<Uuid, Option<PluginEvent>> = HashMap::new();
+        for log in logs {
+            let Some(registered) = self.registry.find(&log.subscriber_id)? else {
+                continue;
+            };
+            let event = match events.get(&log.event_id) {
+                Some(event) => event.clone(),
+                None => {
+                    let event = domain_event::Entity::find_by_id(log.event_id)
+                        .one(&self.db)
+                        .await?
+                        .map(|event| PluginEvent::from(&event));
+                    if event.is_some() {
+                        report.events_processed += 1;
+                    }
+                    events.insert(log.event_id, event.clone());
+                    event
+                }
+            };
+            let Some(event) = event else {
+                continue;
+            };
+            if !registered.subscribes_to(&event.event_type) {
+                continue;
+            }
+            report.deliveries.push(self.deliver(&registered, &event, &log).await?);
+        }
+        Ok(report)
+    }
+
+    /// 在独立任务中调用插件并记录结果
+    async fn deliver(
+        &self,
+        registered: &Arc<RegisteredPlugin>,
+        event: &PluginEvent,
+        log: &event_publish_log::Model,
+    ) -> Result<PluginDelivery> {
+        let outcome = invoke_isolated(registered.plugin.clone(), event.clone(), self.limits.timeout).await;
+        registered.record(&outcome, self.limits.max_consecutive_failures);
+
+        let logs = EventPublishLogRepository::new(self.db.clone());
+        let (delivered, error) = match outcome {
+            Ok(response) => {
+                logs.record_attempt(log.log_id, "delivered", response, None).await?;
+                (true, None)
+            }
+            Err(error) => {
+                logs.record_attempt(log.log_id, "failed", None, Some(error.clone())).await?;
+                tracing::warn!("插件 {} 处理事件 {} 失败: {}", log.subscriber_id, event.event_id, error);
+                (false, Some(error))
+            }
+        };
+        Ok(PluginDelivery {
+            event_id: event.event_id,
+            plugin: log.subscriber_id.clone(),
+            log_id: log.log_id,
+            delivered,
+            error,
+        })
+    }
+}
+
+/// 在独立任务中调用插件，把错误、超时和 panic 都转换为失败原因
+async fn invoke_isolated(
+    plugin: Arc<dyn EventPlugin>,
+    event: PluginEvent,
+    timeout: Duration,
+) -> std::result::Result<Option<JsonValue>, String> {
+    let task = tokio::spawn(async move { plugin.handle(&event).await });
+    let abort = task.abort_handle();
+    match tokio::time::timeout(timeout, task).await {
+        Ok(Ok(Ok(response))) => Ok(response),
+        Ok(Ok(Err(e))) => Err(e.to_string()),
+        Ok(Err(e)) if e.is_panic() => Err("插件处理时发生 panic".to_string()),
+        Ok(Err(e)) => Err(format!("插件任务异常结束: {}", e)),
+        Err(_) => {
+            abort.abort();
+            Err(format!("插件处理超时（{} 毫秒）", timeout.as_millis()))
+        }
+    }
+}
+
+/// 命令插件清单
+///
+/// ```toml
+/// name = "internal-audit"
+/// events = ["TaskCompleted", "SlaBreached"]
+/// command = "/usr/local/bin/audit-hook"
+/// args = ["--env", "prod"]
+///
+/// [env]
+/// AUDIT_ENDPOINT = "https://audit.internal"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    /// 订阅的事件类型，`"*"` 表示全部
+    pub events: Vec<String>,
+    /// 可执行文件，相对路径按清单所在目录解析
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// 额外传给命令的环境变量
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// 命令的工作目录，未指定时为清单所在目录
+    #[serde(default)]
+    pub working_dir: Option<PathBuf>,
+}
+
+/// 读取目录中的所有插件清单（`*.toml`），按文件名排序
+pub fn load_manifests(dir: &Path) -> Result<Vec<PluginManifest>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|extension| extension == "toml"))
+        .collect();
+    paths.sort();
+
+    let mut manifests = Vec::with_capacity(paths.len());
+    for path in paths {
+        let content = std::fs::read_to_string(&path)?;
+        let mut manifest: PluginManifest = toml::from_str(&content)
+            .map_err(|e| DatabaseError::validation(format!("插件清单 {} 无效: {}", path.display(), e)))?;
+        let base = path.parent().unwrap_or(dir);
+        let command = Path::new(&manifest.command);
+        if command.components().count() > 1 && command.is_relative() {
+            manifest.command = base.join(command).display().to_string();
+        }
+        manifest.working_dir = Some(match manifest.working_dir.take() {
+            Some(working_dir) if working_dir.is_relative() => base.join(working_dir),
+            Some(working_dir) => working_dir,
+            None => base.to_path_buf(),
+        });
+        manifests.push(manifest);
+    }
+    Ok(manifests)
+}
+
+/// 以子进程方式运行的插件
+///
+/// 事件以 JSON 写入命令的标准输入；命令以零状态退出视为成功，标准输出若是 JSON 则作为响应数据。
+/// 超时后子进程被终止。
+#[derive(Debug, Clone)]
+pub struct CommandPlugin {
+    manifest: PluginManifest,
+}
+
+impl CommandPlugin {
+    /// 从清单创建插件
+    pub fn new(manifest: PluginManifest) -> Self {
+        Self { manifest }
+    }
+
+    async fn run(&self, event: &PluginEvent) -> Result<Option<JsonValue>> {
+        let mut command = Command::new(&self.manifest.command);
+        command
+            .args(&self.manifest.args)
+            .env_clear()
+            .envs(INHERITED_ENV_VARS.iter().filter_map(|key| std::env::var(key).ok().map(|value| (*key, value))))
+            .envs(&self.manifest.env)
+            .env("SKER_EVENT_TYPE", &event.event_type)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(working_dir) = &self.manifest.working_dir {
+            command.current_dir(working_dir);
+        }
+
+        let mut child = command.spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(&serde_json::to_vec(event)?).await?;
+        }
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            return Err(DatabaseError::business_logic(format!(
+                "命令退出状态 {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(serde_json::from_slice(&output.stdout).ok())
+    }
+}
+
+impl EventPlugin for CommandPlugin {
+    fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        self.manifest.events.clone()
+    }
+
+    fn handle<'a>(&'a self, event: &'a PluginEvent) -> PluginFuture<'a> {
+        Box::pin(self.run(event))
+    }
+}
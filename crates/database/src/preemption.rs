@@ -0,0 +1,367 @@
+//! 紧急任务抢占
+//!
+//! 紧急（critical）任务到达而所有能胜任的Agent都在忙时，默认只能排队等待。
+//! 项目可以配置 [`PreemptionPolicy`]（存放在 `projects.preemption_policy`），
+//! [`preempt_for_critical_task`] 会从同一项目运行中的任务里挑选一个可抢占的任务
+//! （优先级最低、进度最早），为其执行会话写入检查点、把任务放回待处理队列，
+//! 再把紧急任务分配给让出的Agent，并记录 [`TaskPreemptedEvent`]。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        agent::{self, AgentStatus},
+        domain_event::{AggregateType, DomainEventType},
+        execution_log::EventType,
+        execution_session, project, task,
+    },
+    repository::{
+        domain_event_repository::CreateDomainEventData, execution_log_repository::CreateExecutionLogData,
+        AgentRepository, DomainEventRepository, ExecutionLogRepository, ExecutionSessionRepository, TaskRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+use codex_multi_agent::{Clock, EventFactory, TaskPreemptedEvent, TaskPriority};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+/// 紧急任务抢占策略
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreemptionPolicy {
+    /// 是否启用
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 可以被抢占的任务优先级
+    #[serde(default = "default_preemptible_priorities")]
+    pub preemptible_priorities: Vec<String>,
+    /// 完成度超过该值（0.0-1.0）的任务不会被抢占，避免丢弃大量已完成的工作
+    #[serde(default = "default_max_completion_percentage")]
+    pub max_completion_percentage: f32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_preemptible_priorities() -> Vec<String> {
+    vec!["low".to_string()]
+}
+
+fn default_max_completion_percentage() -> f32 {
+    0.3
+}
+
+impl Default for PreemptionPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            preemptible_priorities: default_preemptible_priorities(),
+            max_completion_percentage: default_max_completion_percentage(),
+        }
+    }
+}
+
+impl PreemptionPolicy {
+    /// 校验策略
+    pub fn validate(&self) -> Result<()> {
+        if self.preemptible_priorities.is_empty() {
+            return Err(DatabaseError::validation("至少需要一个可抢占的优先级"));
+        }
+        for priority in &self.preemptible_priorities {
+            match parse_priority(priority) {
+                Some(TaskPriority::Critical) => {
+                    return Err(DatabaseError::validation("紧急任务不能被抢占"));
+                }
+                Some(_) => {}
+                None => return Err(DatabaseError::validation(format!("未知的任务优先级: {}", priority))),
+            }
+        }
+        if !(0.0..=1.0).contains(&self.max_completion_percentage) {
+            return Err(DatabaseError::validation("可抢占的最大完成度必须在0到1之间"));
+        }
+        Ok(())
+    }
+
+    /// 任务优先级是否允许被抢占
+    fn allows(&self, priority: &str) -> bool {
+        self.preemptible_priorities.iter().any(|allowed| allowed == priority)
+    }
+}
+
+/// 可被抢占的运行中任务
+#[derive(Debug, Clone)]
+pub struct PreemptionCandidate {
+    /// 运行中的执行会话
+    pub session: execution_session::Model,
+    /// 会话执行的任务
+    pub task: task::Model,
+    /// 估算的完成度（0.0-1.0）
+    pub completion_percentage: f32,
+    /// 已执行时间（分钟）
+    pub elapsed_minutes: u32,
+}
+
+/// 读取项目的抢占策略，未配置或未启用时返回 None
+pub async fn project_policy(db: &DatabaseConnection, project_id: Uuid) -> Result<Option<PreemptionPolicy>> {
+    let project = project::Entity::find_by_id(project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+
+    let policy = match project.preemption_policy {
+        Some(value) => Some(serde_json::from_value::<PreemptionPolicy>(value)?),
+        None => None,
+    };
+    Ok(policy.filter(|policy| policy.enabled))
+}
+
+/// 为紧急任务挑选可抢占的运行中任务
+///
+/// 只考虑同一项目内、Agent具备紧急任务全部所需能力且优先级和完成度符合策略的任务，
+/// 优先选择优先级最低的，同优先级时选择完成度最低的。已执行时间按 `clock` 的当前时间计算。
+pub async fn select_preemptible_task(
+    db: &DatabaseConnection,
+    policy: &PreemptionPolicy,
+    critical_task: &task::Model,
+    clock: &dyn Clock,
+) -> Result<Option<PreemptionCandidate>> {
+    let now = clock.now();
+    let required = string_list(critical_task.required_capabilities.as_ref());
+    let sessions = ExecutionSessionRepository::new(db.clone()).find_running_sessions().await?;
+    let agents = AgentRepository::new(db.clone());
+
+    let mut best: Option<(TaskPriority, PreemptionCandidate)> = None;
+    for session in sessions {
+        if session.project_id != critical_task.project_id || session.task_id == critical_task.task_id {
+            continue;
+        }
+        let Some(task) = task::Entity::find_by_id(session.task_id).one(db).await? else {
+            continue;
+        };
+        if task.status != "in_progress" || !policy.allows(&task.priority) {
+            continue;
+        }
+        let Some(agent) = agents.find_by_id(session.agent_id).await? else {
+            continue;
+        };
+        if !agent_covers(&agent, &required) {
+            continue;
+        }
+
+        let (completion_percentage, elapsed_minutes) = estimate_progress(db, &session, &task, now).await?;
+        if completion_percentage > policy.max_completion_percentage {
+            continue;
+        }
+
+        let priority = parse_priority(&task.priority).unwrap_or(TaskPriority::Medium);
+        let better = match &best {
+            None => true,
+            Some((best_priority, best_candidate)) => {
+                priority < *best_priority
+                    || (priority == *best_priority && completion_percentage < best_candidate.completion_percentage)
+            }
+        };
+        if better {
+            best = Some((priority, PreemptionCandidate { session, task, completion_percentage, elapsed_minutes }));
+        }
+    }
+
+    Ok(best.map(|(_, candidate)| candidate))
+}
+
+/// 为紧急任务执行抢占
+///
+/// 项目未启用抢占、紧急任务已分配、存在空闲的可胜任Agent或没有可抢占的任务时返回 None。
+/// 抢占成功时被抢占的执行会话写入检查点并以失败结束，任务回到待处理状态，
+/// 紧急任务分配给让出的Agent，返回已持久化的抢占事件。
+/// 检查点、任务更新时间和事件时间戳都取自 `clock`。
+pub async fn preempt_for_critical_task(
+    db: &DatabaseConnection,
+    critical_task_id: Uuid,
+    clock: &dyn Clock,
+) -> Result<Option<TaskPreemptedEvent>> {
+    let tasks = TaskRepository::new(db.clone());
+    let critical = tasks
+        .find_by_id(critical_task_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Task", critical_task_id))?;
+    if parse_priority(&critical.priority) != Some(TaskPriority::Critical) {
+        return Err(DatabaseError::validation("只有紧急任务可以抢占运行中的任务"));
+    }
+    if critical.status != "pending" || critical.assigned_agent_id.is_some() {
+        return Ok(None);
+    }
+
+    let Some(policy) = project_policy(db, critical.project_id).await? else {
+        return Ok(None);
+    };
+
+    let agents = AgentRepository::new(db.clone());
+    let required = string_list(critical.required_capabilities.as_ref());
+    if agents.find_idle_agents().await?.iter().any(|agent| agent_covers(agent, &required)) {
+        return Ok(None);
+    }
+
+    let Some(candidate) = select_preemptible_task(db, &policy, &critical, clock).await? else {
+        return Ok(None);
+    };
+    let now = clock.now();
+    let session = &candidate.session;
+    let victim = &candidate.task;
+    let reason = format!("紧急任务 {} 没有空闲Agent可用", critical.title);
+
+    // 写入检查点，保留被抢占任务的分支和进度，重新执行时可以从这里继续
+    ExecutionLogRepository::new(db.clone())
+        .create(CreateExecutionLogData {
+            session_id: session.session_id,
+            log_level: "warn".to_string(),
+            event_type: EventType::Progress,
+            message: format!("任务被紧急任务 {} 抢占，执行会话已中断", critical.title),
+            details: Some(json!({
+                "checkpoint": true,
+                "reason": "preempted",
+                "preempted_by": critical.task_id,
+                "completion_percentage": candidate.completion_percentage,
+                "git_branch": session.git_branch,
+                "base_commit": session.base_commit,
+            })),
+            timestamp_ms: now.timestamp_millis(),
+        })
+        .await?;
+
+    ExecutionSessionRepository::new(db.clone())
+        .complete_session(
+            session.session_id,
+            false,
+            None,
+            Some(json!({ "preempted_by": critical.task_id })),
+            Some(format!("被紧急任务 {} 抢占", critical.task_id)),
+        )
+        .await?;
+    requeue_task(db, victim.task_id, now).await?;
+
+    let assignment_prompt = critical
+        .assignment_prompt
+        .clone()
+        .unwrap_or_else(|| critical.description.clone());
+    tasks.assign_to_agent(critical.task_id, session.agent_id, assignment_prompt).await?;
+    agents
+        .update_status(session.agent_id, AgentStatus::Working, Some(critical.task_id))
+        .await?;
+
+    let mut event = EventFactory::task_preempted(
+        victim.task_id.into(),
+        session.session_id.into(),
+        parse_priority(&victim.priority).unwrap_or(TaskPriority::Medium),
+        candidate.completion_percentage,
+        candidate.elapsed_minutes,
+        critical.task_id.into(),
+        session.agent_id.into(),
+        reason,
+    );
+    event.metadata = event.metadata.with_timestamp(now);
+    let event_repo = DomainEventRepository::new(db.clone());
+    let event_version = event_repo.get_latest_version(victim.task_id).await? + 1;
+    event_repo
+        .create_correlated(
+            CreateDomainEventData {
+                aggregate_type: AggregateType::Task.to_string(),
+                aggregate_id: victim.task_id,
+                event_type: DomainEventType::TaskPreempted.to_string(),
+                event_data: serde_json::to_value(&event)?,
+                event_version,
+            },
+            critical.task_id,
+        )
+        .await?;
+
+    tracing::info!(
+        "紧急任务 {} 抢占了任务 {}（Agent {}）",
+        critical.task_id,
+        victim.task_id,
+        session.agent_id
+    );
+    Ok(Some(event))
+}
+
+/// 把被抢占的任务放回待处理队列，清除分配和租约
+async fn requeue_task(db: &DatabaseConnection, task_id: Uuid, now: DateTime<Utc>) -> Result<()> {
+    task::Entity::update_many()
+        .col_expr(task::Column::Status, Expr::value("pending"))
+        .col_expr(task::Column::AssignedAgentId, Expr::value(Option::<Uuid>::None))
+        .col_expr(task::Column::AssignedAt, Expr::value(Option::<DateTimeWithTimeZone>::None))
+        .col_expr(task::Column::LeaseOwner, Expr::value(Option::<String>::None))
+        .col_expr(task::Column::LeaseExpiresAt, Expr::value(Option::<DateTimeWithTimeZone>::None))
+        .col_expr(task::Column::UpdatedAt, Expr::value(DateTimeWithTimeZone::from(now)))
+        .filter(task::Column::TaskId.eq(task_id))
+        .filter(task::Column::Status.eq("in_progress"))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// 估算运行中任务的完成度和已执行时间
+///
+/// 优先使用最近一条进度日志上报的 `completion_percentage`，
+/// 没有上报时按已执行时间占预估工时（或会话超时时间）的比例估算。
+async fn estimate_progress(
+    db: &DatabaseConnection,
+    session: &execution_session::Model,
+    task: &task::Model,
+    now: DateTime<Utc>,
+) -> Result<(f32, u32)> {
+    let started_at = session.started_at.unwrap_or(session.created_at);
+    let elapsed_minutes = (now - started_at.with_timezone(&Utc)).num_minutes().max(0) as u32;
+
+    let progress_event = EventType::Progress.to_string();
+    let reported = ExecutionLogRepository::new(db.clone())
+        .find_by_session_id(session.session_id)
+        .await?
+        .into_iter()
+        .rev()
+        .filter(|log| log.event_type == progress_event)
+        .find_map(|log| log.details.as_ref()?.get("completion_percentage")?.as_f64());
+
+    let completion = match reported {
+        Some(value) => value as f32,
+        None => {
+            let budget_minutes = task
+                .estimated_hours
+                .filter(|hours| *hours > 0)
+                .map(|hours| hours * 60)
+                .unwrap_or(session.timeout_minutes)
+                .max(1);
+            elapsed_minutes as f32 / budget_minutes as f32
+        }
+    };
+    Ok((completion.clamp(0.0, 1.0), elapsed_minutes))
+}
+
+/// 解析数据库中的任务优先级，兼容旧的 urgent
+fn parse_priority(priority: &str) -> Option<TaskPriority> {
+    match priority {
+        "low" => Some(TaskPriority::Low),
+        "medium" => Some(TaskPriority::Medium),
+        "high" => Some(TaskPriority::High),
+        "critical" | "urgent" => Some(TaskPriority::Critical),
+        _ => None,
+    }
+}
+
+/// 解析JSON字符串数组
+fn string_list(value: Option<&serde_json::Value>) -> Vec<String> {
+    value
+        .and_then(|value| value.as_array())
+        .map(|items| items.iter().filter_map(|item| item.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+/// Agent是否具备全部所需能力
+fn agent_covers(agent: &agent::Model, required: &[String]) -> bool {
+    let capabilities = string_list(Some(&agent.capabilities));
+    required.iter().all(|capability| capabilities.contains(capability))
+}
@@ -0,0 +1,356 @@
+//! 从现有 Git 仓库导入项目
+//!
+//! [`bootstrap_project`] 接受本地仓库路径或远程地址（远程仓库先克隆到指定的工作空间），
+//! 自动识别项目名称、主分支、远程地址和技术栈（通过 [`CodebaseScanner`]），按检测到的语言
+//! 和仓库中已有的 lint/格式化配置生成建议的编码规范，然后一次性创建项目，
+//! 并通过 [`ContextBuilder`] 采集初始的代码库信息快照。
+//!
+//! [`detect_repository`] 只做检测不创建项目，可用于导入前预览。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::{
+    context::{CodebaseScan, CodebaseScanner, ContextBuilder},
+    entities::project,
+    repository::{project_repository::CreateProjectData, ProjectRepository},
+    DatabaseConnection, DatabaseError, Result,
+};
+use codex_multi_agent::{
+    project_management::{IndentType, IndentationConfig, LanguageConfig, NamingConventions, NamingStyle},
+    CodebaseInfo, CodingStandards,
+};
+
+/// 检测不到主分支时使用的默认值
+const DEFAULT_MAIN_BRANCH: &str = "main";
+
+/// 计入技术栈的语言最低代码占比（百分比）
+const MIN_LANGUAGE_PERCENTAGE: f32 = 5.0;
+
+/// 仓库根目录下的 lint/格式化配置文件：(适用语言, 文件名, 是否为 linter 配置)
+const KNOWN_CONFIG_FILES: &[(&[&str], &str, bool)] = &[
+    (&["Rust"], "rustfmt.toml", false),
+    (&["Rust"], ".rustfmt.toml", false),
+    (&["Rust"], "clippy.toml", true),
+    (&["Rust"], ".clippy.toml", true),
+    (&["TypeScript", "JavaScript", "Vue", "Svelte"], ".prettierrc", false),
+    (&["TypeScript", "JavaScript", "Vue", "Svelte"], ".prettierrc.json", false),
+    (&["TypeScript", "JavaScript", "Vue", "Svelte"], "prettier.config.js", false),
+    (&["TypeScript", "JavaScript", "Vue", "Svelte"], "eslint.config.js", true),
+    (&["TypeScript", "JavaScript", "Vue", "Svelte"], "eslint.config.mjs", true),
+    (&["TypeScript", "JavaScript", "Vue", "Svelte"], ".eslintrc.json", true),
+    (&["TypeScript", "JavaScript", "Vue", "Svelte"], ".eslintrc.js", true),
+    (&["Python"], "ruff.toml", true),
+    (&["Python"], ".flake8", true),
+    (&["Python"], "pyproject.toml", false),
+    (&["Go"], ".golangci.yml", true),
+    (&["Go"], ".golangci.yaml", true),
+];
+
+/// 导入请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BootstrapRequest {
+    /// 本地仓库路径或远程仓库地址
+    pub source: String,
+    /// 工作空间路径：远程仓库克隆到该目录（必填），本地仓库为空时使用仓库路径
+    #[serde(default)]
+    pub workspace_path: Option<String>,
+    /// 项目名称，为空时使用仓库目录名
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 项目描述
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// 仓库检测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryDetection {
+    /// 建议的项目名称
+    pub name: String,
+    /// 远程地址（已去除其中的凭据），没有远程时为本地路径
+    pub repository_url: String,
+    /// 工作空间路径
+    pub workspace_path: String,
+    /// 主分支
+    pub main_branch: String,
+    /// 当前HEAD提交
+    pub head_commit: Option<String>,
+    /// 技术栈：主要语言和识别出的框架
+    pub technology_stack: Vec<String>,
+    /// 建议的编码规范
+    pub coding_standards: CodingStandards,
+    /// 代码库扫描结果
+    pub scan: CodebaseScan,
+}
+
+/// 导入结果
+#[derive(Debug, Clone)]
+pub struct BootstrapResult {
+    pub project: project::Model,
+    pub detection: RepositoryDetection,
+    /// 初始的代码库信息快照
+    pub codebase_info: CodebaseInfo,
+}
+
+/// 是否为远程仓库地址
+pub fn is_remote_source(source: &str) -> bool {
+    let source = source.trim();
+    ["http://", "https://", "ssh://", "git://", "file://"]
+        .iter()
+        .any(|scheme| source.starts_with(scheme))
+        || (source.starts_with("git@") && source.contains(':'))
+}
+
+/// 从现有仓库导入项目
+pub async fn bootstrap_project(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    request: BootstrapRequest,
+) -> Result<BootstrapResult> {
+    let workspace = prepare_workspace(&request).await?;
+    let scanner = Arc::new(CodebaseScanner::new());
+    let mut detection = detect_repository_with(&workspace, &scanner).await?;
+    if let Some(name) = request.name.as_deref().map(str::trim).filter(|name| !name.is_empty()) {
+        detection.name = name.to_string();
+    }
+
+    let projects = ProjectRepository::new(db.clone());
+    let project = projects
+        .create(CreateProjectData {
+            user_id,
+            name: detection.name.clone(),
+            description: request.description.clone(),
+            repository_url: detection.repository_url.clone(),
+            workspace_path: detection.workspace_path.clone(),
+        })
+        .await?;
+    projects.update_main_branch(project.project_id, &detection.main_branch).await?;
+    projects
+        .update_config(
+            project.project_id,
+            Some(serde_json::to_value(&detection.technology_stack)?),
+            Some(serde_json::to_value(&detection.coding_standards)?),
+            None,
+        )
+        .await?;
+
+    // 共享扫描器，上下文构建直接复用刚才的扫描结果并持久化快照
+    let context = ContextBuilder::new(db.clone())
+        .with_scanner(scanner)
+        .build(project.project_id)
+        .await?;
+    let project = projects
+        .find_by_id(project.project_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", project.project_id))?;
+
+    Ok(BootstrapResult {
+        project,
+        detection,
+        codebase_info: context.codebase_info,
+    })
+}
+
+/// 检测本地仓库，不创建项目
+pub async fn detect_repository(workspace: &Path) -> Result<RepositoryDetection> {
+    detect_repository_with(workspace, &CodebaseScanner::new()).await
+}
+
+async fn detect_repository_with(workspace: &Path, scanner: &CodebaseScanner) -> Result<RepositoryDetection> {
+    if !workspace.is_dir() {
+        return Err(DatabaseError::validation(format!("仓库目录不存在: {}", workspace.display())));
+    }
+    if git(workspace, &["rev-parse", "--is-inside-work-tree"]).await.as_deref() != Some("true") {
+        return Err(DatabaseError::validation(format!("不是Git仓库: {}", workspace.display())));
+    }
+    let workspace = workspace.canonicalize()?;
+
+    let name = workspace
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "imported-project".to_string());
+    let repository_url = match git(&workspace, &["remote", "get-url", "origin"]).await {
+        Some(url) => strip_credentials(&url),
+        None => workspace.to_string_lossy().to_string(),
+    };
+    let main_branch = detect_main_branch(&workspace).await;
+
+    let scan = scanner.scan(&workspace).await?;
+    let technology_stack = propose_technology_stack(&scan);
+    let coding_standards = propose_coding_standards(&scan, &main_branch, &workspace);
+
+    Ok(RepositoryDetection {
+        name,
+        repository_url,
+        workspace_path: workspace.to_string_lossy().to_string(),
+        main_branch,
+        head_commit: scan.commit_hash.clone(),
+        technology_stack,
+        coding_standards,
+        scan,
+    })
+}
+
+/// 技术栈：代码占比达到阈值的语言，加上识别出的框架
+pub fn propose_technology_stack(scan: &CodebaseScan) -> Vec<String> {
+    let mut stack: Vec<String> = scan
+        .languages
+        .iter()
+        .filter(|language| language.percentage >= MIN_LANGUAGE_PERCENTAGE)
+        .map(|language| language.language.clone())
+        .collect();
+    for framework in &scan.frameworks {
+        if !stack.contains(&framework.name) {
+            stack.push(framework.name.clone());
+        }
+    }
+    stack
+}
+
+/// 在默认编码规范的基础上，为检测到的语言补充惯用的缩进、行宽和命名规范，
+/// 并引用仓库中已有的 lint/格式化配置
+pub fn propose_coding_standards(scan: &CodebaseScan, main_branch: &str, workspace: &Path) -> CodingStandards {
+    let mut standards = CodingStandards::default();
+    standards.branching_strategy.main_branch = main_branch.to_string();
+    standards.branching_strategy.protected_branches = vec![main_branch.to_string()];
+
+    let mut language_configs = HashMap::new();
+    for stats in scan
+        .languages
+        .iter()
+        .filter(|language| language.percentage >= MIN_LANGUAGE_PERCENTAGE)
+    {
+        let Some(mut config) = language_defaults(&stats.language) else {
+            continue;
+        };
+        for (languages, file, is_linter) in KNOWN_CONFIG_FILES {
+            if !languages.contains(&stats.language.as_str()) || !workspace.join(file).is_file() {
+                continue;
+            }
+            let slot = if *is_linter { &mut config.linter_config } else { &mut config.formatter_config };
+            slot.get_or_insert_with(|| file.to_string());
+        }
+        language_configs.insert(stats.language.clone(), config);
+    }
+    standards.language_configs = language_configs;
+    standards
+}
+
+/// 各语言社区惯用的默认规范
+fn language_defaults(language: &str) -> Option<LanguageConfig> {
+    use NamingStyle::*;
+
+    let (indent_type, indent_size, max_line_length, naming) = match language {
+        "Rust" => (IndentType::Spaces, 4, 100, [SnakeCase, SnakeCase, PascalCase, ScreamingSnakeCase, SnakeCase]),
+        "TypeScript" | "JavaScript" | "Vue" | "Svelte" => {
+            (IndentType::Spaces, 2, 100, [CamelCase, CamelCase, PascalCase, ScreamingSnakeCase, KebabCase])
+        }
+        "Python" => (IndentType::Spaces, 4, 88, [SnakeCase, SnakeCase, PascalCase, ScreamingSnakeCase, SnakeCase]),
+        "Go" => (IndentType::Tabs, 4, 120, [CamelCase, CamelCase, PascalCase, PascalCase, SnakeCase]),
+        "Java" | "Kotlin" | "C#" => {
+            (IndentType::Spaces, 4, 120, [CamelCase, CamelCase, PascalCase, ScreamingSnakeCase, PascalCase])
+        }
+        _ => return None,
+    };
+    let [variables, functions, classes, constants, files] = naming;
+
+    Some(LanguageConfig {
+        language: language.to_string(),
+        linter_config: None,
+        linter_command: None,
+        formatter_config: None,
+        style_guide_url: None,
+        max_line_length: Some(max_line_length),
+        indentation: IndentationConfig { indent_type, indent_size },
+        naming_conventions: NamingConventions {
+            variables,
+            functions,
+            classes,
+            constants,
+            files,
+        },
+        enforce_rules: true,
+    })
+}
+
+/// 准备工作空间：远程仓库克隆到指定目录，本地仓库直接使用
+async fn prepare_workspace(request: &BootstrapRequest) -> Result<PathBuf> {
+    let source = request.source.trim();
+    if source.is_empty() {
+        return Err(DatabaseError::validation("仓库路径或地址不能为空"));
+    }
+    let workspace_path = request
+        .workspace_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty());
+
+    if !is_remote_source(source) {
+        return Ok(PathBuf::from(workspace_path.unwrap_or(source)));
+    }
+
+    let workspace = PathBuf::from(
+        workspace_path.ok_or_else(|| DatabaseError::validation("从远程仓库导入时必须指定工作空间路径"))?,
+    );
+    if workspace.exists() && std::fs::read_dir(&workspace)?.next().is_some() {
+        return Err(DatabaseError::validation(format!("工作空间目录不为空: {}", workspace.display())));
+    }
+    if let Some(parent) = workspace.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let output = Command::new("git")
+        .arg("clone")
+        .arg("--")
+        .arg(source)
+        .arg(&workspace)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(DatabaseError::business_logic(format!(
+            "克隆仓库失败 {}: {}",
+            strip_credentials(source),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(workspace)
+}
+
+/// 主分支：优先使用远程的默认分支，其次是当前分支
+async fn detect_main_branch(workspace: &Path) -> String {
+    if let Some(remote_head) = git(workspace, &["symbolic-ref", "--quiet", "--short", "refs/remotes/origin/HEAD"]).await {
+        if let Some(branch) = remote_head.strip_prefix("origin/") {
+            return branch.to_string();
+        }
+    }
+    git(workspace, &["symbolic-ref", "--quiet", "--short", "HEAD"])
+        .await
+        .unwrap_or_else(|| DEFAULT_MAIN_BRANCH.to_string())
+}
+
+/// 去除地址中的用户名和密码，避免把访问令牌写入数据库
+fn strip_credentials(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    match rest[..authority_end].rfind('@') {
+        Some(at) => format!("{}://{}", scheme, &rest[at + 1..]),
+        None => url.to_string(),
+    }
+}
+
+/// 运行 git 命令并返回去除首尾空白的输出，失败或输出为空时返回 `None`
+async fn git(workspace: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).current_dir(workspace).output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
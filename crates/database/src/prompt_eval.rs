@@ -0,0 +1,589 @@
+//! 提示词与模型的 A/B 评估
+//!
+//! 调整分解提示词模板或更换模型时，需要知道效果是变好还是变差。评估集（[`EvalSet`]）由一组用例组成，
+//! 每个用例包含填入模板的输入和输出应满足的属性（[`ExpectedProperty`]）。[`EvalHarness::run`]
+//! 对每个变体（[`EvalVariant`]，模板 × 模型）逐个用例调用LLM：
+//! - 规则属性（包含、正则、JSON、任务数、长度）直接检查；
+//! - [`ExpectedProperty::Judge`] 交给可选的LLM裁判判定，未配置裁判时跳过，不计入得分；
+//! - 每个用例的得分为通过的属性占已检查属性的比例，结果逐条保存在 `eval_results` 表；
+//! - 报告（[`EvalRunReport`]）汇总各变体的平均得分和通过率，并以第一个变体为基线列出其余变体的变化，
+//!   包括退步和改进的用例。
+//!
+//! 评估调用经 [`RecordingProvider`] 保存调用记录，调用类型为 [`LlmCallKind::Evaluation`]。
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::time::Instant;
+use uuid::Uuid;
+
+use crate::{
+    entities::{eval_result, eval_run, eval_set},
+    llm_provider::{LlmProvider, LlmRequest},
+    llm_transcripts::{LlmCallKind, RecordingProvider, TranscriptLink},
+    repository::{
+        eval_result_repository::CreateEvalResultData, eval_run_repository::CreateEvalRunData,
+        eval_set_repository::SaveEvalSetData, EvalResultRepository, EvalRunRepository, EvalSetRepository,
+    },
+    structured_output::{extract_json, partial_task_titles},
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 用例通过所需的最低得分
+const PASS_SCORE: f64 = 1.0;
+
+/// 得分变化小于该值时不视为退步或改进
+const SCORE_TOLERANCE: f64 = 1e-6;
+
+/// 输出应满足的属性
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExpectedProperty {
+    /// 包含指定文本
+    Contains { text: String },
+    /// 不包含指定文本
+    NotContains { text: String },
+    /// 匹配正则表达式
+    Matches { pattern: String },
+    /// 包含可解析的JSON对象
+    JsonObject,
+    /// 至少识别出指定数量的任务
+    MinTasks { count: usize },
+    /// 不超过指定字符数
+    MaxChars { count: usize },
+    /// 由LLM裁判按标准判定
+    Judge { criterion: String },
+}
+
+impl ExpectedProperty {
+    /// 属性的简短说明，用于报告
+    pub fn describe(&self) -> String {
+        match self {
+            ExpectedProperty::Contains { text } => format!("包含「{}」", text),
+            ExpectedProperty::NotContains { text } => format!("不包含「{}」", text),
+            ExpectedProperty::Matches { pattern } => format!("匹配 /{}/", pattern),
+            ExpectedProperty::JsonObject => "包含JSON对象".to_string(),
+            ExpectedProperty::MinTasks { count } => format!("至少 {} 个任务", count),
+            ExpectedProperty::MaxChars { count } => format!("不超过 {} 个字符", count),
+            ExpectedProperty::Judge { criterion } => format!("裁判：{}", criterion),
+        }
+    }
+}
+
+/// 评估用例
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalCase {
+    /// 用例名称，在评估集内唯一
+    pub name: String,
+    /// 填入模板 `{{名称}}` 占位符的输入
+    #[serde(default)]
+    pub inputs: BTreeMap<String, String>,
+    /// 输出应满足的属性
+    pub expected: Vec<ExpectedProperty>,
+}
+
+/// 评估集
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalSet {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub cases: Vec<EvalCase>,
+}
+
+impl EvalSet {
+    /// 验证评估集：至少一个用例，用例名称唯一，正则表达式有效
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(DatabaseError::validation("评估集名称不能为空"));
+        }
+        if self.cases.is_empty() {
+            return Err(DatabaseError::validation("评估集至少需要一个用例"));
+        }
+        let mut names = HashSet::new();
+        for case in &self.cases {
+            if !names.insert(case.name.as_str()) {
+                return Err(DatabaseError::validation(format!("评估用例名称重复: {}", case.name)));
+            }
+            if case.expected.is_empty() {
+                return Err(DatabaseError::validation(format!("评估用例 {} 没有期望属性", case.name)));
+            }
+            for property in &case.expected {
+                if let ExpectedProperty::Matches { pattern } = property {
+                    Regex::new(pattern)
+                        .map_err(|e| DatabaseError::validation(format!("用例 {} 的正则表达式无效: {}", case.name, e)))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 评估变体：提示词模板 × 模型
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalVariant {
+    /// 变体名称，在一次评估内唯一
+    pub name: String,
+    /// 提示词模板，`{{名称}}` 替换为用例输入
+    pub template: String,
+    pub model: String,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+impl EvalVariant {
+    /// 用用例输入填充模板，模板引用了用例没有的输入时返回验证错误
+    pub fn render(&self, case: &EvalCase) -> Result<String> {
+        let mut prompt = String::with_capacity(self.template.len());
+        let mut rest = self.template.as_str();
+        while let Some(start) = rest.find("{{") {
+            prompt.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let end = after
+                .find("}}")
+                .ok_or_else(|| DatabaseError::validation(format!("变体 {} 的模板中存在未闭合的占位符", self.name)))?;
+            let name = after[..end].trim();
+            let value = case.inputs.get(name).ok_or_else(|| {
+                DatabaseError::validation(format!("用例 {} 缺少变体 {} 模板需要的输入: {}", case.name, self.name, name))
+            })?;
+            prompt.push_str(value);
+            rest = &after[end + 2..];
+        }
+        prompt.push_str(rest);
+        Ok(prompt)
+    }
+}
+
+/// 单个属性的检查结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyCheck {
+    pub property: ExpectedProperty,
+    /// 是否满足，跳过的检查为空
+    pub passed: Option<bool>,
+    /// 失败原因或裁判理由
+    pub detail: Option<String>,
+}
+
+/// 变体在一个用例上的结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalCaseResult {
+    pub case_name: String,
+    pub score: f64,
+    pub passed: bool,
+    /// 调用失败的错误信息
+    pub error_message: Option<String>,
+    pub checks: Vec<PropertyCheck>,
+}
+
+/// 变体汇总
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariantSummary {
+    pub variant: String,
+    pub model: String,
+    /// 平均得分，0 到 1
+    pub mean_score: f64,
+    /// 通过的用例比例
+    pub pass_rate: f64,
+    pub cases_passed: u32,
+    pub total_tokens: u64,
+    pub mean_latency_ms: f64,
+    pub cases: Vec<EvalCaseResult>,
+}
+
+/// 变体相对基线的变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariantDelta {
+    pub variant: String,
+    pub baseline: String,
+    pub score_delta: f64,
+    pub pass_rate_delta: f64,
+    /// 得分低于基线的用例
+    pub regressions: Vec<String>,
+    /// 得分高于基线的用例
+    pub improvements: Vec<String>,
+}
+
+/// 评估报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalRunReport {
+    pub run_id: Uuid,
+    pub eval_set_id: Uuid,
+    /// 按运行顺序排列，第一个为基线
+    pub variants: Vec<VariantSummary>,
+    pub deltas: Vec<VariantDelta>,
+    /// 评估时是否配置了LLM裁判
+    pub judged: bool,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+}
+
+/// 保存评估集，`eval_set_id` 为空时新建，否则覆盖已有的评估集
+pub async fn save_eval_set(
+    db: &DatabaseConnection,
+    eval_set_id: Option<Uuid>,
+    project_id: Option<Uuid>,
+    set: &EvalSet,
+) -> Result<eval_set::Model> {
+    set.validate()?;
+    let data = SaveEvalSetData {
+        project_id,
+        name: set.name.clone(),
+        description: set.description.clone(),
+        cases: serde_json::to_value(&set.cases)?,
+    };
+    let repo = EvalSetRepository::new(db.clone());
+    match eval_set_id {
+        Some(eval_set_id) => repo.update(eval_set_id, data).await,
+        None => repo.create(data).await,
+    }
+}
+
+/// 读取评估集定义
+pub fn decode_eval_set(model: &eval_set::Model) -> Result<EvalSet> {
+    Ok(EvalSet {
+        name: model.name.clone(),
+        description: model.description.clone(),
+        cases: serde_json::from_value(model.cases.clone())
+            .map_err(|e| DatabaseError::column_decode("EvalSet", "cases", e))?,
+    })
+}
+
+/// LLM裁判
+struct EvalJudge<'a> {
+    provider: &'a dyn LlmProvider,
+    model: String,
+}
+
+/// 评估执行器
+pub struct EvalHarness<'a> {
+    db: DatabaseConnection,
+    provider: &'a dyn LlmProvider,
+    judge: Option<EvalJudge<'a>>,
+}
+
+impl<'a> EvalHarness<'a> {
+    pub fn new(db: DatabaseConnection, provider: &'a dyn LlmProvider) -> Self {
+        Self { db, provider, judge: None }
+    }
+
+    /// 使用LLM裁判判定 [`ExpectedProperty::Judge`] 属性
+    pub fn with_judge(mut self, provider: &'a dyn LlmProvider, model: impl Into<String>) -> Self {
+        self.judge = Some(EvalJudge { provider, model: model.into() });
+        self
+    }
+
+    /// 对评估集运行所有变体，保存结果并返回报告
+    pub async fn run(&self, eval_set_id: Uuid, variants: &[EvalVariant]) -> Result<EvalRunReport> {
+        let model = EvalSetRepository::new(self.db.clone())
+            .find_by_id(eval_set_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("EvalSet", eval_set_id))?;
+        let set = decode_eval_set(&model)?;
+        validate_variants(variants, &set)?;
+
+        let run = EvalRunRepository::new(self.db.clone())
+            .create(CreateEvalRunData {
+                eval_set_id,
+                variants: serde_json::to_value(variants)?,
+                judged: self.judge.is_some(),
+            })
+            .await?;
+        let link = TranscriptLink {
+            project_id: model.project_id,
+            ..Default::default()
+        };
+        let provider = RecordingProvider::new(self.provider, self.db.clone(), LlmCallKind::Evaluation).with_link(link.clone());
+        let judge = self.judge.as_ref().map(|judge| {
+            (RecordingProvider::new(judge.provider, self.db.clone(), LlmCallKind::Evaluation).with_link(link.clone()), judge.model.as_str())
+        });
+
+        let results = EvalResultRepository::new(self.db.clone());
+        for variant in variants {
+            for case in &set.cases {
+                let prompt = variant.render(case)?;
+                let mut request = LlmRequest::new(&variant.model, &prompt);
+                request.temperature = variant.temperature;
+                request.max_tokens = variant.max_tokens;
+
+                let started = Instant::now();
+                let completion = provider.complete(&request).await;
+                let latency_ms = started.elapsed().as_millis().min(i64::MAX as u128) as i64;
+                let (output, error_message, tokens_used) = match completion {
+                    Ok(completion) => (Some(completion.content), None, Some(completion.tokens_used)),
+                    Err(e) => (None, Some(e.to_string()), None),
+                };
+
+                let checks = match &output {
+                    Some(output) => {
+                        let mut checks = Vec::with_capacity(case.expected.len());
+                        for property in &case.expected {
+                            checks.push(check_property(property, &prompt, output, judge.as_ref()).await);
+                        }
+                        checks
+                    }
+                    None => case
+                        .expected
+                        .iter()
+                        .map(|property| PropertyCheck {
+                            property: property.clone(),
+                            passed: Some(false),
+                            detail: Some("调用失败".to_string()),
+                        })
+                        .collect(),
+                };
+                let score = score(&checks);
+                results
+                    .create(CreateEvalResultData {
+                        run_id: run.run_id,
+                        variant_name: variant.name.clone(),
+                        case_name: case.name.clone(),
+                        model: variant.model.clone(),
+                        prompt,
+                        output,
+                        error_message,
+                        score,
+                        passed: score >= PASS_SCORE,
+                        checks: serde_json::to_value(&checks)?,
+                        tokens_used: tokens_used.map(|tokens| tokens.min(i32::MAX as u32) as i32),
+                        latency_ms,
+                    })
+                    .await?;
+            }
+        }
+
+        let report = build_report(&run, results.find_by_run(run.run_id).await?)?;
+        tracing::info!(
+            "评估集 {} 运行完成：{}",
+            set.name,
+            report
+                .variants
+                .iter()
+                .map(|v| format!("{} 得分 {:.2}", v.variant, v.mean_score))
+                .collect::<Vec<_>>()
+                .join("，")
+        );
+        Ok(report)
+    }
+}
+
+/// 读取已保存的评估报告
+pub async fn get_eval_report(db: &DatabaseConnection, run_id: Uuid) -> Result<Option<EvalRunReport>> {
+    let Some(run) = EvalRunRepository::new(db.clone()).find_by_id(run_id).await? else {
+        return Ok(None);
+    };
+    let results = EvalResultRepository::new(db.clone()).find_by_run(run_id).await?;
+    build_report(&run, results).map(Some)
+}
+
+/// 评估集的所有评估报告（按时间倒序）
+pub async fn list_eval_reports(db: &DatabaseConnection, eval_set_id: Uuid) -> Result<Vec<EvalRunReport>> {
+    let runs = EvalRunRepository::new(db.clone()).find_by_eval_set(eval_set_id).await?;
+    let results = EvalResultRepository::new(db.clone());
+    let mut reports = Vec::with_capacity(runs.len());
+    for run in runs {
+        let rows = results.find_by_run(run.run_id).await?;
+        reports.push(build_report(&run, rows)?);
+    }
+    Ok(reports)
+}
+
+fn validate_variants(variants: &[EvalVariant], set: &EvalSet) -> Result<()> {
+    if variants.is_empty() {
+        return Err(DatabaseError::validation("至少需要一个评估变体"));
+    }
+    let mut names = HashSet::new();
+    for variant in variants {
+        if !names.insert(variant.name.as_str()) {
+            return Err(DatabaseError::validation(format!("评估变体名称重复: {}", variant.name)));
+        }
+        // 运行前检查所有模板都能填充，避免评估进行到一半失败
+        for case in &set.cases {
+            variant.render(case)?;
+        }
+    }
+    Ok(())
+}
+
+async fn check_property(
+    property: &ExpectedProperty,
+    prompt: &str,
+    output: &str,
+    judge: Option<&(RecordingProvider<'_>, &str)>,
+) -> PropertyCheck {
+    let (passed, detail) = match property {
+        ExpectedProperty::Contains { text } => (Some(output.contains(text.as_str())), None),
+        ExpectedProperty::NotContains { text } => (Some(!output.contains(text.as_str())), None),
+        ExpectedProperty::Matches { pattern } => match Regex::new(pattern) {
+            Ok(regex) => (Some(regex.is_match(output)), None),
+            Err(e) => (Some(false), Some(format!("正则表达式无效: {}", e))),
+        },
+        ExpectedProperty::JsonObject => (Some(extract_json(output).is_some()), None),
+        ExpectedProperty::MinTasks { count } => {
+            let found = partial_task_titles(output).len();
+            (Some(found >= *count), Some(format!("识别出 {} 个任务", found)))
+        }
+        ExpectedProperty::MaxChars { count } => {
+            let chars = output.chars().count();
+            (Some(chars <= *count), Some(format!("{} 个字符", chars)))
+        }
+        ExpectedProperty::Judge { criterion } => match judge {
+            Some((provider, model)) => judge_output(provider, model, criterion, prompt, output).await,
+            None => (None, Some("未配置LLM裁判".to_string())),
+        },
+    };
+    PropertyCheck {
+        property: property.clone(),
+        passed,
+        detail,
+    }
+}
+
+/// 请LLM裁判判定输出是否满足标准，回复首行为 PASS 或 FAIL，其后为理由
+async fn judge_output(
+    provider: &dyn LlmProvider,
+    model: &str,
+    criterion: &str,
+    prompt: &str,
+    output: &str,
+) -> (Option<bool>, Option<String>) {
+    let judge_prompt = format!(
+        "你是评估员，请判断下面的模型输出是否满足评估标准。\n\
+         第一行只回答 PASS 或 FAIL，第二行起给出简短理由。\n\n\
+         ## 评估标准\n{}\n\n## 原始提示词\n{}\n\n## 模型输出\n{}\n",
+        criterion, prompt, output
+    );
+    let mut request = LlmRequest::new(model, judge_prompt);
+    request.temperature = Some(0.0);
+    match provider.complete(&request).await {
+        Ok(completion) => {
+            let mut lines = completion.content.lines().map(str::trim).filter(|line| !line.is_empty());
+            let verdict = lines.next().unwrap_or_default().to_ascii_uppercase();
+            let reason = lines.collect::<Vec<_>>().join(" ");
+            let reason = (!reason.is_empty()).then_some(reason);
+            if verdict.starts_with("PASS") {
+                (Some(true), reason)
+            } else if verdict.starts_with("FAIL") {
+                (Some(false), reason)
+            } else {
+                (None, Some(format!("无法解析裁判的回复: {}", verdict)))
+            }
+        }
+        Err(e) => (None, Some(format!("裁判调用失败: {}", e))),
+    }
+}
+
+/// 通过的属性占已检查属性的比例，全部跳过时为 1
+fn score(checks: &[PropertyCheck]) -> f64 {
+    let evaluated: Vec<bool> = checks.iter().filter_map(|check| check.passed).collect();
+    if evaluated.is_empty() {
+        return 1.0;
+    }
+    evaluated.iter().filter(|passed| **passed).count() as f64 / evaluated.len() as f64
+}
+
+fn build_report(run: &eval_run::Model, results: Vec<eval_result::Model>) -> Result<EvalRunReport> {
+    let variants: Vec<EvalVariant> = serde_json::from_value(run.variants.clone())
+        .map_err(|e| DatabaseError::column_decode("EvalRun", "variants", e))?;
+
+    let mut summaries = Vec::with_capacity(variants.len());
+    for variant in &variants {
+        let rows: Vec<&eval_result::Model> = results.iter().filter(|r| r.variant_name == variant.name).collect();
+        let mut cases = Vec::with_capacity(rows.len());
+        for row in &rows {
+            cases.push(EvalCaseResult {
+                case_name: row.case_name.clone(),
+                score: row.score,
+                passed: row.passed,
+                error_message: row.error_message.clone(),
+                checks: serde_json::from_value(row.checks.clone())
+                    .map_err(|e| DatabaseError::column_decode("EvalResult", "checks", e))?,
+            });
+        }
+        let count = rows.len().max(1) as f64;
+        let cases_passed = rows.iter().filter(|r| r.passed).count();
+        summaries.push(VariantSummary {
+            variant: variant.name.clone(),
+            model: variant.model.clone(),
+            mean_score: rows.iter().map(|r| r.score).sum::<f64>() / count,
+            pass_rate: cases_passed as f64 / count,
+            cases_passed: cases_passed as u32,
+            total_tokens: rows.iter().filter_map(|r| r.tokens_used).map(|t| t.max(0) as u64).sum(),
+            mean_latency_ms: rows.iter().map(|r| r.latency_ms as f64).sum::<f64>() / count,
+            cases,
+        });
+    }
+
+    let deltas = match summaries.split_first() {
+        Some((baseline, others)) => others.iter().map(|summary| delta(baseline, summary)).collect(),
+        None => Vec::new(),
+    };
+
+    Ok(EvalRunReport {
+        run_id: run.run_id,
+        eval_set_id: run.eval_set_id,
+        variants: summaries,
+        deltas,
+        judged: run.judged,
+        created_at: run.created_at,
+    })
+}
+
+fn delta(baseline: &VariantSummary, summary: &VariantSummary) -> VariantDelta {
+    let mut regressions = Vec::new();
+    let mut improvements = Vec::new();
+    for case in &summary.cases {
+        let Some(base) = baseline.cases.iter().find(|c| c.case_name == case.case_name) else {
+            continue;
+        };
+        if case.score < base.score - SCORE_TOLERANCE {
+            regressions.push(case.case_name.clone());
+        } else if case.score > base.score + SCORE_TOLERANCE {
+            improvements.push(case.case_name.clone());
+        }
+    }
+    VariantDelta {
+        variant: summary.variant.clone(),
+        baseline: baseline.variant.clone(),
+        score_delta: summary.mean_score - baseline.mean_score,
+        pass_rate_delta: summary.pass_rate - baseline.pass_rate,
+        regressions,
+        improvements,
+    }
+}
+
+/// 报告的 Markdown 摘要
+pub fn render_report_summary(report: &EvalRunReport) -> String {
+    let mut lines = vec![
+        "| 变体 | 模型 | 平均得分 | 通过率 | token | 平均耗时(ms) |".to_string(),
+        "| --- | --- | --- | --- | --- | --- |".to_string(),
+    ];
+    for variant in &report.variants {
+        lines.push(format!(
+            "| {} | {} | {:.2} | {:.0}% | {} | {:.0} |",
+            variant.variant,
+            variant.model,
+            variant.mean_score,
+            variant.pass_rate * 100.0,
+            variant.total_tokens,
+            variant.mean_latency_ms
+        ));
+    }
+    for delta in &report.deltas {
+        lines.push(format!(
+            "\n{} 相比 {}：得分 {:+.2}，通过率 {:+.0}%",
+            delta.variant,
+            delta.baseline,
+            delta.score_delta,
+            delta.pass_rate_delta * 100.0
+        ));
+        if !delta.regressions.is_empty() {
+            lines.push(format!("- 退步：{}", delta.regressions.join("、")));
+        }
+        if !delta.improvements.is_empty() {
+            lines.push(format!("- 改进：{}", delta.improvements.join("、")));
+        }
+    }
+    lines.join("\n")
+}
+
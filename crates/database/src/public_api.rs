@@ -0,0 +1,344 @@
+//! 自动化脚本使用的 REST 接口
+//!
+//! 所有请求都经过 [`ApiKeyAuthenticator::authenticate`] 认证，密钥通过 `Authorization: Bearer <key>` 传递：
+//! - `GET /api/v1/projects`：列出密钥可访问的项目（`projects:read`）；
+//! - `GET /api/v1/projects/{project_id}/tasks`：列出项目任务（`tasks:read`）；
+//! - `POST /api/v1/projects/{project_id}/tasks`：创建任务（`tasks:write`），携带 `Idempotency-Key`
+//!   请求头的重试返回首次创建的结果，不会重复创建任务。
+//!
+//! [`PublicApi::handle`] 与传输无关，[`serve`] 提供最小的 HTTP/1.1 接入。
+
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use uuid::Uuid;
+
+use crate::{
+    api_keys::{bearer_token, ApiAuthError, ApiKeyAuthenticator, ApiPrincipal, ApiScope},
+    idempotency::{IdempotencyClaim, IdempotencyStore},
+    repository::{
+        task_repository::CreateTaskData, ProjectMemberRepository, ProjectRepository, TaskRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 请求体允许的最大字节数
+pub const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// 一次 API 请求
+#[derive(Debug, Clone)]
+pub struct ApiRequest {
+    pub method: String,
+    pub path: String,
+    /// `Authorization` 请求头
+    pub authorization: Option<String>,
+    /// `Idempotency-Key` 请求头
+    pub idempotency_key: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// API 响应
+#[derive(Debug, Clone)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: JsonValue,
+    /// 被限流时建议的重试等待秒数
+    pub retry_after_secs: Option<u64>,
+}
+
+impl ApiResponse {
+    fn new(status: u16, body: JsonValue) -> Self {
+        Self { status, body, retry_after_secs: None }
+    }
+
+    fn error(status: u16, message: impl std::fmt::Display) -> Self {
+        Self::new(status, json!({ "error": message.to_string() }))
+    }
+}
+
+impl From<ApiAuthError> for ApiResponse {
+    fn from(error: ApiAuthError) -> Self {
+        let mut response = ApiResponse::error(error.status(), &error);
+        if let ApiAuthError::RateLimited { retry_after_secs } = error {
+            response.retry_after_secs = Some(retry_after_secs);
+        }
+        response
+    }
+}
+
+impl From<DatabaseError> for ApiResponse {
+    fn from(error: DatabaseError) -> Self {
+        match error {
+            DatabaseError::EntityNotFound { .. } => ApiResponse::error(404, error),
+            DatabaseError::Conflict { .. } => ApiResponse::error(409, error),
+            e if e.is_validation_error() => ApiResponse::error(400, e),
+            e => {
+                tracing::error!("API 请求处理失败: {}", e);
+                ApiResponse::error(500, e)
+            }
+        }
+    }
+}
+
+/// 创建任务的请求体
+#[derive(Debug, Deserialize)]
+struct CreateTaskBody {
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default = "default_task_type")]
+    task_type: String,
+    #[serde(default)]
+    parent_task_id: Option<Uuid>,
+}
+
+fn default_task_type() -> String {
+    "development".to_string()
+}
+
+enum Route {
+    ListProjects,
+    ListTasks(Uuid),
+    CreateTask(Uuid),
+}
+
+/// REST 接口
+pub struct PublicApi {
+    db: DatabaseConnection,
+    authenticator: ApiKeyAuthenticator,
+}
+
+impl PublicApi {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { authenticator: ApiKeyAuthenticator::new(db.clone()), db }
+    }
+
+    /// 处理一次请求
+    pub async fn handle(&self, request: ApiRequest) -> ApiResponse {
+        let route = match route(&request.method, &request.path) {
+            Ok(route) => route,
+            Err(response) => return response,
+        };
+        let secret = request.authorization.as_deref().and_then(bearer_token);
+        let result = match route {
+            Route::ListProjects => self.list_projects(secret).await,
+            Route::ListTasks(project_id) => self.list_tasks(secret, project_id).await,
+            Route::CreateTask(project_id) => {
+                self.create_task(secret, project_id, &request.body, request.idempotency_key.as_deref()).await
+            }
+        };
+        result.unwrap_or_else(|response| response)
+    }
+
+    async fn list_projects(&self, secret: Option<&str>) -> std::result::Result<ApiResponse, ApiResponse> {
+        let principal = self.authenticator.authenticate(secret, ApiScope::ProjectsRead, None).await?;
+        let project_ids: BTreeSet<Uuid> = match principal.project_id {
+            Some(project_id) => BTreeSet::from([project_id]),
+            None => {
+                let projects = ProjectRepository::new(self.db.clone());
+                let mut ids: BTreeSet<Uuid> = projects.find_by_user(principal.user_id).await?
+                    .into_iter()
+                    .map(|project| project.project_id)
+                    .collect();
+                ids.extend(ProjectMemberRepository::new(self.db.clone()).find_project_ids_by_user(principal.user_id).await?);
+                ids
+            }
+        };
+
+        let members = ProjectMemberRepository::new(self.db.clone());
+        let projects = ProjectRepository::new(self.db.clone());
+        let mut visible = Vec::new();
+        for project_id in project_ids {
+            // 创建者已不在成员列表中的项目不再可见
+            if members.get_role(project_id, principal.user_id).await?.is_none() {
+                continue;
+            }
+            if let Some(project) = projects.find_by_id(project_id).await? {
+                visible.push(json!({
+                    "project_id": project.project_id,
+                    "name": project.name,
+                    "description": project.description,
+                    "status": project.status,
+                    "main_branch": project.main_branch,
+                }));
+            }
+        }
+        Ok(ApiResponse::new(200, json!({ "projects": visible })))
+    }
+
+    async fn list_tasks(&self, secret: Option<&str>, project_id: Uuid) -> std::result::Result<ApiResponse, ApiResponse> {
+        self.authenticator.authenticate(secret, ApiScope::TasksRead, Some(project_id)).await?;
+        let tasks = TaskRepository::new(self.db.clone()).find_by_project(project_id).await?;
+        Ok(ApiResponse::new(200, json!({ "tasks": tasks })))
+    }
+
+    async fn create_task(
+        &self,
+        secret: Option<&str>,
+        project_id: Uuid,
+        body: &[u8],
+        idempotency_key: Option<&str>,
+    ) -> std::result::Result<ApiResponse, ApiResponse> {
+        let principal = self.authenticator.authenticate(secret, ApiScope::TasksWrite, Some(project_id)).await?;
+        let Some(idempotency_key) = idempotency_key else {
+            return self.insert_task(&principal, project_id, body).await;
+        };
+
+        // 幂等键按 API 密钥隔离，保存的结果包含状态码和响应体
+        let scope = format!("public_api:{}", principal.key_id);
+        let request = json!({ "project_id": project_id, "body": String::from_utf8_lossy(body) });
+        match IdempotencyStore::new(self.db.clone()).begin(&scope, idempotency_key, &request).await? {
+            IdempotencyClaim::Replay(stored) => Ok(ApiResponse::new(
+                stored["status"].as_u64().and_then(|status| u16::try_from(status).ok()).unwrap_or(200),
+                stored["body"].clone(),
+            )),
+            IdempotencyClaim::Acquired(guard) => {
+                let result = self.insert_task(&principal, project_id, body).await;
+                match &result {
+                    Ok(response) => guard.complete(&json!({ "status": response.status, "body": response.body })).await?,
+                    Err(_) => guard.release().await?,
+                }
+                result
+            }
+        }
+    }
+
+    async fn insert_task(
+        &self,
+        principal: &ApiPrincipal,
+        project_id: Uuid,
+        body: &[u8],
+    ) -> std::result::Result<ApiResponse, ApiResponse> {
+        let body: CreateTaskBody = serde_json::from_slice(body)
+            .map_err(|e| ApiResponse::error(400, format!("无效的请求体: {}", e)))?;
+        if body.title.trim().is_empty() {
+            return Err(ApiResponse::error(400, "任务标题不能为空"));
+        }
+
+        let tasks = TaskRepository::new(self.db.clone());
+        if let Some(parent_task_id) = body.parent_task_id {
+            let parent = tasks.find_by_id(parent_task_id).await?
+                .ok_or_else(|| ApiResponse::error(404, "父任务不存在"))?;
+            if parent.project_id != project_id {
+                return Err(ApiResponse::error(400, "父任务不属于该项目"));
+            }
+        }
+        let task = tasks.create(CreateTaskData {
+            project_id,
+            parent_task_id: body.parent_task_id,
+            llm_session_id: None,
+            title: body.title.trim().to_string(),
+            description: body.description,
+            task_type: body.task_type,
+        }).await?;
+        tracing::info!(task_id = %task.task_id, key_id = %principal.key_id, "API 密钥创建了任务");
+        Ok(ApiResponse::new(201, json!({ "task": task })))
+    }
+}
+
+/// 解析请求方法和路径
+fn route(method: &str, path: &str) -> std::result::Result<Route, ApiResponse> {
+    let segments: Vec<&str> = path.split('?').next().unwrap_or_default().trim_matches('/').split('/').collect();
+    let route = match segments.as_slice() {
+        ["api", "v1", "projects"] => match method {
+            "GET" => Route::ListProjects,
+            _ => return Err(ApiResponse::error(405, "只支持 GET 请求")),
+        },
+        ["api", "v1", "projects", project_id, "tasks"] => {
+            let project_id = Uuid::parse_str(project_id).map_err(|_| ApiResponse::error(404, "无效的项目ID"))?;
+            match method {
+                "GET" => Route::ListTasks(project_id),
+                "POST" => Route::CreateTask(project_id),
+                _ => return Err(ApiResponse::error(405, "只支持 GET 和 POST 请求")),
+            }
+        }
+        _ => return Err(ApiResponse::error(404, "未知的接口路径")),
+    };
+    Ok(route)
+}
+
+/// 在监听器上提供 HTTP 接入，每个连接处理一个请求
+pub async fn serve(listener: TcpListener, api: Arc<PublicApi>) -> Result<()> {
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let api = api.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_connection(stream, &api).await {
+                tracing::warn!("API 连接 {} 处理失败: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn serve_connection(stream: TcpStream, api: &PublicApi) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    let length = headers.get("content-length").and_then(|value| value.parse::<usize>().ok()).unwrap_or(0);
+    let response = if headers.get("transfer-encoding").is_some_and(|value| value.eq_ignore_ascii_case("chunked")) {
+        ApiResponse::error(411, "不支持分块传输")
+    } else if length > MAX_BODY_BYTES {
+        ApiResponse::error(413, "请求体过大")
+    } else {
+        let mut body = vec![0u8; length];
+        reader.read_exact(&mut body).await?;
+        api.handle(ApiRequest {
+            method,
+            path,
+            authorization: headers.get("authorization").cloned(),
+            idempotency_key: headers.get("idempotency-key").cloned(),
+            body,
+        }).await
+    };
+
+    let body = serde_json::to_vec(&response.body)?;
+    let retry_after = response.retry_after_secs
+        .map(|secs| format!("Retry-After: {}\r\n", secs))
+        .unwrap_or_default();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n",
+        response.status,
+        reason_phrase(response.status),
+        body.len(),
+        retry_after
+    );
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(&body).await?;
+    writer.shutdown().await?;
+    Ok(())
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        429 => "Too Many Requests",
+        _ => "Internal Server Error",
+    }
+}
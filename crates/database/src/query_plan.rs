@@ -0,0 +1,68 @@
+//! SQLite 查询计划检查
+//!
+//! [`explain`] 对查询执行 `EXPLAIN QUERY PLAN`，返回计划中每一步的描述，用于确认热点查询命中了
+//! 迁移中创建的索引（见 `Migrator::create_hot_path_indexes`），以及排查新增查询的全表扫描。
+
+use sea_orm::{ConnectionTrait, DatabaseBackend, QueryTrait, Statement};
+
+use crate::{DatabaseConnection, Result};
+
+/// 查询计划
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryPlan {
+    /// 原始 SQL
+    pub sql: String,
+    /// 计划中每一步的描述，如 `SEARCH tasks USING INDEX idx_tasks_project_status (project_id=? AND status=?)`
+    pub steps: Vec<String>,
+}
+
+impl QueryPlan {
+    /// 是否使用了指定索引（包括覆盖索引）
+    pub fn uses_index(&self, index: &str) -> bool {
+        self.steps.iter().any(|step| {
+            step.contains(&format!("USING INDEX {}", index)) || step.contains(&format!("USING COVERING INDEX {}", index))
+        })
+    }
+
+    /// 是否对指定表做了全表扫描
+    pub fn scans_table(&self, table: &str) -> bool {
+        self.steps.iter().any(|step| {
+            let mut words = step.split_whitespace();
+            words.next() == Some("SCAN") && words.next() == Some(table) && !step.contains(" USING ")
+        })
+    }
+
+    /// 是否需要临时 B 树排序（ORDER BY 没有被索引覆盖）
+    pub fn uses_temp_sort(&self) -> bool {
+        self.steps.iter().any(|step| step.starts_with("USE TEMP B-TREE FOR ORDER BY"))
+    }
+}
+
+impl std::fmt::Display for QueryPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.sql)?;
+        for step in &self.steps {
+            writeln!(f, "  {}", step)?;
+        }
+        Ok(())
+    }
+}
+
+/// 获取 SeaORM 查询在 SQLite 上的执行计划
+pub async fn explain<Q: QueryTrait>(db: &DatabaseConnection, query: &Q) -> Result<QueryPlan> {
+    explain_statement(db, query.build(DatabaseBackend::Sqlite)).await
+}
+
+/// 获取已构建语句的执行计划
+pub async fn explain_statement(db: &DatabaseConnection, statement: Statement) -> Result<QueryPlan> {
+    let explain = Statement {
+        sql: format!("EXPLAIN QUERY PLAN {}", statement.sql),
+        ..statement.clone()
+    };
+    let rows = db.query_all(explain).await?;
+    let mut steps = Vec::new();
+    for row in rows {
+        steps.push(row.try_get::<String>("", "detail")?);
+    }
+    Ok(QueryPlan { sql: statement.sql, steps })
+}
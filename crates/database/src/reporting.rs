@@ -0,0 +1,806 @@
+//! 项目状态报告
+//!
+//! [`collect_weekly_report`] 汇总项目最近一周的状态：完成的任务、交付速度（与之前几周的平均值对比）、
+//! 未解决的冲突、即将到期的里程碑、不稳定的测试以及 LLM 用量和费用。[`render`] 按模板把报告渲染为
+//! Markdown 或 HTML，模板中的 `{{占位符}}` 替换为对应章节，未指定模板时使用内置模板。
+//!
+//! 报告计划（`report_schedules`）按 cron 表达式定期触发：调度方定期调用 [`run_due_schedules`]，
+//! 生成到期计划的报告，渲染结果存入 [`ArtifactStore`]，元数据记录在 `project_reports`，
+//! 再由调度方把返回的报告投递到计划配置的通知渠道。
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::{
+    artifact_store::ArtifactStore,
+    context::builder::parse_milestones,
+    entities::{llm_conversation, project, project_report, report_schedule},
+    export::{keyset_batches, DEFAULT_BATCH_SIZE},
+    flaky_tests::{flaky_candidates, FlakyTestConfig, TestHistory},
+    repository::{
+        project_report_repository::CreateProjectReportData, report_schedule_repository::ReportScheduleData,
+        ConflictRepository, LlmSessionRepository, ProjectReportRepository, ReportScheduleRepository, TaskRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+use codex_multi_agent::llm_orchestration::MilestoneStatus;
+use futures::TryStreamExt;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+/// 报告统计周期（天）
+const REPORT_PERIOD_DAYS: i64 = 7;
+
+/// 计算交付速度时对比的历史周数
+const VELOCITY_WEEKS: i64 = 4;
+
+/// 报告中列出的里程碑截止范围（天）
+const UPCOMING_MILESTONE_DAYS: i64 = 14;
+
+/// 模板中可用的占位符
+pub const TEMPLATE_PLACEHOLDERS: &[&str] = &[
+    "title",
+    "project_name",
+    "period_start",
+    "period_end",
+    "generated_at",
+    "completed_count",
+    "completed_tasks",
+    "velocity",
+    "open_conflict_count",
+    "open_conflicts",
+    "upcoming_milestones",
+    "flaky_tests",
+    "cost",
+];
+
+/// 内置的 Markdown 模板
+pub const DEFAULT_MARKDOWN_TEMPLATE: &str = "# {{title}}
+
+统计周期：{{period_start}} 至 {{period_end}}
+
+## 已完成任务（{{completed_count}}）
+
+{{completed_tasks}}
+
+## 交付速度
+
+{{velocity}}
+
+## 未解决冲突（{{open_conflict_count}}）
+
+{{open_conflicts}}
+
+## 近期里程碑
+
+{{upcoming_milestones}}
+
+## 不稳定测试
+
+{{flaky_tests}}
+
+## LLM 用量与费用
+
+{{cost}}
+
+---
+生成时间：{{generated_at}}
+";
+
+/// 内置的 HTML 模板
+pub const DEFAULT_HTML_TEMPLATE: &str = "<!DOCTYPE html>
+<html lang=\"zh-CN\">
+<head>
+<meta charset=\"utf-8\">
+<title>{{title}}</title>
+</head>
+<body>
+<h1>{{title}}</h1>
+<p>统计周期：{{period_start}} 至 {{period_end}}</p>
+<h2>已完成任务（{{completed_count}}）</h2>
+{{completed_tasks}}
+<h2>交付速度</h2>
+{{velocity}}
+<h2>未解决冲突（{{open_conflict_count}}）</h2>
+{{open_conflicts}}
+<h2>近期里程碑</h2>
+{{upcoming_milestones}}
+<h2>不稳定测试</h2>
+{{flaky_tests}}
+<h2>LLM 用量与费用</h2>
+{{cost}}
+<footer>生成时间：{{generated_at}}</footer>
+</body>
+</html>
+";
+
+/// 报告格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    /// Markdown
+    Markdown,
+    /// HTML
+    Html,
+}
+
+impl ReportFormat {
+    /// 字符串表示
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "markdown",
+            ReportFormat::Html => "html",
+        }
+    }
+
+    /// 文件扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+
+    /// 内置模板
+    pub fn default_template(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => DEFAULT_MARKDOWN_TEMPLATE,
+            ReportFormat::Html => DEFAULT_HTML_TEMPLATE,
+        }
+    }
+}
+
+impl std::fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "markdown" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            _ => Err(format!("未知的报告格式: {}", s)),
+        }
+    }
+}
+
+/// 报告投递的通知渠道
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReportChannel {
+    /// 推送到桌面端
+    Desktop,
+    /// 以 JSON 形式 POST 到指定地址
+    Webhook { url: String },
+}
+
+/// 报告计划配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    /// 计划名称
+    pub name: String,
+    /// cron 表达式（秒 分 时 日 月 周），例如每周一早上九点为 `0 0 9 * * Mon`
+    pub cron_expression: String,
+    /// 报告格式
+    pub format: ReportFormat,
+    /// 自定义模板，为空时使用内置模板
+    #[serde(default)]
+    pub template: Option<String>,
+    /// 通知渠道
+    #[serde(default)]
+    pub channels: Vec<ReportChannel>,
+    /// 每千 token 的费用，为空时报告只统计 token 用量
+    #[serde(default)]
+    pub cost_per_1k_tokens: Option<f64>,
+    /// 是否启用
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl ScheduleConfig {
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(DatabaseError::validation("报告计划名称不能为空"));
+        }
+        parse_cron(&self.cron_expression)?;
+        if let Some(template) = &self.template {
+            validate_template(template)?;
+        }
+        if self.cost_per_1k_tokens.is_some_and(|cost| !cost.is_finite() || cost < 0.0) {
+            return Err(DatabaseError::validation("每千token费用不能为负数"));
+        }
+        for channel in &self.channels {
+            if let ReportChannel::Webhook { url } = channel {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(DatabaseError::validation(format!("无效的通知地址: {}", url)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 生成报告的选项
+    pub fn options(&self) -> ReportOptions {
+        ReportOptions {
+            format: self.format,
+            template: self.template.clone(),
+            cost_per_1k_tokens: self.cost_per_1k_tokens,
+        }
+    }
+}
+
+/// 生成报告的选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportOptions {
+    /// 报告格式
+    pub format: ReportFormat,
+    /// 自定义模板，为空时使用内置模板
+    #[serde(default)]
+    pub template: Option<String>,
+    /// 每千 token 的费用
+    #[serde(default)]
+    pub cost_per_1k_tokens: Option<f64>,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        Self {
+            format: ReportFormat::Markdown,
+            template: None,
+            cost_per_1k_tokens: None,
+        }
+    }
+}
+
+/// 项目周报数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeeklyReport {
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    /// 本周期完成的任务，按完成时间升序
+    pub completed_tasks: Vec<CompletedTask>,
+    pub velocity: Velocity,
+    /// 未解决的冲突，按严重程度降序
+    pub open_conflicts: Vec<OpenConflict>,
+    /// 未完成且即将到期（或已逾期）的里程碑，按截止日期升序
+    pub upcoming_milestones: Vec<UpcomingMilestone>,
+    /// 统计上不稳定的测试，按翻转比例降序
+    pub flaky_tests: Vec<TestHistory>,
+    pub cost: UsageCost,
+}
+
+/// 已完成任务
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompletedTask {
+    pub task_id: Uuid,
+    pub title: String,
+    pub task_type: String,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// 交付速度
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Velocity {
+    /// 本周期完成的任务数
+    pub completed: u32,
+    /// 之前几周平均每周完成的任务数
+    pub previous_weekly_average: f64,
+    /// 相对历史平均的变化比例，没有历史数据时为空
+    pub change_ratio: Option<f64>,
+}
+
+/// 未解决的冲突
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenConflict {
+    pub conflict_id: Uuid,
+    pub title: String,
+    pub severity: String,
+    pub status: String,
+}
+
+/// 近期里程碑
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpcomingMilestone {
+    pub id: String,
+    pub name: String,
+    pub deadline: DateTime<Utc>,
+    pub completion_rate: f32,
+    pub overdue: bool,
+}
+
+/// LLM 用量与费用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageCost {
+    /// 本周期消耗的 token 数
+    pub total_tokens: i64,
+    /// 按配置单价估算的费用
+    pub estimated_cost: Option<f64>,
+}
+
+/// 生成的报告
+#[derive(Debug, Clone)]
+pub struct GeneratedReport {
+    /// 报告元数据
+    pub report: project_report::Model,
+    /// 渲染后的内容
+    pub content: String,
+    /// 需要投递的通知渠道，手动生成时为空
+    pub channels: Vec<ReportChannel>,
+}
+
+/// 汇总项目截至 `period_end` 的一周状态
+pub async fn collect_weekly_report(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    period_end: DateTime<Utc>,
+    cost_per_1k_tokens: Option<f64>,
+) -> Result<WeeklyReport> {
+    let project = project::Entity::find_by_id(project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+    let tasks = TaskRepository::new(db.clone()).find_by_project(project_id).await?;
+    let period = Duration::days(REPORT_PERIOD_DAYS);
+    let period_start = period_end - period;
+
+    let completed_at: Vec<(usize, DateTime<Utc>)> = tasks
+        .iter()
+        .enumerate()
+        .filter(|(_, task)| task.status == "completed")
+        .filter_map(|(index, task)| task.completed_at.map(|at| (index, at.with_timezone(&Utc))))
+        .collect();
+    let count_between = |start: DateTime<Utc>, end: DateTime<Utc>| {
+        completed_at.iter().filter(|(_, at)| *at >= start && *at < end).count()
+    };
+
+    let mut completed_tasks: Vec<CompletedTask> = completed_at
+        .iter()
+        .filter(|(_, at)| *at >= period_start && *at < period_end)
+        .map(|(index, at)| CompletedTask {
+            task_id: tasks[*index].task_id,
+            title: tasks[*index].title.clone(),
+            task_type: tasks[*index].task_type.clone(),
+            completed_at: *at,
+        })
+        .collect();
+    completed_tasks.sort_by_key(|task| task.completed_at);
+
+    let previous_total: usize = (1..=VELOCITY_WEEKS)
+        .map(|week| {
+            let end = period_start - period * (week as i32 - 1);
+            count_between(end - period, end)
+        })
+        .sum();
+    let previous_weekly_average = previous_total as f64 / VELOCITY_WEEKS as f64;
+    let completed = completed_tasks.len() as u32;
+    let velocity = Velocity {
+        completed,
+        previous_weekly_average,
+        change_ratio: (previous_weekly_average > 0.0)
+            .then(|| (completed as f64 - previous_weekly_average) / previous_weekly_average),
+    };
+
+    let task_ids: HashSet<String> = tasks.iter().map(|task| task.task_id.to_string()).collect();
+    let open_conflicts = ConflictRepository::new(db.clone())
+        .find_unresolved()
+        .await?
+        .into_iter()
+        .filter(|conflict| {
+            conflict
+                .affected_tasks
+                .as_array()
+                .is_some_and(|affected| affected.iter().any(|id| id.as_str().is_some_and(|id| task_ids.contains(id))))
+        })
+        .map(|conflict| OpenConflict {
+            conflict_id: conflict.conflict_id,
+            title: conflict.title,
+            severity: conflict.severity,
+            status: conflict.status,
+        })
+        .collect();
+
+    let horizon = period_end + Duration::days(UPCOMING_MILESTONE_DAYS);
+    let mut upcoming_milestones: Vec<UpcomingMilestone> = parse_milestones(&project, &tasks)
+        .into_iter()
+        .filter(|milestone| milestone.status != MilestoneStatus::Completed && milestone.deadline <= horizon)
+        .map(|milestone| UpcomingMilestone {
+            overdue: milestone.deadline < period_end,
+            id: milestone.id,
+            name: milestone.name,
+            deadline: milestone.deadline,
+            completion_rate: milestone.completion_rate,
+        })
+        .collect();
+    upcoming_milestones.sort_by_key(|milestone| milestone.deadline);
+
+    let flaky_tests = flaky_candidates(db, project_id, &FlakyTestConfig::default()).await?.flaky;
+
+    let total_tokens = token_usage(db, project_id, period_start, period_end).await?;
+    let cost = UsageCost {
+        total_tokens,
+        estimated_cost: cost_per_1k_tokens.map(|price| total_tokens as f64 / 1000.0 * price),
+    };
+
+    Ok(WeeklyReport {
+        project_id,
+        project_name: project.name,
+        period_start,
+        period_end,
+        completed_tasks,
+        velocity,
+        open_conflicts,
+        upcoming_milestones,
+        flaky_tests,
+        cost,
+    })
+}
+
+/// 报告标题
+pub fn report_title(report: &WeeklyReport) -> String {
+    format!(
+        "{} 周报（{} - {}）",
+        report.project_name,
+        report.period_start.format("%Y-%m-%d"),
+        report.period_end.format("%Y-%m-%d")
+    )
+}
+
+/// 按模板渲染报告，未指定模板时使用格式对应的内置模板
+pub fn render(
+    report: &WeeklyReport,
+    format: ReportFormat,
+    template: Option<&str>,
+    generated_at: DateTime<Utc>,
+) -> Result<String> {
+    let template = template.unwrap_or(format.default_template());
+
+    let text = |value: &str| match format {
+        ReportFormat::Markdown => value.to_string(),
+        ReportFormat::Html => escape_html(value),
+    };
+    let list = |items: Vec<String>, empty: &str| match format {
+        ReportFormat::Markdown if items.is_empty() => empty.to_string(),
+        ReportFormat::Markdown => items.iter().map(|item| format!("- {}", item)).collect::<Vec<_>>().join("\n"),
+        ReportFormat::Html if items.is_empty() => format!("<p>{}</p>", empty),
+        ReportFormat::Html => format!(
+            "<ul>\n{}\n</ul>",
+            items.iter().map(|item| format!("<li>{}</li>", item)).collect::<Vec<_>>().join("\n")
+        ),
+    };
+    let paragraph = |value: String| match format {
+        ReportFormat::Markdown => value,
+        ReportFormat::Html => format!("<p>{}</p>", value),
+    };
+
+    let completed_tasks = list(
+        report
+            .completed_tasks
+            .iter()
+            .map(|task| {
+                format!("{}（{}，{}）", text(&task.title), text(&task.task_type), task.completed_at.format("%m-%d %H:%M"))
+            })
+            .collect(),
+        "本周期没有完成的任务",
+    );
+
+    let velocity = &report.velocity;
+    let trend = match velocity.change_ratio {
+        Some(ratio) => format!(
+            "，较之前{}周平均 {:.1} 个{} {:.0}%",
+            VELOCITY_WEEKS,
+            velocity.previous_weekly_average,
+            if ratio >= 0.0 { "提升" } else { "下降" },
+            ratio.abs() * 100.0
+        ),
+        None => "，之前几周没有完成的任务可供对比".to_string(),
+    };
+    let velocity = paragraph(format!("本周期完成 {} 个任务{}", velocity.completed, trend));
+
+    let open_conflicts = list(
+        report
+            .open_conflicts
+            .iter()
+            .map(|conflict| format!("[{}] {}（{}）", text(&conflict.severity), text(&conflict.title), text(&conflict.status)))
+            .collect(),
+        "没有未解决的冲突",
+    );
+
+    let upcoming_milestones = list(
+        report
+            .upcoming_milestones
+            .iter()
+            .map(|milestone| {
+                format!(
+                    "{}：截止 {}，完成度 {:.0}%{}",
+                    text(&milestone.name),
+                    milestone.deadline.format("%Y-%m-%d"),
+                    milestone.completion_rate * 100.0,
+                    if milestone.overdue { "，已逾期" } else { "" }
+                )
+            })
+            .collect(),
+        "近期没有到期的里程碑",
+    );
+
+    let flaky_tests = list(
+        report
+            .flaky_tests
+            .iter()
+            .map(|test| {
+                format!(
+                    "{}：最近 {} 次运行失败 {} 次，结果翻转比例 {:.0}%{}",
+                    text(&test.test_name),
+                    test.runs,
+                    test.failures,
+                    test.flip_rate * 100.0,
+                    if test.mixed_commit_outcomes { "，同一提交上结果不一致" } else { "" }
+                )
+            })
+            .collect(),
+        "没有发现不稳定的测试",
+    );
+
+    let cost = paragraph(match report.cost.estimated_cost {
+        Some(cost) => format!("消耗 {} 个 token，估算费用 {:.2}", report.cost.total_tokens, cost),
+        None => format!("消耗 {} 个 token", report.cost.total_tokens),
+    });
+
+    let values = [
+        ("title", text(&report_title(report))),
+        ("project_name", text(&report.project_name)),
+        ("period_start", report.period_start.format("%Y-%m-%d").to_string()),
+        ("period_end", report.period_end.format("%Y-%m-%d").to_string()),
+        ("generated_at", generated_at.format("%Y-%m-%d %H:%M UTC").to_string()),
+        ("completed_count", report.completed_tasks.len().to_string()),
+        ("completed_tasks", completed_tasks),
+        ("velocity", velocity),
+        ("open_conflict_count", report.open_conflicts.len().to_string()),
+        ("open_conflicts", open_conflicts),
+        ("upcoming_milestones", upcoming_milestones),
+        ("flaky_tests", flaky_tests),
+        ("cost", cost),
+    ];
+    let values: HashMap<&str, String> = values.into_iter().collect();
+    fill_template(template, |name| values.get(name).cloned().unwrap_or_default())
+}
+
+/// 校验模板中只使用了已知的占位符
+pub fn validate_template(template: &str) -> Result<()> {
+    fill_template(template, |_| String::new()).map(|_| ())
+}
+
+/// 把模板中的 `{{占位符}}` 替换为对应内容，占位符两侧允许有空白
+fn fill_template(template: &str, value: impl Fn(&str) -> String) -> Result<String> {
+    let mut content = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        content.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| DatabaseError::validation("报告模板中存在未闭合的占位符"))?;
+        let name = after[..end].trim();
+        if !TEMPLATE_PLACEHOLDERS.contains(&name) {
+            return Err(DatabaseError::validation(format!("报告模板中存在未知的占位符: {}", name)));
+        }
+        content.push_str(&value(name));
+        rest = &after[end + 2..];
+    }
+    content.push_str(rest);
+    Ok(content)
+}
+
+/// 生成项目报告，内容存入工件存储
+pub async fn generate_report(
+    store: &ArtifactStore,
+    project_id: Uuid,
+    options: &ReportOptions,
+    schedule_id: Option<Uuid>,
+    now: DateTime<Utc>,
+) -> Result<GeneratedReport> {
+    let report = collect_weekly_report(store.db(), project_id, now, options.cost_per_1k_tokens).await?;
+    let content = render(&report, options.format, options.template.as_deref(), now)?;
+    let checksum = store.write_blob(content.as_bytes()).await?;
+
+    let record = ProjectReportRepository::new(store.db().clone())
+        .create(CreateProjectReportData {
+            project_id,
+            schedule_id,
+            title: report_title(&report),
+            format: options.format.to_string(),
+            period_start: report.period_start,
+            period_end: report.period_end,
+            checksum,
+            size_bytes: content.len() as u64,
+            summary: serde_json::to_value(&report)?,
+        })
+        .await?;
+    tracing::info!("已生成项目 {} 的报告: {}", project_id, record.title);
+
+    Ok(GeneratedReport {
+        report: record,
+        content,
+        channels: Vec::new(),
+    })
+}
+
+/// 读取报告内容
+pub async fn read_report(store: &ArtifactStore, report_id: Uuid) -> Result<(project_report::Model, String)> {
+    let report = ProjectReportRepository::new(store.db().clone())
+        .find_by_id(report_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ProjectReport", report_id))?;
+    let content = store.read_blob(&report.checksum, &report.title).await?;
+    let content = String::from_utf8(content)
+        .map_err(|_| DatabaseError::business_logic(format!("报告内容不是有效的UTF-8: {}", report.title)))?;
+    Ok((report, content))
+}
+
+/// 删除报告，内容不再被引用时一并删除
+pub async fn delete_report(store: &ArtifactStore, report_id: Uuid) -> Result<()> {
+    let reports = ProjectReportRepository::new(store.db().clone());
+    let report = reports
+        .find_by_id(report_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ProjectReport", report_id))?;
+    reports.delete(report_id).await?;
+    store.release_blob(&report.checksum).await
+}
+
+/// 创建报告计划
+pub async fn create_schedule(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    created_by: Uuid,
+    config: ScheduleConfig,
+    now: DateTime<Utc>,
+) -> Result<report_schedule::Model> {
+    config.validate()?;
+    let next_run_at = next_run(&config.cron_expression, now)?;
+    ReportScheduleRepository::new(db.clone())
+        .create(project_id, created_by, schedule_data(config)?, next_run_at)
+        .await
+}
+
+/// 更新报告计划，下一次生成时间按新的 cron 表达式重新计算
+pub async fn update_schedule(
+    db: &DatabaseConnection,
+    schedule_id: Uuid,
+    config: ScheduleConfig,
+    now: DateTime<Utc>,
+) -> Result<report_schedule::Model> {
+    config.validate()?;
+    let next_run_at = next_run(&config.cron_expression, now)?;
+    ReportScheduleRepository::new(db.clone())
+        .update(schedule_id, schedule_data(config)?, next_run_at)
+        .await
+}
+
+/// 读取报告计划的配置
+pub fn schedule_config(schedule: &report_schedule::Model) -> Result<ScheduleConfig> {
+    Ok(ScheduleConfig {
+        name: schedule.name.clone(),
+        cron_expression: schedule.cron_expression.clone(),
+        format: schedule.format.parse().map_err(DatabaseError::validation)?,
+        template: schedule.template.clone(),
+        channels: serde_json::from_value(schedule.channels.clone())?,
+        cost_per_1k_tokens: schedule.cost_per_1k_tokens,
+        enabled: schedule.enabled,
+    })
+}
+
+/// 生成所有到期计划的报告，并把计划推进到下一次生成时间
+///
+/// 单个计划生成失败时记录日志并跳过本次，不影响其他计划。
+pub async fn run_due_schedules(store: &ArtifactStore, now: DateTime<Utc>) -> Result<Vec<GeneratedReport>> {
+    let schedules = ReportScheduleRepository::new(store.db().clone());
+    let mut generated = Vec::new();
+    for schedule in schedules.find_due(now).await? {
+        let result = match schedule_config(&schedule) {
+            Ok(config) => generate_report(store, schedule.project_id, &config.options(), Some(schedule.schedule_id), now)
+                .await
+                .map(|report| GeneratedReport { channels: config.channels, ..report }),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(report) => generated.push(report),
+            Err(e) => tracing::warn!("报告计划 {} 生成失败: {}", schedule.name, e),
+        }
+
+        match next_run(&schedule.cron_expression, now) {
+            Ok(next_run_at) => {
+                schedules.record_run(schedule.schedule_id, now, next_run_at).await?;
+            }
+            Err(e) => tracing::warn!("报告计划 {} 的cron表达式无效: {}", schedule.name, e),
+        }
+    }
+    Ok(generated)
+}
+
+/// 计算 cron 表达式在 `after` 之后的下一次触发时间
+pub fn next_run(cron_expression: &str, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    parse_cron(cron_expression)?
+        .after(&after)
+        .next()
+        .ok_or_else(|| DatabaseError::validation(format!("cron表达式没有后续触发时间: {}", cron_expression)))
+}
+
+fn parse_cron(cron_expression: &str) -> Result<cron::Schedule> {
+    cron::Schedule::from_str(cron_expression)
+        .map_err(|e| DatabaseError::validation(format!("无效的cron表达式 {}: {}", cron_expression, e)))
+}
+
+fn schedule_data(config: ScheduleConfig) -> Result<ReportScheduleData> {
+    Ok(ReportScheduleData {
+        channels: serde_json::to_value(&config.channels)?,
+        name: config.name,
+        cron_expression: config.cron_expression,
+        format: config.format.to_string(),
+        template: config.template,
+        cost_per_1k_tokens: config.cost_per_1k_tokens,
+        enabled: config.enabled,
+    })
+}
+
+/// 项目在统计周期内的 LLM token 用量
+async fn token_usage(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<i64> {
+    let session_ids: Vec<Uuid> = LlmSessionRepository::new(db.clone())
+        .find_by_project(project_id)
+        .await?
+        .into_iter()
+        .map(|session| session.session_id)
+        .collect();
+    if session_ids.is_empty() {
+        return Ok(0);
+    }
+    let start: sea_orm::prelude::DateTimeWithTimeZone = start.into();
+    let end: sea_orm::prelude::DateTimeWithTimeZone = end.into();
+    // 长期运行的项目消息很多，分批读取避免一次性加载
+    let messages = keyset_batches(
+        DEFAULT_BATCH_SIZE,
+        |message: &llm_conversation::Model| (message.created_at, message.message_id),
+        |after, limit| {
+            let mut cursor = llm_conversation::Entity::find()
+                .filter(llm_conversation::Column::SessionId.is_in(session_ids.clone()))
+                .filter(llm_conversation::Column::CreatedAt.gte(start))
+                .filter(llm_conversation::Column::CreatedAt.lt(end))
+                .cursor_by((llm_conversation::Column::CreatedAt, llm_conversation::Column::MessageId));
+            if let Some(after) = after {
+                cursor.after(after);
+            }
+            async move { cursor.first(limit).all(db).await.map_err(DatabaseError::from) }
+        },
+    );
+    messages
+        .try_fold(0i64, |total, message| async move {
+            Ok(total + message.token_count.map(i64::from).unwrap_or(0))
+        })
+        .await
+}
+
+pub(crate) fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
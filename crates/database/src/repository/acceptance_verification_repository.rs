@@ -0,0 +1,125 @@
+//! 验收标准验证记录仓储实现
+
+use crate::{entities::acceptance_verification, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 验收标准验证记录仓储
+pub struct AcceptanceVerificationRepository {
+    db: DatabaseConnection,
+}
+
+/// 写入验证结果的数据结构
+#[derive(Debug, Clone)]
+pub struct UpsertVerificationData {
+    pub task_id: Uuid,
+    pub criterion: String,
+    pub status: String,
+    pub evidence: JsonValue,
+    pub verified_by_agent_id: Option<Uuid>,
+    pub notes: Option<String>,
+}
+
+impl AcceptanceVerificationRepository {
+    /// 创建新的验收标准验证记录仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 写入验证结果，已有记录时覆盖并清除之前的签核
+    pub async fn upsert(&self, data: UpsertVerificationData) -> Result<acceptance_verification::Model> {
+        let now = chrono::Utc::now().into();
+
+        if let Some(existing) = self.find(data.task_id, &data.criterion).await? {
+            let mut verification: acceptance_verification::ActiveModel = existing.into();
+            verification.status = Set(data.status);
+            verification.evidence = Set(data.evidence);
+            verification.verified_by_agent_id = Set(data.verified_by_agent_id);
+            verification.notes = Set(data.notes);
+            verification.signed_off_by = Set(None);
+            verification.signed_off_at = Set(None);
+            verification.updated_at = Set(now);
+            return verification.update(&self.db).await.map_err(DatabaseError::from);
+        }
+
+        let verification_id = Uuid::new_v4();
+        let verification = acceptance_verification::ActiveModel {
+            verification_id: Set(verification_id),
+            task_id: Set(data.task_id),
+            criterion: Set(data.criterion),
+            status: Set(data.status),
+            evidence: Set(data.evidence),
+            verified_by_agent_id: Set(data.verified_by_agent_id),
+            notes: Set(data.notes),
+            signed_off_by: Set(None),
+            signed_off_at: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        acceptance_verification::Entity::insert(verification).exec(&self.db).await?;
+
+        acceptance_verification::Entity::find_by_id(verification_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("AcceptanceVerification", verification_id))
+    }
+
+    /// 记录人工签核，没有验证记录时新建
+    pub async fn sign_off(
+        &self,
+        task_id: Uuid,
+        criterion: &str,
+        status: &str,
+        signed_off_by: Uuid,
+        notes: Option<String>,
+    ) -> Result<acceptance_verification::Model> {
+        let existing = match self.find(task_id, criterion).await? {
+            Some(existing) => existing,
+            None => {
+                self.upsert(UpsertVerificationData {
+                    task_id,
+                    criterion: criterion.to_string(),
+                    status: status.to_string(),
+                    evidence: JsonValue::Array(Vec::new()),
+                    verified_by_agent_id: None,
+                    notes: None,
+                })
+                .await?
+            }
+        };
+
+        let now = chrono::Utc::now().into();
+        let mut verification: acceptance_verification::ActiveModel = existing.into();
+        verification.status = Set(status.to_string());
+        verification.signed_off_by = Set(Some(signed_off_by));
+        verification.signed_off_at = Set(Some(now));
+        if notes.is_some() {
+            verification.notes = Set(notes);
+        }
+        verification.updated_at = Set(now);
+
+        verification.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 查找任务某项验收标准的验证记录
+    pub async fn find(&self, task_id: Uuid, criterion: &str) -> Result<Option<acceptance_verification::Model>> {
+        acceptance_verification::Entity::find()
+            .filter(acceptance_verification::Column::TaskId.eq(task_id))
+            .filter(acceptance_verification::Column::Criterion.eq(criterion))
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找任务的全部验证记录
+    pub async fn find_by_task(&self, task_id: Uuid) -> Result<Vec<acceptance_verification::Model>> {
+        acceptance_verification::Entity::find()
+            .filter(acceptance_verification::Column::TaskId.eq(task_id))
+            .order_by_asc(acceptance_verification::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+}
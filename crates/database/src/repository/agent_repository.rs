@@ -1,55 +1,61 @@
 //! Agent仓储实现
 
+use crate::agent_bundle::{AgentBundle, ImportConflictStrategy};
 use crate::entities::agent::{self, Entity as Agent, ActiveModel, Model, AgentStatus};
 use crate::error::{DatabaseError, Result};
+use crate::tenant_scope::{scoped_condition, TenantScope};
 use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, 
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DatabaseConnection, EntityTrait, 
     QueryFilter, QueryOrder, PaginatorTrait,
 };
 use serde_json::Value as JsonValue;
+use crate::ids::EntityKey;
+use codex_multi_agent::AgentId;
 use uuid::Uuid;
 
 /// Agent仓储
 pub struct AgentRepository {
     db: DatabaseConnection,
+    scope: Option<TenantScope>,
 }
 
 impl AgentRepository {
     /// 创建新的Agent仓储实例
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self { db, scope: None }
     }
 
-    /// 创建新的Agent
-    pub async fn create(&self, agent_data: CreateAgentData) -> Result<Model> {
-        let agent = ActiveModel {
-            agent_id: Set(Uuid::new_v4()),
-            user_id: Set(agent_data.user_id),
-            name: Set(agent_data.name),
-            description: Set(agent_data.description),
-            prompt_template: Set(agent_data.prompt_template),
-            capabilities: Set(agent_data.capabilities),
-            config: Set(agent_data.config),
-            git_config: Set(agent_data.git_config),
-            status: Set(AgentStatus::Idle.to_string()),
-            skill_profile: Set(None),
-            skill_assessments: Set(None),
-            performance_trend: Set(None),
-            current_task_id: Set(None),
-            total_tasks_completed: Set(0),
-            success_rate: Set(0.0),
-            average_completion_time: Set(0),
-            created_at: Set(chrono::Utc::now().into()),
-            updated_at: Set(chrono::Utc::now().into()),
-            last_active_at: Set(chrono::Utc::now().into()),
-        };
+    /// 限定只能访问租户范围内的Agent
+    pub fn with_scope(mut self, scope: TenantScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    fn tenant_condition(&self) -> Condition {
+        scoped_condition(self.scope.as_ref(), TenantScope::agent_condition)
+    }
 
+    /// 创建新的Agent，组织范围内创建的Agent归属该组织
+    pub async fn create(&self, agent_data: CreateAgentData) -> Result<Model> {
+        let owner_id = agent_data.user_id;
+        let mut agent = new_agent_model(agent_data);
+        match self.scope {
+            Some(TenantScope::User(user_id)) if user_id != owner_id => {
+                return Err(DatabaseError::entity_not_found("User", owner_id));
+            }
+            Some(TenantScope::Organization(organization_id)) => {
+                agent.organization_id = Set(Some(organization_id));
+            }
+            _ => {}
+        }
         agent.insert(&self.db).await.map_err(DatabaseError::from)
     }
 
     /// 根据ID查找Agent
-    pub async fn find_by_id(&self, agent_id: Uuid) -> Result<Option<Model>> {
+    pub async fn find_by_id(&self, agent_id: impl EntityKey<AgentId>) -> Result<Option<Model>> {
+        let agent_id = agent_id.into_key();
         Agent::find_by_id(agent_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await
             .map_err(DatabaseError::from)
@@ -58,6 +64,7 @@ impl AgentRepository {
     /// 根据用户ID查找所有Agent
     pub async fn find_by_user_id(&self, user_id: Uuid) -> Result<Vec<Model>> {
         Agent::find()
+            .filter(self.tenant_condition())
             .filter(agent::Column::UserId.eq(user_id))
             .order_by_asc(agent::Column::CreatedAt)
             .all(&self.db)
@@ -68,6 +75,7 @@ impl AgentRepository {
     /// 根据状态查找Agent
     pub async fn find_by_status(&self, status: AgentStatus) -> Result<Vec<Model>> {
         Agent::find()
+            .filter(self.tenant_condition())
             .filter(agent::Column::Status.eq(status.to_string()))
             .order_by_asc(agent::Column::LastActiveAt)
             .all(&self.db)
@@ -82,7 +90,7 @@ impl AgentRepository {
 
     /// 根据能力查找Agent
     pub async fn find_by_capabilities(&self, required_capabilities: &[String]) -> Result<Vec<Model>> {
-        let mut query = Agent::find();
+        let mut query = Agent::find().filter(self.tenant_condition());
         
         for capability in required_capabilities {
             // 使用JSON查询查找包含指定能力的Agent
@@ -105,6 +113,7 @@ impl AgentRepository {
         page_size: u64
     ) -> Result<(Vec<Model>, u64)> {
         let paginator = Agent::find()
+            .filter(self.tenant_condition())
             .order_by_desc(agent::Column::CreatedAt)
             .paginate(&self.db, page_size);
 
@@ -117,10 +126,11 @@ impl AgentRepository {
     /// 更新Agent状态
     pub async fn update_status(
         &self, 
-        agent_id: Uuid, 
+        agent_id: impl EntityKey<AgentId>, 
         status: AgentStatus,
         current_task_id: Option<Uuid>
     ) -> Result<Model> {
+        let agent_id = agent_id.into_key();
         let agent = self.find_by_id(agent_id).await?
             .ok_or_else(|| DatabaseError::entity_not_found("Agent", agent_id.to_string()))?;
 
@@ -136,9 +146,10 @@ impl AgentRepository {
     /// 更新Agent统计信息
     pub async fn update_statistics(
         &self,
-        agent_id: Uuid,
+        agent_id: impl EntityKey<AgentId>,
         stats: AgentStatistics
     ) -> Result<Model> {
+        let agent_id = agent_id.into_key();
         let agent = self.find_by_id(agent_id).await?
             .ok_or_else(|| DatabaseError::entity_not_found("Agent", agent_id.to_string()))?;
 
@@ -164,10 +175,11 @@ impl AgentRepository {
     /// 更新Agent配置
     pub async fn update_config(
         &self,
-        agent_id: Uuid,
+        agent_id: impl EntityKey<AgentId>,
         config: JsonValue,
         git_config: Option<JsonValue>
     ) -> Result<Model> {
+        let agent_id = agent_id.into_key();
         let agent = self.find_by_id(agent_id).await?
             .ok_or_else(|| DatabaseError::entity_not_found("Agent", agent_id.to_string()))?;
 
@@ -181,18 +193,25 @@ impl AgentRepository {
         agent_active.update(&self.db).await.map_err(DatabaseError::from)
     }
 
-    /// 删除Agent
-    pub async fn delete(&self, agent_id: Uuid) -> Result<()> {
-        Agent::delete_by_id(agent_id)
+    /// 删除Agent，设置了租户范围时删除范围外的Agent返回未找到
+    pub async fn delete(&self, agent_id: impl EntityKey<AgentId>) -> Result<()> {
+        let agent_id = agent_id.into_key();
+        let result = Agent::delete_many()
+            .filter(agent::Column::AgentId.eq(agent_id))
+            .filter(self.tenant_condition())
             .exec(&self.db)
             .await
             .map_err(DatabaseError::from)?;
         
+        if self.scope.is_some() && result.rows_affected == 0 {
+            return Err(DatabaseError::entity_not_found("Agent", agent_id.to_string()));
+        }
         Ok(())
     }
 
     /// 获取Agent性能统计
-    pub async fn get_performance_stats(&self, agent_id: Uuid) -> Result<AgentPerformance> {
+    pub async fn get_performance_stats(&self, agent_id: impl EntityKey<AgentId>) -> Result<AgentPerformance> {
+        let agent_id = agent_id.into_key();
         let agent = self.find_by_id(agent_id).await?
             .ok_or_else(|| DatabaseError::entity_not_found("Agent", agent_id.to_string()))?;
 
@@ -213,7 +232,7 @@ impl AgentRepository {
         required_capabilities: &[String],
         exclude_busy: bool
     ) -> Result<Option<Model>> {
-        let mut query = Agent::find();
+        let mut query = Agent::find().filter(self.tenant_condition());
 
         // 如果需要排除忙碌的Agent
         if exclude_busy {
@@ -235,6 +254,135 @@ impl AgentRepository {
             .await
             .map_err(DatabaseError::from)
     }
+
+    /// 根据用户和名称查找Agent
+    pub async fn find_by_name(&self, user_id: Uuid, name: &str) -> Result<Option<Model>> {
+        Agent::find()
+            .filter(self.tenant_condition())
+            .filter(agent::Column::UserId.eq(user_id))
+            .filter(agent::Column::Name.eq(name))
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 导出Agent为签名的Agent包
+    pub async fn export_bundle(
+        &self,
+        agent_id: impl EntityKey<AgentId>,
+        version: &str,
+        author: Option<String>,
+        signing_key: Option<&[u8]>,
+    ) -> Result<AgentBundle> {
+        let agent_id = agent_id.into_key();
+        let agent = self.find_by_id(agent_id).await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Agent", agent_id.to_string()))?;
+
+        let mut bundle = AgentBundle::from_agent(&agent, version, author.clone());
+        bundle.validate()?;
+        bundle.sign(signing_key, author)?;
+        Ok(bundle)
+    }
+
+    /// 导入Agent包：校验结构和签名，并按策略处理同名Agent
+    pub async fn import_bundle(
+        &self,
+        user_id: Uuid,
+        bundle: &AgentBundle,
+        strategy: ImportConflictStrategy,
+        signing_key: Option<&[u8]>,
+    ) -> Result<ImportedAgent> {
+        bundle.validate()?;
+        bundle.verify(signing_key)?;
+
+        let name = bundle.name.trim().to_string();
+        let existing = self.find_by_name(user_id, &name).await?;
+
+        let Some(existing) = existing else {
+            let agent = self.create(self.bundle_agent_data(user_id, name, bundle)).await?;
+            return Ok(ImportedAgent { agent, renamed_from: None, overwritten: false });
+        };
+
+        match strategy {
+            ImportConflictStrategy::Fail => Err(DatabaseError::validation(format!(
+                "已存在同名Agent: {}",
+                name
+            ))),
+            ImportConflictStrategy::Rename => {
+                let mut suffix = 2;
+                let new_name = loop {
+                    let candidate = format!("{} ({})", name, suffix);
+                    if self.find_by_name(user_id, &candidate).await?.is_none() {
+                        break candidate;
+                    }
+                    suffix += 1;
+                };
+                let agent = self.create(self.bundle_agent_data(user_id, new_name, bundle)).await?;
+                Ok(ImportedAgent { agent, renamed_from: Some(name), overwritten: false })
+            }
+            ImportConflictStrategy::Overwrite => {
+                let mut agent_active: ActiveModel = existing.into();
+                agent_active.description = Set(bundle.description.clone());
+                agent_active.prompt_template = Set(bundle.prompt_template.clone());
+                agent_active.capabilities = Set(bundle.capability_names());
+                agent_active.config = Set(bundle.agent_config());
+                agent_active.git_config = Set(bundle.git_config.clone());
+                agent_active.updated_at = Set(chrono::Utc::now().into());
+
+                let agent = agent_active.update(&self.db).await?;
+                Ok(ImportedAgent { agent, renamed_from: None, overwritten: true })
+            }
+        }
+    }
+
+    fn bundle_agent_data(&self, user_id: Uuid, name: String, bundle: &AgentBundle) -> CreateAgentData {
+        CreateAgentData {
+            user_id,
+            name,
+            description: bundle.description.clone(),
+            prompt_template: bundle.prompt_template.clone(),
+            capabilities: bundle.capability_names(),
+            config: bundle.agent_config(),
+            git_config: bundle.git_config.clone(),
+        }
+    }
+}
+
+/// 由创建数据构造新Agent的活动模型，供需要在事务中插入的调用方使用
+pub(crate) fn new_agent_model(agent_data: CreateAgentData) -> ActiveModel {
+    ActiveModel {
+        agent_id: Set(Uuid::new_v4()),
+        user_id: Set(agent_data.user_id),
+        name: Set(agent_data.name),
+        description: Set(agent_data.description),
+        prompt_template: Set(agent_data.prompt_template),
+        capabilities: Set(agent_data.capabilities),
+        config: Set(agent_data.config),
+        git_config: Set(agent_data.git_config),
+        status: Set(AgentStatus::Idle.to_string()),
+        skill_profile: Set(None),
+        skill_assessments: Set(None),
+        performance_trend: Set(None),
+        organization_id: Set(None),
+        current_task_id: Set(None),
+        total_tasks_completed: Set(0),
+        success_rate: Set(0.0),
+        average_completion_time: Set(0),
+        created_at: Set(chrono::Utc::now().into()),
+        updated_at: Set(chrono::Utc::now().into()),
+        last_active_at: Set(chrono::Utc::now().into()),
+    }
+}
+
+/// Agent包导入结果
+#[derive(Debug, Clone)]
+pub struct ImportedAgent {
+    /// 导入或更新后的Agent
+    pub agent: Model,
+    /// 因重名而改名时的原名称
+    pub renamed_from: Option<String>,
+    /// 是否覆盖了已有Agent
+    pub overwritten: bool,
 }
 
 /// 创建Agent的数据结构
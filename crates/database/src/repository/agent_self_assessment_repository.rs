@@ -0,0 +1,96 @@
+//! Agent自我评估仓储实现
+
+use crate::{entities::agent_self_assessment, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// Agent自我评估仓储
+pub struct AgentSelfAssessmentRepository {
+    db: DatabaseConnection,
+}
+
+/// 记录自我评估的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateSelfAssessmentData {
+    pub session_id: Uuid,
+    pub task_id: Uuid,
+    pub agent_id: Uuid,
+    pub confidence: f64,
+    pub summary: String,
+    pub concerns: JsonValue,
+    pub calibrated_confidence: f64,
+    pub escalated: bool,
+}
+
+impl AgentSelfAssessmentRepository {
+    /// 创建新的Agent自我评估仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 记录自我评估
+    pub async fn create(&self, data: CreateSelfAssessmentData) -> Result<agent_self_assessment::Model> {
+        let assessment = agent_self_assessment::ActiveModel {
+            assessment_id: Set(Uuid::new_v4()),
+            session_id: Set(data.session_id),
+            task_id: Set(data.task_id),
+            agent_id: Set(data.agent_id),
+            confidence: Set(data.confidence),
+            summary: Set(data.summary),
+            concerns: Set(data.concerns),
+            calibrated_confidence: Set(data.calibrated_confidence),
+            escalated: Set(data.escalated),
+            review_decision: Set(None),
+            created_at: Set(chrono::Utc::now().into()),
+            reviewed_at: Set(None),
+        };
+        assessment.insert(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 根据执行会话查找自我评估
+    pub async fn find_by_session_id(&self, session_id: Uuid) -> Result<Option<agent_self_assessment::Model>> {
+        agent_self_assessment::Entity::find()
+            .filter(agent_self_assessment::Column::SessionId.eq(session_id))
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找Agent已有审查结果的自我评估，最新的在前
+    pub async fn find_reviewed_by_agent(&self, agent_id: Uuid) -> Result<Vec<agent_self_assessment::Model>> {
+        agent_self_assessment::Entity::find()
+            .filter(agent_self_assessment::Column::AgentId.eq(agent_id))
+            .filter(agent_self_assessment::Column::ReviewDecision.is_not_null())
+            .order_by_desc(agent_self_assessment::Column::ReviewedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找已升级人工审查、尚未有审查结果的自我评估
+    pub async fn find_pending_escalations(&self) -> Result<Vec<agent_self_assessment::Model>> {
+        agent_self_assessment::Entity::find()
+            .filter(agent_self_assessment::Column::Escalated.eq(true))
+            .filter(agent_self_assessment::Column::ReviewDecision.is_null())
+            .order_by_asc(agent_self_assessment::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 记录执行会话的审查决策，会话没有自我评估时返回 `None`
+    pub async fn record_review_decision(
+        &self,
+        session_id: Uuid,
+        decision: String,
+    ) -> Result<Option<agent_self_assessment::Model>> {
+        let Some(assessment) = self.find_by_session_id(session_id).await? else {
+            return Ok(None);
+        };
+        let mut assessment: agent_self_assessment::ActiveModel = assessment.into();
+        assessment.review_decision = Set(Some(decision));
+        assessment.reviewed_at = Set(Some(chrono::Utc::now().into()));
+        assessment.update(&self.db).await.map(Some).map_err(DatabaseError::from)
+    }
+}
@@ -1,7 +1,7 @@
 //! Agent工作历史仓储实现
 
 use crate::{entities::agent_work_history, DatabaseConnection, DatabaseError, Result};
-use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter, QueryOrder};
+use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, PaginatorTrait, QueryFilter, QueryOrder};
 use uuid::Uuid;
 
 /// Agent工作历史仓储
@@ -77,6 +77,63 @@ impl AgentWorkHistoryRepository {
             .map_err(DatabaseError::from)
     }
     
+    /// 分页查询Agent的工作历史（页码从0开始），返回当页记录和总记录数
+    pub async fn find_by_agent_paginated(
+        &self,
+        agent_id: Uuid,
+        page: u64,
+        page_size: u64,
+    ) -> Result<(Vec<agent_work_history::Model>, u64)> {
+        let paginator = agent_work_history::Entity::find()
+            .filter(agent_work_history::Column::AgentId.eq(agent_id))
+            .order_by_desc(agent_work_history::Column::StartedAt)
+            .paginate(&self.db, page_size);
+
+        let total = paginator.num_items().await.map_err(DatabaseError::from)?;
+        let records = paginator.fetch_page(page).await.map_err(DatabaseError::from)?;
+
+        Ok((records, total))
+    }
+
+    /// 查找Agent在指定时间之后开始的工作历史，未指定时间时返回全部
+    pub async fn find_by_agent_since(
+        &self,
+        agent_id: Uuid,
+        since: Option<chrono::DateTime<chrono::FixedOffset>>,
+    ) -> Result<Vec<agent_work_history::Model>> {
+        let mut query = agent_work_history::Entity::find()
+            .filter(agent_work_history::Column::AgentId.eq(agent_id));
+        if let Some(since) = since {
+            query = query.filter(agent_work_history::Column::StartedAt.gte(since));
+        }
+        query
+            .order_by_desc(agent_work_history::Column::StartedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找一组任务在指定时间之后开始的工作历史
+    pub async fn find_by_tasks_since(
+        &self,
+        task_ids: &[Uuid],
+        since: Option<chrono::DateTime<chrono::FixedOffset>>,
+    ) -> Result<Vec<agent_work_history::Model>> {
+        if task_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut query = agent_work_history::Entity::find()
+            .filter(agent_work_history::Column::TaskId.is_in(task_ids.iter().copied()));
+        if let Some(since) = since {
+            query = query.filter(agent_work_history::Column::StartedAt.gte(since));
+        }
+        query
+            .order_by_desc(agent_work_history::Column::StartedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
     /// 根据任务ID查找工作历史
     pub async fn find_by_task_id(&self, task_id: Uuid) -> Result<Vec<agent_work_history::Model>> {
         agent_work_history::Entity::find()
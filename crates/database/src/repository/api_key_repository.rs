@@ -0,0 +1,107 @@
+//! API密钥仓储实现
+
+use crate::{entities::api_key, DatabaseConnection, DatabaseError, Result};
+use chrono::{DateTime, FixedOffset, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use uuid::Uuid;
+
+/// API密钥仓储
+pub struct ApiKeyRepository {
+    db: DatabaseConnection,
+}
+
+/// 创建API密钥的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateApiKeyData {
+    pub user_id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_minute: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKeyRepository {
+    /// 创建新的API密钥仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 创建API密钥
+    pub async fn create(&self, data: CreateApiKeyData) -> Result<api_key::Model> {
+        let key = api_key::ActiveModel {
+            key_id: Set(Uuid::new_v4()),
+            user_id: Set(data.user_id),
+            project_id: Set(data.project_id),
+            name: Set(data.name),
+            key_prefix: Set(data.key_prefix),
+            key_hash: Set(data.key_hash),
+            scopes: Set(serde_json::to_value(data.scopes)?),
+            rate_limit_per_minute: Set(data.rate_limit_per_minute),
+            request_count: Set(0),
+            throttled_count: Set(0),
+            last_used_at: Set(None),
+            expires_at: Set(data.expires_at.map(Into::into)),
+            revoked_at: Set(None),
+            created_at: Set(Utc::now().into()),
+        };
+        key.insert(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 根据ID查找API密钥
+    pub async fn find_by_id(&self, key_id: Uuid) -> Result<Option<api_key::Model>> {
+        api_key::Entity::find_by_id(key_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 根据密钥前缀查找API密钥
+    pub async fn find_by_prefix(&self, key_prefix: &str) -> Result<Option<api_key::Model>> {
+        api_key::Entity::find()
+            .filter(api_key::Column::KeyPrefix.eq(key_prefix))
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找用户的全部API密钥，最近创建的在前
+    pub async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<api_key::Model>> {
+        api_key::Entity::find()
+            .filter(api_key::Column::UserId.eq(user_id))
+            .order_by_desc(api_key::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 吊销API密钥
+    pub async fn revoke(&self, key_id: Uuid) -> Result<api_key::Model> {
+        let key = self.find_by_id(key_id).await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ApiKey", key_id))?;
+        if key.revoked_at.is_some() {
+            return Ok(key);
+        }
+        let mut active: api_key::ActiveModel = key.into();
+        active.revoked_at = Set(Some(Utc::now().into()));
+        active.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 记录一次请求：通过的请求累加请求数并更新最近使用时间，被限流的请求累加限流数
+    pub async fn record_usage(&self, key_id: Uuid, throttled: bool) -> Result<()> {
+        let update = api_key::Entity::update_many().filter(api_key::Column::KeyId.eq(key_id));
+        let update = if throttled {
+            update.col_expr(api_key::Column::ThrottledCount, Expr::col(api_key::Column::ThrottledCount).add(1))
+        } else {
+            update
+                .col_expr(api_key::Column::RequestCount, Expr::col(api_key::Column::RequestCount).add(1))
+                .col_expr(api_key::Column::LastUsedAt, Expr::value(Some(DateTime::<FixedOffset>::from(Utc::now()))))
+        };
+        update.exec(&self.db).await.map_err(DatabaseError::from)?;
+        Ok(())
+    }
+}
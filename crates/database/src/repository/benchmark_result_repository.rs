@@ -0,0 +1,140 @@
+//! 基准测试结果仓储实现
+
+use crate::{entities::benchmark_result, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use uuid::Uuid;
+
+/// 基准测试结果仓储
+pub struct BenchmarkResultRepository {
+    db: DatabaseConnection,
+}
+
+/// 记录基准测试结果的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateBenchmarkResultData {
+    pub project_id: Uuid,
+    pub execution_session_id: Uuid,
+    pub branch: String,
+    pub commit_sha: Option<String>,
+    pub suite: String,
+    pub name: String,
+    pub value: f64,
+    pub unit: String,
+    pub higher_is_better: bool,
+}
+
+impl BenchmarkResultRepository {
+    /// 创建新的基准测试结果仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 批量记录基准测试结果
+    pub async fn create_many(&self, data: Vec<CreateBenchmarkResultData>) -> Result<Vec<benchmark_result::Model>> {
+        let now = chrono::Utc::now();
+        let mut results = Vec::with_capacity(data.len());
+        for item in data {
+            let result = benchmark_result::ActiveModel {
+                result_id: Set(Uuid::new_v4()),
+                project_id: Set(item.project_id),
+                execution_session_id: Set(item.execution_session_id),
+                branch: Set(item.branch),
+                commit_sha: Set(item.commit_sha),
+                suite: Set(item.suite),
+                name: Set(item.name),
+                value: Set(item.value),
+                unit: Set(item.unit),
+                higher_is_better: Set(item.higher_is_better),
+                is_baseline: Set(false),
+                created_at: Set(now.into()),
+            };
+            results.push(result.insert(&self.db).await.map_err(DatabaseError::from)?);
+        }
+        Ok(results)
+    }
+
+    /// 查找执行会话的全部结果，按基准测试命令和名称排列
+    pub async fn find_by_session(&self, session_id: Uuid) -> Result<Vec<benchmark_result::Model>> {
+        benchmark_result::Entity::find()
+            .filter(benchmark_result::Column::ExecutionSessionId.eq(session_id))
+            .order_by_asc(benchmark_result::Column::Suite)
+            .order_by_asc(benchmark_result::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 删除执行会话中指定基准测试命令的结果
+    pub async fn delete_by_session_and_suite(&self, session_id: Uuid, suite: &str) -> Result<u64> {
+        let result = benchmark_result::Entity::delete_many()
+            .filter(benchmark_result::Column::ExecutionSessionId.eq(session_id))
+            .filter(benchmark_result::Column::Suite.eq(suite))
+            .exec(&self.db)
+            .await
+            .map_err(DatabaseError::from)?;
+        Ok(result.rows_affected)
+    }
+
+    /// 查找分支上某项基准测试当前的基线
+    pub async fn find_baseline(
+        &self,
+        project_id: Uuid,
+        branch: &str,
+        suite: &str,
+        name: &str,
+    ) -> Result<Option<benchmark_result::Model>> {
+        benchmark_result::Entity::find()
+            .filter(benchmark_result::Column::ProjectId.eq(project_id))
+            .filter(benchmark_result::Column::Branch.eq(branch))
+            .filter(benchmark_result::Column::Suite.eq(suite))
+            .filter(benchmark_result::Column::Name.eq(name))
+            .filter(benchmark_result::Column::IsBaseline.eq(true))
+            .order_by_desc(benchmark_result::Column::CreatedAt)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 把结果设为所在分支的基线，同一分支上同名基准测试之前的基线随之失效
+    pub async fn mark_baseline(&self, result: &benchmark_result::Model) -> Result<()> {
+        benchmark_result::Entity::update_many()
+            .col_expr(benchmark_result::Column::IsBaseline, Expr::value(false))
+            .filter(benchmark_result::Column::ProjectId.eq(result.project_id))
+            .filter(benchmark_result::Column::Branch.eq(result.branch.as_str()))
+            .filter(benchmark_result::Column::Suite.eq(result.suite.as_str()))
+            .filter(benchmark_result::Column::Name.eq(result.name.as_str()))
+            .exec(&self.db)
+            .await?;
+        benchmark_result::Entity::update_many()
+            .col_expr(benchmark_result::Column::IsBaseline, Expr::value(true))
+            .filter(benchmark_result::Column::ResultId.eq(result.result_id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// 查找分支上某项基准测试最近的结果，按时间先后排列
+    pub async fn find_history(
+        &self,
+        project_id: Uuid,
+        branch: &str,
+        suite: &str,
+        name: &str,
+        limit: u64,
+    ) -> Result<Vec<benchmark_result::Model>> {
+        let mut results = benchmark_result::Entity::find()
+            .filter(benchmark_result::Column::ProjectId.eq(project_id))
+            .filter(benchmark_result::Column::Branch.eq(branch))
+            .filter(benchmark_result::Column::Suite.eq(suite))
+            .filter(benchmark_result::Column::Name.eq(name))
+            .order_by_desc(benchmark_result::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)?;
+        results.reverse();
+        Ok(results)
+    }
+}
@@ -0,0 +1,111 @@
+//! 项目黑板仓储实现
+
+use crate::{entities::blackboard_entry, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set, SqlErr};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// 项目黑板仓储
+pub struct BlackboardRepository {
+    db: DatabaseConnection,
+}
+
+/// 写入条目版本的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateEntryVersionData {
+    pub project_id: Uuid,
+    pub key: String,
+    pub version: i32,
+    pub category: String,
+    pub value: String,
+    pub include_in_prompts: bool,
+    pub deleted: bool,
+    pub author_user_id: Option<Uuid>,
+    pub author_agent_id: Option<Uuid>,
+}
+
+impl BlackboardRepository {
+    /// 创建新的项目黑板仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 写入条目的新版本
+    ///
+    /// 同一版本已被其他写入占用时返回并发冲突错误
+    pub async fn create_version(&self, data: CreateEntryVersionData) -> Result<blackboard_entry::Model> {
+        let entry_id = Uuid::new_v4();
+        let entry = blackboard_entry::ActiveModel {
+            entry_id: Set(entry_id),
+            project_id: Set(data.project_id),
+            key: Set(data.key.clone()),
+            version: Set(data.version),
+            category: Set(data.category),
+            value: Set(data.value),
+            include_in_prompts: Set(data.include_in_prompts),
+            deleted: Set(data.deleted),
+            author_user_id: Set(data.author_user_id),
+            author_agent_id: Set(data.author_agent_id),
+            created_at: Set(chrono::Utc::now().into()),
+        };
+
+        if let Err(e) = blackboard_entry::Entity::insert(entry).exec(&self.db).await {
+            return Err(match e.sql_err() {
+                Some(SqlErr::UniqueConstraintViolation(_)) => DatabaseError::conflict(format!(
+                    "黑板条目 {} 的版本 {} 已被其他写入占用",
+                    data.key, data.version
+                )),
+                _ => e.into(),
+            });
+        }
+
+        blackboard_entry::Entity::find_by_id(entry_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("BlackboardEntry", entry_id))
+    }
+
+    /// 查找条目的最新版本（包括删除记录）
+    pub async fn find_latest(&self, project_id: Uuid, key: &str) -> Result<Option<blackboard_entry::Model>> {
+        blackboard_entry::Entity::find()
+            .filter(blackboard_entry::Column::ProjectId.eq(project_id))
+            .filter(blackboard_entry::Column::Key.eq(key))
+            .order_by_desc(blackboard_entry::Column::Version)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找条目的全部版本（按版本号升序）
+    pub async fn find_history(&self, project_id: Uuid, key: &str) -> Result<Vec<blackboard_entry::Model>> {
+        blackboard_entry::Entity::find()
+            .filter(blackboard_entry::Column::ProjectId.eq(project_id))
+            .filter(blackboard_entry::Column::Key.eq(key))
+            .order_by_asc(blackboard_entry::Column::Version)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找项目每个条目的最新版本（按键排序，包括删除记录）
+    pub async fn find_latest_by_project(&self, project_id: Uuid) -> Result<Vec<blackboard_entry::Model>> {
+        let versions = blackboard_entry::Entity::find()
+            .filter(blackboard_entry::Column::ProjectId.eq(project_id))
+            .all(&self.db)
+            .await?;
+
+        let mut latest: HashMap<String, blackboard_entry::Model> = HashMap::new();
+        for entry in versions {
+            match latest.get(&entry.key) {
+                Some(current) if current.version >= entry.version => {}
+                _ => {
+                    latest.insert(entry.key.clone(), entry);
+                }
+            }
+        }
+
+        let mut entries: Vec<_> = latest.into_values().collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(entries)
+    }
+}
@@ -0,0 +1,136 @@
+//! CI 检查结果仓储实现
+
+use crate::{entities::ci_check_run, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use uuid::Uuid;
+
+/// CI 检查结果仓储
+pub struct CiCheckRunRepository {
+    db: DatabaseConnection,
+}
+
+/// 写入检查结果的数据结构
+#[derive(Debug, Clone)]
+pub struct UpsertCiCheckRunData {
+    pub project_id: Uuid,
+    pub provider: String,
+    pub external_id: String,
+    pub name: String,
+    pub commit_sha: String,
+    pub branch: Option<String>,
+    pub pull_request_url: Option<String>,
+    pub execution_session_id: Option<Uuid>,
+    pub review_id: Option<Uuid>,
+    pub status: String,
+    pub details_url: Option<String>,
+    pub started_at: Option<DateTimeWithTimeZone>,
+    pub completed_at: Option<DateTimeWithTimeZone>,
+}
+
+impl CiCheckRunRepository {
+    /// 创建新的 CI 检查结果仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 写入检查结果
+    ///
+    /// 同一项目、提供方和外部ID的记录已存在时更新状态；新数据缺少的可选字段保留原值
+    pub async fn upsert(&self, data: UpsertCiCheckRunData) -> Result<ci_check_run::Model> {
+        let now: DateTimeWithTimeZone = chrono::Utc::now().into();
+        let existing = ci_check_run::Entity::find()
+            .filter(ci_check_run::Column::ProjectId.eq(data.project_id))
+            .filter(ci_check_run::Column::Provider.eq(data.provider.as_str()))
+            .filter(ci_check_run::Column::ExternalId.eq(data.external_id.as_str()))
+            .one(&self.db)
+            .await?;
+
+        match existing {
+            Some(existing) => {
+                let mut run: ci_check_run::ActiveModel = existing.clone().into();
+                run.name = Set(data.name);
+                run.commit_sha = Set(data.commit_sha);
+                run.branch = Set(data.branch.or(existing.branch));
+                run.pull_request_url = Set(data.pull_request_url.or(existing.pull_request_url));
+                run.execution_session_id = Set(data.execution_session_id.or(existing.execution_session_id));
+                run.review_id = Set(data.review_id.or(existing.review_id));
+                run.status = Set(data.status);
+                run.details_url = Set(data.details_url.or(existing.details_url));
+                run.started_at = Set(data.started_at.or(existing.started_at));
+                run.completed_at = Set(data.completed_at);
+                run.updated_at = Set(now);
+                run.update(&self.db).await.map_err(DatabaseError::from)
+            }
+            None => {
+                let check_run_id = Uuid::new_v4();
+                let run = ci_check_run::ActiveModel {
+                    check_run_id: Set(check_run_id),
+                    project_id: Set(data.project_id),
+                    provider: Set(data.provider),
+                    external_id: Set(data.external_id),
+                    name: Set(data.name),
+                    commit_sha: Set(data.commit_sha),
+                    branch: Set(data.branch),
+                    pull_request_url: Set(data.pull_request_url),
+                    execution_session_id: Set(data.execution_session_id),
+                    review_id: Set(data.review_id),
+                    status: Set(data.status),
+                    details_url: Set(data.details_url),
+                    started_at: Set(data.started_at),
+                    completed_at: Set(data.completed_at),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                };
+                ci_check_run::Entity::insert(run).exec(&self.db).await?;
+
+                ci_check_run::Entity::find_by_id(check_run_id)
+                    .one(&self.db)
+                    .await?
+                    .ok_or_else(|| DatabaseError::entity_not_found("CiCheckRun", check_run_id))
+            }
+        }
+    }
+
+    /// 查找执行会话的检查结果（按更新时间降序）
+    pub async fn find_by_session(&self, session_id: Uuid) -> Result<Vec<ci_check_run::Model>> {
+        ci_check_run::Entity::find()
+            .filter(ci_check_run::Column::ExecutionSessionId.eq(session_id))
+            .order_by_desc(ci_check_run::Column::UpdatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找代码审查的检查结果（按更新时间降序）
+    pub async fn find_by_review(&self, review_id: Uuid) -> Result<Vec<ci_check_run::Model>> {
+        ci_check_run::Entity::find()
+            .filter(ci_check_run::Column::ReviewId.eq(review_id))
+            .order_by_desc(ci_check_run::Column::UpdatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找某个提交的检查结果（按更新时间降序）
+    pub async fn find_by_commit(&self, project_id: Uuid, commit_sha: &str) -> Result<Vec<ci_check_run::Model>> {
+        ci_check_run::Entity::find()
+            .filter(ci_check_run::Column::ProjectId.eq(project_id))
+            .filter(ci_check_run::Column::CommitSha.eq(commit_sha))
+            .order_by_desc(ci_check_run::Column::UpdatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找项目最近的检查结果
+    pub async fn find_by_project(&self, project_id: Uuid, limit: u64) -> Result<Vec<ci_check_run::Model>> {
+        ci_check_run::Entity::find()
+            .filter(ci_check_run::Column::ProjectId.eq(project_id))
+            .order_by_desc(ci_check_run::Column::UpdatedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+}
@@ -1,7 +1,10 @@
 //! 代码审查仓储实现
 
 use crate::{entities::code_review, DatabaseConnection, DatabaseError, Result};
+use crate::repository::{AgentSelfAssessmentRepository, ExecutionArtifactRepository};
+use crate::session_diff::SESSION_DIFF_ARTIFACT;
 use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter, QueryOrder};
+use sea_orm::prelude::Expr;
 use uuid::Uuid;
 
 /// 代码审查仓储
@@ -32,9 +35,18 @@ impl CodeReviewRepository {
     }
 
     /// 创建新的代码审查
+    ///
+    /// 执行会话已采集代码差异时自动关联差异工件
     pub async fn create(&self, review_data: CreateCodeReviewData) -> Result<code_review::Model> {
-        let now = chrono::Utc::now().into();
+        let now = chrono::Utc::now();
         let review_id = Uuid::new_v4();
+        let diff_artifact_id = ExecutionArtifactRepository::new(self.db.clone())
+            .find_latest_by_name(review_data.execution_session_id, SESSION_DIFF_ARTIFACT)
+            .await?
+            .map(|artifact| artifact.artifact_id);
+        
+        let priority = codex_multi_agent::ReviewPriority::Normal;
+        let review_deadline = crate::review_sla::initial_deadline(&self.db, review_data.task_id, &priority, now).await?;
         
         let review = code_review::ActiveModel {
             review_id: Set(review_id),
@@ -49,9 +61,16 @@ impl CodeReviewRepository {
             status: Set(review_data.status),
             decision: Set(review_data.decision),
             overall_comment: Set(review_data.overall_comment),
-            created_at: Set(now),
+            created_at: Set(now.into()),
             reviewed_at: Set(None),
-            ..Default::default()
+            diff_artifact_id: Set(diff_artifact_id),
+            priority: Set(crate::review_sla::priority_str(&priority).to_string()),
+            reviewer_user_id: Set(None),
+            assigned_at: Set(Some(now.into())),
+            review_deadline: Set(Some(review_deadline.into())),
+            reminded_at: Set(None),
+            escalated_at: Set(None),
+            reassignment_count: Set(0),
         };
         
         let _result = code_review::Entity::insert(review).exec(&self.db).await?;
@@ -202,6 +221,155 @@ impl CodeReviewRepository {
             .map_err(DatabaseError::from)
     }
     
+    /// 提交审查决策
+    ///
+    /// 批准前检查 CI 状态：项目质量门禁要求所有检查通过时，CI 为红色的审查不能批准
+    pub async fn submit_decision(
+        &self,
+        review_id: Uuid,
+        decision: code_review::ReviewDecision,
+        overall_comment: Option<String>,
+    ) -> Result<code_review::Model> {
+        let review = code_review::Entity::find_by_id(review_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("CodeReview", review_id))?;
+        
+        if decision == code_review::ReviewDecision::Approved {
+            crate::ci_integration::ensure_review_can_be_approved(&self.db, &review).await?;
+            crate::coverage::ensure_review_can_be_approved(&self.db, &review).await?;
+            crate::static_analysis::ensure_review_can_be_approved(&self.db, &review).await?;
+            crate::perf_budget::ensure_review_can_be_approved(&self.db, &review).await?;
+        }
+        
+        let mut review: code_review::ActiveModel = review.into();
+        review.decision = Set(Some(decision.to_string()));
+        if overall_comment.is_some() {
+            review.overall_comment = Set(overall_comment);
+        }
+        review.status = Set(code_review::ReviewStatus::Completed.to_string());
+        review.reviewed_at = Set(Some(chrono::Utc::now().into()));
+        
+        let review = review.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)?;
+        
+        // 审查结果用于校准Agent的自评置信度
+        AgentSelfAssessmentRepository::new(self.db.clone())
+            .record_review_decision(review.execution_session_id, decision.to_string())
+            .await?;
+        
+        Ok(review)
+    }
+    
+    /// 将执行会话的全部代码审查关联到差异工件
+    pub async fn link_diff_artifact(&self, session_id: Uuid, artifact_id: Uuid) -> Result<u64> {
+        let result = code_review::Entity::update_many()
+            .col_expr(code_review::Column::DiffArtifactId, Expr::value(artifact_id))
+            .filter(code_review::Column::ExecutionSessionId.eq(session_id))
+            .exec(&self.db)
+            .await?;
+        
+        Ok(result.rows_affected)
+    }
+    
+    /// 查找仍在进行、有截止时间且尚未升级的代码审查
+    pub async fn find_open_with_deadline(&self) -> Result<Vec<code_review::Model>> {
+        code_review::Entity::find()
+            .filter(code_review::Column::Status.is_in([
+                code_review::ReviewStatus::Pending.to_string(),
+                code_review::ReviewStatus::InProgress.to_string(),
+            ]))
+            .filter(code_review::Column::ReviewDeadline.is_not_null())
+            .filter(code_review::Column::EscalatedAt.is_null())
+            .order_by_asc(code_review::Column::ReviewDeadline)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
+    /// 查找仍在进行的代码审查
+    pub async fn find_open(&self) -> Result<Vec<code_review::Model>> {
+        code_review::Entity::find()
+            .filter(code_review::Column::Status.is_in([
+                code_review::ReviewStatus::Pending.to_string(),
+                code_review::ReviewStatus::InProgress.to_string(),
+            ]))
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
+    /// 更新审查优先级和截止时间
+    pub async fn update_priority(
+        &self,
+        review_id: Uuid,
+        priority: &str,
+        review_deadline: chrono::DateTime<chrono::Utc>,
+    ) -> Result<code_review::Model> {
+        let review = code_review::Entity::find_by_id(review_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("CodeReview", review_id))?;
+        
+        let mut review: code_review::ActiveModel = review.into();
+        review.priority = Set(priority.to_string());
+        review.review_deadline = Set(Some(review_deadline.into()));
+        review.reminded_at = Set(None);
+        review.escalated_at = Set(None);
+        
+        review.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
+    /// 把审查分配给新的审查者，重新开始计时
+    ///
+    /// `reviewer_user_id` 为空时由审查员Agent负责；`automatic` 为真时计入超时改派次数
+    pub async fn reassign(
+        &self,
+        review: code_review::Model,
+        reviewer_agent_id: Uuid,
+        reviewer_user_id: Option<Uuid>,
+        assigned_at: chrono::DateTime<chrono::Utc>,
+        review_deadline: chrono::DateTime<chrono::Utc>,
+        automatic: bool,
+    ) -> Result<code_review::Model> {
+        let reassignment_count = review.reassignment_count + i32::from(automatic);
+        let mut review: code_review::ActiveModel = review.into();
+        review.reviewer_agent_id = Set(reviewer_agent_id);
+        review.reviewer_user_id = Set(reviewer_user_id);
+        review.assigned_at = Set(Some(assigned_at.into()));
+        review.review_deadline = Set(Some(review_deadline.into()));
+        review.reminded_at = Set(None);
+        review.escalated_at = Set(None);
+        review.reassignment_count = Set(reassignment_count);
+        
+        review.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
+    /// 记录发送提醒的时间
+    pub async fn mark_reminded(&self, review_id: Uuid, at: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        code_review::Entity::update_many()
+            .col_expr(code_review::Column::RemindedAt, Expr::value(sea_orm::prelude::DateTimeWithTimeZone::from(at)))
+            .filter(code_review::Column::ReviewId.eq(review_id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+    
+    /// 记录超时且无法改派后的升级时间
+    pub async fn mark_escalated(&self, review_id: Uuid, at: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        code_review::Entity::update_many()
+            .col_expr(code_review::Column::EscalatedAt, Expr::value(sea_orm::prelude::DateTimeWithTimeZone::from(at)))
+            .filter(code_review::Column::ReviewId.eq(review_id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+    
     /// 删除代码审查
     pub async fn delete(&self, review_id: Uuid) -> Result<()> {
         code_review::Entity::delete_by_id(review_id)
@@ -0,0 +1,86 @@
+//! 命令审批审计日志仓储实现
+
+use crate::{entities::command_audit_log, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use uuid::Uuid;
+
+/// 命令审批审计日志仓储
+pub struct CommandAuditLogRepository {
+    db: DatabaseConnection,
+}
+
+/// 创建审计记录的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateCommandAuditData {
+    pub project_id: Option<Uuid>,
+    pub agent_id: Option<Uuid>,
+    pub conversation_id: Option<String>,
+    pub command: String,
+    pub working_dir: String,
+    pub risk_level: String,
+    pub decision: String,
+    pub matched_rules: serde_json::Value,
+}
+
+impl CommandAuditLogRepository {
+    /// 创建新的命令审批审计日志仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 记录一次命令判定
+    pub async fn create(&self, data: CreateCommandAuditData) -> Result<command_audit_log::Model> {
+        let audit_id = Uuid::new_v4();
+        let entry = command_audit_log::ActiveModel {
+            audit_id: Set(audit_id),
+            project_id: Set(data.project_id),
+            agent_id: Set(data.agent_id),
+            conversation_id: Set(data.conversation_id),
+            command: Set(data.command),
+            working_dir: Set(data.working_dir),
+            risk_level: Set(data.risk_level),
+            decision: Set(data.decision),
+            matched_rules: Set(data.matched_rules),
+            created_at: Set(chrono::Utc::now().into()),
+        };
+
+        command_audit_log::Entity::insert(entry).exec(&self.db).await?;
+
+        command_audit_log::Entity::find_by_id(audit_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("CommandAuditLog", audit_id))
+    }
+
+    /// 查找项目最近的审计记录（按时间倒序）
+    pub async fn find_by_project(&self, project_id: Uuid, limit: u64) -> Result<Vec<command_audit_log::Model>> {
+        command_audit_log::Entity::find()
+            .filter(command_audit_log::Column::ProjectId.eq(project_id))
+            .order_by_desc(command_audit_log::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找Agent最近的审计记录（按时间倒序）
+    pub async fn find_by_agent(&self, agent_id: Uuid, limit: u64) -> Result<Vec<command_audit_log::Model>> {
+        command_audit_log::Entity::find()
+            .filter(command_audit_log::Column::AgentId.eq(agent_id))
+            .order_by_desc(command_audit_log::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找对话中的审计记录（按时间升序）
+    pub async fn find_by_conversation(&self, conversation_id: &str) -> Result<Vec<command_audit_log::Model>> {
+        command_audit_log::Entity::find()
+            .filter(command_audit_log::Column::ConversationId.eq(conversation_id))
+            .order_by_asc(command_audit_log::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+}
@@ -3,10 +3,12 @@
 use crate::entities::{
     conflict::{self, Entity as Conflict, ActiveModel, Model, ConflictType, ConflictSeverity, ConflictStatus},
     human_decision::{self, Entity as HumanDecision},
+    task,
 };
 use crate::error::{DatabaseError, Result};
+use crate::tenant_scope::{scoped_condition, TenantScope};
 use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DatabaseConnection, EntityTrait,
     QueryFilter, QueryOrder, PaginatorTrait,
 };
 use serde_json::Value as JsonValue;
@@ -15,16 +17,33 @@ use uuid::Uuid;
 /// 冲突处理仓储
 pub struct ConflictRepository {
     db: DatabaseConnection,
+    scope: Option<TenantScope>,
 }
 
 impl ConflictRepository {
     /// 创建新的冲突仓储实例
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self { db, scope: None }
     }
 
-    /// 创建新的冲突
+    /// 限定只能访问租户范围内项目的冲突，未关联项目的冲突不可见
+    pub fn with_scope(mut self, scope: TenantScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    fn tenant_condition(&self) -> Condition {
+        scoped_condition(self.scope.as_ref(), |scope| scope.project_ref_condition(conflict::Column::ProjectId))
+    }
+
+    /// 创建新的冲突，设置了租户范围时必须关联范围内的项目
     pub async fn create(&self, conflict_data: CreateConflictData) -> Result<Model> {
+        if let Some(scope) = &self.scope {
+            let project_id = conflict_data
+                .project_id
+                .ok_or_else(|| DatabaseError::validation("限定租户范围时冲突必须关联项目"))?;
+            scope.ensure_project(&self.db, project_id).await?;
+        }
         let conflict = ActiveModel {
             conflict_id: Set(Uuid::new_v4()),
             conflict_type: Set(conflict_data.conflict_type.to_string()),
@@ -34,6 +53,7 @@ impl ConflictRepository {
             related_entities: Set(conflict_data.related_entities),
             affected_tasks: Set(conflict_data.affected_tasks),
             affected_agents: Set(conflict_data.affected_agents),
+            project_id: Set(conflict_data.project_id),
             status: Set(ConflictStatus::Detected.to_string()),
             escalated_to_human: Set(false),
             assigned_user_id: Set(None),
@@ -51,6 +71,7 @@ impl ConflictRepository {
     /// 根据ID查找冲突
     pub async fn find_by_id(&self, conflict_id: Uuid) -> Result<Option<Model>> {
         Conflict::find_by_id(conflict_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await
             .map_err(DatabaseError::from)
@@ -59,6 +80,7 @@ impl ConflictRepository {
     /// 根据类型查找冲突
     pub async fn find_by_type(&self, conflict_type: ConflictType) -> Result<Vec<Model>> {
         Conflict::find()
+            .filter(self.tenant_condition())
             .filter(conflict::Column::ConflictType.eq(conflict_type.to_string()))
             .order_by_desc(conflict::Column::DetectedAt)
             .all(&self.db)
@@ -69,6 +91,7 @@ impl ConflictRepository {
     /// 根据严重性查找冲突
     pub async fn find_by_severity(&self, severity: ConflictSeverity) -> Result<Vec<Model>> {
         Conflict::find()
+            .filter(self.tenant_condition())
             .filter(conflict::Column::Severity.eq(severity.to_string()))
             .order_by_desc(conflict::Column::DetectedAt)
             .all(&self.db)
@@ -79,6 +102,7 @@ impl ConflictRepository {
     /// 根据状态查找冲突
     pub async fn find_by_status(&self, status: ConflictStatus) -> Result<Vec<Model>> {
         Conflict::find()
+            .filter(self.tenant_condition())
             .filter(conflict::Column::Status.eq(status.to_string()))
             .order_by_desc(conflict::Column::DetectedAt)
             .all(&self.db)
@@ -87,10 +111,18 @@ impl ConflictRepository {
     }
 
     /// 查找未解决的冲突
+    ///
+    /// 按未结束的状态枚举查询而不是排除已结束的状态，`!=` 条件无法使用 `idx_conflicts_status_severity`
     pub async fn find_unresolved(&self) -> Result<Vec<Model>> {
+        let open_statuses = [
+            ConflictStatus::Detected,
+            ConflictStatus::Analyzing,
+            ConflictStatus::Escalated,
+            ConflictStatus::Resolving,
+        ];
         Conflict::find()
-            .filter(conflict::Column::Status.ne(ConflictStatus::Resolved.to_string()))
-            .filter(conflict::Column::Status.ne(ConflictStatus::Ignored.to_string()))
+            .filter(self.tenant_condition())
+            .filter(conflict::Column::Status.is_in(open_statuses.iter().map(|status| status.to_string())))
             .order_by_desc(conflict::Column::Severity)
             .order_by_desc(conflict::Column::DetectedAt)
             .all(&self.db)
@@ -101,6 +133,7 @@ impl ConflictRepository {
     /// 查找需要人工干预的冲突
     pub async fn find_requiring_human_intervention(&self) -> Result<Vec<Model>> {
         Conflict::find()
+            .filter(self.tenant_condition())
             .filter(conflict::Column::EscalatedToHuman.eq(true))
             .filter(conflict::Column::Status.eq(ConflictStatus::Escalated.to_string()))
             .order_by_desc(conflict::Column::Severity)
@@ -113,6 +146,7 @@ impl ConflictRepository {
     /// 查找分配给用户的冲突
     pub async fn find_assigned_to_user(&self, user_id: Uuid) -> Result<Vec<Model>> {
         Conflict::find()
+            .filter(self.tenant_condition())
             .filter(conflict::Column::AssignedUserId.eq(user_id))
             .filter(conflict::Column::Status.ne(ConflictStatus::Resolved.to_string()))
             .order_by_desc(conflict::Column::Severity)
@@ -161,6 +195,23 @@ impl ConflictRepository {
         conflict_active.update(&self.db).await.map_err(DatabaseError::from)
     }
 
+    /// 记录自动解决尝试（不改变冲突状态）
+    pub async fn record_resolution_attempt(
+        &self,
+        conflict_id: Uuid,
+        resolution_strategy: String,
+        resolution_note: Option<String>
+    ) -> Result<Model> {
+        let conflict = self.find_by_id(conflict_id).await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Conflict", conflict_id.to_string()))?;
+
+        let mut conflict_active: ActiveModel = conflict.into();
+        conflict_active.resolution_strategy = Set(Some(resolution_strategy));
+        conflict_active.resolution_note = Set(resolution_note);
+
+        conflict_active.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
     /// 忽略冲突
     pub async fn ignore_conflict(&self, conflict_id: Uuid, reason: String) -> Result<Model> {
         let conflict = self.find_by_id(conflict_id).await?
@@ -192,7 +243,7 @@ impl ConflictRepository {
         page_size: u64,
         filter: Option<ConflictFilter>
     ) -> Result<(Vec<Model>, u64)> {
-        let mut query = Conflict::find();
+        let mut query = Conflict::find().filter(self.tenant_condition());
 
         if let Some(filter) = filter {
             if let Some(conflict_type) = filter.conflict_type {
@@ -210,6 +261,22 @@ impl ConflictRepository {
             if let Some(escalated_to_human) = filter.escalated_to_human {
                 query = query.filter(conflict::Column::EscalatedToHuman.eq(escalated_to_human));
             }
+
+            // 冲突没有直接关联项目，按受影响的任务归属过滤
+            if let Some(project_id) = filter.project_id {
+                let tasks = task::Entity::find()
+                    .filter(task::Column::ProjectId.eq(project_id))
+                    .all(&self.db)
+                    .await
+                    .map_err(DatabaseError::from)?;
+                if tasks.is_empty() {
+                    return Ok((Vec::new(), 0));
+                }
+                let condition = tasks.iter().fold(Condition::any(), |condition, task| {
+                    condition.add(conflict::Column::AffectedTasks.contains(format!("\"{}\"", task.task_id)))
+                });
+                query = query.filter(condition);
+            }
         }
 
         let paginator = query
@@ -225,7 +292,11 @@ impl ConflictRepository {
 
     /// 获取冲突统计信息
     pub async fn get_conflict_statistics(&self) -> Result<ConflictStatistics> {
-        let all_conflicts = Conflict::find().all(&self.db).await.map_err(DatabaseError::from)?;
+        let all_conflicts = Conflict::find()
+            .filter(self.tenant_condition())
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)?;
 
         let total_conflicts = all_conflicts.len() as i64;
         let resolved_conflicts = all_conflicts.iter()
@@ -283,6 +354,7 @@ impl ConflictRepository {
     /// 查找影响特定任务的冲突
     pub async fn find_affecting_task(&self, task_id: &str) -> Result<Vec<Model>> {
         Conflict::find()
+            .filter(self.tenant_condition())
             .filter(conflict::Column::AffectedTasks.contains(&format!("\"{}\"", task_id)))
             .filter(conflict::Column::Status.ne(ConflictStatus::Resolved.to_string()))
             .order_by_desc(conflict::Column::Severity)
@@ -294,6 +366,7 @@ impl ConflictRepository {
     /// 查找影响特定Agent的冲突
     pub async fn find_affecting_agent(&self, agent_id: &str) -> Result<Vec<Model>> {
         Conflict::find()
+            .filter(self.tenant_condition())
             .filter(conflict::Column::AffectedAgents.contains(&format!("\"{}\"", agent_id)))
             .filter(conflict::Column::Status.ne(ConflictStatus::Resolved.to_string()))
             .order_by_desc(conflict::Column::Severity)
@@ -323,13 +396,18 @@ impl ConflictRepository {
         }
     }
 
-    /// 删除冲突
+    /// 删除冲突，设置了租户范围时删除范围外的记录返回未找到
     pub async fn delete(&self, conflict_id: Uuid) -> Result<()> {
-        Conflict::delete_by_id(conflict_id)
+        let result = Conflict::delete_many()
+            .filter(conflict::Column::ConflictId.eq(conflict_id))
+            .filter(self.tenant_condition())
             .exec(&self.db)
             .await
             .map_err(DatabaseError::from)?;
         
+        if self.scope.is_some() && result.rows_affected == 0 {
+            return Err(DatabaseError::entity_not_found("Conflict", conflict_id));
+        }
         Ok(())
     }
 }
@@ -344,6 +422,8 @@ pub struct CreateConflictData {
     pub related_entities: JsonValue,
     pub affected_tasks: JsonValue,
     pub affected_agents: JsonValue,
+    /// 所属项目，设置了租户范围的仓储要求必填
+    pub project_id: Option<Uuid>,
 }
 
 /// 冲突查询过滤器
@@ -353,6 +433,8 @@ pub struct ConflictFilter {
     pub severity: Option<ConflictSeverity>,
     pub status: Option<ConflictStatus>,
     pub escalated_to_human: Option<bool>,
+    /// 只返回影响该项目任务的冲突
+    pub project_id: Option<Uuid>,
 }
 
 /// 冲突统计信息
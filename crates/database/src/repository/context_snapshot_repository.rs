@@ -0,0 +1,70 @@
+//! 项目上下文快照仓储实现
+
+use crate::{entities::context_snapshot, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{EntityTrait, Set, ColumnTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+/// 项目上下文快照仓储
+pub struct ContextSnapshotRepository {
+    db: DatabaseConnection,
+}
+
+impl ContextSnapshotRepository {
+    /// 创建新的项目上下文快照仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 记录新的上下文快照
+    pub async fn create(
+        &self,
+        project_id: Uuid,
+        document_id: Option<Uuid>,
+        context: serde_json::Value,
+    ) -> Result<context_snapshot::Model> {
+        let snapshot_id = Uuid::new_v4();
+
+        let snapshot = context_snapshot::ActiveModel {
+            snapshot_id: Set(snapshot_id),
+            project_id: Set(project_id),
+            document_id: Set(document_id),
+            context: Set(context),
+            created_at: Set(chrono::Utc::now().into()),
+        };
+
+        context_snapshot::Entity::insert(snapshot).exec(&self.db).await?;
+
+        context_snapshot::Entity::find_by_id(snapshot_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ContextSnapshot", snapshot_id))
+    }
+
+    /// 根据ID查找快照
+    pub async fn find_by_id(&self, snapshot_id: Uuid) -> Result<Option<context_snapshot::Model>> {
+        context_snapshot::Entity::find_by_id(snapshot_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找项目的所有快照（按时间倒序）
+    pub async fn find_by_project(&self, project_id: Uuid) -> Result<Vec<context_snapshot::Model>> {
+        context_snapshot::Entity::find()
+            .filter(context_snapshot::Column::ProjectId.eq(project_id))
+            .order_by_desc(context_snapshot::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找项目最近一次快照
+    pub async fn find_latest_by_project(&self, project_id: Uuid) -> Result<Option<context_snapshot::Model>> {
+        context_snapshot::Entity::find()
+            .filter(context_snapshot::Column::ProjectId.eq(project_id))
+            .order_by_desc(context_snapshot::Column::CreatedAt)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+}
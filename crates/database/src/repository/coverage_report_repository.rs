@@ -0,0 +1,109 @@
+//! 覆盖率报告仓储实现
+
+use crate::{entities::coverage_report, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 覆盖率报告仓储
+pub struct CoverageReportRepository {
+    db: DatabaseConnection,
+}
+
+/// 保存覆盖率报告的数据结构
+#[derive(Debug, Clone)]
+pub struct SaveCoverageReportData {
+    pub project_id: Uuid,
+    pub execution_session_id: Uuid,
+    pub format: String,
+    pub commit_sha: Option<String>,
+    pub base_report_id: Option<Uuid>,
+    pub lines_total: i64,
+    pub lines_covered: i64,
+    pub line_rate: f64,
+    pub files: JsonValue,
+}
+
+impl CoverageReportRepository {
+    /// 创建新的覆盖率报告仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 保存执行会话的覆盖率报告，会话已有报告时覆盖原记录
+    pub async fn save(&self, data: SaveCoverageReportData) -> Result<coverage_report::Model> {
+        let now = chrono::Utc::now();
+        let existing = self.find_by_session(data.execution_session_id).await?;
+
+        let is_new = existing.is_none();
+        let mut report = match existing {
+            Some(existing) => existing.into(),
+            None => coverage_report::ActiveModel {
+                report_id: Set(Uuid::new_v4()),
+                project_id: Set(data.project_id),
+                execution_session_id: Set(data.execution_session_id),
+                ..Default::default()
+            },
+        };
+        report.format = Set(data.format);
+        report.commit_sha = Set(data.commit_sha);
+        report.base_report_id = Set(data.base_report_id);
+        report.lines_total = Set(data.lines_total);
+        report.lines_covered = Set(data.lines_covered);
+        report.line_rate = Set(data.line_rate);
+        report.files = Set(data.files);
+        report.created_at = Set(now.into());
+        if is_new {
+            report.insert(&self.db).await.map_err(DatabaseError::from)
+        } else {
+            report.update(&self.db).await.map_err(DatabaseError::from)
+        }
+    }
+
+    /// 根据ID查找覆盖率报告
+    pub async fn find_by_id(&self, report_id: Uuid) -> Result<Option<coverage_report::Model>> {
+        coverage_report::Entity::find_by_id(report_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找执行会话的覆盖率报告
+    pub async fn find_by_session(&self, session_id: Uuid) -> Result<Option<coverage_report::Model>> {
+        coverage_report::Entity::find()
+            .filter(coverage_report::Column::ExecutionSessionId.eq(session_id))
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找项目在指定提交上最近一次的覆盖率报告，可排除某个执行会话
+    pub async fn find_latest_by_commit(
+        &self,
+        project_id: Uuid,
+        commit_sha: &str,
+        exclude_session_id: Option<Uuid>,
+    ) -> Result<Option<coverage_report::Model>> {
+        let mut query = coverage_report::Entity::find()
+            .filter(coverage_report::Column::ProjectId.eq(project_id))
+            .filter(coverage_report::Column::CommitSha.eq(commit_sha));
+        if let Some(session_id) = exclude_session_id {
+            query = query.filter(coverage_report::Column::ExecutionSessionId.ne(session_id));
+        }
+        query
+            .order_by_desc(coverage_report::Column::CreatedAt)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找项目最近一次的覆盖率报告
+    pub async fn find_latest_by_project(&self, project_id: Uuid) -> Result<Option<coverage_report::Model>> {
+        coverage_report::Entity::find()
+            .filter(coverage_report::Column::ProjectId.eq(project_id))
+            .order_by_desc(coverage_report::Column::CreatedAt)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+}
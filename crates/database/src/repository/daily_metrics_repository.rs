@@ -0,0 +1,147 @@
+//! 每日指标仓储实现
+
+use crate::{
+    entities::{agent_daily_metric, project_daily_metric},
+    metrics_rollup::{compute_day, date_key, parse_date_key, rolled_up_dates, DailyCounts},
+    DatabaseConnection, DatabaseError, Result,
+};
+use chrono::{Duration, NaiveDate};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+
+/// 查询的最大天数
+pub const MAX_RANGE_DAYS: i64 = 366;
+
+/// 一天的指标
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyMetrics {
+    pub date: NaiveDate,
+    #[serde(flatten)]
+    pub counts: DailyCounts,
+    /// 是否来自汇总表，false 表示实时计算
+    pub from_rollup: bool,
+}
+
+/// 每日指标仓储，优先读取汇总表，尚未汇总的日期实时计算
+pub struct DailyMetricsRepository {
+    db: DatabaseConnection,
+}
+
+impl DailyMetricsRepository {
+    /// 创建新的每日指标仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 项目在 `from` 至 `to`（含）每天的指标，没有数据的日期指标为零
+    pub async fn project_daily(
+        &self,
+        project_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+        cost_per_1k_tokens: Option<f64>,
+    ) -> Result<Vec<DailyMetrics>> {
+        validate_range(from, to)?;
+        let rows = project_daily_metric::Entity::find()
+            .filter(project_daily_metric::Column::ProjectId.eq(project_id))
+            .filter(project_daily_metric::Column::MetricDate.gte(date_key(from)))
+            .filter(project_daily_metric::Column::MetricDate.lte(date_key(to)))
+            .order_by_asc(project_daily_metric::Column::MetricDate)
+            .all(&self.db)
+            .await?;
+        let mut stored = BTreeMap::new();
+        for row in rows {
+            stored.insert(
+                parse_date_key(&row.metric_date)?,
+                DailyCounts {
+                    tasks_completed: row.tasks_completed,
+                    tasks_failed: row.tasks_failed,
+                    total_tokens: row.total_tokens,
+                    total_cost: row.total_cost,
+                    error_count: row.error_count,
+                },
+            );
+        }
+
+        self.merge(from, to, stored, cost_per_1k_tokens, |aggregates| {
+            aggregates.projects.get(&project_id).cloned().unwrap_or_default()
+        })
+        .await
+    }
+
+    /// Agent在 `from` 至 `to`（含）每天的指标，各项目的指标合并计算
+    pub async fn agent_daily(
+        &self,
+        agent_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+        cost_per_1k_tokens: Option<f64>,
+    ) -> Result<Vec<DailyMetrics>> {
+        validate_range(from, to)?;
+        let rows = agent_daily_metric::Entity::find()
+            .filter(agent_daily_metric::Column::AgentId.eq(agent_id))
+            .filter(agent_daily_metric::Column::MetricDate.gte(date_key(from)))
+            .filter(agent_daily_metric::Column::MetricDate.lte(date_key(to)))
+            .order_by_asc(agent_daily_metric::Column::MetricDate)
+            .all(&self.db)
+            .await?;
+        let mut stored: BTreeMap<NaiveDate, DailyCounts> = BTreeMap::new();
+        for row in rows {
+            stored.entry(parse_date_key(&row.metric_date)?).or_default().add(&DailyCounts {
+                tasks_completed: row.tasks_completed,
+                tasks_failed: row.tasks_failed,
+                total_tokens: row.total_tokens,
+                total_cost: row.total_cost,
+                error_count: row.error_count,
+            });
+        }
+
+        self.merge(from, to, stored, cost_per_1k_tokens, |aggregates| {
+            let mut counts = DailyCounts::default();
+            for ((id, _), agent_counts) in &aggregates.agents {
+                if *id == agent_id {
+                    counts.add(agent_counts);
+                }
+            }
+            counts
+        })
+        .await
+    }
+
+    /// 已汇总的日期使用汇总表中的指标，其余日期实时计算
+    async fn merge(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        mut stored: BTreeMap<NaiveDate, DailyCounts>,
+        cost_per_1k_tokens: Option<f64>,
+        select: impl Fn(&crate::metrics_rollup::DayAggregates) -> DailyCounts,
+    ) -> Result<Vec<DailyMetrics>> {
+        let rolled_up: HashSet<NaiveDate> = rolled_up_dates(&self.db, from, to).await?.into_iter().collect();
+        let mut metrics = Vec::new();
+        let mut date = from;
+        while date <= to {
+            let from_rollup = rolled_up.contains(&date);
+            let counts = if from_rollup {
+                stored.remove(&date).unwrap_or_default()
+            } else {
+                select(&compute_day(&self.db, date, cost_per_1k_tokens).await?)
+            };
+            metrics.push(DailyMetrics { date, counts, from_rollup });
+            date += Duration::days(1);
+        }
+        Ok(metrics)
+    }
+}
+
+fn validate_range(from: NaiveDate, to: NaiveDate) -> Result<()> {
+    if from > to {
+        return Err(DatabaseError::validation("开始日期不能晚于结束日期"));
+    }
+    if (to - from).num_days() >= MAX_RANGE_DAYS {
+        return Err(DatabaseError::validation(format!("查询范围不能超过{}天", MAX_RANGE_DAYS)));
+    }
+    Ok(())
+}
@@ -1,12 +1,19 @@
 //! 领域事件仓储实现
 
-use crate::{entities::domain_event, DatabaseConnection, DatabaseError, Result};
-use sea_orm::{EntityTrait, Set, ColumnTrait, QueryFilter, QueryOrder};
+use crate::{
+    entities::domain_event,
+    payload_storage::{self, PayloadPolicy, PayloadStore, DOMAIN_EVENTS},
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::{ActiveModelTrait, EntityTrait, Set, ColumnTrait, QueryFilter, QueryOrder, QuerySelect};
 use uuid::Uuid;
 
 /// 领域事件仓储
+///
+/// 较大的 `event_data` 写入时压缩，读取时自动还原，见 [`crate::payload_storage`]
 pub struct DomainEventRepository {
     db: DatabaseConnection,
+    payloads: Option<PayloadStore>,
 }
 
 /// 创建领域事件的数据结构
@@ -22,11 +29,59 @@ pub struct CreateDomainEventData {
 impl DomainEventRepository {
     /// 创建新的领域事件仓储实例
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self { db, payloads: None }
+    }
+
+    /// 挂载负载存储，超大的事件数据转存到工件存储，读取时从工件存储还原
+    pub fn with_payload_store(mut self, payloads: PayloadStore) -> Self {
+        self.payloads = Some(payloads);
+        self
+    }
+
+    /// 编码事件数据
+    async fn encode(&self, event_id: Uuid, event_data: serde_json::Value) -> Result<serde_json::Value> {
+        match &self.payloads {
+            Some(payloads) => payloads.encode(DOMAIN_EVENTS, event_id, event_data).await,
+            None => payload_storage::compress(event_data, &PayloadPolicy::default()),
+        }
+    }
+
+    /// 还原事件数据；没有挂载负载存储时转存的数据保留引用
+    async fn decode(&self, mut event: domain_event::Model) -> Result<domain_event::Model> {
+        event.event_data = match &self.payloads {
+            Some(payloads) => payloads.decode(event.event_data).await?,
+            None => payload_storage::inflate(event.event_data)?,
+        };
+        Ok(event)
+    }
+
+    async fn decode_all(&self, events: Vec<domain_event::Model>) -> Result<Vec<domain_event::Model>> {
+        let mut decoded = Vec::with_capacity(events.len());
+        for event in events {
+            decoded.push(self.decode(event).await?);
+        }
+        Ok(decoded)
     }
 
     /// 创建新的领域事件
     pub async fn create(&self, event_data: CreateDomainEventData) -> Result<domain_event::Model> {
+        self.insert(event_data, None).await
+    }
+
+    /// 创建带关联ID的领域事件，同一任务生命周期内的事件共享关联ID
+    pub async fn create_correlated(
+        &self,
+        event_data: CreateDomainEventData,
+        correlation_id: Uuid,
+    ) -> Result<domain_event::Model> {
+        self.insert(event_data, Some(correlation_id)).await
+    }
+
+    async fn insert(
+        &self,
+        event_data: CreateDomainEventData,
+        correlation_id: Option<Uuid>,
+    ) -> Result<domain_event::Model> {
         let now = chrono::Utc::now().into();
         let event_id = Uuid::new_v4();
         
@@ -35,8 +90,9 @@ impl DomainEventRepository {
             aggregate_type: Set(event_data.aggregate_type),
             aggregate_id: Set(event_data.aggregate_id),
             event_type: Set(event_data.event_type),
-            event_data: Set(event_data.event_data),
+            event_data: Set(self.encode(event_id, event_data.event_data).await?),
             event_version: Set(event_data.event_version),
+            correlation_id: Set(correlation_id),
             occurred_at: Set(now),
             is_processed: Set(false),
             ..Default::default()
@@ -44,48 +100,57 @@ impl DomainEventRepository {
         
         let _result = domain_event::Entity::insert(event).exec(&self.db).await?;
         
-        domain_event::Entity::find_by_id(event_id)
-            .one(&self.db)
+        self.find_by_id(event_id)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("DomainEvent", event_id))
     }
     
     /// 根据ID查找领域事件
     pub async fn find_by_id(&self, event_id: Uuid) -> Result<Option<domain_event::Model>> {
-        domain_event::Entity::find_by_id(event_id)
-            .one(&self.db)
-            .await
-            .map_err(DatabaseError::from)
+        match domain_event::Entity::find_by_id(event_id).one(&self.db).await? {
+            Some(event) => Ok(Some(self.decode(event).await?)),
+            None => Ok(None),
+        }
     }
     
     /// 根据聚合ID查找事件
     pub async fn find_by_aggregate_id(&self, aggregate_id: Uuid) -> Result<Vec<domain_event::Model>> {
-        domain_event::Entity::find()
+        let events = domain_event::Entity::find()
             .filter(domain_event::Column::AggregateId.eq(aggregate_id))
             .order_by_asc(domain_event::Column::EventVersion)
             .all(&self.db)
-            .await
-            .map_err(DatabaseError::from)
+            .await?;
+        self.decode_all(events).await
+    }
+    
+    /// 根据关联ID查找事件，按发生时间排序
+    pub async fn find_by_correlation_id(&self, correlation_id: Uuid) -> Result<Vec<domain_event::Model>> {
+        let events = domain_event::Entity::find()
+            .filter(domain_event::Column::CorrelationId.eq(correlation_id))
+            .order_by_asc(domain_event::Column::OccurredAt)
+            .all(&self.db)
+            .await?;
+        self.decode_all(events).await
     }
     
     /// 根据聚合类型查找事件
     pub async fn find_by_aggregate_type(&self, aggregate_type: &str) -> Result<Vec<domain_event::Model>> {
-        domain_event::Entity::find()
+        let events = domain_event::Entity::find()
             .filter(domain_event::Column::AggregateType.eq(aggregate_type))
             .order_by_desc(domain_event::Column::OccurredAt)
             .all(&self.db)
-            .await
-            .map_err(DatabaseError::from)
+            .await?;
+        self.decode_all(events).await
     }
     
     /// 根据事件类型查找事件
     pub async fn find_by_event_type(&self, event_type: &str) -> Result<Vec<domain_event::Model>> {
-        domain_event::Entity::find()
+        let events = domain_event::Entity::find()
             .filter(domain_event::Column::EventType.eq(event_type))
             .order_by_desc(domain_event::Column::OccurredAt)
             .all(&self.db)
-            .await
-            .map_err(DatabaseError::from)
+            .await?;
+        self.decode_all(events).await
     }
     
     /// 根据聚合ID和版本范围查找事件
@@ -103,11 +168,11 @@ impl DomainEventRepository {
             query = query.filter(domain_event::Column::EventVersion.lte(to_ver));
         }
         
-        query
+        let events = query
             .order_by_asc(domain_event::Column::EventVersion)
             .all(&self.db)
-            .await
-            .map_err(DatabaseError::from)
+            .await?;
+        self.decode_all(events).await
     }
     
     /// 批量创建事件
@@ -125,7 +190,7 @@ impl DomainEventRepository {
                 aggregate_type: Set(event_data.aggregate_type),
                 aggregate_id: Set(event_data.aggregate_id),
                 event_type: Set(event_data.event_type),
-                event_data: Set(event_data.event_data),
+                event_data: Set(self.encode(event_id, event_data.event_data).await?),
                 event_version: Set(event_data.event_version),
                 occurred_at: Set(now),
                 is_processed: Set(false),
@@ -138,11 +203,40 @@ impl DomainEventRepository {
         domain_event::Entity::insert_many(active_models).exec(&self.db).await?;
         
         // 返回插入的记录
-        domain_event::Entity::find()
+        let events = domain_event::Entity::find()
             .filter(domain_event::Column::EventId.is_in(event_ids))
             .all(&self.db)
-            .await
-            .map_err(DatabaseError::from)
+            .await?;
+        self.decode_all(events).await
+    }
+    
+    /// 查找尚未处理的事件，按发生时间排序
+    pub async fn find_unprocessed(&self, limit: u64) -> Result<Vec<domain_event::Model>> {
+        let events = domain_event::Entity::find()
+            .filter(domain_event::Column::IsProcessed.eq(false))
+            .order_by_asc(domain_event::Column::OccurredAt)
+            .limit(limit)
+            .all(&self.db)
+            .await?;
+        self.decode_all(events).await
+    }
+    
+    /// 标记事件已处理，记录本次处理的错误信息
+    pub async fn mark_processed(&self, event_id: Uuid, error_message: Option<String>) -> Result<domain_event::Model> {
+        let event = domain_event::Entity::find_by_id(event_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("DomainEvent", event_id))?;
+        
+        let attempts = event.processing_attempts;
+        let mut event: domain_event::ActiveModel = event.into();
+        event.is_processed = Set(true);
+        event.processed_at = Set(Some(chrono::Utc::now().into()));
+        event.processing_attempts = Set(attempts + 1);
+        event.error_message = Set(error_message);
+        
+        let event = event.update(&self.db).await?;
+        self.decode(event).await
     }
     
     /// 获取聚合的最新版本号
@@ -156,11 +250,14 @@ impl DomainEventRepository {
         Ok(latest_event.map(|e| e.event_version).unwrap_or(0))
     }
     
-    /// 删除领域事件（谨慎使用）
+    /// 删除领域事件（谨慎使用），挂载了负载存储时一并释放转存的数据
     pub async fn delete(&self, event_id: Uuid) -> Result<()> {
         domain_event::Entity::delete_by_id(event_id)
             .exec(&self.db)
             .await?;
+        if let Some(payloads) = &self.payloads {
+            payloads.release(DOMAIN_EVENTS, event_id).await?;
+        }
         
         Ok(())
     }
@@ -0,0 +1,99 @@
+//! 语义检索向量仓储实现
+
+use crate::{
+    entities::embedding::{self, encode_vector, EmbeddingSourceType},
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+/// 语义检索向量仓储
+pub struct EmbeddingRepository {
+    db: DatabaseConnection,
+}
+
+/// 写入向量的数据结构
+#[derive(Debug, Clone)]
+pub struct UpsertEmbeddingData {
+    pub project_id: Uuid,
+    pub source_type: EmbeddingSourceType,
+    pub source_id: Uuid,
+    pub chunk_index: i32,
+    pub content: String,
+    pub content_hash: String,
+    pub model: String,
+    pub vector: Vec<f32>,
+}
+
+impl EmbeddingRepository {
+    /// 创建新的语义检索向量仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 写入分块向量，同一来源的同一分块已存在时覆盖
+    pub async fn upsert(&self, data: UpsertEmbeddingData) -> Result<embedding::Model> {
+        let now = chrono::Utc::now();
+        let existing = embedding::Entity::find()
+            .filter(embedding::Column::SourceType.eq(data.source_type.as_str()))
+            .filter(embedding::Column::SourceId.eq(data.source_id))
+            .filter(embedding::Column::ChunkIndex.eq(data.chunk_index))
+            .one(&self.db)
+            .await?;
+
+        let is_new = existing.is_none();
+        let mut active: embedding::ActiveModel = match existing {
+            Some(model) => model.into(),
+            None => embedding::ActiveModel {
+                embedding_id: Set(Uuid::new_v4()),
+                source_type: Set(data.source_type.to_string()),
+                source_id: Set(data.source_id),
+                chunk_index: Set(data.chunk_index),
+                created_at: Set(now.into()),
+                ..Default::default()
+            },
+        };
+        active.project_id = Set(data.project_id);
+        active.content = Set(data.content);
+        active.content_hash = Set(data.content_hash);
+        active.model = Set(data.model);
+        active.vector = Set(encode_vector(&data.vector));
+        active.updated_at = Set(now.into());
+
+        if is_new {
+            active.insert(&self.db).await.map_err(DatabaseError::from)
+        } else {
+            active.update(&self.db).await.map_err(DatabaseError::from)
+        }
+    }
+
+    /// 查找项目的向量，`source_types` 为空时返回全部来源
+    pub async fn find_by_project(
+        &self,
+        project_id: Uuid,
+        source_types: &[EmbeddingSourceType],
+    ) -> Result<Vec<embedding::Model>> {
+        let mut query = embedding::Entity::find().filter(embedding::Column::ProjectId.eq(project_id));
+        if !source_types.is_empty() {
+            query = query.filter(
+                embedding::Column::SourceType.is_in(source_types.iter().map(|source_type| source_type.as_str())),
+            );
+        }
+
+        query
+            .order_by_asc(embedding::Column::SourceType)
+            .order_by_asc(embedding::Column::ChunkIndex)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 删除向量
+    pub async fn delete(&self, embedding_id: Uuid) -> Result<()> {
+        embedding::Entity::delete_by_id(embedding_id)
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+//! 评估结果仓储实现
+
+use crate::{entities::eval_result, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+/// 评估结果仓储
+pub struct EvalResultRepository {
+    db: DatabaseConnection,
+}
+
+impl EvalResultRepository {
+    /// 创建新的评估结果仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 保存一个变体在一个用例上的结果
+    pub async fn create(&self, data: CreateEvalResultData) -> Result<eval_result::Model> {
+        let result_id = Uuid::new_v4();
+
+        let result = eval_result::ActiveModel {
+            result_id: Set(result_id),
+            run_id: Set(data.run_id),
+            variant_name: Set(data.variant_name),
+            case_name: Set(data.case_name),
+            model: Set(data.model),
+            prompt: Set(data.prompt),
+            output: Set(data.output),
+            error_message: Set(data.error_message),
+            score: Set(data.score),
+            passed: Set(data.passed),
+            checks: Set(data.checks),
+            tokens_used: Set(data.tokens_used),
+            latency_ms: Set(data.latency_ms),
+            created_at: Set(chrono::Utc::now().into()),
+        };
+
+        eval_result::Entity::insert(result).exec(&self.db).await?;
+
+        eval_result::Entity::find_by_id(result_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("EvalResult", result_id))
+    }
+
+    /// 查找评估运行的所有结果（按保存顺序）
+    pub async fn find_by_run(&self, run_id: Uuid) -> Result<Vec<eval_result::Model>> {
+        eval_result::Entity::find()
+            .filter(eval_result::Column::RunId.eq(run_id))
+            .order_by_asc(eval_result::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+}
+
+/// 创建评估结果数据
+#[derive(Debug, Clone)]
+pub struct CreateEvalResultData {
+    pub run_id: Uuid,
+    pub variant_name: String,
+    pub case_name: String,
+    pub model: String,
+    pub prompt: String,
+    pub output: Option<String>,
+    pub error_message: Option<String>,
+    pub score: f64,
+    pub passed: bool,
+    pub checks: serde_json::Value,
+    pub tokens_used: Option<i32>,
+    pub latency_ms: i64,
+}
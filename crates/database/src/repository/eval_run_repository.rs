@@ -0,0 +1,63 @@
+//! 评估运行仓储实现
+
+use crate::{entities::eval_run, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+/// 评估运行仓储
+pub struct EvalRunRepository {
+    db: DatabaseConnection,
+}
+
+impl EvalRunRepository {
+    /// 创建新的评估运行仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 创建评估运行
+    pub async fn create(&self, data: CreateEvalRunData) -> Result<eval_run::Model> {
+        let run_id = Uuid::new_v4();
+
+        let run = eval_run::ActiveModel {
+            run_id: Set(run_id),
+            eval_set_id: Set(data.eval_set_id),
+            variants: Set(data.variants),
+            judged: Set(data.judged),
+            created_at: Set(chrono::Utc::now().into()),
+        };
+
+        eval_run::Entity::insert(run).exec(&self.db).await?;
+
+        eval_run::Entity::find_by_id(run_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("EvalRun", run_id))
+    }
+
+    /// 根据ID查找评估运行
+    pub async fn find_by_id(&self, run_id: Uuid) -> Result<Option<eval_run::Model>> {
+        eval_run::Entity::find_by_id(run_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找评估集的所有运行（按时间倒序）
+    pub async fn find_by_eval_set(&self, eval_set_id: Uuid) -> Result<Vec<eval_run::Model>> {
+        eval_run::Entity::find()
+            .filter(eval_run::Column::EvalSetId.eq(eval_set_id))
+            .order_by_desc(eval_run::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+}
+
+/// 创建评估运行数据
+#[derive(Debug, Clone)]
+pub struct CreateEvalRunData {
+    pub eval_set_id: Uuid,
+    pub variants: serde_json::Value,
+    pub judged: bool,
+}
@@ -0,0 +1,88 @@
+//! 评估集仓储实现
+
+use crate::{entities::eval_set, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+/// 评估集仓储
+pub struct EvalSetRepository {
+    db: DatabaseConnection,
+}
+
+impl EvalSetRepository {
+    /// 创建新的评估集仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 创建评估集
+    pub async fn create(&self, data: SaveEvalSetData) -> Result<eval_set::Model> {
+        let eval_set_id = Uuid::new_v4();
+        let now = chrono::Utc::now();
+
+        let eval_set = eval_set::ActiveModel {
+            eval_set_id: Set(eval_set_id),
+            project_id: Set(data.project_id),
+            name: Set(data.name),
+            description: Set(data.description),
+            cases: Set(data.cases),
+            created_at: Set(now.into()),
+            updated_at: Set(now.into()),
+        };
+
+        eval_set::Entity::insert(eval_set).exec(&self.db).await?;
+
+        eval_set::Entity::find_by_id(eval_set_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("EvalSet", eval_set_id))
+    }
+
+    /// 覆盖评估集的名称、描述和用例
+    pub async fn update(&self, eval_set_id: Uuid, data: SaveEvalSetData) -> Result<eval_set::Model> {
+        let eval_set = self
+            .find_by_id(eval_set_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("EvalSet", eval_set_id))?;
+
+        let mut eval_set: eval_set::ActiveModel = eval_set.into();
+        eval_set.project_id = Set(data.project_id);
+        eval_set.name = Set(data.name);
+        eval_set.description = Set(data.description);
+        eval_set.cases = Set(data.cases);
+        eval_set.updated_at = Set(chrono::Utc::now().into());
+
+        eval_set.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 根据ID查找评估集
+    pub async fn find_by_id(&self, eval_set_id: Uuid) -> Result<Option<eval_set::Model>> {
+        eval_set::Entity::find_by_id(eval_set_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找项目的评估集（按更新时间倒序），`project_id` 为空时查找全局评估集
+    pub async fn find_by_project(&self, project_id: Option<Uuid>) -> Result<Vec<eval_set::Model>> {
+        let query = match project_id {
+            Some(project_id) => eval_set::Entity::find().filter(eval_set::Column::ProjectId.eq(project_id)),
+            None => eval_set::Entity::find().filter(eval_set::Column::ProjectId.is_null()),
+        };
+
+        query
+            .order_by_desc(eval_set::Column::UpdatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+}
+
+/// 保存评估集数据
+#[derive(Debug, Clone)]
+pub struct SaveEvalSetData {
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub cases: serde_json::Value,
+}
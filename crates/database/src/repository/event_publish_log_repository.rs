@@ -150,6 +150,49 @@ impl EventPublishLogRepository {
             .map_err(DatabaseError::from)
     }
     
+    /// 查找指定订阅者类型中失败且尚未用完尝试次数的日志
+    pub async fn find_retryable(&self, subscriber_type: &str) -> Result<Vec<event_publish_log::Model>> {
+        let logs = event_publish_log::Entity::find()
+            .filter(event_publish_log::Column::SubscriberType.eq(subscriber_type))
+            .filter(event_publish_log::Column::Status.eq("failed"))
+            .order_by_asc(event_publish_log::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+        
+        Ok(logs.into_iter().filter(|log| log.attempts < log.max_attempts).collect())
+    }
+    
+    /// 记录一次投递尝试的结果，尝试次数加一并更新对应的时间戳
+    pub async fn record_attempt(
+        &self,
+        log_id: Uuid,
+        status: &str,
+        response_data: Option<serde_json::Value>,
+        error_message: Option<String>,
+    ) -> Result<event_publish_log::Model> {
+        let log = event_publish_log::Entity::find_by_id(log_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("EventPublishLog", log_id))?;
+        
+        let attempts = log.attempts;
+        let now = chrono::Utc::now().into();
+        let mut log: event_publish_log::ActiveModel = log.into();
+        log.attempts = Set(attempts + 1);
+        log.status = Set(status.to_string());
+        log.response_data = Set(response_data);
+        log.error_message = Set(error_message);
+        match status {
+            "delivered" => log.delivered_at = Set(Some(now)),
+            "failed" => log.failed_at = Set(Some(now)),
+            _ => {}
+        }
+        
+        log.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
     /// 批量创建发布日志
     pub async fn create_batch(&self, logs_data: Vec<CreateEventPublishLogData>) -> Result<Vec<event_publish_log::Model>> {
         let now = chrono::Utc::now().into();
@@ -0,0 +1,107 @@
+//! 执行工件仓储实现
+
+use crate::{entities::execution_artifact, DatabaseConnection, DatabaseError, Result};
+use codex_multi_agent::ArtifactType;
+use sea_orm::{EntityTrait, Set, ColumnTrait, QueryFilter, QueryOrder, PaginatorTrait};
+use uuid::Uuid;
+
+/// 执行工件仓储
+pub struct ExecutionArtifactRepository {
+    db: DatabaseConnection,
+}
+
+/// 创建工件记录的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateArtifactData {
+    pub session_id: Uuid,
+    pub name: String,
+    pub artifact_type: ArtifactType,
+    pub checksum: String,
+    pub size_bytes: u64,
+    pub description: Option<String>,
+}
+
+impl ExecutionArtifactRepository {
+    /// 创建新的执行工件仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 创建工件记录
+    pub async fn create(&self, data: CreateArtifactData) -> Result<execution_artifact::Model> {
+        let artifact_id = Uuid::new_v4();
+
+        let artifact = execution_artifact::ActiveModel {
+            artifact_id: Set(artifact_id),
+            session_id: Set(data.session_id),
+            name: Set(data.name),
+            artifact_type: Set(data.artifact_type.to_string()),
+            checksum: Set(data.checksum),
+            size_bytes: Set(data.size_bytes as i64),
+            description: Set(data.description),
+            created_at: Set(chrono::Utc::now().into()),
+        };
+
+        execution_artifact::Entity::insert(artifact).exec(&self.db).await?;
+
+        self.find_by_id(artifact_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ExecutionArtifact", artifact_id))
+    }
+
+    /// 根据ID查找工件
+    pub async fn find_by_id(&self, artifact_id: Uuid) -> Result<Option<execution_artifact::Model>> {
+        execution_artifact::Entity::find_by_id(artifact_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找执行会话的全部工件（按创建时间升序）
+    pub async fn find_by_session_id(&self, session_id: Uuid) -> Result<Vec<execution_artifact::Model>> {
+        execution_artifact::Entity::find()
+            .filter(execution_artifact::Column::SessionId.eq(session_id))
+            .order_by_asc(execution_artifact::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找执行会话中指定名称的最新工件
+    pub async fn find_latest_by_name(&self, session_id: Uuid, name: &str) -> Result<Option<execution_artifact::Model>> {
+        execution_artifact::Entity::find()
+            .filter(execution_artifact::Column::SessionId.eq(session_id))
+            .filter(execution_artifact::Column::Name.eq(name))
+            .order_by_desc(execution_artifact::Column::CreatedAt)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找全部工件（按创建时间升序，最旧的在前）
+    pub async fn find_all_oldest_first(&self) -> Result<Vec<execution_artifact::Model>> {
+        execution_artifact::Entity::find()
+            .order_by_asc(execution_artifact::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 统计引用指定内容的工件数量
+    pub async fn count_by_checksum(&self, checksum: &str) -> Result<u64> {
+        execution_artifact::Entity::find()
+            .filter(execution_artifact::Column::Checksum.eq(checksum))
+            .count(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 删除工件记录
+    pub async fn delete(&self, artifact_id: Uuid) -> Result<()> {
+        execution_artifact::Entity::delete_by_id(artifact_id)
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,12 +1,21 @@
 //! 执行日志仓储实现
 
-use crate::{entities::execution_log, DatabaseConnection, DatabaseError, Result};
-use sea_orm::{EntityTrait, Set, ColumnTrait, QueryFilter, QueryOrder, PaginatorTrait};
+use crate::{
+    connection::{retry_on_busy, BusyRetryPolicy},
+    entities::{execution_log::{self, EventType}, execution_session},
+    export::keyset_batches,
+    tenant_scope::{scoped_condition, TenantScope},
+    DatabaseConnection, DatabaseError, Result,
+};
+use futures::Stream;
+use sea_orm::{sea_query::{Expr, Query}, EntityTrait, Set, ColumnTrait, Condition, QueryFilter, QueryOrder, QuerySelect, PaginatorTrait};
+use std::collections::{BTreeSet, HashMap};
 use uuid::Uuid;
 
 /// 执行日志仓储
 pub struct ExecutionLogRepository {
     db: DatabaseConnection,
+    scope: Option<TenantScope>,
 }
 
 /// 创建执行日志的数据结构
@@ -14,7 +23,7 @@ pub struct ExecutionLogRepository {
 pub struct CreateExecutionLogData {
     pub session_id: Uuid,
     pub log_level: String,
-    pub event_type: String,
+    pub event_type: EventType,
     pub message: String,
     pub details: Option<serde_json::Value>,
     pub timestamp_ms: i64,
@@ -34,11 +43,24 @@ pub struct LogStatistics {
 impl ExecutionLogRepository {
     /// 创建新的执行日志仓储实例
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self { db, scope: None }
+    }
+
+    /// 限定只能访问租户范围内项目的执行会话日志
+    pub fn with_scope(mut self, scope: TenantScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    fn tenant_condition(&self) -> Condition {
+        scoped_condition(self.scope.as_ref(), |scope| scope.session_ref_condition(execution_log::Column::SessionId))
     }
 
     /// 创建新的执行日志
     pub async fn create(&self, log_data: CreateExecutionLogData) -> Result<execution_log::Model> {
+        if let Some(scope) = &self.scope {
+            scope.ensure_session(&self.db, log_data.session_id).await?;
+        }
         let now = chrono::Utc::now().into();
         let log_id = Uuid::new_v4();
         
@@ -46,7 +68,7 @@ impl ExecutionLogRepository {
             log_id: Set(log_id),
             session_id: Set(log_data.session_id),
             log_level: Set(log_data.log_level),
-            event_type: Set(log_data.event_type),
+            event_type: Set(log_data.event_type.to_string()),
             message: Set(log_data.message),
             details: Set(log_data.details),
             timestamp_ms: Set(log_data.timestamp_ms),
@@ -57,6 +79,7 @@ impl ExecutionLogRepository {
         let _result = execution_log::Entity::insert(log).exec(&self.db).await?;
         
         execution_log::Entity::find_by_id(log_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("ExecutionLog", log_id))
@@ -65,6 +88,7 @@ impl ExecutionLogRepository {
     /// 根据ID查找执行日志
     pub async fn find_by_id(&self, log_id: Uuid) -> Result<Option<execution_log::Model>> {
         execution_log::Entity::find_by_id(log_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await
             .map_err(DatabaseError::from)
@@ -73,6 +97,7 @@ impl ExecutionLogRepository {
     /// 根据执行会话ID查找日志
     pub async fn find_by_session_id(&self, session_id: Uuid) -> Result<Vec<execution_log::Model>> {
         execution_log::Entity::find()
+            .filter(self.tenant_condition())
             .filter(execution_log::Column::SessionId.eq(session_id))
             .order_by_asc(execution_log::Column::CreatedAt)
             .all(&self.db)
@@ -80,9 +105,39 @@ impl ExecutionLogRepository {
             .map_err(DatabaseError::from)
     }
     
+    /// 按创建时间流式读取项目所有执行会话的日志，每次查询 `batch_size` 行
+    pub fn stream_by_project(
+        &self,
+        project_id: Uuid,
+        batch_size: u64,
+    ) -> impl Stream<Item = Result<execution_log::Model>> + Send + 'static {
+        let db = self.db.clone();
+        let tenant_condition = self.tenant_condition();
+        keyset_batches(batch_size, |log: &execution_log::Model| (log.created_at, log.log_id), move |after, limit| {
+            let db = db.clone();
+            let tenant_condition = tenant_condition.clone();
+            async move {
+                let sessions = Query::select()
+                    .column(execution_session::Column::SessionId)
+                    .from(execution_session::Entity)
+                    .and_where(execution_session::Column::ProjectId.eq(project_id))
+                    .to_owned();
+                let mut cursor = execution_log::Entity::find()
+                    .filter(execution_log::Column::SessionId.in_subquery(sessions))
+                    .filter(tenant_condition)
+                    .cursor_by((execution_log::Column::CreatedAt, execution_log::Column::LogId));
+                if let Some(after) = after {
+                    cursor.after(after);
+                }
+                cursor.first(limit).all(&db).await.map_err(DatabaseError::from)
+            }
+        })
+    }
+    
     /// 根据日志级别查找日志
     pub async fn find_by_log_level(&self, log_level: &str) -> Result<Vec<execution_log::Model>> {
         execution_log::Entity::find()
+            .filter(self.tenant_condition())
             .filter(execution_log::Column::LogLevel.eq(log_level))
             .order_by_desc(execution_log::Column::CreatedAt)
             .all(&self.db)
@@ -92,6 +147,12 @@ impl ExecutionLogRepository {
     
     /// 批量创建日志
     pub async fn create_batch(&self, logs_data: Vec<CreateExecutionLogData>) -> Result<Vec<execution_log::Model>> {
+        if let Some(scope) = &self.scope {
+            let session_ids: BTreeSet<Uuid> = logs_data.iter().map(|log| log.session_id).collect();
+            for session_id in session_ids {
+                scope.ensure_session(&self.db, session_id).await?;
+            }
+        }
         let now = chrono::Utc::now().into();
         let mut active_models = Vec::new();
         let mut log_ids = Vec::new();
@@ -104,7 +165,7 @@ impl ExecutionLogRepository {
                 log_id: Set(log_id),
                 session_id: Set(log_data.session_id),
                 log_level: Set(log_data.log_level),
-                event_type: Set(log_data.event_type),
+                event_type: Set(log_data.event_type.to_string()),
                 message: Set(log_data.message),
                 details: Set(log_data.details),
                 timestamp_ms: Set(log_data.timestamp_ms),
@@ -115,27 +176,41 @@ impl ExecutionLogRepository {
             active_models.push(log);
         }
         
-        execution_log::Entity::insert_many(active_models).exec(&self.db).await?;
+        // 日志写入与调度器、界面并发，锁等待超时后整体重试这条插入
+        retry_on_busy(BusyRetryPolicy::default(), || async {
+            execution_log::Entity::insert_many(active_models.clone()).exec(&self.db).await?;
+            Ok(())
+        })
+        .await?;
         
         // 返回插入的记录
         execution_log::Entity::find()
+            .filter(self.tenant_condition())
             .filter(execution_log::Column::LogId.is_in(log_ids))
             .all(&self.db)
             .await
             .map_err(DatabaseError::from)
     }
     
-    /// 删除执行日志
+    /// 删除执行日志，设置了租户范围时删除范围外的日志返回未找到
     pub async fn delete(&self, log_id: Uuid) -> Result<()> {
-        execution_log::Entity::delete_by_id(log_id)
+        let result = execution_log::Entity::delete_many()
+            .filter(execution_log::Column::LogId.eq(log_id))
+            .filter(self.tenant_condition())
             .exec(&self.db)
             .await?;
         
+        if self.scope.is_some() && result.rows_affected == 0 {
+            return Err(DatabaseError::entity_not_found("ExecutionLog", log_id));
+        }
         Ok(())
     }
     
-    /// 根据会话ID删除所有日志
+    /// 根据会话ID删除所有日志，设置了租户范围时范围外的会话返回未找到
     pub async fn delete_by_session_id(&self, session_id: Uuid) -> Result<()> {
+        if let Some(scope) = &self.scope {
+            scope.ensure_session(&self.db, session_id).await?;
+        }
         execution_log::Entity::delete_many()
             .filter(execution_log::Column::SessionId.eq(session_id))
             .exec(&self.db)
@@ -147,6 +222,7 @@ impl ExecutionLogRepository {
     /// 获取日志统计信息
     pub async fn get_log_statistics(&self, session_id: Uuid) -> Result<LogStatistics> {
         let logs = execution_log::Entity::find()
+            .filter(self.tenant_condition())
             .filter(execution_log::Column::SessionId.eq(session_id))
             .all(&self.db)
             .await
@@ -183,6 +259,7 @@ impl ExecutionLogRepository {
         value: &str,
     ) -> Result<Vec<execution_log::Model>> {
         let logs = execution_log::Entity::find()
+            .filter(self.tenant_condition())
             .filter(execution_log::Column::SessionId.eq(session_id))
             .all(&self.db)
             .await
@@ -217,6 +294,7 @@ impl ExecutionLogRepository {
         end_time: Option<chrono::DateTime<chrono::Utc>>,
     ) -> Result<Vec<execution_log::Model>> {
         let mut query = execution_log::Entity::find()
+            .filter(self.tenant_condition())
             .filter(execution_log::Column::SessionId.eq(session_id));
 
         if let Some(level) = log_level {
@@ -250,6 +328,7 @@ impl ExecutionLogRepository {
         end_timestamp_ms: i64,
     ) -> Result<Vec<execution_log::Model>> {
         execution_log::Entity::find()
+            .filter(self.tenant_condition())
             .filter(execution_log::Column::SessionId.eq(session_id))
             .filter(execution_log::Column::TimestampMs.gte(start_timestamp_ms))
             .filter(execution_log::Column::TimestampMs.lte(end_timestamp_ms))
@@ -268,6 +347,7 @@ impl ExecutionLogRepository {
     ) -> Result<(Vec<execution_log::Model>, u64)> {
         // 获取总数
         let total_count = execution_log::Entity::find()
+            .filter(self.tenant_condition())
             .filter(execution_log::Column::SessionId.eq(session_id))
             .count(&self.db)
             .await
@@ -277,6 +357,7 @@ impl ExecutionLogRepository {
 
         // 获取分页数据
         let logs = execution_log::Entity::find()
+            .filter(self.tenant_condition())
             .filter(execution_log::Column::SessionId.eq(session_id))
             .order_by_desc(execution_log::Column::CreatedAt)
             .paginate(&self.db, page_size)
@@ -290,12 +371,36 @@ impl ExecutionLogRepository {
     /// 根据事件类型查找日志
     pub async fn find_by_event_type(&self, event_type: &str) -> Result<Vec<execution_log::Model>> {
         execution_log::Entity::find()
+            .filter(self.tenant_condition())
             .filter(execution_log::Column::EventType.eq(event_type))
             .order_by_desc(execution_log::Column::CreatedAt)
             .all(&self.db)
             .await
             .map_err(DatabaseError::from)
     }
+
+    /// 统计会话中各事件类型的日志数量，未出现的类型不返回
+    ///
+    /// 结果按 [`EventType::ALL`] 的顺序排列，数据库中无法识别的旧取值不计入
+    pub async fn count_by_event_type(&self, session_id: Uuid) -> Result<Vec<(EventType, u64)>> {
+        let rows: Vec<(String, i64)> = execution_log::Entity::find()
+            .filter(self.tenant_condition())
+            .select_only()
+            .column(execution_log::Column::EventType)
+            .column_as(Expr::col(execution_log::Column::LogId).count(), "count")
+            .filter(execution_log::Column::SessionId.eq(session_id))
+            .group_by(execution_log::Column::EventType)
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+        let counts: HashMap<String, i64> = rows.into_iter().collect();
+        Ok(EventType::ALL
+            .into_iter()
+            .filter_map(|event_type| {
+                counts.get(event_type.as_str()).map(|count| (event_type, *count as u64))
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -318,7 +423,7 @@ mod tests {
         let log_data = CreateExecutionLogData {
             session_id: Uuid::new_v4(),
             log_level: "info".to_string(),
-            event_type: "execution_start".to_string(),
+            event_type: EventType::SessionStarted,
             message: "执行开始".to_string(),
             details: Some(serde_json::json!({"step": 1})),
             timestamp_ms: chrono::Utc::now().timestamp_millis(),
@@ -339,7 +444,7 @@ mod tests {
         let log_data = CreateExecutionLogData {
             session_id,
             log_level: "info".to_string(),
-            event_type: "test_event".to_string(),
+            event_type: EventType::Progress,
             message: "测试日志".to_string(),
             details: None,
             timestamp_ms: chrono::Utc::now().timestamp_millis(),
@@ -362,7 +467,7 @@ mod tests {
             CreateExecutionLogData {
                 session_id,
                 log_level: "info".to_string(),
-                event_type: "batch_test_1".to_string(),
+                event_type: EventType::Progress,
                 message: "日志1".to_string(),
                 details: None,
                 timestamp_ms: chrono::Utc::now().timestamp_millis(),
@@ -370,7 +475,7 @@ mod tests {
             CreateExecutionLogData {
                 session_id,
                 log_level: "warn".to_string(),
-                event_type: "batch_test_2".to_string(),
+                event_type: EventType::TestRun,
                 message: "日志2".to_string(),
                 details: None,
                 timestamp_ms: chrono::Utc::now().timestamp_millis(),
@@ -3,25 +3,75 @@
 use crate::entities::execution_session::{self, Entity as ExecutionSession, ActiveModel, Model, ExecutionStatus};
 use crate::error::{DatabaseError, Result};
 use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DatabaseConnection, EntityTrait,
     QueryFilter, QueryOrder, PaginatorTrait,
 };
 use serde_json::Value as JsonValue;
+use crate::ids::EntityKey;
+use crate::payload_storage::{self, PayloadPolicy, PayloadStore, EXECUTION_SESSIONS};
+use crate::tenant_scope::{scoped_condition, TenantScope};
+use codex_multi_agent::{AgentId, ExecutionSessionId, ProjectId, TaskId};
 use uuid::Uuid;
 
 /// 执行会话仓储
+///
+/// 较大的 `result_data` 写入时压缩，读取时自动还原，见 [`crate::payload_storage`]
 pub struct ExecutionSessionRepository {
     db: DatabaseConnection,
+    payloads: Option<PayloadStore>,
+    scope: Option<TenantScope>,
+    #[cfg(feature = "fault_injection")]
+    faults: Option<crate::fault_injection::FaultInjector>,
 }
 
 impl ExecutionSessionRepository {
     /// 创建新的执行会话仓储实例
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self {
+            db,
+            payloads: None,
+            scope: None,
+            #[cfg(feature = "fault_injection")]
+            faults: None,
+        }
+    }
+
+    /// 限定只能访问租户范围内项目的执行会话
+    pub fn with_scope(mut self, scope: TenantScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    fn tenant_condition(&self) -> Condition {
+        scoped_condition(self.scope.as_ref(), |scope| scope.project_ref_condition(execution_session::Column::ProjectId))
+    }
+
+    /// 挂载负载存储，超大的结果数据转存到工件存储，读取时从工件存储还原
+    pub fn with_payload_store(mut self, payloads: PayloadStore) -> Self {
+        self.payloads = Some(payloads);
+        self
+    }
+
+    /// 挂载故障注入器（启动会话时检查 Agent 崩溃故障点）
+    #[cfg(feature = "fault_injection")]
+    pub fn with_fault_injector(mut self, injector: crate::fault_injection::FaultInjector) -> Self {
+        self.faults = Some(injector);
+        self
     }
 
     /// 创建新的执行会话
+    #[tracing::instrument(
+        name = "execution_session.create",
+        skip(self, session_data),
+        fields(correlation_id = %session_data.task_id, agent_id = %session_data.agent_id)
+    )]
     pub async fn create(&self, session_data: CreateSessionData) -> Result<Model> {
+        if let Some(scope) = &self.scope {
+            scope.ensure_project(&self.db, session_data.project_id).await?;
+        }
+        // 工作空间超出磁盘配额时拒绝启动新的构建
+        crate::workspace_quota::ensure_within_quota(&self.db, session_data.project_id).await?;
+
         let session = ActiveModel {
             session_id: Set(Uuid::new_v4()),
             task_id: Set(session_data.task_id),
@@ -39,57 +89,67 @@ impl ExecutionSessionRepository {
             success: Set(None),
             result_data: Set(None),
             error_message: Set(None),
+            checkpoint: Set(None),
+            worktree_path: Set(None),
         };
 
         session.insert(&self.db).await.map_err(DatabaseError::from)
     }
 
     /// 根据ID查找执行会话
-    pub async fn find_by_id(&self, session_id: Uuid) -> Result<Option<Model>> {
-        ExecutionSession::find_by_id(session_id)
-            .one(&self.db)
-            .await
-            .map_err(DatabaseError::from)
+    pub async fn find_by_id(&self, session_id: impl EntityKey<ExecutionSessionId>) -> Result<Option<Model>> {
+        let session_id = session_id.into_key();
+        match ExecutionSession::find_by_id(session_id).filter(self.tenant_condition()).one(&self.db).await? {
+            Some(session) => Ok(Some(self.decode(session).await?)),
+            None => Ok(None),
+        }
     }
 
     /// 根据任务ID查找执行会话
-    pub async fn find_by_task_id(&self, task_id: Uuid) -> Result<Vec<Model>> {
-        ExecutionSession::find()
+    pub async fn find_by_task_id(&self, task_id: impl EntityKey<TaskId>) -> Result<Vec<Model>> {
+        let task_id = task_id.into_key();
+        let sessions = ExecutionSession::find()
+            .filter(self.tenant_condition())
             .filter(execution_session::Column::TaskId.eq(task_id))
             .order_by_desc(execution_session::Column::CreatedAt)
             .all(&self.db)
-            .await
-            .map_err(DatabaseError::from)
+            .await?;
+        self.decode_all(sessions).await
     }
 
     /// 根据Agent ID查找执行会话
-    pub async fn find_by_agent_id(&self, agent_id: Uuid) -> Result<Vec<Model>> {
-        ExecutionSession::find()
+    pub async fn find_by_agent_id(&self, agent_id: impl EntityKey<AgentId>) -> Result<Vec<Model>> {
+        let agent_id = agent_id.into_key();
+        let sessions = ExecutionSession::find()
+            .filter(self.tenant_condition())
             .filter(execution_session::Column::AgentId.eq(agent_id))
             .order_by_desc(execution_session::Column::CreatedAt)
             .all(&self.db)
-            .await
-            .map_err(DatabaseError::from)
+            .await?;
+        self.decode_all(sessions).await
     }
 
     /// 根据项目ID查找执行会话
-    pub async fn find_by_project_id(&self, project_id: Uuid) -> Result<Vec<Model>> {
-        ExecutionSession::find()
+    pub async fn find_by_project_id(&self, project_id: impl EntityKey<ProjectId>) -> Result<Vec<Model>> {
+        let project_id = project_id.into_key();
+        let sessions = ExecutionSession::find()
+            .filter(self.tenant_condition())
             .filter(execution_session::Column::ProjectId.eq(project_id))
             .order_by_desc(execution_session::Column::CreatedAt)
             .all(&self.db)
-            .await
-            .map_err(DatabaseError::from)
+            .await?;
+        self.decode_all(sessions).await
     }
 
     /// 根据状态查找执行会话
     pub async fn find_by_status(&self, status: ExecutionStatus) -> Result<Vec<Model>> {
-        ExecutionSession::find()
+        let sessions = ExecutionSession::find()
+            .filter(self.tenant_condition())
             .filter(execution_session::Column::Status.eq(status.to_string()))
             .order_by_asc(execution_session::Column::CreatedAt)
             .all(&self.db)
-            .await
-            .map_err(DatabaseError::from)
+            .await?;
+        self.decode_all(sessions).await
     }
 
     /// 查找运行中的会话
@@ -103,9 +163,12 @@ impl ExecutionSessionRepository {
     }
 
     /// 启动执行会话
-    pub async fn start_session(&self, session_id: Uuid) -> Result<Model> {
+    #[tracing::instrument(name = "execution_session.start", skip(self), fields(correlation_id = tracing::field::Empty))]
+    pub async fn start_session(&self, session_id: impl EntityKey<ExecutionSessionId>) -> Result<Model> {
+        let session_id = session_id.into_key();
         let session = self.find_by_id(session_id).await?
             .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id.to_string()))?;
+        tracing::Span::current().record("correlation_id", tracing::field::display(session.task_id));
 
         if session.status != ExecutionStatus::Pending.to_string() {
             return Err(DatabaseError::validation(
@@ -113,24 +176,39 @@ impl ExecutionSessionRepository {
             ));
         }
 
+        #[cfg(feature = "fault_injection")]
+        crate::fault_injection::check(
+            &self.faults,
+            crate::fault_injection::FaultPoint::AgentCrash,
+            Some(session.agent_id),
+        )?;
+
         let mut session_active: ActiveModel = session.into();
         session_active.status = Set(ExecutionStatus::Running.to_string());
         session_active.started_at = Set(Some(chrono::Utc::now().into()));
 
-        session_active.update(&self.db).await.map_err(DatabaseError::from)
+        let session = session_active.update(&self.db).await?;
+        self.decode(session).await
     }
 
     /// 完成执行会话
+    #[tracing::instrument(
+        name = "execution_session.complete",
+        skip(self, final_commit, result_data, error_message),
+        fields(correlation_id = tracing::field::Empty)
+    )]
     pub async fn complete_session(
         &self,
-        session_id: Uuid,
+        session_id: impl EntityKey<ExecutionSessionId>,
         success: bool,
         final_commit: Option<String>,
         result_data: Option<JsonValue>,
         error_message: Option<String>
     ) -> Result<Model> {
+        let session_id = session_id.into_key();
         let session = self.find_by_id(session_id).await?
             .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id.to_string()))?;
+        tracing::Span::current().record("correlation_id", tracing::field::display(session.task_id));
 
         if session.status != ExecutionStatus::Running.to_string() {
             return Err(DatabaseError::validation(
@@ -147,14 +225,19 @@ impl ExecutionSessionRepository {
         session_active.completed_at = Set(Some(chrono::Utc::now().into()));
         session_active.success = Set(Some(success));
         session_active.final_commit = Set(final_commit);
-        session_active.result_data = Set(result_data);
+        session_active.result_data = Set(match result_data {
+            Some(result_data) => Some(self.encode(session_id, result_data).await?),
+            None => None,
+        });
         session_active.error_message = Set(error_message);
 
-        session_active.update(&self.db).await.map_err(DatabaseError::from)
+        let session = session_active.update(&self.db).await?;
+        self.decode(session).await
     }
 
     /// 标记会话超时
-    pub async fn timeout_session(&self, session_id: Uuid, error_message: String) -> Result<Model> {
+    pub async fn timeout_session(&self, session_id: impl EntityKey<ExecutionSessionId>, error_message: String) -> Result<Model> {
+        let session_id = session_id.into_key();
         let session = self.find_by_id(session_id).await?
             .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id.to_string()))?;
 
@@ -164,23 +247,52 @@ impl ExecutionSessionRepository {
         session_active.success = Set(Some(false));
         session_active.error_message = Set(Some(error_message));
 
-        session_active.update(&self.db).await.map_err(DatabaseError::from)
+        let session = session_active.update(&self.db).await?;
+        self.decode(session).await
+    }
+
+    /// 更新会话的检查点
+    pub async fn update_checkpoint(&self, session_id: impl EntityKey<ExecutionSessionId>, checkpoint: Option<JsonValue>) -> Result<Model> {
+        let session_id = session_id.into_key();
+        let session = self.find_by_id(session_id).await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id.to_string()))?;
+
+        let mut session_active: ActiveModel = session.into();
+        session_active.checkpoint = Set(checkpoint);
+
+        let session = session_active.update(&self.db).await?;
+        self.decode(session).await
+    }
+
+    /// 记录会话使用的工作树
+    pub async fn set_worktree_path(&self, session_id: impl EntityKey<ExecutionSessionId>, worktree_path: Option<String>) -> Result<Model> {
+        let session_id = session_id.into_key();
+        let session = self.find_by_id(session_id).await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id.to_string()))?;
+
+        let mut session_active: ActiveModel = session.into();
+        session_active.worktree_path = Set(worktree_path);
+
+        let session = session_active.update(&self.db).await?;
+        self.decode(session).await
     }
 
     /// 检查超时的会话
     pub async fn find_timeout_sessions(&self, timeout_minutes: i32) -> Result<Vec<Model>> {
         let timeout_threshold = chrono::Utc::now() - chrono::Duration::minutes(timeout_minutes as i64);
         
-        ExecutionSession::find()
+        let sessions = ExecutionSession::find()
+            .filter(self.tenant_condition())
             .filter(execution_session::Column::Status.eq(ExecutionStatus::Running.to_string()))
             .filter(execution_session::Column::StartedAt.lt(timeout_threshold))
             .all(&self.db)
-            .await
-            .map_err(DatabaseError::from)
+            .await?;
+        self.decode_all(sessions).await
     }
 
     /// 获取会话执行时长
-    pub async fn get_execution_duration(&self, session_id: Uuid) -> Result<Option<chrono::Duration>> {
+    pub async fn get_execution_duration(&self, session_id: impl EntityKey<ExecutionSessionId>) -> Result<Option<chrono::Duration>> {
+        let session_id = session_id.into_key();
         let session = self.find_by_id(session_id).await?
             .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id.to_string()))?;
 
@@ -199,7 +311,7 @@ impl ExecutionSessionRepository {
         page_size: u64,
         filter: Option<SessionFilter>
     ) -> Result<(Vec<Model>, u64)> {
-        let mut query = ExecutionSession::find();
+        let mut query = ExecutionSession::find().filter(self.tenant_condition());
 
         if let Some(filter) = filter {
             if let Some(project_id) = filter.project_id {
@@ -222,12 +334,12 @@ impl ExecutionSessionRepository {
         let total_pages = paginator.num_pages().await.map_err(DatabaseError::from)?;
         let sessions = paginator.fetch_page(page).await.map_err(DatabaseError::from)?;
 
-        Ok((sessions, total_pages))
+        Ok((self.decode_all(sessions).await?, total_pages))
     }
 
     /// 获取会话统计信息
     pub async fn get_session_statistics(&self, project_id: Option<Uuid>) -> Result<SessionStatistics> {
-        let mut query = ExecutionSession::find();
+        let mut query = ExecutionSession::find().filter(self.tenant_condition());
         
         if let Some(project_id) = project_id {
             query = query.filter(execution_session::Column::ProjectId.eq(project_id));
@@ -279,15 +391,51 @@ impl ExecutionSessionRepository {
         })
     }
 
-    /// 删除执行会话
-    pub async fn delete(&self, session_id: Uuid) -> Result<()> {
-        ExecutionSession::delete_by_id(session_id)
+    /// 删除执行会话，挂载了负载存储时一并释放转存的数据
+    ///
+    /// 设置了租户范围时删除范围外的会话返回未找到
+    pub async fn delete(&self, session_id: impl EntityKey<ExecutionSessionId>) -> Result<()> {
+        let session_id = session_id.into_key();
+        let result = ExecutionSession::delete_many()
+            .filter(execution_session::Column::SessionId.eq(session_id))
+            .filter(self.tenant_condition())
             .exec(&self.db)
             .await
             .map_err(DatabaseError::from)?;
+        if self.scope.is_some() && result.rows_affected == 0 {
+            return Err(DatabaseError::entity_not_found("ExecutionSession", session_id.to_string()));
+        }
+        if let Some(payloads) = &self.payloads {
+            payloads.release(EXECUTION_SESSIONS, session_id).await?;
+        }
         
         Ok(())
     }
+
+    /// 编码结果数据
+    async fn encode(&self, session_id: Uuid, result_data: JsonValue) -> Result<JsonValue> {
+        match &self.payloads {
+            Some(payloads) => payloads.encode(EXECUTION_SESSIONS, session_id, result_data).await,
+            None => payload_storage::compress(result_data, &PayloadPolicy::default()),
+        }
+    }
+
+    /// 还原结果数据；没有挂载负载存储时转存的数据保留引用
+    async fn decode(&self, mut session: Model) -> Result<Model> {
+        session.result_data = match &self.payloads {
+            Some(payloads) => payloads.decode_option(session.result_data).await?,
+            None => payload_storage::inflate_option(session.result_data)?,
+        };
+        Ok(session)
+    }
+
+    async fn decode_all(&self, sessions: Vec<Model>) -> Result<Vec<Model>> {
+        let mut decoded = Vec::with_capacity(sessions.len());
+        for session in sessions {
+            decoded.push(self.decode(session).await?);
+        }
+        Ok(decoded)
+    }
 }
 
 /// 创建执行会话的数据结构
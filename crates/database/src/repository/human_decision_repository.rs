@@ -1,12 +1,17 @@
 //! 人工决策仓储实现
 
-use crate::{entities::human_decision, DatabaseConnection, DatabaseError, Result};
-use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter, QueryOrder};
+use crate::{
+    entities::human_decision,
+    tenant_scope::{scoped_condition, TenantScope},
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter, QueryOrder, Condition};
 use uuid::Uuid;
 
 /// 人工决策仓储
 pub struct HumanDecisionRepository {
     db: DatabaseConnection,
+    scope: Option<TenantScope>,
 }
 
 /// 创建人工决策的数据结构
@@ -59,11 +64,24 @@ pub struct DecisionFrequencyStats {
 impl HumanDecisionRepository {
     /// 创建新的人工决策仓储实例
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self { db, scope: None }
+    }
+
+    /// 限定只能访问租户范围内项目的冲突上的决策
+    pub fn with_scope(mut self, scope: TenantScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    fn tenant_condition(&self) -> Condition {
+        scoped_condition(self.scope.as_ref(), |scope| scope.conflict_ref_condition(human_decision::Column::ConflictId))
     }
 
     /// 创建新的人工决策
     pub async fn create(&self, decision_data: CreateHumanDecisionData) -> Result<human_decision::Model> {
+        if let Some(scope) = &self.scope {
+            scope.ensure_conflict(&self.db, decision_data.conflict_id).await?;
+        }
         let now = chrono::Utc::now().into();
         let decision_id = Uuid::new_v4();
         
@@ -83,6 +101,7 @@ impl HumanDecisionRepository {
         let _result = human_decision::Entity::insert(decision).exec(&self.db).await?;
         
         human_decision::Entity::find_by_id(decision_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("HumanDecision", decision_id))
@@ -91,6 +110,7 @@ impl HumanDecisionRepository {
     /// 根据ID查找人工决策
     pub async fn find_by_id(&self, decision_id: Uuid) -> Result<Option<human_decision::Model>> {
         human_decision::Entity::find_by_id(decision_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await
             .map_err(DatabaseError::from)
@@ -99,6 +119,7 @@ impl HumanDecisionRepository {
     /// 根据冲突ID查找决策
     pub async fn find_by_conflict_id(&self, conflict_id: Uuid) -> Result<Vec<human_decision::Model>> {
         human_decision::Entity::find()
+            .filter(self.tenant_condition())
             .filter(human_decision::Column::ConflictId.eq(conflict_id))
             .order_by_desc(human_decision::Column::CreatedAt)
             .all(&self.db)
@@ -109,6 +130,7 @@ impl HumanDecisionRepository {
     /// 根据用户ID查找决策
     pub async fn find_by_user_id(&self, user_id: Uuid) -> Result<Vec<human_decision::Model>> {
         human_decision::Entity::find()
+            .filter(self.tenant_condition())
             .filter(human_decision::Column::UserId.eq(user_id))
             .order_by_desc(human_decision::Column::CreatedAt)
             .all(&self.db)
@@ -119,6 +141,7 @@ impl HumanDecisionRepository {
     /// 根据决策类型查找决策
     pub async fn find_by_decision_type(&self, decision_type: &str) -> Result<Vec<human_decision::Model>> {
         human_decision::Entity::find()
+            .filter(self.tenant_condition())
             .filter(human_decision::Column::DecisionType.eq(decision_type))
             .order_by_desc(human_decision::Column::CreatedAt)
             .all(&self.db)
@@ -133,6 +156,7 @@ impl HumanDecisionRepository {
         reasoning: String,
     ) -> Result<human_decision::Model> {
         let decision = human_decision::Entity::find_by_id(decision_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("HumanDecision", decision_id))?;
@@ -152,6 +176,7 @@ impl HumanDecisionRepository {
         metadata: serde_json::Value,
     ) -> Result<human_decision::Model> {
         let decision = human_decision::Entity::find_by_id(decision_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("HumanDecision", decision_id))?;
@@ -164,18 +189,24 @@ impl HumanDecisionRepository {
             .map_err(DatabaseError::from)
     }
     
-    /// 删除人工决策
+    /// 删除人工决策，设置了租户范围时删除范围外的记录返回未找到
     pub async fn delete(&self, decision_id: Uuid) -> Result<()> {
-        human_decision::Entity::delete_by_id(decision_id)
+        let result = human_decision::Entity::delete_many()
+            .filter(human_decision::Column::DecisionId.eq(decision_id))
+            .filter(self.tenant_condition())
             .exec(&self.db)
             .await?;
         
+        if self.scope.is_some() && result.rows_affected == 0 {
+            return Err(DatabaseError::entity_not_found("HumanDecision", decision_id));
+        }
         Ok(())
     }
 
     /// 分析冲突的决策路径
     pub async fn analyze_decision_path(&self, conflict_id: Uuid) -> Result<DecisionPath> {
         let decisions = human_decision::Entity::find()
+            .filter(self.tenant_condition())
             .filter(human_decision::Column::ConflictId.eq(conflict_id))
             .order_by_asc(human_decision::Column::CreatedAt)
             .all(&self.db)
@@ -237,6 +268,7 @@ impl HumanDecisionRepository {
         additional_actions: serde_json::Value,
     ) -> Result<human_decision::Model> {
         let decision = human_decision::Entity::find_by_id(decision_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("HumanDecision", decision_id))?;
@@ -259,6 +291,7 @@ impl HumanDecisionRepository {
     /// 获取决策统计信息
     pub async fn get_decision_statistics(&self) -> Result<DecisionStatistics> {
         let decisions = human_decision::Entity::find()
+            .filter(self.tenant_condition())
             .all(&self.db)
             .await
             .map_err(DatabaseError::from)?;
@@ -304,6 +337,7 @@ impl HumanDecisionRepository {
         end_time: chrono::DateTime<chrono::Utc>,
     ) -> Result<Vec<human_decision::Model>> {
         human_decision::Entity::find()
+            .filter(self.tenant_condition())
             .filter(human_decision::Column::CreatedAt.gte(start_time))
             .filter(human_decision::Column::CreatedAt.lte(end_time))
             .order_by_desc(human_decision::Column::CreatedAt)
@@ -315,6 +349,7 @@ impl HumanDecisionRepository {
     /// 获取每小时决策频率统计
     pub async fn get_decision_frequency_by_hour(&self) -> Result<DecisionFrequencyStats> {
         let decisions = human_decision::Entity::find()
+            .filter(self.tenant_condition())
             .all(&self.db)
             .await
             .map_err(DatabaseError::from)?;
@@ -0,0 +1,98 @@
+//! 幂等键仓储实现
+
+use crate::{entities::idempotency_key, DatabaseConnection, DatabaseError, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set, SqlErr};
+use uuid::Uuid;
+
+/// 幂等键仓储
+pub struct IdempotencyKeyRepository {
+    db: DatabaseConnection,
+}
+
+impl IdempotencyKeyRepository {
+    /// 创建新的幂等键仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 以处理中状态占用幂等键，键已被占用时返回 `None`
+    pub async fn try_claim(
+        &self,
+        scope: &str,
+        key: &str,
+        request_hash: String,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<idempotency_key::Model>> {
+        let record_id = Uuid::new_v4();
+        let record = idempotency_key::ActiveModel {
+            record_id: Set(record_id),
+            scope: Set(scope.to_string()),
+            idempotency_key: Set(key.to_string()),
+            request_hash: Set(request_hash),
+            status: Set("processing".to_string()),
+            result: Set(None),
+            result_hash: Set(None),
+            created_at: Set(Utc::now().into()),
+            completed_at: Set(None),
+            expires_at: Set(expires_at.into()),
+        };
+
+        if let Err(e) = idempotency_key::Entity::insert(record).exec(&self.db).await {
+            return match e.sql_err() {
+                Some(SqlErr::UniqueConstraintViolation(_)) => Ok(None),
+                _ => Err(e.into()),
+            };
+        }
+
+        idempotency_key::Entity::find_by_id(record_id)
+            .one(&self.db)
+            .await?
+            .map(Some)
+            .ok_or_else(|| DatabaseError::entity_not_found("IdempotencyKey", record_id))
+    }
+
+    /// 根据作用域和键查找记录
+    pub async fn find(&self, scope: &str, key: &str) -> Result<Option<idempotency_key::Model>> {
+        idempotency_key::Entity::find()
+            .filter(idempotency_key::Column::Scope.eq(scope))
+            .filter(idempotency_key::Column::IdempotencyKey.eq(key))
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 保存执行结果并标记为已完成
+    pub async fn complete(
+        &self,
+        record_id: Uuid,
+        result: serde_json::Value,
+        result_hash: String,
+    ) -> Result<idempotency_key::Model> {
+        let record = idempotency_key::Entity::find_by_id(record_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("IdempotencyKey", record_id))?;
+        let mut active: idempotency_key::ActiveModel = record.into();
+        active.status = Set("completed".to_string());
+        active.result = Set(Some(result));
+        active.result_hash = Set(Some(result_hash));
+        active.completed_at = Set(Some(Utc::now().into()));
+        active.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 删除记录
+    pub async fn delete(&self, record_id: Uuid) -> Result<()> {
+        idempotency_key::Entity::delete_by_id(record_id).exec(&self.db).await?;
+        Ok(())
+    }
+
+    /// 删除已过期的记录
+    pub async fn delete_expired(&self, now: DateTime<Utc>) -> Result<u64> {
+        let result = idempotency_key::Entity::delete_many()
+            .filter(idempotency_key::Column::ExpiresAt.lt(now))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,78 @@
+//! 静态分析发现仓储实现
+
+use crate::{entities::lint_finding, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+/// 静态分析发现仓储
+pub struct LintFindingRepository {
+    db: DatabaseConnection,
+}
+
+/// 记录静态分析发现的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateLintFindingData {
+    pub project_id: Uuid,
+    pub execution_session_id: Uuid,
+    pub tool: String,
+    pub language: String,
+    pub file_path: String,
+    pub line: Option<i32>,
+    pub column: Option<i32>,
+    pub rule: Option<String>,
+    pub severity: String,
+    pub message: String,
+}
+
+impl LintFindingRepository {
+    /// 创建新的静态分析发现仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 批量记录静态分析发现
+    pub async fn create_many(&self, data: Vec<CreateLintFindingData>) -> Result<Vec<lint_finding::Model>> {
+        let now = chrono::Utc::now();
+        let mut findings = Vec::with_capacity(data.len());
+        for item in data {
+            let finding = lint_finding::ActiveModel {
+                finding_id: Set(Uuid::new_v4()),
+                project_id: Set(item.project_id),
+                execution_session_id: Set(item.execution_session_id),
+                tool: Set(item.tool),
+                language: Set(item.language),
+                file_path: Set(item.file_path),
+                line: Set(item.line),
+                column: Set(item.column),
+                rule: Set(item.rule),
+                severity: Set(item.severity),
+                message: Set(item.message),
+                created_at: Set(now.into()),
+            };
+            findings.push(finding.insert(&self.db).await.map_err(DatabaseError::from)?);
+        }
+        Ok(findings)
+    }
+
+    /// 查找执行会话的全部发现，按文件和行号排列
+    pub async fn find_by_session(&self, session_id: Uuid) -> Result<Vec<lint_finding::Model>> {
+        lint_finding::Entity::find()
+            .filter(lint_finding::Column::ExecutionSessionId.eq(session_id))
+            .order_by_asc(lint_finding::Column::FilePath)
+            .order_by_asc(lint_finding::Column::Line)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 删除执行会话中指定 Linter 的发现
+    pub async fn delete_by_session_and_tool(&self, session_id: Uuid, tool: &str) -> Result<u64> {
+        let result = lint_finding::Entity::delete_many()
+            .filter(lint_finding::Column::ExecutionSessionId.eq(session_id))
+            .filter(lint_finding::Column::Tool.eq(tool))
+            .exec(&self.db)
+            .await
+            .map_err(DatabaseError::from)?;
+        Ok(result.rows_affected)
+    }
+}
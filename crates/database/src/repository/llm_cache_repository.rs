@@ -0,0 +1,141 @@
+//! LLM响应缓存仓储实现
+
+use crate::{entities::llm_cache_entry, DatabaseConnection, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{ColumnTrait, EntityTrait, FromQueryResult, QueryFilter, QueryOrder, QuerySelect, Set};
+
+/// LLM响应缓存仓储
+pub struct LlmCacheRepository {
+    db: DatabaseConnection,
+}
+
+/// 写入缓存条目的数据结构
+#[derive(Debug, Clone)]
+pub struct PutLlmCacheData {
+    pub cache_key: String,
+    pub model: String,
+    pub prompt_hash: String,
+    pub response: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 缓存表的容量统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, FromQueryResult)]
+pub struct LlmCacheUsage {
+    pub entries: i64,
+    pub total_bytes: Option<i64>,
+    pub total_hits: Option<i64>,
+}
+
+impl LlmCacheRepository {
+    /// 创建新的LLM响应缓存仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 查找未过期的缓存条目
+    pub async fn find_valid(&self, cache_key: &str, now: DateTime<Utc>) -> Result<Option<llm_cache_entry::Model>> {
+        llm_cache_entry::Entity::find_by_id(cache_key.to_string())
+            .filter(llm_cache_entry::Column::ExpiresAt.gt(now))
+            .one(&self.db)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// 写入缓存条目，已存在时覆盖响应并重置命中次数和过期时间
+    pub async fn put(&self, data: PutLlmCacheData) -> Result<()> {
+        let now = Utc::now();
+        let entry = llm_cache_entry::ActiveModel {
+            cache_key: Set(data.cache_key),
+            model: Set(data.model),
+            prompt_hash: Set(data.prompt_hash),
+            size_bytes: Set(data.response.len() as i64),
+            response: Set(data.response),
+            hit_count: Set(0),
+            created_at: Set(now.into()),
+            last_used_at: Set(now.into()),
+            expires_at: Set(data.expires_at.into()),
+        };
+        llm_cache_entry::Entity::insert(entry)
+            .on_conflict(
+                OnConflict::column(llm_cache_entry::Column::CacheKey)
+                    .update_columns([
+                        llm_cache_entry::Column::Response,
+                        llm_cache_entry::Column::SizeBytes,
+                        llm_cache_entry::Column::HitCount,
+                        llm_cache_entry::Column::CreatedAt,
+                        llm_cache_entry::Column::LastUsedAt,
+                        llm_cache_entry::Column::ExpiresAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// 记录一次命中
+    pub async fn record_hit(&self, cache_key: &str, now: DateTime<Utc>) -> Result<()> {
+        let now: sea_orm::prelude::DateTimeWithTimeZone = now.into();
+        llm_cache_entry::Entity::update_many()
+            .col_expr(llm_cache_entry::Column::HitCount, Expr::col(llm_cache_entry::Column::HitCount).add(1))
+            .col_expr(llm_cache_entry::Column::LastUsedAt, Expr::value(now))
+            .filter(llm_cache_entry::Column::CacheKey.eq(cache_key))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// 删除已过期的条目，返回删除数量
+    pub async fn delete_expired(&self, now: DateTime<Utc>) -> Result<u64> {
+        let result = llm_cache_entry::Entity::delete_many()
+            .filter(llm_cache_entry::Column::ExpiresAt.lte(now))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 按最近使用时间从旧到新列出条目的（缓存键, 大小）
+    pub async fn list_least_recently_used(&self) -> Result<Vec<(String, i64)>> {
+        Ok(llm_cache_entry::Entity::find()
+            .select_only()
+            .column(llm_cache_entry::Column::CacheKey)
+            .column(llm_cache_entry::Column::SizeBytes)
+            .order_by_asc(llm_cache_entry::Column::LastUsedAt)
+            .into_tuple()
+            .all(&self.db)
+            .await?)
+    }
+
+    /// 删除指定的条目，返回删除数量
+    pub async fn delete_keys(&self, cache_keys: Vec<String>) -> Result<u64> {
+        if cache_keys.is_empty() {
+            return Ok(0);
+        }
+        let result = llm_cache_entry::Entity::delete_many()
+            .filter(llm_cache_entry::Column::CacheKey.is_in(cache_keys))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 清空缓存，返回删除数量
+    pub async fn clear(&self) -> Result<u64> {
+        let result = llm_cache_entry::Entity::delete_many().exec(&self.db).await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 统计条目数、总大小和累计命中次数
+    pub async fn usage(&self) -> Result<LlmCacheUsage> {
+        Ok(llm_cache_entry::Entity::find()
+            .select_only()
+            .column_as(llm_cache_entry::Column::CacheKey.count(), "entries")
+            .column_as(llm_cache_entry::Column::SizeBytes.sum(), "total_bytes")
+            .column_as(llm_cache_entry::Column::HitCount.sum(), "total_hits")
+            .into_model::<LlmCacheUsage>()
+            .one(&self.db)
+            .await?
+            .unwrap_or_default())
+    }
+}
@@ -16,6 +16,16 @@ impl LlmConversationRepository {
     }
 
     /// 创建新对话消息
+    #[tracing::instrument(
+        name = "llm_conversation.create",
+        skip(self, message_data),
+        fields(
+            session_id = %message_data.session_id,
+            role = %message_data.role,
+            model = message_data.model_used.as_deref(),
+            processing_time_ms = message_data.processing_time_ms,
+        )
+    )]
     pub async fn create(&self, message_data: CreateConversationMessageData) -> Result<llm_conversation::Model> {
         let now = chrono::Utc::now().into();
         let message_id = Uuid::new_v4();
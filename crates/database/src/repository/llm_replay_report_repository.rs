@@ -0,0 +1,71 @@
+//! LLM会话重放报告仓储实现
+
+use crate::{entities::llm_replay_report, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{EntityTrait, Set, ColumnTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+/// LLM会话重放报告仓储
+pub struct LlmReplayReportRepository {
+    db: DatabaseConnection,
+}
+
+impl LlmReplayReportRepository {
+    /// 创建新的LLM会话重放报告仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 保存重放报告
+    pub async fn create(&self, data: CreateLlmReplayReportData) -> Result<llm_replay_report::Model> {
+        let report_id = Uuid::new_v4();
+
+        let report = llm_replay_report::ActiveModel {
+            report_id: Set(report_id),
+            llm_session_id: Set(data.llm_session_id),
+            provider: Set(data.provider),
+            model_override: Set(data.model_override),
+            total_calls: Set(data.total_calls),
+            changed_calls: Set(data.changed_calls),
+            failed_calls: Set(data.failed_calls),
+            calls: Set(data.calls),
+            created_at: Set(chrono::Utc::now().into()),
+        };
+
+        llm_replay_report::Entity::insert(report).exec(&self.db).await?;
+
+        llm_replay_report::Entity::find_by_id(report_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("LlmReplayReport", report_id))
+    }
+
+    /// 根据ID查找重放报告
+    pub async fn find_by_id(&self, report_id: Uuid) -> Result<Option<llm_replay_report::Model>> {
+        llm_replay_report::Entity::find_by_id(report_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找LLM会话的所有重放报告（按时间倒序）
+    pub async fn find_by_session(&self, llm_session_id: Uuid) -> Result<Vec<llm_replay_report::Model>> {
+        llm_replay_report::Entity::find()
+            .filter(llm_replay_report::Column::LlmSessionId.eq(llm_session_id))
+            .order_by_desc(llm_replay_report::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+}
+
+/// 创建重放报告数据
+#[derive(Debug, Clone)]
+pub struct CreateLlmReplayReportData {
+    pub llm_session_id: Uuid,
+    pub provider: String,
+    pub model_override: Option<String>,
+    pub total_calls: i32,
+    pub changed_calls: i32,
+    pub failed_calls: i32,
+    pub calls: serde_json::Value,
+}
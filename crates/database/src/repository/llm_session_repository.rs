@@ -1,23 +1,55 @@
 //! LLM会话仓储实现
 
-use crate::{entities::llm_session, DatabaseConnection, DatabaseError, Result};
-use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter};
+use crate::{
+    entities::llm_session,
+    tenant_scope::{scoped_condition, TenantScope},
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter, Condition};
 use serde_json::Value as JsonValue;
 use uuid::Uuid;
 
 /// LLM会话仓储
 pub struct LlmSessionRepository {
     db: DatabaseConnection,
+    scope: Option<TenantScope>,
+    #[cfg(feature = "fault_injection")]
+    faults: Option<crate::fault_injection::FaultInjector>,
 }
 
 impl LlmSessionRepository {
     /// 创建新的LLM会话仓储实例
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self {
+            db,
+            scope: None,
+            #[cfg(feature = "fault_injection")]
+            faults: None,
+        }
+    }
+
+    /// 限定只能访问租户范围内项目的LLM会话
+    pub fn with_scope(mut self, scope: TenantScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    fn tenant_condition(&self) -> Condition {
+        scoped_condition(self.scope.as_ref(), |scope| scope.project_ref_condition(llm_session::Column::ProjectId))
+    }
+
+    /// 挂载故障注入器（写入会话结果时检查 LLM 超时故障点）
+    #[cfg(feature = "fault_injection")]
+    pub fn with_fault_injector(mut self, injector: crate::fault_injection::FaultInjector) -> Self {
+        self.faults = Some(injector);
+        self
     }
 
     /// 创建新LLM会话
     pub async fn create(&self, session_data: CreateLlmSessionData) -> Result<llm_session::Model> {
+        if let Some(scope) = &self.scope {
+            scope.ensure_project(&self.db, session_data.project_id).await?;
+        }
         let now = chrono::Utc::now().into();
         let session_id = Uuid::new_v4();
         
@@ -38,6 +70,7 @@ impl LlmSessionRepository {
         
         // 获取插入的会话
         llm_session::Entity::find_by_id(session_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("LlmSession", session_id))
@@ -46,6 +79,7 @@ impl LlmSessionRepository {
     /// 根据ID查找LLM会话
     pub async fn find_by_id(&self, session_id: Uuid) -> Result<Option<llm_session::Model>> {
         llm_session::Entity::find_by_id(session_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await
             .map_err(DatabaseError::from)
@@ -55,6 +89,7 @@ impl LlmSessionRepository {
     pub async fn find_by_project(&self, project_id: Uuid) -> Result<Vec<llm_session::Model>> {
         llm_session::Entity::find()
             .filter(llm_session::Column::ProjectId.eq(project_id))
+            .filter(self.tenant_condition())
             .all(&self.db)
             .await
             .map_err(DatabaseError::from)
@@ -64,6 +99,7 @@ impl LlmSessionRepository {
     pub async fn find_by_type(&self, project_id: Uuid, session_type: &str) -> Result<Vec<llm_session::Model>> {
         llm_session::Entity::find()
             .filter(llm_session::Column::ProjectId.eq(project_id))
+            .filter(self.tenant_condition())
             .filter(llm_session::Column::SessionType.eq(session_type))
             .all(&self.db)
             .await
@@ -74,6 +110,7 @@ impl LlmSessionRepository {
     pub async fn find_active(&self, project_id: Uuid) -> Result<Vec<llm_session::Model>> {
         llm_session::Entity::find()
             .filter(llm_session::Column::ProjectId.eq(project_id))
+            .filter(self.tenant_condition())
             .filter(llm_session::Column::Status.eq("active"))
             .all(&self.db)
             .await
@@ -81,6 +118,7 @@ impl LlmSessionRepository {
     }
     
     /// 更新会话结果
+    #[tracing::instrument(name = "llm_session.update_result", skip(self, result_data), fields(session_type = tracing::field::Empty))]
     pub async fn update_result(
         &self,
         session_id: Uuid,
@@ -88,18 +126,36 @@ impl LlmSessionRepository {
         status: String,
     ) -> Result<llm_session::Model> {
         let session = llm_session::Entity::find_by_id(session_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("LlmSession", session_id))?;
+        tracing::Span::current().record("session_type", session.session_type.as_str());
+
+        #[cfg(feature = "fault_injection")]
+        crate::fault_injection::check(
+            &self.faults,
+            crate::fault_injection::FaultPoint::LlmTimeout,
+            Some(session_id),
+        )?;
         
-        let now = chrono::Utc::now().into();
+        let now = chrono::Utc::now();
+        let finished = status == "completed" || status == "failed" || status == "cancelled";
+        if finished {
+            // 会话从创建到产出结果的耗时即一次LLM调用的端到端延迟
+            if let Ok(elapsed) = (now - session.created_at.with_timezone(&chrono::Utc)).to_std() {
+                crate::telemetry::record_llm_latency(&session.session_type, elapsed);
+            }
+        }
+
+        let now = now.into();
         let mut session: llm_session::ActiveModel = session.into();
         
         session.result_data = Set(Some(result_data));
         session.status = Set(status.clone());
         session.updated_at = Set(now);
         
-        if status == "completed" || status == "failed" || status == "cancelled" {
+        if finished {
             session.completed_at = Set(Some(now));
         }
         
@@ -117,6 +173,7 @@ impl LlmSessionRepository {
         allocation_prompt: Option<String>,
     ) -> Result<llm_session::Model> {
         let session = llm_session::Entity::find_by_id(session_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("LlmSession", session_id))?;
@@ -142,12 +199,17 @@ impl LlmSessionRepository {
             .map_err(DatabaseError::from)
     }
     
-    /// 删除LLM会话
+    /// 删除LLM会话，设置了租户范围时删除范围外的记录返回未找到
     pub async fn delete(&self, session_id: Uuid) -> Result<()> {
-        llm_session::Entity::delete_by_id(session_id)
+        let result = llm_session::Entity::delete_many()
+            .filter(llm_session::Column::SessionId.eq(session_id))
+            .filter(self.tenant_condition())
             .exec(&self.db)
             .await?;
         
+        if self.scope.is_some() && result.rows_affected == 0 {
+            return Err(DatabaseError::entity_not_found("LlmSession", session_id));
+        }
         Ok(())
     }
 }
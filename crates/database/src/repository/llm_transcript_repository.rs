@@ -0,0 +1,150 @@
+//! LLM调用记录仓储实现
+
+use crate::{
+    entities::llm_transcript,
+    tenant_scope::{scoped_condition, TenantScope},
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::{EntityTrait, Set, ColumnTrait, QueryFilter, QueryOrder, QuerySelect, Condition};
+use uuid::Uuid;
+
+/// LLM调用记录仓储
+pub struct LlmTranscriptRepository {
+    db: DatabaseConnection,
+    scope: Option<TenantScope>,
+}
+
+impl LlmTranscriptRepository {
+    /// 创建新的LLM调用记录仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db, scope: None }
+    }
+
+    /// 限定只能访问租户范围内项目的调用记录
+    pub fn with_scope(mut self, scope: TenantScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    fn tenant_condition(&self) -> Condition {
+        scoped_condition(self.scope.as_ref(), |scope| scope.project_ref_condition(llm_transcript::Column::ProjectId))
+    }
+
+    /// 保存一次调用记录
+    pub async fn create(&self, data: CreateLlmTranscriptData) -> Result<llm_transcript::Model> {
+        if let Some(scope) = &self.scope {
+            let project_id = data.project_id
+                .ok_or_else(|| DatabaseError::validation("租户范围内的记录必须归属于项目"))?;
+            scope.ensure_project(&self.db, project_id).await?;
+        }
+        let transcript_id = Uuid::new_v4();
+
+        let transcript = llm_transcript::ActiveModel {
+            transcript_id: Set(transcript_id),
+            call_kind: Set(data.call_kind),
+            provider: Set(data.provider),
+            model: Set(data.model),
+            prompt: Set(data.prompt),
+            request_params: Set(data.request_params),
+            response: Set(data.response),
+            error_message: Set(data.error_message),
+            tokens_used: Set(data.tokens_used),
+            latency_ms: Set(data.latency_ms),
+            project_id: Set(data.project_id),
+            task_id: Set(data.task_id),
+            llm_session_id: Set(data.llm_session_id),
+            execution_session_id: Set(data.execution_session_id),
+            conversation_id: Set(data.conversation_id),
+            created_at: Set(chrono::Utc::now().into()),
+        };
+
+        llm_transcript::Entity::insert(transcript).exec(&self.db).await?;
+
+        llm_transcript::Entity::find_by_id(transcript_id)
+            .filter(self.tenant_condition())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("LlmTranscript", transcript_id))
+    }
+
+    /// 根据ID查找调用记录
+    pub async fn find_by_id(&self, transcript_id: Uuid) -> Result<Option<llm_transcript::Model>> {
+        llm_transcript::Entity::find_by_id(transcript_id)
+            .filter(self.tenant_condition())
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找LLM会话的所有调用记录（按调用顺序）
+    pub async fn find_by_llm_session(&self, llm_session_id: Uuid) -> Result<Vec<llm_transcript::Model>> {
+        llm_transcript::Entity::find()
+            .filter(llm_transcript::Column::LlmSessionId.eq(llm_session_id))
+            .filter(self.tenant_condition())
+            .order_by_asc(llm_transcript::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 按条件查找调用记录（按时间倒序）
+    pub async fn find(&self, filter: &LlmTranscriptFilter) -> Result<Vec<llm_transcript::Model>> {
+        let mut query = llm_transcript::Entity::find().filter(self.tenant_condition());
+        if let Some(project_id) = filter.project_id {
+            query = query.filter(llm_transcript::Column::ProjectId.eq(project_id));
+        }
+        if let Some(task_id) = filter.task_id {
+            query = query.filter(llm_transcript::Column::TaskId.eq(task_id));
+        }
+        if let Some(llm_session_id) = filter.llm_session_id {
+            query = query.filter(llm_transcript::Column::LlmSessionId.eq(llm_session_id));
+        }
+        if let Some(execution_session_id) = filter.execution_session_id {
+            query = query.filter(llm_transcript::Column::ExecutionSessionId.eq(execution_session_id));
+        }
+        if let Some(conversation_id) = &filter.conversation_id {
+            query = query.filter(llm_transcript::Column::ConversationId.eq(conversation_id.as_str()));
+        }
+        if let Some(call_kind) = &filter.call_kind {
+            query = query.filter(llm_transcript::Column::CallKind.eq(call_kind.as_str()));
+        }
+        query
+            .order_by_desc(llm_transcript::Column::CreatedAt)
+            .limit(filter.limit)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+}
+
+/// 创建调用记录数据
+#[derive(Debug, Clone)]
+pub struct CreateLlmTranscriptData {
+    pub call_kind: String,
+    pub provider: String,
+    pub model: String,
+    pub prompt: String,
+    pub request_params: Option<serde_json::Value>,
+    pub response: Option<String>,
+    pub error_message: Option<String>,
+    pub tokens_used: Option<i32>,
+    pub latency_ms: i64,
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub llm_session_id: Option<Uuid>,
+    pub execution_session_id: Option<Uuid>,
+    pub conversation_id: Option<String>,
+}
+
+/// 调用记录查询条件，为空的条件不参与过滤
+#[derive(Debug, Clone, Default)]
+pub struct LlmTranscriptFilter {
+    pub project_id: Option<Uuid>,
+    pub task_id: Option<Uuid>,
+    pub llm_session_id: Option<Uuid>,
+    pub execution_session_id: Option<Uuid>,
+    pub conversation_id: Option<String>,
+    pub call_kind: Option<String>,
+    /// 最多返回的条数，为空时不限制
+    pub limit: Option<u64>,
+}
@@ -0,0 +1,169 @@
+//! 维护任务仓储实现
+
+use crate::{entities::maintenance_job, DatabaseConnection, DatabaseError, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, Set};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 维护任务仓储
+pub struct MaintenanceJobRepository {
+    db: DatabaseConnection,
+}
+
+/// 维护任务的可配置内容
+#[derive(Debug, Clone)]
+pub struct MaintenanceJobData {
+    pub name: String,
+    pub kind: String,
+    pub cron_expression: String,
+    pub jitter_secs: i32,
+    pub params: JsonValue,
+    pub enabled: bool,
+}
+
+impl MaintenanceJobRepository {
+    /// 创建新的维护任务仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 创建维护任务
+    pub async fn create(&self, data: MaintenanceJobData, next_run_at: DateTime<Utc>) -> Result<maintenance_job::Model> {
+        let now = Utc::now().into();
+        let job = maintenance_job::ActiveModel {
+            job_id: Set(Uuid::new_v4()),
+            name: Set(data.name),
+            kind: Set(data.kind),
+            cron_expression: Set(data.cron_expression),
+            jitter_secs: Set(data.jitter_secs),
+            params: Set(data.params),
+            enabled: Set(data.enabled),
+            next_run_at: Set(next_run_at.into()),
+            last_run_at: Set(None),
+            last_status: Set(None),
+            running_since: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+        job.insert(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 根据ID查找维护任务
+    pub async fn find_by_id(&self, job_id: Uuid) -> Result<Option<maintenance_job::Model>> {
+        maintenance_job::Entity::find_by_id(job_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 根据名称查找维护任务
+    pub async fn find_by_name(&self, name: &str) -> Result<Option<maintenance_job::Model>> {
+        maintenance_job::Entity::find()
+            .filter(maintenance_job::Column::Name.eq(name))
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 列出全部维护任务
+    pub async fn list(&self) -> Result<Vec<maintenance_job::Model>> {
+        maintenance_job::Entity::find()
+            .order_by_asc(maintenance_job::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找已到执行时间的启用任务
+    pub async fn find_due(&self, now: DateTime<Utc>) -> Result<Vec<maintenance_job::Model>> {
+        maintenance_job::Entity::find()
+            .filter(maintenance_job::Column::Enabled.eq(true))
+            .filter(maintenance_job::Column::NextRunAt.lte(now))
+            .order_by_asc(maintenance_job::Column::NextRunAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 更新执行计划
+    pub async fn update_schedule(
+        &self,
+        job_id: Uuid,
+        cron_expression: String,
+        jitter_secs: i32,
+        enabled: bool,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<maintenance_job::Model> {
+        let job = self
+            .find_by_id(job_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("MaintenanceJob", job_id))?;
+        let mut job: maintenance_job::ActiveModel = job.into();
+        job.cron_expression = Set(cron_expression);
+        job.jitter_secs = Set(jitter_secs);
+        job.enabled = Set(enabled);
+        job.next_run_at = Set(next_run_at.into());
+        job.updated_at = Set(Utc::now().into());
+        job.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 以条件更新占用执行锁，锁空闲或开始时间早于 `stale_before` 时成功
+    pub async fn try_lock(&self, job_id: Uuid, now: DateTime<Utc>, stale_before: DateTime<Utc>) -> Result<bool> {
+        let now: sea_orm::prelude::DateTimeWithTimeZone = now.into();
+        let stale_before: sea_orm::prelude::DateTimeWithTimeZone = stale_before.into();
+        let result = maintenance_job::Entity::update_many()
+            .col_expr(maintenance_job::Column::RunningSince, Expr::value(now))
+            .filter(maintenance_job::Column::JobId.eq(job_id))
+            .filter(
+                Condition::any()
+                    .add(maintenance_job::Column::RunningSince.is_null())
+                    .add(maintenance_job::Column::RunningSince.lt(stale_before)),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected == 1)
+    }
+
+    /// 释放执行锁并记录执行结果
+    pub async fn finish_run(
+        &self,
+        job_id: Uuid,
+        status: &str,
+        run_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<maintenance_job::Model> {
+        let job = self
+            .find_by_id(job_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("MaintenanceJob", job_id))?;
+        let mut job: maintenance_job::ActiveModel = job.into();
+        job.running_since = Set(None);
+        job.last_run_at = Set(Some(run_at.into()));
+        job.last_status = Set(Some(status.to_string()));
+        if let Some(next_run_at) = next_run_at {
+            job.next_run_at = Set(next_run_at.into());
+        }
+        job.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 设置下一次执行时间
+    pub async fn set_next_run(&self, job_id: Uuid, next_run_at: DateTime<Utc>) -> Result<()> {
+        let next_run_at: sea_orm::prelude::DateTimeWithTimeZone = next_run_at.into();
+        maintenance_job::Entity::update_many()
+            .col_expr(maintenance_job::Column::NextRunAt, Expr::value(next_run_at))
+            .filter(maintenance_job::Column::JobId.eq(job_id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// 删除维护任务，执行记录随之删除
+    pub async fn delete(&self, job_id: Uuid) -> Result<()> {
+        maintenance_job::Entity::delete_by_id(job_id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+//! 维护任务执行记录仓储实现
+
+use crate::{entities::maintenance_job_run, DatabaseConnection, DatabaseError, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 维护任务执行记录仓储
+pub struct MaintenanceJobRunRepository {
+    db: DatabaseConnection,
+}
+
+impl MaintenanceJobRunRepository {
+    /// 创建新的执行记录仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 记录一次执行的开始
+    pub async fn create(
+        &self,
+        job_id: Uuid,
+        trigger: &str,
+        status: &str,
+        started_at: DateTime<Utc>,
+        error_message: Option<String>,
+    ) -> Result<maintenance_job_run::Model> {
+        let run = maintenance_job_run::ActiveModel {
+            run_id: Set(Uuid::new_v4()),
+            job_id: Set(job_id),
+            trigger: Set(trigger.to_string()),
+            status: Set(status.to_string()),
+            started_at: Set(started_at.into()),
+            finished_at: Set(None),
+            duration_ms: Set(None),
+            result: Set(None),
+            error_message: Set(error_message),
+        };
+        run.insert(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 记录一次执行的结束
+    pub async fn finish(
+        &self,
+        run_id: Uuid,
+        status: &str,
+        finished_at: DateTime<Utc>,
+        duration_ms: i64,
+        result: Option<JsonValue>,
+        error_message: Option<String>,
+    ) -> Result<maintenance_job_run::Model> {
+        let run = maintenance_job_run::Entity::find_by_id(run_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("MaintenanceJobRun", run_id))?;
+        let mut run: maintenance_job_run::ActiveModel = run.into();
+        run.status = Set(status.to_string());
+        run.finished_at = Set(Some(finished_at.into()));
+        run.duration_ms = Set(Some(duration_ms));
+        run.result = Set(result);
+        run.error_message = Set(error_message);
+        run.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 把仍处于执行中的记录标记为失败，用于接管超时的执行锁
+    pub async fn fail_running(&self, job_id: Uuid, finished_at: DateTime<Utc>, message: &str) -> Result<u64> {
+        let finished_at: sea_orm::prelude::DateTimeWithTimeZone = finished_at.into();
+        let result = maintenance_job_run::Entity::update_many()
+            .col_expr(maintenance_job_run::Column::Status, Expr::value("failed"))
+            .col_expr(maintenance_job_run::Column::FinishedAt, Expr::value(finished_at))
+            .col_expr(maintenance_job_run::Column::ErrorMessage, Expr::value(message))
+            .filter(maintenance_job_run::Column::JobId.eq(job_id))
+            .filter(maintenance_job_run::Column::Status.eq("running"))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 按开始时间倒序列出任务的执行记录
+    pub async fn find_by_job(&self, job_id: Uuid, limit: u64) -> Result<Vec<maintenance_job_run::Model>> {
+        maintenance_job_run::Entity::find()
+            .filter(maintenance_job_run::Column::JobId.eq(job_id))
+            .order_by_desc(maintenance_job_run::Column::StartedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 删除开始时间早于 `before` 的已结束记录，返回删除数量
+    pub async fn delete_finished_before(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = maintenance_job_run::Entity::delete_many()
+            .filter(maintenance_job_run::Column::StartedAt.lt(before))
+            .filter(maintenance_job_run::Column::Status.ne("running"))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,121 @@
+//! 合并冲突解决记录仓储实现
+
+use crate::{
+    entities::merge_resolution::{self, MergeResolutionStatus},
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+/// 合并冲突解决记录仓储
+pub struct MergeResolutionRepository {
+    db: DatabaseConnection,
+}
+
+/// 创建解决记录的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateMergeResolutionData {
+    pub conflict_id: Option<Uuid>,
+    pub file_path: String,
+    pub hunk_hash: String,
+    pub resolution: String,
+    pub strategy: String,
+    pub confidence: f64,
+    pub status: MergeResolutionStatus,
+}
+
+impl MergeResolutionRepository {
+    /// 创建新的解决记录仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 创建解决记录
+    pub async fn create(&self, data: CreateMergeResolutionData) -> Result<merge_resolution::Model> {
+        let now = chrono::Utc::now().into();
+        let resolution_id = Uuid::new_v4();
+
+        let record = merge_resolution::ActiveModel {
+            resolution_id: Set(resolution_id),
+            conflict_id: Set(data.conflict_id),
+            file_path: Set(data.file_path),
+            hunk_hash: Set(data.hunk_hash),
+            resolution: Set(data.resolution),
+            strategy: Set(data.strategy),
+            confidence: Set(data.confidence),
+            status: Set(data.status.to_string()),
+            use_count: Set(0),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        merge_resolution::Entity::insert(record).exec(&self.db).await?;
+
+        self.find_by_id(resolution_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("MergeResolution", resolution_id))
+    }
+
+    /// 根据ID查找解决记录
+    pub async fn find_by_id(&self, resolution_id: Uuid) -> Result<Option<merge_resolution::Model>> {
+        merge_resolution::Entity::find_by_id(resolution_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找冲突块已批准的解决记录（最近使用优先）
+    pub async fn find_approved_by_hash(&self, hunk_hash: &str) -> Result<Option<merge_resolution::Model>> {
+        merge_resolution::Entity::find()
+            .filter(merge_resolution::Column::HunkHash.eq(hunk_hash))
+            .filter(merge_resolution::Column::Status.eq(MergeResolutionStatus::Approved.to_string()))
+            .order_by_desc(merge_resolution::Column::UpdatedAt)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找冲突下等待批准的解决记录
+    pub async fn find_pending_by_conflict(&self, conflict_id: Uuid) -> Result<Vec<merge_resolution::Model>> {
+        merge_resolution::Entity::find()
+            .filter(merge_resolution::Column::ConflictId.eq(conflict_id))
+            .filter(merge_resolution::Column::Status.eq(MergeResolutionStatus::Pending.to_string()))
+            .order_by_asc(merge_resolution::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 更新解决记录状态
+    pub async fn update_status(
+        &self,
+        resolution_id: Uuid,
+        status: MergeResolutionStatus,
+    ) -> Result<merge_resolution::Model> {
+        let record = self
+            .find_by_id(resolution_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("MergeResolution", resolution_id))?;
+
+        let mut record: merge_resolution::ActiveModel = record.into();
+        record.status = Set(status.to_string());
+        record.updated_at = Set(chrono::Utc::now().into());
+
+        record.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 记录一次复用
+    pub async fn increment_use_count(&self, resolution_id: Uuid) -> Result<merge_resolution::Model> {
+        let record = self
+            .find_by_id(resolution_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("MergeResolution", resolution_id))?;
+
+        let use_count = record.use_count + 1;
+        let mut record: merge_resolution::ActiveModel = record.into();
+        record.use_count = Set(use_count);
+        record.updated_at = Set(chrono::Utc::now().into());
+
+        record.update(&self.db).await.map_err(DatabaseError::from)
+    }
+}
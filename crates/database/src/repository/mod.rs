@@ -8,11 +8,17 @@ pub mod project_repository;
 pub mod requirement_document_repository;
 pub mod llm_session_repository;
 pub mod llm_conversation_repository;
+pub mod llm_transcript_repository;
+pub mod llm_replay_report_repository;
+pub mod eval_set_repository;
+pub mod eval_run_repository;
+pub mod eval_result_repository;
 pub mod task_repository;
 pub mod agent_repository;
 pub mod agent_work_history_repository;
 pub mod execution_session_repository;
 pub mod execution_log_repository;
+pub mod execution_artifact_repository;
 pub mod conflict_repository;
 pub mod human_decision_repository;
 pub mod domain_event_repository;
@@ -20,6 +26,41 @@ pub mod event_publish_log_repository;
 pub mod code_review_repository;
 pub mod task_dependency_repository;
 pub mod agent_performance_metrics_repository;
+pub mod technical_debt_snapshot_repository;
+pub mod merge_resolution_repository;
+pub mod project_member_repository;
+pub mod organization_repository;
+pub mod embedding_repository;
+pub mod requirement_coverage_repository;
+pub mod acceptance_verification_repository;
+pub mod blackboard_repository;
+pub mod command_audit_log_repository;
+pub mod pii_mapping_repository;
+pub mod ci_check_run_repository;
+pub mod remote_worker_repository;
+pub mod sla_breach_repository;
+pub mod webhook_dead_letter_repository;
+pub mod report_schedule_repository;
+pub mod project_report_repository;
+pub mod llm_cache_repository;
+pub mod agent_self_assessment_repository;
+pub mod task_gate_repository;
+pub mod test_case_result_repository;
+pub mod coverage_report_repository;
+pub mod lint_finding_repository;
+pub mod security_finding_repository;
+pub mod benchmark_result_repository;
+pub mod api_key_repository;
+pub mod user_identity_repository;
+pub mod user_two_factor_repository;
+pub mod notification_repository;
+pub mod idempotency_key_repository;
+pub mod maintenance_job_repository;
+pub mod maintenance_job_run_repository;
+pub mod daily_metrics_repository;
+pub mod offloaded_payload_repository;
+pub mod project_partition_repository;
+pub mod context_snapshot_repository;
 
 // 重新导出
 pub use user_repository::UserRepository;
@@ -33,10 +74,51 @@ pub use agent_repository::AgentRepository;
 pub use agent_work_history_repository::AgentWorkHistoryRepository;
 pub use execution_session_repository::ExecutionSessionRepository;
 pub use execution_log_repository::ExecutionLogRepository;
+pub use execution_artifact_repository::ExecutionArtifactRepository;
 pub use conflict_repository::ConflictRepository;
 pub use human_decision_repository::HumanDecisionRepository;
 pub use domain_event_repository::DomainEventRepository;
 pub use event_publish_log_repository::EventPublishLogRepository;
 pub use code_review_repository::CodeReviewRepository;
 pub use task_dependency_repository::TaskDependencyRepository;
-pub use agent_performance_metrics_repository::AgentPerformanceMetricsRepository;
\ No newline at end of file
+pub use agent_performance_metrics_repository::AgentPerformanceMetricsRepository;
+pub use technical_debt_snapshot_repository::TechnicalDebtSnapshotRepository;
+pub use merge_resolution_repository::MergeResolutionRepository;
+pub use project_member_repository::ProjectMemberRepository;
+pub use organization_repository::OrganizationRepository;
+pub use embedding_repository::EmbeddingRepository;
+pub use requirement_coverage_repository::RequirementCoverageRepository;
+pub use acceptance_verification_repository::AcceptanceVerificationRepository;
+pub use blackboard_repository::BlackboardRepository;
+pub use command_audit_log_repository::CommandAuditLogRepository;
+pub use pii_mapping_repository::PiiMappingRepository;
+pub use ci_check_run_repository::CiCheckRunRepository;
+pub use remote_worker_repository::RemoteWorkerRepository;
+pub use sla_breach_repository::SlaBreachRepository;
+pub use webhook_dead_letter_repository::WebhookDeadLetterRepository;
+pub use report_schedule_repository::ReportScheduleRepository;
+pub use project_report_repository::ProjectReportRepository;
+pub use llm_cache_repository::LlmCacheRepository;
+pub use agent_self_assessment_repository::AgentSelfAssessmentRepository;
+pub use task_gate_repository::TaskGateRepository;
+pub use test_case_result_repository::TestCaseResultRepository;
+pub use coverage_report_repository::CoverageReportRepository;
+pub use lint_finding_repository::LintFindingRepository;
+pub use security_finding_repository::SecurityFindingRepository;
+pub use benchmark_result_repository::BenchmarkResultRepository;
+pub use api_key_repository::ApiKeyRepository;
+pub use user_identity_repository::UserIdentityRepository;
+pub use user_two_factor_repository::UserTwoFactorRepository;
+pub use notification_repository::NotificationRepository;
+pub use idempotency_key_repository::IdempotencyKeyRepository;
+pub use maintenance_job_repository::MaintenanceJobRepository;
+pub use maintenance_job_run_repository::MaintenanceJobRunRepository;
+pub use daily_metrics_repository::DailyMetricsRepository;
+pub use offloaded_payload_repository::OffloadedPayloadRepository;
+pub use project_partition_repository::ProjectPartitionRepository;
+pub use context_snapshot_repository::ContextSnapshotRepository;
+pub use llm_transcript_repository::LlmTranscriptRepository;
+pub use llm_replay_report_repository::LlmReplayReportRepository;
+pub use eval_set_repository::EvalSetRepository;
+pub use eval_run_repository::EvalRunRepository;
+pub use eval_result_repository::EvalResultRepository;
\ No newline at end of file
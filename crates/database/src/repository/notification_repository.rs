@@ -0,0 +1,100 @@
+//! 通知仓储实现
+
+use crate::{entities::notification, DatabaseConnection, DatabaseError, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use uuid::Uuid;
+
+/// 通知仓储
+pub struct NotificationRepository {
+    db: DatabaseConnection,
+}
+
+/// 创建通知的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateNotificationData {
+    pub user_id: Uuid,
+    pub category: String,
+    pub channel: String,
+    pub title: String,
+    pub body: String,
+    pub project_id: Option<Uuid>,
+    pub urgent: bool,
+    pub digest: bool,
+    pub deliver_at: DateTime<Utc>,
+}
+
+impl NotificationRepository {
+    /// 创建新的通知仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 创建通知
+    pub async fn create(&self, data: CreateNotificationData) -> Result<notification::Model> {
+        let notification = notification::ActiveModel {
+            notification_id: Set(Uuid::new_v4()),
+            user_id: Set(data.user_id),
+            category: Set(data.category),
+            channel: Set(data.channel),
+            title: Set(data.title),
+            body: Set(data.body),
+            project_id: Set(data.project_id),
+            urgent: Set(data.urgent),
+            digest: Set(data.digest),
+            deliver_at: Set(data.deliver_at.into()),
+            delivered_at: Set(None),
+            read_at: Set(None),
+            created_at: Set(Utc::now().into()),
+        };
+        notification.insert(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 查找到期未投递的通知，按计划投递时间排序
+    pub async fn find_due(&self, now: DateTime<Utc>) -> Result<Vec<notification::Model>> {
+        notification::Entity::find()
+            .filter(notification::Column::DeliveredAt.is_null())
+            .filter(notification::Column::DeliverAt.lte(now))
+            .order_by_asc(notification::Column::DeliverAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找用户在某个渠道的通知，最新的在前
+    pub async fn find_by_user(&self, user_id: Uuid, channel: &str, limit: u64) -> Result<Vec<notification::Model>> {
+        notification::Entity::find()
+            .filter(notification::Column::UserId.eq(user_id))
+            .filter(notification::Column::Channel.eq(channel))
+            .order_by_desc(notification::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 标记为已投递
+    pub async fn mark_delivered(&self, notification_ids: Vec<Uuid>, delivered_at: DateTime<Utc>) -> Result<u64> {
+        let result = notification::Entity::update_many()
+            .col_expr(notification::Column::DeliveredAt, Expr::value(delivered_at))
+            .filter(notification::Column::NotificationId.is_in(notification_ids))
+            .filter(notification::Column::DeliveredAt.is_null())
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// 标记用户的通知为已读
+    pub async fn mark_read(&self, user_id: Uuid, notification_ids: Vec<Uuid>) -> Result<u64> {
+        let result = notification::Entity::update_many()
+            .col_expr(notification::Column::ReadAt, Expr::value(Utc::now()))
+            .filter(notification::Column::UserId.eq(user_id))
+            .filter(notification::Column::NotificationId.is_in(notification_ids))
+            .filter(notification::Column::ReadAt.is_null())
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,89 @@
+//! 转存负载仓储实现
+
+use crate::{entities::offloaded_payload, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QuerySelect, Set};
+use uuid::Uuid;
+
+/// 转存负载仓储
+pub struct OffloadedPayloadRepository {
+    db: DatabaseConnection,
+}
+
+/// 记录转存负载的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateOffloadedPayloadData {
+    pub owner_table: String,
+    pub owner_id: Uuid,
+    pub checksum: String,
+    pub original_size: u64,
+    pub stored_size: u64,
+}
+
+impl OffloadedPayloadRepository {
+    /// 创建新的转存负载仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 记录转存负载
+    pub async fn create(&self, data: CreateOffloadedPayloadData) -> Result<offloaded_payload::Model> {
+        let payload = offloaded_payload::ActiveModel {
+            payload_id: Set(Uuid::new_v4()),
+            owner_table: Set(data.owner_table),
+            owner_id: Set(data.owner_id),
+            checksum: Set(data.checksum),
+            original_size: Set(data.original_size as i64),
+            stored_size: Set(data.stored_size as i64),
+            created_at: Set(chrono::Utc::now().into()),
+        };
+        payload.insert(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 查找某一行引用的转存负载
+    pub async fn find_by_owner(&self, owner_table: &str, owner_id: Uuid) -> Result<Vec<offloaded_payload::Model>> {
+        offloaded_payload::Entity::find()
+            .filter(offloaded_payload::Column::OwnerTable.eq(owner_table))
+            .filter(offloaded_payload::Column::OwnerId.eq(owner_id))
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找某张表的全部转存负载
+    pub async fn find_by_table(&self, owner_table: &str) -> Result<Vec<offloaded_payload::Model>> {
+        offloaded_payload::Entity::find()
+            .filter(offloaded_payload::Column::OwnerTable.eq(owner_table))
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 引用指定内容的记录数
+    pub async fn count_by_checksum(&self, checksum: &str) -> Result<u64> {
+        offloaded_payload::Entity::find()
+            .filter(offloaded_payload::Column::Checksum.eq(checksum))
+            .count(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 所有转存负载引用的内容校验和
+    pub async fn find_all_checksums(&self) -> Result<Vec<String>> {
+        offloaded_payload::Entity::find()
+            .select_only()
+            .column(offloaded_payload::Column::Checksum)
+            .distinct()
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 删除记录
+    pub async fn delete(&self, payload_id: Uuid) -> Result<()> {
+        offloaded_payload::Entity::delete_by_id(payload_id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,357 @@
+//! 组织仓储实现
+
+use crate::{
+    entities::{agent, organization, organization_member, project, team},
+    DatabaseConnection, DatabaseError, Result,
+};
+use codex_multi_agent::{CodingStandards, OrganizationRole, OrganizationSettings};
+use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+/// 组织仓储
+pub struct OrganizationRepository {
+    db: DatabaseConnection,
+}
+
+impl OrganizationRepository {
+    /// 创建新的组织仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 创建组织，创建者自动成为所有者
+    pub async fn create(&self, data: CreateOrganizationData) -> Result<organization::Model> {
+        let exists = organization::Entity::find()
+            .filter(organization::Column::Name.eq(data.name.as_str()))
+            .one(&self.db)
+            .await?;
+        if exists.is_some() {
+            return Err(DatabaseError::validation(format!("组织名称已存在: {}", data.name)));
+        }
+
+        let now = chrono::Utc::now().into();
+        let organization_id = Uuid::new_v4();
+        let settings = data.settings
+            .map(serde_json::to_value)
+            .transpose()?;
+
+        let organization = organization::ActiveModel {
+            organization_id: Set(organization_id),
+            name: Set(data.name),
+            description: Set(data.description),
+            owner_id: Set(data.owner_id),
+            settings: Set(settings),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        organization::Entity::insert(organization).exec(&self.db).await?;
+        self.add_member(organization_id, data.owner_id, OrganizationRole::Owner, None).await?;
+
+        self.find_by_id(organization_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Organization", organization_id))
+    }
+
+    /// 根据ID查找组织
+    pub async fn find_by_id(&self, organization_id: Uuid) -> Result<Option<organization::Model>> {
+        organization::Entity::find_by_id(organization_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找用户所属的所有组织
+    pub async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<organization::Model>> {
+        let organization_ids: Vec<Uuid> = organization_member::Entity::find()
+            .filter(organization_member::Column::UserId.eq(user_id))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|m| m.organization_id)
+            .collect();
+
+        organization::Entity::find()
+            .filter(organization::Column::OrganizationId.is_in(organization_ids))
+            .order_by_asc(organization::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 更新组织级设置
+    pub async fn update_settings(
+        &self,
+        organization_id: Uuid,
+        settings: OrganizationSettings,
+    ) -> Result<organization::Model> {
+        let organization = self.find_by_id(organization_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Organization", organization_id))?;
+
+        let mut organization: organization::ActiveModel = organization.into();
+        organization.settings = Set(Some(serde_json::to_value(settings)?));
+        organization.updated_at = Set(chrono::Utc::now().into());
+
+        organization.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 删除组织（项目和Agent的组织归属会被置空）
+    pub async fn delete(&self, organization_id: Uuid) -> Result<()> {
+        organization::Entity::delete_by_id(organization_id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------
+    // 成员与团队
+    // ------------------------------------------------------------------
+
+    /// 添加组织成员
+    pub async fn add_member(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        role: OrganizationRole,
+        team_id: Option<Uuid>,
+    ) -> Result<organization_member::Model> {
+        if self.find_member(organization_id, user_id).await?.is_some() {
+            return Err(DatabaseError::validation("用户已是组织成员"));
+        }
+        if let Some(team_id) = team_id {
+            self.ensure_team_in_organization(organization_id, team_id).await?;
+        }
+
+        let member_id = Uuid::new_v4();
+        let member = organization_member::ActiveModel {
+            member_id: Set(member_id),
+            organization_id: Set(organization_id),
+            user_id: Set(user_id),
+            team_id: Set(team_id),
+            role: Set(role.as_str().to_string()),
+            joined_at: Set(chrono::Utc::now().into()),
+        };
+
+        organization_member::Entity::insert(member).exec(&self.db).await?;
+
+        organization_member::Entity::find_by_id(member_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("OrganizationMember", member_id))
+    }
+
+    /// 查找用户在组织中的成员记录
+    pub async fn find_member(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<organization_member::Model>> {
+        organization_member::Entity::find()
+            .filter(organization_member::Column::OrganizationId.eq(organization_id))
+            .filter(organization_member::Column::UserId.eq(user_id))
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 列出组织成员
+    pub async fn list_members(&self, organization_id: Uuid) -> Result<Vec<organization_member::Model>> {
+        organization_member::Entity::find()
+            .filter(organization_member::Column::OrganizationId.eq(organization_id))
+            .order_by_asc(organization_member::Column::JoinedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 移除组织成员，组织所有者不可移除
+    pub async fn remove_member(&self, organization_id: Uuid, user_id: Uuid) -> Result<()> {
+        let member = self.find_member(organization_id, user_id)
+            .await?
+            .ok_or_else(|| DatabaseError::validation("用户不是组织成员"))?;
+        if member.role == OrganizationRole::Owner.as_str() {
+            return Err(DatabaseError::business_logic("不能移除组织所有者"));
+        }
+
+        organization_member::Entity::delete_by_id(member.member_id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// 获取用户在组织中的角色
+    pub async fn get_role(&self, organization_id: Uuid, user_id: Uuid) -> Result<Option<OrganizationRole>> {
+        match self.find_member(organization_id, user_id).await? {
+            Some(member) => member.role
+                .parse::<OrganizationRole>()
+                .map(Some)
+                .map_err(DatabaseError::validation),
+            None => Ok(None),
+        }
+    }
+
+    /// 创建团队
+    pub async fn create_team(
+        &self,
+        organization_id: Uuid,
+        name: String,
+        description: Option<String>,
+    ) -> Result<team::Model> {
+        let exists = team::Entity::find()
+            .filter(team::Column::OrganizationId.eq(organization_id))
+            .filter(team::Column::Name.eq(name.as_str()))
+            .one(&self.db)
+            .await?;
+        if exists.is_some() {
+            return Err(DatabaseError::validation(format!("团队名称已存在: {}", name)));
+        }
+
+        let now = chrono::Utc::now().into();
+        let team_id = Uuid::new_v4();
+        let team = team::ActiveModel {
+            team_id: Set(team_id),
+            organization_id: Set(organization_id),
+            name: Set(name),
+            description: Set(description),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        team::Entity::insert(team).exec(&self.db).await?;
+
+        team::Entity::find_by_id(team_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Team", team_id))
+    }
+
+    /// 列出组织下的团队
+    pub async fn list_teams(&self, organization_id: Uuid) -> Result<Vec<team::Model>> {
+        team::Entity::find()
+            .filter(team::Column::OrganizationId.eq(organization_id))
+            .order_by_asc(team::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 将组织成员分配到团队（None 表示移出团队）
+    pub async fn assign_member_team(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        team_id: Option<Uuid>,
+    ) -> Result<organization_member::Model> {
+        let member = self.find_member(organization_id, user_id)
+            .await?
+            .ok_or_else(|| DatabaseError::validation("用户不是组织成员"))?;
+        if let Some(team_id) = team_id {
+            self.ensure_team_in_organization(organization_id, team_id).await?;
+        }
+
+        let mut member: organization_member::ActiveModel = member.into();
+        member.team_id = Set(team_id);
+        member.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 列出团队成员
+    pub async fn list_team_members(&self, team_id: Uuid) -> Result<Vec<organization_member::Model>> {
+        organization_member::Entity::find()
+            .filter(organization_member::Column::TeamId.eq(team_id))
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    async fn ensure_team_in_organization(&self, organization_id: Uuid, team_id: Uuid) -> Result<()> {
+        let team = team::Entity::find_by_id(team_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Team", team_id))?;
+        if team.organization_id != organization_id {
+            return Err(DatabaseError::validation("团队不属于该组织"));
+        }
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------
+    // 项目与Agent池
+    // ------------------------------------------------------------------
+
+    /// 设置项目所属组织（None 表示移出组织）
+    pub async fn assign_project(&self, project_id: Uuid, organization_id: Option<Uuid>) -> Result<project::Model> {
+        let project = project::Entity::find_by_id(project_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+
+        let mut project: project::ActiveModel = project.into();
+        project.organization_id = Set(organization_id);
+        project.updated_at = Set(chrono::Utc::now().into());
+        project.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 列出组织下的项目
+    pub async fn list_projects(&self, organization_id: Uuid) -> Result<Vec<project::Model>> {
+        project::Entity::find()
+            .filter(project::Column::OrganizationId.eq(organization_id))
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 将Agent加入组织共享池（None 表示移出）
+    pub async fn assign_agent(&self, agent_id: Uuid, organization_id: Option<Uuid>) -> Result<agent::Model> {
+        let agent = agent::Entity::find_by_id(agent_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Agent", agent_id))?;
+
+        let mut agent: agent::ActiveModel = agent.into();
+        agent.organization_id = Set(organization_id);
+        agent.updated_at = Set(chrono::Utc::now().into());
+        agent.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 列出组织共享的Agent池
+    pub async fn list_agent_pool(&self, organization_id: Uuid) -> Result<Vec<agent::Model>> {
+        agent::Entity::find()
+            .filter(agent::Column::OrganizationId.eq(organization_id))
+            .order_by_asc(agent::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 计算项目生效的编码规范：项目配置优先，其次继承所属组织的默认设置
+    pub async fn resolve_project_standards(&self, project_id: Uuid) -> Result<CodingStandards> {
+        let project = project::Entity::find_by_id(project_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+
+        let project_standards = project.coding_standards
+            .as_ref()
+            .and_then(|value| serde_json::from_value::<CodingStandards>(value.clone()).ok());
+
+        let settings = match project.organization_id {
+            Some(organization_id) => self.find_by_id(organization_id)
+                .await?
+                .map(|organization| organization.get_settings())
+                .unwrap_or_default(),
+            None => OrganizationSettings::default(),
+        };
+
+        Ok(settings.resolve_coding_standards(project_standards))
+    }
+}
+
+/// 创建组织的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateOrganizationData {
+    pub name: String,
+    pub description: Option<String>,
+    pub owner_id: Uuid,
+    pub settings: Option<OrganizationSettings>,
+}
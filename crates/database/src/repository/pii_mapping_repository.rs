@@ -0,0 +1,72 @@
+//! 个人信息脱敏映射仓储实现
+
+use crate::{entities::pii_mapping, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set, SqlErr};
+use uuid::Uuid;
+
+/// 个人信息脱敏映射仓储
+pub struct PiiMappingRepository {
+    db: DatabaseConnection,
+}
+
+/// 创建映射的数据结构
+#[derive(Debug, Clone)]
+pub struct CreatePiiMappingData {
+    pub project_id: Uuid,
+    pub placeholder: String,
+    pub label: String,
+    pub original: String,
+}
+
+impl PiiMappingRepository {
+    /// 创建新的个人信息脱敏映射仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 保存映射，占位符已被占用时返回并发冲突错误
+    pub async fn create(&self, data: CreatePiiMappingData) -> Result<pii_mapping::Model> {
+        let mapping_id = Uuid::new_v4();
+        let mapping = pii_mapping::ActiveModel {
+            mapping_id: Set(mapping_id),
+            project_id: Set(data.project_id),
+            placeholder: Set(data.placeholder.clone()),
+            label: Set(data.label),
+            original: Set(data.original),
+            created_at: Set(chrono::Utc::now().into()),
+        };
+
+        if let Err(e) = pii_mapping::Entity::insert(mapping).exec(&self.db).await {
+            return Err(match e.sql_err() {
+                Some(SqlErr::UniqueConstraintViolation(_)) => {
+                    DatabaseError::conflict(format!("占位符 {} 已被其他映射占用", data.placeholder))
+                }
+                _ => e.into(),
+            });
+        }
+
+        pii_mapping::Entity::find_by_id(mapping_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("PiiMapping", mapping_id))
+    }
+
+    /// 查找项目的全部映射（按创建时间升序）
+    pub async fn find_by_project(&self, project_id: Uuid) -> Result<Vec<pii_mapping::Model>> {
+        pii_mapping::Entity::find()
+            .filter(pii_mapping::Column::ProjectId.eq(project_id))
+            .order_by_asc(pii_mapping::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 删除项目的全部映射，返回删除数量
+    pub async fn delete_by_project(&self, project_id: Uuid) -> Result<u64> {
+        let result = pii_mapping::Entity::delete_many()
+            .filter(pii_mapping::Column::ProjectId.eq(project_id))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,211 @@
+//! 项目成员仓储实现
+
+use crate::{
+    entities::{project, project_member},
+    DatabaseConnection, DatabaseError, Result,
+};
+use codex_multi_agent::ProjectRole;
+use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+/// 项目成员仓储
+pub struct ProjectMemberRepository {
+    db: DatabaseConnection,
+}
+
+impl ProjectMemberRepository {
+    /// 创建新的项目成员仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 添加项目成员
+    pub async fn add_member(
+        &self,
+        project_id: Uuid,
+        user_id: Uuid,
+        role: ProjectRole,
+        invited_by: Option<Uuid>,
+    ) -> Result<project_member::Model> {
+        if self.find_member(project_id, user_id).await?.is_some() {
+            return Err(DatabaseError::validation("用户已是项目成员"));
+        }
+
+        // 旧项目首次添加成员时，先把创建者登记为所有者，避免其失去隐式所有权
+        if !self.has_members(project_id).await? {
+            let project = project::Entity::find_by_id(project_id)
+                .one(&self.db)
+                .await?
+                .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+            if project.user_id != user_id {
+                self.insert_member(project_id, project.user_id, ProjectRole::Owner, None).await?;
+            }
+        }
+
+        self.insert_member(project_id, user_id, role, invited_by).await
+    }
+
+    async fn insert_member(
+        &self,
+        project_id: Uuid,
+        user_id: Uuid,
+        role: ProjectRole,
+        invited_by: Option<Uuid>,
+    ) -> Result<project_member::Model> {
+        let now = chrono::Utc::now().into();
+        let member_id = Uuid::new_v4();
+
+        let member = project_member::ActiveModel {
+            member_id: Set(member_id),
+            project_id: Set(project_id),
+            user_id: Set(user_id),
+            role: Set(role.as_str().to_string()),
+            invited_by: Set(invited_by),
+            joined_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        project_member::Entity::insert(member).exec(&self.db).await?;
+
+        project_member::Entity::find_by_id(member_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ProjectMember", member_id))
+    }
+
+    /// 查找用户在项目中的成员记录
+    pub async fn find_member(&self, project_id: Uuid, user_id: Uuid) -> Result<Option<project_member::Model>> {
+        project_member::Entity::find()
+            .filter(project_member::Column::ProjectId.eq(project_id))
+            .filter(project_member::Column::UserId.eq(user_id))
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 列出项目的所有成员
+    pub async fn list_members(&self, project_id: Uuid) -> Result<Vec<project_member::Model>> {
+        project_member::Entity::find()
+            .filter(project_member::Column::ProjectId.eq(project_id))
+            .order_by_asc(project_member::Column::JoinedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找用户参与的所有项目ID
+    pub async fn find_project_ids_by_user(&self, user_id: Uuid) -> Result<Vec<Uuid>> {
+        let members = project_member::Entity::find()
+            .filter(project_member::Column::UserId.eq(user_id))
+            .all(&self.db)
+            .await?;
+
+        Ok(members.into_iter().map(|m| m.project_id).collect())
+    }
+
+    /// 获取用户在项目中的角色
+    ///
+    /// 项目没有任何成员记录时，项目创建者（projects.user_id）视为所有者，兼容成员表之前创建的项目
+    pub async fn get_role(&self, project_id: Uuid, user_id: Uuid) -> Result<Option<ProjectRole>> {
+        if let Some(member) = self.find_member(project_id, user_id).await? {
+            return member.role.parse::<ProjectRole>()
+                .map(Some)
+                .map_err(DatabaseError::validation);
+        }
+
+        if self.has_members(project_id).await? {
+            return Ok(None);
+        }
+
+        let project = project::Entity::find_by_id(project_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+
+        Ok((project.user_id == user_id).then_some(ProjectRole::Owner))
+    }
+
+    /// 校验用户至少具备指定角色，返回其实际角色
+    pub async fn require_role(
+        &self,
+        project_id: Uuid,
+        user_id: Uuid,
+        required: ProjectRole,
+    ) -> Result<ProjectRole> {
+        match self.get_role(project_id, user_id).await? {
+            Some(role) if role.at_least(required) => Ok(role),
+            Some(role) => Err(DatabaseError::business_logic(format!(
+                "权限不足：需要 {} 角色，当前为 {}",
+                required.as_str(),
+                role.as_str()
+            ))),
+            None => Err(DatabaseError::business_logic("不是该项目的成员")),
+        }
+    }
+
+    /// 更新成员角色，返回变更前的角色
+    pub async fn update_role(
+        &self,
+        project_id: Uuid,
+        user_id: Uuid,
+        role: ProjectRole,
+    ) -> Result<(project_member::Model, ProjectRole)> {
+        let member = self.find_member(project_id, user_id)
+            .await?
+            .ok_or_else(|| DatabaseError::validation("用户不是项目成员"))?;
+        let previous_role = member.role.parse::<ProjectRole>()
+            .map_err(DatabaseError::validation)?;
+
+        if previous_role == ProjectRole::Owner && role != ProjectRole::Owner {
+            self.ensure_not_last_owner(project_id).await?;
+        }
+
+        let mut member: project_member::ActiveModel = member.into();
+        member.role = Set(role.as_str().to_string());
+        member.updated_at = Set(chrono::Utc::now().into());
+
+        let updated = member.update(&self.db).await?;
+        Ok((updated, previous_role))
+    }
+
+    /// 移除项目成员，返回被移除成员的角色
+    pub async fn remove_member(&self, project_id: Uuid, user_id: Uuid) -> Result<ProjectRole> {
+        let member = self.find_member(project_id, user_id)
+            .await?
+            .ok_or_else(|| DatabaseError::validation("用户不是项目成员"))?;
+        let role = member.role.parse::<ProjectRole>()
+            .map_err(DatabaseError::validation)?;
+
+        if role == ProjectRole::Owner {
+            self.ensure_not_last_owner(project_id).await?;
+        }
+
+        project_member::Entity::delete_by_id(member.member_id)
+            .exec(&self.db)
+            .await?;
+
+        Ok(role)
+    }
+
+    async fn has_members(&self, project_id: Uuid) -> Result<bool> {
+        let member = project_member::Entity::find()
+            .filter(project_member::Column::ProjectId.eq(project_id))
+            .one(&self.db)
+            .await?;
+        Ok(member.is_some())
+    }
+
+    /// 项目至少保留一个所有者
+    async fn ensure_not_last_owner(&self, project_id: Uuid) -> Result<()> {
+        let owners = project_member::Entity::find()
+            .filter(project_member::Column::ProjectId.eq(project_id))
+            .filter(project_member::Column::Role.eq(ProjectRole::Owner.as_str()))
+            .all(&self.db)
+            .await?;
+
+        if owners.len() <= 1 {
+            return Err(DatabaseError::business_logic("项目至少需要保留一个所有者"));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+//! 项目数据库文件仓储实现
+
+use crate::{entities::project_partition, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder, Set};
+use uuid::Uuid;
+
+/// 项目数据库文件仓储，读写目录数据库中的登记表
+pub struct ProjectPartitionRepository {
+    db: DatabaseConnection,
+}
+
+impl ProjectPartitionRepository {
+    /// 创建新的项目数据库文件仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 登记项目数据库文件
+    pub async fn create(&self, project_id: Uuid, file_name: &str) -> Result<project_partition::Model> {
+        let partition = project_partition::ActiveModel {
+            project_id: Set(project_id),
+            file_name: Set(file_name.to_string()),
+            created_at: Set(chrono::Utc::now().into()),
+            last_attached_at: Set(None),
+        };
+        partition.insert(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 查找项目的数据库文件
+    pub async fn find_by_project(&self, project_id: Uuid) -> Result<Option<project_partition::Model>> {
+        project_partition::Entity::find_by_id(project_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 列出全部项目数据库文件
+    pub async fn list(&self) -> Result<Vec<project_partition::Model>> {
+        project_partition::Entity::find()
+            .order_by_asc(project_partition::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 记录最近一次打开的时间
+    pub async fn touch_attached(&self, project_id: Uuid) -> Result<project_partition::Model> {
+        let partition = self
+            .find_by_project(project_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ProjectPartition", project_id))?;
+        let mut active: project_partition::ActiveModel = partition.into();
+        active.last_attached_at = Set(Some(chrono::Utc::now().into()));
+        active.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 删除登记记录，不删除数据库文件
+    pub async fn delete(&self, project_id: Uuid) -> Result<bool> {
+        let result = project_partition::Entity::delete_by_id(project_id).exec(&self.db).await?;
+        Ok(result.rows_affected > 0)
+    }
+}
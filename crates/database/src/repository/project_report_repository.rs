@@ -0,0 +1,98 @@
+//! 项目报告仓储实现
+
+use crate::{entities::project_report, DatabaseConnection, DatabaseError, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use uuid::Uuid;
+
+/// 项目报告仓储
+pub struct ProjectReportRepository {
+    db: DatabaseConnection,
+}
+
+/// 记录项目报告的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateProjectReportData {
+    pub project_id: Uuid,
+    pub schedule_id: Option<Uuid>,
+    pub title: String,
+    pub format: String,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub checksum: String,
+    pub size_bytes: u64,
+    pub summary: serde_json::Value,
+}
+
+impl ProjectReportRepository {
+    /// 创建新的项目报告仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 记录生成的报告
+    pub async fn create(&self, data: CreateProjectReportData) -> Result<project_report::Model> {
+        let report = project_report::ActiveModel {
+            report_id: Set(Uuid::new_v4()),
+            project_id: Set(data.project_id),
+            schedule_id: Set(data.schedule_id),
+            title: Set(data.title),
+            format: Set(data.format),
+            period_start: Set(data.period_start.into()),
+            period_end: Set(data.period_end.into()),
+            checksum: Set(data.checksum),
+            size_bytes: Set(data.size_bytes as i64),
+            summary: Set(data.summary),
+            created_at: Set(Utc::now().into()),
+        };
+        report.insert(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 根据ID查找报告
+    pub async fn find_by_id(&self, report_id: Uuid) -> Result<Option<project_report::Model>> {
+        project_report::Entity::find_by_id(report_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找项目的报告（按生成时间降序）
+    pub async fn find_by_project(&self, project_id: Uuid, limit: u64) -> Result<Vec<project_report::Model>> {
+        project_report::Entity::find()
+            .filter(project_report::Column::ProjectId.eq(project_id))
+            .order_by_desc(project_report::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 引用指定内容的报告数量
+    pub async fn count_by_checksum(&self, checksum: &str) -> Result<u64> {
+        project_report::Entity::find()
+            .filter(project_report::Column::Checksum.eq(checksum))
+            .count(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 所有报告引用的内容校验和
+    pub async fn find_all_checksums(&self) -> Result<Vec<String>> {
+        project_report::Entity::find()
+            .select_only()
+            .column(project_report::Column::Checksum)
+            .distinct()
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 删除报告记录
+    pub async fn delete(&self, report_id: Uuid) -> Result<()> {
+        project_report::Entity::delete_by_id(report_id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,23 +1,45 @@
 //! 项目仓储实现
 
-use crate::{entities::project, DatabaseConnection, DatabaseError, Result};
-use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter};
+use crate::{code_ownership::CodeOwnership, entities::project, perf_budget::PerfBudget, pii_scrubbing::PiiScrubbingConfig, preemption::PreemptionPolicy, sla::SlaPolicy, tenant_scope::{scoped_condition, TenantScope}, workspace_quota::WorkspaceQuota, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, Condition, QueryFilter};
 use serde_json::Value as JsonValue;
+use crate::ids::EntityKey;
+use codex_multi_agent::ProjectId;
 use uuid::Uuid;
 
 /// 项目仓储
 pub struct ProjectRepository {
     db: DatabaseConnection,
+    scope: Option<TenantScope>,
 }
 
 impl ProjectRepository {
     /// 创建新的项目仓储实例
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self { db, scope: None }
+    }
+
+    /// 限定只能访问租户范围内的项目
+    pub fn with_scope(mut self, scope: TenantScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    fn tenant_condition(&self) -> Condition {
+        scoped_condition(self.scope.as_ref(), TenantScope::project_condition)
     }
 
     /// 创建新项目
+    ///
+    /// 用户范围只能为该用户创建项目，组织范围创建的项目归属于该组织
     pub async fn create(&self, project_data: CreateProjectData) -> Result<project::Model> {
+        let organization_id = match self.scope {
+            Some(TenantScope::User(user_id)) if user_id != project_data.user_id => {
+                return Err(DatabaseError::validation("不能为租户范围之外的用户创建项目"));
+            }
+            Some(TenantScope::Organization(organization_id)) => Some(organization_id),
+            _ => None,
+        };
         let now = chrono::Utc::now().into();
         let project_id = Uuid::new_v4();
         
@@ -32,6 +54,7 @@ impl ProjectRepository {
             status: Set("active".to_string()),
             created_at: Set(now),
             updated_at: Set(now),
+            organization_id: Set(organization_id),
             ..Default::default()
         };
         
@@ -39,14 +62,17 @@ impl ProjectRepository {
         
         // 获取插入的项目
         project::Entity::find_by_id(project_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))
     }
     
     /// 根据ID查找项目
-    pub async fn find_by_id(&self, project_id: Uuid) -> Result<Option<project::Model>> {
+    pub async fn find_by_id(&self, project_id: impl EntityKey<ProjectId>) -> Result<Option<project::Model>> {
+        let project_id = project_id.into_key();
         project::Entity::find_by_id(project_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await
             .map_err(DatabaseError::from)
@@ -55,21 +81,33 @@ impl ProjectRepository {
     /// 根据用户ID查找项目
     pub async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<project::Model>> {
         project::Entity::find()
+            .filter(self.tenant_condition())
             .filter(project::Column::UserId.eq(user_id))
             .all(&self.db)
             .await
             .map_err(DatabaseError::from)
     }
     
+    /// 查找全部项目，设置了租户范围时只返回范围内的项目
+    pub async fn find_all(&self) -> Result<Vec<project::Model>> {
+        project::Entity::find()
+            .filter(self.tenant_condition())
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
     /// 更新项目配置
     pub async fn update_config(
         &self,
-        project_id: Uuid,
+        project_id: impl EntityKey<ProjectId>,
         technology_stack: Option<JsonValue>,
         coding_standards: Option<JsonValue>,
         git_settings: Option<JsonValue>,
     ) -> Result<project::Model> {
+        let project_id = project_id.into_key();
         let project = project::Entity::find_by_id(project_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
@@ -98,11 +136,13 @@ impl ProjectRepository {
     /// 更新项目上下文信息
     pub async fn update_context(
         &self,
-        project_id: Uuid,
+        project_id: impl EntityKey<ProjectId>,
         codebase_info: Option<JsonValue>,
         project_context: Option<JsonValue>,
     ) -> Result<project::Model> {
+        let project_id = project_id.into_key();
         let project = project::Entity::find_by_id(project_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
@@ -124,13 +164,33 @@ impl ProjectRepository {
             .map_err(DatabaseError::from)
     }
     
+    /// 更新项目主分支
+    pub async fn update_main_branch(&self, project_id: impl EntityKey<ProjectId>, main_branch: &str) -> Result<project::Model> {
+        let project_id = project_id.into_key();
+        let project = project::Entity::find_by_id(project_id)
+            .filter(self.tenant_condition())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+
+        let mut project: project::ActiveModel = project.into();
+        project.main_branch = Set(main_branch.to_string());
+        project.updated_at = Set(chrono::Utc::now().into());
+
+        project.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
     /// 更新项目状态
     pub async fn update_status(
         &self,
-        project_id: Uuid,
+        project_id: impl EntityKey<ProjectId>,
         status: &str,
     ) -> Result<project::Model> {
+        let project_id = project_id.into_key();
         let project = project::Entity::find_by_id(project_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
@@ -144,12 +204,174 @@ impl ProjectRepository {
             .map_err(DatabaseError::from)
     }
     
-    /// 删除项目
-    pub async fn delete(&self, project_id: Uuid) -> Result<()> {
-        project::Entity::delete_by_id(project_id)
+    /// 设置工作空间磁盘配额，传入 None 取消限制
+    pub async fn set_workspace_quota(
+        &self,
+        project_id: impl EntityKey<ProjectId>,
+        quota: Option<WorkspaceQuota>,
+    ) -> Result<project::Model> {
+        let project_id = project_id.into_key();
+        if let Some(quota) = &quota {
+            quota.validate()?;
+        }
+        
+        let project = project::Entity::find_by_id(project_id)
+            .filter(self.tenant_condition())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+        
+        let mut project: project::ActiveModel = project.into();
+        project.workspace_quota = Set(quota.map(serde_json::to_value).transpose()?);
+        project.updated_at = Set(chrono::Utc::now().into());
+        
+        project.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
+    /// 设置个人信息脱敏配置，传入 None 关闭脱敏
+    pub async fn set_pii_scrubbing(
+        &self,
+        project_id: impl EntityKey<ProjectId>,
+        config: Option<PiiScrubbingConfig>,
+    ) -> Result<project::Model> {
+        let project_id = project_id.into_key();
+        if let Some(config) = &config {
+            config.validate()?;
+        }
+        
+        let project = project::Entity::find_by_id(project_id)
+            .filter(self.tenant_condition())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+        
+        let mut project: project::ActiveModel = project.into();
+        project.pii_scrubbing = Set(config.map(serde_json::to_value).transpose()?);
+        project.updated_at = Set(chrono::Utc::now().into());
+        
+        project.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
+    /// 设置紧急任务抢占策略，传入 None 关闭抢占
+    pub async fn set_preemption_policy(
+        &self,
+        project_id: impl EntityKey<ProjectId>,
+        policy: Option<PreemptionPolicy>,
+    ) -> Result<project::Model> {
+        let project_id = project_id.into_key();
+        if let Some(policy) = &policy {
+            policy.validate()?;
+        }
+        
+        let project = project::Entity::find_by_id(project_id)
+            .filter(self.tenant_condition())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+        
+        let mut project: project::ActiveModel = project.into();
+        project.preemption_policy = Set(policy.map(serde_json::to_value).transpose()?);
+        project.updated_at = Set(chrono::Utc::now().into());
+        
+        project.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
+    /// 设置SLA配置，传入 None 关闭SLA监控
+    pub async fn set_sla_policy(
+        &self,
+        project_id: impl EntityKey<ProjectId>,
+        policy: Option<SlaPolicy>,
+    ) -> Result<project::Model> {
+        let project_id = project_id.into_key();
+        if let Some(policy) = &policy {
+            policy.validate()?;
+        }
+        
+        let project = project::Entity::find_by_id(project_id)
+            .filter(self.tenant_condition())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+        
+        let mut project: project::ActiveModel = project.into();
+        project.sla_policy = Set(policy.map(serde_json::to_value).transpose()?);
+        project.updated_at = Set(chrono::Utc::now().into());
+        
+        project.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
+    /// 设置代码归属规则，传入 None 清除规则
+    pub async fn set_code_ownership(
+        &self,
+        project_id: impl EntityKey<ProjectId>,
+        ownership: Option<CodeOwnership>,
+    ) -> Result<project::Model> {
+        let project_id = project_id.into_key();
+        if let Some(ownership) = &ownership {
+            ownership.validate()?;
+        }
+        
+        let project = project::Entity::find_by_id(project_id)
+            .filter(self.tenant_condition())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+        
+        let mut project: project::ActiveModel = project.into();
+        project.code_ownership = Set(ownership.map(serde_json::to_value).transpose()?);
+        project.updated_at = Set(chrono::Utc::now().into());
+        
+        project.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
+    /// 设置项目的性能预算，传入 None 表示不做性能回归检查
+    pub async fn set_perf_budget(
+        &self,
+        project_id: impl EntityKey<ProjectId>,
+        budget: Option<PerfBudget>,
+    ) -> Result<project::Model> {
+        let project_id = project_id.into_key();
+        if let Some(budget) = &budget {
+            budget.validate()?;
+        }
+        
+        let project = project::Entity::find_by_id(project_id)
+            .filter(self.tenant_condition())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+        
+        let mut project: project::ActiveModel = project.into();
+        project.perf_budget = Set(budget.map(serde_json::to_value).transpose()?);
+        project.updated_at = Set(chrono::Utc::now().into());
+        
+        project.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
+    /// 删除项目，设置了租户范围时删除范围外的项目返回未找到
+    pub async fn delete(&self, project_id: impl EntityKey<ProjectId>) -> Result<()> {
+        let project_id = project_id.into_key();
+        let result = project::Entity::delete_many()
+            .filter(project::Column::ProjectId.eq(project_id))
+            .filter(self.tenant_condition())
             .exec(&self.db)
             .await?;
         
+        if self.scope.is_some() && result.rows_affected == 0 {
+            return Err(DatabaseError::entity_not_found("Project", project_id));
+        }
         Ok(())
     }
 }
@@ -0,0 +1,122 @@
+//! 远程工作进程仓储实现
+
+use crate::{entities::remote_worker, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use uuid::Uuid;
+
+/// 工作进程在线状态
+pub const WORKER_STATUS_ONLINE: &str = "online";
+
+/// 工作进程离线状态
+pub const WORKER_STATUS_OFFLINE: &str = "offline";
+
+/// 远程工作进程仓储
+pub struct RemoteWorkerRepository {
+    db: DatabaseConnection,
+}
+
+/// 注册工作进程的数据结构
+#[derive(Debug, Clone)]
+pub struct RegisterWorkerData {
+    pub name: String,
+    pub agent_ids: Vec<Uuid>,
+    pub capabilities: Vec<String>,
+    pub max_concurrent_tasks: i32,
+    pub protocol_version: i32,
+}
+
+impl RemoteWorkerRepository {
+    /// 创建新的远程工作进程仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 注册工作进程，每次注册都分配新的工作进程ID
+    pub async fn register(&self, data: RegisterWorkerData) -> Result<remote_worker::Model> {
+        if data.max_concurrent_tasks < 1 {
+            return Err(DatabaseError::validation("最大并发任务数必须大于0"));
+        }
+
+        let now: DateTimeWithTimeZone = chrono::Utc::now().into();
+        let worker_id = Uuid::new_v4();
+        let worker = remote_worker::ActiveModel {
+            worker_id: Set(worker_id),
+            name: Set(data.name),
+            agent_ids: Set(serde_json::to_value(data.agent_ids)?),
+            capabilities: Set(serde_json::to_value(data.capabilities)?),
+            max_concurrent_tasks: Set(data.max_concurrent_tasks),
+            protocol_version: Set(data.protocol_version),
+            status: Set(WORKER_STATUS_ONLINE.to_string()),
+            last_heartbeat_at: Set(now),
+            registered_at: Set(now),
+            updated_at: Set(now),
+        };
+        remote_worker::Entity::insert(worker).exec(&self.db).await?;
+
+        self.find_by_id(worker_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("RemoteWorker", worker_id))
+    }
+
+    /// 根据ID查找工作进程
+    pub async fn find_by_id(&self, worker_id: Uuid) -> Result<Option<remote_worker::Model>> {
+        remote_worker::Entity::find_by_id(worker_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 记录心跳，离线的工作进程不能通过心跳恢复，需要重新注册
+    pub async fn heartbeat(&self, worker_id: Uuid) -> Result<remote_worker::Model> {
+        let worker = self.find_online(worker_id).await?;
+        let now: DateTimeWithTimeZone = chrono::Utc::now().into();
+        let mut worker: remote_worker::ActiveModel = worker.into();
+        worker.last_heartbeat_at = Set(now);
+        worker.updated_at = Set(now);
+        worker.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 查找在线的工作进程，不存在或已离线时返回错误
+    pub async fn find_online(&self, worker_id: Uuid) -> Result<remote_worker::Model> {
+        let worker = self
+            .find_by_id(worker_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("RemoteWorker", worker_id))?;
+        if worker.status != WORKER_STATUS_ONLINE {
+            return Err(DatabaseError::business_logic(format!("工作进程 {} 已离线，请重新注册", worker_id)));
+        }
+        Ok(worker)
+    }
+
+    /// 标记工作进程离线
+    pub async fn mark_offline(&self, worker_id: Uuid) -> Result<remote_worker::Model> {
+        let worker = self
+            .find_by_id(worker_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("RemoteWorker", worker_id))?;
+        let mut worker: remote_worker::ActiveModel = worker.into();
+        worker.status = Set(WORKER_STATUS_OFFLINE.to_string());
+        worker.updated_at = Set(chrono::Utc::now().into());
+        worker.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 查找最后心跳早于指定时间的在线工作进程
+    pub async fn find_stale(&self, heartbeat_before: DateTimeWithTimeZone) -> Result<Vec<remote_worker::Model>> {
+        remote_worker::Entity::find()
+            .filter(remote_worker::Column::Status.eq(WORKER_STATUS_ONLINE))
+            .filter(remote_worker::Column::LastHeartbeatAt.lt(heartbeat_before))
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 列出所有工作进程（按最后心跳时间降序）
+    pub async fn list(&self) -> Result<Vec<remote_worker::Model>> {
+        remote_worker::Entity::find()
+            .order_by_desc(remote_worker::Column::LastHeartbeatAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+}
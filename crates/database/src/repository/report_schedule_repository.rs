@@ -0,0 +1,136 @@
+//! 报告计划仓储实现
+
+use crate::{entities::report_schedule, DatabaseConnection, DatabaseError, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+/// 报告计划仓储
+pub struct ReportScheduleRepository {
+    db: DatabaseConnection,
+}
+
+/// 报告计划的可配置内容
+#[derive(Debug, Clone)]
+pub struct ReportScheduleData {
+    pub name: String,
+    pub cron_expression: String,
+    pub format: String,
+    pub template: Option<String>,
+    pub channels: serde_json::Value,
+    pub cost_per_1k_tokens: Option<f64>,
+    pub enabled: bool,
+}
+
+impl ReportScheduleRepository {
+    /// 创建新的报告计划仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 创建报告计划
+    pub async fn create(
+        &self,
+        project_id: Uuid,
+        created_by: Uuid,
+        data: ReportScheduleData,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<report_schedule::Model> {
+        let now = Utc::now().into();
+        let schedule = report_schedule::ActiveModel {
+            schedule_id: Set(Uuid::new_v4()),
+            project_id: Set(project_id),
+            name: Set(data.name),
+            cron_expression: Set(data.cron_expression),
+            format: Set(data.format),
+            template: Set(data.template),
+            channels: Set(data.channels),
+            cost_per_1k_tokens: Set(data.cost_per_1k_tokens),
+            enabled: Set(data.enabled),
+            last_run_at: Set(None),
+            next_run_at: Set(next_run_at.into()),
+            created_by: Set(created_by),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+        schedule.insert(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 根据ID查找报告计划
+    pub async fn find_by_id(&self, schedule_id: Uuid) -> Result<Option<report_schedule::Model>> {
+        report_schedule::Entity::find_by_id(schedule_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找项目的报告计划
+    pub async fn find_by_project(&self, project_id: Uuid) -> Result<Vec<report_schedule::Model>> {
+        report_schedule::Entity::find()
+            .filter(report_schedule::Column::ProjectId.eq(project_id))
+            .order_by_asc(report_schedule::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找已到生成时间的启用计划
+    pub async fn find_due(&self, now: DateTime<Utc>) -> Result<Vec<report_schedule::Model>> {
+        report_schedule::Entity::find()
+            .filter(report_schedule::Column::Enabled.eq(true))
+            .filter(report_schedule::Column::NextRunAt.lte(now))
+            .order_by_asc(report_schedule::Column::NextRunAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 更新报告计划的配置
+    pub async fn update(
+        &self,
+        schedule_id: Uuid,
+        data: ReportScheduleData,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<report_schedule::Model> {
+        let schedule = self
+            .find_by_id(schedule_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ReportSchedule", schedule_id))?;
+        let mut schedule: report_schedule::ActiveModel = schedule.into();
+        schedule.name = Set(data.name);
+        schedule.cron_expression = Set(data.cron_expression);
+        schedule.format = Set(data.format);
+        schedule.template = Set(data.template);
+        schedule.channels = Set(data.channels);
+        schedule.cost_per_1k_tokens = Set(data.cost_per_1k_tokens);
+        schedule.enabled = Set(data.enabled);
+        schedule.next_run_at = Set(next_run_at.into());
+        schedule.updated_at = Set(Utc::now().into());
+        schedule.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 记录一次生成，并设置下一次生成时间
+    pub async fn record_run(
+        &self,
+        schedule_id: Uuid,
+        run_at: DateTime<Utc>,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<report_schedule::Model> {
+        let schedule = self
+            .find_by_id(schedule_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ReportSchedule", schedule_id))?;
+        let mut schedule: report_schedule::ActiveModel = schedule.into();
+        schedule.last_run_at = Set(Some(run_at.into()));
+        schedule.next_run_at = Set(next_run_at.into());
+        schedule.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 删除报告计划，已生成的报告保留
+    pub async fn delete(&self, schedule_id: Uuid) -> Result<()> {
+        report_schedule::Entity::delete_by_id(schedule_id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
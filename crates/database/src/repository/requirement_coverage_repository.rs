@@ -0,0 +1,110 @@
+//! 需求覆盖关系仓储实现
+
+use crate::{entities::requirement_coverage, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+/// 需求覆盖关系仓储
+pub struct RequirementCoverageRepository {
+    db: DatabaseConnection,
+}
+
+/// 创建覆盖关系的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateCoverageData {
+    pub project_id: Uuid,
+    pub document_id: Uuid,
+    pub section_id: String,
+    pub section_title: String,
+    pub task_id: Uuid,
+}
+
+impl RequirementCoverageRepository {
+    /// 创建新的需求覆盖关系仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 创建覆盖关系，相同的关系已存在时直接返回
+    pub async fn create(&self, data: CreateCoverageData) -> Result<requirement_coverage::Model> {
+        if let Some(existing) = self.find_link(data.document_id, &data.section_id, data.task_id).await? {
+            return Ok(existing);
+        }
+
+        let coverage_id = Uuid::new_v4();
+        let coverage = requirement_coverage::ActiveModel {
+            coverage_id: Set(coverage_id),
+            project_id: Set(data.project_id),
+            document_id: Set(data.document_id),
+            section_id: Set(data.section_id),
+            section_title: Set(data.section_title),
+            task_id: Set(data.task_id),
+            created_at: Set(chrono::Utc::now().into()),
+        };
+
+        requirement_coverage::Entity::insert(coverage).exec(&self.db).await?;
+
+        requirement_coverage::Entity::find_by_id(coverage_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("RequirementCoverage", coverage_id))
+    }
+
+    /// 查找指定的覆盖关系
+    pub async fn find_link(
+        &self,
+        document_id: Uuid,
+        section_id: &str,
+        task_id: Uuid,
+    ) -> Result<Option<requirement_coverage::Model>> {
+        requirement_coverage::Entity::find()
+            .filter(requirement_coverage::Column::DocumentId.eq(document_id))
+            .filter(requirement_coverage::Column::SectionId.eq(section_id))
+            .filter(requirement_coverage::Column::TaskId.eq(task_id))
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找项目的全部覆盖关系
+    pub async fn find_by_project(&self, project_id: Uuid) -> Result<Vec<requirement_coverage::Model>> {
+        requirement_coverage::Entity::find()
+            .filter(requirement_coverage::Column::ProjectId.eq(project_id))
+            .order_by_asc(requirement_coverage::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找任务覆盖的需求章节
+    pub async fn find_by_task(&self, task_id: Uuid) -> Result<Vec<requirement_coverage::Model>> {
+        requirement_coverage::Entity::find()
+            .filter(requirement_coverage::Column::TaskId.eq(task_id))
+            .order_by_asc(requirement_coverage::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找需求文档的全部覆盖关系
+    pub async fn find_by_document(&self, document_id: Uuid) -> Result<Vec<requirement_coverage::Model>> {
+        requirement_coverage::Entity::find()
+            .filter(requirement_coverage::Column::DocumentId.eq(document_id))
+            .order_by_asc(requirement_coverage::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 删除覆盖关系，返回是否存在
+    pub async fn delete_link(&self, document_id: Uuid, section_id: &str, task_id: Uuid) -> Result<bool> {
+        let result = requirement_coverage::Entity::delete_many()
+            .filter(requirement_coverage::Column::DocumentId.eq(document_id))
+            .filter(requirement_coverage::Column::SectionId.eq(section_id))
+            .filter(requirement_coverage::Column::TaskId.eq(task_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
@@ -1,22 +1,40 @@
 //! 需求文档仓储实现
 
-use crate::{entities::requirement_document, DatabaseConnection, DatabaseError, Result};
-use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter};
+use crate::{
+    entities::requirement_document,
+    tenant_scope::{scoped_condition, TenantScope},
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter, Condition};
 use uuid::Uuid;
 
 /// 需求文档仓储
 pub struct RequirementDocumentRepository {
     db: DatabaseConnection,
+    scope: Option<TenantScope>,
 }
 
 impl RequirementDocumentRepository {
     /// 创建新的需求文档仓储实例
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self { db, scope: None }
+    }
+
+    /// 限定只能访问租户范围内项目的需求文档
+    pub fn with_scope(mut self, scope: TenantScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    fn tenant_condition(&self) -> Condition {
+        scoped_condition(self.scope.as_ref(), |scope| scope.project_ref_condition(requirement_document::Column::ProjectId))
     }
 
     /// 创建新需求文档
     pub async fn create(&self, document_data: CreateRequirementDocumentData) -> Result<requirement_document::Model> {
+        if let Some(scope) = &self.scope {
+            scope.ensure_project(&self.db, document_data.project_id).await?;
+        }
         let now = chrono::Utc::now().into();
         let document_id = Uuid::new_v4();
         
@@ -38,6 +56,7 @@ impl RequirementDocumentRepository {
         
         // 获取插入的文档
         requirement_document::Entity::find_by_id(document_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("RequirementDocument", document_id))
@@ -46,6 +65,7 @@ impl RequirementDocumentRepository {
     /// 根据ID查找需求文档
     pub async fn find_by_id(&self, document_id: Uuid) -> Result<Option<requirement_document::Model>> {
         requirement_document::Entity::find_by_id(document_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await
             .map_err(DatabaseError::from)
@@ -55,6 +75,7 @@ impl RequirementDocumentRepository {
     pub async fn find_by_project(&self, project_id: Uuid) -> Result<Vec<requirement_document::Model>> {
         requirement_document::Entity::find()
             .filter(requirement_document::Column::ProjectId.eq(project_id))
+            .filter(self.tenant_condition())
             .all(&self.db)
             .await
             .map_err(DatabaseError::from)
@@ -64,6 +85,7 @@ impl RequirementDocumentRepository {
     pub async fn find_by_type(&self, project_id: Uuid, document_type: &str) -> Result<Vec<requirement_document::Model>> {
         requirement_document::Entity::find()
             .filter(requirement_document::Column::ProjectId.eq(project_id))
+            .filter(self.tenant_condition())
             .filter(requirement_document::Column::DocumentType.eq(document_type))
             .all(&self.db)
             .await
@@ -74,6 +96,7 @@ impl RequirementDocumentRepository {
     pub async fn find_unprocessed(&self, project_id: Uuid) -> Result<Vec<requirement_document::Model>> {
         requirement_document::Entity::find()
             .filter(requirement_document::Column::ProjectId.eq(project_id))
+            .filter(self.tenant_condition())
             .filter(requirement_document::Column::LlmProcessed.eq(false))
             .all(&self.db)
             .await
@@ -89,6 +112,7 @@ impl RequirementDocumentRepository {
         priority: Option<String>,
     ) -> Result<requirement_document::Model> {
         let document = requirement_document::Entity::find_by_id(document_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("RequirementDocument", document_id))?;
@@ -122,6 +146,7 @@ impl RequirementDocumentRepository {
         processing_session_id: Uuid,
     ) -> Result<requirement_document::Model> {
         let document = requirement_document::Entity::find_by_id(document_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("RequirementDocument", document_id))?;
@@ -140,12 +165,17 @@ impl RequirementDocumentRepository {
             .map_err(DatabaseError::from)
     }
     
-    /// 删除需求文档
+    /// 删除需求文档，设置了租户范围时删除范围外的记录返回未找到
     pub async fn delete(&self, document_id: Uuid) -> Result<()> {
-        requirement_document::Entity::delete_by_id(document_id)
+        let result = requirement_document::Entity::delete_many()
+            .filter(requirement_document::Column::DocumentId.eq(document_id))
+            .filter(self.tenant_condition())
             .exec(&self.db)
             .await?;
         
+        if self.scope.is_some() && result.rows_affected == 0 {
+            return Err(DatabaseError::entity_not_found("RequirementDocument", document_id));
+        }
         Ok(())
     }
 }
@@ -0,0 +1,85 @@
+//! 安全扫描发现仓储实现
+
+use crate::{entities::security_finding, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 安全扫描发现仓储
+pub struct SecurityFindingRepository {
+    db: DatabaseConnection,
+}
+
+/// 记录安全扫描发现的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateSecurityFindingData {
+    pub project_id: Uuid,
+    pub execution_session_id: Uuid,
+    pub scanner: String,
+    pub category: String,
+    pub severity: String,
+    pub title: String,
+    pub advisory_id: Option<String>,
+    pub package_name: Option<String>,
+    pub package_version: Option<String>,
+    pub fixed_versions: JsonValue,
+    pub file_path: Option<String>,
+    pub line: Option<i32>,
+    pub remediation_task_id: Option<Uuid>,
+}
+
+impl SecurityFindingRepository {
+    /// 创建新的安全扫描发现仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 批量记录安全扫描发现
+    pub async fn create_many(&self, data: Vec<CreateSecurityFindingData>) -> Result<Vec<security_finding::Model>> {
+        let now = chrono::Utc::now();
+        let mut findings = Vec::with_capacity(data.len());
+        for item in data {
+            let finding = security_finding::ActiveModel {
+                finding_id: Set(Uuid::new_v4()),
+                project_id: Set(item.project_id),
+                execution_session_id: Set(item.execution_session_id),
+                scanner: Set(item.scanner),
+                category: Set(item.category),
+                severity: Set(item.severity),
+                title: Set(item.title),
+                advisory_id: Set(item.advisory_id),
+                package_name: Set(item.package_name),
+                package_version: Set(item.package_version),
+                fixed_versions: Set(item.fixed_versions),
+                file_path: Set(item.file_path),
+                line: Set(item.line),
+                remediation_task_id: Set(item.remediation_task_id),
+                created_at: Set(now.into()),
+            };
+            findings.push(finding.insert(&self.db).await.map_err(DatabaseError::from)?);
+        }
+        Ok(findings)
+    }
+
+    /// 查找执行会话的全部发现，按扫描器和标题排列
+    pub async fn find_by_session(&self, session_id: Uuid) -> Result<Vec<security_finding::Model>> {
+        security_finding::Entity::find()
+            .filter(security_finding::Column::ExecutionSessionId.eq(session_id))
+            .order_by_asc(security_finding::Column::Scanner)
+            .order_by_asc(security_finding::Column::Title)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 删除执行会话中指定扫描器的发现
+    pub async fn delete_by_session_and_scanner(&self, session_id: Uuid, scanner: &str) -> Result<u64> {
+        let result = security_finding::Entity::delete_many()
+            .filter(security_finding::Column::ExecutionSessionId.eq(session_id))
+            .filter(security_finding::Column::Scanner.eq(scanner))
+            .exec(&self.db)
+            .await
+            .map_err(DatabaseError::from)?;
+        Ok(result.rows_affected)
+    }
+}
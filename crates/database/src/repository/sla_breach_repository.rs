@@ -0,0 +1,119 @@
+//! SLA违约记录仓储实现
+
+use crate::{entities::sla_breach, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use uuid::Uuid;
+
+/// SLA违约记录仓储
+pub struct SlaBreachRepository {
+    db: DatabaseConnection,
+}
+
+/// 记录违约的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateSlaBreachData {
+    pub project_id: Uuid,
+    pub sla_type: String,
+    pub subject_type: String,
+    pub subject_id: String,
+    pub subject_name: String,
+    pub severity: String,
+    pub threshold_minutes: i64,
+    pub actual_minutes: i64,
+    pub deadline: Option<DateTimeWithTimeZone>,
+    pub detected_at: DateTimeWithTimeZone,
+}
+
+impl SlaBreachRepository {
+    /// 创建新的SLA违约记录仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 记录新的违约
+    pub async fn create(&self, data: CreateSlaBreachData) -> Result<sla_breach::Model> {
+        let breach = sla_breach::ActiveModel {
+            breach_id: Set(Uuid::new_v4()),
+            project_id: Set(data.project_id),
+            sla_type: Set(data.sla_type),
+            subject_type: Set(data.subject_type),
+            subject_id: Set(data.subject_id),
+            subject_name: Set(data.subject_name),
+            severity: Set(data.severity),
+            threshold_minutes: Set(data.threshold_minutes),
+            actual_minutes: Set(data.actual_minutes),
+            deadline: Set(data.deadline),
+            detected_at: Set(data.detected_at),
+            updated_at: Set(data.detected_at),
+            resolved_at: Set(None),
+        };
+        breach.insert(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 根据ID查找违约记录
+    pub async fn find_by_id(&self, breach_id: Uuid) -> Result<Option<sla_breach::Model>> {
+        sla_breach::Entity::find_by_id(breach_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 更新违约的严重程度和实际耗时
+    pub async fn update_measurement(
+        &self,
+        breach_id: Uuid,
+        severity: &str,
+        actual_minutes: i64,
+        evaluated_at: DateTimeWithTimeZone,
+    ) -> Result<sla_breach::Model> {
+        let breach = self
+            .find_by_id(breach_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("SlaBreach", breach_id))?;
+        let mut breach: sla_breach::ActiveModel = breach.into();
+        breach.severity = Set(severity.to_string());
+        breach.actual_minutes = Set(actual_minutes);
+        breach.updated_at = Set(evaluated_at);
+        breach.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 标记违约已解决
+    pub async fn resolve(&self, breach_id: Uuid, resolved_at: DateTimeWithTimeZone) -> Result<sla_breach::Model> {
+        let breach = self
+            .find_by_id(breach_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("SlaBreach", breach_id))?;
+        let mut breach: sla_breach::ActiveModel = breach.into();
+        breach.updated_at = Set(resolved_at);
+        breach.resolved_at = Set(Some(resolved_at));
+        breach.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 查找未解决的违约，不指定项目时返回所有项目的违约（按严重程度、发现时间排序）
+    pub async fn find_open(&self, project_id: Option<Uuid>) -> Result<Vec<sla_breach::Model>> {
+        let mut query = sla_breach::Entity::find().filter(sla_breach::Column::ResolvedAt.is_null());
+        if let Some(project_id) = project_id {
+            query = query.filter(sla_breach::Column::ProjectId.eq(project_id));
+        }
+        query
+            .order_by_asc(sla_breach::Column::Severity)
+            .order_by_asc(sla_breach::Column::DetectedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找项目的违约记录（按发现时间降序）
+    pub async fn find_by_project(&self, project_id: Uuid, include_resolved: bool) -> Result<Vec<sla_breach::Model>> {
+        let mut query = sla_breach::Entity::find().filter(sla_breach::Column::ProjectId.eq(project_id));
+        if !include_resolved {
+            query = query.filter(sla_breach::Column::ResolvedAt.is_null());
+        }
+        query
+            .order_by_desc(sla_breach::Column::DetectedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+}
@@ -0,0 +1,135 @@
+//! 任务人工检查点仓储实现
+
+use crate::{entities::task_gate, DatabaseConnection, DatabaseError, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+/// 任务人工检查点仓储
+pub struct TaskGateRepository {
+    db: DatabaseConnection,
+}
+
+/// 定义检查点的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateTaskGateData {
+    pub task_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub sequence: i32,
+}
+
+/// 记录检查点决策的数据结构
+#[derive(Debug, Clone)]
+pub struct GateDecisionData {
+    pub status: String,
+    pub decided_by: Uuid,
+    pub comment: Option<String>,
+    pub decided_at: DateTime<Utc>,
+    /// 本次等待的时长（秒），累加到已结束的等待时长中
+    pub waited_seconds: i64,
+}
+
+impl TaskGateRepository {
+    /// 创建新的任务人工检查点仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 定义检查点
+    pub async fn create(&self, data: CreateTaskGateData) -> Result<task_gate::Model> {
+        let gate = task_gate::ActiveModel {
+            gate_id: Set(Uuid::new_v4()),
+            task_id: Set(data.task_id),
+            name: Set(data.name),
+            description: Set(data.description),
+            sequence: Set(data.sequence),
+            status: Set("pending".to_string()),
+            resume_status: Set(None),
+            requested_at: Set(None),
+            decided_at: Set(None),
+            decided_by: Set(None),
+            comment: Set(None),
+            waiting_seconds: Set(0),
+            request_count: Set(0),
+            created_at: Set(Utc::now().into()),
+        };
+        gate.insert(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 根据ID查找检查点
+    pub async fn find_by_id(&self, gate_id: Uuid) -> Result<Option<task_gate::Model>> {
+        task_gate::Entity::find_by_id(gate_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找任务的全部检查点，按流程顺序排列
+    pub async fn find_by_task(&self, task_id: Uuid) -> Result<Vec<task_gate::Model>> {
+        task_gate::Entity::find()
+            .filter(task_gate::Column::TaskId.eq(task_id))
+            .order_by_asc(task_gate::Column::Sequence)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找任务中指定状态的检查点
+    pub async fn find_by_task_and_status(&self, task_id: Uuid, status: &str) -> Result<Vec<task_gate::Model>> {
+        task_gate::Entity::find()
+            .filter(task_gate::Column::TaskId.eq(task_id))
+            .filter(task_gate::Column::Status.eq(status))
+            .order_by_asc(task_gate::Column::Sequence)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找所有正在等待人工决策的检查点，等待最久的在前
+    pub async fn find_waiting(&self) -> Result<Vec<task_gate::Model>> {
+        task_gate::Entity::find()
+            .filter(task_gate::Column::Status.eq("waiting"))
+            .order_by_asc(task_gate::Column::RequestedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 检查点进入等待
+    pub async fn mark_waiting(
+        &self,
+        gate: task_gate::Model,
+        resume_status: String,
+        requested_at: DateTime<Utc>,
+    ) -> Result<task_gate::Model> {
+        let request_count = gate.request_count;
+        let mut gate: task_gate::ActiveModel = gate.into();
+        gate.status = Set("waiting".to_string());
+        gate.resume_status = Set(Some(resume_status));
+        gate.requested_at = Set(Some(requested_at.into()));
+        gate.request_count = Set(request_count + 1);
+        gate.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 记录检查点决策，结束当前等待
+    pub async fn record_decision(&self, gate: task_gate::Model, data: GateDecisionData) -> Result<task_gate::Model> {
+        let waiting_seconds = gate.waiting_seconds;
+        let mut gate: task_gate::ActiveModel = gate.into();
+        gate.status = Set(data.status);
+        gate.decided_by = Set(Some(data.decided_by));
+        gate.comment = Set(data.comment);
+        gate.decided_at = Set(Some(data.decided_at.into()));
+        gate.waiting_seconds = Set(waiting_seconds + data.waited_seconds.max(0));
+        gate.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 删除检查点
+    pub async fn delete(&self, gate_id: Uuid) -> Result<()> {
+        task_gate::Entity::delete_by_id(gate_id)
+            .exec(&self.db)
+            .await
+            .map_err(DatabaseError::from)?;
+        Ok(())
+    }
+}
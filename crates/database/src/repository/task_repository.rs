@@ -1,23 +1,51 @@
 //! 任务仓储实现
 
-use crate::{entities::task, DatabaseConnection, DatabaseError, Result};
-use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter, QueryOrder};
+use crate::{
+    entities::{domain_event::{AggregateType, DomainEventType}, task, task_dependency},
+    export::keyset_batches,
+    ids::EntityKey,
+    repository::domain_event_repository::{CreateDomainEventData, DomainEventRepository},
+    tenant_scope::{scoped_condition, TenantScope},
+    DatabaseConnection, DatabaseError, Result,
+};
+use codex_multi_agent::{
+    AgentId, EventFactory, ProjectId, SubtaskProgress, TaskId, TaskProgressRollup, TaskProgressUpdatedEvent, TaskStatus,
+};
+use sea_orm::{ActiveValue, EntityTrait, Set, ActiveModelTrait, ColumnTrait, Condition, QueryFilter, QueryOrder};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::{Alias, Expr, JoinType, Query};
+use futures::Stream;
 use serde_json::Value as JsonValue;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// 任务仓储
 pub struct TaskRepository {
     db: DatabaseConnection,
+    scope: Option<TenantScope>,
 }
 
 impl TaskRepository {
     /// 创建新的任务仓储实例
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self { db, scope: None }
+    }
+
+    /// 限定只能访问租户范围内项目的任务
+    pub fn with_scope(mut self, scope: TenantScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    fn tenant_condition(&self) -> Condition {
+        scoped_condition(self.scope.as_ref(), |scope| scope.project_ref_condition(task::Column::ProjectId))
     }
 
     /// 创建新任务
     pub async fn create(&self, task_data: CreateTaskData) -> Result<task::Model> {
+        if let Some(scope) = &self.scope {
+            scope.ensure_project(&self.db, task_data.project_id).await?;
+        }
         let now = chrono::Utc::now().into();
         let task_id = Uuid::new_v4();
         
@@ -36,37 +64,156 @@ impl TaskRepository {
             ..Default::default()
         };
         
-        let _result = task::Entity::insert(task).exec(&self.db).await?;
+        let _result = crate::telemetry::time_db("task_insert", task::Entity::insert(task).exec(&self.db)).await?;
+        crate::telemetry::record_task_created();
         
         // 获取插入的任务
         task::Entity::find_by_id(task_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("Task", task_id))
     }
     
+    /// 在父任务下创建子任务
+    ///
+    /// 子任务继承父任务的项目、LLM会话和工作范围，创建后会重新汇总父任务进度
+    pub async fn create_subtask(
+        &self,
+        parent_task_id: impl EntityKey<TaskId>,
+        title: String,
+        description: String,
+        task_type: String,
+    ) -> Result<task::Model> {
+        let parent_task_id = parent_task_id.into_key();
+        let parent = task::Entity::find_by_id(parent_task_id)
+            .filter(self.tenant_condition())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Task", parent_task_id))?;
+
+        if matches!(parent.status.as_str(), "completed" | "cancelled") {
+            return Err(DatabaseError::business_logic(format!(
+                "父任务 {parent_task_id} 已结束，不能再添加子任务"
+            )));
+        }
+
+        let subtask = self
+            .create(CreateTaskData {
+                project_id: parent.project_id,
+                parent_task_id: Some(parent_task_id),
+                llm_session_id: parent.llm_session_id,
+                title,
+                description,
+                task_type,
+            })
+            .await?;
+        let subtask = match parent.scope_path {
+            Some(scope_path) => self.set_scope_path(subtask.task_id, Some(scope_path)).await?,
+            None => subtask,
+        };
+
+        self.recompute_parent_progress(subtask.task_id).await?;
+
+        Ok(subtask)
+    }
+
     /// 根据ID查找任务
-    pub async fn find_by_id(&self, task_id: Uuid) -> Result<Option<task::Model>> {
+    pub async fn find_by_id(&self, task_id: impl EntityKey<TaskId>) -> Result<Option<task::Model>> {
+        let task_id = task_id.into_key();
         task::Entity::find_by_id(task_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await
             .map_err(DatabaseError::from)
     }
     
+    /// 重建任务从分解到审查的完整时间线
+    pub async fn trace_task(&self, task_id: impl EntityKey<TaskId>) -> Result<crate::task_trace::TaskTrace> {
+        let task_id = task_id.into_key();
+        if self.scope.is_some() && self.find_by_id(task_id).await?.is_none() {
+            return Err(DatabaseError::entity_not_found("Task", task_id));
+        }
+        crate::task_trace::trace_task(&self.db, task_id).await
+    }
+    
     /// 根据项目ID查找任务
-    pub async fn find_by_project(&self, project_id: Uuid) -> Result<Vec<task::Model>> {
+    pub async fn find_by_project(&self, project_id: impl EntityKey<ProjectId>) -> Result<Vec<task::Model>> {
+        let project_id = project_id.into_key();
         task::Entity::find()
             .filter(task::Column::ProjectId.eq(project_id))
+            .filter(self.tenant_condition())
             .order_by_asc(task::Column::CreatedAt)
             .all(&self.db)
             .await
             .map_err(DatabaseError::from)
     }
     
+    /// 按创建时间流式读取项目的任务，每次查询 `batch_size` 行，用于导出大量任务
+    pub fn stream_by_project(
+        &self,
+        project_id: impl EntityKey<ProjectId>,
+        batch_size: u64,
+    ) -> impl Stream<Item = Result<task::Model>> + Send + 'static {
+        let project_id = project_id.into_key();
+        let db = self.db.clone();
+        let tenant_condition = self.tenant_condition();
+        keyset_batches(batch_size, |task: &task::Model| (task.created_at, task.task_id), move |after, limit| {
+            let db = db.clone();
+            let tenant_condition = tenant_condition.clone();
+            async move {
+                let mut cursor = task::Entity::find()
+                    .filter(task::Column::ProjectId.eq(project_id))
+                    .filter(tenant_condition)
+                    .cursor_by((task::Column::CreatedAt, task::Column::TaskId));
+                if let Some(after) = after {
+                    cursor.after(after);
+                }
+                cursor.first(limit).all(&db).await.map_err(DatabaseError::from)
+            }
+        })
+    }
+    
     /// 根据父任务ID查找子任务
-    pub async fn find_subtasks(&self, parent_task_id: Uuid) -> Result<Vec<task::Model>> {
+    pub async fn find_subtasks(&self, parent_task_id: impl EntityKey<TaskId>) -> Result<Vec<task::Model>> {
+        let parent_task_id = parent_task_id.into_key();
         task::Entity::find()
             .filter(task::Column::ParentTaskId.eq(parent_task_id))
+            .filter(self.tenant_condition())
+            .order_by_asc(task::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
+    /// 查找项目中可以开始执行的任务：状态为 pending 且所有前置任务都已完成，按创建时间排序
+    ///
+    /// 使用 `idx_tasks_project_status` 定位待处理任务，对每个任务经 `idx_task_deps_child_parent`
+    /// 检查是否存在未完成的前置任务
+    pub async fn find_ready_tasks(&self, project_id: impl EntityKey<ProjectId>) -> Result<Vec<task::Model>> {
+        let project_id = project_id.into_key();
+        let prerequisite = Alias::new("prerequisite");
+        let unfinished_prerequisites = Query::select()
+            .expr(Expr::val(1))
+            .from(task_dependency::Entity)
+            .join_as(
+                JoinType::InnerJoin,
+                task::Entity,
+                prerequisite.clone(),
+                Expr::col((prerequisite.clone(), task::Column::TaskId))
+                    .equals((task_dependency::Entity, task_dependency::Column::ParentTaskId)),
+            )
+            .and_where(
+                Expr::col((task_dependency::Entity, task_dependency::Column::ChildTaskId))
+                    .equals((task::Entity, task::Column::TaskId)),
+            )
+            .and_where(Expr::col((prerequisite, task::Column::Status)).ne("completed"))
+            .to_owned();
+        task::Entity::find()
+            .filter(task::Column::ProjectId.eq(project_id))
+            .filter(self.tenant_condition())
+            .filter(task::Column::Status.eq("pending"))
+            .filter(Expr::exists(unfinished_prerequisites).not())
             .order_by_asc(task::Column::CreatedAt)
             .all(&self.db)
             .await
@@ -74,9 +221,11 @@ impl TaskRepository {
     }
     
     /// 根据状态查找任务
-    pub async fn find_by_status(&self, project_id: Uuid, status: &str) -> Result<Vec<task::Model>> {
+    pub async fn find_by_status(&self, project_id: impl EntityKey<ProjectId>, status: &str) -> Result<Vec<task::Model>> {
+        let project_id = project_id.into_key();
         task::Entity::find()
             .filter(task::Column::ProjectId.eq(project_id))
+            .filter(self.tenant_condition())
             .filter(task::Column::Status.eq(status))
             .order_by_asc(task::Column::CreatedAt)
             .all(&self.db)
@@ -85,9 +234,11 @@ impl TaskRepository {
     }
     
     /// 根据分配的Agent查找任务
-    pub async fn find_by_agent(&self, agent_id: Uuid) -> Result<Vec<task::Model>> {
+    pub async fn find_by_agent(&self, agent_id: impl EntityKey<AgentId>) -> Result<Vec<task::Model>> {
+        let agent_id = agent_id.into_key();
         task::Entity::find()
             .filter(task::Column::AssignedAgentId.eq(agent_id))
+            .filter(self.tenant_condition())
             .order_by_desc(task::Column::UpdatedAt)
             .all(&self.db)
             .await
@@ -95,9 +246,11 @@ impl TaskRepository {
     }
     
     /// 查找顶级任务（没有父任务的任务）
-    pub async fn find_top_level_tasks(&self, project_id: Uuid) -> Result<Vec<task::Model>> {
+    pub async fn find_top_level_tasks(&self, project_id: impl EntityKey<ProjectId>) -> Result<Vec<task::Model>> {
+        let project_id = project_id.into_key();
         task::Entity::find()
             .filter(task::Column::ProjectId.eq(project_id))
+            .filter(self.tenant_condition())
             .filter(task::Column::ParentTaskId.is_null())
             .order_by_asc(task::Column::CreatedAt)
             .all(&self.db)
@@ -106,16 +259,38 @@ impl TaskRepository {
     }
     
     /// 更新任务状态
+    ///
+    /// 完成任务时要求验收标准全部验证通过，强制完成见 [`crate::acceptance::complete_with_override`]
+    #[tracing::instrument(name = "task.update_status", skip(self), fields(correlation_id = %task_id))]
     pub async fn update_status(
+        &self,
+        task_id: impl EntityKey<TaskId>,
+        status: &str,
+    ) -> Result<task::Model> {
+        let task_id = task_id.into_key();
+        self.set_status(task_id, status, true).await
+    }
+
+    /// 更新任务状态，`check_acceptance` 为 false 时跳过验收标准检查
+    pub(crate) async fn set_status(
         &self,
         task_id: Uuid,
         status: &str,
+        check_acceptance: bool,
     ) -> Result<task::Model> {
         let task = task::Entity::find_by_id(task_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("Task", task_id))?;
         
+        if check_acceptance && status == "completed" && task.status != "completed" {
+            crate::acceptance::ensure_criteria_verified(&self.db, &task).await?;
+            crate::checkpoint_gates::ensure_gates_approved(&self.db, &task).await?;
+            crate::security_scan::ensure_task_can_complete(&self.db, &task).await?;
+        }
+        
+        let previous_status = task.status.clone();
         let now = chrono::Utc::now().into();
         let mut task: task::ActiveModel = task.into();
         
@@ -123,37 +298,142 @@ impl TaskRepository {
         task.updated_at = Set(now);
         
         // 设置状态相关的时间戳
+        // 从已有记录转换的 ActiveModel 字段均为 Unchanged，需要判断实际值是否为空
         match status {
             "in_progress" => {
-                if task.started_at.is_not_set() {
+                if matches!(task.started_at, ActiveValue::Unchanged(None) | ActiveValue::NotSet) {
                     task.started_at = Set(Some(now));
                 }
             }
             "completed" | "failed" => {
-                if task.completed_at.is_not_set() {
+                if matches!(task.completed_at, ActiveValue::Unchanged(None) | ActiveValue::NotSet) {
                     task.completed_at = Set(Some(now));
                 }
             }
             _ => {}
         }
         
-        task.update(&self.db)
-            .await
-            .map_err(DatabaseError::from)
+        let updated = crate::telemetry::time_db("task_update_status", task.update(&self.db)).await?;
+        crate::telemetry::record_task_status(status);
+
+        // 子任务状态变化时向上汇总父任务进度
+        if updated.parent_task_id.is_some() {
+            self.recompute_parent_progress(updated.task_id).await?;
+        }
+
+        // 取消任务可能使其覆盖的需求章节失去覆盖
+        if status == "cancelled" && previous_status != "cancelled" {
+            crate::traceability::record_coverage_drop(&self.db, &updated).await?;
+        }
+
+        Ok(updated)
+    }
+
+    /// 计算父任务的进度汇总
+    ///
+    /// 有下级任务的子任务按其自身的汇总完成度参与计算
+    pub async fn calculate_progress_rollup(&self, parent_task_id: impl EntityKey<TaskId>) -> Result<TaskProgressRollup> {
+        let parent_task_id = parent_task_id.into_key();
+        let parent = task::Entity::find_by_id(parent_task_id)
+            .filter(self.tenant_condition())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Task", parent_task_id))?;
+
+        let project_tasks = self.find_by_project(parent.project_id).await?;
+        let mut children: HashMap<Uuid, Vec<task::Model>> = HashMap::new();
+        for task in project_tasks {
+            if let Some(parent_id) = task.parent_task_id {
+                children.entry(parent_id).or_default().push(task);
+            }
+        }
+
+        Ok(rollup_from_children(parent_task_id, &children))
+    }
+
+    /// 重新汇总指定任务所有祖先任务的进度
+    ///
+    /// 祖先任务状态与子任务推导状态不一致时同步更新，并为每个祖先任务记录
+    /// TaskProgressUpdated 领域事件，返回生成的进度事件（由近到远）
+    pub async fn recompute_parent_progress(&self, task_id: impl EntityKey<TaskId>) -> Result<Vec<TaskProgressUpdatedEvent>> {
+        let task_id = task_id.into_key();
+        let task = task::Entity::find_by_id(task_id)
+            .filter(self.tenant_condition())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Task", task_id))?;
+
+        let event_repo = DomainEventRepository::new(self.db.clone());
+        let mut events = Vec::new();
+        let mut current_parent = task.parent_task_id;
+
+        while let Some(parent_id) = current_parent {
+            let parent = task::Entity::find_by_id(parent_id)
+                .filter(self.tenant_condition())
+                .one(&self.db)
+                .await?
+                .ok_or_else(|| DatabaseError::entity_not_found("Task", parent_id))?;
+
+            let rollup = self.calculate_progress_rollup(parent_id).await?;
+            let derived_status = task_status_to_str(&rollup.derived_status);
+
+            // 已取消的父任务保持原状态，只记录进度
+            if parent.status != derived_status && parent.status != "cancelled" {
+                let now = chrono::Utc::now().into();
+                let mut active: task::ActiveModel = parent.clone().into();
+                active.status = Set(derived_status.to_string());
+                active.updated_at = Set(now);
+                if derived_status == "in_progress" && parent.started_at.is_none() {
+                    active.started_at = Set(Some(now));
+                }
+                if matches!(derived_status, "completed" | "failed") {
+                    active.completed_at = Set(Some(now));
+                }
+                active.update(&self.db).await?;
+            }
+
+            let event = EventFactory::parent_task_progress_updated(&rollup);
+            let event_version = event_repo.get_latest_version(parent_id).await? + 1;
+            event_repo
+                .create_correlated(
+                    CreateDomainEventData {
+                        aggregate_type: AggregateType::Task.to_string(),
+                        aggregate_id: parent_id,
+                        event_type: DomainEventType::TaskProgressUpdated.to_string(),
+                        event_data: serde_json::to_value(&event)?,
+                        event_version,
+                    },
+                    parent_id,
+                )
+                .await?;
+            events.push(event);
+
+            current_parent = parent.parent_task_id;
+        }
+
+        Ok(events)
     }
     
     /// 分配任务给Agent
+    ///
+    /// 任务限定了工作范围时范围说明和项目黑板中纳入提示词的条目依次附加到分配提示词末尾
+    #[tracing::instrument(name = "task.assign", skip(self, assignment_prompt), fields(correlation_id = %task_id))]
     pub async fn assign_to_agent(
         &self,
-        task_id: Uuid,
-        agent_id: Uuid,
+        task_id: impl EntityKey<TaskId>,
+        agent_id: impl EntityKey<AgentId>,
         assignment_prompt: String,
     ) -> Result<task::Model> {
+        let task_id = task_id.into_key();
+        let agent_id = agent_id.into_key();
         let task = task::Entity::find_by_id(task_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("Task", task_id))?;
         
+        let assignment_prompt = crate::task_scope::append_to_prompt(task.scope_path.as_deref(), assignment_prompt);
+        let assignment_prompt = crate::blackboard::append_to_prompt(&self.db, task.project_id, assignment_prompt).await?;
         let now = chrono::Utc::now().into();
         let mut task: task::ActiveModel = task.into();
         
@@ -170,13 +450,15 @@ impl TaskRepository {
     /// 更新任务详情
     pub async fn update_details(
         &self,
-        task_id: Uuid,
+        task_id: impl EntityKey<TaskId>,
         title: Option<String>,
         description: Option<String>,
         priority: Option<String>,
         estimated_hours: Option<i32>,
     ) -> Result<task::Model> {
+        let task_id = task_id.into_key();
         let task = task::Entity::find_by_id(task_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("Task", task_id))?;
@@ -206,14 +488,54 @@ impl TaskRepository {
             .map_err(DatabaseError::from)
     }
     
+    /// 设置任务的工作范围，`None` 表示整个仓库
+    ///
+    /// 范围必须是工作空间内的相对路径；父任务限定了范围时，子任务的范围不能超出父任务
+    pub async fn set_scope_path(&self, task_id: impl EntityKey<TaskId>, scope_path: Option<String>) -> Result<task::Model> {
+        let task_id = task_id.into_key();
+        let task = task::Entity::find_by_id(task_id)
+            .filter(self.tenant_condition())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Task", task_id))?;
+
+        let scope_path = match scope_path {
+            Some(scope_path) => crate::task_scope::normalize_scope(&scope_path)?,
+            None => None,
+        };
+        if let Some(parent_task_id) = task.parent_task_id {
+            let parent_scope = task::Entity::find_by_id(parent_task_id)
+                .filter(self.tenant_condition())
+                .one(&self.db)
+                .await?
+                .and_then(|parent| parent.scope_path);
+            if !crate::task_scope::is_within(parent_scope.as_deref(), scope_path.as_deref()) {
+                return Err(DatabaseError::validation(format!(
+                    "子任务的工作范围必须位于父任务的范围 {} 之内",
+                    parent_scope.unwrap_or_default()
+                )));
+            }
+        }
+
+        let mut task: task::ActiveModel = task.into();
+        task.scope_path = Set(scope_path);
+        task.updated_at = Set(chrono::Utc::now().into());
+
+        task.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
     /// 更新任务需求配置
     pub async fn update_requirements(
         &self,
-        task_id: Uuid,
+        task_id: impl EntityKey<TaskId>,
         required_capabilities: Option<JsonValue>,
         acceptance_criteria: Option<JsonValue>,
     ) -> Result<task::Model> {
+        let task_id = task_id.into_key();
         let task = task::Entity::find_by_id(task_id)
+            .filter(self.tenant_condition())
             .one(&self.db)
             .await?
             .ok_or_else(|| DatabaseError::entity_not_found("Task", task_id))?;
@@ -235,12 +557,143 @@ impl TaskRepository {
             .map_err(DatabaseError::from)
     }
     
-    /// 删除任务
-    pub async fn delete(&self, task_id: Uuid) -> Result<()> {
-        task::Entity::delete_by_id(task_id)
+    /// 获取任务租约
+    ///
+    /// 租约空闲、已过期或已由同一持有者持有时获取成功（同一持有者重复获取相当于续约）；
+    /// 租约被其他持有者占用或任务已完成、已取消时返回 `None`。判断在同一条 UPDATE 语句中完成，
+    /// 多个调度器并发获取同一任务时只有一个成功
+    pub async fn acquire_lease(&self, task_id: impl EntityKey<TaskId>, owner: &str, ttl: std::time::Duration) -> Result<Option<task::Model>> {
+        let task_id = task_id.into_key();
+        let now = chrono::Utc::now();
+        let expires_at = now + lease_duration(ttl)?;
+        let now: DateTimeWithTimeZone = now.into();
+
+        let result = task::Entity::update_many()
+            .col_expr(task::Column::LeaseOwner, Expr::value(owner))
+            .col_expr(task::Column::LeaseExpiresAt, Expr::value(DateTimeWithTimeZone::from(expires_at)))
+            .col_expr(task::Column::UpdatedAt, Expr::value(now))
+            .filter(task::Column::TaskId.eq(task_id))
+            .filter(self.tenant_condition())
+            .filter(task::Column::Status.is_not_in(["completed", "cancelled"]))
+            .filter(
+                Condition::any()
+                    .add(task::Column::LeaseOwner.is_null())
+                    .add(task::Column::LeaseOwner.eq(owner))
+                    .add(task::Column::LeaseExpiresAt.is_null())
+                    .add(task::Column::LeaseExpiresAt.lt(now)),
+            )
+            .exec(&self.db)
+            .await?;
+
+        if result.rows_affected == 0 {
+            if self.find_by_id(task_id).await?.is_none() {
+                return Err(DatabaseError::entity_not_found("Task", task_id));
+            }
+            return Ok(None);
+        }
+        self.find_by_id(task_id).await
+    }
+
+    /// 续约，只有未过期租约的持有者可以续约
+    pub async fn renew_lease(&self, task_id: impl EntityKey<TaskId>, owner: &str, ttl: std::time::Duration) -> Result<task::Model> {
+        let task_id = task_id.into_key();
+        let now = chrono::Utc::now();
+        let expires_at = now + lease_duration(ttl)?;
+        let now: DateTimeWithTimeZone = now.into();
+
+        let result = task::Entity::update_many()
+            .col_expr(task::Column::LeaseExpiresAt, Expr::value(DateTimeWithTimeZone::from(expires_at)))
+            .col_expr(task::Column::UpdatedAt, Expr::value(now))
+            .filter(task::Column::TaskId.eq(task_id))
+            .filter(self.tenant_condition())
+            .filter(task::Column::LeaseOwner.eq(owner))
+            .filter(task::Column::LeaseExpiresAt.gte(now))
+            .exec(&self.db)
+            .await?;
+
+        if result.rows_affected == 0 {
+            return Err(DatabaseError::conflict(format!("任务 {} 的租约不属于 {} 或已过期", task_id, owner)));
+        }
+        self.find_by_id(task_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Task", task_id))
+    }
+
+    /// 释放租约，返回租约是否由该持有者持有
+    pub async fn release_lease(&self, task_id: impl EntityKey<TaskId>, owner: &str) -> Result<bool> {
+        let task_id = task_id.into_key();
+        let result = task::Entity::update_many()
+            .col_expr(task::Column::LeaseOwner, Expr::value(Option::<String>::None))
+            .col_expr(task::Column::LeaseExpiresAt, Expr::value(Option::<DateTimeWithTimeZone>::None))
+            .col_expr(task::Column::UpdatedAt, Expr::value(DateTimeWithTimeZone::from(chrono::Utc::now())))
+            .filter(task::Column::TaskId.eq(task_id))
+            .filter(self.tenant_condition())
+            .filter(task::Column::LeaseOwner.eq(owner))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// 回收所有过期的租约
+    ///
+    /// 进行中的任务回到待处理状态并清除Agent分配，以便重新调度；返回被回收的任务。
+    /// 每个任务以"租约仍已过期"为条件更新，与并发的续约互不覆盖
+    pub async fn reclaim_expired_leases(&self) -> Result<Vec<task::Model>> {
+        let now: DateTimeWithTimeZone = chrono::Utc::now().into();
+        let expired = task::Entity::find()
+            .filter(task::Column::LeaseOwner.is_not_null())
+            .filter(self.tenant_condition())
+            .filter(task::Column::LeaseExpiresAt.lt(now))
+            .all(&self.db)
+            .await?;
+
+        let mut reclaimed = Vec::new();
+        for task in expired {
+            let owner = task.lease_owner.clone().unwrap_or_default();
+            let mut update = task::Entity::update_many()
+                .col_expr(task::Column::LeaseOwner, Expr::value(Option::<String>::None))
+                .col_expr(task::Column::LeaseExpiresAt, Expr::value(Option::<DateTimeWithTimeZone>::None))
+                .col_expr(task::Column::UpdatedAt, Expr::value(now));
+            if task.status == "in_progress" {
+                update = update
+                    .col_expr(task::Column::Status, Expr::value("pending"))
+                    .col_expr(task::Column::AssignedAgentId, Expr::value(Option::<Uuid>::None))
+                    .col_expr(task::Column::AssignedAt, Expr::value(Option::<DateTimeWithTimeZone>::None));
+            }
+            let result = update
+                .filter(task::Column::TaskId.eq(task.task_id))
+                .filter(self.tenant_condition())
+                .filter(task::Column::Status.eq(task.status.as_str()))
+                .filter(task::Column::LeaseOwner.eq(owner.as_str()))
+                .filter(task::Column::LeaseExpiresAt.lt(now))
+                .exec(&self.db)
+                .await?;
+            if result.rows_affected == 0 {
+                continue;
+            }
+
+            tracing::warn!("任务 {} 的租约（持有者 {}）已过期，已回收", task.task_id, owner);
+            if let Some(task) = self.find_by_id(task.task_id).await? {
+                reclaimed.push(task);
+            }
+        }
+
+        Ok(reclaimed)
+    }
+    
+    /// 删除任务，设置了租户范围时删除范围外的任务返回未找到
+    pub async fn delete(&self, task_id: impl EntityKey<TaskId>) -> Result<()> {
+        let task_id = task_id.into_key();
+        let result = task::Entity::delete_many()
+            .filter(task::Column::TaskId.eq(task_id))
+            .filter(self.tenant_condition())
             .exec(&self.db)
             .await?;
         
+        if self.scope.is_some() && result.rows_affected == 0 {
+            return Err(DatabaseError::entity_not_found("Task", task_id));
+        }
         Ok(())
     }
 }
@@ -254,4 +707,63 @@ pub struct CreateTaskData {
     pub title: String,
     pub description: String,
     pub task_type: String,
-}
\ No newline at end of file
+}
+
+/// 将租约时长转换为 chrono 时长
+fn lease_duration(ttl: std::time::Duration) -> Result<chrono::Duration> {
+    if ttl.is_zero() {
+        return Err(DatabaseError::validation("租约时长必须大于0"));
+    }
+    chrono::Duration::from_std(ttl).map_err(|e| DatabaseError::validation(format!("无效的租约时长: {}", e)))
+}
+
+/// 递归计算父任务的进度汇总
+fn rollup_from_children(parent_task_id: Uuid, children: &HashMap<Uuid, Vec<task::Model>>) -> TaskProgressRollup {
+    let subtasks: Vec<SubtaskProgress> = children
+        .get(&parent_task_id)
+        .map(|tasks| {
+            tasks
+                .iter()
+                .map(|child| {
+                    let status = task_status_from_str(&child.status);
+                    let completion_percentage = if children.contains_key(&child.task_id) {
+                        rollup_from_children(child.task_id, children).completion_percentage
+                    } else if status == TaskStatus::Completed {
+                        1.0
+                    } else {
+                        0.0
+                    };
+
+                    SubtaskProgress {
+                        task_id: TaskId::from(child.task_id),
+                        status,
+                        completion_percentage,
+                        weight: child.estimated_hours.unwrap_or(1) as f32,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    TaskProgressRollup::from_subtasks(TaskId::from(parent_task_id), &subtasks)
+}
+
+/// 将数据库中的任务状态字符串转换为协议状态
+fn task_status_from_str(status: &str) -> TaskStatus {
+    serde_json::from_value(JsonValue::String(status.to_string())).unwrap_or(TaskStatus::Pending)
+}
+
+/// 将协议状态转换为数据库中的任务状态字符串
+fn task_status_to_str(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Pending => "pending",
+        TaskStatus::InProgress => "in_progress",
+        TaskStatus::Completed => "completed",
+        TaskStatus::Failed => "failed",
+        TaskStatus::Cancelled => "cancelled",
+        TaskStatus::OnHold => "on_hold",
+        TaskStatus::WaitingForDependency => "waiting_for_dependency",
+        TaskStatus::WaitingForReview => "waiting_for_review",
+        TaskStatus::WaitingForHuman => "waiting_for_human",
+    }
+}
@@ -0,0 +1,83 @@
+//! 技术债务快照仓储实现
+
+use crate::{entities::technical_debt_snapshot, DatabaseConnection, DatabaseError, Result};
+use sea_orm::{EntityTrait, Set, ColumnTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+/// 技术债务快照仓储
+pub struct TechnicalDebtSnapshotRepository {
+    db: DatabaseConnection,
+}
+
+/// 创建技术债务快照的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateTechnicalDebtSnapshotData {
+    pub project_id: Uuid,
+    pub commit_hash: Option<String>,
+    pub estimated_hours: i32,
+    pub severity_distribution: serde_json::Value,
+    pub main_debt_types: serde_json::Value,
+    pub findings: serde_json::Value,
+    pub trend: String,
+}
+
+impl TechnicalDebtSnapshotRepository {
+    /// 创建新的技术债务快照仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 记录新的技术债务快照
+    pub async fn create(&self, snapshot_data: CreateTechnicalDebtSnapshotData) -> Result<technical_debt_snapshot::Model> {
+        let snapshot_id = Uuid::new_v4();
+
+        let snapshot = technical_debt_snapshot::ActiveModel {
+            snapshot_id: Set(snapshot_id),
+            project_id: Set(snapshot_data.project_id),
+            commit_hash: Set(snapshot_data.commit_hash),
+            estimated_hours: Set(snapshot_data.estimated_hours),
+            severity_distribution: Set(snapshot_data.severity_distribution),
+            main_debt_types: Set(snapshot_data.main_debt_types),
+            findings: Set(snapshot_data.findings),
+            trend: Set(snapshot_data.trend),
+            created_at: Set(chrono::Utc::now().into()),
+        };
+
+        technical_debt_snapshot::Entity::insert(snapshot).exec(&self.db).await?;
+
+        technical_debt_snapshot::Entity::find_by_id(snapshot_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("TechnicalDebtSnapshot", snapshot_id))
+    }
+
+    /// 查找项目的所有快照（按时间倒序）
+    pub async fn find_by_project(&self, project_id: Uuid) -> Result<Vec<technical_debt_snapshot::Model>> {
+        technical_debt_snapshot::Entity::find()
+            .filter(technical_debt_snapshot::Column::ProjectId.eq(project_id))
+            .order_by_desc(technical_debt_snapshot::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找项目最近一次快照
+    pub async fn find_latest_by_project(&self, project_id: Uuid) -> Result<Option<technical_debt_snapshot::Model>> {
+        technical_debt_snapshot::Entity::find()
+            .filter(technical_debt_snapshot::Column::ProjectId.eq(project_id))
+            .order_by_desc(technical_debt_snapshot::Column::CreatedAt)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 删除项目的所有快照
+    pub async fn delete_by_project(&self, project_id: Uuid) -> Result<u64> {
+        let result = technical_debt_snapshot::Entity::delete_many()
+            .filter(technical_debt_snapshot::Column::ProjectId.eq(project_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+}
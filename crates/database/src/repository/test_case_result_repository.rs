@@ -0,0 +1,88 @@
+//! 测试用例结果仓储实现
+
+use crate::{entities::test_case_result, DatabaseConnection, DatabaseError, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+/// 测试用例结果仓储
+pub struct TestCaseResultRepository {
+    db: DatabaseConnection,
+}
+
+/// 记录测试用例结果的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateTestCaseResultData {
+    pub project_id: Uuid,
+    pub execution_session_id: Uuid,
+    pub test_name: String,
+    pub outcome: String,
+    pub duration_ms: Option<i64>,
+    pub commit_sha: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl TestCaseResultRepository {
+    /// 创建新的测试用例结果仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 批量记录测试用例结果
+    pub async fn create_many(&self, data: Vec<CreateTestCaseResultData>) -> Result<Vec<test_case_result::Model>> {
+        let mut results = Vec::with_capacity(data.len());
+        for item in data {
+            let result = test_case_result::ActiveModel {
+                result_id: Set(Uuid::new_v4()),
+                project_id: Set(item.project_id),
+                execution_session_id: Set(item.execution_session_id),
+                test_name: Set(item.test_name),
+                outcome: Set(item.outcome),
+                duration_ms: Set(item.duration_ms),
+                commit_sha: Set(item.commit_sha),
+                recorded_at: Set(item.recorded_at.into()),
+            };
+            results.push(result.insert(&self.db).await.map_err(DatabaseError::from)?);
+        }
+        Ok(results)
+    }
+
+    /// 查找执行会话的全部测试用例结果
+    pub async fn find_by_session(&self, session_id: Uuid) -> Result<Vec<test_case_result::Model>> {
+        test_case_result::Entity::find()
+            .filter(test_case_result::Column::ExecutionSessionId.eq(session_id))
+            .order_by_asc(test_case_result::Column::TestName)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找项目在指定时间之后的测试用例结果，按测试名称和记录时间排列
+    pub async fn find_by_project_since(
+        &self,
+        project_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<test_case_result::Model>> {
+        let mut query = test_case_result::Entity::find()
+            .filter(test_case_result::Column::ProjectId.eq(project_id));
+        if let Some(since) = since {
+            query = query.filter(test_case_result::Column::RecordedAt.gte(since));
+        }
+        query
+            .order_by_asc(test_case_result::Column::TestName)
+            .order_by_asc(test_case_result::Column::RecordedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 删除执行会话的全部测试用例结果
+    pub async fn delete_by_session(&self, session_id: Uuid) -> Result<u64> {
+        let result = test_case_result::Entity::delete_many()
+            .filter(test_case_result::Column::ExecutionSessionId.eq(session_id))
+            .exec(&self.db)
+            .await
+            .map_err(DatabaseError::from)?;
+        Ok(result.rows_affected)
+    }
+}
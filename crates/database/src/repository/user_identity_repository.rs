@@ -0,0 +1,147 @@
+//! 外部身份仓储实现
+
+use crate::{entities::user_identity, DatabaseConnection, DatabaseError, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+/// 外部身份仓储
+pub struct UserIdentityRepository {
+    db: DatabaseConnection,
+}
+
+/// 关联外部身份的数据结构
+#[derive(Debug, Clone)]
+pub struct LinkIdentityData {
+    pub user_id: Uuid,
+    pub provider: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    pub refresh_token: Option<String>,
+    pub token_expires_at: Option<DateTime<Utc>>,
+}
+
+impl UserIdentityRepository {
+    /// 创建新的外部身份仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 把外部身份关联到本地用户；同一身份只能关联一个用户，一个用户在每个提供方只能关联一个身份
+    pub async fn link(&self, data: LinkIdentityData) -> Result<user_identity::Model> {
+        if let Some(existing) = self.find_by_subject(&data.provider, &data.subject).await? {
+            return Err(DatabaseError::validation(if existing.user_id == data.user_id {
+                format!("已关联 {} 身份", data.provider)
+            } else {
+                format!("该 {} 身份已关联到其他用户", data.provider)
+            }));
+        }
+        let linked = user_identity::Entity::find()
+            .filter(user_identity::Column::UserId.eq(data.user_id))
+            .filter(user_identity::Column::Provider.eq(data.provider.as_str()))
+            .one(&self.db)
+            .await?;
+        if linked.is_some() {
+            return Err(DatabaseError::validation(format!("已关联其他 {} 身份，请先解除关联", data.provider)));
+        }
+
+        let now = Utc::now();
+        let identity = user_identity::ActiveModel {
+            identity_id: Set(Uuid::new_v4()),
+            user_id: Set(data.user_id),
+            provider: Set(data.provider),
+            subject: Set(data.subject),
+            email: Set(data.email),
+            display_name: Set(data.display_name),
+            refresh_token: Set(data.refresh_token),
+            token_expires_at: Set(data.token_expires_at.map(Into::into)),
+            last_login_at: Set(None),
+            created_at: Set(now.into()),
+            updated_at: Set(now.into()),
+        };
+        identity.insert(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 根据提供方和外部用户标识查找身份
+    pub async fn find_by_subject(&self, provider: &str, subject: &str) -> Result<Option<user_identity::Model>> {
+        user_identity::Entity::find()
+            .filter(user_identity::Column::Provider.eq(provider))
+            .filter(user_identity::Column::Subject.eq(subject))
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 查找用户关联的全部身份
+    pub async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<user_identity::Model>> {
+        user_identity::Entity::find()
+            .filter(user_identity::Column::UserId.eq(user_id))
+            .order_by_asc(user_identity::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 记录一次登录，并保存身份提供方新签发的令牌和资料
+    pub async fn record_login(
+        &self,
+        identity_id: Uuid,
+        email: Option<String>,
+        display_name: Option<String>,
+        refresh_token: Option<String>,
+        token_expires_at: Option<DateTime<Utc>>,
+    ) -> Result<user_identity::Model> {
+        let identity = self.get(identity_id).await?;
+        let now = Utc::now();
+        let mut active: user_identity::ActiveModel = identity.into();
+        if email.is_some() {
+            active.email = Set(email);
+        }
+        if display_name.is_some() {
+            active.display_name = Set(display_name);
+        }
+        // 身份提供方没有返回新的刷新令牌时沿用原来的
+        if refresh_token.is_some() {
+            active.refresh_token = Set(refresh_token);
+        }
+        active.token_expires_at = Set(token_expires_at.map(Into::into));
+        active.last_login_at = Set(Some(now.into()));
+        active.updated_at = Set(now.into());
+        active.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 更新刷新后的令牌
+    pub async fn update_tokens(
+        &self,
+        identity_id: Uuid,
+        refresh_token: Option<String>,
+        token_expires_at: Option<DateTime<Utc>>,
+    ) -> Result<user_identity::Model> {
+        let identity = self.get(identity_id).await?;
+        let mut active: user_identity::ActiveModel = identity.into();
+        if refresh_token.is_some() {
+            active.refresh_token = Set(refresh_token);
+        }
+        active.token_expires_at = Set(token_expires_at.map(Into::into));
+        active.updated_at = Set(Utc::now().into());
+        active.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 解除用户的外部身份关联
+    pub async fn unlink(&self, user_id: Uuid, identity_id: Uuid) -> Result<user_identity::Model> {
+        let identity = self.get(identity_id).await?;
+        if identity.user_id != user_id {
+            return Err(DatabaseError::entity_not_found("UserIdentity", identity_id));
+        }
+        user_identity::Entity::delete_by_id(identity_id).exec(&self.db).await?;
+        Ok(identity)
+    }
+
+    async fn get(&self, identity_id: Uuid) -> Result<user_identity::Model> {
+        user_identity::Entity::find_by_id(identity_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("UserIdentity", identity_id))
+    }
+}
@@ -1,7 +1,7 @@
 //! 用户会话仓储实现
 
 use crate::{entities::user_session, DatabaseConnection, DatabaseError, Result};
-use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter, QueryOrder};
+use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, Condition, QueryFilter, QueryOrder};
 use sea_orm::prelude::Expr;
 use uuid::Uuid;
 use chrono::{Duration, Utc};
@@ -19,7 +19,9 @@ pub struct CreateSessionData {
     pub refresh_token: String,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
+    pub device_name: Option<String>,
     pub expires_in_hours: i64, // 会话有效期（小时）
+    pub refresh_expires_in_hours: Option<i64>, // 刷新令牌有效期（小时），为空时与会话一致
 }
 
 impl UserSessionRepository {
@@ -32,6 +34,8 @@ impl UserSessionRepository {
     pub async fn create(&self, session_data: CreateSessionData) -> Result<user_session::Model> {
         let now = Utc::now().into();
         let expires_at = (Utc::now() + Duration::hours(session_data.expires_in_hours)).into();
+        let refresh_expires_at = session_data.refresh_expires_in_hours
+            .map(|hours| (Utc::now() + Duration::hours(hours)).into());
         let session_id = Uuid::new_v4();
         
         let session = user_session::ActiveModel {
@@ -44,6 +48,10 @@ impl UserSessionRepository {
             last_active_at: Set(now),
             ip_address: Set(session_data.ip_address),
             user_agent: Set(session_data.user_agent),
+            device_name: Set(session_data.device_name),
+            refresh_expires_at: Set(refresh_expires_at),
+            previous_refresh_token: Set(None),
+            revoked_at: Set(None),
             is_active: Set(true),
         };
         
@@ -85,6 +93,32 @@ impl UserSessionRepository {
             .map_err(DatabaseError::from)
     }
     
+    /// 列出用户仍可使用的会话（活跃且刷新令牌未过期）
+    pub async fn list_usable_sessions_by_user(&self, user_id: Uuid) -> Result<Vec<user_session::Model>> {
+        let sessions = self.find_active_sessions_by_user(user_id).await?;
+        Ok(sessions.into_iter().filter(|s| s.can_refresh()).collect())
+    }
+    
+    /// 撤销用户的指定会话
+    pub async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> Result<user_session::Model> {
+        let session = user_session::Entity::find_by_id(session_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("UserSession", session_id))?;
+        
+        if session.user_id != user_id {
+            return Err(DatabaseError::business_logic("无权撤销其他用户的会话"));
+        }
+        
+        let mut session: user_session::ActiveModel = session.into();
+        session.is_active = Set(false);
+        session.revoked_at = Set(Some(Utc::now().into()));
+        
+        session.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
     /// 更新最后活跃时间
     pub async fn update_last_active(&self, session_id: Uuid) -> Result<user_session::Model> {
         let session = user_session::Entity::find_by_id(session_id)
@@ -127,6 +161,50 @@ impl UserSessionRepository {
             .map_err(DatabaseError::from)
     }
     
+    /// 轮换刷新令牌
+    ///
+    /// 旧刷新令牌只能使用一次；已轮换的令牌被再次使用时视为泄露，立即撤销整个会话
+    pub async fn rotate_refresh_token(
+        &self,
+        refresh_token: &str,
+        new_token: String,
+        new_refresh_token: String,
+        expires_in_hours: i64,
+        refresh_expires_in_hours: i64,
+    ) -> Result<user_session::Model> {
+        let Some(session) = self.find_by_refresh_token(refresh_token).await? else {
+            // 检测已轮换令牌的重放
+            let reused = user_session::Entity::find()
+                .filter(user_session::Column::PreviousRefreshToken.eq(refresh_token))
+                .one(&self.db)
+                .await?;
+            if let Some(reused) = reused {
+                if reused.is_active {
+                    self.revoke_session(reused.user_id, reused.session_id).await?;
+                }
+                return Err(DatabaseError::business_logic("刷新令牌已被使用，会话已撤销"));
+            }
+            return Err(DatabaseError::validation("无效的刷新令牌"));
+        };
+        
+        if !session.can_refresh() {
+            return Err(DatabaseError::validation("刷新令牌已过期或会话已失效"));
+        }
+        
+        let now = Utc::now();
+        let mut session: user_session::ActiveModel = session.into();
+        session.previous_refresh_token = Set(Some(refresh_token.to_string()));
+        session.token = Set(new_token);
+        session.refresh_token = Set(new_refresh_token);
+        session.expires_at = Set((now + Duration::hours(expires_in_hours)).into());
+        session.refresh_expires_at = Set(Some((now + Duration::hours(refresh_expires_in_hours)).into()));
+        session.last_active_at = Set(now.into());
+        
+        session.update(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+    
     /// 使会话失效
     pub async fn invalidate_session(&self, session_id: Uuid) -> Result<()> {
         let session = user_session::Entity::find_by_id(session_id)
@@ -153,11 +231,21 @@ impl UserSessionRepository {
     }
     
     /// 清理过期会话
+    ///
+    /// 仅删除已无法刷新的会话：刷新令牌过期，或未设置刷新有效期且会话本身已过期
     pub async fn cleanup_expired_sessions(&self) -> Result<u64> {
         let now: sea_orm::prelude::DateTimeWithTimeZone = Utc::now().into();
         
         let result = user_session::Entity::delete_many()
-            .filter(user_session::Column::ExpiresAt.lt(now))
+            .filter(
+                Condition::any()
+                    .add(user_session::Column::RefreshExpiresAt.lt(now))
+                    .add(
+                        Condition::all()
+                            .add(user_session::Column::RefreshExpiresAt.is_null())
+                            .add(user_session::Column::ExpiresAt.lt(now)),
+                    ),
+            )
             .exec(&self.db)
             .await?;
         
@@ -179,7 +267,10 @@ impl UserSessionRepository {
                     return Ok(None);
                 }
                 
-                self.invalidate_session(session.session_id).await?;
+                // 访问令牌过期但仍可刷新时保留会话，等待客户端轮换令牌
+                if !session.can_refresh() {
+                    self.invalidate_session(session.session_id).await?;
+                }
                 Ok(None)
             }
         } else {
@@ -227,7 +318,9 @@ mod tests {
             refresh_token: "test_refresh_token".to_string(),
             ip_address: Some("127.0.0.1".to_string()),
             user_agent: Some("test_agent".to_string()),
+            device_name: Some("test_device".to_string()),
             expires_in_hours: 24,
+            refresh_expires_in_hours: Some(24 * 30),
         };
         
         let session = repo.create(session_data).await.unwrap();
@@ -250,7 +343,9 @@ mod tests {
             refresh_token: "test_refresh_token".to_string(),
             ip_address: None,
             user_agent: None,
+            device_name: None,
             expires_in_hours: 24,
+            refresh_expires_in_hours: None,
         };
         
         let created_session = repo.create(session_data).await.unwrap();
@@ -279,7 +374,9 @@ mod tests {
             refresh_token: "test_refresh_token".to_string(),
             ip_address: None,
             user_agent: None,
+            device_name: None,
             expires_in_hours: 24,
+            refresh_expires_in_hours: None,
         };
         
         let session = repo.create(session_data).await.unwrap();
@@ -291,4 +388,114 @@ mod tests {
         let invalid_session = repo.validate_session("test_token").await.unwrap();
         assert!(invalid_session.is_none());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_rotate_refresh_token_detects_reuse() {
+        let db = setup_test_db().await;
+        let user_id = create_test_user(&db).await;
+        let repo = UserSessionRepository::new(db);
+        
+        let session = repo.create(CreateSessionData {
+            user_id,
+            token: "token_1".to_string(),
+            refresh_token: "refresh_1".to_string(),
+            ip_address: None,
+            user_agent: None,
+            device_name: None,
+            expires_in_hours: 1,
+            refresh_expires_in_hours: Some(24),
+        }).await.unwrap();
+        
+        // 正常轮换
+        let rotated = repo.rotate_refresh_token("refresh_1", "token_2".to_string(), "refresh_2".to_string(), 1, 24)
+            .await.unwrap();
+        assert_eq!(rotated.session_id, session.session_id);
+        assert_eq!(rotated.token, "token_2");
+        assert_eq!(rotated.previous_refresh_token.as_deref(), Some("refresh_1"));
+        assert!(repo.validate_session("token_1").await.unwrap().is_none());
+        
+        // 重放旧刷新令牌会撤销整个会话
+        assert!(repo.rotate_refresh_token("refresh_1", "token_3".to_string(), "refresh_3".to_string(), 1, 24)
+            .await.is_err());
+        assert!(repo.validate_session("token_2").await.unwrap().is_none());
+        assert!(repo.rotate_refresh_token("refresh_2", "token_4".to_string(), "refresh_4".to_string(), 1, 24)
+            .await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_and_list_sessions() {
+        let db = setup_test_db().await;
+        let user_id = create_test_user(&db).await;
+        let repo = UserSessionRepository::new(db);
+        
+        let mut session_ids = Vec::new();
+        for i in 0..2 {
+            let session = repo.create(CreateSessionData {
+                user_id,
+                token: format!("token_{}", i),
+                refresh_token: format!("refresh_{}", i),
+                ip_address: Some("10.0.0.1".to_string()),
+                user_agent: Some("agent".to_string()),
+                device_name: Some(format!("device_{}", i)),
+                expires_in_hours: 24,
+                refresh_expires_in_hours: Some(24 * 30),
+            }).await.unwrap();
+            session_ids.push(session.session_id);
+        }
+        
+        assert_eq!(repo.list_usable_sessions_by_user(user_id).await.unwrap().len(), 2);
+        
+        // 其他用户不能撤销
+        assert!(repo.revoke_session(Uuid::new_v4(), session_ids[0]).await.is_err());
+        
+        let revoked = repo.revoke_session(user_id, session_ids[0]).await.unwrap();
+        assert!(!revoked.is_active);
+        assert!(revoked.revoked_at.is_some());
+        
+        let remaining = repo.list_usable_sessions_by_user(user_id).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].device_name.as_deref(), Some("device_1"));
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_keeps_refreshable_sessions() {
+        let db = setup_test_db().await;
+        let user_id = create_test_user(&db).await;
+        let repo = UserSessionRepository::new(db);
+        
+        // 访问令牌已过期但刷新令牌仍有效
+        repo.create(CreateSessionData {
+            user_id,
+            token: "refreshable".to_string(),
+            refresh_token: "refreshable_refresh".to_string(),
+            ip_address: None,
+            user_agent: None,
+            device_name: None,
+            expires_in_hours: -1,
+            refresh_expires_in_hours: Some(24),
+        }).await.unwrap();
+        
+        // 完全过期
+        repo.create(CreateSessionData {
+            user_id,
+            token: "expired".to_string(),
+            refresh_token: "expired_refresh".to_string(),
+            ip_address: None,
+            user_agent: None,
+            device_name: None,
+            expires_in_hours: -2,
+            refresh_expires_in_hours: Some(-1),
+        }).await.unwrap();
+        
+        // 过期访问令牌不会使可刷新的会话失效
+        assert!(repo.validate_session("refreshable").await.unwrap().is_none());
+        
+        let removed = repo.cleanup_expired_sessions().await.unwrap();
+        assert_eq!(removed, 1);
+        assert!(repo.find_by_token("expired").await.unwrap().is_none());
+        
+        let rotated = repo.rotate_refresh_token("refreshable_refresh", "new".to_string(), "new_refresh".to_string(), 24, 24)
+            .await.unwrap();
+        assert!(rotated.is_valid());
+    }
+}
@@ -0,0 +1,196 @@
+//! 两步验证仓储实现
+
+use crate::{entities::user_two_factor, DatabaseConnection, DatabaseError, Result};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    prelude::DateTimeWithTimeZone, sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, EntityTrait,
+    QueryFilter, Set,
+};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// 两步验证仓储
+pub struct UserTwoFactorRepository {
+    db: DatabaseConnection,
+}
+
+impl UserTwoFactorRepository {
+    /// 创建新的两步验证仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 查找用户的两步验证设置
+    pub async fn find_by_user(&self, user_id: Uuid) -> Result<Option<user_two_factor::Model>> {
+        user_two_factor::Entity::find_by_id(user_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 开始登记：保存新的密钥，确认前不会在登录时校验
+    pub async fn start_enrollment(&self, user_id: Uuid, secret: String) -> Result<user_two_factor::Model> {
+        let existing = self.find_by_user(user_id).await?;
+        if existing.as_ref().is_some_and(|record| record.enabled) {
+            return Err(DatabaseError::validation("已启用两步验证，请先停用后再重新登记"));
+        }
+        self.upsert(user_id, existing, |active| {
+            active.secret = Set(Some(secret));
+            active.last_used_step = Set(None);
+        }).await
+    }
+
+    /// 确认登记，启用两步验证并保存备用恢复码摘要
+    pub async fn enable(&self, user_id: Uuid, backup_code_hashes: Vec<String>) -> Result<user_two_factor::Model> {
+        let existing = self.get(user_id).await?;
+        self.upsert(user_id, Some(existing), |active| {
+            active.enabled = Set(true);
+            active.backup_code_hashes = Set(serde_json::json!(backup_code_hashes));
+            active.confirmed_at = Set(Some(Utc::now().into()));
+        }).await
+    }
+
+    /// 记录通过校验的 TOTP 时间步
+    pub async fn record_step(&self, user_id: Uuid, step: i64) -> Result<user_two_factor::Model> {
+        let existing = self.get(user_id).await?;
+        self.upsert(user_id, Some(existing), |active| {
+            active.last_used_step = Set(Some(step));
+        }).await
+    }
+
+    /// 消耗通过校验的 TOTP 时间步，只有晚于上次使用的时间步才会写入
+    ///
+    /// 条件更新在 SQL 层面完成，并发提交同一时间步的验证码时只有一方返回 `true`
+    pub async fn consume_step(&self, user_id: Uuid, step: i64) -> Result<bool> {
+        let result = user_two_factor::Entity::update_many()
+            .col_expr(user_two_factor::Column::LastUsedStep, Expr::value(step))
+            .col_expr(user_two_factor::Column::UpdatedAt, Expr::value(DateTimeWithTimeZone::from(Utc::now())))
+            .filter(user_two_factor::Column::UserId.eq(user_id))
+            .filter(
+                Condition::any()
+                    .add(user_two_factor::Column::LastUsedStep.is_null())
+                    .add(user_two_factor::Column::LastUsedStep.lt(step)),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// 消耗一个备用恢复码：仅当保存的摘要列表仍是读取时的 `expected` 才替换为 `remaining`
+    ///
+    /// 列表已被并发修改时返回 `false`，调用方应重新读取后再匹配
+    pub async fn consume_backup_code(
+        &self,
+        user_id: Uuid,
+        expected: &JsonValue,
+        remaining: Vec<String>,
+    ) -> Result<bool> {
+        let result = user_two_factor::Entity::update_many()
+            .col_expr(user_two_factor::Column::BackupCodeHashes, Expr::value(serde_json::json!(remaining)))
+            .col_expr(user_two_factor::Column::UpdatedAt, Expr::value(DateTimeWithTimeZone::from(Utc::now())))
+            .filter(user_two_factor::Column::UserId.eq(user_id))
+            .filter(user_two_factor::Column::BackupCodeHashes.eq(expected.clone()))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// 记录一次校验失败，达到上限时锁定到指定时间并清零计数
+    pub async fn record_failure(
+        &self,
+        user_id: Uuid,
+        max_attempts: i32,
+        lock_until: DateTime<Utc>,
+    ) -> Result<user_two_factor::Model> {
+        let existing = self.get(user_id).await?;
+        let attempts = existing.failed_attempts + 1;
+        self.upsert(user_id, Some(existing), |active| {
+            if attempts >= max_attempts {
+                active.failed_attempts = Set(0);
+                active.locked_until = Set(Some(lock_until.into()));
+            } else {
+                active.failed_attempts = Set(attempts);
+            }
+        }).await
+    }
+
+    /// 校验成功后清除失败次数和锁定
+    pub async fn reset_failures(&self, user_id: Uuid) -> Result<user_two_factor::Model> {
+        let existing = self.get(user_id).await?;
+        self.upsert(user_id, Some(existing), |active| {
+            active.failed_attempts = Set(0);
+            active.locked_until = Set(None);
+        }).await
+    }
+
+    /// 替换备用恢复码摘要（重新生成时）
+    pub async fn replace_backup_codes(&self, user_id: Uuid, backup_code_hashes: Vec<String>) -> Result<user_two_factor::Model> {
+        let existing = self.get(user_id).await?;
+        self.upsert(user_id, Some(existing), |active| {
+            active.backup_code_hashes = Set(serde_json::json!(backup_code_hashes));
+        }).await
+    }
+
+    /// 停用两步验证，清除密钥和恢复码，保留强制设置
+    pub async fn disable(&self, user_id: Uuid) -> Result<user_two_factor::Model> {
+        let existing = self.get(user_id).await?;
+        self.upsert(user_id, Some(existing), |active| {
+            active.secret = Set(None);
+            active.enabled = Set(false);
+            active.backup_code_hashes = Set(serde_json::json!([]));
+            active.last_used_step = Set(None);
+            active.failed_attempts = Set(0);
+            active.locked_until = Set(None);
+            active.confirmed_at = Set(None);
+        }).await
+    }
+
+    /// 设置是否强制该用户使用两步验证
+    pub async fn set_required(&self, user_id: Uuid, required: bool) -> Result<user_two_factor::Model> {
+        let existing = self.find_by_user(user_id).await?;
+        self.upsert(user_id, existing, |active| {
+            active.required = Set(required);
+        }).await
+    }
+
+    async fn get(&self, user_id: Uuid) -> Result<user_two_factor::Model> {
+        self.find_by_user(user_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("UserTwoFactor", user_id))
+    }
+
+    /// 在已有记录上修改，没有记录时按默认值新建
+    async fn upsert(
+        &self,
+        user_id: Uuid,
+        existing: Option<user_two_factor::Model>,
+        apply: impl FnOnce(&mut user_two_factor::ActiveModel),
+    ) -> Result<user_two_factor::Model> {
+        let now = Utc::now();
+        match existing {
+            Some(record) => {
+                let mut active: user_two_factor::ActiveModel = record.into();
+                apply(&mut active);
+                active.updated_at = Set(now.into());
+                active.update(&self.db).await.map_err(DatabaseError::from)
+            }
+            None => {
+                let mut active = user_two_factor::ActiveModel {
+                    user_id: Set(user_id),
+                    secret: Set(None),
+                    enabled: Set(false),
+                    required: Set(false),
+                    backup_code_hashes: Set(serde_json::json!([])),
+                    last_used_step: Set(None),
+                    failed_attempts: Set(0),
+                    locked_until: Set(None),
+                    confirmed_at: Set(None),
+                    created_at: Set(now.into()),
+                    updated_at: Set(now.into()),
+                };
+                apply(&mut active);
+                active.insert(&self.db).await.map_err(DatabaseError::from)
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Webhook死信仓储实现
+
+use crate::{entities::webhook_dead_letter, DatabaseConnection, DatabaseError, Result};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use uuid::Uuid;
+
+/// Webhook死信仓储
+pub struct WebhookDeadLetterRepository {
+    db: DatabaseConnection,
+}
+
+/// 记录死信的数据结构
+#[derive(Debug, Clone)]
+pub struct CreateDeadLetterData {
+    pub project_id: Uuid,
+    pub source: String,
+    pub event_name: Option<String>,
+    pub delivery_id: Option<String>,
+    pub payload: String,
+    pub error: String,
+}
+
+impl WebhookDeadLetterRepository {
+    /// 创建新的Webhook死信仓储实例
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// 记录无法映射的载荷
+    pub async fn create(&self, data: CreateDeadLetterData) -> Result<webhook_dead_letter::Model> {
+        let dead_letter = webhook_dead_letter::ActiveModel {
+            dead_letter_id: Set(Uuid::new_v4()),
+            project_id: Set(data.project_id),
+            source: Set(data.source),
+            event_name: Set(data.event_name),
+            delivery_id: Set(data.delivery_id),
+            payload: Set(data.payload),
+            error: Set(data.error),
+            received_at: Set(Utc::now().into()),
+            replay_count: Set(0),
+            last_replayed_at: Set(None),
+            resolved_at: Set(None),
+        };
+        dead_letter.insert(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 根据ID查找死信
+    pub async fn find_by_id(&self, dead_letter_id: Uuid) -> Result<Option<webhook_dead_letter::Model>> {
+        webhook_dead_letter::Entity::find_by_id(dead_letter_id)
+            .one(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+
+    /// 记录一次重放结果，成功时标记为已处理，失败时更新失败原因
+    pub async fn record_replay(
+        &self,
+        dead_letter_id: Uuid,
+        error: Option<String>,
+    ) -> Result<webhook_dead_letter::Model> {
+        let dead_letter = self
+            .find_by_id(dead_letter_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("WebhookDeadLetter", dead_letter_id))?;
+        let replay_count = dead_letter.replay_count + 1;
+        let now = Utc::now();
+        let mut dead_letter: webhook_dead_letter::ActiveModel = dead_letter.into();
+        dead_letter.replay_count = Set(replay_count);
+        dead_letter.last_replayed_at = Set(Some(now.into()));
+        match error {
+            Some(error) => dead_letter.error = Set(error),
+            None => dead_letter.resolved_at = Set(Some(now.into())),
+        }
+        dead_letter.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 丢弃死信，不再重放
+    pub async fn discard(&self, dead_letter_id: Uuid) -> Result<webhook_dead_letter::Model> {
+        let dead_letter = self
+            .find_by_id(dead_letter_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("WebhookDeadLetter", dead_letter_id))?;
+        let mut dead_letter: webhook_dead_letter::ActiveModel = dead_letter.into();
+        dead_letter.resolved_at = Set(Some(Utc::now().into()));
+        dead_letter.update(&self.db).await.map_err(DatabaseError::from)
+    }
+
+    /// 查找项目的死信（按接收时间降序）
+    pub async fn find_by_project(
+        &self,
+        project_id: Uuid,
+        include_resolved: bool,
+        limit: u64,
+    ) -> Result<Vec<webhook_dead_letter::Model>> {
+        let mut query = webhook_dead_letter::Entity::find()
+            .filter(webhook_dead_letter::Column::ProjectId.eq(project_id));
+        if !include_resolved {
+            query = query.filter(webhook_dead_letter::Column::ResolvedAt.is_null());
+        }
+        query
+            .order_by_desc(webhook_dead_letter::Column::ReceivedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .map_err(DatabaseError::from)
+    }
+}
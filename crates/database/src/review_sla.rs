@@ -0,0 +1,486 @@
+//! 代码审查时限与超时改派
+//!
+//! 代码审查创建时按 [`ReviewPriority`] 计算截止时间，时限来自项目SLA配置中的
+//! [`ReviewDeadlinePolicy`]，未配置时使用默认值。后台定期调用 [`evaluate_all`]：
+//! - 剩余时间不足时限的 `reminder_ratio` 时提醒审查者一次；
+//! - 超过截止时间后改派给负载最低的其他审查者，重新计时；变更文件的负责人优先，
+//!   其次是同类审查者（Agent换Agent、人换人）；
+//! - 没有可改派的审查者或改派次数达到上限时升级为超时，不再自动处理。
+//!
+//! 每次提醒、改派和超时都记录为任务的领域事件。
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{hash_map::Entry, HashMap};
+use uuid::Uuid;
+
+use crate::{
+    code_ownership,
+    entities::{
+        code_review::{self, ReviewStatus},
+        domain_event::{AggregateType, DomainEventType},
+        project,
+    },
+    repository::{
+        domain_event_repository::CreateDomainEventData, AgentRepository, CodeReviewRepository,
+        DomainEventRepository, ExecutionSessionRepository, ProjectMemberRepository, TaskRepository,
+    },
+    sla::SlaPolicy,
+    DatabaseConnection, DatabaseError, Result,
+};
+use codex_multi_agent::{Clock, ProjectRole, ReviewPriority};
+use sea_orm::EntityTrait;
+
+/// 代码审查时限配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReviewDeadlinePolicy {
+    /// 是否自动提醒、改派和升级超时的审查
+    pub enabled: bool,
+    /// 紧急审查的时限（小时）
+    pub urgent_hours: i64,
+    /// 高优先级审查的时限（小时）
+    pub high_hours: i64,
+    /// 正常优先级审查的时限（小时）
+    pub normal_hours: i64,
+    /// 低优先级审查的时限（小时）
+    pub low_hours: i64,
+    /// 剩余时间不足时限的该比例时提醒审查者，为0时不提醒
+    pub reminder_ratio: f64,
+    /// 超时自动改派的最多次数，达到后升级为超时
+    pub max_reassignments: i32,
+}
+
+impl Default for ReviewDeadlinePolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            urgent_hours: 4,
+            high_hours: 12,
+            normal_hours: 48,
+            low_hours: 120,
+            reminder_ratio: 0.25,
+            max_reassignments: 2,
+        }
+    }
+}
+
+impl ReviewDeadlinePolicy {
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        if [self.urgent_hours, self.high_hours, self.normal_hours, self.low_hours]
+            .iter()
+            .any(|hours| *hours <= 0)
+        {
+            return Err(DatabaseError::validation("审查时限必须大于0"));
+        }
+        if !(0.0..1.0).contains(&self.reminder_ratio) {
+            return Err(DatabaseError::validation("审查提醒比例必须在0到1之间"));
+        }
+        if self.max_reassignments < 0 {
+            return Err(DatabaseError::validation("审查改派次数上限不能为负数"));
+        }
+        Ok(())
+    }
+
+    /// 指定优先级的审查时限
+    pub fn window(&self, priority: &ReviewPriority) -> Duration {
+        let hours = match priority {
+            ReviewPriority::Urgent => self.urgent_hours,
+            ReviewPriority::High => self.high_hours,
+            ReviewPriority::Normal => self.normal_hours,
+            ReviewPriority::Low => self.low_hours,
+        };
+        Duration::hours(hours)
+    }
+
+    /// 从分配时间开始计算的截止时间
+    pub fn deadline(&self, priority: &ReviewPriority, assigned_at: DateTime<Utc>) -> DateTime<Utc> {
+        assigned_at + self.window(priority)
+    }
+
+    /// 截止时间前开始提醒的时间点
+    fn reminder_at(&self, priority: &ReviewPriority, deadline: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.reminder_ratio <= 0.0 {
+            return None;
+        }
+        let lead_seconds = self.window(priority).num_seconds() as f64 * self.reminder_ratio;
+        Some(deadline - Duration::seconds(lead_seconds.round() as i64))
+    }
+}
+
+/// 审查者：审查员Agent或项目成员
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum Reviewer {
+    Agent(Uuid),
+    User(Uuid),
+}
+
+impl Reviewer {
+    /// 代码审查当前的审查者，指定了人工审查者时以人为准
+    pub fn of(review: &code_review::Model) -> Self {
+        match review.reviewer_user_id {
+            Some(user_id) => Reviewer::User(user_id),
+            None => Reviewer::Agent(review.reviewer_agent_id),
+        }
+    }
+}
+
+/// 一次提醒、改派或超时的通知
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewSlaNotice {
+    pub review_id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub priority: ReviewPriority,
+    /// 当前的审查者
+    pub reviewer: Reviewer,
+    /// 改派前的审查者，仅改派通知有值
+    pub previous_reviewer: Option<Reviewer>,
+    pub review_deadline: DateTime<Utc>,
+    pub reassignment_count: i32,
+}
+
+/// 一次评估的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewSlaReport {
+    pub reminders: Vec<ReviewSlaNotice>,
+    pub reassignments: Vec<ReviewSlaNotice>,
+    pub escalations: Vec<ReviewSlaNotice>,
+}
+
+impl ReviewSlaReport {
+    /// 本次评估是否没有任何变化
+    pub fn is_empty(&self) -> bool {
+        self.reminders.is_empty() && self.reassignments.is_empty() && self.escalations.is_empty()
+    }
+}
+
+/// 审查优先级的存储值
+pub fn priority_str(priority: &ReviewPriority) -> &'static str {
+    match priority {
+        ReviewPriority::Low => "low",
+        ReviewPriority::Normal => "normal",
+        ReviewPriority::High => "high",
+        ReviewPriority::Urgent => "urgent",
+    }
+}
+
+/// 解析存储的审查优先级，无法识别时视为正常优先级
+pub fn parse_priority(priority: &str) -> ReviewPriority {
+    match priority {
+        "low" => ReviewPriority::Low,
+        "high" => ReviewPriority::High,
+        "urgent" => ReviewPriority::Urgent,
+        _ => ReviewPriority::Normal,
+    }
+}
+
+/// 读取项目的审查时限配置，项目未配置SLA时使用默认值
+///
+/// 审查截止时间始终计算，SLA的总开关只影响任务与里程碑的违约检查。
+pub async fn project_review_policy(db: &DatabaseConnection, project_id: Uuid) -> Result<ReviewDeadlinePolicy> {
+    let project = project::Entity::find_by_id(project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+    review_policy_of(&project)
+}
+
+fn review_policy_of(project: &project::Model) -> Result<ReviewDeadlinePolicy> {
+    match &project.sla_policy {
+        Some(value) => Ok(serde_json::from_value::<SlaPolicy>(value.clone())?.review_deadlines),
+        None => Ok(ReviewDeadlinePolicy::default()),
+    }
+}
+
+/// 计算新审查的截止时间，任务不存在时使用默认时限
+pub(crate) async fn initial_deadline(
+    db: &DatabaseConnection,
+    task_id: Uuid,
+    priority: &ReviewPriority,
+    assigned_at: DateTime<Utc>,
+) -> Result<DateTime<Utc>> {
+    let policy = match TaskRepository::new(db.clone()).find_by_id(task_id).await? {
+        Some(task) => project_review_policy(db, task.project_id).await?,
+        None => ReviewDeadlinePolicy::default(),
+    };
+    Ok(policy.deadline(priority, assigned_at))
+}
+
+/// 调整审查优先级，按原分配时间重新计算截止时间
+pub async fn set_review_priority(
+    db: &DatabaseConnection,
+    review_id: Uuid,
+    priority: ReviewPriority,
+) -> Result<code_review::Model> {
+    let reviews = CodeReviewRepository::new(db.clone());
+    let review = find_open_review(&reviews, review_id).await?;
+    let (_, policy) = review_project(db, &review).await?;
+
+    let assigned_at = review.assigned_at.unwrap_or(review.created_at).with_timezone(&Utc);
+    reviews
+        .update_priority(review_id, priority_str(&priority), policy.deadline(&priority, assigned_at))
+        .await
+}
+
+/// 手动把审查分配给指定的审查者，重新开始计时
+///
+/// 人工审查者必须至少是项目贡献者；手动分配不计入自动改派次数。
+pub async fn assign_reviewer(
+    db: &DatabaseConnection,
+    review_id: Uuid,
+    reviewer: Reviewer,
+    assigned_by: Uuid,
+    clock: &dyn Clock,
+) -> Result<code_review::Model> {
+    let reviews = CodeReviewRepository::new(db.clone());
+    let review = find_open_review(&reviews, review_id).await?;
+    let (project_id, policy) = review_project(db, &review).await?;
+
+    let (agent_id, user_id) = match reviewer {
+        Reviewer::Agent(agent_id) => {
+            AgentRepository::new(db.clone())
+                .find_by_id(agent_id)
+                .await?
+                .ok_or_else(|| DatabaseError::entity_not_found("Agent", agent_id))?;
+            (agent_id, None)
+        }
+        Reviewer::User(user_id) => {
+            ProjectMemberRepository::new(db.clone())
+                .require_role(project_id, user_id, ProjectRole::Contributor)
+                .await?;
+            (review.reviewer_agent_id, Some(user_id))
+        }
+    };
+
+    let previous = Reviewer::of(&review);
+    let now = clock.now();
+    let priority = parse_priority(&review.priority);
+    let updated = reviews
+        .reassign(review, agent_id, user_id, now, policy.deadline(&priority, now), false)
+        .await?;
+    record_event(
+        db,
+        DomainEventType::ReviewReassigned,
+        &notice(&updated, project_id, Some(previous)),
+        json!({ "reason": "manual", "assigned_by": assigned_by }),
+    )
+    .await?;
+    Ok(updated)
+}
+
+/// 评估所有进行中且有截止时间的审查
+pub async fn evaluate_all(db: &DatabaseConnection, now: DateTime<Utc>) -> Result<ReviewSlaReport> {
+    let reviews = CodeReviewRepository::new(db.clone()).find_open_with_deadline().await?;
+    evaluate_reviews(db, reviews, None, now).await
+}
+
+/// 在指定时间点评估项目中进行中的审查
+pub async fn evaluate_project(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<ReviewSlaReport> {
+    let reviews = CodeReviewRepository::new(db.clone()).find_open_with_deadline().await?;
+    evaluate_reviews(db, reviews, Some(project_id), now).await
+}
+
+async fn evaluate_reviews(
+    db: &DatabaseConnection,
+    reviews: Vec<code_review::Model>,
+    only_project: Option<Uuid>,
+    now: DateTime<Utc>,
+) -> Result<ReviewSlaReport> {
+    let repository = CodeReviewRepository::new(db.clone());
+    let tasks = TaskRepository::new(db.clone());
+    let mut projects: HashMap<Uuid, (project::Model, ReviewDeadlinePolicy)> = HashMap::new();
+    let mut load = reviewer_load(&repository).await?;
+    let mut report = ReviewSlaReport::default();
+
+    for review in reviews {
+        let Some(task) = tasks.find_by_id(review.task_id).await? else {
+            continue;
+        };
+        if only_project.is_some_and(|project_id| project_id != task.project_id) {
+            continue;
+        }
+        if let Entry::Vacant(entry) = projects.entry(task.project_id) {
+            let project = project::Entity::find_by_id(task.project_id)
+                .one(db)
+                .await?
+                .ok_or_else(|| DatabaseError::entity_not_found("Project", task.project_id))?;
+            let policy = review_policy_of(&project)?;
+            entry.insert((project, policy));
+        }
+        let (project, policy) = &projects[&task.project_id];
+        if !policy.enabled {
+            continue;
+        }
+        let Some(deadline) = review.review_deadline.map(|deadline| deadline.with_timezone(&Utc)) else {
+            continue;
+        };
+        let priority = parse_priority(&review.priority);
+
+        if now < deadline {
+            let due = policy.reminder_at(&priority, deadline).is_some_and(|at| now >= at);
+            if due && review.reminded_at.is_none() {
+                repository.mark_reminded(review.review_id, now).await?;
+                let notice = notice(&review, project.project_id, None);
+                record_event(db, DomainEventType::ReviewReminderSent, &notice, json!({})).await?;
+                report.reminders.push(notice);
+            }
+            continue;
+        }
+
+        let previous = Reviewer::of(&review);
+        let alternate = if review.reassignment_count < policy.max_reassignments {
+            pick_alternate(db, project, &review, &load).await?
+        } else {
+            None
+        };
+        match alternate {
+            Some(next) => {
+                let (agent_id, user_id) = match next {
+                    Reviewer::Agent(agent_id) => (agent_id, None),
+                    Reviewer::User(user_id) => (review.reviewer_agent_id, Some(user_id)),
+                };
+                let new_deadline = policy.deadline(&priority, now);
+                let updated = repository
+                    .reassign(review, agent_id, user_id, now, new_deadline, true)
+                    .await?;
+                if let Some(count) = load.get_mut(&previous) {
+                    *count = count.saturating_sub(1);
+                }
+                *load.entry(next).or_default() += 1;
+
+                let notice = notice(&updated, project.project_id, Some(previous));
+                record_event(
+                    db,
+                    DomainEventType::ReviewReassigned,
+                    &notice,
+                    json!({ "reason": "deadline_missed", "missed_deadline": deadline }),
+                )
+                .await?;
+                report.reassignments.push(notice);
+            }
+            None => {
+                repository.mark_escalated(review.review_id, now).await?;
+                let notice = notice(&review, project.project_id, None);
+                record_event(db, DomainEventType::ReviewDeadlineMissed, &notice, json!({})).await?;
+                report.escalations.push(notice);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// 选择负载最低的其他审查者
+///
+/// 依次考虑变更文件的负责人、与当前审查者同类的审查者、另一类审查者
+async fn pick_alternate(
+    db: &DatabaseConnection,
+    project: &project::Model,
+    review: &code_review::Model,
+    load: &HashMap<Reviewer, usize>,
+) -> Result<Option<Reviewer>> {
+    let current = Reviewer::of(review);
+    let author_agent_id = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(review.execution_session_id)
+        .await?
+        .map(|session| session.agent_id);
+    let candidates =
+        code_ownership::reviewer_candidates(db, project, &code_ownership::review_files(review), author_agent_id)
+            .await?;
+
+    let (same, other) = match current {
+        Reviewer::Agent(_) => (candidates.agents, candidates.users),
+        Reviewer::User(_) => (candidates.users, candidates.agents),
+    };
+    for tier in [candidates.owners, same, other] {
+        let best = tier
+            .into_iter()
+            .filter(|candidate| *candidate != current)
+            .min_by_key(|candidate| load.get(candidate).copied().unwrap_or(0));
+        if best.is_some() {
+            return Ok(best);
+        }
+    }
+    Ok(None)
+}
+
+/// 各审查者手上进行中的审查数
+async fn reviewer_load(repository: &CodeReviewRepository) -> Result<HashMap<Reviewer, usize>> {
+    let mut load = HashMap::new();
+    for review in repository.find_open().await? {
+        *load.entry(Reviewer::of(&review)).or_default() += 1;
+    }
+    Ok(load)
+}
+
+async fn find_open_review(reviews: &CodeReviewRepository, review_id: Uuid) -> Result<code_review::Model> {
+    let review = reviews
+        .find_by_id(review_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("CodeReview", review_id))?;
+    match ReviewStatus::from(review.status.clone()) {
+        ReviewStatus::Pending | ReviewStatus::InProgress => Ok(review),
+        _ => Err(DatabaseError::business_logic("审查已结束，不能调整时限或审查者")),
+    }
+}
+
+async fn review_project(
+    db: &DatabaseConnection,
+    review: &code_review::Model,
+) -> Result<(Uuid, ReviewDeadlinePolicy)> {
+    let task = TaskRepository::new(db.clone())
+        .find_by_id(review.task_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Task", review.task_id))?;
+    let policy = project_review_policy(db, task.project_id).await?;
+    Ok((task.project_id, policy))
+}
+
+fn notice(review: &code_review::Model, project_id: Uuid, previous_reviewer: Option<Reviewer>) -> ReviewSlaNotice {
+    ReviewSlaNotice {
+        review_id: review.review_id,
+        task_id: review.task_id,
+        project_id,
+        priority: parse_priority(&review.priority),
+        reviewer: Reviewer::of(review),
+        previous_reviewer,
+        review_deadline: review
+            .review_deadline
+            .map(|deadline| deadline.with_timezone(&Utc))
+            .unwrap_or_else(|| review.created_at.with_timezone(&Utc)),
+        reassignment_count: review.reassignment_count,
+    }
+}
+
+async fn record_event(
+    db: &DatabaseConnection,
+    event_type: DomainEventType,
+    notice: &ReviewSlaNotice,
+    extra: serde_json::Value,
+) -> Result<()> {
+    let mut event_data = serde_json::to_value(notice)?;
+    if let (Some(data), Some(extra)) = (event_data.as_object_mut(), extra.as_object()) {
+        data.extend(extra.clone());
+    }
+
+    let events = DomainEventRepository::new(db.clone());
+    let version = events.get_latest_version(notice.task_id).await? + 1;
+    events
+        .create(CreateDomainEventData {
+            aggregate_type: AggregateType::Task.to_string(),
+            aggregate_id: notice.task_id,
+            event_type: event_type.to_string(),
+            event_data,
+            event_version: version,
+        })
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,779 @@
+//! 安全扫描阶段
+//!
+//! 执行配置 `quality_checks.enable_security_check` 控制执行会话是否进行安全扫描（未配置时默认开启）。
+//! [`run_security_scan`] 在会话工作空间中运行：
+//! - 依赖漏洞扫描：存在 `Cargo.lock` 时运行 `cargo audit --json`，存在 `package-lock.json` 时运行
+//!   `npm audit --json`，通过 [`ScanRunner`] 执行；
+//! - 密钥扫描：内置规则在文本文件中查找私钥、云服务访问密钥和令牌，发现中不记录密钥本身。
+//!
+//! 各扫描器的输出统一为 [`SecurityIssue`] 并保存到 `security_findings`。严重程度达到项目质量门禁
+//! `security_blocking_severity`（默认 blocker，即 critical 级别的漏洞和泄露的密钥）的发现会自动在会话的
+//! 任务下创建修复子任务，并阻止任务完成，直到重新扫描不再报告这些问题。
+
+use codex_multi_agent::{project_management::SeverityLevel, AgentCapability, IssueSeverity, QualityCheckConfig};
+use regex::Regex;
+use sea_orm::EntityTrait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::OnceLock;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::{
+    dependency_audit::{create_remediation_task, MAX_DEPTH, SKIPPED_DIRS},
+    entities::{execution_session, security_finding, task},
+    repository::{
+        security_finding_repository::CreateSecurityFindingData, ExecutionSessionRepository,
+        OrganizationRepository, SecurityFindingRepository, TaskRepository,
+    },
+    static_analysis::{parse_severity, severity_level, severity_str},
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 密钥扫描跳过的大文件（字节）
+const MAX_SCANNED_FILE_BYTES: u64 = 1024 * 1024;
+
+/// 安全扫描器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityScanner {
+    /// cargo-audit（RustSec 公告）
+    CargoAudit,
+    /// npm audit（GitHub 公告）
+    NpmAudit,
+    /// 内置密钥扫描
+    Secrets,
+}
+
+impl SecurityScanner {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityScanner::CargoAudit => "cargo_audit",
+            SecurityScanner::NpmAudit => "npm_audit",
+            SecurityScanner::Secrets => "secrets",
+        }
+    }
+
+    /// 外部扫描器的命令，内置扫描器为空
+    pub fn command(&self) -> Option<&'static str> {
+        match self {
+            SecurityScanner::CargoAudit => Some("cargo audit --json"),
+            SecurityScanner::NpmAudit => Some("npm audit --json"),
+            SecurityScanner::Secrets => None,
+        }
+    }
+
+    /// 修复该扫描器发现的问题所需的能力
+    fn capabilities(&self) -> Vec<AgentCapability> {
+        match self {
+            SecurityScanner::CargoAudit => vec![AgentCapability::SecurityAudit, AgentCapability::BackendDevelopment],
+            SecurityScanner::NpmAudit => vec![AgentCapability::SecurityAudit, AgentCapability::FrontendDevelopment],
+            SecurityScanner::Secrets => vec![AgentCapability::SecurityAudit],
+        }
+    }
+}
+
+/// 安全问题类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityCategory {
+    /// 依赖中的已知漏洞
+    Vulnerability,
+    /// 提交到代码中的密钥
+    Secret,
+}
+
+impl SecurityCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityCategory::Vulnerability => "vulnerability",
+            SecurityCategory::Secret => "secret",
+        }
+    }
+}
+
+/// 统一格式的安全问题
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecurityIssue {
+    pub scanner: SecurityScanner,
+    pub category: SecurityCategory,
+    pub severity: IssueSeverity,
+    pub title: String,
+    /// 安全公告编号或密钥规则标识
+    pub advisory_id: Option<String>,
+    pub package_name: Option<String>,
+    pub package_version: Option<String>,
+    #[serde(default)]
+    pub fixed_versions: Vec<String>,
+    /// 相对工作空间的文件路径
+    pub file_path: Option<String>,
+    pub line: Option<u32>,
+}
+
+/// 安全公告的严重程度标签（critical、high、moderate、low）对应的问题严重程度
+pub fn advisory_severity(label: &str) -> IssueSeverity {
+    match label.to_ascii_lowercase().as_str() {
+        "critical" => IssueSeverity::Blocker,
+        "high" => IssueSeverity::Critical,
+        "moderate" | "medium" => IssueSeverity::Major,
+        "low" => IssueSeverity::Minor,
+        _ => IssueSeverity::Info,
+    }
+}
+
+/// 计算 CVSS v3 向量的基础分，向量无效时返回 None
+pub fn cvss_base_score(vector: &str) -> Option<f64> {
+    let metrics: HashMap<&str, &str> = vector
+        .split('/')
+        .filter_map(|part| part.split_once(':'))
+        .collect();
+    if !metrics.get("CVSS").is_some_and(|version| version.starts_with('3')) {
+        return None;
+    }
+    let changed = match *metrics.get("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+    let attack_vector = match *metrics.get("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let attack_complexity = match *metrics.get("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let privileges = match (*metrics.get("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let user_interaction = match *metrics.get("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let impact_of = |key: &str| match metrics.get(key).copied() {
+        Some("H") => Some(0.56),
+        Some("L") => Some(0.22),
+        Some("N") => Some(0.0),
+        _ => None,
+    };
+    let base_impact = 1.0 - (1.0 - impact_of("C")?) * (1.0 - impact_of("I")?) * (1.0 - impact_of("A")?);
+    let impact = if changed {
+        7.52 * (base_impact - 0.029) - 3.25 * (base_impact - 0.02f64).powi(15)
+    } else {
+        6.42 * base_impact
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+    let exploitability = 8.22 * attack_vector * attack_complexity * privileges * user_interaction;
+    let score = if changed {
+        (1.08 * (impact + exploitability)).min(10.0)
+    } else {
+        (impact + exploitability).min(10.0)
+    };
+    // CVSS 规定向上取整到一位小数
+    Some((score * 10.0 - 1e-9).ceil() / 10.0)
+}
+
+/// CVSS 基础分对应的严重程度标签
+fn cvss_label(score: f64) -> &'static str {
+    match score {
+        s if s >= 9.0 => "critical",
+        s if s >= 7.0 => "high",
+        s if s >= 4.0 => "moderate",
+        s if s > 0.0 => "low",
+        _ => "none",
+    }
+}
+
+/// 解析 `cargo audit --json` 的输出
+///
+/// 漏洞的严重程度按公告的 CVSS 向量计算，缺少向量时按 high 处理；unmaintained、unsound 等
+/// 带公告的警告记为 minor。
+pub fn parse_cargo_audit(output: &str) -> Result<Vec<SecurityIssue>> {
+    let report: JsonValue = serde_json::from_str(output.trim())?;
+    let mut issues = Vec::new();
+
+    let mut push = |entry: &JsonValue, severity: IssueSeverity| {
+        let advisory = &entry["advisory"];
+        let package = &entry["package"];
+        issues.push(SecurityIssue {
+            scanner: SecurityScanner::CargoAudit,
+            category: SecurityCategory::Vulnerability,
+            severity,
+            title: advisory["title"].as_str().unwrap_or("未命名公告").to_string(),
+            advisory_id: advisory["id"].as_str().map(str::to_string),
+            package_name: package["name"].as_str().map(str::to_string),
+            package_version: package["version"].as_str().map(str::to_string),
+            fixed_versions: entry["versions"]["patched"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|version| version.as_str().map(str::to_string))
+                .collect(),
+            file_path: Some("Cargo.lock".to_string()),
+            line: None,
+        });
+    };
+
+    for entry in report["vulnerabilities"]["list"].as_array().into_iter().flatten() {
+        let label = entry["advisory"]["cvss"]
+            .as_str()
+            .and_then(cvss_base_score)
+            .map(cvss_label)
+            .unwrap_or("high");
+        push(entry, advisory_severity(label));
+    }
+    for warnings in report["warnings"].as_object().into_iter().flat_map(|warnings| warnings.values()) {
+        for entry in warnings.as_array().into_iter().flatten() {
+            if entry["advisory"].is_object() {
+                push(entry, IssueSeverity::Minor);
+            }
+        }
+    }
+    Ok(issues)
+}
+
+/// 解析 `npm audit --json`（npm 7 及以上）的输出
+///
+/// 只记录直接关联公告的包，仅因依赖了其他存在漏洞的包而受影响的条目不重复记录。
+pub fn parse_npm_audit(output: &str) -> Result<Vec<SecurityIssue>> {
+    let report: JsonValue = serde_json::from_str(output.trim())?;
+    if let Some(error) = report.get("error") {
+        return Err(DatabaseError::business_logic(format!(
+            "npm audit 运行失败: {}",
+            error["summary"].as_str().unwrap_or("未知错误")
+        )));
+    }
+
+    let mut issues = Vec::new();
+    for (name, entry) in report["vulnerabilities"].as_object().into_iter().flatten() {
+        let fixed_versions: Vec<String> = entry["fixAvailable"]["version"]
+            .as_str()
+            .map(|version| vec![version.to_string()])
+            .unwrap_or_default();
+        for advisory in entry["via"].as_array().into_iter().flatten().filter(|via| via.is_object()) {
+            let advisory_id = advisory["url"]
+                .as_str()
+                .and_then(|url| url.rsplit('/').next())
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .or_else(|| advisory["source"].as_u64().map(|source| source.to_string()));
+            let label = advisory["severity"].as_str().or(entry["severity"].as_str()).unwrap_or("info");
+            issues.push(SecurityIssue {
+                scanner: SecurityScanner::NpmAudit,
+                category: SecurityCategory::Vulnerability,
+                severity: advisory_severity(label),
+                title: advisory["title"].as_str().unwrap_or("未命名公告").to_string(),
+                advisory_id,
+                package_name: Some(advisory["name"].as_str().unwrap_or(name).to_string()),
+                package_version: advisory["range"].as_str().map(str::to_string),
+                fixed_versions: fixed_versions.clone(),
+                file_path: Some("package-lock.json".to_string()),
+                line: None,
+            });
+        }
+    }
+    Ok(issues)
+}
+
+/// 密钥规则：标识、描述、正则表达式、严重程度
+fn secret_rules() -> &'static [(&'static str, &'static str, Regex, IssueSeverity)] {
+    static RULES: OnceLock<Vec<(&'static str, &'static str, Regex, IssueSeverity)>> = OnceLock::new();
+    RULES.get_or_init(|| {
+        [
+            (
+                "private_key",
+                "私钥",
+                r"-----BEGIN (?:RSA |EC |DSA |OPENSSH |PGP |ENCRYPTED )?PRIVATE KEY(?: BLOCK)?-----",
+                IssueSeverity::Blocker,
+            ),
+            ("aws_access_key", "AWS 访问密钥", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b", IssueSeverity::Blocker),
+            ("github_token", "GitHub 令牌", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b", IssueSeverity::Blocker),
+            ("slack_token", "Slack 令牌", r"\bxox[abprs]-[A-Za-z0-9-]{10,}", IssueSeverity::Blocker),
+            ("llm_api_key", "LLM 服务 API 密钥", r"\bsk-(?:ant-|proj-)?[A-Za-z0-9_-]{32,}", IssueSeverity::Blocker),
+            (
+                "hardcoded_secret",
+                "硬编码的密码或令牌",
+                r#"(?i)\b(?:api[_-]?key|secret|password|passwd|access[_-]?token|auth[_-]?token)\b["']?\s*[:=]\s*["']([^"'\s]{12,})["']"#,
+                IssueSeverity::Major,
+            ),
+        ]
+        .into_iter()
+        .map(|(id, description, pattern, severity)| {
+            (id, description, Regex::new(pattern).expect("有效的正则表达式"), severity)
+        })
+        .collect()
+    })
+}
+
+/// 明显是占位符的值不视为密钥
+fn is_placeholder(value: &str) -> bool {
+    let lower = value.to_ascii_lowercase();
+    ["example", "placeholder", "changeme", "your_", "your-", "xxxx", "${", "{{", "<"]
+        .iter()
+        .any(|marker| lower.contains(marker))
+}
+
+/// 把文本中的密钥替换为 `[REDACTED:<规则标识>]`，返回替换后的文本和替换数量
+///
+/// 规则与 [`scan_text_for_secrets`] 相同，私钥整块替换，赋值形式只替换值，占位符保留
+pub fn redact_secrets(text: &str) -> (String, usize) {
+    static PRIVATE_KEY_BLOCK: OnceLock<Regex> = OnceLock::new();
+    let block = PRIVATE_KEY_BLOCK.get_or_init(|| {
+        Regex::new(r"(?s)-----BEGIN [A-Z ]*PRIVATE KEY(?: BLOCK)?-----.*?(?:-----END [A-Z ]*PRIVATE KEY(?: BLOCK)?-----|$)")
+            .expect("有效的正则表达式")
+    });
+    let mut count = block.find_iter(text).count();
+    let mut redacted = block.replace_all(text, "[REDACTED:private_key]").into_owned();
+
+    for (id, _, regex, _) in secret_rules() {
+        redacted = regex
+            .replace_all(&redacted, |captures: &regex::Captures| {
+                let whole = captures.get(0).expect("整个匹配");
+                let secret = captures.get(1).unwrap_or(whole);
+                if is_placeholder(secret.as_str()) {
+                    return whole.as_str().to_string();
+                }
+                count += 1;
+                format!(
+                    "{}[REDACTED:{}]{}",
+                    &whole.as_str()[..secret.start() - whole.start()],
+                    id,
+                    &whole.as_str()[secret.end() - whole.start()..]
+                )
+            })
+            .into_owned();
+    }
+    (redacted, count)
+}
+
+/// 扫描文本内容中的密钥，`file_path` 为相对工作空间的路径
+pub fn scan_text_for_secrets(file_path: &str, content: &str) -> Vec<SecurityIssue> {
+    let mut issues = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        for (id, description, regex, severity) in secret_rules() {
+            let Some(captures) = regex.captures(line) else {
+                continue;
+            };
+            let value = captures.get(1).or_else(|| captures.get(0)).map(|m| m.as_str()).unwrap_or_default();
+            if is_placeholder(value) {
+                continue;
+            }
+            issues.push(SecurityIssue {
+                scanner: SecurityScanner::Secrets,
+                category: SecurityCategory::Secret,
+                severity: severity.clone(),
+                title: format!("{}中疑似包含{}", file_path, description),
+                advisory_id: Some(id.to_string()),
+                package_name: None,
+                package_version: None,
+                fixed_versions: Vec::new(),
+                file_path: Some(file_path.to_string()),
+                line: Some(index as u32 + 1),
+            });
+        }
+    }
+    issues
+}
+
+/// 扫描工作空间中的密钥，跳过依赖目录、隐藏目录、大文件和非 UTF-8 文件
+pub fn scan_secrets(root: &Path) -> Result<Vec<SecurityIssue>> {
+    let mut files = Vec::new();
+    collect_files(root, 0, &mut files)?;
+    files.sort();
+
+    let mut issues = Vec::new();
+    for file in files {
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let relative = file.strip_prefix(root).unwrap_or(&file).to_string_lossy().replace('\\', "/");
+        issues.extend(scan_text_for_secrets(&relative, &content));
+    }
+    Ok(issues)
+}
+
+fn collect_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) -> Result<()> {
+    if depth > MAX_DEPTH {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_str()) && !name.starts_with('.') {
+                collect_files(&entry.path(), depth + 1, files)?;
+            }
+        } else if file_type.is_file() && entry.metadata()?.len() <= MAX_SCANNED_FILE_BYTES {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// 扫描器运行的异步结果（标准输出）
+pub type ScanOutputFuture<'a> = Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>>;
+
+/// 外部安全扫描器运行器
+pub trait ScanRunner: Send + Sync {
+    /// 在工作空间中运行扫描器，返回标准输出
+    fn run<'a>(&'a self, scanner: SecurityScanner, workspace: &'a Path) -> ScanOutputFuture<'a>;
+}
+
+/// 以子进程方式运行扫描器
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CommandScanRunner;
+
+impl ScanRunner for CommandScanRunner {
+    fn run<'a>(&'a self, scanner: SecurityScanner, workspace: &'a Path) -> ScanOutputFuture<'a> {
+        Box::pin(async move {
+            let command = scanner
+                .command()
+                .ok_or_else(|| DatabaseError::validation(format!("{} 不是外部扫描器", scanner.as_str())))?;
+            let mut parts = command.split_whitespace();
+            let program = parts.next().unwrap_or_default();
+            let output = Command::new(program).args(parts).current_dir(workspace).output().await?;
+            let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+            // 发现漏洞时扫描器以非零状态退出，只有没有输出时才视为运行失败
+            if !output.status.success() && stdout.trim().is_empty() {
+                return Err(DatabaseError::business_logic(format!(
+                    "{} 运行失败: {}",
+                    scanner.as_str(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            Ok(stdout)
+        })
+    }
+}
+
+/// 工作空间需要运行的扫描器
+pub fn applicable_scanners(workspace: &Path) -> Vec<SecurityScanner> {
+    let mut scanners = Vec::new();
+    if workspace.join("Cargo.lock").is_file() {
+        scanners.push(SecurityScanner::CargoAudit);
+    }
+    if workspace.join("package-lock.json").is_file() {
+        scanners.push(SecurityScanner::NpmAudit);
+    }
+    scanners.push(SecurityScanner::Secrets);
+    scanners
+}
+
+/// 单个扫描器的运行情况
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanRun {
+    pub scanner: SecurityScanner,
+    pub finding_count: usize,
+    /// 运行或解析失败的原因，失败时保留该扫描器之前的发现
+    pub error: Option<String>,
+}
+
+/// 执行会话的安全扫描报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecurityScanReport {
+    pub session_id: Uuid,
+    /// 执行配置是否启用了安全检查，未启用时不运行扫描器
+    pub enabled: bool,
+    pub runs: Vec<ScanRun>,
+    pub findings: Vec<security_finding::Model>,
+    /// 达到阻塞级别的发现数量
+    pub blocking_count: usize,
+    /// 本次扫描关联的修复任务
+    pub remediation_task_ids: Vec<Uuid>,
+}
+
+/// 执行会话是否启用了安全检查，执行配置未指定质量检查时默认启用
+pub fn security_check_enabled(session: &execution_session::Model) -> bool {
+    session
+        .execution_config
+        .as_ref()
+        .and_then(|config| config.get("quality_checks"))
+        .and_then(|checks| serde_json::from_value::<QualityCheckConfig>(checks.clone()).ok())
+        .is_none_or(|checks| checks.enable_security_check)
+}
+
+fn level_rank(level: &SeverityLevel) -> u8 {
+    match level {
+        SeverityLevel::Info => 0,
+        SeverityLevel::Warning => 1,
+        SeverityLevel::Error => 2,
+        SeverityLevel::Blocker => 3,
+    }
+}
+
+/// 问题是否达到阻塞级别
+pub fn is_blocking(severity: &IssueSeverity, threshold: &SeverityLevel) -> bool {
+    level_rank(&severity_level(severity)) >= level_rank(threshold)
+}
+
+/// 项目的安全阻塞级别
+async fn blocking_threshold(db: &DatabaseConnection, project_id: Uuid) -> Result<SeverityLevel> {
+    Ok(OrganizationRepository::new(db.clone())
+        .resolve_project_standards(project_id)
+        .await?
+        .quality_gates
+        .security_blocking_severity)
+}
+
+/// 在工作空间中运行安全扫描并保存发现，为阻塞级别的问题创建修复子任务
+pub async fn run_security_scan(
+    db: &DatabaseConnection,
+    session_id: Uuid,
+    workspace: &Path,
+    runner: &dyn ScanRunner,
+) -> Result<SecurityScanReport> {
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    let repository = SecurityFindingRepository::new(db.clone());
+    if !security_check_enabled(&session) {
+        return Ok(SecurityScanReport {
+            session_id,
+            enabled: false,
+            runs: Vec::new(),
+            findings: repository.find_by_session(session_id).await?,
+            blocking_count: 0,
+            remediation_task_ids: Vec::new(),
+        });
+    }
+
+    let mut runs = Vec::new();
+    let mut scanned = Vec::new();
+    for scanner in applicable_scanners(workspace) {
+        let result = match scanner {
+            SecurityScanner::Secrets => {
+                let root = workspace.to_path_buf();
+                tokio::task::spawn_blocking(move || scan_secrets(&root))
+                    .await
+                    .map_err(|e| DatabaseError::business_logic(format!("密钥扫描任务异常退出: {}", e)))
+                    .and_then(|result| result)
+            }
+            SecurityScanner::CargoAudit => runner.run(scanner, workspace).await.and_then(|out| parse_cargo_audit(&out)),
+            SecurityScanner::NpmAudit => runner.run(scanner, workspace).await.and_then(|out| parse_npm_audit(&out)),
+        };
+        match result {
+            Ok(issues) => {
+                runs.push(ScanRun {
+                    scanner,
+                    finding_count: issues.len(),
+                    error: None,
+                });
+                scanned.push((scanner, issues));
+            }
+            Err(e) => {
+                tracing::warn!(session_id = %session_id, scanner = scanner.as_str(), error = %e, "安全扫描器运行失败");
+                runs.push(ScanRun {
+                    scanner,
+                    finding_count: 0,
+                    error: Some(e.to_string()),
+                });
+            }
+        }
+    }
+
+    let threshold = blocking_threshold(db, session.project_id).await?;
+    let parent_task = task::Entity::find_by_id(session.task_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Task", session.task_id))?;
+    let blocking: Vec<&SecurityIssue> = scanned
+        .iter()
+        .flat_map(|(_, issues)| issues.iter())
+        .filter(|issue| is_blocking(&issue.severity, &threshold))
+        .collect();
+    let remediation = create_remediation_tasks(db, &parent_task, &blocking).await?;
+
+    for (scanner, issues) in scanned {
+        repository.delete_by_session_and_scanner(session_id, scanner.as_str()).await?;
+        let data = issues
+            .into_iter()
+            .map(|issue| CreateSecurityFindingData {
+                project_id: session.project_id,
+                execution_session_id: session_id,
+                scanner: scanner.as_str().to_string(),
+                category: issue.category.as_str().to_string(),
+                severity: severity_str(&issue.severity).to_string(),
+                remediation_task_id: remediation.get(&remediation_key(&issue)).copied(),
+                title: issue.title,
+                advisory_id: issue.advisory_id,
+                package_name: issue.package_name,
+                package_version: issue.package_version,
+                fixed_versions: json!(issue.fixed_versions),
+                file_path: issue.file_path,
+                line: issue.line.map(|line| line as i32),
+            })
+            .collect();
+        repository.create_many(data).await?;
+    }
+
+    let findings = repository.find_by_session(session_id).await?;
+    let blocking_count = findings
+        .iter()
+        .filter(|finding| is_blocking(&parse_severity(&finding.severity), &threshold))
+        .count();
+    let remediation_task_ids: Vec<Uuid> = remediation.values().copied().collect::<HashSet<_>>().into_iter().collect();
+    Ok(SecurityScanReport {
+        session_id,
+        enabled: true,
+        runs,
+        findings,
+        blocking_count,
+        remediation_task_ids,
+    })
+}
+
+/// 修复任务的分组：漏洞按扫描器和依赖包，密钥合并为一个任务
+fn remediation_key(issue: &SecurityIssue) -> (SecurityScanner, Option<String>) {
+    match issue.category {
+        SecurityCategory::Vulnerability => (issue.scanner, issue.package_name.clone()),
+        SecurityCategory::Secret => (issue.scanner, None),
+    }
+}
+
+/// 为阻塞级别的问题创建修复子任务，已存在同名子任务时复用
+async fn create_remediation_tasks(
+    db: &DatabaseConnection,
+    parent_task: &task::Model,
+    issues: &[&SecurityIssue],
+) -> Result<HashMap<(SecurityScanner, Option<String>), Uuid>> {
+    let mut groups: BTreeMap<(SecurityScanner, Option<String>), Vec<&SecurityIssue>> = BTreeMap::new();
+    for issue in issues {
+        groups.entry(remediation_key(issue)).or_default().push(issue);
+    }
+    if groups.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let tasks = TaskRepository::new(db.clone());
+    let existing: HashMap<String, Uuid> = tasks
+        .find_subtasks(parent_task.task_id)
+        .await?
+        .into_iter()
+        .map(|task| (task.title, task.task_id))
+        .collect();
+
+    let mut created = HashMap::new();
+    for (key, issues) in groups {
+        let (scanner, package) = &key;
+        let (title, mut description, criteria) = match package {
+            Some(package) => (
+                format!("修复依赖 {} 的安全漏洞", package),
+                format!("{} 报告依赖 {} 存在以下漏洞：\n", scanner.as_str(), package),
+                vec![
+                    format!("{} 升级到不受上述漏洞影响的版本", package),
+                    "安全扫描不再报告这些漏洞".to_string(),
+                    "现有测试全部通过".to_string(),
+                ],
+            ),
+            None => (
+                "移除代码中泄露的密钥".to_string(),
+                "密钥扫描在以下位置发现疑似密钥，请移除并轮换对应的凭据：\n".to_string(),
+                vec!["代码和配置中不再包含密钥".to_string(), "已轮换泄露的凭据".to_string()],
+            ),
+        };
+        for issue in &issues {
+            let location = match (&issue.file_path, issue.line) {
+                (Some(file), Some(line)) => format!("{}:{}", file, line),
+                (Some(file), None) => file.clone(),
+                _ => "未知位置".to_string(),
+            };
+            description.push_str(&format!(
+                "- {}（{}，{}）：{}\n",
+                issue.advisory_id.as_deref().unwrap_or("未知规则"),
+                severity_str(&issue.severity),
+                location,
+                issue.title
+            ));
+            if !issue.fixed_versions.is_empty() {
+                description.push_str(&format!("  修复版本：{}\n", issue.fixed_versions.join(", ")));
+            }
+        }
+
+        let task_id = match existing.get(&title) {
+            Some(task_id) => *task_id,
+            None => {
+                create_remediation_task(&tasks, parent_task, title, description, "high", scanner.capabilities(), criteria)
+                    .await?
+            }
+        };
+        created.insert(key, task_id);
+    }
+    Ok(created)
+}
+
+/// 安全扫描门禁结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecurityGateResult {
+    pub session_id: Uuid,
+    pub threshold: SeverityLevel,
+    pub passed: bool,
+    pub blocking: Vec<security_finding::Model>,
+}
+
+/// 评估执行会话的安全扫描门禁，执行配置关闭了安全检查时返回 None
+pub async fn evaluate_security_gate(db: &DatabaseConnection, session_id: Uuid) -> Result<Option<SecurityGateResult>> {
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    if !security_check_enabled(&session) {
+        return Ok(None);
+    }
+
+    let threshold = blocking_threshold(db, session.project_id).await?;
+    let blocking: Vec<_> = SecurityFindingRepository::new(db.clone())
+        .find_by_session(session_id)
+        .await?
+        .into_iter()
+        .filter(|finding| is_blocking(&parse_severity(&finding.severity), &threshold))
+        .collect();
+    Ok(Some(SecurityGateResult {
+        session_id,
+        threshold,
+        passed: blocking.is_empty(),
+        blocking,
+    }))
+}
+
+/// 检查任务能否完成：任务最近一次执行会话的安全扫描存在阻塞级别的问题时不能完成
+pub async fn ensure_task_can_complete(db: &DatabaseConnection, task: &task::Model) -> Result<()> {
+    let Some(session) = ExecutionSessionRepository::new(db.clone())
+        .find_by_task_id(task.task_id)
+        .await?
+        .into_iter()
+        .next()
+    else {
+        return Ok(());
+    };
+
+    match evaluate_security_gate(db, session.session_id).await? {
+        Some(gate) if !gate.passed => {
+            let titles: Vec<&str> = gate.blocking.iter().take(3).map(|finding| finding.title.as_str()).collect();
+            Err(DatabaseError::business_logic(format!(
+                "任务 {} 的安全扫描存在 {} 个阻塞问题（{}），请先完成修复任务并重新扫描",
+                task.task_id,
+                gate.blocking.len(),
+                titles.join("；")
+            )))
+        }
+        _ => Ok(()),
+    }
+}
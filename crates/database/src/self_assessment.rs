@@ -0,0 +1,218 @@
+//! Agent自我评估与置信度校准
+//!
+//! Agent完成任务时在 [`TaskResult::self_assessment`](codex_multi_agent::TaskResult) 中上报置信度和自评，
+//! [`SelfAssessmentService::record`] 把它与执行会话一起保存。审查提交决策后，自评记录会带上审查结果，
+//! [`AgentCalibration`] 据此比较Agent历史上报的置信度与实际审查结果：
+//! - 校准后的置信度 = 上报置信度 − 权重 × 历史偏差，权重随样本数增长，样本少时以上报值为主；
+//! - 校准后的置信度低于 [`ConfidenceCalibrationConfig::escalation_threshold`] 的结果标记为需要人工审查；
+//! - [`SelfAssessmentService::calibrate_simulation_agents`] 把Agent整体的校准置信度交给调度模拟，
+//!   用于调整任务分配的置信度。
+
+use codex_multi_agent::{SelfAssessment, SimulationAgent};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    entities::{agent_self_assessment, code_review::ReviewDecision},
+    repository::{
+        agent_self_assessment_repository::CreateSelfAssessmentData, AgentSelfAssessmentRepository,
+        ExecutionSessionRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 默认的先验样本数：审查结果达到该数量时，历史偏差的权重为一半
+pub const DEFAULT_PRIOR_SAMPLES: f64 = 5.0;
+
+/// 默认的人工审查阈值
+pub const DEFAULT_ESCALATION_THRESHOLD: f64 = 0.5;
+
+/// 默认参与校准的最近审查结果数
+pub const DEFAULT_CALIBRATION_WINDOW: usize = 50;
+
+/// 置信度校准配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceCalibrationConfig {
+    /// 先验样本数，越大越信任Agent上报的置信度
+    pub prior_samples: f64,
+    /// 校准后的置信度低于该值时需要人工审查
+    pub escalation_threshold: f64,
+    /// 参与校准的最近审查结果数
+    pub window: usize,
+}
+
+impl Default for ConfidenceCalibrationConfig {
+    fn default() -> Self {
+        Self {
+            prior_samples: DEFAULT_PRIOR_SAMPLES,
+            escalation_threshold: DEFAULT_ESCALATION_THRESHOLD,
+            window: DEFAULT_CALIBRATION_WINDOW,
+        }
+    }
+}
+
+/// 审查决策对应的结果得分：批准为 1，需要修改为 0.5，拒绝为 0
+pub fn review_outcome_score(decision: &ReviewDecision) -> f64 {
+    match decision {
+        ReviewDecision::Approved => 1.0,
+        ReviewDecision::ChangesRequested => 0.5,
+        ReviewDecision::Rejected => 0.0,
+    }
+}
+
+/// Agent的置信度校准结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentCalibration {
+    pub agent_id: Uuid,
+    /// 有审查结果的自评数量
+    pub samples: usize,
+    /// 平均上报置信度
+    pub mean_confidence: f64,
+    /// 平均审查结果得分
+    pub mean_outcome: f64,
+    /// 过度自信程度：平均上报置信度 − 平均审查结果得分，为负表示低估自己
+    pub bias: f64,
+    /// 平均绝对误差：|上报置信度 − 审查结果得分|
+    pub calibration_error: f64,
+    /// 历史偏差的权重（0.0-1.0）
+    pub weight: f64,
+}
+
+impl AgentCalibration {
+    /// 由有审查结果的自评计算校准结果，审查决策无法识别的记录被忽略
+    pub fn from_assessments(agent_id: Uuid, assessments: &[agent_self_assessment::Model], prior_samples: f64) -> Self {
+        let pairs: Vec<(f64, f64)> = assessments
+            .iter()
+            .filter_map(|assessment| {
+                let decision = match assessment.review_decision.as_deref()? {
+                    "approved" => ReviewDecision::Approved,
+                    "changes_requested" => ReviewDecision::ChangesRequested,
+                    "rejected" => ReviewDecision::Rejected,
+                    _ => return None,
+                };
+                Some((assessment.confidence, review_outcome_score(&decision)))
+            })
+            .collect();
+
+        let samples = pairs.len();
+        if samples == 0 {
+            return Self {
+                agent_id,
+                samples,
+                mean_confidence: 0.0,
+                mean_outcome: 0.0,
+                bias: 0.0,
+                calibration_error: 0.0,
+                weight: 0.0,
+            };
+        }
+        let n = samples as f64;
+        let mean_confidence = pairs.iter().map(|(confidence, _)| confidence).sum::<f64>() / n;
+        let mean_outcome = pairs.iter().map(|(_, outcome)| outcome).sum::<f64>() / n;
+        let calibration_error = pairs.iter().map(|(confidence, outcome)| (confidence - outcome).abs()).sum::<f64>() / n;
+        Self {
+            agent_id,
+            samples,
+            mean_confidence,
+            mean_outcome,
+            bias: mean_confidence - mean_outcome,
+            calibration_error,
+            weight: n / (n + prior_samples.max(0.0)),
+        }
+    }
+
+    /// 校准一次上报的置信度
+    pub fn calibrate(&self, reported: f64) -> f64 {
+        (reported - self.weight * self.bias).clamp(0.0, 1.0)
+    }
+
+    /// Agent整体的校准置信度，用于任务分配；没有审查结果时为 `None`
+    pub fn assignment_confidence(&self) -> Option<f32> {
+        (self.samples > 0).then(|| self.calibrate(self.mean_confidence) as f32)
+    }
+}
+
+/// 自我评估服务
+pub struct SelfAssessmentService {
+    db: DatabaseConnection,
+    repository: AgentSelfAssessmentRepository,
+    config: ConfidenceCalibrationConfig,
+}
+
+impl SelfAssessmentService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            repository: AgentSelfAssessmentRepository::new(db.clone()),
+            db,
+            config: ConfidenceCalibrationConfig::default(),
+        }
+    }
+
+    /// 设置校准配置
+    pub fn with_config(mut self, config: ConfidenceCalibrationConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 记录执行会话的自我评估，校准置信度并判断是否需要人工审查
+    ///
+    /// 置信度必须在 0.0-1.0 之间，每个会话只能记录一次
+    pub async fn record(&self, session_id: Uuid, assessment: &SelfAssessment) -> Result<agent_self_assessment::Model> {
+        if !assessment.confidence.is_finite() || !(0.0..=1.0).contains(&assessment.confidence) {
+            return Err(DatabaseError::validation("置信度必须在 0.0 到 1.0 之间"));
+        }
+        let session = ExecutionSessionRepository::new(self.db.clone())
+            .find_by_id(session_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+        if self.repository.find_by_session_id(session_id).await?.is_some() {
+            return Err(DatabaseError::conflict(format!("执行会话 {} 已经记录过自我评估", session_id)));
+        }
+
+        let confidence = assessment.confidence as f64;
+        let calibrated_confidence = self.calibration(session.agent_id).await?.calibrate(confidence);
+        let escalated = calibrated_confidence < self.config.escalation_threshold;
+        if escalated {
+            tracing::warn!(
+                "任务 {} 的执行结果校准后置信度为 {:.2}（上报 {:.2}），需要人工审查",
+                session.task_id,
+                calibrated_confidence,
+                confidence
+            );
+        }
+
+        self.repository
+            .create(CreateSelfAssessmentData {
+                session_id,
+                task_id: session.task_id,
+                agent_id: session.agent_id,
+                confidence,
+                summary: assessment.summary.clone(),
+                concerns: json!(assessment.concerns),
+                calibrated_confidence,
+                escalated,
+            })
+            .await
+    }
+
+    /// 按最近的审查结果计算Agent的置信度校准
+    pub async fn calibration(&self, agent_id: Uuid) -> Result<AgentCalibration> {
+        let mut reviewed = self.repository.find_reviewed_by_agent(agent_id).await?;
+        reviewed.truncate(self.config.window);
+        Ok(AgentCalibration::from_assessments(agent_id, &reviewed, self.config.prior_samples))
+    }
+
+    /// 为调度模拟中的Agent填入校准置信度
+    pub async fn calibrate_simulation_agents(&self, agents: &mut [SimulationAgent]) -> Result<()> {
+        for agent in agents.iter_mut() {
+            agent.calibrated_confidence = self.calibration(agent.agent_id.0).await?.assignment_confidence();
+        }
+        Ok(())
+    }
+
+    /// 等待人工审查的低置信度结果
+    pub async fn pending_escalations(&self) -> Result<Vec<agent_self_assessment::Model>> {
+        self.repository.find_pending_escalations().await
+    }
+}
@@ -0,0 +1,261 @@
+//! 执行会话检查点与恢复
+//!
+//! 长任务被中断（应用关闭、抢占、超时）后不必从头开始。Agent 在执行过程中调用
+//! [`save_checkpoint`] 把已完成的步骤、工作笔记和未提交的差异写入执行会话的
+//! `checkpoint` 字段，每次保存同时记录一条检查点进度日志。
+//!
+//! [`resume_session`] 为已中断的会话创建新的执行会话，沿用原工作分支并复制检查点，
+//! 再由 [`build_resume_prompt`] 根据检查点重建提示词上下文，作为任务的分配提示词。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        execution_log::EventType,
+        execution_session::{self, ExecutionStatus},
+        task,
+    },
+    repository::{
+        execution_log_repository::CreateExecutionLogData, execution_session_repository::CreateSessionData,
+        ExecutionLogRepository, ExecutionSessionRepository, TaskRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 检查点中未提交差异的最大字节数
+pub const MAX_PARTIAL_DIFF_BYTES: usize = 512 * 1024;
+
+/// Agent 上报的中间状态
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointData {
+    /// 已完成的步骤
+    #[serde(default)]
+    pub completed_steps: Vec<String>,
+    /// 中断时正在进行的步骤
+    #[serde(default)]
+    pub current_step: Option<String>,
+    /// 工作笔记，例如已排除的方案、待确认的问题
+    #[serde(default)]
+    pub scratch_notes: Vec<String>,
+    /// 尚未提交的差异（git diff 输出）
+    #[serde(default)]
+    pub partial_diff: Option<String>,
+    /// 工作分支上最近一次提交
+    #[serde(default)]
+    pub last_commit: Option<String>,
+    /// 完成度（0.0-1.0）
+    #[serde(default)]
+    pub completion_percentage: Option<f32>,
+}
+
+impl CheckpointData {
+    /// 校验检查点数据
+    pub fn validate(&self) -> Result<()> {
+        if let Some(percentage) = self.completion_percentage {
+            if !(0.0..=1.0).contains(&percentage) {
+                return Err(DatabaseError::validation("完成度必须在0到1之间"));
+            }
+        }
+        if self.partial_diff.as_ref().is_some_and(|diff| diff.len() > MAX_PARTIAL_DIFF_BYTES) {
+            return Err(DatabaseError::validation(format!(
+                "未提交的差异超过 {} 字节，请先提交部分工作",
+                MAX_PARTIAL_DIFF_BYTES
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 保存在执行会话上的检查点
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionCheckpoint {
+    /// 检查点序号，同一任务恢复后的会话继续递增
+    pub sequence: u32,
+    /// 保存时间
+    pub saved_at: DateTime<Utc>,
+    /// 中间状态
+    #[serde(flatten)]
+    pub data: CheckpointData,
+}
+
+/// 恢复结果
+#[derive(Debug, Clone)]
+pub struct ResumedSession {
+    /// 新创建的执行会话
+    pub session: execution_session::Model,
+    /// 恢复所依据的检查点
+    pub checkpoint: SessionCheckpoint,
+    /// 根据检查点重建的提示词
+    pub prompt: String,
+}
+
+/// 保存检查点，只有运行中的会话可以保存
+pub async fn save_checkpoint(
+    db: &DatabaseConnection,
+    session_id: Uuid,
+    data: CheckpointData,
+) -> Result<SessionCheckpoint> {
+    data.validate()?;
+
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let session = sessions
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    if session.status != ExecutionStatus::Running.to_string() {
+        return Err(DatabaseError::validation("只有运行中的执行会话可以保存检查点"));
+    }
+
+    let sequence = parse_checkpoint(&session)?.map(|previous| previous.sequence + 1).unwrap_or(1);
+    let checkpoint = SessionCheckpoint { sequence, saved_at: Utc::now(), data };
+    sessions
+        .update_checkpoint(session_id, Some(serde_json::to_value(&checkpoint)?))
+        .await?;
+
+    ExecutionLogRepository::new(db.clone())
+        .create(CreateExecutionLogData {
+            session_id,
+            log_level: "info".to_string(),
+            event_type: EventType::Progress,
+            message: format!("保存检查点 #{}", sequence),
+            details: Some(json!({
+                "checkpoint": true,
+                "reason": "agent",
+                "sequence": sequence,
+                "completed_steps": checkpoint.data.completed_steps.len(),
+                "completion_percentage": checkpoint.data.completion_percentage,
+                "git_branch": session.git_branch,
+                "last_commit": checkpoint.data.last_commit,
+            })),
+            timestamp_ms: checkpoint.saved_at.timestamp_millis(),
+        })
+        .await?;
+
+    Ok(checkpoint)
+}
+
+/// 获取执行会话最近一次检查点
+pub async fn latest_checkpoint(db: &DatabaseConnection, session_id: Uuid) -> Result<Option<SessionCheckpoint>> {
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    parse_checkpoint(&session)
+}
+
+/// 从检查点恢复已中断的执行会话
+///
+/// 新会话沿用原工作分支，以检查点的最近提交为基准，并复制检查点以便继续递增序号；
+/// 任务重新分配给指定Agent（默认原Agent），分配提示词替换为根据检查点重建的提示词。
+pub async fn resume_session(
+    db: &DatabaseConnection,
+    session_id: Uuid,
+    agent_id: Option<Uuid>,
+) -> Result<ResumedSession> {
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let interrupted = sessions
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    let finished = interrupted.status != ExecutionStatus::Pending.to_string()
+        && interrupted.status != ExecutionStatus::Running.to_string();
+    if !finished || interrupted.success == Some(true) {
+        return Err(DatabaseError::validation("只能恢复已中断的执行会话"));
+    }
+    let checkpoint = parse_checkpoint(&interrupted)?
+        .ok_or_else(|| DatabaseError::validation("执行会话没有可恢复的检查点"))?;
+
+    let tasks = TaskRepository::new(db.clone());
+    let task = tasks
+        .find_by_id(interrupted.task_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Task", interrupted.task_id))?;
+    if matches!(task.status.as_str(), "completed" | "cancelled") {
+        return Err(DatabaseError::business_logic(format!("任务已{}，无需恢复", task.status)));
+    }
+
+    let agent_id = agent_id.unwrap_or(interrupted.agent_id);
+    let base_commit = checkpoint
+        .data
+        .last_commit
+        .clone()
+        .or_else(|| interrupted.final_commit.clone())
+        .or_else(|| interrupted.base_commit.clone());
+    let session = sessions
+        .create(CreateSessionData {
+            task_id: task.task_id,
+            agent_id,
+            project_id: interrupted.project_id,
+            git_branch: interrupted.git_branch.clone(),
+            base_commit,
+            execution_config: Some(json!({
+                "resumed_from": session_id,
+                "checkpoint_sequence": checkpoint.sequence,
+            })),
+            timeout_minutes: interrupted.timeout_minutes,
+        })
+        .await?;
+    let session = sessions
+        .update_checkpoint(session.session_id, interrupted.checkpoint.clone())
+        .await?;
+
+    let prompt = build_resume_prompt(&task, &checkpoint);
+    tasks.assign_to_agent(task.task_id, agent_id, prompt.clone()).await?;
+
+    tracing::info!(
+        "执行会话 {} 从检查点 #{} 恢复为 {}",
+        session_id,
+        checkpoint.sequence,
+        session.session_id
+    );
+    Ok(ResumedSession { session, checkpoint, prompt })
+}
+
+/// 根据检查点重建任务提示词
+pub fn build_resume_prompt(task: &task::Model, checkpoint: &SessionCheckpoint) -> String {
+    let data = &checkpoint.data;
+    let mut prompt = format!("任务: {}\n\n{}\n\n", task.title, task.description);
+    prompt.push_str(&format!(
+        "该任务此前的执行在检查点 #{} 中断，请从检查点继续，不要重复已完成的步骤。\n",
+        checkpoint.sequence
+    ));
+    if let Some(percentage) = data.completion_percentage {
+        prompt.push_str(&format!("中断时完成度: {:.0}%\n", percentage * 100.0));
+    }
+
+    if !data.completed_steps.is_empty() {
+        prompt.push_str("\n已完成的步骤:\n");
+        for (index, step) in data.completed_steps.iter().enumerate() {
+            prompt.push_str(&format!("{}. {}\n", index + 1, step));
+        }
+    }
+    if let Some(step) = &data.current_step {
+        prompt.push_str(&format!("\n中断时正在进行: {}\n", step));
+    }
+    if !data.scratch_notes.is_empty() {
+        prompt.push_str("\n工作笔记:\n");
+        for note in &data.scratch_notes {
+            prompt.push_str(&format!("- {}\n", note));
+        }
+    }
+    if let Some(commit) = &data.last_commit {
+        prompt.push_str(&format!("\n工作分支最近提交: {}\n", commit));
+    }
+    if let Some(diff) = data.partial_diff.as_ref().filter(|diff| !diff.trim().is_empty()) {
+        prompt.push_str("\n中断时尚未提交的变更（需要重新应用）:\n```diff\n");
+        prompt.push_str(diff.trim_end());
+        prompt.push_str("\n```\n");
+    }
+    prompt
+}
+
+/// 解析会话上保存的检查点
+fn parse_checkpoint(session: &execution_session::Model) -> Result<Option<SessionCheckpoint>> {
+    match &session.checkpoint {
+        Some(value) => Ok(Some(serde_json::from_value(value.clone())?)),
+        None => Ok(None),
+    }
+}
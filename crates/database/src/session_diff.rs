@@ -0,0 +1,469 @@
+//! 执行会话代码差异采集
+//!
+//! 执行会话结束时计算 `base_commit..final_commit` 的 Git 差异，解析为按文件、差异块
+//! 组织的结构化数据，以 JSON 工件的形式存入 [`ArtifactStore`]，并关联到该会话的代码审查，
+//! 供界面展示和审查Agent读取。
+//!
+//! 这里的 [`FileDiff`]、[`DiffHunk`]、[`DiffLine`] 和 [`parse_unified_diff`] 也用于待审批补丁的差异展示。
+
+use chrono::{DateTime, Utc};
+use codex_multi_agent::ArtifactType;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::path::Path;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::{
+    artifact_store::ArtifactStore,
+    entities::execution_session,
+    repository::{CodeReviewRepository, ExecutionArtifactRepository, ExecutionSessionRepository, ProjectRepository},
+    task_scope, DatabaseError, Result,
+};
+
+/// 会话差异工件的名称
+pub const SESSION_DIFF_ARTIFACT: &str = "session-diff.json";
+
+/// 文件变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChangeType {
+    /// 新增文件
+    Add,
+    /// 删除文件
+    Delete,
+    /// 修改文件（包括重命名）
+    Update,
+}
+
+/// 差异行类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    /// 上下文行
+    Context,
+    /// 新增行
+    Addition,
+    /// 删除行
+    Deletion,
+}
+
+/// 差异行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffLine {
+    /// 行类型
+    pub kind: DiffLineKind,
+    /// 行内容（不含前缀符号）
+    pub content: String,
+    /// 旧文件中的行号
+    pub old_line_number: Option<u32>,
+    /// 新文件中的行号
+    pub new_line_number: Option<u32>,
+}
+
+/// 差异块
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffHunk {
+    /// 块头（"@@ -a,b +c,d @@ ..."）
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// 块内的差异行
+    pub lines: Vec<DiffLine>,
+}
+
+/// 单个文件的差异
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileDiff {
+    /// 文件路径，重命名时为原路径
+    pub path: String,
+    /// 重命名后的路径
+    pub move_path: Option<String>,
+    /// 变更类型
+    pub change_type: FileChangeType,
+    /// 是否为二进制文件（二进制文件没有差异块）
+    pub is_binary: bool,
+    pub additions: usize,
+    pub deletions: usize,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// 差异统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiffStats {
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+impl DiffStats {
+    /// 汇总文件差异的统计
+    pub fn from_files(files: &[FileDiff]) -> Self {
+        Self {
+            files_changed: files.len(),
+            additions: files.iter().map(|file| file.additions).sum(),
+            deletions: files.iter().map(|file| file.deletions).sum(),
+        }
+    }
+}
+
+/// 执行会话的代码差异
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionDiff {
+    pub session_id: Uuid,
+    pub base_commit: String,
+    pub final_commit: String,
+    pub files: Vec<FileDiff>,
+    pub stats: DiffStats,
+    /// 采集时间
+    pub captured_at: DateTime<Utc>,
+}
+
+/// 完成执行会话并采集代码差异
+///
+/// 任务限定了工作范围时先校验差异，修改了范围之外文件的会话按失败完成。
+/// 范围校验和差异采集失败只记录警告，不影响会话完成。
+pub async fn complete_session_with_diff(
+    store: &ArtifactStore,
+    session_id: Uuid,
+    success: bool,
+    final_commit: Option<String>,
+    result_data: Option<JsonValue>,
+    error_message: Option<String>,
+) -> Result<execution_session::Model> {
+    let (success, error_message) = if success {
+        match task_scope::check_session_scope(store.db(), session_id, final_commit.as_deref()).await {
+            Ok(Some(violation)) => {
+                tracing::warn!("执行会话 {} {}", session_id, violation.message());
+                (false, Some(violation.message()))
+            }
+            Ok(None) => (success, error_message),
+            Err(e) => {
+                tracing::warn!("校验执行会话 {} 的工作范围失败: {}", session_id, e);
+                (success, error_message)
+            }
+        }
+    } else {
+        (success, error_message)
+    };
+
+    let session = ExecutionSessionRepository::new(store.db().clone())
+        .complete_session(session_id, success, final_commit, result_data, error_message)
+        .await?;
+
+    if let Err(e) = capture_session_diff(store, session_id).await {
+        tracing::warn!("采集执行会话 {} 的代码差异失败: {}", session_id, e);
+    }
+
+    Ok(session)
+}
+
+/// 采集执行会话的代码差异并保存为工件
+///
+/// 会话缺少基准提交或最终提交时返回 `None`。重复采集会替换之前的差异工件，
+/// 并把会话的代码审查关联到新工件。
+pub async fn capture_session_diff(store: &ArtifactStore, session_id: Uuid) -> Result<Option<SessionDiff>> {
+    let db = store.db();
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    let (Some(base_commit), Some(final_commit)) = (session.base_commit, session.final_commit) else {
+        return Ok(None);
+    };
+    let project = ProjectRepository::new(db.clone())
+        .find_by_id(session.project_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", session.project_id))?;
+
+    let raw = git_diff(Path::new(&project.workspace_path), &base_commit, &final_commit).await?;
+    let files = parse_git_diff(&raw);
+    let diff = SessionDiff {
+        session_id,
+        base_commit,
+        final_commit,
+        stats: DiffStats::from_files(&files),
+        files,
+        captured_at: Utc::now(),
+    };
+
+    let artifacts = ExecutionArtifactRepository::new(db.clone());
+    let previous: Vec<Uuid> = artifacts
+        .find_by_session_id(session_id)
+        .await?
+        .into_iter()
+        .filter(|artifact| artifact.name == SESSION_DIFF_ARTIFACT)
+        .map(|artifact| artifact.artifact_id)
+        .collect();
+
+    let artifact = store
+        .save(
+            session_id,
+            SESSION_DIFF_ARTIFACT,
+            ArtifactType::Patch,
+            &serde_json::to_vec(&diff)?,
+            Some(format!(
+                "{} 个文件, +{} -{}",
+                diff.stats.files_changed, diff.stats.additions, diff.stats.deletions
+            )),
+        )
+        .await?;
+    CodeReviewRepository::new(db.clone())
+        .link_diff_artifact(session_id, artifact.artifact_id)
+        .await?;
+    for artifact_id in previous {
+        store.delete(artifact_id).await?;
+    }
+
+    Ok(Some(diff))
+}
+
+/// 读取执行会话的代码差异，未采集或工件已被清理时返回 `None`
+pub async fn get_session_diff(store: &ArtifactStore, session_id: Uuid) -> Result<Option<SessionDiff>> {
+    let Some(artifact) = ExecutionArtifactRepository::new(store.db().clone())
+        .find_latest_by_name(session_id, SESSION_DIFF_ARTIFACT)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let (_, content) = store.fetch(artifact.artifact_id).await?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+/// 在工作空间中计算两个提交之间的差异（统一差异格式）
+pub async fn git_diff(workspace: &Path, base_commit: &str, final_commit: &str) -> Result<String> {
+    let output = Command::new("git")
+        .args(["-c", "core.quotePath=false", "diff", "--no-color", "--no-ext-diff", "-M"])
+        .args(["--src-prefix=a/", "--dst-prefix=b/", base_commit, final_commit])
+        .current_dir(workspace)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(DatabaseError::business_logic(format!(
+            "计算代码差异失败 {}..{}: {}",
+            base_commit,
+            final_commit,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// 解析 `git diff` 输出为文件差异列表
+pub fn parse_git_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut cursor = LineCursor::default();
+
+    for line in diff.lines() {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            files.push(FileDiff {
+                path: header_path(header),
+                move_path: None,
+                change_type: FileChangeType::Update,
+                is_binary: false,
+                additions: 0,
+                deletions: 0,
+                hunks: Vec::new(),
+            });
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if line.starts_with("@@") {
+            if let Some(hunk) = cursor.start_hunk(line) {
+                file.hunks.push(hunk);
+            }
+            continue;
+        }
+
+        // 第一个差异块之前是文件的扩展头
+        let Some(hunk) = file.hunks.last_mut() else {
+            parse_extended_header(file, line);
+            continue;
+        };
+
+        match cursor.push_line(hunk, line) {
+            Some(DiffLineKind::Addition) => file.additions += 1,
+            Some(DiffLineKind::Deletion) => file.deletions += 1,
+            _ => {}
+        }
+    }
+
+    files
+}
+
+/// 解析单个文件的统一差异格式（unified diff）为差异块，差异块之前的文件头被忽略
+pub fn parse_unified_diff(diff: &str) -> Vec<DiffHunk> {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    let mut cursor = LineCursor::default();
+
+    for line in diff.lines() {
+        if line.starts_with("@@") {
+            if let Some(hunk) = cursor.start_hunk(line) {
+                hunks.push(hunk);
+            }
+            continue;
+        }
+        if let Some(hunk) = hunks.last_mut() {
+            cursor.push_line(hunk, line);
+        }
+    }
+
+    hunks
+}
+
+/// 整个文件新增或删除时，把文件内容作为一个差异块
+pub fn whole_file_hunk(content: &str, kind: DiffLineKind) -> Option<DiffHunk> {
+    let line_count = content.lines().count() as u32;
+    if line_count == 0 {
+        return None;
+    }
+
+    let is_addition = kind == DiffLineKind::Addition;
+    let lines = content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let number = Some(i as u32 + 1);
+            DiffLine {
+                kind,
+                content: line.to_string(),
+                old_line_number: if is_addition { None } else { number },
+                new_line_number: if is_addition { number } else { None },
+            }
+        })
+        .collect();
+
+    let (old_start, old_lines, new_start, new_lines) = if is_addition {
+        (0, 0, 1, line_count)
+    } else {
+        (1, line_count, 0, 0)
+    };
+
+    Some(DiffHunk {
+        header: format!("@@ -{},{} +{},{} @@", old_start, old_lines, new_start, new_lines),
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        lines,
+    })
+}
+
+impl DiffHunk {
+    /// 块内指定类型的行数
+    pub fn count(&self, kind: DiffLineKind) -> usize {
+        self.lines.iter().filter(|line| line.kind == kind).count()
+    }
+}
+
+/// 差异块内的当前行号
+#[derive(Default)]
+struct LineCursor {
+    old_line: u32,
+    new_line: u32,
+}
+
+impl LineCursor {
+    /// 解析块头并从块的起始行开始计数
+    fn start_hunk(&mut self, header: &str) -> Option<DiffHunk> {
+        let (old_start, old_lines, new_start, new_lines) = parse_hunk_header(header)?;
+        self.old_line = old_start;
+        self.new_line = new_start;
+        Some(DiffHunk {
+            header: header.to_string(),
+            old_start,
+            old_lines,
+            new_start,
+            new_lines,
+            lines: Vec::new(),
+        })
+    }
+
+    /// 把一行差异加入差异块，返回行类型；"\ No newline at end of file" 等非差异行返回 `None`
+    fn push_line(&mut self, hunk: &mut DiffHunk, line: &str) -> Option<DiffLineKind> {
+        let (kind, content) = match line.chars().next() {
+            Some('+') => (DiffLineKind::Addition, &line[1..]),
+            Some('-') => (DiffLineKind::Deletion, &line[1..]),
+            Some(' ') => (DiffLineKind::Context, &line[1..]),
+            None => (DiffLineKind::Context, ""),
+            _ => return None,
+        };
+
+        let (old_number, new_number) = match kind {
+            DiffLineKind::Addition => {
+                self.new_line += 1;
+                (None, Some(self.new_line - 1))
+            }
+            DiffLineKind::Deletion => {
+                self.old_line += 1;
+                (Some(self.old_line - 1), None)
+            }
+            DiffLineKind::Context => {
+                self.old_line += 1;
+                self.new_line += 1;
+                (Some(self.old_line - 1), Some(self.new_line - 1))
+            }
+        };
+
+        hunk.lines.push(DiffLine {
+            kind,
+            content: content.to_string(),
+            old_line_number: old_number,
+            new_line_number: new_number,
+        });
+        Some(kind)
+    }
+}
+
+fn parse_extended_header(file: &mut FileDiff, line: &str) {
+    if line.starts_with("new file mode") {
+        file.change_type = FileChangeType::Add;
+    } else if line.starts_with("deleted file mode") {
+        file.change_type = FileChangeType::Delete;
+    } else if let Some(from) = line.strip_prefix("rename from ") {
+        file.path = from.to_string();
+    } else if let Some(to) = line.strip_prefix("rename to ") {
+        file.move_path = Some(to.to_string());
+    } else if line.starts_with("Binary files") || line.starts_with("GIT binary patch") {
+        file.is_binary = true;
+    } else if let Some(path) = line.strip_prefix("--- a/") {
+        file.path = path.to_string();
+    } else if let Some(path) = line.strip_prefix("+++ b/") {
+        // 新增文件的旧路径是 /dev/null，以新路径为准
+        if file.change_type == FileChangeType::Add {
+            file.path = path.to_string();
+        }
+    }
+}
+
+/// 从 "a/旧路径 b/新路径" 中取旧路径，后续的扩展头会给出更准确的路径
+fn header_path(header: &str) -> String {
+    let old = header.strip_prefix("a/").unwrap_or(header);
+    old.split_once(" b/").map_or(old, |(old, _)| old).to_string()
+}
+
+/// 解析 "@@ -a,b +c,d @@" 格式的块头
+fn parse_hunk_header(header: &str) -> Option<(u32, u32, u32, u32)> {
+    let inner = header.trim_start_matches("@@").split("@@").next()?.trim();
+    let mut parts = inner.split_whitespace();
+    let old = parts.next()?.strip_prefix('-')?;
+    let new = parts.next()?.strip_prefix('+')?;
+
+    let parse_range = |range: &str| -> Option<(u32, u32)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+
+    let (old_start, old_lines) = parse_range(old)?;
+    let (new_start, new_lines) = parse_range(new)?;
+    Some((old_start, old_lines, new_start, new_lines))
+}
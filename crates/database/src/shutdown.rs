@@ -0,0 +1,317 @@
+//! 优雅关闭
+//!
+//! [`ShutdownCoordinator`] 在应用退出时按顺序：
+//! 1. 停止接受新工作，[`ShutdownCoordinator::begin_work`] 此后返回 [`DatabaseError::ShuttingDown`]；
+//! 2. 触发 [`ShutdownCoordinator::token`]，通知执行中的工作尽快到达检查点，并在宽限期内等待它们结束；
+//! 3. 为仍在运行的执行会话写入检查点日志；
+//! 4. 依次运行注册的关闭钩子（刷新日志缓冲、事件发件箱等）；
+//! 5. 写入干净关闭标记。
+//!
+//! 启动时 [`ShutdownCoordinator::begin_run`] 读取上次的标记并把本次标记为运行中，
+//! 上次没有写入干净关闭标记说明应用异常退出。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::{
+    entities::execution_log::EventType,
+    operations::CancellationToken,
+    repository::{execution_log_repository::CreateExecutionLogData, ExecutionLogRepository, ExecutionSessionRepository},
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 等待执行中工作结束的默认宽限期
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// 单个关闭钩子的最长运行时间
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 关闭钩子返回的 future
+pub type ShutdownHookFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+type ShutdownHook = Box<dyn FnOnce() -> ShutdownHookFuture + Send>;
+
+/// 关闭标记，记录一次运行的起止
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShutdownMarker {
+    /// 是否干净关闭
+    pub clean: bool,
+    pub started_at: DateTime<Utc>,
+    pub shutdown_at: Option<DateTime<Utc>>,
+    /// 关闭时仍在运行、已写入检查点的执行会话
+    #[serde(default)]
+    pub interrupted_sessions: Vec<Uuid>,
+    /// 关闭过程中失败的步骤
+    #[serde(default)]
+    pub failed_steps: Vec<String>,
+}
+
+/// 上一次运行的结束方式
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreviousShutdown {
+    /// 首次运行，没有标记
+    FirstRun,
+    /// 干净关闭
+    Clean(ShutdownMarker),
+    /// 异常退出（标记仍为运行中或无法解析）
+    Unclean(Option<ShutdownMarker>),
+}
+
+impl PreviousShutdown {
+    /// 上次是否异常退出
+    pub fn is_unclean(&self) -> bool {
+        matches!(self, PreviousShutdown::Unclean(_))
+    }
+}
+
+/// 关闭结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShutdownReport {
+    /// 写入检查点的执行会话
+    pub interrupted_sessions: Vec<Uuid>,
+    /// 宽限期结束时仍未结束的工作数
+    pub abandoned_work: usize,
+    /// 失败的步骤及原因
+    pub failed_steps: Vec<String>,
+}
+
+/// 执行中工作的登记凭证，drop 时注销
+#[must_use = "工作结束前需要持有登记凭证"]
+pub struct WorkGuard {
+    coordinator: Arc<ShutdownCoordinator>,
+}
+
+impl std::fmt::Debug for WorkGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkGuard").finish_non_exhaustive()
+    }
+}
+
+impl Drop for WorkGuard {
+    fn drop(&mut self) {
+        if self.coordinator.active_work.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.coordinator.idle.notify_waiters();
+        }
+    }
+}
+
+/// 关闭协调器
+pub struct ShutdownCoordinator {
+    marker_path: PathBuf,
+    grace_period: Duration,
+    started_at: DateTime<Utc>,
+    accepting: AtomicBool,
+    finished: AtomicBool,
+    token: CancellationToken,
+    active_work: AtomicUsize,
+    idle: Notify,
+    hooks: Mutex<Vec<(String, ShutdownHook)>>,
+}
+
+impl ShutdownCoordinator {
+    /// 创建协调器，关闭标记写入 `marker_path`
+    pub fn new(marker_path: impl Into<PathBuf>) -> Self {
+        Self {
+            marker_path: marker_path.into(),
+            grace_period: DEFAULT_GRACE_PERIOD,
+            started_at: Utc::now(),
+            accepting: AtomicBool::new(true),
+            finished: AtomicBool::new(false),
+            token: CancellationToken::new(),
+            active_work: AtomicUsize::new(0),
+            idle: Notify::new(),
+            hooks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 设置等待执行中工作结束的宽限期
+    pub fn with_grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// 读取上次运行的关闭标记，并把本次运行标记为未干净关闭
+    pub async fn begin_run(&self) -> Result<PreviousShutdown> {
+        let previous = match tokio::fs::read(&self.marker_path).await {
+            Ok(content) => match serde_json::from_slice::<ShutdownMarker>(&content) {
+                Ok(marker) if marker.clean => PreviousShutdown::Clean(marker),
+                Ok(marker) => PreviousShutdown::Unclean(Some(marker)),
+                Err(_) => PreviousShutdown::Unclean(None),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => PreviousShutdown::FirstRun,
+            Err(e) => return Err(e.into()),
+        };
+
+        self.write_marker(&ShutdownMarker {
+            clean: false,
+            started_at: self.started_at,
+            shutdown_at: None,
+            interrupted_sessions: Vec::new(),
+            failed_steps: Vec::new(),
+        })
+        .await?;
+        Ok(previous)
+    }
+
+    /// 是否仍接受新工作
+    pub fn is_accepting(&self) -> bool {
+        self.accepting.load(Ordering::SeqCst)
+    }
+
+    /// 关闭流程是否已完成
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::SeqCst)
+    }
+
+    /// 关闭信号，执行方收到后应尽快到达检查点并结束
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// 登记一项执行中的工作，关闭时在宽限期内等待其结束
+    pub fn begin_work(self: &Arc<Self>) -> Result<WorkGuard> {
+        self.active_work.fetch_add(1, Ordering::SeqCst);
+        let guard = WorkGuard {
+            coordinator: self.clone(),
+        };
+        if !self.is_accepting() {
+            return Err(DatabaseError::ShuttingDown);
+        }
+        Ok(guard)
+    }
+
+    /// 注册关闭钩子，按注册顺序运行
+    pub fn register_hook<F>(&self, name: &str, hook: F)
+    where
+        F: FnOnce() -> ShutdownHookFuture + Send + 'static,
+    {
+        if let Ok(mut hooks) = self.hooks.lock() {
+            hooks.push((name.to_string(), Box::new(hook)));
+        }
+    }
+
+    /// 执行关闭流程，重复调用时只有第一次生效
+    pub async fn shutdown(&self, db: Option<&DatabaseConnection>) -> ShutdownReport {
+        let mut report = ShutdownReport::default();
+        if !self.accepting.swap(false, Ordering::SeqCst) {
+            return report;
+        }
+        self.token.cancel();
+
+        report.abandoned_work = self.wait_for_work().await;
+
+        if let Some(db) = db {
+            match checkpoint_running_sessions(db).await {
+                Ok(sessions) => report.interrupted_sessions = sessions,
+                Err(e) => report.failed_steps.push(format!("保存会话检查点: {}", e)),
+            }
+        }
+
+        let hooks = self
+            .hooks
+            .lock()
+            .map(|mut hooks| std::mem::take(&mut *hooks))
+            .unwrap_or_default();
+        for (name, hook) in hooks {
+            match tokio::time::timeout(HOOK_TIMEOUT, hook()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => report.failed_steps.push(format!("{}: {}", name, e)),
+                Err(_) => report.failed_steps.push(format!("{}: 超时", name)),
+            }
+        }
+
+        let marker = ShutdownMarker {
+            clean: true,
+            started_at: self.started_at,
+            shutdown_at: Some(Utc::now()),
+            interrupted_sessions: report.interrupted_sessions.clone(),
+            failed_steps: report.failed_steps.clone(),
+        };
+        if let Err(e) = self.write_marker(&marker).await {
+            report.failed_steps.push(format!("写入关闭标记: {}", e));
+        }
+
+        self.finished.store(true, Ordering::SeqCst);
+        report
+    }
+
+    /// 在宽限期内等待执行中的工作结束，返回超时后仍未结束的数量
+    async fn wait_for_work(&self) -> usize {
+        let deadline = tokio::time::Instant::now() + self.grace_period;
+        loop {
+            let idle = self.idle.notified();
+            let active = self.active_work.load(Ordering::SeqCst);
+            if active == 0 {
+                return 0;
+            }
+            if tokio::time::timeout_at(deadline, idle).await.is_err() {
+                return self.active_work.load(Ordering::SeqCst);
+            }
+        }
+    }
+
+    async fn write_marker(&self, marker: &ShutdownMarker) -> Result<()> {
+        if let Some(dir) = self.marker_path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let tmp = self.marker_path.with_extension("tmp");
+        tokio::fs::write(&tmp, serde_json::to_vec_pretty(marker)?).await?;
+        tokio::fs::rename(&tmp, &self.marker_path).await?;
+        Ok(())
+    }
+}
+
+/// 为仍在运行的执行会话写入检查点日志，返回会话ID
+async fn checkpoint_running_sessions(db: &DatabaseConnection) -> Result<Vec<Uuid>> {
+    let sessions = ExecutionSessionRepository::new(db.clone()).find_running_sessions().await?;
+    let logs = ExecutionLogRepository::new(db.clone());
+    let now = Utc::now();
+
+    let mut interrupted = Vec::with_capacity(sessions.len());
+    for session in sessions {
+        logs.create(CreateExecutionLogData {
+            session_id: session.session_id,
+            log_level: "warn".to_string(),
+            event_type: EventType::Progress,
+            message: "应用关闭，执行会话已中断".to_string(),
+            details: Some(json!({
+                "checkpoint": true,
+                "reason": "shutdown",
+                "git_branch": session.git_branch,
+                "base_commit": session.base_commit,
+            })),
+            timestamp_ms: now.timestamp_millis(),
+        })
+        .await?;
+        interrupted.push(session.session_id);
+    }
+    Ok(interrupted)
+}
+
+/// 结束上次运行遗留的执行会话，返回被结束的会话ID
+///
+/// 应用重启后这些会话的执行方已不存在，统一标记为超时；上次异常退出时会话没有检查点。
+pub async fn recover_interrupted_sessions(db: &DatabaseConnection, previous: &PreviousShutdown) -> Result<Vec<Uuid>> {
+    let repo = ExecutionSessionRepository::new(db.clone());
+    let message = if previous.is_unclean() {
+        "应用异常退出，执行会话未保存检查点"
+    } else {
+        "应用关闭时执行会话被中断"
+    };
+
+    let mut recovered = Vec::new();
+    for session in repo.find_running_sessions().await? {
+        repo.timeout_session(session.session_id, message.to_string()).await?;
+        recovered.push(session.session_id);
+    }
+    Ok(recovered)
+}
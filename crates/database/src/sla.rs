@@ -0,0 +1,349 @@
+//! 任务与里程碑SLA监控
+//!
+//! 项目可以配置 [`SlaPolicy`]（存放在 `projects.sla_policy`）：任务在待处理状态的最长时间、
+//! 任务执行的最长时长，以及里程碑截止日期前的缓冲时间。监控方定期调用 [`evaluate_all`]，
+//! 把评估结果与 `sla_breaches` 中未解决的记录对比：
+//! - 新发现的违约写入记录并产生 [`SlaBreachedEvent`]；
+//! - 已有违约升级为更高严重程度时更新记录并再次产生事件；
+//! - 条件不再满足的违约标记为已解决。
+//!
+//! 仪表盘和通知通过 [`SlaBreachRepository`] 查询当前违约。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::{
+    context::builder::parse_milestones,
+    entities::{
+        domain_event::{AggregateType, DomainEventType},
+        project, sla_breach, task,
+    },
+    repository::{
+        domain_event_repository::CreateDomainEventData, sla_breach_repository::CreateSlaBreachData,
+        DomainEventRepository, SlaBreachRepository, TaskRepository,
+    },
+    review_sla::ReviewDeadlinePolicy,
+    DatabaseConnection, DatabaseError, Result,
+};
+use codex_multi_agent::{llm_orchestration::MilestoneStatus, Clock, EventFactory, SlaBreachedEvent, SlaSeverity, SlaType};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+/// 违约对象为任务
+pub const SUBJECT_TASK: &str = "task";
+
+/// 违约对象为里程碑
+pub const SUBJECT_MILESTONE: &str = "milestone";
+
+/// 项目SLA配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlaPolicy {
+    /// 是否启用
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 任务在待处理状态的最长时间（分钟）
+    #[serde(default)]
+    pub max_pending_minutes: Option<i64>,
+    /// 任务执行的最长时长（分钟）
+    #[serde(default)]
+    pub max_execution_minutes: Option<i64>,
+    /// 里程碑截止日期前的缓冲时间（小时），进入缓冲期仍未完成即视为违约
+    #[serde(default)]
+    pub milestone_buffer_hours: Option<i64>,
+    /// 代码审查的时限、提醒与超时改派配置
+    #[serde(default)]
+    pub review_deadlines: ReviewDeadlinePolicy,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for SlaPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_pending_minutes: None,
+            max_execution_minutes: None,
+            milestone_buffer_hours: None,
+            review_deadlines: ReviewDeadlinePolicy::default(),
+        }
+    }
+}
+
+impl SlaPolicy {
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        if self.max_pending_minutes.is_some_and(|minutes| minutes <= 0) {
+            return Err(DatabaseError::validation("待处理时间上限必须大于0"));
+        }
+        if self.max_execution_minutes.is_some_and(|minutes| minutes <= 0) {
+            return Err(DatabaseError::validation("执行时长上限必须大于0"));
+        }
+        if self.milestone_buffer_hours.is_some_and(|hours| hours < 0) {
+            return Err(DatabaseError::validation("里程碑缓冲时间不能为负数"));
+        }
+        self.review_deadlines.validate()
+    }
+}
+
+/// 一次评估发现的违约
+#[derive(Debug, Clone)]
+struct Finding {
+    sla_type: SlaType,
+    subject_type: &'static str,
+    subject_id: String,
+    subject_name: String,
+    severity: SlaSeverity,
+    threshold_minutes: i64,
+    actual_minutes: i64,
+    deadline: Option<DateTime<Utc>>,
+}
+
+/// 读取项目的SLA配置，未配置或未启用时返回 None
+pub async fn project_policy(db: &DatabaseConnection, project_id: Uuid) -> Result<Option<SlaPolicy>> {
+    let project = project::Entity::find_by_id(project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+    parse_policy(&project)
+}
+
+/// 按时钟的当前时间评估所有配置了SLA或仍有未解决违约的项目
+pub async fn evaluate_all(db: &DatabaseConnection, clock: &dyn Clock) -> Result<Vec<SlaBreachedEvent>> {
+    let mut project_ids: Vec<Uuid> = project::Entity::find()
+        .filter(project::Column::SlaPolicy.is_not_null())
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|project| project.project_id)
+        .collect();
+    for breach in SlaBreachRepository::new(db.clone()).find_open(None).await? {
+        if !project_ids.contains(&breach.project_id) {
+            project_ids.push(breach.project_id);
+        }
+    }
+
+    let now = clock.now();
+    let mut events = Vec::new();
+    for project_id in project_ids {
+        events.extend(evaluate_project(db, project_id, now).await?);
+    }
+    Ok(events)
+}
+
+/// 在指定时间点评估项目的SLA，返回新产生的违约事件
+///
+/// 项目关闭SLA后，未解决的违约全部标记为已解决。
+pub async fn evaluate_project(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Vec<SlaBreachedEvent>> {
+    let project = project::Entity::find_by_id(project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+    let findings = match parse_policy(&project)? {
+        Some(policy) => {
+            let tasks = TaskRepository::new(db.clone()).find_by_project(project_id).await?;
+            collect_findings(&policy, &project, &tasks, now)
+        }
+        None => Vec::new(),
+    };
+
+    let breaches = SlaBreachRepository::new(db.clone());
+    let mut open: HashMap<(String, String), sla_breach::Model> = breaches
+        .find_by_project(project_id, false)
+        .await?
+        .into_iter()
+        .map(|breach| ((breach.sla_type.clone(), breach.subject_id.clone()), breach))
+        .collect();
+    let evaluated_at = now.into();
+
+    let mut events = Vec::new();
+    let mut seen = HashSet::new();
+    for finding in findings {
+        let key = (finding.sla_type.to_string(), finding.subject_id.clone());
+        seen.insert(key.clone());
+        let breach = match open.remove(&key) {
+            Some(existing) => {
+                let escalated = parse_severity(&existing.severity) < finding.severity;
+                let updated = breaches
+                    .update_measurement(
+                        existing.breach_id,
+                        finding.severity.as_str(),
+                        finding.actual_minutes,
+                        evaluated_at,
+                    )
+                    .await?;
+                if !escalated {
+                    continue;
+                }
+                updated
+            }
+            None => {
+                breaches
+                    .create(CreateSlaBreachData {
+                        project_id,
+                        sla_type: finding.sla_type.to_string(),
+                        subject_type: finding.subject_type.to_string(),
+                        subject_id: finding.subject_id.clone(),
+                        subject_name: finding.subject_name.clone(),
+                        severity: finding.severity.to_string(),
+                        threshold_minutes: finding.threshold_minutes,
+                        actual_minutes: finding.actual_minutes,
+                        deadline: finding.deadline.map(Into::into),
+                        detected_at: evaluated_at,
+                    })
+                    .await?
+            }
+        };
+        events.push(record_event(db, &breach, &finding, now).await?);
+    }
+
+    // 本次评估没有再发现的违约视为已解决
+    for (key, breach) in open {
+        if !seen.contains(&key) {
+            breaches.resolve(breach.breach_id, evaluated_at).await?;
+        }
+    }
+
+    Ok(events)
+}
+
+/// 按配置检查任务和里程碑
+fn collect_findings(
+    policy: &SlaPolicy,
+    project: &project::Model,
+    tasks: &[task::Model],
+    now: DateTime<Utc>,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for task in tasks {
+        let (sla_type, threshold, since) = match task.status.as_str() {
+            "pending" => match policy.max_pending_minutes {
+                Some(threshold) => (SlaType::PendingTime, threshold, task.created_at),
+                None => continue,
+            },
+            "in_progress" => match policy.max_execution_minutes {
+                Some(threshold) => (
+                    SlaType::ExecutionTime,
+                    threshold,
+                    task.started_at.or(task.assigned_at).unwrap_or(task.updated_at),
+                ),
+                None => continue,
+            },
+            _ => continue,
+        };
+        let elapsed = (now - since.with_timezone(&Utc)).num_minutes();
+        if elapsed <= threshold {
+            continue;
+        }
+        // 紧急任务或超出阈值一倍以上视为严重违约
+        let severity = if elapsed >= threshold * 2 || matches!(task.priority.as_str(), "critical" | "urgent") {
+            SlaSeverity::Critical
+        } else {
+            SlaSeverity::Warning
+        };
+        findings.push(Finding {
+            sla_type,
+            subject_type: SUBJECT_TASK,
+            subject_id: task.task_id.to_string(),
+            subject_name: task.title.clone(),
+            severity,
+            threshold_minutes: threshold,
+            actual_minutes: elapsed,
+            deadline: None,
+        });
+    }
+
+    if let Some(buffer_hours) = policy.milestone_buffer_hours {
+        let buffer_minutes = buffer_hours * 60;
+        for milestone in parse_milestones(project, tasks) {
+            if milestone.status == MilestoneStatus::Completed {
+                continue;
+            }
+            let remaining = (milestone.deadline - now).num_minutes();
+            if remaining > buffer_minutes {
+                continue;
+            }
+            findings.push(Finding {
+                sla_type: SlaType::MilestoneDeadline,
+                subject_type: SUBJECT_MILESTONE,
+                subject_id: milestone.id.clone(),
+                subject_name: milestone.name.clone(),
+                severity: if remaining < 0 { SlaSeverity::Critical } else { SlaSeverity::Warning },
+                threshold_minutes: buffer_minutes,
+                actual_minutes: remaining,
+                deadline: Some(milestone.deadline),
+            });
+        }
+    }
+
+    findings
+}
+
+/// 创建违约事件并持久化为项目的领域事件
+async fn record_event(
+    db: &DatabaseConnection,
+    breach: &sla_breach::Model,
+    finding: &Finding,
+    now: DateTime<Utc>,
+) -> Result<SlaBreachedEvent> {
+    let mut event = EventFactory::sla_breached(
+        breach.breach_id.to_string(),
+        breach.project_id.into(),
+        finding.sla_type,
+        finding.severity,
+        finding.subject_id.clone(),
+        finding.subject_name.clone(),
+        finding.threshold_minutes,
+        finding.actual_minutes,
+        finding.deadline,
+    );
+    event.metadata = event.metadata.with_timestamp(now);
+
+    let correlation_id = Uuid::parse_str(&finding.subject_id).unwrap_or(breach.project_id);
+    let event_repo = DomainEventRepository::new(db.clone());
+    let event_version = event_repo.get_latest_version(breach.project_id).await? + 1;
+    event_repo
+        .create_correlated(
+            CreateDomainEventData {
+                aggregate_type: AggregateType::Project.to_string(),
+                aggregate_id: breach.project_id,
+                event_type: DomainEventType::SlaBreached.to_string(),
+                event_data: serde_json::to_value(&event)?,
+                event_version,
+            },
+            correlation_id,
+        )
+        .await?;
+
+    tracing::warn!(
+        "项目 {} 的{} {} 违反SLA {}（{}）",
+        breach.project_id,
+        breach.subject_type,
+        breach.subject_name,
+        breach.sla_type,
+        breach.severity
+    );
+    Ok(event)
+}
+
+fn parse_policy(project: &project::Model) -> Result<Option<SlaPolicy>> {
+    let policy = match &project.sla_policy {
+        Some(value) => Some(serde_json::from_value::<SlaPolicy>(value.clone())?),
+        None => None,
+    };
+    Ok(policy.filter(|policy| policy.enabled))
+}
+
+fn parse_severity(severity: &str) -> SlaSeverity {
+    match severity {
+        "critical" => SlaSeverity::Critical,
+        _ => SlaSeverity::Warning,
+    }
+}
@@ -0,0 +1,460 @@
+//! 静态分析（Linter）接入
+//!
+//! 按项目编码规范中的语言配置（[`LanguageConfig`]）确定要运行的 Linter：Rust 使用 clippy，
+//! TypeScript/JavaScript/Vue/Svelte 使用 ESLint，`linter_command` 可以覆盖内置命令。
+//! [`run_session_analysis`] 通过 [`LintRunner`] 在执行会话的工作空间中运行各 Linter，把输出解析为
+//! 统一的 [`Finding`]（文件、行、规则、严重程度）并保存到 `lint_findings`。
+//!
+//! 各 Linter 的级别映射到 [`IssueSeverity`]，再按 [`severity_level`] 对应到质量门禁的
+//! [`SeverityLevel`]：启用了 `enforce_rules` 的语言中，级别不在 `allowed_severity_levels` 内的发现
+//! 会阻止审查被批准。
+
+use codex_multi_agent::{
+    project_management::{LanguageConfig, SeverityLevel},
+    CodingStandards, IssueSeverity,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::{
+    entities::{code_review, lint_finding},
+    repository::{
+        lint_finding_repository::CreateLintFindingData, ExecutionSessionRepository, LintFindingRepository,
+        OrganizationRepository, ProjectRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 内置支持的 Linter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintTool {
+    /// Rust clippy（`--message-format=json` 输出）
+    Clippy,
+    /// ESLint（`--format json` 输出）
+    Eslint,
+}
+
+impl LintTool {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintTool::Clippy => "clippy",
+            LintTool::Eslint => "eslint",
+        }
+    }
+
+    /// 语言对应的 Linter，语言名称与编码规范中的语言配置一致
+    pub fn for_language(language: &str) -> Option<Self> {
+        match language {
+            "Rust" => Some(LintTool::Clippy),
+            "TypeScript" | "JavaScript" | "Vue" | "Svelte" => Some(LintTool::Eslint),
+            _ => None,
+        }
+    }
+
+    /// 内置命令
+    pub fn default_command(&self) -> &'static str {
+        match self {
+            LintTool::Clippy => "cargo clippy --workspace --all-targets --message-format=json",
+            LintTool::Eslint => "npx eslint . --format json",
+        }
+    }
+
+    /// 把 Linter 输出解析为统一的发现
+    pub fn parse_output(&self, output: &str, workspace: &Path) -> Result<Vec<Finding>> {
+        match self {
+            LintTool::Clippy => parse_clippy(output),
+            LintTool::Eslint => parse_eslint(output, workspace),
+        }
+    }
+}
+
+/// 需要运行的一个 Linter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinterSpec {
+    pub tool: LintTool,
+    /// 由该 Linter 检查的语言，按名称排序
+    pub languages: Vec<String>,
+    /// 按空白分隔参数的命令
+    pub command: String,
+}
+
+impl LinterSpec {
+    /// 发现所属的语言：ESLint 按文件扩展名区分，其余取第一个语言
+    fn language_for(&self, file_path: &str) -> String {
+        let extension = Path::new(file_path).extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+        let guessed = match (self.tool, extension) {
+            (LintTool::Eslint, "ts" | "tsx" | "mts" | "cts") => Some("TypeScript"),
+            (LintTool::Eslint, "vue") => Some("Vue"),
+            (LintTool::Eslint, "svelte") => Some("Svelte"),
+            (LintTool::Eslint, "js" | "jsx" | "mjs" | "cjs") => Some("JavaScript"),
+            _ => None,
+        };
+        guessed
+            .filter(|language| self.languages.iter().any(|configured| configured == language))
+            .map(str::to_string)
+            .or_else(|| self.languages.first().cloned())
+            .unwrap_or_default()
+    }
+}
+
+/// 按语言配置确定要运行的 Linter，使用相同 Linter 和命令的语言合并为一次运行
+pub fn configured_linters(standards: &CodingStandards) -> Vec<LinterSpec> {
+    let mut specs: BTreeMap<(LintTool, String), Vec<String>> = BTreeMap::new();
+    for config in standards.language_configs.values() {
+        let Some(tool) = LintTool::for_language(&config.language) else {
+            continue;
+        };
+        let command = linter_command(config, tool);
+        specs.entry((tool, command)).or_default().push(config.language.clone());
+    }
+    specs
+        .into_iter()
+        .map(|((tool, command), mut languages)| {
+            languages.sort();
+            LinterSpec { tool, languages, command }
+        })
+        .collect()
+}
+
+fn linter_command(config: &LanguageConfig, tool: LintTool) -> String {
+    config
+        .linter_command
+        .as_deref()
+        .map(str::trim)
+        .filter(|command| !command.is_empty())
+        .unwrap_or(tool.default_command())
+        .to_string()
+}
+
+/// 统一格式的静态分析发现
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub file_path: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub rule: Option<String>,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+/// 解析 `cargo clippy --message-format=json` 的输出
+///
+/// 只取有主要位置的编译器消息，同一问题在多个目标中重复报告时只保留一条。
+/// 编译错误映射为 Critical，clippy 的 deny 级别为 Major，警告为 Minor，note/help 为 Info
+pub fn parse_clippy(output: &str) -> Result<Vec<Finding>> {
+    let mut seen = HashSet::new();
+    let mut findings = Vec::new();
+    for line in output.lines().map(str::trim).filter(|line| line.starts_with('{')) {
+        let value: JsonValue = serde_json::from_str(line)?;
+        if value["reason"] != "compiler-message" {
+            continue;
+        }
+        let message = &value["message"];
+        let Some(span) = message["spans"]
+            .as_array()
+            .and_then(|spans| spans.iter().find(|span| span["is_primary"] == true))
+        else {
+            continue;
+        };
+        let rule = message["code"]["code"].as_str().map(str::to_string);
+        let is_lint = rule.as_deref().is_some_and(|rule| rule.starts_with("clippy::"));
+        let severity = match message["level"].as_str().unwrap_or_default() {
+            "error: internal compiler error" => IssueSeverity::Blocker,
+            "error" if is_lint => IssueSeverity::Major,
+            "error" => IssueSeverity::Critical,
+            "warning" => IssueSeverity::Minor,
+            _ => IssueSeverity::Info,
+        };
+        let finding = Finding {
+            file_path: span["file_name"].as_str().unwrap_or_default().to_string(),
+            line: span["line_start"].as_u64().map(|line| line as u32),
+            column: span["column_start"].as_u64().map(|column| column as u32),
+            rule,
+            severity,
+            message: message["message"].as_str().unwrap_or_default().to_string(),
+        };
+        let key = (finding.file_path.clone(), finding.line, finding.column, finding.rule.clone(), finding.message.clone());
+        if seen.insert(key) {
+            findings.push(finding);
+        }
+    }
+    Ok(findings)
+}
+
+/// 解析 `eslint --format json` 的输出，文件路径转换为相对工作空间的路径
+///
+/// 规则级别 2 映射为 Major，1 映射为 Minor，解析失败（fatal）映射为 Critical
+pub fn parse_eslint(output: &str, workspace: &Path) -> Result<Vec<Finding>> {
+    let output = output.trim();
+    if output.is_empty() {
+        return Ok(Vec::new());
+    }
+    let files: Vec<JsonValue> = serde_json::from_str(output)?;
+    let mut findings = Vec::new();
+    for file in files {
+        let path = file["filePath"].as_str().unwrap_or_default();
+        let file_path = Path::new(path)
+            .strip_prefix(workspace)
+            .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|_| path.to_string());
+        for message in file["messages"].as_array().into_iter().flatten() {
+            let severity = if message["fatal"] == true {
+                IssueSeverity::Critical
+            } else if message["severity"] == 2 {
+                IssueSeverity::Major
+            } else {
+                IssueSeverity::Minor
+            };
+            findings.push(Finding {
+                file_path: file_path.clone(),
+                line: message["line"].as_u64().map(|line| line as u32),
+                column: message["column"].as_u64().map(|column| column as u32),
+                rule: message["ruleId"].as_str().map(str::to_string),
+                severity,
+                message: message["message"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+    }
+    Ok(findings)
+}
+
+/// Linter 运行结果的异步输出（标准输出内容）
+pub type LintOutputFuture<'a> = Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>>;
+
+/// Linter 运行器
+pub trait LintRunner: Send + Sync {
+    /// 在工作空间中运行 Linter，返回标准输出
+    fn run<'a>(&'a self, spec: &'a LinterSpec, workspace: &'a Path) -> LintOutputFuture<'a>;
+}
+
+/// 以子进程方式运行 Linter
+///
+/// Linter 发现问题时通常以非零状态退出，只有没有任何输出时才视为运行失败
+pub struct CommandLintRunner;
+
+impl LintRunner for CommandLintRunner {
+    fn run<'a>(&'a self, spec: &'a LinterSpec, workspace: &'a Path) -> LintOutputFuture<'a> {
+        Box::pin(async move {
+            let mut parts = spec.command.split_whitespace();
+            let program = parts
+                .next()
+                .ok_or_else(|| DatabaseError::validation(format!("{} 的命令为空", spec.tool.as_str())))?;
+            let output = Command::new(program).args(parts).current_dir(workspace).output().await?;
+            let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+            if !output.status.success() && stdout.trim().is_empty() {
+                return Err(DatabaseError::business_logic(format!(
+                    "{} 运行失败: {}",
+                    spec.tool.as_str(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            Ok(stdout)
+        })
+    }
+}
+
+/// 单个 Linter 的运行情况
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintRun {
+    pub tool: LintTool,
+    pub languages: Vec<String>,
+    pub finding_count: usize,
+    /// 运行或解析失败的原因，失败时保留该 Linter 之前的发现
+    pub error: Option<String>,
+}
+
+/// 执行会话的静态分析报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaticAnalysisReport {
+    pub session_id: Uuid,
+    pub runs: Vec<LintRun>,
+    pub findings: Vec<lint_finding::Model>,
+}
+
+/// 记录执行会话中某个 Linter 的发现，覆盖该 Linter 之前的记录
+pub async fn record_findings(
+    db: &DatabaseConnection,
+    session_id: Uuid,
+    spec: &LinterSpec,
+    findings: Vec<Finding>,
+) -> Result<Vec<lint_finding::Model>> {
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+
+    let repo = LintFindingRepository::new(db.clone());
+    repo.delete_by_session_and_tool(session_id, spec.tool.as_str()).await?;
+    repo.create_many(
+        findings
+            .into_iter()
+            .map(|finding| CreateLintFindingData {
+                project_id: session.project_id,
+                execution_session_id: session_id,
+                tool: spec.tool.as_str().to_string(),
+                language: spec.language_for(&finding.file_path),
+                file_path: finding.file_path,
+                line: finding.line.map(|line| line as i32),
+                column: finding.column.map(|column| column as i32),
+                rule: finding.rule,
+                severity: severity_str(&finding.severity).to_string(),
+                message: finding.message,
+            })
+            .collect(),
+    )
+    .await
+}
+
+/// 执行会话的工作空间：优先使用会话的工作树，否则使用项目工作空间
+pub async fn session_workspace(db: &DatabaseConnection, session_id: Uuid) -> Result<PathBuf> {
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    if let Some(worktree_path) = session.worktree_path {
+        return Ok(PathBuf::from(worktree_path));
+    }
+    let project = ProjectRepository::new(db.clone())
+        .find_by_id(session.project_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", session.project_id))?;
+    Ok(PathBuf::from(project.workspace_path))
+}
+
+/// 在工作空间中运行项目配置的全部 Linter 并保存发现
+pub async fn run_session_analysis(
+    db: &DatabaseConnection,
+    session_id: Uuid,
+    workspace: &Path,
+    runner: &dyn LintRunner,
+) -> Result<StaticAnalysisReport> {
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    let standards = OrganizationRepository::new(db.clone())
+        .resolve_project_standards(session.project_id)
+        .await?;
+
+    let mut runs = Vec::new();
+    for spec in configured_linters(&standards) {
+        let parsed = match runner.run(&spec, workspace).await {
+            Ok(output) => spec.tool.parse_output(&output, workspace),
+            Err(e) => Err(e),
+        };
+        let run = match parsed {
+            Ok(findings) => {
+                let finding_count = record_findings(db, session_id, &spec, findings).await?.len();
+                LintRun { tool: spec.tool, languages: spec.languages, finding_count, error: None }
+            }
+            Err(e) => {
+                tracing::warn!("执行会话 {} 运行 {} 失败: {}", session_id, spec.tool.as_str(), e);
+                LintRun { tool: spec.tool, languages: spec.languages, finding_count: 0, error: Some(e.to_string()) }
+            }
+        };
+        runs.push(run);
+    }
+
+    Ok(StaticAnalysisReport {
+        session_id,
+        runs,
+        findings: LintFindingRepository::new(db.clone()).find_by_session(session_id).await?,
+    })
+}
+
+/// 严重程度的存储值
+pub fn severity_str(severity: &IssueSeverity) -> &'static str {
+    match severity {
+        IssueSeverity::Info => "info",
+        IssueSeverity::Minor => "minor",
+        IssueSeverity::Moderate => "moderate",
+        IssueSeverity::Major => "major",
+        IssueSeverity::Critical => "critical",
+        IssueSeverity::Blocker => "blocker",
+    }
+}
+
+pub(crate) fn parse_severity(value: &str) -> IssueSeverity {
+    match value {
+        "minor" => IssueSeverity::Minor,
+        "moderate" => IssueSeverity::Moderate,
+        "major" => IssueSeverity::Major,
+        "critical" => IssueSeverity::Critical,
+        "blocker" => IssueSeverity::Blocker,
+        _ => IssueSeverity::Info,
+    }
+}
+
+/// 问题严重程度对应的质量门禁级别
+pub fn severity_level(severity: &IssueSeverity) -> SeverityLevel {
+    match severity {
+        IssueSeverity::Info => SeverityLevel::Info,
+        IssueSeverity::Minor | IssueSeverity::Moderate => SeverityLevel::Warning,
+        IssueSeverity::Major | IssueSeverity::Critical => SeverityLevel::Error,
+        IssueSeverity::Blocker => SeverityLevel::Blocker,
+    }
+}
+
+/// 静态分析门禁结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintGateResult {
+    pub session_id: Uuid,
+    pub passed: bool,
+    /// 各严重程度的发现数量
+    pub counts: BTreeMap<String, usize>,
+    /// 阻止批准的发现
+    pub blocking: Vec<lint_finding::Model>,
+}
+
+/// 按项目质量门禁评估执行会话的静态分析发现
+pub async fn evaluate_lint_gate(db: &DatabaseConnection, session_id: Uuid) -> Result<LintGateResult> {
+    let findings = LintFindingRepository::new(db.clone()).find_by_session(session_id).await?;
+    let mut counts = BTreeMap::new();
+    for finding in &findings {
+        *counts.entry(finding.severity.clone()).or_insert(0) += 1;
+    }
+    if findings.is_empty() {
+        return Ok(LintGateResult { session_id, passed: true, counts, blocking: Vec::new() });
+    }
+
+    let standards = OrganizationRepository::new(db.clone())
+        .resolve_project_standards(findings[0].project_id)
+        .await?;
+    let enforced: HashSet<&str> = standards
+        .language_configs
+        .values()
+        .filter(|config| config.enforce_rules)
+        .map(|config| config.language.as_str())
+        .collect();
+    let allowed = &standards.quality_gates.allowed_severity_levels;
+    let blocking: Vec<lint_finding::Model> = findings
+        .iter()
+        .filter(|finding| enforced.contains(finding.language.as_str()))
+        .filter(|finding| !allowed.contains(&severity_level(&parse_severity(&finding.severity))))
+        .cloned()
+        .collect();
+
+    Ok(LintGateResult { session_id, passed: blocking.is_empty(), counts, blocking })
+}
+
+/// 检查审查能否被批准：存在质量门禁不允许的静态分析发现时不能批准
+pub async fn ensure_review_can_be_approved(db: &DatabaseConnection, review: &code_review::Model) -> Result<()> {
+    let gate = evaluate_lint_gate(db, review.execution_session_id).await?;
+    match gate.blocking.first() {
+        Some(first) => Err(DatabaseError::business_logic(format!(
+            "静态分析发现 {} 个质量门禁不允许的问题，例如 {}:{} {}",
+            gate.blocking.len(),
+            first.file_path,
+            first.line.unwrap_or_default(),
+            first.message
+        ))),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,331 @@
+//! 结构化输出解析
+//!
+//! 从LLM回复中提取结构化数据：优先解析JSON（代码块或裸JSON），
+//! 失败时回退到Markdown启发式解析。
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::sync::OnceLock;
+
+/// 标题最大字符数
+const MAX_TITLE_CHARS: usize = 80;
+
+/// 从消息中提取的任务草稿
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskDraft {
+    /// 任务标题
+    pub title: String,
+    /// 任务描述
+    pub description: String,
+    /// 验收标准
+    #[serde(default)]
+    pub acceptance_criteria: Vec<String>,
+    /// 任务类型
+    #[serde(default)]
+    pub task_type: Option<String>,
+    /// 优先级
+    #[serde(default)]
+    pub priority: Option<String>,
+}
+
+/// 从LLM回复中解析任务草稿
+pub fn parse_task_draft(text: &str) -> Option<TaskDraft> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    extract_json(text)
+        .and_then(|value| task_draft_from_json(&value))
+        .or_else(|| task_draft_from_markdown(text))
+}
+
+/// 提取文本中的JSON对象：```json 代码块优先，其次整段或首个花括号区间
+pub fn extract_json(text: &str) -> Option<JsonValue> {
+    for block in fenced_blocks(text) {
+        if let Ok(value) = serde_json::from_str::<JsonValue>(block.trim()) {
+            if value.is_object() {
+                return Some(value);
+            }
+        }
+    }
+
+    if let Ok(value) = serde_json::from_str::<JsonValue>(text) {
+        if value.is_object() {
+            return Some(value);
+        }
+    }
+
+    // 逐个尝试花括号起点，只取能完整解析的第一个对象
+    text.match_indices('{').find_map(|(start, _)| {
+        serde_json::Deserializer::from_str(&text[start..])
+            .into_iter::<JsonValue>()
+            .next()
+            .and_then(|result| result.ok())
+            .filter(|value| value.as_object().is_some_and(|object| !object.is_empty()))
+    })
+}
+
+/// 从仍在生成中的LLM输出里提取已经完整出现的任务标题，按出现顺序去重
+///
+/// JSON输出取已闭合的 `"title"` 字段；Markdown输出只看已换行结束的行，优先取二级及以下标题，
+/// 没有标题时取顶层有序列表项，验收标准小节中的内容不算作任务
+pub fn partial_task_titles(text: &str) -> Vec<String> {
+    static TITLE_FIELD: OnceLock<Regex> = OnceLock::new();
+    let pattern = TITLE_FIELD.get_or_init(|| Regex::new(r#""title"\s*:\s*("(?:[^"\\]|\\.)*")"#).unwrap());
+
+    let mut titles: Vec<String> = pattern
+        .captures_iter(text)
+        .filter_map(|captures| serde_json::from_str::<String>(&captures[1]).ok())
+        .map(|title| truncate_title(&title))
+        .filter(|title| !title.is_empty())
+        .collect();
+    if titles.is_empty() {
+        let complete = &text[..text.rfind('\n').map_or(0, |end| end + 1)];
+        titles = markdown_task_titles(complete);
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    titles.retain(|title| seen.insert(title.clone()));
+    titles
+}
+
+fn markdown_task_titles(text: &str) -> Vec<String> {
+    let mut headings = Vec::new();
+    let mut items = Vec::new();
+    let mut in_criteria = false;
+    let mut in_code_block = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block || trimmed.is_empty() {
+            continue;
+        }
+        if let Some(heading) = trimmed.strip_prefix("##") {
+            let heading = clean_inline(heading.trim_start_matches('#'));
+            in_criteria = is_criteria_heading(&heading);
+            if !in_criteria && !heading.is_empty() {
+                headings.push(truncate_title(&heading));
+            }
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            in_criteria = false;
+            continue;
+        }
+        if is_criteria_heading(trimmed.trim_end_matches([':', '：']).trim_matches('*')) {
+            in_criteria = true;
+            continue;
+        }
+
+        // 顶层有序列表项，缩进的列表项是任务的细节
+        let digits = line.chars().take_while(char::is_ascii_digit).count();
+        if !in_criteria && digits > 0 {
+            let rest = &line[digits..];
+            if rest.starts_with(". ") || rest.starts_with(") ") || rest.starts_with('、') {
+                let item = clean_inline(rest.trim_start_matches(['.', ')', '、']));
+                if !item.is_empty() {
+                    items.push(truncate_title(&item));
+                }
+            }
+        }
+    }
+
+    if headings.is_empty() {
+        items
+    } else {
+        headings
+    }
+}
+
+/// 返回所有 ``` 代码块的内容（语言标记为空或json）
+fn fenced_blocks(text: &str) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find("```") {
+        let after_open = &rest[open + 3..];
+        let Some(line_end) = after_open.find('\n') else {
+            break;
+        };
+        let lang = after_open[..line_end].trim().to_lowercase();
+        let body = &after_open[line_end + 1..];
+        let Some(close) = body.find("```") else {
+            break;
+        };
+        if lang.is_empty() || lang == "json" {
+            blocks.push(&body[..close]);
+        }
+        rest = &body[close + 3..];
+    }
+    blocks
+}
+
+fn task_draft_from_json(value: &JsonValue) -> Option<TaskDraft> {
+    // 兼容 {"task": {...}} 形式的包装
+    let value = value.get("task").filter(|v| v.is_object()).unwrap_or(value);
+
+    let title = string_field(value, &["title", "name", "summary"])?;
+    let description = string_field(value, &["description", "details", "body"]).unwrap_or_default();
+    let acceptance_criteria = value
+        .get("acceptance_criteria")
+        .or_else(|| value.get("acceptanceCriteria"))
+        .or_else(|| value.get("criteria"))
+        .map(|criteria| match criteria {
+            JsonValue::Array(items) => items
+                .iter()
+                .filter_map(|item| item.as_str().map(|s| s.trim().to_string()))
+                .filter(|s| !s.is_empty())
+                .collect(),
+            JsonValue::String(s) => s
+                .lines()
+                .map(strip_list_marker)
+                .filter(|s| !s.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        })
+        .unwrap_or_default();
+
+    Some(TaskDraft {
+        title: truncate_title(&title),
+        description,
+        acceptance_criteria,
+        task_type: string_field(value, &["task_type", "taskType", "type"]),
+        priority: string_field(value, &["priority"]).map(|p| p.to_lowercase()),
+    })
+}
+
+fn string_field(value: &JsonValue, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| value.get(*key).and_then(JsonValue::as_str))
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Markdown启发式解析：首个标题（或首行）作为标题，验收标准小节或复选框作为验收标准
+fn task_draft_from_markdown(text: &str) -> Option<TaskDraft> {
+    let mut title: Option<String> = None;
+    let mut description_lines = Vec::new();
+    let mut acceptance_criteria = Vec::new();
+    let mut in_criteria = false;
+    let mut in_code_block = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            if !in_criteria {
+                description_lines.push(line);
+            }
+            continue;
+        }
+        if in_code_block {
+            if !in_criteria {
+                description_lines.push(line);
+            }
+            continue;
+        }
+
+        if let Some(heading) = trimmed.strip_prefix('#') {
+            let heading = heading.trim_start_matches('#').trim();
+            if is_criteria_heading(heading) {
+                in_criteria = true;
+                continue;
+            }
+            in_criteria = false;
+            if title.is_none() && !heading.is_empty() {
+                title = Some(clean_inline(heading));
+                continue;
+            }
+        } else if is_criteria_heading(trimmed.trim_end_matches([':', '：']).trim_matches('*')) {
+            in_criteria = true;
+            continue;
+        }
+
+        if let Some(item) = checkbox_item(trimmed) {
+            acceptance_criteria.push(item);
+            continue;
+        }
+
+        if in_criteria {
+            let item = strip_list_marker(trimmed);
+            if !item.is_empty() {
+                acceptance_criteria.push(item);
+            }
+            continue;
+        }
+
+        if title.is_none() && !trimmed.is_empty() {
+            title = Some(clean_inline(trimmed));
+            continue;
+        }
+
+        description_lines.push(line);
+    }
+
+    let title = title.filter(|t| !t.is_empty())?;
+    Some(TaskDraft {
+        title: truncate_title(&title),
+        description: description_lines.join("\n").trim().to_string(),
+        acceptance_criteria,
+        task_type: None,
+        priority: None,
+    })
+}
+
+fn is_criteria_heading(heading: &str) -> bool {
+    let lower = heading.trim().to_lowercase();
+    lower == "验收标准"
+        || lower == "完成标准"
+        || lower.starts_with("acceptance criteria")
+        || lower == "definition of done"
+}
+
+fn checkbox_item(line: &str) -> Option<String> {
+    let rest = line
+        .strip_prefix("- [ ]")
+        .or_else(|| line.strip_prefix("* [ ]"))
+        .or_else(|| line.strip_prefix("- [x]"))
+        .or_else(|| line.strip_prefix("- [X]"))?;
+    let item = rest.trim();
+    (!item.is_empty()).then(|| item.to_string())
+}
+
+fn strip_list_marker(line: &str) -> String {
+    let line = line.trim();
+    let line = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+        .unwrap_or(line);
+    // 有序列表 "1. " / "1) "
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let line = if digits > 0 {
+        line[digits..]
+            .strip_prefix(". ")
+            .or_else(|| line[digits..].strip_prefix(") "))
+            .unwrap_or(line)
+    } else {
+        line
+    };
+    line.trim().to_string()
+}
+
+fn clean_inline(text: &str) -> String {
+    text.replace(['*', '`'], "").trim().to_string()
+}
+
+fn truncate_title(title: &str) -> String {
+    let title = title.trim();
+    if title.chars().count() <= MAX_TITLE_CHARS {
+        return title.to_string();
+    }
+    let mut truncated: String = title.chars().take(MAX_TITLE_CHARS).collect();
+    truncated.push_str("...");
+    truncated
+}
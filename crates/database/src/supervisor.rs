@@ -0,0 +1,393 @@
+//! 后台服务监督
+//!
+//! 常驻服务器上的调度、事件投递、SLA 监控等后台服务由 [`Supervisor`] 统一启动。每个服务是一个
+//! 接收关闭信号的异步函数：收到信号后应尽快返回 `Ok(())`。服务返回错误或 panic 时按其
+//! [`RestartPolicy`] 以指数退避重启；连续运行超过稳定时长后退避重新计算。
+//!
+//! [`SupervisorHandle`] 提供各服务的健康快照，[`serve_health`] 在 HTTP 端点上暴露：
+//! `GET /healthz` 返回整体状态（有服务失败时为 503），`GET /health` 返回每个服务的详情。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::{operations::CancellationToken, Result};
+
+/// 默认的首次重启退避
+pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// 默认的最长重启退避
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// 默认的稳定运行时长，服务运行超过该时长后退避重新计算
+pub const DEFAULT_STABLE_AFTER: Duration = Duration::from_secs(60);
+
+/// 服务运行的 future
+pub type ServiceFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+type ServiceFactory = Arc<dyn Fn(CancellationToken) -> ServiceFuture + Send + Sync>;
+
+/// 服务重启策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RestartPolicy {
+    /// 不重启，服务结束后保持停止或失败
+    Never,
+    /// 返回错误或 panic 时重启，`max_restarts` 为空表示不限次数
+    OnFailure { max_restarts: Option<u32> },
+    /// 除关闭外总是重启，正常返回也会重启
+    Always,
+}
+
+/// 服务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceState {
+    /// 运行中
+    Running,
+    /// 等待重启
+    Backoff,
+    /// 未启动、正常结束或已关闭
+    Stopped,
+    /// 失败且不再重启
+    Failed,
+}
+
+/// 单个服务的健康状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServiceHealth {
+    pub name: String,
+    pub state: ServiceState,
+    pub policy: RestartPolicy,
+    /// 已重启次数
+    pub restarts: u32,
+    /// 最近一次失败的原因
+    pub last_error: Option<String>,
+    /// 最近一次启动时间
+    pub started_at: Option<DateTime<Utc>>,
+}
+
+/// 整体健康状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// 所有服务运行中或正常结束
+    Healthy,
+    /// 有服务在等待重启
+    Degraded,
+    /// 有服务失败且不再重启
+    Unhealthy,
+}
+
+/// 监督器的健康快照
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SupervisorHealth {
+    pub status: HealthStatus,
+    pub started_at: DateTime<Utc>,
+    /// 是否已收到关闭信号
+    pub shutting_down: bool,
+    pub services: Vec<ServiceHealth>,
+}
+
+struct ServiceSpec {
+    name: String,
+    policy: RestartPolicy,
+    factory: ServiceFactory,
+}
+
+struct SharedState {
+    started_at: DateTime<Utc>,
+    services: Mutex<Vec<ServiceHealth>>,
+}
+
+/// 读取监督器健康状态的句柄
+#[derive(Clone)]
+pub struct SupervisorHandle {
+    state: Arc<SharedState>,
+    token: CancellationToken,
+}
+
+impl SupervisorHandle {
+    /// 当前的健康快照
+    pub fn health(&self) -> SupervisorHealth {
+        let services = self.state.services.lock().map(|services| services.clone()).unwrap_or_default();
+        let status = if services.iter().any(|service| service.state == ServiceState::Failed) {
+            HealthStatus::Unhealthy
+        } else if services.iter().any(|service| service.state == ServiceState::Backoff) {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        };
+        SupervisorHealth {
+            status,
+            started_at: self.state.started_at,
+            shutting_down: self.token.is_cancelled(),
+            services,
+        }
+    }
+
+    fn update(&self, index: usize, apply: impl FnOnce(&mut ServiceHealth)) {
+        if let Some(service) = self.state.services.lock().ok().as_mut().and_then(|services| services.get_mut(index)) {
+            apply(service);
+        }
+    }
+}
+
+/// 后台服务监督器
+pub struct Supervisor {
+    token: CancellationToken,
+    services: Vec<ServiceSpec>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    stable_after: Duration,
+}
+
+impl Supervisor {
+    /// 创建监督器，`token` 被取消时所有服务收到关闭信号
+    pub fn new(token: CancellationToken) -> Self {
+        Self {
+            token,
+            services: Vec::new(),
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            stable_after: DEFAULT_STABLE_AFTER,
+        }
+    }
+
+    /// 设置重启退避：首次等待 `initial`，之后每次翻倍，不超过 `max`
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// 设置稳定运行时长
+    pub fn with_stable_after(mut self, stable_after: Duration) -> Self {
+        self.stable_after = stable_after;
+        self
+    }
+
+    /// 添加服务，`factory` 每次启动时调用
+    pub fn add_service<F, Fut>(mut self, name: &str, policy: RestartPolicy, factory: F) -> Self
+    where
+        F: Fn(CancellationToken) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.services.push(ServiceSpec {
+            name: name.to_string(),
+            policy,
+            factory: Arc::new(move |token| Box::pin(factory(token))),
+        });
+        self
+    }
+
+    /// 健康状态句柄，服务启动前即可获取
+    pub fn handle(&self) -> SupervisorHandle {
+        let services = self
+            .services
+            .iter()
+            .map(|spec| ServiceHealth {
+                name: spec.name.clone(),
+                state: ServiceState::Stopped,
+                policy: spec.policy,
+                restarts: 0,
+                last_error: None,
+                started_at: None,
+            })
+            .collect();
+        SupervisorHandle {
+            state: Arc::new(SharedState {
+                started_at: Utc::now(),
+                services: Mutex::new(services),
+            }),
+            token: self.token.clone(),
+        }
+    }
+
+    /// 启动所有服务并监督，直到所有服务结束（通常在关闭信号之后）
+    pub async fn run(self, handle: SupervisorHandle) {
+        let mut tasks = Vec::with_capacity(self.services.len());
+        for (index, spec) in self.services.into_iter().enumerate() {
+            let supervision = Supervision {
+                index,
+                spec,
+                handle: handle.clone(),
+                token: self.token.clone(),
+                initial_backoff: self.initial_backoff,
+                max_backoff: self.max_backoff,
+                stable_after: self.stable_after,
+            };
+            tasks.push(tokio::spawn(supervision.run()));
+        }
+        for task in tasks {
+            let _ = task.await;
+        }
+    }
+}
+
+/// 单个服务的监督循环
+struct Supervision {
+    index: usize,
+    spec: ServiceSpec,
+    handle: SupervisorHandle,
+    token: CancellationToken,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    stable_after: Duration,
+}
+
+impl Supervision {
+    async fn run(self) {
+        let name = self.spec.name.as_str();
+        // 连续快速失败的次数，决定退避时长
+        let mut consecutive = 0u32;
+        loop {
+            if self.token.is_cancelled() {
+                self.handle.update(self.index, |service| service.state = ServiceState::Stopped);
+                return;
+            }
+
+            let started = tokio::time::Instant::now();
+            self.handle.update(self.index, |service| {
+                service.state = ServiceState::Running;
+                service.started_at = Some(Utc::now());
+            });
+            let future = (self.spec.factory)(self.token.clone());
+            let outcome = match tokio::spawn(future).await {
+                Ok(Ok(())) => None,
+                Ok(Err(e)) => Some(e.to_string()),
+                Err(e) if e.is_panic() => Some(format!("panic: {}", panic_message(e.into_panic()))),
+                Err(e) => Some(e.to_string()),
+            };
+
+            if self.token.is_cancelled() {
+                self.handle.update(self.index, |service| {
+                    service.state = ServiceState::Stopped;
+                    if let Some(error) = outcome {
+                        service.last_error = Some(error);
+                    }
+                });
+                return;
+            }
+
+            let failed = outcome.is_some();
+            if let Some(error) = &outcome {
+                tracing::warn!("后台服务 {} 失败: {}", name, error);
+            } else {
+                tracing::info!("后台服务 {} 已结束", name);
+            }
+            let restarts = self.handle.health().services.get(self.index).map_or(0, |service| service.restarts);
+            let restart = match self.spec.policy {
+                RestartPolicy::Never => false,
+                RestartPolicy::OnFailure { max_restarts } => failed && max_restarts.is_none_or(|max| restarts < max),
+                RestartPolicy::Always => true,
+            };
+            if !restart {
+                self.handle.update(self.index, |service| {
+                    service.state = if failed { ServiceState::Failed } else { ServiceState::Stopped };
+                    service.last_error = outcome;
+                });
+                return;
+            }
+
+            consecutive = if started.elapsed() >= self.stable_after { 0 } else { consecutive + 1 };
+            let backoff = self.backoff(consecutive);
+            self.handle.update(self.index, |service| {
+                service.state = ServiceState::Backoff;
+                service.restarts += 1;
+                if outcome.is_some() {
+                    service.last_error = outcome;
+                }
+            });
+            tokio::select! {
+                _ = tokio::time::sleep(backoff) => {}
+                _ = self.token.cancelled() => {}
+            }
+        }
+    }
+
+    /// 第 `attempt` 次连续重启的退避时长
+    fn backoff(&self, attempt: u32) -> Duration {
+        if attempt == 0 {
+            return Duration::ZERO;
+        }
+        let factor = 2u32.saturating_pow(attempt - 1);
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "未知错误".to_string())
+}
+
+/// 在 HTTP 端点上暴露健康状态，`token` 被取消时停止接受连接
+pub async fn serve_health(listener: TcpListener, handle: SupervisorHandle, token: CancellationToken) -> Result<()> {
+    loop {
+        let (stream, peer) = tokio::select! {
+            accepted = listener.accept() => accepted?,
+            _ = token.cancelled() => return Ok(()),
+        };
+        let handle = handle.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_connection(stream, &handle).await {
+                tracing::warn!("健康检查连接 {} 处理失败: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn serve_connection(stream: TcpStream, handle: &SupervisorHandle) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+    let health = handle.health();
+    let status_code = if health.status == HealthStatus::Unhealthy { 503 } else { 200 };
+    let (status, body) = match (method, path.split('?').next().unwrap_or_default()) {
+        ("GET", "/healthz") => (status_code, json!({ "status": health.status })),
+        ("GET", "/health") => (status_code, serde_json::to_value(&health)?),
+        ("GET", _) => (404, json!({ "error": "路径应为 /healthz 或 /health" })),
+        _ => (405, json!({ "error": "只支持 GET 请求" })),
+    };
+
+    let body = serde_json::to_vec(&body)?;
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        reason_phrase(status),
+        body.len()
+    );
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(&body).await?;
+    writer.shutdown().await?;
+    Ok(())
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
@@ -0,0 +1,214 @@
+//! 任务工作范围（大型仓库的按目录划分）
+//!
+//! 任务的 `scope_path` 限定被分配的 Agent 可以修改的子目录，路径相对工作空间根目录，
+//! 为空表示整个仓库：
+//! - 分配任务时范围说明附加到分配提示词；
+//! - 命令策略把写入范围之外的命令上报人工确认；
+//! - 执行会话完成时由 [`check_session_scope`] 校验代码差异，修改了范围之外文件的会话按失败处理；
+//! - [`detect_scope_conflicts`] 只为范围重叠、由不同 Agent 同时执行的任务登记冲突，
+//!   范围互不相交的 Agent 不会产生冲突。
+
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        conflict::{self, ConflictSeverity, ConflictType},
+        task,
+    },
+    repository::{
+        conflict_repository::CreateConflictData, ConflictRepository, ExecutionSessionRepository, ProjectRepository,
+        TaskRepository,
+    },
+    session_diff::{git_diff, parse_git_diff, FileDiff},
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 修改了范围之外文件的执行会话
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScopeViolation {
+    pub task_id: Uuid,
+    pub scope_path: String,
+    /// 范围之外被修改的文件
+    pub files: Vec<String>,
+}
+
+impl ScopeViolation {
+    /// 写入执行会话的错误信息
+    pub fn message(&self) -> String {
+        format!("修改了工作范围 {} 之外的文件: {}", self.scope_path, self.files.join(", "))
+    }
+}
+
+/// 规范化工作范围：统一分隔符、去除 `./` 和首尾斜杠，空路径或 `.` 表示整个仓库
+///
+/// 绝对路径和包含 `..` 的路径返回验证错误
+pub fn normalize_scope(scope: &str) -> Result<Option<String>> {
+    let scope = scope.trim().replace('\\', "/");
+    if scope.starts_with('/') || scope.contains(':') {
+        return Err(DatabaseError::validation(format!("工作范围必须是工作空间内的相对路径: {}", scope)));
+    }
+    let mut components = Vec::new();
+    for component in scope.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => return Err(DatabaseError::validation(format!("工作范围不能包含 `..`: {}", scope))),
+            component => components.push(component),
+        }
+    }
+    Ok((!components.is_empty()).then(|| components.join("/")))
+}
+
+/// 相对工作空间根目录的路径是否位于范围内
+pub fn contains(scope: Option<&str>, path: &str) -> bool {
+    let Some(scope) = scope else {
+        return true;
+    };
+    let path = path.trim_start_matches("./");
+    path == scope || path.strip_prefix(scope).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// 范围 `inner` 是否完全位于范围 `outer` 之内
+pub fn is_within(outer: Option<&str>, inner: Option<&str>) -> bool {
+    match inner {
+        Some(inner) => contains(outer, inner),
+        None => outer.is_none(),
+    }
+}
+
+/// 两个范围是否重叠，任一方为整个仓库时总是重叠
+pub fn overlaps(a: Option<&str>, b: Option<&str>) -> bool {
+    is_within(a, b) || is_within(b, a)
+}
+
+/// 差异中位于范围之外的文件，重命名时原路径和新路径都需要在范围内
+pub fn out_of_scope_files(scope: Option<&str>, files: &[FileDiff]) -> Vec<String> {
+    files
+        .iter()
+        .flat_map(|file| std::iter::once(&file.path).chain(file.move_path.iter()))
+        .filter(|path| !contains(scope, path))
+        .cloned()
+        .collect()
+}
+
+/// 在分配提示词末尾附加工作范围说明
+pub fn append_to_prompt(scope: Option<&str>, prompt: String) -> String {
+    match scope {
+        Some(scope) => format!(
+            "{}\n\n## 工作范围\n只允许修改 `{}` 目录下的文件，需要改动其他目录时请说明原因并停止。\n",
+            prompt.trim_end(),
+            scope
+        ),
+        None => prompt,
+    }
+}
+
+/// 校验执行会话的代码差异是否超出任务的工作范围
+///
+/// `final_commit` 为空时使用会话已记录的最终提交；任务未限定范围或会话缺少提交时返回 `None`
+pub async fn check_session_scope(
+    db: &DatabaseConnection,
+    session_id: Uuid,
+    final_commit: Option<&str>,
+) -> Result<Option<ScopeViolation>> {
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(session_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+    let Some(scope_path) = TaskRepository::new(db.clone())
+        .find_by_id(session.task_id)
+        .await?
+        .and_then(|task| task.scope_path)
+    else {
+        return Ok(None);
+    };
+    let (Some(base_commit), Some(final_commit)) = (session.base_commit, final_commit.map(str::to_string).or(session.final_commit))
+    else {
+        return Ok(None);
+    };
+    let project = ProjectRepository::new(db.clone())
+        .find_by_id(session.project_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", session.project_id))?;
+
+    let diff = git_diff(Path::new(&project.workspace_path), &base_commit, &final_commit).await?;
+    let files = out_of_scope_files(Some(&scope_path), &parse_git_diff(&diff));
+    Ok((!files.is_empty()).then_some(ScopeViolation {
+        task_id: session.task_id,
+        scope_path,
+        files,
+    }))
+}
+
+/// 检测项目中由不同 Agent 同时执行、工作范围重叠的任务，为尚未登记的任务对创建资源冲突
+pub async fn detect_scope_conflicts(db: &DatabaseConnection, project_id: Uuid) -> Result<Vec<conflict::Model>> {
+    let running = task::Entity::find()
+        .filter(task::Column::ProjectId.eq(project_id))
+        .filter(task::Column::Status.eq("in_progress"))
+        .filter(task::Column::AssignedAgentId.is_not_null())
+        .order_by_asc(task::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    let conflicts = ConflictRepository::new(db.clone());
+    let mut created = Vec::new();
+    for (index, first) in running.iter().enumerate() {
+        for second in &running[index + 1..] {
+            if first.assigned_agent_id == second.assigned_agent_id
+                || !overlaps(first.scope_path.as_deref(), second.scope_path.as_deref())
+            {
+                continue;
+            }
+            let second_id = format!("\"{}\"", second.task_id);
+            let registered = conflicts
+                .find_affecting_task(&first.task_id.to_string())
+                .await?
+                .into_iter()
+                .any(|existing| {
+                    existing.conflict_type == ConflictType::Resource.to_string()
+                        && existing.status != "ignored"
+                        && existing.affected_tasks.to_string().contains(&second_id)
+                });
+            if registered {
+                continue;
+            }
+
+            let conflict = conflicts
+                .create(CreateConflictData {
+                    conflict_type: ConflictType::Resource,
+                    severity: ConflictSeverity::Medium,
+                    title: "任务工作范围重叠".to_string(),
+                    description: format!(
+                        "任务「{}」（范围 {}）和任务「{}」（范围 {}）由不同 Agent 同时修改重叠的目录",
+                        first.title,
+                        display_scope(first.scope_path.as_deref()),
+                        second.title,
+                        display_scope(second.scope_path.as_deref())
+                    ),
+                    related_entities: json!({
+                        "project_id": project_id,
+                        "scope_paths": [first.scope_path, second.scope_path],
+                    }),
+                    affected_tasks: json!([first.task_id.to_string(), second.task_id.to_string()]),
+                    affected_agents: json!([first.assigned_agent_id, second.assigned_agent_id]),
+                    project_id: Some(project_id),
+                })
+                .await?;
+            tracing::info!(
+                "检测到任务 {} 与 {} 的工作范围重叠，已登记冲突 {}",
+                first.task_id,
+                second.task_id,
+                conflict.conflict_id
+            );
+            created.push(conflict);
+        }
+    }
+    Ok(created)
+}
+
+fn display_scope(scope: Option<&str>) -> &str {
+    scope.unwrap_or("整个仓库")
+}
@@ -0,0 +1,256 @@
+//! 任务生命周期追踪
+//!
+//! 一个任务的生命周期跨越分解（LLM会话）、分配、执行（执行会话与日志）和审查，
+//! 各阶段的数据分散在不同的表中。任务的关联ID即任务ID：领域事件通过
+//! `correlation_id` 串联，仓储操作在 `tracing` span 中记录同名字段。
+//!
+//! [`trace_task`] 汇总这些数据，按时间重建完整时间线，用于排查任务停滞的原因。
+
+use chrono::{DateTime, Utc};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::{
+    entities::{domain_event::DomainEventType, task},
+    repository::{
+        CodeReviewRepository, DomainEventRepository, ExecutionLogRepository, ExecutionSessionRepository,
+        LlmConversationRepository, LlmSessionRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::EntityTrait;
+
+/// 时间线所属阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceStage {
+    /// 需求分解（LLM会话、任务创建）
+    Decomposition,
+    /// 任务分配
+    Assignment,
+    /// 执行
+    Execution,
+    /// 代码审查
+    Review,
+    /// 其他领域事件
+    Event,
+}
+
+/// 时间线条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEntry {
+    /// 发生时间
+    pub timestamp: DateTime<Utc>,
+    /// 所属阶段
+    pub stage: TraceStage,
+    /// 条目类型，如 task_created、llm_message、session_started、log
+    pub kind: String,
+    /// 可读描述
+    pub message: String,
+    /// 来源实体ID（任务、LLM会话、执行会话、审查、事件等）
+    pub source_id: Uuid,
+    /// 附加信息
+    pub details: Option<JsonValue>,
+}
+
+/// 时间线中相邻两条记录之间的空档
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceGap {
+    /// 空档前的最后一条记录
+    pub after: TraceEntry,
+    /// 空档后的第一条记录
+    pub before: TraceEntry,
+    /// 空档时长（秒）
+    pub seconds: i64,
+}
+
+/// 任务的完整时间线
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskTrace {
+    /// 任务ID
+    pub task_id: Uuid,
+    /// 关联ID
+    pub correlation_id: Uuid,
+    /// 任务当前状态
+    pub status: String,
+    /// 按时间排序的条目
+    pub entries: Vec<TraceEntry>,
+}
+
+impl TaskTrace {
+    /// 某一阶段的条目
+    pub fn stage_entries(&self, stage: TraceStage) -> impl Iterator<Item = &TraceEntry> {
+        self.entries.iter().filter(move |e| e.stage == stage)
+    }
+
+    /// 最后一次活动时间
+    pub fn last_activity_at(&self) -> Option<DateTime<Utc>> {
+        self.entries.last().map(|e| e.timestamp)
+    }
+
+    /// 时间线中最长的空档，通常就是任务停滞的位置
+    pub fn longest_gap(&self) -> Option<TraceGap> {
+        self.entries
+            .windows(2)
+            .map(|pair| (pair, (pair[1].timestamp - pair[0].timestamp).num_seconds()))
+            .max_by_key(|(_, seconds)| *seconds)
+            .map(|(pair, seconds)| TraceGap {
+                after: pair[0].clone(),
+                before: pair[1].clone(),
+                seconds,
+            })
+    }
+}
+
+/// 任务操作的 tracing span，外部的LLM/提供方调用可以挂在此 span 下以共享关联ID
+pub fn task_span(task_id: Uuid) -> tracing::Span {
+    tracing::info_span!("task", task_id = %task_id, correlation_id = %task_id)
+}
+
+/// 重建任务的完整时间线
+pub async fn trace_task(db: &DatabaseConnection, task_id: Uuid) -> Result<TaskTrace> {
+    let task = task::Entity::find_by_id(task_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Task", task_id))?;
+
+    let mut entries = Vec::new();
+    let mut push = |timestamp: DateTime<Utc>, stage, kind: &str, message: String, source_id, details| {
+        entries.push(TraceEntry {
+            timestamp,
+            stage,
+            kind: kind.to_string(),
+            message,
+            source_id,
+            details,
+        });
+    };
+
+    // 分解：产生该任务的LLM会话及其中的每次模型调用
+    if let Some(llm_session_id) = task.llm_session_id {
+        if let Some(session) = LlmSessionRepository::new(db.clone()).find_by_id(llm_session_id).await? {
+            push(utc(session.created_at), TraceStage::Decomposition, "llm_session_started",
+                format!("开始{}会话", session.session_type), session.session_id, None);
+            for message in LlmConversationRepository::new(db.clone()).find_by_session(session.session_id).await? {
+                let model = message.model_used.as_deref().unwrap_or("未知模型");
+                let text = match message.processing_time_ms {
+                    Some(ms) => format!("{} 消息（{}，耗时 {} ms）", message.role, model, ms),
+                    None => format!("{} 消息（{}）", message.role, model),
+                };
+                push(utc(message.created_at), TraceStage::Decomposition, "llm_message", text, message.message_id,
+                    Some(json!({
+                        "role": message.role,
+                        "model_used": message.model_used,
+                        "token_count": message.token_count,
+                        "processing_time_ms": message.processing_time_ms,
+                    })));
+            }
+            if let Some(completed_at) = session.completed_at {
+                push(utc(completed_at), TraceStage::Decomposition, "llm_session_completed",
+                    format!("{}会话结束（{}）", session.session_type, session.status), session.session_id, None);
+            }
+        }
+    }
+
+    push(utc(task.created_at), TraceStage::Decomposition, "task_created",
+        format!("任务创建：{}", task.title), task.task_id, None);
+
+    if let Some(assigned_at) = task.assigned_at {
+        push(utc(assigned_at), TraceStage::Assignment, "task_assigned", "任务已分配".to_string(), task.task_id,
+            Some(json!({ "agent_id": task.assigned_agent_id })));
+    }
+    if let Some(started_at) = task.started_at {
+        push(utc(started_at), TraceStage::Execution, "task_started", "任务开始执行".to_string(), task.task_id, None);
+    }
+    if let Some(completed_at) = task.completed_at {
+        push(utc(completed_at), TraceStage::Execution, &format!("task_{}", task.status),
+            format!("任务结束（{}）", task.status), task.task_id, None);
+    }
+
+    // 执行：每个执行会话的状态变化和日志
+    let log_repo = ExecutionLogRepository::new(db.clone());
+    for session in ExecutionSessionRepository::new(db.clone()).find_by_task_id(task_id).await? {
+        push(utc(session.created_at), TraceStage::Execution, "session_created",
+            format!("创建执行会话（分支 {}）", session.git_branch), session.session_id,
+            Some(json!({ "agent_id": session.agent_id })));
+        if let Some(started_at) = session.started_at {
+            push(utc(started_at), TraceStage::Execution, "session_started", "执行会话开始".to_string(),
+                session.session_id, None);
+        }
+        for log in log_repo.find_by_session_id(session.session_id).await? {
+            let timestamp = DateTime::from_timestamp_millis(log.timestamp_ms).unwrap_or_else(|| utc(log.created_at));
+            push(timestamp, TraceStage::Execution, "log",
+                format!("[{}] {}: {}", log.log_level, log.event_type, log.message), log.session_id, log.details);
+        }
+        if let Some(completed_at) = session.completed_at {
+            push(utc(completed_at), TraceStage::Execution, "session_completed",
+                format!("执行会话结束（{}）", session.status), session.session_id,
+                session.error_message.map(|error| json!({ "error_message": error })));
+        }
+    }
+
+    // 审查
+    for review in CodeReviewRepository::new(db.clone()).find_by_task_id(task_id).await? {
+        push(utc(review.created_at), TraceStage::Review, "review_requested",
+            format!("发起代码审查（{} → {}）", review.source_branch, review.target_branch), review.review_id,
+            Some(json!({ "reviewer_agent_id": review.reviewer_agent_id })));
+        if let Some(reviewed_at) = review.reviewed_at {
+            push(utc(reviewed_at), TraceStage::Review, "review_completed",
+                format!("审查完成（{}）", review.decision.as_deref().unwrap_or(&review.status)), review.review_id,
+                review.overall_comment.map(|comment| json!({ "overall_comment": comment })));
+        }
+    }
+
+    // 领域事件：任务自身的事件和带相同关联ID的事件
+    let event_repo = DomainEventRepository::new(db.clone());
+    let mut seen = HashSet::new();
+    let events = event_repo
+        .find_by_aggregate_id(task_id)
+        .await?
+        .into_iter()
+        .chain(event_repo.find_by_correlation_id(task_id).await?);
+    for event in events {
+        if !seen.insert(event.event_id) {
+            continue;
+        }
+        push(utc(event.occurred_at), event_stage(&event.event_type), "domain_event", event.event_type.clone(),
+            event.event_id, Some(json!({ "aggregate_type": event.aggregate_type, "aggregate_id": event.aggregate_id })));
+    }
+
+    // 稳定排序，同一时刻的记录保持写入顺序
+    entries.sort_by_key(|e| e.timestamp);
+
+    Ok(TaskTrace {
+        task_id,
+        correlation_id: task_id,
+        status: task.status,
+        entries,
+    })
+}
+
+fn utc(timestamp: DateTimeWithTimeZone) -> DateTime<Utc> {
+    timestamp.with_timezone(&Utc)
+}
+
+fn event_stage(event_type: &str) -> TraceStage {
+    let stage_of = |t: DomainEventType| t.to_string() == event_type;
+    if stage_of(DomainEventType::TaskAssigned) {
+        TraceStage::Assignment
+    } else if [
+        DomainEventType::TaskStarted,
+        DomainEventType::TaskCompleted,
+        DomainEventType::TaskFailed,
+        DomainEventType::ExecutionSessionStarted,
+        DomainEventType::ExecutionSessionCompleted,
+    ]
+    .into_iter()
+    .any(stage_of)
+    {
+        TraceStage::Execution
+    } else {
+        TraceStage::Event
+    }
+}
@@ -0,0 +1,502 @@
+//! 遥测指标
+//!
+//! 进程内的计数器、仪表和直方图注册表，记录任务吞吐、LLM延迟、队列深度和数据库耗时。
+//! 指标可以通过 [`serve_prometheus`] 暴露为 Prometheus 抓取端点，也可以用
+//! [`Telemetry::otlp_payload`] 生成 OTLP/HTTP JSON 请求体推送到 collector。
+//!
+//! 默认关闭，关闭时所有记录函数只做一次原子读取；由应用设置调用 [`Telemetry::set_enabled`] 开启。
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use serde_json::{json, Value as JsonValue};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use crate::{
+    entities::{execution_session, task},
+    DatabaseConnection, Result,
+};
+
+/// 任务创建数
+pub const TASKS_CREATED_TOTAL: &str = "sker_tasks_created_total";
+/// 任务状态变更数（按目标状态）
+pub const TASK_STATUS_TRANSITIONS_TOTAL: &str = "sker_task_status_transitions_total";
+/// LLM调用耗时（秒）
+pub const LLM_REQUEST_DURATION_SECONDS: &str = "sker_llm_request_duration_seconds";
+/// 队列深度
+pub const QUEUE_DEPTH: &str = "sker_queue_depth";
+/// 数据库操作耗时（秒）
+pub const DB_OPERATION_DURATION_SECONDS: &str = "sker_db_operation_duration_seconds";
+/// LLM响应缓存查询数（按结果：hit、miss、bypass）
+pub const LLM_CACHE_REQUESTS_TOTAL: &str = "sker_llm_cache_requests_total";
+
+/// LLM调用耗时的直方图分桶（秒）
+const LLM_BUCKETS: &[f64] = &[0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+/// 数据库操作耗时的直方图分桶（秒）
+const DB_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 1.0];
+
+/// 指标标签，按键排序保证同一组标签只对应一条时间序列
+pub type Labels = Vec<(&'static str, String)>;
+
+type MetricKey = (&'static str, Labels);
+
+#[derive(Debug, Clone)]
+struct Histogram {
+    bounds: &'static [f64],
+    /// 每个分桶的计数（非累计），最后一个为 +Inf
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len() + 1],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        let index = self.bounds.iter().position(|b| value <= *b).unwrap_or(self.bounds.len());
+        self.counts[index] += 1;
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    counters: BTreeMap<MetricKey, u64>,
+    gauges: BTreeMap<MetricKey, f64>,
+    histograms: BTreeMap<MetricKey, Histogram>,
+}
+
+/// 指标注册表
+#[derive(Debug)]
+pub struct Telemetry {
+    enabled: AtomicBool,
+    registry: Mutex<Registry>,
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Telemetry {
+    /// 创建关闭状态的注册表
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            registry: Mutex::new(Registry::default()),
+        }
+    }
+
+    /// 开启或关闭指标记录，关闭不会清空已有数据
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// 是否正在记录指标
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// 计数器加 `value`
+    pub fn increment_counter(&self, name: &'static str, labels: Labels, value: u64) {
+        if !self.is_enabled() {
+            return;
+        }
+        *self.lock().counters.entry((name, sorted(labels))).or_insert(0) += value;
+    }
+
+    /// 设置仪表当前值
+    pub fn set_gauge(&self, name: &'static str, labels: Labels, value: f64) {
+        if !self.is_enabled() {
+            return;
+        }
+        self.lock().gauges.insert((name, sorted(labels)), value);
+    }
+
+    /// 记录一次直方图观测值
+    pub fn observe(&self, name: &'static str, labels: Labels, bounds: &'static [f64], value: f64) {
+        if !self.is_enabled() {
+            return;
+        }
+        self.lock()
+            .histograms
+            .entry((name, sorted(labels)))
+            .or_insert_with(|| Histogram::new(bounds))
+            .observe(value);
+    }
+
+    /// 读取计数器的值，便于测试和诊断
+    pub fn counter_value(&self, name: &'static str, labels: Labels) -> u64 {
+        self.lock().counters.get(&(name, sorted(labels))).copied().unwrap_or(0)
+    }
+
+    /// 读取仪表的值
+    pub fn gauge_value(&self, name: &'static str, labels: Labels) -> Option<f64> {
+        self.lock().gauges.get(&(name, sorted(labels))).copied()
+    }
+
+    /// 读取直方图的观测次数
+    pub fn histogram_count(&self, name: &'static str, labels: Labels) -> u64 {
+        self.lock().histograms.get(&(name, sorted(labels))).map(|h| h.count).unwrap_or(0)
+    }
+
+    /// 清空所有指标
+    pub fn reset(&self) {
+        *self.lock() = Registry::default();
+    }
+
+    /// 以 Prometheus 文本格式（0.0.4）输出所有指标
+    pub fn render_prometheus(&self) -> String {
+        let registry = self.lock();
+        let mut out = String::new();
+        let mut last_name = "";
+
+        for ((name, labels), value) in &registry.counters {
+            if *name != last_name {
+                out.push_str(&format!("# TYPE {} counter\n", name));
+                last_name = name;
+            }
+            out.push_str(&format!("{}{} {}\n", name, format_labels(labels, None), value));
+        }
+        for ((name, labels), value) in &registry.gauges {
+            if *name != last_name {
+                out.push_str(&format!("# TYPE {} gauge\n", name));
+                last_name = name;
+            }
+            out.push_str(&format!("{}{} {}\n", name, format_labels(labels, None), value));
+        }
+        for ((name, labels), histogram) in &registry.histograms {
+            if *name != last_name {
+                out.push_str(&format!("# TYPE {} histogram\n", name));
+                last_name = name;
+            }
+            let mut cumulative = 0;
+            for (bound, count) in histogram.bounds.iter().zip(&histogram.counts) {
+                cumulative += count;
+                let le = bound.to_string();
+                out.push_str(&format!("{}_bucket{} {}\n", name, format_labels(labels, Some(&le)), cumulative));
+            }
+            out.push_str(&format!("{}_bucket{} {}\n", name, format_labels(labels, Some("+Inf")), histogram.count));
+            out.push_str(&format!("{}_sum{} {}\n", name, format_labels(labels, None), histogram.sum));
+            out.push_str(&format!("{}_count{} {}\n", name, format_labels(labels, None), histogram.count));
+        }
+
+        out
+    }
+
+    /// 生成 OTLP/HTTP JSON 格式的指标请求体（POST 到 collector 的 `/v1/metrics`）
+    pub fn otlp_payload(&self, service_name: &str) -> JsonValue {
+        let registry = self.lock();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+            .to_string();
+
+        let mut metrics: BTreeMap<&'static str, JsonValue> = BTreeMap::new();
+        for ((name, labels), value) in &registry.counters {
+            let point = json!({ "attributes": otlp_attributes(labels), "timeUnixNano": now, "asInt": value.to_string() });
+            metrics
+                .entry(name)
+                .or_insert_with(|| json!({ "name": name, "sum": { "dataPoints": [], "aggregationTemporality": 2, "isMonotonic": true } }))
+                ["sum"]["dataPoints"]
+                .as_array_mut()
+                .expect("dataPoints 为数组")
+                .push(point);
+        }
+        for ((name, labels), value) in &registry.gauges {
+            let point = json!({ "attributes": otlp_attributes(labels), "timeUnixNano": now, "asDouble": value });
+            metrics
+                .entry(name)
+                .or_insert_with(|| json!({ "name": name, "gauge": { "dataPoints": [] } }))
+                ["gauge"]["dataPoints"]
+                .as_array_mut()
+                .expect("dataPoints 为数组")
+                .push(point);
+        }
+        for ((name, labels), histogram) in &registry.histograms {
+            let point = json!({
+                "attributes": otlp_attributes(labels),
+                "timeUnixNano": now,
+                "count": histogram.count.to_string(),
+                "sum": histogram.sum,
+                "bucketCounts": histogram.counts.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+                "explicitBounds": histogram.bounds,
+            });
+            metrics
+                .entry(name)
+                .or_insert_with(|| json!({ "name": name, "unit": "s", "histogram": { "dataPoints": [], "aggregationTemporality": 2 } }))
+                ["histogram"]["dataPoints"]
+                .as_array_mut()
+                .expect("dataPoints 为数组")
+                .push(point);
+        }
+
+        json!({
+            "resourceMetrics": [{
+                "resource": {
+                    "attributes": [{ "key": "service.name", "value": { "stringValue": service_name } }]
+                },
+                "scopeMetrics": [{
+                    "scope": { "name": "codex-database", "version": env!("CARGO_PKG_VERSION") },
+                    "metrics": metrics.into_values().collect::<Vec<_>>(),
+                }]
+            }]
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Registry> {
+        self.registry.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn sorted(mut labels: Labels) -> Labels {
+    labels.sort();
+    labels
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+        .collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{}\"", le));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn otlp_attributes(labels: &Labels) -> JsonValue {
+    json!(labels
+        .iter()
+        .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+        .collect::<Vec<_>>())
+}
+
+/// 全局指标注册表
+pub fn global() -> &'static Telemetry {
+    static GLOBAL: OnceLock<Telemetry> = OnceLock::new();
+    GLOBAL.get_or_init(Telemetry::new)
+}
+
+// ============================================================================
+// 业务指标
+// ============================================================================
+
+/// 记录任务创建
+pub fn record_task_created() {
+    global().increment_counter(TASKS_CREATED_TOTAL, Vec::new(), 1);
+}
+
+/// 记录任务状态变更，completed 的计数即任务吞吐量
+pub fn record_task_status(status: &str) {
+    global().increment_counter(TASK_STATUS_TRANSITIONS_TOTAL, vec![("status", status.to_string())], 1);
+}
+
+/// 记录一次LLM调用耗时
+pub fn record_llm_latency(session_type: &str, elapsed: Duration) {
+    global().observe(
+        LLM_REQUEST_DURATION_SECONDS,
+        vec![("session_type", session_type.to_string())],
+        LLM_BUCKETS,
+        elapsed.as_secs_f64(),
+    );
+}
+
+/// 记录一次LLM响应缓存查询
+pub fn record_llm_cache(result: &str) {
+    global().increment_counter(LLM_CACHE_REQUESTS_TOTAL, vec![("result", result.to_string())], 1);
+}
+
+/// 设置队列深度
+pub fn set_queue_depth(queue: &str, depth: u64) {
+    global().set_gauge(QUEUE_DEPTH, vec![("queue", queue.to_string())], depth as f64);
+}
+
+/// 记录一次数据库操作耗时
+pub fn record_db_timing(operation: &'static str, elapsed: Duration) {
+    global().observe(
+        DB_OPERATION_DURATION_SECONDS,
+        vec![("operation", operation.to_string())],
+        DB_BUCKETS,
+        elapsed.as_secs_f64(),
+    );
+}
+
+/// 执行数据库操作并记录耗时
+pub async fn time_db<T>(operation: &'static str, future: impl std::future::Future<Output = T>) -> T {
+    if !global().is_enabled() {
+        return future.await;
+    }
+    let started = Instant::now();
+    let output = future.await;
+    record_db_timing(operation, started.elapsed());
+    output
+}
+
+/// 从数据库统计待处理任务和待执行会话数量，更新队列深度
+pub async fn refresh_queue_depths(db: &DatabaseConnection) -> Result<()> {
+    if !global().is_enabled() {
+        return Ok(());
+    }
+
+    let pending_tasks = task::Entity::find()
+        .filter(task::Column::Status.eq("pending"))
+        .count(db)
+        .await?;
+    let pending_sessions = execution_session::Entity::find()
+        .filter(execution_session::Column::Status.eq("pending"))
+        .count(db)
+        .await?;
+    let running_sessions = execution_session::Entity::find()
+        .filter(execution_session::Column::Status.eq("running"))
+        .count(db)
+        .await?;
+
+    set_queue_depth("pending_tasks", pending_tasks);
+    set_queue_depth("pending_sessions", pending_sessions);
+    set_queue_depth("running_sessions", running_sessions);
+    Ok(())
+}
+
+// ============================================================================
+// Prometheus 抓取端点
+// ============================================================================
+
+/// 运行中的 Prometheus 抓取端点，drop 不会停止，需显式调用 [`PrometheusServer::shutdown`]
+#[derive(Debug)]
+pub struct PrometheusServer {
+    local_addr: SocketAddr,
+    handle: JoinHandle<()>,
+}
+
+impl PrometheusServer {
+    /// 实际监听地址（绑定端口 0 时可据此获得分配的端口）
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// 停止抓取端点
+    pub fn shutdown(self) {
+        self.handle.abort();
+    }
+}
+
+/// 在 `addr` 上提供 `GET /metrics` 抓取端点
+pub async fn serve_prometheus(addr: SocketAddr) -> std::io::Result<PrometheusServer> {
+    let listener = TcpListener::bind(addr).await?;
+    let local_addr = listener.local_addr()?;
+    tracing::info!("Prometheus 抓取端点已启动: http://{}/metrics", local_addr);
+
+    let handle = tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    tracing::warn!("接受抓取连接失败: {}", e);
+                    continue;
+                }
+            };
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let path = request.split_whitespace().nth(1).unwrap_or("");
+
+                let (status, body) = if request.starts_with("GET ") && path == "/metrics" {
+                    ("200 OK", global().render_prometheus())
+                } else {
+                    ("404 Not Found", "not found\n".to_string())
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                if let Err(e) = stream.write_all(response.as_bytes()).await {
+                    tracing::debug!("写入抓取响应失败: {}", e);
+                }
+            });
+        }
+    });
+
+    Ok(PrometheusServer { local_addr, handle })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_records_nothing() {
+        let telemetry = Telemetry::new();
+        telemetry.increment_counter(TASKS_CREATED_TOTAL, Vec::new(), 1);
+        assert_eq!(telemetry.counter_value(TASKS_CREATED_TOTAL, Vec::new()), 0);
+
+        telemetry.set_enabled(true);
+        telemetry.increment_counter(TASKS_CREATED_TOTAL, Vec::new(), 2);
+        assert_eq!(telemetry.counter_value(TASKS_CREATED_TOTAL, Vec::new()), 2);
+    }
+
+    #[test]
+    fn test_render_prometheus() {
+        let telemetry = Telemetry::new();
+        telemetry.set_enabled(true);
+        telemetry.increment_counter(TASK_STATUS_TRANSITIONS_TOTAL, vec![("status", "completed".to_string())], 3);
+        telemetry.set_gauge(QUEUE_DEPTH, vec![("queue", "pending_tasks".to_string())], 7.0);
+        telemetry.observe(DB_OPERATION_DURATION_SECONDS, vec![("operation", "task_create".to_string())], DB_BUCKETS, 0.002);
+        telemetry.observe(DB_OPERATION_DURATION_SECONDS, vec![("operation", "task_create".to_string())], DB_BUCKETS, 2.0);
+
+        let text = telemetry.render_prometheus();
+        assert!(text.contains("# TYPE sker_task_status_transitions_total counter"));
+        assert!(text.contains("sker_task_status_transitions_total{status=\"completed\"} 3"));
+        assert!(text.contains("sker_queue_depth{queue=\"pending_tasks\"} 7"));
+        assert!(text.contains("sker_db_operation_duration_seconds_bucket{operation=\"task_create\",le=\"0.005\"} 1"));
+        assert!(text.contains("sker_db_operation_duration_seconds_bucket{operation=\"task_create\",le=\"+Inf\"} 2"));
+        assert!(text.contains("sker_db_operation_duration_seconds_count{operation=\"task_create\"} 2"));
+    }
+
+    #[test]
+    fn test_otlp_payload() {
+        let telemetry = Telemetry::new();
+        telemetry.set_enabled(true);
+        telemetry.increment_counter(TASKS_CREATED_TOTAL, Vec::new(), 5);
+        telemetry.observe(LLM_REQUEST_DURATION_SECONDS, vec![("session_type", "decomposition".to_string())], LLM_BUCKETS, 3.0);
+
+        let payload = telemetry.otlp_payload("sker-desktop");
+        let resource = &payload["resourceMetrics"][0];
+        assert_eq!(resource["resource"]["attributes"][0]["value"]["stringValue"], "sker-desktop");
+
+        let metrics = resource["scopeMetrics"][0]["metrics"].as_array().unwrap();
+        let counter = metrics.iter().find(|m| m["name"] == TASKS_CREATED_TOTAL).unwrap();
+        assert_eq!(counter["sum"]["dataPoints"][0]["asInt"], "5");
+        let histogram = metrics.iter().find(|m| m["name"] == LLM_REQUEST_DURATION_SECONDS).unwrap();
+        assert_eq!(histogram["histogram"]["dataPoints"][0]["count"], "1");
+        assert_eq!(histogram["histogram"]["dataPoints"][0]["bucketCounts"].as_array().unwrap().len(), LLM_BUCKETS.len() + 1);
+    }
+}
@@ -0,0 +1,162 @@
+//! 多租户隔离
+//!
+//! 仓储默认只按显式ID过滤，调用方忘记做权限检查时可以读写其他用户的数据。[`TenantScope`]
+//! 限定仓储只能访问某个租户的项目及其下属数据，条件直接加在SQL的WHERE子句中：
+//! - [`TenantScope::User`]：用户创建的项目和作为成员参与的项目；
+//! - [`TenantScope::Organization`]：归属于组织的项目。
+//!
+//! 支持租户范围的仓储通过 `with_scope` 设置范围，设置后：
+//! - 查询只返回范围内的记录，范围外的记录视同不存在；
+//! - 更新和删除只作用于范围内的记录，范围外的记录返回 [`DatabaseError::EntityNotFound`]；
+//! - 在范围外的项目下创建记录同样返回 [`DatabaseError::EntityNotFound`]。
+//!
+//! 没有 `project_id` 列的表按归属关系限定：执行日志跟随执行会话，人工决策跟随冲突，
+//! Agent按创建用户（[`TenantScope::User`]）或所属组织（[`TenantScope::Organization`]）限定。
+//! 未关联项目的旧冲突记录不在任何范围内。
+//!
+//! 未设置范围的仓储不受限制，供调度器、守护进程等系统任务使用。
+//!
+//! [`DatabaseError::EntityNotFound`]: crate::DatabaseError::EntityNotFound
+
+use sea_orm::sea_query::Query;
+use sea_orm::{ColumnTrait, Condition, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    entities::{agent, conflict, execution_session, project, project_member},
+    DatabaseError, Result,
+};
+
+/// 租户范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "id", rename_all = "snake_case")]
+pub enum TenantScope {
+    /// 用户创建或作为成员参与的项目
+    User(Uuid),
+    /// 归属于组织的项目
+    Organization(Uuid),
+}
+
+impl TenantScope {
+    pub fn user(user_id: Uuid) -> Self {
+        TenantScope::User(user_id)
+    }
+
+    pub fn organization(organization_id: Uuid) -> Self {
+        TenantScope::Organization(organization_id)
+    }
+
+    /// `projects` 表上的范围条件
+    pub fn project_condition(&self) -> Condition {
+        match *self {
+            TenantScope::User(user_id) => Condition::any()
+                .add(project::Column::UserId.eq(user_id))
+                .add(
+                    project::Column::ProjectId.in_subquery(
+                        Query::select()
+                            .column(project_member::Column::ProjectId)
+                            .from(project_member::Entity)
+                            .and_where(project_member::Column::UserId.eq(user_id))
+                            .to_owned(),
+                    ),
+                ),
+            TenantScope::Organization(organization_id) => {
+                Condition::all().add(project::Column::OrganizationId.eq(organization_id))
+            }
+        }
+    }
+
+    /// 通过 `project_id` 列归属于项目的表上的范围条件，`project_id` 为空的记录不在任何范围内
+    pub fn project_ref_condition<C: ColumnTrait>(&self, project_column: C) -> Condition {
+        Condition::all().add(
+            project_column.in_subquery(
+                Query::select()
+                    .column(project::Column::ProjectId)
+                    .from(project::Entity)
+                    .cond_where(self.project_condition())
+                    .to_owned(),
+            ),
+        )
+    }
+
+    /// 通过执行会话归属于项目的表上的范围条件
+    pub fn session_ref_condition<C: ColumnTrait>(&self, session_column: C) -> Condition {
+        Condition::all().add(
+            session_column.in_subquery(
+                Query::select()
+                    .column(execution_session::Column::SessionId)
+                    .from(execution_session::Entity)
+                    .cond_where(self.project_ref_condition(execution_session::Column::ProjectId))
+                    .to_owned(),
+            ),
+        )
+    }
+
+    /// 通过冲突归属于项目的表上的范围条件
+    pub fn conflict_ref_condition<C: ColumnTrait>(&self, conflict_column: C) -> Condition {
+        Condition::all().add(
+            conflict_column.in_subquery(
+                Query::select()
+                    .column(conflict::Column::ConflictId)
+                    .from(conflict::Entity)
+                    .cond_where(self.project_ref_condition(conflict::Column::ProjectId))
+                    .to_owned(),
+            ),
+        )
+    }
+
+    /// `agents` 表上的范围条件：用户创建的Agent或组织的共享Agent
+    pub fn agent_condition(&self) -> Condition {
+        match *self {
+            TenantScope::User(user_id) => Condition::all().add(agent::Column::UserId.eq(user_id)),
+            TenantScope::Organization(organization_id) => {
+                Condition::all().add(agent::Column::OrganizationId.eq(organization_id))
+            }
+        }
+    }
+
+    /// 校验项目在范围内，范围外的项目视同不存在
+    pub async fn ensure_project<C: ConnectionTrait>(&self, db: &C, project_id: Uuid) -> Result<()> {
+        let visible = project::Entity::find_by_id(project_id)
+            .filter(self.project_condition())
+            .count(db)
+            .await?;
+        if visible == 0 {
+            return Err(DatabaseError::entity_not_found("Project", project_id));
+        }
+        Ok(())
+    }
+
+    /// 校验执行会话在范围内，范围外的会话视同不存在
+    pub async fn ensure_session<C: ConnectionTrait>(&self, db: &C, session_id: Uuid) -> Result<()> {
+        let visible = execution_session::Entity::find_by_id(session_id)
+            .filter(self.project_ref_condition(execution_session::Column::ProjectId))
+            .count(db)
+            .await?;
+        if visible == 0 {
+            return Err(DatabaseError::entity_not_found("ExecutionSession", session_id));
+        }
+        Ok(())
+    }
+
+    /// 校验冲突在范围内，范围外的冲突视同不存在
+    pub async fn ensure_conflict<C: ConnectionTrait>(&self, db: &C, conflict_id: Uuid) -> Result<()> {
+        let visible = conflict::Entity::find_by_id(conflict_id)
+            .filter(self.project_ref_condition(conflict::Column::ProjectId))
+            .count(db)
+            .await?;
+        if visible == 0 {
+            return Err(DatabaseError::entity_not_found("Conflict", conflict_id));
+        }
+        Ok(())
+    }
+}
+
+/// 可选范围的条件，未设置范围时不附加任何条件
+pub(crate) fn scoped_condition(
+    scope: Option<&TenantScope>,
+    condition: impl FnOnce(&TenantScope) -> Condition,
+) -> Condition {
+    scope.map(condition).unwrap_or_else(Condition::all)
+}
@@ -0,0 +1,365 @@
+//! 需求追踪矩阵
+//!
+//! 需求文档按 Markdown 标题划分章节，任务通过 `requirement_coverage` 关联到生成它的章节。
+//! 章节本身或任一下级章节关联了未取消的任务即视为已覆盖；任务本身或任一上级任务
+//! 关联了章节即视为可追溯，否则为孤立任务。
+//!
+//! 任务取消后如果有章节因此失去覆盖，记录 `RequirementCoverageDropped` 事件。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        domain_event::{AggregateType, DomainEventType},
+        requirement_coverage, requirement_document, task,
+    },
+    repository::{
+        domain_event_repository::CreateDomainEventData,
+        requirement_coverage_repository::CreateCoverageData,
+        DomainEventRepository, RequirementCoverageRepository, RequirementDocumentRepository, TaskRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 没有标题的文档整体作为一个章节
+pub const WHOLE_DOCUMENT_SECTION: &str = "document";
+
+/// 需求章节
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequirementSection {
+    /// 章节标识，由标题生成，同名标题依次追加 -1、-2
+    pub section_id: String,
+    pub title: String,
+    /// 标题级别（1-6），整篇文档为 0
+    pub level: u8,
+    /// 上级章节标识
+    pub parent_id: Option<String>,
+}
+
+/// 章节覆盖情况
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionCoverage {
+    pub document_id: Uuid,
+    pub document_title: String,
+    pub section_id: String,
+    pub section_title: String,
+    pub level: u8,
+    /// 直接关联到该章节的未取消任务
+    pub task_ids: Vec<Uuid>,
+    /// 章节本身或下级章节是否有未取消的任务
+    pub covered: bool,
+}
+
+/// 没有关联任何需求章节的任务
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrphanTask {
+    pub task_id: Uuid,
+    pub title: String,
+    pub status: String,
+}
+
+/// 需求覆盖报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub project_id: Uuid,
+    pub total_sections: usize,
+    pub covered_sections: usize,
+    /// 已覆盖章节占比，没有需求章节时为 1
+    pub coverage_ratio: f64,
+    /// 全部章节，按文档创建顺序和章节顺序排列
+    pub sections: Vec<SectionCoverage>,
+    /// 未覆盖的章节
+    pub uncovered: Vec<SectionCoverage>,
+    /// 未取消的孤立任务
+    pub orphan_tasks: Vec<OrphanTask>,
+    /// 指向已不存在章节的覆盖关系（文档修改后标题变化）
+    pub stale_links: Vec<requirement_coverage::Model>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// 解析需求文档的章节
+///
+/// 识别 `#` 至 `######` 标题，跳过代码块；没有标题时整篇文档作为一个章节。
+pub fn parse_sections(document_title: &str, content: &str) -> Vec<RequirementSection> {
+    let mut sections = Vec::new();
+    let mut used_ids: HashMap<String, usize> = HashMap::new();
+    let mut stack: Vec<(u8, String)> = Vec::new();
+    let mut in_code_block = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        let Some((level, title)) = parse_heading(trimmed) else {
+            continue;
+        };
+
+        let slug = slugify(&title);
+        let count = used_ids.entry(slug.clone()).or_insert(0);
+        let section_id = if *count == 0 { slug } else { format!("{}-{}", slug, count) };
+        *count += 1;
+
+        while stack.last().is_some_and(|(parent_level, _)| *parent_level >= level) {
+            stack.pop();
+        }
+        sections.push(RequirementSection {
+            section_id: section_id.clone(),
+            title,
+            level,
+            parent_id: stack.last().map(|(_, id)| id.clone()),
+        });
+        stack.push((level, section_id));
+    }
+
+    if sections.is_empty() {
+        sections.push(RequirementSection {
+            section_id: WHOLE_DOCUMENT_SECTION.to_string(),
+            title: document_title.to_string(),
+            level: 0,
+            parent_id: None,
+        });
+    }
+    sections
+}
+
+/// 读取需求文档的章节
+pub async fn document_sections(db: &DatabaseConnection, document_id: Uuid) -> Result<Vec<RequirementSection>> {
+    let document = find_document(db, document_id).await?;
+    Ok(parse_sections(&document.title, &document.content))
+}
+
+/// 记录任务由需求文档的某个章节生成
+pub async fn link_task_to_section(
+    db: &DatabaseConnection,
+    document_id: Uuid,
+    section_id: &str,
+    task_id: Uuid,
+) -> Result<requirement_coverage::Model> {
+    let document = find_document(db, document_id).await?;
+    let task = TaskRepository::new(db.clone())
+        .find_by_id(task_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Task", task_id))?;
+    if task.project_id != document.project_id {
+        return Err(DatabaseError::validation("任务与需求文档不属于同一项目"));
+    }
+
+    let section = parse_sections(&document.title, &document.content)
+        .into_iter()
+        .find(|section| section.section_id == section_id)
+        .ok_or_else(|| DatabaseError::validation(format!("需求文档中不存在章节: {}", section_id)))?;
+
+    RequirementCoverageRepository::new(db.clone())
+        .create(CreateCoverageData {
+            project_id: document.project_id,
+            document_id,
+            section_id: section.section_id,
+            section_title: section.title,
+            task_id,
+        })
+        .await
+}
+
+/// 生成项目的需求覆盖报告
+pub async fn coverage_report(db: &DatabaseConnection, project_id: Uuid) -> Result<CoverageReport> {
+    let documents = RequirementDocumentRepository::new(db.clone())
+        .find_by_project(project_id)
+        .await?;
+    let tasks: HashMap<Uuid, task::Model> = TaskRepository::new(db.clone())
+        .find_by_project(project_id)
+        .await?
+        .into_iter()
+        .map(|task| (task.task_id, task))
+        .collect();
+    let links = RequirementCoverageRepository::new(db.clone())
+        .find_by_project(project_id)
+        .await?;
+    let is_active = |task_id: &Uuid| tasks.get(task_id).is_some_and(|task| task.status != "cancelled");
+
+    let mut sections = Vec::new();
+    let mut stale_links = Vec::new();
+    for document in &documents {
+        let parsed = parse_sections(&document.title, &document.content);
+        let known: HashSet<&str> = parsed.iter().map(|section| section.section_id.as_str()).collect();
+
+        let mut direct: HashMap<&str, Vec<Uuid>> = HashMap::new();
+        for link in links.iter().filter(|link| link.document_id == document.document_id) {
+            if !known.contains(link.section_id.as_str()) {
+                stale_links.push(link.clone());
+            } else if is_active(&link.task_id) {
+                direct.entry(link.section_id.as_str()).or_default().push(link.task_id);
+            }
+        }
+
+        // 下级章节总在上级之后，倒序遍历即可把覆盖状态传递给上级
+        let mut covered: HashSet<&str> = direct.keys().copied().collect();
+        for section in parsed.iter().rev() {
+            if let Some(parent_id) = &section.parent_id {
+                if covered.contains(section.section_id.as_str()) {
+                    covered.insert(parent_id.as_str());
+                }
+            }
+        }
+
+        for section in &parsed {
+            sections.push(SectionCoverage {
+                document_id: document.document_id,
+                document_title: document.title.clone(),
+                section_id: section.section_id.clone(),
+                section_title: section.title.clone(),
+                level: section.level,
+                task_ids: direct.get(section.section_id.as_str()).cloned().unwrap_or_default(),
+                covered: covered.contains(section.section_id.as_str()),
+            });
+        }
+    }
+
+    // 任务本身或任一上级任务有关联即可追溯
+    let linked: HashSet<Uuid> = links.iter().map(|link| link.task_id).collect();
+    let is_traced = |task: &task::Model| {
+        let mut current = Some(task);
+        let mut visited = HashSet::new();
+        while let Some(task) = current {
+            if linked.contains(&task.task_id) {
+                return true;
+            }
+            if !visited.insert(task.task_id) {
+                break;
+            }
+            current = task.parent_task_id.and_then(|parent_id| tasks.get(&parent_id));
+        }
+        false
+    };
+    let mut orphan_tasks: Vec<OrphanTask> = tasks
+        .values()
+        .filter(|task| task.status != "cancelled" && !is_traced(task))
+        .map(|task| OrphanTask {
+            task_id: task.task_id,
+            title: task.title.clone(),
+            status: task.status.clone(),
+        })
+        .collect();
+    orphan_tasks.sort_by(|a, b| a.title.cmp(&b.title));
+
+    let uncovered: Vec<SectionCoverage> = sections.iter().filter(|section| !section.covered).cloned().collect();
+    let total_sections = sections.len();
+    let covered_sections = total_sections - uncovered.len();
+    Ok(CoverageReport {
+        project_id,
+        total_sections,
+        covered_sections,
+        coverage_ratio: if total_sections == 0 {
+            1.0
+        } else {
+            covered_sections as f64 / total_sections as f64
+        },
+        sections,
+        uncovered,
+        orphan_tasks,
+        stale_links,
+        generated_at: Utc::now(),
+    })
+}
+
+/// 任务取消后检查其覆盖的章节，失去覆盖时记录事件
+pub(crate) async fn record_coverage_drop(db: &DatabaseConnection, task: &task::Model) -> Result<()> {
+    let links = RequirementCoverageRepository::new(db.clone())
+        .find_by_task(task.task_id)
+        .await?;
+    if links.is_empty() {
+        return Ok(());
+    }
+
+    let report = coverage_report(db, task.project_id).await?;
+    let linked: HashSet<(Uuid, &str)> = links
+        .iter()
+        .map(|link| (link.document_id, link.section_id.as_str()))
+        .collect();
+    let lost: Vec<_> = report
+        .uncovered
+        .iter()
+        .filter(|section| linked.contains(&(section.document_id, section.section_id.as_str())))
+        .map(|section| {
+            json!({
+                "document_id": section.document_id,
+                "document_title": section.document_title,
+                "section_id": section.section_id,
+                "section_title": section.section_title,
+            })
+        })
+        .collect();
+    if lost.is_empty() {
+        return Ok(());
+    }
+
+    let repo = DomainEventRepository::new(db.clone());
+    let version = repo.get_latest_version(task.project_id).await? + 1;
+    repo.create(CreateDomainEventData {
+        aggregate_type: AggregateType::Project.to_string(),
+        aggregate_id: task.project_id,
+        event_type: DomainEventType::RequirementCoverageDropped.to_string(),
+        event_data: json!({
+            "task_id": task.task_id,
+            "task_title": task.title,
+            "uncovered_sections": lost,
+            "covered_sections": report.covered_sections,
+            "total_sections": report.total_sections,
+            "coverage_ratio": report.coverage_ratio,
+        }),
+        event_version: version,
+    })
+    .await?;
+    Ok(())
+}
+
+async fn find_document(db: &DatabaseConnection, document_id: Uuid) -> Result<requirement_document::Model> {
+    RequirementDocumentRepository::new(db.clone())
+        .find_by_id(document_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("RequirementDocument", document_id))
+}
+
+/// 解析 "## 标题" 形式的标题行
+fn parse_heading(line: &str) -> Option<(u8, String)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.starts_with(' ') && !rest.starts_with('\t') {
+        return None;
+    }
+    let title = rest.trim().trim_end_matches('#').trim();
+    (!title.is_empty()).then(|| (level as u8, title.to_string()))
+}
+
+/// 标题转换为章节标识：小写，空白替换为 "-"，去掉标点
+fn slugify(title: &str) -> String {
+    let slug: String = title
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                Some(c)
+            } else if c.is_whitespace() {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect();
+    if slug.is_empty() {
+        "section".to_string()
+    } else {
+        slug
+    }
+}
@@ -0,0 +1,430 @@
+//! 基于 TOTP 的两步验证
+//!
+//! 登记流程：[`TwoFactorService::begin_enrollment`] 生成密钥和 `otpauth://` 配置地址（前端渲染为二维码），
+//! 用户在验证器应用中输入一次验证码后 [`TwoFactorService::confirm_enrollment`] 启用两步验证并返回一组备用恢复码。
+//!
+//! 登录时 [`TwoFactorService::verify`] 既接受 TOTP 验证码（允许前后各一个时间步的时钟偏差，同一时间步不能重复使用），
+//! 也接受备用恢复码（每个只能使用一次）。两者都通过条件更新消耗，并发登录不能重复使用同一个验证码。丢失验证器的用户用恢复码登录后，可以停用并重新登记。
+//! 连续校验失败 [`MAX_FAILED_ATTEMPTS`] 次后锁定 [`LOCKOUT_MINUTES`] 分钟，锁定期间拒绝所有验证码。
+//! 被强制要求使用两步验证的用户不能停用。
+//!
+//! 密钥和恢复码使用操作系统的安全随机数生成，恢复码只保存加盐的 HMAC-SHA256 摘要。
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    entities::user_two_factor,
+    repository::UserTwoFactorRepository,
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 验证码位数
+pub const TOTP_DIGITS: u32 = 6;
+
+/// 时间步长度（秒）
+pub const TOTP_PERIOD_SECS: i64 = 30;
+
+/// 允许的时钟偏差（时间步数）
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+
+/// 每次生成的备用恢复码数量
+pub const BACKUP_CODE_COUNT: usize = 10;
+
+/// 恢复码的随机字节数（80 位，Base32 编码为 16 个字符）
+const BACKUP_CODE_BYTES: usize = 10;
+
+/// 恢复码摘要的盐长度（字节）
+const BACKUP_CODE_SALT_BYTES: usize = 16;
+
+/// 加盐摘要的前缀，没有前缀的是旧版本保存的无盐 SHA-256 摘要
+const SALTED_HASH_PREFIX: &str = "hmac-sha256";
+
+/// 锁定前允许的连续失败次数
+pub const MAX_FAILED_ATTEMPTS: i32 = 5;
+
+/// 连续失败过多后的锁定时长（分钟）
+pub const LOCKOUT_MINUTES: i64 = 15;
+
+/// 密钥长度（字节），与 HMAC-SHA1 输出长度一致
+const SECRET_BYTES: usize = 20;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// 新登记的密钥和配置地址
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TotpEnrollment {
+    /// Base32 编码的密钥，供无法扫码时手动输入
+    pub secret: String,
+    /// `otpauth://totp/...` 配置地址
+    pub provisioning_uri: String,
+}
+
+/// 用户的两步验证状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TwoFactorStatus {
+    pub enabled: bool,
+    pub required: bool,
+    /// 已生成密钥但尚未确认
+    pub pending_enrollment: bool,
+    pub backup_codes_remaining: usize,
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+/// 通过校验的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TwoFactorMethod {
+    Totp,
+    BackupCode,
+}
+
+/// 生成新的随机密钥（Base32 编码）
+pub fn generate_secret() -> String {
+    base32_encode(&random_bytes::<SECRET_BYTES>())
+}
+
+/// 时间点所在的 TOTP 时间步
+pub fn time_step(at: DateTime<Utc>) -> i64 {
+    at.timestamp().div_euclid(TOTP_PERIOD_SECS)
+}
+
+/// 计算指定时间步的验证码，密钥不是合法 Base32 时返回 `None`
+pub fn totp_code(secret: &str, step: i64) -> Option<String> {
+    let key = base32_decode(secret)?;
+    let mut mac = Hmac::<Sha1>::new_from_slice(&key).ok()?;
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // RFC 4226 动态截断
+    let offset = usize::from(digest[digest.len() - 1] & 0x0f);
+    let binary = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]])
+        & 0x7fff_ffff;
+    Some(format!("{:0width$}", binary % 10u32.pow(TOTP_DIGITS), width = TOTP_DIGITS as usize))
+}
+
+/// 生成验证器应用使用的配置地址
+pub fn provisioning_uri(issuer: &str, account_name: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account_name),
+        secret,
+        percent_encode(issuer),
+        TOTP_DIGITS,
+        TOTP_PERIOD_SECS
+    )
+}
+
+/// 两步验证服务
+pub struct TwoFactorService {
+    repo: UserTwoFactorRepository,
+}
+
+impl TwoFactorService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            repo: UserTwoFactorRepository::new(db),
+        }
+    }
+
+    /// 查询用户的两步验证状态
+    pub async fn status(&self, user_id: Uuid) -> Result<TwoFactorStatus> {
+        Ok(match self.repo.find_by_user(user_id).await? {
+            Some(record) => TwoFactorStatus {
+                enabled: record.enabled,
+                required: record.required,
+                pending_enrollment: !record.enabled && record.secret.is_some(),
+                backup_codes_remaining: backup_code_hashes(&record).len(),
+                confirmed_at: record.confirmed_at.map(|time| time.with_timezone(&Utc)),
+            },
+            None => TwoFactorStatus {
+                enabled: false,
+                required: false,
+                pending_enrollment: false,
+                backup_codes_remaining: 0,
+                confirmed_at: None,
+            },
+        })
+    }
+
+    /// 登录时是否需要校验两步验证码
+    pub async fn is_enabled(&self, user_id: Uuid) -> Result<bool> {
+        Ok(self.repo.find_by_user(user_id).await?.is_some_and(|record| record.enabled))
+    }
+
+    /// 开始登记，生成新的密钥；重复调用会替换尚未确认的密钥
+    pub async fn begin_enrollment(&self, user_id: Uuid, issuer: &str, account_name: &str) -> Result<TotpEnrollment> {
+        let secret = generate_secret();
+        self.repo.start_enrollment(user_id, secret.clone()).await?;
+        Ok(TotpEnrollment {
+            provisioning_uri: provisioning_uri(issuer, account_name, &secret),
+            secret,
+        })
+    }
+
+    /// 用验证器应用给出的验证码确认登记，返回备用恢复码明文（只返回这一次）
+    pub async fn confirm_enrollment(&self, user_id: Uuid, code: &str) -> Result<Vec<String>> {
+        self.confirm_enrollment_at(user_id, code, Utc::now()).await
+    }
+
+    /// 在指定时间确认登记
+    pub async fn confirm_enrollment_at(&self, user_id: Uuid, code: &str, now: DateTime<Utc>) -> Result<Vec<String>> {
+        let record = self.repo.find_by_user(user_id).await?
+            .filter(|record| !record.enabled && record.secret.is_some())
+            .ok_or_else(|| DatabaseError::business_logic("没有待确认的两步验证登记"))?;
+        let step = matching_step(&record, code, now)
+            .ok_or_else(|| DatabaseError::validation("验证码无效"))?;
+
+        let codes = generate_backup_codes();
+        self.repo.enable(user_id, codes.iter().map(|code| hash_backup_code(code)).collect()).await?;
+        self.repo.record_step(user_id, step).await?;
+        Ok(codes)
+    }
+
+    /// 校验登录时提交的验证码或备用恢复码
+    pub async fn verify(&self, user_id: Uuid, code: &str) -> Result<TwoFactorMethod> {
+        self.verify_at(user_id, code, Utc::now()).await
+    }
+
+    /// 在指定时间校验验证码或备用恢复码
+    ///
+    /// 锁定期间直接拒绝；校验失败计入失败次数，达到上限后锁定
+    pub async fn verify_at(&self, user_id: Uuid, code: &str, now: DateTime<Utc>) -> Result<TwoFactorMethod> {
+        let record = self.repo.find_by_user(user_id).await?
+            .filter(|record| record.enabled)
+            .ok_or_else(|| DatabaseError::business_logic("未启用两步验证"))?;
+        if let Some(locked_until) = record.locked_until.map(|time| time.with_timezone(&Utc)) {
+            if locked_until > now {
+                return Err(locked(locked_until));
+            }
+        }
+
+        let method = self.check_code(&record, code, now).await?;
+        if let Some(method) = method {
+            if record.failed_attempts > 0 || record.locked_until.is_some() {
+                self.repo.reset_failures(user_id).await?;
+            }
+            return Ok(method);
+        }
+
+        let lock_until = now + Duration::minutes(LOCKOUT_MINUTES);
+        let updated = self.repo.record_failure(user_id, MAX_FAILED_ATTEMPTS, lock_until).await?;
+        if updated.locked_until.is_some_and(|time| time.with_timezone(&Utc) > now) {
+            tracing::warn!("用户 {} 两步验证连续失败 {} 次，已锁定", user_id, MAX_FAILED_ATTEMPTS);
+            return Err(locked(lock_until));
+        }
+        Err(DatabaseError::validation("验证码无效"))
+    }
+
+    /// 依次尝试 TOTP 验证码和备用恢复码，匹配时记录使用
+    async fn check_code(
+        &self,
+        record: &user_two_factor::Model,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<TwoFactorMethod>> {
+        let user_id = record.user_id;
+        if let Some(step) = matching_step(record, code, now) {
+            // 并发提交同一时间步的验证码时只有一方能写入，另一方按无效验证码处理
+            if self.repo.consume_step(user_id, step).await? {
+                return Ok(Some(TwoFactorMethod::Totp));
+            }
+            return Ok(None);
+        }
+
+        let mut record = record.clone();
+        loop {
+            let mut hashes = backup_code_hashes(&record);
+            let Some(index) = hashes.iter().position(|stored| backup_code_matches(stored, code)) else {
+                return Ok(None);
+            };
+            hashes.remove(index);
+            let remaining = hashes.len();
+            if self.repo.consume_backup_code(user_id, &record.backup_code_hashes, hashes).await? {
+                tracing::info!("用户 {} 使用了备用恢复码，剩余 {} 个", user_id, remaining);
+                return Ok(Some(TwoFactorMethod::BackupCode));
+            }
+
+            // 摘要列表已被并发修改（可能正是同一个恢复码被使用），重新读取后再匹配
+            let current = self.repo.find_by_user(user_id).await?
+                .filter(|current| current.enabled)
+                .ok_or_else(|| DatabaseError::business_logic("未启用两步验证"))?;
+            if current.backup_code_hashes == record.backup_code_hashes {
+                return Ok(None);
+            }
+            record = current;
+        }
+    }
+
+    /// 校验验证码后重新生成备用恢复码，旧的恢复码全部失效
+    pub async fn regenerate_backup_codes(&self, user_id: Uuid, code: &str) -> Result<Vec<String>> {
+        self.verify(user_id, code).await?;
+        let codes = generate_backup_codes();
+        self.repo.replace_backup_codes(user_id, codes.iter().map(|code| hash_backup_code(code)).collect()).await?;
+        Ok(codes)
+    }
+
+    /// 校验验证码后停用两步验证；被强制要求使用两步验证的用户不能停用
+    pub async fn disable(&self, user_id: Uuid, code: &str) -> Result<TwoFactorStatus> {
+        if self.repo.find_by_user(user_id).await?.is_some_and(|record| record.required) {
+            return Err(DatabaseError::business_logic("已被要求使用两步验证，不能停用"));
+        }
+        self.verify(user_id, code).await?;
+        self.repo.disable(user_id).await?;
+        self.status(user_id).await
+    }
+
+    /// 被要求使用两步验证但尚未登记，此时只允许完成登记
+    pub async fn setup_required(&self, user_id: Uuid) -> Result<bool> {
+        Ok(self.repo.find_by_user(user_id).await?.is_some_and(|record| record.required && !record.enabled))
+    }
+
+    /// 设置是否强制该用户使用两步验证
+    pub async fn set_required(&self, user_id: Uuid, required: bool) -> Result<TwoFactorStatus> {
+        self.repo.set_required(user_id, required).await?;
+        self.status(user_id).await
+    }
+}
+
+/// 在允许的偏差范围内查找与验证码匹配、且晚于上次使用的时间步
+fn matching_step(record: &user_two_factor::Model, code: &str, now: DateTime<Utc>) -> Option<i64> {
+    let secret = record.secret.as_deref()?;
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let current = time_step(now);
+    (current - ALLOWED_DRIFT_STEPS..=current + ALLOWED_DRIFT_STEPS)
+        .filter(|step| record.last_used_step.is_none_or(|last| *step > last))
+        .find(|step| totp_code(secret, *step).as_deref() == Some(code))
+}
+
+fn backup_code_hashes(record: &user_two_factor::Model) -> Vec<String> {
+    serde_json::from_value(record.backup_code_hashes.clone()).unwrap_or_default()
+}
+
+fn locked(until: DateTime<Utc>) -> DatabaseError {
+    DatabaseError::business_logic(format!(
+        "两步验证失败次数过多，已锁定到 {}",
+        until.format("%Y-%m-%d %H:%M:%S UTC")
+    ))
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// 生成 `xxxx-xxxx-xxxx-xxxx` 形式的备用恢复码
+fn generate_backup_codes() -> Vec<String> {
+    (0..BACKUP_CODE_COUNT)
+        .map(|_| {
+            let raw = base32_encode(&random_bytes::<BACKUP_CODE_BYTES>()).to_ascii_lowercase();
+            raw.as_bytes()
+                .chunks(4)
+                .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .collect()
+}
+
+/// 恢复码的加盐摘要：`hmac-sha256$<盐>$<摘要>`，以随机盐为密钥计算 HMAC-SHA256
+fn hash_backup_code(code: &str) -> String {
+    let salt = random_bytes::<BACKUP_CODE_SALT_BYTES>();
+    format!(
+        "{}${}${}",
+        SALTED_HASH_PREFIX,
+        base32_encode(&salt),
+        base32_encode(&salted_digest(&salt, code))
+    )
+}
+
+/// 比较恢复码与保存的摘要，兼容旧版本的无盐 SHA-256 摘要
+fn backup_code_matches(stored: &str, code: &str) -> bool {
+    let mut parts = stored.split('$');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(SALTED_HASH_PREFIX), Some(salt), Some(digest), None) => {
+            let (Some(salt), Some(digest)) = (base32_decode(salt), base32_decode(digest)) else {
+                return false;
+            };
+            let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(&salt) else {
+                return false;
+            };
+            mac.update(normalize_backup_code(code).as_bytes());
+            // 常量时间比较
+            mac.verify_slice(&digest).is_ok()
+        }
+        _ => stored == format!("{:x}", Sha256::digest(normalize_backup_code(code).as_bytes())),
+    }
+}
+
+fn salted_digest(salt: &[u8], code: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(salt).expect("HMAC 接受任意长度的密钥");
+    mac.update(normalize_backup_code(code).as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// 忽略大小写和分隔符
+fn normalize_backup_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut output = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in bytes {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(char::from(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize]));
+        }
+    }
+    if bits > 0 {
+        output.push(char::from(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize]));
+    }
+    output
+}
+
+fn base32_decode(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in input.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = BASE32_ALPHABET.iter().position(|a| char::from(*a) == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push(((buffer >> bits) & 0xff) as u8);
+        }
+    }
+    if output.is_empty() {
+        None
+    } else {
+        Some(output)
+    }
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => char::from(byte).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
@@ -0,0 +1,604 @@
+//! 外部 webhook 接入
+//!
+//! 除了向外发送 webhook，sker 也接收 GitHub / GitLab 的仓库推送、CI 状态和议题更新，
+//! 把它们转换为领域事件写入事件总线：
+//! - [`WebhookIngestor::verify`] 按来源校验签名（GitHub 的 HMAC-SHA256、GitLab 的令牌），
+//!   未配置密钥的来源拒绝所有投递；
+//! - [`map_payload`] 按来源把载荷映射为 [`ExternalEvent`]，CI 状态同时写入 `ci_check_runs`；
+//! - 签名有效但无法映射的载荷写入 `webhook_dead_letters`，映射规则补充后由
+//!   [`replay_dead_letter`] 重放；
+//! - 来源重发的投递（相同投递ID）按幂等键返回首次处理的结果，不会重复写入事件。
+//!
+//! [`serve`] 提供最小的 HTTP 接入端点：`POST /webhooks/{source}/{project_id}`。
+
+use codex_multi_agent::{
+    CiStatusReceivedEvent, EventFactory, ExternalIssueUpdatedEvent, GitPushReceivedEvent, WebhookSource,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use uuid::Uuid;
+
+use crate::{
+    ci_integration::{self, CiCheckUpdate},
+    idempotency::IdempotencyStore,
+    entities::{
+        domain_event::{AggregateType, DomainEventType},
+        project, webhook_dead_letter,
+    },
+    repository::{
+        domain_event_repository::CreateDomainEventData, webhook_dead_letter_repository::CreateDeadLetterData,
+        DomainEventRepository, ExecutionSessionRepository, WebhookDeadLetterRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::EntityTrait;
+
+/// 接入端点允许的最大载荷字节数
+pub const MAX_PAYLOAD_BYTES: usize = 5 * 1024 * 1024;
+
+/// 接入端点允许的请求行或单个请求头的最大字节数
+pub const MAX_HEADER_LINE_BYTES: usize = 8 * 1024;
+
+/// 接入端点允许的最大请求头数量
+pub const MAX_HEADER_COUNT: usize = 64;
+
+/// 收到的一次 webhook 投递
+#[derive(Debug, Clone)]
+pub struct WebhookDelivery {
+    pub source: WebhookSource,
+    pub project_id: Uuid,
+    /// 来源的事件名称，GitHub 必须提供（X-GitHub-Event）
+    pub event_name: Option<String>,
+    /// 来源的投递ID（X-GitHub-Delivery / X-Gitlab-Event-UUID）
+    pub delivery_id: Option<String>,
+    /// 签名（X-Hub-Signature-256）或令牌（X-Gitlab-Token）
+    pub signature: Option<String>,
+    /// 原始载荷
+    pub payload: String,
+}
+
+/// 从外部载荷映射出的事件
+#[derive(Debug, Clone)]
+pub enum ExternalEvent {
+    /// 仓库推送
+    GitPush(GitPushReceivedEvent),
+    /// CI 状态，附带写入检查结果所需的数据
+    CiStatus {
+        event: CiStatusReceivedEvent,
+        update: CiCheckUpdate,
+    },
+    /// 议题更新
+    IssueUpdated(ExternalIssueUpdatedEvent),
+}
+
+impl ExternalEvent {
+    /// 对应的领域事件类型
+    pub fn domain_event_type(&self) -> DomainEventType {
+        match self {
+            ExternalEvent::GitPush(_) => DomainEventType::GitPushReceived,
+            ExternalEvent::CiStatus { .. } => DomainEventType::CiStatusReceived,
+            ExternalEvent::IssueUpdated(_) => DomainEventType::ExternalIssueUpdated,
+        }
+    }
+
+    fn event_data(&self) -> Result<JsonValue> {
+        Ok(match self {
+            ExternalEvent::GitPush(event) => serde_json::to_value(event)?,
+            ExternalEvent::CiStatus { event, .. } => serde_json::to_value(event)?,
+            ExternalEvent::IssueUpdated(event) => serde_json::to_value(event)?,
+        })
+    }
+}
+
+/// 一次投递的处理结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum IngestOutcome {
+    /// 已写入领域事件
+    Ingested { event_id: Uuid, event_type: String },
+    /// 无需处理的事件，例如 GitHub 的 ping
+    Ignored { reason: String },
+    /// 无法映射，已写入死信
+    DeadLettered { dead_letter_id: Uuid, error: String },
+}
+
+/// Webhook 接入器
+#[derive(Clone)]
+pub struct WebhookIngestor {
+    db: DatabaseConnection,
+    secrets: HashMap<WebhookSource, String>,
+}
+
+impl WebhookIngestor {
+    /// 创建接入器，只接受已配置密钥的来源的投递
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db, secrets: HashMap::new() }
+    }
+
+    /// 配置来源的签名密钥，空字符串视为未配置
+    pub fn with_secret(mut self, source: WebhookSource, secret: impl Into<String>) -> Self {
+        let secret = secret.into();
+        if secret.is_empty() {
+            self.secrets.remove(&source);
+        } else {
+            self.secrets.insert(source, secret);
+        }
+        self
+    }
+
+    /// 是否配置了任一来源的密钥，未配置时接入端点会拒绝所有投递
+    pub fn has_secrets(&self) -> bool {
+        !self.secrets.is_empty()
+    }
+
+    /// 校验投递的签名，未配置密钥的来源直接拒绝
+    pub fn verify(&self, delivery: &WebhookDelivery) -> Result<()> {
+        let secret = self.secrets.get(&delivery.source).ok_or_else(|| {
+            DatabaseError::validation(format!("未配置 {} webhook 密钥，拒绝投递", delivery.source))
+        })?;
+        let signature = delivery
+            .signature
+            .as_deref()
+            .ok_or_else(|| DatabaseError::validation("缺少 webhook 签名"))?;
+        match delivery.source {
+            WebhookSource::Github => {
+                ci_integration::verify_github_signature(secret, delivery.payload.as_bytes(), signature)
+            }
+            WebhookSource::Gitlab => ci_integration::verify_gitlab_token(secret, signature),
+        }
+    }
+
+    /// 接入一次投递：校验签名后映射并写入领域事件，无法映射的载荷写入死信
+    ///
+    /// 签名无效或项目不存在时返回错误，不记录死信
+    pub async fn ingest(&self, delivery: WebhookDelivery) -> Result<IngestOutcome> {
+        self.verify(&delivery)?;
+        self.ingest_verified(delivery).await
+    }
+
+    /// 接入已通过 [`Self::verify`] 校验的投递
+    async fn ingest_verified(&self, delivery: WebhookDelivery) -> Result<IngestOutcome> {
+        project::Entity::find_by_id(delivery.project_id)
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", delivery.project_id))?;
+
+        match delivery.delivery_id.clone() {
+            Some(delivery_id) => {
+                let scope = format!("webhook:{}:{}", delivery.source, delivery.project_id);
+                let payload = delivery.payload.clone();
+                IdempotencyStore::new(self.db.clone())
+                    .execute(&scope, &delivery_id, &payload, || self.process(delivery))
+                    .await
+            }
+            None => self.process(delivery).await,
+        }
+    }
+
+    /// 映射并写入已校验的投递
+    async fn process(&self, delivery: WebhookDelivery) -> Result<IngestOutcome> {
+        let mapped = parse_and_map(
+            delivery.source,
+            delivery.project_id,
+            delivery.event_name.as_deref(),
+            &delivery.payload,
+        );
+        match mapped {
+            Ok(Some(event)) => publish(&self.db, delivery.project_id, event).await,
+            Ok(None) => Ok(ignored(delivery.source, delivery.event_name.as_deref())),
+            Err(e) => {
+                let error = e.to_string();
+                let dead_letter = WebhookDeadLetterRepository::new(self.db.clone())
+                    .create(CreateDeadLetterData {
+                        project_id: delivery.project_id,
+                        source: delivery.source.to_string(),
+                        event_name: delivery.event_name,
+                        delivery_id: delivery.delivery_id,
+                        payload: delivery.payload,
+                        error: error.clone(),
+                    })
+                    .await?;
+                tracing::warn!(
+                    "项目 {} 的 {} webhook 无法映射，已写入死信 {}: {}",
+                    dead_letter.project_id,
+                    dead_letter.source,
+                    dead_letter.dead_letter_id,
+                    error
+                );
+                Ok(IngestOutcome::DeadLettered { dead_letter_id: dead_letter.dead_letter_id, error })
+            }
+        }
+    }
+}
+
+/// 重放死信，映射成功后标记为已处理，仍然失败时更新失败原因
+pub async fn replay_dead_letter(db: &DatabaseConnection, dead_letter_id: Uuid) -> Result<IngestOutcome> {
+    let dead_letters = WebhookDeadLetterRepository::new(db.clone());
+    let dead_letter = dead_letters
+        .find_by_id(dead_letter_id)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("WebhookDeadLetter", dead_letter_id))?;
+    if !dead_letter.is_pending() {
+        return Err(DatabaseError::validation("死信已处理，不能重放"));
+    }
+    let source = dead_letter
+        .source
+        .parse::<WebhookSource>()
+        .map_err(DatabaseError::validation)?;
+
+    match parse_and_map(source, dead_letter.project_id, dead_letter.event_name.as_deref(), &dead_letter.payload) {
+        Ok(Some(event)) => {
+            let outcome = publish(db, dead_letter.project_id, event).await?;
+            dead_letters.record_replay(dead_letter_id, None).await?;
+            Ok(outcome)
+        }
+        Ok(None) => {
+            dead_letters.record_replay(dead_letter_id, None).await?;
+            Ok(ignored(source, dead_letter.event_name.as_deref()))
+        }
+        Err(e) => {
+            let error = e.to_string();
+            dead_letters.record_replay(dead_letter_id, Some(error.clone())).await?;
+            Ok(IngestOutcome::DeadLettered { dead_letter_id, error })
+        }
+    }
+}
+
+/// 待处理的死信
+pub async fn pending_dead_letters(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    limit: u64,
+) -> Result<Vec<webhook_dead_letter::Model>> {
+    WebhookDeadLetterRepository::new(db.clone())
+        .find_by_project(project_id, false, limit)
+        .await
+}
+
+/// 按来源把载荷映射为外部事件，无需处理的事件返回 None，无法映射时返回错误
+pub fn map_payload(
+    source: WebhookSource,
+    project_id: Uuid,
+    event_name: Option<&str>,
+    payload: &JsonValue,
+) -> Result<Option<ExternalEvent>> {
+    match source {
+        WebhookSource::Github => {
+            let event_name = event_name.ok_or_else(|| DatabaseError::validation("缺少 GitHub 事件类型"))?;
+            map_github(project_id, event_name, payload)
+        }
+        WebhookSource::Gitlab => map_gitlab(project_id, payload),
+    }
+}
+
+/// 映射 GitHub 的 push、issues、workflow_run 和 check_run 事件
+fn map_github(project_id: Uuid, event_name: &str, payload: &JsonValue) -> Result<Option<ExternalEvent>> {
+    let source = WebhookSource::Github;
+    match event_name {
+        "ping" => Ok(None),
+        "push" => Ok(Some(ExternalEvent::GitPush(EventFactory::git_push_received(
+            project_id.into(),
+            source,
+            required_str(&payload["repository"], "full_name", event_name)?,
+            required_str(payload, "ref", event_name)?,
+            required_str(payload, "before", event_name)?,
+            required_str(payload, "after", event_name)?,
+            payload["commits"].as_array().map(|commits| commits.len() as u32).unwrap_or_default(),
+            payload["pusher"]["name"].as_str().map(str::to_string),
+        )))),
+        "issues" => {
+            let issue = &payload["issue"];
+            Ok(Some(ExternalEvent::IssueUpdated(EventFactory::external_issue_updated(
+                project_id.into(),
+                source,
+                required_u64(issue, "number", event_name)?,
+                required_str(issue, "title", event_name)?,
+                required_str(payload, "action", event_name)?,
+                required_str(issue, "state", event_name)?,
+                issue["html_url"].as_str().map(str::to_string),
+            ))))
+        }
+        "workflow_run" | "check_run" => match ci_integration::parse_github_webhook(event_name, payload)? {
+            Some(update) => Ok(Some(ci_event(project_id, source, update))),
+            None => Err(unsupported(source, event_name)),
+        },
+        other => Err(unsupported(source, other)),
+    }
+}
+
+/// 映射 GitLab 的 push、tag_push、issue、pipeline 和 build 事件，事件类型取自 `object_kind`
+fn map_gitlab(project_id: Uuid, payload: &JsonValue) -> Result<Option<ExternalEvent>> {
+    let source = WebhookSource::Gitlab;
+    let kind = payload["object_kind"]
+        .as_str()
+        .ok_or_else(|| DatabaseError::validation("GitLab 载荷缺少 object_kind"))?;
+    match kind {
+        "push" | "tag_push" => Ok(Some(ExternalEvent::GitPush(EventFactory::git_push_received(
+            project_id.into(),
+            source,
+            required_str(&payload["project"], "path_with_namespace", kind)?,
+            required_str(payload, "ref", kind)?,
+            required_str(payload, "before", kind)?,
+            required_str(payload, "after", kind)?,
+            payload["total_commits_count"].as_u64().unwrap_or_default() as u32,
+            payload["user_username"].as_str().or_else(|| payload["user_name"].as_str()).map(str::to_string),
+        )))),
+        "issue" => {
+            let attributes = &payload["object_attributes"];
+            Ok(Some(ExternalEvent::IssueUpdated(EventFactory::external_issue_updated(
+                project_id.into(),
+                source,
+                required_u64(attributes, "iid", kind)?,
+                required_str(attributes, "title", kind)?,
+                attributes["action"].as_str().unwrap_or("update").to_string(),
+                required_str(attributes, "state", kind)?,
+                attributes["url"].as_str().map(str::to_string),
+            ))))
+        }
+        "pipeline" | "build" => match ci_integration::parse_gitlab_webhook(payload)? {
+            Some(update) => Ok(Some(ci_event(project_id, source, update))),
+            None => Err(unsupported(source, kind)),
+        },
+        other => Err(unsupported(source, other)),
+    }
+}
+
+fn ci_event(project_id: Uuid, source: WebhookSource, update: CiCheckUpdate) -> ExternalEvent {
+    let event = EventFactory::ci_status_received(
+        project_id.into(),
+        source,
+        update.name.clone(),
+        update.commit_sha.clone(),
+        update.branch.clone(),
+        update.status,
+        update.details_url.clone(),
+    );
+    ExternalEvent::CiStatus { event, update }
+}
+
+fn parse_and_map(
+    source: WebhookSource,
+    project_id: Uuid,
+    event_name: Option<&str>,
+    payload: &str,
+) -> Result<Option<ExternalEvent>> {
+    let payload: JsonValue = serde_json::from_str(payload)
+        .map_err(|e| DatabaseError::validation(format!("webhook 载荷不是有效的JSON: {}", e)))?;
+    map_payload(source, project_id, event_name, &payload)
+}
+
+/// 把外部事件写入项目的领域事件
+///
+/// CI 状态先写入检查结果，匹配到执行会话时以其任务作为关联ID
+async fn publish(db: &DatabaseConnection, project_id: Uuid, event: ExternalEvent) -> Result<IngestOutcome> {
+    let mut correlation_id = None;
+    if let ExternalEvent::CiStatus { update, .. } = &event {
+        let run = ci_integration::ingest_check(db, project_id, update.clone()).await?;
+        if let Some(session_id) = run.execution_session_id {
+            correlation_id = ExecutionSessionRepository::new(db.clone())
+                .find_by_id(session_id)
+                .await?
+                .map(|session| session.task_id);
+        }
+    }
+
+    let event_repo = DomainEventRepository::new(db.clone());
+    let data = CreateDomainEventData {
+        aggregate_type: AggregateType::Project.to_string(),
+        aggregate_id: project_id,
+        event_type: event.domain_event_type().to_string(),
+        event_data: event.event_data()?,
+        event_version: event_repo.get_latest_version(project_id).await? + 1,
+    };
+    let recorded = match correlation_id {
+        Some(correlation_id) => event_repo.create_correlated(data, correlation_id).await?,
+        None => event_repo.create(data).await?,
+    };
+
+    tracing::info!("项目 {} 收到外部事件 {}", project_id, recorded.event_type);
+    Ok(IngestOutcome::Ingested { event_id: recorded.event_id, event_type: recorded.event_type })
+}
+
+fn ignored(source: WebhookSource, event_name: Option<&str>) -> IngestOutcome {
+    IngestOutcome::Ignored { reason: format!("{} 事件 {} 无需处理", source, event_name.unwrap_or_default()) }
+}
+
+fn unsupported(source: WebhookSource, event_name: &str) -> DatabaseError {
+    DatabaseError::validation(format!("不支持的 {} 事件: {}", source, event_name))
+}
+
+fn required_str(value: &JsonValue, key: &str, event: &str) -> Result<String> {
+    value[key]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| DatabaseError::validation(format!("{} 事件缺少 {}", event, key)))
+}
+
+fn required_u64(value: &JsonValue, key: &str, event: &str) -> Result<u64> {
+    value[key]
+        .as_u64()
+        .ok_or_else(|| DatabaseError::validation(format!("{} 事件缺少 {}", event, key)))
+}
+
+/// 在 HTTP 端点上接收 webhook：`POST /webhooks/{source}/{project_id}`
+///
+/// 每个连接只处理一个请求。签名无效或来源未配置密钥返回 401，项目不存在返回 404，
+/// 请求头超过 [`MAX_HEADER_LINE_BYTES`] 或 [`MAX_HEADER_COUNT`] 返回 431，接入成功、忽略或写入死信都返回 202，响应体为 [`IngestOutcome`]
+pub async fn serve(listener: TcpListener, ingestor: Arc<WebhookIngestor>) -> Result<()> {
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let ingestor = ingestor.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_connection(stream, &ingestor).await {
+                tracing::warn!("webhook 连接 {} 处理失败: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn serve_connection(stream: TcpStream, ingestor: &WebhookIngestor) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let (status, body) = match read_head(&mut reader).await? {
+        Err(response) => response,
+        Ok((request_line, headers)) => match route(&request_line, &headers) {
+            Err(response) => response,
+            Ok((source, project_id)) => read_delivery(&mut reader, &headers, source, project_id, ingestor).await?,
+        },
+    };
+
+    let body = serde_json::to_vec(&body)?;
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        reason_phrase(status),
+        body.len()
+    );
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(&body).await?;
+    writer.shutdown().await?;
+    Ok(())
+}
+
+type Headers = HashMap<String, String>;
+
+/// 读取请求行和请求头，超过长度或数量限制时返回 431
+async fn read_head<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> Result<std::result::Result<(String, Headers), (u16, JsonValue)>> {
+    let too_large = || (431, json!({ "error": "请求头过大" }));
+    let Some(request_line) = read_bounded_line(reader).await? else {
+        return Ok(Err(too_large()));
+    };
+    let mut headers = HashMap::new();
+    for count in 0.. {
+        let Some(line) = read_bounded_line(reader).await? else {
+            return Ok(Err(too_large()));
+        };
+        if line.trim().is_empty() {
+            break;
+        }
+        if count >= MAX_HEADER_COUNT {
+            return Ok(Err(too_large()));
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+    Ok(Ok((request_line, headers)))
+}
+
+/// 读取一行，超过 [`MAX_HEADER_LINE_BYTES`] 时返回 None，连接关闭时返回空行
+async fn read_bounded_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<String>> {
+    let mut line = Vec::new();
+    (&mut *reader)
+        .take(MAX_HEADER_LINE_BYTES as u64 + 1)
+        .read_until(b'\n', &mut line)
+        .await?;
+    if line.len() > MAX_HEADER_LINE_BYTES {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&line).into_owned()))
+}
+
+/// 读取载荷并接入
+async fn read_delivery<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    headers: &Headers,
+    source: WebhookSource,
+    project_id: Uuid,
+    ingestor: &WebhookIngestor,
+) -> Result<(u16, JsonValue)> {
+    let length = headers.get("content-length").and_then(|value| value.parse::<usize>().ok());
+    Ok(match length {
+        None => (411, json!({ "error": "缺少 Content-Length" })),
+        Some(length) if length > MAX_PAYLOAD_BYTES => (413, json!({ "error": "载荷过大" })),
+        Some(length) => {
+            let mut payload = vec![0u8; length];
+            reader.read_exact(&mut payload).await?;
+            // 载荷按原始字节签名，不能做有损转换，非 UTF-8 的载荷直接拒绝
+            let Ok(payload) = String::from_utf8(payload) else {
+                return Ok((400, json!({ "error": "载荷不是有效的 UTF-8" })));
+            };
+            let delivery = WebhookDelivery {
+                source,
+                project_id,
+                event_name: header(headers, source, "x-github-event", "x-gitlab-event"),
+                delivery_id: header(headers, source, "x-github-delivery", "x-gitlab-event-uuid"),
+                signature: header(headers, source, "x-hub-signature-256", "x-gitlab-token"),
+                payload,
+            };
+            respond(ingestor, delivery).await
+        }
+    })
+}
+
+/// 解析请求行，得到来源和项目ID
+fn route(
+    request_line: &str,
+    headers: &Headers,
+) -> std::result::Result<(WebhookSource, Uuid), (u16, JsonValue)> {
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+    if method != "POST" {
+        return Err((405, json!({ "error": "只支持 POST 请求" })));
+    }
+    let segments: Vec<&str> = path.split('?').next().unwrap_or_default().trim_matches('/').split('/').collect();
+    let (source, project_id) = match segments.as_slice() {
+        ["webhooks", source, project_id] => (*source, *project_id),
+        _ => return Err((404, json!({ "error": "路径应为 /webhooks/{source}/{project_id}" }))),
+    };
+    let source = source.parse::<WebhookSource>().map_err(|e| (404, json!({ "error": e })))?;
+    let project_id = Uuid::parse_str(project_id).map_err(|_| (404, json!({ "error": "无效的项目ID" })))?;
+    if headers.get("transfer-encoding").is_some_and(|value| value.eq_ignore_ascii_case("chunked")) {
+        return Err((411, json!({ "error": "不支持分块传输" })));
+    }
+    Ok((source, project_id))
+}
+
+async fn respond(ingestor: &WebhookIngestor, delivery: WebhookDelivery) -> (u16, JsonValue) {
+    if let Err(e) = ingestor.verify(&delivery) {
+        return (401, json!({ "error": e.to_string() }));
+    }
+    match ingestor.ingest_verified(delivery).await {
+        Ok(outcome) => (202, serde_json::to_value(&outcome).unwrap_or_default()),
+        Err(e @ DatabaseError::EntityNotFound { .. }) => (404, json!({ "error": e.to_string() })),
+        Err(e) => {
+            tracing::error!("webhook 接入失败: {}", e);
+            (500, json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+fn header(
+    headers: &HashMap<String, String>,
+    source: WebhookSource,
+    github: &str,
+    gitlab: &str,
+) -> Option<String> {
+    let name = match source {
+        WebhookSource::Github => github,
+        WebhookSource::Gitlab => gitlab,
+    };
+    headers.get(name).cloned()
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
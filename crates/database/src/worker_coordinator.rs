@@ -0,0 +1,556 @@
+//! 远程工作进程协调器
+//!
+//! 实现 [`codex_multi_agent::worker_protocol`] 的协调器一端：工作进程注册后按能力领取
+//! 任务租约，执行状态写回中心数据库。每个租约对应一个执行会话，会话的
+//! `execution_config.remote_worker_id` 记录持有租约的工作进程；任务本身通过
+//! [`TaskRepository::acquire_lease`] 加锁，心跳时续约，过期的租约会被回收并重新调度。
+//!
+//! [`serve`] 提供按行分隔JSON的TCP传输，每行一条消息；WebSocket等其他传输只需把
+//! 文本帧交给 [`WorkerCoordinator::handle`]。
+
+use chrono::Utc;
+use codex_multi_agent::{
+    AgentCapability, CoordinatorMessage, ExecutionSessionId, TaskLease, WorkerId, WorkerMessage,
+    WORKER_PROTOCOL_VERSION,
+};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use uuid::Uuid;
+
+use crate::{
+    entities::{agent, execution_session, execution_session::ExecutionStatus, remote_worker, task},
+    repository::{
+        execution_session_repository::CreateSessionData, remote_worker_repository::RegisterWorkerData,
+        ExecutionSessionRepository, RemoteWorkerRepository, TaskDependencyRepository, TaskRepository,
+    },
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 默认心跳间隔
+pub const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 默认租约执行超时（分钟）
+pub const DEFAULT_LEASE_TIMEOUT_MINUTES: i32 = 60;
+
+/// 默认任务租约时长，工作进程需在此时间内发送心跳续约
+pub const DEFAULT_LEASE_TTL: Duration = Duration::from_secs(60);
+
+/// 执行配置中记录工作进程ID的字段
+const REMOTE_WORKER_KEY: &str = "remote_worker_id";
+
+/// 远程工作进程协调器
+#[derive(Clone)]
+pub struct WorkerCoordinator {
+    db: DatabaseConnection,
+    heartbeat_interval: Duration,
+    lease_ttl: Duration,
+    lease_timeout_minutes: i32,
+}
+
+impl WorkerCoordinator {
+    /// 创建协调器
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
+            lease_ttl: DEFAULT_LEASE_TTL,
+            lease_timeout_minutes: DEFAULT_LEASE_TIMEOUT_MINUTES,
+        }
+    }
+
+    /// 设置下发给工作进程的心跳间隔
+    pub fn with_heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = interval;
+        self
+    }
+
+    /// 设置任务租约时长，应大于心跳间隔
+    pub fn with_lease_ttl(mut self, ttl: Duration) -> Self {
+        self.lease_ttl = ttl;
+        self
+    }
+
+    /// 设置租约执行超时（分钟）
+    pub fn with_lease_timeout_minutes(mut self, minutes: i32) -> Self {
+        self.lease_timeout_minutes = minutes;
+        self
+    }
+
+    /// 心跳间隔
+    pub fn heartbeat_interval(&self) -> Duration {
+        self.heartbeat_interval
+    }
+
+    /// 处理一条工作进程消息，处理失败时返回 [`CoordinatorMessage::Error`]
+    pub async fn handle(&self, message: WorkerMessage) -> CoordinatorMessage {
+        let message_type = message.message_type();
+        match self.dispatch(message).await {
+            Ok(reply) => reply,
+            Err(e) => {
+                tracing::warn!("处理工作进程消息 {} 失败: {}", message_type, e);
+                CoordinatorMessage::Error { message: e.to_string() }
+            }
+        }
+    }
+
+    async fn dispatch(&self, message: WorkerMessage) -> Result<CoordinatorMessage> {
+        match message {
+            WorkerMessage::Register { protocol_version, name, agent_ids, capabilities, max_concurrent_tasks } => {
+                self.register(protocol_version, name, agent_ids.into_iter().map(Uuid::from).collect(), &capabilities, max_concurrent_tasks)
+                    .await
+            }
+            WorkerMessage::Heartbeat { worker_id, active_leases } => {
+                let revoked_leases = self.heartbeat(worker_id.into_uuid(), &active_leases).await?;
+                Ok(CoordinatorMessage::HeartbeatAck { revoked_leases })
+            }
+            WorkerMessage::LeaseRequest { worker_id } => Ok(match self.lease(worker_id.into_uuid()).await? {
+                Some(lease) => CoordinatorMessage::TaskLease(lease),
+                None => CoordinatorMessage::NoWork,
+            }),
+            WorkerMessage::Ack { worker_id, lease_id } => {
+                self.ack(worker_id.into_uuid(), lease_id.as_uuid()).await?;
+                Ok(CoordinatorMessage::Accepted { lease_id })
+            }
+            WorkerMessage::Complete { worker_id, lease_id, final_commit, result } => {
+                self.complete(worker_id.into_uuid(), lease_id.as_uuid(), final_commit, result).await?;
+                Ok(CoordinatorMessage::Accepted { lease_id })
+            }
+            WorkerMessage::Fail { worker_id, lease_id, error, retryable } => {
+                self.fail(worker_id.into_uuid(), lease_id.as_uuid(), error, retryable).await?;
+                Ok(CoordinatorMessage::Accepted { lease_id })
+            }
+        }
+    }
+
+    /// 注册工作进程，托管的Agent必须已存在
+    async fn register(
+        &self,
+        protocol_version: u32,
+        name: String,
+        agent_ids: Vec<Uuid>,
+        capabilities: &[AgentCapability],
+        max_concurrent_tasks: u32,
+    ) -> Result<CoordinatorMessage> {
+        if protocol_version != WORKER_PROTOCOL_VERSION {
+            return Err(DatabaseError::validation(format!(
+                "不支持的协议版本 {}，协调器使用版本 {}",
+                protocol_version, WORKER_PROTOCOL_VERSION
+            )));
+        }
+        if agent_ids.is_empty() {
+            return Err(DatabaseError::validation("工作进程至少需要托管一个Agent"));
+        }
+        let found = agent::Entity::find()
+            .filter(agent::Column::AgentId.is_in(agent_ids.clone()))
+            .all(&self.db)
+            .await?;
+        if let Some(missing) = agent_ids.iter().find(|id| !found.iter().any(|agent| agent.agent_id == **id)) {
+            return Err(DatabaseError::entity_not_found("Agent", missing));
+        }
+
+        let worker = RemoteWorkerRepository::new(self.db.clone())
+            .register(RegisterWorkerData {
+                name,
+                agent_ids,
+                capabilities: capabilities.iter().map(capability_name).collect(),
+                max_concurrent_tasks: max_concurrent_tasks as i32,
+                protocol_version: protocol_version as i32,
+            })
+            .await?;
+        tracing::info!("远程工作进程 {} ({}) 已注册", worker.name, worker.worker_id);
+
+        Ok(CoordinatorMessage::Registered {
+            worker_id: WorkerId::from(worker.worker_id),
+            heartbeat_interval_secs: self.heartbeat_interval.as_secs(),
+        })
+    }
+
+    /// 记录心跳并为持有的任务租约续约，返回工作进程上报但已不属于它的租约
+    async fn heartbeat(&self, worker_id: Uuid, reported: &[ExecutionSessionId]) -> Result<Vec<ExecutionSessionId>> {
+        let worker = RemoteWorkerRepository::new(self.db.clone()).heartbeat(worker_id).await?;
+        let tasks = TaskRepository::new(self.db.clone());
+        let owner = worker_id.to_string();
+
+        let mut active = HashSet::new();
+        for session in self.active_leases(&worker).await? {
+            match tasks.renew_lease(session.task_id, &owner, self.lease_ttl).await {
+                Ok(_) => {
+                    active.insert(session.session_id);
+                }
+                Err(e) if e.is_conflict_error() => self.expire_session(&session).await?,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(reported.iter().filter(|lease| !active.contains(lease.as_uuid())).cloned().collect())
+    }
+
+    /// 为工作进程分配一个任务租约
+    ///
+    /// 只考虑前置任务均已完成的待处理任务，且任务所需能力必须同时被工作进程和执行的Agent覆盖；
+    /// 先获取任务租约，再通过条件更新从 pending 转为 in_progress，多个工作进程并发领取时只有一个成功
+    async fn lease(&self, worker_id: Uuid) -> Result<Option<TaskLease>> {
+        let worker = RemoteWorkerRepository::new(self.db.clone()).find_online(worker_id).await?;
+        if self.active_leases(&worker).await?.len() >= worker.max_concurrent_tasks as usize {
+            return Ok(None);
+        }
+
+        let worker_capabilities: HashSet<String> = worker.capability_list().into_iter().collect();
+        let agent_ids = worker.agent_id_list();
+        let agents = agent::Entity::find()
+            .filter(agent::Column::AgentId.is_in(agent_ids.clone()))
+            .all(&self.db)
+            .await?;
+
+        let mut candidates = task::Entity::find()
+            .filter(task::Column::Status.eq("pending"))
+            .order_by_asc(task::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+        candidates.sort_by_key(|task| priority_rank(&task.priority));
+
+        let tasks = TaskRepository::new(self.db.clone());
+        let dependencies = TaskDependencyRepository::new(self.db.clone());
+        for candidate in candidates {
+            if candidate.assigned_agent_id.is_some_and(|agent_id| !agent_ids.contains(&agent_id)) {
+                continue;
+            }
+            let required = string_list(candidate.required_capabilities.as_ref());
+            if !required.iter().all(|capability| worker_capabilities.contains(capability)) {
+                continue;
+            }
+            let Some(agent) = agents.iter().find(|agent| {
+                candidate.assigned_agent_id.is_none_or(|agent_id| agent_id == agent.agent_id)
+                    && agent_covers(agent, &required)
+            }) else {
+                continue;
+            };
+            if !self.prerequisites_completed(&dependencies, candidate.task_id).await? {
+                continue;
+            }
+            let owner = worker_id.to_string();
+            let Some(leased) = tasks.acquire_lease(candidate.task_id, &owner, self.lease_ttl).await? else {
+                continue;
+            };
+            if leased.status != "pending" || !self.claim_task(candidate.task_id, agent.agent_id).await? {
+                tasks.release_lease(candidate.task_id, &owner).await?;
+                continue;
+            }
+
+            return self.issue_lease(&worker, candidate, agent.agent_id, required).await.map(Some);
+        }
+
+        Ok(None)
+    }
+
+    /// 为已领取的任务创建执行会话并生成租约
+    async fn issue_lease(
+        &self,
+        worker: &remote_worker::Model,
+        claimed: task::Model,
+        agent_id: Uuid,
+        required: Vec<String>,
+    ) -> Result<TaskLease> {
+        let tasks = TaskRepository::new(self.db.clone());
+        let claimed = tasks.set_status(claimed.task_id, "in_progress", false).await?;
+        let git_branch = format!("task/{}", &claimed.task_id.to_string()[..8]);
+        let session = ExecutionSessionRepository::new(self.db.clone())
+            .create(CreateSessionData {
+                task_id: claimed.task_id,
+                agent_id,
+                project_id: claimed.project_id,
+                git_branch: git_branch.clone(),
+                base_commit: None,
+                execution_config: Some(json!({ REMOTE_WORKER_KEY: worker.worker_id })),
+                timeout_minutes: self.lease_timeout_minutes,
+            })
+            .await;
+        let session = match session {
+            Ok(session) => session,
+            Err(e) => {
+                // 会话创建失败时归还任务，避免任务停留在进行中
+                self.requeue_task(claimed.task_id, worker.worker_id).await?;
+                return Err(e);
+            }
+        };
+        tracing::info!("任务 {} 租约 {} 已分配给工作进程 {}", claimed.task_id, session.session_id, worker.worker_id);
+
+        Ok(TaskLease {
+            lease_id: ExecutionSessionId::from(session.session_id),
+            task_id: claimed.task_id.into(),
+            project_id: claimed.project_id.into(),
+            agent_id: agent_id.into(),
+            title: claimed.title,
+            description: claimed.description,
+            task_type: claimed.task_type,
+            required_capabilities: required
+                .into_iter()
+                .filter_map(|capability| serde_json::from_value(JsonValue::String(capability)).ok())
+                .collect(),
+            assignment_prompt: claimed.assignment_prompt,
+            git_branch,
+            timeout_minutes: self.lease_timeout_minutes.max(0) as u32,
+            leased_at: Utc::now(),
+        })
+    }
+
+    /// 确认租约，执行会话进入运行状态
+    async fn ack(&self, worker_id: Uuid, lease_id: &Uuid) -> Result<()> {
+        let session = self.find_lease(worker_id, lease_id).await?;
+        TaskRepository::new(self.db.clone())
+            .renew_lease(session.task_id, &worker_id.to_string(), self.lease_ttl)
+            .await?;
+        if session.status == ExecutionStatus::Running.to_string() {
+            return Ok(());
+        }
+        ExecutionSessionRepository::new(self.db.clone()).start_session(session.session_id).await?;
+        Ok(())
+    }
+
+    /// 完成租约，任务进入待审查状态
+    async fn complete(&self, worker_id: Uuid, lease_id: &Uuid, final_commit: Option<String>, result: Option<JsonValue>) -> Result<()> {
+        let session = self.find_lease(worker_id, lease_id).await?;
+        let sessions = ExecutionSessionRepository::new(self.db.clone());
+        if session.status == ExecutionStatus::Pending.to_string() {
+            sessions.start_session(session.session_id).await?;
+        }
+        sessions.complete_session(session.session_id, true, final_commit, result, None).await?;
+        let tasks = TaskRepository::new(self.db.clone());
+        tasks.set_status(session.task_id, "waiting_for_review", false).await?;
+        tasks.release_lease(session.task_id, &worker_id.to_string()).await?;
+        Ok(())
+    }
+
+    /// 租约执行失败，可重试的任务重新回到待处理队列
+    async fn fail(&self, worker_id: Uuid, lease_id: &Uuid, error: String, retryable: bool) -> Result<()> {
+        let session = self.find_lease(worker_id, lease_id).await?;
+        let sessions = ExecutionSessionRepository::new(self.db.clone());
+        if session.status == ExecutionStatus::Pending.to_string() {
+            sessions.start_session(session.session_id).await?;
+        }
+        sessions.complete_session(session.session_id, false, None, None, Some(error)).await?;
+        if retryable {
+            self.requeue_task(session.task_id, worker_id).await?;
+        } else {
+            let tasks = TaskRepository::new(self.db.clone());
+            tasks.set_status(session.task_id, "failed", false).await?;
+            tasks.release_lease(session.task_id, &worker_id.to_string()).await?;
+        }
+        Ok(())
+    }
+
+    /// 回收心跳超时的工作进程，其未完成的租约标记为超时并把任务放回队列；
+    /// 同时回收所有过期的任务租约
+    ///
+    /// 返回被标记为离线的工作进程ID
+    pub async fn reap_stale_workers(&self, heartbeat_timeout: Duration) -> Result<Vec<Uuid>> {
+        let timeout = chrono::Duration::from_std(heartbeat_timeout)
+            .map_err(|e| DatabaseError::validation(format!("无效的心跳超时: {}", e)))?;
+        let workers = RemoteWorkerRepository::new(self.db.clone());
+        let sessions = ExecutionSessionRepository::new(self.db.clone());
+
+        let mut reaped = Vec::new();
+        for worker in workers.find_stale((Utc::now() - timeout).into()).await? {
+            for session in self.active_leases(&worker).await? {
+                sessions
+                    .timeout_session(session.session_id, format!("工作进程 {} 心跳超时", worker.worker_id))
+                    .await?;
+                self.requeue_task(session.task_id, worker.worker_id).await?;
+            }
+            workers.mark_offline(worker.worker_id).await?;
+            tracing::warn!("远程工作进程 {} ({}) 心跳超时，已标记为离线", worker.name, worker.worker_id);
+            reaped.push(worker.worker_id);
+        }
+
+        // 租约过期的任务已回到队列，结束仍挂在这些任务上的远程执行会话
+        for task in TaskRepository::new(self.db.clone()).reclaim_expired_leases().await? {
+            for session in sessions.find_by_task_id(task.task_id).await? {
+                if is_active(&session) && lease_owner(&session).is_some() {
+                    sessions.timeout_session(session.session_id, "任务租约已过期".to_string()).await?;
+                }
+            }
+        }
+
+        Ok(reaped)
+    }
+
+    /// 查找工作进程持有的租约，租约不存在、不属于该工作进程或已结束时返回错误
+    async fn find_lease(&self, worker_id: Uuid, lease_id: &Uuid) -> Result<execution_session::Model> {
+        RemoteWorkerRepository::new(self.db.clone()).find_online(worker_id).await?;
+        let session = ExecutionSessionRepository::new(self.db.clone())
+            .find_by_id(*lease_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("TaskLease", lease_id))?;
+        if lease_owner(&session) != Some(worker_id) {
+            return Err(DatabaseError::business_logic(format!("租约 {} 不属于工作进程 {}", lease_id, worker_id)));
+        }
+        if !is_active(&session) {
+            return Err(DatabaseError::conflict(format!("租约 {} 已结束（{}）", lease_id, session.status)));
+        }
+
+        let task = TaskRepository::new(self.db.clone())
+            .find_by_id(session.task_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Task", session.task_id))?;
+        let expired = task.lease_expires_at.is_none_or(|expires_at| expires_at < Utc::now());
+        if task.lease_owner.as_deref() != Some(worker_id.to_string().as_str()) || expired {
+            self.expire_session(&session).await?;
+            return Err(DatabaseError::conflict(format!("租约 {} 已过期", lease_id)));
+        }
+        Ok(session)
+    }
+
+    /// 查找工作进程未结束的租约
+    async fn active_leases(&self, worker: &remote_worker::Model) -> Result<Vec<execution_session::Model>> {
+        let sessions = execution_session::Entity::find()
+            .filter(execution_session::Column::AgentId.is_in(worker.agent_id_list()))
+            .filter(execution_session::Column::Status.is_in([
+                ExecutionStatus::Pending.to_string(),
+                ExecutionStatus::Running.to_string(),
+            ]))
+            .all(&self.db)
+            .await?;
+
+        Ok(sessions.into_iter().filter(|session| lease_owner(session) == Some(worker.worker_id)).collect())
+    }
+
+    /// 前置任务是否均已完成
+    async fn prerequisites_completed(&self, dependencies: &TaskDependencyRepository, task_id: Uuid) -> Result<bool> {
+        let prerequisites = dependencies.get_prerequisite_task_ids(task_id).await?;
+        if prerequisites.is_empty() {
+            return Ok(true);
+        }
+        let unfinished = task::Entity::find()
+            .filter(task::Column::TaskId.is_in(prerequisites))
+            .filter(task::Column::Status.ne("completed"))
+            .all(&self.db)
+            .await?;
+        Ok(unfinished.is_empty())
+    }
+
+    /// 以条件更新领取待处理任务，返回是否领取成功
+    async fn claim_task(&self, task_id: Uuid, agent_id: Uuid) -> Result<bool> {
+        let now: sea_orm::prelude::DateTimeWithTimeZone = Utc::now().into();
+        let result = task::Entity::update_many()
+            .col_expr(task::Column::Status, Expr::value("in_progress"))
+            .col_expr(task::Column::AssignedAgentId, Expr::value(agent_id))
+            .col_expr(task::Column::AssignedAt, Expr::value(now))
+            .col_expr(task::Column::UpdatedAt, Expr::value(now))
+            .filter(task::Column::TaskId.eq(task_id))
+            .filter(task::Column::Status.eq("pending"))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected == 1)
+    }
+
+    /// 租约已失效时结束执行会话
+    async fn expire_session(&self, session: &execution_session::Model) -> Result<()> {
+        ExecutionSessionRepository::new(self.db.clone())
+            .timeout_session(session.session_id, "任务租约已过期".to_string())
+            .await?;
+        Ok(())
+    }
+
+    /// 把进行中的任务放回待处理队列并释放工作进程持有的租约
+    async fn requeue_task(&self, task_id: Uuid, worker_id: Uuid) -> Result<()> {
+        TaskRepository::new(self.db.clone()).release_lease(task_id, &worker_id.to_string()).await?;
+        task::Entity::update_many()
+            .col_expr(task::Column::Status, Expr::value("pending"))
+            .col_expr(task::Column::UpdatedAt, Expr::value(sea_orm::prelude::DateTimeWithTimeZone::from(Utc::now())))
+            .filter(task::Column::TaskId.eq(task_id))
+            .filter(task::Column::Status.eq("in_progress"))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
+
+/// 以按行分隔的JSON在TCP连接上为工作进程提供协议服务
+///
+/// 每个连接独立处理，连接断开不会立即回收租约，由 [`WorkerCoordinator::reap_stale_workers`]
+/// 按心跳超时统一回收
+pub async fn serve(listener: TcpListener, coordinator: Arc<WorkerCoordinator>) -> Result<()> {
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let coordinator = coordinator.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_connection(stream, &coordinator).await {
+                tracing::warn!("工作进程连接 {} 异常断开: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn serve_connection(stream: TcpStream, coordinator: &WorkerCoordinator) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str::<WorkerMessage>(&line) {
+            Ok(message) => coordinator.handle(message).await,
+            Err(e) => CoordinatorMessage::Error { message: format!("无法解析消息: {}", e) },
+        };
+        let mut payload = serde_json::to_vec(&reply)?;
+        payload.push(b'\n');
+        writer.write_all(&payload).await?;
+    }
+
+    Ok(())
+}
+
+/// 能力的协议名称，例如 backend_development
+fn capability_name(capability: &AgentCapability) -> String {
+    serde_json::to_value(capability)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// 解析JSON字符串数组
+fn string_list(value: Option<&JsonValue>) -> Vec<String> {
+    value
+        .and_then(|value| value.as_array())
+        .map(|items| items.iter().filter_map(|item| item.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
+
+/// Agent是否具备全部所需能力
+fn agent_covers(agent: &agent::Model, required: &[String]) -> bool {
+    let capabilities = string_list(Some(&agent.capabilities));
+    required.iter().all(|capability| capabilities.contains(capability))
+}
+
+/// 优先级排序，数值越小越先分配
+fn priority_rank(priority: &str) -> u8 {
+    match priority {
+        "critical" | "urgent" => 0,
+        "high" => 1,
+        "medium" => 2,
+        "low" => 3,
+        _ => 2,
+    }
+}
+
+/// 执行会话所属的工作进程
+fn lease_owner(session: &execution_session::Model) -> Option<Uuid> {
+    session
+        .execution_config
+        .as_ref()
+        .and_then(|config| config.get(REMOTE_WORKER_KEY))
+        .and_then(|value| value.as_str())
+        .and_then(|value| Uuid::parse_str(value).ok())
+}
+
+/// 执行会话是否尚未结束
+fn is_active(session: &execution_session::Model) -> bool {
+    session.status == ExecutionStatus::Pending.to_string() || session.status == ExecutionStatus::Running.to_string()
+}
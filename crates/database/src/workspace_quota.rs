@@ -0,0 +1,404 @@
+//! 工作空间磁盘配额
+//!
+//! Agent 在项目工作空间中克隆仓库、构建和运行测试，构建产物和依赖目录会持续占用磁盘。
+//! 每个项目可以配置 [`WorkspaceQuota`]（存放在 `projects.workspace_quota`），
+//! [`scan_usage`] 统计工作空间的实际占用，[`check_workspace_quota`] 在占用跨越阈值时
+//! 记录 `WorkspaceQuotaThresholdCrossed` 领域事件并附带清理建议。
+//!
+//! 克隆仓库、启动执行会话等会继续写入工作空间的操作应先调用 [`ensure_within_quota`]，
+//! 超出配额时返回 [`DatabaseError::QuotaExceeded`]。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::{
+    entities::{
+        domain_event::{AggregateType, DomainEventType},
+        project,
+    },
+    repository::{domain_event_repository::CreateDomainEventData, DomainEventRepository},
+    DatabaseConnection, DatabaseError, Result,
+};
+use sea_orm::EntityTrait;
+
+/// 可安全删除、能够重新生成的目录及原因
+const REGENERABLE_DIRS: &[(&str, &str)] = &[
+    ("target", "Rust 构建产物，可通过 cargo build 重新生成"),
+    ("node_modules", "Node 依赖目录，可通过包管理器重新安装"),
+    ("dist", "前端打包产物，可重新构建"),
+    ("build", "构建产物，可重新构建"),
+    (".next", "Next.js 构建缓存，可重新构建"),
+    (".turbo", "Turborepo 缓存，可重新生成"),
+    (".cache", "工具缓存，可重新生成"),
+    ("__pycache__", "Python 字节码缓存，可重新生成"),
+    (".pytest_cache", "pytest 缓存，可重新生成"),
+    ("coverage", "测试覆盖率报告，可重新生成"),
+];
+
+/// 清理建议最多返回的条数
+const MAX_SUGGESTIONS: usize = 10;
+
+/// 工作空间磁盘配额
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceQuota {
+    /// 最大占用字节数
+    pub max_bytes: u64,
+    /// 达到该比例时发出警告
+    #[serde(default = "default_warning_ratio")]
+    pub warning_ratio: f64,
+    /// 达到该比例时发出严重警告
+    #[serde(default = "default_critical_ratio")]
+    pub critical_ratio: f64,
+}
+
+fn default_warning_ratio() -> f64 {
+    0.8
+}
+
+fn default_critical_ratio() -> f64 {
+    0.95
+}
+
+impl WorkspaceQuota {
+    /// 使用默认阈值创建配额
+    pub fn new(max_bytes: u64) -> Self {
+        Self {
+            max_bytes,
+            warning_ratio: default_warning_ratio(),
+            critical_ratio: default_critical_ratio(),
+        }
+    }
+
+    /// 校验配额配置
+    pub fn validate(&self) -> Result<()> {
+        if self.max_bytes == 0 {
+            return Err(DatabaseError::validation("工作空间配额必须大于0"));
+        }
+        if !(self.warning_ratio > 0.0 && self.warning_ratio <= self.critical_ratio && self.critical_ratio <= 1.0) {
+            return Err(DatabaseError::validation(format!(
+                "配额阈值无效: 需满足 0 < 警告阈值({}) <= 严重阈值({}) <= 1",
+                self.warning_ratio, self.critical_ratio
+            )));
+        }
+        Ok(())
+    }
+
+    /// 读取项目配置的配额，未配置时返回 None
+    pub fn from_project(project: &project::Model) -> Result<Option<Self>> {
+        project
+            .workspace_quota
+            .clone()
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(DatabaseError::from)
+    }
+
+    /// 计算给定占用所处的级别
+    pub fn level_for(&self, used_bytes: u64) -> QuotaLevel {
+        let ratio = used_bytes as f64 / self.max_bytes as f64;
+        if used_bytes > self.max_bytes {
+            QuotaLevel::Exceeded
+        } else if ratio >= self.critical_ratio {
+            QuotaLevel::Critical
+        } else if ratio >= self.warning_ratio {
+            QuotaLevel::Warning
+        } else {
+            QuotaLevel::Normal
+        }
+    }
+}
+
+/// 配额占用级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaLevel {
+    /// 正常
+    Normal,
+    /// 达到警告阈值
+    Warning,
+    /// 达到严重阈值
+    Critical,
+    /// 超出配额
+    Exceeded,
+}
+
+impl std::fmt::Display for QuotaLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuotaLevel::Normal => write!(f, "normal"),
+            QuotaLevel::Warning => write!(f, "warning"),
+            QuotaLevel::Critical => write!(f, "critical"),
+            QuotaLevel::Exceeded => write!(f, "exceeded"),
+        }
+    }
+}
+
+/// 目录占用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DirectoryUsage {
+    /// 相对工作空间根目录的路径
+    pub path: String,
+    /// 占用字节数
+    pub bytes: u64,
+}
+
+/// 工作空间占用统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceUsage {
+    /// 总字节数
+    pub total_bytes: u64,
+    /// 文件总数
+    pub file_count: u64,
+    /// 顶层条目占用，按字节数降序
+    pub top_level: Vec<DirectoryUsage>,
+    /// 可重新生成的目录（构建产物、依赖、缓存），按字节数降序
+    pub regenerable: Vec<DirectoryUsage>,
+    /// 扫描时间
+    pub scanned_at: Option<DateTime<Utc>>,
+}
+
+/// 清理建议
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CleanupSuggestion {
+    /// 相对工作空间根目录的路径
+    pub path: String,
+    /// 可释放的字节数
+    pub bytes: u64,
+    /// 建议原因
+    pub reason: String,
+}
+
+/// 配额检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaReport {
+    /// 项目ID
+    pub project_id: Uuid,
+    /// 工作空间路径
+    pub workspace_path: String,
+    /// 项目配额，未配置时为空
+    pub quota: Option<WorkspaceQuota>,
+    /// 占用统计
+    pub usage: WorkspaceUsage,
+    /// 占用级别，未配置配额时总是 normal
+    pub level: QuotaLevel,
+    /// 清理建议
+    pub suggestions: Vec<CleanupSuggestion>,
+}
+
+impl QuotaReport {
+    /// 占用比例，未配置配额时为空
+    pub fn usage_ratio(&self) -> Option<f64> {
+        self.quota
+            .as_ref()
+            .map(|quota| self.usage.total_bytes as f64 / quota.max_bytes as f64)
+    }
+}
+
+/// 统计工作空间的磁盘占用
+///
+/// 包含 `.git` 等隐藏目录；不跟随符号链接，避免重复统计或统计到工作空间之外。
+/// 目录不存在时返回空统计。
+pub fn scan_usage(root: &Path) -> WorkspaceUsage {
+    /// 统计单个条目的占用；目录递归统计，并记录可再生目录
+    fn entry_bytes(
+        entry: &std::fs::DirEntry,
+        root: &Path,
+        usage: &mut WorkspaceUsage,
+        regenerable: &mut Vec<DirectoryUsage>,
+    ) -> Option<u64> {
+        let file_type = entry.file_type().ok()?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            let bytes = std::fs::read_dir(&path)
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .filter_map(|child| entry_bytes(&child, root, usage, regenerable))
+                        .sum()
+                })
+                .unwrap_or(0);
+            let name = entry.file_name();
+            if REGENERABLE_DIRS.iter().any(|(dir_name, _)| name.to_string_lossy() == *dir_name) {
+                regenerable.push(DirectoryUsage { path: relative_path(root, &path), bytes });
+            }
+            Some(bytes)
+        } else if file_type.is_file() {
+            let bytes = entry.metadata().ok()?.len();
+            usage.file_count += 1;
+            Some(bytes)
+        } else {
+            None
+        }
+    }
+
+    let mut usage = WorkspaceUsage {
+        scanned_at: Some(Utc::now()),
+        ..Default::default()
+    };
+    let mut regenerable = Vec::new();
+    let mut top_level = Vec::new();
+
+    if let Ok(entries) = std::fs::read_dir(root) {
+        for entry in entries.flatten() {
+            if let Some(bytes) = entry_bytes(&entry, root, &mut usage, &mut regenerable) {
+                usage.total_bytes += bytes;
+                top_level.push(DirectoryUsage { path: relative_path(root, &entry.path()), bytes });
+            }
+        }
+    }
+
+    top_level.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    usage.top_level = top_level;
+
+    // 嵌套的可再生目录（如 target 下的 node_modules）已包含在外层目录中，只保留最外层
+    regenerable.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut outermost: Vec<DirectoryUsage> = Vec::new();
+    for dir in regenerable {
+        let nested = outermost
+            .iter()
+            .any(|outer| dir.path.starts_with(&format!("{}/", outer.path)));
+        if !nested {
+            outermost.push(dir);
+        }
+    }
+    outermost.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    usage.regenerable = outermost;
+
+    usage
+}
+
+/// 根据占用统计生成清理建议，按可释放空间降序
+pub fn cleanup_suggestions(usage: &WorkspaceUsage) -> Vec<CleanupSuggestion> {
+    usage
+        .regenerable
+        .iter()
+        .filter(|dir| dir.bytes > 0)
+        .take(MAX_SUGGESTIONS)
+        .map(|dir| {
+            let name = dir.path.rsplit('/').next().unwrap_or(&dir.path);
+            let reason = REGENERABLE_DIRS
+                .iter()
+                .find(|(dir_name, _)| *dir_name == name)
+                .map(|(_, reason)| reason.to_string())
+                .unwrap_or_else(|| "可重新生成".to_string());
+            CleanupSuggestion {
+                path: dir.path.clone(),
+                bytes: dir.bytes,
+                reason,
+            }
+        })
+        .collect()
+}
+
+/// 检查项目工作空间占用
+///
+/// 占用级别与上一次记录的级别不同时（包括回落），记录 `WorkspaceQuotaThresholdCrossed`
+/// 领域事件，事件数据中附带清理建议。未配置配额时只返回占用统计。
+pub async fn check_workspace_quota(db: &DatabaseConnection, project_id: Uuid) -> Result<QuotaReport> {
+    let project = project::Entity::find_by_id(project_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+    let quota = WorkspaceQuota::from_project(&project)?;
+
+    let root = std::path::PathBuf::from(&project.workspace_path);
+    let usage = tokio::task::spawn_blocking(move || scan_usage(&root))
+        .await
+        .map_err(|e| DatabaseError::Other(e.into()))?;
+
+    let level = quota
+        .as_ref()
+        .map(|quota| quota.level_for(usage.total_bytes))
+        .unwrap_or(QuotaLevel::Normal);
+    let suggestions = cleanup_suggestions(&usage);
+
+    let report = QuotaReport {
+        project_id,
+        workspace_path: project.workspace_path,
+        quota,
+        usage,
+        level,
+        suggestions,
+    };
+
+    if let Some(quota) = &report.quota {
+        let previous = last_recorded_level(db, project_id).await?;
+        if previous != level {
+            record_threshold_event(db, &report, quota, previous).await?;
+        }
+    }
+
+    Ok(report)
+}
+
+/// 在写入工作空间前检查配额，超出配额时拒绝
+///
+/// 项目不存在或未配置配额时直接放行。
+pub async fn ensure_within_quota(db: &DatabaseConnection, project_id: Uuid) -> Result<()> {
+    let Some(project) = project::Entity::find_by_id(project_id).one(db).await? else {
+        return Ok(());
+    };
+    if project.workspace_quota.is_none() {
+        return Ok(());
+    }
+
+    let report = check_workspace_quota(db, project_id).await?;
+    match (&report.quota, report.level) {
+        (Some(quota), QuotaLevel::Exceeded) => Err(DatabaseError::QuotaExceeded {
+            project_id: project_id.to_string(),
+            used_bytes: report.usage.total_bytes,
+            max_bytes: quota.max_bytes,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// 最近一次阈值事件记录的级别，没有记录时视为正常
+async fn last_recorded_level(db: &DatabaseConnection, project_id: Uuid) -> Result<QuotaLevel> {
+    let event_type = DomainEventType::WorkspaceQuotaThresholdCrossed.to_string();
+    let level = DomainEventRepository::new(db.clone())
+        .find_by_aggregate_id(project_id)
+        .await?
+        .into_iter()
+        .rev()
+        .find(|event| event.event_type == event_type)
+        .and_then(|event| serde_json::from_value(event.event_data["level"].clone()).ok())
+        .unwrap_or(QuotaLevel::Normal);
+    Ok(level)
+}
+
+async fn record_threshold_event(
+    db: &DatabaseConnection,
+    report: &QuotaReport,
+    quota: &WorkspaceQuota,
+    previous: QuotaLevel,
+) -> Result<()> {
+    let repo = DomainEventRepository::new(db.clone());
+    let version = repo.get_latest_version(report.project_id).await? + 1;
+    repo.create(CreateDomainEventData {
+        aggregate_type: AggregateType::Project.to_string(),
+        aggregate_id: report.project_id,
+        event_type: DomainEventType::WorkspaceQuotaThresholdCrossed.to_string(),
+        event_data: json!({
+            "previous_level": previous,
+            "level": report.level,
+            "used_bytes": report.usage.total_bytes,
+            "max_bytes": quota.max_bytes,
+            "workspace_path": report.workspace_path,
+            "suggestions": report.suggestions,
+        }),
+        event_version: version,
+    })
+    .await?;
+    Ok(())
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
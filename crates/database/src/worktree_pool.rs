@@ -0,0 +1,311 @@
+//! 执行会话的Git工作树池
+//!
+//! 同一仓库上的并发任务不再共用项目的 `workspace_path`：[`WorktreePool`] 为每个执行会话
+//! 分配一个独立的 `git worktree`，路径记录在 `execution_sessions.worktree_path`。
+//! 工作树位于 `<池根目录>/<项目ID>/wt-<序号>`，生命周期由会话状态决定：
+//! - 被等待中或运行中的会话引用时为使用中；
+//! - 会话释放后工作树切换到分离头指针并清理未提交的改动，成为空闲工作树，供后续会话复用；
+//! - 空闲工作树超过上限或目录已丢失时由 [`WorktreePool::prune`] 删除。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::{
+    entities::execution_session::{self, ExecutionStatus},
+    repository::{ExecutionSessionRepository, ProjectRepository},
+    DatabaseConnection, DatabaseError, Result,
+};
+
+/// 默认每个项目保留的空闲工作树数量
+pub const DEFAULT_MAX_IDLE_WORKTREES: usize = 2;
+
+/// 工作树目录名前缀
+const WORKTREE_PREFIX: &str = "wt-";
+
+/// 工作树状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorktreeState {
+    /// 被等待中或运行中的会话使用
+    InUse,
+    /// 空闲，可被复用
+    Idle,
+}
+
+/// 工作树信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorktreeInfo {
+    pub path: PathBuf,
+    /// 检出的分支，分离头指针时为空
+    pub branch: Option<String>,
+    /// 使用中的会话
+    pub session_id: Option<Uuid>,
+    pub state: WorktreeState,
+}
+
+/// 工作树池
+pub struct WorktreePool {
+    db: DatabaseConnection,
+    root: PathBuf,
+    max_idle: usize,
+    /// 串行化分配和回收，避免两个会话拿到同一个空闲工作树
+    lock: Mutex<()>,
+}
+
+impl WorktreePool {
+    /// 在指定根目录下创建工作树池
+    pub fn new(db: DatabaseConnection, root: impl Into<PathBuf>) -> Self {
+        Self {
+            db,
+            root: root.into(),
+            max_idle: DEFAULT_MAX_IDLE_WORKTREES,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// 设置每个项目保留的空闲工作树数量
+    pub fn with_max_idle(mut self, max_idle: usize) -> Self {
+        self.max_idle = max_idle;
+        self
+    }
+
+    /// 项目的工作树目录
+    pub fn project_dir(&self, project_id: Uuid) -> PathBuf {
+        self.root.join(project_id.to_string())
+    }
+
+    /// 为会话分配工作树并检出会话分支
+    ///
+    /// 会话已分配过工作树时直接返回；优先复用空闲工作树，没有空闲时新建。
+    /// 分支从会话的基准提交创建，没有基准提交时使用项目工作空间的当前提交
+    pub async fn acquire(&self, session_id: Uuid) -> Result<PathBuf> {
+        let _guard = self.lock.lock().await;
+        let sessions = ExecutionSessionRepository::new(self.db.clone());
+        let session = sessions
+            .find_by_id(session_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+        if let Some(path) = &session.worktree_path {
+            if is_active(&session) && Path::new(path).is_dir() {
+                return Ok(PathBuf::from(path));
+            }
+        }
+        if !is_active(&session) {
+            return Err(DatabaseError::business_logic(format!(
+                "执行会话 {} 已结束（{}），不能分配工作树",
+                session_id, session.status
+            )));
+        }
+
+        let workspace = self.workspace(session.project_id).await?;
+        let base = match &session.base_commit {
+            Some(commit) => commit.clone(),
+            None => git(&workspace, &["rev-parse", "HEAD"]).await?,
+        };
+
+        let idle = self.idle_worktrees(session.project_id).await?;
+        let path = match idle.into_iter().next() {
+            Some(path) => {
+                git(&path, &["reset", "--hard", "-q"]).await?;
+                git(&path, &["clean", "-fdxq"]).await?;
+                git(&path, &["checkout", "-q", "-B", &session.git_branch, &base]).await?;
+                tracing::info!("执行会话 {} 复用工作树 {}", session_id, path.display());
+                path
+            }
+            None => {
+                let path = self.next_slot(session.project_id).await?;
+                let target = path.to_string_lossy().to_string();
+                git(&workspace, &["worktree", "add", "-q", "-B", &session.git_branch, &target, &base]).await?;
+                tracing::info!("执行会话 {} 新建工作树 {}", session_id, path.display());
+                path
+            }
+        };
+
+        sessions
+            .set_worktree_path(session_id, Some(path.to_string_lossy().to_string()))
+            .await?;
+        Ok(path)
+    }
+
+    /// 会话结束后释放其工作树：清理未提交的改动并切换到分离头指针，超出空闲上限的工作树被删除
+    ///
+    /// 会话分支保留在仓库中，会话记录的工作树路径不清除，便于追溯
+    pub async fn release(&self, session_id: Uuid) -> Result<()> {
+        let session = ExecutionSessionRepository::new(self.db.clone())
+            .find_by_id(session_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("ExecutionSession", session_id))?;
+        let Some(path) = session.worktree_path.map(PathBuf::from) else {
+            return Ok(());
+        };
+
+        {
+            let _guard = self.lock.lock().await;
+            if path.is_dir() {
+                git(&path, &["reset", "--hard", "-q"]).await?;
+                git(&path, &["clean", "-fdxq"]).await?;
+                git(&path, &["checkout", "-q", "--detach"]).await?;
+            }
+        }
+        self.prune(session.project_id).await?;
+        Ok(())
+    }
+
+    /// 删除超出空闲上限的工作树，并清理目录已丢失的工作树记录，返回被删除的路径
+    pub async fn prune(&self, project_id: Uuid) -> Result<Vec<PathBuf>> {
+        let _guard = self.lock.lock().await;
+        let workspace = self.workspace(project_id).await?;
+        git(&workspace, &["worktree", "prune"]).await?;
+
+        let idle = self.idle_worktrees(project_id).await?;
+        let mut removed = Vec::new();
+        for path in idle.into_iter().skip(self.max_idle) {
+            let target = path.to_string_lossy().to_string();
+            git(&workspace, &["worktree", "remove", "--force", &target]).await?;
+            tracing::info!("删除空闲工作树 {}", path.display());
+            removed.push(path);
+        }
+        Ok(removed)
+    }
+
+    /// 列出项目的工作树及其状态
+    pub async fn list(&self, project_id: Uuid) -> Result<Vec<WorktreeInfo>> {
+        let workspace = self.workspace(project_id).await?;
+        let active = self.active_sessions(project_id).await?;
+        let listing = git(&workspace, &["worktree", "list", "--porcelain"]).await?;
+        let project_dir = canonical(&self.project_dir(project_id));
+
+        Ok(parse_worktree_list(&listing)
+            .into_iter()
+            .filter(|(path, _)| path.starts_with(&project_dir))
+            .map(|(path, branch)| {
+                let session_id = active
+                    .iter()
+                    .find(|session| session.worktree_path.as_deref().map(|used| canonical(Path::new(used))) == Some(path.clone()))
+                    .map(|session| session.session_id);
+                WorktreeInfo {
+                    state: if session_id.is_some() { WorktreeState::InUse } else { WorktreeState::Idle },
+                    path,
+                    branch,
+                    session_id,
+                }
+            })
+            .collect())
+    }
+
+    async fn workspace(&self, project_id: Uuid) -> Result<PathBuf> {
+        let project = ProjectRepository::new(self.db.clone())
+            .find_by_id(project_id)
+            .await?
+            .ok_or_else(|| DatabaseError::entity_not_found("Project", project_id))?;
+        Ok(PathBuf::from(project.workspace_path))
+    }
+
+    async fn active_sessions(&self, project_id: Uuid) -> Result<Vec<execution_session::Model>> {
+        Ok(ExecutionSessionRepository::new(self.db.clone())
+            .find_by_project_id(project_id)
+            .await?
+            .into_iter()
+            .filter(is_active)
+            .collect())
+    }
+
+    /// 项目目录下未被活动会话引用的工作树，按序号排序
+    async fn idle_worktrees(&self, project_id: Uuid) -> Result<Vec<PathBuf>> {
+        let in_use: HashSet<PathBuf> = self
+            .active_sessions(project_id)
+            .await?
+            .into_iter()
+            .filter_map(|session| session.worktree_path.map(PathBuf::from))
+            .collect();
+        Ok(self
+            .slots(project_id)
+            .await?
+            .into_iter()
+            .map(|(_, path)| path)
+            .filter(|path| !in_use.contains(path))
+            .collect())
+    }
+
+    /// 项目目录下已存在的工作树（序号, 路径），按序号排序
+    async fn slots(&self, project_id: Uuid) -> Result<Vec<(usize, PathBuf)>> {
+        let dir = self.project_dir(project_id);
+        let mut slots = Vec::new();
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(slots),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(index) = name.strip_prefix(WORKTREE_PREFIX).and_then(|index| index.parse().ok()) else {
+                continue;
+            };
+            // 只有包含 `.git` 文件的目录才是有效的工作树
+            if entry.path().join(".git").exists() {
+                slots.push((index, entry.path()));
+            }
+        }
+        slots.sort();
+        Ok(slots)
+    }
+
+    /// 最小的未使用序号对应的路径
+    async fn next_slot(&self, project_id: Uuid) -> Result<PathBuf> {
+        let dir = self.project_dir(project_id);
+        tokio::fs::create_dir_all(&dir).await?;
+        let mut index = 0;
+        while dir.join(format!("{}{}", WORKTREE_PREFIX, index)).exists() {
+            index += 1;
+        }
+        Ok(dir.join(format!("{}{}", WORKTREE_PREFIX, index)))
+    }
+}
+
+fn is_active(session: &execution_session::Model) -> bool {
+    matches!(
+        ExecutionStatus::from(session.status.clone()),
+        ExecutionStatus::Pending | ExecutionStatus::Running
+    )
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// 解析 `git worktree list --porcelain` 的输出为（路径, 分支）
+fn parse_worktree_list(listing: &str) -> Vec<(PathBuf, Option<String>)> {
+    let mut worktrees = Vec::new();
+    for block in listing.split("\n\n") {
+        let mut path = None;
+        let mut branch = None;
+        for line in block.lines() {
+            if let Some(value) = line.strip_prefix("worktree ") {
+                path = Some(PathBuf::from(value));
+            } else if let Some(value) = line.strip_prefix("branch ") {
+                branch = Some(value.strip_prefix("refs/heads/").unwrap_or(value).to_string());
+            }
+        }
+        if let Some(path) = path {
+            worktrees.push((path, branch));
+        }
+    }
+    worktrees
+}
+
+/// 运行 git 命令，失败时返回业务错误
+async fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output().await?;
+    if !output.status.success() {
+        return Err(DatabaseError::business_logic(format!(
+            "git {} 失败: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
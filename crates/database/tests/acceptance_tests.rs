@@ -0,0 +1,212 @@
+//! 验收标准核对清单测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    acceptance::{
+        checklist, complete_with_override, mark_criterion, sign_off, CriterionEvidence, CriterionStatus, EvidenceKind,
+        MarkCriterionData,
+    },
+    entities::{
+        domain_event::DomainEventType,
+        execution_log::{EventType, LogLevel},
+    },
+    repository::{
+        AgentRepository, DomainEventRepository, ExecutionLogRepository, ExecutionSessionRepository, ProjectRepository,
+        TaskRepository, UserRepository,
+        agent_repository::CreateAgentData,
+        execution_log_repository::CreateExecutionLogData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    user_id: Uuid,
+    agent_id: Uuid,
+    task_id: Uuid,
+    log_id: Uuid,
+}
+
+async fn setup_task(db: &DatabaseConnection) -> Fixture {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "验收项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/acceptance".to_string(),
+        })
+        .await
+        .unwrap();
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "开发Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个测试Agent".to_string(),
+            capabilities: json!(["Development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+
+    let tasks = TaskRepository::new(db.clone());
+    let task = tasks
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "登录限流".to_string(),
+            description: "为登录接口增加限流".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+    tasks
+        .update_requirements(task.task_id, None, Some(json!(["每分钟最多5次", "超限返回429"])))
+        .await
+        .unwrap();
+
+    let session = ExecutionSessionRepository::new(db.clone())
+        .create(CreateSessionData {
+            task_id: task.task_id,
+            agent_id: agent.agent_id,
+            project_id: project.project_id,
+            git_branch: "feature/rate-limit".to_string(),
+            base_commit: None,
+            execution_config: None,
+            timeout_minutes: 30,
+        })
+        .await
+        .unwrap();
+    let log = ExecutionLogRepository::new(db.clone())
+        .create(CreateExecutionLogData {
+            session_id: session.session_id,
+            log_level: LogLevel::Info.to_string(),
+            event_type: EventType::TestRun,
+            message: "限流测试通过".to_string(),
+            details: None,
+            timestamp_ms: 0,
+        })
+        .await
+        .unwrap();
+
+    Fixture {
+        user_id: user.user_id,
+        agent_id: agent.agent_id,
+        task_id: task.task_id,
+        log_id: log.log_id,
+    }
+}
+
+#[tokio::test]
+async fn test_completion_requires_verified_criteria() {
+    let db = setup_test_db().await;
+    let fixture = setup_task(&db).await;
+    let tasks = TaskRepository::new(db.clone());
+
+    let error = tasks.update_status(fixture.task_id, "completed").await.unwrap_err();
+    assert!(error.is_unverified_acceptance_criteria());
+
+    // 已验证必须附带证据，且证据必须属于该任务
+    let missing_evidence = mark_criterion(
+        &db,
+        MarkCriterionData {
+            task_id: fixture.task_id,
+            criterion: "每分钟最多5次".to_string(),
+            status: CriterionStatus::Verified,
+            evidence: Vec::new(),
+            agent_id: Some(fixture.agent_id),
+            notes: None,
+        },
+    )
+    .await;
+    assert!(missing_evidence.unwrap_err().is_validation_error());
+    let unknown_criterion = sign_off(&db, fixture.task_id, "不存在的标准", fixture.user_id, true, None).await;
+    assert!(unknown_criterion.unwrap_err().is_validation_error());
+
+    let item = mark_criterion(
+        &db,
+        MarkCriterionData {
+            task_id: fixture.task_id,
+            criterion: "每分钟最多5次".to_string(),
+            status: CriterionStatus::Verified,
+            evidence: vec![CriterionEvidence {
+                kind: EvidenceKind::Log,
+                reference: fixture.log_id.to_string(),
+                description: Some("集成测试日志".to_string()),
+            }],
+            agent_id: Some(fixture.agent_id),
+            notes: None,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(item.status, CriterionStatus::Verified);
+    assert_eq!(item.verified_by_agent_id, Some(fixture.agent_id));
+
+    let list = checklist(&db, fixture.task_id).await.unwrap();
+    assert_eq!(list.verified_count, 1);
+    assert_eq!(list.unverified, vec!["超限返回429".to_string()]);
+    assert!(tasks.update_status(fixture.task_id, "completed").await.is_err());
+
+    // 人工驳回后再签核通过
+    let rejected = sign_off(&db, fixture.task_id, "超限返回429", fixture.user_id, false, Some("缺少测试".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(rejected.status, CriterionStatus::Failed);
+    let approved = sign_off(&db, fixture.task_id, "超限返回429", fixture.user_id, true, None).await.unwrap();
+    assert_eq!(approved.status, CriterionStatus::Verified);
+    assert_eq!(approved.signed_off_by, Some(fixture.user_id));
+    assert_eq!(approved.notes.as_deref(), Some("缺少测试"));
+
+    let list = checklist(&db, fixture.task_id).await.unwrap();
+    assert!(list.is_complete());
+    assert!(list.status_map().values().all(|verified| *verified));
+
+    let completed = tasks.update_status(fixture.task_id, "completed").await.unwrap();
+    assert_eq!(completed.status, "completed");
+}
+
+#[tokio::test]
+async fn test_complete_with_override_records_event() {
+    let db = setup_test_db().await;
+    let fixture = setup_task(&db).await;
+
+    assert!(complete_with_override(&db, fixture.task_id, fixture.user_id, "  ").await.unwrap_err().is_validation_error());
+
+    let task = complete_with_override(&db, fixture.task_id, fixture.user_id, "紧急发布，测试后补")
+        .await
+        .unwrap();
+    assert_eq!(task.status, "completed");
+    assert!(task.completed_at.is_some());
+
+    let events = DomainEventRepository::new(db.clone())
+        .find_by_aggregate_id(fixture.task_id)
+        .await
+        .unwrap();
+    let event = events
+        .iter()
+        .find(|event| event.event_type == DomainEventType::AcceptanceCriteriaOverridden.to_string())
+        .expect("应记录强制完成事件");
+    assert_eq!(event.event_data["overridden_by"], json!(fixture.user_id));
+    assert_eq!(event.event_data["unverified_criteria"], json!(["每分钟最多5次", "超限返回429"]));
+}
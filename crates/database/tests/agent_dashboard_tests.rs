@@ -0,0 +1,225 @@
+//! 智能体性能仪表盘测试
+
+use crate::common::setup_test_db;
+use chrono::{Duration, Utc};
+use codex_database::{
+    agent_dashboard::{agent_history, agent_metrics, team_leaderboard},
+    repository::{
+        AgentPerformanceMetricsRepository, AgentRepository, AgentWorkHistoryRepository, ProjectRepository,
+        TaskRepository, UserRepository,
+        agent_performance_metrics_repository::CreateAgentPerformanceMetricsData,
+        agent_repository::CreateAgentData,
+        agent_work_history_repository::CreateAgentWorkHistoryData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use codex_multi_agent::{AgentId, MetricsPeriod, PaginationParams};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    user_id: Uuid,
+    project_id: Uuid,
+}
+
+async fn setup_project(db: &DatabaseConnection) -> Fixture {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "仪表盘项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/dashboard".to_string(),
+        })
+        .await
+        .unwrap();
+    Fixture { user_id: user.user_id, project_id: project.project_id }
+}
+
+async fn create_agent(db: &DatabaseConnection, fixture: &Fixture, name: &str) -> Uuid {
+    AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: fixture.user_id,
+            name: name.to_string(),
+            description: None,
+            prompt_template: "你是一个开发Agent".to_string(),
+            capabilities: json!(["backend_development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap()
+        .agent_id
+}
+
+async fn create_task(db: &DatabaseConnection, project_id: Uuid, title: &str) -> Uuid {
+    TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: title.to_string(),
+            description: format!("{}的实现", title),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap()
+        .task_id
+}
+
+async fn record_work(
+    db: &DatabaseConnection,
+    agent_id: Uuid,
+    task_id: Uuid,
+    success: Option<bool>,
+    minutes: i32,
+    quality: f64,
+) {
+    AgentWorkHistoryRepository::new(db.clone())
+        .create(CreateAgentWorkHistoryData {
+            agent_id,
+            task_id,
+            task_type: "development".to_string(),
+            success,
+            completion_time_minutes: Some(minutes),
+            quality_score: Some(quality),
+            work_details: None,
+            technologies_used: json!(["rust", "sql"]),
+            error_message: if success == Some(false) { Some("编译失败".to_string()) } else { None },
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_agent_metrics_and_history() {
+    let db = setup_test_db().await;
+    let fixture = setup_project(&db).await;
+    let agent_id = create_agent(&db, &fixture, "后端Agent").await;
+    let first = create_task(&db, fixture.project_id, "实现登录接口").await;
+    let second = create_task(&db, fixture.project_id, "实现注册接口").await;
+
+    record_work(&db, agent_id, first, Some(true), 60, 0.9).await;
+    record_work(&db, agent_id, second, Some(false), 120, 0.5).await;
+    record_work(&db, agent_id, second, Some(true), 90, 0.7).await;
+    // 尚未结束的工作不计入统计
+    record_work(&db, agent_id, second, None, 30, 0.0).await;
+
+    let now = Utc::now() + Duration::minutes(1);
+    let report = agent_metrics(&db, agent_id, MetricsPeriod::Week, now).await.unwrap();
+    assert_eq!(report.agent_name, "后端Agent");
+    assert_eq!(report.tasks_completed, 3);
+    assert_eq!(report.tasks_successful, 2);
+    assert_eq!(report.tasks_failed, 1);
+    assert!((report.success_rate - 2.0 / 3.0).abs() < 1e-9);
+    assert_eq!(report.avg_completion_minutes, Some(90.0));
+    assert!((report.avg_quality_score.unwrap() - 0.7).abs() < 1e-9);
+    assert!(report.performance_score > 0.0 && report.performance_score <= 10.0);
+    assert_eq!(report.task_type_breakdown.get("development"), Some(&3));
+    assert_eq!(report.technologies.get("rust"), Some(&3));
+
+    // 统计周期之外的工作不计入
+    let report = agent_metrics(&db, agent_id, MetricsPeriod::Day, now + Duration::days(3)).await.unwrap();
+    assert_eq!(report.tasks_completed, 0);
+    assert_eq!(report.performance_score, 0.0);
+    assert!(report.avg_quality_score.is_none());
+
+    // 定期汇总的指标作为趋势快照按时间升序返回
+    let metrics = AgentPerformanceMetricsRepository::new(db.clone());
+    for weeks_ago in [2, 1] {
+        let end = Utc::now() - Duration::weeks(weeks_ago);
+        metrics
+            .create(CreateAgentPerformanceMetricsData {
+                agent_id,
+                period_start: (end - Duration::weeks(1)).into(),
+                period_end: end.into(),
+                tasks_completed: 4,
+                tasks_successful: 3,
+                avg_completion_time: 2.0,
+                avg_code_quality: 8.0,
+                skill_improvements: json!({}),
+            })
+            .await
+            .unwrap();
+    }
+    let report = agent_metrics(&db, agent_id, MetricsPeriod::All, now).await.unwrap();
+    assert_eq!(report.trend.len(), 2);
+    assert!(report.trend[0].period_end < report.trend[1].period_end);
+    assert!(report.period_start.is_none());
+
+    assert!(agent_metrics(&db, Uuid::new_v4(), MetricsPeriod::Week, now).await.is_err());
+
+    // 分页查询工作历史
+    let params = PaginationParams { page: Some(1), page_size: Some(3), ..Default::default() };
+    let page = agent_history(&db, agent_id, &params).await.unwrap();
+    assert_eq!(page.total_count, 4);
+    assert_eq!(page.items.len(), 3);
+    assert_eq!(page.total_pages, 2);
+    assert!(page.has_next_page);
+    assert_eq!(page.items[0].agent_id, AgentId(agent_id));
+    assert!(page.items.iter().all(|item| item.task_title.is_some()));
+    assert_eq!(page.items[0].technologies_used, vec!["rust".to_string(), "sql".to_string()]);
+
+    let params = PaginationParams { page: Some(2), page_size: Some(3), ..Default::default() };
+    let page = agent_history(&db, agent_id, &params).await.unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert!(!page.has_next_page);
+}
+
+#[tokio::test]
+async fn test_team_leaderboard_ranks_project_agents() {
+    let db = setup_test_db().await;
+    let fixture = setup_project(&db).await;
+    let other = setup_project(&db).await;
+    let strong = create_agent(&db, &fixture, "资深Agent").await;
+    let weak = create_agent(&db, &fixture, "新手Agent").await;
+    let idle = create_agent(&db, &fixture, "空闲Agent").await;
+
+    let task = create_task(&db, fixture.project_id, "实现缓存").await;
+    let other_task = create_task(&db, other.project_id, "其他项目任务").await;
+
+    record_work(&db, strong, task, Some(true), 60, 0.9).await;
+    record_work(&db, strong, task, Some(true), 60, 0.8).await;
+    record_work(&db, weak, task, Some(false), 240, 0.3).await;
+    record_work(&db, weak, task, Some(true), 180, 0.6).await;
+    record_work(&db, idle, task, None, 10, 0.0).await;
+    // 其他项目的工作不计入本项目排行榜
+    record_work(&db, weak, other_task, Some(true), 30, 1.0).await;
+
+    let now = Utc::now() + Duration::minutes(1);
+    let leaderboard = team_leaderboard(&db, fixture.project_id, MetricsPeriod::Month, now).await.unwrap();
+    assert_eq!(leaderboard.entries.len(), 2);
+    assert_eq!(leaderboard.entries[0].rank, 1);
+    assert_eq!(leaderboard.entries[0].agent_name, "资深Agent");
+    assert_eq!(leaderboard.entries[0].tasks_successful, 2);
+    assert_eq!(leaderboard.entries[1].rank, 2);
+    assert_eq!(leaderboard.entries[1].agent_id, AgentId(weak));
+    assert_eq!(leaderboard.entries[1].tasks_completed, 2);
+    assert!(leaderboard.entries[0].performance_score > leaderboard.entries[1].performance_score);
+
+    let leaderboard = team_leaderboard(&db, fixture.project_id, MetricsPeriod::Day, now + Duration::days(2))
+        .await
+        .unwrap();
+    assert!(leaderboard.entries.is_empty());
+
+    let project_without_tasks = setup_project(&db).await;
+    let leaderboard = team_leaderboard(&db, project_without_tasks.project_id, MetricsPeriod::All, now)
+        .await
+        .unwrap();
+    assert!(leaderboard.entries.is_empty());
+}
@@ -0,0 +1,128 @@
+//! Agent批量配置集成测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    agent_provisioning::{plan_agents, provision_agents, ProvisioningSpec},
+    repository::{
+        AgentRepository, UserRepository,
+        agent_repository::CreateAgentData,
+        user_repository::CreateUserData,
+    },
+};
+use codex_multi_agent::{AgentApprovalPolicy, AgentCapability};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建测试用户的辅助函数
+async fn create_test_user(db: &codex_database::DatabaseConnection) -> Uuid {
+    let repo = UserRepository::new(db.clone());
+    let user_data = CreateUserData {
+        username: format!("provision_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("provision_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    };
+    repo.create(user_data).await.unwrap().user_id
+}
+
+fn team_spec() -> ProvisioningSpec {
+    serde_json::from_value(json!({
+        "template": { "max_concurrent_tasks": 2, "custom_settings": { "team": "core" } },
+        "roles": [
+            {
+                "role": "backend",
+                "capabilities": ["backend_development", "database_design"],
+                "count": 3,
+                "template": { "prompt_template": "你是后端工程师 {role}-{index}" }
+            },
+            { "role": "qa", "capabilities": ["testing"], "count": 1, "template": { "max_concurrent_tasks": 4 } }
+        ]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_plan_agents_applies_templates_and_naming() {
+    let planned = plan_agents(&team_spec()).unwrap();
+    let names: Vec<_> = planned.iter().map(|agent| agent.config.name.as_str()).collect();
+    assert_eq!(names, ["backend-01", "backend-02", "backend-03", "qa-01"]);
+
+    let backend = &planned[1].config;
+    assert_eq!(backend.prompt_template, "你是后端工程师 backend-02");
+    assert_eq!(backend.max_concurrent_tasks, 2);
+    assert_eq!(backend.custom_settings["team"], "core");
+    assert_eq!(backend.capabilities, vec![AgentCapability::BackendDevelopment, AgentCapability::DatabaseDesign]);
+    assert_eq!(backend.approval_policy, AgentApprovalPolicy::default());
+
+    let qa = &planned[3].config;
+    assert_eq!(qa.max_concurrent_tasks, 4, "角色模板应覆盖共用模板");
+    assert!(qa.description.contains("testing"));
+
+    // 命名规则缺少序号、角色重复或能力为空时拒绝
+    let mut spec = team_spec();
+    spec.name_pattern = "{role}".to_string();
+    assert!(plan_agents(&spec).unwrap_err().is_validation_error());
+    let mut spec = team_spec();
+    spec.roles[1].role = "backend".to_string();
+    assert!(plan_agents(&spec).unwrap_err().is_validation_error());
+    let mut spec = team_spec();
+    spec.roles[1].capabilities.clear();
+    assert!(plan_agents(&spec).unwrap_err().is_validation_error());
+}
+
+#[tokio::test]
+async fn test_provision_agents_creates_all_in_one_transaction() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let repo = AgentRepository::new(db.clone());
+
+    let report = provision_agents(&db, user_id, &team_spec()).await.unwrap();
+    assert_eq!(report.created.len(), 4);
+    assert_eq!(report.created_by_role["backend"], 3);
+    assert_eq!(report.created_by_role["qa"], 1);
+
+    let agents = repo.find_by_user_id(user_id).await.unwrap();
+    assert_eq!(agents.len(), 4);
+    let qa = agents.iter().find(|agent| agent.name == "qa-01").unwrap();
+    assert_eq!(qa.capabilities, json!(["testing"]));
+    assert_eq!(qa.config["provisioned_role"], "qa");
+    assert_eq!(qa.config["max_concurrent_tasks"], 4);
+    AgentApprovalPolicy::from_agent_config_json(&qa.config).unwrap();
+
+    // 重名时整体拒绝，不会创建任何Agent
+    let mut spec = team_spec();
+    spec.roles[1].count = 2;
+    let err = provision_agents(&db, user_id, &spec).await.unwrap_err();
+    assert!(err.is_validation_error());
+    assert_eq!(repo.find_by_user_id(user_id).await.unwrap().len(), 4);
+
+    // 跳过已有Agent时只创建新增的部分
+    spec.skip_existing = true;
+    let report = provision_agents(&db, user_id, &spec).await.unwrap();
+    assert_eq!(report.skipped.len(), 4);
+    assert_eq!(report.created.len(), 1);
+    assert_eq!(report.created[0].name, "qa-02");
+}
+
+#[tokio::test]
+async fn test_provision_agents_ignores_other_users() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let other_user = create_test_user(&db).await;
+    AgentRepository::new(db.clone()).create(CreateAgentData {
+        user_id: other_user,
+        name: "qa-01".to_string(),
+        description: None,
+        prompt_template: "测试".to_string(),
+        capabilities: json!(["testing"]),
+        config: json!({}),
+        git_config: None,
+    }).await.unwrap();
+
+    let report = provision_agents(&db, user_id, &team_spec()).await.unwrap();
+    assert_eq!(report.created.len(), 4);
+    assert!(report.skipped.is_empty());
+}
@@ -25,6 +25,7 @@ async fn create_test_db() -> codex_database::DatabaseConnection {
         connect_timeout: 10,
         idle_timeout: 60,
         enable_logging: false,
+        ..Default::default()
     };
     
     initialize_database(&config).await.expect("初始化测试数据库失败")
@@ -54,6 +54,7 @@ fn test_agent_skill_profile_structure() {
         skill_profile: Some(skill_profile.clone()),
         skill_assessments: Some(json!([])),
         performance_trend: Some(json!({"trend": "improving", "score": 8.5})),
+        organization_id: None,
         created_at: now,
         updated_at: now,
         last_active_at: now,
@@ -225,6 +226,7 @@ fn create_test_agent_with_skills() -> AgentModel {
         skill_profile: Some(skill_profile),
         skill_assessments: Some(json!([])),
         performance_trend: Some(json!({"trend": "stable", "score": 8.0})),
+        organization_id: None,
         created_at: now,
         updated_at: now,
         last_active_at: now,
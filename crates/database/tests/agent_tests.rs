@@ -21,6 +21,7 @@ async fn create_test_db() -> codex_database::DatabaseConnection {
         connect_timeout: 10,
         idle_timeout: 60,
         enable_logging: false,
+        ..Default::default()
     };
     
     initialize_database(&config).await.expect("初始化测试数据库失败")
@@ -74,6 +75,7 @@ async fn test_agent_creation() {
         skill_profile: Set(None),
         skill_assessments: Set(None),
         performance_trend: Set(None),
+        organization_id: Set(None),
         total_tasks_completed: Set(0),
         success_rate: Set(0.0),
         average_completion_time: Set(0),
@@ -113,6 +115,7 @@ async fn test_agent_status_updates() {
         skill_profile: Set(None),
         skill_assessments: Set(None),
         performance_trend: Set(None),
+        organization_id: Set(None),
         total_tasks_completed: Set(0),
         success_rate: Set(0.0),
         average_completion_time: Set(0),
@@ -158,6 +161,7 @@ async fn test_agent_capabilities_validation() {
             skill_profile: Set(None),
             skill_assessments: Set(None),
             performance_trend: Set(None),
+            organization_id: Set(None),
             status: Set(AgentStatus::Idle.to_string()),
             current_task_id: Set(None),
             total_tasks_completed: Set(0),
@@ -188,6 +192,7 @@ async fn test_agent_statistics() {
         skill_profile: Set(None),
         skill_assessments: Set(None),
         performance_trend: Set(None),
+        organization_id: Set(None),
         capabilities: Set(json!(["Development"])),
         config: Set(json!({})),
         git_config: Set(None),
@@ -256,6 +261,7 @@ async fn test_agent_config_validation() {
         skill_profile: Set(None),
         skill_assessments: Set(None),
         performance_trend: Set(None),
+        organization_id: Set(None),
         total_tasks_completed: Set(0),
         success_rate: Set(0.0),
         average_completion_time: Set(0),
@@ -0,0 +1,159 @@
+//! API 密钥认证、限流与 REST 接口集成测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    api_keys::{issue_api_key, ApiAuthError, ApiKeyAuthenticator, ApiScope, IssueApiKey},
+    public_api::{ApiRequest, PublicApi},
+    repository::{
+        ApiKeyRepository, ProjectMemberRepository, ProjectRepository, UserRepository,
+        project_repository::CreateProjectData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建测试用户的辅助函数
+async fn create_test_user(db: &DatabaseConnection) -> Uuid {
+    let repo = UserRepository::new(db.clone());
+    repo.create(CreateUserData {
+        username: format!("api_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("api_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id
+}
+
+/// 创建测试项目的辅助函数
+async fn create_test_project(db: &DatabaseConnection, user_id: Uuid) -> Uuid {
+    let repo = ProjectRepository::new(db.clone());
+    repo.create(CreateProjectData {
+        user_id,
+        name: "自动化项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/api.git".to_string(),
+        workspace_path: "/workspace/api".to_string(),
+    }).await.unwrap().project_id
+}
+
+fn issue_request(user_id: Uuid, project_id: Option<Uuid>, scopes: Vec<ApiScope>) -> IssueApiKey {
+    IssueApiKey {
+        user_id,
+        project_id,
+        name: "ci 脚本".to_string(),
+        scopes,
+        rate_limit_per_minute: None,
+        expires_at: None,
+    }
+}
+
+#[tokio::test]
+async fn test_authenticate_checks_scope_project_and_role() {
+    let db = setup_test_db().await;
+    let owner_id = create_test_user(&db).await;
+    let viewer_id = create_test_user(&db).await;
+    let project_id = create_test_project(&db, owner_id).await;
+    let other_project = create_test_project(&db, owner_id).await;
+    ProjectMemberRepository::new(db.clone())
+        .add_member(project_id, viewer_id, ProjectRole::Viewer, Some(owner_id)).await.unwrap();
+
+    let issued = issue_api_key(&db, issue_request(owner_id, Some(project_id), vec![ApiScope::TasksRead]))
+        .await
+        .expect("创建密钥应该成功");
+    assert!(issued.secret.starts_with("sker_"));
+    assert_ne!(issued.key.key_hash, issued.secret);
+
+    let authenticator = ApiKeyAuthenticator::new(db.clone());
+    let principal = authenticator.authenticate(Some(&issued.secret), ApiScope::TasksRead, Some(project_id))
+        .await
+        .expect("认证应该通过");
+    assert_eq!(principal.user_id, owner_id);
+
+    let err = authenticator.authenticate(Some(&issued.secret), ApiScope::TasksWrite, Some(project_id)).await.unwrap_err();
+    assert!(matches!(err, ApiAuthError::InsufficientScope(ApiScope::TasksWrite)));
+    let err = authenticator.authenticate(Some(&issued.secret), ApiScope::TasksRead, Some(other_project)).await.unwrap_err();
+    assert!(matches!(err, ApiAuthError::ProjectNotAllowed(_)));
+    let err = authenticator.authenticate(Some("sker_unknown_secret"), ApiScope::TasksRead, None).await.unwrap_err();
+    assert_eq!(err.status(), 401);
+
+    // 访客的密钥即使带有写权限范围，也不能创建任务
+    let viewer_key = issue_api_key(&db, issue_request(viewer_id, None, vec![ApiScope::TasksWrite])).await.unwrap();
+    let err = authenticator.authenticate(Some(&viewer_key.secret), ApiScope::TasksWrite, Some(project_id)).await.unwrap_err();
+    assert!(matches!(err, ApiAuthError::Forbidden(_)));
+
+    ApiKeyRepository::new(db.clone()).revoke(issued.key.key_id).await.unwrap();
+    let err = authenticator.authenticate(Some(&issued.secret), ApiScope::TasksRead, Some(project_id)).await.unwrap_err();
+    assert!(matches!(err, ApiAuthError::Revoked));
+}
+
+#[tokio::test]
+async fn test_rate_limit_and_usage_counters() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let project_id = create_test_project(&db, user_id).await;
+    let mut request = issue_request(user_id, None, vec![ApiScope::TasksRead]);
+    request.rate_limit_per_minute = Some(2);
+    let issued = issue_api_key(&db, request).await.unwrap();
+
+    let authenticator = ApiKeyAuthenticator::new(db.clone());
+    for _ in 0..2 {
+        authenticator.authenticate(Some(&issued.secret), ApiScope::TasksRead, Some(project_id)).await.unwrap();
+    }
+    let err = authenticator.authenticate(Some(&issued.secret), ApiScope::TasksRead, Some(project_id)).await.unwrap_err();
+    assert_eq!(err.status(), 429);
+    assert!(matches!(err, ApiAuthError::RateLimited { retry_after_secs } if retry_after_secs > 0));
+
+    let key = ApiKeyRepository::new(db.clone()).find_by_id(issued.key.key_id).await.unwrap().unwrap();
+    assert_eq!(key.request_count, 2);
+    assert_eq!(key.throttled_count, 1);
+    assert!(key.last_used_at.is_some());
+}
+
+#[tokio::test]
+async fn test_public_api_creates_and_lists_tasks() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let project_id = create_test_project(&db, user_id).await;
+    let issued = issue_api_key(
+        &db,
+        issue_request(user_id, Some(project_id), vec![ApiScope::TasksRead, ApiScope::TasksWrite]),
+    ).await.unwrap();
+    let authorization = Some(format!("Bearer {}", issued.secret));
+
+    let api = PublicApi::new(db.clone());
+    let response = api.handle(ApiRequest {
+        method: "POST".to_string(),
+        path: format!("/api/v1/projects/{}/tasks", project_id),
+        authorization: authorization.clone(),
+        idempotency_key: None,
+        body: serde_json::to_vec(&serde_json::json!({
+            "title": "由脚本创建的任务",
+            "description": "夜间构建失败",
+        })).unwrap(),
+    }).await;
+    assert_eq!(response.status, 201);
+    assert_eq!(response.body["task"]["title"], "由脚本创建的任务");
+
+    let response = api.handle(ApiRequest {
+        method: "GET".to_string(),
+        path: format!("/api/v1/projects/{}/tasks", project_id),
+        authorization,
+        idempotency_key: None,
+        body: Vec::new(),
+    }).await;
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body["tasks"].as_array().map(Vec::len), Some(1));
+
+    let response = api.handle(ApiRequest {
+        method: "GET".to_string(),
+        path: "/api/v1/projects".to_string(),
+        authorization: None,
+        idempotency_key: None,
+        body: Vec::new(),
+    }).await;
+    assert_eq!(response.status, 401);
+}
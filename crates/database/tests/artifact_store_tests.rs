@@ -0,0 +1,190 @@
+//! 执行工件存储测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    artifact_store::{ArtifactStore, RetentionPolicy},
+    repository::{
+        AgentRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository, UserRepository,
+        agent_repository::CreateAgentData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+};
+use chrono::{Duration, Utc};
+use codex_multi_agent::{ArtifactType, TestClock};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建一个执行会话（含所需的用户、项目、Agent和任务）
+async fn create_test_session(db: &codex_database::DatabaseConnection) -> Uuid {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "工件项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/artifacts".to_string(),
+        })
+        .await
+        .unwrap();
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "构建Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个测试Agent".to_string(),
+            capabilities: json!(["Development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "构建发布包".to_string(),
+            description: "构建并上传产物".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+    ExecutionSessionRepository::new(db.clone())
+        .create(CreateSessionData {
+            task_id: task.task_id,
+            agent_id: agent.agent_id,
+            project_id: project.project_id,
+            git_branch: "feature/artifacts".to_string(),
+            base_commit: None,
+            execution_config: None,
+            timeout_minutes: 30,
+        })
+        .await
+        .unwrap()
+        .session_id
+}
+
+#[tokio::test]
+async fn test_save_fetch_and_list_artifacts() {
+    let db = setup_test_db().await;
+    let root = tempfile::tempdir().unwrap();
+    let store = ArtifactStore::new(db.clone(), root.path());
+    let session_id = create_test_session(&db).await;
+
+    let patch = store
+        .save(session_id, "changes.patch", ArtifactType::SourceCode, b"diff --git a/x b/x\n", Some("代码变更".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(patch.size_bytes, 19);
+    assert_eq!(patch.checksum.len(), 64);
+    assert!(store.blob_path(&patch.checksum).unwrap().exists());
+
+    // 相同内容只存一份
+    let copy = store
+        .save(session_id, "copy.patch", ArtifactType::SourceCode, b"diff --git a/x b/x\n", None)
+        .await
+        .unwrap();
+    assert_eq!(copy.checksum, patch.checksum);
+
+    let (artifact, content) = store.fetch(patch.artifact_id).await.unwrap();
+    assert_eq!(artifact.name, "changes.patch");
+    assert_eq!(content, b"diff --git a/x b/x\n");
+
+    let infos = store.list(session_id).await.unwrap();
+    assert_eq!(infos.len(), 2);
+    assert_eq!(infos[0].artifact_type, ArtifactType::SourceCode);
+    assert_eq!(infos[0].description.as_deref(), Some("代码变更"));
+
+    // 还有引用时不删除数据
+    store.delete(copy.artifact_id).await.unwrap();
+    assert!(store.blob_path(&patch.checksum).unwrap().exists());
+    store.delete(patch.artifact_id).await.unwrap();
+    assert!(!store.blob_path(&patch.checksum).unwrap().exists());
+    assert!(store.list(session_id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_fetch_detects_tampered_content() {
+    let db = setup_test_db().await;
+    let root = tempfile::tempdir().unwrap();
+    let store = ArtifactStore::new(db.clone(), root.path());
+    let session_id = create_test_session(&db).await;
+
+    let artifact = store
+        .save(session_id, "report.txt", ArtifactType::Documentation, b"all tests passed", None)
+        .await
+        .unwrap();
+    std::fs::write(store.blob_path(&artifact.checksum).unwrap(), b"tampered").unwrap();
+
+    assert!(store.fetch(artifact.artifact_id).await.unwrap_err().is_business_error());
+    assert!(store.blob_path("../../etc/passwd").is_err());
+}
+
+#[tokio::test]
+async fn test_retention_policy() {
+    let db = setup_test_db().await;
+    let root = tempfile::tempdir().unwrap();
+    let store = ArtifactStore::new(db.clone(), root.path());
+    let session_id = create_test_session(&db).await;
+
+    let oldest = store.save(session_id, "a.bin", ArtifactType::Binary, &[1; 100], None).await.unwrap();
+    store.save(session_id, "b.bin", ArtifactType::Binary, &[2; 100], None).await.unwrap();
+    let newest = store.save(session_id, "c.bin", ArtifactType::Binary, &[3; 100], None).await.unwrap();
+
+    // 未超限时不清理
+    let report = store.apply_retention(&RetentionPolicy::default()).await.unwrap();
+    assert_eq!(report.removed_artifacts, 0);
+
+    // 总大小超限时从最旧的开始清理
+    let policy = RetentionPolicy { max_age_days: None, max_total_bytes: Some(150) };
+    let report = store.apply_retention(&policy).await.unwrap();
+    assert_eq!(report.removed_artifacts, 2);
+    assert_eq!(report.removed_blobs, 2);
+    assert_eq!(report.freed_bytes, 200);
+    assert!(!store.blob_path(&oldest.checksum).unwrap().exists());
+
+    let remaining = store.list(session_id).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].checksum, newest.checksum);
+
+    // 执行会话删除后，级联删除的工件数据被回收
+    ExecutionSessionRepository::new(db.clone()).delete(session_id).await.unwrap();
+    let report = store.apply_retention(&RetentionPolicy::default()).await.unwrap();
+    assert_eq!(report.removed_blobs, 1);
+    assert!(!store.blob_path(&newest.checksum).unwrap().exists());
+}
+
+#[tokio::test]
+async fn test_retention_by_age_uses_injected_clock() {
+    let db = setup_test_db().await;
+    let root = tempfile::tempdir().unwrap();
+    let clock = TestClock::shared(Utc::now());
+    let store = ArtifactStore::new(db.clone(), root.path()).with_clock(clock.clone());
+    let session_id = create_test_session(&db).await;
+    store.save(session_id, "a.bin", ArtifactType::Binary, &[1; 100], None).await.unwrap();
+
+    let policy = RetentionPolicy { max_age_days: Some(30), max_total_bytes: None };
+    let report = store.apply_retention(&policy).await.unwrap();
+    assert_eq!(report.removed_artifacts, 0);
+
+    // 时钟拨到保留期之后，工件过期被清理
+    clock.advance(Duration::days(31));
+    let report = store.apply_retention(&policy).await.unwrap();
+    assert_eq!(report.removed_artifacts, 1);
+    assert_eq!(report.freed_bytes, 100);
+}
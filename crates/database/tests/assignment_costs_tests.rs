@@ -0,0 +1,167 @@
+//! 任务分配历史成本测试
+
+use crate::common::setup_test_db;
+use chrono::Utc;
+use codex_database::{
+    assignment_costs::{annotate_task_costs, work_cost},
+    repository::{
+        AgentRepository, AgentWorkHistoryRepository, ProjectRepository, TaskRepository, UserRepository,
+        agent_repository::CreateAgentData,
+        agent_work_history_repository::CreateAgentWorkHistoryData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use codex_multi_agent::{
+    AgentCapability, AgentId, AssignmentPolicy, AssignmentStrategy, ProjectId, SimulationAgent, SimulationReport,
+    TaskInfo, TaskType,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+mod common;
+
+/// 测试夹具：用户、项目和两个Agent
+struct Fixture {
+    project_id: Uuid,
+    cheap: Uuid,
+    pricey: Uuid,
+}
+
+async fn setup(db: &DatabaseConnection) -> Fixture {
+    let user_id = UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("cost_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("cost_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id;
+    let project_id = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: "成本项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/cost.git".to_string(),
+        workspace_path: "/workspace/cost".to_string(),
+    }).await.unwrap().project_id;
+    let agents = AgentRepository::new(db.clone());
+    let mut ids = Vec::new();
+    for name in ["低成本Agent", "高成本Agent"] {
+        ids.push(agents.create(CreateAgentData {
+            user_id,
+            name: name.to_string(),
+            description: None,
+            prompt_template: "测试".to_string(),
+            capabilities: json!(["backend_development"]),
+            config: json!({}),
+            git_config: None,
+        }).await.unwrap().agent_id);
+    }
+    Fixture { project_id, cheap: ids[0], pricey: ids[1] }
+}
+
+async fn record_work(db: &DatabaseConnection, fixture: &Fixture, agent_id: Uuid, task_type: &str, details: serde_json::Value) {
+    let task_id = TaskRepository::new(db.clone()).create(CreateTaskData {
+        project_id: fixture.project_id,
+        parent_task_id: None,
+        llm_session_id: None,
+        title: "历史任务".to_string(),
+        description: "测试".to_string(),
+        task_type: "development".to_string(),
+    }).await.unwrap().task_id;
+    AgentWorkHistoryRepository::new(db.clone()).create(CreateAgentWorkHistoryData {
+        agent_id,
+        task_id,
+        task_type: task_type.to_string(),
+        success: Some(true),
+        completion_time_minutes: Some(30),
+        quality_score: None,
+        work_details: Some(details),
+        technologies_used: json!([]),
+        error_message: None,
+    }).await.unwrap();
+}
+
+fn backend_agent(agent_id: Uuid) -> SimulationAgent {
+    SimulationAgent {
+        agent_id: AgentId(agent_id),
+        capabilities: vec![AgentCapability::BackendDevelopment],
+        available_from: None,
+        calibrated_confidence: None,
+        familiar_paths: vec![],
+        task_costs: Default::default(),
+    }
+}
+
+#[test]
+fn test_work_cost_prefers_recorded_cost() {
+    assert_eq!(work_cost(Some(&json!({ "cost": 1.5, "token_count": 9000 })), Some(1.0)), Some(1.5));
+    assert_eq!(work_cost(Some(&json!({ "token_count": 2000 })), Some(0.5)), Some(1.0));
+    assert_eq!(work_cost(Some(&json!({ "token_count": 2000 })), None), None);
+    assert_eq!(work_cost(Some(&json!({ "cost": -1.0 })), None), None);
+    assert_eq!(work_cost(None, Some(1.0)), None);
+}
+
+#[tokio::test]
+async fn test_annotated_costs_drive_cost_optimized_assignment() {
+    let db = setup_test_db().await;
+    let fixture = setup(&db).await;
+    let (cheap, pricey) = (fixture.cheap, fixture.pricey);
+    record_work(&db, &fixture, cheap, "development", json!({ "cost": 1.0 })).await;
+    record_work(&db, &fixture, cheap, "development", json!({ "token_count": 4000 })).await;
+    record_work(&db, &fixture, cheap, "testing", json!({ "cost": 9.0 })).await;
+    record_work(&db, &fixture, cheap, "unknown_type", json!({ "cost": 100.0 })).await;
+    record_work(&db, &fixture, pricey, "development", json!({ "cost": 6.0 })).await;
+    record_work(&db, &fixture, pricey, "development", json!({ "summary": "没有成本数据" })).await;
+
+    let mut agents = vec![backend_agent(pricey), backend_agent(cheap)];
+    annotate_task_costs(&db, &mut agents, Some(0.5)).await.unwrap();
+    assert_eq!(agents[0].task_costs, HashMap::from([(TaskType::Development, 6.0)]));
+    assert_eq!(agents[1].task_costs, HashMap::from([(TaskType::Development, 1.5), (TaskType::Testing, 9.0)]));
+
+    let task: TaskInfo = serde_json::from_value(json!({
+        "task_id": Uuid::new_v4(),
+        "title": "订单接口",
+        "description": "",
+        "task_type": "development",
+        "priority": "medium",
+        "estimated_hours": 4,
+        "required_capabilities": ["backend_development"],
+        "dependencies": [],
+        "acceptance_criteria": [],
+        "tags": [],
+        "related_files": [],
+        "test_requirements": {
+            "needs_unit_tests": false,
+            "needs_integration_tests": false,
+            "needs_e2e_tests": false,
+            "required_coverage": 0.0,
+            "special_test_scenarios": []
+        },
+        "complexity_assessment": {
+            "technical_complexity": 3,
+            "business_complexity": 3,
+            "integration_complexity": 3,
+            "overall_complexity": 3,
+            "complexity_notes": []
+        },
+        "risk_factors": [],
+        "subtasks": [],
+        "related_issues": []
+    }))
+    .unwrap();
+    let policy = AssignmentPolicy { strategy: AssignmentStrategy::CostOptimized, ..Default::default() };
+    let report = SimulationReport::simulate_with_policy(
+        ProjectId::new(),
+        std::slice::from_ref(&task),
+        &agents,
+        Utc::now(),
+        &policy,
+        &HashMap::new(),
+    );
+    assert_eq!(report.assignments[0].agent_id, AgentId(cheap));
+    assert!(report.assignments[0].assignment_reasoning.contains("成本 1.50-6.00"));
+    assert_eq!(report.estimated_cost, Some(1.5));
+}
@@ -0,0 +1,325 @@
+//! 项目审计包测试
+
+use crate::common::setup_test_db;
+use chrono::Utc;
+use codex_database::{
+    audit_bundle::{
+        collect_project_audit, export_project_audit, verify_audit_bundle, write_audit_bundle, CHECKSUMS_FILE,
+    },
+    entities::{
+        conflict::{ConflictSeverity, ConflictType},
+        execution_log::{EventType, LogLevel},
+        human_decision::DecisionType,
+    },
+    repository::{
+        AgentRepository, CodeReviewRepository, ConflictRepository, DomainEventRepository, ExecutionArtifactRepository,
+        ExecutionLogRepository, ExecutionSessionRepository, HumanDecisionRepository, ProjectRepository,
+        TaskRepository, UserRepository,
+        agent_repository::CreateAgentData,
+        code_review_repository::CreateCodeReviewData,
+        conflict_repository::CreateConflictData,
+        domain_event_repository::CreateDomainEventData,
+        execution_artifact_repository::CreateArtifactData,
+        execution_log_repository::CreateExecutionLogData,
+        execution_session_repository::CreateSessionData,
+        human_decision_repository::CreateHumanDecisionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use codex_multi_agent::ArtifactType;
+use serde_json::json;
+use std::io::{Cursor, Read, Write};
+use uuid::Uuid;
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+mod common;
+
+struct Fixture {
+    project_id: Uuid,
+    task_id: Uuid,
+    session_id: Uuid,
+}
+
+async fn setup_project(db: &DatabaseConnection, name: &str) -> Fixture {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: name.to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/audit".to_string(),
+        })
+        .await
+        .unwrap();
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "开发Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个开发Agent".to_string(),
+            capabilities: json!(["backend_development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "实现<登录>接口".to_string(),
+            description: "登录接口的实现".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let session = sessions
+        .create(CreateSessionData {
+            task_id: task.task_id,
+            agent_id: agent.agent_id,
+            project_id: project.project_id,
+            git_branch: "feature/login".to_string(),
+            base_commit: None,
+            execution_config: None,
+            timeout_minutes: 30,
+        })
+        .await
+        .unwrap();
+    sessions.start_session(session.session_id).await.unwrap();
+    sessions
+        .complete_session(session.session_id, true, Some("abc123".to_string()), None, None)
+        .await
+        .unwrap();
+
+    let logs = ExecutionLogRepository::new(db.clone());
+    for level in [LogLevel::Info, LogLevel::Info, LogLevel::Error] {
+        logs.create(CreateExecutionLogData {
+            session_id: session.session_id,
+            log_level: level.to_string(),
+            event_type: EventType::TestRun,
+            message: "运行测试".to_string(),
+            details: None,
+            timestamp_ms: 0,
+        })
+        .await
+        .unwrap();
+    }
+    ExecutionArtifactRepository::new(db.clone())
+        .create(CreateArtifactData {
+            session_id: session.session_id,
+            name: "test-report.xml".to_string(),
+            artifact_type: ArtifactType::TestFile,
+            checksum: "0".repeat(64),
+            size_bytes: 128,
+            description: None,
+        })
+        .await
+        .unwrap();
+
+    CodeReviewRepository::new(db.clone())
+        .create(CreateCodeReviewData {
+            task_id: task.task_id,
+            execution_session_id: session.session_id,
+            reviewer_agent_id: agent.agent_id,
+            pull_request_url: "https://github.com/test/repo/pull/1".to_string(),
+            source_branch: "feature/login".to_string(),
+            target_branch: "main".to_string(),
+            review_comments: json!([]),
+            code_changes: json!([]),
+            status: "approved".to_string(),
+            decision: Some("approve".to_string()),
+            overall_comment: Some("实现清晰".to_string()),
+        })
+        .await
+        .unwrap();
+
+    let conflict = ConflictRepository::new(db.clone())
+        .create(CreateConflictData {
+            conflict_type: ConflictType::TaskDependency,
+            severity: ConflictSeverity::High,
+            title: "登录接口依赖冲突".to_string(),
+            description: "依赖的会话模块尚未完成".to_string(),
+            related_entities: json!({}),
+            affected_tasks: json!([task.task_id.to_string()]),
+            affected_agents: json!([]),
+            project_id: None,
+        })
+        .await
+        .unwrap();
+    HumanDecisionRepository::new(db.clone())
+        .create(CreateHumanDecisionData {
+            conflict_id: conflict.conflict_id,
+            user_id: user.user_id,
+            decision_type: DecisionType::Approve.to_string(),
+            decision_data: None,
+            reasoning: Some("先合并登录接口".to_string()),
+            affected_entities: json!({}),
+            follow_up_actions: json!([]),
+        })
+        .await
+        .unwrap();
+
+    let events = DomainEventRepository::new(db.clone());
+    for (aggregate_type, aggregate_id, event_type) in [
+        ("Task", task.task_id, "TaskCreated"),
+        ("ExecutionSession", session.session_id, "SessionCompleted"),
+    ] {
+        events
+            .create(CreateDomainEventData {
+                aggregate_type: aggregate_type.to_string(),
+                aggregate_id,
+                event_type: event_type.to_string(),
+                event_data: json!({}),
+                event_version: 1,
+            })
+            .await
+            .unwrap();
+    }
+
+    Fixture {
+        project_id: project.project_id,
+        task_id: task.task_id,
+        session_id: session.session_id,
+    }
+}
+
+#[tokio::test]
+async fn test_collect_project_audit_scopes_records_to_project() {
+    let db = setup_test_db().await;
+    let fixture = setup_project(&db, "审计项目").await;
+    // 其他项目的记录不进入审计包
+    setup_project(&db, "其他项目").await;
+    DomainEventRepository::new(db.clone())
+        .create(CreateDomainEventData {
+            aggregate_type: "Task".to_string(),
+            aggregate_id: Uuid::new_v4(),
+            event_type: "TaskCreated".to_string(),
+            event_data: json!({}),
+            event_version: 1,
+        })
+        .await
+        .unwrap();
+
+    let audit = collect_project_audit(&db, fixture.project_id).await.unwrap();
+    assert_eq!(audit.project.name, "审计项目");
+    assert_eq!(audit.tasks.len(), 1);
+    assert_eq!(audit.tasks[0].task_id, fixture.task_id);
+    assert_eq!(audit.events.len(), 2);
+    assert_eq!(audit.reviews.len(), 1);
+    assert_eq!(audit.decisions.len(), 1);
+    assert_eq!(audit.decisions[0].decisions.len(), 1);
+    assert_eq!(audit.decisions[0].decisions[0].reasoning.as_deref(), Some("先合并登录接口"));
+
+    assert_eq!(audit.executions.len(), 1);
+    let execution = &audit.executions[0];
+    assert_eq!(execution.session_id, fixture.session_id);
+    assert_eq!(execution.success, Some(true));
+    assert_eq!(execution.final_commit.as_deref(), Some("abc123"));
+    assert!(execution.duration_seconds.is_some());
+    assert_eq!(execution.log_counts.get(&LogLevel::Info.to_string()), Some(&2));
+    assert_eq!(execution.log_counts.get(&LogLevel::Error.to_string()), Some(&1));
+    assert_eq!(execution.artifacts.len(), 1);
+    assert_eq!(execution.artifacts[0].name, "test-report.xml");
+
+    assert!(collect_project_audit(&db, Uuid::new_v4()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_audit_bundle_checksums_and_verification() {
+    let db = setup_test_db().await;
+    let fixture = setup_project(&db, "审计项目").await;
+    let audit = collect_project_audit(&db, fixture.project_id).await.unwrap();
+
+    let now = Utc::now();
+    let mut buffer = Cursor::new(Vec::new());
+    let manifest = write_audit_bundle(&audit, &mut buffer, now).unwrap();
+    assert_eq!(manifest.project_id, fixture.project_id);
+    assert_eq!(manifest.counts.tasks, 1);
+    assert_eq!(manifest.counts.events, 2);
+    assert_eq!(manifest.counts.conflicts, 1);
+    assert_eq!(manifest.counts.decisions, 1);
+    assert_eq!(manifest.counts.reviews, 1);
+    assert_eq!(manifest.counts.executions, 1);
+    let paths: Vec<&str> = manifest.files.iter().map(|file| file.path.as_str()).collect();
+    for path in ["project.json", "tasks.json", "events.json", "decisions.json", "reviews.json", "executions.json", "index.html"] {
+        assert!(paths.contains(&path), "缺少 {}", path);
+    }
+
+    // 相同的审计记录得到相同的归档
+    let mut again = Cursor::new(Vec::new());
+    write_audit_bundle(&audit, &mut again, now).unwrap();
+    assert_eq!(buffer.get_ref(), again.get_ref());
+
+    let bytes = buffer.into_inner();
+    let verified = verify_audit_bundle(Cursor::new(bytes.clone())).unwrap();
+    assert_eq!(verified, manifest);
+
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+    let mut html = String::new();
+    archive.by_name("index.html").unwrap().read_to_string(&mut html).unwrap();
+    assert!(html.contains("实现&lt;登录&gt;接口"));
+    assert!(html.contains("先合并登录接口"));
+    let mut checksums = String::new();
+    archive.by_name(CHECKSUMS_FILE).unwrap().read_to_string(&mut checksums).unwrap();
+    let tasks_sha = &manifest.files.iter().find(|file| file.path == "tasks.json").unwrap().sha256;
+    assert!(checksums.contains(&format!("{}  tasks.json", tasks_sha)));
+
+    // 篡改任意文件后校验失败
+    let mut tampered = ZipWriter::new(Cursor::new(Vec::new()));
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).unwrap();
+        let name = entry.name().to_string();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).unwrap();
+        if name == "tasks.json" {
+            content = b"[]".to_vec();
+        }
+        tampered.start_file(name, SimpleFileOptions::default()).unwrap();
+        tampered.write_all(&content).unwrap();
+    }
+    let tampered = tampered.finish().unwrap().into_inner();
+    let error = verify_audit_bundle(Cursor::new(tampered)).unwrap_err();
+    assert!(error.is_validation_error());
+    assert!(error.to_string().contains("tasks.json"));
+
+    // 缺少清单的归档不是审计包
+    let mut bare = ZipWriter::new(Cursor::new(Vec::new()));
+    bare.start_file("tasks.json", SimpleFileOptions::default()).unwrap();
+    bare.write_all(b"[]").unwrap();
+    let bare = bare.finish().unwrap().into_inner();
+    assert!(verify_audit_bundle(Cursor::new(bare)).is_err());
+}
+
+#[tokio::test]
+async fn test_export_project_audit_writes_file() {
+    let db = setup_test_db().await;
+    let fixture = setup_project(&db, "审计项目").await;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.zip");
+
+    let manifest = export_project_audit(&db, fixture.project_id, &path, Utc::now()).await.unwrap();
+    let verified = verify_audit_bundle(std::fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(verified, manifest);
+    assert!(!dir.path().join("audit.zip.partial").exists());
+
+    // 项目不存在时不产生文件
+    let missing = dir.path().join("missing.zip");
+    assert!(export_project_audit(&db, Uuid::new_v4(), &missing, Utc::now()).await.is_err());
+    assert!(!missing.exists());
+}
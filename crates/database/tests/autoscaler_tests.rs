@@ -0,0 +1,154 @@
+//! Agent池自动伸缩集成测试
+
+use crate::common::setup_test_db;
+use chrono::{Duration, Utc};
+use codex_database::{
+    autoscaler::{AgentAutoscaler, AutoscalerConfig, ScalingDirection, AUTOSCALE_POOL_CONFIG_KEY},
+    entities::agent::AgentStatus,
+    repository::{
+        AgentRepository, DomainEventRepository, ProjectRepository, TaskRepository, UserRepository,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建测试用户和项目
+async fn create_user_and_project(db: &DatabaseConnection) -> (Uuid, Uuid) {
+    let user_id = UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("autoscale_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("autoscale_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id;
+    let project_id = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: "伸缩项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/autoscale.git".to_string(),
+        workspace_path: "/workspace/autoscale".to_string(),
+    }).await.unwrap().project_id;
+    (user_id, project_id)
+}
+
+/// 创建需要指定能力的待执行任务
+async fn create_queued_tasks(db: &DatabaseConnection, project_id: Uuid, capability: &str, count: usize) -> Vec<Uuid> {
+    let repo = TaskRepository::new(db.clone());
+    let mut task_ids = Vec::new();
+    for i in 0..count {
+        let task = repo.create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: format!("任务{}", i),
+            description: "测试".to_string(),
+            task_type: "development".to_string(),
+        }).await.unwrap();
+        repo.update_requirements(task.task_id, Some(json!([capability])), None).await.unwrap();
+        task_ids.push(task.task_id);
+    }
+    task_ids
+}
+
+fn backend_pool(min_agents: u32, max_agents: u32) -> AutoscalerConfig {
+    serde_json::from_value(json!({
+        "pools": [{
+            "capability": "backend_development",
+            "min_agents": min_agents,
+            "max_agents": max_agents,
+            "max_step": 2,
+            "scale_up_cooldown_secs": 60,
+            "scale_down_cooldown_secs": 300,
+        }]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_autoscaler_config_validation() {
+    backend_pool(0, 3).validate().unwrap();
+    assert!(backend_pool(4, 3).validate().unwrap_err().is_validation_error());
+    assert!(backend_pool(0, 0).validate().unwrap_err().is_validation_error());
+
+    let mut config = backend_pool(0, 3);
+    config.pools[0].scale_down_threshold = 3.0;
+    assert!(config.validate().unwrap_err().is_validation_error());
+
+    let mut config = backend_pool(0, 3);
+    config.pools.push(config.pools[0].clone());
+    assert!(config.validate().unwrap_err().is_validation_error());
+}
+
+#[tokio::test]
+async fn test_autoscaler_scales_up_with_queue_and_cooldown() {
+    let db = setup_test_db().await;
+    let (user_id, project_id) = create_user_and_project(&db).await;
+    create_queued_tasks(&db, project_id, "backend_development", 6).await;
+    create_queued_tasks(&db, project_id, "testing", 2).await;
+    let autoscaler = AgentAutoscaler::new(db.clone(), user_id, backend_pool(0, 3)).unwrap();
+
+    let now = Utc::now();
+    let actions = autoscaler.evaluate(now).await.unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].direction, ScalingDirection::Up);
+    assert_eq!((actions[0].from, actions[0].to), (0, 2), "单次扩容不超过 max_step");
+    assert_eq!(actions[0].queued, 6);
+
+    let agents = AgentRepository::new(db.clone()).find_by_user_id(user_id).await.unwrap();
+    let mut names: Vec<_> = agents.iter().map(|agent| agent.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, ["auto-backend_development-01", "auto-backend_development-02"]);
+    assert_eq!(agents[0].config[AUTOSCALE_POOL_CONFIG_KEY], "backend_development");
+    assert_eq!(agents[0].capabilities, json!(["backend_development"]));
+
+    // 冷却期内不再扩容，冷却结束后扩到上限
+    assert!(autoscaler.evaluate(now + Duration::seconds(30)).await.unwrap().is_empty());
+    let actions = autoscaler.evaluate(now + Duration::seconds(61)).await.unwrap();
+    assert_eq!((actions[0].from, actions[0].to), (2, 3));
+    assert!(autoscaler.evaluate(now + Duration::seconds(200)).await.unwrap().is_empty(), "达到上限后不再扩容");
+
+    let events = DomainEventRepository::new(db.clone()).find_by_event_type("AgentPoolScaled").await.unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].aggregate_id, user_id);
+    assert_eq!(events[0].event_data["direction"], "up");
+}
+
+#[tokio::test]
+async fn test_autoscaler_scales_down_idle_agents_and_reactivates() {
+    let db = setup_test_db().await;
+    let (user_id, project_id) = create_user_and_project(&db).await;
+    let tasks = create_queued_tasks(&db, project_id, "backend_development", 4).await;
+    let autoscaler = AgentAutoscaler::new(db.clone(), user_id, backend_pool(1, 4)).unwrap();
+    let now = Utc::now();
+    autoscaler.evaluate(now).await.unwrap();
+    assert_eq!(autoscaler.snapshot().await.unwrap()[0].active, 2);
+
+    // 队列清空后，冷却结束前不缩容
+    let task_repo = TaskRepository::new(db.clone());
+    for task_id in &tasks {
+        task_repo.update_status(*task_id, "completed").await.unwrap();
+    }
+    assert!(autoscaler.evaluate(now + Duration::seconds(120)).await.unwrap().is_empty());
+
+    let actions = autoscaler.evaluate(now + Duration::seconds(301)).await.unwrap();
+    assert_eq!(actions[0].direction, ScalingDirection::Down);
+    assert_eq!((actions[0].from, actions[0].to), (2, 1), "缩容不低于 min_agents");
+    let offline = AgentRepository::new(db.clone()).find_by_id(actions[0].deactivated[0]).await.unwrap().unwrap();
+    assert_eq!(offline.status, AgentStatus::Offline.to_string());
+
+    let snapshot = autoscaler.snapshot().await.unwrap();
+    assert_eq!((snapshot[0].active, snapshot[0].offline), (1, 1));
+
+    // 再次扩容时先恢复已下线的Agent
+    create_queued_tasks(&db, project_id, "backend_development", 5).await;
+    let actions = autoscaler.evaluate(now + Duration::seconds(400)).await.unwrap();
+    assert_eq!(actions[0].reactivated, vec![offline.agent_id]);
+    assert_eq!(actions[0].created.len(), 1);
+    assert_eq!(actions[0].to, 3);
+}
@@ -0,0 +1,150 @@
+//! 项目黑板测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    blackboard::{
+        delete_entry, entry_history, get_entry, list_entries, write_entry, EntryAuthor, WriteEntryData,
+    },
+    repository::{
+        AgentRepository, ProjectRepository, TaskRepository, UserRepository,
+        agent_repository::CreateAgentData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    ContextBuilder, DatabaseConnection, DatabaseError,
+};
+use codex_multi_agent::SharedContextCategory;
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+async fn create_test_project(db: &DatabaseConnection) -> (Uuid, Uuid) {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "黑板项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/blackboard".to_string(),
+        })
+        .await
+        .unwrap();
+    (user.user_id, project.project_id)
+}
+
+fn entry(project_id: Uuid, key: &str, value: &str, expected_version: Option<u32>, author: EntryAuthor) -> WriteEntryData {
+    WriteEntryData {
+        project_id,
+        key: key.to_string(),
+        category: SharedContextCategory::Decision,
+        value: value.to_string(),
+        include_in_prompts: true,
+        expected_version,
+        author,
+    }
+}
+
+#[tokio::test]
+async fn test_versioned_writes_detect_conflicts() {
+    let db = setup_test_db().await;
+    let (user_id, project_id) = create_test_project(&db).await;
+    let user = EntryAuthor::User(user_id);
+
+    let created = write_entry(&db, entry(project_id, "数据库", "使用 SQLite", None, user)).await.unwrap();
+    assert_eq!(created.version, 1);
+
+    // 新建已存在的键、使用过期版本写入都视为并发冲突
+    let duplicate = write_entry(&db, entry(project_id, "数据库", "使用 PostgreSQL", None, user)).await;
+    assert!(matches!(duplicate, Err(DatabaseError::Conflict { .. })));
+
+    let updated = write_entry(&db, entry(project_id, "数据库", "使用 PostgreSQL", Some(1), user)).await.unwrap();
+    assert_eq!(updated.version, 2);
+    let stale = write_entry(&db, entry(project_id, "数据库", "使用 MySQL", Some(1), user)).await;
+    assert!(matches!(stale, Err(DatabaseError::Conflict { .. })));
+
+    let current = get_entry(&db, project_id, "数据库").await.unwrap().unwrap();
+    assert_eq!(current.value, "使用 PostgreSQL");
+    assert_eq!(current.author, Some(user));
+
+    assert!(matches!(
+        delete_entry(&db, project_id, "数据库", 1, user).await,
+        Err(DatabaseError::Conflict { .. })
+    ));
+    delete_entry(&db, project_id, "数据库", 2, user).await.unwrap();
+    assert!(get_entry(&db, project_id, "数据库").await.unwrap().is_none());
+    assert!(list_entries(&db, project_id).await.unwrap().is_empty());
+
+    // 删除后重新创建，版本号继续递增
+    let recreated = write_entry(&db, entry(project_id, "数据库", "使用 SQLite", None, user)).await.unwrap();
+    assert_eq!(recreated.version, 4);
+    let history = entry_history(&db, project_id, "数据库").await.unwrap();
+    assert_eq!(history.iter().map(|v| v.version).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    assert!(history[2].deleted);
+}
+
+#[tokio::test]
+async fn test_prompt_entries_included_in_context_and_assignment() {
+    let db = setup_test_db().await;
+    let (user_id, project_id) = create_test_project(&db).await;
+
+    write_entry(&db, entry(project_id, "接口前缀", "所有接口以 /api/v1 开头", None, EntryAuthor::User(user_id)))
+        .await
+        .unwrap();
+    let mut private = entry(project_id, "草稿", "尚未确定", None, EntryAuthor::User(user_id));
+    private.include_in_prompts = false;
+    write_entry(&db, private).await.unwrap();
+
+    let context = ContextBuilder::new(db.clone())
+        .with_workspace_scan(false)
+        .build(project_id)
+        .await
+        .unwrap();
+    assert_eq!(context.shared_context.len(), 1);
+    assert_eq!(context.shared_context[0].key, "接口前缀");
+
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id,
+            name: "开发Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个测试Agent".to_string(),
+            capabilities: json!(["Development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+    let tasks = TaskRepository::new(db.clone());
+    let task = tasks
+        .create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "用户接口".to_string(),
+            description: "实现用户查询接口".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+    let assigned = tasks
+        .assign_to_agent(task.task_id, agent.agent_id, "请实现用户查询接口".to_string())
+        .await
+        .unwrap();
+
+    let prompt = assigned.assignment_prompt.unwrap();
+    assert!(prompt.starts_with("请实现用户查询接口"));
+    assert!(prompt.contains("- [决策] 接口前缀: 所有接口以 /api/v1 开头"));
+    assert!(!prompt.contains("草稿"));
+}
@@ -0,0 +1,159 @@
+//! 任务人工检查点测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    checkpoint_gates::{
+        decide_gate, define_gate, gate_timeline, request_gate, waiting_gates, DefineGateData, GateStatus,
+        WAITING_FOR_HUMAN,
+    },
+    entities::domain_event::DomainEventType,
+    repository::{
+        DomainEventRepository, ProjectRepository, TaskRepository, UserRepository,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建进行中的任务，返回 (用户ID, 任务ID)
+async fn setup_task(db: &DatabaseConnection) -> (Uuid, Uuid) {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "检查点项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/gates".to_string(),
+        })
+        .await
+        .unwrap();
+    let tasks = TaskRepository::new(db.clone());
+    let task = tasks
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "拆分订单表".to_string(),
+            description: "按年份拆分订单表并迁移历史数据".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+    tasks.update_status(task.task_id, "in_progress").await.unwrap();
+    (user.user_id, task.task_id)
+}
+
+async fn define(db: &DatabaseConnection, task_id: Uuid, name: &str) -> Uuid {
+    define_gate(db, DefineGateData {
+        task_id,
+        name: name.to_string(),
+        description: None,
+    })
+    .await
+    .unwrap()
+    .gate_id
+}
+
+#[tokio::test]
+async fn test_gates_pause_task_until_approved_in_order() {
+    let db = setup_test_db().await;
+    let (user_id, task_id) = setup_task(&db).await;
+    let tasks = TaskRepository::new(db.clone());
+
+    let design = define(&db, task_id, "设计评审").await;
+    let migration = define(&db, task_id, "迁移前确认").await;
+
+    // 必须按顺序通过
+    let error = request_gate(&db, task_id, "迁移前确认").await.unwrap_err();
+    assert!(error.is_business_error());
+
+    let gate = request_gate(&db, task_id, "设计评审").await.unwrap();
+    assert_eq!(gate.status, "waiting");
+    assert_eq!(gate.request_count, 1);
+    assert_eq!(tasks.find_by_id(task_id).await.unwrap().unwrap().status, WAITING_FOR_HUMAN);
+    assert_eq!(waiting_gates(&db).await.unwrap().len(), 1);
+
+    // 等待期间不能进入其他检查点，也不能完成任务
+    assert!(request_gate(&db, task_id, "设计评审").await.unwrap_err().is_conflict_error());
+    assert!(tasks.update_status(task_id, "completed").await.unwrap_err().is_business_error());
+
+    // 驳回必须填写意见，驳回后任务恢复执行，可以重新提交
+    assert!(decide_gate(&db, design, user_id, false, None).await.unwrap_err().is_validation_error());
+    let rejected = decide_gate(&db, design, user_id, false, Some("缺少回滚方案".to_string())).await.unwrap();
+    assert_eq!(rejected.status, "rejected");
+    assert_eq!(rejected.comment.as_deref(), Some("缺少回滚方案"));
+    assert_eq!(tasks.find_by_id(task_id).await.unwrap().unwrap().status, "in_progress");
+    assert!(decide_gate(&db, design, user_id, true, None).await.unwrap_err().is_validation_error());
+
+    let resubmitted = request_gate(&db, task_id, "设计评审").await.unwrap();
+    assert_eq!(resubmitted.request_count, 2);
+    decide_gate(&db, design, user_id, true, Some("方案可行".to_string())).await.unwrap();
+
+    request_gate(&db, task_id, "迁移前确认").await.unwrap();
+    let timeline = gate_timeline(&db, task_id).await.unwrap();
+    assert_eq!(timeline.gates.len(), 2);
+    assert_eq!(timeline.gates[0].name, "设计评审");
+    assert_eq!(timeline.gates[0].status, GateStatus::Approved);
+    assert_eq!(timeline.gates[0].request_count, 2);
+    assert_eq!(timeline.gates[1].status, GateStatus::Waiting);
+    assert_eq!(timeline.waiting_gate_id, Some(migration));
+    assert!(timeline.total_waiting_seconds >= 0);
+
+    decide_gate(&db, migration, user_id, true, None).await.unwrap();
+    assert!(waiting_gates(&db).await.unwrap().is_empty());
+    let task = tasks.update_status(task_id, "completed").await.unwrap();
+    assert_eq!(task.status, "completed");
+
+    let events = DomainEventRepository::new(db.clone())
+        .find_by_aggregate_id(task_id)
+        .await
+        .unwrap();
+    let decisions: Vec<_> = events
+        .iter()
+        .filter(|event| event.event_type == DomainEventType::HumanGateDecided.to_string())
+        .map(|event| event.event_data["decision"].clone())
+        .collect();
+    assert_eq!(decisions, vec![json!("rejected"), json!("approved"), json!("approved")]);
+}
+
+#[tokio::test]
+async fn test_define_gate_validation() {
+    let db = setup_test_db().await;
+    let (_, task_id) = setup_task(&db).await;
+
+    define(&db, task_id, "设计评审").await;
+    let duplicate = define_gate(&db, DefineGateData {
+        task_id,
+        name: " 设计评审 ".to_string(),
+        description: None,
+    })
+    .await
+    .unwrap_err();
+    assert!(duplicate.is_conflict_error());
+
+    let empty = define_gate(&db, DefineGateData {
+        task_id,
+        name: "  ".to_string(),
+        description: None,
+    })
+    .await
+    .unwrap_err();
+    assert!(empty.is_validation_error());
+
+    assert!(request_gate(&db, task_id, "不存在").await.is_err());
+}
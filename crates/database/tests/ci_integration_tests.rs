@@ -0,0 +1,251 @@
+//! CI 流水线集成测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    ci_integration::{
+        ingest_check, parse_github_webhook, parse_gitlab_webhook, review_ci_status, task_ci_status,
+        verify_github_signature, verify_gitlab_token, CiProvider,
+    },
+    entities::code_review::ReviewDecision,
+    repository::{
+        AgentRepository, CodeReviewRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository,
+        UserRepository,
+        agent_repository::CreateAgentData,
+        code_review_repository::CreateCodeReviewData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use codex_multi_agent::{CiStatus, CodingStandards};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use uuid::Uuid;
+
+mod common;
+
+const REPO_URL: &str = "https://github.com/test/repo";
+
+fn workflow_run(id: u64, sha: &str, status: &str, conclusion: Option<&str>) -> Value {
+    json!({
+        "action": "completed",
+        "workflow_run": {
+            "id": id,
+            "name": "CI",
+            "head_sha": sha,
+            "head_branch": "feature/ci",
+            "status": status,
+            "conclusion": conclusion,
+            "html_url": format!("{}/actions/runs/{}", REPO_URL, id),
+            "run_started_at": "2024-05-01T10:00:00Z",
+            "updated_at": "2024-05-01T10:05:00Z",
+            "pull_requests": [{ "number": 7 }]
+        },
+        "repository": { "html_url": REPO_URL }
+    })
+}
+
+struct Fixture {
+    project_id: Uuid,
+    task_id: Uuid,
+    review_id: Uuid,
+}
+
+async fn setup_review(db: &DatabaseConnection) -> Fixture {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "CI项目".to_string(),
+            description: None,
+            repository_url: format!("{}.git", REPO_URL),
+            workspace_path: "/workspace/ci".to_string(),
+        })
+        .await
+        .unwrap();
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "开发Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个开发Agent".to_string(),
+            capabilities: json!(["backend_development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "接入CI".to_string(),
+            description: "CI 结果参与质量门禁".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let session = sessions
+        .create(CreateSessionData {
+            task_id: task.task_id,
+            agent_id: agent.agent_id,
+            project_id: project.project_id,
+            git_branch: "feature/ci".to_string(),
+            base_commit: None,
+            execution_config: None,
+            timeout_minutes: 30,
+        })
+        .await
+        .unwrap();
+    sessions.start_session(session.session_id).await.unwrap();
+    sessions
+        .complete_session(session.session_id, true, Some("aaa111".to_string()), None, None)
+        .await
+        .unwrap();
+
+    // 审查使用的 PR 地址与 webhook 中的不同，只能通过执行会话关联
+    let review = CodeReviewRepository::new(db.clone())
+        .create(CreateCodeReviewData {
+            task_id: task.task_id,
+            execution_session_id: session.session_id,
+            reviewer_agent_id: agent.agent_id,
+            pull_request_url: format!("{}/pull/99", REPO_URL),
+            source_branch: "feature/ci".to_string(),
+            target_branch: "main".to_string(),
+            review_comments: json!([]),
+            code_changes: json!([]),
+            status: "pending".to_string(),
+            decision: None,
+            overall_comment: None,
+        })
+        .await
+        .unwrap();
+
+    Fixture {
+        project_id: project.project_id,
+        task_id: task.task_id,
+        review_id: review.review_id,
+    }
+}
+
+#[test]
+fn test_parse_webhooks_and_verify_signatures() {
+    let failed = parse_github_webhook("workflow_run", &workflow_run(11, "aaa111", "completed", Some("failure")))
+        .unwrap()
+        .unwrap();
+    assert_eq!(failed.provider, CiProvider::GithubActions);
+    assert_eq!(failed.external_id, "workflow_run:11");
+    assert_eq!(failed.status, CiStatus::Failed);
+    assert_eq!(failed.branch.as_deref(), Some("feature/ci"));
+    assert_eq!(failed.pull_request_url.as_deref(), Some("https://github.com/test/repo/pull/7"));
+    assert!(failed.completed_at.is_some());
+
+    let running = parse_github_webhook("workflow_run", &workflow_run(11, "aaa111", "in_progress", None))
+        .unwrap()
+        .unwrap();
+    assert_eq!(running.status, CiStatus::Running);
+    assert!(running.completed_at.is_none());
+    assert!(parse_github_webhook("push", &json!({})).unwrap().is_none());
+    assert!(parse_github_webhook("check_run", &json!({ "check_run": { "id": 1 } }))
+        .err()
+        .unwrap()
+        .is_validation_error());
+
+    let job = parse_gitlab_webhook(&json!({
+        "object_kind": "build",
+        "build_id": 42,
+        "build_name": "test",
+        "build_status": "canceled",
+        "sha": "bbb222",
+        "ref": "main",
+        "build_started_at": "2024-05-01 10:00:00 UTC",
+        "build_finished_at": "2024-05-01 10:03:00 UTC",
+        "repository": { "homepage": "https://gitlab.com/test/repo" }
+    }))
+    .unwrap()
+    .unwrap();
+    assert_eq!(job.external_id, "job:42");
+    assert_eq!(job.status, CiStatus::Cancelled);
+    assert_eq!(job.details_url.as_deref(), Some("https://gitlab.com/test/repo/-/jobs/42"));
+    assert!(job.started_at.is_some() && job.completed_at.is_some());
+
+    let body = br#"{"zen":"Keep it logically awesome."}"#;
+    let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+    mac.update(body);
+    let signature: String = mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+    assert!(verify_github_signature("secret", body, &format!("sha256={}", signature)).is_ok());
+    assert!(verify_github_signature("other", body, &format!("sha256={}", signature)).is_err());
+    assert!(verify_github_signature("secret", body, &signature).is_err());
+    assert!(verify_gitlab_token("token", "token").is_ok());
+    assert!(verify_gitlab_token("token", "tokem").is_err());
+}
+
+#[tokio::test]
+async fn test_ingest_maps_runs_and_gates_review_approval() {
+    let db = setup_test_db().await;
+    let fixture = setup_review(&db).await;
+    let reviews = CodeReviewRepository::new(db.clone());
+    let ingest = |payload: Value| {
+        let db = db.clone();
+        async move {
+            let update = parse_github_webhook("workflow_run", &payload).unwrap().unwrap();
+            ingest_check(&db, fixture.project_id, update).await.unwrap()
+        }
+    };
+
+    assert_eq!(task_ci_status(&db, fixture.task_id).await.unwrap(), None);
+
+    // 按最终提交映射到执行会话，再关联到会话的审查
+    let run = ingest(workflow_run(11, "aaa111", "completed", Some("failure"))).await;
+    assert!(run.execution_session_id.is_some());
+    assert_eq!(run.review_id, Some(fixture.review_id));
+    assert_eq!(task_ci_status(&db, fixture.task_id).await.unwrap(), Some(CiStatus::Failed));
+
+    // CI 为红色时不能批准，但可以要求修改
+    let error = reviews
+        .submit_decision(fixture.review_id, ReviewDecision::Approved, None)
+        .await
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("CI"));
+
+    // 重新运行通过后可以批准；同一运行的后续 webhook 更新原记录
+    let rerun = ingest(workflow_run(11, "aaa111", "completed", Some("success"))).await;
+    assert_eq!(rerun.check_run_id, run.check_run_id);
+    let review = reviews.find_by_id(fixture.review_id).await.unwrap().unwrap();
+    assert_eq!(review_ci_status(&db, &review).await.unwrap(), Some(CiStatus::Passed));
+
+    // 新提交的检查按分支映射，旧提交的结果不再参与汇总
+    ingest(workflow_run(12, "ccc333", "completed", Some("cancelled"))).await;
+    assert_eq!(task_ci_status(&db, fixture.task_id).await.unwrap(), Some(CiStatus::Cancelled));
+    assert!(reviews.submit_decision(fixture.review_id, ReviewDecision::Approved, None).await.is_err());
+
+    // 质量门禁不要求所有检查通过时不阻止批准
+    let mut standards = CodingStandards::default();
+    standards.quality_gates.require_all_checks_pass = false;
+    ProjectRepository::new(db.clone())
+        .update_config(fixture.project_id, None, Some(serde_json::to_value(standards).unwrap()), None)
+        .await
+        .unwrap();
+    let approved = reviews
+        .submit_decision(fixture.review_id, ReviewDecision::Approved, Some("CI 失败与本次改动无关".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(approved.decision.as_deref(), Some("approved"));
+    assert_eq!(approved.status, "completed");
+}
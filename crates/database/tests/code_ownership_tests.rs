@@ -0,0 +1,260 @@
+//! 代码归属规则测试
+
+use crate::common::setup_test_db;
+use chrono::{Duration, Utc};
+use codex_database::{
+    code_ownership::{annotate_simulation_agents, suggest_reviewers, CodeOwnership, OwnershipRule},
+    repository::{
+        AgentRepository, CodeReviewRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository,
+        UserRepository,
+        agent_repository::CreateAgentData,
+        code_review_repository::CreateCodeReviewData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    review_sla::{evaluate_project, Reviewer},
+    DatabaseConnection,
+};
+use codex_multi_agent::{AgentCapability, AgentId, SimulationAgent};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    project_id: Uuid,
+    task_id: Uuid,
+    developer: Uuid,
+}
+
+async fn setup(db: &DatabaseConnection) -> (Fixture, Uuid) {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "代码归属项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/ownership".to_string(),
+        })
+        .await
+        .unwrap();
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "修复退款金额".to_string(),
+            description: "部分退款时金额计算错误".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+    let developer = create_agent(db, user.user_id, "开发Agent", &["backend_development"]).await;
+
+    (
+        Fixture {
+            project_id: project.project_id,
+            task_id: task.task_id,
+            developer,
+        },
+        user.user_id,
+    )
+}
+
+async fn create_agent(db: &DatabaseConnection, user_id: Uuid, name: &str, capabilities: &[&str]) -> Uuid {
+    AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id,
+            name: name.to_string(),
+            description: None,
+            prompt_template: "你是一个Agent".to_string(),
+            capabilities: json!(capabilities),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap()
+        .agent_id
+}
+
+/// 由开发Agent完成一次修改了指定文件的执行会话，并创建对应的审查
+async fn reviewed_session(db: &DatabaseConnection, fixture: &Fixture, reviewer: Uuid, files: &[&str]) -> Uuid {
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let session = sessions
+        .create(CreateSessionData {
+            task_id: fixture.task_id,
+            agent_id: fixture.developer,
+            project_id: fixture.project_id,
+            git_branch: "fix/refund".to_string(),
+            base_commit: None,
+            execution_config: None,
+            timeout_minutes: 30,
+        })
+        .await
+        .unwrap();
+    sessions.start_session(session.session_id).await.unwrap();
+    sessions
+        .complete_session(session.session_id, true, None, None, None)
+        .await
+        .unwrap();
+
+    let code_changes: Vec<_> = files
+        .iter()
+        .map(|file| json!({ "file_path": file, "change_type": "modified", "lines_added": 3, "lines_removed": 1 }))
+        .collect();
+    CodeReviewRepository::new(db.clone())
+        .create(CreateCodeReviewData {
+            task_id: fixture.task_id,
+            execution_session_id: session.session_id,
+            reviewer_agent_id: reviewer,
+            pull_request_url: "https://github.com/test/repo/pull/9".to_string(),
+            source_branch: "fix/refund".to_string(),
+            target_branch: "main".to_string(),
+            review_comments: json!([]),
+            code_changes: json!(code_changes),
+            status: "pending".to_string(),
+            decision: None,
+            overall_comment: None,
+        })
+        .await
+        .unwrap()
+        .review_id
+}
+
+#[test]
+fn test_codeowners_patterns_and_last_match_wins() {
+    let payments_owner = Uuid::new_v4();
+    let docs_owner = Uuid::new_v4();
+    let ownership = CodeOwnership::parse(&format!(
+        "# 默认由后端Agent审查\n\
+         *.rs capability:backend_development\n\
+         /src/payments/ agent:{payments_owner} capability:security_audit\n\
+         docs/**/*.md user:{docs_owner}\n"
+    ))
+    .unwrap();
+    assert_eq!(ownership.rules.len(), 3);
+
+    let rule = |path: &str| ownership.rule_for(path).map(|rule| rule.pattern.as_str());
+    assert_eq!(rule("crates/core/lib.rs"), Some("*.rs"));
+    assert_eq!(rule("src/payments/refund.rs"), Some("/src/payments/"));
+    assert_eq!(rule("lib/src/payments/refund.rs"), Some("*.rs"));
+    assert_eq!(rule("docs/guide/setup/install.md"), Some("docs/**/*.md"));
+    assert_eq!(rule("docs/index.md"), Some("docs/**/*.md"));
+    assert_eq!(rule("README.md"), None);
+
+    let resolved = ownership.resolve(&[
+        "src/payments/refund.rs".to_string(),
+        "src/payments/mod.rs".to_string(),
+        "docs/api/refund.md".to_string(),
+        "README.md".to_string(),
+    ]);
+    assert_eq!(resolved.owners, vec![Reviewer::Agent(payments_owner), Reviewer::User(docs_owner)]);
+    assert_eq!(resolved.capabilities, vec![AgentCapability::SecurityAudit]);
+    assert_eq!(resolved.unowned_files, vec!["README.md".to_string()]);
+
+    assert!(CodeOwnership::parse("src/ someone").unwrap_err().is_validation_error());
+    assert!(CodeOwnership::parse("src/").unwrap_err().is_validation_error());
+    assert!(CodeOwnership::parse("src/ capability:unknown").unwrap_err().is_validation_error());
+}
+
+#[tokio::test]
+async fn test_owners_are_preferred_for_review_and_reassignment() {
+    let db = setup_test_db().await;
+    let (fixture, user_id) = setup(&db).await;
+    let first = create_agent(&db, user_id, "审查员A", &["code_review"]).await;
+    let general = create_agent(&db, user_id, "审查员B", &["code_review"]).await;
+    let security = create_agent(&db, user_id, "安全审查员", &["code_review", "security_audit"]).await;
+    let payments_owner = create_agent(&db, user_id, "支付负责人", &["backend_development"]).await;
+
+    let projects = ProjectRepository::new(db.clone());
+    let invalid = CodeOwnership {
+        rules: vec![OwnershipRule { pattern: "src/".to_string(), owners: vec![], capabilities: vec![] }],
+    };
+    assert!(projects.set_code_ownership(fixture.project_id, Some(invalid)).await.unwrap_err().is_validation_error());
+    projects
+        .set_code_ownership(
+            fixture.project_id,
+            Some(CodeOwnership {
+                rules: vec![
+                    OwnershipRule {
+                        pattern: "/src/payments/".to_string(),
+                        owners: vec![Reviewer::Agent(payments_owner), Reviewer::Agent(fixture.developer)],
+                        capabilities: vec![],
+                    },
+                    OwnershipRule {
+                        pattern: "*.sql".to_string(),
+                        owners: vec![],
+                        capabilities: vec![AgentCapability::SecurityAudit],
+                    },
+                ],
+            }),
+        )
+        .await
+        .unwrap();
+
+    // 负责人在前（提交变更的开发Agent除外），其后是具备所需能力的审查员，再是其他审查员和项目所有者
+    let files = vec!["src/payments/refund.rs".to_string(), "migrations/refund.sql".to_string()];
+    let suggested = suggest_reviewers(&db, fixture.project_id, &files, Some(fixture.developer)).await.unwrap();
+    assert_eq!(
+        suggested,
+        vec![
+            Reviewer::Agent(payments_owner),
+            Reviewer::Agent(security),
+            Reviewer::Agent(first),
+            Reviewer::Agent(general),
+            Reviewer::User(user_id),
+        ]
+    );
+
+    // 超时改派时先改派给负责人
+    let review_id = reviewed_session(&db, &fixture, first, &["src/payments/refund.rs"]).await;
+    let review = CodeReviewRepository::new(db.clone()).find_by_id(review_id).await.unwrap().unwrap();
+    let overdue = review.review_deadline.unwrap().with_timezone(&Utc) + Duration::hours(1);
+    let report = evaluate_project(&db, fixture.project_id, overdue).await.unwrap();
+    assert_eq!(report.reassignments.len(), 1);
+    assert_eq!(report.reassignments[0].reviewer, Reviewer::Agent(payments_owner));
+}
+
+#[tokio::test]
+async fn test_simulation_agents_get_path_history() {
+    let db = setup_test_db().await;
+    let (fixture, user_id) = setup(&db).await;
+    let reviewer = create_agent(&db, user_id, "审查员", &["code_review"]).await;
+    reviewed_session(&db, &fixture, reviewer, &["src/payments/refund.rs", "src/payments/mod.rs"]).await;
+
+    let mut agents = vec![
+        SimulationAgent {
+            agent_id: AgentId(fixture.developer),
+            capabilities: vec![AgentCapability::BackendDevelopment],
+            available_from: None,
+            calibrated_confidence: None,
+            familiar_paths: vec![],
+            task_costs: Default::default(),
+        },
+        SimulationAgent {
+            agent_id: AgentId(reviewer),
+            capabilities: vec![AgentCapability::CodeReview],
+            available_from: None,
+            calibrated_confidence: None,
+            familiar_paths: vec![],
+            task_costs: Default::default(),
+        },
+    ];
+    annotate_simulation_agents(&db, &mut agents).await.unwrap();
+
+    assert_eq!(agents[0].familiar_paths, vec!["src/payments/refund.rs", "src/payments/mod.rs"]);
+    assert_eq!(agents[0].path_familiarity(&["src/payments/capture.rs".to_string()]), 1);
+    assert!(agents[1].familiar_paths.is_empty());
+}
@@ -34,6 +34,14 @@ async fn test_code_review_creation() {
         overall_comment: None,
         created_at: now,
         reviewed_at: None,
+        diff_artifact_id: None,
+        priority: "normal".to_string(),
+        reviewer_user_id: None,
+        assigned_at: None,
+        review_deadline: None,
+        reminded_at: None,
+        escalated_at: None,
+        reassignment_count: 0,
     };
 
     assert_eq!(code_review.review_id, review_id);
@@ -145,6 +153,14 @@ fn test_code_review_business_methods() {
             .unwrap()
             .with_timezone(&FixedOffset::east_opt(0).unwrap()),
         reviewed_at: None,
+        diff_artifact_id: None,
+        priority: "normal".to_string(),
+        reviewer_user_id: None,
+        assigned_at: None,
+        review_deadline: None,
+        reminded_at: None,
+        escalated_at: None,
+        reassignment_count: 0,
     };
 
     // 测试开始审查
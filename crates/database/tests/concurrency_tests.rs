@@ -0,0 +1,211 @@
+//! SQLite 并发写入测试
+
+use codex_database::{
+    connection::{establish_connection_with_config, retry_on_busy, BusyRetryPolicy},
+    entities::{execution_log::EventType, task},
+    initialize_database,
+    repository::{
+        agent_repository::CreateAgentData, execution_log_repository::CreateExecutionLogData,
+        execution_session_repository::CreateSessionData, project_repository::CreateProjectData,
+        task_repository::CreateTaskData, user_repository::CreateUserData, AgentRepository, ExecutionLogRepository,
+        ExecutionSessionRepository, ProjectRepository, TaskRepository, UserRepository,
+    },
+    DatabaseConfig, DatabaseConnection,
+};
+use sea_orm::{ActiveModelTrait, ConnectionTrait, EntityTrait, Set, Statement, TransactionTrait};
+use serde_json::json;
+use std::time::Duration;
+use uuid::Uuid;
+
+struct Fixture {
+    project_id: Uuid,
+    session_id: Uuid,
+}
+
+async fn setup_session(db: &DatabaseConnection) -> Fixture {
+    let user_id = UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("writer_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("writer_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id;
+    let project_id = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: "并发项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/concurrency.git".to_string(),
+        workspace_path: "/workspace/concurrency".to_string(),
+    }).await.unwrap().project_id;
+    let agent_id = AgentRepository::new(db.clone()).create(CreateAgentData {
+        user_id,
+        name: "开发Agent".to_string(),
+        description: None,
+        prompt_template: "测试".to_string(),
+        capabilities: json!(["Development"]),
+        config: json!({}),
+        git_config: None,
+    }).await.unwrap().agent_id;
+    let task_id = TaskRepository::new(db.clone()).create(task_data(project_id, "执行任务")).await.unwrap().task_id;
+    let session_id = ExecutionSessionRepository::new(db.clone()).create(CreateSessionData {
+        task_id,
+        agent_id,
+        project_id,
+        git_branch: "feature/concurrency".to_string(),
+        base_commit: None,
+        execution_config: None,
+        timeout_minutes: 30,
+    }).await.unwrap().session_id;
+    Fixture { project_id, session_id }
+}
+
+fn task_data(project_id: Uuid, title: &str) -> CreateTaskData {
+    CreateTaskData {
+        project_id,
+        parent_task_id: None,
+        llm_session_id: None,
+        title: title.to_string(),
+        description: "测试".to_string(),
+        task_type: "development".to_string(),
+    }
+}
+
+fn file_config(path: &std::path::Path) -> DatabaseConfig {
+    DatabaseConfig {
+        max_connections: 8,
+        enable_logging: false,
+        ..DatabaseConfig::file(path)
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_writers_do_not_fail_with_busy() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = initialize_database(&file_config(&dir.path().join("stress.db"))).await.unwrap();
+    let mode = db
+        .query_one(Statement::from_string(db.get_database_backend(), "PRAGMA journal_mode"))
+        .await
+        .unwrap()
+        .unwrap()
+        .try_get::<String>("", "journal_mode")
+        .unwrap();
+    assert_eq!(mode, "wal");
+    let fixture = setup_session(&db).await;
+
+    const LOG_WRITERS: usize = 6;
+    const BATCHES: usize = 20;
+    const TASK_WRITERS: usize = 3;
+    const UPDATES: usize = 15;
+
+    // 日志写入：批量插入
+    let mut handles = Vec::new();
+    for writer in 0..LOG_WRITERS {
+        let logs = ExecutionLogRepository::new(db.clone());
+        let session_id = fixture.session_id;
+        handles.push(tokio::spawn(async move {
+            for batch in 0..BATCHES {
+                let data = (0..5)
+                    .map(|line| CreateExecutionLogData {
+                        session_id,
+                        log_level: "info".to_string(),
+                        event_type: EventType::Progress,
+                        message: format!("writer {} batch {} line {}", writer, batch, line),
+                        details: None,
+                        timestamp_ms: line,
+                    })
+                    .collect();
+                logs.create_batch(data).await.unwrap();
+            }
+        }));
+    }
+
+    // 调度器：先读后写的事务，读事务升级为写事务时可能立即返回 SQLITE_BUSY，整体重试
+    for writer in 0..TASK_WRITERS {
+        let db = db.clone();
+        let task_id = TaskRepository::new(db.clone())
+            .create(task_data(fixture.project_id, &format!("调度任务 {}", writer)))
+            .await
+            .unwrap()
+            .task_id;
+        handles.push(tokio::spawn(async move {
+            for _ in 0..UPDATES {
+                retry_on_busy(BusyRetryPolicy { max_retries: 20, ..Default::default() }, || {
+                    let db = db.clone();
+                    async move {
+                        let txn = db.begin().await?;
+                        let current = task::Entity::find_by_id(task_id).one(&txn).await?.unwrap();
+                        let count: usize = current.description.parse().unwrap_or(0);
+                        let mut active: task::ActiveModel = current.into();
+                        active.description = Set((count + 1).to_string());
+                        active.update(&txn).await?;
+                        txn.commit().await?;
+                        Ok(())
+                    }
+                })
+                .await
+                .unwrap();
+            }
+        }));
+    }
+
+    // 界面：持续读取
+    let reader = {
+        let logs = ExecutionLogRepository::new(db.clone());
+        let session_id = fixture.session_id;
+        tokio::spawn(async move {
+            for _ in 0..50 {
+                logs.find_by_session_id(session_id).await.unwrap();
+            }
+        })
+    };
+
+    for handle in handles {
+        handle.await.unwrap();
+    }
+    reader.await.unwrap();
+
+    let logs = ExecutionLogRepository::new(db.clone()).find_by_session_id(fixture.session_id).await.unwrap();
+    assert_eq!(logs.len(), LOG_WRITERS * BATCHES * 5);
+    let tasks = TaskRepository::new(db.clone()).find_by_project(fixture.project_id).await.unwrap();
+    assert_eq!(tasks.len(), TASK_WRITERS + 1);
+    // 每次读-改-写都基于最新值，没有丢失更新
+    assert_eq!(tasks.iter().filter(|t| t.description == UPDATES.to_string()).count(), TASK_WRITERS);
+    db.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_busy_error_is_detected_and_retried() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("locked.db");
+    let holder = initialize_database(&file_config(&path)).await.unwrap();
+    // 不等待锁的连接，立即得到 SQLITE_BUSY
+    let impatient = establish_connection_with_config(&DatabaseConfig {
+        busy_timeout_ms: 0,
+        ..file_config(&path)
+    })
+    .await
+    .unwrap();
+
+    let txn = holder.begin().await.unwrap();
+    txn.execute_unprepared("CREATE TABLE lock_probe (id INTEGER)").await.unwrap();
+
+    let insert = || async {
+        impatient.execute_unprepared("CREATE TABLE other_probe (id INTEGER)").await?;
+        Ok(())
+    };
+    let err = retry_on_busy(BusyRetryPolicy::none(), insert).await.unwrap_err();
+    assert!(err.is_busy(), "应为忙错误: {}", err);
+
+    // 持锁事务稍后提交，重试成功
+    let release = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        txn.commit().await.unwrap();
+    });
+    let policy = BusyRetryPolicy {
+        max_retries: 10,
+        initial_backoff: Duration::from_millis(20),
+        max_backoff: Duration::from_millis(100),
+    };
+    retry_on_busy(policy, insert).await.unwrap();
+    release.await.unwrap();
+}
@@ -3,7 +3,9 @@
 use crate::common::setup_test_db;
 use codex_database::{
     repository::{
-        ConflictRepository, UserRepository,
+        ConflictRepository, ProjectRepository, TaskRepository, UserRepository,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
         user_repository::CreateUserData,
         conflict_repository::{CreateConflictData, ConflictFilter},
     },
@@ -44,6 +46,7 @@ async fn test_create_conflict() {
         }),
         affected_tasks: json!(["task_1", "task_2"]),
         affected_agents: json!(["agent_1"]),
+        project_id: None,
     };
     
     let conflict = conflict_repo.create(conflict_data).await.unwrap();
@@ -73,6 +76,7 @@ async fn test_find_conflict_by_id() {
         }),
         affected_tasks: json!(["task_1", "task_2"]),
         affected_agents: json!(["agent_1", "agent_2"]),
+        project_id: None,
     };
     
     let created_conflict = conflict_repo.create(conflict_data).await.unwrap();
@@ -109,6 +113,7 @@ async fn test_find_conflicts_by_type() {
             related_entities: json!({}),
             affected_tasks: json!([]),
             affected_agents: json!([]),
+            project_id: None,
         };
         conflict_repo.create(conflict_data).await.unwrap();
     }
@@ -156,6 +161,7 @@ async fn test_find_conflicts_by_severity() {
             related_entities: json!({}),
             affected_tasks: json!([]),
             affected_agents: json!([]),
+            project_id: None,
         };
         conflict_repo.create(conflict_data).await.unwrap();
     }
@@ -196,6 +202,7 @@ async fn test_find_conflicts_by_status() {
             related_entities: json!({}),
             affected_tasks: json!([]),
             affected_agents: json!([]),
+            project_id: None,
         };
         let conflict = conflict_repo.create(conflict_data).await.unwrap();
         conflicts.push(conflict);
@@ -247,6 +254,7 @@ async fn test_find_unresolved_conflicts() {
             related_entities: json!({}),
             affected_tasks: json!([]),
             affected_agents: json!([]),
+            project_id: None,
         };
         let conflict = conflict_repo.create(conflict_data).await.unwrap();
         conflicts.push(conflict);
@@ -291,6 +299,7 @@ async fn test_escalate_to_human() {
         related_entities: json!({}),
         affected_tasks: json!([]),
         affected_agents: json!([]),
+        project_id: None,
     };
     
     let conflict = conflict_repo.create(conflict_data).await.unwrap();
@@ -320,6 +329,7 @@ async fn test_resolve_conflict() {
         related_entities: json!({}),
         affected_tasks: json!([]),
         affected_agents: json!([]),
+        project_id: None,
     };
     
     let conflict = conflict_repo.create(conflict_data).await.unwrap();
@@ -356,6 +366,7 @@ async fn test_ignore_conflict() {
         related_entities: json!({}),
         affected_tasks: json!([]),
         affected_agents: json!([]),
+        project_id: None,
     };
     
     let conflict = conflict_repo.create(conflict_data).await.unwrap();
@@ -390,6 +401,7 @@ async fn test_find_requiring_human_intervention() {
             related_entities: json!({}),
             affected_tasks: json!([]),
             affected_agents: json!([]),
+            project_id: None,
         };
         let conflict = conflict_repo.create(conflict_data).await.unwrap();
         conflicts.push(conflict);
@@ -431,6 +443,7 @@ async fn test_find_assigned_to_user() {
             related_entities: json!({}),
             affected_tasks: json!([]),
             affected_agents: json!([]),
+            project_id: None,
         };
         let conflict = conflict_repo.create(conflict_data).await.unwrap();
         conflicts.push(conflict);
@@ -487,6 +500,7 @@ async fn test_conflict_pagination() {
             related_entities: json!({}),
             affected_tasks: json!([]),
             affected_agents: json!([]),
+            project_id: None,
         };
         conflict_repo.create(conflict_data).await.unwrap();
     }
@@ -540,6 +554,7 @@ async fn test_conflict_pagination_with_filter() {
             related_entities: json!({}),
             affected_tasks: json!([]),
             affected_agents: json!([]),
+            project_id: None,
         };
         conflict_repo.create(conflict_data).await.unwrap();
     }
@@ -550,6 +565,7 @@ async fn test_conflict_pagination_with_filter() {
         severity: Some(ConflictSeverity::High),
         status: None,
         escalated_to_human: None,
+        project_id: None,
     };
     
     let (filtered_conflicts, _) = conflict_repo
@@ -588,6 +604,7 @@ async fn test_conflict_statistics() {
             related_entities: json!({}),
             affected_tasks: json!([]),
             affected_agents: json!([]),
+            project_id: None,
         };
         let conflict = conflict_repo.create(conflict_data).await.unwrap();
         conflicts.push(conflict);
@@ -650,6 +667,7 @@ async fn test_find_affecting_task() {
         related_entities: json!({}),
         affected_tasks: json!([task_id, "task_456"]),
         affected_agents: json!([]),
+        project_id: None,
     };
     
     let conflict_data2 = CreateConflictData {
@@ -660,6 +678,7 @@ async fn test_find_affecting_task() {
         related_entities: json!({}),
         affected_tasks: json!([task_id]),
         affected_agents: json!([]),
+        project_id: None,
     };
     
     // 创建不影响该任务的冲突
@@ -671,6 +690,7 @@ async fn test_find_affecting_task() {
         related_entities: json!({}),
         affected_tasks: json!(["task_789"]),
         affected_agents: json!([]),
+        project_id: None,
     };
     
     let conflict1 = conflict_repo.create(conflict_data1).await.unwrap();
@@ -694,6 +714,72 @@ async fn test_find_affecting_task() {
     assert_eq!(affecting_conflicts[0].conflict_id, conflict1.conflict_id);
 }
 
+#[tokio::test]
+async fn test_filter_conflicts_by_project() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let conflict_repo = ConflictRepository::new(db.clone());
+
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id,
+            name: "冲突项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/conflicts".to_string(),
+        })
+        .await
+        .unwrap();
+    let filter = ConflictFilter {
+        conflict_type: None,
+        severity: None,
+        status: None,
+        escalated_to_human: None,
+        project_id: Some(project.project_id),
+    };
+
+    // 项目还没有任务时没有冲突
+    let (conflicts, _) = conflict_repo.find_with_pagination(0, 10, Some(filter.clone())).await.unwrap();
+    assert!(conflicts.is_empty());
+
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "实现登录".to_string(),
+            description: "实现登录接口".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+    let in_project = conflict_repo.create(CreateConflictData {
+        conflict_type: ConflictType::GitMerge,
+        severity: ConflictSeverity::High,
+        title: "登录模块合并冲突".to_string(),
+        description: "两个分支同时修改了登录接口".to_string(),
+        related_entities: json!({}),
+        affected_tasks: json!([task.task_id.to_string()]),
+        affected_agents: json!([]),
+        project_id: None,
+    }).await.unwrap();
+    conflict_repo.create(CreateConflictData {
+        conflict_type: ConflictType::Resource,
+        severity: ConflictSeverity::Low,
+        title: "其他项目的冲突".to_string(),
+        description: "与该项目无关".to_string(),
+        related_entities: json!({}),
+        affected_tasks: json!([Uuid::new_v4().to_string()]),
+        affected_agents: json!([]),
+        project_id: None,
+    }).await.unwrap();
+
+    let (conflicts, total_pages) = conflict_repo.find_with_pagination(0, 10, Some(filter)).await.unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].conflict_id, in_project.conflict_id);
+    assert_eq!(total_pages, 1);
+}
+
 #[tokio::test]
 async fn test_find_affecting_agent() {
     let db = setup_test_db().await;
@@ -711,6 +797,7 @@ async fn test_find_affecting_agent() {
         related_entities: json!({}),
         affected_tasks: json!([]),
         affected_agents: json!([agent_id, "agent_456"]),
+        project_id: None,
     };
     
     let conflict_data2 = CreateConflictData {
@@ -721,6 +808,7 @@ async fn test_find_affecting_agent() {
         related_entities: json!({}),
         affected_tasks: json!([]),
         affected_agents: json!([agent_id]),
+        project_id: None,
     };
     
     let conflict1 = conflict_repo.create(conflict_data1).await.unwrap();
@@ -757,6 +845,7 @@ async fn test_delete_conflict() {
         related_entities: json!({}),
         affected_tasks: json!([]),
         affected_agents: json!([]),
+        project_id: None,
     };
     
     let conflict = conflict_repo.create(conflict_data).await.unwrap();
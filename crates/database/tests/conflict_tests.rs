@@ -24,6 +24,7 @@ async fn create_test_db() -> codex_database::DatabaseConnection {
         connect_timeout: 10,
         idle_timeout: 60,
         enable_logging: false,
+        ..Default::default()
     };
     
     initialize_database(&config).await.expect("初始化测试数据库失败")
@@ -78,6 +79,7 @@ async fn test_conflict_creation() {
         related_entities: Set(related_entities.clone()),
         affected_tasks: Set(affected_tasks.clone()),
         affected_agents: Set(affected_agents.clone()),
+        project_id: Set(None),
         status: Set(ConflictStatus::Detected.to_string()),
         escalated_to_human: Set(false),
         assigned_user_id: Set(None),
@@ -115,6 +117,7 @@ async fn test_conflict_escalation() {
         related_entities: Set(json!([])),
         affected_tasks: Set(json!(["task-789", "task-012"])),
         affected_agents: Set(json!(["agent-003", "agent-004", "agent-005"])),
+        project_id: Set(None),
         status: Set(ConflictStatus::Detected.to_string()),
         escalated_to_human: Set(false),
         assigned_user_id: Set(None),
@@ -160,6 +163,7 @@ async fn test_conflict_resolution() {
         ])),
         affected_tasks: Set(json!(["task-A", "task-B"])),
         affected_agents: Set(json!([])),
+        project_id: Set(None),
         status: Set(ConflictStatus::Escalated.to_string()),
         escalated_to_human: Set(true),
         assigned_user_id: Set(Some(_user.user_id)),
@@ -202,6 +206,7 @@ async fn test_human_decision_creation() {
         related_entities: Set(json!([])),
         affected_tasks: Set(json!(["urgent-task-1", "urgent-task-2"])),
         affected_agents: Set(json!(["agent-006"])),
+        project_id: Set(None),
         status: Set(ConflictStatus::Escalated.to_string()),
         escalated_to_human: Set(true),
         assigned_user_id: Set(Some(_user.user_id)),
@@ -285,6 +290,7 @@ async fn test_auto_resolution() {
         ])),
         affected_tasks: Set(json!(["task-special"])),
         affected_agents: Set(json!([])),
+        project_id: Set(None),
         status: Set(ConflictStatus::Analyzing.to_string()),
         escalated_to_human: Set(false),
         assigned_user_id: Set(None),
@@ -378,6 +384,7 @@ async fn test_complex_conflict_scenario() {
         related_entities: Set(complex_entities),
         affected_tasks: Set(json!(["task-complex-1", "task-complex-2"])),
         affected_agents: Set(json!(["agent-specialist"])),
+        project_id: Set(None),
         status: Set(ConflictStatus::Detected.to_string()),
         escalated_to_human: Set(false),
         assigned_user_id: Set(None),
@@ -0,0 +1,171 @@
+//! 项目上下文快照比较测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    blackboard::{write_entry, EntryAuthor, WriteEntryData},
+    context::diff::{diff_snapshots, latest_context},
+    entities::conflict::{ConflictSeverity, ConflictType},
+    pii_scrubbing::build_decomposition_prompt,
+    repository::{
+        agent_repository::CreateAgentData, conflict_repository::CreateConflictData,
+        project_repository::CreateProjectData, requirement_document_repository::CreateRequirementDocumentData,
+        task_repository::CreateTaskData, user_repository::CreateUserData, AgentRepository, ConflictRepository,
+        ContextSnapshotRepository, ProjectRepository, RequirementDocumentRepository, TaskRepository, UserRepository,
+    },
+    DatabaseConnection,
+};
+use codex_multi_agent::SharedContextCategory;
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+async fn create_test_project(db: &DatabaseConnection) -> (Uuid, Uuid) {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "上下文比较项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/context-diff".to_string(),
+        })
+        .await
+        .unwrap();
+    (user.user_id, project.project_id)
+}
+
+async fn create_document(db: &DatabaseConnection, project_id: Uuid) -> Uuid {
+    RequirementDocumentRepository::new(db.clone())
+        .create(CreateRequirementDocumentData {
+            project_id,
+            title: "用户登录".to_string(),
+            content: "实现用户名密码登录".to_string(),
+            document_type: "prd".to_string(),
+        })
+        .await
+        .unwrap()
+        .document_id
+}
+
+fn task_data(project_id: Uuid, title: &str) -> CreateTaskData {
+    CreateTaskData {
+        project_id,
+        parent_task_id: None,
+        llm_session_id: None,
+        title: title.to_string(),
+        description: "测试".to_string(),
+        task_type: "development".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_second_decomposition_includes_changes_since_last_run() {
+    let db = setup_test_db().await;
+    let (user_id, project_id) = create_test_project(&db).await;
+    let document_id = create_document(&db, project_id).await;
+    let tasks = TaskRepository::new(db.clone());
+    let login = tasks.create(task_data(project_id, "实现登录接口")).await.unwrap();
+
+    // 首次分解没有可比较的快照
+    let first = build_decomposition_prompt(&db, document_id).await.unwrap();
+    assert!(first.context_diff.is_none());
+    assert!(!first.prompt.contains("与上次分解相比的变化"));
+    assert!(latest_context(&db, project_id).await.unwrap().is_some());
+
+    // 两次分解之间：完成一个任务，出现一个冲突，记录一项决策
+    let agent_id = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id,
+            name: "开发Agent".to_string(),
+            description: None,
+            prompt_template: "测试".to_string(),
+            capabilities: json!(["Development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap()
+        .agent_id;
+    tasks.assign_to_agent(login.task_id, agent_id, "实现登录接口".to_string()).await.unwrap();
+    tasks.update_status(login.task_id, "completed").await.unwrap();
+    let session = tasks.create(task_data(project_id, "会话管理")).await.unwrap();
+    let conflict = ConflictRepository::new(db.clone())
+        .create(CreateConflictData {
+            conflict_type: ConflictType::TaskDependency,
+            severity: ConflictSeverity::High,
+            title: "会话模块依赖冲突".to_string(),
+            description: "会话管理依赖的存储方案未确定".to_string(),
+            related_entities: json!({}),
+            affected_tasks: json!([session.task_id.to_string()]),
+            affected_agents: json!([]),
+            project_id: None,
+        })
+        .await
+        .unwrap();
+    write_entry(
+        &db,
+        WriteEntryData {
+            project_id,
+            key: "session_store".to_string(),
+            category: SharedContextCategory::Decision,
+            value: "会话存储使用 Redis".to_string(),
+            include_in_prompts: true,
+            expected_version: None,
+            author: EntryAuthor::User(user_id),
+        },
+    )
+    .await
+    .unwrap();
+
+    let second = build_decomposition_prompt(&db, document_id).await.unwrap();
+    let diff = second.context_diff.clone().expect("第二次分解应包含变化");
+    assert_eq!(diff.completed_tasks.len(), 1);
+    assert_eq!(diff.completed_tasks[0].task_id, login.task_id);
+    assert_eq!(diff.new_risks.len(), 1);
+    assert_eq!(diff.new_risks[0].risk_id, conflict.conflict_id.to_string());
+    assert!(diff.resolved_risks.is_empty());
+    assert_eq!(diff.shared_context_changes.len(), 1);
+    assert!(diff.shared_context_changes[0].added);
+    assert!(second.prompt.contains("与上次分解相比的变化"));
+    assert!(second.prompt.contains("实现登录接口"));
+    assert!(second.prompt.contains("会话管理依赖的存储方案未确定"));
+
+    // 两次分解各保存一份快照，按时间倒序
+    let snapshots = ContextSnapshotRepository::new(db.clone()).find_by_project(project_id).await.unwrap();
+    assert_eq!(snapshots.len(), 2);
+    assert!(snapshots.iter().all(|s| s.document_id == Some(document_id)));
+    let (newer, older) = (snapshots[0].snapshot_id, snapshots[1].snapshot_id);
+    assert_eq!(diff_snapshots(&db, older, newer).await.unwrap(), diff);
+
+    // 没有新变化时不再附加变化章节
+    let third = build_decomposition_prompt(&db, document_id).await.unwrap();
+    assert!(third.context_diff.is_none());
+}
+
+#[tokio::test]
+async fn test_diff_snapshots_rejects_different_projects() {
+    let db = setup_test_db().await;
+    let (_, first_project) = create_test_project(&db).await;
+    let (_, second_project) = create_test_project(&db).await;
+    for project_id in [first_project, second_project] {
+        let document_id = create_document(&db, project_id).await;
+        build_decomposition_prompt(&db, document_id).await.unwrap();
+    }
+
+    let repo = ContextSnapshotRepository::new(db.clone());
+    let first = repo.find_latest_by_project(first_project).await.unwrap().unwrap();
+    let second = repo.find_latest_by_project(second_project).await.unwrap().unwrap();
+    let err = diff_snapshots(&db, first.snapshot_id, second.snapshot_id).await.unwrap_err();
+    assert!(err.is_validation_error());
+    assert!(diff_snapshots(&db, first.snapshot_id, Uuid::new_v4()).await.is_err());
+}
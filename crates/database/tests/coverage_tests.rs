@@ -0,0 +1,256 @@
+//! 测试覆盖率报告接入测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    coverage::{
+        evaluate_coverage_gate, ingest_session_coverage, parse_cobertura, parse_lcov, session_coverage,
+        CoverageFormat, FileCoverage,
+    },
+    entities::code_review::ReviewDecision,
+    repository::{
+        AgentRepository, CodeReviewRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository,
+        UserRepository,
+        agent_repository::CreateAgentData,
+        code_review_repository::CreateCodeReviewData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    task_id: Uuid,
+    agent_id: Uuid,
+    project_id: Uuid,
+}
+
+async fn setup(db: &DatabaseConnection) -> Fixture {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "覆盖率项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/coverage".to_string(),
+        })
+        .await
+        .unwrap();
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "开发Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个开发Agent".to_string(),
+            capabilities: json!(["backend_development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "补充测试".to_string(),
+            description: "覆盖率参与质量门禁".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+
+    Fixture {
+        task_id: task.task_id,
+        agent_id: agent.agent_id,
+        project_id: project.project_id,
+    }
+}
+
+async fn completed_session(
+    db: &DatabaseConnection,
+    fixture: &Fixture,
+    base_commit: Option<&str>,
+    final_commit: &str,
+    execution_config: Option<Value>,
+) -> Uuid {
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let session = sessions
+        .create(CreateSessionData {
+            task_id: fixture.task_id,
+            agent_id: fixture.agent_id,
+            project_id: fixture.project_id,
+            git_branch: "feature/coverage".to_string(),
+            base_commit: base_commit.map(str::to_string),
+            execution_config,
+            timeout_minutes: 30,
+        })
+        .await
+        .unwrap();
+    sessions.start_session(session.session_id).await.unwrap();
+    sessions
+        .complete_session(session.session_id, true, Some(final_commit.to_string()), None, None)
+        .await
+        .unwrap();
+    session.session_id
+}
+
+/// 生成 lcov 报告：每个文件 10 行，`covered` 为被覆盖的行数
+fn lcov(files: &[(&str, u32)]) -> String {
+    files
+        .iter()
+        .map(|(path, covered)| {
+            let lines: String = (1..=10)
+                .map(|line| format!("DA:{},{}\n", line, if line <= *covered { 3 } else { 0 }))
+                .collect();
+            format!("TN:\nSF:{}\n{}LF:10\nLH:{}\nend_of_record\n", path, lines, covered)
+        })
+        .collect()
+}
+
+#[test]
+fn test_parse_lcov_and_cobertura() {
+    let summary = parse_lcov(
+        "TN:unit\nSF:src/lib.rs\nDA:1,1\nDA:2,0\nDA:3,5,abc\nend_of_record\n\
+         TN:integration\nSF:src/lib.rs\nDA:2,4\nDA:4,0\nend_of_record\n\
+         SF:src/empty.rs\nLF:4\nLH:1\nend_of_record\n",
+    )
+    .unwrap();
+    assert_eq!(
+        summary.files,
+        vec![
+            FileCoverage { path: "src/empty.rs".to_string(), lines_total: 4, lines_covered: 1 },
+            FileCoverage { path: "src/lib.rs".to_string(), lines_total: 4, lines_covered: 3 },
+        ]
+    );
+    assert_eq!((summary.lines_covered, summary.lines_total), (4, 8));
+    assert!((summary.line_rate() - 0.5).abs() < 1e-9);
+
+    assert!(parse_lcov("DA:1,1\n").unwrap_err().is_validation_error());
+    assert!(parse_lcov("SF:a.rs\nDA:x,1\n").unwrap_err().is_validation_error());
+    assert!(parse_lcov("TN:\n").unwrap_err().is_validation_error());
+
+    let summary = parse_cobertura(
+        r#"<?xml version="1.0" ?>
+<coverage line-rate="0.6" version="1.9">
+  <sources><source>/workspace</source></sources>
+  <packages>
+    <package name="app">
+      <classes>
+        <class name="Service" filename="app/service.py" line-rate="0.5">
+          <methods>
+            <method name="run"><lines><line number="2" hits="1"/></lines></method>
+          </methods>
+          <lines>
+            <line number="1" hits="1"/>
+            <line number="2" hits="1"/>
+            <line hits="0" number="3" branch="false"/>
+            <line number="4" hits="0"/>
+          </lines>
+        </class>
+        <class name="Util" filename="app/a&amp;b.py"><lines><line number="1" hits="2"/></lines></class>
+        <class name="Empty" filename="app/__init__.py"/>
+      </classes>
+    </package>
+  </packages>
+</coverage>"#,
+    )
+    .unwrap();
+    let files: Vec<_> = summary.files.iter().map(|file| (file.path.as_str(), file.lines_covered, file.lines_total)).collect();
+    assert_eq!(files, vec![("app/__init__.py", 0, 0), ("app/a&b.py", 1, 1), ("app/service.py", 2, 4)]);
+    assert!((summary.line_rate() - 0.6).abs() < 1e-9);
+    assert!(parse_cobertura("<report/>").unwrap_err().is_validation_error());
+}
+
+#[tokio::test]
+async fn test_coverage_delta_and_quality_gate() {
+    let db = setup_test_db().await;
+    let fixture = setup(&db).await;
+
+    // 基准提交：两个文件共 16/20 行被覆盖
+    let base = completed_session(&db, &fixture, None, "base1", None).await;
+    ingest_session_coverage(&db, base, CoverageFormat::Lcov, &lcov(&[("src/a.rs", 8), ("src/b.rs", 8)]))
+        .await
+        .unwrap();
+    let base_coverage = session_coverage(&db, base).await.unwrap().unwrap();
+    assert!((base_coverage.line_rate - 0.8).abs() < 1e-9);
+    assert_eq!(base_coverage.delta, None);
+    assert!(evaluate_coverage_gate(&db, base).await.unwrap().unwrap().passed);
+
+    // 基于 base1 的改动降低了 b.rs 的覆盖率并新增了 c.rs
+    let feature = completed_session(&db, &fixture, Some("base1"), "feat1", None).await;
+    let report = lcov(&[("src/a.rs", 9), ("src/b.rs", 3), ("src/c.rs", 6)]);
+    ingest_session_coverage(&db, feature, CoverageFormat::Lcov, &report).await.unwrap();
+    // 重新上报覆盖原报告
+    let report = ingest_session_coverage(&db, feature, CoverageFormat::Lcov, &report).await.unwrap();
+    assert_eq!(report.commit_sha.as_deref(), Some("feat1"));
+
+    let coverage = session_coverage(&db, feature).await.unwrap().unwrap();
+    assert!((coverage.line_rate - 0.6).abs() < 1e-9);
+    assert!((coverage.delta.unwrap() + 0.2).abs() < 1e-9);
+    let files: Vec<_> = coverage.files.iter().map(|file| file.path.as_str()).collect();
+    assert_eq!(files, vec!["src/b.rs", "src/a.rs", "src/c.rs"]);
+    assert!((coverage.files[0].delta.unwrap() + 0.5).abs() < 1e-9);
+    assert_eq!(coverage.files[2].base_line_rate, None);
+
+    // 低于项目默认门槛（80%）时不能批准审查
+    let gate = evaluate_coverage_gate(&db, feature).await.unwrap().unwrap();
+    assert!(!gate.passed);
+    assert!((gate.threshold - 0.8).abs() < 1e-6);
+    let reviews = CodeReviewRepository::new(db.clone());
+    let review_id = reviews
+        .create(CreateCodeReviewData {
+            task_id: fixture.task_id,
+            execution_session_id: feature,
+            reviewer_agent_id: fixture.agent_id,
+            pull_request_url: "https://github.com/test/repo/pull/5".to_string(),
+            source_branch: "feature/coverage".to_string(),
+            target_branch: "main".to_string(),
+            review_comments: json!([]),
+            code_changes: json!([]),
+            status: "pending".to_string(),
+            decision: None,
+            overall_comment: None,
+        })
+        .await
+        .unwrap()
+        .review_id;
+    let error = reviews.submit_decision(review_id, ReviewDecision::Approved, None).await.unwrap_err();
+    assert!(error.to_string().contains("覆盖率"));
+    assert!(reviews.submit_decision(review_id, ReviewDecision::ChangesRequested, None).await.is_ok());
+
+    // 执行配置中的门槛优先于项目门禁，关闭检查时不评估
+    let quality_checks = |enabled: bool| {
+        json!({ "quality_checks": {
+            "enable_style_check": false,
+            "enable_coverage_check": enabled,
+            "enable_security_check": false,
+            "min_coverage_threshold": 0.5,
+            "custom_rules": []
+        }})
+    };
+    let relaxed = completed_session(&db, &fixture, Some("base1"), "feat2", Some(quality_checks(true))).await;
+    ingest_session_coverage(&db, relaxed, CoverageFormat::Lcov, &lcov(&[("src/a.rs", 6)])).await.unwrap();
+    let gate = evaluate_coverage_gate(&db, relaxed).await.unwrap().unwrap();
+    assert!(gate.passed);
+    assert!((gate.threshold - 0.5).abs() < 1e-6);
+
+    let disabled = completed_session(&db, &fixture, None, "feat3", Some(quality_checks(false))).await;
+    ingest_session_coverage(&db, disabled, CoverageFormat::Lcov, &lcov(&[("src/a.rs", 1)])).await.unwrap();
+    assert!(evaluate_coverage_gate(&db, disabled).await.unwrap().is_none());
+}
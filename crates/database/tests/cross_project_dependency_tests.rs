@@ -0,0 +1,153 @@
+//! 跨项目任务依赖测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    cross_project_dependencies::{link_tasks, list_cross_project_links, unlink_tasks},
+    entities::task_dependency::DependencyType,
+    parallel_planner::{ParallelExecutionConfig, ParallelPlanner, WaveTaskState},
+    repository::{
+        agent_repository::CreateAgentData, project_repository::CreateProjectData, task_repository::CreateTaskData,
+        user_repository::CreateUserData, AgentRepository, ProjectMemberRepository, ProjectRepository,
+        TaskRepository, UserRepository,
+    },
+    DatabaseConnection,
+};
+use codex_multi_agent::ProjectRole;
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+async fn create_user(db: &DatabaseConnection) -> Uuid {
+    UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("cross_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("cross_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id
+}
+
+async fn create_project(db: &DatabaseConnection, user_id: Uuid, name: &str) -> Uuid {
+    ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: name.to_string(),
+        description: None,
+        repository_url: format!("https://github.com/test/{}.git", name),
+        workspace_path: format!("/workspace/{}", name),
+    }).await.unwrap().project_id
+}
+
+async fn create_task(db: &DatabaseConnection, project_id: Uuid, title: &str) -> Uuid {
+    TaskRepository::new(db.clone()).create(CreateTaskData {
+        project_id,
+        parent_task_id: None,
+        llm_session_id: None,
+        title: title.to_string(),
+        description: "测试".to_string(),
+        task_type: "development".to_string(),
+    }).await.unwrap().task_id
+}
+
+#[tokio::test]
+async fn test_cross_project_link_requires_membership_of_both_projects() {
+    let db = setup_test_db().await;
+    let alice = create_user(&db).await;
+    let bob = create_user(&db).await;
+    let platform = create_project(&db, alice, "platform").await;
+    let shop = create_project(&db, bob, "shop").await;
+    let auth = create_task(&db, platform, "统一认证").await;
+    let checkout = create_task(&db, shop, "结算页登录").await;
+
+    // bob 不是 platform 的成员
+    let err = link_tasks(&db, bob, auth, checkout, DependencyType::Blocking).await.unwrap_err();
+    assert!(err.to_string().contains("两个项目的成员"));
+
+    // 访客不能在依赖任务所在项目中建立依赖
+    let members = ProjectMemberRepository::new(db.clone());
+    members.add_member(shop, alice, ProjectRole::Viewer, Some(bob)).await.unwrap();
+    assert!(link_tasks(&db, alice, auth, checkout, DependencyType::Blocking).await.is_err());
+
+    members.add_member(platform, bob, ProjectRole::Viewer, Some(alice)).await.unwrap();
+    let link = link_tasks(&db, bob, auth, checkout, DependencyType::Blocking).await.unwrap();
+    assert!(link.is_cross_project);
+    assert_eq!(link.created_by, Some(bob));
+    assert!(link_tasks(&db, bob, auth, checkout, DependencyType::Blocking).await.unwrap_err().is_validation_error());
+
+    // 两端项目分别看到出站和入站依赖；非对方项目成员看不到对方任务详情
+    let outbound = list_cross_project_links(&db, alice, platform).await.unwrap();
+    assert_eq!(outbound.len(), 1);
+    assert!(!outbound[0].inbound);
+    assert_eq!(outbound[0].other_task_title.as_deref(), Some("结算页登录"));
+    let inbound = list_cross_project_links(&db, bob, shop).await.unwrap();
+    assert!(inbound[0].inbound);
+    assert_eq!(inbound[0].other_project_id, platform);
+
+    let carol = create_user(&db).await;
+    members.add_member(shop, carol, ProjectRole::Contributor, Some(bob)).await.unwrap();
+    let hidden = list_cross_project_links(&db, carol, shop).await.unwrap();
+    assert_eq!(hidden[0].other_task_title, None);
+    assert!(unlink_tasks(&db, carol, link.dependency_id).await.is_err());
+
+    unlink_tasks(&db, bob, link.dependency_id).await.unwrap();
+    assert!(list_cross_project_links(&db, bob, shop).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_cross_project_cycle_detection() {
+    let db = setup_test_db().await;
+    let user = create_user(&db).await;
+    let x = create_project(&db, user, "x").await;
+    let y = create_project(&db, user, "y").await;
+    let a = create_task(&db, x, "A").await;
+    let b = create_task(&db, y, "B").await;
+    let c = create_task(&db, x, "C").await;
+
+    // C 依赖 B，B 依赖 A；A 再依赖 C 会跨两个项目形成循环
+    link_tasks(&db, user, a, b, DependencyType::Blocking).await.unwrap();
+    let back_link = link_tasks(&db, user, b, c, DependencyType::Soft).await.unwrap();
+    assert!(back_link.is_cross_project);
+    let err = link_tasks(&db, user, c, a, DependencyType::Blocking).await.unwrap_err();
+    assert!(err.to_string().contains("循环依赖"));
+    assert!(link_tasks(&db, user, a, a, DependencyType::Blocking).await.is_err());
+}
+
+#[tokio::test]
+async fn test_planner_waits_for_prerequisite_in_other_project() {
+    let db = setup_test_db().await;
+    let user = create_user(&db).await;
+    let platform = create_project(&db, user, "platform").await;
+    let shop = create_project(&db, user, "shop").await;
+    let agent_id = AgentRepository::new(db.clone()).create(CreateAgentData {
+        user_id: user,
+        name: "结算Agent".to_string(),
+        description: None,
+        prompt_template: "测试".to_string(),
+        capabilities: json!(["backend_development"]),
+        config: json!({}),
+        git_config: None,
+    }).await.unwrap().agent_id;
+
+    let auth = create_task(&db, platform, "统一认证").await;
+    let checkout = create_task(&db, shop, "结算页登录").await;
+    TaskRepository::new(db.clone()).assign_to_agent(checkout, agent_id, "结算".to_string()).await.unwrap();
+    link_tasks(&db, user, auth, checkout, DependencyType::Blocking).await.unwrap();
+
+    let planner = ParallelPlanner::new(db.clone(), ParallelExecutionConfig::default());
+    let view = planner.wave_view(shop).await.unwrap();
+    assert_eq!(view.waves.len(), 1, "其他项目的前置任务不计入波次");
+    assert_eq!(view.waves[0].tasks[0].state, WaveTaskState::Waiting);
+    assert_eq!(view.waves[0].tasks[0].waiting_on, vec![auth]);
+    assert!(planner.launch_ready(shop).await.unwrap().launched.is_empty());
+
+    let tasks = TaskRepository::new(db.clone());
+    tasks.update_status(auth, "failed").await.unwrap();
+    let view = planner.wave_view(shop).await.unwrap();
+    assert_eq!(view.waves[0].tasks[0].state, WaveTaskState::Blocked);
+    assert_eq!(view.waves[0].tasks[0].blocked_reason.as_deref(), Some("其他项目的前置任务「统一认证」执行失败"));
+
+    tasks.update_status(auth, "completed").await.unwrap();
+    let report = planner.launch_ready(shop).await.unwrap();
+    assert_eq!(report.launched.len(), 1);
+    assert_eq!(report.launched[0].task_id, checkout);
+}
@@ -0,0 +1,124 @@
+//! 用户数据清除集成测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    data_purge::{is_purged, purge_user_data},
+    entities::domain_event::{AggregateType, DomainEventType},
+    repository::{
+        DomainEventRepository, ProjectMemberRepository, ProjectRepository, UserRepository, UserSessionRepository,
+        domain_event_repository::CreateDomainEventData,
+        project_repository::CreateProjectData,
+        user_repository::CreateUserData,
+        user_session_repository::CreateSessionData,
+    },
+    DatabaseConnection,
+};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建测试用户的辅助函数
+async fn create_test_user(db: &DatabaseConnection) -> Uuid {
+    let repo = UserRepository::new(db.clone());
+    repo.create(CreateUserData {
+        username: format!("purge_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("purge_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: Some(serde_json::json!({ "display_name": "张三" })),
+        settings: None,
+    }).await.unwrap().user_id
+}
+
+/// 创建测试项目的辅助函数
+async fn create_test_project(db: &DatabaseConnection, user_id: Uuid, name: &str) -> Uuid {
+    let repo = ProjectRepository::new(db.clone());
+    repo.create(CreateProjectData {
+        user_id,
+        name: name.to_string(),
+        description: None,
+        repository_url: "https://github.com/test/purge.git".to_string(),
+        workspace_path: format!("/workspace/{}", name),
+    }).await.unwrap().project_id
+}
+
+#[tokio::test]
+async fn test_purge_deletes_sole_projects_and_transfers_shared_ones() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let maintainer_id = create_test_user(&db).await;
+    let viewer_id = create_test_user(&db).await;
+    let solo_project = create_test_project(&db, user_id, "solo").await;
+    let shared_project = create_test_project(&db, user_id, "shared").await;
+
+    let members = ProjectMemberRepository::new(db.clone());
+    members.add_member(shared_project, viewer_id, ProjectRole::Viewer, Some(user_id)).await.unwrap();
+    members.add_member(shared_project, maintainer_id, ProjectRole::Maintainer, Some(user_id)).await.unwrap();
+
+    let report = purge_user_data(&db, user_id, Some(user_id)).await.expect("清除应该成功");
+    assert_eq!(report.deleted_projects, vec![solo_project]);
+    assert_eq!(report.transferred_projects.len(), 1);
+    assert_eq!(report.transferred_projects[0].id, shared_project);
+    // 维护者权限高于访客，优先接管
+    assert_eq!(report.transferred_projects[0].new_owner_id, maintainer_id);
+
+    let projects = ProjectRepository::new(db.clone());
+    assert!(projects.find_by_id(solo_project).await.unwrap().is_none());
+    let shared = projects.find_by_id(shared_project).await.unwrap().unwrap();
+    assert_eq!(shared.user_id, maintainer_id);
+    assert_eq!(members.get_role(shared_project, maintainer_id).await.unwrap(), Some(ProjectRole::Owner));
+    assert_eq!(members.get_role(shared_project, user_id).await.unwrap(), None);
+
+    let remaining = members.list_members(shared_project).await.unwrap();
+    assert!(remaining.iter().all(|member| member.invited_by != Some(user_id)));
+}
+
+#[tokio::test]
+async fn test_purge_anonymizes_user_and_records_audit_event() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let admin_id = create_test_user(&db).await;
+
+    let sessions = UserSessionRepository::new(db.clone());
+    sessions.create(CreateSessionData {
+        user_id,
+        token: "token".to_string(),
+        refresh_token: "refresh".to_string(),
+        ip_address: Some("10.0.0.1".to_string()),
+        user_agent: None,
+        device_name: None,
+        expires_in_hours: 24,
+        refresh_expires_in_hours: None,
+    }).await.unwrap();
+
+    let events = DomainEventRepository::new(db.clone());
+    events.create(CreateDomainEventData {
+        aggregate_type: AggregateType::User.to_string(),
+        aggregate_id: user_id,
+        event_type: "UserRegistered".to_string(),
+        event_data: serde_json::json!({ "email": "secret@example.com" }),
+        event_version: 1,
+    }).await.unwrap();
+
+    let report = purge_user_data(&db, user_id, Some(admin_id)).await.unwrap();
+    assert_eq!(report.deleted.get("user_sessions"), Some(&1));
+    assert_eq!(report.anonymized.get("users"), Some(&1));
+
+    let user = UserRepository::new(db.clone()).find_by_id(user_id).await.unwrap().unwrap();
+    assert!(is_purged(&user));
+    assert!(!user.is_active);
+    assert!(user.profile_data.is_none());
+    assert!(user.password_hash.is_empty());
+
+    let history = events.find_by_aggregate_id(user_id).await.unwrap();
+    assert!(history.iter().all(|event| !event.event_data.to_string().contains("secret@example.com")));
+    let audit = history.iter()
+        .find(|event| event.event_id == report.audit_event_id)
+        .expect("应记录审计事件");
+    assert_eq!(audit.event_type, DomainEventType::UserDataPurged.to_string());
+    assert_eq!(audit.event_version, 2);
+    assert_eq!(audit.user_id, Some(admin_id));
+
+    // 重复清除应失败
+    assert!(purge_user_data(&db, user_id, Some(admin_id)).await.is_err());
+}
@@ -0,0 +1,107 @@
+//! 需求分解流式进度测试
+
+use codex_database::{
+    decomposition_progress::{stream_decomposition, DecompositionProgressTracker},
+    llm_provider::{LlmCompletion, LlmFuture, LlmProvider, LlmRequest},
+    structured_output::partial_task_titles,
+};
+use codex_multi_agent::{LlmSessionId, ProjectId};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// 按固定片段流式输出的提供方
+struct ScriptedProvider {
+    chunks: Vec<&'static str>,
+}
+
+impl LlmProvider for ScriptedProvider {
+    fn name(&self) -> &str {
+        "scripted"
+    }
+
+    fn complete<'a>(&'a self, _request: &'a LlmRequest) -> LlmFuture<'a> {
+        Box::pin(async move {
+            Ok(LlmCompletion {
+                content: self.chunks.concat(),
+                tokens_used: self.chunks.len() as u32,
+            })
+        })
+    }
+
+    fn complete_streaming<'a>(&'a self, request: &'a LlmRequest, tokens: UnboundedSender<String>) -> LlmFuture<'a> {
+        Box::pin(async move {
+            for chunk in &self.chunks {
+                let _ = tokens.send(chunk.to_string());
+                tokio::task::yield_now().await;
+            }
+            self.complete(request).await
+        })
+    }
+}
+
+/// 只实现一次性生成的提供方
+struct BlockingProvider;
+
+impl LlmProvider for BlockingProvider {
+    fn name(&self) -> &str {
+        "blocking"
+    }
+
+    fn complete<'a>(&'a self, _request: &'a LlmRequest) -> LlmFuture<'a> {
+        Box::pin(async move {
+            Ok(LlmCompletion {
+                content: "1. 设计数据库表\n2. 实现登录接口".to_string(),
+                tokens_used: 12,
+            })
+        })
+    }
+}
+
+#[test]
+fn test_partial_task_titles() {
+    // JSON 只取已闭合的标题
+    let partial = r#"{"tasks": [{"title": "实现登录\"接口\"", "description": "..."}, {"title": "编写测"#;
+    assert_eq!(partial_task_titles(partial), vec!["实现登录\"接口\"".to_string()]);
+
+    // Markdown 标题优先，验收标准小节和未结束的行不算
+    let markdown = "# 分解结果\n## 设计数据库表\n1. 用户表\n### 验收标准\n1. 迁移可重复执行\n## 实现登录接口\n## 编写";
+    assert_eq!(partial_task_titles(markdown), vec!["设计数据库表".to_string(), "实现登录接口".to_string()]);
+
+    // 没有标题时取顶层有序列表项
+    let list = "任务如下：\n1. 设计数据库表\n   1. 用户表\n2、实现登录接口\n";
+    assert_eq!(partial_task_titles(list), vec!["设计数据库表".to_string(), "实现登录接口".to_string()]);
+}
+
+#[tokio::test]
+async fn test_stream_decomposition_reports_incremental_tasks() {
+    let provider = ScriptedProvider {
+        chunks: vec!["{\"tasks\": [{\"title\": \"设计", "数据库表\"}, ", "{\"title\": \"实现登录接口\"", "}]}"],
+    };
+    let request = LlmRequest::new("gpt-4o", "分解需求");
+    let mut tracker = DecompositionProgressTracker::new(LlmSessionId::new(), ProjectId::new());
+    let mut events = Vec::new();
+    let completion = stream_decomposition(&provider, &request, &mut tracker, |event| events.push(event))
+        .await
+        .unwrap();
+
+    assert_eq!(completion.tokens_used, 4);
+    assert_eq!(tracker.output(), completion.content);
+    let identified: Vec<Vec<String>> = events.iter().map(|event| event.tasks_identified.clone()).collect();
+    assert_eq!(
+        identified,
+        vec![
+            vec!["设计数据库表".to_string()],
+            vec!["设计数据库表".to_string(), "实现登录接口".to_string()],
+            vec!["设计数据库表".to_string(), "实现登录接口".to_string()],
+        ]
+    );
+    assert!(events.last().unwrap().finished && events.iter().filter(|event| event.finished).count() == 1);
+    assert_eq!(events.last().unwrap().tokens_received, 4);
+
+    // 默认实现在结束时一次性发送完整输出，最后一行也会被识别
+    let mut tracker = DecompositionProgressTracker::new(LlmSessionId::new(), ProjectId::new());
+    let mut events = Vec::new();
+    stream_decomposition(&BlockingProvider, &request, &mut tracker, |event| events.push(event))
+        .await
+        .unwrap();
+    assert_eq!(events.last().unwrap().tasks_identified, vec!["设计数据库表".to_string(), "实现登录接口".to_string()]);
+}
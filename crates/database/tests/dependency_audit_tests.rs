@@ -0,0 +1,266 @@
+//! 许可证与依赖审计测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    artifact_store::ArtifactStore,
+    dependency_audit::{
+        run_dependency_audit, scan_dependencies, Dependency, DependencyAuditOptions, DependencyAuditReport,
+        Ecosystem, LicensePolicy, Vulnerability, VulnerabilityFuture, VulnerabilitySource,
+        AUDIT_REPORT_ARTIFACT, DEPENDENCY_AUDIT_TASK_TYPE,
+    },
+    repository::{
+        AgentRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository, UserRepository,
+        agent_repository::CreateAgentData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection, DatabaseError,
+};
+use serde_json::json;
+use std::path::Path;
+use uuid::Uuid;
+
+mod common;
+
+/// 对指定依赖返回固定漏洞的数据源
+struct StaticSource;
+
+impl VulnerabilitySource for StaticSource {
+    fn query<'a>(&'a self, dependencies: &'a [Dependency]) -> VulnerabilityFuture<'a> {
+        Box::pin(async move {
+            Ok(dependencies
+                .iter()
+                .map(|dependency| match (dependency.name.as_str(), dependency.version.as_str()) {
+                    ("lodash", "4.17.15") => vec![Vulnerability {
+                        id: "GHSA-p6mc-m468-83gw".to_string(),
+                        summary: Some("Prototype Pollution in lodash".to_string()),
+                        severity: Some("HIGH".to_string()),
+                        aliases: vec!["CVE-2020-8203".to_string()],
+                        fixed_versions: vec!["4.17.19".to_string()],
+                    }],
+                    _ => Vec::new(),
+                })
+                .collect())
+        })
+    }
+}
+
+/// 总是失败的数据源
+struct OfflineSource;
+
+impl VulnerabilitySource for OfflineSource {
+    fn query<'a>(&'a self, _dependencies: &'a [Dependency]) -> VulnerabilityFuture<'a> {
+        Box::pin(async { Err(DatabaseError::business_logic("OSV 服务不可用")) })
+    }
+}
+
+fn write_workspace(root: &Path) {
+    std::fs::write(
+        root.join("Cargo.lock"),
+        r#"version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+
+[[package]]
+name = "serde"
+version = "1.0.190"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "abc"
+"#,
+    )
+    .unwrap();
+
+    let web = root.join("web");
+    std::fs::create_dir_all(web.join("node_modules/left-pad")).unwrap();
+    std::fs::write(
+        web.join("package-lock.json"),
+        r#"{
+  "name": "web",
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "name": "web" },
+    "node_modules/lodash": { "version": "4.17.15", "license": "MIT" },
+    "node_modules/copyleft-lib": { "version": "2.0.0", "license": "GPL-3.0-only" },
+    "node_modules/dual": { "version": "1.0.0", "license": "(MIT OR GPL-3.0)" }
+  }
+}"#,
+    )
+    .unwrap();
+    // node_modules 中的清单不参与扫描
+    std::fs::write(web.join("node_modules/left-pad/requirements.txt"), "ignored==1.0\n").unwrap();
+
+    std::fs::write(
+        root.join("requirements.txt"),
+        "# 运行时依赖\nrequests[security]==2.31.0 ; python_version >= '3.8'\nflask>=2.0\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("go.mod"),
+        "module example.com/app\n\ngo 1.21\n\nrequire github.com/pkg/errors v0.9.1\n\nrequire (\n\tgolang.org/x/text v0.14.0 // indirect\n)\n",
+    )
+    .unwrap();
+}
+
+/// 创建依赖审计任务，返回 (任务ID, Agent ID, 项目ID)
+async fn create_audit_task(db: &DatabaseConnection, workspace: &Path) -> (Uuid, Uuid, Uuid) {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "审计项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: workspace.to_string_lossy().to_string(),
+        })
+        .await
+        .unwrap();
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "审计Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个安全审计Agent".to_string(),
+            capabilities: json!(["security_audit"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "依赖审计".to_string(),
+            description: "扫描依赖许可证和已知漏洞".to_string(),
+            task_type: DEPENDENCY_AUDIT_TASK_TYPE.to_string(),
+        })
+        .await
+        .unwrap();
+    (task.task_id, agent.agent_id, project.project_id)
+}
+
+async fn start_audit_session(db: &DatabaseConnection, task_id: Uuid, agent_id: Uuid, project_id: Uuid) -> Uuid {
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let session = sessions
+        .create(CreateSessionData {
+            task_id,
+            agent_id,
+            project_id,
+            git_branch: "audit".to_string(),
+            base_commit: None,
+            execution_config: None,
+            timeout_minutes: 30,
+        })
+        .await
+        .unwrap();
+    sessions.start_session(session.session_id).await.unwrap();
+    session.session_id
+}
+
+#[test]
+fn test_scan_manifests_and_license_policy() {
+    let workspace = tempfile::tempdir().unwrap();
+    write_workspace(workspace.path());
+
+    let (manifests, dependencies) = scan_dependencies(workspace.path()).unwrap();
+    assert_eq!(manifests, vec!["Cargo.lock", "go.mod", "requirements.txt", "web/package-lock.json"]);
+
+    let find = |name: &str| dependencies.iter().find(|d| d.name == name).unwrap();
+    // 本地 crate 不是注册表依赖
+    assert!(dependencies.iter().all(|d| d.name != "app"));
+    assert_eq!(find("serde").ecosystem, Ecosystem::CratesIo);
+    assert_eq!(find("lodash").license.as_deref(), Some("MIT"));
+    assert_eq!(find("lodash").manifest, "web/package-lock.json");
+    assert_eq!(find("requests").version, "2.31.0");
+    assert!(dependencies.iter().all(|d| d.name != "flask" && d.name != "ignored"));
+    assert_eq!(find("golang.org/x/text").version, "0.14.0");
+    assert_eq!(find("github.com/pkg/errors").ecosystem, Ecosystem::Go);
+
+    let policy = LicensePolicy::default();
+    assert!(policy.check(Some("MIT")).is_none());
+    assert!(policy.check(Some("(MIT OR GPL-3.0)")).is_none());
+    assert!(policy.check(Some("GPL-3.0-only")).is_some());
+    assert!(policy.check(Some("MIT AND AGPL-3.0-or-later")).is_some());
+    assert!(policy.check(None).is_none());
+    let strict = LicensePolicy {
+        flag_unknown: true,
+        ..Default::default()
+    };
+    assert!(strict.check(None).is_some());
+}
+
+#[tokio::test]
+async fn test_run_audit_saves_report_and_creates_remediation_tasks() {
+    let db = setup_test_db().await;
+    let workspace = tempfile::tempdir().unwrap();
+    let artifacts = tempfile::tempdir().unwrap();
+    write_workspace(workspace.path());
+    let store = ArtifactStore::new(db.clone(), artifacts.path());
+    let (task_id, agent_id, project_id) = create_audit_task(&db, workspace.path()).await;
+    let session_id = start_audit_session(&db, task_id, agent_id, project_id).await;
+
+    let options = DependencyAuditOptions {
+        create_remediation_tasks: true,
+        ..Default::default()
+    };
+    let outcome = run_dependency_audit(&store, Some(&StaticSource), session_id, &options).await.unwrap();
+
+    let report = &outcome.report;
+    assert!(report.vulnerabilities_checked && report.has_findings());
+    assert_eq!(report.dependency_count, 7);
+    assert_eq!(report.license_issues.len(), 1);
+    assert_eq!(report.license_issues[0].dependency.name, "copyleft-lib");
+    assert_eq!(report.vulnerable_dependencies.len(), 1);
+    assert_eq!(report.licenses["MIT"], 1);
+
+    // 报告保存为会话工件
+    let (artifact, content) = store.fetch(outcome.artifact_id).await.unwrap();
+    assert_eq!(artifact.name, AUDIT_REPORT_ARTIFACT);
+    let saved: DependencyAuditReport = serde_json::from_slice(&content).unwrap();
+    assert_eq!(&saved, report);
+
+    let session = ExecutionSessionRepository::new(db.clone()).find_by_id(session_id).await.unwrap().unwrap();
+    assert_eq!(session.status, "completed");
+
+    // 修复子任务带有能力要求和优先级
+    let tasks = TaskRepository::new(db.clone());
+    let subtasks = tasks.find_subtasks(task_id).await.unwrap();
+    assert_eq!(subtasks.len(), 2);
+    assert_eq!(outcome.remediation_task_ids.len(), 2);
+    let upgrade = subtasks.iter().find(|t| t.title.contains("lodash")).unwrap();
+    assert_eq!(upgrade.task_type, "bug_fix");
+    assert_eq!(upgrade.priority, "high");
+    assert!(upgrade.description.contains("4.17.19"));
+    assert_eq!(
+        upgrade.required_capabilities.clone().unwrap(),
+        json!(["security_audit", "frontend_development"])
+    );
+    let license = subtasks.iter().find(|t| t.title.contains("许可证")).unwrap();
+    assert!(license.description.contains("copyleft-lib"));
+
+    // 再次审计不会重复创建子任务；数据源失败时记录在报告中
+    let second_session = start_audit_session(&db, task_id, agent_id, project_id).await;
+    let offline = run_dependency_audit(&store, Some(&OfflineSource), second_session, &options).await.unwrap();
+    assert!(!offline.report.vulnerabilities_checked);
+    assert!(offline.report.vulnerability_error.as_deref().unwrap().contains("OSV"));
+    assert!(offline.remediation_task_ids.is_empty());
+    assert_eq!(tasks.find_subtasks(task_id).await.unwrap().len(), 2);
+
+    // 已结束的会话不能再次审计
+    let finished = run_dependency_audit(&store, None, session_id, &options).await;
+    assert!(finished.err().unwrap().is_validation_error());
+}
@@ -0,0 +1,163 @@
+//! 语义检索索引测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    embeddings::{EmbeddingIndex, EmbeddingProvider, HashingEmbeddingProvider, SearchScope},
+    entities::{embedding::EmbeddingSourceType, task},
+    repository::{
+        ProjectRepository, RequirementDocumentRepository, TaskRepository, UserRepository,
+        project_repository::CreateProjectData,
+        requirement_document_repository::CreateRequirementDocumentData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+};
+use sea_orm::{ActiveModelTrait, Set};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+mod common;
+
+async fn create_test_project(db: &codex_database::DatabaseConnection) -> Uuid {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+
+    ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "检索项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/search".to_string(),
+        })
+        .await
+        .unwrap()
+        .project_id
+}
+
+async fn create_task(db: &codex_database::DatabaseConnection, project_id: Uuid, title: &str, description: &str) -> task::Model {
+    TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: title.to_string(),
+            description: description.to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_hashing_provider_similarity() {
+    let provider = HashingEmbeddingProvider::default();
+    let texts = vec![
+        "用户登录失败时显示错误提示".to_string(),
+        "登录失败后提示用户错误信息".to_string(),
+        "导出月度财务报表".to_string(),
+    ];
+    let vectors = provider.embed(&texts).await.unwrap();
+    assert_eq!(vectors.len(), 3);
+    assert_eq!(vectors[0].len(), 512);
+
+    let dot = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+    assert!((dot(&vectors[0], &vectors[0]) - 1.0).abs() < 1e-4);
+    assert!(dot(&vectors[0], &vectors[1]) > dot(&vectors[0], &vectors[2]));
+
+    // 相同输入得到相同向量
+    assert_eq!(provider.embed_text("Retry failed uploads"), provider.embed_text("Retry failed uploads"));
+}
+
+#[tokio::test]
+async fn test_index_and_semantic_search() {
+    let db = setup_test_db().await;
+    let project_id = create_test_project(&db).await;
+    let index = EmbeddingIndex::new(db.clone(), Arc::new(HashingEmbeddingProvider::default()));
+
+    let document = RequirementDocumentRepository::new(db.clone())
+        .create(CreateRequirementDocumentData {
+            project_id,
+            title: "认证需求".to_string(),
+            content: "用户使用邮箱和密码登录系统。\n\n连续五次登录失败后锁定账户十五分钟。".to_string(),
+            document_type: "functional".to_string(),
+        })
+        .await
+        .unwrap();
+    let upload_task = create_task(&db, project_id, "实现文件上传", "支持断点续传的大文件上传接口").await;
+    let report_task = create_task(&db, project_id, "生成财务报表", "按月导出财务报表为Excel").await;
+
+    // 任务执行总结中的经验教训单独建立索引
+    let mut active: task::ActiveModel = report_task.clone().into();
+    active.execution_result = Set(Some(json!({
+        "execution_summary": {
+            "lessons_learned": ["导出大数据量报表时需要分页查询，避免内存溢出"]
+        }
+    })));
+    active.update(&db).await.unwrap();
+
+    let report = index.index_project(project_id).await.unwrap();
+    assert_eq!(report.embedded, 4);
+
+    let hits = index
+        .semantic_search("账户登录失败锁定", &SearchScope::project(project_id), 2)
+        .await
+        .unwrap();
+    assert_eq!(hits[0].source_type, EmbeddingSourceType::RequirementDocument);
+    assert_eq!(hits[0].source_id, document.document_id);
+    assert!(hits[0].content.contains("锁定账户"));
+    assert!(hits.len() <= 2);
+
+    let scope = SearchScope {
+        project_id,
+        source_types: vec![EmbeddingSourceType::Lesson],
+    };
+    let hits = index.semantic_search("报表内存溢出", &scope, 5).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].source_id, report_task.task_id);
+
+    let hits = index
+        .semantic_search("大文件断点续传", &SearchScope::project(project_id), 1)
+        .await
+        .unwrap();
+    assert_eq!(hits[0].source_id, upload_task.task_id);
+    assert_eq!(hits[0].source_type, EmbeddingSourceType::Task);
+
+    // 内容未变化时不重新计算，来源删除后移除向量
+    TaskRepository::new(db.clone()).delete(upload_task.task_id).await.unwrap();
+    let report = index.index_project(project_id).await.unwrap();
+    assert_eq!(report.embedded, 0);
+    assert_eq!(report.unchanged, 3);
+    assert_eq!(report.removed, 1);
+
+    assert!(index.semantic_search("  ", &SearchScope::project(project_id), 3).await.unwrap_err().is_validation_error());
+}
+
+#[tokio::test]
+async fn test_reindex_on_model_change() {
+    let db = setup_test_db().await;
+    let project_id = create_test_project(&db).await;
+    create_task(&db, project_id, "修复缓存失效", "配置更新后刷新缓存").await;
+
+    let small = EmbeddingIndex::new(db.clone(), Arc::new(HashingEmbeddingProvider::new(64)));
+    assert_eq!(small.index_project(project_id).await.unwrap().embedded, 1);
+
+    // 换模型后旧向量不参与检索，重新索引后恢复
+    let large = EmbeddingIndex::new(db.clone(), Arc::new(HashingEmbeddingProvider::new(256)));
+    let scope = SearchScope::project(project_id);
+    assert!(large.semantic_search("缓存", &scope, 3).await.unwrap().is_empty());
+
+    let report = large.index_all().await.unwrap();
+    assert_eq!(report.embedded, 1);
+    assert_eq!(report.unchanged, 0);
+    assert_eq!(large.semantic_search("缓存", &scope, 3).await.unwrap().len(), 1);
+}
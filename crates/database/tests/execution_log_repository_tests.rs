@@ -12,8 +12,9 @@ use codex_database::{
         execution_session_repository::CreateSessionData,
         execution_log_repository::CreateExecutionLogData,
     },
-    entities::execution_log::{LogLevel, EventType},
+    entities::execution_log::{self, LogLevel, EventType},
 };
+use sea_orm::{ActiveModelTrait, Set};
 use serde_json::json;
 use uuid::Uuid;
 
@@ -113,7 +114,7 @@ async fn test_create_execution_log() {
     let log_data = CreateExecutionLogData {
         session_id,
         log_level: LogLevel::Info.to_string(),
-        event_type: EventType::GitOperation.to_string(),
+        event_type: EventType::GitOperation,
         message: "开始Git操作".to_string(),
         details: Some(json!({
             "command": "git checkout -b feature/test",
@@ -145,7 +146,7 @@ async fn test_find_log_by_id() {
     let log_data = CreateExecutionLogData {
         session_id,
         log_level: LogLevel::Debug.to_string(),
-        event_type: EventType::FileChange.to_string(),
+        event_type: EventType::FileChange,
         message: "文件已修改".to_string(),
         details: Some(json!({
             "file_path": "src/lib.rs",
@@ -191,12 +192,12 @@ async fn test_find_logs_by_session_id() {
             session_id,
             log_level: LogLevel::Info.to_string(),
             event_type: match i {
-                0 => EventType::GitOperation.to_string(),
-                1 => EventType::DependencyInstall.to_string(),
-                2 => EventType::Compilation.to_string(),
-                3 => EventType::TestRun.to_string(),
-                4 => EventType::GitOperation.to_string(),
-                _ => EventType::GitOperation.to_string(),
+                0 => EventType::GitOperation,
+                1 => EventType::DependencyInstall,
+                2 => EventType::Compilation,
+                3 => EventType::TestRun,
+                4 => EventType::GitOperation,
+                _ => EventType::GitOperation,
             },
             message: message.to_string(),
             details: Some(json!({
@@ -242,7 +243,7 @@ async fn test_find_logs_by_log_level() {
         let log_data = CreateExecutionLogData {
             session_id,
             log_level: level.to_string(),
-            event_type: EventType::GitOperation.to_string(),
+            event_type: EventType::GitOperation,
             message: message.to_string(),
             details: None,
             timestamp_ms: chrono::Utc::now().timestamp_millis(),
@@ -294,7 +295,7 @@ async fn test_find_logs_by_event_type() {
         let log_data = CreateExecutionLogData {
             session_id,
             log_level: LogLevel::Info.to_string(),
-            event_type: event_type.to_string(),
+            event_type,
             message: message.to_string(),
             details: None,
             timestamp_ms: chrono::Utc::now().timestamp_millis(),
@@ -302,6 +303,15 @@ async fn test_find_logs_by_event_type() {
         log_repo.create(log_data).await.unwrap();
     }
     
+    // 按事件类型统计
+    let counts = log_repo.count_by_event_type(session_id).await.unwrap();
+    assert_eq!(counts, vec![
+        (EventType::GitOperation, 2),
+        (EventType::FileChange, 1),
+        (EventType::TestRun, 1),
+        (EventType::Compilation, 1),
+    ]);
+    
     // 查找Git操作的日志
     let git_logs = log_repo
         .find_by_event_type(&EventType::GitOperation.to_string())
@@ -338,7 +348,7 @@ async fn test_create_batch_logs() {
         CreateExecutionLogData {
             session_id,
             log_level: LogLevel::Info.to_string(),
-            event_type: EventType::GitOperation.to_string(),
+            event_type: EventType::GitOperation,
             message: "批量日志1".to_string(),
             details: Some(json!({"batch_id": 1})),
             timestamp_ms: chrono::Utc::now().timestamp_millis(),
@@ -346,7 +356,7 @@ async fn test_create_batch_logs() {
         CreateExecutionLogData {
             session_id,
             log_level: LogLevel::Debug.to_string(),
-            event_type: EventType::FileChange.to_string(),
+            event_type: EventType::FileChange,
             message: "批量日志2".to_string(),
             details: Some(json!({"batch_id": 2})),
             timestamp_ms: chrono::Utc::now().timestamp_millis() + 1000,
@@ -354,7 +364,7 @@ async fn test_create_batch_logs() {
         CreateExecutionLogData {
             session_id,
             log_level: LogLevel::Warn.to_string(),
-            event_type: EventType::TestRun.to_string(),
+            event_type: EventType::TestRun,
             message: "批量日志3".to_string(),
             details: Some(json!({"batch_id": 3})),
             timestamp_ms: chrono::Utc::now().timestamp_millis() + 2000,
@@ -393,7 +403,7 @@ async fn test_log_pagination() {
         let log_data = CreateExecutionLogData {
             session_id,
             log_level: LogLevel::Info.to_string(),
-            event_type: EventType::GitOperation.to_string(),
+            event_type: EventType::GitOperation,
             message: format!("分页日志 {}", i + 1),
             details: Some(json!({"index": i})),
             timestamp_ms: chrono::Utc::now().timestamp_millis() + (i as i64 * 100),
@@ -450,7 +460,7 @@ async fn test_log_filtering() {
         let log_data = CreateExecutionLogData {
             session_id,
             log_level: level.to_string(),
-            event_type: event_type.to_string(),
+            event_type,
             message: message.to_string(),
             details: None,
             timestamp_ms: chrono::Utc::now().timestamp_millis(),
@@ -498,7 +508,7 @@ async fn test_delete_log() {
     let log_data = CreateExecutionLogData {
         session_id,
         log_level: LogLevel::Info.to_string(),
-        event_type: EventType::GitOperation.to_string(),
+        event_type: EventType::GitOperation,
         message: "待删除的日志".to_string(),
         details: None,
         timestamp_ms: chrono::Utc::now().timestamp_millis(),
@@ -535,7 +545,7 @@ async fn test_delete_logs_by_session_id() {
         let log_data = CreateExecutionLogData {
             session_id,
             log_level: LogLevel::Info.to_string(),
-            event_type: EventType::GitOperation.to_string(),
+            event_type: EventType::GitOperation,
             message: format!("日志 {}", i + 1),
             details: None,
             timestamp_ms: chrono::Utc::now().timestamp_millis() + (i as i64 * 100),
@@ -582,7 +592,7 @@ async fn test_log_statistics() {
         let log_data = CreateExecutionLogData {
             session_id,
             log_level: level.to_string(),
-            event_type: EventType::GitOperation.to_string(),
+            event_type: EventType::GitOperation,
             message: format!("{}级别日志", level),
             details: None,
             timestamp_ms: chrono::Utc::now().timestamp_millis(),
@@ -646,7 +656,7 @@ async fn test_complex_log_details() {
     let log_data = CreateExecutionLogData {
         session_id,
         log_level: LogLevel::Info.to_string(),
-        event_type: EventType::GitOperation.to_string(),
+        event_type: EventType::GitOperation,
         message: "复杂Git提交操作完成".to_string(),
         details: Some(complex_details.clone()),
         timestamp_ms: chrono::Utc::now().timestamp_millis(),
@@ -666,4 +676,48 @@ async fn test_complex_log_details() {
     
     assert_eq!(logs_with_git_operation.len(), 1);
     assert_eq!(logs_with_git_operation[0].log_id, log.log_id);
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_count_by_event_type_skips_legacy_values() {
+    let db = setup_test_db().await;
+    
+    let user_id = create_test_user(&db).await;
+    let project_id = create_test_project(&db, user_id).await;
+    let agent_id = create_test_agent(&db, user_id).await;
+    let task_id = create_test_task(&db, project_id).await;
+    let session_id = create_test_session(&db, task_id, agent_id, project_id).await;
+    
+    let log_repo = ExecutionLogRepository::new(db.clone());
+    let log_data = |event_type: EventType| CreateExecutionLogData {
+        session_id,
+        log_level: LogLevel::Warn.to_string(),
+        event_type,
+        message: "3 个测试用例失败".to_string(),
+        details: None,
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+    };
+    let logs = log_repo
+        .create_batch(vec![log_data(EventType::TestsFailed), log_data(EventType::TestsFailed), log_data(EventType::TestRun)])
+        .await
+        .unwrap();
+    assert!(logs.iter().all(|log| log.event_type.parse::<EventType>().is_ok()));
+    
+    // 引入事件类型枚举之前写入的拼写错误取值不计入统计
+    execution_log::ActiveModel {
+        log_id: Set(Uuid::new_v4()),
+        session_id: Set(session_id),
+        log_level: Set(LogLevel::Warn.to_string()),
+        event_type: Set("test_failed".to_string()),
+        message: Set("旧版本日志".to_string()),
+        details: Set(None),
+        timestamp_ms: Set(chrono::Utc::now().timestamp_millis()),
+        created_at: Set(chrono::Utc::now().into()),
+    }
+    .insert(&db)
+    .await
+    .unwrap();
+    
+    let counts = log_repo.count_by_event_type(session_id).await.unwrap();
+    assert_eq!(counts, vec![(EventType::TestRun, 1), (EventType::TestsFailed, 2)]);
+}
@@ -26,6 +26,7 @@ async fn create_test_db() -> codex_database::DatabaseConnection {
         connect_timeout: 10,
         idle_timeout: 60,
         enable_logging: false,
+        ..Default::default()
     };
     
     initialize_database(&config).await.expect("初始化测试数据库失败")
@@ -64,6 +65,13 @@ async fn create_test_data(db: &codex_database::DatabaseConnection) -> (user::Mod
         project_context: Set(None),
         quality_standards: Set(None),
         automation_config: Set(None),
+        organization_id: Set(None),
+        workspace_quota: Set(None),
+        pii_scrubbing: Set(None),
+        preemption_policy: Set(None),
+        sla_policy: Set(None),
+        code_ownership: Set(None),
+        perf_budget: Set(None),
         status: Set("active".to_string()),
         created_at: Set(chrono::Utc::now().into()),
         updated_at: Set(chrono::Utc::now().into()),
@@ -85,6 +93,7 @@ async fn create_test_data(db: &codex_database::DatabaseConnection) -> (user::Mod
         skill_profile: Set(None),
         skill_assessments: Set(None),
         performance_trend: Set(None),
+        organization_id: Set(None),
         total_tasks_completed: Set(0),
         success_rate: Set(0.0),
         average_completion_time: Set(0),
@@ -116,6 +125,9 @@ async fn create_test_data(db: &codex_database::DatabaseConnection) -> (user::Mod
         dependency_count: Set(0),
         blocking_tasks_count: Set(0),
         execution_result: Set(None),
+        lease_owner: Set(None),
+        lease_expires_at: Set(None),
+        scope_path: Set(None),
         created_at: Set(chrono::Utc::now().into()),
         updated_at: Set(chrono::Utc::now().into()),
     };
@@ -154,6 +166,8 @@ async fn test_execution_session_creation() {
         success: Set(None),
         result_data: Set(None),
         error_message: Set(None),
+        checkpoint: Set(None),
+        worktree_path: Set(None),
     };
     
     let created_session = session.insert(&db).await.expect("创建执行会话失败");
@@ -191,6 +205,8 @@ async fn test_execution_session_lifecycle() {
         success: Set(None),
         result_data: Set(None),
         error_message: Set(None),
+        checkpoint: Set(None),
+        worktree_path: Set(None),
     };
     
     let created_session = session.insert(&db).await.expect("创建执行会话失败");
@@ -246,6 +262,8 @@ async fn test_execution_session_failure() {
         success: Set(None),
         result_data: Set(None),
         error_message: Set(None),
+        checkpoint: Set(None),
+        worktree_path: Set(None),
     };
     
     let created_session = session.insert(&db).await.expect("创建执行会话失败");
@@ -295,6 +313,8 @@ async fn test_execution_session_timeout() {
         success: Set(None),
         result_data: Set(None),
         error_message: Set(None),
+        checkpoint: Set(None),
+        worktree_path: Set(None),
     };
     
     let created_session = session.insert(&db).await.expect("创建执行会话失败");
@@ -393,6 +413,8 @@ async fn test_execution_session_complex_config() {
         success: Set(None),
         result_data: Set(None),
         error_message: Set(None),
+        checkpoint: Set(None),
+        worktree_path: Set(None),
     };
     
     let created_session = session.insert(&db).await.expect("创建复杂配置执行会话失败");
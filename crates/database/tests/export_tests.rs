@@ -0,0 +1,174 @@
+//! 流式导出测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::execution_log::{EventType, LogLevel},
+    export::{export_project_logs, export_project_tasks, ExportFormat, ExportWriter},
+    repository::{
+        agent_repository::CreateAgentData, execution_log_repository::CreateExecutionLogData,
+        execution_session_repository::CreateSessionData, project_repository::CreateProjectData,
+        task_repository::CreateTaskData, user_repository::CreateUserData, AgentRepository, ExecutionLogRepository,
+        ExecutionSessionRepository, ProjectRepository, TaskRepository, UserRepository,
+    },
+    DatabaseConnection,
+};
+use futures::TryStreamExt;
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    project_id: Uuid,
+    task_ids: Vec<Uuid>,
+    session_id: Uuid,
+}
+
+async fn setup_project(db: &DatabaseConnection, task_count: usize) -> Fixture {
+    let user_id = UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("export_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("export_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id;
+    let project_id = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: "导出项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/export.git".to_string(),
+        workspace_path: "/workspace/export".to_string(),
+    }).await.unwrap().project_id;
+    let agent_id = AgentRepository::new(db.clone()).create(CreateAgentData {
+        user_id,
+        name: "开发Agent".to_string(),
+        description: None,
+        prompt_template: "测试".to_string(),
+        capabilities: json!(["Development"]),
+        config: json!({}),
+        git_config: None,
+    }).await.unwrap().agent_id;
+
+    let tasks = TaskRepository::new(db.clone());
+    let mut task_ids = Vec::new();
+    for i in 0..task_count {
+        task_ids.push(tasks.create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: format!("任务 {}, \"导出\"", i),
+            description: "第一行\n第二行".to_string(),
+            task_type: "development".to_string(),
+        }).await.unwrap().task_id);
+    }
+    let session_id = ExecutionSessionRepository::new(db.clone()).create(CreateSessionData {
+        task_id: task_ids[0],
+        agent_id,
+        project_id,
+        git_branch: "feature/export".to_string(),
+        base_commit: None,
+        execution_config: None,
+        timeout_minutes: 30,
+    }).await.unwrap().session_id;
+
+    Fixture { project_id, task_ids, session_id }
+}
+
+fn log(session_id: Uuid, message: String) -> CreateExecutionLogData {
+    CreateExecutionLogData {
+        session_id,
+        log_level: LogLevel::Info.to_string(),
+        event_type: EventType::TestRun,
+        message,
+        details: Some(json!({ "passed": 3 })),
+        timestamp_ms: 0,
+    }
+}
+
+#[tokio::test]
+async fn test_stream_reads_all_rows_in_batches() {
+    let db = setup_test_db().await;
+    let fixture = setup_project(&db, 7).await;
+    let other = setup_project(&db, 2).await;
+
+    let streamed: Vec<Uuid> = TaskRepository::new(db.clone())
+        .stream_by_project(fixture.project_id, 3)
+        .map_ok(|task| task.task_id)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(streamed, fixture.task_ids, "按创建时间顺序逐批读取");
+
+    // 批量写入的日志创建时间相同，按ID区分批次边界
+    let logs = ExecutionLogRepository::new(db.clone());
+    logs.create_batch((0..5).map(|i| log(fixture.session_id, format!("日志 {}", i))).collect()).await.unwrap();
+    logs.create(log(other.session_id, "其他项目".to_string())).await.unwrap();
+    let messages: Vec<String> = logs
+        .stream_by_project(fixture.project_id, 2)
+        .map_ok(|log| log.message)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(messages.len(), 5);
+    assert_eq!(messages.iter().collect::<HashSet<_>>().len(), 5, "批次之间没有重复或遗漏");
+    assert!(!messages.contains(&"其他项目".to_string()));
+}
+
+#[tokio::test]
+async fn test_export_ndjson_and_csv() {
+    let db = setup_test_db().await;
+    let fixture = setup_project(&db, 3).await;
+
+    let mut ndjson = Vec::new();
+    let summary = export_project_tasks(&db, fixture.project_id, ExportFormat::Ndjson, &mut ndjson).await.unwrap();
+    assert_eq!(summary.rows, 3);
+    assert_eq!(summary.bytes, ndjson.len() as u64);
+    let rows: Vec<JsonValue> = String::from_utf8(ndjson)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(rows[0]["task_id"], fixture.task_ids[0].to_string());
+    assert_eq!(rows[2]["title"], "任务 2, \"导出\"");
+
+    // CSV 按指定的列输出，含逗号、引号和换行的字段加引号
+    let mut csv = Vec::new();
+    let mut writer = ExportWriter::new(&mut csv, ExportFormat::Csv)
+        .with_columns(vec!["title".to_string(), "description".to_string(), "progress".to_string()]);
+    writer.write_row(&json!({ "title": "任务 0, \"导出\"", "description": "第一行\n第二行", "progress": 0.5 })).await.unwrap();
+    writer.write_row(&json!({ "title": "简单", "extra": [1, 2] })).await.unwrap();
+    let summary = writer.finish().await.unwrap();
+    assert_eq!(summary.rows, 2);
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "title,description,progress\r\n\"任务 0, \"\"导出\"\"\",\"第一行\n第二行\",0.5\r\n简单,,\r\n"
+    );
+
+    let mut empty = Vec::new();
+    let summary = export_project_logs(&db, fixture.project_id, ExportFormat::Csv, &mut empty).await.unwrap();
+    assert_eq!(summary.rows, 0);
+    assert!(empty.is_empty());
+    assert!("xlsx".parse::<ExportFormat>().is_err());
+}
+
+#[tokio::test]
+async fn test_writer_flushes_incrementally() {
+    let mut out = Vec::new();
+    let mut writer = ExportWriter::new(&mut out, ExportFormat::Ndjson).with_flush_every(2);
+    writer.write_row(&json!({ "n": 1 })).await.unwrap();
+    assert_eq!(writer.summary().bytes, 0, "未满一批时留在缓冲中");
+    writer.write_row(&json!({ "n": 2 })).await.unwrap();
+    let flushed = writer.summary().bytes;
+    assert!(flushed > 0);
+    writer.write_row(&json!({ "n": 3 })).await.unwrap();
+    assert_eq!(writer.summary().bytes, flushed);
+
+    let summary = writer.finish().await.unwrap();
+    assert_eq!(summary.rows, 3);
+    assert_eq!(String::from_utf8(out).unwrap(), "{\"n\":1}\n{\"n\":2}\n{\"n\":3}\n");
+
+    let mut csv = Vec::new();
+    let mut writer = ExportWriter::new(&mut csv, ExportFormat::Csv);
+    assert!(writer.write_row(&json!([1, 2])).await.unwrap_err().is_validation_error());
+}
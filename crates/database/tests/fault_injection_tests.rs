@@ -0,0 +1,126 @@
+//! 故障注入集成测试
+//!
+//! 运行方式：cargo test -p codex-database --features fault_injection
+
+#![cfg(feature = "fault_injection")]
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::execution_session::ExecutionStatus,
+    fault_injection::{FaultInjector, FaultPoint, FaultRule, FaultTrigger},
+    repository::{
+        AgentRepository, ExecutionSessionRepository, LlmSessionRepository, ProjectRepository,
+        TaskRepository, UserRepository,
+        agent_repository::CreateAgentData,
+        execution_session_repository::CreateSessionData,
+        llm_session_repository::CreateLlmSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection, DatabaseError,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建执行会话所需的用户、项目、Agent和任务，返回 (用户ID, 项目ID, AgentID, 任务ID)
+async fn create_fixture(db: &DatabaseConnection) -> (Uuid, Uuid, Uuid, Uuid) {
+    let user_id = UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("fault_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("fault_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id;
+
+    let project_id = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: "故障注入项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/fault.git".to_string(),
+        workspace_path: "/workspace/fault".to_string(),
+    }).await.unwrap().project_id;
+
+    let agent_id = AgentRepository::new(db.clone()).create(CreateAgentData {
+        user_id,
+        name: "故障Agent".to_string(),
+        description: None,
+        prompt_template: "你是一个开发助手".to_string(),
+        capabilities: json!(["BackendDevelopment"]),
+        config: json!({}),
+        git_config: None,
+    }).await.unwrap().agent_id;
+
+    let task_id = TaskRepository::new(db.clone()).create(CreateTaskData {
+        project_id,
+        parent_task_id: None,
+        llm_session_id: None,
+        title: "故障任务".to_string(),
+        description: "验证重试路径".to_string(),
+        task_type: "development".to_string(),
+    }).await.unwrap().task_id;
+
+    (user_id, project_id, agent_id, task_id)
+}
+
+#[tokio::test]
+async fn test_agent_crash_then_retry_succeeds() {
+    let db = setup_test_db().await;
+    let (_, project_id, agent_id, task_id) = create_fixture(&db).await;
+
+    let injector = FaultInjector::new(1)
+        .with_rule(FaultRule::new(FaultPoint::AgentCrash, FaultTrigger::Target(agent_id)).times(1));
+    let repo = ExecutionSessionRepository::new(db.clone()).with_fault_injector(injector.clone());
+
+    let session = repo.create(CreateSessionData {
+        task_id,
+        agent_id,
+        project_id,
+        git_branch: "feature/fault".to_string(),
+        base_commit: None,
+        execution_config: None,
+        timeout_minutes: 30,
+    }).await.unwrap();
+
+    let error = repo.start_session(session.session_id).await.unwrap_err();
+    assert!(matches!(error, DatabaseError::InjectedFault { .. }));
+
+    // 崩溃发生在状态变更之前，会话仍可重试
+    let pending = repo.find_by_id(session.session_id).await.unwrap().unwrap();
+    assert_eq!(pending.status, ExecutionStatus::Pending.to_string());
+
+    let started = repo.start_session(session.session_id).await.expect("重试应该成功");
+    assert_eq!(started.status, ExecutionStatus::Running.to_string());
+    assert_eq!(injector.fired(FaultPoint::AgentCrash), 1);
+    assert_eq!(injector.calls(FaultPoint::AgentCrash), 2);
+}
+
+#[tokio::test]
+async fn test_llm_timeout_on_nth_call() {
+    let db = setup_test_db().await;
+    let (user_id, project_id, _, _) = create_fixture(&db).await;
+
+    let injector = FaultInjector::new(1)
+        .with_rule(FaultRule::new(FaultPoint::LlmTimeout, FaultTrigger::NthCall(2)));
+    let repo = LlmSessionRepository::new(db.clone()).with_fault_injector(injector.clone());
+
+    let session = repo.create(CreateLlmSessionData {
+        project_id,
+        user_id,
+        session_type: "decomposition".to_string(),
+        system_prompt: None,
+        decomposition_prompt: None,
+    }).await.unwrap();
+
+    repo.update_result(session.session_id, json!({"step": 1}), "active".to_string())
+        .await
+        .expect("第一次调用不应触发故障");
+    assert!(repo.update_result(session.session_id, json!({"step": 2}), "completed".to_string()).await.is_err());
+
+    // 超时的结果不会被写入
+    let stored = repo.find_by_id(session.session_id).await.unwrap().unwrap();
+    assert_eq!(stored.result_data, Some(json!({"step": 1})));
+    assert_eq!(stored.status, "active");
+}
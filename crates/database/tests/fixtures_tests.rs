@@ -0,0 +1,93 @@
+//! 演示数据集成测试
+//!
+//! 运行方式：cargo test -p codex-database --features fixtures
+
+#![cfg(feature = "fixtures")]
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::{agent, conflict, domain_event, execution_log, execution_session, project, task},
+    fixtures::{
+        delete_demo_data, find_demo_data, seed_demo_data, AgentBuilder, ProjectBuilder, TaskBuilder, UserBuilder,
+        DEMO_AGENT_COUNT, DEMO_PROJECT_COUNT, DEMO_TASK_COUNT,
+    },
+};
+use sea_orm::{EntityTrait, PaginatorTrait};
+
+mod common;
+
+#[tokio::test]
+async fn test_seed_demo_data() {
+    let db = setup_test_db().await;
+    let data = seed_demo_data(&db).await.expect("写入演示数据应该成功");
+
+    assert_eq!(data.projects.len(), DEMO_PROJECT_COUNT);
+    assert_eq!(data.agents.len(), DEMO_AGENT_COUNT);
+    assert_eq!(data.tasks.len(), DEMO_TASK_COUNT);
+    assert_eq!(task::Entity::find().count(&db).await.unwrap(), DEMO_TASK_COUNT as u64);
+    assert!(data.conflicts.len() >= 5);
+
+    // 每个状态都有任务，已开始的任务都有执行会话和日志
+    for status in ["pending", "in_progress", "completed", "failed"] {
+        assert!(data.tasks.iter().any(|t| t.status == status), "缺少状态为 {status} 的任务");
+    }
+    let started = data.tasks.iter().filter(|t| t.status != "pending").count();
+    assert_eq!(data.sessions.len(), started);
+    assert_eq!(execution_log::Entity::find().count(&db).await.unwrap(), data.logs.len() as u64);
+    assert!(data.agents.iter().any(|a| a.status == "working" && a.current_task_id.is_some()));
+    assert_eq!(data.events.len(), DEMO_TASK_COUNT);
+}
+
+#[tokio::test]
+async fn test_delete_demo_data() {
+    let db = setup_test_db().await;
+    let seeded = seed_demo_data(&db).await.unwrap();
+
+    // 通过标记找回的数据与写入时一致
+    let data = find_demo_data(&db, seeded.user_id).await.unwrap().expect("应能找回演示数据");
+    assert_eq!(data.projects.len(), seeded.projects.len());
+    assert_eq!(data.tasks.len(), seeded.tasks.len());
+    assert_eq!(data.conflicts.len(), seeded.conflicts.len());
+    assert_eq!(data.logs.len(), seeded.logs.len());
+    assert_eq!(data.events.len(), seeded.events.len());
+
+    delete_demo_data(&db, &data).await.expect("删除演示数据应该成功");
+    assert!(find_demo_data(&db, seeded.user_id).await.unwrap().is_none());
+
+    assert_eq!(project::Entity::find().count(&db).await.unwrap(), 0);
+    assert_eq!(agent::Entity::find().count(&db).await.unwrap(), 0);
+    assert_eq!(task::Entity::find().count(&db).await.unwrap(), 0);
+    assert_eq!(conflict::Entity::find().count(&db).await.unwrap(), 0);
+    assert_eq!(execution_session::Entity::find().count(&db).await.unwrap(), 0);
+    assert_eq!(domain_event::Entity::find().count(&db).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_builders() {
+    let db = setup_test_db().await;
+    let user = UserBuilder::new("builder_user").insert(&db).await.unwrap();
+    assert_eq!(user.email, "builder_user@example.com");
+
+    let project = ProjectBuilder::new(user.user_id, "构建器项目")
+        .technology_stack(&["rust"])
+        .insert(&db)
+        .await
+        .unwrap();
+    assert_eq!(project.get_technology_stack(), vec!["rust".to_string()]);
+
+    let agent = AgentBuilder::new(user.user_id, "构建器Agent")
+        .capabilities(&["Testing"])
+        .insert(&db)
+        .await
+        .unwrap();
+
+    let task = TaskBuilder::new(project.project_id, "构建器任务")
+        .status("completed")
+        .priority("high")
+        .assigned_to(agent.agent_id)
+        .insert(&db)
+        .await
+        .unwrap();
+    assert_eq!(task.assigned_agent_id, Some(agent.agent_id));
+    assert!(task.started_at.is_some() && task.completed_at.is_some());
+}
@@ -0,0 +1,265 @@
+//! 不稳定测试识别测试
+
+use crate::common::setup_test_db;
+use chrono::{Duration, Utc};
+use codex_database::{
+    ci_integration::{ingest_check, CiCheckUpdate, CiProvider},
+    entities::{code_review::ReviewDecision, test_case_result},
+    flaky_tests::{
+        classify, evaluate_session_tests, flaky_candidates, record_session_results, FlakyTestConfig,
+        TestCaseReport, TestGateVerdict, TestOutcome, TestStability,
+    },
+    repository::{
+        AgentRepository, CodeReviewRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository,
+        UserRepository,
+        agent_repository::CreateAgentData,
+        code_review_repository::CreateCodeReviewData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use codex_multi_agent::{CiStatus, CodingStandards};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    project_id: Uuid,
+    task_id: Uuid,
+    agent_id: Uuid,
+}
+
+async fn setup(db: &DatabaseConnection) -> Fixture {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "不稳定测试项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/flaky".to_string(),
+        })
+        .await
+        .unwrap();
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "开发Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个开发Agent".to_string(),
+            capabilities: json!(["backend_development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "稳定测试套件".to_string(),
+            description: "识别不稳定的测试".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+
+    Fixture {
+        project_id: project.project_id,
+        task_id: task.task_id,
+        agent_id: agent.agent_id,
+    }
+}
+
+/// 完成一次执行会话并记录测试结果，`failed` 中的测试失败，其余通过
+async fn run_tests(db: &DatabaseConnection, fixture: &Fixture, commit: &str, failed: &[&str]) -> Uuid {
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let session = sessions
+        .create(CreateSessionData {
+            task_id: fixture.task_id,
+            agent_id: fixture.agent_id,
+            project_id: fixture.project_id,
+            git_branch: "feature/tests".to_string(),
+            base_commit: None,
+            execution_config: None,
+            timeout_minutes: 30,
+        })
+        .await
+        .unwrap();
+    sessions.start_session(session.session_id).await.unwrap();
+    sessions
+        .complete_session(session.session_id, true, Some(commit.to_string()), None, None)
+        .await
+        .unwrap();
+
+    let reports: Vec<TestCaseReport> = ["network_retry", "always_broken", "stable_math", "checkout_total"]
+        .iter()
+        .map(|name| TestCaseReport {
+            name: name.to_string(),
+            outcome: if failed.contains(name) { TestOutcome::Failed } else { TestOutcome::Passed },
+            duration_ms: Some(12),
+        })
+        .collect();
+    record_session_results(db, session.session_id, &reports).await.unwrap();
+    session.session_id
+}
+
+fn result(outcome: &str, commit: &str, minutes: i64) -> test_case_result::Model {
+    test_case_result::Model {
+        result_id: Uuid::new_v4(),
+        project_id: Uuid::nil(),
+        execution_session_id: Uuid::new_v4(),
+        test_name: "t".to_string(),
+        outcome: outcome.to_string(),
+        duration_ms: None,
+        commit_sha: Some(commit.to_string()),
+        recorded_at: (Utc::now() + Duration::minutes(minutes)).into(),
+    }
+}
+
+#[test]
+fn test_classify_history() {
+    let config = FlakyTestConfig::default();
+    let history = |results: &[test_case_result::Model]| classify("t", results, &config).unwrap();
+
+    // 同一提交上结果不一致，即使运行次数不足也判定为不稳定
+    let mixed = history(&[result("passed", "a", 0), result("failed", "a", 1)]);
+    assert!(mixed.mixed_commit_outcomes);
+    assert_eq!(mixed.stability, TestStability::Flaky);
+
+    let short = history(&[result("passed", "a", 0), result("failed", "b", 1)]);
+    assert_eq!(short.stability, TestStability::InsufficientData);
+
+    // 跳过的运行不参与统计
+    let outcomes = ["passed", "failed", "skipped", "passed", "failed", "passed", "passed"];
+    let results: Vec<_> = outcomes
+        .iter()
+        .enumerate()
+        .map(|(i, outcome)| result(outcome, &format!("c{}", i), i as i64))
+        .collect();
+    let flaky = history(&results);
+    assert_eq!(flaky.runs, 6);
+    assert_eq!(flaky.failures, 2);
+    assert!((flaky.flip_rate - 0.8).abs() < 1e-9);
+    assert_eq!(flaky.stability, TestStability::Flaky);
+
+    let results: Vec<_> = (0..6).map(|i| result("failed", &format!("c{}", i), i)).collect();
+    assert_eq!(history(&results).stability, TestStability::Failing);
+
+    // 长期通过后的一次失败是回归，不是不稳定
+    let mut results: Vec<_> = (0..9).map(|i| result("passed", &format!("c{}", i), i)).collect();
+    results.push(result("failed", "c9", 9));
+    let regression = history(&results);
+    assert!((regression.flip_rate - 1.0 / 9.0).abs() < 1e-9);
+    assert_eq!(regression.stability, TestStability::Stable);
+
+    assert!(classify("t", &[result("skipped", "a", 0)], &config).is_none());
+    assert!(FlakyTestConfig { min_runs: 1, ..FlakyTestConfig::default() }.validate().unwrap_err().is_validation_error());
+    assert!(FlakyTestConfig { min_flip_rate: 1.5, ..FlakyTestConfig::default() }.validate().is_err());
+}
+
+#[tokio::test]
+async fn test_flaky_failures_are_quarantined_by_quality_gate() {
+    let db = setup_test_db().await;
+    let fixture = setup(&db).await;
+
+    let history: [&[&str]; 6] = [
+        &["network_retry", "always_broken"],
+        &["always_broken"],
+        &["network_retry", "always_broken"],
+        &["always_broken"],
+        &["network_retry", "always_broken"],
+        &["always_broken"],
+    ];
+    for (i, failed) in history.iter().enumerate() {
+        run_tests(&db, &fixture, &format!("commit{}", i), failed).await;
+    }
+
+    let report = flaky_candidates(&db, fixture.project_id, &FlakyTestConfig::default()).await.unwrap();
+    assert_eq!(report.total_tests, 4);
+    let flaky: Vec<_> = report.flaky.iter().map(|test| test.test_name.as_str()).collect();
+    assert_eq!(flaky, vec!["network_retry"]);
+    let failing: Vec<_> = report.failing.iter().map(|test| test.test_name.as_str()).collect();
+    assert_eq!(failing, vec!["always_broken"]);
+
+    // 只有不稳定测试失败：未开启隔离时阻止
+    let session_id = run_tests(&db, &fixture, "commit6", &["network_retry"]).await;
+    let gate = evaluate_session_tests(&db, session_id, &FlakyTestConfig::default()).await.unwrap().unwrap();
+    assert_eq!(gate.verdict, TestGateVerdict::Blocked);
+    assert_eq!(gate.blocking_failures, vec!["network_retry".to_string()]);
+
+    // CI 为红色时审查不能批准
+    let review_id = CodeReviewRepository::new(db.clone())
+        .create(CreateCodeReviewData {
+            task_id: fixture.task_id,
+            execution_session_id: session_id,
+            reviewer_agent_id: fixture.agent_id,
+            pull_request_url: "https://github.com/test/repo/pull/3".to_string(),
+            source_branch: "feature/tests".to_string(),
+            target_branch: "main".to_string(),
+            review_comments: json!([]),
+            code_changes: json!([]),
+            status: "pending".to_string(),
+            decision: None,
+            overall_comment: None,
+        })
+        .await
+        .unwrap()
+        .review_id;
+    let run = ingest_check(
+        &db,
+        fixture.project_id,
+        CiCheckUpdate {
+            provider: CiProvider::GithubActions,
+            external_id: "workflow_run:1".to_string(),
+            name: "CI".to_string(),
+            commit_sha: "commit6".to_string(),
+            branch: Some("feature/tests".to_string()),
+            pull_request_url: None,
+            status: CiStatus::Failed,
+            details_url: None,
+            started_at: None,
+            completed_at: None,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(run.review_id, Some(review_id));
+    let reviews = CodeReviewRepository::new(db.clone());
+    assert!(reviews.submit_decision(review_id, ReviewDecision::Approved, None).await.is_err());
+
+    // 开启隔离后，失败的测试全部不稳定时只给出警告，审查可以批准
+    let mut standards = CodingStandards::default();
+    standards.quality_gates.quarantine_flaky_tests = true;
+    ProjectRepository::new(db.clone())
+        .update_config(fixture.project_id, None, Some(serde_json::to_value(standards).unwrap()), None)
+        .await
+        .unwrap();
+    let gate = evaluate_session_tests(&db, session_id, &FlakyTestConfig::default()).await.unwrap().unwrap();
+    assert_eq!(gate.verdict, TestGateVerdict::Warning);
+    assert_eq!(gate.quarantined_failures, vec!["network_retry".to_string()]);
+    assert!(gate.blocking_failures.is_empty());
+    let approved = reviews.submit_decision(review_id, ReviewDecision::Approved, None).await.unwrap();
+    assert_eq!(approved.decision.as_deref(), Some("approved"));
+
+    // 稳定测试的新失败仍然阻止
+    let session_id = run_tests(&db, &fixture, "commit7", &["network_retry", "checkout_total"]).await;
+    let gate = evaluate_session_tests(&db, session_id, &FlakyTestConfig::default()).await.unwrap().unwrap();
+    assert_eq!(gate.verdict, TestGateVerdict::Blocked);
+    assert_eq!(gate.blocking_failures, vec!["checkout_total".to_string()]);
+    assert_eq!(gate.quarantined_failures, vec!["network_retry".to_string()]);
+}
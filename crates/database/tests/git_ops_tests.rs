@@ -0,0 +1,166 @@
+//! 分支保护测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    command_policy::{CommandDecision, CommandPolicy, CommandPolicyEngine, CommandRequest, CommandRule, RULE_PROTECTED_BRANCH},
+    entities::domain_event::DomainEventType,
+    git_ops::{
+        find_project_for_path, resolve_protection, BranchGuard, BranchProtection, BranchViolationKind,
+        BRANCH_PROTECTION_ERROR,
+    },
+    repository::{
+        project_repository::CreateProjectData, user_repository::CreateUserData, CommandAuditLogRepository,
+        DomainEventRepository, ProjectRepository, UserRepository,
+    },
+};
+use codex_multi_agent::{ErrorEvent, EventSource};
+use uuid::Uuid;
+
+mod common;
+
+fn protection() -> BranchProtection {
+    BranchProtection {
+        main_branch: "main".to_string(),
+        protected_branches: vec!["main".to_string(), "release/*".to_string()],
+    }
+}
+
+fn words(command: &str) -> Vec<String> {
+    command.split_whitespace().map(str::to_string).collect()
+}
+
+fn check(command: &str, current_branch: Option<&str>) -> Option<BranchViolationKind> {
+    let protection = protection();
+    let mut guard = BranchGuard::new(&protection, current_branch.map(str::to_string));
+    guard.check(&words(command)).map(|violation| violation.kind)
+}
+
+#[test]
+fn test_branch_guard() {
+    assert!(protection().is_protected("release/1.2"));
+    assert!(protection().is_protected("refs/heads/main"));
+    assert!(!protection().is_protected("feature/login"));
+
+    // 受保护分支上的提交类操作
+    for command in ["git commit -m fix", "git -C . merge feature/login", "git rebase origin/main", "git cherry-pick abc123"] {
+        assert_eq!(check(command, Some("main")), Some(BranchViolationKind::DirectCommit), "{}", command);
+        assert_eq!(check(command, Some("feature/login")), None, "{}", command);
+    }
+    assert_eq!(check("git rebase --abort", Some("main")), None);
+    assert_eq!(check("git commit -m fix", None), None);
+
+    // 推送：显式目标、HEAD、省略目标和删除
+    assert_eq!(check("git push origin main", Some("feature/login")), Some(BranchViolationKind::Push));
+    assert_eq!(check("git push origin HEAD:refs/heads/release/2.0", Some("feature/login")), Some(BranchViolationKind::Push));
+    assert_eq!(check("git push", Some("main")), Some(BranchViolationKind::Push));
+    assert_eq!(check("git push -u origin HEAD", Some("feature/login")), None);
+    assert_eq!(check("git push origin --delete main", None), Some(BranchViolationKind::Delete));
+    assert_eq!(check("git push origin :release/1.0", None), Some(BranchViolationKind::Delete));
+    assert_eq!(check("git branch -D main", None), Some(BranchViolationKind::Delete));
+    assert_eq!(check("git branch -d feature/login", None), None);
+
+    // 强制推送
+    for command in ["git push --force origin main", "git push -uf origin main", "git push origin +main"] {
+        assert_eq!(check(command, None), Some(BranchViolationKind::ForcePush), "{}", command);
+    }
+    assert_eq!(check("git push --force-with-lease origin feature/login", Some("main")), None);
+
+    // 切换分支后按新分支判断
+    let protection = protection();
+    let mut guard = BranchGuard::new(&protection, Some("feature/login".to_string()));
+    assert!(guard.check(&words("git checkout main")).is_none());
+    assert_eq!(guard.current_branch(), Some("main"));
+    assert!(guard.check(&words("git commit -m fix")).is_some());
+    assert!(guard.check(&words("git switch -c feature/fix")).is_none());
+    assert!(guard.check(&words("git commit -m fix")).is_none());
+}
+
+#[tokio::test]
+async fn test_engine_denies_protected_branch_operations() {
+    let db = setup_test_db().await;
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let workspace = tempfile::tempdir().unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "分支保护项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: workspace.path().to_string_lossy().to_string(),
+        })
+        .await
+        .unwrap();
+
+    // 子目录归属到项目，受保护分支来自编码规范
+    let found = find_project_for_path(&db, &workspace.path().join("src")).await.unwrap().unwrap();
+    assert_eq!(found.project_id, project.project_id);
+    let protection = resolve_protection(&db, project.project_id).await.unwrap();
+    assert!(protection.is_protected(&protection.main_branch.clone()));
+    let main_branch = protection.main_branch.clone();
+
+    let mut policy = CommandPolicy::default();
+    policy.rules.push(CommandRule::allow("git", r"^git\b", "git 操作"));
+    let engine = CommandPolicyEngine::new(db.clone(), policy).unwrap();
+    let request = |script: &str| {
+        let mut request = CommandRequest::new(
+            vec!["bash".to_string(), "-lc".to_string(), script.to_string()],
+            workspace.path(),
+        );
+        request.project_id = Some(project.project_id);
+        request.conversation_id = Some("conversation-git".to_string());
+        request.branch_protection = Some(protection.clone());
+        request.current_branch = Some("feature/login".to_string());
+        request
+    };
+
+    // 功能分支上的操作正常放行
+    let allowed = engine.evaluate(&request("git commit -am wip && git push origin feature/login")).await.unwrap();
+    assert_eq!(allowed.decision, CommandDecision::AutoApprove);
+
+    let denied = engine
+        .evaluate(&request(&format!("git checkout {0} && git merge feature/login && git push --force origin {0}", main_branch)))
+        .await
+        .unwrap();
+    assert_eq!(denied.decision, CommandDecision::Deny);
+    assert_eq!(denied.matched_rules.iter().filter(|matched| matched.rule == RULE_PROTECTED_BRANCH).count(), 2);
+
+    // 未配置分支保护时只按常规规则判断
+    let mut unprotected = request(&format!("git push origin {}", main_branch));
+    unprotected.branch_protection = None;
+    assert_eq!(engine.classify(&unprotected).decision, CommandDecision::AutoApprove);
+
+    // 审计记录和安全错误事件
+    let audit = CommandAuditLogRepository::new(db.clone())
+        .find_by_conversation("conversation-git")
+        .await
+        .unwrap();
+    assert_eq!(audit.len(), 2);
+    let denied_audit = audit.iter().find(|entry| entry.decision == "deny").unwrap();
+    assert_eq!(Some(denied_audit.audit_id), denied.audit_id);
+
+    let events = DomainEventRepository::new(db.clone())
+        .find_by_correlation_id(denied_audit.audit_id)
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|event| event.event_type == DomainEventType::BranchProtectionViolated.to_string()));
+    let errors: Vec<ErrorEvent> = events
+        .iter()
+        .map(|event| serde_json::from_value(event.event_data.clone()).unwrap())
+        .collect();
+    assert!(errors.iter().all(|error| error.metadata.source == EventSource::Security
+        && error.error_type == BRANCH_PROTECTION_ERROR
+        && error.related_entity_id == Some(denied_audit.audit_id.to_string())));
+    let mut codes: Vec<_> = errors.iter().filter_map(|error| error.error_code.clone()).collect();
+    codes.sort();
+    assert_eq!(codes, vec!["direct_commit".to_string(), "force_push".to_string()]);
+}
@@ -43,6 +43,7 @@ async fn create_test_conflict(db: &codex_database::DatabaseConnection) -> Uuid {
         related_entities: json!({}),
         affected_tasks: json!([]),
         affected_agents: json!([]),
+        project_id: None,
     };
     let conflict = conflict_repo.create(conflict_data).await.unwrap();
     conflict.conflict_id
@@ -0,0 +1,90 @@
+//! 幂等键集成测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    api_keys::{issue_api_key, ApiScope, IssueApiKey},
+    idempotency::IdempotencyStore,
+    public_api::{ApiRequest, PublicApi},
+    repository::{
+        ProjectRepository, TaskRepository, UserRepository,
+        project_repository::CreateProjectData,
+        user_repository::CreateUserData,
+    },
+    DatabaseError,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use uuid::Uuid;
+
+mod common;
+
+#[tokio::test]
+async fn test_duplicate_returns_original_result() {
+    let db = setup_test_db().await;
+    let store = IdempotencyStore::new(db.clone());
+    let runs = AtomicUsize::new(0);
+
+    let run = || async {
+        runs.fetch_add(1, Ordering::SeqCst);
+        Ok::<_, DatabaseError>(Uuid::new_v4())
+    };
+    let first = store.execute("tasks.create", "key-1", "标题", run).await.unwrap();
+    let second = store.execute("tasks.create", "key-1", "标题", run).await.unwrap();
+    assert_eq!(first, second);
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    // 相同键携带不同请求时拒绝；不同作用域互不影响
+    let err = store.execute("tasks.create", "key-1", "其他标题", run).await.unwrap_err();
+    assert!(err.is_validation_error());
+    store.execute("notifications.send", "key-1", "标题", run).await.unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+    // 执行失败时释放键，重试会重新执行
+    let failed: Result<Uuid, _> = store
+        .execute("tasks.create", "key-2", "标题", || async { Err(DatabaseError::validation("失败")) })
+        .await;
+    assert!(failed.is_err());
+    store.execute("tasks.create", "key-2", "标题", run).await.expect("释放后应该可以重试");
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_public_api_create_task_is_idempotent() {
+    let db = setup_test_db().await;
+    let user_id = UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("idem_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("idem_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id;
+    let project_id = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: "幂等项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/idempotency.git".to_string(),
+        workspace_path: "/workspace/idempotency".to_string(),
+    }).await.unwrap().project_id;
+    let issued = issue_api_key(&db, IssueApiKey {
+        user_id,
+        project_id: Some(project_id),
+        name: "重试脚本".to_string(),
+        scopes: vec![ApiScope::TasksWrite],
+        rate_limit_per_minute: None,
+        expires_at: None,
+    }).await.unwrap();
+
+    let api = PublicApi::new(db.clone());
+    let request = ApiRequest {
+        method: "POST".to_string(),
+        path: format!("/api/v1/projects/{}/tasks", project_id),
+        authorization: Some(format!("Bearer {}", issued.secret)),
+        idempotency_key: Some("nightly-build-42".to_string()),
+        body: serde_json::to_vec(&serde_json::json!({ "title": "修复夜间构建" })).unwrap(),
+    };
+    let first = api.handle(request.clone()).await;
+    let retry = api.handle(request).await;
+    assert_eq!(first.status, 201);
+    assert_eq!(retry.status, 201);
+    assert_eq!(retry.body["task"]["task_id"], first.body["task"]["task_id"]);
+    assert_eq!(TaskRepository::new(db.clone()).find_by_project(project_id).await.unwrap().len(), 1);
+}
@@ -0,0 +1,100 @@
+//! LLM响应缓存测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    llm_cache::{CacheOutcome, CachedResponse, LlmCache, LlmCacheConfig, LlmCacheRequest},
+    DatabaseError,
+};
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod common;
+
+fn config() -> LlmCacheConfig {
+    LlmCacheConfig { enabled: true, ..Default::default() }
+}
+
+#[tokio::test]
+async fn test_cache_hit_miss_and_bypass() {
+    let db = setup_test_db().await;
+    let cache = LlmCache::new(db.clone(), config());
+    let calls = AtomicUsize::new(0);
+    let call = || async {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Ok("分解结果".to_string())
+    };
+
+    // 参数键顺序不影响缓存键，模型或参数不同则不同
+    let request = LlmCacheRequest::new("gpt-4o", "分解需求").with_params(json!({"temperature": 0, "max_tokens": 512}));
+    let reordered = LlmCacheRequest::new("gpt-4o", "分解需求").with_params(json!({"max_tokens": 512, "temperature": 0}));
+    assert_eq!(request.cache_key(), reordered.cache_key());
+    assert_ne!(request.cache_key(), LlmCacheRequest::new("gpt-4o-mini", "分解需求").with_params(request.params.clone()).cache_key());
+
+    assert_eq!(cache.get_or_call(&request, call).await.unwrap().outcome, CacheOutcome::Miss);
+    let cached = cache.get_or_call(&reordered, call).await.unwrap();
+    assert_eq!(cached, CachedResponse { response: "分解结果".to_string(), outcome: CacheOutcome::Hit });
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // 跳过缓存的调用总是重新请求
+    let creative = request.clone().bypass();
+    assert_eq!(cache.get_or_call(&creative, call).await.unwrap().outcome, CacheOutcome::Bypass);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    // 调用失败不写入缓存
+    let failing = LlmCacheRequest::new("gpt-4o", "评审代码");
+    let error = cache
+        .get_or_call(&failing, || async { Err(DatabaseError::business_logic("LLM不可用")) })
+        .await
+        .unwrap_err();
+    assert!(error.is_business_error());
+    assert!(cache.get(&failing).await.unwrap().is_none());
+
+    let stats = cache.stats().await.unwrap();
+    assert_eq!((stats.metrics.hits, stats.metrics.misses, stats.metrics.bypassed), (1, 3, 1));
+    assert_eq!(stats.entries, 1);
+    assert_eq!(stats.total_hits, 1);
+
+    // 未启用时不读写
+    let disabled = LlmCache::new(db.clone(), LlmCacheConfig::default());
+    assert!(disabled.get(&request).await.unwrap().is_none());
+    assert_eq!(disabled.metrics().bypassed, 1);
+}
+
+#[tokio::test]
+async fn test_cache_expiry_and_size_limits() {
+    let db = setup_test_db().await;
+
+    // 已过期的条目不会命中
+    let expired = LlmCache::new(db.clone(), LlmCacheConfig { ttl_seconds: 0, ..config() });
+    let request = LlmCacheRequest::new("gpt-4o", "过期提示词");
+    expired.put(&request, "旧响应").await.unwrap();
+    assert!(expired.get(&request).await.unwrap().is_none());
+
+    // 过大的响应不缓存
+    let cache = LlmCache::new(
+        db.clone(),
+        LlmCacheConfig { max_entries: 2, max_response_bytes: 16, ..config() },
+    );
+    let large = LlmCacheRequest::new("gpt-4o", "大响应");
+    cache.put(&large, &"x".repeat(17)).await.unwrap();
+    assert!(cache.get(&large).await.unwrap().is_none());
+
+    // 超出条目上限时淘汰最久未使用的条目
+    let requests: Vec<LlmCacheRequest> =
+        (0..3).map(|index| LlmCacheRequest::new("gpt-4o", format!("提示词{}", index))).collect();
+    cache.put(&requests[0], "响应0").await.unwrap();
+    cache.put(&requests[1], "响应1").await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    assert!(cache.get(&requests[0]).await.unwrap().is_some());
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    cache.put(&requests[2], "响应2").await.unwrap();
+
+    assert!(cache.get(&requests[1]).await.unwrap().is_none());
+    assert!(cache.get(&requests[0]).await.unwrap().is_some());
+    assert!(cache.get(&requests[2]).await.unwrap().is_some());
+    let stats = cache.stats().await.unwrap();
+    assert_eq!(stats.entries, 2);
+    assert!(stats.metrics.evictions >= 1);
+
+    assert_eq!(cache.clear().await.unwrap(), 2);
+}
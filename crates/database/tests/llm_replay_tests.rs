@@ -0,0 +1,170 @@
+//! LLM会话重放测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    llm_provider::{LlmCompletion, LlmFuture, LlmProvider, LlmRequest},
+    llm_replay::{get_replay_report, list_replay_reports, replay_session, ReplayOptions},
+    llm_transcripts::{LlmCallKind, RecordingProvider, TranscriptLink},
+    repository::{
+        llm_session_repository::CreateLlmSessionData, project_repository::CreateProjectData,
+        user_repository::CreateUserData, LlmSessionRepository, ProjectRepository, UserRepository,
+    },
+    DatabaseConnection, DatabaseError,
+};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+mod common;
+
+/// 按提示词返回固定输出的提供方，记录收到的请求
+struct EchoProvider {
+    suffix: &'static str,
+    requests: Mutex<Vec<LlmRequest>>,
+}
+
+impl EchoProvider {
+    fn new(suffix: &'static str) -> Self {
+        Self { suffix, requests: Mutex::new(Vec::new()) }
+    }
+}
+
+impl LlmProvider for EchoProvider {
+    fn name(&self) -> &str {
+        "echo"
+    }
+
+    fn complete<'a>(&'a self, request: &'a LlmRequest) -> LlmFuture<'a> {
+        Box::pin(async move {
+            self.requests.lock().unwrap().push(request.clone());
+            if request.prompt.contains("超时") {
+                return Err(DatabaseError::business_logic("模型响应超时"));
+            }
+            Ok(LlmCompletion {
+                content: format!("回复：{}\n{}", request.prompt, self.suffix),
+                tokens_used: 10,
+            })
+        })
+    }
+}
+
+async fn create_session(db: &DatabaseConnection) -> (Uuid, Uuid) {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "重放项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/replay".to_string(),
+        })
+        .await
+        .unwrap();
+    let session = LlmSessionRepository::new(db.clone())
+        .create(CreateLlmSessionData {
+            project_id: project.project_id,
+            user_id: user.user_id,
+            session_type: "decomposition".to_string(),
+            system_prompt: None,
+            decomposition_prompt: None,
+        })
+        .await
+        .unwrap();
+    (project.project_id, session.session_id)
+}
+
+#[tokio::test]
+async fn test_replay_session_reissues_prompts_and_diffs_outputs() {
+    let db = setup_test_db().await;
+    let (project_id, session_id) = create_session(&db).await;
+
+    // 原始会话：分解和一次修订
+    let original = EchoProvider::new("计划 A");
+    let recording = RecordingProvider::new(&original, db.clone(), LlmCallKind::Decomposition).with_link(TranscriptLink {
+        llm_session_id: Some(session_id),
+        ..TranscriptLink::project(project_id)
+    });
+    let mut request = LlmRequest::new("model-v1", "分解登录需求");
+    request.temperature = Some(0.25);
+    request.max_tokens = Some(2048);
+    recording.complete(&request).await.unwrap();
+    recording.complete(&LlmRequest::new("model-v1", "修订计划")).await.unwrap();
+
+    // 相同输出的提供方可以复现会话
+    let same = EchoProvider::new("计划 A");
+    let report = replay_session(&db, &same, session_id, &ReplayOptions::default()).await.unwrap();
+    assert!(report.is_reproducible());
+    assert_eq!(report.total_calls, 2);
+    assert!(report.calls.iter().all(|call| call.diff.is_empty() && call.similarity == 1.0));
+    // 按调用顺序使用原始模型和参数
+    let requests = same.requests.lock().unwrap().clone();
+    assert_eq!(requests[0].prompt, "分解登录需求");
+    assert_eq!(requests[0].model, "model-v1");
+    assert_eq!(requests[0].temperature, Some(0.25));
+    assert_eq!(requests[0].max_tokens, Some(2048));
+    assert_eq!(requests[1].prompt, "修订计划");
+
+    // 指定模型重放，输出变化记录为差异
+    let changed = EchoProvider::new("计划 B");
+    let report = replay_session(&db, &changed, session_id, &ReplayOptions { model: Some("model-v2".to_string()) })
+        .await
+        .unwrap();
+    assert_eq!(report.changed_calls, 2);
+    assert_eq!(report.failed_calls, 0);
+    assert_eq!(report.model_override.as_deref(), Some("model-v2"));
+    let call = &report.calls[0];
+    assert_eq!((call.original_model.as_str(), call.replay_model.as_str()), ("model-v1", "model-v2"));
+    assert!(call.diff.contains("-计划 A") && call.diff.contains("+计划 B"));
+    assert!(call.similarity > 0.0 && call.similarity < 1.0);
+
+    // 报告关联到会话，最新的在前
+    let reports = list_replay_reports(&db, session_id).await.unwrap();
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].report_id, report.report_id);
+    assert_eq!(get_replay_report(&db, report.report_id).await.unwrap().unwrap(), report);
+}
+
+#[tokio::test]
+async fn test_replay_reports_failures_and_requires_transcripts() {
+    let db = setup_test_db().await;
+    let (project_id, session_id) = create_session(&db).await;
+
+    let empty = replay_session(&db, &EchoProvider::new(""), session_id, &ReplayOptions::default()).await.unwrap_err();
+    assert!(empty.is_validation_error());
+    let missing = replay_session(&db, &EchoProvider::new(""), Uuid::new_v4(), &ReplayOptions::default()).await;
+    assert!(missing.is_err());
+
+    // 原始调用成功，重放时失败
+    struct PromptProvider;
+    impl LlmProvider for PromptProvider {
+        fn name(&self) -> &str {
+            "prompt"
+        }
+
+        fn complete<'a>(&'a self, _request: &'a LlmRequest) -> LlmFuture<'a> {
+            Box::pin(async move { Ok(LlmCompletion { content: "完成".to_string(), tokens_used: 1 }) })
+        }
+    }
+    RecordingProvider::new(&PromptProvider, db.clone(), LlmCallKind::Review)
+        .with_link(TranscriptLink {
+            llm_session_id: Some(session_id),
+            ..TranscriptLink::project(project_id)
+        })
+        .complete(&LlmRequest::new("model-v1", "评审超时的计划"))
+        .await
+        .unwrap();
+
+    let report = replay_session(&db, &EchoProvider::new(""), session_id, &ReplayOptions::default()).await.unwrap();
+    assert_eq!((report.total_calls, report.changed_calls, report.failed_calls), (1, 1, 1));
+    assert!(report.calls[0].replay_error.as_deref().unwrap().contains("模型响应超时"));
+    assert_eq!(report.calls[0].original_response.as_deref(), Some("完成"));
+    assert_eq!(report.calls[0].call_kind, "review");
+}
@@ -0,0 +1,116 @@
+//! 执行日志背压管道测试
+
+use codex_database::log_pipeline::{LogBatch, LogLine, LogPipeline, LogPipelineConfig};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn line(stream: &str, message: &str) -> LogLine {
+    LogLine {
+        stream: stream.to_string(),
+        log_level: "info".to_string(),
+        event_type: "Progress".to_string(),
+        message: message.to_string(),
+        timestamp_ms: 0,
+    }
+}
+
+fn collector() -> (Arc<Mutex<Vec<LogBatch>>>, impl FnMut(LogBatch) + Send + 'static) {
+    let batches = Arc::new(Mutex::new(Vec::new()));
+    let sink = batches.clone();
+    (batches, move |batch| sink.lock().unwrap().push(batch))
+}
+
+#[tokio::test]
+async fn test_batches_coalesce_repeated_lines_per_stream() {
+    let (batches, emit) = collector();
+    let config = LogPipelineConfig { flush_interval: Duration::from_secs(60), ..Default::default() };
+    let (pipeline, task) = LogPipeline::new(config, emit).unwrap();
+    let handle = tokio::spawn(task);
+
+    pipeline.push(line("a", "编译中"));
+    for _ in 0..5 {
+        pipeline.push(line("a", "下载依赖..."));
+    }
+    pipeline.push(line("b", "运行测试"));
+    pipeline.push(line("a", "完成"));
+    let metrics = pipeline.metrics();
+    drop(pipeline);
+    handle.await.unwrap();
+
+    let batches = batches.lock().unwrap();
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[0].stream, "a");
+    let lines: Vec<(&str, u64)> = batches[0].lines.iter().map(|l| (l.message.as_str(), l.repeat)).collect();
+    assert_eq!(lines, [("编译中", 1), ("下载依赖...", 5), ("完成", 1)]);
+    assert_eq!(batches[1].stream, "b");
+    assert_eq!(batches[1].omitted, 0);
+    assert_eq!(metrics.received, 8);
+    assert_eq!(metrics.dropped, 0);
+}
+
+#[tokio::test]
+async fn test_full_queue_drops_to_summary() {
+    let (batches, emit) = collector();
+    let config = LogPipelineConfig { capacity: 4, ..Default::default() };
+    let (pipeline, task) = LogPipeline::new(config, emit).unwrap();
+    let handle = tokio::spawn(task);
+
+    // 发送任务还没有机会运行，队列只能容纳 4 行
+    let accepted = (0..100).filter(|n| pipeline.push(line("session", &format!("第 {} 行", n)))).count();
+    assert_eq!(accepted, 4);
+    let metrics = pipeline.metrics();
+    drop(pipeline);
+    handle.await.unwrap();
+
+    let batches = batches.lock().unwrap();
+    let lines: usize = batches.iter().map(|b| b.lines.len()).sum();
+    let omitted: u64 = batches.iter().map(|b| b.omitted).sum();
+    assert_eq!(lines, 4);
+    assert_eq!(omitted, 96);
+    assert_eq!(metrics.received, 100);
+    assert_eq!(metrics.dropped, 96);
+}
+
+#[tokio::test]
+async fn test_batch_keeps_latest_lines_and_metrics_balance() {
+    let (batches, emit) = collector();
+    let config = LogPipelineConfig {
+        capacity: 1000,
+        flush_interval: Duration::from_millis(20),
+        max_batch_lines: 10,
+    };
+    let (pipeline, task) = LogPipeline::new(config, emit).unwrap();
+    let handle = tokio::spawn(task);
+    let producer = pipeline.clone();
+    tokio::spawn(async move {
+        for n in 0..50 {
+            producer.push(line("session", &format!("第 {} 行", n)));
+        }
+    })
+    .await
+    .unwrap();
+    // 等待至少一次定时发送
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let metrics = pipeline.metrics();
+    assert!(metrics.batches >= 1);
+    assert_eq!(metrics.received, metrics.emitted + metrics.dropped);
+    drop(pipeline);
+    handle.await.unwrap();
+
+    let batches = batches.lock().unwrap();
+    let last = batches.last().unwrap();
+    assert_eq!(last.lines.len(), 10);
+    assert_eq!(last.lines.last().unwrap().message, "第 49 行");
+    assert_eq!(batches.iter().map(|b| b.lines.len() as u64 + b.omitted).sum::<u64>(), 50);
+}
+
+#[test]
+fn test_invalid_config() {
+    for config in [
+        LogPipelineConfig { capacity: 0, ..Default::default() },
+        LogPipelineConfig { flush_interval: Duration::ZERO, ..Default::default() },
+        LogPipelineConfig { max_batch_lines: 0, ..Default::default() },
+    ] {
+        assert!(LogPipeline::new(config, |_| {}).err().unwrap().is_validation_error());
+    }
+}
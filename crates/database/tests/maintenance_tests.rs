@@ -0,0 +1,179 @@
+//! 定期维护任务测试
+
+use crate::common::setup_test_db;
+use chrono::{Duration, Utc};
+use codex_database::{
+    maintenance::{
+        default_jobs, MaintenanceFuture, MaintenanceJobHandler, MaintenanceJobSpec, MaintenanceRunner,
+        JOB_METRICS_ROLLUP, JOB_PRUNE, JOB_SNAPSHOT, JOB_VACUUM, LOCK_TIMEOUT,
+    },
+    repository::{IdempotencyKeyRepository, MaintenanceJobRepository, MaintenanceJobRunRepository},
+    establish_connection, migrations::Migrator, DatabaseConnection, DatabaseError,
+};
+use serde_json::{json, Value as JsonValue};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+mod common;
+
+/// 记录执行次数，参数 `fail` 为 true 时返回错误
+struct CountingJob {
+    calls: AtomicUsize,
+}
+
+impl MaintenanceJobHandler for CountingJob {
+    fn kind(&self) -> &str {
+        "counting"
+    }
+
+    fn run<'a>(&'a self, _db: &'a DatabaseConnection, params: &'a JsonValue, _now: chrono::DateTime<Utc>) -> MaintenanceFuture<'a> {
+        Box::pin(async move {
+            let calls = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if params["fail"].as_bool().unwrap_or(false) {
+                return Err(DatabaseError::business_logic("模拟失败"));
+            }
+            Ok(json!({ "calls": calls }))
+        })
+    }
+}
+
+fn counting_spec(name: &str, params: JsonValue) -> MaintenanceJobSpec {
+    MaintenanceJobSpec {
+        name: name.to_string(),
+        kind: "counting".to_string(),
+        cron_expression: "0 0 * * * *".to_string(),
+        jitter_secs: 0,
+        params,
+        enabled: true,
+    }
+}
+
+#[tokio::test]
+async fn test_ensure_default_jobs_with_jitter() {
+    let db = setup_test_db().await;
+    let snapshots = tempfile::tempdir().unwrap();
+    let runner = MaintenanceRunner::with_builtin_jobs(db.clone(), Some(snapshots.path().to_path_buf()));
+    assert_eq!(runner.kinds(), [JOB_METRICS_ROLLUP, JOB_PRUNE, JOB_SNAPSHOT, JOB_VACUUM]);
+
+    let now = Utc::now();
+    let created = runner.ensure_jobs(default_jobs(), now).await.unwrap();
+    assert_eq!(created.len(), 4);
+    for job in &created {
+        let next = job.next_run_at.with_timezone(&Utc);
+        assert!(next > now);
+        assert!(next <= now + Duration::days(7) + Duration::seconds(job.jitter_secs as i64));
+    }
+    assert!(runner.ensure_jobs(default_jobs(), now).await.unwrap().is_empty(), "已存在的任务不重复创建");
+
+    // 没有快照目录时不登记快照任务
+    let db = setup_test_db().await;
+    let runner = MaintenanceRunner::with_builtin_jobs(db.clone(), None);
+    let created = runner.ensure_jobs(default_jobs(), now).await.unwrap();
+    assert!(created.iter().all(|job| job.kind != JOB_SNAPSHOT));
+
+    let invalid = MaintenanceJobSpec { cron_expression: "每天".to_string(), ..counting_spec("bad", json!({})) };
+    let mut runner = MaintenanceRunner::new(db.clone());
+    runner.register(Arc::new(CountingJob { calls: AtomicUsize::new(0) }));
+    assert!(runner.create_job(invalid, now).await.unwrap_err().is_validation_error());
+}
+
+#[tokio::test]
+async fn test_run_due_records_history_and_prevents_overlap() {
+    let db = setup_test_db().await;
+    let handler = Arc::new(CountingJob { calls: AtomicUsize::new(0) });
+    let mut runner = MaintenanceRunner::new(db.clone());
+    runner.register(handler.clone());
+    let now = Utc::now();
+    let job = runner.create_job(counting_spec("hourly", json!({})), now).await.unwrap();
+    let failing = runner.create_job(counting_spec("failing", json!({ "fail": true })), now).await.unwrap();
+
+    assert!(runner.run_due(now).await.unwrap().is_empty(), "未到执行时间");
+    let later = now + Duration::hours(1);
+    let runs = runner.run_due(later).await.unwrap();
+    assert_eq!(runs.len(), 2);
+    let ok = runs.iter().find(|run| run.job_id == job.job_id).unwrap();
+    assert_eq!(ok.status, "succeeded");
+    assert_eq!(ok.trigger, "schedule");
+    assert!(ok.result.as_ref().unwrap()["calls"].as_u64().is_some());
+    assert!(ok.duration_ms.is_some());
+    let failed = runs.iter().find(|run| run.job_id == failing.job_id).unwrap();
+    assert_eq!(failed.status, "failed");
+    assert!(failed.error_message.as_deref().unwrap().contains("模拟失败"));
+
+    let jobs = MaintenanceJobRepository::new(db.clone());
+    let refreshed = jobs.find_by_id(job.job_id).await.unwrap().unwrap();
+    assert!(refreshed.next_run_at.with_timezone(&Utc) > later);
+    assert_eq!(refreshed.last_status.as_deref(), Some("succeeded"));
+    assert!(!refreshed.is_running());
+
+    // 另一个进程正在执行时跳过本次调度，手动执行返回错误
+    assert!(jobs.try_lock(job.job_id, later, later - LOCK_TIMEOUT).await.unwrap());
+    let calls = handler.calls.load(Ordering::SeqCst);
+    let next = later + Duration::hours(1);
+    let runs = runner.run_due(next).await.unwrap();
+    let skipped = runs.iter().find(|run| run.job_id == job.job_id).unwrap();
+    assert_eq!(skipped.status, "skipped");
+    assert!(runner.trigger(job.job_id, next).await.unwrap_err().to_string().contains("正在执行"));
+    assert_eq!(handler.calls.load(Ordering::SeqCst), calls + 1, "只有失败的任务被执行");
+
+    // 执行锁超时后被接管
+    let stale = later + LOCK_TIMEOUT + Duration::minutes(1);
+    let run = runner.trigger(job.job_id, stale).await.unwrap();
+    assert_eq!(run.status, "succeeded");
+    assert_eq!(run.trigger, "manual");
+
+    let history = MaintenanceJobRunRepository::new(db.clone()).find_by_job(job.job_id, 10).await.unwrap();
+    let statuses: Vec<&str> = history.iter().map(|run| run.status.as_str()).collect();
+    assert_eq!(statuses, ["succeeded", "skipped", "succeeded"]);
+}
+
+#[tokio::test]
+async fn test_builtin_prune_vacuum_and_snapshot() {
+    // 快照需要文件数据库
+    let data_dir = tempfile::tempdir().unwrap();
+    let db = establish_connection(&format!("sqlite:{}?mode=rwc", data_dir.path().join("sker.db").display())).await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+    let snapshots = tempfile::tempdir().unwrap();
+    let runner = MaintenanceRunner::with_builtin_jobs(db.clone(), Some(snapshots.path().to_path_buf()));
+    let now = Utc::now();
+    runner.ensure_jobs(default_jobs(), now).await.unwrap();
+    let jobs = MaintenanceJobRepository::new(db.clone());
+
+    let keys = IdempotencyKeyRepository::new(db.clone());
+    keys.try_claim("test", "expired", "hash".to_string(), now - Duration::minutes(1)).await.unwrap();
+    keys.try_claim("test", "active", "hash".to_string(), now + Duration::days(1)).await.unwrap();
+
+    let prune = jobs.find_by_name(JOB_PRUNE).await.unwrap().unwrap();
+    let run = runner.trigger(prune.job_id, now).await.unwrap();
+    assert_eq!(run.status, "succeeded");
+    let result = run.result.unwrap();
+    assert_eq!(result["idempotency_keys"], 1);
+    assert_eq!(result["retention_days"], 30);
+    assert!(keys.find("test", "active").await.unwrap().is_some());
+
+    let vacuum = jobs.find_by_name(JOB_VACUUM).await.unwrap().unwrap();
+    let run = runner.trigger(vacuum.job_id, now).await.unwrap();
+    assert_eq!(run.status, "succeeded", "{:?}", run.error_message);
+    assert!(run.result.unwrap()["pages_after"].as_i64().unwrap() > 0);
+
+    // 快照只保留最近的 keep 份
+    let snapshot = jobs.find_by_name(JOB_SNAPSHOT).await.unwrap().unwrap();
+    let mut paths = Vec::new();
+    for day in 0..8 {
+        let run = runner.trigger(snapshot.job_id, now + Duration::days(day)).await.unwrap();
+        assert_eq!(run.status, "succeeded", "{:?}", run.error_message);
+        paths.push(run.result.unwrap()["path"].as_str().unwrap().to_string());
+    }
+    let remaining = std::fs::read_dir(snapshots.path()).unwrap().count();
+    assert_eq!(remaining, 7);
+    assert!(!std::path::Path::new(&paths[0]).exists());
+    assert!(std::path::Path::new(&paths[7]).exists());
+
+    // 内存数据库不能生成快照
+    let memory = setup_test_db().await;
+    let runner = MaintenanceRunner::with_builtin_jobs(memory.clone(), Some(snapshots.path().to_path_buf()));
+    runner.ensure_jobs(default_jobs(), now).await.unwrap();
+    let snapshot = MaintenanceJobRepository::new(memory.clone()).find_by_name(JOB_SNAPSHOT).await.unwrap().unwrap();
+    let run = runner.trigger(snapshot.job_id, now).await.unwrap();
+    assert_eq!(run.status, "failed");
+}
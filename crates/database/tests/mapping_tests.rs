@@ -0,0 +1,139 @@
+//! 实体与协议结构映射测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    mapping::{self, agent_create_data},
+    repository::{
+        AgentRepository, ProjectRepository, TaskRepository, UserRepository,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use codex_multi_agent::{
+    AgentCapability, AgentConfig, AgentStatus, AgentSummary, ConflictType, ProjectInfo, TaskInfo, TaskPriority,
+    TaskType,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+mod common;
+
+async fn create_user(db: &DatabaseConnection) -> Uuid {
+    UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap()
+        .user_id
+}
+
+#[tokio::test]
+async fn test_agent_config_round_trip_and_decode_errors() {
+    let db = setup_test_db().await;
+    let user_id = create_user(&db).await;
+
+    let config = AgentConfig {
+        name: "后端Agent".to_string(),
+        description: "负责接口实现".to_string(),
+        prompt_template: "你是后端工程师".to_string(),
+        capabilities: vec![AgentCapability::BackendDevelopment, AgentCapability::Testing],
+        max_concurrent_tasks: 3,
+        timeout_minutes: 90,
+        git_config: None,
+        custom_settings: HashMap::from([("model".to_string(), json!("gpt-4"))]),
+        priority_weight: 0.8,
+        verbose_logging: true,
+        resource_limits: None,
+        approval_policy: Default::default(),
+    };
+    let agents = AgentRepository::new(db.clone());
+    let agent = agents.create(agent_create_data(user_id, &config).unwrap()).await.unwrap();
+
+    let decoded = AgentConfig::try_from(&agent).unwrap();
+    assert_eq!(decoded.capabilities, config.capabilities);
+    assert_eq!(decoded.max_concurrent_tasks, 3);
+    assert_eq!(decoded.timeout_minutes, 90);
+    assert_eq!(decoded.custom_settings["model"], json!("gpt-4"));
+    assert!(decoded.verbose_logging);
+
+    let summary = AgentSummary::try_from(&agent).unwrap();
+    assert_eq!(summary.status, AgentStatus::Idle);
+    assert_eq!(summary.agent_id.0, agent.agent_id);
+
+    // 缺省的运行参数取默认值，无法解码的列报告实体和列名
+    let mut legacy = agent.clone();
+    legacy.config = json!({});
+    assert_eq!(AgentConfig::try_from(&legacy).unwrap().max_concurrent_tasks, mapping::DEFAULT_MAX_CONCURRENT_TASKS);
+    legacy.capabilities = json!(["time_travel"]);
+    let error = AgentConfig::try_from(&legacy).unwrap_err();
+    assert!(error.is_column_decode_error());
+    assert!(error.to_string().contains("agent.capabilities"));
+}
+
+#[tokio::test]
+async fn test_project_and_task_mapping() {
+    let db = setup_test_db().await;
+    let user_id = create_user(&db).await;
+    let projects = ProjectRepository::new(db.clone());
+    let project = projects
+        .create(CreateProjectData {
+            user_id,
+            name: "映射项目".to_string(),
+            description: Some("映射测试".to_string()),
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/mapping".to_string(),
+        })
+        .await
+        .unwrap();
+    let project = projects
+        .update_config(project.project_id, Some(json!(["rust", "react"])), None, None)
+        .await
+        .unwrap();
+
+    let info = ProjectInfo::try_from(&project).unwrap();
+    assert_eq!(info.technology_stack, vec!["rust", "react"]);
+    assert_eq!(info.owner, user_id.to_string());
+    let data = mapping::project_create_data(user_id, &info);
+    assert_eq!(data.workspace_path, "/workspace/mapping");
+    assert_eq!(mapping::project_config_columns(&info).unwrap().technology_stack, json!(["rust", "react"]));
+
+    let tasks = TaskRepository::new(db.clone());
+    let task = tasks
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "实现接口".to_string(),
+            description: "实现用户接口".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+    let task = tasks
+        .update_requirements(task.task_id, Some(json!(["backend_development"])), Some(json!(["接口返回200"])))
+        .await
+        .unwrap();
+
+    let info = TaskInfo::try_from(&task).unwrap();
+    assert_eq!(info.task_type, TaskType::Development);
+    assert_eq!(info.priority, TaskPriority::Medium);
+    assert_eq!(info.required_capabilities, vec![AgentCapability::BackendDevelopment]);
+    assert_eq!(info.acceptance_criteria, vec!["接口返回200"]);
+    let data = mapping::task_create_data(project.project_id, None, &info).unwrap();
+    assert_eq!(data.task_type, "development");
+
+    let mut corrupted = task.clone();
+    corrupted.priority = "someday".to_string();
+    assert!(TaskInfo::try_from(&corrupted).unwrap_err().is_column_decode_error());
+
+    assert!(mapping::conflict_type_to_entity(&ConflictType::GitMergeConflict).is_ok());
+    assert!(mapping::conflict_type_to_entity(&ConflictType::CodeStyleConflict).unwrap_err().is_validation_error());
+}
@@ -0,0 +1,133 @@
+//! Git合并冲突自动解决测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::conflict::{ConflictSeverity, ConflictType},
+    merge_resolver::{
+        parse_conflict_markers, ConflictedFile, MergeConflictResolver, MergeSide, ResolutionStrategy, Segment,
+    },
+    repository::{ConflictRepository, conflict_repository::CreateConflictData},
+};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建测试冲突的辅助函数
+async fn create_conflict(db: &codex_database::DatabaseConnection, conflict_type: ConflictType) -> Uuid {
+    ConflictRepository::new(db.clone())
+        .create(CreateConflictData {
+            conflict_type,
+            severity: ConflictSeverity::Medium,
+            title: "合并冲突".to_string(),
+            description: "feature 分支合并到 main 时产生冲突".to_string(),
+            related_entities: json!({}),
+            affected_tasks: json!([]),
+            affected_agents: json!([]),
+            project_id: None,
+        })
+        .await
+        .unwrap()
+        .conflict_id
+}
+
+fn conflicted(path: &str, ours: &str, theirs: &str) -> ConflictedFile {
+    ConflictedFile {
+        path: path.to_string(),
+        content: format!("header\n<<<<<<< HEAD\n{ours}=======\n{theirs}>>>>>>> feature\nfooter\n"),
+    }
+}
+
+#[tokio::test]
+async fn test_auto_resolve_with_path_policy() {
+    let db = setup_test_db().await;
+    let conflict_id = create_conflict(&db, ConflictType::GitMerge).await;
+
+    let resolver = MergeConflictResolver::new(db.clone()).with_policy("**/*.lock", MergeSide::Theirs);
+    let files = vec![
+        conflicted("Cargo.lock", "version = 1\n", "version = 2\n"),
+        conflicted("src/lib.rs", "same\n", "same\n"),
+    ];
+
+    let report = resolver.resolve(conflict_id, &files).await.unwrap();
+    assert!(report.auto_resolved);
+    assert!(!report.escalated);
+    assert_eq!(
+        report.files[0].resolved_content.as_deref(),
+        Some("header\nversion = 2\nfooter\n")
+    );
+    assert_eq!(
+        report.strategies(),
+        vec![ResolutionStrategy::PathPolicy(MergeSide::Theirs), ResolutionStrategy::Trivial]
+    );
+
+    let conflict = ConflictRepository::new(db.clone()).find_by_id(conflict_id).await.unwrap().unwrap();
+    assert_eq!(conflict.status, "resolved");
+    assert!(conflict.auto_resolved);
+    assert_eq!(conflict.resolution_strategy.as_deref(), Some("path_policy:theirs+trivial"));
+}
+
+#[tokio::test]
+async fn test_llm_proposal_requires_approval_then_reused() {
+    let db = setup_test_db().await;
+    let conflict_id = create_conflict(&db, ConflictType::GitMerge).await;
+    let resolver = MergeConflictResolver::new(db.clone());
+    let files = vec![conflicted("src/main.rs", "let a = 1;\n", "let a = 2;\n")];
+    let conflict_repo = ConflictRepository::new(db.clone());
+
+    // 没有可用策略时上报人工
+    let report = resolver.resolve(conflict_id, &files).await.unwrap();
+    assert!(report.escalated);
+    assert_eq!(report.unresolved_locations(), vec!["src/main.rs:2".to_string()]);
+    let conflict = conflict_repo.find_by_id(conflict_id).await.unwrap().unwrap();
+    assert_eq!(conflict.status, "escalated");
+
+    // 提交LLM方案后等待批准，不会直接生效
+    let segments = parse_conflict_markers(&files[0].content).unwrap();
+    let Segment::Conflict(hunk) = &segments[1] else {
+        panic!("应为冲突块");
+    };
+    let proposal = resolver
+        .submit_llm_proposal(conflict_id, "src/main.rs", hunk, "let a = 3;\n".to_string(), 0.9)
+        .await
+        .unwrap();
+    let report = resolver.resolve(conflict_id, &files).await.unwrap();
+    assert!(!report.auto_resolved);
+    assert_eq!(report.pending_approvals, 1);
+    let conflict = conflict_repo.find_by_id(conflict_id).await.unwrap().unwrap();
+    assert_eq!(conflict.status, "resolving");
+
+    // 批准后复用方案自动解决
+    resolver.approve_proposal(proposal.resolution_id).await.unwrap();
+    assert!(resolver.approve_proposal(proposal.resolution_id).await.is_err());
+    let report = resolver.resolve(conflict_id, &files).await.unwrap();
+    assert!(report.auto_resolved);
+    assert_eq!(report.strategies(), vec![ResolutionStrategy::Rerere]);
+    assert_eq!(
+        report.files[0].resolved_content.as_deref(),
+        Some("header\nlet a = 3;\nfooter\n")
+    );
+}
+
+#[tokio::test]
+async fn test_low_confidence_union_is_escalated() {
+    let db = setup_test_db().await;
+    let conflict_id = create_conflict(&db, ConflictType::GitMerge).await;
+    let resolver = MergeConflictResolver::new(db.clone()).with_policy("CHANGELOG.md", MergeSide::Union);
+
+    let report = resolver
+        .resolve(conflict_id, &[conflicted("CHANGELOG.md", "- a\n", "- b\n")])
+        .await
+        .unwrap();
+    assert!(report.escalated);
+    assert!(report.files[0].resolved_content.is_some());
+    assert!(report.confidence < 0.8);
+}
+
+#[tokio::test]
+async fn test_rejects_non_git_conflicts() {
+    let db = setup_test_db().await;
+    let conflict_id = create_conflict(&db, ConflictType::Resource).await;
+    let resolver = MergeConflictResolver::new(db.clone());
+    assert!(resolver.resolve(conflict_id, &[]).await.is_err());
+}
@@ -0,0 +1,212 @@
+//! 每日指标汇总测试
+
+use crate::common::setup_test_db;
+use chrono::{Duration, Utc};
+use codex_database::{
+    entities::{
+        execution_log::{EventType, LogLevel},
+        project_daily_metric,
+    },
+    maintenance::{default_jobs, MaintenanceRunner, JOB_METRICS_ROLLUP},
+    metrics_rollup::{backfill_missing, date_key, rollup_day},
+    repository::{
+        agent_repository::CreateAgentData, agent_work_history_repository::CreateAgentWorkHistoryData,
+        execution_log_repository::CreateExecutionLogData, execution_session_repository::CreateSessionData,
+        llm_conversation_repository::CreateConversationMessageData, llm_session_repository::CreateLlmSessionData,
+        project_repository::CreateProjectData, task_repository::CreateTaskData, user_repository::CreateUserData,
+        AgentRepository, AgentWorkHistoryRepository, DailyMetricsRepository, ExecutionLogRepository,
+        ExecutionSessionRepository, LlmConversationRepository, LlmSessionRepository, MaintenanceJobRepository,
+        ProjectRepository, TaskRepository, UserRepository,
+    },
+    DatabaseConnection,
+};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    project_id: Uuid,
+    agent_id: Uuid,
+    session_id: Uuid,
+}
+
+/// 一个完成、一个失败的任务，各有一条工作历史，另有 LLM 对话和一条错误日志
+async fn setup_activity(db: &DatabaseConnection) -> Fixture {
+    let user_id = UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("metrics_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("metrics_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id;
+    let project_id = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: "指标项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/metrics.git".to_string(),
+        workspace_path: "/workspace/metrics".to_string(),
+    }).await.unwrap().project_id;
+    let agent_id = AgentRepository::new(db.clone()).create(CreateAgentData {
+        user_id,
+        name: "开发Agent".to_string(),
+        description: None,
+        prompt_template: "测试".to_string(),
+        capabilities: json!(["backend_development"]),
+        config: json!({}),
+        git_config: None,
+    }).await.unwrap().agent_id;
+
+    let tasks = TaskRepository::new(db.clone());
+    let mut task_ids = Vec::new();
+    for (title, status, success, details) in [
+        ("登录接口", "completed", true, json!({ "token_count": 2000, "cost": 0.5 })),
+        ("注册接口", "failed", false, json!({ "token_count": 1000 })),
+    ] {
+        let task_id = tasks.create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: title.to_string(),
+            description: "测试".to_string(),
+            task_type: "development".to_string(),
+        }).await.unwrap().task_id;
+        tasks.update_status(task_id, status).await.unwrap();
+        AgentWorkHistoryRepository::new(db.clone()).create(CreateAgentWorkHistoryData {
+            agent_id,
+            task_id,
+            task_type: "development".to_string(),
+            success: Some(success),
+            completion_time_minutes: Some(30),
+            quality_score: None,
+            work_details: Some(details),
+            technologies_used: json!([]),
+            error_message: None,
+        }).await.unwrap();
+        task_ids.push(task_id);
+    }
+
+    let llm_session_id = LlmSessionRepository::new(db.clone()).create(CreateLlmSessionData {
+        project_id,
+        user_id,
+        session_type: "decomposition".to_string(),
+        system_prompt: None,
+        decomposition_prompt: None,
+    }).await.unwrap().session_id;
+    LlmConversationRepository::new(db.clone()).create(CreateConversationMessageData {
+        session_id: llm_session_id,
+        role: "assistant".to_string(),
+        content: "拆分结果".to_string(),
+        message_order: 1,
+        token_count: Some(300),
+        model_used: None,
+        processing_time_ms: None,
+    }).await.unwrap();
+
+    let session_id = ExecutionSessionRepository::new(db.clone()).create(CreateSessionData {
+        task_id: task_ids[1],
+        agent_id,
+        project_id,
+        git_branch: "feature/register".to_string(),
+        base_commit: None,
+        execution_config: None,
+        timeout_minutes: 30,
+    }).await.unwrap().session_id;
+    add_log(db, session_id, LogLevel::Error).await;
+    add_log(db, session_id, LogLevel::Info).await;
+
+    Fixture { project_id, agent_id, session_id }
+}
+
+async fn add_log(db: &DatabaseConnection, session_id: Uuid, level: LogLevel) {
+    ExecutionLogRepository::new(db.clone()).create(CreateExecutionLogData {
+        session_id,
+        log_level: level.to_string(),
+        event_type: EventType::TestRun,
+        message: "测试日志".to_string(),
+        details: None,
+        timestamp_ms: 0,
+    }).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_rollup_backfill_is_idempotent_and_read_first() {
+    let db = setup_test_db().await;
+    let fixture = setup_activity(&db).await;
+    let metrics = DailyMetricsRepository::new(db.clone());
+    let today = Utc::now().date_naive();
+
+    // 尚未汇总时实时计算
+    let live = metrics.project_daily(fixture.project_id, today, today, Some(0.1)).await.unwrap();
+    assert!(!live[0].from_rollup);
+    assert_eq!(live[0].counts.tasks_completed, 1);
+    assert_eq!(live[0].counts.tasks_failed, 1);
+    assert_eq!(live[0].counts.total_tokens, 300);
+    assert!((live[0].counts.total_cost - 0.6).abs() < 1e-9);
+    assert_eq!(live[0].counts.error_count, 1);
+
+    // 补算最近三天，再次补算时没有遗漏的日期
+    let tomorrow = Utc::now() + Duration::days(1);
+    let rollups = backfill_missing(&db, tomorrow, 3, Some(0.1)).await.unwrap();
+    let dates: Vec<_> = rollups.iter().map(|rollup| rollup.date).collect();
+    assert_eq!(dates, [today - Duration::days(2), today - Duration::days(1), today]);
+    assert_eq!((rollups[2].project_count, rollups[2].agent_count), (1, 1));
+    assert!(backfill_missing(&db, tomorrow, 3, Some(0.1)).await.unwrap().is_empty());
+
+    // 汇总后的新数据在重新汇总前不可见
+    add_log(&db, fixture.session_id, LogLevel::Error).await;
+    let stored = metrics.project_daily(fixture.project_id, today, today, Some(0.1)).await.unwrap();
+    assert!(stored[0].from_rollup);
+    assert_eq!(stored[0].counts, live[0].counts);
+
+    rollup_day(&db, today, Some(0.1)).await.unwrap();
+    let refreshed = metrics.project_daily(fixture.project_id, today, today, Some(0.1)).await.unwrap();
+    assert_eq!(refreshed[0].counts.error_count, 2);
+    let rows = project_daily_metric::Entity::find()
+        .filter(project_daily_metric::Column::MetricDate.eq(date_key(today)))
+        .count(&db)
+        .await
+        .unwrap();
+    assert_eq!(rows, 1, "重新汇总替换原有记录");
+
+    let agent = metrics
+        .agent_daily(fixture.agent_id, today - Duration::days(2), today + Duration::days(1), Some(0.1))
+        .await
+        .unwrap();
+    assert_eq!(agent.len(), 4);
+    assert!(agent[0].from_rollup);
+    assert_eq!(agent[0].counts.tasks_completed, 0);
+    assert_eq!(agent[2].counts.total_tokens, 3000);
+    assert_eq!(agent[2].counts.error_count, 2);
+    assert!(!agent[3].from_rollup, "未汇总的日期实时计算");
+
+    assert!(metrics
+        .project_daily(fixture.project_id, today, today - Duration::days(1), None)
+        .await
+        .unwrap_err()
+        .is_validation_error());
+}
+
+#[tokio::test]
+async fn test_rollup_runs_as_maintenance_job() {
+    let db = setup_test_db().await;
+    let fixture = setup_activity(&db).await;
+    let runner = MaintenanceRunner::with_builtin_jobs(db.clone(), None);
+    let now = Utc::now();
+    runner.ensure_jobs(default_jobs(), now).await.unwrap();
+
+    let job = MaintenanceJobRepository::new(db.clone()).find_by_name(JOB_METRICS_ROLLUP).await.unwrap().unwrap();
+    let run = runner.trigger(job.job_id, now + Duration::days(1)).await.unwrap();
+    assert_eq!(run.status, "succeeded", "{:?}", run.error_message);
+    let result = run.result.unwrap();
+    assert_eq!(result["dates"].as_array().unwrap().len(), 7);
+    assert_eq!(result["projects"], 1);
+
+    let today = now.date_naive();
+    let metrics = DailyMetricsRepository::new(db.clone());
+    let stored = metrics.project_daily(fixture.project_id, today, today, None).await.unwrap();
+    assert!(stored[0].from_rollup);
+    assert_eq!(stored[0].counts.tasks_completed, 1);
+    assert!((stored[0].counts.total_cost - 0.5).abs() < 1e-9, "未配置单价时只计入直接记录的费用");
+}
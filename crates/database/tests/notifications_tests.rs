@@ -0,0 +1,101 @@
+//! 通知偏好与通知路由集成测试
+
+use crate::common::setup_test_db;
+use chrono::{NaiveTime, TimeZone, Utc};
+use codex_database::{
+    notifications::{
+        CategoryPreference, DeliveryMode, NewNotification, NotificationCategory, NotificationChannel,
+        NotificationPreferences, NotificationRouter, QuietHours,
+    },
+    repository::{UserRepository, user_repository::CreateUserData},
+};
+use uuid::Uuid;
+
+mod common;
+
+/// 创建测试用户的辅助函数
+async fn create_test_user(db: &codex_database::DatabaseConnection) -> Uuid {
+    let repo = UserRepository::new(db.clone());
+    repo.create(CreateUserData {
+        username: format!("notify_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("notify_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: Some(serde_json::json!({ "theme": "dark" })),
+    }).await.unwrap().user_id
+}
+
+fn notification(user_id: Uuid, category: NotificationCategory, urgent: bool) -> NewNotification {
+    NewNotification {
+        user_id,
+        category,
+        title: "任务状态变化".to_string(),
+        body: "任务已完成".to_string(),
+        project_id: None,
+        urgent,
+    }
+}
+
+#[test]
+fn test_quiet_hours_in_local_timezone() {
+    // 东八区 22:00 - 08:00
+    let quiet_hours = QuietHours {
+        start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+        end: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+        utc_offset_minutes: 480,
+        allow_urgent: true,
+    };
+    // UTC 15:00 是本地 23:00
+    let night = Utc.with_ymd_and_hms(2024, 3, 1, 15, 0, 0).unwrap();
+    assert!(quiet_hours.contains(night));
+    assert_eq!(quiet_hours.next_end(night), Utc.with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap());
+    // UTC 04:00 是本地 12:00
+    assert!(!quiet_hours.contains(Utc.with_ymd_and_hms(2024, 3, 1, 4, 0, 0).unwrap()));
+}
+
+#[tokio::test]
+async fn test_router_respects_preferences() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let router = NotificationRouter::new(db.clone());
+
+    let mut preferences = NotificationPreferences::default();
+    preferences.categories.insert(NotificationCategory::CiResults, CategoryPreference {
+        channels: vec![NotificationChannel::InApp, NotificationChannel::Email],
+        delivery: DeliveryMode::Digest,
+    });
+    preferences.quiet_hours = Some(QuietHours {
+        start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+        end: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+        utc_offset_minutes: 0,
+        allow_urgent: true,
+    });
+    router.update_preferences(user_id, preferences.clone()).await.expect("保存偏好应该成功");
+    assert_eq!(router.preferences(user_id).await.unwrap(), preferences);
+    let user = UserRepository::new(db.clone()).find_by_id(user_id).await.unwrap().unwrap();
+    assert_eq!(user.settings.unwrap()["theme"], "dark");
+
+    let mut invalid = preferences.clone();
+    invalid.digest_interval_minutes = 1;
+    assert!(router.update_preferences(user_id, invalid).await.unwrap_err().is_validation_error());
+
+    // 免打扰时段内：应用内通知立即投递，桌面通知推迟到 08:00，紧急通知穿透
+    let night = Utc.with_ymd_and_hms(2024, 3, 1, 23, 10, 0).unwrap();
+    let created = router.route_at(notification(user_id, NotificationCategory::TaskUpdates, false), night).await.unwrap();
+    assert_eq!(created.len(), 2);
+    let desktop = created.iter().find(|n| n.channel == "desktop").unwrap();
+    assert_eq!(desktop.deliver_at, Utc.with_ymd_and_hms(2024, 3, 2, 8, 0, 0).unwrap());
+    let urgent = router.route_at(notification(user_id, NotificationCategory::Security, true), night).await.unwrap();
+    assert!(urgent.iter().all(|n| n.deliver_at == night));
+
+    // 摘要通知按间隔合并
+    let noon = Utc.with_ymd_and_hms(2024, 3, 2, 12, 5, 0).unwrap();
+    for _ in 0..2 {
+        router.route_at(notification(user_id, NotificationCategory::CiResults, false), noon).await.unwrap();
+    }
+    let batches = router.take_due(Utc.with_ymd_and_hms(2024, 3, 2, 13, 0, 0).unwrap()).await.unwrap();
+    let digest = batches.iter().find(|batch| batch.digest).expect("应该有摘要批次");
+    assert_eq!(digest.channel, "email");
+    assert_eq!(digest.notifications.len(), 2);
+    assert!(router.take_due(Utc.with_ymd_and_hms(2024, 3, 2, 13, 0, 0).unwrap()).await.unwrap().is_empty());
+}
@@ -0,0 +1,166 @@
+//! 长时间运行操作测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    embeddings::{EmbeddingIndex, HashingEmbeddingProvider, IndexReport},
+    operations::{OperationRegistry, OperationStatus},
+    repository::{
+        ProjectRepository, TaskRepository, UserRepository,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseError,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+mod common;
+
+async fn create_test_project(db: &codex_database::DatabaseConnection) -> Uuid {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+
+    ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "操作项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/operations".to_string(),
+        })
+        .await
+        .unwrap()
+        .project_id
+}
+
+/// 等待操作结束
+async fn wait_finished(registry: &OperationRegistry, operation_id: Uuid) -> codex_database::operations::OperationProgress {
+    for _ in 0..200 {
+        let progress = registry.get(operation_id).unwrap();
+        if progress.status.is_finished() {
+            return progress;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("操作 {} 未在预期时间内结束", operation_id);
+}
+
+#[tokio::test]
+async fn test_progress_events_and_cooperative_cancel() {
+    let registry = Arc::new(OperationRegistry::new());
+    let mut events = registry.subscribe();
+
+    let operation = registry.start("codebase_scan", None);
+    operation.set_phase("扫描文件", 10.0);
+    operation.report(150.0, Some("已扫描 42 个文件".to_string()));
+
+    let started = events.recv().await.unwrap();
+    assert_eq!(started.operation_id, operation.id());
+    assert_eq!(started.status, OperationStatus::Running);
+    assert_eq!(events.recv().await.unwrap().phase, "扫描文件");
+    let reported = events.recv().await.unwrap();
+    assert_eq!(reported.percentage, 100.0);
+    assert_eq!(reported.message.as_deref(), Some("已扫描 42 个文件"));
+
+    // 取消只设置标记，执行方在检查点结束操作
+    assert!(registry.cancel(operation.id()).unwrap());
+    assert!(events.recv().await.unwrap().cancel_requested);
+    let cancelled = operation.check_cancelled().unwrap_err();
+    assert!(cancelled.is_operation_cancelled());
+    operation.finish::<()>(Err(cancelled));
+
+    let progress = registry.get(operation.id()).unwrap();
+    assert_eq!(progress.status, OperationStatus::Cancelled);
+    assert!(progress.error.is_none());
+
+    // 已结束的操作不再接受进度和取消
+    operation.report(10.0, None);
+    assert_eq!(registry.get(operation.id()).unwrap().percentage, 100.0);
+    assert!(!registry.cancel(operation.id()).unwrap());
+    assert!(matches!(
+        registry.cancel(Uuid::new_v4()),
+        Err(DatabaseError::EntityNotFound { .. })
+    ));
+
+    let first = registry.start("search_index", None);
+    let second = registry.start("codebase_scan", None);
+    assert_eq!(registry.cancel_all(), 2);
+    assert!(first.is_cancelled() && second.is_cancelled());
+}
+
+#[tokio::test]
+async fn test_spawned_operation_records_result_and_cancellation() {
+    let registry = Arc::new(OperationRegistry::new());
+
+    let completed = registry.spawn("count", None, |operation| async move {
+        operation.set_phase("计数", 50.0);
+        Ok(42)
+    });
+    let progress = wait_finished(&registry, completed).await;
+    assert_eq!(progress.status, OperationStatus::Completed);
+    assert_eq!(progress.percentage, 100.0);
+    assert_eq!(progress.result, Some(serde_json::json!(42)));
+
+    let failed = registry.spawn("fail", None, |_| async move {
+        Err::<(), _>(DatabaseError::validation("参数错误"))
+    });
+    let progress = wait_finished(&registry, failed).await;
+    assert_eq!(progress.status, OperationStatus::Failed);
+    assert!(progress.error.unwrap().contains("参数错误"));
+
+    let waiting = registry.spawn("wait", None, |operation| async move {
+        operation.token().cancelled().await;
+        operation.check_cancelled()
+    });
+    assert!(registry.cancel(waiting).unwrap());
+    assert_eq!(wait_finished(&registry, waiting).await.status, OperationStatus::Cancelled);
+
+    assert_eq!(registry.list().len(), 3);
+}
+
+#[tokio::test]
+async fn test_embedding_indexing_reports_progress_and_stops_on_cancel() {
+    let db = setup_test_db().await;
+    let project_id = create_test_project(&db).await;
+    for title in ["实现登录", "实现注册"] {
+        TaskRepository::new(db.clone())
+            .create(CreateTaskData {
+                project_id,
+                parent_task_id: None,
+                llm_session_id: None,
+                title: title.to_string(),
+                description: format!("{}接口", title),
+                task_type: "development".to_string(),
+            })
+            .await
+            .unwrap();
+    }
+    let index = Arc::new(EmbeddingIndex::new(db.clone(), Arc::new(HashingEmbeddingProvider::default())));
+    let registry = Arc::new(OperationRegistry::new());
+
+    // 开始前已取消：不写入任何向量
+    let operation = registry.start("search_index", Some(project_id));
+    registry.cancel(operation.id()).unwrap();
+    let result = index.index_project_with_progress(project_id, &operation).await;
+    assert!(result.unwrap_err().is_operation_cancelled());
+    assert_eq!(index.index_project(project_id).await.unwrap().embedded, 2);
+
+    let indexer = index.clone();
+    let operation_id = registry.spawn("search_index", Some(project_id), move |operation| async move {
+        indexer.index_project_with_progress(project_id, &operation).await
+    });
+    let progress = wait_finished(&registry, operation_id).await;
+    assert_eq!(progress.status, OperationStatus::Completed);
+    let report: IndexReport = serde_json::from_value(progress.result.unwrap()).unwrap();
+    assert_eq!(report.unchanged, 2);
+}
@@ -0,0 +1,131 @@
+//! 编排默认配置（sker.toml）测试
+
+use codex_database::orchestration_config::{
+    ConfigLayer, OrchestrationConfigLoader, OrchestrationConfigWatcher, CONFIG_FILE_NAME,
+};
+use codex_multi_agent::{AssignmentStrategy, MultiAgentEvent, OrganizationSettings, CodingStandards};
+use serde_json::json;
+use uuid::Uuid;
+
+#[test]
+fn test_layers_merge_in_order_and_record_sources() {
+    let workspace = tempfile::tempdir().unwrap();
+    std::fs::write(
+        workspace.path().join(CONFIG_FILE_NAME),
+        r#"
+[quality_gates]
+max_complexity = 15
+
+[scheduler]
+max_parallel_sessions = 8
+
+[assignment]
+strategy = "cost_optimized"
+max_cost_per_task = 5.0
+
+[provider]
+name = "anthropic"
+model = "claude-sonnet"
+"#,
+    )
+    .unwrap();
+
+    let mut organization_gates = CodingStandards::default().quality_gates;
+    organization_gates.min_test_coverage = 0.9;
+    organization_gates.max_complexity = 12;
+    let organization = OrganizationSettings {
+        default_coding_standards: None,
+        default_quality_gates: Some(organization_gates),
+    };
+
+    let resolved = OrchestrationConfigLoader::new(workspace.path())
+        .with_organization_settings(&organization)
+        .unwrap()
+        .with_ui_overrides(&json!({ "scheduler": { "max_parallel_sessions": 2 } }))
+        .unwrap()
+        .load()
+        .unwrap();
+
+    assert!(resolved.file_present);
+    assert_eq!(resolved.config.quality_gates.min_test_coverage, 0.9);
+    assert_eq!(resolved.config.quality_gates.max_complexity, 15);
+    assert_eq!(resolved.config.scheduler.max_parallel_sessions, 2);
+    assert_eq!(resolved.config.scheduler.session_timeout_minutes, 60);
+    assert_eq!(resolved.config.provider.name, "anthropic");
+    assert_eq!(resolved.config.assignment.strategy, AssignmentStrategy::CostOptimized);
+    assert_eq!(resolved.config.assignment.max_cost_per_task, Some(5.0));
+    assert_eq!(resolved.config.assignment.max_total_cost, None);
+
+    assert_eq!(resolved.source_of("quality_gates.min_test_coverage"), Some(ConfigLayer::Organization));
+    assert_eq!(resolved.source_of("quality_gates.max_complexity"), Some(ConfigLayer::ProjectFile));
+    assert_eq!(resolved.source_of("scheduler.max_parallel_sessions"), Some(ConfigLayer::UiOverrides));
+    assert_eq!(resolved.source_of("scheduler.session_timeout_minutes"), Some(ConfigLayer::Defaults));
+    assert_eq!(resolved.source_of("assignment.strategy"), Some(ConfigLayer::ProjectFile));
+}
+
+#[test]
+fn test_missing_file_uses_defaults_and_invalid_files_are_rejected() {
+    let workspace = tempfile::tempdir().unwrap();
+    let loader = OrchestrationConfigLoader::new(workspace.path());
+    let resolved = loader.load().unwrap();
+    assert!(!resolved.file_present);
+    assert_eq!(resolved.config.provider.name, "openai");
+
+    let path = workspace.path().join(CONFIG_FILE_NAME);
+    let cases = [
+        ("[scheduler]\nmax_paralel_sessions = 3\n", "scheduler.max_paralel_sessions"),
+        ("[scheduler]\nmax_parallel_sessions = \"many\"\n", "编排配置无效"),
+        ("[scheduler]\nmax_parallel_sessions = 0\n", "max_parallel_sessions"),
+        ("[provider]\nname = \"custom\"\n", "base_url"),
+        ("[assignment]\nstrategy = \"cheapest\"\n", "编排配置无效"),
+        ("[assignment]\nmax_total_cost = -1.0\n", "assignment.max_total_cost"),
+        ("[provider\n", "格式错误"),
+    ];
+    for (content, expected) in cases {
+        std::fs::write(&path, content).unwrap();
+        let error = loader.load().unwrap_err();
+        assert!(error.to_string().contains(expected), "{}: {}", content, error);
+    }
+
+    let error = loader.clone().with_ui_overrides(&json!(["scheduler"])).unwrap_err();
+    assert!(error.is_validation_error());
+}
+
+#[test]
+fn test_watcher_reports_changed_keys_and_keeps_last_valid_config() {
+    let workspace = tempfile::tempdir().unwrap();
+    let path = workspace.path().join(CONFIG_FILE_NAME);
+    let project_id = Uuid::new_v4();
+    let mut watcher =
+        OrchestrationConfigWatcher::new(Some(project_id), OrchestrationConfigLoader::new(workspace.path())).unwrap();
+    assert!(watcher.poll().unwrap().is_none());
+
+    std::fs::write(&path, "[scheduler]\nmax_parallel_sessions = 6\n").unwrap();
+    let event = watcher.poll().unwrap().expect("配置变化应产生事件");
+    assert_eq!(event.changed_keys, vec!["scheduler.max_parallel_sessions"]);
+    assert_eq!(event.related_entity_ids(), vec![project_id.to_string()]);
+    assert_eq!(event.event_type(), "orchestration_config_changed");
+
+    // 只改注释不影响生效值
+    std::fs::write(&path, "# 调度上限\n[scheduler]\nmax_parallel_sessions = 6\n").unwrap();
+    assert!(watcher.poll().unwrap().is_none());
+
+    // 无效内容只报告一次，保留上一次的有效配置
+    std::fs::write(&path, "[scheduler]\nmax_parallel_sessions = 0\n").unwrap();
+    assert!(watcher.poll().is_err());
+    assert!(watcher.poll().unwrap().is_none());
+    assert_eq!(watcher.current().config.scheduler.max_parallel_sessions, 6);
+    std::fs::write(&path, "[scheduler]\nmax_parallel_sessions = 6\n").unwrap();
+    assert!(watcher.poll().unwrap().is_none());
+
+    let event = watcher
+        .set_ui_overrides(&json!({ "preemption": { "enabled": false } }))
+        .unwrap()
+        .expect("界面覆盖变化应产生事件");
+    assert_eq!(event.changed_keys, vec!["preemption.enabled"]);
+    assert!(!watcher.current().config.preemption.enabled);
+
+    std::fs::remove_file(&path).unwrap();
+    let event = watcher.poll().unwrap().expect("删除配置文件应恢复默认值");
+    assert_eq!(event.changed_keys, vec!["scheduler.max_parallel_sessions"]);
+}
@@ -0,0 +1,156 @@
+//! 组织与团队集成测试
+
+use crate::common::setup_test_db;
+use codex_database::repository::{
+    AgentRepository, OrganizationRepository, ProjectRepository, UserRepository,
+    agent_repository::CreateAgentData,
+    organization_repository::CreateOrganizationData,
+    project_repository::CreateProjectData,
+    user_repository::CreateUserData,
+};
+use codex_multi_agent::{
+    OrganizationRole, OrganizationSettings, QualityGates,
+    project_management::SeverityLevel,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建测试用户的辅助函数
+async fn create_test_user(db: &codex_database::DatabaseConnection) -> Uuid {
+    let repo = UserRepository::new(db.clone());
+    let user_data = CreateUserData {
+        username: format!("org_user_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("org_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    };
+    repo.create(user_data).await.unwrap().user_id
+}
+
+/// 创建测试组织的辅助函数
+async fn create_test_organization(
+    repo: &OrganizationRepository,
+    owner_id: Uuid,
+    settings: Option<OrganizationSettings>,
+) -> Uuid {
+    repo.create(CreateOrganizationData {
+        name: format!("组织_{}", &Uuid::new_v4().to_string()[..8]),
+        description: None,
+        owner_id,
+        settings,
+    }).await.expect("创建组织应该成功").organization_id
+}
+
+#[tokio::test]
+async fn test_create_organization_with_members_and_teams() {
+    let db = setup_test_db().await;
+    let owner_id = create_test_user(&db).await;
+    let member_id = create_test_user(&db).await;
+    let repo = OrganizationRepository::new(db.clone());
+
+    let organization_id = create_test_organization(&repo, owner_id, None).await;
+    assert_eq!(repo.get_role(organization_id, owner_id).await.unwrap(), Some(OrganizationRole::Owner));
+
+    let team = repo.create_team(organization_id, "后端组".to_string(), None).await.unwrap();
+    assert!(repo.create_team(organization_id, "后端组".to_string(), None).await.is_err());
+
+    repo.add_member(organization_id, member_id, OrganizationRole::Member, Some(team.team_id))
+        .await
+        .expect("添加组织成员应该成功");
+    assert_eq!(repo.list_members(organization_id).await.unwrap().len(), 2);
+    assert_eq!(repo.list_team_members(team.team_id).await.unwrap().len(), 1);
+
+    let organizations = repo.find_by_user(member_id).await.unwrap();
+    assert_eq!(organizations.len(), 1);
+    assert_eq!(organizations[0].organization_id, organization_id);
+
+    // 所有者不可移除，普通成员可以
+    assert!(repo.remove_member(organization_id, owner_id).await.is_err());
+    repo.remove_member(organization_id, member_id).await.unwrap();
+    assert!(repo.find_by_user(member_id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_organization_project_and_agent_pool() {
+    let db = setup_test_db().await;
+    let owner_id = create_test_user(&db).await;
+    let repo = OrganizationRepository::new(db.clone());
+    let organization_id = create_test_organization(&repo, owner_id, None).await;
+
+    let project = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id: owner_id,
+        name: "组织项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/org.git".to_string(),
+        workspace_path: "/workspace/org".to_string(),
+    }).await.unwrap();
+    let agent = AgentRepository::new(db.clone()).create(CreateAgentData {
+        user_id: owner_id,
+        name: "共享Agent".to_string(),
+        description: None,
+        prompt_template: "你是一个开发助手".to_string(),
+        capabilities: json!(["BackendDevelopment"]),
+        config: json!({}),
+        git_config: None,
+    }).await.unwrap();
+
+    let project = repo.assign_project(project.project_id, Some(organization_id)).await.unwrap();
+    assert_eq!(project.organization_id, Some(organization_id));
+    repo.assign_agent(agent.agent_id, Some(organization_id)).await.unwrap();
+
+    assert_eq!(repo.list_projects(organization_id).await.unwrap().len(), 1);
+    let pool = repo.list_agent_pool(organization_id).await.unwrap();
+    assert_eq!(pool.len(), 1);
+    assert_eq!(pool[0].agent_id, agent.agent_id);
+
+    repo.assign_agent(agent.agent_id, None).await.unwrap();
+    assert!(repo.list_agent_pool(organization_id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_project_inherits_organization_settings() {
+    let db = setup_test_db().await;
+    let owner_id = create_test_user(&db).await;
+    let repo = OrganizationRepository::new(db.clone());
+
+    let settings = OrganizationSettings {
+        default_coding_standards: None,
+        default_quality_gates: Some(QualityGates {
+            min_test_coverage: 0.95,
+            max_complexity: 6,
+            max_tech_debt_hours: 2,
+            max_duplication_rate: 0.01,
+            required_reviewers: 3,
+            require_all_checks_pass: true,
+            quarantine_flaky_tests: false,
+            security_blocking_severity: SeverityLevel::Blocker,
+            allowed_severity_levels: vec![SeverityLevel::Info],
+        }),
+    };
+    let organization_id = create_test_organization(&repo, owner_id, Some(settings)).await;
+
+    let project = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id: owner_id,
+        name: "继承设置项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/inherit.git".to_string(),
+        workspace_path: "/workspace/inherit".to_string(),
+    }).await.unwrap();
+
+    // 未归属组织时使用内置默认值
+    let standards = repo.resolve_project_standards(project.project_id).await.unwrap();
+    assert_eq!(standards.quality_gates.required_reviewers, 1);
+
+    repo.assign_project(project.project_id, Some(organization_id)).await.unwrap();
+    let standards = repo.resolve_project_standards(project.project_id).await.unwrap();
+    assert_eq!(standards.quality_gates.required_reviewers, 3);
+    assert_eq!(standards.quality_gates.max_complexity, 6);
+
+    // 组织设置更新后项目随之生效
+    repo.update_settings(organization_id, OrganizationSettings::default()).await.unwrap();
+    let standards = repo.resolve_project_standards(project.project_id).await.unwrap();
+    assert_eq!(standards.quality_gates.required_reviewers, 1);
+}
@@ -0,0 +1,176 @@
+//! 并行执行规划测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    parallel_planner::{ParallelExecutionConfig, ParallelPlanner, WaveTaskState},
+    repository::{
+        agent_repository::CreateAgentData, project_repository::CreateProjectData,
+        task_dependency_repository::CreateTaskDependencyData, task_repository::CreateTaskData,
+        user_repository::CreateUserData, AgentRepository, ExecutionSessionRepository, ProjectRepository,
+        TaskDependencyRepository, TaskRepository, UserRepository,
+    },
+    DatabaseConnection,
+};
+use chrono::{Duration, Utc};
+use codex_multi_agent::{Clock, TestClock};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建测试用户和项目，返回用户ID和项目ID
+async fn create_project(db: &DatabaseConnection) -> (Uuid, Uuid) {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "并行项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/parallel.git".to_string(),
+            workspace_path: "/tmp/parallel".to_string(),
+        })
+        .await
+        .unwrap();
+    (user.user_id, project.project_id)
+}
+
+async fn create_agent(db: &DatabaseConnection, user_id: Uuid, name: &str, limit: usize) -> Uuid {
+    AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id,
+            name: name.to_string(),
+            description: None,
+            prompt_template: "你是一个测试Agent".to_string(),
+            capabilities: json!(["Development"]),
+            config: json!({ "max_concurrent_tasks": limit }),
+            git_config: None,
+        })
+        .await
+        .unwrap()
+        .agent_id
+}
+
+async fn create_task(db: &DatabaseConnection, project_id: Uuid, title: &str, agent_id: Option<Uuid>, scope: Option<&str>) -> Uuid {
+    let tasks = TaskRepository::new(db.clone());
+    let task = tasks
+        .create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: title.to_string(),
+            description: title.to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+    tasks.set_scope_path(task.task_id, scope.map(str::to_string)).await.unwrap();
+    if let Some(agent_id) = agent_id {
+        tasks.assign_to_agent(task.task_id, agent_id, title.to_string()).await.unwrap();
+    }
+    task.task_id
+}
+
+#[tokio::test]
+async fn test_waves_and_parallel_launch() {
+    let db = setup_test_db().await;
+    let (user_id, project_id) = create_project(&db).await;
+    let mut agents = Vec::new();
+    for (name, limit) in [("接口Agent", 2), ("前端Agent", 1)] {
+        agents.push(create_agent(&db, user_id, name, limit).await);
+    }
+
+    // 第一波：三个互不依赖的任务，其中两个范围重叠；第二波依赖接口任务
+    let api = create_task(&db, project_id, "接口", Some(agents[0]), Some("services/api")).await;
+    let api_docs = create_task(&db, project_id, "接口文档", Some(agents[0]), Some("services/api/docs")).await;
+    let web = create_task(&db, project_id, "页面", Some(agents[1]), Some("services/web")).await;
+    let unassigned = create_task(&db, project_id, "待分配", None, Some("tools")).await;
+    let integration = create_task(&db, project_id, "联调", Some(agents[1]), Some("services/web")).await;
+    let dependencies = TaskDependencyRepository::new(db.clone());
+    dependencies
+        .create(CreateTaskDependencyData {
+            parent_task_id: api,
+            child_task_id: integration,
+            dependency_type: "blocks".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let planner = ParallelPlanner::new(db.clone(), ParallelExecutionConfig::default());
+    let view = planner.wave_view(project_id).await.unwrap();
+    assert_eq!(view.waves.len(), 2);
+    assert_eq!(view.waves[0].tasks.len(), 4);
+    assert_eq!(view.waves[1].tasks[0].task_id, integration);
+    assert_eq!(view.waves[1].tasks[0].state, WaveTaskState::Waiting);
+    assert_eq!(view.waves[1].tasks[0].waiting_on, vec![api]);
+
+    // 范围重叠和未分配的任务推迟启动
+    let report = planner.launch_ready(project_id).await.unwrap();
+    let mut launched: Vec<Uuid> = report.launched.iter().map(|session| session.task_id).collect();
+    launched.sort();
+    let mut expected = vec![api, web];
+    expected.sort();
+    assert_eq!(launched, expected);
+    let deferred: Vec<Uuid> = report.deferred.iter().map(|task| task.task_id).collect();
+    assert!(deferred.contains(&api_docs) && deferred.contains(&unassigned));
+
+    let view = planner.wave_view(project_id).await.unwrap();
+    assert_eq!(view.running_sessions, 2);
+    let running = view.waves[0].tasks.iter().filter(|task| task.state == WaveTaskState::Running).count();
+    assert_eq!(running, 2);
+
+    // 再次启动不会重复领取任务
+    assert!(planner.launch_ready(project_id).await.unwrap().launched.is_empty());
+
+    // 接口任务完成后，联调任务受前端Agent并发上限限制，接口文档任务可以启动
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let api_session = report.launched.iter().find(|session| session.task_id == api).unwrap().session_id;
+    sessions.start_session(api_session).await.unwrap();
+    sessions.complete_session(api_session, true, None, None, None).await.unwrap();
+    TaskRepository::new(db.clone()).update_status(api, "completed").await.unwrap();
+
+    let report = planner.launch_ready(project_id).await.unwrap();
+    assert_eq!(report.launched.len(), 1);
+    assert_eq!(report.launched[0].task_id, api_docs);
+    let blocked = report.deferred.iter().find(|task| task.task_id == integration).unwrap();
+    assert!(blocked.reason.contains("并发上限"));
+
+    // 项目并行会话上限
+    let limited = ParallelPlanner::new(
+        db.clone(),
+        ParallelExecutionConfig { max_parallel_sessions: 2, ..Default::default() },
+    );
+    let report = limited.launch_ready(project_id).await.unwrap();
+    assert!(report.launched.is_empty());
+    assert!(report.deferred.iter().all(|task| task.reason.contains("并行会话上限") || task.task_id == unassigned));
+}
+
+#[tokio::test]
+async fn test_planner_uses_injected_clock() {
+    let db = setup_test_db().await;
+    let (user_id, project_id) = create_project(&db).await;
+    let agent_id = create_agent(&db, user_id, "接口Agent", 1).await;
+    let task_id = create_task(&db, project_id, "接口", Some(agent_id), Some("services/api")).await;
+
+    let clock = TestClock::shared(Utc::now() - Duration::days(3));
+    let planner = ParallelPlanner::new(db.clone(), ParallelExecutionConfig::default()).with_clock(clock.clone());
+    assert_eq!(planner.wave_view(project_id).await.unwrap().generated_at, clock.now());
+
+    // 领取任务时的开始时间和更新时间来自注入的时钟
+    clock.advance(Duration::minutes(5));
+    let report = planner.launch_ready(project_id).await.unwrap();
+    assert_eq!(report.launched.len(), 1);
+    let task = TaskRepository::new(db.clone()).find_by_id(task_id).await.unwrap().unwrap();
+    assert_eq!(task.status, "in_progress");
+    assert_eq!(task.started_at.unwrap().with_timezone(&Utc), clock.now());
+    assert_eq!(task.updated_at.with_timezone(&Utc), clock.now());
+    assert_eq!(planner.wave_view(project_id).await.unwrap().generated_at, clock.now());
+}
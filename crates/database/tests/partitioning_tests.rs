@@ -0,0 +1,150 @@
+//! 按项目拆分数据库文件测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::user,
+    establish_connection,
+    partitioning::ProjectDatabases,
+    repository::{
+        agent_repository::CreateAgentData, execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData, task_repository::CreateTaskData, user_repository::CreateUserData,
+        AgentRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository, UserRepository,
+    },
+    DatabaseConnection,
+};
+use sea_orm::EntityTrait;
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    user_id: Uuid,
+    agent_id: Uuid,
+}
+
+async fn setup_owner(db: &DatabaseConnection) -> Fixture {
+    let user_id = UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("partition_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("partition_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id;
+    let agent_id = AgentRepository::new(db.clone()).create(CreateAgentData {
+        user_id,
+        name: "开发Agent".to_string(),
+        description: None,
+        prompt_template: "测试".to_string(),
+        capabilities: json!(["Development"]),
+        config: json!({}),
+        git_config: None,
+    }).await.unwrap().agent_id;
+    Fixture { user_id, agent_id }
+}
+
+async fn create_project(db: &DatabaseConnection, user_id: Uuid, name: &str) -> Uuid {
+    ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: name.to_string(),
+        description: None,
+        repository_url: "https://github.com/test/partition.git".to_string(),
+        workspace_path: "/workspace/partition".to_string(),
+    }).await.unwrap().project_id
+}
+
+fn task_data(project_id: Uuid, title: &str) -> CreateTaskData {
+    CreateTaskData {
+        project_id,
+        parent_task_id: None,
+        llm_session_id: None,
+        title: title.to_string(),
+        description: "测试".to_string(),
+        task_type: "development".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_partitioned_project_routes_to_own_file() {
+    let catalog = setup_test_db().await;
+    let dir = tempfile::tempdir().unwrap();
+    let databases = ProjectDatabases::partitioned(catalog.clone(), dir.path());
+    let fixture = setup_owner(&catalog).await;
+    let partitioned = create_project(&catalog, fixture.user_id, "拆分项目").await;
+    let shared = create_project(&catalog, fixture.user_id, "共享项目").await;
+
+    databases.create_partition(partitioned).await.unwrap();
+    assert!(databases.partition_path(partitioned).unwrap().exists());
+    assert!(databases.is_partitioned(partitioned).await.unwrap());
+
+    // 已拆分项目的数据写入项目文件，Agent 等引用行已复制，外键约束成立
+    let tasks = databases.scoped(partitioned, TaskRepository::new).await.unwrap();
+    let task_id = tasks.create(task_data(partitioned, "拆分任务")).await.unwrap().task_id;
+    let sessions = databases.scoped(partitioned, ExecutionSessionRepository::new).await.unwrap();
+    sessions.create(CreateSessionData {
+        task_id,
+        agent_id: fixture.agent_id,
+        project_id: partitioned,
+        git_branch: "feature/partition".to_string(),
+        base_commit: None,
+        execution_config: None,
+        timeout_minutes: 30,
+    }).await.unwrap();
+    assert!(TaskRepository::new(catalog.clone()).find_by_project(partitioned).await.unwrap().is_empty());
+
+    // 未拆分的项目仍使用目录数据库
+    databases.scoped(shared, TaskRepository::new).await.unwrap().create(task_data(shared, "共享任务")).await.unwrap();
+    assert_eq!(TaskRepository::new(catalog.clone()).find_by_project(shared).await.unwrap().len(), 1);
+
+    // 关闭后重新打开，数据仍在
+    assert!(databases.detach(partitioned).await.unwrap());
+    assert_eq!(databases.open_count().await, 0);
+    let reopened = databases.scoped(partitioned, TaskRepository::new).await.unwrap();
+    assert_eq!(reopened.find_by_project(partitioned).await.unwrap()[0].task_id, task_id);
+
+    assert!(databases.create_partition(partitioned).await.unwrap_err().is_validation_error());
+    assert!(databases.create_partition(shared).await.unwrap_err().is_validation_error(), "已有任务的项目不能拆分");
+    assert!(ProjectDatabases::single(catalog.clone())
+        .create_partition(Uuid::new_v4())
+        .await
+        .unwrap_err()
+        .is_validation_error());
+    databases.detach_all().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_open_limit_export_and_remove() {
+    let catalog = setup_test_db().await;
+    let dir = tempfile::tempdir().unwrap();
+    let databases = ProjectDatabases::partitioned(catalog.clone(), dir.path().join("projects")).with_max_open(1).unwrap();
+    let fixture = setup_owner(&catalog).await;
+    let first = create_project(&catalog, fixture.user_id, "项目一").await;
+    let second = create_project(&catalog, fixture.user_id, "项目二").await;
+    for project_id in [first, second] {
+        databases.create_partition(project_id).await.unwrap();
+        let tasks = databases.scoped(project_id, TaskRepository::new).await.unwrap();
+        tasks.create(task_data(project_id, "任务")).await.unwrap();
+    }
+    assert_eq!(databases.open_count().await, 1, "超过上限时移除最久未用的文件");
+    let tasks = databases.scoped(first, TaskRepository::new).await.unwrap();
+    assert_eq!(tasks.find_by_project(first).await.unwrap().len(), 1);
+
+    // 导出为单个文件，不包含密码哈希
+    let dest = dir.path().join("export.db");
+    let exported = databases.export_project(first, &dest).await.unwrap();
+    assert!(exported.size_bytes > 0);
+    let file = establish_connection(&format!("sqlite:{}?mode=ro", dest.display())).await.unwrap();
+    assert_eq!(TaskRepository::new(file.clone()).find_by_project(first).await.unwrap().len(), 1);
+    let users = user::Entity::find().all(&file).await.unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].password_hash, "");
+    file.close().await.unwrap();
+    assert!(databases.export_project(first, &dest).await.unwrap_err().is_validation_error(), "不覆盖已有文件");
+
+    let path = databases.partition_path(second).unwrap();
+    assert!(databases.remove_partition(second).await.unwrap());
+    assert!(!path.exists());
+    assert!(!databases.is_partitioned(second).await.unwrap());
+    assert!(!databases.remove_partition(second).await.unwrap());
+    databases.detach_all().await.unwrap();
+}
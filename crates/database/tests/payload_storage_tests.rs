@@ -0,0 +1,183 @@
+//! 大字段压缩与转存测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    artifact_store::{ArtifactStore, RetentionPolicy},
+    entities::{domain_event, execution_session},
+    payload_storage::{codec, compress, inflate, is_offloaded, PayloadPolicy, PayloadStore, CODEC_ZSTD},
+    repository::{
+        agent_repository::CreateAgentData, domain_event_repository::CreateDomainEventData,
+        execution_session_repository::CreateSessionData, project_repository::CreateProjectData,
+        task_repository::CreateTaskData, user_repository::CreateUserData, AgentRepository, DomainEventRepository,
+        ExecutionSessionRepository, OffloadedPayloadRepository, ProjectRepository, TaskRepository, UserRepository,
+    },
+    DatabaseConnection,
+};
+use sea_orm::EntityTrait;
+use serde_json::{json, Value as JsonValue};
+use uuid::Uuid;
+
+mod common;
+
+/// 压缩率不高的大字段，模拟完整的 diff 输出
+fn large_diff(lines: usize) -> JsonValue {
+    let diff: Vec<String> = (0..lines).map(|i| format!("+{} {}", i, Uuid::new_v4())).collect();
+    json!({ "diff": diff.join("\n"), "files": lines })
+}
+
+fn small_policy() -> PayloadPolicy {
+    PayloadPolicy {
+        compress_threshold: 1024,
+        offload_threshold: 8 * 1024,
+        level: 3,
+    }
+}
+
+async fn create_test_session(db: &DatabaseConnection) -> Uuid {
+    let user_id = UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("payload_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("payload_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id;
+    let project_id = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: "负载项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/payload.git".to_string(),
+        workspace_path: "/workspace/payload".to_string(),
+    }).await.unwrap().project_id;
+    let agent_id = AgentRepository::new(db.clone()).create(CreateAgentData {
+        user_id,
+        name: "构建Agent".to_string(),
+        description: None,
+        prompt_template: "测试".to_string(),
+        capabilities: json!(["Development"]),
+        config: json!({}),
+        git_config: None,
+    }).await.unwrap().agent_id;
+    let task_id = TaskRepository::new(db.clone()).create(CreateTaskData {
+        project_id,
+        parent_task_id: None,
+        llm_session_id: None,
+        title: "重构模块".to_string(),
+        description: "测试".to_string(),
+        task_type: "development".to_string(),
+    }).await.unwrap().task_id;
+    ExecutionSessionRepository::new(db.clone()).create(CreateSessionData {
+        task_id,
+        agent_id,
+        project_id,
+        git_branch: "feature/refactor".to_string(),
+        base_commit: None,
+        execution_config: None,
+        timeout_minutes: 30,
+    }).await.unwrap().session_id
+}
+
+#[tokio::test]
+async fn test_large_event_data_is_compressed_transparently() {
+    let small = json!({ "title": "新任务" });
+    assert_eq!(compress(small.clone(), &PayloadPolicy::default()).unwrap(), small);
+    let diff = large_diff(1000);
+    let compressed = compress(diff.clone(), &PayloadPolicy::default()).unwrap();
+    assert_eq!(codec(&compressed), Some(CODEC_ZSTD));
+    assert!(compressed.to_string().len() < diff.to_string().len());
+    assert_eq!(inflate(compressed).unwrap(), diff);
+    assert!(PayloadPolicy { offload_threshold: 10, ..small_policy() }.validate().is_err());
+
+    let db = setup_test_db().await;
+    let events = DomainEventRepository::new(db.clone());
+    let event = events.create(CreateDomainEventData {
+        aggregate_type: "task".to_string(),
+        aggregate_id: Uuid::new_v4(),
+        event_type: "task_completed".to_string(),
+        event_data: diff.clone(),
+        event_version: 1,
+    }).await.unwrap();
+    assert_eq!(event.event_data, diff);
+
+    let stored = domain_event::Entity::find_by_id(event.event_id).one(&db).await.unwrap().unwrap();
+    assert_eq!(codec(&stored.event_data), Some(CODEC_ZSTD), "行内保存压缩后的数据");
+    let listed = events.find_by_aggregate_id(event.aggregate_id).await.unwrap();
+    assert_eq!(listed[0].event_data, diff);
+}
+
+#[tokio::test]
+async fn test_oversized_result_data_is_offloaded_to_artifact_store() {
+    let db = setup_test_db().await;
+    let dir = tempfile::tempdir().unwrap();
+    let store = ArtifactStore::new(db.clone(), dir.path());
+    let payloads = PayloadStore::new(store.clone()).with_policy(small_policy()).unwrap();
+    let session_id = create_test_session(&db).await;
+
+    let sessions = ExecutionSessionRepository::new(db.clone()).with_payload_store(payloads.clone());
+    sessions.start_session(session_id).await.unwrap();
+    let result = large_diff(2000);
+    let completed = sessions
+        .complete_session(session_id, true, Some("abc123".to_string()), Some(result.clone()), None)
+        .await
+        .unwrap();
+    assert_eq!(completed.result_data.as_ref(), Some(&result));
+
+    // 行内只保存引用；没有挂载负载存储的仓储返回引用而不读取大文件
+    let stored = execution_session::Entity::find_by_id(session_id).one(&db).await.unwrap().unwrap();
+    assert!(is_offloaded(stored.result_data.as_ref().unwrap()));
+    let plain = ExecutionSessionRepository::new(db.clone()).find_by_id(session_id).await.unwrap().unwrap();
+    assert!(is_offloaded(plain.result_data.as_ref().unwrap()));
+    assert_eq!(sessions.find_by_id(session_id).await.unwrap().unwrap().result_data, Some(result));
+
+    // 保留策略不会回收被转存负载引用的数据
+    let checksum = stored.result_data.as_ref().unwrap()["checksum"].as_str().unwrap().to_string();
+    let report = store.apply_retention(&RetentionPolicy { max_age_days: Some(0), max_total_bytes: Some(0) }).await.unwrap();
+    assert_eq!(report.removed_blobs, 0);
+    assert!(store.blob_path(&checksum).unwrap().exists());
+
+    sessions.delete(session_id).await.unwrap();
+    assert!(!store.blob_path(&checksum).unwrap().exists(), "删除会话时释放转存的数据");
+    assert_eq!(OffloadedPayloadRepository::new(db.clone()).count_by_checksum(&checksum).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_offload_existing_rows_and_release_orphans() {
+    let db = setup_test_db().await;
+    let dir = tempfile::tempdir().unwrap();
+    let store = ArtifactStore::new(db.clone(), dir.path());
+    let payloads = PayloadStore::new(store.clone()).with_policy(small_policy()).unwrap();
+
+    // 之前写入的事件只做了内联压缩
+    let events = DomainEventRepository::new(db.clone());
+    let diff = large_diff(2000);
+    let large = events.create(CreateDomainEventData {
+        aggregate_type: "task".to_string(),
+        aggregate_id: Uuid::new_v4(),
+        event_type: "task_completed".to_string(),
+        event_data: diff.clone(),
+        event_version: 1,
+    }).await.unwrap();
+    let small = events.create(CreateDomainEventData {
+        aggregate_type: "task".to_string(),
+        aggregate_id: Uuid::new_v4(),
+        event_type: "task_created".to_string(),
+        event_data: json!({ "title": "新任务" }),
+        event_version: 1,
+    }).await.unwrap();
+
+    let report = payloads.offload_existing(100).await.unwrap();
+    assert_eq!(report.domain_events, 1);
+    assert!(report.stored_bytes < report.original_bytes);
+    assert_eq!(payloads.offload_existing(100).await.unwrap().domain_events, 0, "已转存的数据不重复处理");
+
+    let stored = domain_event::Entity::find_by_id(large.event_id).one(&db).await.unwrap().unwrap();
+    assert!(is_offloaded(&stored.event_data));
+    let with_store = DomainEventRepository::new(db.clone()).with_payload_store(payloads.clone());
+    assert_eq!(with_store.find_by_id(large.event_id).await.unwrap().unwrap().event_data, diff);
+    assert_eq!(with_store.find_by_id(small.event_id).await.unwrap().unwrap().event_data["title"], "新任务");
+
+    // 直接删除的行留下的转存负载被回收
+    let checksum = stored.event_data["checksum"].as_str().unwrap().to_string();
+    domain_event::Entity::delete_by_id(large.event_id).exec(&db).await.unwrap();
+    assert_eq!(payloads.release_orphans().await.unwrap(), 1);
+    assert!(!store.blob_path(&checksum).unwrap().exists());
+}
@@ -0,0 +1,217 @@
+//! 性能回归预算测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::code_review::ReviewDecision,
+    perf_budget::{
+        benchmark_history, evaluate_perf_gate, parse_criterion, parse_custom, promote_session_baseline,
+        run_session_benchmarks, BenchmarkCommand, BenchmarkFormat, BenchmarkOutputFuture, BenchmarkRunner, PerfBudget,
+    },
+    repository::{
+        AgentRepository, CodeReviewRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository,
+        UserRepository,
+        agent_repository::CreateAgentData,
+        code_review_repository::CreateCodeReviewData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseError,
+};
+use serde_json::json;
+use std::path::Path;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+mod common;
+
+/// 返回预设 criterion 输出的基准测试运行器
+struct FakeRunner {
+    nanoseconds: Mutex<f64>,
+}
+
+impl BenchmarkRunner for FakeRunner {
+    fn run<'a>(&'a self, _benchmark: &'a BenchmarkCommand, _workspace: &'a Path) -> BenchmarkOutputFuture<'a> {
+        let estimate = *self.nanoseconds.lock().unwrap();
+        Box::pin(async move {
+            Ok(json!({
+                "reason": "benchmark-complete",
+                "id": "parser/large",
+                "typical": { "estimate": estimate, "lower_bound": estimate, "upper_bound": estimate, "unit": "ns" }
+            })
+            .to_string())
+        })
+    }
+}
+
+#[test]
+fn test_parse_benchmark_output() {
+    let output = [
+        json!({ "reason": "group-complete", "group_name": "parser" }).to_string(),
+        json!({
+            "reason": "benchmark-complete",
+            "id": "parser/small",
+            "typical": { "estimate": 1.5, "lower_bound": 1.4, "upper_bound": 1.6, "unit": "ms" }
+        })
+        .to_string(),
+        "Benchmarking parser/small: Analyzing".to_string(),
+    ]
+    .join("\n");
+    let measurements = parse_criterion(&output).unwrap();
+    assert_eq!(measurements.len(), 1);
+    assert_eq!(measurements[0].name, "parser/small");
+    assert_eq!(measurements[0].value, 1_500_000.0);
+    assert_eq!(measurements[0].unit, "ns");
+    assert!(!measurements[0].higher_is_better);
+
+    let output = "warming up\n{\"name\":\"throughput\",\"value\":1200.0,\"unit\":\"req/s\",\"higher_is_better\":true}\n";
+    let measurements = parse_custom(output).unwrap();
+    assert_eq!(measurements.len(), 1);
+    assert!(measurements[0].higher_is_better);
+    assert!(parse_custom("{\"name\":\"\",\"value\":1.0,\"unit\":\"ms\"}").is_err());
+
+    let budget = PerfBudget {
+        benchmarks: vec![BenchmarkCommand {
+            name: "core".to_string(),
+            command: "cargo criterion --message-format=json".to_string(),
+            format: BenchmarkFormat::Criterion,
+            max_regression_percent: Some(-1.0),
+        }],
+        max_regression_percent: 10.0,
+        baseline_branch: None,
+    };
+    assert!(budget.validate().is_err());
+}
+
+#[tokio::test]
+async fn test_regression_blocks_review_and_baselines_per_branch() -> Result<(), DatabaseError> {
+    let db = setup_test_db().await;
+    let workspace = tempfile::tempdir().unwrap();
+
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await?;
+    let projects = ProjectRepository::new(db.clone());
+    let project = projects
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "性能预算项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: workspace.path().to_string_lossy().to_string(),
+        })
+        .await?;
+    projects
+        .set_perf_budget(
+            project.project_id,
+            Some(PerfBudget {
+                benchmarks: vec![BenchmarkCommand {
+                    name: "core".to_string(),
+                    command: "cargo criterion --message-format=json".to_string(),
+                    format: BenchmarkFormat::Criterion,
+                    max_regression_percent: None,
+                }],
+                max_regression_percent: 10.0,
+                baseline_branch: None,
+            }),
+        )
+        .await?;
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "开发Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个开发Agent".to_string(),
+            capabilities: json!(["backend_development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await?;
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "优化解析器".to_string(),
+            description: "性能回归检查".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await?;
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let mut session_ids = Vec::new();
+    for branch in [project.main_branch.as_str(), "feature/parser"] {
+        let session = sessions
+            .create(CreateSessionData {
+                task_id: task.task_id,
+                agent_id: agent.agent_id,
+                project_id: project.project_id,
+                git_branch: branch.to_string(),
+                base_commit: None,
+                execution_config: None,
+                timeout_minutes: 30,
+            })
+            .await?;
+        session_ids.push(session.session_id);
+    }
+    let (main_session, feature_session) = (session_ids[0], session_ids[1]);
+
+    // 主分支第一次运行时没有基线，结果直接成为基线
+    let runner = FakeRunner { nanoseconds: Mutex::new(100.0) };
+    let report = run_session_benchmarks(&db, main_session, workspace.path(), &runner).await?;
+    assert!(report.gate.passed);
+    assert_eq!(report.promoted_baselines, 1);
+    assert_eq!(report.gate.comparisons[0].baseline, None);
+
+    // 功能分支没有自己的基线，与主分支基线比较
+    *runner.nanoseconds.lock().unwrap() = 130.0;
+    let report = run_session_benchmarks(&db, feature_session, workspace.path(), &runner).await?;
+    assert!(!report.gate.passed);
+    assert_eq!(report.promoted_baselines, 0);
+    let comparison = &report.gate.comparisons[0];
+    assert_eq!(comparison.baseline, Some(100.0));
+    assert_eq!(comparison.baseline_branch.as_deref(), Some(project.main_branch.as_str()));
+    assert!((comparison.regression_percent.unwrap() - 30.0).abs() < 1e-6);
+
+    let reviews = CodeReviewRepository::new(db.clone());
+    let review = reviews
+        .create(CreateCodeReviewData {
+            task_id: task.task_id,
+            execution_session_id: feature_session,
+            reviewer_agent_id: agent.agent_id,
+            pull_request_url: "https://github.com/test/repo/pull/9".to_string(),
+            source_branch: "feature/parser".to_string(),
+            target_branch: project.main_branch.clone(),
+            review_comments: json!([]),
+            code_changes: json!([]),
+            status: "pending".to_string(),
+            decision: None,
+            overall_comment: None,
+        })
+        .await?;
+    let error = reviews.submit_decision(review.review_id, ReviewDecision::Approved, None).await.unwrap_err();
+    assert!(error.to_string().contains("parser/large"));
+
+    // 重新运行后回归在预算内即可批准
+    *runner.nanoseconds.lock().unwrap() = 105.0;
+    run_session_benchmarks(&db, feature_session, workspace.path(), &runner).await?;
+    assert!(evaluate_perf_gate(&db, feature_session).await?.unwrap().passed);
+    reviews.submit_decision(review.review_id, ReviewDecision::Approved, None).await?;
+
+    // 手动提升后功能分支使用自己的基线
+    assert_eq!(promote_session_baseline(&db, feature_session).await?, 1);
+    let history = benchmark_history(&db, project.project_id, "feature/parser", "core", "parser/large", 10).await?;
+    assert_eq!(history.len(), 1);
+    assert!(history[0].is_baseline);
+    assert_eq!(history[0].value, 105.0);
+    let history = benchmark_history(&db, project.project_id, &project.main_branch, "core", "parser/large", 10).await?;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].value, 100.0);
+    Ok(())
+}
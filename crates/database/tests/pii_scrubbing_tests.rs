@@ -0,0 +1,141 @@
+//! 需求文档个人信息脱敏测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    blackboard::{write_entry, EntryAuthor, WriteEntryData},
+    pii_scrubbing::{
+        build_decomposition_prompt, reidentify, PiiMapping, PiiPattern, PiiScrubber, PiiScrubbingConfig,
+    },
+    repository::{
+        PiiMappingRepository, ProjectRepository, RequirementDocumentRepository, UserRepository,
+        project_repository::CreateProjectData,
+        requirement_document_repository::CreateRequirementDocumentData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use codex_multi_agent::SharedContextCategory;
+use uuid::Uuid;
+
+mod common;
+
+async fn create_test_project(db: &DatabaseConnection) -> (Uuid, Uuid) {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "脱敏项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/pii".to_string(),
+        })
+        .await
+        .unwrap();
+    (user.user_id, project.project_id)
+}
+
+fn config() -> PiiScrubbingConfig {
+    PiiScrubbingConfig {
+        names: vec!["张三".to_string(), "Alice Smith".to_string()],
+        patterns: vec![PiiPattern {
+            label: "EMPLOYEE_ID".to_string(),
+            pattern: r"EMP-\d{6}".to_string(),
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_scrub_and_reidentify_in_memory() {
+    let scrubber = PiiScrubber::new(&config()).unwrap();
+    let mut mapping = PiiMapping::default();
+
+    let text = "联系人张三（工号 EMP-004211），邮箱 zhangsan@corp.example.com，电话13812345678；\
+                备用联系人 Alice Smith, +1 415-555-0100。再次联系张三。订单号 2023138123456789 不是电话。";
+    let (scrubbed, replaced) = scrubber.scrub(text, &mut mapping);
+
+    assert_eq!(replaced, 7);
+    for pii in ["张三", "EMP-004211", "zhangsan@corp.example.com", "Alice Smith", "415-555-0100"] {
+        assert!(!scrubbed.contains(pii), "{} 未被替换: {}", pii, scrubbed);
+    }
+    // 同一原文使用同一占位符
+    assert_eq!(scrubbed.matches("[NAME_1]").count(), 2);
+    assert!(scrubbed.contains("[EMAIL_1]") && scrubbed.contains("[EMPLOYEE_ID_1]"));
+    // 更长数字串中的号码片段不替换
+    assert!(scrubbed.contains("电话[PHONE_1]") && scrubbed.contains("2023138123456789"));
+    assert_eq!(mapping.reidentify(&scrubbed), text);
+
+    let invalid = PiiScrubbingConfig {
+        patterns: vec![PiiPattern {
+            label: "bad label".to_string(),
+            pattern: ".*".to_string(),
+        }],
+        ..Default::default()
+    };
+    assert!(PiiScrubber::new(&invalid).err().unwrap().is_validation_error());
+}
+
+#[tokio::test]
+async fn test_decomposition_prompt_scrubbed_with_persistent_mapping() {
+    let db = setup_test_db().await;
+    let (user_id, project_id) = create_test_project(&db).await;
+    let documents = RequirementDocumentRepository::new(db.clone());
+    let document = documents
+        .create(CreateRequirementDocumentData {
+            project_id,
+            title: "客户门户".to_string(),
+            content: "张三需要在登录后看到订单，问题反馈发送到 support@corp.example.com。".to_string(),
+            document_type: "prd".to_string(),
+        })
+        .await
+        .unwrap();
+    write_entry(
+        &db,
+        WriteEntryData {
+            project_id,
+            key: "产品负责人".to_string(),
+            category: SharedContextCategory::Decision,
+            value: "由张三确认所有界面改动".to_string(),
+            include_in_prompts: true,
+            expected_version: None,
+            author: EntryAuthor::User(user_id),
+        },
+    )
+    .await
+    .unwrap();
+
+    // 未启用脱敏时原样发送
+    let plain = build_decomposition_prompt(&db, document.document_id).await.unwrap();
+    assert_eq!(plain.scrubbed_items, 0);
+    assert!(plain.prompt.contains("张三") && plain.prompt.contains("客户门户"));
+
+    let projects = ProjectRepository::new(db.clone());
+    projects.set_pii_scrubbing(project_id, Some(config())).await.unwrap();
+    let scrubbed = build_decomposition_prompt(&db, document.document_id).await.unwrap();
+    assert_eq!(scrubbed.scrubbed_items, 3);
+    assert!(!scrubbed.prompt.contains("张三"));
+    assert!(!scrubbed.prompt.contains("support@corp.example.com"));
+
+    // 映射保存在本地，重复构建使用相同占位符，LLM 结果可以还原
+    let mappings = PiiMappingRepository::new(db.clone()).find_by_project(project_id).await.unwrap();
+    assert_eq!(mappings.len(), 2);
+    assert_eq!(build_decomposition_prompt(&db, document.document_id).await.unwrap().prompt, scrubbed.prompt);
+    let restored = reidentify(&db, project_id, "任务：为 [NAME_1] 实现订单列表，通知 [EMAIL_1]").await.unwrap();
+    assert_eq!(restored, "任务：为 张三 实现订单列表，通知 support@corp.example.com");
+
+    let disabled = PiiScrubbingConfig {
+        enabled: false,
+        ..config()
+    };
+    projects.set_pii_scrubbing(project_id, Some(disabled)).await.unwrap();
+    assert_eq!(build_decomposition_prompt(&db, document.document_id).await.unwrap().scrubbed_items, 0);
+}
@@ -0,0 +1,206 @@
+//! 任务计划护栏测试
+
+use codex_database::{
+    llm_provider::{LlmCompletion, LlmFuture, LlmProvider, LlmRequest},
+    plan_guardrails::PlanGuardrail,
+};
+use codex_multi_agent::{
+    llm_orchestration::{ComplexityAssessment, TaskTestRequirements},
+    AgentCapability, AgentId, PlanValidator, PlanViolationType, ProjectContext, ProjectId, SimulationAgent, TaskId,
+    TaskInfo, TaskPriority, TaskType,
+};
+use serde_json::Value as JsonValue;
+use std::sync::Mutex;
+
+/// 依次返回预设输出的提供方，并记录收到的提示词
+struct RevisingProvider {
+    outputs: Mutex<Vec<&'static str>>,
+    prompts: Mutex<Vec<String>>,
+}
+
+impl LlmProvider for RevisingProvider {
+    fn name(&self) -> &str {
+        "revising"
+    }
+
+    fn complete<'a>(&'a self, request: &'a LlmRequest) -> LlmFuture<'a> {
+        Box::pin(async move {
+            self.prompts.lock().unwrap().push(request.prompt.clone());
+            let content = self.outputs.lock().unwrap().remove(0).to_string();
+            Ok(LlmCompletion { content, tokens_used: 10 })
+        })
+    }
+}
+
+/// 解析 `[{"title", "hours", "capability"}]` 形式的计划
+fn parse_plan(text: &str) -> Option<Vec<TaskInfo>> {
+    let items: Vec<JsonValue> = serde_json::from_str(text).ok()?;
+    items
+        .iter()
+        .map(|item| {
+            let capability: AgentCapability = serde_json::from_value(item.get("capability")?.clone()).ok()?;
+            Some(TaskInfo {
+                task_id: TaskId::new(),
+                title: item.get("title")?.as_str()?.to_string(),
+                description: String::new(),
+                task_type: TaskType::Development,
+                priority: TaskPriority::Medium,
+                estimated_hours: item.get("hours")?.as_u64()? as u32,
+                required_capabilities: vec![capability],
+                dependencies: vec![],
+                acceptance_criteria: vec![],
+                tags: vec![],
+                related_files: vec![],
+                test_requirements: TaskTestRequirements {
+                    needs_unit_tests: false,
+                    needs_integration_tests: false,
+                    needs_e2e_tests: false,
+                    required_coverage: 0.0,
+                    special_test_scenarios: vec![],
+                },
+                complexity_assessment: ComplexityAssessment {
+                    technical_complexity: 3,
+                    business_complexity: 3,
+                    integration_complexity: 3,
+                    overall_complexity: 3,
+                    complexity_notes: vec![],
+                },
+                risk_factors: vec![],
+                subtasks: vec![],
+                related_issues: vec![],
+                ci_status: None,
+            })
+        })
+        .collect()
+}
+
+fn context() -> ProjectContext {
+    serde_json::from_value(serde_json::json!({
+        "codebase_info": {
+            "total_files": 0,
+            "total_lines": 0,
+            "main_languages": [],
+            "framework_info": [],
+            "quality_metrics": {
+                "test_coverage": 0.0,
+                "average_complexity": 0.0,
+                "duplication_rate": 0.0,
+                "style_violations": 0,
+                "security_issues": 0,
+                "performance_issues": 0,
+                "overall_quality_score": 0
+            },
+            "recent_commit_stats": {
+                "commits_last_30_days": 0,
+                "active_contributors": 0,
+                "average_commits_per_day": 0.0,
+                "commit_type_distribution": {},
+                "last_commit_time": chrono::Utc::now()
+            },
+            "technical_debt": {
+                "estimated_hours": 0,
+                "severity_distribution": {},
+                "main_debt_types": [],
+                "trend": "stable"
+            }
+        },
+        "existing_architecture": null,
+        "development_constraints": [],
+        "timeline_requirements": null,
+        "completed_tasks_summary": [],
+        "active_tasks": [],
+        "risk_assessment": {
+            "overall_risk_level": "low",
+            "risk_items": [],
+            "risk_matrix": {"high_risk_count": 0, "medium_risk_count": 0, "low_risk_count": 0, "risk_distribution": {}},
+            "mitigation_plan": []
+        },
+        "resource_availability": {
+            "available_agents": [],
+            "agent_workloads": {},
+            "estimated_resource_demand": {"capability_demands": {}, "peak_demand_periods": [], "total_estimated_hours": 0},
+            "resource_gaps": []
+        },
+        "external_dependencies_status": []
+    }))
+    .unwrap()
+}
+
+fn validator() -> PlanValidator {
+    PlanValidator::new(vec![SimulationAgent {
+        agent_id: AgentId::new(),
+        capabilities: vec![AgentCapability::BackendDevelopment],
+        available_from: None,
+        calibrated_confidence: None,
+        familiar_paths: vec![],
+        task_costs: Default::default(),
+    }])
+    .with_max_task_hours(16)
+}
+
+const INVALID_PLAN: &str = r#"[{"title": "后端重写", "hours": 80, "capability": "backend_development"},
+    {"title": "安全审计", "hours": 4, "capability": "security_audit"}]"#;
+const VALID_PLAN: &str = r#"[{"title": "拆分接口", "hours": 8, "capability": "backend_development"},
+    {"title": "迁移数据", "hours": 8, "capability": "backend_development"}]"#;
+
+#[tokio::test]
+async fn test_guardrail_revises_invalid_plan() {
+    let provider = RevisingProvider {
+        outputs: Mutex::new(vec![VALID_PLAN]),
+        prompts: Mutex::new(Vec::new()),
+    };
+    let request = LlmRequest::new("gpt-4o", "请分解需求");
+    let initial = LlmCompletion { content: INVALID_PLAN.to_string(), tokens_used: 20 };
+
+    let plan = PlanGuardrail::new(&provider, validator())
+        .review(ProjectId::new(), &context(), &request, initial, parse_plan)
+        .await
+        .unwrap();
+
+    assert!(plan.is_accepted());
+    assert_eq!(plan.revisions, 1);
+    assert_eq!(plan.tokens_used, 30);
+    assert_eq!(plan.tasks[0].title, "拆分接口");
+
+    // 修订提示词包含原始提示词、上一版计划和违规反馈
+    let prompt = provider.prompts.lock().unwrap()[0].clone();
+    assert!(prompt.starts_with("请分解需求"));
+    assert!(prompt.contains("后端重写") && prompt.contains("超过单个任务上限 16 小时"));
+    assert!(prompt.contains("任务「安全审计」"));
+}
+
+#[tokio::test]
+async fn test_guardrail_annotates_plan_after_max_revisions() {
+    let provider = RevisingProvider {
+        outputs: Mutex::new(vec![INVALID_PLAN, "无法解析的输出"]),
+        prompts: Mutex::new(Vec::new()),
+    };
+    let request = LlmRequest::new("gpt-4o", "请分解需求");
+    let initial = LlmCompletion { content: INVALID_PLAN.to_string(), tokens_used: 20 };
+
+    let plan = PlanGuardrail::new(&provider, validator())
+        .with_max_revisions(3)
+        .review(ProjectId::new(), &context(), &request, initial, parse_plan)
+        .await
+        .unwrap();
+
+    // 第二轮修订无法解析，保留上一版计划并标注违规
+    assert!(!plan.is_accepted());
+    assert_eq!(plan.revisions, 2);
+    let kinds: Vec<_> = plan.report.violations.iter().map(|violation| violation.violation_type).collect();
+    assert!(kinds.contains(&PlanViolationType::OversizedTask) && kinds.contains(&PlanViolationType::MissingCapability));
+    assert!(plan.tasks.iter().all(|task| !task.risk_factors.is_empty()));
+
+    // 初始输出无法解析时返回验证错误
+    let error = PlanGuardrail::new(&provider, validator())
+        .review(
+            ProjectId::new(),
+            &context(),
+            &request,
+            LlmCompletion { content: "抱歉".to_string(), tokens_used: 1 },
+            parse_plan,
+        )
+        .await
+        .unwrap_err();
+    assert!(error.is_validation_error());
+}
@@ -0,0 +1,247 @@
+//! 事件处理插件测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    plugins::{
+        load_manifests, CommandPlugin, EventPlugin, PluginDispatcher, PluginEvent, PluginFuture, PluginLimits,
+        PluginRegistry, PLUGIN_SUBSCRIBER_TYPE,
+    },
+    repository::{domain_event_repository::CreateDomainEventData, DomainEventRepository, EventPublishLogRepository},
+    DatabaseConnection, DatabaseError,
+};
+use serde_json::json;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+mod common;
+
+#[derive(Clone, Copy)]
+enum Behavior {
+    Record,
+    Fail,
+    Panic,
+    Hang,
+    /// 前 N 次失败，之后成功
+    FailTimes(u32),
+}
+
+struct TestPlugin {
+    name: String,
+    events: Vec<String>,
+    behavior: Behavior,
+    calls: AtomicU32,
+    received: Mutex<Vec<String>>,
+}
+
+impl TestPlugin {
+    fn new(name: &str, events: &[&str], behavior: Behavior) -> Arc<Self> {
+        Arc::new(Self {
+            name: name.to_string(),
+            events: events.iter().map(|event| event.to_string()).collect(),
+            behavior,
+            calls: AtomicU32::new(0),
+            received: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn received(&self) -> Vec<String> {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+impl EventPlugin for TestPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        self.events.clone()
+    }
+
+    fn handle<'a>(&'a self, event: &'a PluginEvent) -> PluginFuture<'a> {
+        Box::pin(async move {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            match self.behavior {
+                Behavior::Record => {}
+                Behavior::Fail => return Err(DatabaseError::business_logic("内部系统不可用")),
+                Behavior::Panic => panic!("插件崩溃"),
+                Behavior::Hang => tokio::time::sleep(Duration::from_secs(30)).await,
+                Behavior::FailTimes(times) if call < times => {
+                    return Err(DatabaseError::business_logic("暂时失败"));
+                }
+                Behavior::FailTimes(_) => {}
+            }
+            self.received.lock().unwrap().push(event.event_type.clone());
+            Ok(Some(json!({ "ack": event.event_id })))
+        })
+    }
+}
+
+async fn create_event(db: &DatabaseConnection, event_type: &str) -> Uuid {
+    DomainEventRepository::new(db.clone())
+        .create(CreateDomainEventData {
+            aggregate_type: "Task".to_string(),
+            aggregate_id: Uuid::new_v4(),
+            event_type: event_type.to_string(),
+            event_data: json!({ "title": "实现登录" }),
+            event_version: 1,
+        })
+        .await
+        .unwrap()
+        .event_id
+}
+
+fn limits(max_consecutive_failures: u32) -> PluginLimits {
+    PluginLimits {
+        timeout: Duration::from_millis(200),
+        max_consecutive_failures,
+        max_attempts: 3,
+    }
+}
+
+#[test]
+fn test_registry_validation() {
+    let registry = PluginRegistry::new();
+    registry.register(TestPlugin::new("audit", &["TaskCompleted"], Behavior::Record)).unwrap();
+
+    let duplicate = registry.register(TestPlugin::new("audit", &["TaskFailed"], Behavior::Record));
+    assert!(duplicate.unwrap_err().is_validation_error());
+    let no_events = registry.register(TestPlugin::new("empty", &[" "], Behavior::Record));
+    assert!(no_events.unwrap_err().is_validation_error());
+
+    assert_eq!(registry.statuses().len(), 1);
+    assert!(registry.unregister("audit").unwrap());
+    assert!(!registry.unregister("audit").unwrap());
+    assert!(registry.statuses().is_empty());
+}
+
+#[tokio::test]
+async fn test_dispatch_isolates_failing_plugins() {
+    let db = setup_test_db().await;
+    let registry = Arc::new(PluginRegistry::new());
+    let audit = TestPlugin::new("audit", &["TaskCompleted"], Behavior::Record);
+    let firehose = TestPlugin::new("firehose", &["*"], Behavior::Record);
+    registry.register(audit.clone()).unwrap();
+    registry.register(firehose.clone()).unwrap();
+    registry.register(TestPlugin::new("broken", &["TaskCompleted"], Behavior::Fail)).unwrap();
+    registry.register(TestPlugin::new("crashing", &["TaskCompleted"], Behavior::Panic)).unwrap();
+    registry.register(TestPlugin::new("slow", &["TaskCompleted"], Behavior::Hang)).unwrap();
+    let dispatcher = PluginDispatcher::new(db.clone(), registry.clone()).with_limits(limits(5));
+
+    let completed = create_event(&db, "TaskCompleted").await;
+    create_event(&db, "TaskStarted").await;
+
+    let report = dispatcher.dispatch_pending().await.unwrap();
+    assert_eq!(report.events_processed, 2);
+    assert_eq!(report.deliveries.len(), 6);
+    assert_eq!(report.failed_count(), 3);
+    assert_eq!(audit.received(), vec!["TaskCompleted"]);
+    assert_eq!(firehose.received().len(), 2);
+
+    let errors: Vec<String> = report.deliveries.iter().filter_map(|delivery| delivery.error.clone()).collect();
+    assert!(errors.iter().any(|error| error.contains("内部系统不可用")));
+    assert!(errors.iter().any(|error| error.contains("panic")));
+    assert!(errors.iter().any(|error| error.contains("超时")));
+
+    // 事件已处理并记录失败的插件，再次投递不会重复处理
+    let event = DomainEventRepository::new(db.clone()).find_by_id(completed).await.unwrap().unwrap();
+    assert!(event.is_processed);
+    assert!(event.error_message.unwrap().contains("broken"));
+    assert_eq!(dispatcher.dispatch_pending().await.unwrap().events_processed, 0);
+
+    let logs = EventPublishLogRepository::new(db.clone()).find_by_event_id(completed).await.unwrap();
+    assert_eq!(logs.len(), 5);
+    assert!(logs.iter().all(|log| log.subscriber_type == PLUGIN_SUBSCRIBER_TYPE && log.attempts == 1));
+    let audit_log = logs.iter().find(|log| log.subscriber_id == "audit").unwrap();
+    assert_eq!(audit_log.status, "delivered");
+    assert_eq!(audit_log.response_data, Some(json!({ "ack": completed })));
+    assert!(logs.iter().find(|log| log.subscriber_id == "slow").unwrap().failed_at.is_some());
+}
+
+#[tokio::test]
+async fn test_consecutive_failures_disable_plugin_and_retry() {
+    let db = setup_test_db().await;
+    let registry = Arc::new(PluginRegistry::new());
+    let flaky = TestPlugin::new("flaky", &["TaskFailed"], Behavior::FailTimes(2));
+    registry.register(flaky.clone()).unwrap();
+    let dispatcher = PluginDispatcher::new(db.clone(), registry.clone()).with_limits(limits(2));
+
+    create_event(&db, "TaskFailed").await;
+    create_event(&db, "TaskFailed").await;
+    let report = dispatcher.dispatch_pending().await.unwrap();
+    assert_eq!(report.failed_count(), 2);
+
+    // 连续失败达到上限后停用，停用期间不投递新事件也不重试
+    let status = registry.statuses().remove(0);
+    assert!(!status.enabled);
+    assert_eq!(status.consecutive_failures, 2);
+    create_event(&db, "TaskFailed").await;
+    let report = dispatcher.dispatch_pending().await.unwrap();
+    assert_eq!(report.events_processed, 1);
+    assert!(report.deliveries.is_empty());
+    assert!(dispatcher.retry_failed().await.unwrap().deliveries.is_empty());
+
+    // 重新启用后重试失败的投递
+    assert!(registry.enable("flaky").unwrap());
+    let retried = dispatcher.retry_failed().await.unwrap();
+    assert_eq!(retried.deliveries.len(), 2);
+    assert!(retried.deliveries.iter().all(|delivery| delivery.delivered));
+    assert_eq!(flaky.received().len(), 2);
+    assert!(dispatcher.retry_failed().await.unwrap().deliveries.is_empty());
+
+    let status = registry.statuses().remove(0);
+    assert_eq!((status.delivered, status.failed, status.consecutive_failures), (2, 2, 0));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_command_plugin_from_manifest() {
+    let db = setup_test_db().await;
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("audit.toml"),
+        r#"
+name = "audit"
+events = ["TaskCompleted"]
+command = "sh"
+args = ["-c", "cat > received.json; echo \"{\\\"endpoint\\\": \\\"$AUDIT_ENDPOINT\\\", \\\"secret\\\": \\\"$PLUGIN_TEST_SECRET\\\"}\""]
+
+[env]
+AUDIT_ENDPOINT = "https://audit.internal"
+"#,
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "不是清单").unwrap();
+
+    let manifests = load_manifests(dir.path()).unwrap();
+    assert_eq!(manifests.len(), 1);
+    assert_eq!(manifests[0].working_dir.as_deref(), Some(dir.path()));
+
+    // 宿主进程的其他环境变量不会传给插件
+    std::env::set_var("PLUGIN_TEST_SECRET", "leaked");
+    let registry = Arc::new(PluginRegistry::new());
+    for manifest in manifests {
+        registry.register(Arc::new(CommandPlugin::new(manifest))).unwrap();
+    }
+    let dispatcher = PluginDispatcher::new(db.clone(), registry).with_limits(limits(5));
+    let event_id = create_event(&db, "TaskCompleted").await;
+    let report = dispatcher.dispatch_pending().await.unwrap();
+    assert_eq!(report.failed_count(), 0, "{:?}", report);
+
+    let received: PluginEvent =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("received.json")).unwrap()).unwrap();
+    assert_eq!(received.event_id, event_id);
+    assert_eq!(received.event_data["title"], "实现登录");
+
+    let logs = EventPublishLogRepository::new(db.clone()).find_by_event_id(event_id).await.unwrap();
+    assert_eq!(logs[0].response_data, Some(json!({ "endpoint": "https://audit.internal", "secret": "" })));
+}
+
+#[test]
+fn test_invalid_manifest_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("broken.toml"), "name = \"broken\"\n").unwrap();
+    assert!(load_manifests(dir.path()).unwrap_err().is_validation_error());
+}
@@ -0,0 +1,243 @@
+//! 紧急任务抢占测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::{agent::AgentStatus, domain_event::DomainEventType, execution_log::EventType},
+    preemption::{preempt_for_critical_task, PreemptionPolicy},
+    repository::{
+        AgentRepository, DomainEventRepository, ExecutionLogRepository, ExecutionSessionRepository,
+        ProjectRepository, TaskRepository, UserRepository,
+        agent_repository::CreateAgentData,
+        execution_log_repository::CreateExecutionLogData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use chrono::{Duration, Utc};
+use codex_multi_agent::{Clock, SystemClock, TaskPriority, TestClock};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    user_id: Uuid,
+    project_id: Uuid,
+}
+
+async fn setup_project(db: &DatabaseConnection) -> Fixture {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "抢占项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/preemption".to_string(),
+        })
+        .await
+        .unwrap();
+    Fixture { user_id: user.user_id, project_id: project.project_id }
+}
+
+async fn create_task(db: &DatabaseConnection, project_id: Uuid, title: &str, priority: &str) -> Uuid {
+    let tasks = TaskRepository::new(db.clone());
+    let task = tasks
+        .create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: title.to_string(),
+            description: format!("{}的实现", title),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+    tasks
+        .update_details(task.task_id, None, None, Some(priority.to_string()), None)
+        .await
+        .unwrap();
+    tasks
+        .update_requirements(task.task_id, Some(json!(["backend_development"])), None)
+        .await
+        .unwrap();
+    task.task_id
+}
+
+/// 创建一个正在执行指定任务的Agent，返回Agent ID和执行会话ID
+async fn start_running(db: &DatabaseConnection, fixture: &Fixture, task_id: Uuid) -> (Uuid, Uuid) {
+    let agents = AgentRepository::new(db.clone());
+    let agent = agents
+        .create(CreateAgentData {
+            user_id: fixture.user_id,
+            name: format!("后端Agent-{}", &Uuid::new_v4().to_string()[..8]),
+            description: None,
+            prompt_template: "你是一个后端开发Agent".to_string(),
+            capabilities: json!(["backend_development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+    agents.update_status(agent.agent_id, AgentStatus::Working, Some(task_id)).await.unwrap();
+
+    let tasks = TaskRepository::new(db.clone());
+    tasks.assign_to_agent(task_id, agent.agent_id, "开始实现".to_string()).await.unwrap();
+    tasks.update_status(task_id, "in_progress").await.unwrap();
+
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let session = sessions
+        .create(CreateSessionData {
+            task_id,
+            agent_id: agent.agent_id,
+            project_id: fixture.project_id,
+            git_branch: format!("task/{}", task_id),
+            base_commit: Some("base123".to_string()),
+            execution_config: None,
+            timeout_minutes: 60,
+        })
+        .await
+        .unwrap();
+    sessions.start_session(session.session_id).await.unwrap();
+    (agent.agent_id, session.session_id)
+}
+
+#[tokio::test]
+async fn test_critical_task_preempts_lowest_priority_task() {
+    let db = setup_test_db().await;
+    let fixture = setup_project(&db).await;
+    let medium_task = create_task(&db, fixture.project_id, "报表导出", "medium").await;
+    let low_task = create_task(&db, fixture.project_id, "日志清理", "low").await;
+    let critical_task = create_task(&db, fixture.project_id, "修复支付故障", "critical").await;
+    start_running(&db, &fixture, medium_task).await;
+    let (low_agent, low_session) = start_running(&db, &fixture, low_task).await;
+
+    ProjectRepository::new(db.clone())
+        .set_preemption_policy(
+            fixture.project_id,
+            Some(PreemptionPolicy {
+                preemptible_priorities: vec!["low".to_string(), "medium".to_string()],
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+
+    let event = preempt_for_critical_task(&db, critical_task, &SystemClock).await.unwrap().unwrap();
+    assert_eq!(event.preempted_task_id.into_uuid(), low_task);
+    assert_eq!(event.preempted_session_id.into_uuid(), low_session);
+    assert_eq!(event.preempting_task_id.into_uuid(), critical_task);
+    assert_eq!(event.agent_id.into_uuid(), low_agent);
+    assert_eq!(event.preempted_priority, TaskPriority::Low);
+    assert_eq!(event.metadata.correlation_id, Some(critical_task.to_string()));
+
+    // 被抢占的任务回到队列，执行会话写入检查点后结束
+    let tasks = TaskRepository::new(db.clone());
+    let low = tasks.find_by_id(low_task).await.unwrap().unwrap();
+    assert_eq!(low.status, "pending");
+    assert!(low.assigned_agent_id.is_none());
+    assert_eq!(tasks.find_by_id(medium_task).await.unwrap().unwrap().status, "in_progress");
+
+    let session = ExecutionSessionRepository::new(db.clone()).find_by_id(low_session).await.unwrap().unwrap();
+    assert_eq!(session.status, "failed");
+    assert_eq!(session.result_data, Some(json!({ "preempted_by": critical_task })));
+    let logs = ExecutionLogRepository::new(db.clone()).find_by_session_id(low_session).await.unwrap();
+    let checkpoint = logs.last().unwrap().details.clone().unwrap();
+    assert_eq!(checkpoint["checkpoint"], json!(true));
+    assert_eq!(checkpoint["reason"], json!("preempted"));
+    assert_eq!(checkpoint["git_branch"], json!(format!("task/{}", low_task)));
+
+    // 紧急任务分配给让出的Agent
+    let critical = tasks.find_by_id(critical_task).await.unwrap().unwrap();
+    assert_eq!(critical.assigned_agent_id, Some(low_agent));
+    let agent = AgentRepository::new(db.clone()).find_by_id(low_agent).await.unwrap().unwrap();
+    assert_eq!(agent.current_task_id, Some(critical_task));
+
+    let events = DomainEventRepository::new(db.clone()).find_by_correlation_id(critical_task).await.unwrap();
+    assert!(events.iter().any(|e| e.event_type == DomainEventType::TaskPreempted.to_string()));
+
+    // 已分配的紧急任务不会重复抢占
+    assert!(preempt_for_critical_task(&db, critical_task, &SystemClock).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_preemption_respects_policy() {
+    let db = setup_test_db().await;
+    let fixture = setup_project(&db).await;
+    let low_task = create_task(&db, fixture.project_id, "日志清理", "low").await;
+    let critical_task = create_task(&db, fixture.project_id, "修复支付故障", "critical").await;
+    let (_, session_id) = start_running(&db, &fixture, low_task).await;
+
+    // 未配置策略时不抢占，非紧急任务不能发起抢占
+    assert!(preempt_for_critical_task(&db, critical_task, &SystemClock).await.unwrap().is_none());
+    assert!(preempt_for_critical_task(&db, low_task, &SystemClock).await.unwrap_err().is_validation_error());
+
+    let projects = ProjectRepository::new(db.clone());
+    let invalid = PreemptionPolicy { preemptible_priorities: vec!["critical".to_string()], ..Default::default() };
+    assert!(projects.set_preemption_policy(fixture.project_id, Some(invalid)).await.is_err());
+    let disabled = PreemptionPolicy { enabled: false, ..Default::default() };
+    projects.set_preemption_policy(fixture.project_id, Some(disabled)).await.unwrap();
+    assert!(preempt_for_critical_task(&db, critical_task, &SystemClock).await.unwrap().is_none());
+
+    // 进度已超过阈值的任务不会被抢占
+    projects.set_preemption_policy(fixture.project_id, Some(PreemptionPolicy::default())).await.unwrap();
+    ExecutionLogRepository::new(db.clone())
+        .create(CreateExecutionLogData {
+            session_id,
+            log_level: "info".to_string(),
+            event_type: EventType::Progress,
+            message: "主要功能已完成".to_string(),
+            details: Some(json!({ "completion_percentage": 0.8 })),
+            timestamp_ms: Utc::now().timestamp_millis(),
+        })
+        .await
+        .unwrap();
+    assert!(preempt_for_critical_task(&db, critical_task, &SystemClock).await.unwrap().is_none());
+
+    let tasks = TaskRepository::new(db.clone());
+    assert_eq!(tasks.find_by_id(low_task).await.unwrap().unwrap().status, "in_progress");
+    assert!(tasks.find_by_id(critical_task).await.unwrap().unwrap().assigned_agent_id.is_none());
+}
+
+#[tokio::test]
+async fn test_preemption_progress_follows_clock() {
+    let db = setup_test_db().await;
+    let fixture = setup_project(&db).await;
+    let low_task = create_task(&db, fixture.project_id, "日志清理", "low").await;
+    let critical_task = create_task(&db, fixture.project_id, "修复支付故障", "critical").await;
+    let (_, session_id) = start_running(&db, &fixture, low_task).await;
+    ProjectRepository::new(db.clone())
+        .set_preemption_policy(fixture.project_id, Some(PreemptionPolicy::default()))
+        .await
+        .unwrap();
+
+    let session = ExecutionSessionRepository::new(db.clone()).find_by_id(session_id).await.unwrap().unwrap();
+    let started_at = session.started_at.unwrap().with_timezone(&Utc);
+
+    // 没有进度上报时按已执行时间估算：60分钟超时已执行45分钟，超过30%的阈值
+    let clock = TestClock::new(started_at + Duration::minutes(45));
+    assert!(preempt_for_critical_task(&db, critical_task, &clock).await.unwrap().is_none());
+
+    // 时钟回到开始后10分钟，完成度低于阈值，可以抢占
+    clock.set(started_at + Duration::minutes(10));
+    let event = preempt_for_critical_task(&db, critical_task, &clock).await.unwrap().unwrap();
+    assert_eq!(event.elapsed_minutes, 10);
+    assert_eq!(event.metadata.timestamp, clock.now());
+
+    let logs = ExecutionLogRepository::new(db.clone()).find_by_session_id(session_id).await.unwrap();
+    assert_eq!(logs.last().unwrap().timestamp_ms, clock.now().timestamp_millis());
+    let task = TaskRepository::new(db.clone()).find_by_id(low_task).await.unwrap().unwrap();
+    assert_eq!(task.updated_at.with_timezone(&Utc), clock.now());
+}
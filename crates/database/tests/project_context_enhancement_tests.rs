@@ -79,6 +79,13 @@ fn test_project_context_enhancement() {
                 "require_approval_count": 2
             }
         })),
+        organization_id: None,
+        workspace_quota: None,
+        pii_scrubbing: None,
+        preemption_policy: None,
+        sla_policy: None,
+        code_ownership: None,
+        perf_budget: None,
         status: "active".to_string(),
         created_at: now,
         updated_at: now,
@@ -124,6 +131,13 @@ fn test_project_configuration_management() {
         project_context: None,
         quality_standards: None,
         automation_config: None,
+        organization_id: None,
+        workspace_quota: None,
+        pii_scrubbing: None,
+        preemption_policy: None,
+        sla_policy: None,
+        code_ownership: None,
+        perf_budget: None,
         status: "setup".to_string(),
         created_at: now,
         updated_at: now,
@@ -183,6 +197,13 @@ fn test_project_status_management() {
             "code_quality_score": 8.0
         })),
         automation_config: None,
+        organization_id: None,
+        workspace_quota: None,
+        pii_scrubbing: None,
+        preemption_policy: None,
+        sla_policy: None,
+        code_ownership: None,
+        perf_budget: None,
         status: "planning".to_string(),
         created_at: now,
         updated_at: now,
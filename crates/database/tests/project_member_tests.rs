@@ -0,0 +1,116 @@
+//! 项目成员与角色权限集成测试
+
+use crate::common::setup_test_db;
+use codex_database::repository::{
+    ProjectMemberRepository, ProjectRepository, UserRepository,
+    user_repository::CreateUserData,
+    project_repository::CreateProjectData,
+};
+use codex_multi_agent::ProjectRole;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建测试用户的辅助函数
+async fn create_test_user(db: &codex_database::DatabaseConnection) -> Uuid {
+    let repo = UserRepository::new(db.clone());
+    let user_data = CreateUserData {
+        username: format!("member_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("member_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    };
+    repo.create(user_data).await.unwrap().user_id
+}
+
+/// 创建测试项目的辅助函数
+async fn create_test_project(db: &codex_database::DatabaseConnection, user_id: Uuid) -> Uuid {
+    let repo = ProjectRepository::new(db.clone());
+    let project = repo.create(CreateProjectData {
+        user_id,
+        name: "协作项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/members.git".to_string(),
+        workspace_path: "/workspace/members".to_string(),
+    }).await.unwrap();
+    project.project_id
+}
+
+#[tokio::test]
+async fn test_add_and_list_members() {
+    let db = setup_test_db().await;
+    let owner_id = create_test_user(&db).await;
+    let contributor_id = create_test_user(&db).await;
+    let project_id = create_test_project(&db, owner_id).await;
+
+    let repo = ProjectMemberRepository::new(db.clone());
+    repo.add_member(project_id, owner_id, ProjectRole::Owner, None).await.unwrap();
+    let member = repo.add_member(project_id, contributor_id, ProjectRole::Contributor, Some(owner_id))
+        .await
+        .expect("添加成员应该成功");
+    assert_eq!(member.role, "contributor");
+    assert_eq!(member.invited_by, Some(owner_id));
+
+    // 重复添加应失败
+    assert!(repo.add_member(project_id, contributor_id, ProjectRole::Viewer, None).await.is_err());
+
+    let members = repo.list_members(project_id).await.unwrap();
+    assert_eq!(members.len(), 2);
+
+    let project_ids = repo.find_project_ids_by_user(contributor_id).await.unwrap();
+    assert_eq!(project_ids, vec![project_id]);
+}
+
+#[tokio::test]
+async fn test_require_role() {
+    let db = setup_test_db().await;
+    let owner_id = create_test_user(&db).await;
+    let viewer_id = create_test_user(&db).await;
+    let outsider_id = create_test_user(&db).await;
+    let project_id = create_test_project(&db, owner_id).await;
+
+    let repo = ProjectMemberRepository::new(db.clone());
+
+    // 没有任何成员记录的旧项目，创建者视为所有者
+    assert_eq!(repo.get_role(project_id, owner_id).await.unwrap(), Some(ProjectRole::Owner));
+    assert!(repo.require_role(project_id, owner_id, ProjectRole::Owner).await.is_ok());
+
+    // 首次添加成员时创建者被登记为所有者
+    repo.add_member(project_id, viewer_id, ProjectRole::Viewer, Some(owner_id)).await.unwrap();
+    assert_eq!(repo.find_member(project_id, owner_id).await.unwrap().unwrap().role, "owner");
+
+    assert!(repo.require_role(project_id, viewer_id, ProjectRole::Viewer).await.is_ok());
+    assert!(repo.require_role(project_id, viewer_id, ProjectRole::Contributor).await.is_err());
+
+    assert_eq!(repo.get_role(project_id, outsider_id).await.unwrap(), None);
+    assert!(repo.require_role(project_id, outsider_id, ProjectRole::Viewer).await.is_err());
+}
+
+#[tokio::test]
+async fn test_update_and_remove_member_keeps_last_owner() {
+    let db = setup_test_db().await;
+    let owner_id = create_test_user(&db).await;
+    let maintainer_id = create_test_user(&db).await;
+    let project_id = create_test_project(&db, owner_id).await;
+
+    let repo = ProjectMemberRepository::new(db.clone());
+    repo.add_member(project_id, owner_id, ProjectRole::Owner, None).await.unwrap();
+    repo.add_member(project_id, maintainer_id, ProjectRole::Contributor, Some(owner_id)).await.unwrap();
+
+    let (updated, previous) = repo.update_role(project_id, maintainer_id, ProjectRole::Maintainer)
+        .await
+        .unwrap();
+    assert_eq!(previous, ProjectRole::Contributor);
+    assert_eq!(updated.role, "maintainer");
+
+    // 不能降级或移除唯一的所有者
+    assert!(repo.update_role(project_id, owner_id, ProjectRole::Viewer).await.is_err());
+    assert!(repo.remove_member(project_id, owner_id).await.is_err());
+
+    // 存在其他所有者后可以移除
+    repo.update_role(project_id, maintainer_id, ProjectRole::Owner).await.unwrap();
+    assert_eq!(repo.remove_member(project_id, owner_id).await.unwrap(), ProjectRole::Owner);
+    assert!(repo.find_member(project_id, owner_id).await.unwrap().is_none());
+    assert_eq!(repo.get_role(project_id, owner_id).await.unwrap(), None);
+}
@@ -0,0 +1,190 @@
+//! 提示词评估测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    llm_provider::{LlmCompletion, LlmFuture, LlmProvider, LlmRequest},
+    llm_transcripts::{list_transcripts, LlmCallKind, TranscriptQuery},
+    prompt_eval::{get_eval_report, save_eval_set, EvalCase, EvalHarness, EvalSet, EvalVariant, ExpectedProperty},
+};
+use std::collections::BTreeMap;
+
+mod common;
+
+/// 按模型返回不同质量输出的提供方：`strong` 返回JSON任务列表，其他模型只返回一句话
+struct ModelProvider;
+
+impl LlmProvider for ModelProvider {
+    fn name(&self) -> &str {
+        "model"
+    }
+
+    fn complete<'a>(&'a self, request: &'a LlmRequest) -> LlmFuture<'a> {
+        Box::pin(async move {
+            let content = if request.model == "strong" {
+                format!(
+                    "```json\n{{\"tasks\": [{{\"title\": \"实现{}\"}}, {{\"title\": \"测试{}\"}}]}}\n```",
+                    request.prompt, request.prompt
+                )
+            } else {
+                format!("需要完成{}", request.prompt)
+            };
+            Ok(LlmCompletion { content, tokens_used: 20 })
+        })
+    }
+}
+
+/// 输出包含JSON时判定通过的裁判
+struct JsonJudge;
+
+impl LlmProvider for JsonJudge {
+    fn name(&self) -> &str {
+        "judge"
+    }
+
+    fn complete<'a>(&'a self, request: &'a LlmRequest) -> LlmFuture<'a> {
+        Box::pin(async move {
+            let output = request.prompt.split("## 模型输出").nth(1).unwrap_or_default();
+            let content = if output.contains("```json") {
+                "PASS\n任务拆分清晰"
+            } else {
+                "FAIL\n没有给出任务列表"
+            };
+            Ok(LlmCompletion {
+                content: content.to_string(),
+                tokens_used: 5,
+            })
+        })
+    }
+}
+
+fn case(name: &str, feature: &str) -> EvalCase {
+    EvalCase {
+        name: name.to_string(),
+        inputs: BTreeMap::from([("feature".to_string(), feature.to_string())]),
+        expected: vec![
+            ExpectedProperty::Contains { text: feature.to_string() },
+            ExpectedProperty::JsonObject,
+            ExpectedProperty::MinTasks { count: 2 },
+            ExpectedProperty::Judge {
+                criterion: "给出可执行的任务列表".to_string(),
+            },
+        ],
+    }
+}
+
+fn eval_set() -> EvalSet {
+    EvalSet {
+        name: "分解质量".to_string(),
+        description: None,
+        cases: vec![case("登录", "登录"), case("导出", "报表导出")],
+    }
+}
+
+fn variant(name: &str, model: &str) -> EvalVariant {
+    EvalVariant {
+        name: name.to_string(),
+        template: "{{ feature }}".to_string(),
+        model: model.to_string(),
+        temperature: None,
+        max_tokens: None,
+    }
+}
+
+#[tokio::test]
+async fn test_eval_run_scores_variants_and_reports_deltas() {
+    let db = setup_test_db().await;
+    let set = save_eval_set(&db, None, None, &eval_set()).await.unwrap();
+    let provider = ModelProvider;
+    let judge = JsonJudge;
+
+    let report = EvalHarness::new(db.clone(), &provider)
+        .with_judge(&judge, "judge-model")
+        .run(set.eval_set_id, &[variant("基线", "weak"), variant("强模型", "strong")])
+        .await
+        .unwrap();
+
+    assert!(report.judged);
+    assert_eq!(report.variants.len(), 2);
+    let baseline = &report.variants[0];
+    assert_eq!(baseline.variant, "基线");
+    // 只满足「包含」属性
+    assert!((baseline.mean_score - 0.25).abs() < 1e-9);
+    assert_eq!(baseline.cases_passed, 0);
+    let judge_check = baseline.cases[0]
+        .checks
+        .iter()
+        .find(|check| matches!(check.property, ExpectedProperty::Judge { .. }))
+        .unwrap();
+    assert_eq!(judge_check.passed, Some(false));
+    assert_eq!(judge_check.detail.as_deref(), Some("没有给出任务列表"));
+
+    let strong = &report.variants[1];
+    assert!((strong.mean_score - 1.0).abs() < 1e-9);
+    assert!((strong.pass_rate - 1.0).abs() < 1e-9);
+    assert_eq!(strong.total_tokens, 40);
+
+    assert_eq!(report.deltas.len(), 1);
+    let delta = &report.deltas[0];
+    assert_eq!(delta.baseline, "基线");
+    assert_eq!(delta.variant, "强模型");
+    assert!((delta.score_delta - 0.75).abs() < 1e-9);
+    assert!((delta.pass_rate_delta - 1.0).abs() < 1e-9);
+    assert!(delta.regressions.is_empty());
+    assert_eq!(delta.improvements, vec!["登录".to_string(), "导出".to_string()]);
+
+    // 报告可以从数据库重新读取
+    let stored = get_eval_report(&db, report.run_id).await.unwrap().unwrap();
+    assert_eq!(stored.variants, report.variants);
+    assert_eq!(stored.deltas, report.deltas);
+
+    // 评估调用和裁判调用都保存了调用记录
+    let transcripts = list_transcripts(
+        &db,
+        &TranscriptQuery {
+            call_kind: Some(LlmCallKind::Evaluation),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(transcripts.len(), 8);
+}
+
+#[tokio::test]
+async fn test_eval_without_judge_skips_judge_properties() {
+    let db = setup_test_db().await;
+    let set = save_eval_set(&db, None, None, &eval_set()).await.unwrap();
+    let provider = ModelProvider;
+
+    let report = EvalHarness::new(db.clone(), &provider)
+        .run(set.eval_set_id, &[variant("强模型", "strong")])
+        .await
+        .unwrap();
+
+    assert!(!report.judged);
+    assert!(report.deltas.is_empty());
+    let summary = &report.variants[0];
+    // 跳过的裁判属性不计入得分
+    assert!((summary.mean_score - 1.0).abs() < 1e-9);
+    let judge_check = summary.cases[0]
+        .checks
+        .iter()
+        .find(|check| matches!(check.property, ExpectedProperty::Judge { .. }))
+        .unwrap();
+    assert_eq!(judge_check.passed, None);
+
+    // 模板引用了用例没有的输入
+    let mut broken = variant("缺少输入", "strong");
+    broken.template = "{{feature}} {{context}}".to_string();
+    let err = EvalHarness::new(db.clone(), &provider)
+        .run(set.eval_set_id, &[broken])
+        .await
+        .unwrap_err();
+    assert!(err.is_validation_error());
+
+    // 用例名称重复的评估集不能保存
+    let mut duplicated = eval_set();
+    duplicated.cases.push(case("登录", "注册"));
+    let err = save_eval_set(&db, Some(set.eval_set_id), None, &duplicated).await.unwrap_err();
+    assert!(err.is_validation_error());
+}
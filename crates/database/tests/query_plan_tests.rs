@@ -0,0 +1,176 @@
+//! 热点查询的索引使用测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::{conflict, domain_event, execution_log, execution_session, task, task_dependency},
+    query_plan::{explain, QueryPlan},
+    repository::{
+        project_repository::CreateProjectData, task_dependency_repository::CreateTaskDependencyData,
+        task_repository::CreateTaskData, user_repository::CreateUserData, ProjectRepository,
+        TaskDependencyRepository, TaskRepository, UserRepository,
+    },
+};
+use sea_orm::{
+    sea_query::{Alias, Expr, JoinType, Query},
+    ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use uuid::Uuid;
+
+mod common;
+
+fn assert_indexed(plan: &QueryPlan, index: &str, table: &str) {
+    assert!(plan.uses_index(index), "应使用 {}:\n{}", index, plan);
+    assert!(!plan.scans_table(table), "不应全表扫描 {}:\n{}", table, plan);
+    assert!(!plan.uses_temp_sort(), "排序应由索引覆盖:\n{}", plan);
+}
+
+#[tokio::test]
+async fn test_task_queries_use_composite_indexes() {
+    let db = setup_test_db().await;
+    let project_id = Uuid::new_v4();
+
+    // find_by_status
+    let by_status = task::Entity::find()
+        .filter(task::Column::ProjectId.eq(project_id))
+        .filter(task::Column::Status.eq("pending"))
+        .order_by_asc(task::Column::CreatedAt);
+    assert_indexed(&explain(&db, &by_status).await.unwrap(), "idx_tasks_project_status", "tasks");
+
+    // find_by_project
+    let by_project = task::Entity::find()
+        .filter(task::Column::ProjectId.eq(project_id))
+        .order_by_asc(task::Column::CreatedAt);
+    assert_indexed(&explain(&db, &by_project).await.unwrap(), "idx_tasks_project_created", "tasks");
+
+    // find_ready_tasks
+    let prerequisite = Alias::new("prerequisite");
+    let unfinished = Query::select()
+        .expr(Expr::val(1))
+        .from(task_dependency::Entity)
+        .join_as(
+            JoinType::InnerJoin,
+            task::Entity,
+            prerequisite.clone(),
+            Expr::col((prerequisite.clone(), task::Column::TaskId))
+                .equals((task_dependency::Entity, task_dependency::Column::ParentTaskId)),
+        )
+        .and_where(
+            Expr::col((task_dependency::Entity, task_dependency::Column::ChildTaskId))
+                .equals((task::Entity, task::Column::TaskId)),
+        )
+        .and_where(Expr::col((prerequisite, task::Column::Status)).ne("completed"))
+        .to_owned();
+    let ready = task::Entity::find()
+        .filter(task::Column::ProjectId.eq(project_id))
+        .filter(task::Column::Status.eq("pending"))
+        .filter(Expr::exists(unfinished).not())
+        .order_by_asc(task::Column::CreatedAt);
+    let plan = explain(&db, &ready).await.unwrap();
+    assert_indexed(&plan, "idx_tasks_project_status", "tasks");
+    assert!(plan.uses_index("idx_task_deps_child_parent"), "{}", plan);
+    assert!(!plan.scans_table("task_dependencies"), "{}", plan);
+}
+
+#[tokio::test]
+async fn test_conflict_log_and_event_queries_use_indexes() {
+    let db = setup_test_db().await;
+
+    // find_unresolved
+    let unresolved = conflict::Entity::find()
+        .filter(conflict::Column::Status.is_in(["detected", "analyzing", "escalated", "resolving"]))
+        .order_by_desc(conflict::Column::Severity)
+        .order_by_desc(conflict::Column::DetectedAt);
+    let plan = explain(&db, &unresolved).await.unwrap();
+    assert!(plan.uses_index("idx_conflicts_status_severity"), "{}", plan);
+    assert!(!plan.scans_table("conflicts"), "{}", plan);
+
+    // 日志跟踪：最新的若干条
+    let session_id = Uuid::new_v4();
+    let tail = execution_log::Entity::find()
+        .filter(execution_log::Column::SessionId.eq(session_id))
+        .order_by_desc(execution_log::Column::CreatedAt)
+        .limit(50);
+    assert_indexed(&explain(&db, &tail).await.unwrap(), "idx_execution_logs_session_created", "execution_logs");
+
+    // 按项目导出日志的键集分页
+    let sessions = Query::select()
+        .column(execution_session::Column::SessionId)
+        .from(execution_session::Entity)
+        .and_where(execution_session::Column::ProjectId.eq(Uuid::new_v4()))
+        .to_owned();
+    let page = execution_log::Entity::find()
+        .filter(execution_log::Column::SessionId.in_subquery(sessions))
+        .order_by_asc(execution_log::Column::CreatedAt)
+        .order_by_asc(execution_log::Column::LogId)
+        .limit(500);
+    let plan = explain(&db, &page).await.unwrap();
+    assert!(plan.uses_index("idx_execution_sessions_project"), "{}", plan);
+    assert!(plan.uses_index("idx_execution_logs_session_created"), "{}", plan);
+    assert!(!plan.scans_table("execution_logs"), "{}", plan);
+
+    // find_by_project_id
+    let project_sessions = execution_session::Entity::find()
+        .filter(execution_session::Column::ProjectId.eq(Uuid::new_v4()))
+        .order_by_desc(execution_session::Column::CreatedAt);
+    assert_indexed(
+        &explain(&db, &project_sessions).await.unwrap(),
+        "idx_execution_sessions_project",
+        "execution_sessions",
+    );
+
+    // find_unprocessed
+    let unprocessed = domain_event::Entity::find()
+        .filter(domain_event::Column::IsProcessed.eq(false))
+        .order_by_asc(domain_event::Column::OccurredAt)
+        .limit(100);
+    assert_indexed(&explain(&db, &unprocessed).await.unwrap(), "idx_domain_events_unprocessed", "domain_events");
+}
+
+#[tokio::test]
+async fn test_find_ready_tasks() {
+    let db = setup_test_db().await;
+    let user_id = UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("ready_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("ready_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id;
+    let project_id = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: "就绪任务项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/ready.git".to_string(),
+        workspace_path: "/workspace/ready".to_string(),
+    }).await.unwrap().project_id;
+
+    let tasks = TaskRepository::new(db.clone());
+    let mut ids = Vec::new();
+    for title in ["数据模型", "接口实现", "前端页面", "独立任务"] {
+        ids.push(tasks.create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: title.to_string(),
+            description: "测试".to_string(),
+            task_type: "development".to_string(),
+        }).await.unwrap().task_id);
+    }
+    // 接口实现依赖数据模型，前端页面依赖接口实现
+    let dependencies = TaskDependencyRepository::new(db.clone());
+    for (parent, child) in [(ids[0], ids[1]), (ids[1], ids[2])] {
+        dependencies.create(CreateTaskDependencyData {
+            parent_task_id: parent,
+            child_task_id: child,
+            dependency_type: "blocks".to_string(),
+        }).await.unwrap();
+    }
+
+    let ready: Vec<Uuid> = tasks.find_ready_tasks(project_id).await.unwrap().into_iter().map(|t| t.task_id).collect();
+    assert_eq!(ready, [ids[0], ids[3]]);
+
+    tasks.update_status(ids[0], "completed").await.unwrap();
+    tasks.update_status(ids[3], "in_progress").await.unwrap();
+    let ready: Vec<Uuid> = tasks.find_ready_tasks(project_id).await.unwrap().into_iter().map(|t| t.task_id).collect();
+    assert_eq!(ready, [ids[1]], "前置任务完成后解除阻塞，已开始的任务不再就绪");
+}
@@ -0,0 +1,311 @@
+//! 项目状态报告测试
+
+use crate::common::setup_test_db;
+use chrono::{Duration, TimeZone, Utc};
+use codex_database::{
+    artifact_store::{ArtifactStore, RetentionPolicy},
+    entities::conflict::{ConflictSeverity, ConflictType},
+    reporting::{
+        collect_weekly_report, create_schedule, delete_report, generate_report, next_run, read_report, render,
+        run_due_schedules, ReportChannel, ReportFormat, ReportOptions, ScheduleConfig,
+    },
+    repository::{
+        ConflictRepository, LlmConversationRepository, LlmSessionRepository, ProjectReportRepository,
+        ProjectRepository, ReportScheduleRepository, TaskRepository, UserRepository,
+        conflict_repository::CreateConflictData,
+        llm_conversation_repository::CreateConversationMessageData,
+        llm_session_repository::CreateLlmSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    DatabaseConnection,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    user_id: Uuid,
+    project_id: Uuid,
+}
+
+async fn setup_project(db: &DatabaseConnection) -> Fixture {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "报告<项目>".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/report".to_string(),
+        })
+        .await
+        .unwrap();
+    Fixture { user_id: user.user_id, project_id: project.project_id }
+}
+
+async fn create_task(db: &DatabaseConnection, project_id: Uuid, title: &str) -> Uuid {
+    TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: title.to_string(),
+            description: format!("{}的实现", title),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap()
+        .task_id
+}
+
+/// 准备两个已完成任务、一个进行中任务、一个未解决冲突、一个近期里程碑和LLM用量
+async fn seed_activity(db: &DatabaseConnection, fixture: &Fixture) {
+    let tasks = TaskRepository::new(db.clone());
+    let login = create_task(db, fixture.project_id, "实现登录").await;
+    let signup = create_task(db, fixture.project_id, "实现注册").await;
+    let profile = create_task(db, fixture.project_id, "实现个人资料").await;
+    tasks.update_status(login, "completed").await.unwrap();
+    tasks.update_status(signup, "completed").await.unwrap();
+    tasks.update_status(profile, "in_progress").await.unwrap();
+
+    ConflictRepository::new(db.clone())
+        .create(CreateConflictData {
+            conflict_type: ConflictType::GitMerge,
+            severity: ConflictSeverity::High,
+            title: "登录模块合并冲突".to_string(),
+            description: "两个分支同时修改了登录接口".to_string(),
+            related_entities: json!({}),
+            affected_tasks: json!([login.to_string()]),
+            affected_agents: json!([]),
+            project_id: None,
+        })
+        .await
+        .unwrap();
+    // 其他项目的冲突不出现在报告中
+    ConflictRepository::new(db.clone())
+        .create(CreateConflictData {
+            conflict_type: ConflictType::GitMerge,
+            severity: ConflictSeverity::Low,
+            title: "无关冲突".to_string(),
+            description: "其他项目".to_string(),
+            related_entities: json!({}),
+            affected_tasks: json!([Uuid::new_v4().to_string()]),
+            affected_agents: json!([]),
+            project_id: None,
+        })
+        .await
+        .unwrap();
+
+    ProjectRepository::new(db.clone())
+        .update_context(
+            fixture.project_id,
+            None,
+            Some(json!({
+                "milestones": [
+                    {
+                        "id": "m1",
+                        "name": "账号体系",
+                        "deadline": Utc::now() + Duration::days(3),
+                        "deliverables": ["登录", "注册", "个人资料"],
+                        "dependent_tasks": [login, signup, profile],
+                        "status": "in_progress",
+                        "completion_rate": 0.0,
+                        "risk_level": "medium"
+                    },
+                    {
+                        "id": "m2",
+                        "name": "远期规划",
+                        "deadline": Utc::now() + Duration::days(60),
+                        "deliverables": [],
+                        "dependent_tasks": [],
+                        "status": "planned",
+                        "completion_rate": 0.0,
+                        "risk_level": "low"
+                    }
+                ]
+            })),
+        )
+        .await
+        .unwrap();
+
+    let session = LlmSessionRepository::new(db.clone())
+        .create(CreateLlmSessionData {
+            project_id: fixture.project_id,
+            user_id: fixture.user_id,
+            session_type: "decomposition".to_string(),
+            system_prompt: None,
+            decomposition_prompt: None,
+        })
+        .await
+        .unwrap();
+    let messages = LlmConversationRepository::new(db.clone());
+    for (order, tokens) in [(1, Some(1500)), (2, Some(500)), (3, None)] {
+        messages
+            .create(CreateConversationMessageData {
+                session_id: session.session_id,
+                role: "assistant".to_string(),
+                content: "拆解结果".to_string(),
+                message_order: order,
+                token_count: tokens,
+                model_used: None,
+                processing_time_ms: None,
+            })
+            .await
+            .unwrap();
+    }
+}
+
+fn schedule_config(channels: Vec<ReportChannel>) -> ScheduleConfig {
+    ScheduleConfig {
+        name: "每周一周报".to_string(),
+        cron_expression: "0 0 9 * * Mon".to_string(),
+        format: ReportFormat::Html,
+        template: None,
+        channels,
+        cost_per_1k_tokens: Some(0.02),
+        enabled: true,
+    }
+}
+
+#[tokio::test]
+async fn test_weekly_report_contents_and_rendering() {
+    let db = setup_test_db().await;
+    let fixture = setup_project(&db).await;
+    seed_activity(&db, &fixture).await;
+
+    let now = Utc::now() + Duration::minutes(1);
+    let report = collect_weekly_report(&db, fixture.project_id, now, Some(0.02)).await.unwrap();
+    assert_eq!(report.completed_tasks.len(), 2);
+    assert_eq!(report.velocity.completed, 2);
+    assert!(report.velocity.change_ratio.is_none());
+    assert_eq!(report.open_conflicts.len(), 1);
+    assert_eq!(report.open_conflicts[0].title, "登录模块合并冲突");
+    assert_eq!(report.upcoming_milestones.len(), 1);
+    assert_eq!(report.upcoming_milestones[0].id, "m1");
+    assert!((report.upcoming_milestones[0].completion_rate - 2.0 / 3.0).abs() < 1e-6);
+    assert_eq!(report.cost.total_tokens, 2000);
+    assert!((report.cost.estimated_cost.unwrap() - 0.04).abs() < 1e-9);
+
+    // 一周后本周完成的任务计入历史平均
+    let next_week = collect_weekly_report(&db, fixture.project_id, now + Duration::days(7), None).await.unwrap();
+    assert_eq!(next_week.velocity.completed, 0);
+    assert_eq!(next_week.velocity.previous_weekly_average, 0.5);
+    assert_eq!(next_week.velocity.change_ratio, Some(-1.0));
+    assert_eq!(next_week.cost.total_tokens, 0);
+    assert!(next_week.cost.estimated_cost.is_none());
+
+    let markdown = render(&report, ReportFormat::Markdown, None, now).unwrap();
+    assert!(markdown.starts_with("# 报告<项目> 周报"));
+    assert!(markdown.contains("## 已完成任务（2）"));
+    assert!(markdown.contains("- 实现登录（development"));
+    assert!(markdown.contains("[high] 登录模块合并冲突（detected）"));
+    assert!(markdown.contains("账号体系"));
+    assert!(!markdown.contains("远期规划"));
+    assert!(markdown.contains("消耗 2000 个 token，估算费用 0.04"));
+    assert!(!markdown.contains("{{"));
+
+    // HTML 中的文本需要转义
+    let html = render(&report, ReportFormat::Html, None, now).unwrap();
+    assert!(html.contains("<h1>报告&lt;项目&gt; 周报"));
+    assert!(html.contains("<li>实现注册"));
+
+    let custom = render(&report, ReportFormat::Markdown, Some("{{project_name}}: {{completed_count}} / {{ cost }}"), now);
+    assert_eq!(custom.unwrap(), "报告<项目>: 2 / 消耗 2000 个 token，估算费用 0.04");
+    let unknown = render(&report, ReportFormat::Markdown, Some("{{velocity}} {{owner}}"), now);
+    assert!(unknown.unwrap_err().is_validation_error());
+    assert!(render(&report, ReportFormat::Markdown, Some("{{title"), now).is_err());
+
+    assert!(collect_weekly_report(&db, Uuid::new_v4(), now, None).await.is_err());
+}
+
+#[tokio::test]
+async fn test_reports_are_stored_as_artifacts() {
+    let db = setup_test_db().await;
+    let fixture = setup_project(&db).await;
+    seed_activity(&db, &fixture).await;
+    let root = tempfile::tempdir().unwrap();
+    let store = ArtifactStore::new(db.clone(), root.path());
+
+    let options = ReportOptions { format: ReportFormat::Markdown, ..Default::default() };
+    let generated = generate_report(&store, fixture.project_id, &options, None, Utc::now()).await.unwrap();
+    assert_eq!(generated.report.format, "markdown");
+    assert_eq!(generated.report.size_bytes as usize, generated.content.len());
+    assert_eq!(generated.report.summary["completed_tasks"].as_array().unwrap().len(), 2);
+    assert!(generated.channels.is_empty());
+
+    let (report, content) = read_report(&store, generated.report.report_id).await.unwrap();
+    assert_eq!(report.title, generated.report.title);
+    assert_eq!(content, generated.content);
+
+    // 报告内容不受工件保留策略清理
+    let policy = RetentionPolicy { max_age_days: Some(0), max_total_bytes: Some(0) };
+    store.apply_retention(&policy).await.unwrap();
+    assert!(store.blob_path(&report.checksum).unwrap().exists());
+
+    delete_report(&store, report.report_id).await.unwrap();
+    assert!(!store.blob_path(&report.checksum).unwrap().exists());
+    assert!(read_report(&store, report.report_id).await.unwrap_err().to_string().contains("ProjectReport"));
+}
+
+#[tokio::test]
+async fn test_due_schedules_generate_reports_and_advance() {
+    let db = setup_test_db().await;
+    let fixture = setup_project(&db).await;
+    seed_activity(&db, &fixture).await;
+    let root = tempfile::tempdir().unwrap();
+    let store = ArtifactStore::new(db.clone(), root.path());
+
+    // 2026-10-14 是周三，下一次触发为 10-19 周一 09:00
+    let wednesday = Utc.with_ymd_and_hms(2026, 10, 14, 12, 0, 0).unwrap();
+    let monday = Utc.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap();
+    assert_eq!(next_run("0 0 9 * * Mon", wednesday).unwrap(), monday);
+
+    let mut invalid = schedule_config(vec![]);
+    invalid.cron_expression = "每周一".to_string();
+    assert!(create_schedule(&db, fixture.project_id, fixture.user_id, invalid, wednesday).await.unwrap_err().is_validation_error());
+    let mut invalid = schedule_config(vec![ReportChannel::Webhook { url: "ftp://example.com".to_string() }]);
+    assert!(invalid.validate().is_err());
+    invalid.channels.clear();
+    invalid.template = Some("{{unknown}}".to_string());
+    assert!(invalid.validate().is_err());
+
+    let channels = vec![ReportChannel::Desktop, ReportChannel::Webhook { url: "https://example.com/hook".to_string() }];
+    let schedule = create_schedule(&db, fixture.project_id, fixture.user_id, schedule_config(channels.clone()), wednesday)
+        .await
+        .unwrap();
+    assert_eq!(schedule.next_run_at, monday);
+
+    let mut disabled = schedule_config(vec![]);
+    disabled.enabled = false;
+    create_schedule(&db, fixture.project_id, fixture.user_id, disabled, wednesday).await.unwrap();
+
+    // 尚未到期时不生成
+    assert!(run_due_schedules(&store, wednesday + Duration::days(1)).await.unwrap().is_empty());
+
+    let run_at = monday + Duration::minutes(1);
+    let generated = run_due_schedules(&store, run_at).await.unwrap();
+    assert_eq!(generated.len(), 1);
+    assert_eq!(generated[0].channels, channels);
+    assert_eq!(generated[0].report.schedule_id, Some(schedule.schedule_id));
+    assert!(generated[0].content.starts_with("<!DOCTYPE html>"));
+
+    let schedule = ReportScheduleRepository::new(db.clone()).find_by_id(schedule.schedule_id).await.unwrap().unwrap();
+    assert_eq!(schedule.last_run_at, Some(run_at.into()));
+    assert_eq!(schedule.next_run_at, monday + Duration::weeks(1));
+    assert!(run_due_schedules(&store, run_at).await.unwrap().is_empty());
+
+    let reports = ProjectReportRepository::new(db.clone()).find_by_project(fixture.project_id, 10).await.unwrap();
+    assert_eq!(reports.len(), 1);
+}
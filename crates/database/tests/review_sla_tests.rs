@@ -0,0 +1,242 @@
+//! 代码审查时限与超时改派测试
+
+use crate::common::setup_test_db;
+use chrono::{Duration, Utc};
+use codex_database::{
+    entities::domain_event::DomainEventType,
+    repository::{
+        AgentRepository, CodeReviewRepository, DomainEventRepository, ExecutionSessionRepository,
+        ProjectRepository, TaskRepository, UserRepository,
+        agent_repository::CreateAgentData,
+        code_review_repository::CreateCodeReviewData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    review_sla::{
+        assign_reviewer, evaluate_project, set_review_priority, ReviewDeadlinePolicy, Reviewer,
+    },
+    sla::SlaPolicy,
+    DatabaseConnection,
+};
+use codex_multi_agent::{Clock, ReviewPriority, TestClock};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    user_id: Uuid,
+    project_id: Uuid,
+    task_id: Uuid,
+    reviewers: [Uuid; 2],
+    review_id: Uuid,
+}
+
+async fn create_user(db: &DatabaseConnection) -> Uuid {
+    UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap()
+        .user_id
+}
+
+async fn create_agent(db: &DatabaseConnection, user_id: Uuid, name: &str, capability: &str) -> Uuid {
+    AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id,
+            name: name.to_string(),
+            description: None,
+            prompt_template: "你是一个Agent".to_string(),
+            capabilities: json!([capability]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap()
+        .agent_id
+}
+
+/// 创建项目、开发Agent、两个审查员Agent，以及由第一个审查员负责的审查
+async fn setup(db: &DatabaseConnection) -> Fixture {
+    let user_id = create_user(db).await;
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id,
+            name: "审查时限项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/review-sla".to_string(),
+        })
+        .await
+        .unwrap();
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "实现支付回调".to_string(),
+            description: "处理支付平台的异步回调".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let developer = create_agent(db, user_id, "开发Agent", "backend_development").await;
+    let reviewers = [
+        create_agent(db, user_id, "审查员A", "code_review").await,
+        create_agent(db, user_id, "审查员B", "code_review").await,
+    ];
+    let session = ExecutionSessionRepository::new(db.clone())
+        .create(CreateSessionData {
+            task_id: task.task_id,
+            agent_id: developer,
+            project_id: project.project_id,
+            git_branch: "feature/payment".to_string(),
+            base_commit: None,
+            execution_config: None,
+            timeout_minutes: 30,
+        })
+        .await
+        .unwrap();
+    let review = CodeReviewRepository::new(db.clone())
+        .create(CreateCodeReviewData {
+            task_id: task.task_id,
+            execution_session_id: session.session_id,
+            reviewer_agent_id: reviewers[0],
+            pull_request_url: "https://github.com/test/repo/pull/7".to_string(),
+            source_branch: "feature/payment".to_string(),
+            target_branch: "main".to_string(),
+            review_comments: json!([]),
+            code_changes: json!([]),
+            status: "pending".to_string(),
+            decision: None,
+            overall_comment: None,
+        })
+        .await
+        .unwrap();
+
+    Fixture {
+        user_id,
+        project_id: project.project_id,
+        task_id: task.task_id,
+        reviewers,
+        review_id: review.review_id,
+    }
+}
+
+#[tokio::test]
+async fn test_stale_review_is_reminded_reassigned_then_escalated() {
+    let db = setup_test_db().await;
+    let fixture = setup(&db).await;
+    let reviews = CodeReviewRepository::new(db.clone());
+
+    // 新审查默认正常优先级，截止时间为分配后48小时
+    let review = reviews.find_by_id(fixture.review_id).await.unwrap().unwrap();
+    let assigned_at = review.assigned_at.unwrap().with_timezone(&Utc);
+    assert_eq!(review.priority, "normal");
+    assert_eq!(review.review_deadline.unwrap().with_timezone(&Utc), assigned_at + Duration::hours(48));
+
+    ProjectRepository::new(db.clone())
+        .set_sla_policy(
+            fixture.project_id,
+            Some(SlaPolicy {
+                review_deadlines: ReviewDeadlinePolicy { max_reassignments: 1, ..Default::default() },
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    let review = set_review_priority(&db, fixture.review_id, ReviewPriority::Urgent).await.unwrap();
+    assert_eq!(review.review_deadline.unwrap().with_timezone(&Utc), assigned_at + Duration::hours(4));
+
+    // 剩余时间不足四分之一时提醒一次
+    let report = evaluate_project(&db, fixture.project_id, assigned_at + Duration::hours(2)).await.unwrap();
+    assert!(report.is_empty());
+    let report = evaluate_project(&db, fixture.project_id, assigned_at + Duration::minutes(210)).await.unwrap();
+    assert_eq!(report.reminders.len(), 1);
+    assert_eq!(report.reminders[0].reviewer, Reviewer::Agent(fixture.reviewers[0]));
+    let report = evaluate_project(&db, fixture.project_id, assigned_at + Duration::minutes(220)).await.unwrap();
+    assert!(report.is_empty());
+
+    // 超时后改派给另一个审查员，不会改派给提交变更的开发Agent
+    let reassigned_at = assigned_at + Duration::hours(5);
+    let report = evaluate_project(&db, fixture.project_id, reassigned_at).await.unwrap();
+    assert_eq!(report.reassignments.len(), 1);
+    assert_eq!(report.reassignments[0].reviewer, Reviewer::Agent(fixture.reviewers[1]));
+    assert_eq!(report.reassignments[0].previous_reviewer, Some(Reviewer::Agent(fixture.reviewers[0])));
+    let review = reviews.find_by_id(fixture.review_id).await.unwrap().unwrap();
+    assert_eq!(review.reviewer_agent_id, fixture.reviewers[1]);
+    assert_eq!(review.reassignment_count, 1);
+    assert!(review.reminded_at.is_none());
+    assert_eq!(review.review_deadline.unwrap().with_timezone(&Utc), reassigned_at + Duration::hours(4));
+
+    // 改派次数达到上限后升级为超时，只升级一次
+    let report = evaluate_project(&db, fixture.project_id, reassigned_at + Duration::hours(5)).await.unwrap();
+    assert_eq!(report.escalations.len(), 1);
+    assert!(report.reassignments.is_empty());
+    let report = evaluate_project(&db, fixture.project_id, reassigned_at + Duration::hours(6)).await.unwrap();
+    assert!(report.is_empty());
+    assert!(reviews.find_by_id(fixture.review_id).await.unwrap().unwrap().escalated_at.is_some());
+
+    let event_types: Vec<String> = DomainEventRepository::new(db.clone())
+        .find_by_aggregate_id(fixture.task_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|event| event.event_type)
+        .filter(|event_type| event_type.starts_with("Review"))
+        .collect();
+    assert_eq!(
+        event_types,
+        vec![
+            DomainEventType::ReviewReminderSent.to_string(),
+            DomainEventType::ReviewReassigned.to_string(),
+            DomainEventType::ReviewDeadlineMissed.to_string(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_assign_human_reviewer_and_policy_validation() {
+    let db = setup_test_db().await;
+    let fixture = setup(&db).await;
+
+    let clock = TestClock::new(Utc::now());
+    let outsider = create_user(&db).await;
+    let error = assign_reviewer(&db, fixture.review_id, Reviewer::User(outsider), fixture.user_id, &clock)
+        .await
+        .unwrap_err();
+    assert!(error.is_business_error());
+
+    let review = assign_reviewer(&db, fixture.review_id, Reviewer::User(fixture.user_id), fixture.user_id, &clock)
+        .await
+        .unwrap();
+    assert_eq!(review.reviewer_user_id, Some(fixture.user_id));
+    assert_eq!(review.reassignment_count, 0);
+    assert_eq!(review.assigned_at.unwrap().with_timezone(&Utc), clock.now());
+
+    // 人工审查者超时后，没有其他维护者时改派给审查员Agent
+    clock.advance(Duration::hours(49));
+    let report = evaluate_project(&db, fixture.project_id, clock.now()).await.unwrap();
+    assert_eq!(report.reassignments.len(), 1);
+    assert!(matches!(report.reassignments[0].reviewer, Reviewer::Agent(_)));
+    assert_eq!(report.reassignments[0].previous_reviewer, Some(Reviewer::User(fixture.user_id)));
+
+    let projects = ProjectRepository::new(db.clone());
+    for invalid in [
+        ReviewDeadlinePolicy { urgent_hours: 0, ..Default::default() },
+        ReviewDeadlinePolicy { reminder_ratio: 1.0, ..Default::default() },
+        ReviewDeadlinePolicy { max_reassignments: -1, ..Default::default() },
+    ] {
+        let policy = SlaPolicy { review_deadlines: invalid, ..Default::default() };
+        assert!(projects.set_sla_policy(fixture.project_id, Some(policy)).await.unwrap_err().is_validation_error());
+    }
+}
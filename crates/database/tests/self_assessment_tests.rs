@@ -0,0 +1,224 @@
+//! Agent自我评估与置信度校准测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::code_review::ReviewDecision,
+    repository::{
+        AgentRepository, CodeReviewRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository,
+        UserRepository,
+        agent_repository::CreateAgentData,
+        code_review_repository::CreateCodeReviewData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    self_assessment::{ConfidenceCalibrationConfig, SelfAssessmentService},
+    DatabaseConnection,
+};
+use codex_multi_agent::{AgentId, SelfAssessment, SimulationAgent};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    project_id: Uuid,
+    task_id: Uuid,
+    agent_id: Uuid,
+}
+
+async fn setup(db: &DatabaseConnection) -> Fixture {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "自评项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/assessment".to_string(),
+        })
+        .await
+        .unwrap();
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "开发Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个开发Agent".to_string(),
+            capabilities: json!(["backend_development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "实现登录接口".to_string(),
+            description: "用户名密码登录".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+
+    Fixture {
+        project_id: project.project_id,
+        task_id: task.task_id,
+        agent_id: agent.agent_id,
+    }
+}
+
+/// 创建已完成的执行会话，返回会话ID
+async fn completed_session(db: &DatabaseConnection, fixture: &Fixture) -> Uuid {
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let session = sessions
+        .create(CreateSessionData {
+            task_id: fixture.task_id,
+            agent_id: fixture.agent_id,
+            project_id: fixture.project_id,
+            git_branch: "feature/login".to_string(),
+            base_commit: None,
+            execution_config: None,
+            timeout_minutes: 30,
+        })
+        .await
+        .unwrap();
+    sessions.start_session(session.session_id).await.unwrap();
+    sessions
+        .complete_session(session.session_id, true, None, None, None)
+        .await
+        .unwrap();
+    session.session_id
+}
+
+/// 对执行会话提交审查决策
+async fn review(db: &DatabaseConnection, fixture: &Fixture, session_id: Uuid, decision: ReviewDecision) {
+    let reviews = CodeReviewRepository::new(db.clone());
+    let review = reviews
+        .create(CreateCodeReviewData {
+            task_id: fixture.task_id,
+            execution_session_id: session_id,
+            reviewer_agent_id: fixture.agent_id,
+            pull_request_url: "https://github.com/test/repo/pull/1".to_string(),
+            source_branch: "feature/login".to_string(),
+            target_branch: "main".to_string(),
+            review_comments: json!([]),
+            code_changes: json!([]),
+            status: "pending".to_string(),
+            decision: None,
+            overall_comment: None,
+        })
+        .await
+        .unwrap();
+    reviews.submit_decision(review.review_id, decision, None).await.unwrap();
+}
+
+fn assessment(confidence: f32) -> SelfAssessment {
+    SelfAssessment {
+        confidence,
+        summary: "接口已实现并通过单元测试".to_string(),
+        concerns: vec!["未覆盖账号锁定场景".to_string()],
+    }
+}
+
+#[tokio::test]
+async fn test_self_assessment_is_calibrated_by_review_outcomes() {
+    let db = setup_test_db().await;
+    let fixture = setup(&db).await;
+    let service = SelfAssessmentService::new(db.clone()).with_config(ConfidenceCalibrationConfig {
+        escalation_threshold: 0.7,
+        ..Default::default()
+    });
+
+    // 没有审查历史时，校准后的置信度等于上报值
+    let mut reviewed_sessions = Vec::new();
+    for _ in 0..2 {
+        let session_id = completed_session(&db, &fixture).await;
+        let recorded = service.record(session_id, &assessment(0.9)).await.unwrap();
+        assert!((recorded.calibrated_confidence - 0.9).abs() < 1e-6);
+        assert!(!recorded.escalated);
+        assert_eq!(recorded.concerns, json!(["未覆盖账号锁定场景"]));
+        reviewed_sessions.push(session_id);
+    }
+    for session_id in reviewed_sessions {
+        review(&db, &fixture, session_id, ReviewDecision::Rejected).await;
+    }
+
+    // 两次高置信度的结果都被拒绝：偏差 0.9，权重 2/7
+    let calibration = service.calibration(fixture.agent_id).await.unwrap();
+    assert_eq!(calibration.samples, 2);
+    assert!((calibration.bias - 0.9).abs() < 1e-6);
+    assert!((calibration.weight - 2.0 / 7.0).abs() < 1e-6);
+    let expected = 0.9 - 0.9 * 2.0 / 7.0;
+    assert!((calibration.calibrate(0.9) - expected).abs() < 1e-6);
+
+    let session_id = completed_session(&db, &fixture).await;
+    let recorded = service.record(session_id, &assessment(0.9)).await.unwrap();
+    assert!((recorded.calibrated_confidence - expected).abs() < 1e-6);
+    assert!(recorded.escalated);
+    assert!(recorded.review_decision.is_none());
+
+    let pending = service.pending_escalations().await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].session_id, session_id);
+
+    // 审查完成后不再等待人工审查
+    review(&db, &fixture, session_id, ReviewDecision::Approved).await;
+    assert!(service.pending_escalations().await.unwrap().is_empty());
+
+    // 校准置信度用于调度模拟中的任务分配
+    let mut agents = vec![
+        SimulationAgent {
+            agent_id: AgentId(fixture.agent_id),
+            capabilities: vec![],
+            available_from: None,
+            calibrated_confidence: None,
+            familiar_paths: vec![],
+            task_costs: Default::default(),
+        },
+        SimulationAgent {
+            agent_id: AgentId(Uuid::new_v4()),
+            capabilities: vec![],
+            available_from: None,
+            calibrated_confidence: Some(0.8),
+            familiar_paths: vec![],
+            task_costs: Default::default(),
+        },
+    ];
+    service.calibrate_simulation_agents(&mut agents).await.unwrap();
+    let calibrated = agents[0].calibrated_confidence.unwrap();
+    assert!(calibrated < 0.9);
+    assert!(agents[1].calibrated_confidence.is_none());
+}
+
+#[tokio::test]
+async fn test_self_assessment_validation() {
+    let db = setup_test_db().await;
+    let fixture = setup(&db).await;
+    let service = SelfAssessmentService::new(db.clone());
+    let session_id = completed_session(&db, &fixture).await;
+
+    let error = service.record(session_id, &assessment(1.5)).await.unwrap_err();
+    assert!(error.is_validation_error());
+    let error = service.record(session_id, &assessment(f32::NAN)).await.unwrap_err();
+    assert!(error.is_validation_error());
+
+    let error = service.record(Uuid::new_v4(), &assessment(0.5)).await.unwrap_err();
+    assert!(matches!(error, codex_database::DatabaseError::EntityNotFound { .. }));
+
+    service.record(session_id, &assessment(0.5)).await.unwrap();
+    let error = service.record(session_id, &assessment(0.6)).await.unwrap_err();
+    assert!(error.is_conflict_error());
+}
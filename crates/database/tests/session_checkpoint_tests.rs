@@ -0,0 +1,181 @@
+//! 执行会话检查点与恢复测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    repository::{
+        AgentRepository, ExecutionLogRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository,
+        UserRepository,
+        agent_repository::CreateAgentData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    session_checkpoint::{latest_checkpoint, resume_session, save_checkpoint, CheckpointData},
+    DatabaseConnection,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建一个运行中的执行会话，返回任务ID和会话ID
+async fn start_session(db: &DatabaseConnection) -> (Uuid, Uuid) {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "检查点项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/checkpoint".to_string(),
+        })
+        .await
+        .unwrap();
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "后端Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个后端开发Agent".to_string(),
+            capabilities: json!(["backend_development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "迁移订单服务".to_string(),
+            description: "把订单服务迁移到新的存储层".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let session = sessions
+        .create(CreateSessionData {
+            task_id: task.task_id,
+            agent_id: agent.agent_id,
+            project_id: project.project_id,
+            git_branch: "task/orders".to_string(),
+            base_commit: Some("base000".to_string()),
+            execution_config: None,
+            timeout_minutes: 120,
+        })
+        .await
+        .unwrap();
+    sessions.start_session(session.session_id).await.unwrap();
+    (task.task_id, session.session_id)
+}
+
+#[tokio::test]
+async fn test_save_checkpoint_increments_sequence() {
+    let db = setup_test_db().await;
+    let (_, session_id) = start_session(&db).await;
+    assert!(latest_checkpoint(&db, session_id).await.unwrap().is_none());
+
+    let first = save_checkpoint(
+        &db,
+        session_id,
+        CheckpointData { completed_steps: vec!["梳理订单表结构".to_string()], ..Default::default() },
+    )
+    .await
+    .unwrap();
+    assert_eq!(first.sequence, 1);
+
+    let second = save_checkpoint(
+        &db,
+        session_id,
+        CheckpointData {
+            completed_steps: vec!["梳理订单表结构".to_string(), "实现新的仓储接口".to_string()],
+            completion_percentage: Some(0.4),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(second.sequence, 2);
+    assert_eq!(latest_checkpoint(&db, session_id).await.unwrap(), Some(second));
+
+    // 每次保存都记录检查点日志
+    let logs = ExecutionLogRepository::new(db.clone()).find_by_session_id(session_id).await.unwrap();
+    let checkpoints: Vec<_> = logs
+        .iter()
+        .filter_map(|log| log.details.as_ref())
+        .filter(|details| details["checkpoint"] == json!(true))
+        .collect();
+    assert_eq!(checkpoints.len(), 2);
+    assert_eq!(checkpoints[1]["completion_percentage"].as_f64().map(|v| v as f32), Some(0.4));
+
+    // 非法数据和已结束的会话不能保存检查点
+    let invalid = CheckpointData { completion_percentage: Some(1.5), ..Default::default() };
+    assert!(save_checkpoint(&db, session_id, invalid).await.unwrap_err().is_validation_error());
+    ExecutionSessionRepository::new(db.clone())
+        .complete_session(session_id, true, Some("done111".to_string()), None, None)
+        .await
+        .unwrap();
+    assert!(save_checkpoint(&db, session_id, CheckpointData::default()).await.is_err());
+    assert!(resume_session(&db, session_id, None).await.unwrap_err().is_validation_error());
+}
+
+#[tokio::test]
+async fn test_resume_rebuilds_prompt_from_checkpoint() {
+    let db = setup_test_db().await;
+    let (task_id, session_id) = start_session(&db).await;
+    let sessions = ExecutionSessionRepository::new(db.clone());
+
+    save_checkpoint(
+        &db,
+        session_id,
+        CheckpointData {
+            completed_steps: vec!["梳理订单表结构".to_string(), "实现新的仓储接口".to_string()],
+            current_step: Some("迁移历史数据".to_string()),
+            scratch_notes: vec!["旧表的 status 字段存在空值".to_string()],
+            partial_diff: Some("+ fn migrate_orders() {}\n".to_string()),
+            last_commit: Some("wip222".to_string()),
+            completion_percentage: Some(0.5),
+        },
+    )
+    .await
+    .unwrap();
+
+    // 运行中的会话不能恢复
+    assert!(resume_session(&db, session_id, None).await.is_err());
+    sessions.timeout_session(session_id, "执行超时".to_string()).await.unwrap();
+
+    let resumed = resume_session(&db, session_id, None).await.unwrap();
+    assert_ne!(resumed.session.session_id, session_id);
+    assert_eq!(resumed.session.git_branch, "task/orders");
+    assert_eq!(resumed.session.base_commit.as_deref(), Some("wip222"));
+    assert_eq!(resumed.session.status, "pending");
+    assert_eq!(resumed.session.execution_config.as_ref().unwrap()["resumed_from"], json!(session_id));
+    assert_eq!(resumed.checkpoint.sequence, 1);
+
+    assert!(resumed.prompt.contains("迁移订单服务"));
+    assert!(resumed.prompt.contains("检查点 #1"));
+    assert!(resumed.prompt.contains("2. 实现新的仓储接口"));
+    assert!(resumed.prompt.contains("中断时正在进行: 迁移历史数据"));
+    assert!(resumed.prompt.contains("旧表的 status 字段存在空值"));
+    assert!(resumed.prompt.contains("+ fn migrate_orders() {}"));
+    let task = TaskRepository::new(db.clone()).find_by_id(task_id).await.unwrap().unwrap();
+    assert!(task.assignment_prompt.unwrap().starts_with(&resumed.prompt));
+
+    // 恢复后的会话继续递增检查点序号
+    sessions.start_session(resumed.session.session_id).await.unwrap();
+    let next = save_checkpoint(&db, resumed.session.session_id, CheckpointData::default()).await.unwrap();
+    assert_eq!(next.sequence, 2);
+}
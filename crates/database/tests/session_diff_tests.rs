@@ -0,0 +1,245 @@
+//! 执行会话代码差异采集测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    artifact_store::ArtifactStore,
+    repository::{
+        AgentRepository, CodeReviewRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository,
+        UserRepository,
+        agent_repository::CreateAgentData,
+        code_review_repository::CreateCodeReviewData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    session_diff::{
+        complete_session_with_diff, get_session_diff, parse_git_diff, parse_unified_diff, whole_file_hunk, DiffLineKind,
+        FileChangeType,
+    },
+};
+use serde_json::json;
+use std::path::Path;
+use uuid::Uuid;
+
+mod common;
+
+const SAMPLE_DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,4 @@
+ fn main() {
+-    println!(\"old\");
++    println!(\"new\");
++    println!(\"more\");
+ }
+diff --git a/README.md b/README.md
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/README.md
+@@ -0,0 +1 @@
++# 项目
+\\ No newline at end of file
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+index 4444444..0000000
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+diff --git a/a.txt b/b.txt
+similarity index 100%
+rename from a.txt
+rename to b.txt
+diff --git a/logo.png b/logo.png
+index 5555555..6666666 100644
+Binary files a/logo.png and b/logo.png differ
+";
+
+#[test]
+fn test_parse_git_diff() {
+    let files = parse_git_diff(SAMPLE_DIFF);
+    assert_eq!(files.len(), 5);
+
+    let lib = &files[0];
+    assert_eq!(lib.path, "src/lib.rs");
+    assert_eq!(lib.change_type, FileChangeType::Update);
+    assert_eq!((lib.additions, lib.deletions), (2, 1));
+    let hunk = &lib.hunks[0];
+    assert_eq!((hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines), (1, 3, 1, 4));
+    assert_eq!(hunk.lines[1].kind, DiffLineKind::Deletion);
+    assert_eq!(hunk.lines[1].old_line_number, Some(2));
+    assert_eq!(hunk.lines[3].content, "    println!(\"more\");");
+    assert_eq!(hunk.lines[3].new_line_number, Some(3));
+    assert_eq!(hunk.lines[4].old_line_number, Some(3));
+    assert_eq!(hunk.lines[4].new_line_number, Some(4));
+
+    assert_eq!(files[1].path, "README.md");
+    assert_eq!(files[1].change_type, FileChangeType::Add);
+    assert_eq!(files[1].hunks[0].lines.len(), 1);
+
+    assert_eq!(files[2].path, "old.txt");
+    assert_eq!(files[2].change_type, FileChangeType::Delete);
+    assert_eq!(files[2].deletions, 1);
+
+    assert_eq!(files[3].path, "a.txt");
+    assert_eq!(files[3].move_path.as_deref(), Some("b.txt"));
+    assert!(files[3].hunks.is_empty());
+
+    assert!(files[4].is_binary);
+    assert!(files[4].hunks.is_empty());
+}
+
+#[test]
+fn test_parse_unified_diff_and_whole_file_hunk() {
+    let hunks = parse_unified_diff("--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -2,2 +2,2 @@ fn main() {\n-old\n+new\n context\n\\ No newline at end of file\n");
+    assert_eq!(hunks.len(), 1);
+    assert_eq!(hunks[0].header, "@@ -2,2 +2,2 @@ fn main() {");
+    assert_eq!(hunks[0].lines.len(), 3);
+    assert_eq!(hunks[0].count(DiffLineKind::Addition), 1);
+    assert_eq!(hunks[0].lines[2].old_line_number, Some(3));
+    assert_eq!(hunks[0].lines[2].new_line_number, Some(3));
+
+    let added = whole_file_hunk("a\nb\n", DiffLineKind::Addition).unwrap();
+    assert_eq!(added.header, "@@ -0,0 +1,2 @@");
+    assert_eq!(added.lines[1].new_line_number, Some(2));
+    assert_eq!(added.lines[1].old_line_number, None);
+    let deleted = whole_file_hunk("a\n", DiffLineKind::Deletion).unwrap();
+    assert_eq!((deleted.old_start, deleted.old_lines, deleted.new_start, deleted.new_lines), (1, 1, 0, 0));
+    assert!(whole_file_hunk("", DiffLineKind::Addition).is_none());
+}
+
+fn git(workspace: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(["-c", "user.name=测试", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(workspace)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn commit_all(workspace: &Path, message: &str) -> String {
+    git(workspace, &["add", "-A"]);
+    git(workspace, &["commit", "-q", "-m", message]);
+    git(workspace, &["rev-parse", "HEAD"])
+}
+
+#[tokio::test]
+async fn test_capture_and_link_session_diff() {
+    let db = setup_test_db().await;
+    let workspace = tempfile::tempdir().unwrap();
+    let root = workspace.path();
+    git(root, &["init", "-q"]);
+    std::fs::write(root.join("main.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("notes.txt"), "todo\n").unwrap();
+    let base_commit = commit_all(root, "base");
+
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "差异项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: root.to_string_lossy().to_string(),
+        })
+        .await
+        .unwrap();
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "开发Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个测试Agent".to_string(),
+            capabilities: json!(["Development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "实现入口".to_string(),
+            description: "补充主函数".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let session = sessions
+        .create(CreateSessionData {
+            task_id: task.task_id,
+            agent_id: agent.agent_id,
+            project_id: project.project_id,
+            git_branch: "feature/diff".to_string(),
+            base_commit: Some(base_commit.clone()),
+            execution_config: None,
+            timeout_minutes: 30,
+        })
+        .await
+        .unwrap();
+    sessions.start_session(session.session_id).await.unwrap();
+
+    let reviews = CodeReviewRepository::new(db.clone());
+    let review_data = CreateCodeReviewData {
+        task_id: task.task_id,
+        execution_session_id: session.session_id,
+        reviewer_agent_id: agent.agent_id,
+        pull_request_url: "https://github.com/test/repo/pull/1".to_string(),
+        source_branch: "feature/diff".to_string(),
+        target_branch: "main".to_string(),
+        review_comments: json!([]),
+        code_changes: json!([]),
+        status: "pending".to_string(),
+        decision: None,
+        overall_comment: None,
+    };
+    let early_review = reviews.create(review_data.clone()).await.unwrap();
+    assert!(early_review.diff_artifact_id.is_none());
+
+    let store_root = tempfile::tempdir().unwrap();
+    let store = ArtifactStore::new(db.clone(), store_root.path());
+    assert!(get_session_diff(&store, session.session_id).await.unwrap().is_none());
+
+    std::fs::write(root.join("main.rs"), "fn main() {\n    run();\n}\n").unwrap();
+    std::fs::remove_file(root.join("notes.txt")).unwrap();
+    let final_commit = commit_all(root, "final");
+
+    complete_session_with_diff(&store, session.session_id, true, Some(final_commit.clone()), None, None)
+        .await
+        .unwrap();
+
+    let diff = get_session_diff(&store, session.session_id).await.unwrap().unwrap();
+    assert_eq!(diff.base_commit, base_commit);
+    assert_eq!(diff.final_commit, final_commit);
+    assert_eq!(diff.stats.files_changed, 2);
+    assert_eq!(diff.stats.additions, 3);
+    assert_eq!(diff.stats.deletions, 2);
+    assert_eq!(diff.files[0].path, "main.rs");
+    assert_eq!(diff.files[1].change_type, FileChangeType::Delete);
+
+    // 采集前创建的审查被关联，之后创建的审查自动关联
+    let artifact = store.list(session.session_id).await.unwrap();
+    assert_eq!(artifact.len(), 1);
+    let linked = reviews.find_by_id(early_review.review_id).await.unwrap().unwrap();
+    let artifact_id = linked.diff_artifact_id.unwrap();
+    let late_review = reviews.create(review_data).await.unwrap();
+    assert_eq!(late_review.diff_artifact_id, Some(artifact_id));
+}
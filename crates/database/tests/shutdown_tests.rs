@@ -0,0 +1,170 @@
+//! 优雅关闭测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::execution_session::ExecutionStatus,
+    repository::{
+        AgentRepository, ExecutionLogRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository,
+        UserRepository,
+        agent_repository::CreateAgentData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    shutdown::{recover_interrupted_sessions, PreviousShutdown, ShutdownCoordinator, ShutdownHookFuture},
+    DatabaseConnection, DatabaseError,
+};
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建一个运行中的执行会话
+async fn create_running_session(db: &DatabaseConnection) -> Uuid {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "关闭项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/shutdown".to_string(),
+        })
+        .await
+        .unwrap();
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "开发Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个测试Agent".to_string(),
+            capabilities: json!(["Development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "长时间任务".to_string(),
+            description: "执行中被关闭".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let session = sessions
+        .create(CreateSessionData {
+            task_id: task.task_id,
+            agent_id: agent.agent_id,
+            project_id: project.project_id,
+            git_branch: "feature/shutdown".to_string(),
+            base_commit: None,
+            execution_config: None,
+            timeout_minutes: 30,
+        })
+        .await
+        .unwrap();
+    sessions.start_session(session.session_id).await.unwrap();
+    session.session_id
+}
+
+#[tokio::test]
+async fn test_shutdown_checkpoints_sessions_and_writes_clean_marker() {
+    let db = setup_test_db().await;
+    let session_id = create_running_session(&db).await;
+    let data_dir = tempfile::tempdir().unwrap();
+    let marker_path = data_dir.path().join("shutdown.json");
+
+    let coordinator = Arc::new(ShutdownCoordinator::new(&marker_path).with_grace_period(Duration::from_secs(2)));
+    assert_eq!(coordinator.begin_run().await.unwrap(), PreviousShutdown::FirstRun);
+
+    // 执行中的工作收到关闭信号后结束
+    let guard = coordinator.begin_work().unwrap();
+    let signal = coordinator.token();
+    let worker = tokio::spawn(async move {
+        signal.cancelled().await;
+        drop(guard);
+    });
+
+    let flushed = Arc::new(AtomicBool::new(false));
+    let flag = flushed.clone();
+    coordinator.register_hook("刷新日志", move || -> ShutdownHookFuture {
+        Box::pin(async move {
+            flag.store(true, Ordering::SeqCst);
+            Ok(())
+        })
+    });
+    coordinator.register_hook("发送事件", || -> ShutdownHookFuture {
+        Box::pin(async { Err(DatabaseError::business_logic("连接已断开")) })
+    });
+
+    let report = coordinator.shutdown(Some(&db)).await;
+    worker.await.unwrap();
+    assert_eq!(report.abandoned_work, 0);
+    assert_eq!(report.interrupted_sessions, vec![session_id]);
+    assert!(flushed.load(Ordering::SeqCst));
+    assert_eq!(report.failed_steps.len(), 1);
+    assert!(report.failed_steps[0].starts_with("发送事件"));
+    assert!(coordinator.is_finished());
+    assert!(coordinator.begin_work().unwrap_err().is_shutting_down());
+
+    let logs = ExecutionLogRepository::new(db.clone()).find_by_session_id(session_id).await.unwrap();
+    assert!(logs.iter().any(|log| log.details.as_ref().is_some_and(|d| d["checkpoint"] == json!(true))));
+
+    // 下次启动读取到干净关闭标记，遗留会话标记为超时
+    let restarted = ShutdownCoordinator::new(&marker_path);
+    let previous = restarted.begin_run().await.unwrap();
+    let PreviousShutdown::Clean(marker) = &previous else {
+        panic!("期望干净关闭，实际为 {:?}", previous);
+    };
+    assert_eq!(marker.interrupted_sessions, vec![session_id]);
+    assert_eq!(recover_interrupted_sessions(&db, &previous).await.unwrap(), vec![session_id]);
+    let session = ExecutionSessionRepository::new(db.clone()).find_by_id(session_id).await.unwrap().unwrap();
+    assert_eq!(session.status, ExecutionStatus::Timeout.to_string());
+}
+
+#[tokio::test]
+async fn test_unclean_exit_detected_on_next_start() {
+    let db = setup_test_db().await;
+    let data_dir = tempfile::tempdir().unwrap();
+    let marker_path = data_dir.path().join("shutdown.json");
+
+    let coordinator = Arc::new(ShutdownCoordinator::new(&marker_path).with_grace_period(Duration::from_millis(50)));
+    coordinator.begin_run().await.unwrap();
+    let session_id = create_running_session(&db).await;
+
+    // 没有调用 shutdown 就退出
+    drop(coordinator);
+
+    let restarted = Arc::new(ShutdownCoordinator::new(&marker_path).with_grace_period(Duration::from_millis(50)));
+    let previous = restarted.begin_run().await.unwrap();
+    assert!(previous.is_unclean());
+    assert_eq!(recover_interrupted_sessions(&db, &previous).await.unwrap(), vec![session_id]);
+    let session = ExecutionSessionRepository::new(db.clone()).find_by_id(session_id).await.unwrap().unwrap();
+    assert!(session.error_message.unwrap().contains("异常退出"));
+
+    // 宽限期内未结束的工作计入放弃数
+    let _guard = restarted.begin_work().unwrap();
+    let report = restarted.shutdown(None).await;
+    assert_eq!(report.abandoned_work, 1);
+    assert!(report.failed_steps.is_empty());
+    assert!(!restarted.begin_run().await.unwrap().is_unclean());
+}
@@ -0,0 +1,178 @@
+//! 任务与里程碑SLA监控测试
+
+use crate::common::setup_test_db;
+use chrono::{Duration, Utc};
+use codex_database::{
+    entities::domain_event::DomainEventType,
+    repository::{
+        DomainEventRepository, ProjectRepository, SlaBreachRepository, TaskRepository, UserRepository,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    sla::{evaluate_all, evaluate_project, SlaPolicy},
+    DatabaseConnection,
+};
+use codex_multi_agent::{SlaSeverity, SlaType, TestClock};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+async fn create_project(db: &DatabaseConnection) -> Uuid {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "SLA项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/sla".to_string(),
+        })
+        .await
+        .unwrap()
+        .project_id
+}
+
+async fn create_task(db: &DatabaseConnection, project_id: Uuid, title: &str) -> Uuid {
+    TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: title.to_string(),
+            description: format!("{}的实现", title),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap()
+        .task_id
+}
+
+#[tokio::test]
+async fn test_task_breaches_escalate_and_resolve() {
+    let db = setup_test_db().await;
+    let project_id = create_project(&db).await;
+    let waiting = create_task(&db, project_id, "等待分配的任务").await;
+    let running = create_task(&db, project_id, "执行中的任务").await;
+    let tasks = TaskRepository::new(db.clone());
+    tasks.update_status(running, "in_progress").await.unwrap();
+
+    // 未配置SLA时不产生违约
+    let later = Utc::now() + Duration::minutes(45);
+    assert!(evaluate_project(&db, project_id, later).await.unwrap().is_empty());
+
+    ProjectRepository::new(db.clone())
+        .set_sla_policy(
+            project_id,
+            Some(SlaPolicy { max_pending_minutes: Some(30), max_execution_minutes: Some(60), ..Default::default() }),
+        )
+        .await
+        .unwrap();
+
+    // 待处理超过30分钟产生警告，执行45分钟尚未超限
+    let events = evaluate_project(&db, project_id, later).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].sla_type, SlaType::PendingTime);
+    assert_eq!(events[0].severity, SlaSeverity::Warning);
+    assert_eq!(events[0].subject_id, waiting.to_string());
+    assert_eq!(events[0].threshold_minutes, 30);
+    assert_eq!(events[0].metadata.timestamp, later);
+
+    // 同一违约再次评估不重复产生事件，超出一倍后升级为严重
+    assert!(evaluate_project(&db, project_id, later + Duration::minutes(5)).await.unwrap().is_empty());
+    let much_later = Utc::now() + Duration::minutes(70);
+    let events = evaluate_project(&db, project_id, much_later).await.unwrap();
+    let kinds: Vec<_> = events.iter().map(|e| (e.sla_type, e.severity)).collect();
+    assert!(kinds.contains(&(SlaType::PendingTime, SlaSeverity::Critical)));
+    assert!(kinds.contains(&(SlaType::ExecutionTime, SlaSeverity::Warning)));
+
+    let breaches = SlaBreachRepository::new(db.clone());
+    let open = breaches.find_open(Some(project_id)).await.unwrap();
+    assert_eq!(open.len(), 2);
+    assert_eq!(open[0].severity, "critical");
+
+    // 任务完成或开始执行后原违约解决，开始执行的任务改按执行时长评估
+    tasks.update_status(waiting, "in_progress").await.unwrap();
+    tasks.update_status(running, "completed").await.unwrap();
+    evaluate_project(&db, project_id, much_later).await.unwrap();
+    let open = breaches.find_open(Some(project_id)).await.unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].sla_type, "execution_time");
+    assert_eq!(open[0].subject_id, waiting.to_string());
+    assert_eq!(breaches.find_by_project(project_id, true).await.unwrap().len(), 3);
+
+    let recorded = DomainEventRepository::new(db.clone())
+        .find_by_event_type(&DomainEventType::SlaBreached.to_string())
+        .await
+        .unwrap();
+    assert_eq!(recorded.len(), 4);
+
+    // 关闭SLA后未解决的违约一并关闭
+    ProjectRepository::new(db.clone()).set_sla_policy(project_id, None).await.unwrap();
+    assert!(evaluate_all(&db, &TestClock::new(much_later)).await.unwrap().is_empty());
+    assert!(breaches.find_open(Some(project_id)).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_milestone_deadline_breaches() {
+    let db = setup_test_db().await;
+    let project_id = create_project(&db).await;
+    let task_id = create_task(&db, project_id, "里程碑任务").await;
+    let projects = ProjectRepository::new(db.clone());
+
+    let invalid = SlaPolicy { max_pending_minutes: Some(0), ..Default::default() };
+    assert!(projects.set_sla_policy(project_id, Some(invalid)).await.unwrap_err().is_validation_error());
+    projects
+        .set_sla_policy(project_id, Some(SlaPolicy { milestone_buffer_hours: Some(24), ..Default::default() }))
+        .await
+        .unwrap();
+
+    let deadline = Utc::now() + Duration::hours(12);
+    projects
+        .update_context(
+            project_id,
+            None,
+            Some(json!({
+                "milestones": [{
+                    "id": "m1",
+                    "name": "第一阶段",
+                    "deadline": deadline,
+                    "deliverables": ["接口"],
+                    "dependent_tasks": [task_id],
+                    "status": "planned",
+                    "completion_rate": 0.0,
+                    "risk_level": "low"
+                }]
+            })),
+        )
+        .await
+        .unwrap();
+
+    // 进入缓冲期为警告，错过截止日期为严重
+    let events = evaluate_project(&db, project_id, Utc::now()).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].sla_type, SlaType::MilestoneDeadline);
+    assert_eq!(events[0].severity, SlaSeverity::Warning);
+    assert_eq!(events[0].subject_id, "m1");
+    assert_eq!(events[0].deadline, Some(deadline));
+
+    let events = evaluate_project(&db, project_id, deadline + Duration::hours(1)).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].severity, SlaSeverity::Critical);
+    assert!(events[0].actual_minutes < 0);
+
+    // 里程碑完成后违约解决
+    TaskRepository::new(db.clone()).update_status(task_id, "completed").await.unwrap();
+    evaluate_project(&db, project_id, deadline + Duration::hours(1)).await.unwrap();
+    assert!(SlaBreachRepository::new(db.clone()).find_open(Some(project_id)).await.unwrap().is_empty());
+}
@@ -0,0 +1,244 @@
+//! 静态分析接入测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::code_review::ReviewDecision,
+    repository::{
+        AgentRepository, CodeReviewRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository,
+        UserRepository,
+        agent_repository::CreateAgentData,
+        code_review_repository::CreateCodeReviewData,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    static_analysis::{
+        configured_linters, evaluate_lint_gate, parse_clippy, parse_eslint, run_session_analysis, LintOutputFuture,
+        LintRunner, LintTool, LinterSpec,
+    },
+    DatabaseError,
+};
+use codex_multi_agent::{CodingStandards, IssueSeverity};
+use serde_json::json;
+use std::path::Path;
+use uuid::Uuid;
+
+mod common;
+
+const CLIPPY_OUTPUT: &str = r#"{"reason":"compiler-artifact","package_id":"app 0.1.0"}
+{"reason":"compiler-message","message":{"level":"warning","message":"unneeded `return` statement","code":{"code":"clippy::needless_return"},"spans":[{"file_name":"src/lib.rs","line_start":12,"column_start":5,"is_primary":true}]}}
+{"reason":"compiler-message","message":{"level":"warning","message":"unneeded `return` statement","code":{"code":"clippy::needless_return"},"spans":[{"file_name":"src/lib.rs","line_start":12,"column_start":5,"is_primary":true}]}}
+{"reason":"compiler-message","message":{"level":"error","message":"this comparison involving the minimum or maximum element for this type contains a case that is always true or always false","code":{"code":"clippy::absurd_extreme_comparisons"},"spans":[{"file_name":"src/math.rs","line_start":3,"column_start":8,"is_primary":false},{"file_name":"src/math.rs","line_start":4,"column_start":8,"is_primary":true}]}}
+{"reason":"compiler-message","message":{"level":"error","message":"aborting due to 1 previous error","code":null,"spans":[]}}
+{"reason":"build-finished","success":false}
+"#;
+
+fn eslint_output(workspace: &str) -> String {
+    json!([
+        {
+            "filePath": format!("{}/src/app.ts", workspace),
+            "messages": [
+                { "ruleId": "no-unused-vars", "severity": 1, "message": "'x' is defined but never used.", "line": 3, "column": 7 },
+                { "ruleId": "eqeqeq", "severity": 2, "message": "Expected '===' and instead saw '=='.", "line": 9, "column": 11 }
+            ]
+        },
+        {
+            "filePath": format!("{}/src/legacy.js", workspace),
+            "messages": [
+                { "ruleId": null, "fatal": true, "severity": 2, "message": "Parsing error: Unexpected token", "line": 1, "column": 1 }
+            ]
+        },
+        { "filePath": format!("{}/src/clean.ts", workspace), "messages": [] }
+    ])
+    .to_string()
+}
+
+fn language_config(language: &str, enforce_rules: bool) -> serde_json::Value {
+    json!({
+        "language": language,
+        "linter_config": null,
+        "formatter_config": null,
+        "style_guide_url": null,
+        "max_line_length": 100,
+        "indentation": { "indent_type": "spaces", "indent_size": 2 },
+        "naming_conventions": {
+            "variables": "camel_case",
+            "functions": "camel_case",
+            "classes": "pascal_case",
+            "constants": "screaming_snake_case",
+            "files": "kebab_case"
+        },
+        "enforce_rules": enforce_rules
+    })
+}
+
+/// 返回固定输出的 Linter 运行器
+struct FakeRunner;
+
+impl LintRunner for FakeRunner {
+    fn run<'a>(&'a self, spec: &'a LinterSpec, workspace: &'a Path) -> LintOutputFuture<'a> {
+        Box::pin(async move {
+            match spec.tool {
+                LintTool::Clippy => Ok(CLIPPY_OUTPUT.to_string()),
+                LintTool::Eslint => Ok(eslint_output(workspace.to_str().unwrap())),
+            }
+        })
+    }
+}
+
+#[test]
+fn test_parse_linter_output() {
+    let findings = parse_clippy(CLIPPY_OUTPUT).unwrap();
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0].file_path, "src/lib.rs");
+    assert_eq!((findings[0].line, findings[0].column), (Some(12), Some(5)));
+    assert_eq!(findings[0].rule.as_deref(), Some("clippy::needless_return"));
+    assert_eq!(findings[0].severity, IssueSeverity::Minor);
+    assert_eq!(findings[1].line, Some(4));
+    assert_eq!(findings[1].severity, IssueSeverity::Major);
+    assert!(parse_clippy("{not json").is_err());
+
+    let findings = parse_eslint(&eslint_output("/work/app"), Path::new("/work/app")).unwrap();
+    let summary: Vec<_> = findings
+        .iter()
+        .map(|finding| (finding.file_path.as_str(), finding.rule.as_deref(), finding.severity.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("src/app.ts", Some("no-unused-vars"), IssueSeverity::Minor),
+            ("src/app.ts", Some("eqeqeq"), IssueSeverity::Major),
+            ("src/legacy.js", None, IssueSeverity::Critical),
+        ]
+    );
+    assert!(parse_eslint("", Path::new("/work/app")).unwrap().is_empty());
+
+    // 相同 Linter 和命令的语言合并为一次运行，自定义命令单独运行
+    let mut standards = CodingStandards::default();
+    for (language, command) in [("TypeScript", None), ("JavaScript", None), ("Rust", Some("cargo clippy --message-format=json")), ("Go", None)] {
+        let mut config = language_config(language, true);
+        config["linter_command"] = json!(command);
+        standards.language_configs.insert(language.to_string(), serde_json::from_value(config).unwrap());
+    }
+    let specs = configured_linters(&standards);
+    assert_eq!(specs.len(), 2);
+    assert_eq!(specs[0].tool, LintTool::Clippy);
+    assert_eq!(specs[0].command, "cargo clippy --message-format=json");
+    assert_eq!(specs[1].languages, vec!["JavaScript".to_string(), "TypeScript".to_string()]);
+    assert_eq!(specs[1].command, LintTool::Eslint.default_command());
+}
+
+#[tokio::test]
+async fn test_findings_are_stored_and_gate_review_approval() -> Result<(), DatabaseError> {
+    let db = setup_test_db().await;
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await?;
+    let projects = ProjectRepository::new(db.clone());
+    let project = projects
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "静态分析项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/lint".to_string(),
+        })
+        .await?;
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "开发Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个开发Agent".to_string(),
+            capabilities: json!(["backend_development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await?;
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "修复lint".to_string(),
+            description: "静态分析参与质量门禁".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await?;
+    let session = ExecutionSessionRepository::new(db.clone())
+        .create(CreateSessionData {
+            task_id: task.task_id,
+            agent_id: agent.agent_id,
+            project_id: project.project_id,
+            git_branch: "fix/lint".to_string(),
+            base_commit: None,
+            execution_config: None,
+            timeout_minutes: 30,
+        })
+        .await?;
+
+    // Rust 强制执行规范，JavaScript 不强制；默认只允许 info 和 warning 级别
+    let mut standards = CodingStandards::default();
+    for (language, enforce) in [("Rust", true), ("TypeScript", true), ("JavaScript", false)] {
+        standards
+            .language_configs
+            .insert(language.to_string(), serde_json::from_value(language_config(language, enforce)).unwrap());
+    }
+    projects
+        .update_config(project.project_id, None, Some(serde_json::to_value(&standards).unwrap()), None)
+        .await?;
+
+    let report = run_session_analysis(&db, session.session_id, Path::new("/workspace/lint"), &FakeRunner).await?;
+    assert_eq!(report.runs.len(), 2);
+    assert!(report.runs.iter().all(|run| run.error.is_none()));
+    assert_eq!(report.findings.len(), 5);
+    let legacy = report.findings.iter().find(|finding| finding.file_path == "src/legacy.js").unwrap();
+    assert_eq!(legacy.language, "JavaScript");
+    assert_eq!(legacy.severity, "critical");
+
+    // 重新运行覆盖之前的发现
+    let report = run_session_analysis(&db, session.session_id, Path::new("/workspace/lint"), &FakeRunner).await?;
+    assert_eq!(report.findings.len(), 5);
+
+    // clippy 的 deny 级别和 ESLint 的 error 阻止批准，不强制的 JavaScript 不参与
+    let gate = evaluate_lint_gate(&db, session.session_id).await?;
+    assert!(!gate.passed);
+    let blocking: Vec<_> = gate.blocking.iter().map(|finding| finding.rule.as_deref().unwrap_or_default()).collect();
+    assert_eq!(blocking, vec!["eqeqeq", "clippy::absurd_extreme_comparisons"]);
+    assert_eq!(gate.counts.get("minor"), Some(&2));
+
+    let reviews = CodeReviewRepository::new(db.clone());
+    let review = reviews
+        .create(CreateCodeReviewData {
+            task_id: task.task_id,
+            execution_session_id: session.session_id,
+            reviewer_agent_id: agent.agent_id,
+            pull_request_url: "https://github.com/test/repo/pull/8".to_string(),
+            source_branch: "fix/lint".to_string(),
+            target_branch: "main".to_string(),
+            review_comments: json!([]),
+            code_changes: json!([]),
+            status: "pending".to_string(),
+            decision: None,
+            overall_comment: None,
+        })
+        .await?;
+    let error = reviews.submit_decision(review.review_id, ReviewDecision::Approved, None).await.unwrap_err();
+    assert!(error.to_string().contains("静态分析"));
+
+    // 门禁允许 error 级别后可以批准
+    standards.quality_gates.allowed_severity_levels.push(codex_multi_agent::project_management::SeverityLevel::Error);
+    projects
+        .update_config(project.project_id, None, Some(serde_json::to_value(&standards).unwrap()), None)
+        .await?;
+    assert!(evaluate_lint_gate(&db, session.session_id).await?.passed);
+    reviews.submit_decision(review.review_id, ReviewDecision::Approved, None).await?;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! 结构化输出解析测试
+
+use codex_database::structured_output::{extract_json, parse_task_draft};
+
+#[test]
+fn test_parse_task_draft_from_json_block() {
+    let text = r#"好的，以下是任务定义：
+
+```json
+{
+  "title": "实现登录限流",
+  "description": "对登录接口按IP限流",
+  "acceptance_criteria": ["每分钟最多5次", "超限返回429"],
+  "priority": "High"
+}
+```
+"#;
+
+    let draft = parse_task_draft(text).expect("应解析出任务草稿");
+    assert_eq!(draft.title, "实现登录限流");
+    assert_eq!(draft.description, "对登录接口按IP限流");
+    assert_eq!(draft.acceptance_criteria, vec!["每分钟最多5次", "超限返回429"]);
+    assert_eq!(draft.priority.as_deref(), Some("high"));
+}
+
+#[test]
+fn test_parse_task_draft_from_wrapped_json() {
+    let text = r#"{"task": {"name": "修复缓存", "acceptanceCriteria": "- 命中率恢复\n- 无内存泄漏"}}"#;
+
+    let draft = parse_task_draft(text).unwrap();
+    assert_eq!(draft.title, "修复缓存");
+    assert_eq!(draft.acceptance_criteria, vec!["命中率恢复", "无内存泄漏"]);
+}
+
+#[test]
+fn test_parse_task_draft_from_markdown() {
+    let text = "## 添加导出功能\n\n支持将报表导出为CSV。\n\n### 验收标准\n1. 导出文件包含表头\n2. 大数据量时分批写入\n\n### 备注\n后续支持Excel";
+
+    let draft = parse_task_draft(text).unwrap();
+    assert_eq!(draft.title, "添加导出功能");
+    assert_eq!(draft.acceptance_criteria, vec!["导出文件包含表头", "大数据量时分批写入"]);
+    assert!(draft.description.contains("支持将报表导出为CSV"));
+    assert!(draft.description.contains("后续支持Excel"));
+    assert!(!draft.description.contains("导出文件包含表头"));
+}
+
+#[test]
+fn test_parse_task_draft_checkboxes_and_plain_text() {
+    let text = "重构配置加载逻辑\n- [ ] 移除重复代码\n- [x] 增加单元测试";
+    let draft = parse_task_draft(text).unwrap();
+    assert_eq!(draft.title, "重构配置加载逻辑");
+    assert_eq!(draft.acceptance_criteria, vec!["移除重复代码", "增加单元测试"]);
+
+    assert!(parse_task_draft("   ").is_none());
+}
+
+#[test]
+fn test_extract_json_ignores_non_json_blocks() {
+    let text = "```rust\nfn main() {}\n```\n结果: {\"title\": \"x\"}";
+    let value = extract_json(text).unwrap();
+    assert_eq!(value["title"], "x");
+}
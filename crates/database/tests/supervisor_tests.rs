@@ -0,0 +1,181 @@
+//! 后台服务监督测试
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_database::operations::CancellationToken;
+use codex_database::supervisor::{
+    serve_health, HealthStatus, RestartPolicy, ServiceState, Supervisor, SupervisorHandle,
+};
+use codex_database::DatabaseError;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+const BACKOFF: Duration = Duration::from_millis(5);
+
+/// 等待某个服务达到指定状态
+async fn wait_for_state(handle: &SupervisorHandle, name: &str, state: ServiceState) {
+    for _ in 0..400 {
+        if handle.health().services.iter().any(|service| service.name == name && service.state == state) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    panic!("服务 {} 未达到 {:?}: {:?}", name, state, handle.health());
+}
+
+#[tokio::test]
+async fn test_restart_policies_and_clean_shutdown() {
+    let token = CancellationToken::new();
+    let failing_runs = Arc::new(AtomicU32::new(0));
+    let finishing_runs = Arc::new(AtomicU32::new(0));
+    let panicking_runs = Arc::new(AtomicU32::new(0));
+
+    let supervisor = Supervisor::new(token.clone())
+        .with_backoff(BACKOFF, BACKOFF * 4)
+        .add_service("long_running", RestartPolicy::Always, |token: CancellationToken| async move {
+            token.cancelled().await;
+            Ok(())
+        })
+        .add_service("failing", RestartPolicy::OnFailure { max_restarts: Some(2) }, {
+            let runs = failing_runs.clone();
+            move |_| {
+                let runs = runs.clone();
+                async move {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    Err(DatabaseError::business_logic("连接失败"))
+                }
+            }
+        })
+        .add_service("one_shot", RestartPolicy::OnFailure { max_restarts: None }, {
+            let runs = finishing_runs.clone();
+            move |_| {
+                let runs = runs.clone();
+                async move {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            }
+        })
+        .add_service("panicking", RestartPolicy::Never, {
+            let runs = panicking_runs.clone();
+            move |_| {
+                let runs = runs.clone();
+                async move {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    panic!("服务崩溃");
+                }
+            }
+        });
+    let handle = supervisor.handle();
+    let running = tokio::spawn(supervisor.run(handle.clone()));
+
+    wait_for_state(&handle, "failing", ServiceState::Failed).await;
+    wait_for_state(&handle, "one_shot", ServiceState::Stopped).await;
+    wait_for_state(&handle, "panicking", ServiceState::Failed).await;
+    wait_for_state(&handle, "long_running", ServiceState::Running).await;
+
+    // 首次运行加两次重启后不再重启
+    assert_eq!(failing_runs.load(Ordering::SeqCst), 3);
+    assert_eq!(finishing_runs.load(Ordering::SeqCst), 1);
+    assert_eq!(panicking_runs.load(Ordering::SeqCst), 1);
+
+    let health = handle.health();
+    assert_eq!(health.status, HealthStatus::Unhealthy);
+    let failing = health.services.iter().find(|service| service.name == "failing").unwrap();
+    assert_eq!(failing.restarts, 2);
+    assert!(failing.last_error.as_deref().unwrap().contains("连接失败"));
+    let panicking = health.services.iter().find(|service| service.name == "panicking").unwrap();
+    assert!(panicking.last_error.as_deref().unwrap().contains("服务崩溃"));
+
+    token.cancel();
+    tokio::time::timeout(Duration::from_secs(2), running).await.expect("关闭后监督器应结束").unwrap();
+    let health = handle.health();
+    assert!(health.shutting_down);
+    assert_eq!(
+        health.services.iter().find(|service| service.name == "long_running").unwrap().state,
+        ServiceState::Stopped
+    );
+}
+
+#[tokio::test]
+async fn test_always_policy_restarts_finished_service_until_shutdown() {
+    let token = CancellationToken::new();
+    let runs = Arc::new(AtomicU32::new(0));
+    let supervisor = Supervisor::new(token.clone()).with_backoff(BACKOFF, BACKOFF).add_service(
+        "ticker",
+        RestartPolicy::Always,
+        {
+            let runs = runs.clone();
+            move |_| {
+                let runs = runs.clone();
+                async move {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            }
+        },
+    );
+    let handle = supervisor.handle();
+    let running = tokio::spawn(supervisor.run(handle.clone()));
+
+    for _ in 0..400 {
+        if runs.load(Ordering::SeqCst) >= 3 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    assert!(runs.load(Ordering::SeqCst) >= 3);
+    assert_ne!(handle.health().status, HealthStatus::Unhealthy);
+
+    token.cancel();
+    tokio::time::timeout(Duration::from_secs(2), running).await.unwrap().unwrap();
+    assert_eq!(handle.health().services[0].state, ServiceState::Stopped);
+}
+
+async fn http_get(address: std::net::SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    stream
+        .write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes())
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn test_health_endpoint_reports_service_status() {
+    let token = CancellationToken::new();
+    let supervisor = Supervisor::new(token.clone())
+        .with_backoff(BACKOFF, BACKOFF)
+        .add_service("idle", RestartPolicy::Always, |token: CancellationToken| async move {
+            token.cancelled().await;
+            Ok(())
+        })
+        .add_service("broken", RestartPolicy::Never, |_| async { Err(DatabaseError::business_logic("端口被占用")) });
+    let handle = supervisor.handle();
+    let running = tokio::spawn(supervisor.run(handle.clone()));
+    wait_for_state(&handle, "broken", ServiceState::Failed).await;
+    wait_for_state(&handle, "idle", ServiceState::Running).await;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = tokio::spawn(serve_health(listener, handle.clone(), token.clone()));
+
+    let response = http_get(address, "/healthz").await;
+    assert!(response.starts_with("HTTP/1.1 503"), "{}", response);
+    assert!(response.ends_with(r#"{"status":"unhealthy"}"#));
+
+    let response = http_get(address, "/health").await;
+    let body: serde_json::Value = serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+    assert_eq!(body["services"][0]["state"], "running");
+    assert!(body["services"][1]["last_error"].as_str().unwrap().contains("端口被占用"));
+
+    assert!(http_get(address, "/metrics").await.starts_with("HTTP/1.1 404"));
+
+    token.cancel();
+    tokio::time::timeout(Duration::from_secs(2), server).await.unwrap().unwrap().unwrap();
+    tokio::time::timeout(Duration::from_secs(2), running).await.unwrap().unwrap();
+}
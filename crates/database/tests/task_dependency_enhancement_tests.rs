@@ -58,6 +58,9 @@ fn test_task_dependency_management() {
         execution_result: None,
         created_at: now,
         updated_at: now,
+        lease_owner: None,
+        lease_expires_at: None,
+        scope_path: None,
     };
 
     // 测试依赖计数功能
@@ -112,6 +115,9 @@ fn test_task_execution_result() {
         execution_result: None,
         created_at: now,
         updated_at: now,
+        lease_owner: None,
+        lease_expires_at: None,
+        scope_path: None,
     };
 
     // 测试执行结果记录
@@ -177,6 +183,9 @@ fn test_task_readiness_check() {
         execution_result: None,
         created_at: now,
         updated_at: now,
+        lease_owner: None,
+        lease_expires_at: None,
+        scope_path: None,
     };
 
     // 有依赖时不能开始
@@ -261,6 +270,9 @@ fn test_acceptance_criteria_evaluation() {
         })),
         created_at: now,
         updated_at: now,
+        lease_owner: None,
+        lease_expires_at: None,
+        scope_path: None,
     };
 
     // 测试验收标准评估
@@ -326,6 +338,9 @@ fn test_task_complexity_estimation() {
         execution_result: None,
         created_at: now,
         updated_at: now,
+        lease_owner: None,
+        lease_expires_at: None,
+        scope_path: None,
     };
 
     // 测试复杂度计算
@@ -24,6 +24,8 @@ async fn test_task_dependency_creation() {
         parent_task_id,
         child_task_id,
         dependency_type: DependencyType::Blocking.to_string(),
+        is_cross_project: false,
+        created_by: None,
         created_at: now,
     };
 
@@ -54,6 +56,8 @@ fn test_dependency_business_logic() {
         parent_task_id: Uuid::new_v4(),
         child_task_id: Uuid::new_v4(),
         dependency_type: DependencyType::Blocking.to_string(),
+        is_cross_project: false,
+        created_by: None,
         created_at: DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
             .unwrap()
             .with_timezone(&FixedOffset::east_opt(0).unwrap()),
@@ -74,6 +78,8 @@ fn test_soft_dependency() {
         parent_task_id: Uuid::new_v4(),
         child_task_id: Uuid::new_v4(),
         dependency_type: DependencyType::Soft.to_string(),
+        is_cross_project: false,
+        created_by: None,
         created_at: DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
             .unwrap()
             .with_timezone(&FixedOffset::east_opt(0).unwrap()),
@@ -92,6 +98,8 @@ fn test_resource_dependency() {
         parent_task_id: Uuid::new_v4(),
         child_task_id: Uuid::new_v4(),
         dependency_type: DependencyType::Resource.to_string(),
+        is_cross_project: false,
+        created_by: None,
         created_at: DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
             .unwrap()
             .with_timezone(&FixedOffset::east_opt(0).unwrap()),
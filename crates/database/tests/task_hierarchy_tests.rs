@@ -0,0 +1,149 @@
+//! 任务层级与进度汇总测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    repository::{
+        DomainEventRepository, ProjectRepository, TaskRepository, UserRepository,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+};
+use codex_multi_agent::TaskStatus;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建测试项目的辅助函数
+async fn create_test_project(db: &codex_database::DatabaseConnection) -> Uuid {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "层级任务项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/tmp/hierarchy".to_string(),
+        })
+        .await
+        .unwrap();
+
+    project.project_id
+}
+
+#[tokio::test]
+async fn test_subtask_progress_rolls_up_to_parent() {
+    let db = setup_test_db().await;
+    let project_id = create_test_project(&db).await;
+    let task_repo = TaskRepository::new(db.clone());
+
+    let parent = task_repo
+        .create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "实现用户模块".to_string(),
+            description: "拆分为多个子任务".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let first = task_repo
+        .create_subtask(parent.task_id, "数据模型".to_string(), "定义实体".to_string(), "development".to_string())
+        .await
+        .unwrap();
+    let second = task_repo
+        .create_subtask(parent.task_id, "接口实现".to_string(), "实现API".to_string(), "development".to_string())
+        .await
+        .unwrap();
+    assert_eq!(first.project_id, project_id);
+    assert_eq!(first.parent_task_id, Some(parent.task_id));
+
+    task_repo.update_status(first.task_id, "completed").await.unwrap();
+
+    let rollup = task_repo.calculate_progress_rollup(parent.task_id).await.unwrap();
+    assert_eq!(rollup.total_subtasks, 2);
+    assert_eq!(rollup.completed_subtasks, 1);
+    assert!((rollup.completion_percentage - 0.5).abs() < f32::EPSILON);
+    assert_eq!(rollup.derived_status, TaskStatus::InProgress);
+
+    let parent_after = task_repo.find_by_id(parent.task_id).await.unwrap().unwrap();
+    assert_eq!(parent_after.status, "in_progress");
+    assert!(parent_after.started_at.is_some());
+
+    task_repo.update_status(second.task_id, "completed").await.unwrap();
+
+    let parent_done = task_repo.find_by_id(parent.task_id).await.unwrap().unwrap();
+    assert_eq!(parent_done.status, "completed");
+    assert!(parent_done.completed_at.is_some());
+
+    // 每次子任务变化都会为父任务记录进度事件
+    let events = DomainEventRepository::new(db.clone())
+        .find_by_aggregate_id(parent.task_id)
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 4);
+    assert!(events.iter().all(|e| e.event_type == "TaskProgressUpdated"));
+}
+
+#[tokio::test]
+async fn test_nested_subtask_progress() {
+    let db = setup_test_db().await;
+    let project_id = create_test_project(&db).await;
+    let task_repo = TaskRepository::new(db.clone());
+
+    let root = task_repo
+        .create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "根任务".to_string(),
+            description: "多级任务".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let middle = task_repo
+        .create_subtask(root.task_id, "中间任务".to_string(), "".to_string(), "development".to_string())
+        .await
+        .unwrap();
+    let leaf_a = task_repo
+        .create_subtask(middle.task_id, "叶子A".to_string(), "".to_string(), "development".to_string())
+        .await
+        .unwrap();
+    task_repo
+        .create_subtask(middle.task_id, "叶子B".to_string(), "".to_string(), "development".to_string())
+        .await
+        .unwrap();
+
+    task_repo.update_status(leaf_a.task_id, "completed").await.unwrap();
+
+    // 进度事件沿祖先链逐级生成
+    let events = task_repo.recompute_parent_progress(leaf_a.task_id).await.unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].task_id.as_ref().map(|id| *id.as_uuid()), Some(middle.task_id));
+
+    let root_rollup = task_repo.calculate_progress_rollup(root.task_id).await.unwrap();
+    assert_eq!(root_rollup.total_subtasks, 1);
+    assert!((root_rollup.completion_percentage - 0.5).abs() < f32::EPSILON);
+    assert_eq!(root_rollup.derived_status, TaskStatus::InProgress);
+
+    // 父任务已完成时不能再追加子任务
+    task_repo.update_status(root.task_id, "completed").await.unwrap();
+    assert!(task_repo
+        .create_subtask(root.task_id, "追加".to_string(), "".to_string(), "development".to_string())
+        .await
+        .is_err());
+}
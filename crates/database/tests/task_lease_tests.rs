@@ -0,0 +1,122 @@
+//! 任务租约测试
+
+use crate::common::setup_test_db;
+use codex_database::repository::{
+    ProjectRepository, TaskRepository, UserRepository,
+    project_repository::CreateProjectData,
+    task_repository::CreateTaskData,
+    user_repository::CreateUserData,
+};
+use std::time::Duration;
+use uuid::Uuid;
+
+mod common;
+
+/// 创建测试任务的辅助函数
+async fn create_test_task(db: &codex_database::DatabaseConnection) -> Uuid {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "租约项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/tmp/lease".to_string(),
+        })
+        .await
+        .unwrap();
+
+    TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "实现登录接口".to_string(),
+            description: "只能由一个调度器执行".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap()
+        .task_id
+}
+
+#[tokio::test]
+async fn test_concurrent_acquire_grants_single_lease() {
+    let db = setup_test_db().await;
+    let task_id = create_test_task(&db).await;
+
+    let attempts = (0..8).map(|i| {
+        let tasks = TaskRepository::new(db.clone());
+        tokio::spawn(async move {
+            let owner = format!("scheduler-{}", i);
+            tasks.acquire_lease(task_id, &owner, Duration::from_secs(30)).await.unwrap().map(|_| owner)
+        })
+    });
+    let mut winners = Vec::new();
+    for attempt in attempts.collect::<Vec<_>>() {
+        if let Some(owner) = attempt.await.unwrap() {
+            winners.push(owner);
+        }
+    }
+    assert_eq!(winners.len(), 1);
+    let owner = winners.remove(0);
+
+    let tasks = TaskRepository::new(db.clone());
+    let task = tasks.find_by_id(task_id).await.unwrap().unwrap();
+    assert_eq!(task.lease_owner.as_deref(), Some(owner.as_str()));
+    assert!(task.lease_expires_at.is_some());
+
+    // 其他持有者不能续约或释放，持有者重复获取相当于续约
+    assert!(tasks.renew_lease(task_id, "intruder", Duration::from_secs(30)).await.unwrap_err().is_conflict_error());
+    assert!(!tasks.release_lease(task_id, "intruder").await.unwrap());
+    assert!(tasks.acquire_lease(task_id, &owner, Duration::from_secs(60)).await.unwrap().is_some());
+    assert!(tasks.acquire_lease(task_id, "intruder", Duration::from_secs(30)).await.unwrap().is_none());
+
+    // 释放后其他持有者可以获取；已完成的任务不能再加租约
+    assert!(tasks.release_lease(task_id, &owner).await.unwrap());
+    assert!(tasks.acquire_lease(task_id, "intruder", Duration::from_secs(30)).await.unwrap().is_some());
+    tasks.update_status(task_id, "completed").await.unwrap();
+    assert!(tasks.release_lease(task_id, "intruder").await.unwrap());
+    assert!(tasks.acquire_lease(task_id, "late", Duration::from_secs(30)).await.unwrap().is_none());
+    assert!(tasks.acquire_lease(Uuid::new_v4(), "late", Duration::from_secs(30)).await.is_err());
+    assert!(tasks.acquire_lease(task_id, "late", Duration::ZERO).await.unwrap_err().is_validation_error());
+}
+
+#[tokio::test]
+async fn test_expired_leases_are_reclaimed() {
+    let db = setup_test_db().await;
+    let task_id = create_test_task(&db).await;
+    let tasks = TaskRepository::new(db.clone());
+
+    tasks.acquire_lease(task_id, "worker-a", Duration::from_millis(20)).await.unwrap().unwrap();
+    tasks.update_status(task_id, "in_progress").await.unwrap();
+    assert!(tasks.reclaim_expired_leases().await.unwrap().is_empty());
+
+    // 过期后不能续约，其他持有者可以直接获取
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    assert!(tasks.renew_lease(task_id, "worker-a", Duration::from_secs(30)).await.is_err());
+
+    // 回收后进行中的任务回到待处理状态
+    let reclaimed = tasks.reclaim_expired_leases().await.unwrap();
+    assert_eq!(reclaimed.len(), 1);
+    assert_eq!(reclaimed[0].task_id, task_id);
+    assert_eq!(reclaimed[0].status, "pending");
+    assert!(reclaimed[0].lease_owner.is_none());
+    assert!(reclaimed[0].lease_expires_at.is_none());
+    assert!(tasks.reclaim_expired_leases().await.unwrap().is_empty());
+
+    tasks.acquire_lease(task_id, "worker-a", Duration::from_millis(20)).await.unwrap().unwrap();
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    let task = tasks.acquire_lease(task_id, "worker-b", Duration::from_secs(30)).await.unwrap().unwrap();
+    assert_eq!(task.lease_owner.as_deref(), Some("worker-b"));
+    assert!(!tasks.release_lease(task_id, "worker-a").await.unwrap());
+}
@@ -0,0 +1,232 @@
+//! 任务工作范围测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    artifact_store::ArtifactStore,
+    command_policy::{CommandPolicy, CommandPolicyEngine, CommandRequest, RULE_WRITE_OUTSIDE_SCOPE, RULE_WRITE_OUTSIDE_WORKSPACE},
+    repository::{
+        agent_repository::CreateAgentData, execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData, task_repository::CreateTaskData, user_repository::CreateUserData,
+        AgentRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository, UserRepository,
+    },
+    session_diff::complete_session_with_diff,
+    task_scope::{contains, detect_scope_conflicts, normalize_scope, overlaps},
+    DatabaseConnection,
+};
+use serde_json::json;
+use std::path::Path;
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    project_id: Uuid,
+    agents: Vec<Uuid>,
+}
+
+async fn setup(db: &DatabaseConnection, workspace: &Path) -> Fixture {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "单体仓库".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/monorepo.git".to_string(),
+            workspace_path: workspace.to_string_lossy().to_string(),
+        })
+        .await
+        .unwrap();
+    let mut agents = Vec::new();
+    for name in ["接口Agent", "前端Agent", "全栈Agent"] {
+        let agent = AgentRepository::new(db.clone())
+            .create(CreateAgentData {
+                user_id: user.user_id,
+                name: name.to_string(),
+                description: None,
+                prompt_template: "你是一个测试Agent".to_string(),
+                capabilities: json!(["Development"]),
+                config: json!({}),
+                git_config: None,
+            })
+            .await
+            .unwrap();
+        agents.push(agent.agent_id);
+    }
+    Fixture { project_id: project.project_id, agents }
+}
+
+async fn create_task(db: &DatabaseConnection, project_id: Uuid, title: &str, scope: Option<&str>) -> Uuid {
+    let tasks = TaskRepository::new(db.clone());
+    let task = tasks
+        .create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: title.to_string(),
+            description: title.to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+    tasks.set_scope_path(task.task_id, scope.map(str::to_string)).await.unwrap().task_id
+}
+
+#[test]
+fn test_scope_paths() {
+    assert_eq!(normalize_scope("./services/api/").unwrap().as_deref(), Some("services/api"));
+    assert_eq!(normalize_scope("services\\web").unwrap().as_deref(), Some("services/web"));
+    assert_eq!(normalize_scope(" . ").unwrap(), None);
+    for invalid in ["/etc", "../other", "services/../..", "C:\\repo"] {
+        assert!(normalize_scope(invalid).unwrap_err().is_validation_error(), "{}", invalid);
+    }
+
+    assert!(contains(Some("services/api"), "services/api/src/main.rs"));
+    assert!(contains(Some("services/api"), "./services/api"));
+    assert!(!contains(Some("services/api"), "services/api-gateway/main.rs"));
+    assert!(contains(None, "anything.rs"));
+
+    assert!(!overlaps(Some("services/api"), Some("services/web")));
+    assert!(overlaps(Some("services"), Some("services/web")));
+    assert!(overlaps(None, Some("services/web")));
+}
+
+#[tokio::test]
+async fn test_scope_assignment_policy_and_conflicts() {
+    let db = setup_test_db().await;
+    let workspace = tempfile::tempdir().unwrap();
+    let fixture = setup(&db, workspace.path()).await;
+    let tasks = TaskRepository::new(db.clone());
+
+    // 子任务继承父任务的范围，且不能超出父任务的范围
+    let api = create_task(&db, fixture.project_id, "接口改造", Some("services/api")).await;
+    let subtask = tasks
+        .create_subtask(api, "补充测试".to_string(), "接口测试".to_string(), "testing".to_string())
+        .await
+        .unwrap();
+    assert_eq!(subtask.scope_path.as_deref(), Some("services/api"));
+    assert!(tasks.set_scope_path(subtask.task_id, Some("services/api/tests".to_string())).await.is_ok());
+    for scope in [Some("services/web".to_string()), None] {
+        assert!(tasks.set_scope_path(subtask.task_id, scope).await.unwrap_err().is_validation_error());
+    }
+
+    // 分配提示词包含范围说明
+    let assigned = tasks.assign_to_agent(api, fixture.agents[0], "实现接口".to_string()).await.unwrap();
+    assert!(assigned.assignment_prompt.unwrap().contains("只允许修改 `services/api` 目录下的文件"));
+
+    // 命令策略上报写入范围之外的命令
+    let engine = CommandPolicyEngine::new(db.clone(), CommandPolicy::default()).unwrap();
+    let classify = |script: &str| {
+        let mut request = CommandRequest::new(
+            vec!["bash".to_string(), "-lc".to_string(), script.to_string()],
+            "/workspace/monorepo",
+        );
+        request.scope_path = Some("services/api".to_string());
+        engine.classify(&request)
+    };
+    assert!(!classify("touch services/api/src/new.rs").matched(RULE_WRITE_OUTSIDE_SCOPE));
+    assert!(classify("touch services/web/index.ts").matched(RULE_WRITE_OUTSIDE_SCOPE));
+    assert!(classify("cd services/api && rm -rf ../web").matched(RULE_WRITE_OUTSIDE_SCOPE));
+    let outside_workspace = classify("touch /tmp/marker");
+    assert!(outside_workspace.matched(RULE_WRITE_OUTSIDE_WORKSPACE) && !outside_workspace.matched(RULE_WRITE_OUTSIDE_SCOPE));
+
+    // 范围互不相交的 Agent 不冲突
+    let web = create_task(&db, fixture.project_id, "页面改造", Some("services/web")).await;
+    tasks.assign_to_agent(web, fixture.agents[1], "实现页面".to_string()).await.unwrap();
+    for task_id in [api, web] {
+        tasks.update_status(task_id, "in_progress").await.unwrap();
+    }
+    assert!(detect_scope_conflicts(&db, fixture.project_id).await.unwrap().is_empty());
+
+    // 未限定范围的任务与所有任务重叠，冲突只登记一次
+    let full = create_task(&db, fixture.project_id, "全局重构", None).await;
+    tasks.assign_to_agent(full, fixture.agents[2], "重构".to_string()).await.unwrap();
+    tasks.update_status(full, "in_progress").await.unwrap();
+    let conflicts = detect_scope_conflicts(&db, fixture.project_id).await.unwrap();
+    assert_eq!(conflicts.len(), 2);
+    assert!(conflicts.iter().all(|conflict| conflict.conflict_type == "resource"
+        && conflict.affected_tasks.to_string().contains(&full.to_string())));
+    assert!(detect_scope_conflicts(&db, fixture.project_id).await.unwrap().is_empty());
+}
+
+fn git(workspace: &Path, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["-c", "user.name=测试", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(workspace)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn commit_all(workspace: &Path, message: &str) -> Option<String> {
+    git(workspace, &["add", "-A"])?;
+    git(workspace, &["commit", "-q", "-m", message])?;
+    git(workspace, &["rev-parse", "HEAD"])
+}
+
+#[tokio::test]
+async fn test_session_outside_scope_fails() {
+    let db = setup_test_db().await;
+    let workspace = tempfile::tempdir().unwrap();
+    let root = workspace.path();
+    std::fs::create_dir_all(root.join("services/api")).unwrap();
+    std::fs::create_dir_all(root.join("services/web")).unwrap();
+    std::fs::write(root.join("services/api/main.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("services/web/index.ts"), "export {};\n").unwrap();
+    if git(root, &["init", "-q"]).is_none() {
+        return;
+    }
+    let base_commit = commit_all(root, "base").unwrap();
+
+    let fixture = setup(&db, root).await;
+    let task_id = create_task(&db, fixture.project_id, "接口改造", Some("services/api")).await;
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let store_root = tempfile::tempdir().unwrap();
+    let store = ArtifactStore::new(db.clone(), store_root.path());
+    let run = |file: &'static str| {
+        let sessions = &sessions;
+        let store = &store;
+        let base_commit = base_commit.clone();
+        let agent_id = fixture.agents[0];
+        let project_id = fixture.project_id;
+        async move {
+            let session = sessions
+                .create(CreateSessionData {
+                    task_id,
+                    agent_id,
+                    project_id,
+                    git_branch: "feature/api".to_string(),
+                    base_commit: Some(base_commit),
+                    execution_config: None,
+                    timeout_minutes: 30,
+                })
+                .await
+                .unwrap();
+            sessions.start_session(session.session_id).await.unwrap();
+            std::fs::write(root.join(file), format!("// {}\n", session.session_id)).unwrap();
+            let final_commit = commit_all(root, file).unwrap();
+            complete_session_with_diff(store, session.session_id, true, Some(final_commit), None, None)
+                .await
+                .unwrap()
+        }
+    };
+
+    let inside = run("services/api/main.rs").await;
+    assert_eq!(inside.success, Some(true));
+
+    let outside = run("services/web/index.ts").await;
+    assert_eq!(outside.success, Some(false));
+    assert!(outside.error_message.unwrap().contains("services/web/index.ts"));
+}
@@ -0,0 +1,131 @@
+//! 任务生命周期追踪集成测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::execution_log::EventType,
+    repository::{
+        AgentRepository, DomainEventRepository, ExecutionLogRepository, ExecutionSessionRepository,
+        LlmConversationRepository, LlmSessionRepository, ProjectRepository, TaskRepository, UserRepository,
+        agent_repository::CreateAgentData,
+        execution_log_repository::CreateExecutionLogData,
+        execution_session_repository::CreateSessionData,
+        llm_conversation_repository::CreateConversationMessageData,
+        llm_session_repository::CreateLlmSessionData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    task_trace::TraceStage,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+#[tokio::test]
+async fn test_trace_task_reconstructs_lifecycle() {
+    let db = setup_test_db().await;
+    let user_id = UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("trace_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("trace_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id;
+    let project_id = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: "追踪项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/trace.git".to_string(),
+        workspace_path: "/workspace/trace".to_string(),
+    }).await.unwrap().project_id;
+    let agent_id = AgentRepository::new(db.clone()).create(CreateAgentData {
+        user_id,
+        name: "追踪Agent".to_string(),
+        description: None,
+        prompt_template: "你是一个开发助手".to_string(),
+        capabilities: json!(["BackendDevelopment"]),
+        config: json!({}),
+        git_config: None,
+    }).await.unwrap().agent_id;
+
+    // 分解：LLM会话产出任务
+    let llm_repo = LlmSessionRepository::new(db.clone());
+    let llm_session = llm_repo.create(CreateLlmSessionData {
+        project_id,
+        user_id,
+        session_type: "decomposition".to_string(),
+        system_prompt: None,
+        decomposition_prompt: None,
+    }).await.unwrap();
+    LlmConversationRepository::new(db.clone()).create(CreateConversationMessageData {
+        session_id: llm_session.session_id,
+        role: "assistant".to_string(),
+        content: "拆分为两个子任务".to_string(),
+        message_order: 1,
+        token_count: Some(120),
+        model_used: Some("gpt-4".to_string()),
+        processing_time_ms: Some(2300),
+    }).await.unwrap();
+    llm_repo.update_result(llm_session.session_id, json!({"tasks": 1}), "completed".to_string()).await.unwrap();
+
+    let task_repo = TaskRepository::new(db.clone());
+    let task = task_repo.create(CreateTaskData {
+        project_id,
+        parent_task_id: None,
+        llm_session_id: Some(llm_session.session_id),
+        title: "实现登录接口".to_string(),
+        description: "支持用户名密码登录".to_string(),
+        task_type: "development".to_string(),
+    }).await.unwrap();
+
+    // 分配与执行
+    task_repo.assign_to_agent(task.task_id, agent_id, "请实现登录接口".to_string()).await.unwrap();
+    task_repo.update_status(task.task_id, "in_progress").await.unwrap();
+    let session_repo = ExecutionSessionRepository::new(db.clone());
+    let session = session_repo.create(CreateSessionData {
+        task_id: task.task_id,
+        agent_id,
+        project_id,
+        git_branch: "feature/login".to_string(),
+        base_commit: None,
+        execution_config: None,
+        timeout_minutes: 30,
+    }).await.unwrap();
+    session_repo.start_session(session.session_id).await.unwrap();
+    ExecutionLogRepository::new(db.clone()).create(CreateExecutionLogData {
+        session_id: session.session_id,
+        log_level: "warn".to_string(),
+        event_type: EventType::TestsFailed,
+        message: "2 个测试失败".to_string(),
+        details: None,
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+    }).await.unwrap();
+
+    // 子任务状态变化产生带关联ID的父任务进度事件
+    let subtask = task_repo
+        .create_subtask(task.task_id, "编写测试".to_string(), "覆盖登录流程".to_string(), "testing".to_string())
+        .await
+        .unwrap();
+    task_repo.update_status(subtask.task_id, "completed").await.unwrap();
+    let correlated = DomainEventRepository::new(db.clone()).find_by_correlation_id(task.task_id).await.unwrap();
+    assert!(!correlated.is_empty());
+
+    let trace = task_repo.trace_task(task.task_id).await.expect("重建时间线应该成功");
+    assert_eq!(trace.correlation_id, task.task_id);
+    // 唯一的子任务完成后父任务随之完成
+    assert_eq!(trace.status, "completed");
+
+    let kinds: Vec<&str> = trace.entries.iter().map(|e| e.kind.as_str()).collect();
+    for kind in ["llm_session_started", "llm_message", "llm_session_completed", "task_created", "task_assigned",
+        "task_started", "session_created", "session_started", "log", "task_completed", "domain_event"] {
+        assert!(kinds.contains(&kind), "时间线缺少 {kind}: {kinds:?}");
+    }
+    assert!(trace.entries.windows(2).all(|pair| pair[0].timestamp <= pair[1].timestamp));
+    assert!(kinds.iter().position(|k| *k == "llm_session_started") < kinds.iter().position(|k| *k == "session_started"));
+    assert!(trace.stage_entries(TraceStage::Assignment).count() >= 1);
+    assert!(trace.entries.iter().any(|e| e.message.contains("gpt-4") && e.message.contains("2300 ms")));
+    assert!(trace.longest_gap().is_some());
+
+    assert!(task_repo.trace_task(Uuid::new_v4()).await.is_err());
+}
@@ -0,0 +1,69 @@
+//! 技术债务快照测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    repository::{
+        ProjectRepository, TechnicalDebtSnapshotRepository, UserRepository,
+        project_repository::CreateProjectData,
+        user_repository::CreateUserData,
+    },
+    ContextBuilder,
+};
+use codex_multi_agent::llm_orchestration::{DebtTrend, DebtType};
+use uuid::Uuid;
+
+mod common;
+
+/// 创建工作空间指向指定目录的测试项目
+async fn create_test_project(db: &codex_database::DatabaseConnection, workspace: &std::path::Path) -> Uuid {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+
+    ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "债务项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: workspace.to_string_lossy().to_string(),
+        })
+        .await
+        .unwrap()
+        .project_id
+}
+
+#[tokio::test]
+async fn test_debt_snapshots_track_trend() {
+    let db = setup_test_db().await;
+    let workspace = tempfile::tempdir().unwrap();
+    std::fs::write(workspace.path().join("lib.rs"), "// TODO: 补充错误处理\npub fn f() {}\n").unwrap();
+    let project_id = create_test_project(&db, workspace.path()).await;
+
+    let builder = ContextBuilder::new(db.clone());
+    let first = builder.build(project_id).await.unwrap();
+    assert_eq!(first.codebase_info.technical_debt.estimated_hours, 1);
+    assert_eq!(first.codebase_info.technical_debt.trend, DebtTrend::Stable);
+    assert_eq!(first.codebase_info.technical_debt.main_debt_types, vec![DebtType::PoorDesign]);
+
+    // 新增大量FIXME后债务上升
+    let fixmes = (0..10).map(|i| format!("// FIXME: 临时实现 {i}\n")).collect::<String>();
+    std::fs::write(workspace.path().join("hacks.rs"), fixmes).unwrap();
+    let second = builder.build(project_id).await.unwrap();
+    assert_eq!(second.codebase_info.technical_debt.trend, DebtTrend::Increasing);
+    assert_eq!(second.codebase_info.technical_debt.severity_distribution.get("medium"), Some(&10));
+
+    let snapshots = TechnicalDebtSnapshotRepository::new(db.clone())
+        .find_by_project(project_id)
+        .await
+        .unwrap();
+    assert_eq!(snapshots.len(), 2);
+    assert!(snapshots.iter().any(|s| s.trend == "increasing"));
+}
@@ -0,0 +1,77 @@
+//! 遥测指标集成测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    repository::{
+        ProjectRepository, TaskRepository, UserRepository,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    telemetry::{self, DB_OPERATION_DURATION_SECONDS, QUEUE_DEPTH, TASKS_CREATED_TOTAL, TASK_STATUS_TRANSITIONS_TOTAL},
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use uuid::Uuid;
+
+mod common;
+
+#[tokio::test]
+async fn test_repository_metrics_and_scrape_endpoint() {
+    let db = setup_test_db().await;
+    telemetry::global().set_enabled(true);
+
+    let user_id = UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("telemetry_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("telemetry_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id;
+    let project_id = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: "遥测项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/telemetry.git".to_string(),
+        workspace_path: "/workspace/telemetry".to_string(),
+    }).await.unwrap().project_id;
+
+    let global = telemetry::global();
+    let created_before = global.counter_value(TASKS_CREATED_TOTAL, Vec::new());
+    let completed_before = global.counter_value(TASK_STATUS_TRANSITIONS_TOTAL, vec![("status", "completed".to_string())]);
+
+    let repo = TaskRepository::new(db.clone());
+    for i in 0..3 {
+        repo.create(CreateTaskData {
+            project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: format!("遥测任务{}", i),
+            description: "统计吞吐量".to_string(),
+            task_type: "development".to_string(),
+        }).await.unwrap();
+    }
+    let done = repo.find_by_project(project_id).await.unwrap()[0].task_id;
+    repo.update_status(done, "completed").await.unwrap();
+
+    assert_eq!(global.counter_value(TASKS_CREATED_TOTAL, Vec::new()) - created_before, 3);
+    assert_eq!(
+        global.counter_value(TASK_STATUS_TRANSITIONS_TOTAL, vec![("status", "completed".to_string())]) - completed_before,
+        1
+    );
+    assert!(global.histogram_count(DB_OPERATION_DURATION_SECONDS, vec![("operation", "task_insert".to_string())]) >= 3);
+
+    telemetry::refresh_queue_depths(&db).await.unwrap();
+    assert_eq!(global.gauge_value(QUEUE_DEPTH, vec![("queue", "pending_tasks".to_string())]), Some(2.0));
+
+    // 抓取端点返回 Prometheus 文本
+    let server = telemetry::serve_prometheus("127.0.0.1:0".parse().unwrap()).await.unwrap();
+    let mut stream = tokio::net::TcpStream::connect(server.local_addr()).await.unwrap();
+    stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    server.shutdown();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("sker_queue_depth{queue=\"pending_tasks\"} 2"));
+    assert!(response.contains("# TYPE sker_tasks_created_total counter"));
+}
@@ -0,0 +1,512 @@
+//! 多租户隔离测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::{
+        agent::AgentStatus,
+        conflict::{ConflictSeverity, ConflictStatus, ConflictType},
+        execution_log::EventType,
+    },
+    repository::{
+        agent_repository::CreateAgentData, conflict_repository::CreateConflictData,
+        execution_log_repository::CreateExecutionLogData, execution_session_repository::CreateSessionData,
+        human_decision_repository::CreateHumanDecisionData, llm_session_repository::CreateLlmSessionData,
+        llm_transcript_repository::CreateLlmTranscriptData, llm_transcript_repository::LlmTranscriptFilter,
+        organization_repository::CreateOrganizationData, project_repository::CreateProjectData,
+        requirement_document_repository::CreateRequirementDocumentData, task_repository::CreateTaskData,
+        user_repository::CreateUserData, AgentRepository, ConflictRepository, ExecutionLogRepository,
+        ExecutionSessionRepository, HumanDecisionRepository, LlmSessionRepository, LlmTranscriptRepository,
+        OrganizationRepository, ProjectMemberRepository, ProjectRepository, RequirementDocumentRepository,
+        TaskRepository, UserRepository,
+    },
+    DatabaseConnection, DatabaseError, TenantScope,
+};
+use codex_multi_agent::ProjectRole;
+use serde_json::json;
+use uuid::Uuid;
+
+mod common;
+
+async fn create_user(db: &DatabaseConnection) -> Uuid {
+    UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("tenant_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("tenant_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap()
+        .user_id
+}
+
+fn is_not_found(err: &DatabaseError) -> bool {
+    matches!(err, DatabaseError::EntityNotFound { .. })
+}
+
+fn project_data(user_id: Uuid, name: &str) -> CreateProjectData {
+    CreateProjectData {
+        user_id,
+        name: name.to_string(),
+        description: None,
+        repository_url: "https://github.com/test/repo.git".to_string(),
+        workspace_path: format!("/workspace/{}", name),
+    }
+}
+
+fn task_data(project_id: Uuid, title: &str) -> CreateTaskData {
+    CreateTaskData {
+        project_id,
+        parent_task_id: None,
+        llm_session_id: None,
+        title: title.to_string(),
+        description: "租户隔离".to_string(),
+        task_type: "development".to_string(),
+    }
+}
+
+fn agent_data(user_id: Uuid, name: &str) -> CreateAgentData {
+    CreateAgentData {
+        user_id,
+        name: name.to_string(),
+        description: None,
+        prompt_template: "你是一个测试Agent".to_string(),
+        capabilities: json!(["Development"]),
+        config: json!({}),
+        git_config: None,
+    }
+}
+
+fn session_data(project_id: Uuid, task_id: Uuid, agent_id: Uuid) -> CreateSessionData {
+    CreateSessionData {
+        task_id,
+        agent_id,
+        project_id,
+        git_branch: "feature/tenant".to_string(),
+        base_commit: None,
+        execution_config: None,
+        timeout_minutes: 30,
+    }
+}
+
+fn log_data(session_id: Uuid) -> CreateExecutionLogData {
+    CreateExecutionLogData {
+        session_id,
+        log_level: "info".to_string(),
+        event_type: EventType::TestRun,
+        message: "运行测试".to_string(),
+        details: None,
+        timestamp_ms: 1,
+    }
+}
+
+fn conflict_data(project_id: Option<Uuid>) -> CreateConflictData {
+    CreateConflictData {
+        conflict_type: ConflictType::TaskDependency,
+        severity: ConflictSeverity::High,
+        title: "依赖冲突".to_string(),
+        description: "租户隔离".to_string(),
+        related_entities: json!([]),
+        affected_tasks: json!([]),
+        affected_agents: json!([]),
+        project_id,
+    }
+}
+
+fn decision_data(conflict_id: Uuid, user_id: Uuid) -> CreateHumanDecisionData {
+    CreateHumanDecisionData {
+        conflict_id,
+        user_id,
+        decision_type: "approve".to_string(),
+        decision_data: None,
+        reasoning: None,
+        affected_entities: json!([]),
+        follow_up_actions: json!([]),
+    }
+}
+
+/// 爱丽丝和鲍勃各自的项目，鲍勃的项目下有一个任务、Agent和执行会话
+struct TwoTenants {
+    alice: Uuid,
+    bob: Uuid,
+    alice_project: Uuid,
+    bob_project: Uuid,
+    bob_task: Uuid,
+    bob_agent: Uuid,
+    bob_session: Uuid,
+}
+
+async fn two_tenants(db: &DatabaseConnection) -> TwoTenants {
+    let alice = create_user(db).await;
+    let bob = create_user(db).await;
+    let projects = ProjectRepository::new(db.clone());
+    let alice_project = projects.create(project_data(alice, "alice")).await.unwrap().project_id;
+    let bob_project = projects.create(project_data(bob, "bob")).await.unwrap().project_id;
+    let bob_task = TaskRepository::new(db.clone())
+        .create(task_data(bob_project, "鲍勃的任务"))
+        .await
+        .unwrap()
+        .task_id;
+    let bob_agent = AgentRepository::new(db.clone()).create(agent_data(bob, "鲍勃的Agent")).await.unwrap().agent_id;
+    let bob_session = ExecutionSessionRepository::new(db.clone())
+        .create(session_data(bob_project, bob_task, bob_agent))
+        .await
+        .unwrap()
+        .session_id;
+    TwoTenants { alice, bob, alice_project, bob_project, bob_task, bob_agent, bob_session }
+}
+
+fn transcript_data(project_id: Option<Uuid>) -> CreateLlmTranscriptData {
+    CreateLlmTranscriptData {
+        call_kind: "decomposition".to_string(),
+        provider: "test".to_string(),
+        model: "test-model".to_string(),
+        prompt: "分解需求".to_string(),
+        request_params: None,
+        response: Some("1. 实现".to_string()),
+        error_message: None,
+        tokens_used: Some(10),
+        latency_ms: 5,
+        project_id,
+        task_id: None,
+        llm_session_id: None,
+        execution_session_id: None,
+        conversation_id: None,
+    }
+}
+
+#[tokio::test]
+async fn test_user_scope_blocks_cross_tenant_reads_and_writes() {
+    let db = setup_test_db().await;
+    let alice = create_user(&db).await;
+    let bob = create_user(&db).await;
+    let projects = ProjectRepository::new(db.clone());
+    let alice_project = projects.create(project_data(alice, "alice")).await.unwrap();
+    let bob_project = projects.create(project_data(bob, "bob")).await.unwrap();
+    let bob_task = TaskRepository::new(db.clone())
+        .create(task_data(bob_project.project_id, "鲍勃的任务"))
+        .await
+        .unwrap();
+    let bob_document = RequirementDocumentRepository::new(db.clone())
+        .create(CreateRequirementDocumentData {
+            project_id: bob_project.project_id,
+            title: "需求".to_string(),
+            content: "内容".to_string(),
+            document_type: "requirement".to_string(),
+        })
+        .await
+        .unwrap();
+    let bob_session = LlmSessionRepository::new(db.clone())
+        .create(CreateLlmSessionData {
+            project_id: bob_project.project_id,
+            user_id: bob,
+            session_type: "decomposition".to_string(),
+            system_prompt: None,
+            decomposition_prompt: None,
+        })
+        .await
+        .unwrap();
+    let bob_transcript = LlmTranscriptRepository::new(db.clone())
+        .create(transcript_data(Some(bob_project.project_id)))
+        .await
+        .unwrap();
+
+    let scope = TenantScope::user(alice);
+
+    // 读：范围外的记录视同不存在
+    let scoped_projects = ProjectRepository::new(db.clone()).with_scope(scope);
+    let visible: Vec<Uuid> = scoped_projects.find_all().await.unwrap().iter().map(|p| p.project_id).collect();
+    assert_eq!(visible, vec![alice_project.project_id]);
+    assert!(scoped_projects.find_by_id(bob_project.project_id).await.unwrap().is_none());
+    assert!(scoped_projects.find_by_user(bob).await.unwrap().is_empty());
+
+    let scoped_tasks = TaskRepository::new(db.clone()).with_scope(scope);
+    assert!(scoped_tasks.find_by_id(bob_task.task_id).await.unwrap().is_none());
+    assert!(scoped_tasks.find_by_project(bob_project.project_id).await.unwrap().is_empty());
+
+    let scoped_documents = RequirementDocumentRepository::new(db.clone()).with_scope(scope);
+    assert!(scoped_documents.find_by_id(bob_document.document_id).await.unwrap().is_none());
+    let scoped_sessions = LlmSessionRepository::new(db.clone()).with_scope(scope);
+    assert!(scoped_sessions.find_by_id(bob_session.session_id).await.unwrap().is_none());
+    let scoped_transcripts = LlmTranscriptRepository::new(db.clone()).with_scope(scope);
+    assert!(scoped_transcripts.find_by_id(bob_transcript.transcript_id).await.unwrap().is_none());
+    let listed = scoped_transcripts
+        .find(&LlmTranscriptFilter {
+            project_id: Some(bob_project.project_id),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(listed.is_empty());
+
+    // 写：范围外的记录不能更新、删除，也不能在范围外的项目下创建记录
+    let err = scoped_projects.update_status(bob_project.project_id, "archived").await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped_projects.delete(bob_project.project_id).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped_tasks.update_status(bob_task.task_id, "cancelled").await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped_tasks.delete(bob_task.task_id).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped_tasks.create(task_data(bob_project.project_id, "越权任务")).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped_documents.delete(bob_document.document_id).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped_sessions
+        .update_result(bob_session.session_id, serde_json::json!({}), "completed".to_string())
+        .await
+        .unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped_transcripts.create(transcript_data(Some(bob_project.project_id))).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped_transcripts.create(transcript_data(None)).await.unwrap_err();
+    assert!(err.is_validation_error());
+    let err = scoped_projects.create(project_data(bob, "冒名项目")).await.unwrap_err();
+    assert!(err.is_validation_error());
+
+    // 鲍勃的数据没有被改动
+    let unchanged = ProjectRepository::new(db.clone()).find_by_id(bob_project.project_id).await.unwrap().unwrap();
+    assert_eq!(unchanged.status, "active");
+    let unchanged = TaskRepository::new(db.clone()).find_by_id(bob_task.task_id).await.unwrap().unwrap();
+    assert_eq!(unchanged.status, "pending");
+    assert!(RequirementDocumentRepository::new(db.clone())
+        .find_by_id(bob_document.document_id)
+        .await
+        .unwrap()
+        .is_some());
+
+    // 范围内的记录照常读写
+    let alice_task = scoped_tasks.create(task_data(alice_project.project_id, "爱丽丝的任务")).await.unwrap();
+    assert_eq!(scoped_tasks.find_by_project(alice_project.project_id).await.unwrap().len(), 1);
+    scoped_tasks.delete(alice_task.task_id).await.unwrap();
+
+    // 加入鲍勃的项目后可以访问
+    ProjectMemberRepository::new(db.clone())
+        .add_member(bob_project.project_id, alice, ProjectRole::Viewer, Some(bob))
+        .await
+        .unwrap();
+    assert!(scoped_projects.find_by_id(bob_project.project_id).await.unwrap().is_some());
+    assert!(scoped_tasks.find_by_id(bob_task.task_id).await.unwrap().is_some());
+    assert_eq!(scoped_projects.find_all().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_organization_scope_limits_projects_to_organization() {
+    let db = setup_test_db().await;
+    let owner = create_user(&db).await;
+    let organizations = OrganizationRepository::new(db.clone());
+    let mut organization_ids = Vec::new();
+    for _ in 0..2 {
+        let organization = organizations
+            .create(CreateOrganizationData {
+                name: format!("组织_{}", &Uuid::new_v4().to_string()[..8]),
+                description: None,
+                owner_id: owner,
+                settings: None,
+            })
+            .await
+            .unwrap();
+        organization_ids.push(organization.organization_id);
+    }
+    let first = TenantScope::organization(organization_ids[0]);
+    let second = TenantScope::organization(organization_ids[1]);
+
+    // 组织范围内创建的项目自动归属于该组织
+    let project = ProjectRepository::new(db.clone())
+        .with_scope(first)
+        .create(project_data(owner, "org"))
+        .await
+        .unwrap();
+    assert_eq!(project.organization_id, Some(organization_ids[0]));
+    let task = TaskRepository::new(db.clone())
+        .with_scope(first)
+        .create(task_data(project.project_id, "组织任务"))
+        .await
+        .unwrap();
+
+    let other_projects = ProjectRepository::new(db.clone()).with_scope(second);
+    assert!(other_projects.find_all().await.unwrap().is_empty());
+    assert!(other_projects.find_by_id(project.project_id).await.unwrap().is_none());
+    let other_tasks = TaskRepository::new(db.clone()).with_scope(second);
+    assert!(other_tasks.find_by_id(task.task_id).await.unwrap().is_none());
+    assert!(is_not_found(&other_tasks.delete(task.task_id).await.unwrap_err()));
+
+    // 未归属组织的个人项目不在任何组织范围内
+    let personal = ProjectRepository::new(db.clone()).create(project_data(owner, "personal")).await.unwrap();
+    let first_projects = ProjectRepository::new(db.clone()).with_scope(first);
+    assert!(first_projects.find_by_id(personal.project_id).await.unwrap().is_none());
+    assert_eq!(first_projects.find_all().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_agent_scope_isolates_agents() {
+    let db = setup_test_db().await;
+    let tenants = two_tenants(&db).await;
+    let scoped = AgentRepository::new(db.clone()).with_scope(TenantScope::user(tenants.alice));
+
+    assert!(scoped.find_by_id(tenants.bob_agent).await.unwrap().is_none());
+    assert!(scoped.find_by_user_id(tenants.bob).await.unwrap().is_empty());
+    assert!(scoped.find_idle_agents().await.unwrap().is_empty());
+    assert!(scoped.find_best_match(&[], false).await.unwrap().is_none());
+
+    let err = scoped.update_status(tenants.bob_agent, AgentStatus::Working, None).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped.delete(tenants.bob_agent).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped.create(agent_data(tenants.bob, "冒名Agent")).await.unwrap_err();
+    assert!(is_not_found(&err));
+    assert!(AgentRepository::new(db.clone()).find_by_id(tenants.bob_agent).await.unwrap().is_some());
+
+    let alice_agent = scoped.create(agent_data(tenants.alice, "爱丽丝的Agent")).await.unwrap();
+    assert_eq!(scoped.find_idle_agents().await.unwrap().len(), 1);
+    scoped.delete(alice_agent.agent_id).await.unwrap();
+
+    // 组织范围内创建的Agent归属该组织，其他组织看不到
+    let organization_id = OrganizationRepository::new(db.clone())
+        .create(CreateOrganizationData {
+            name: format!("组织_{}", &Uuid::new_v4().to_string()[..8]),
+            description: None,
+            owner_id: tenants.bob,
+            settings: None,
+        })
+        .await
+        .unwrap()
+        .organization_id;
+    let shared = AgentRepository::new(db.clone())
+        .with_scope(TenantScope::organization(organization_id))
+        .create(agent_data(tenants.bob, "共享Agent"))
+        .await
+        .unwrap();
+    assert_eq!(shared.organization_id, Some(organization_id));
+    assert!(scoped.find_by_id(shared.agent_id).await.unwrap().is_none());
+    let other_organization = AgentRepository::new(db.clone()).with_scope(TenantScope::organization(Uuid::new_v4()));
+    assert!(other_organization.find_by_id(shared.agent_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_execution_session_scope_isolates_sessions() {
+    let db = setup_test_db().await;
+    let tenants = two_tenants(&db).await;
+    let scoped = ExecutionSessionRepository::new(db.clone()).with_scope(TenantScope::user(tenants.alice));
+
+    assert!(scoped.find_by_id(tenants.bob_session).await.unwrap().is_none());
+    assert!(scoped.find_by_project_id(tenants.bob_project).await.unwrap().is_empty());
+    assert!(scoped.find_by_agent_id(tenants.bob_agent).await.unwrap().is_empty());
+    assert!(scoped.find_pending_sessions().await.unwrap().is_empty());
+    assert_eq!(scoped.get_session_statistics(None).await.unwrap().total_sessions, 0);
+
+    let err = scoped.start_session(tenants.bob_session).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped.delete(tenants.bob_session).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped
+        .create(session_data(tenants.bob_project, tenants.bob_task, tenants.bob_agent))
+        .await
+        .unwrap_err();
+    assert!(is_not_found(&err));
+    let unchanged = ExecutionSessionRepository::new(db.clone()).find_by_id(tenants.bob_session).await.unwrap().unwrap();
+    assert_eq!(unchanged.status, "pending");
+
+    let alice_task = TaskRepository::new(db.clone())
+        .create(task_data(tenants.alice_project, "爱丽丝的任务"))
+        .await
+        .unwrap();
+    let alice_session = scoped
+        .create(session_data(tenants.alice_project, alice_task.task_id, tenants.bob_agent))
+        .await
+        .unwrap();
+    scoped.start_session(alice_session.session_id).await.unwrap();
+    assert_eq!(scoped.get_session_statistics(None).await.unwrap().total_sessions, 1);
+}
+
+#[tokio::test]
+async fn test_execution_log_scope_follows_session() {
+    let db = setup_test_db().await;
+    let tenants = two_tenants(&db).await;
+    let bob_log = ExecutionLogRepository::new(db.clone()).create(log_data(tenants.bob_session)).await.unwrap();
+    let scoped = ExecutionLogRepository::new(db.clone()).with_scope(TenantScope::user(tenants.alice));
+
+    assert!(scoped.find_by_id(bob_log.log_id).await.unwrap().is_none());
+    assert!(scoped.find_by_session_id(tenants.bob_session).await.unwrap().is_empty());
+    assert!(scoped.find_by_log_level("info").await.unwrap().is_empty());
+    assert!(scoped.count_by_event_type(tenants.bob_session).await.unwrap().is_empty());
+
+    let err = scoped.create(log_data(tenants.bob_session)).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped.create_batch(vec![log_data(tenants.bob_session)]).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped.delete(bob_log.log_id).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped.delete_by_session_id(tenants.bob_session).await.unwrap_err();
+    assert!(is_not_found(&err));
+
+    let logs = ExecutionLogRepository::new(db.clone()).find_by_session_id(tenants.bob_session).await.unwrap();
+    assert_eq!(logs.len(), 1);
+
+    // 鲍勃自己的范围可以看到
+    let bob_scoped = ExecutionLogRepository::new(db.clone()).with_scope(TenantScope::user(tenants.bob));
+    assert_eq!(bob_scoped.count_by_event_type(tenants.bob_session).await.unwrap(), vec![(EventType::TestRun, 1)]);
+}
+
+#[tokio::test]
+async fn test_conflict_scope_isolates_conflicts() {
+    let db = setup_test_db().await;
+    let tenants = two_tenants(&db).await;
+    let conflicts = ConflictRepository::new(db.clone());
+    let bob_conflict = conflicts.create(conflict_data(Some(tenants.bob_project))).await.unwrap();
+    let legacy_conflict = conflicts.create(conflict_data(None)).await.unwrap();
+    let scoped = ConflictRepository::new(db.clone()).with_scope(TenantScope::user(tenants.alice));
+
+    assert!(scoped.find_by_id(bob_conflict.conflict_id).await.unwrap().is_none());
+    assert!(scoped.find_by_id(legacy_conflict.conflict_id).await.unwrap().is_none());
+    assert!(scoped.find_unresolved().await.unwrap().is_empty());
+    assert_eq!(scoped.get_conflict_statistics().await.unwrap().total_conflicts, 0);
+
+    let err = scoped.update_status(bob_conflict.conflict_id, ConflictStatus::Ignored).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped.delete(bob_conflict.conflict_id).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped.create(conflict_data(Some(tenants.bob_project))).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped.create(conflict_data(None)).await.unwrap_err();
+    assert!(err.is_validation_error());
+    assert!(conflicts.find_by_id(bob_conflict.conflict_id).await.unwrap().is_some());
+
+    let alice_conflict = scoped.create(conflict_data(Some(tenants.alice_project))).await.unwrap();
+    assert_eq!(alice_conflict.project_id, Some(tenants.alice_project));
+    assert_eq!(scoped.find_unresolved().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_human_decision_scope_follows_conflict() {
+    let db = setup_test_db().await;
+    let tenants = two_tenants(&db).await;
+    let bob_conflict = ConflictRepository::new(db.clone())
+        .create(conflict_data(Some(tenants.bob_project)))
+        .await
+        .unwrap();
+    let bob_decision = HumanDecisionRepository::new(db.clone())
+        .create(decision_data(bob_conflict.conflict_id, tenants.bob))
+        .await
+        .unwrap();
+    let scoped = HumanDecisionRepository::new(db.clone()).with_scope(TenantScope::user(tenants.alice));
+
+    assert!(scoped.find_by_id(bob_decision.decision_id).await.unwrap().is_none());
+    assert!(scoped.find_by_conflict_id(bob_conflict.conflict_id).await.unwrap().is_empty());
+    assert!(scoped.find_by_user_id(tenants.bob).await.unwrap().is_empty());
+
+    let err = scoped.update_reasoning(bob_decision.decision_id, "越权".to_string()).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped.delete(bob_decision.decision_id).await.unwrap_err();
+    assert!(is_not_found(&err));
+    let err = scoped.create(decision_data(bob_conflict.conflict_id, tenants.alice)).await.unwrap_err();
+    assert!(is_not_found(&err));
+
+    let unchanged = HumanDecisionRepository::new(db.clone())
+        .find_by_id(bob_decision.decision_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(unchanged.reasoning.is_none());
+}
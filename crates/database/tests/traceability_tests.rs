@@ -0,0 +1,184 @@
+//! 需求追踪矩阵测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::domain_event::DomainEventType,
+    repository::{
+        DomainEventRepository, ProjectRepository, RequirementDocumentRepository, TaskRepository, UserRepository,
+        project_repository::CreateProjectData,
+        requirement_document_repository::CreateRequirementDocumentData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    traceability::{coverage_report, link_task_to_section, parse_sections, WHOLE_DOCUMENT_SECTION},
+};
+use uuid::Uuid;
+
+mod common;
+
+const AUTH_DOCUMENT: &str = "\
+# 认证
+系统需要支持账户体系。
+
+## 登录
+使用邮箱和密码登录。
+
+```markdown
+# 代码块中的标题不算章节
+```
+
+## 注销
+注销后清除会话。
+
+# 报表
+按月导出报表。
+";
+
+async fn create_test_project(db: &codex_database::DatabaseConnection) -> Uuid {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+
+    ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "追踪项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/trace".to_string(),
+        })
+        .await
+        .unwrap()
+        .project_id
+}
+
+async fn create_task(
+    db: &codex_database::DatabaseConnection,
+    project_id: Uuid,
+    parent_task_id: Option<Uuid>,
+    title: &str,
+) -> Uuid {
+    TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id,
+            parent_task_id,
+            llm_session_id: None,
+            title: title.to_string(),
+            description: format!("{}的实现", title),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap()
+        .task_id
+}
+
+async fn coverage_drop_events(db: &codex_database::DatabaseConnection, project_id: Uuid) -> Vec<serde_json::Value> {
+    DomainEventRepository::new(db.clone())
+        .find_by_aggregate_id(project_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|event| event.event_type == DomainEventType::RequirementCoverageDropped.to_string())
+        .map(|event| event.event_data)
+        .collect()
+}
+
+#[test]
+fn test_parse_sections() {
+    let sections = parse_sections("认证需求", AUTH_DOCUMENT);
+    let ids: Vec<_> = sections.iter().map(|section| section.section_id.as_str()).collect();
+    assert_eq!(ids, vec!["认证", "登录", "注销", "报表"]);
+    assert_eq!(sections[1].parent_id.as_deref(), Some("认证"));
+    assert_eq!(sections[3].parent_id, None);
+
+    // 同名标题追加序号，标点被去掉
+    let sections = parse_sections("说明", "# API 设计\n## 错误码\n# API 设计!\n#不是标题");
+    let ids: Vec<_> = sections.iter().map(|section| section.section_id.as_str()).collect();
+    assert_eq!(ids, vec!["api-设计", "错误码", "api-设计-1"]);
+
+    // 没有标题时整篇文档作为一个章节
+    let sections = parse_sections("简短需求", "只有一段描述");
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].section_id, WHOLE_DOCUMENT_SECTION);
+    assert_eq!(sections[0].title, "简短需求");
+}
+
+#[tokio::test]
+async fn test_coverage_report_and_drop_events() {
+    let db = setup_test_db().await;
+    let project_id = create_test_project(&db).await;
+    let documents = RequirementDocumentRepository::new(db.clone());
+    let document = documents
+        .create(CreateRequirementDocumentData {
+            project_id,
+            title: "认证需求".to_string(),
+            content: AUTH_DOCUMENT.to_string(),
+            document_type: "functional".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let login_task = create_task(&db, project_id, None, "实现登录").await;
+    let logout_task = create_task(&db, project_id, None, "实现注销").await;
+    let orphan_task = create_task(&db, project_id, None, "重构日志").await;
+    create_task(&db, project_id, Some(login_task), "登录页面").await;
+    let cancelled_task = create_task(&db, project_id, None, "废弃任务").await;
+    let tasks = TaskRepository::new(db.clone());
+    tasks.update_status(cancelled_task, "cancelled").await.unwrap();
+
+    link_task_to_section(&db, document.document_id, "登录", login_task).await.unwrap();
+    link_task_to_section(&db, document.document_id, "注销", logout_task).await.unwrap();
+    // 重复关联不会产生新记录
+    link_task_to_section(&db, document.document_id, "注销", logout_task).await.unwrap();
+
+    let error = link_task_to_section(&db, document.document_id, "不存在", login_task).await.unwrap_err();
+    assert!(error.is_validation_error());
+    let other_project = create_test_project(&db).await;
+    let foreign_task = create_task(&db, other_project, None, "其他项目任务").await;
+    let error = link_task_to_section(&db, document.document_id, "登录", foreign_task).await.unwrap_err();
+    assert!(error.is_validation_error());
+
+    let report = coverage_report(&db, project_id).await.unwrap();
+    assert_eq!(report.total_sections, 4);
+    assert_eq!(report.covered_sections, 3);
+    assert_eq!(report.sections[2].task_ids, vec![logout_task]);
+    let uncovered: Vec<_> = report.uncovered.iter().map(|section| section.section_id.as_str()).collect();
+    assert_eq!(uncovered, vec!["报表"]);
+    // 子任务通过上级任务追溯，已取消的任务不算孤立任务
+    let orphans: Vec<_> = report.orphan_tasks.iter().map(|task| task.task_id).collect();
+    assert_eq!(orphans, vec![orphan_task]);
+
+    // 取消没有关联的任务不记录事件
+    tasks.update_status(orphan_task, "cancelled").await.unwrap();
+    assert!(coverage_drop_events(&db, project_id).await.is_empty());
+
+    // 取消后章节失去覆盖时记录事件；上级章节仍由其他下级章节覆盖
+    tasks.update_status(logout_task, "cancelled").await.unwrap();
+    let events = coverage_drop_events(&db, project_id).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["task_id"], logout_task.to_string());
+    assert_eq!(events[0]["uncovered_sections"][0]["section_id"], "注销");
+    assert_eq!(events[0]["uncovered_sections"].as_array().unwrap().len(), 1);
+    assert_eq!(events[0]["covered_sections"], 2);
+
+    // 重复取消不会再次记录
+    tasks.update_status(logout_task, "cancelled").await.unwrap();
+    assert_eq!(coverage_drop_events(&db, project_id).await.len(), 1);
+
+    // 文档修改后不再存在的章节关联被标记为失效
+    documents
+        .update_content(document.document_id, None, Some("# 认证\n## 单点登录\n".to_string()), None)
+        .await
+        .unwrap();
+    let report = coverage_report(&db, project_id).await.unwrap();
+    assert_eq!(report.total_sections, 2);
+    assert_eq!(report.covered_sections, 0);
+    assert_eq!(report.stale_links.len(), 2);
+}
@@ -0,0 +1,178 @@
+//! 两步验证集成测试
+
+use crate::common::setup_test_db;
+use chrono::{Duration, Utc};
+use codex_database::{
+    repository::{UserRepository, UserTwoFactorRepository, user_repository::CreateUserData},
+    two_factor::{
+        self, TwoFactorMethod, TwoFactorService, BACKUP_CODE_COUNT, LOCKOUT_MINUTES, MAX_FAILED_ATTEMPTS,
+        TOTP_PERIOD_SECS,
+    },
+};
+use uuid::Uuid;
+
+mod common;
+
+/// 创建测试用户的辅助函数
+async fn create_test_user(db: &codex_database::DatabaseConnection) -> Uuid {
+    let repo = UserRepository::new(db.clone());
+    repo.create(CreateUserData {
+        username: format!("totp_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("totp_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id
+}
+
+#[test]
+fn test_totp_matches_rfc_6238_vectors() {
+    // RFC 6238 附录 B 的 SHA1 测试密钥 "12345678901234567890"
+    let secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+    assert_eq!(two_factor::totp_code(secret, 59 / TOTP_PERIOD_SECS).as_deref(), Some("287082"));
+    assert_eq!(two_factor::totp_code(secret, 1111111109 / TOTP_PERIOD_SECS).as_deref(), Some("081804"));
+
+    let uri = two_factor::provisioning_uri("Sker", "dev@example.com", secret);
+    assert!(uri.starts_with("otpauth://totp/Sker:dev%40example.com?secret=GEZDGNBV"));
+}
+
+#[tokio::test]
+async fn test_enrollment_login_verification_and_recovery() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let service = TwoFactorService::new(db.clone());
+    let now = Utc::now();
+
+    let enrollment = service.begin_enrollment(user_id, "Sker", "dev@example.com").await.unwrap();
+    assert!(service.status(user_id).await.unwrap().pending_enrollment);
+    assert!(!service.is_enabled(user_id).await.unwrap());
+    assert!(service.confirm_enrollment_at(user_id, "000000x", now).await.is_err());
+
+    let code = two_factor::totp_code(&enrollment.secret, two_factor::time_step(now)).unwrap();
+    let backup_codes = service.confirm_enrollment_at(user_id, &code, now).await.expect("确认登记应该成功");
+    assert_eq!(backup_codes.len(), BACKUP_CODE_COUNT);
+    assert!(backup_codes.iter().all(|code| code.len() == 19 && code.matches('-').count() == 3));
+    assert!(service.is_enabled(user_id).await.unwrap());
+
+    // 同一时间步的验证码不能重放，下一个时间步的验证码在偏差范围内有效
+    assert!(service.verify_at(user_id, &code, now).await.unwrap_err().is_validation_error());
+    let later = now + Duration::seconds(TOTP_PERIOD_SECS);
+    let next_code = two_factor::totp_code(&enrollment.secret, two_factor::time_step(later)).unwrap();
+    assert_eq!(service.verify_at(user_id, &next_code, now).await.unwrap(), TwoFactorMethod::Totp);
+
+    // 备用恢复码只能使用一次，忽略大小写和分隔符
+    let recovery = backup_codes[0].to_uppercase().replace('-', "");
+    assert_eq!(service.verify_at(user_id, &recovery, now).await.unwrap(), TwoFactorMethod::BackupCode);
+    assert!(service.verify_at(user_id, &backup_codes[0], now).await.is_err());
+    assert_eq!(service.status(user_id).await.unwrap().backup_codes_remaining, BACKUP_CODE_COUNT - 1);
+
+    // 被强制要求时不能停用
+    service.set_required(user_id, true).await.unwrap();
+    assert!(service.disable(user_id, &backup_codes[1]).await.unwrap_err().is_business_error());
+    assert!(service.is_enabled(user_id).await.unwrap());
+
+    // 取消强制后用恢复码停用，可以重新登记
+    service.set_required(user_id, false).await.unwrap();
+    let status = service.disable(user_id, &backup_codes[1]).await.unwrap();
+    assert!(!status.enabled);
+    assert_eq!(status.backup_codes_remaining, 0);
+    service.begin_enrollment(user_id, "Sker", "dev@example.com").await.unwrap();
+
+    // 强制要求但尚未登记时只允许完成登记
+    assert!(!service.setup_required(user_id).await.unwrap());
+    service.set_required(user_id, true).await.unwrap();
+    assert!(service.setup_required(user_id).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_repeated_failures_lock_verification() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let service = TwoFactorService::new(db.clone());
+    let now = Utc::now();
+
+    let enrollment = service.begin_enrollment(user_id, "Sker", "dev@example.com").await.unwrap();
+    let code = two_factor::totp_code(&enrollment.secret, two_factor::time_step(now)).unwrap();
+    let backup_codes = service.confirm_enrollment_at(user_id, &code, now).await.unwrap();
+
+    // 失败次数未达上限时，成功校验会清零计数
+    for _ in 1..MAX_FAILED_ATTEMPTS {
+        assert!(service.verify_at(user_id, "000000", now).await.unwrap_err().is_validation_error());
+    }
+    assert_eq!(service.verify_at(user_id, &backup_codes[0], now).await.unwrap(), TwoFactorMethod::BackupCode);
+
+    // 连续失败达到上限后锁定，锁定期间正确的恢复码同样被拒绝
+    for _ in 1..MAX_FAILED_ATTEMPTS {
+        assert!(service.verify_at(user_id, "000000", now).await.unwrap_err().is_validation_error());
+    }
+    assert!(service.verify_at(user_id, "000000", now).await.unwrap_err().is_business_error());
+    assert!(service.verify_at(user_id, &backup_codes[1], now).await.unwrap_err().is_business_error());
+    assert_eq!(service.status(user_id).await.unwrap().backup_codes_remaining, BACKUP_CODE_COUNT - 1);
+
+    // 锁定到期后恢复
+    let unlocked = now + Duration::minutes(LOCKOUT_MINUTES) + Duration::seconds(1);
+    assert_eq!(service.verify_at(user_id, &backup_codes[1], unlocked).await.unwrap(), TwoFactorMethod::BackupCode);
+}
+
+#[tokio::test]
+async fn test_concurrent_verification_consumes_code_once() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let service = TwoFactorService::new(db.clone());
+    let now = Utc::now();
+
+    let enrollment = service.begin_enrollment(user_id, "Sker", "dev@example.com").await.unwrap();
+    let code = two_factor::totp_code(&enrollment.secret, two_factor::time_step(now)).unwrap();
+    let backup_codes = service.confirm_enrollment_at(user_id, &code, now).await.unwrap();
+
+    // 两个登录同时提交同一个恢复码，只有一个通过
+    let (first, second) = tokio::join!(
+        service.verify_at(user_id, &backup_codes[0], now),
+        service.verify_at(user_id, &backup_codes[0], now),
+    );
+    assert_eq!([first.is_ok(), second.is_ok()].iter().filter(|ok| **ok).count(), 1);
+    assert_eq!(service.status(user_id).await.unwrap().backup_codes_remaining, BACKUP_CODE_COUNT - 1);
+
+    // 同时使用不同的恢复码互不影响
+    let (first, second) = tokio::join!(
+        service.verify_at(user_id, &backup_codes[1], now),
+        service.verify_at(user_id, &backup_codes[2], now),
+    );
+    assert_eq!(first.unwrap(), TwoFactorMethod::BackupCode);
+    assert_eq!(second.unwrap(), TwoFactorMethod::BackupCode);
+    assert_eq!(service.status(user_id).await.unwrap().backup_codes_remaining, BACKUP_CODE_COUNT - 3);
+
+    // 同一时间步的验证码并发提交，只有一个通过
+    let later = now + Duration::seconds(TOTP_PERIOD_SECS);
+    let next_code = two_factor::totp_code(&enrollment.secret, two_factor::time_step(later)).unwrap();
+    let (first, second) = tokio::join!(
+        service.verify_at(user_id, &next_code, now),
+        service.verify_at(user_id, &next_code, now),
+    );
+    assert_eq!([first.is_ok(), second.is_ok()].iter().filter(|ok| **ok).count(), 1);
+}
+
+#[tokio::test]
+async fn test_stale_two_factor_updates_are_rejected() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let service = TwoFactorService::new(db.clone());
+    let repo = UserTwoFactorRepository::new(db.clone());
+    let now = Utc::now();
+
+    let enrollment = service.begin_enrollment(user_id, "Sker", "dev@example.com").await.unwrap();
+    let code = two_factor::totp_code(&enrollment.secret, two_factor::time_step(now)).unwrap();
+    service.confirm_enrollment_at(user_id, &code, now).await.unwrap();
+
+    // 不晚于上次使用的时间步不能写入
+    let step = two_factor::time_step(now);
+    assert!(!repo.consume_step(user_id, step).await.unwrap());
+    assert!(repo.consume_step(user_id, step + 1).await.unwrap());
+    assert!(!repo.consume_step(user_id, step + 1).await.unwrap());
+
+    // 基于过期的摘要列表消耗恢复码时不写入
+    let stale = repo.find_by_user(user_id).await.unwrap().unwrap().backup_code_hashes;
+    assert!(repo.consume_backup_code(user_id, &stale, vec!["a".to_string()]).await.unwrap());
+    assert!(!repo.consume_backup_code(user_id, &stale, vec![]).await.unwrap());
+    assert_eq!(service.status(user_id).await.unwrap().backup_codes_remaining, 1);
+}
@@ -0,0 +1,66 @@
+//! 强类型ID仓储参数测试
+//!
+//! 运行方式：cargo test -p codex-database --features typed_ids
+
+#![cfg(feature = "typed_ids")]
+
+use crate::common::setup_test_db;
+use codex_database::repository::{
+    ProjectRepository, TaskRepository, UserRepository,
+    project_repository::CreateProjectData,
+    task_repository::CreateTaskData,
+    user_repository::CreateUserData,
+};
+use codex_multi_agent::{ProjectId, TaskId};
+use uuid::Uuid;
+
+mod common;
+
+#[tokio::test]
+async fn test_repositories_accept_typed_and_raw_ids() {
+    let db = setup_test_db().await;
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let projects = ProjectRepository::new(db.clone());
+    let project = projects
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "强类型ID项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/typed".to_string(),
+        })
+        .await
+        .unwrap();
+    let project_id = ProjectId::from(project.project_id);
+    assert!(projects.find_by_id(&project_id).await.unwrap().is_some());
+    assert!(projects.find_by_id(project.project_id).await.unwrap().is_some());
+
+    let tasks = TaskRepository::new(db.clone());
+    let task = tasks
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "强类型任务".to_string(),
+            description: "强类型任务的实现".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+    let task_id = TaskId::try_from(task.task_id.to_string()).unwrap();
+    assert_eq!(tasks.find_by_project(project_id).await.unwrap().len(), 1);
+    let updated = tasks.update_status(task_id.clone(), "in_progress").await.unwrap();
+    assert_eq!(updated.status, "in_progress");
+
+    tasks.delete(task_id.clone()).await.unwrap();
+    assert!(tasks.find_by_id(task_id).await.unwrap().is_none());
+}
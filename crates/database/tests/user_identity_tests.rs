@@ -0,0 +1,62 @@
+//! 外部身份关联集成测试
+
+use crate::common::setup_test_db;
+use codex_database::repository::{
+    UserIdentityRepository, UserRepository,
+    user_identity_repository::LinkIdentityData,
+    user_repository::CreateUserData,
+};
+use uuid::Uuid;
+
+mod common;
+
+/// 创建测试用户的辅助函数
+async fn create_test_user(db: &codex_database::DatabaseConnection) -> Uuid {
+    let repo = UserRepository::new(db.clone());
+    repo.create(CreateUserData {
+        username: format!("oidc_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("oidc_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: String::new(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id
+}
+
+fn link_data(user_id: Uuid, provider: &str, subject: &str) -> LinkIdentityData {
+    LinkIdentityData {
+        user_id,
+        provider: provider.to_string(),
+        subject: subject.to_string(),
+        email: Some("dev@example.com".to_string()),
+        display_name: None,
+        refresh_token: Some("refresh-1".to_string()),
+        token_expires_at: None,
+    }
+}
+
+#[tokio::test]
+async fn test_link_and_find_identity() {
+    let db = setup_test_db().await;
+    let user_id = create_test_user(&db).await;
+    let other_user = create_test_user(&db).await;
+    let repo = UserIdentityRepository::new(db.clone());
+
+    let identity = repo.link(link_data(user_id, "google", "sub-1")).await.expect("关联应该成功");
+    let found = repo.find_by_subject("google", "sub-1").await.unwrap().unwrap();
+    assert_eq!(found.user_id, user_id);
+
+    // 同一外部身份不能关联到其他用户，同一用户在一个提供方只能关联一个身份
+    assert!(repo.link(link_data(other_user, "google", "sub-1")).await.is_err());
+    assert!(repo.link(link_data(user_id, "google", "sub-2")).await.is_err());
+    repo.link(link_data(user_id, "github", "42")).await.unwrap();
+    assert_eq!(repo.find_by_user(user_id).await.unwrap().len(), 2);
+
+    // 身份提供方未返回新的刷新令牌时保留原令牌
+    let updated = repo.record_login(identity.identity_id, None, Some("开发者".to_string()), None, None).await.unwrap();
+    assert_eq!(updated.refresh_token.as_deref(), Some("refresh-1"));
+    assert!(updated.last_login_at.is_some());
+
+    assert!(repo.unlink(other_user, identity.identity_id).await.is_err());
+    repo.unlink(user_id, identity.identity_id).await.unwrap();
+    assert!(repo.find_by_subject("google", "sub-1").await.unwrap().is_none());
+}
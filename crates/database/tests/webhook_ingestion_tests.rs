@@ -0,0 +1,309 @@
+//! 外部 webhook 接入测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::domain_event::DomainEventType,
+    repository::{
+        CiCheckRunRepository, DomainEventRepository, ProjectRepository, UserRepository, WebhookDeadLetterRepository,
+        project_repository::CreateProjectData,
+        user_repository::CreateUserData,
+        webhook_dead_letter_repository::CreateDeadLetterData,
+    },
+    webhook_ingestion::{
+        pending_dead_letters, replay_dead_letter, serve, IngestOutcome, WebhookDelivery, WebhookIngestor,
+        MAX_HEADER_COUNT, MAX_HEADER_LINE_BYTES,
+    },
+    DatabaseConnection,
+};
+use codex_multi_agent::WebhookSource;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use uuid::Uuid;
+
+mod common;
+
+async fn create_project(db: &DatabaseConnection) -> Uuid {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "Webhook项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/webhook".to_string(),
+        })
+        .await
+        .unwrap()
+        .project_id
+}
+
+fn sign(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(body.as_bytes());
+    let hex: String = mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+fn github_delivery(project_id: Uuid, event: &str, payload: &Value, secret: &str) -> WebhookDelivery {
+    let payload = payload.to_string();
+    WebhookDelivery {
+        source: WebhookSource::Github,
+        project_id,
+        event_name: Some(event.to_string()),
+        delivery_id: Some(Uuid::new_v4().to_string()),
+        signature: Some(sign(secret, &payload)),
+        payload,
+    }
+}
+
+#[tokio::test]
+async fn test_github_events_become_domain_events() {
+    let db = setup_test_db().await;
+    let project_id = create_project(&db).await;
+    let ingestor = WebhookIngestor::new(db.clone()).with_secret(WebhookSource::Github, "secret");
+
+    let push = json!({
+        "ref": "refs/heads/main",
+        "before": "aaa111",
+        "after": "bbb222",
+        "commits": [{ "id": "bbb222" }, { "id": "abc123" }],
+        "repository": { "full_name": "test/repo" },
+        "pusher": { "name": "alice" }
+    });
+
+    // 签名无效或缺失时拒绝，不记录死信
+    let forged = github_delivery(project_id, "push", &push, "other");
+    assert!(ingestor.ingest(forged).await.unwrap_err().is_validation_error());
+    let mut unsigned = github_delivery(project_id, "push", &push, "secret");
+    unsigned.signature = None;
+    assert!(ingestor.verify(&unsigned).is_err());
+
+    // 未配置密钥的来源拒绝所有投递
+    let unconfigured = WebhookDelivery {
+        source: WebhookSource::Gitlab,
+        ..github_delivery(project_id, "push", &push, "secret")
+    };
+    assert!(ingestor.ingest(unconfigured).await.unwrap_err().is_validation_error());
+    let open = WebhookIngestor::new(db.clone()).with_secret(WebhookSource::Github, "");
+    assert!(!open.has_secrets());
+    assert!(open.verify(&github_delivery(project_id, "push", &push, "")).is_err());
+
+    let delivery = github_delivery(project_id, "push", &push, "secret");
+    let outcome = ingestor.ingest(delivery.clone()).await.unwrap();
+    assert!(matches!(&outcome, IngestOutcome::Ingested { event_type, .. } if event_type == "GitPushReceived"));
+
+    // 来源重发相同投递时返回首次的结果，不重复写入事件
+    assert_eq!(ingestor.ingest(delivery).await.unwrap(), outcome);
+
+    let ping = ingestor
+        .ingest(github_delivery(project_id, "ping", &json!({ "zen": "Keep it simple." }), "secret"))
+        .await
+        .unwrap();
+    assert!(matches!(ping, IngestOutcome::Ignored { .. }));
+
+    let issue = json!({
+        "action": "closed",
+        "issue": { "number": 42, "title": "登录失败", "state": "closed", "html_url": "https://github.com/test/repo/issues/42" }
+    });
+    ingestor.ingest(github_delivery(project_id, "issues", &issue, "secret")).await.unwrap();
+
+    // CI 状态同时写入检查结果
+    let workflow = json!({
+        "workflow_run": {
+            "id": 7,
+            "name": "CI",
+            "head_sha": "bbb222",
+            "head_branch": "main",
+            "status": "completed",
+            "conclusion": "failure",
+            "html_url": "https://github.com/test/repo/actions/runs/7"
+        }
+    });
+    let outcome = ingestor.ingest(github_delivery(project_id, "workflow_run", &workflow, "secret")).await.unwrap();
+    assert!(matches!(&outcome, IngestOutcome::Ingested { event_type, .. } if event_type == "CiStatusReceived"));
+    let runs = CiCheckRunRepository::new(db.clone()).find_by_project(project_id, 10).await.unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].status, "failed");
+
+    let events = DomainEventRepository::new(db.clone());
+    let pushes = events.find_by_event_type(&DomainEventType::GitPushReceived.to_string()).await.unwrap();
+    assert_eq!(pushes.len(), 1);
+    assert_eq!(pushes[0].aggregate_id, project_id);
+    assert_eq!(pushes[0].event_data["git_ref"], json!("refs/heads/main"));
+    assert_eq!(pushes[0].event_data["commit_count"], json!(2));
+    assert_eq!(pushes[0].event_data["pusher"], json!("alice"));
+    let issues = events.find_by_event_type(&DomainEventType::ExternalIssueUpdated.to_string()).await.unwrap();
+    assert_eq!(issues[0].event_data["issue_number"], json!(42));
+    assert_eq!(issues[0].event_data["action"], json!("closed"));
+    let ci = events.find_by_event_type(&DomainEventType::CiStatusReceived.to_string()).await.unwrap();
+    assert_eq!(ci[0].event_data["status"], json!("failed"));
+
+    // 不存在的项目返回错误
+    let missing = github_delivery(Uuid::new_v4(), "push", &push, "secret");
+    assert!(ingestor.ingest(missing).await.is_err());
+}
+
+#[tokio::test]
+async fn test_unmappable_payloads_are_dead_lettered_and_replayed() {
+    let db = setup_test_db().await;
+    let project_id = create_project(&db).await;
+    let ingestor = WebhookIngestor::new(db.clone()).with_secret(WebhookSource::Gitlab, "token");
+
+    let gitlab = |payload: String, token: &str| WebhookDelivery {
+        source: WebhookSource::Gitlab,
+        project_id,
+        event_name: Some("Issue Hook".to_string()),
+        delivery_id: None,
+        signature: Some(token.to_string()),
+        payload,
+    };
+    assert!(ingestor.ingest(gitlab("{}".to_string(), "wrong")).await.is_err());
+
+    // 不支持的事件、缺少字段和无效JSON都写入死信
+    let wiki = json!({ "object_kind": "wiki_page", "object_attributes": { "title": "首页" } });
+    let missing_iid = json!({ "object_kind": "issue", "object_attributes": { "title": "崩溃", "state": "opened" } });
+    for payload in [wiki.to_string(), missing_iid.to_string(), "not json".to_string()] {
+        let outcome = ingestor.ingest(gitlab(payload, "token")).await.unwrap();
+        assert!(matches!(outcome, IngestOutcome::DeadLettered { .. }));
+    }
+    let pending = pending_dead_letters(&db, project_id, 10).await.unwrap();
+    assert_eq!(pending.len(), 3);
+    assert!(pending.iter().any(|letter| letter.error.contains("wiki_page")));
+    assert!(pending.iter().any(|letter| letter.error.contains("iid")));
+
+    // 重放仍然失败时累计次数并保留死信
+    let wiki_letter = pending.iter().find(|letter| letter.error.contains("wiki_page")).unwrap();
+    let outcome = replay_dead_letter(&db, wiki_letter.dead_letter_id).await.unwrap();
+    assert!(matches!(outcome, IngestOutcome::DeadLettered { .. }));
+    let dead_letters = WebhookDeadLetterRepository::new(db.clone());
+    let replayed = dead_letters.find_by_id(wiki_letter.dead_letter_id).await.unwrap().unwrap();
+    assert_eq!(replayed.replay_count, 1);
+    assert!(replayed.is_pending());
+
+    // 映射规则支持后重放成功，死信标记为已处理
+    let letter = dead_letters
+        .create(CreateDeadLetterData {
+            project_id,
+            source: "gitlab".to_string(),
+            event_name: Some("Push Hook".to_string()),
+            delivery_id: None,
+            payload: json!({
+                "object_kind": "push",
+                "ref": "refs/heads/develop",
+                "before": "000000",
+                "after": "ccc333",
+                "total_commits_count": 3,
+                "user_username": "bob",
+                "project": { "path_with_namespace": "group/repo" }
+            })
+            .to_string(),
+            error: "不支持的 gitlab 事件: push".to_string(),
+        })
+        .await
+        .unwrap();
+    let outcome = replay_dead_letter(&db, letter.dead_letter_id).await.unwrap();
+    assert!(matches!(&outcome, IngestOutcome::Ingested { event_type, .. } if event_type == "GitPushReceived"));
+    let resolved = dead_letters.find_by_id(letter.dead_letter_id).await.unwrap().unwrap();
+    assert!(!resolved.is_pending());
+    assert!(replay_dead_letter(&db, letter.dead_letter_id).await.unwrap_err().is_validation_error());
+
+    dead_letters.discard(wiki_letter.dead_letter_id).await.unwrap();
+    assert_eq!(pending_dead_letters(&db, project_id, 10).await.unwrap().len(), 2);
+}
+
+async fn post(address: std::net::SocketAddr, path: &str, headers: &[(&str, String)], body: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    let mut request = format!("POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n", path, body.len());
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let status = response.split_whitespace().nth(1).unwrap().parse().unwrap();
+    let body = response.split("\r\n\r\n").nth(1).unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[tokio::test]
+async fn test_http_endpoint() {
+    let db = setup_test_db().await;
+    let project_id = create_project(&db).await;
+    let ingestor = Arc::new(WebhookIngestor::new(db.clone()).with_secret(WebhookSource::Github, "secret"));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(serve(listener, ingestor));
+
+    let body = json!({
+        "action": "opened",
+        "issue": { "number": 7, "title": "文档缺失", "state": "open" }
+    })
+    .to_string();
+    let path = format!("/webhooks/github/{}", project_id);
+
+    let (status, response) = post(
+        address,
+        &path,
+        &[("X-GitHub-Event", "issues".to_string()), ("X-Hub-Signature-256", sign("secret", &body))],
+        &body,
+    )
+    .await;
+    assert_eq!(status, 202);
+    assert_eq!(response["status"], json!("ingested"));
+    assert_eq!(response["event_type"], json!("ExternalIssueUpdated"));
+
+    let (status, _) = post(
+        address,
+        &path,
+        &[("X-GitHub-Event", "issues".to_string()), ("X-Hub-Signature-256", sign("other", &body))],
+        &body,
+    )
+    .await;
+    assert_eq!(status, 401);
+
+    // 没有配置 GitLab 令牌
+    let (status, _) = post(
+        address,
+        &format!("/webhooks/gitlab/{}", project_id),
+        &[("X-Gitlab-Token", String::new())],
+        &body,
+    )
+    .await;
+    assert_eq!(status, 401);
+
+    let (status, _) = post(address, &format!("/webhooks/bitbucket/{}", project_id), &[], &body).await;
+    assert_eq!(status, 404);
+
+    // 请求头过长或过多
+    let (status, _) = post(address, &path, &[("X-Padding", "a".repeat(MAX_HEADER_LINE_BYTES))], "").await;
+    assert_eq!(status, 431);
+    let many: Vec<(&str, String)> = (0..MAX_HEADER_COUNT).map(|index| ("X-Extra", index.to_string())).collect();
+    let (status, _) = post(address, &path, &many, "").await;
+    assert_eq!(status, 431);
+
+    let (status, response) = post(
+        address,
+        &path,
+        &[("X-GitHub-Event", "star".to_string()), ("X-Hub-Signature-256", sign("secret", &body))],
+        &body,
+    )
+    .await;
+    assert_eq!(status, 202);
+    assert_eq!(response["status"], json!("dead_lettered"));
+}
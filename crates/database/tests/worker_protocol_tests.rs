@@ -0,0 +1,261 @@
+//! 远程工作进程协议测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    repository::{
+        AgentRepository, ExecutionSessionRepository, ProjectRepository, RemoteWorkerRepository, TaskRepository,
+        UserRepository,
+        agent_repository::CreateAgentData,
+        project_repository::CreateProjectData,
+        task_repository::CreateTaskData,
+        user_repository::CreateUserData,
+    },
+    worker_coordinator::{serve, WorkerCoordinator},
+    DatabaseConnection,
+};
+use codex_multi_agent::{
+    AgentCapability, AgentId, CoordinatorMessage, TaskLease, WorkerId, WorkerMessage, WORKER_PROTOCOL_VERSION,
+};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use uuid::Uuid;
+
+mod common;
+
+struct Fixture {
+    agent_id: Uuid,
+    backend_task_id: Uuid,
+    frontend_task_id: Uuid,
+}
+
+async fn setup_tasks(db: &DatabaseConnection) -> Fixture {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "分布式执行".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: "/workspace/remote".to_string(),
+        })
+        .await
+        .unwrap();
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "远程后端Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个后端开发Agent".to_string(),
+            capabilities: json!(["backend_development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+
+    let tasks = TaskRepository::new(db.clone());
+    let mut task_ids = Vec::new();
+    for (title, capability) in [("登录页面", "frontend_development"), ("登录接口", "backend_development")] {
+        let task = tasks
+            .create(CreateTaskData {
+                project_id: project.project_id,
+                parent_task_id: None,
+                llm_session_id: None,
+                title: title.to_string(),
+                description: format!("{}的实现", title),
+                task_type: "development".to_string(),
+            })
+            .await
+            .unwrap();
+        tasks.update_requirements(task.task_id, Some(json!([capability])), None).await.unwrap();
+        task_ids.push(task.task_id);
+    }
+
+    Fixture {
+        agent_id: agent.agent_id,
+        backend_task_id: task_ids[1],
+        frontend_task_id: task_ids[0],
+    }
+}
+
+fn register(agent_id: Uuid, max_concurrent_tasks: u32) -> WorkerMessage {
+    WorkerMessage::Register {
+        protocol_version: WORKER_PROTOCOL_VERSION,
+        name: "build-01".to_string(),
+        agent_ids: vec![AgentId::from(agent_id)],
+        capabilities: vec![AgentCapability::BackendDevelopment],
+        max_concurrent_tasks,
+    }
+}
+
+fn registered_id(reply: CoordinatorMessage) -> WorkerId {
+    match reply {
+        CoordinatorMessage::Registered { worker_id, .. } => worker_id,
+        other => panic!("期望注册成功，实际收到 {:?}", other),
+    }
+}
+
+fn lease_of(reply: CoordinatorMessage) -> TaskLease {
+    match reply {
+        CoordinatorMessage::TaskLease(lease) => lease,
+        other => panic!("期望任务租约，实际收到 {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_worker_leases_matching_task_over_tcp() {
+    let db = setup_test_db().await;
+    let fixture = setup_tasks(&db).await;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let coordinator = Arc::new(WorkerCoordinator::new(db.clone()).with_heartbeat_interval(Duration::from_secs(5)));
+    tokio::spawn(serve(listener, coordinator));
+
+    let (reader, mut writer) = TcpStream::connect(address).await.unwrap().into_split();
+    let mut lines = BufReader::new(reader).lines();
+    macro_rules! request {
+        ($message:expr) => {{
+            let mut payload = serde_json::to_vec(&$message).unwrap();
+            payload.push(b'\n');
+            writer.write_all(&payload).await.unwrap();
+            let line = lines.next_line().await.unwrap().unwrap();
+            serde_json::from_str::<CoordinatorMessage>(&line).unwrap()
+        }};
+    }
+
+    let reply = request!(register(fixture.agent_id, 1));
+    assert!(matches!(reply, CoordinatorMessage::Registered { heartbeat_interval_secs: 5, .. }));
+    let worker_id = registered_id(reply);
+
+    // 只分配能力匹配的后端任务
+    let lease = lease_of(request!(WorkerMessage::LeaseRequest { worker_id: worker_id.clone() }));
+    assert_eq!(lease.task_id.into_uuid(), fixture.backend_task_id);
+    assert_eq!(lease.agent_id.into_uuid(), fixture.agent_id);
+    assert_eq!(lease.required_capabilities, vec![AgentCapability::BackendDevelopment]);
+    let leased = TaskRepository::new(db.clone()).find_by_id(fixture.backend_task_id).await.unwrap().unwrap();
+    assert_eq!(leased.lease_owner, Some(worker_id.to_string()));
+
+    // 达到并发上限后不再分配
+    assert_eq!(request!(WorkerMessage::LeaseRequest { worker_id: worker_id.clone() }), CoordinatorMessage::NoWork);
+
+    let reply = request!(WorkerMessage::Ack { worker_id: worker_id.clone(), lease_id: lease.lease_id.clone() });
+    assert_eq!(reply, CoordinatorMessage::Accepted { lease_id: lease.lease_id.clone() });
+    let reply = request!(WorkerMessage::Heartbeat {
+        worker_id: worker_id.clone(),
+        active_leases: vec![lease.lease_id.clone()],
+    });
+    assert_eq!(reply, CoordinatorMessage::HeartbeatAck { revoked_leases: vec![] });
+
+    let reply = request!(WorkerMessage::Complete {
+        worker_id: worker_id.clone(),
+        lease_id: lease.lease_id.clone(),
+        final_commit: Some("abc123".to_string()),
+        result: Some(json!({ "tests_passed": true })),
+    });
+    assert_eq!(reply, CoordinatorMessage::Accepted { lease_id: lease.lease_id.clone() });
+
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(*lease.lease_id.as_uuid())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(session.status, "completed");
+    assert_eq!(session.final_commit.as_deref(), Some("abc123"));
+    let tasks = TaskRepository::new(db.clone());
+    let backend = tasks.find_by_id(fixture.backend_task_id).await.unwrap().unwrap();
+    assert_eq!(backend.status, "waiting_for_review");
+    assert_eq!(backend.assigned_agent_id, Some(fixture.agent_id));
+    assert!(backend.lease_owner.is_none());
+    let frontend = tasks.find_by_id(fixture.frontend_task_id).await.unwrap().unwrap();
+    assert_eq!(frontend.status, "pending");
+
+    // 已结束的租约不能重复完成，无法解析的消息返回错误
+    let reply = request!(WorkerMessage::Fail {
+        worker_id,
+        lease_id: lease.lease_id,
+        error: "重复上报".to_string(),
+        retryable: true,
+    });
+    assert!(matches!(reply, CoordinatorMessage::Error { .. }));
+    writer.write_all(b"{\"type\":\"unknown\"}\n").await.unwrap();
+    let line = lines.next_line().await.unwrap().unwrap();
+    assert!(matches!(serde_json::from_str::<CoordinatorMessage>(&line).unwrap(), CoordinatorMessage::Error { .. }));
+}
+
+#[tokio::test]
+async fn test_failed_and_stale_leases_are_requeued() {
+    let db = setup_test_db().await;
+    let fixture = setup_tasks(&db).await;
+    let coordinator = WorkerCoordinator::new(db.clone());
+    let tasks = TaskRepository::new(db.clone());
+
+    let mismatched = WorkerMessage::Register {
+        protocol_version: WORKER_PROTOCOL_VERSION + 1,
+        name: "old".to_string(),
+        agent_ids: vec![AgentId::from(fixture.agent_id)],
+        capabilities: vec![],
+        max_concurrent_tasks: 1,
+    };
+    assert!(matches!(coordinator.handle(mismatched).await, CoordinatorMessage::Error { .. }));
+
+    let first = registered_id(coordinator.handle(register(fixture.agent_id, 2)).await);
+    let second = registered_id(coordinator.handle(register(fixture.agent_id, 2)).await);
+
+    // 可重试的失败把任务放回队列，其他工作进程可以重新领取
+    let lease = lease_of(coordinator.handle(WorkerMessage::LeaseRequest { worker_id: first.clone() }).await);
+    assert_eq!(
+        coordinator.handle(WorkerMessage::LeaseRequest { worker_id: second.clone() }).await,
+        CoordinatorMessage::NoWork
+    );
+    let stolen = WorkerMessage::Ack { worker_id: second.clone(), lease_id: lease.lease_id.clone() };
+    assert!(matches!(coordinator.handle(stolen).await, CoordinatorMessage::Error { .. }));
+    coordinator
+        .handle(WorkerMessage::Fail {
+            worker_id: first.clone(),
+            lease_id: lease.lease_id.clone(),
+            error: "构建环境缺少依赖".to_string(),
+            retryable: true,
+        })
+        .await;
+    let task = tasks.find_by_id(fixture.backend_task_id).await.unwrap().unwrap();
+    assert_eq!(task.status, "pending");
+
+    // 心跳超时的工作进程被标记离线，未完成的租约超时并归还任务
+    let relet = lease_of(coordinator.handle(WorkerMessage::LeaseRequest { worker_id: second.clone() }).await);
+    assert_eq!(relet.task_id.into_uuid(), fixture.backend_task_id);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    coordinator
+        .handle(WorkerMessage::Heartbeat { worker_id: first.clone(), active_leases: vec![lease.lease_id.clone()] })
+        .await;
+    let reaped = coordinator.reap_stale_workers(Duration::from_millis(10)).await.unwrap();
+    assert_eq!(reaped, vec![second.clone().into_uuid()]);
+
+    let workers = RemoteWorkerRepository::new(db.clone());
+    assert_eq!(workers.find_by_id(second.clone().into_uuid()).await.unwrap().unwrap().status, "offline");
+    assert_eq!(workers.find_by_id(first.into_uuid()).await.unwrap().unwrap().status, "online");
+    let session = ExecutionSessionRepository::new(db.clone())
+        .find_by_id(relet.lease_id.into_uuid())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(session.status, "timeout");
+    let task = tasks.find_by_id(fixture.backend_task_id).await.unwrap().unwrap();
+    assert_eq!(task.status, "pending");
+    assert!(matches!(
+        coordinator.handle(WorkerMessage::LeaseRequest { worker_id: second }).await,
+        CoordinatorMessage::Error { .. }
+    ));
+}
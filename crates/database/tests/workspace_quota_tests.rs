@@ -0,0 +1,168 @@
+//! 工作空间磁盘配额测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    entities::domain_event::DomainEventType,
+    repository::{
+        DomainEventRepository, ExecutionSessionRepository, ProjectRepository, UserRepository,
+        execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData,
+        user_repository::CreateUserData,
+    },
+    workspace_quota::{check_workspace_quota, ensure_within_quota, scan_usage, QuotaLevel, WorkspaceQuota},
+};
+use uuid::Uuid;
+
+mod common;
+
+/// 创建工作空间指向指定目录的测试项目
+async fn create_test_project(db: &codex_database::DatabaseConnection, workspace: &std::path::Path) -> Uuid {
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+
+    ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "配额项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: workspace.to_string_lossy().to_string(),
+        })
+        .await
+        .unwrap()
+        .project_id
+}
+
+async fn threshold_events(db: &codex_database::DatabaseConnection, project_id: Uuid) -> Vec<serde_json::Value> {
+    DomainEventRepository::new(db.clone())
+        .find_by_aggregate_id(project_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|event| event.event_type == DomainEventType::WorkspaceQuotaThresholdCrossed.to_string())
+        .map(|event| event.event_data)
+        .collect()
+}
+
+#[test]
+fn test_scan_usage_finds_regenerable_dirs() {
+    let workspace = tempfile::tempdir().unwrap();
+    let root = workspace.path();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("target/debug")).unwrap();
+    std::fs::create_dir_all(root.join("web/node_modules/react")).unwrap();
+    std::fs::write(root.join("src/main.rs"), vec![b'a'; 100]).unwrap();
+    std::fs::write(root.join("target/debug/app"), vec![0; 400]).unwrap();
+    std::fs::write(root.join("web/node_modules/react/index.js"), vec![b'b'; 250]).unwrap();
+
+    let usage = scan_usage(root);
+    assert_eq!(usage.total_bytes, 750);
+    assert_eq!(usage.file_count, 3);
+    assert_eq!(usage.top_level[0].path, "target");
+    assert_eq!(usage.top_level[0].bytes, 400);
+
+    let regenerable: Vec<_> = usage.regenerable.iter().map(|dir| (dir.path.as_str(), dir.bytes)).collect();
+    assert_eq!(regenerable, vec![("target", 400), ("web/node_modules", 250)]);
+
+    // 不存在的目录返回空统计
+    let missing = scan_usage(&root.join("missing"));
+    assert_eq!(missing.total_bytes, 0);
+}
+
+#[tokio::test]
+async fn test_threshold_events_and_enforcement() {
+    let db = setup_test_db().await;
+    let workspace = tempfile::tempdir().unwrap();
+    let root = workspace.path();
+    std::fs::create_dir_all(root.join("target")).unwrap();
+    std::fs::write(root.join("target/a.o"), vec![0; 500]).unwrap();
+    let project_id = create_test_project(&db, root).await;
+
+    // 未配置配额时不记录事件，也不限制
+    let report = check_workspace_quota(&db, project_id).await.unwrap();
+    assert_eq!(report.level, QuotaLevel::Normal);
+    assert!(report.quota.is_none());
+    assert!(threshold_events(&db, project_id).await.is_empty());
+
+    ProjectRepository::new(db.clone())
+        .set_workspace_quota(project_id, Some(WorkspaceQuota::new(1000)))
+        .await
+        .unwrap();
+    check_workspace_quota(&db, project_id).await.unwrap();
+    assert!(threshold_events(&db, project_id).await.is_empty());
+
+    // 跨越警告阈值时记录事件并附带清理建议
+    std::fs::write(root.join("target/b.o"), vec![0; 350]).unwrap();
+    let report = check_workspace_quota(&db, project_id).await.unwrap();
+    assert_eq!(report.level, QuotaLevel::Warning);
+    assert_eq!(report.suggestions[0].path, "target");
+    assert_eq!(report.suggestions[0].bytes, 850);
+
+    let events = threshold_events(&db, project_id).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["previous_level"], "normal");
+    assert_eq!(events[0]["level"], "warning");
+    assert_eq!(events[0]["suggestions"][0]["path"], "target");
+
+    // 级别未变化时不重复记录
+    check_workspace_quota(&db, project_id).await.unwrap();
+    assert_eq!(threshold_events(&db, project_id).await.len(), 1);
+
+    // 超出配额后拒绝新的执行会话
+    std::fs::write(root.join("target/c.o"), vec![0; 200]).unwrap();
+    let error = ensure_within_quota(&db, project_id).await.unwrap_err();
+    assert!(error.is_quota_exceeded());
+
+    let result = ExecutionSessionRepository::new(db.clone())
+        .create(CreateSessionData {
+            task_id: Uuid::new_v4(),
+            agent_id: Uuid::new_v4(),
+            project_id,
+            git_branch: "feature/quota".to_string(),
+            base_commit: None,
+            execution_config: None,
+            timeout_minutes: 30,
+        })
+        .await;
+    assert!(result.unwrap_err().is_quota_exceeded());
+
+    let events = threshold_events(&db, project_id).await;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1]["level"], "exceeded");
+
+    // 清理后回落到正常
+    std::fs::remove_dir_all(root.join("target")).unwrap();
+    ensure_within_quota(&db, project_id).await.unwrap();
+    let events = threshold_events(&db, project_id).await;
+    assert_eq!(events.last().unwrap()["level"], "normal");
+}
+
+#[tokio::test]
+async fn test_set_workspace_quota_validation() {
+    let db = setup_test_db().await;
+    let workspace = tempfile::tempdir().unwrap();
+    let project_id = create_test_project(&db, workspace.path()).await;
+    let repo = ProjectRepository::new(db.clone());
+
+    let invalid = WorkspaceQuota {
+        max_bytes: 1000,
+        warning_ratio: 0.9,
+        critical_ratio: 0.5,
+    };
+    assert!(repo.set_workspace_quota(project_id, Some(invalid)).await.unwrap_err().is_validation_error());
+    assert!(repo.set_workspace_quota(project_id, Some(WorkspaceQuota::new(0))).await.is_err());
+
+    let project = repo.set_workspace_quota(project_id, Some(WorkspaceQuota::new(1 << 30))).await.unwrap();
+    assert_eq!(WorkspaceQuota::from_project(&project).unwrap(), Some(WorkspaceQuota::new(1 << 30)));
+
+    let project = repo.set_workspace_quota(project_id, None).await.unwrap();
+    assert!(project.workspace_quota.is_none());
+}
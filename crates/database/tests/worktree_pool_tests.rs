@@ -0,0 +1,145 @@
+//! 执行会话工作树池测试
+
+use crate::common::setup_test_db;
+use codex_database::{
+    repository::{
+        agent_repository::CreateAgentData, execution_session_repository::CreateSessionData,
+        project_repository::CreateProjectData, task_repository::CreateTaskData, user_repository::CreateUserData,
+        AgentRepository, ExecutionSessionRepository, ProjectRepository, TaskRepository, UserRepository,
+    },
+    worktree_pool::{WorktreePool, WorktreeState},
+};
+use serde_json::json;
+use std::path::Path;
+use uuid::Uuid;
+
+mod common;
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["-c", "user.name=测试", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[tokio::test]
+async fn test_worktree_lifecycle() {
+    let db = setup_test_db().await;
+    let dir = tempfile::tempdir().unwrap();
+    let workspace = dir.path().join("repo");
+    std::fs::create_dir_all(&workspace).unwrap();
+    std::fs::write(workspace.join("main.rs"), "fn main() {}\n").unwrap();
+    if git(&workspace, &["init", "-q"]).is_none() {
+        return;
+    }
+    git(&workspace, &["add", "-A"]).unwrap();
+    git(&workspace, &["commit", "-q", "-m", "base"]).unwrap();
+
+    let user = UserRepository::new(db.clone())
+        .create(CreateUserData {
+            username: format!("test_user_{}", &Uuid::new_v4().to_string()[..8]),
+            email: format!("test_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+            password_hash: "password_hash".to_string(),
+            profile_data: None,
+            settings: None,
+        })
+        .await
+        .unwrap();
+    let project = ProjectRepository::new(db.clone())
+        .create(CreateProjectData {
+            user_id: user.user_id,
+            name: "工作树项目".to_string(),
+            description: None,
+            repository_url: "https://github.com/test/repo.git".to_string(),
+            workspace_path: workspace.to_string_lossy().to_string(),
+        })
+        .await
+        .unwrap();
+    let agent = AgentRepository::new(db.clone())
+        .create(CreateAgentData {
+            user_id: user.user_id,
+            name: "开发Agent".to_string(),
+            description: None,
+            prompt_template: "你是一个测试Agent".to_string(),
+            capabilities: json!(["Development"]),
+            config: json!({}),
+            git_config: None,
+        })
+        .await
+        .unwrap();
+    let task = TaskRepository::new(db.clone())
+        .create(CreateTaskData {
+            project_id: project.project_id,
+            parent_task_id: None,
+            llm_session_id: None,
+            title: "并发任务".to_string(),
+            description: "并发任务".to_string(),
+            task_type: "development".to_string(),
+        })
+        .await
+        .unwrap();
+    let sessions = ExecutionSessionRepository::new(db.clone());
+    let mut session_ids = Vec::new();
+    for branch in ["feature/a", "feature/b", "feature/c"] {
+        let session = sessions
+            .create(CreateSessionData {
+                task_id: task.task_id,
+                agent_id: agent.agent_id,
+                project_id: project.project_id,
+                git_branch: branch.to_string(),
+                base_commit: None,
+                execution_config: None,
+                timeout_minutes: 30,
+            })
+            .await
+            .unwrap();
+        session_ids.push(session.session_id);
+    }
+
+    let pool = WorktreePool::new(db.clone(), dir.path().join("worktrees")).with_max_idle(1);
+
+    // 并发会话各自拥有独立的工作树，路径记录在会话上
+    let first = pool.acquire(session_ids[0]).await.unwrap();
+    let second = pool.acquire(session_ids[1]).await.unwrap();
+    assert_ne!(first, second);
+    assert_eq!(pool.acquire(session_ids[0]).await.unwrap(), first);
+    assert_eq!(git(&first, &["symbolic-ref", "--short", "HEAD"]).as_deref(), Some("feature/a"));
+    assert!(first.join("main.rs").is_file());
+    let recorded = sessions.find_by_id(session_ids[0]).await.unwrap().unwrap();
+    assert_eq!(recorded.worktree_path, Some(first.to_string_lossy().to_string()));
+
+    // 结束的会话释放工作树后清理改动，供下一个会话复用
+    std::fs::write(first.join("scratch.txt"), "临时文件").unwrap();
+    sessions.start_session(session_ids[0]).await.unwrap();
+    sessions.complete_session(session_ids[0], true, None, None, None).await.unwrap();
+    pool.release(session_ids[0]).await.unwrap();
+    assert!(!first.join("scratch.txt").exists());
+    let listed = pool.list(project.project_id).await.unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed.iter().filter(|info| info.state == WorktreeState::Idle).count(), 1);
+    assert!(listed.iter().any(|info| info.session_id == Some(session_ids[1]) && info.branch.as_deref() == Some("feature/b")));
+
+    let third = pool.acquire(session_ids[2]).await.unwrap();
+    assert_eq!(third, first);
+    assert_eq!(git(&third, &["symbolic-ref", "--short", "HEAD"]).as_deref(), Some("feature/c"));
+
+    // 已结束的会话不能再分配工作树
+    assert!(pool.acquire(session_ids[0]).await.unwrap_err().is_business_error());
+
+    // 空闲工作树超过上限时被删除
+    for session_id in [session_ids[1], session_ids[2]] {
+        sessions.start_session(session_id).await.unwrap();
+        sessions.complete_session(session_id, true, None, None, None).await.unwrap();
+        pool.release(session_id).await.unwrap();
+    }
+    let listed = pool.list(project.project_id).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert!(listed.iter().all(|info| info.state == WorktreeState::Idle && info.branch.is_none()));
+    assert!(pool.prune(project.project_id).await.unwrap().is_empty());
+}
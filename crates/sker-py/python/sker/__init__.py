@@ -0,0 +1,46 @@
+"""sker 多Agent协同开发系统的 Python 绑定
+
+协议类型与服务端共用同一套 Rust 定义和校验规则，REST 客户端对应 ``/api/v1`` 接口。
+"""
+
+from ._sker import (
+    PROTOCOL_VERSION,
+    WORKER_PROTOCOL_VERSION,
+    AgentConfig,
+    ApiRequestError,
+    Client,
+    Event,
+    PlanValidationReport,
+    ProjectContext,
+    SimulationAgent,
+    SkerError,
+    TaskInfo,
+    WorkerMessage,
+    __version__,
+    parse_tasks,
+    validate_agent_config,
+    validate_event,
+    validate_plan,
+    validate_worker_message,
+)
+
+__all__ = [
+    "PROTOCOL_VERSION",
+    "WORKER_PROTOCOL_VERSION",
+    "AgentConfig",
+    "ApiRequestError",
+    "Client",
+    "Event",
+    "PlanValidationReport",
+    "ProjectContext",
+    "SimulationAgent",
+    "SkerError",
+    "TaskInfo",
+    "WorkerMessage",
+    "__version__",
+    "parse_tasks",
+    "validate_agent_config",
+    "validate_event",
+    "validate_plan",
+    "validate_worker_message",
+]
@@ -0,0 +1,144 @@
+//! REST 客户端
+//!
+//! 对应 `codex_database::public_api` 提供的 `/api/v1` 接口，使用阻塞式 HTTP 请求，
+//! 便于在 Python 脚本中直接调用。密钥通过 `Authorization: Bearer <key>` 传递。
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// 默认的请求超时
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 客户端错误
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    /// 服务端返回了错误状态
+    #[error("API 请求失败（{status}）: {message}")]
+    Status {
+        status: u16,
+        message: String,
+        /// 被限流时建议的重试等待秒数
+        retry_after_secs: Option<u64>,
+    },
+
+    /// 网络或传输错误
+    #[error("API 请求发送失败: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    /// 响应无法解析
+    #[error("API 响应无效: {0}")]
+    InvalidResponse(String),
+}
+
+impl ApiError {
+    /// 错误对应的 HTTP 状态码，传输错误返回 None
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ApiError::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+/// 创建任务的请求
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct NewTask {
+    pub title: String,
+    pub description: String,
+    pub task_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_task_id: Option<Uuid>,
+}
+
+/// sker REST 接口客户端
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    base_url: String,
+    api_key: String,
+    http: reqwest::blocking::Client,
+}
+
+impl ApiClient {
+    /// 创建客户端，`base_url` 形如 `https://sker.example.com`
+    pub fn new(base_url: &str, api_key: &str, timeout: Duration) -> Result<Self, ApiError> {
+        let http = reqwest::blocking::Client::builder().timeout(timeout).build()?;
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            http,
+        })
+    }
+
+    /// 服务地址
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// 列出密钥可访问的项目
+    pub fn list_projects(&self) -> Result<Vec<JsonValue>, ApiError> {
+        let body = self.send(self.http.get(self.url("/api/v1/projects")))?;
+        field_array(body, "projects")
+    }
+
+    /// 列出项目的任务
+    pub fn list_tasks(&self, project_id: Uuid) -> Result<Vec<JsonValue>, ApiError> {
+        let body = self.send(self.http.get(self.url(&format!("/api/v1/projects/{project_id}/tasks"))))?;
+        field_array(body, "tasks")
+    }
+
+    /// 创建任务，携带幂等键的重试返回首次创建的任务
+    pub fn create_task(
+        &self,
+        project_id: Uuid,
+        task: &NewTask,
+        idempotency_key: Option<&str>,
+    ) -> Result<JsonValue, ApiError> {
+        let mut request = self
+            .http
+            .post(self.url(&format!("/api/v1/projects/{project_id}/tasks")))
+            .json(task);
+        if let Some(key) = idempotency_key {
+            request = request.header("Idempotency-Key", key);
+        }
+        let mut body = self.send(request)?;
+        match body.get_mut("task").map(JsonValue::take) {
+            Some(task) if task.is_object() => Ok(task),
+            _ => Err(ApiError::InvalidResponse("缺少 task 字段".to_string())),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn send(&self, request: reqwest::blocking::RequestBuilder) -> Result<JsonValue, ApiError> {
+        let response = request.bearer_auth(&self.api_key).send()?;
+        let status = response.status().as_u16();
+        let retry_after_secs = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok());
+        let text = response.text()?;
+        let body: JsonValue =
+            serde_json::from_str(&text).map_err(|e| ApiError::InvalidResponse(format!("{e}: {text}")))?;
+        if !(200..300).contains(&status) {
+            let message = body
+                .get("error")
+                .and_then(JsonValue::as_str)
+                .map(str::to_string)
+                .unwrap_or(text);
+            return Err(ApiError::Status { status, message, retry_after_secs });
+        }
+        Ok(body)
+    }
+}
+
+fn field_array(mut body: JsonValue, field: &str) -> Result<Vec<JsonValue>, ApiError> {
+    match body.get_mut(field).map(JsonValue::take) {
+        Some(JsonValue::Array(items)) => Ok(items),
+        _ => Err(ApiError::InvalidResponse(format!("缺少 {field} 字段"))),
+    }
+}
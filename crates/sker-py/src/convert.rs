@@ -0,0 +1,32 @@
+//! Python 对象与 JSON 之间的转换
+//!
+//! 通过标准库 `json` 模块往返转换，Python 端得到的是普通的 dict/list，
+//! 与服务端 JSON 的结构完全一致。
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyString};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// 把 Python 对象（dict/list 或 JSON 字符串）转换为 JSON 文本
+pub fn to_json_text(value: &Bound<'_, PyAny>) -> PyResult<String> {
+    if let Ok(text) = value.downcast::<PyString>() {
+        return Ok(text.to_str()?.to_string());
+    }
+    let json = value.py().import("json")?;
+    let kwargs = [("default", value.py().get_type::<PyString>())].into_py_dict(value.py())?;
+    json.call_method("dumps", (value,), Some(&kwargs))?.extract()
+}
+
+/// 把 Python 对象反序列化为 Rust 类型
+pub fn from_py<T: DeserializeOwned>(value: &Bound<'_, PyAny>) -> PyResult<T> {
+    let text = to_json_text(value)?;
+    serde_json::from_str(&text).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// 把 Rust 值转换为 Python 的 dict/list
+pub fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let text = serde_json::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (text,))?.unbind())
+}
@@ -0,0 +1,178 @@
+//! 任务批量导入
+//!
+//! 从 JSON 数组、JSON Lines 或 CSV 读取任务行，通过 [`ApiClient::create_task`] 逐个创建。
+//! 每行的幂等键取自 `key` 列；未提供时若指定了前缀则使用 `<前缀>:<行号>`，
+//! 因此中途失败后用相同前缀重新导入同一文件不会重复创建已成功的任务。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::client::{ApiClient, NewTask};
+
+/// 未指定任务类型时使用的类型
+pub const DEFAULT_TASK_TYPE: &str = "development";
+
+/// 导入文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Json,
+    JsonLines,
+    Csv,
+}
+
+impl ImportFormat {
+    /// 按名称或文件扩展名解析格式（`json`、`jsonl`/`ndjson`、`csv`）
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().trim_start_matches('.').to_ascii_lowercase().as_str() {
+            "json" => Some(ImportFormat::Json),
+            "jsonl" | "ndjson" => Some(ImportFormat::JsonLines),
+            "csv" => Some(ImportFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// 解析错误，`row` 从 1 开始计数
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("第 {row} 行无效: {message}")]
+pub struct ImportError {
+    pub row: usize,
+    pub message: String,
+}
+
+/// 待导入的一行任务
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskRow {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub task_type: Option<String>,
+    #[serde(default)]
+    pub parent_task_id: Option<Uuid>,
+    /// 幂等键
+    #[serde(default)]
+    pub key: Option<String>,
+}
+
+/// 单行导入失败
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportFailure {
+    pub row: usize,
+    pub title: String,
+    pub error: String,
+    pub status: Option<u16>,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ImportReport {
+    /// 创建（或按幂等键返回）的任务
+    pub created: Vec<JsonValue>,
+    pub failed: Vec<ImportFailure>,
+}
+
+/// 解析导入内容，标题为空的行返回错误
+pub fn parse_tasks(content: &str, format: ImportFormat) -> Result<Vec<TaskRow>, ImportError> {
+    let rows = match format {
+        ImportFormat::Json => {
+            let values: Vec<JsonValue> =
+                serde_json::from_str(content).map_err(|e| ImportError { row: 0, message: e.to_string() })?;
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(index, value)| row_from_json(index + 1, value))
+                .collect::<Result<Vec<_>, _>>()?
+        }
+        ImportFormat::JsonLines => content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let value = serde_json::from_str(line)
+                    .map_err(|e| ImportError { row: index + 1, message: e.to_string() })?;
+                row_from_json(index + 1, value)
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        ImportFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(content.as_bytes());
+            let mut rows = Vec::new();
+            for (index, record) in reader.deserialize::<CsvRow>().enumerate() {
+                // 第 1 行是表头
+                let row = index + 2;
+                let record = record.map_err(|e| ImportError { row, message: e.to_string() })?;
+                rows.push(record.into_task_row(row)?);
+            }
+            rows
+        }
+    };
+
+    for (index, row) in rows.iter().enumerate() {
+        if row.title.trim().is_empty() {
+            return Err(ImportError { row: index + 1, message: "任务标题不能为空".to_string() });
+        }
+    }
+    Ok(rows)
+}
+
+/// CSV 中空单元格表示未填写
+#[derive(Debug, Deserialize)]
+struct CsvRow {
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    task_type: String,
+    #[serde(default)]
+    parent_task_id: String,
+    #[serde(default)]
+    key: String,
+}
+
+impl CsvRow {
+    fn into_task_row(self, row: usize) -> Result<TaskRow, ImportError> {
+        let parent_task_id = match self.parent_task_id.as_str() {
+            "" => None,
+            id => Some(Uuid::parse_str(id).map_err(|e| ImportError { row, message: format!("parent_task_id: {e}") })?),
+        };
+        Ok(TaskRow {
+            title: self.title,
+            description: self.description,
+            task_type: (!self.task_type.is_empty()).then_some(self.task_type),
+            parent_task_id,
+            key: (!self.key.is_empty()).then_some(self.key),
+        })
+    }
+}
+
+fn row_from_json(row: usize, value: JsonValue) -> Result<TaskRow, ImportError> {
+    serde_json::from_value(value).map_err(|e| ImportError { row, message: e.to_string() })
+}
+
+/// 逐行创建任务，单行失败不会中断导入
+pub fn import_tasks(client: &ApiClient, project_id: Uuid, rows: &[TaskRow], key_prefix: Option<&str>) -> ImportReport {
+    let mut report = ImportReport::default();
+    for (index, row) in rows.iter().enumerate() {
+        let key = row
+            .key
+            .clone()
+            .or_else(|| key_prefix.map(|prefix| format!("{}:{}", prefix, index + 1)));
+        let task = NewTask {
+            title: row.title.trim().to_string(),
+            description: row.description.clone(),
+            task_type: row.task_type.clone().unwrap_or_else(|| DEFAULT_TASK_TYPE.to_string()),
+            parent_task_id: row.parent_task_id,
+        };
+        match client.create_task(project_id, &task, key.as_deref()) {
+            Ok(created) => report.created.push(created),
+            Err(e) => report.failed.push(ImportFailure {
+                row: index + 1,
+                title: task.title,
+                status: e.status(),
+                error: e.to_string(),
+            }),
+        }
+    }
+    report
+}
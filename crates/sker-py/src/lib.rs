@@ -0,0 +1,186 @@
+//! # sker Python 绑定
+//!
+//! 数据团队用 Python 编写自动化脚本时，直接使用与服务端相同的协议类型和校验规则，不必重新实现 schema：
+//! - 协议类型包装（[`types`]），像 dataclass 一样按字段读取并与 dict/JSON 互相转换；
+//! - REST 客户端（[`client`]），对应 `/api/v1` 接口；
+//! - 任务批量导入（[`import`]），支持 JSON、JSON Lines 和 CSV。
+//!
+//! 使用 maturin 打包为 wheel，Python 包名为 `sker`，扩展模块为 `sker._sker`。
+
+pub mod client;
+mod convert;
+pub mod import;
+pub mod types;
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+use uuid::Uuid;
+
+use crate::client::{ApiClient, ApiError, NewTask};
+use crate::convert::{from_py, to_py};
+use crate::import::{ImportFormat, TaskRow};
+
+create_exception!(sker, SkerError, PyException, "sker 绑定的基础异常");
+create_exception!(
+    sker,
+    ApiRequestError,
+    SkerError,
+    "API 请求失败，args 为 (message, status, retry_after_secs)，传输错误时 status 为 None"
+);
+
+impl From<ApiError> for PyErr {
+    fn from(error: ApiError) -> Self {
+        let (status, retry_after_secs) = match &error {
+            ApiError::Status { status, retry_after_secs, .. } => (Some(*status), *retry_after_secs),
+            _ => (None, None),
+        };
+        ApiRequestError::new_err((error.to_string(), status, retry_after_secs))
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> PyResult<Uuid> {
+    Uuid::parse_str(value).map_err(|e| PyValueError::new_err(format!("{field} 不是有效的UUID: {e}")))
+}
+
+/// sker REST 接口客户端
+#[pyclass(name = "Client", module = "sker", frozen)]
+pub struct PyClient {
+    inner: ApiClient,
+}
+
+#[pymethods]
+impl PyClient {
+    #[new]
+    #[pyo3(signature = (base_url, api_key, timeout = 30.0))]
+    fn new(base_url: &str, api_key: &str, timeout: f64) -> PyResult<Self> {
+        if !timeout.is_finite() || timeout <= 0.0 {
+            return Err(PyValueError::new_err("timeout 必须为正数"));
+        }
+        Ok(Self { inner: ApiClient::new(base_url, api_key, Duration::from_secs_f64(timeout))? })
+    }
+
+    /// 服务地址
+    #[getter]
+    fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    /// 列出密钥可访问的项目
+    fn list_projects(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let projects = py.allow_threads(|| self.inner.list_projects())?;
+        to_py(py, &projects)
+    }
+
+    /// 列出项目的任务
+    fn list_tasks(&self, py: Python<'_>, project_id: &str) -> PyResult<PyObject> {
+        let project_id = parse_uuid(project_id, "project_id")?;
+        let tasks = py.allow_threads(|| self.inner.list_tasks(project_id))?;
+        to_py(py, &tasks)
+    }
+
+    /// 创建任务，携带幂等键的重试返回首次创建的任务
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (project_id, title, description = String::new(), task_type = None, parent_task_id = None, idempotency_key = None))]
+    fn create_task(
+        &self,
+        py: Python<'_>,
+        project_id: &str,
+        title: String,
+        description: String,
+        task_type: Option<String>,
+        parent_task_id: Option<&str>,
+        idempotency_key: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let project_id = parse_uuid(project_id, "project_id")?;
+        let task = NewTask {
+            title,
+            description,
+            task_type: task_type.unwrap_or_else(|| import::DEFAULT_TASK_TYPE.to_string()),
+            parent_task_id: parent_task_id.map(|id| parse_uuid(id, "parent_task_id")).transpose()?,
+        };
+        let created = py.allow_threads(|| self.inner.create_task(project_id, &task, idempotency_key))?;
+        to_py(py, &created)
+    }
+
+    /// 批量导入任务
+    ///
+    /// `tasks` 为文件路径（按扩展名识别格式）或 dict 列表，返回 `{"created": [...], "failed": [...]}`
+    #[pyo3(signature = (project_id, tasks, format = None, key_prefix = None))]
+    fn import_tasks(
+        &self,
+        py: Python<'_>,
+        project_id: &str,
+        tasks: &Bound<'_, PyAny>,
+        format: Option<&str>,
+        key_prefix: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let project_id = parse_uuid(project_id, "project_id")?;
+        let rows = if tasks.is_instance_of::<PyList>() {
+            let rows: Vec<TaskRow> = from_py(tasks)?;
+            if let Some(index) = rows.iter().position(|row| row.title.trim().is_empty()) {
+                return Err(PyValueError::new_err(format!("第 {} 行无效: 任务标题不能为空", index + 1)));
+            }
+            rows
+        } else {
+            let path: PathBuf = tasks.extract()?;
+            let format = format
+                .map(str::to_string)
+                .or_else(|| path.extension().map(|extension| extension.to_string_lossy().into_owned()));
+            read_tasks(&path, format.as_deref())?
+        };
+        let report = py.allow_threads(|| import::import_tasks(&self.inner, project_id, &rows, key_prefix));
+        to_py(py, &report)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Client(base_url={:?})", self.inner.base_url())
+    }
+}
+
+fn read_tasks(path: &std::path::Path, format: Option<&str>) -> PyResult<Vec<TaskRow>> {
+    let format = format
+        .and_then(ImportFormat::parse)
+        .ok_or_else(|| PyValueError::new_err("无法识别导入格式，请指定 json、jsonl 或 csv"))?;
+    let content = std::fs::read_to_string(path)?;
+    import::parse_tasks(&content, format).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// 解析任务导入内容，返回 dict 列表
+#[pyfunction]
+fn parse_tasks(py: Python<'_>, content: &str, format: &str) -> PyResult<PyObject> {
+    let format = ImportFormat::parse(format)
+        .ok_or_else(|| PyValueError::new_err(format!("不支持的导入格式: {format}")))?;
+    let rows = import::parse_tasks(content, format).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    to_py(py, &rows)
+}
+
+#[pymodule]
+#[pyo3(name = "_sker")]
+fn sker_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add("PROTOCOL_VERSION", codex_multi_agent::VERSION)?;
+    m.add("WORKER_PROTOCOL_VERSION", codex_multi_agent::WORKER_PROTOCOL_VERSION)?;
+    m.add("SkerError", m.py().get_type::<SkerError>())?;
+    m.add("ApiRequestError", m.py().get_type::<ApiRequestError>())?;
+
+    m.add_class::<PyClient>()?;
+    m.add_class::<types::PyAgentConfig>()?;
+    m.add_class::<types::PyTaskInfo>()?;
+    m.add_class::<types::PyProjectContext>()?;
+    m.add_class::<types::PySimulationAgent>()?;
+    m.add_class::<types::PyPlanValidationReport>()?;
+    m.add_class::<types::PyEvent>()?;
+    m.add_class::<types::PyWorkerMessage>()?;
+
+    m.add_function(wrap_pyfunction!(parse_tasks, m)?)?;
+    m.add_function(wrap_pyfunction!(types::validate_event, m)?)?;
+    m.add_function(wrap_pyfunction!(types::validate_worker_message, m)?)?;
+    m.add_function(wrap_pyfunction!(types::validate_agent_config, m)?)?;
+    m.add_function(wrap_pyfunction!(types::validate_plan, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! 协议类型的 Python 包装
+//!
+//! 每个包装类像 dataclass 一样按字段名读取属性（`task.title`），并支持与 dict/JSON 互相转换。
+//! 构造时按协议的 serde 规则校验字段，缺少字段或类型不符时抛出 `ValueError`。
+
+use codex_multi_agent::validation;
+use codex_multi_agent::{
+    AgentConfig, EventEnvelope, MultiAgentEvent, PlanValidationReport, ProjectContext, SimulationAgent, TaskInfo,
+    WorkerMessage,
+};
+use pyo3::exceptions::{PyAttributeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::convert::{from_py, to_py};
+
+/// 定义协议类型的包装类
+macro_rules! protocol_class {
+    ($(#[$doc:meta])* $wrapper:ident => $inner:ty, $name:literal $({ $($extra:tt)* })?) => {
+        $(#[$doc])*
+        #[pyclass(name = $name, module = "sker", frozen)]
+        #[derive(Clone)]
+        pub struct $wrapper {
+            pub(crate) inner: $inner,
+        }
+
+        #[pymethods]
+        impl $wrapper {
+            /// 以关键字参数构造，字段与协议 JSON 一致
+            #[new]
+            #[pyo3(signature = (**fields))]
+            fn new(py: Python<'_>, fields: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+                let fields = match fields {
+                    Some(fields) => fields.clone(),
+                    None => PyDict::new(py),
+                };
+                Ok(Self { inner: from_py(fields.as_any())? })
+            }
+
+            /// 从 dict 构造
+            #[staticmethod]
+            fn from_dict(data: &Bound<'_, PyAny>) -> PyResult<Self> {
+                Ok(Self { inner: from_py(data)? })
+            }
+
+            /// 从 JSON 文本构造
+            #[staticmethod]
+            fn from_json(json: &str) -> PyResult<Self> {
+                let inner = serde_json::from_str(json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+                Ok(Self { inner })
+            }
+
+            /// 转换为 dict
+            fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+                to_py(py, &self.inner)
+            }
+
+            /// 转换为 JSON 文本
+            fn to_json(&self) -> PyResult<String> {
+                serde_json::to_string(&self.inner).map_err(|e| PyValueError::new_err(e.to_string()))
+            }
+
+            fn __getattr__(&self, py: Python<'_>, name: &str) -> PyResult<PyObject> {
+                let value = serde_json::to_value(&self.inner).map_err(|e| PyValueError::new_err(e.to_string()))?;
+                match value.get(name) {
+                    Some(field) => to_py(py, field),
+                    None => Err(PyAttributeError::new_err(format!("{} 没有字段 {}", $name, name))),
+                }
+            }
+
+            fn __eq__(&self, other: &Self) -> bool {
+                serde_json::to_value(&self.inner).ok() == serde_json::to_value(&other.inner).ok()
+            }
+
+            fn __repr__(&self) -> String {
+                format!("{}({})", $name, serde_json::to_string(&self.inner).unwrap_or_default())
+            }
+
+            $($($extra)*)?
+        }
+    };
+}
+
+protocol_class! {
+    /// Agent配置
+    PyAgentConfig => AgentConfig, "AgentConfig" {
+        /// 校验配置，无效时抛出 `ValueError`
+        fn validate(&self) -> PyResult<()> {
+            self.inner.validate().map_err(PyValueError::new_err)
+        }
+    }
+}
+
+protocol_class! {
+    /// LLM分解出的任务
+    PyTaskInfo => TaskInfo, "TaskInfo"
+}
+
+protocol_class! {
+    /// 项目上下文
+    PyProjectContext => ProjectContext, "ProjectContext"
+}
+
+protocol_class! {
+    /// 计划校验使用的可用Agent
+    PySimulationAgent => SimulationAgent, "SimulationAgent"
+}
+
+protocol_class! {
+    /// 计划校验报告
+    PyPlanValidationReport => PlanValidationReport, "PlanValidationReport"
+}
+
+protocol_class! {
+    /// 事件信封
+    PyEvent => EventEnvelope, "Event" {
+        /// 事件类型，例如 `task_preempted`
+        #[getter]
+        fn event_type(&self) -> &'static str {
+            self.inner.event_type()
+        }
+
+        /// 是否为关键事件
+        #[getter]
+        fn is_critical(&self) -> bool {
+            self.inner.is_critical()
+        }
+
+        /// 事件元数据
+        #[getter]
+        fn metadata(&self, py: Python<'_>) -> PyResult<PyObject> {
+            to_py(py, self.inner.metadata())
+        }
+    }
+}
+
+protocol_class! {
+    /// 远程工作进程发送给协调器的消息
+    PyWorkerMessage => WorkerMessage, "WorkerMessage" {
+        /// 消息类型，例如 `heartbeat`
+        #[getter]
+        fn message_type(&self) -> &'static str {
+            self.inner.message_type()
+        }
+    }
+}
+
+/// 校验事件信封（dict 或 JSON 文本），返回 `{"valid", "kind", "errors"}`
+#[pyfunction]
+pub fn validate_event(py: Python<'_>, payload: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+    let text = crate::convert::to_json_text(payload)?;
+    to_py(py, &validation::validate_event_json(&text))
+}
+
+/// 校验工作进程消息（dict 或 JSON 文本）
+#[pyfunction]
+pub fn validate_worker_message(py: Python<'_>, payload: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+    let text = crate::convert::to_json_text(payload)?;
+    to_py(py, &validation::validate_worker_message_json(&text))
+}
+
+/// 校验Agent配置（dict 或 JSON 文本）
+#[pyfunction]
+pub fn validate_agent_config(py: Python<'_>, payload: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+    let text = crate::convert::to_json_text(payload)?;
+    to_py(py, &validation::validate_agent_config_json(&text))
+}
+
+/// 按计划护栏规则校验任务计划，参数结构同 `PlanValidationRequest`
+#[pyfunction]
+pub fn validate_plan(payload: &Bound<'_, PyAny>) -> PyResult<PyPlanValidationReport> {
+    let text = crate::convert::to_json_text(payload)?;
+    let inner = validation::validate_plan_json(&text).map_err(PyValueError::new_err)?;
+    Ok(PyPlanValidationReport { inner })
+}
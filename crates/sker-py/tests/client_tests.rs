@@ -0,0 +1,142 @@
+//! REST 客户端和任务导入测试
+
+use codex_database::{
+    api_keys::{issue_api_key, ApiScope, IssueApiKey},
+    establish_connection,
+    migrations::Migrator,
+    public_api::{self, PublicApi},
+    repository::{
+        project_repository::CreateProjectData, user_repository::CreateUserData, ProjectRepository,
+        UserRepository,
+    },
+    DatabaseConnection,
+};
+use sker::client::{ApiClient, ApiError, NewTask, DEFAULT_TIMEOUT};
+use sker::import::{import_tasks, parse_tasks, ImportFormat};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+/// 启动公开接口，返回服务地址、项目ID和密钥
+async fn start_api(scopes: Vec<ApiScope>) -> (String, Uuid, String, DatabaseConnection) {
+    let db = establish_connection("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+    let user_id = UserRepository::new(db.clone()).create(CreateUserData {
+        username: format!("py_{}", &Uuid::new_v4().to_string()[..8]),
+        email: format!("py_{}@example.com", &Uuid::new_v4().to_string()[..8]),
+        password_hash: "password_hash".to_string(),
+        profile_data: None,
+        settings: None,
+    }).await.unwrap().user_id;
+    let project_id = ProjectRepository::new(db.clone()).create(CreateProjectData {
+        user_id,
+        name: "Python项目".to_string(),
+        description: None,
+        repository_url: "https://github.com/test/python.git".to_string(),
+        workspace_path: "/workspace/python".to_string(),
+    }).await.unwrap().project_id;
+    let issued = issue_api_key(&db, IssueApiKey {
+        user_id,
+        project_id: Some(project_id),
+        name: "数据脚本".to_string(),
+        scopes,
+        rate_limit_per_minute: None,
+        expires_at: None,
+    }).await.unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(public_api::serve(listener, Arc::new(PublicApi::new(db.clone()))));
+    (base_url, project_id, issued.secret, db)
+}
+
+/// 阻塞式客户端不能在异步运行时线程上调用
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    tokio::task::spawn_blocking(f).await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_client_lists_and_creates_tasks() {
+    let (base_url, project_id, secret, _db) =
+        start_api(vec![ApiScope::ProjectsRead, ApiScope::TasksRead, ApiScope::TasksWrite]).await;
+
+    let (projects, first, retried, tasks) = blocking(move || {
+        let client = ApiClient::new(&format!("{base_url}/"), &secret, DEFAULT_TIMEOUT).unwrap();
+        let projects = client.list_projects().unwrap();
+        let task = NewTask {
+            title: "导出报表".to_string(),
+            description: "每周导出".to_string(),
+            task_type: "development".to_string(),
+            parent_task_id: None,
+        };
+        let first = client.create_task(project_id, &task, Some("report-1")).unwrap();
+        let retried = client.create_task(project_id, &task, Some("report-1")).unwrap();
+        let tasks = client.list_tasks(project_id).unwrap();
+        (projects, first, retried, tasks)
+    }).await;
+
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0]["project_id"], project_id.to_string());
+    assert_eq!(first["title"], "导出报表");
+    assert_eq!(first["task_id"], retried["task_id"], "相同幂等键应返回首次创建的任务");
+    assert_eq!(tasks.len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_client_reports_status_errors() {
+    let (base_url, project_id, secret, _db) = start_api(vec![ApiScope::TasksRead]).await;
+
+    let (unauthorized, forbidden) = blocking(move || {
+        let invalid = ApiClient::new(&base_url, "invalid", DEFAULT_TIMEOUT).unwrap();
+        let unauthorized = invalid.list_tasks(project_id).unwrap_err();
+        let client = ApiClient::new(&base_url, &secret, DEFAULT_TIMEOUT).unwrap();
+        let forbidden = client.create_task(project_id, &NewTask { title: "无权限".to_string(), ..Default::default() }, None).unwrap_err();
+        (unauthorized, forbidden)
+    }).await;
+
+    assert_eq!(unauthorized.status(), Some(401));
+    assert_eq!(forbidden.status(), Some(403));
+    assert!(matches!(forbidden, ApiError::Status { .. }));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_import_tasks_resumes_with_key_prefix() {
+    let (base_url, project_id, secret, _db) = start_api(vec![ApiScope::TasksRead, ApiScope::TasksWrite]).await;
+    let rows = parse_tasks("title,description,task_type\n清洗数据,,\n训练模型,离线训练,testing\n", ImportFormat::Csv).unwrap();
+
+    let (first, second, tasks) = blocking(move || {
+        let client = ApiClient::new(&base_url, &secret, DEFAULT_TIMEOUT).unwrap();
+        let first = import_tasks(&client, project_id, &rows[..1], Some("batch"));
+        let second = import_tasks(&client, project_id, &rows, Some("batch"));
+        (first, second, client.list_tasks(project_id).unwrap())
+    }).await;
+
+    assert_eq!(first.created.len(), 1);
+    assert!(second.failed.is_empty());
+    assert_eq!(second.created.len(), 2);
+    assert_eq!(first.created[0]["task_id"], second.created[0]["task_id"]);
+    assert_eq!(tasks.len(), 2, "重新导入不应重复创建已成功的任务");
+}
+
+#[test]
+fn test_parse_tasks_formats() {
+    assert_eq!(ImportFormat::parse(".NDJSON"), Some(ImportFormat::JsonLines));
+    assert_eq!(ImportFormat::parse("xlsx"), None);
+
+    let json = parse_tasks(r#"[{"title": "A", "key": "a"}, {"title": "B", "task_type": "testing"}]"#, ImportFormat::Json).unwrap();
+    assert_eq!(json.len(), 2);
+    assert_eq!(json[0].key.as_deref(), Some("a"));
+    assert_eq!(json[1].task_type.as_deref(), Some("testing"));
+
+    let lines = parse_tasks("{\"title\": \"A\"}\n\n{\"title\": \"B\"}\n", ImportFormat::JsonLines).unwrap();
+    assert_eq!(lines.len(), 2);
+
+    let csv = parse_tasks("title,parent_task_id,key\nA,,k1\n", ImportFormat::Csv).unwrap();
+    assert_eq!(csv[0].parent_task_id, None);
+    assert_eq!(csv[0].key.as_deref(), Some("k1"));
+
+    let err = parse_tasks("title,parent_task_id\nA,not-a-uuid\n", ImportFormat::Csv).unwrap_err();
+    assert_eq!(err.row, 2);
+    let err = parse_tasks("{\"title\": \"A\"}\n{\"title\": \" \"}\n", ImportFormat::JsonLines).unwrap_err();
+    assert_eq!(err.row, 2);
+}
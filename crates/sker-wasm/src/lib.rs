@@ -0,0 +1,6 @@
+//! # sker WebAssembly 绑定
+//!
+//! 把 [`codex_multi_agent::wasm`] 中的校验函数打包为 cdylib，编译到 `wasm32-unknown-unknown` 后由
+//! wasm-bindgen 生成 JS 绑定，见 `crates/codex-multi-agent/README.md`。
+
+pub use codex_multi_agent::wasm::*;